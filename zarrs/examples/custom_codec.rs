@@ -0,0 +1,247 @@
+#![allow(missing_docs)]
+
+//! An out-of-tree `example.xor` bytes to bytes codec registered at runtime.
+
+use std::{borrow::Cow, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use zarrs::{
+    array::{
+        codec::{
+            codec_register, codec_unregister, BytesPartialDecoderTraits,
+            BytesPartialEncoderDefault, BytesPartialEncoderTraits, BytesToBytesCodecTraits, Codec,
+            CodecError, CodecOptions, CodecPlugin, CodecTraits,
+        },
+        ArrayMetadataOptions, BytesRepresentation, RawBytes, RecommendedConcurrency,
+    },
+    byte_range::ByteRange,
+    metadata::v3::MetadataV3,
+    plugin::{PluginCreateError, PluginMetadataInvalidError},
+};
+
+#[cfg(feature = "async")]
+use zarrs::array::codec::AsyncBytesPartialDecoderTraits;
+
+const IDENTIFIER: &str = "example.xor";
+
+/// The configuration of the `example.xor` codec.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+struct XorCodecConfiguration {
+    key: u8,
+}
+
+/// A codec that XORs each byte with a key.
+#[derive(Clone, Debug)]
+struct XorCodec {
+    key: u8,
+}
+
+fn xor(bytes: &[u8], key: u8) -> Vec<u8> {
+    bytes.iter().map(|byte| byte ^ key).collect()
+}
+
+fn create_codec_xor(metadata: &MetadataV3) -> Result<Codec, PluginCreateError> {
+    let configuration: XorCodecConfiguration = metadata
+        .to_configuration()
+        .map_err(|_| PluginMetadataInvalidError::new(IDENTIFIER, "codec", metadata.clone()))?;
+    Ok(Codec::BytesToBytes(Arc::new(XorCodec {
+        key: configuration.key,
+    })))
+}
+
+impl CodecTraits for XorCodec {
+    fn create_metadata_opt(&self, _options: &ArrayMetadataOptions) -> Option<MetadataV3> {
+        let configuration = XorCodecConfiguration { key: self.key };
+        Some(MetadataV3::new_with_serializable_configuration(IDENTIFIER, &configuration).unwrap())
+    }
+
+    fn partial_decoder_should_cache_input(&self) -> bool {
+        false
+    }
+
+    fn partial_decoder_decodes_all(&self) -> bool {
+        false
+    }
+}
+
+#[cfg_attr(feature = "async", async_trait::async_trait)]
+impl BytesToBytesCodecTraits for XorCodec {
+    fn dynamic(self: Arc<Self>) -> Arc<dyn BytesToBytesCodecTraits> {
+        self as Arc<dyn BytesToBytesCodecTraits>
+    }
+
+    fn recommended_concurrency(
+        &self,
+        _decoded_representation: &BytesRepresentation,
+    ) -> Result<RecommendedConcurrency, CodecError> {
+        Ok(RecommendedConcurrency::new_maximum(1))
+    }
+
+    fn compute_encoded_size(
+        &self,
+        decoded_representation: &BytesRepresentation,
+    ) -> BytesRepresentation {
+        *decoded_representation
+    }
+
+    fn encode<'a>(
+        &self,
+        decoded_value: RawBytes<'a>,
+        _options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        Ok(Cow::Owned(xor(&decoded_value, self.key)))
+    }
+
+    fn decode<'a>(
+        &self,
+        encoded_value: RawBytes<'a>,
+        _decoded_representation: &BytesRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        Ok(Cow::Owned(xor(&encoded_value, self.key)))
+    }
+
+    fn partial_decoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn BytesPartialDecoderTraits>,
+        _decoded_representation: &BytesRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn BytesPartialDecoderTraits>, CodecError> {
+        Ok(Arc::new(XorPartialDecoder {
+            input_handle,
+            key: self.key,
+        }))
+    }
+
+    fn partial_encoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn BytesPartialDecoderTraits>,
+        output_handle: Arc<dyn BytesPartialEncoderTraits>,
+        decoded_representation: &BytesRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn BytesPartialEncoderTraits>, CodecError> {
+        Ok(Arc::new(BytesPartialEncoderDefault::new(
+            input_handle,
+            output_handle,
+            *decoded_representation,
+            self,
+        )))
+    }
+
+    #[cfg(feature = "async")]
+    async fn async_partial_decoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+        _decoded_representation: &BytesRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn AsyncBytesPartialDecoderTraits>, CodecError> {
+        Ok(Arc::new(AsyncXorPartialDecoder {
+            input_handle,
+            key: self.key,
+        }))
+    }
+}
+
+/// The bytes of the `example.xor` codec map one-to-one, so byte ranges are decoded directly from the input.
+struct XorPartialDecoder {
+    input_handle: Arc<dyn BytesPartialDecoderTraits>,
+    key: u8,
+}
+
+impl BytesPartialDecoderTraits for XorPartialDecoder {
+    fn partial_decode(
+        &self,
+        decoded_regions: &[ByteRange],
+        options: &CodecOptions,
+    ) -> Result<Option<Vec<RawBytes<'_>>>, CodecError> {
+        Ok(self
+            .input_handle
+            .partial_decode(decoded_regions, options)?
+            .map(|encoded_values| {
+                encoded_values
+                    .iter()
+                    .map(|encoded_value| Cow::Owned(xor(encoded_value, self.key)))
+                    .collect()
+            }))
+    }
+}
+
+#[cfg(feature = "async")]
+struct AsyncXorPartialDecoder {
+    input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+    key: u8,
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl AsyncBytesPartialDecoderTraits for AsyncXorPartialDecoder {
+    async fn partial_decode(
+        &self,
+        decoded_regions: &[ByteRange],
+        options: &CodecOptions,
+    ) -> Result<Option<Vec<RawBytes<'_>>>, CodecError> {
+        Ok(self
+            .input_handle
+            .partial_decode(decoded_regions, options)
+            .await?
+            .map(|encoded_values| {
+                encoded_values
+                    .iter()
+                    .map(|encoded_value| Cow::Owned(xor(encoded_value, self.key)))
+                    .collect()
+            }))
+    }
+}
+
+fn custom_codec() -> Result<(), Box<dyn std::error::Error>> {
+    use zarrs::{
+        array::{Array, ArrayBuilder, DataType, FillValue},
+        storage::store::MemoryStore,
+    };
+
+    // Register the codec so that it is picked up when parsing array metadata
+    codec_register(CodecPlugin::new(
+        IDENTIFIER,
+        |name| name == IDENTIFIER,
+        create_codec_xor,
+    ));
+
+    // Create an array with the codec
+    let store = Arc::new(MemoryStore::new());
+    let array_path = "/array";
+    let array = ArrayBuilder::new(
+        vec![4, 4], // array shape
+        DataType::UInt8,
+        vec![2, 2].try_into()?, // regular chunk shape
+        FillValue::from(0u8),
+    )
+    .bytes_to_bytes_codecs(vec![Arc::new(XorCodec { key: 0x5a })])
+    .build(store.clone(), array_path)?;
+    array.store_metadata()?;
+    array.store_chunk_elements::<u8>(&[0, 0], &[1, 2, 3, 4])?;
+
+    println!(
+        "The array metadata is:\n{}\n",
+        serde_json::to_string_pretty(&array.metadata()).unwrap()
+    );
+
+    // Open the array, which creates the codec from its metadata
+    let array = Array::open(store.clone(), array_path)?;
+    let elements = array.retrieve_chunk_elements::<u8>(&[0, 0])?;
+    println!("Chunk [0, 0] is {elements:?}");
+
+    // An unregistered codec is not supported
+    codec_unregister(IDENTIFIER);
+    if let Err(err) = Array::open(store, array_path) {
+        println!("Opening the array after unregistering the codec fails: {err}");
+    }
+
+    Ok(())
+}
+
+fn main() {
+    if let Err(err) = custom_codec() {
+        println!("{err:?}");
+    }
+}
@@ -23,9 +23,18 @@
 
 mod array_builder;
 mod array_bytes;
+mod array_bytes_view;
+mod array_cached_statistics;
+mod array_compare;
+mod array_coordinates;
+mod array_elements_iterator;
 mod array_errors;
 mod array_metadata_options;
+mod array_orphaned_chunks;
+mod array_reductions;
 mod array_representation;
+mod array_validity;
+mod array_view;
 mod bytes_representation;
 mod chunk_cache;
 pub mod chunk_grid;
@@ -36,13 +45,27 @@ pub mod data_type;
 mod element;
 mod fill_value;
 pub mod storage_transformer;
+mod typed_array;
+mod virtual_store;
 
+#[cfg(feature = "arrow")]
+mod array_arrow;
+#[cfg(feature = "checksum_manifest")]
+mod array_checksum_manifest;
 #[cfg(feature = "sharding")]
 mod array_sharded_ext;
 #[cfg(feature = "sharding")]
 mod array_sync_sharded_readable_ext;
+#[cfg(feature = "sharding")]
+mod array_sync_sharded_writable_ext;
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    sync::{Arc, Mutex, PoisonError},
+};
 
-use std::sync::Arc;
+use serde::{de::DeserializeOwned, Serialize};
 
 pub use self::{
     array_builder::ArrayBuilder,
@@ -50,13 +73,23 @@ pub use self::{
         copy_fill_value_into, update_array_bytes, ArrayBytes, ArrayBytesError, RawBytes,
         RawBytesOffsets,
     },
+    array_bytes_view::ArrayBytesView,
+    array_cached_statistics::{
+        CachedStatistics, CachedStatisticsLevel, CACHED_STATISTICS_ATTRIBUTE,
+    },
+    array_compare::{compare, ArrayCompareOptions, ArrayComparison},
+    array_elements_iterator::ArrayElementsIterator,
     array_errors::{ArrayCreateError, ArrayError},
     array_metadata_options::ArrayMetadataOptions,
+    array_orphaned_chunks::OrphanedChunks,
+    array_reductions::ArrayStatistics,
     array_representation::{
         ArrayRepresentation, ArrayRepresentationBase, ArraySize, ChunkRepresentation,
     },
+    array_validity::ValiditySource,
+    array_view::ArrayView,
     bytes_representation::BytesRepresentation,
-    chunk_grid::ChunkGrid,
+    chunk_grid::{ChunkGrid, ChunkGridTraits},
     chunk_key_encoding::{ChunkKeyEncoding, ChunkKeySeparator},
     codec::ArrayCodecTraits,
     codec::CodecChain,
@@ -65,8 +98,10 @@ pub use self::{
     element::{Element, ElementFixedLength, ElementOwned},
     fill_value::FillValue,
     storage_transformer::StorageTransformerChain,
+    typed_array::TypedArray,
+    virtual_store::VirtualStore,
 };
-pub use crate::metadata::v2::ArrayMetadataV2;
+pub use crate::metadata::v2::{array::ARRAY_DIMENSIONS_ATTRIBUTE, ArrayMetadataV2};
 use crate::metadata::v2_to_v3::ArrayMetadataV2ToV3ConversionError;
 pub use crate::metadata::v3::{
     array::data_type::DataTypeSize,
@@ -75,6 +110,15 @@ pub use crate::metadata::v3::{
     ArrayMetadataV3,
 };
 pub use crate::metadata::{ArrayMetadata, ArrayShape, ChunkShape, DimensionName, Endianness};
+#[cfg(feature = "arrow")]
+pub use array_arrow::{
+    data_type_from_arrow, data_type_to_arrow, store_record_batch, store_record_batch_opt,
+    ArrayReader,
+};
+#[cfg(feature = "checksum_manifest")]
+pub use array_checksum_manifest::{
+    ChecksumManifest, ChecksumVerification, CHECKSUM_MANIFEST_FILE_NAME,
+};
 
 /// An alias for [`FillValueMetadataV3`].
 #[deprecated(since = "0.17.0", note = "use FillValueMetadataV3 instead")]
@@ -89,14 +133,24 @@ pub use chunk_cache::{
 pub use array_sharded_ext::ArrayShardedExt;
 #[cfg(feature = "sharding")]
 pub use array_sync_sharded_readable_ext::{ArrayShardedReadableExt, ArrayShardedReadableExtCache};
+#[cfg(feature = "sharding")]
+pub use array_sync_sharded_writable_ext::ArrayShardedWritableExt;
 // TODO: Add AsyncArrayShardedReadableExt and AsyncArrayShardedReadableExtCache
 
+use array_bytes::update_bytes_flen;
+use array_cached_statistics::StatisticsCache;
+use codec::{options::CodecOptions, ArrayToBytesCodecTraits, CodecError};
+use unsafe_cell_slice::UnsafeCellSlice;
+
 use crate::{
     array_subset::{ArraySubset, IncompatibleDimensionalityError},
     config::MetadataConvertVersion,
-    metadata::{v2_to_v3::array_metadata_v2_to_v3, v3::AdditionalFields},
-    node::{data_key, NodePath},
-    storage::StoreKey,
+    metadata::{nczarr::NCZarrMetadata, v2_to_v3::array_metadata_v2_to_v3, v3::AdditionalFields},
+    node::{
+        data_key, meta_key_v2_attributes, meta_key_v3, AttributesChanges, AttributesDocument,
+        NodePath,
+    },
+    storage::{MaybeBytes, StorageError, StoreKey, StoreValueValidator},
 };
 
 /// An ND index to an element in an array.
@@ -145,7 +199,7 @@ pub fn chunk_shape_to_array_shape(chunk_shape: &[std::num::NonZeroU64]) -> Array
 /// Do not forget to store metadata after mutation.
 ///  - [`shape`](Array::shape) / [`set_shape`](Array::set_shape)
 ///  - [`attributes`](Array::attributes) / [`attributes_mut`](Array::attributes_mut)
-///  - [`dimension_names`](Array::dimension_names) / [`set_dimension_names`](Array::set_dimension_names)
+///  - [`dimension_names`](Array::dimension_names) / [`set_dimension_names`](Array::set_dimension_names) / [`dimension_index`](Array::dimension_index)
 ///
 /// ### `zarrs` Metadata
 /// By default, the `zarrs` version and a link to its source code is written to the `_zarrs` attribute in array metadata when calling [`store_metadata`](Array::store_metadata).
@@ -174,11 +228,14 @@ pub fn chunk_shape_to_array_shape(chunk_shape: &[std::num::NonZeroU64]) -> Array
 ///    - [`store_chunk_subset`](Array::store_chunk_subset)
 ///    - [`store_array_subset`](Array::store_array_subset)
 ///    - [`partial_encoder`](Array::partial_encoder)
+///  - [`ReadableWritableListableStorageTraits`](crate::storage::ReadableWritableListableStorageTraits): store maintenance operations requiring listing
+///    - [`erase_orphaned_chunks`](Array::erase_orphaned_chunks)
 ///
 /// Many `retrieve` and `store` methods have multiple variants:
 ///   - Standard variants store or retrieve data represented as [`ArrayBytes`] (representing fixed or variable length bytes).
 ///   - `_elements` suffix variants can store or retrieve chunks with a known type.
 ///   - `_ndarray` suffix variants can store or retrieve [`ndarray::Array`]s (requires `ndarray` feature).
+///   - `_arrow` suffix variants can store or retrieve Arrow arrays of 1-dimensional arrays (requires `arrow` feature).
 ///   - `_opt` suffix variants have a [`CodecOptions`](crate::array::codec::CodecOptions) parameter for fine-grained concurrency control and more.
 ///   - Variants without the `_opt` suffix use default [`CodecOptions`](crate::array::codec::CodecOptions).
 ///   - **Experimental**: `async_` prefix variants can be used with async stores (requires `async` feature).
@@ -198,6 +255,19 @@ pub fn chunk_shape_to_array_shape(chunk_shape: &[std::num::NonZeroU64]) -> Array
 ///
 /// An [`ArraySubset`] spanning the entire array can be retrieved with [`subset_all`](Array::subset_all).
 ///
+/// ### Label-based Selection
+/// The dimensions of an array can be indexed by the labels of one-dimensional coordinate arrays named after its [`dimension_names`](Array::dimension_names) in the same group.
+/// [`select`](Array::select) (with `_elements` and `_ndarray` variants) retrieves the subset spanned by ranges of labels, for example `array.select(&[("time", t0..t1), ("lat", 10.0..20.0)])`.
+/// Coordinate labels are cached by the array, see [`clear_coordinate_cache`](Array::clear_coordinate_cache).
+///
+/// ### Missing Data
+/// [`retrieve_array_subset_elements_with_validity`](Array::retrieve_array_subset_elements_with_validity) and [`retrieve_array_subset_elements_optional`](Array::retrieve_array_subset_elements_optional) retrieve elements together with a validity mask.
+/// The [`ValiditySource`] determines whether elements equal to the fill value (NaN-aware), elements in chunks that were never written, or elements masked by an accompanying `bool` array are missing.
+///
+/// ### Cached Statistics
+/// An array returned by [`with_cached_statistics`](Array::with_cached_statistics) tracks the minimum, maximum, and count of the elements of each chunk it writes.
+/// These are written to the [`CACHED_STATISTICS_ATTRIBUTE`] attribute by [`store_metadata`](Array::store_metadata) and can be queried with [`cached_statistics`](Array::cached_statistics) and [`cached_chunk_statistics`](Array::cached_chunk_statistics) without scanning chunks.
+///
 /// ## Example: Update an Array Chunk-by-Chunk (in Parallel)
 /// In the below example, an array is updated chunk-by-chunk in parallel.
 /// This makes use of [`chunk_subset_bounded`](Array::chunk_subset_bounded) to retrieve and store only the subset of chunks that are within the array bounds.
@@ -269,7 +339,7 @@ pub fn chunk_shape_to_array_shape(chunk_shape: &[std::num::NonZeroU64]) -> Array
 /// In contrast, the [`retrieve_chunk_subset`](Array::retrieve_chunk_subset) and [`retrieve_array_subset`](Array::retrieve_array_subset) may use partial decoders which can be less efficient with some codecs/stores.
 /// Like their write counterparts, these methods will use a fast path if subsets cover entire chunks.
 ///
-/// **Standard [`Array`] retrieve methods do not perform any caching**.
+/// **Standard [`Array`] retrieve methods do not perform any caching** unless the array has a chunk cache (see [Chunk Caching](#chunk-caching)).
 /// For this reason, retrieving multiple subsets in a chunk with [`retrieve_chunk_subset`](Array::store_chunk_subset) is very inefficient and strongly discouraged.
 /// For example, consider that a compressed chunk may need to be retrieved and decoded in its entirety even if only a small part of the data is needed.
 /// In such situations, prefer to retrieve a partial decoder for a chunk with [`partial_decoder`](Array::partial_decoder) and then retrieve multiple chunk subsets with [`partial_decode`](codec::ArrayPartialDecoderTraits::partial_decode).
@@ -277,7 +347,10 @@ pub fn chunk_shape_to_array_shape(chunk_shape: &[std::num::NonZeroU64]) -> Array
 /// Another alternative is to use [Chunk Caching](#chunk-caching).
 ///
 /// ### Chunk Caching
-/// The [`ArrayChunkCacheExt`] trait adds [`Array`] retrieve methods that utilise chunk caching:
+/// [`Array::with_chunk_cache`] attaches a [`ChunkCacheDecodedLruSizeLimit`] to an array that is consulted by all of its decoded chunk retrieve methods.
+/// This suits access patterns that repeatedly read overlapping regions, such as sliding windows in viewers or data loaders.
+///
+/// Alternatively, the [`ArrayChunkCacheExt`] trait adds [`Array`] retrieve methods that utilise chunk caching:
 ///  - [`retrieve_chunk_opt_cached`](ArrayChunkCacheExt::retrieve_chunk_opt_cached)
 ///  - [`retrieve_chunks_opt_cached`](ArrayChunkCacheExt::retrieve_chunks_opt_cached)
 ///  - [`retrieve_chunk_subset_opt_cached`](ArrayChunkCacheExt::retrieve_chunk_subset_opt_cached)
@@ -370,6 +443,17 @@ pub struct Array<TStorage: ?Sized> {
     // additional_fields: AdditionalFields,
     /// Metadata used to create the array
     metadata: ArrayMetadata,
+    /// The entity tags of the metadata keys as last read from or written to the store.
+    metadata_etags: Mutex<HashMap<StoreKey, StoreValueValidator>>,
+    /// An optional cache of decoded chunks consulted by retrieve methods.
+    chunk_cache: Option<Arc<ChunkCacheDecodedLruSizeLimit>>,
+    /// An optional cache of decoded shard indices consulted by partial decoders.
+    #[cfg(feature = "sharding")]
+    shard_index_cache: Option<Arc<codec::ShardIndexCache>>,
+    /// The labels of coordinate arrays read by [`Array::select`], keyed by dimension name.
+    coordinate_labels: Mutex<HashMap<String, Arc<Vec<f64>>>>,
+    /// The statistics of written chunks, if enabled with [`Array::with_cached_statistics`].
+    statistics_cache: Option<StatisticsCache>,
 }
 
 impl<TStorage: ?Sized> Array<TStorage> {
@@ -439,6 +523,12 @@ impl<TStorage: ?Sized> Array<TStorage> {
             storage_transformers,
             dimension_names: metadata_v3.dimension_names,
             metadata,
+            metadata_etags: Mutex::default(),
+            chunk_cache: None,
+            #[cfg(feature = "sharding")]
+            shard_index_cache: None,
+            coordinate_labels: Mutex::default(),
+            statistics_cache: None,
         })
     }
 
@@ -493,6 +583,23 @@ impl<TStorage: ?Sized> Array<TStorage> {
         &self.codecs
     }
 
+    /// Set the partial decoder cache mode of the codecs.
+    ///
+    /// This overrides where partial decoders of the array cache encoded or decoded chunks, which is otherwise determined by the codecs.
+    /// See [`PartialDecoderCacheMode`](codec::PartialDecoderCacheMode).
+    pub fn set_partial_decoder_cache_mode(
+        &mut self,
+        mode: codec::PartialDecoderCacheMode,
+    ) -> &mut Self {
+        self.codecs = Arc::new(
+            self.codecs
+                .as_ref()
+                .clone()
+                .with_partial_decoder_cache_mode(mode),
+        );
+        self
+    }
+
     /// Get the chunk grid.
     #[must_use]
     pub const fn chunk_grid(&self) -> &ChunkGrid {
@@ -512,20 +619,110 @@ impl<TStorage: ?Sized> Array<TStorage> {
     }
 
     /// Get the dimension names.
+    ///
+    /// The dimension names of a Zarr V2 array are read from the `_ARRAY_DIMENSIONS` attribute, or otherwise the dimensions referenced by the `NCZarr` `_nczarr_array` attribute.
     #[must_use]
     pub const fn dimension_names(&self) -> &Option<Vec<DimensionName>> {
         &self.dimension_names
     }
 
     /// Set the dimension names.
+    ///
+    /// Zarr V2 arrays store the dimension names in the `_ARRAY_DIMENSIONS` attribute by the [xarray convention](https://docs.xarray.dev/en/stable/internals/zarr-encoding-spec.html).
+    /// Unnamed dimensions of a Zarr V2 array are stored as an empty name.
+    ///
+    /// # Errors
+    /// Returns [`ArrayCreateError::InvalidDimensionNames`] if the number of dimension names does not match the array dimensionality.
     pub fn set_dimension_names(
         &mut self,
         dimension_names: Option<Vec<DimensionName>>,
-    ) -> &mut Self {
+    ) -> Result<&mut Self, ArrayCreateError> {
+        if let Some(dimension_names) = &dimension_names {
+            if dimension_names.len() != self.dimensionality() {
+                return Err(ArrayCreateError::InvalidDimensionNames(
+                    dimension_names.len(),
+                    self.dimensionality(),
+                ));
+            }
+        }
+        match &mut self.metadata {
+            ArrayMetadata::V3(metadata) => {
+                metadata.dimension_names.clone_from(&dimension_names);
+            }
+            ArrayMetadata::V2(metadata) => {
+                if let Some(dimension_names) = &dimension_names {
+                    let dimension_names: Vec<&str> = dimension_names
+                        .iter()
+                        .map(|dimension_name| dimension_name.as_str().unwrap_or_default())
+                        .collect();
+                    metadata.attributes.insert(
+                        ARRAY_DIMENSIONS_ATTRIBUTE.to_string(),
+                        dimension_names.into(),
+                    );
+                } else {
+                    metadata.attributes.remove(ARRAY_DIMENSIONS_ATTRIBUTE);
+                }
+            }
+        }
         self.dimension_names = dimension_names;
+        self.clear_coordinate_cache();
+        Ok(self)
+    }
+
+    /// Return the index of the dimension named `dimension_name`, if any.
+    #[must_use]
+    pub fn dimension_index(&self, dimension_name: &str) -> Option<usize> {
+        self.dimension_names.as_ref().and_then(|dimension_names| {
+            dimension_names
+                .iter()
+                .position(|name| name.as_str() == Some(dimension_name))
+        })
+    }
+
+    /// Return the array with a decoded chunk cache.
+    ///
+    /// Retrieve methods (e.g. [`retrieve_chunk`](Array::retrieve_chunk), [`retrieve_chunk_subset`](Array::retrieve_chunk_subset), [`retrieve_array_subset`](Array::retrieve_array_subset), and their async variants) retrieve intersected chunks from the cache where possible.
+    /// Chunks that are not in the cache are retrieved and decoded in their entirety and then inserted into the cache, so partial decoding is not used.
+    /// Chunks that do not exist are not cached.
+    ///
+    /// Chunks are keyed by their chunk indices, so a cache must not be shared between arrays.
+    /// Cached chunks are invalidated when they are written or erased by this array, but writes by other means (such as another [`Array`] or process) are not observed.
+    #[must_use]
+    pub fn with_chunk_cache(mut self, chunk_cache: Arc<ChunkCacheDecodedLruSizeLimit>) -> Self {
+        self.chunk_cache = Some(chunk_cache);
+        self
+    }
+
+    /// Get the decoded chunk cache, if any.
+    #[must_use]
+    pub fn chunk_cache(&self) -> Option<&Arc<ChunkCacheDecodedLruSizeLimit>> {
+        self.chunk_cache.as_ref()
+    }
+
+    #[cfg(feature = "sharding")]
+    /// Return the array with a shard index cache.
+    ///
+    /// Partial decoders of shards created by this array retrieve decoded shard indices from the cache where possible.
+    /// See [`ShardIndexCache`](codec::ShardIndexCache).
+    ///
+    /// Shard indices are keyed by the store key of their shard, so a cache must not be shared between arrays.
+    /// Cached shard indices are invalidated when their shard is written or erased by this array, but writes by other means (such as another [`Array`] or process) are not observed.
+    #[must_use]
+    pub fn with_shard_index_cache(
+        mut self,
+        shard_index_cache: Arc<codec::ShardIndexCache>,
+    ) -> Self {
+        self.shard_index_cache = Some(shard_index_cache);
         self
     }
 
+    #[cfg(feature = "sharding")]
+    /// Get the shard index cache, if any.
+    #[must_use]
+    pub fn shard_index_cache(&self) -> Option<&Arc<codec::ShardIndexCache>> {
+        self.shard_index_cache.as_ref()
+    }
+
     /// Get the attributes.
     #[must_use]
     pub const fn attributes(&self) -> &serde_json::Map<String, serde_json::Value> {
@@ -544,6 +741,42 @@ impl<TStorage: ?Sized> Array<TStorage> {
         }
     }
 
+    /// Get the `NCZarr` metadata in the attributes.
+    ///
+    /// `NCZarr` attributes that are absent are [`None`].
+    ///
+    /// # Errors
+    /// Returns a [`serde_json::Error`] if the `NCZarr` attributes cannot be deserialized.
+    pub fn nczarr_metadata(&self) -> Result<NCZarrMetadata, serde_json::Error> {
+        NCZarrMetadata::from_attributes(self.attributes())
+    }
+
+    /// Deserialize the attributes into `T`.
+    ///
+    /// Attributes that are not fields of `T` are ignored, unless `T` denies unknown fields.
+    ///
+    /// # Errors
+    /// Returns a [`serde_json::Error`] if the attributes cannot be deserialized into `T`.
+    pub fn attributes_as<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_value(serde_json::Value::Object(self.attributes().clone()))
+    }
+
+    /// Serialize `value` and set it as the attribute `key`, replacing any existing attribute with the same key.
+    ///
+    /// This does **not** write to the store, use [`update_attributes`](Array::update_attributes) or [`store_metadata`](Array::store_metadata) to write the attributes to the store.
+    ///
+    /// # Errors
+    /// Returns a [`serde_json::Error`] if `value` cannot be serialized.
+    pub fn set_attribute<T: Serialize>(
+        &mut self,
+        key: &str,
+        value: T,
+    ) -> Result<&mut Self, serde_json::Error> {
+        let value = serde_json::to_value(value)?;
+        self.attributes_mut().insert(key.to_string(), value);
+        Ok(self)
+    }
+
     /// Get the additional fields.
     #[must_use]
     pub const fn additional_fields(&self) -> &AdditionalFields {
@@ -599,6 +832,13 @@ impl<TStorage: ?Sized> Array<TStorage> {
                 serde_json::to_value(zarrs_metadata).unwrap_unchecked()
             });
         }
+        if let Some(statistics) = self.cached_statistics_attribute_value() {
+            let attributes = match &mut metadata {
+                AM::V3(metadata) => &mut metadata.attributes,
+                AM::V2(metadata) => &mut metadata.attributes,
+            };
+            attributes.insert(CACHED_STATISTICS_ATTRIBUTE.to_string(), statistics);
+        }
 
         // Codec metadata manipulation
         match &mut metadata {
@@ -640,6 +880,28 @@ impl<TStorage: ?Sized> Array<TStorage> {
         data_key(self.path(), &self.chunk_key_encoding.encode(chunk_indices))
     }
 
+    /// Return the indices of the chunk with the store `key`, if it is a chunk key of the array.
+    ///
+    /// The indices are not checked to be within the bounds of the chunk grid.
+    /// Returns [`None`] if the chunk key encoding does not support [`decode`](chunk_key_encoding::ChunkKeyEncodingTraits::decode).
+    #[must_use]
+    pub fn chunk_indices_from_key(&self, key: &StoreKey) -> Option<ArrayIndices> {
+        if self.dimensionality() == 0 {
+            return (*key == self.chunk_key(&[])).then(Vec::new);
+        }
+        let path = self.path().as_str();
+        let path = path.strip_prefix('/').unwrap_or(path);
+        let chunk_key = if path.is_empty() {
+            key.as_str()
+        } else {
+            key.as_str().strip_prefix(path)?.strip_prefix('/')?
+        };
+        let chunk_indices = self
+            .chunk_key_encoding
+            .decode(&StoreKey::new(chunk_key).ok()?)?;
+        (chunk_indices.len() == self.dimensionality()).then_some(chunk_indices)
+    }
+
     /// Return the origin of the chunk at `chunk_indices`.
     ///
     /// # Errors
@@ -770,6 +1032,114 @@ impl<TStorage: ?Sized> Array<TStorage> {
             .chunks_in_array_subset(array_subset, self.shape())
     }
 
+    /// Return an iterator over the chunks of the array.
+    ///
+    /// Yields the indices of each chunk and its array subset bounded by the array shape.
+    /// Chunks are iterated over with the last dimension of the chunk grid fastest.
+    /// Nothing is yielded if the chunk grid shape cannot be determined.
+    pub fn chunks(&self) -> impl Iterator<Item = (ArrayIndices, ArraySubset)> + '_ {
+        let (chunk_grid_shape, num_chunks) =
+            self.chunk_grid_shape()
+                .map_or((vec![], 0), |chunk_grid_shape| {
+                    let num_chunks = chunk_grid_shape.iter().product();
+                    (chunk_grid_shape, num_chunks)
+                });
+        (0..num_chunks).filter_map(move |index| {
+            let chunk_indices = unravel_index(index, &chunk_grid_shape);
+            // SAFETY: the length of the chunk indices and the array shape match the dimensionality of the chunk grid
+            let chunk_subset = unsafe {
+                self.chunk_grid()
+                    .subset_unchecked(&chunk_indices, self.shape())
+            }?;
+            let chunk_subset = unsafe { chunk_subset.bound_unchecked(self.shape()) };
+            Some((chunk_indices, chunk_subset))
+        })
+    }
+
+    /// Split `array_subset` into slabs along the first dimension at the chunk boundaries.
+    ///
+    /// Each slab spans the chunks intersecting `array_subset` along the first dimension, and the elements of consecutive slabs are consecutive in the C-contiguous order of `array_subset`.
+    fn array_subset_slabs(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<Vec<ArraySubset>, ArrayError> {
+        if array_subset.dimensionality() == 0 {
+            return Ok(vec![array_subset.clone()]);
+        } else if array_subset.num_elements() == 0 {
+            return Ok(vec![]);
+        }
+
+        let mut slabs = Vec::new();
+        let mut slab_start = array_subset.start().to_vec();
+        let end = array_subset.end_exc()[0];
+        while slab_start[0] < end {
+            // The slab ends at the end of the chunks containing its first row
+            let mut array_indices = vec![0; self.dimensionality()];
+            array_indices[0] = slab_start[0];
+            let slab_end = self
+                .chunk_grid()
+                .chunk_indices(&array_indices, self.shape())?
+                .map(|chunk_indices| self.chunk_subset(&chunk_indices))
+                .transpose()?
+                .map_or(slab_start[0] + 1, |chunk_subset| chunk_subset.end_exc()[0])
+                .min(end);
+            let mut slab_shape = array_subset.shape().to_vec();
+            slab_shape[0] = slab_end - slab_start[0];
+            slabs.push(ArraySubset::new_with_start_shape(
+                slab_start.clone(),
+                slab_shape,
+            )?);
+            slab_start[0] = slab_end;
+        }
+        Ok(slabs)
+    }
+
+    /// Group the elements at `indices` by the chunks containing them.
+    ///
+    /// Returns the indices of each chunk containing an element, and the position in `indices` and the subset within the chunk of each of its elements.
+    /// Elements within a chunk are ordered by their position in `indices`.
+    fn element_subsets_by_chunk(
+        &self,
+        indices: &[ArrayIndices],
+    ) -> Result<HashMap<ArrayIndices, Vec<(usize, ArraySubset)>>, ArrayError> {
+        let mut chunks: HashMap<ArrayIndices, Vec<(usize, ArraySubset)>> = HashMap::new();
+        for (position, array_indices) in indices.iter().enumerate() {
+            if array_indices.len() != self.dimensionality() {
+                return Err(IncompatibleDimensionalityError::new(
+                    array_indices.len(),
+                    self.dimensionality(),
+                )
+                .into());
+            }
+            let element_subset = ArraySubset::new_with_start_shape(
+                array_indices.clone(),
+                vec![1; array_indices.len()],
+            )?;
+            let out_of_bounds =
+                || ArrayError::InvalidArraySubset(element_subset.clone(), self.shape().to_vec());
+            if !element_subset.inbounds(self.shape()) {
+                return Err(out_of_bounds());
+            }
+            let chunk_indices = self
+                .chunk_grid()
+                .chunk_indices(array_indices, self.shape())?
+                .ok_or_else(out_of_bounds)?;
+            let chunk_element_indices = self
+                .chunk_grid()
+                .chunk_element_indices(array_indices, self.shape())?
+                .ok_or_else(out_of_bounds)?;
+            let chunk_element_subset = ArraySubset::new_with_start_shape(
+                chunk_element_indices,
+                vec![1; array_indices.len()],
+            )?;
+            chunks
+                .entry(chunk_indices)
+                .or_default()
+                .push((position, chunk_element_subset));
+        }
+        Ok(chunks)
+    }
+
     /// Calculate the recommended codec concurrency.
     fn recommended_codec_concurrency(
         &self,
@@ -780,6 +1150,168 @@ impl<TStorage: ?Sized> Array<TStorage> {
             .recommended_concurrency(chunk_representation)?)
     }
 
+    /// Decode the encoded chunk at `chunk_indices` into the `output_subset` of `output` with `output_shape`.
+    ///
+    /// The fill value is copied into `output_subset` if the chunk does not exist.
+    unsafe fn decode_chunk_into(
+        &self,
+        chunk_indices: &[u64],
+        chunk_encoded: MaybeBytes,
+        output: &UnsafeCellSlice<u8>,
+        output_shape: &[u64],
+        output_subset: &ArraySubset,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        if let Some(chunk_encoded) = chunk_encoded {
+            let chunk_encoded: Vec<u8> = chunk_encoded.into();
+            let chunk_representation = self.chunk_array_representation(chunk_indices)?;
+            unsafe {
+                self.codecs().decode_into(
+                    Cow::Owned(chunk_encoded),
+                    &chunk_representation,
+                    output,
+                    output_shape,
+                    output_subset,
+                    options,
+                )
+            }
+            .map_err(ArrayError::CodecError)
+        } else {
+            unsafe {
+                copy_fill_value_into(
+                    self.data_type(),
+                    self.fill_value(),
+                    output,
+                    output_shape,
+                    output_subset,
+                )
+            }
+            .map_err(ArrayError::CodecError)
+        }
+    }
+
+    /// Copy the `chunk_subset` of a cached `chunk` with `chunk_shape` into the `output_subset` of `output` with `output_shape`.
+    ///
+    /// The fill value is copied into `output_subset` if the chunk does not exist.
+    unsafe fn copy_cached_chunk_subset_into(
+        &self,
+        chunk: Option<&ArrayBytes<'_>>,
+        chunk_shape: &[u64],
+        chunk_subset: &ArraySubset,
+        output: &UnsafeCellSlice<u8>,
+        output_shape: &[u64],
+        output_subset: &ArraySubset,
+    ) -> Result<(), ArrayError> {
+        let Some(chunk) = chunk else {
+            return unsafe {
+                copy_fill_value_into(
+                    self.data_type(),
+                    self.fill_value(),
+                    output,
+                    output_shape,
+                    output_subset,
+                )
+            }
+            .map_err(ArrayError::CodecError);
+        };
+        let DataTypeSize::Fixed(data_type_size) = self.data_type().size() else {
+            return Err(CodecError::ExpectedFixedLengthBytes.into());
+        };
+        let chunk_subset_bytes = if chunk_subset.shape() == chunk_shape {
+            Cow::Borrowed(chunk)
+        } else {
+            Cow::Owned(chunk.extract_array_subset(chunk_subset, chunk_shape, self.data_type())?)
+        };
+        let ArrayBytes::Fixed(chunk_subset_bytes) = chunk_subset_bytes.as_ref() else {
+            return Err(CodecError::ExpectedFixedLengthBytes.into());
+        };
+        update_bytes_flen(
+            output,
+            output_shape,
+            chunk_subset_bytes,
+            output_subset,
+            data_type_size,
+        );
+        Ok(())
+    }
+
+    /// Invalidate the chunk at `chunk_indices` in the chunk cache of the array, if any.
+    ///
+    /// This must be called after the chunk is written or erased.
+    fn invalidate_chunk_cache(&self, chunk_indices: &[u64]) {
+        if let Some(chunk_cache) = &self.chunk_cache {
+            chunk_cache.invalidate(chunk_indices);
+        }
+    }
+
+    /// Return the entity tag of the metadata at `key` as last read from or written to the store.
+    fn metadata_etag(&self, key: &StoreKey) -> Option<StoreValueValidator> {
+        self.metadata_etags
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(key)
+            .cloned()
+    }
+
+    /// Record the entity tag of the metadata at `key`, or forget it if `etag` is [`None`].
+    fn set_metadata_etag(&self, key: &StoreKey, etag: Option<StoreValueValidator>) {
+        let mut metadata_etags = self
+            .metadata_etags
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(etag) = etag {
+            metadata_etags.insert(key.clone(), etag);
+        } else {
+            metadata_etags.remove(key);
+        }
+    }
+
+    /// Return the key and document of the metadata holding the attributes, and the changes made to the attributes by `update`.
+    fn attributes_update(
+        &self,
+        update: impl FnOnce(&mut serde_json::Map<String, serde_json::Value>),
+    ) -> Result<(StoreKey, AttributesDocument, AttributesChanges), StorageError> {
+        let mut attributes = self.attributes().clone();
+        update(&mut attributes);
+        let changes = AttributesChanges::new(self.attributes(), &attributes);
+        match &self.metadata {
+            ArrayMetadata::V3(metadata) => {
+                let key = meta_key_v3(self.path());
+                let document = serde_json::to_value(metadata)
+                    .map_err(|err| StorageError::InvalidMetadata(key.clone(), err.to_string()))?;
+                Ok((key, AttributesDocument::V3(document), changes))
+            }
+            ArrayMetadata::V2(metadata) => Ok((
+                meta_key_v2_attributes(self.path()),
+                AttributesDocument::V2(metadata.attributes.clone()),
+                changes,
+            )),
+        }
+    }
+
+    /// Invalidate the cached shard index of the chunk at `chunk_indices` in the shard index cache, if any.
+    ///
+    /// This must be called after the chunk is written or erased.
+    #[cfg_attr(not(feature = "sharding"), allow(unused_variables, clippy::unused_self))]
+    fn invalidate_shard_index(&self, chunk_indices: &[u64]) {
+        #[cfg(feature = "sharding")]
+        if let Some(shard_index_cache) = &self.shard_index_cache {
+            shard_index_cache.invalidate(&self.chunk_key(chunk_indices));
+        }
+    }
+
+    /// Return `options` with the shard index cache of the array, if any, for creating a partial decoder.
+    #[cfg_attr(not(feature = "sharding"), allow(clippy::unused_self))]
+    fn partial_decoder_options<'a>(&self, options: &'a CodecOptions) -> Cow<'a, CodecOptions> {
+        #[cfg(feature = "sharding")]
+        if let Some(shard_index_cache) = &self.shard_index_cache {
+            let mut options = options.clone();
+            options.set_shard_index_cache(Some(shard_index_cache.clone()));
+            return Cow::Owned(options);
+        }
+        Cow::Borrowed(options)
+    }
+
     /// Convert the array to Zarr V3.
     ///
     /// # Errors
@@ -799,6 +1331,12 @@ impl<TStorage: ?Sized> Array<TStorage> {
                     storage_transformers: self.storage_transformers,
                     dimension_names: self.dimension_names,
                     metadata,
+                    metadata_etags: self.metadata_etags,
+                    chunk_cache: self.chunk_cache,
+                    #[cfg(feature = "sharding")]
+                    shard_index_cache: self.shard_index_cache,
+                    coordinate_labels: self.coordinate_labels,
+                    statistics_cache: self.statistics_cache,
                 })
             }
             ArrayMetadata::V3(_) => Ok(self),
@@ -936,7 +1474,7 @@ pub fn bytes_to_ndarray<T: bytemuck::Pod>(
 
 #[cfg(test)]
 mod tests {
-    use crate::storage::store::MemoryStore;
+    use crate::{node::AttributesUpdateOptions, storage::store::MemoryStore};
     use zarrs_filesystem::FilesystemStore;
 
     use super::*;
@@ -1048,6 +1586,430 @@ mod tests {
             .is_none());
     }
 
+    #[test]
+    fn array_append_resize() {
+        let store = Arc::new(MemoryStore::default());
+        let array_path = "/array";
+        let mut array = ArrayBuilder::new(
+            vec![2, 3], // array shape
+            DataType::UInt8,
+            vec![2, 2].try_into().unwrap(), // regular chunk shape
+            FillValue::from(0u8),
+        )
+        .build(store.clone(), array_path)
+        .unwrap();
+        array
+            .store_array_subset_elements::<u8>(&array.subset_all(), &[1, 2, 3, 4, 5, 6])
+            .unwrap();
+
+        // Append along each axis
+        assert!(array.append_elements::<u8>(2, &[7, 8]).is_err());
+        assert!(array.append_elements::<u8>(0, &[7, 8]).is_err());
+        assert_eq!(
+            array.append_elements::<u8>(0, &[7, 8, 9]).unwrap(),
+            ArraySubset::new_with_ranges(&[2..3, 0..3])
+        );
+        assert_eq!(
+            array.append_elements::<u8>(1, &[10, 11, 12]).unwrap(),
+            ArraySubset::new_with_ranges(&[0..3, 3..4])
+        );
+        assert_eq!(array.shape(), &[3, 4]);
+        assert_eq!(
+            Array::open(store.clone(), array_path).unwrap().shape(),
+            &[3, 4]
+        );
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<u8>(&array.subset_all())
+                .unwrap(),
+            vec![1, 2, 3, 10, 4, 5, 6, 11, 7, 8, 9, 12]
+        );
+
+        // Shrinking erases chunks outside of the array and resets straddling chunks
+        assert!(array.resize(vec![1]).is_err());
+        array.resize(vec![1, 1]).unwrap();
+        assert_eq!(
+            Array::open(store.clone(), array_path).unwrap().shape(),
+            &[1, 1]
+        );
+        assert!(array.retrieve_chunk_if_exists(&[0, 1]).unwrap().is_none());
+        assert!(array.retrieve_chunk_if_exists(&[1, 0]).unwrap().is_none());
+        array.resize(vec![3, 4]).unwrap();
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<u8>(&array.subset_all())
+                .unwrap(),
+            vec![1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn array_by_indices() {
+        let store = Arc::new(MemoryStore::default());
+        let array_path = "/array";
+        let array = ArrayBuilder::new(
+            vec![4, 4], // array shape
+            DataType::UInt16,
+            vec![2, 2].try_into().unwrap(), // regular chunk shape
+            FillValue::from(0u16),
+        )
+        .build(store, array_path)
+        .unwrap();
+
+        let indices = vec![vec![0, 0], vec![3, 1], vec![1, 1], vec![2, 2], vec![3, 1]];
+        assert!(array
+            .store_elements_by_indices::<u16>(&indices, &[1, 2, 3])
+            .is_err());
+        assert!(array
+            .store_elements_by_indices::<u16>(&[vec![4, 0]], &[1])
+            .is_err());
+        assert!(array
+            .store_elements_by_indices::<u16>(&[vec![0]], &[1])
+            .is_err());
+        array
+            .store_elements_by_indices::<u16>(&indices, &[1, 2, 3, 4, 5])
+            .unwrap();
+        assert!(array.retrieve_chunk_if_exists(&[0, 1]).unwrap().is_none());
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<u16>(&array.subset_all())
+                .unwrap(),
+            vec![
+                1, 0, 0, 0, //
+                0, 3, 0, 0, //
+                0, 0, 4, 0, //
+                0, 5, 0, 0, //
+            ]
+        );
+
+        let indices = vec![vec![3, 1], vec![0, 3], vec![0, 0], vec![2, 2]];
+        assert_eq!(
+            array.retrieve_elements_by_indices::<u16>(&indices).unwrap(),
+            vec![5, 0, 1, 4]
+        );
+        assert!(array
+            .retrieve_elements_by_indices::<u16>(&[vec![0, 4]])
+            .is_err());
+        assert!(array
+            .retrieve_elements_by_indices::<u16>(&[])
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn array_subset_masked() {
+        let store = Arc::new(MemoryStore::default());
+        let array_path = "/array";
+        let array = ArrayBuilder::new(
+            vec![4, 4], // array shape
+            DataType::UInt8,
+            vec![2, 2].try_into().unwrap(), // regular chunk shape
+            FillValue::from(0u8),
+        )
+        .build(store, array_path)
+        .unwrap();
+        array
+            .store_array_subset_elements::<u8>(&array.subset_all(), &(0..16).collect::<Vec<_>>())
+            .unwrap();
+
+        let array_subset = ArraySubset::new_with_ranges(&[1..3, 1..4]);
+        #[rustfmt::skip]
+        let mask = [
+            true, false, true,
+            false, false, true,
+        ];
+        assert_eq!(
+            array
+                .retrieve_array_subset_masked_elements::<u8>(&array_subset, &mask)
+                .unwrap(),
+            vec![5, 7, 11]
+        );
+        assert!(array
+            .retrieve_array_subset_masked_elements::<u8>(&array_subset, &mask[..5])
+            .is_err());
+        assert!(array
+            .retrieve_array_subset_masked_elements::<u8>(
+                &ArraySubset::new_with_ranges(&[3..5, 0..1]),
+                &[true, true]
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn array_chunks_visit() {
+        use std::sync::{
+            atomic::{AtomicU64, Ordering},
+            Mutex,
+        };
+
+        let store = Arc::new(MemoryStore::default());
+        let array = ArrayBuilder::new(
+            vec![5, 5], // array shape
+            DataType::UInt8,
+            vec![2, 2].try_into().unwrap(), // regular chunk shape
+            FillValue::from(0u8),
+        )
+        .build(store, "/array")
+        .unwrap();
+        array
+            .store_array_subset_elements::<u8>(&array.subset_all(), &(0..25).collect::<Vec<_>>())
+            .unwrap();
+
+        let chunks: Vec<_> = array.chunks().collect();
+        assert_eq!(chunks.len(), 9);
+        assert_eq!(
+            chunks[0],
+            (vec![0, 0], ArraySubset::new_with_ranges(&[0..2, 0..2]))
+        );
+        assert_eq!(
+            chunks[8],
+            (vec![2, 2], ArraySubset::new_with_ranges(&[4..5, 4..5]))
+        );
+
+        let sum = AtomicU64::new(0);
+        let edge_chunk = Mutex::new(vec![]);
+        array
+            .par_visit_chunks_elements::<u8>(|chunk_indices, chunk_subset, elements| {
+                assert_eq!(elements.len() as u64, chunk_subset.num_elements());
+                sum.fetch_add(
+                    elements.iter().map(|&element| u64::from(element)).sum(),
+                    Ordering::Relaxed,
+                );
+                if chunk_indices == [2, 1] {
+                    *edge_chunk.lock().unwrap() = elements;
+                }
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(sum.into_inner(), (0..25u64).sum::<u64>());
+        assert_eq!(edge_chunk.into_inner().unwrap(), vec![22, 23]);
+
+        assert!(array
+            .par_visit_chunks(|_, _, _| Err(ArrayError::InvalidElementValue))
+            .is_err());
+        assert!(array
+            .par_visit_chunks_elements::<u16>(|_, _, _| Ok(()))
+            .is_err());
+    }
+
+    #[test]
+    fn array_retrieve_chunk_bytes() {
+        use crate::array::codec::BytesCodec;
+
+        let mut builder = ArrayBuilder::new(
+            vec![4, 4], // array shape
+            DataType::UInt16,
+            vec![2, 2].try_into().unwrap(), // regular chunk shape
+            FillValue::from(0u16),
+        );
+        let array = builder
+            .build(Arc::new(MemoryStore::default()), "/array")
+            .unwrap();
+        assert!(array
+            .codecs()
+            .is_passthrough(&array.chunk_array_representation(&[0, 0]).unwrap()));
+        array
+            .store_chunk_elements::<u16>(&[0, 0], &[1, 2, 3, 4])
+            .unwrap();
+        let chunk_bytes = array.retrieve_chunk_bytes(&[0, 0]).unwrap();
+        assert_eq!(
+            chunk_bytes,
+            array
+                .retrieve_encoded_chunk_bytes(&[0, 0])
+                .unwrap()
+                .unwrap()
+        );
+        assert_eq!(chunk_bytes.as_ref(), transmute_to_bytes(&[1u16, 2, 3, 4]));
+        assert_eq!(
+            array.retrieve_chunk_bytes(&[1, 1]).unwrap().as_ref(),
+            &[0; 8]
+        );
+
+        // The encoded bytes are transformed if the endianness is not native
+        let bytes_codec = if Endianness::native() == Endianness::Little {
+            BytesCodec::big()
+        } else {
+            BytesCodec::little()
+        };
+        let array = builder
+            .array_to_bytes_codec(Arc::new(bytes_codec))
+            .build(Arc::new(MemoryStore::default()), "/array")
+            .unwrap();
+        assert!(!array
+            .codecs()
+            .is_passthrough(&array.chunk_array_representation(&[0, 0]).unwrap()));
+        array
+            .store_chunk_elements::<u16>(&[0, 0], &[1, 2, 3, 4])
+            .unwrap();
+        assert_eq!(
+            array.retrieve_chunk_bytes(&[0, 0]).unwrap().as_ref(),
+            transmute_to_bytes(&[1u16, 2, 3, 4])
+        );
+    }
+
+    #[test]
+    fn array_store_array_subset_from_iter() {
+        let array = ArrayBuilder::new(
+            vec![5, 4], // array shape
+            DataType::UInt8,
+            vec![2, 2].try_into().unwrap(), // regular chunk shape
+            FillValue::from(0u8),
+        )
+        .build(Arc::new(MemoryStore::default()), "/array")
+        .unwrap();
+
+        let array_subset = ArraySubset::new_with_ranges(&[1..5, 1..4]);
+        array
+            .store_array_subset_from_iter(&array_subset, 1..=12u8)
+            .unwrap();
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<u8>(&array.subset_all())
+                .unwrap(),
+            vec![0, 0, 0, 0, 0, 1, 2, 3, 0, 4, 5, 6, 0, 7, 8, 9, 0, 10, 11, 12]
+        );
+
+        assert!(array
+            .store_array_subset_from_iter(&array_subset, 0..11u8)
+            .is_err());
+        assert!(array
+            .store_array_subset_from_iter(&array_subset, std::iter::repeat(0u8))
+            .is_err());
+        assert!(array
+            .store_array_subset_from_iter(&ArraySubset::new_with_ranges(&[4..6, 0..4]), 0..8u8)
+            .is_err());
+    }
+
+    #[test]
+    fn array_update_array_subset() {
+        use crate::storage::{
+            storage_adapter::locking::LockingStorageAdapter, store_lock::InProcessStoreLocks,
+        };
+
+        let store = Arc::new(LockingStorageAdapter::new(
+            Arc::new(MemoryStore::default()),
+            Arc::new(InProcessStoreLocks::new()),
+        ));
+        let array = ArrayBuilder::new(
+            vec![5, 4], // array shape
+            DataType::UInt16,
+            vec![2, 2].try_into().unwrap(), // regular chunk shape
+            FillValue::from(0u16),
+        )
+        .build(store, "/array")
+        .unwrap();
+
+        assert!(array
+            .update_array_subset(&array.subset_all(), |_, _: &mut [u8]| {})
+            .is_err());
+        assert!(array
+            .update_array_subset(
+                &ArraySubset::new_with_ranges(&[4..6, 0..4]),
+                |_, _: &mut [u16]| {}
+            )
+            .is_err());
+
+        // Concurrent writers accumulate into overlapping subsets
+        let array_subset = ArraySubset::new_with_ranges(&[1..5, 1..4]);
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    array
+                        .update_array_subset(&array_subset, |_, elements: &mut [u16]| {
+                            for element in elements {
+                                *element += 1;
+                            }
+                        })
+                        .unwrap();
+                });
+            }
+        });
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<u16>(&array.subset_all())
+                .unwrap(),
+            vec![0, 0, 0, 0, 0, 8, 8, 8, 0, 8, 8, 8, 0, 8, 8, 8, 0, 8, 8, 8]
+        );
+
+        // The update function receives the overlap of each chunk with the array subset
+        array
+            .update_array_subset(&array_subset, |overlap, elements: &mut [u16]| {
+                for (indices, element) in std::iter::zip(overlap.indices().iter(), elements) {
+                    *element = u16::try_from(indices[0] * 10 + indices[1]).unwrap();
+                }
+            })
+            .unwrap();
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<u16>(&array_subset)
+                .unwrap(),
+            vec![11, 12, 13, 21, 22, 23, 31, 32, 33, 41, 42, 43]
+        );
+    }
+
+    #[test]
+    fn array_slice() {
+        use crate::array_subset::ArraySlice;
+        use std::num::NonZeroU64;
+
+        let store = Arc::new(MemoryStore::default());
+        let array_path = "/array";
+        let array = ArrayBuilder::new(
+            vec![6, 6], // array shape
+            DataType::UInt8,
+            vec![2, 2].try_into().unwrap(), // regular chunk shape
+            FillValue::from(0u8),
+        )
+        .build(store, array_path)
+        .unwrap();
+        array
+            .store_array_subset_elements::<u8>(&array.subset_all(), &(0..36).collect::<Vec<_>>())
+            .unwrap();
+
+        let step = [NonZeroU64::new(4).unwrap(), NonZeroU64::new(2).unwrap()];
+        let array_slice = ArraySlice::new_with_ranges_step(&[1..6, 0..6], &step).unwrap();
+        assert_eq!(
+            array
+                .retrieve_array_slice_elements::<u8>(&array_slice)
+                .unwrap(),
+            vec![6, 8, 10, 30, 32, 34]
+        );
+        assert!(array
+            .retrieve_array_slice_elements::<u8>(
+                &ArraySlice::new_with_ranges_step(&[1..10, 0..6], &step).unwrap()
+            )
+            .is_err());
+        assert!(array
+            .store_array_slice_elements::<u8>(&array_slice, &[1, 2, 3])
+            .is_err());
+        array
+            .store_array_slice_elements::<u8>(&array_slice, &[0; 6])
+            .unwrap();
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<u8>(&ArraySubset::new_with_ranges(&[1..2, 0..6]))
+                .unwrap(),
+            vec![0, 7, 0, 9, 0, 11]
+        );
+        assert_eq!(
+            array
+                .retrieve_array_slice_elements::<u8>(&array_slice)
+                .unwrap(),
+            vec![0; 6]
+        );
+
+        // A contiguous array slice is an array subset
+        let array_subset = ArraySubset::new_with_ranges(&[2..4, 1..3]);
+        assert_eq!(
+            array
+                .retrieve_array_slice_elements::<u8>(&array_subset.clone().into())
+                .unwrap(),
+            array
+                .retrieve_array_subset_elements::<u8>(&array_subset)
+                .unwrap()
+        );
+    }
+
     #[allow(dead_code)]
     fn array_v2_to_v3(path_in: &str, path_out: &str) {
         let store = Arc::new(FilesystemStore::new(path_in).unwrap());
@@ -1164,6 +2126,216 @@ mod tests {
         )
     }
 
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn array_conditional_writes() {
+        let path = tempfile::TempDir::new().unwrap();
+        let store = Arc::new(FilesystemStore::new(path.path()).unwrap());
+        let array_path = "/array";
+        let array = ArrayBuilder::new(
+            vec![8, 8],
+            DataType::UInt8,
+            vec![4, 4].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .build(store.clone(), array_path)
+        .unwrap();
+        array.store_metadata().unwrap();
+
+        // The metadata of an array is only stored if it is unmodified since it was opened or last stored
+        let array_a = Array::open(store.clone(), array_path).unwrap();
+        let array_b = Array::open(store.clone(), array_path).unwrap();
+        array_a.store_metadata().unwrap();
+        array_a.store_metadata().unwrap();
+        assert!(matches!(
+            array_b.store_metadata(),
+            Err(StorageError::PreconditionFailed(_))
+        ));
+        assert!(matches!(
+            array.store_metadata(),
+            Err(StorageError::PreconditionFailed(_))
+        ));
+        Array::open(store.clone(), array_path)
+            .unwrap()
+            .store_metadata()
+            .unwrap();
+
+        // Chunk subsets are updated with conditional writes
+        array_a
+            .store_chunk_subset_elements(
+                &[0, 0],
+                &ArraySubset::new_with_ranges(&[0..1, 0..1]),
+                &[1u8],
+            )
+            .unwrap();
+        array_b
+            .store_chunk_subset_elements(
+                &[0, 0],
+                &ArraySubset::new_with_ranges(&[1..2, 1..2]),
+                &[2u8],
+            )
+            .unwrap();
+        let elements = array_a
+            .retrieve_chunk_subset_elements::<u8>(
+                &[0, 0],
+                &ArraySubset::new_with_ranges(&[0..2, 0..2]),
+            )
+            .unwrap();
+        assert_eq!(elements, vec![1, 0, 0, 2]);
+        array_a
+            .store_chunk_subset_elements(
+                &[0, 0],
+                &ArraySubset::new_with_ranges(&[0..2, 0..2]),
+                &[0u8; 4],
+            )
+            .unwrap();
+        // A chunk updated to the fill value is written conditionally rather than erased
+        assert!(array_a.retrieve_chunk_if_exists(&[0, 0]).unwrap().is_some());
+        assert_eq!(
+            array_b.retrieve_chunk_elements::<u8>(&[0, 0]).unwrap(),
+            vec![0u8; 16]
+        );
+    }
+
+    #[test]
+    fn array_attributes_as() {
+        #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+        struct Provenance {
+            source: String,
+            version: u32,
+        }
+
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct Attributes {
+            units: String,
+            scale_factor: f64,
+            provenance: Provenance,
+        }
+
+        let store = Arc::new(MemoryStore::new());
+        let mut array = ArrayBuilder::new(
+            vec![8, 8],
+            DataType::UInt8,
+            vec![4, 4].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .build(store, "/array")
+        .unwrap();
+        let provenance = Provenance {
+            source: "sensor".to_string(),
+            version: 2,
+        };
+        array
+            .set_attribute("units", "m")
+            .unwrap()
+            .set_attribute("scale_factor", 0.5)
+            .unwrap()
+            .set_attribute("provenance", &provenance)
+            .unwrap();
+        assert_eq!(array.attributes()["units"], "m");
+        assert_eq!(
+            array.attributes_as::<Attributes>().unwrap(),
+            Attributes {
+                units: "m".to_string(),
+                scale_factor: 0.5,
+                provenance,
+            }
+        );
+
+        array.set_attribute("scale_factor", "half").unwrap();
+        assert!(array.attributes_as::<Attributes>().is_err());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn array_update_attributes() {
+        let path = tempfile::TempDir::new().unwrap();
+        let store = Arc::new(FilesystemStore::new(path.path()).unwrap());
+        let array_path = "/array";
+        let mut attributes = serde_json::Map::new();
+        attributes.insert("a".to_string(), 1.into());
+        let array = ArrayBuilder::new(
+            vec![8, 8],
+            DataType::UInt8,
+            vec![4, 4].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .attributes(attributes)
+        .build(store.clone(), array_path)
+        .unwrap();
+        array
+            .store_metadata_opt(&ArrayMetadataOptions::default().with_include_zarrs_metadata(false))
+            .unwrap();
+
+        // Attributes changed by another writer are preserved
+        let options = AttributesUpdateOptions::default().with_optimistic_concurrency(true);
+        let mut array_a = Array::open(store.clone(), array_path).unwrap();
+        let mut array_b = Array::open(store.clone(), array_path).unwrap();
+        array_a
+            .update_attributes_opt(
+                |attributes| {
+                    attributes.insert("b".to_string(), 2.into());
+                },
+                &options,
+            )
+            .unwrap();
+        array_b
+            .update_attributes_opt(
+                |attributes| {
+                    attributes.remove("a");
+                    attributes.insert("c".to_string(), 3.into());
+                },
+                &options,
+            )
+            .unwrap();
+        let expected = serde_json::json!({"b": 2, "c": 3});
+        assert_eq!(
+            serde_json::Value::Object(array_b.attributes().clone()),
+            expected
+        );
+        let array = Array::open(store.clone(), array_path).unwrap();
+        assert_eq!(
+            serde_json::Value::Object(array.attributes().clone()),
+            expected
+        );
+
+        // The entity tag of the metadata is updated
+        assert!(matches!(
+            array_a.store_metadata(),
+            Err(StorageError::PreconditionFailed(_))
+        ));
+        array_b.store_metadata().unwrap();
+
+        // Optimistic concurrency requires conditional writes
+        let store = Arc::new(MemoryStore::new());
+        let mut array = ArrayBuilder::new(
+            vec![8, 8],
+            DataType::UInt8,
+            vec![4, 4].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .build(store.clone(), array_path)
+        .unwrap();
+        array
+            .update_attributes(|attributes| {
+                attributes.insert("a".to_string(), 1.into());
+            })
+            .unwrap();
+        assert_eq!(
+            Array::open(store.clone(), array_path).unwrap().attributes()["a"],
+            1
+        );
+        assert!(matches!(
+            array.update_attributes_opt(
+                |attributes| {
+                    attributes.clear();
+                },
+                &options,
+            ),
+            Err(StorageError::Unsupported(_))
+        ));
+    }
+
     // fn array_subset_locking(locks: StoreLocks, expect_equal: bool) {
     //     let store = Arc::new(MemoryStore::new_with_locks(locks));
 
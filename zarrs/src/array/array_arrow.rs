@@ -0,0 +1,695 @@
+use std::{num::NonZeroU64, sync::Arc};
+
+use arrow_array::{
+    cast::AsArray,
+    types::{
+        ByteArrayType, Float16Type, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type,
+        Int8Type, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+    },
+    ArrayRef, ArrowPrimitiveType, BooleanArray, FixedSizeBinaryArray, GenericByteArray,
+    LargeBinaryArray, LargeStringArray, PrimitiveArray, RecordBatch, RecordBatchReader,
+};
+use arrow_buffer::{Buffer, OffsetBuffer, ScalarBuffer};
+use arrow_schema::{ArrowError, Field, Schema, SchemaRef};
+
+use crate::{
+    array_subset::{ArraySubset, IncompatibleDimensionalityError},
+    storage::{ReadableStorageTraits, ReadableWritableStorageTraits},
+};
+
+use super::{codec::CodecOptions, Array, ArrayBytes, ArrayError, DataType, FillValue};
+
+/// Return the Arrow data type corresponding to a Zarr data type.
+///
+/// `string` and `bytes` map to the large (64-bit offset) Arrow variable length types and `r*` maps to a fixed size binary type.
+///
+/// # Errors
+/// Returns [`ArrayError::ArrowError`] if the data type has no Arrow equivalent (`bfloat16`, `complex64`, and `complex128`).
+pub fn data_type_to_arrow(data_type: &DataType) -> Result<arrow_schema::DataType, ArrayError> {
+    Ok(match data_type {
+        DataType::Bool => arrow_schema::DataType::Boolean,
+        DataType::Int8 => arrow_schema::DataType::Int8,
+        DataType::Int16 => arrow_schema::DataType::Int16,
+        DataType::Int32 => arrow_schema::DataType::Int32,
+        DataType::Int64 => arrow_schema::DataType::Int64,
+        DataType::UInt8 => arrow_schema::DataType::UInt8,
+        DataType::UInt16 => arrow_schema::DataType::UInt16,
+        DataType::UInt32 => arrow_schema::DataType::UInt32,
+        DataType::UInt64 => arrow_schema::DataType::UInt64,
+        DataType::Float16 => arrow_schema::DataType::Float16,
+        DataType::Float32 => arrow_schema::DataType::Float32,
+        DataType::Float64 => arrow_schema::DataType::Float64,
+        DataType::RawBits(size) => arrow_schema::DataType::FixedSizeBinary(
+            i32::try_from(*size).map_err(|_| unsupported_data_type(data_type))?,
+        ),
+        DataType::String => arrow_schema::DataType::LargeUtf8,
+        DataType::Binary => arrow_schema::DataType::LargeBinary,
+        DataType::BFloat16 | DataType::Complex64 | DataType::Complex128 => {
+            return Err(unsupported_data_type(data_type));
+        }
+    })
+}
+
+/// Return the Zarr data type corresponding to an Arrow data type.
+///
+/// Both the regular and large Arrow variable length types map to `string` and `bytes`.
+///
+/// # Errors
+/// Returns [`ArrayError::ArrowError`] if the Arrow data type has no Zarr equivalent.
+pub fn data_type_from_arrow(data_type: &arrow_schema::DataType) -> Result<DataType, ArrayError> {
+    Ok(match data_type {
+        arrow_schema::DataType::Boolean => DataType::Bool,
+        arrow_schema::DataType::Int8 => DataType::Int8,
+        arrow_schema::DataType::Int16 => DataType::Int16,
+        arrow_schema::DataType::Int32 => DataType::Int32,
+        arrow_schema::DataType::Int64 => DataType::Int64,
+        arrow_schema::DataType::UInt8 => DataType::UInt8,
+        arrow_schema::DataType::UInt16 => DataType::UInt16,
+        arrow_schema::DataType::UInt32 => DataType::UInt32,
+        arrow_schema::DataType::UInt64 => DataType::UInt64,
+        arrow_schema::DataType::Float16 => DataType::Float16,
+        arrow_schema::DataType::Float32 => DataType::Float32,
+        arrow_schema::DataType::Float64 => DataType::Float64,
+        arrow_schema::DataType::FixedSizeBinary(size) => DataType::RawBits(
+            usize::try_from(*size).map_err(|_| unsupported_arrow_data_type(data_type))?,
+        ),
+        arrow_schema::DataType::Utf8 | arrow_schema::DataType::LargeUtf8 => DataType::String,
+        arrow_schema::DataType::Binary | arrow_schema::DataType::LargeBinary => DataType::Binary,
+        _ => return Err(unsupported_arrow_data_type(data_type)),
+    })
+}
+
+fn unsupported_data_type(data_type: &DataType) -> ArrayError {
+    ArrayError::ArrowError(format!("data type {data_type} has no Arrow equivalent"))
+}
+
+fn unsupported_arrow_data_type(data_type: &arrow_schema::DataType) -> ArrayError {
+    ArrayError::ArrowError(format!(
+        "Arrow data type {data_type} has no Zarr equivalent"
+    ))
+}
+
+fn arrow_error(err: &ArrowError) -> ArrayError {
+    ArrayError::ArrowError(err.to_string())
+}
+
+/// Check that an array is 1-dimensional, the only dimensionality supported for Arrow conversions.
+fn validate_arrow_dimensionality<TStorage: ?Sized>(
+    array: &Array<TStorage>,
+) -> Result<(), ArrayError> {
+    if array.dimensionality() == 1 {
+        Ok(())
+    } else {
+        Err(IncompatibleDimensionalityError::new(array.dimensionality(), 1).into())
+    }
+}
+
+/// Convert the bytes of elements with `data_type` to an Arrow array.
+fn array_bytes_to_arrow(
+    bytes: ArrayBytes<'_>,
+    data_type: &DataType,
+) -> Result<ArrayRef, ArrayError> {
+    fn primitive<T: ArrowPrimitiveType>(bytes: &[u8]) -> ArrayRef
+    where
+        T::Native: bytemuck::Pod,
+    {
+        let values = bytemuck::pod_collect_to_vec::<u8, T::Native>(bytes);
+        Arc::new(PrimitiveArray::<T>::new(ScalarBuffer::from(values), None))
+    }
+
+    fn offsets(offsets: &[usize]) -> Result<OffsetBuffer<i64>, ArrayError> {
+        let offsets = offsets
+            .iter()
+            .map(|offset| i64::try_from(*offset))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| ArrayError::ArrowError(err.to_string()))?;
+        Ok(OffsetBuffer::new(ScalarBuffer::from(offsets)))
+    }
+
+    match data_type {
+        DataType::String => {
+            let (bytes, element_offsets) = bytes.into_variable()?;
+            let array = LargeStringArray::try_new(
+                offsets(&element_offsets)?,
+                Buffer::from_vec(bytes.into_owned()),
+                None,
+            )
+            .map_err(|err| arrow_error(&err))?;
+            Ok(Arc::new(array))
+        }
+        DataType::Binary => {
+            let (bytes, element_offsets) = bytes.into_variable()?;
+            let array = LargeBinaryArray::try_new(
+                offsets(&element_offsets)?,
+                Buffer::from_vec(bytes.into_owned()),
+                None,
+            )
+            .map_err(|err| arrow_error(&err))?;
+            Ok(Arc::new(array))
+        }
+        DataType::RawBits(size) => {
+            let size = i32::try_from(*size).map_err(|_| unsupported_data_type(data_type))?;
+            let bytes = bytes.into_fixed()?;
+            let array =
+                FixedSizeBinaryArray::try_new(size, Buffer::from_vec(bytes.into_owned()), None)
+                    .map_err(|err| arrow_error(&err))?;
+            Ok(Arc::new(array))
+        }
+        DataType::Bool => {
+            let bytes = bytes.into_fixed()?;
+            Ok(Arc::new(BooleanArray::from_iter(
+                bytes.iter().map(|byte| Some(*byte != 0)),
+            )))
+        }
+        DataType::Int8 => Ok(primitive::<Int8Type>(&bytes.into_fixed()?)),
+        DataType::Int16 => Ok(primitive::<Int16Type>(&bytes.into_fixed()?)),
+        DataType::Int32 => Ok(primitive::<Int32Type>(&bytes.into_fixed()?)),
+        DataType::Int64 => Ok(primitive::<Int64Type>(&bytes.into_fixed()?)),
+        DataType::UInt8 => Ok(primitive::<UInt8Type>(&bytes.into_fixed()?)),
+        DataType::UInt16 => Ok(primitive::<UInt16Type>(&bytes.into_fixed()?)),
+        DataType::UInt32 => Ok(primitive::<UInt32Type>(&bytes.into_fixed()?)),
+        DataType::UInt64 => Ok(primitive::<UInt64Type>(&bytes.into_fixed()?)),
+        DataType::Float16 => Ok(primitive::<Float16Type>(&bytes.into_fixed()?)),
+        DataType::Float32 => Ok(primitive::<Float32Type>(&bytes.into_fixed()?)),
+        DataType::Float64 => Ok(primitive::<Float64Type>(&bytes.into_fixed()?)),
+        DataType::BFloat16 | DataType::Complex64 | DataType::Complex128 => {
+            Err(unsupported_data_type(data_type))
+        }
+    }
+}
+
+/// Convert an Arrow array to the bytes of elements with `data_type`.
+///
+/// Null elements are replaced by `fill_value`.
+fn arrow_to_array_bytes(
+    array: &dyn arrow_array::Array,
+    data_type: &DataType,
+    fill_value: &FillValue,
+) -> Result<ArrayBytes<'static>, ArrayError> {
+    fn primitive<T: ArrowPrimitiveType>(
+        array: &dyn arrow_array::Array,
+        fill_value: &[u8],
+    ) -> ArrayBytes<'static>
+    where
+        T::Native: bytemuck::Pod,
+    {
+        let array = array.as_primitive::<T>();
+        let mut bytes = bytemuck::cast_slice::<T::Native, u8>(array.values()).to_vec();
+        if let Some(nulls) = array.nulls() {
+            for (element_bytes, valid) in
+                std::iter::zip(bytes.chunks_exact_mut(fill_value.len()), nulls.iter())
+            {
+                if !valid {
+                    element_bytes.copy_from_slice(fill_value);
+                }
+            }
+        }
+        ArrayBytes::new_flen(bytes)
+    }
+
+    fn variable<T: ByteArrayType>(
+        array: &GenericByteArray<T>,
+        fill_value: &[u8],
+    ) -> ArrayBytes<'static>
+    where
+        T::Native: AsRef<[u8]>,
+    {
+        let mut bytes = Vec::with_capacity(array.values().len());
+        let mut offsets = Vec::with_capacity(array.len() + 1);
+        offsets.push(0);
+        for element in array {
+            bytes.extend_from_slice(element.map_or(fill_value, <T::Native as AsRef<[u8]>>::as_ref));
+            offsets.push(bytes.len());
+        }
+        ArrayBytes::new_vlen(bytes, offsets)
+    }
+
+    let arrow_data_type = array.data_type();
+    if &data_type_from_arrow(arrow_data_type)? != data_type {
+        return Err(ArrayError::ArrowError(format!(
+            "Arrow data type {arrow_data_type} is incompatible with data type {data_type}"
+        )));
+    }
+
+    let fill_value = fill_value.as_ne_bytes();
+    Ok(match arrow_data_type {
+        arrow_schema::DataType::Utf8 => variable(array.as_string::<i32>(), fill_value),
+        arrow_schema::DataType::LargeUtf8 => variable(array.as_string::<i64>(), fill_value),
+        arrow_schema::DataType::Binary => variable(array.as_binary::<i32>(), fill_value),
+        arrow_schema::DataType::LargeBinary => variable(array.as_binary::<i64>(), fill_value),
+        arrow_schema::DataType::FixedSizeBinary(_) => {
+            let mut bytes = Vec::with_capacity(array.len() * fill_value.len());
+            for element in array.as_fixed_size_binary() {
+                bytes.extend_from_slice(element.unwrap_or(fill_value));
+            }
+            ArrayBytes::new_flen(bytes)
+        }
+        arrow_schema::DataType::Boolean => {
+            let mut bytes = Vec::with_capacity(array.len());
+            for element in array.as_boolean() {
+                bytes.push(element.map_or(fill_value[0], u8::from));
+            }
+            ArrayBytes::new_flen(bytes)
+        }
+        arrow_schema::DataType::Int8 => primitive::<Int8Type>(array, fill_value),
+        arrow_schema::DataType::Int16 => primitive::<Int16Type>(array, fill_value),
+        arrow_schema::DataType::Int32 => primitive::<Int32Type>(array, fill_value),
+        arrow_schema::DataType::Int64 => primitive::<Int64Type>(array, fill_value),
+        arrow_schema::DataType::UInt8 => primitive::<UInt8Type>(array, fill_value),
+        arrow_schema::DataType::UInt16 => primitive::<UInt16Type>(array, fill_value),
+        arrow_schema::DataType::UInt32 => primitive::<UInt32Type>(array, fill_value),
+        arrow_schema::DataType::UInt64 => primitive::<UInt64Type>(array, fill_value),
+        arrow_schema::DataType::Float16 => primitive::<Float16Type>(array, fill_value),
+        arrow_schema::DataType::Float32 => primitive::<Float32Type>(array, fill_value),
+        arrow_schema::DataType::Float64 => primitive::<Float64Type>(array, fill_value),
+        _ => return Err(unsupported_arrow_data_type(arrow_data_type)),
+    })
+}
+
+impl<TStorage: ?Sized> Array<TStorage> {
+    /// Return the Arrow field of a 1-dimensional array.
+    ///
+    /// The field is named after the last component of the array path and is not nullable.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if the array is not 1-dimensional or its data type has no Arrow equivalent.
+    pub fn arrow_field(&self) -> Result<Field, ArrayError> {
+        validate_arrow_dimensionality(self)?;
+        let name = self.path().as_str().rsplit('/').next().unwrap_or_default();
+        Ok(Field::new(
+            name,
+            data_type_to_arrow(self.data_type())?,
+            false,
+        ))
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
+    /// Read and decode the `array_subset` of a 1-dimensional array into an Arrow array.
+    ///
+    /// See [`data_type_to_arrow`] for the mapping of data types.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - the array is not 1-dimensional or its data type has no Arrow equivalent, or
+    ///  - a [`retrieve_array_subset`](Array::retrieve_array_subset) error condition is met.
+    pub fn retrieve_array_subset_arrow(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<ArrayRef, ArrayError> {
+        self.retrieve_array_subset_arrow_opt(array_subset, &CodecOptions::default())
+    }
+
+    /// Explicit options version of [`retrieve_array_subset_arrow`](Array::retrieve_array_subset_arrow).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_array_subset_arrow_opt(
+        &self,
+        array_subset: &ArraySubset,
+        options: &CodecOptions,
+    ) -> Result<ArrayRef, ArrayError> {
+        validate_arrow_dimensionality(self)?;
+        data_type_to_arrow(self.data_type())?;
+        let bytes = self.retrieve_array_subset_opt(array_subset, options)?;
+        array_bytes_to_arrow(bytes, self.data_type())
+    }
+}
+
+impl<TStorage: ?Sized + ReadableWritableStorageTraits + 'static> Array<TStorage> {
+    /// Encode the Arrow array `subset_array` and store in the subset of a 1-dimensional array starting at `subset_start`.
+    ///
+    /// The Arrow data type of `subset_array` must map to the data type of the array (see [`data_type_from_arrow`]).
+    /// Null elements are stored as the fill value.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - the array is not 1-dimensional,
+    ///  - the data type of `subset_array` is incompatible with the array, or
+    ///  - a [`store_array_subset`](Array::store_array_subset) error condition is met.
+    pub fn store_array_subset_arrow(
+        &self,
+        subset_start: &[u64],
+        subset_array: &dyn arrow_array::Array,
+    ) -> Result<(), ArrayError> {
+        self.store_array_subset_arrow_opt(subset_start, subset_array, &CodecOptions::default())
+    }
+
+    /// Explicit options version of [`store_array_subset_arrow`](Array::store_array_subset_arrow).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn store_array_subset_arrow_opt(
+        &self,
+        subset_start: &[u64],
+        subset_array: &dyn arrow_array::Array,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        validate_arrow_dimensionality(self)?;
+        let subset = ArraySubset::new_with_start_shape(
+            subset_start.to_vec(),
+            vec![subset_array.len() as u64],
+        )?;
+        let bytes = arrow_to_array_bytes(subset_array, self.data_type(), self.fill_value())?;
+        self.store_array_subset_opt(&subset, bytes, options)
+    }
+}
+
+/// Store the columns of the Arrow `batch` in 1-dimensional `arrays` starting at `batch_start`.
+///
+/// The columns of `batch` are matched to `arrays` by position.
+/// See [`Array::store_array_subset_arrow`].
+///
+/// # Errors
+/// Returns an [`ArrayError`] if the number of columns of `batch` does not match the number of `arrays`, or a [`Array::store_array_subset_arrow`] error condition is met.
+pub fn store_record_batch<TStorage: ?Sized + ReadableWritableStorageTraits + 'static>(
+    arrays: &[Array<TStorage>],
+    batch_start: u64,
+    batch: &RecordBatch,
+) -> Result<(), ArrayError> {
+    store_record_batch_opt(arrays, batch_start, batch, &CodecOptions::default())
+}
+
+/// Explicit options version of [`store_record_batch`].
+#[allow(clippy::missing_errors_doc)]
+pub fn store_record_batch_opt<TStorage: ?Sized + ReadableWritableStorageTraits + 'static>(
+    arrays: &[Array<TStorage>],
+    batch_start: u64,
+    batch: &RecordBatch,
+    options: &CodecOptions,
+) -> Result<(), ArrayError> {
+    if batch.num_columns() != arrays.len() {
+        return Err(ArrayError::IncompatibleArrays(format!(
+            "record batch has {} columns, expected {}",
+            batch.num_columns(),
+            arrays.len()
+        )));
+    }
+    for (array, column) in std::iter::zip(arrays, batch.columns()) {
+        array.store_array_subset_arrow_opt(&[batch_start], column, options)?;
+    }
+    Ok(())
+}
+
+/// A reader of 1-dimensional arrays of equal length as Arrow [`RecordBatch`]es.
+///
+/// Each array is a column of the record batches, with a field given by [`Array::arrow_field`].
+/// By default, the batches are aligned to the chunks of the first array, so each chunk is retrieved once.
+///
+/// [`ArrayReader`] implements [`RecordBatchReader`], so zarr arrays can be used as a source for Arrow based query engines.
+#[derive(Debug)]
+pub struct ArrayReader<TStorage: ?Sized> {
+    arrays: Vec<Array<TStorage>>,
+    schema: SchemaRef,
+    options: CodecOptions,
+    /// The batches that have not been retrieved.
+    batches: std::vec::IntoIter<ArraySubset>,
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + 'static> ArrayReader<TStorage> {
+    /// Create a new reader of `arrays` with default codec options.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if the arrays are not 1-dimensional, have different lengths, or have a data type with no Arrow equivalent.
+    pub fn new(arrays: Vec<Array<TStorage>>) -> Result<Self, ArrayError> {
+        Self::new_opt(arrays, &CodecOptions::default())
+    }
+
+    /// Explicit options version of [`new`](ArrayReader::new).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn new_opt(
+        arrays: Vec<Array<TStorage>>,
+        options: &CodecOptions,
+    ) -> Result<Self, ArrayError> {
+        let fields = arrays
+            .iter()
+            .map(Array::arrow_field)
+            .collect::<Result<Vec<_>, _>>()?;
+        let batches = if let Some(first) = arrays.first() {
+            if let Some(array) = arrays.iter().find(|array| array.shape() != first.shape()) {
+                return Err(ArrayError::IncompatibleArrays(format!(
+                    "array {} has shape {:?}, expected {:?}",
+                    array.path(),
+                    array.shape(),
+                    first.shape()
+                )));
+            }
+            first.array_subset_slabs(&first.subset_all())?
+        } else {
+            vec![]
+        };
+        Ok(Self {
+            arrays,
+            schema: Arc::new(Schema::new(fields)),
+            options: options.clone(),
+            batches: batches.into_iter(),
+        })
+    }
+
+    /// Set the number of rows of each batch, rather than aligning batches to the chunks of the first array.
+    ///
+    /// The last batch may have fewer rows.
+    #[must_use]
+    pub fn with_batch_size(mut self, batch_size: NonZeroU64) -> Self {
+        let len = self.len();
+        let batch_size = batch_size.get();
+        self.batches = (0..len)
+            .step_by(usize::try_from(batch_size).unwrap_or(usize::MAX))
+            .map(|start| ArraySubset::new_with_ranges(&[start..(start + batch_size).min(len)]))
+            .collect::<Vec<_>>()
+            .into_iter();
+        self
+    }
+
+    /// Return the number of rows of the arrays.
+    #[must_use]
+    pub fn len(&self) -> u64 {
+        self.arrays.first().map_or(0, |array| array.shape()[0])
+    }
+
+    /// Returns true if the arrays have no rows.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the arrays of the reader.
+    #[must_use]
+    pub fn arrays(&self) -> &[Array<TStorage>] {
+        &self.arrays
+    }
+
+    fn retrieve_record_batch(&self, batch: &ArraySubset) -> Result<RecordBatch, ArrayError> {
+        let columns = self
+            .arrays
+            .iter()
+            .map(|array| array.retrieve_array_subset_arrow_opt(batch, &self.options))
+            .collect::<Result<Vec<_>, _>>()?;
+        RecordBatch::try_new(self.schema.clone(), columns).map_err(|err| arrow_error(&err))
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Iterator for ArrayReader<TStorage> {
+    type Item = Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let batch = self.batches.next()?;
+        match self.retrieve_record_batch(&batch) {
+            Ok(record_batch) => Some(Ok(record_batch)),
+            Err(err) => {
+                // Stop iterating after an error
+                self.batches = Vec::new().into_iter();
+                Some(Err(ArrowError::ExternalError(Box::new(err))))
+            }
+        }
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + 'static> RecordBatchReader
+    for ArrayReader<TStorage>
+{
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::{Float32Array, Int64Array, StringArray};
+
+    use crate::{array::ArrayBuilder, storage::store::MemoryStore};
+
+    use super::*;
+
+    #[test]
+    fn array_arrow_data_types() {
+        for data_type in [
+            DataType::Bool,
+            DataType::Int8,
+            DataType::UInt64,
+            DataType::Float16,
+            DataType::Float64,
+            DataType::RawBits(3),
+            DataType::String,
+            DataType::Binary,
+        ] {
+            let arrow_data_type = data_type_to_arrow(&data_type).unwrap();
+            assert_eq!(data_type_from_arrow(&arrow_data_type).unwrap(), data_type);
+        }
+        assert!(data_type_to_arrow(&DataType::Complex64).is_err());
+        assert_eq!(
+            data_type_from_arrow(&arrow_schema::DataType::Utf8).unwrap(),
+            DataType::String
+        );
+        assert!(data_type_from_arrow(&arrow_schema::DataType::Date32).is_err());
+    }
+
+    #[test]
+    fn array_arrow_store_retrieve() {
+        let store = Arc::new(MemoryStore::new());
+        let array = ArrayBuilder::new(
+            vec![6],
+            DataType::Int64,
+            vec![4].try_into().unwrap(),
+            FillValue::from(-1i64),
+        )
+        .build(store.clone(), "/int")
+        .unwrap();
+        array
+            .store_array_subset_arrow(&[1], &Int64Array::from(vec![Some(1), None, Some(3)]))
+            .unwrap();
+        let retrieved = array
+            .retrieve_array_subset_arrow(&array.subset_all())
+            .unwrap();
+        assert_eq!(
+            retrieved.as_primitive::<Int64Type>(),
+            &Int64Array::from(vec![-1, 1, -1, 3, -1, -1])
+        );
+        assert!(array
+            .store_array_subset_arrow(&[0], &Float32Array::from(vec![1.0]))
+            .is_err());
+
+        let array = ArrayBuilder::new(
+            vec![3],
+            DataType::String,
+            vec![2].try_into().unwrap(),
+            FillValue::from(""),
+        )
+        .build(store.clone(), "/string")
+        .unwrap();
+        array
+            .store_array_subset_arrow(&[0], &StringArray::from(vec![Some("a"), None, Some("cd")]))
+            .unwrap();
+        let retrieved = array
+            .retrieve_array_subset_arrow(&array.subset_all())
+            .unwrap();
+        assert_eq!(
+            retrieved.as_string::<i64>(),
+            &LargeStringArray::from(vec!["a", "", "cd"])
+        );
+
+        let array = ArrayBuilder::new(
+            vec![2, 2],
+            DataType::Int64,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0i64),
+        )
+        .build(store, "/2d")
+        .unwrap();
+        assert!(array.arrow_field().is_err());
+        assert!(array
+            .retrieve_array_subset_arrow(&array.subset_all())
+            .is_err());
+    }
+
+    #[test]
+    fn array_arrow_reader() {
+        let store = Arc::new(MemoryStore::new());
+        let x = ArrayBuilder::new(
+            vec![5],
+            DataType::Float32,
+            vec![2].try_into().unwrap(),
+            FillValue::from(0.0f32),
+        )
+        .build(store.clone(), "/table/x")
+        .unwrap();
+        let name = ArrayBuilder::new(
+            vec![5],
+            DataType::String,
+            vec![3].try_into().unwrap(),
+            FillValue::from(""),
+        )
+        .build(store.clone(), "/table/name")
+        .unwrap();
+        let batch = RecordBatch::try_from_iter([
+            (
+                "x",
+                Arc::new(Float32Array::from(vec![1.0, 2.0, 3.0, 4.0, 5.0])) as ArrayRef,
+            ),
+            (
+                "name",
+                Arc::new(StringArray::from(vec!["a", "b", "c", "d", "e"])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+        let arrays = vec![x, name];
+        store_record_batch(&arrays, 0, &batch).unwrap();
+        assert!(store_record_batch(&arrays[..1], 0, &batch).is_err());
+
+        let reader = ArrayReader::new(arrays).unwrap();
+        assert_eq!(reader.len(), 5);
+        let schema = reader.schema();
+        assert_eq!(schema.field(0).name(), "x");
+        assert_eq!(schema.field(1).name(), "name");
+        assert_eq!(
+            schema.field(1).data_type(),
+            &arrow_schema::DataType::LargeUtf8
+        );
+        let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(
+            batches
+                .iter()
+                .map(RecordBatch::num_rows)
+                .collect::<Vec<_>>(),
+            vec![2, 2, 1]
+        );
+        assert_eq!(
+            batches[1].column(0).as_primitive::<Float32Type>(),
+            &Float32Array::from(vec![3.0, 4.0])
+        );
+        assert_eq!(
+            batches[2].column(1).as_string::<i64>(),
+            &LargeStringArray::from(vec!["e"])
+        );
+
+        let arrays = vec![ArrayBuilder::new(
+            vec![5],
+            DataType::Float32,
+            vec![2].try_into().unwrap(),
+            FillValue::from(0.0f32),
+        )
+        .build(store.clone(), "/table/x")
+        .unwrap()];
+        let reader = ArrayReader::new(arrays)
+            .unwrap()
+            .with_batch_size(NonZeroU64::new(4).unwrap());
+        let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].num_rows(), 4);
+        assert_eq!(batches[1].num_rows(), 1);
+
+        let y = ArrayBuilder::new(
+            vec![4],
+            DataType::Float32,
+            vec![2].try_into().unwrap(),
+            FillValue::from(0.0f32),
+        )
+        .build(store.clone(), "/table/y")
+        .unwrap();
+        let x = ArrayBuilder::new(
+            vec![5],
+            DataType::Float32,
+            vec![2].try_into().unwrap(),
+            FillValue::from(0.0f32),
+        )
+        .build(store, "/table/x")
+        .unwrap();
+        assert!(ArrayReader::new(vec![x, y]).is_err());
+    }
+}
@@ -6,20 +6,25 @@ use unsafe_cell_slice::UnsafeCellSlice;
 use crate::{
     array_subset::ArraySubset,
     config::MetadataRetrieveVersion,
-    node::{meta_key_v2_array, meta_key_v2_attributes, meta_key_v3, NodePath},
-    storage::{AsyncBytes, AsyncReadableStorageTraits, StorageError, StorageHandle},
+    node::{
+        meta_key_v2_array, meta_key_v2_attributes, meta_key_v3, metadata_v2_from_slices, NodePath,
+    },
+    storage::{
+        AsyncBytes, AsyncReadableStorageTraits, MaybeAsyncBytes, StorageError, StorageHandle,
+        StoreKey,
+    },
 };
 
 use super::{
-    array_bytes::{copy_fill_value_into, merge_chunks_vlen},
+    array_bytes::merge_chunks_vlen,
     codec::{
         options::CodecOptions, ArrayToBytesCodecTraits, AsyncArrayPartialDecoderTraits,
         AsyncStoragePartialDecoder,
     },
     concurrency::concurrency_chunks_and_codec,
     element::ElementOwned,
-    Array, ArrayBytes, ArrayCreateError, ArrayError, ArrayMetadata, ArrayMetadataV2,
-    ArrayMetadataV3, ArraySize, DataTypeSize,
+    Array, ArrayBytes, ArrayCreateError, ArrayError, ArrayIndices, ArrayMetadata, ArrayMetadataV2,
+    ArrayMetadataV3, ArraySize, ChunkCache, ChunkCacheDecodedLruSizeLimit, DataTypeSize,
 };
 
 #[cfg(feature = "ndarray")]
@@ -47,29 +52,34 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
         if let MetadataRetrieveVersion::Default | MetadataRetrieveVersion::V3 = version {
             // Try V3
             let key_v3 = meta_key_v3(&node_path);
-            if let Some(metadata) = storage.get(&key_v3).await? {
-                let metadata: ArrayMetadataV3 = serde_json::from_slice(&metadata)
-                    .map_err(|err| StorageError::InvalidMetadata(key_v3, err.to_string()))?;
-                return Self::new_with_metadata(storage, path, ArrayMetadata::V3(metadata));
+            if let (Some(metadata), etag) = storage.get_with_etag(&key_v3).await? {
+                let metadata: ArrayMetadataV3 =
+                    serde_json::from_slice(&metadata).map_err(|err| {
+                        StorageError::InvalidMetadata(key_v3.clone(), err.to_string())
+                    })?;
+                let array = Self::new_with_metadata(storage, path, ArrayMetadata::V3(metadata))?;
+                array.set_metadata_etag(&key_v3, etag);
+                return Ok(array);
             }
         }
 
         if let MetadataRetrieveVersion::Default | MetadataRetrieveVersion::V2 = version {
             // Try V2
             let key_v2 = meta_key_v2_array(&node_path);
-            if let Some(metadata) = storage.get(&key_v2).await? {
-                let mut metadata: ArrayMetadataV2 = serde_json::from_slice(&metadata)
-                    .map_err(|err| StorageError::InvalidMetadata(key_v2, err.to_string()))?;
-
+            if let (Some(metadata), etag) = storage.get_with_etag(&key_v2).await? {
                 let attributes_key = meta_key_v2_attributes(&node_path);
-                let attributes = storage.get(&attributes_key).await?;
-                if let Some(attributes) = attributes {
-                    metadata.attributes = serde_json::from_slice(&attributes).map_err(|err| {
-                        StorageError::InvalidMetadata(attributes_key, err.to_string())
-                    })?;
-                }
+                let (attributes, attributes_etag) = storage.get_with_etag(&attributes_key).await?;
+                let metadata: ArrayMetadataV2 = metadata_v2_from_slices(
+                    &key_v2,
+                    &metadata,
+                    &attributes_key,
+                    attributes.as_deref(),
+                )?;
 
-                return Self::new_with_metadata(storage, path, ArrayMetadata::V2(metadata));
+                let array = Self::new_with_metadata(storage, path, ArrayMetadata::V2(metadata))?;
+                array.set_metadata_etag(&key_v2, etag);
+                array.set_metadata_etag(&attributes_key, attributes_etag);
+                return Ok(array);
             }
         }
 
@@ -137,6 +147,16 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
             .await
     }
 
+    /// Async variant of [`retrieve_chunk_bytes`](Array::retrieve_chunk_bytes).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_retrieve_chunk_bytes(
+        &self,
+        chunk_indices: &[u64],
+    ) -> Result<AsyncBytes, ArrayError> {
+        self.async_retrieve_chunk_bytes_opt(chunk_indices, &CodecOptions::default())
+            .await
+    }
+
     /// Async variant of [`retrieve_chunk_elements`](Array::retrieve_chunk_elements).
     #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
     pub async fn async_retrieve_chunk_elements<T: ElementOwned + Send + Sync>(
@@ -282,6 +302,43 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
         &self,
         chunk_indices: &[u64],
         options: &CodecOptions,
+    ) -> Result<Option<ArrayBytes<'_>>, ArrayError> {
+        if let Some(chunk_cache) = &self.chunk_cache {
+            Ok(self
+                .async_retrieve_chunk_cached(chunk_cache, chunk_indices, options)
+                .await?
+                .map(Arc::unwrap_or_clone))
+        } else {
+            self.async_retrieve_chunk_if_exists_uncached(chunk_indices, options)
+                .await
+        }
+    }
+
+    /// Async variant of [`retrieve_chunk_cached`](Array::retrieve_chunk_cached).
+    async fn async_retrieve_chunk_cached(
+        &self,
+        chunk_cache: &ChunkCacheDecodedLruSizeLimit,
+        chunk_indices: &[u64],
+        options: &CodecOptions,
+    ) -> Result<Option<Arc<ArrayBytes<'static>>>, ArrayError> {
+        if let Some(chunk) = chunk_cache.get(chunk_indices) {
+            return Ok(Some(chunk));
+        }
+        let chunk = self
+            .async_retrieve_chunk_if_exists_uncached(chunk_indices, options)
+            .await?
+            .map(|chunk| Arc::new(chunk.into_owned()));
+        if let Some(chunk) = &chunk {
+            chunk_cache.insert(chunk_indices.to_vec(), chunk.clone());
+        }
+        Ok(chunk)
+    }
+
+    /// Async variant of [`retrieve_chunk_if_exists_uncached`](Array::retrieve_chunk_if_exists_uncached).
+    async fn async_retrieve_chunk_if_exists_uncached(
+        &self,
+        chunk_indices: &[u64],
+        options: &CodecOptions,
     ) -> Result<Option<ArrayBytes<'_>>, ArrayError> {
         if chunk_indices.len() != self.dimensionality() {
             return Err(ArrayError::InvalidChunkGridIndicesError(
@@ -334,6 +391,28 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
         }
     }
 
+    /// Async variant of [`retrieve_chunk_bytes_opt`](Array::retrieve_chunk_bytes_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_retrieve_chunk_bytes_opt(
+        &self,
+        chunk_indices: &[u64],
+        options: &CodecOptions,
+    ) -> Result<AsyncBytes, ArrayError> {
+        let chunk_representation = self.chunk_array_representation(chunk_indices)?;
+        if self.chunk_cache.is_none() && self.codecs().is_passthrough(&chunk_representation) {
+            if let Some(chunk_encoded) = self.async_retrieve_encoded_chunk(chunk_indices).await? {
+                ArrayBytes::new_flen(chunk_encoded.as_ref())
+                    .validate(chunk_representation.num_elements(), self.data_type().size())?;
+                return Ok(chunk_encoded);
+            }
+        }
+        let chunk_bytes = self
+            .async_retrieve_chunk_opt(chunk_indices, options)
+            .await?
+            .into_fixed()?;
+        Ok(AsyncBytes::from(chunk_bytes.into_owned()))
+    }
+
     /// Async variant of [`retrieve_chunk_into`](Array::retrieve_chunk_into).
     async unsafe fn async_retrieve_chunk_into(
         &self,
@@ -357,33 +436,43 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
             .get(&self.chunk_key(chunk_indices))
             .await
             .map_err(ArrayError::StorageError)?;
-        if let Some(chunk_encoded) = chunk_encoded {
-            let chunk_encoded: Vec<u8> = chunk_encoded.into();
-            let chunk_representation = self.chunk_array_representation(chunk_indices)?;
-            unsafe {
-                self.codecs()
-                    .decode_into(
-                        Cow::Owned(chunk_encoded),
-                        &chunk_representation,
-                        output,
-                        output_shape,
-                        output_subset,
-                        options,
-                    )
-                    .map_err(ArrayError::CodecError)
-            }
-        } else {
-            unsafe {
-                copy_fill_value_into(
-                    self.data_type(),
-                    self.fill_value(),
-                    output,
-                    output_shape,
-                    output_subset,
-                )
-                .map_err(ArrayError::CodecError)
-            }
+        unsafe {
+            self.decode_chunk_into(
+                chunk_indices,
+                chunk_encoded,
+                output,
+                output_shape,
+                output_subset,
+                options,
+            )
+        }
+    }
+
+    /// Retrieve the encoded bytes of the chunks at `chunk_indices` with [`get_many`](AsyncReadableStorageTraits::get_many).
+    ///
+    /// The chunks are retrieved in consecutive batches of up to `batch_size` chunks.
+    async fn async_retrieve_encoded_chunks_batched(
+        &self,
+        chunk_indices: &[ArrayIndices],
+        batch_size: usize,
+    ) -> Result<Vec<MaybeAsyncBytes>, StorageError> {
+        if chunk_indices.is_empty() {
+            return Ok(vec![]);
+        }
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_async_readable_transformer(storage_handle)
+            .await?;
+        let keys: Vec<StoreKey> = chunk_indices
+            .iter()
+            .map(|chunk_indices| self.chunk_key(chunk_indices))
+            .collect();
+        let mut chunks_encoded = Vec::with_capacity(keys.len());
+        for keys in keys.chunks(batch_size.max(1)) {
+            chunks_encoded.extend(storage_transformer.get_many(keys).await?);
         }
+        Ok(chunks_encoded)
     }
 
     /// Async variant of [`retrieve_chunk_elements_if_exists_opt`](Array::retrieve_chunk_elements_if_exists_opt).
@@ -472,26 +561,8 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
         chunks: &ArraySubset,
         options: &CodecOptions,
     ) -> Result<Vec<Option<AsyncBytes>>, StorageError> {
-        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
-        let storage_transformer = self
-            .storage_transformers()
-            .create_async_readable_transformer(storage_handle)
-            .await?;
-
-        let retrieve_encoded_chunk = |chunk_indices: Vec<u64>| {
-            let storage_transformer = storage_transformer.clone();
-            async move {
-                storage_transformer
-                    .get(&self.chunk_key(&chunk_indices))
-                    .await
-            }
-        };
-
-        let indices = chunks.indices();
-        let futures = indices.into_iter().map(retrieve_encoded_chunk);
-        futures::stream::iter(futures)
-            .buffered(options.concurrent_target())
-            .try_collect()
+        let chunk_indices: Vec<ArrayIndices> = chunks.indices().into_iter().collect();
+        self.async_retrieve_encoded_chunks_batched(&chunk_indices, options.concurrent_target())
             .await
     }
 
@@ -644,6 +715,46 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
                         {
                             let output =
                                 UnsafeCellSlice::new_from_vec_with_spare_capacity(&mut output);
+
+                            // Retrieve the chunks entirely within the array subset together, then decode them
+                            //   Chunks are retrieved with one get_many per group of `chunk_concurrent_limit` chunks, so only the encoded bytes of a group are held in memory
+                            //   Chunks are retrieved individually if they may be in the chunk cache
+                            let (chunks_whole, chunks_part): (Vec<_>, Vec<_>) =
+                                chunks.indices().into_iter().partition(|chunk_indices| {
+                                    self.chunk_cache.is_none()
+                                        && self.chunk_subset(chunk_indices).is_ok_and(
+                                            |chunk_subset| {
+                                                chunk_subset
+                                                    .overlap(array_subset)
+                                                    .is_ok_and(|overlap| overlap == chunk_subset)
+                                            },
+                                        )
+                                });
+                            for chunks_group in chunks_whole.chunks(chunk_concurrent_limit.max(1)) {
+                                let chunks_group_encoded = self
+                                    .async_retrieve_encoded_chunks_batched(
+                                        chunks_group,
+                                        chunks_group.len(),
+                                    )
+                                    .await?;
+                                for (chunk_indices, chunk_encoded) in
+                                    chunks_group.iter().zip(chunks_group_encoded)
+                                {
+                                    let chunk_subset = self.chunk_subset(chunk_indices)?;
+                                    unsafe {
+                                        self.decode_chunk_into(
+                                            chunk_indices,
+                                            chunk_encoded,
+                                            &output,
+                                            array_subset.shape(),
+                                            &chunk_subset.relative_to(array_subset.start())?,
+                                            &options,
+                                        )?;
+                                    }
+                                }
+                            }
+
+                            // Retrieve the subsets of the chunks partially within the array subset
                             let retrieve_chunk = |chunk_indices: Vec<u64>| {
                                 let options = options.clone();
                                 async move {
@@ -684,7 +795,7 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
                                 }
                             };
 
-                            futures::stream::iter(&chunks.indices())
+                            futures::stream::iter(chunks_part)
                                 .map(Ok)
                                 .try_for_each_concurrent(
                                     Some(chunk_concurrent_limit),
@@ -750,6 +861,23 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
             // Fast path if `chunk_subset` encompasses the whole chunk
             self.async_retrieve_chunk_opt(chunk_indices, options)
                 .await?
+        } else if let Some(chunk_cache) = &self.chunk_cache {
+            if let Some(chunk) = self
+                .async_retrieve_chunk_cached(chunk_cache, chunk_indices, options)
+                .await?
+            {
+                chunk
+                    .extract_array_subset(
+                        chunk_subset,
+                        &chunk_representation.shape_u64(),
+                        self.data_type(),
+                    )?
+                    .into_owned()
+            } else {
+                let array_size =
+                    ArraySize::new(self.data_type().size(), chunk_subset.num_elements());
+                ArrayBytes::new_fill_value(array_size, self.fill_value())
+            }
         } else {
             let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
             let storage_transformer = self
@@ -762,7 +890,11 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
             ));
             self.codecs
                 .clone()
-                .async_partial_decoder(input_handle, &chunk_representation, options)
+                .async_partial_decoder(
+                    input_handle,
+                    &chunk_representation,
+                    &self.partial_decoder_options(options),
+                )
                 .await?
                 .partial_decode(&[chunk_subset.clone()], options)
                 .await?
@@ -790,7 +922,21 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
             ));
         }
 
-        if chunk_subset.start().iter().all(|&o| o == 0)
+        if let Some(chunk_cache) = &self.chunk_cache {
+            let chunk = self
+                .async_retrieve_chunk_cached(chunk_cache, chunk_indices, options)
+                .await?;
+            unsafe {
+                self.copy_cached_chunk_subset_into(
+                    chunk.as_deref(),
+                    &chunk_representation.shape_u64(),
+                    chunk_subset,
+                    output,
+                    output_shape,
+                    output_subset,
+                )
+            }
+        } else if chunk_subset.start().iter().all(|&o| o == 0)
             && chunk_subset.shape() == chunk_representation.shape_u64()
         {
             // Fast path if `chunk_subset` encompasses the whole chunk
@@ -818,7 +964,11 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
             unsafe {
                 self.codecs
                     .clone()
-                    .async_partial_decoder(input_handle, &chunk_representation, options)
+                    .async_partial_decoder(
+                        input_handle,
+                        &chunk_representation,
+                        &self.partial_decoder_options(options),
+                    )
                     .await?
                     .partial_decode_into(chunk_subset, output, output_shape, output_subset, options)
                     .await?;
@@ -877,7 +1027,11 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
         Ok(self
             .codecs
             .clone()
-            .async_partial_decoder(input_handle, &chunk_representation, options)
+            .async_partial_decoder(
+                input_handle,
+                &chunk_representation,
+                &self.partial_decoder_options(options),
+            )
             .await?)
     }
 }
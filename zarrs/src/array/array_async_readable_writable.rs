@@ -1,15 +1,54 @@
-use futures::{StreamExt, TryStreamExt};
+use std::{borrow::Cow, sync::Arc};
+
+use futures::{Stream, StreamExt, TryStreamExt};
 
 use crate::{
-    array::ArrayBytes, array_subset::ArraySubset, storage::AsyncReadableWritableStorageTraits,
+    array::ArrayBytes,
+    array_subset::ArraySubset,
+    node::{async_update_attributes, AttributesUpdateOptions},
+    storage::{AsyncBytes, AsyncReadableWritableStorageTraits, StorageError, StorageHandle},
 };
 
 use super::{
-    array_bytes::update_array_bytes, codec::options::CodecOptions,
-    concurrency::concurrency_chunks_and_codec, Array, ArrayError, Element,
+    array_bytes::update_array_bytes,
+    codec::{
+        options::CodecOptions, ArrayToBytesCodecTraits, AsyncArrayPartialEncoderTraits,
+        AsyncStoragePartialDecoder, AsyncStoragePartialEncoder,
+    },
+    concurrency::concurrency_chunks_and_codec,
+    Array, ArrayError, ArraySize, Element,
 };
 
 impl<TStorage: ?Sized + AsyncReadableWritableStorageTraits + 'static> Array<TStorage> {
+    /// Async variant of [`update_attributes`](Array::update_attributes).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_update_attributes(
+        &mut self,
+        update: impl FnOnce(&mut serde_json::Map<String, serde_json::Value>),
+    ) -> Result<(), StorageError> {
+        self.async_update_attributes_opt(update, &AttributesUpdateOptions::default())
+            .await
+    }
+
+    /// Async variant of [`update_attributes_opt`](Array::update_attributes_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_update_attributes_opt(
+        &mut self,
+        update: impl FnOnce(&mut serde_json::Map<String, serde_json::Value>),
+        options: &AttributesUpdateOptions,
+    ) -> Result<(), StorageError> {
+        let (key, document, changes) = self.attributes_update(update)?;
+        if changes.is_empty() {
+            return Ok(());
+        }
+        let storage_handle = StorageHandle::new(self.storage.clone());
+        let (attributes, etag) =
+            async_update_attributes(&storage_handle, &key, &document, &changes, options).await?;
+        *self.attributes_mut() = attributes;
+        self.set_metadata_etag(&key, etag);
+        Ok(())
+    }
+
     /// Async variant of [`store_chunk_subset`](Array::store_chunk_subset).
     #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
     pub async fn async_store_chunk_subset<'a>(
@@ -91,6 +130,21 @@ impl<TStorage: ?Sized + AsyncReadableWritableStorageTraits + 'static> Array<TSto
         .await
     }
 
+    /// Async variant of [`store_array_subset_from_iter`](Array::store_array_subset_from_iter) consuming the elements of a stream.
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_store_array_subset_from_stream<T: Element + Send + Sync>(
+        &self,
+        array_subset: &ArraySubset,
+        subset_elements: impl Stream<Item = T> + Send,
+    ) -> Result<(), ArrayError> {
+        self.async_store_array_subset_from_stream_opt(
+            array_subset,
+            subset_elements,
+            &CodecOptions::default(),
+        )
+        .await
+    }
+
     #[cfg(feature = "ndarray")]
     /// Async variant of [`store_array_subset_ndarray`](Array::store_array_subset_ndarray).
     #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
@@ -150,7 +204,29 @@ impl<TStorage: ?Sized + AsyncReadableWritableStorageTraits + 'static> Array<TSto
             // let mutex = self.storage.mutex(&key).await?;
             // let _lock = mutex.lock();
 
-            // TODO: Add async partial encoding
+            if options.experimental_partial_encoding() {
+                let partial_encoder = self.async_partial_encoder(chunk_indices, options).await?;
+                partial_encoder
+                    .partial_encode(&[(chunk_subset, chunk_subset_bytes)], options)
+                    .await?;
+                self.invalidate_shard_index(chunk_indices);
+                self.invalidate_chunk_cache(chunk_indices);
+                return Ok(());
+            }
+
+            if self.storage.supports_set_if_match() {
+                self.async_store_chunk_subset_if_match(
+                    chunk_indices,
+                    &chunk_shape,
+                    chunk_subset,
+                    &chunk_subset_bytes,
+                    options,
+                )
+                .await?;
+                self.invalidate_shard_index(chunk_indices);
+                self.invalidate_chunk_cache(chunk_indices);
+                return Ok(());
+            }
 
             // Decode the entire chunk
             let chunk_bytes_old = self
@@ -174,6 +250,83 @@ impl<TStorage: ?Sized + AsyncReadableWritableStorageTraits + 'static> Array<TSto
         }
     }
 
+    /// Async variant of [`store_chunk_subset_if_match`](Array::store_chunk_subset_if_match).
+    async fn async_store_chunk_subset_if_match(
+        &self,
+        chunk_indices: &[u64],
+        chunk_shape: &[u64],
+        chunk_subset: &ArraySubset,
+        chunk_subset_bytes: &ArrayBytes<'_>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer_read = self
+            .storage_transformers()
+            .create_async_readable_transformer(storage_handle.clone())
+            .await?;
+        let storage_transformer_write = self
+            .storage_transformers()
+            .create_async_writable_transformer(storage_handle)
+            .await?;
+        let chunk_key = self.chunk_key(chunk_indices);
+        let chunk_representation = self.chunk_array_representation(chunk_indices)?;
+
+        // Decode the entire chunk
+        let (chunk_encoded, etag) = storage_transformer_read.get_with_etag(&chunk_key).await?;
+        let chunk_exists = chunk_encoded.is_some();
+        let chunk_bytes_old = if let Some(chunk_encoded) = chunk_encoded {
+            let chunk_encoded: Vec<u8> = chunk_encoded.into();
+            self.codecs()
+                .decode(Cow::Owned(chunk_encoded), &chunk_representation, options)
+                .map_err(ArrayError::CodecError)?
+        } else {
+            let array_size =
+                ArraySize::new(self.data_type().size(), chunk_representation.num_elements());
+            ArrayBytes::new_fill_value(array_size, self.fill_value())
+        };
+        chunk_bytes_old.validate(chunk_shape.iter().product(), self.data_type().size())?;
+
+        // Update the chunk
+        let chunk_bytes_new = unsafe {
+            update_array_bytes(
+                chunk_bytes_old,
+                chunk_shape,
+                chunk_subset,
+                chunk_subset_bytes,
+                self.data_type().size(),
+            )
+        };
+
+        // Store the updated chunk
+        let chunk_is_fill_value =
+            !options.store_empty_chunks() && chunk_bytes_new.is_fill_value(self.fill_value());
+        if chunk_is_fill_value && etag.is_none() {
+            // The entity tag of an existing chunk is unknown, so it is erased unconditionally
+            if chunk_exists {
+                self.async_erase_chunk(chunk_indices).await?;
+            }
+            return Ok(());
+        }
+        let chunk_encoded = self
+            .codecs()
+            .encode(chunk_bytes_new, &chunk_representation, options)
+            .map_err(ArrayError::CodecError)?;
+        let chunk_encoded = AsyncBytes::from(chunk_encoded.into_owned());
+        match storage_transformer_write
+            .set_if_match(&chunk_key, chunk_encoded.clone(), etag.as_ref())
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(StorageError::Unsupported(_)) if chunk_is_fill_value => {
+                Ok(self.async_erase_chunk(chunk_indices).await?)
+            }
+            Err(StorageError::Unsupported(_)) => Ok(storage_transformer_write
+                .set(&chunk_key, chunk_encoded)
+                .await?),
+            Err(err) => Err(err.into()),
+        }
+    }
+
     /// Async variant of [`store_chunk_subset_elements_opt`](Array::store_chunk_subset_elements_opt).
     #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
     pub async fn async_store_chunk_subset_elements_opt<T: Element + Send + Sync>(
@@ -325,6 +478,53 @@ impl<TStorage: ?Sized + AsyncReadableWritableStorageTraits + 'static> Array<TSto
             .await
     }
 
+    /// Async variant of [`store_array_subset_from_iter_opt`](Array::store_array_subset_from_iter_opt) consuming the elements of a stream.
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_store_array_subset_from_stream_opt<T: Element + Send + Sync>(
+        &self,
+        array_subset: &ArraySubset,
+        subset_elements: impl Stream<Item = T> + Send,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        T::validate_data_type(self.data_type())?;
+        if array_subset.dimensionality() != self.dimensionality()
+            || !array_subset.inbounds(self.shape())
+        {
+            return Err(ArrayError::InvalidArraySubset(
+                array_subset.clone(),
+                self.shape().to_vec(),
+            ));
+        }
+
+        let num_elements = array_subset.num_elements_usize();
+        let mut subset_elements = std::pin::pin!(subset_elements);
+        let mut num_consumed = 0;
+        for slab in self.array_subset_slabs(array_subset)? {
+            let slab_elements: Vec<T> = subset_elements
+                .as_mut()
+                .take(slab.num_elements_usize())
+                .collect()
+                .await;
+            num_consumed += slab_elements.len();
+            if slab_elements.len() != slab.num_elements_usize() {
+                return Err(ArrayError::InvalidDataShape(
+                    vec![num_consumed],
+                    vec![num_elements],
+                ));
+            }
+            self.async_store_array_subset_elements_opt(&slab, &slab_elements, options)
+                .await?;
+        }
+        if subset_elements.next().await.is_some() {
+            // The remaining elements are not consumed
+            return Err(ArrayError::InvalidDataShape(
+                vec![num_elements + 1],
+                vec![num_elements],
+            ));
+        }
+        Ok(())
+    }
+
     #[cfg(feature = "ndarray")]
     /// Async variant of [`store_array_subset_ndarray_opt`](Array::store_array_subset_ndarray_opt).
     #[allow(clippy::missing_errors_doc)]
@@ -346,4 +546,41 @@ impl<TStorage: ?Sized + AsyncReadableWritableStorageTraits + 'static> Array<TSto
         self.async_store_array_subset_elements_opt(&subset, &subset_array, options)
             .await
     }
+
+    /// Async variant of [`partial_encoder`](Array::partial_encoder).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_partial_encoder(
+        &self,
+        chunk_indices: &[u64],
+        options: &CodecOptions,
+    ) -> Result<Arc<dyn AsyncArrayPartialEncoderTraits>, ArrayError> {
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+
+        // Input
+        let storage_transformer_read = self
+            .storage_transformers()
+            .create_async_readable_transformer(storage_handle.clone())
+            .await?;
+        let input_handle = Arc::new(AsyncStoragePartialDecoder::new(
+            storage_transformer_read,
+            self.chunk_key(chunk_indices),
+        ));
+        let chunk_representation = self.chunk_array_representation(chunk_indices)?;
+
+        // Output
+        let storage_transformer_write = self
+            .storage_transformers()
+            .create_async_writable_transformer(storage_handle)
+            .await?;
+        let output_handle = Arc::new(AsyncStoragePartialEncoder::new(
+            storage_transformer_write,
+            self.chunk_key(chunk_indices),
+        ));
+
+        Ok(self
+            .codecs
+            .clone()
+            .async_partial_encoder(input_handle, output_handle, &chunk_representation, options)
+            .await?)
+    }
 }
@@ -7,7 +7,7 @@ use crate::{
     array_subset::ArraySubset,
     config::{global_config, MetadataEraseVersion},
     node::{meta_key_v2_array, meta_key_v2_attributes, meta_key_v3},
-    storage::{AsyncBytes, AsyncWritableStorageTraits, StorageError, StorageHandle},
+    storage::{AsyncBytes, AsyncWritableStorageTraits, StorageError, StorageHandle, StoreKey},
 };
 
 use super::{
@@ -46,7 +46,8 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits + 'static> Array<TStorage> {
                 let key = meta_key_v3(path);
                 let json = serde_json::to_vec_pretty(&metadata)
                     .map_err(|err| StorageError::InvalidMetadata(key.clone(), err.to_string()))?;
-                storage_transformer.set(&key, json.into()).await
+                self.async_store_metadata_key(&*storage_transformer, &key, json.into())
+                    .await
             }
             ArrayMetadata::V2(metadata) => {
                 let mut metadata = metadata.clone();
@@ -57,8 +58,7 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits + 'static> Array<TStorage> {
                     let json = serde_json::to_vec_pretty(&metadata.attributes).map_err(|err| {
                         StorageError::InvalidMetadata(key.clone(), err.to_string())
                     })?;
-                    storage_transformer
-                        .set(&meta_key_v2_attributes(path), json.into())
+                    self.async_store_metadata_key(&*storage_transformer, &key, json.into())
                         .await?;
 
                     metadata.attributes = serde_json::Map::default();
@@ -68,9 +68,39 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits + 'static> Array<TStorage> {
                 let key = meta_key_v2_array(path);
                 let json = serde_json::to_vec_pretty(&metadata)
                     .map_err(|err| StorageError::InvalidMetadata(key.clone(), err.to_string()))?;
-                storage_transformer.set(&key, json.into()).await
+                self.async_store_metadata_key(&*storage_transformer, &key, json.into())
+                    .await
+            }
+        }
+    }
+
+    /// Async variant of [`store_metadata_key`](Array::store_metadata_key).
+    async fn async_store_metadata_key(
+        &self,
+        storage: &dyn AsyncWritableStorageTraits,
+        key: &StoreKey,
+        value: AsyncBytes,
+    ) -> Result<(), StorageError> {
+        if storage.supports_set_if_match() {
+            // If the entity tag is unknown, the key is only written if it does not exist so the entity tag of the initial write is known
+            let etag = self.metadata_etag(key);
+            match storage
+                .set_if_match(key, value.clone(), etag.as_ref())
+                .await
+            {
+                Ok(etag) => {
+                    self.set_metadata_etag(key, etag);
+                    return Ok(());
+                }
+                // The key exists but its entity tag is unknown, e.g. the array was created over existing metadata
+                Err(StorageError::PreconditionFailed(_)) if etag.is_none() => {}
+                Err(StorageError::Unsupported(_)) => {}
+                Err(err) => return Err(err),
             }
         }
+        storage.set(key, value).await?;
+        self.set_metadata_etag(key, None);
+        Ok(())
     }
 
     /// Async variant of [`store_chunk`](Array::store_chunk).
@@ -199,7 +229,11 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits + 'static> Array<TStorage> {
             .await?;
         storage_transformer
             .erase(&self.chunk_key(chunk_indices))
-            .await
+            .await?;
+        self.invalidate_shard_index(chunk_indices);
+        self.invalidate_chunk_cache(chunk_indices);
+        self.invalidate_cached_statistics(chunk_indices);
+        Ok(())
     }
 
     /// Async variant of [`erase_chunks`](Array::erase_chunks).
@@ -215,7 +249,11 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits + 'static> Array<TStorage> {
             async move {
                 storage_transformer
                     .erase(&self.chunk_key(&chunk_indices))
-                    .await
+                    .await?;
+                self.invalidate_shard_index(&chunk_indices);
+                self.invalidate_chunk_cache(&chunk_indices);
+                self.invalidate_cached_statistics(&chunk_indices);
+                Ok::<_, StorageError>(())
             }
         };
         futures::stream::iter(chunks.indices().into_iter())
@@ -250,6 +288,7 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits + 'static> Array<TStorage> {
         if is_fill_value {
             self.async_erase_chunk(chunk_indices).await?;
         } else {
+            self.update_cached_statistics(chunk_indices, &chunk_bytes)?;
             let chunk_encoded = self
                 .codecs()
                 .encode(chunk_bytes, &chunk_array_representation, options)
@@ -257,6 +296,8 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits + 'static> Array<TStorage> {
             let chunk_encoded = AsyncBytes::from(chunk_encoded.to_vec());
             unsafe { self.async_store_encoded_chunk(chunk_indices, chunk_encoded) }.await?;
         }
+        self.invalidate_shard_index(chunk_indices);
+        self.invalidate_chunk_cache(chunk_indices);
         Ok(())
     }
 
@@ -275,6 +316,8 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits + 'static> Array<TStorage> {
         storage_transformer
             .set(&self.chunk_key(chunk_indices), encoded_chunk_bytes)
             .await?;
+        self.invalidate_shard_index(chunk_indices);
+        self.invalidate_chunk_cache(chunk_indices);
         Ok(())
     }
 
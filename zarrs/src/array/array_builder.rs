@@ -347,6 +347,12 @@ impl ArrayBuilder {
             dimension_names: self.dimension_names.clone(),
             // additional_fields: self.additional_fields.clone(),
             metadata: array_metadata,
+            metadata_etags: std::sync::Mutex::default(),
+            chunk_cache: None,
+            #[cfg(feature = "sharding")]
+            shard_index_cache: None,
+            coordinate_labels: std::sync::Mutex::default(),
+            statistics_cache: None,
         })
     }
 
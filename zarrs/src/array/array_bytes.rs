@@ -512,6 +512,59 @@ pub(crate) fn extract_decoded_regions_vlen<'a>(
     Ok(out)
 }
 
+/// Concatenate array bytes of a `data_type_size`.
+///
+/// This function is used internally by [`retrieve_by_indices_opt`](crate::array::Array::retrieve_by_indices_opt).
+pub(crate) fn concatenate_array_bytes<'a>(
+    array_bytes: Vec<ArrayBytes<'_>>,
+    data_type_size: DataTypeSize,
+) -> Result<ArrayBytes<'a>, CodecError> {
+    let size = array_bytes.iter().map(ArrayBytes::size).sum();
+    let mut bytes = Vec::with_capacity(size);
+    match data_type_size {
+        DataTypeSize::Fixed(_) => {
+            for array_bytes in array_bytes {
+                bytes.extend_from_slice(&array_bytes.into_fixed()?);
+            }
+            Ok(ArrayBytes::new_flen(bytes))
+        }
+        DataTypeSize::Variable => {
+            let mut offsets = vec![0];
+            for array_bytes in array_bytes {
+                let (array_bytes, array_offsets) = array_bytes.into_variable()?;
+                let offset = bytes.len();
+                bytes.extend_from_slice(&array_bytes);
+                offsets.extend(array_offsets.iter().skip(1).map(|o| offset + o));
+            }
+            Ok(ArrayBytes::new_vlen(bytes, offsets))
+        }
+    }
+}
+
+/// Split array bytes into the bytes of each element.
+///
+/// This function is used internally by [`store_by_indices_opt`](crate::array::Array::store_by_indices_opt).
+/// The caller must validate `array_bytes` beforehand.
+pub(crate) fn split_array_bytes_elements<'a>(
+    array_bytes: &'a ArrayBytes<'_>,
+    data_type_size: DataTypeSize,
+) -> Vec<ArrayBytes<'a>> {
+    match (array_bytes, data_type_size) {
+        (ArrayBytes::Fixed(bytes), DataTypeSize::Fixed(data_type_size)) => bytes
+            .chunks_exact(data_type_size)
+            .map(ArrayBytes::from)
+            .collect(),
+        (ArrayBytes::Variable(bytes, offsets), DataTypeSize::Variable) => offsets
+            .iter()
+            .tuple_windows()
+            .map(|(&curr, &next)| ArrayBytes::new_vlen(&bytes[curr..next], vec![0, next - curr]))
+            .collect(),
+        (_, _) => {
+            unreachable!("Validation should occur outside of this function")
+        }
+    }
+}
+
 /// Decode the fill value into a subset of a preallocated output.
 ///
 /// This method is intended for internal use by Array.
@@ -0,0 +1,402 @@
+use crate::{
+    array_subset::{ArraySubset, IncompatibleDimensionalityError},
+    storage::ReadableStorageTraits,
+};
+
+use super::{
+    chunk_shape_to_array_shape,
+    codec::{CodecError, CodecOptions},
+    Array, ArrayBytes, ArrayError, ArrayShape, DataType, ElementOwned, RawBytes,
+};
+
+/// The bytes of a fixed length array with shape, strides, and data type metadata.
+///
+/// An [`ArrayBytesView`] is a lightweight alternative to an `ndarray` array for shape-aware results that does not require the `ndarray` feature.
+/// The element at `indices` starts at byte offset `sum(indices[i] * strides[i])` of [`bytes`](ArrayBytesView::bytes), like a `NumPy` array.
+/// Elements are in native endianness.
+///
+/// Bytes in standard (C-contiguous) layout can be passed directly to crates expecting a raw buffer and shape, such as the `image` crate.
+/// Use [`into_standard_layout`](ArrayBytesView::into_standard_layout) or [`into_bytes`](ArrayBytesView::into_bytes) after [`transpose`](ArrayBytesView::transpose) to copy the elements into standard layout.
+///
+/// Created with [`Array::retrieve_chunk_bytes_view`] or [`Array::retrieve_array_subset_bytes_view`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArrayBytesView<'a> {
+    bytes: RawBytes<'a>,
+    shape: ArrayShape,
+    /// The byte strides of each dimension.
+    strides: Vec<usize>,
+    data_type: DataType,
+}
+
+/// Return the byte strides of a C-contiguous array with `shape` and elements of `data_type_size` bytes.
+fn standard_strides(shape: &[u64], data_type_size: usize) -> Vec<usize> {
+    let mut strides = vec![data_type_size; shape.len()];
+    for i in (1..shape.len()).rev() {
+        strides[i - 1] = strides[i] * usize::try_from(shape[i]).unwrap();
+    }
+    strides
+}
+
+impl<'a> ArrayBytesView<'a> {
+    /// Create a new view of `bytes` in standard (C-contiguous) layout.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - `data_type` does not have a fixed size, or
+    ///  - the length of `bytes` does not match `shape` and `data_type`.
+    pub fn new(
+        bytes: impl Into<RawBytes<'a>>,
+        shape: ArrayShape,
+        data_type: DataType,
+    ) -> Result<Self, ArrayError> {
+        let data_type_size = data_type
+            .fixed_size()
+            .ok_or(CodecError::ExpectedFixedLengthBytes)?;
+        let strides = standard_strides(&shape, data_type_size);
+        Self::new_with_strides(bytes, shape, strides, data_type)
+    }
+
+    /// Create a new view of `bytes` with byte `strides`.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - `data_type` does not have a fixed size,
+    ///  - the dimensionality of `strides` does not match `shape`, or
+    ///  - an element is outside of `bytes`.
+    ///
+    /// # Panics
+    /// Panics if any dimension in `shape` is `usize::MAX` or larger.
+    pub fn new_with_strides(
+        bytes: impl Into<RawBytes<'a>>,
+        shape: ArrayShape,
+        strides: Vec<usize>,
+        data_type: DataType,
+    ) -> Result<Self, ArrayError> {
+        let bytes = bytes.into();
+        let data_type_size = data_type
+            .fixed_size()
+            .ok_or(CodecError::ExpectedFixedLengthBytes)?;
+        if strides.len() != shape.len() {
+            return Err(IncompatibleDimensionalityError::new(strides.len(), shape.len()).into());
+        }
+        if !shape.contains(&0) {
+            let end = std::iter::zip(&shape, &strides)
+                .map(|(&shape, stride)| (usize::try_from(shape).unwrap() - 1) * stride)
+                .sum::<usize>()
+                + data_type_size;
+            if end > bytes.len() {
+                return Err(ArrayError::InvalidBytesInputSize(bytes.len(), end as u64));
+            }
+        }
+        Ok(Self {
+            bytes,
+            shape,
+            strides,
+            data_type,
+        })
+    }
+
+    /// Create a new view of fixed length [`ArrayBytes`] in standard (C-contiguous) layout.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if `bytes` are variable length or a [`new`](ArrayBytesView::new) error condition is met.
+    pub fn from_array_bytes(
+        bytes: ArrayBytes<'a>,
+        shape: ArrayShape,
+        data_type: DataType,
+    ) -> Result<Self, ArrayError> {
+        Self::new(bytes.into_fixed()?, shape, data_type)
+    }
+
+    /// Return the underlying bytes.
+    ///
+    /// The bytes are only in C-contiguous order if the view [`is_standard_layout`](ArrayBytesView::is_standard_layout).
+    #[must_use]
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Return a pointer to the underlying bytes.
+    #[must_use]
+    pub fn as_ptr(&self) -> *const u8 {
+        self.bytes.as_ptr()
+    }
+
+    /// Return the shape of the view.
+    #[must_use]
+    pub fn shape(&self) -> &[u64] {
+        &self.shape
+    }
+
+    /// Return the byte strides of each dimension of the view.
+    #[must_use]
+    pub fn strides(&self) -> &[usize] {
+        &self.strides
+    }
+
+    /// Return the data type of the elements of the view.
+    #[must_use]
+    pub const fn data_type(&self) -> &DataType {
+        &self.data_type
+    }
+
+    /// Return the dimensionality of the view.
+    #[must_use]
+    pub fn dimensionality(&self) -> usize {
+        self.shape.len()
+    }
+
+    /// Return the number of elements of the view.
+    #[must_use]
+    pub fn num_elements(&self) -> u64 {
+        self.shape.iter().product()
+    }
+
+    /// Returns true if the elements of the view are contiguous and in C order.
+    #[must_use]
+    pub fn is_standard_layout(&self) -> bool {
+        let standard_strides = standard_strides(&self.shape, self.data_type_size());
+        self.num_elements() == 0
+            || itertools::izip!(&self.shape, &self.strides, &standard_strides)
+                .all(|(&shape, stride, standard_stride)| shape == 1 || stride == standard_stride)
+    }
+
+    /// Return a view with its dimensions permuted, without copying.
+    ///
+    /// Dimension `i` of the returned view is dimension `axes[i]` of this view.
+    ///
+    /// # Errors
+    /// Returns [`ArrayError::InvalidPermutation`] if `axes` is not a permutation of the dimensions of the view.
+    pub fn transpose(self, axes: &[usize]) -> Result<Self, ArrayError> {
+        if axes.len() != self.dimensionality() {
+            return Err(ArrayError::InvalidPermutation(axes.to_vec()));
+        }
+        let mut permuted = vec![false; self.dimensionality()];
+        for &axis in axes {
+            match permuted.get_mut(axis) {
+                Some(permuted) if !*permuted => *permuted = true,
+                _ => return Err(ArrayError::InvalidPermutation(axes.to_vec())),
+            }
+        }
+        Ok(Self {
+            shape: axes.iter().map(|&axis| self.shape[axis]).collect(),
+            strides: axes.iter().map(|&axis| self.strides[axis]).collect(),
+            ..self
+        })
+    }
+
+    /// Return a view in standard (C-contiguous) layout.
+    ///
+    /// The bytes are only copied if the view is not already in standard layout.
+    ///
+    /// # Panics
+    /// Panics if any dimension of the view is `usize::MAX` or larger.
+    #[must_use]
+    pub fn into_standard_layout(self) -> Self {
+        if self.is_standard_layout() {
+            return self;
+        }
+        let data_type_size = self.data_type_size();
+        let num_elements = usize::try_from(self.num_elements()).unwrap();
+        let mut bytes = Vec::with_capacity(num_elements * data_type_size);
+        let mut indices = vec![0u64; self.dimensionality()];
+        for _ in 0..num_elements {
+            let offset = std::iter::zip(&indices, &self.strides)
+                .map(|(&index, stride)| usize::try_from(index).unwrap() * stride)
+                .sum::<usize>();
+            bytes.extend_from_slice(&self.bytes[offset..offset + data_type_size]);
+            for (index, &shape) in std::iter::zip(indices.iter_mut(), &self.shape).rev() {
+                *index += 1;
+                if *index < shape {
+                    break;
+                }
+                *index = 0;
+            }
+        }
+        Self {
+            bytes: bytes.into(),
+            strides: standard_strides(&self.shape, data_type_size),
+            ..self
+        }
+    }
+
+    /// Return the bytes of the elements of the view in C-contiguous order.
+    ///
+    /// See [`into_standard_layout`](ArrayBytesView::into_standard_layout).
+    #[must_use]
+    pub fn into_bytes(self) -> RawBytes<'a> {
+        self.into_standard_layout().bytes
+    }
+
+    /// Convert into an owned [`ArrayBytesView`].
+    #[must_use]
+    pub fn into_owned<'b>(self) -> ArrayBytesView<'b> {
+        ArrayBytesView {
+            bytes: self.bytes.into_owned().into(),
+            shape: self.shape,
+            strides: self.strides,
+            data_type: self.data_type,
+        }
+    }
+
+    /// Return the elements of the view in C-contiguous order.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if `T` is incompatible with the data type of the view.
+    pub fn to_elements<T: ElementOwned>(&self) -> Result<Vec<T>, ArrayError> {
+        let bytes = self.clone().into_bytes();
+        T::from_array_bytes(&self.data_type, ArrayBytes::new_flen(bytes))
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Return the elements of the view as an [`ndarray::ArrayD`].
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if `T` is incompatible with the data type of the view.
+    pub fn to_ndarray<T: ElementOwned>(&self) -> Result<ndarray::ArrayD<T>, ArrayError> {
+        super::elements_to_ndarray(&self.shape, self.to_elements()?)
+    }
+
+    fn data_type_size(&self) -> usize {
+        self.data_type
+            .fixed_size()
+            .expect("the data type of an ArrayBytesView has a fixed size")
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
+    /// Read and decode the chunk at `chunk_indices` into an [`ArrayBytesView`] with the chunk shape.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - the data type of the array does not have a fixed size, or
+    ///  - a [`retrieve_chunk`](Array::retrieve_chunk) error condition is met.
+    pub fn retrieve_chunk_bytes_view(
+        &self,
+        chunk_indices: &[u64],
+    ) -> Result<ArrayBytesView<'_>, ArrayError> {
+        self.retrieve_chunk_bytes_view_opt(chunk_indices, &CodecOptions::default())
+    }
+
+    /// Explicit options version of [`retrieve_chunk_bytes_view`](Array::retrieve_chunk_bytes_view).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_chunk_bytes_view_opt(
+        &self,
+        chunk_indices: &[u64],
+        options: &CodecOptions,
+    ) -> Result<ArrayBytesView<'_>, ArrayError> {
+        let chunk_shape = chunk_shape_to_array_shape(&self.chunk_shape(chunk_indices)?);
+        let bytes = self.retrieve_chunk_opt(chunk_indices, options)?;
+        ArrayBytesView::from_array_bytes(bytes, chunk_shape, self.data_type().clone())
+    }
+
+    /// Read and decode the `array_subset` of array into an [`ArrayBytesView`] with the shape of `array_subset`.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - the data type of the array does not have a fixed size, or
+    ///  - a [`retrieve_array_subset`](Array::retrieve_array_subset) error condition is met.
+    pub fn retrieve_array_subset_bytes_view(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<ArrayBytesView<'_>, ArrayError> {
+        self.retrieve_array_subset_bytes_view_opt(array_subset, &CodecOptions::default())
+    }
+
+    /// Explicit options version of [`retrieve_array_subset_bytes_view`](Array::retrieve_array_subset_bytes_view).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_array_subset_bytes_view_opt(
+        &self,
+        array_subset: &ArraySubset,
+        options: &CodecOptions,
+    ) -> Result<ArrayBytesView<'_>, ArrayError> {
+        let bytes = self.retrieve_array_subset_opt(array_subset, options)?;
+        ArrayBytesView::from_array_bytes(
+            bytes,
+            array_subset.shape().to_vec(),
+            self.data_type().clone(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        array::{ArrayBuilder, FillValue},
+        storage::store::MemoryStore,
+    };
+
+    use super::*;
+
+    #[test]
+    fn array_bytes_view() {
+        let bytes: Vec<u8> = (0..6).collect();
+        let view = ArrayBytesView::new(bytes.as_slice(), vec![2, 3], DataType::UInt8).unwrap();
+        assert_eq!(view.shape(), &[2, 3]);
+        assert_eq!(view.strides(), &[3, 1]);
+        assert_eq!(view.num_elements(), 6);
+        assert!(view.is_standard_layout());
+        assert_eq!(view.as_ptr(), bytes.as_ptr());
+        assert!(ArrayBytesView::new(bytes.as_slice(), vec![2, 4], DataType::UInt8).is_err());
+        assert!(ArrayBytesView::new(bytes.as_slice(), vec![6], DataType::String).is_err());
+
+        let view = view.transpose(&[1, 0]).unwrap();
+        assert_eq!(view.shape(), &[3, 2]);
+        assert_eq!(view.strides(), &[1, 3]);
+        assert!(!view.is_standard_layout());
+        assert_eq!(view.to_elements::<u8>().unwrap(), vec![0, 3, 1, 4, 2, 5]);
+        assert!(view.to_elements::<u16>().is_err());
+        assert!(view.clone().transpose(&[0, 0]).is_err());
+        let view = view.into_standard_layout();
+        assert!(view.is_standard_layout());
+        assert_eq!(view.strides(), &[2, 1]);
+        assert_eq!(view.bytes(), &[0, 3, 1, 4, 2, 5]);
+
+        let view =
+            ArrayBytesView::new_with_strides(bytes.as_slice(), vec![3], vec![2], DataType::UInt8)
+                .unwrap();
+        assert_eq!(view.into_bytes().as_ref(), &[0, 2, 4]);
+        assert!(ArrayBytesView::new_with_strides(
+            bytes.as_slice(),
+            vec![4],
+            vec![2],
+            DataType::UInt8
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn array_retrieve_bytes_view() {
+        let store = Arc::new(MemoryStore::new());
+        let array = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::UInt16,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u16),
+        )
+        .build(store, "/")
+        .unwrap();
+        array
+            .store_array_subset_elements(&array.subset_all(), &(0..16).collect::<Vec<u16>>())
+            .unwrap();
+
+        let view = array.retrieve_chunk_bytes_view(&[0, 1]).unwrap();
+        assert_eq!(view.shape(), &[2, 2]);
+        assert_eq!(view.strides(), &[4, 2]);
+        assert_eq!(view.data_type(), &DataType::UInt16);
+        assert_eq!(view.to_elements::<u16>().unwrap(), vec![2, 3, 6, 7]);
+
+        let view = array
+            .retrieve_array_subset_bytes_view(&ArraySubset::new_with_ranges(&[1..4, 0..2]))
+            .unwrap();
+        assert_eq!(view.shape(), &[3, 2]);
+        assert_eq!(view.to_elements::<u16>().unwrap(), vec![4, 5, 8, 9, 12, 13]);
+        #[cfg(feature = "ndarray")]
+        assert_eq!(
+            view.to_ndarray::<u16>().unwrap(),
+            ndarray::array![[4, 5], [8, 9], [12, 13]].into_dyn()
+        );
+    }
+}
@@ -0,0 +1,371 @@
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    sync::{Mutex, PoisonError},
+};
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
+
+use crate::{array_subset::ArraySubset, storage::ReadableStorageTraits};
+
+use super::{element::elements_to_f64, Array, ArrayBytes, ArrayError, ArrayIndices};
+
+/// The attribute holding the statistics cached by an array, see [`Array::with_cached_statistics`].
+pub const CACHED_STATISTICS_ATTRIBUTE: &str = "_zarrs_statistics";
+
+/// The statistics cached in the attributes of an array.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CachedStatisticsLevel {
+    /// Cache the statistics of the array.
+    Array,
+    /// Cache the statistics of the array and each of its chunks.
+    Chunks,
+}
+
+/// Minimum, maximum, and count statistics cached in the attributes of an array.
+///
+/// Elements are converted to [`f64`] and only finite elements are included.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct CachedStatistics {
+    /// The number of finite elements.
+    pub count: u64,
+    /// The minimum finite element, or [`None`] if there are none.
+    pub min: Option<f64>,
+    /// The maximum finite element, or [`None`] if there are none.
+    pub max: Option<f64>,
+}
+
+impl CachedStatistics {
+    /// Compute the statistics of `elements`.
+    fn from_elements(elements: &[f64]) -> Self {
+        elements.iter().filter(|element| element.is_finite()).fold(
+            Self::default(),
+            |statistics, &element| {
+                statistics.merge(Self {
+                    count: 1,
+                    min: Some(element),
+                    max: Some(element),
+                })
+            },
+        )
+    }
+
+    /// Merge the statistics of two disjoint sets of elements.
+    fn merge(self, other: Self) -> Self {
+        let merge = |a: Option<f64>, b: Option<f64>, f: fn(f64, f64) -> f64| match (a, b) {
+            (Some(a), Some(b)) => Some(f(a, b)),
+            (a, b) => a.or(b),
+        };
+        Self {
+            count: self.count + other.count,
+            min: merge(self.min, other.min, f64::min),
+            max: merge(self.max, other.max, f64::max),
+        }
+    }
+}
+
+/// The statistics of a chunk in the [`CACHED_STATISTICS_ATTRIBUTE`].
+#[derive(Serialize, Deserialize)]
+struct CachedChunkStatistics {
+    chunk_indices: ArrayIndices,
+    #[serde(flatten)]
+    statistics: CachedStatistics,
+}
+
+/// The value of the [`CACHED_STATISTICS_ATTRIBUTE`].
+#[derive(Serialize, Deserialize)]
+struct CachedStatisticsAttribute {
+    #[serde(flatten)]
+    statistics: CachedStatistics,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    chunks: Vec<CachedChunkStatistics>,
+}
+
+/// The statistics of the chunks written by an array with [`Array::with_cached_statistics`].
+#[derive(Debug)]
+pub(super) struct StatisticsCache {
+    level: CachedStatisticsLevel,
+    chunks: Mutex<BTreeMap<ArrayIndices, CachedStatistics>>,
+}
+
+impl<TStorage: ?Sized> Array<TStorage> {
+    /// Return the array with cached statistics.
+    ///
+    /// The minimum, maximum, and count of the finite elements of each chunk are computed when it is stored by this array, and removed when it is erased.
+    /// They are written to the [`CACHED_STATISTICS_ATTRIBUTE`] attribute with the array metadata by [`store_metadata`](Array::store_metadata), so viewers can query the range of the data without scanning chunks.
+    /// The statistics of each chunk are also written if `level` is [`CachedStatisticsLevel::Chunks`].
+    ///
+    /// Chunk statistics already in the attributes are loaded, so statistics persist across sessions if `level` is [`CachedStatisticsLevel::Chunks`].
+    /// Otherwise, only the chunks stored by this array are included, unless the statistics are recomputed with [`refresh_cached_statistics`](Array::refresh_cached_statistics).
+    /// Chunks that are not stored (such as those equal to the fill value) and chunks written with experimental partial encoding are not included.
+    ///
+    /// # Errors
+    /// Returns [`ArrayError::IncompatibleElementType`] if the data type is not boolean, integer, or floating point.
+    pub fn with_cached_statistics(
+        mut self,
+        level: CachedStatisticsLevel,
+    ) -> Result<Self, ArrayError> {
+        // Check the data type is supported
+        elements_to_f64(self.data_type(), ArrayBytes::new_flen(Vec::<u8>::new()))?;
+
+        let chunks = self
+            .cached_statistics_attribute()
+            .map(|attribute| {
+                attribute
+                    .chunks
+                    .into_iter()
+                    .map(|chunk| (chunk.chunk_indices, chunk.statistics))
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.statistics_cache = Some(StatisticsCache {
+            level,
+            chunks: Mutex::new(chunks),
+        });
+        Ok(self)
+    }
+
+    /// Return the level of cached statistics, if enabled with [`with_cached_statistics`](Array::with_cached_statistics).
+    #[must_use]
+    pub fn cached_statistics_level(&self) -> Option<CachedStatisticsLevel> {
+        self.statistics_cache.as_ref().map(|cache| cache.level)
+    }
+
+    /// Return the cached statistics of the array.
+    ///
+    /// If statistics caching is enabled, these are the statistics of the chunks written by this array, see [`with_cached_statistics`](Array::with_cached_statistics).
+    /// Otherwise, they are read from the [`CACHED_STATISTICS_ATTRIBUTE`] attribute and are [`None`] if it does not exist or is invalid.
+    #[must_use]
+    pub fn cached_statistics(&self) -> Option<CachedStatistics> {
+        if let Some(cache) = &self.statistics_cache {
+            let chunks = cache.chunks.lock().unwrap_or_else(PoisonError::into_inner);
+            Some(
+                chunks
+                    .values()
+                    .fold(CachedStatistics::default(), |a, b| a.merge(*b)),
+            )
+        } else {
+            self.cached_statistics_attribute()
+                .map(|attribute| attribute.statistics)
+        }
+    }
+
+    /// Return the cached statistics of the chunk at `chunk_indices`.
+    ///
+    /// Returns [`None`] if the statistics of the chunk are not cached.
+    /// See [`cached_statistics`](Array::cached_statistics).
+    #[must_use]
+    pub fn cached_chunk_statistics(&self, chunk_indices: &[u64]) -> Option<CachedStatistics> {
+        if let Some(cache) = &self.statistics_cache {
+            let chunks = cache.chunks.lock().unwrap_or_else(PoisonError::into_inner);
+            chunks.get(chunk_indices).copied()
+        } else {
+            self.cached_statistics_attribute()?
+                .chunks
+                .into_iter()
+                .find(|chunk| chunk.chunk_indices == chunk_indices)
+                .map(|chunk| chunk.statistics)
+        }
+    }
+
+    /// Update the cached statistics of the chunk at `chunk_indices` with `chunk_bytes`, if statistics caching is enabled.
+    pub(super) fn update_cached_statistics(
+        &self,
+        chunk_indices: &[u64],
+        chunk_bytes: &ArrayBytes<'_>,
+    ) -> Result<(), ArrayError> {
+        let Some(cache) = &self.statistics_cache else {
+            return Ok(());
+        };
+        let ArrayBytes::Fixed(bytes) = chunk_bytes else {
+            return Err(ArrayError::IncompatibleElementType);
+        };
+        let elements = elements_to_f64(
+            self.data_type(),
+            ArrayBytes::new_flen(Cow::Borrowed(&**bytes)),
+        )?;
+        let statistics = CachedStatistics::from_elements(&elements);
+        cache
+            .chunks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(chunk_indices.to_vec(), statistics);
+        Ok(())
+    }
+
+    /// Remove the cached statistics of the chunk at `chunk_indices`, if statistics caching is enabled.
+    pub(super) fn invalidate_cached_statistics(&self, chunk_indices: &[u64]) {
+        if let Some(cache) = &self.statistics_cache {
+            cache
+                .chunks
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .remove(chunk_indices);
+        }
+    }
+
+    /// Return the value of the [`CACHED_STATISTICS_ATTRIBUTE`] to write with the array metadata, if statistics caching is enabled.
+    pub(super) fn cached_statistics_attribute_value(&self) -> Option<serde_json::Value> {
+        let cache = self.statistics_cache.as_ref()?;
+        let chunks = cache.chunks.lock().unwrap_or_else(PoisonError::into_inner);
+        let attribute = CachedStatisticsAttribute {
+            statistics: chunks
+                .values()
+                .fold(CachedStatistics::default(), |a, b| a.merge(*b)),
+            chunks: match cache.level {
+                CachedStatisticsLevel::Array => vec![],
+                CachedStatisticsLevel::Chunks => chunks
+                    .iter()
+                    .map(|(chunk_indices, statistics)| CachedChunkStatistics {
+                        chunk_indices: chunk_indices.clone(),
+                        statistics: *statistics,
+                    })
+                    .collect(),
+            },
+        };
+        serde_json::to_value(attribute).ok()
+    }
+
+    /// Parse the [`CACHED_STATISTICS_ATTRIBUTE`] in the array attributes.
+    fn cached_statistics_attribute(&self) -> Option<CachedStatisticsAttribute> {
+        let attribute = self.attributes().get(CACHED_STATISTICS_ATTRIBUTE)?;
+        serde_json::from_value(attribute.clone()).ok()
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
+    /// Recompute the cached statistics of every stored chunk of the array in parallel.
+    ///
+    /// Does nothing if statistics caching is not enabled with [`with_cached_statistics`](Array::with_cached_statistics).
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if there is a codec decoding error or an underlying store error.
+    pub fn refresh_cached_statistics(&self) -> Result<(), ArrayError> {
+        let Some(cache) = &self.statistics_cache else {
+            return Ok(());
+        };
+        let Some(chunk_grid_shape) = self.chunk_grid_shape() else {
+            return Err(ArrayError::InvalidArraySubset(
+                self.subset_all(),
+                self.shape().to_vec(),
+            ));
+        };
+        let chunk_statistics = |chunk_indices: Vec<u64>| -> Result<Option<(ArrayIndices, CachedStatistics)>, ArrayError> {
+            let Some(chunk_bytes) = self.retrieve_chunk_if_exists(&chunk_indices)? else {
+                return Ok(None);
+            };
+            let elements = elements_to_f64(self.data_type(), chunk_bytes)?;
+            Ok(Some((
+                chunk_indices,
+                CachedStatistics::from_elements(&elements),
+            )))
+        };
+        let chunks = ArraySubset::new_with_shape(chunk_grid_shape)
+            .indices()
+            .into_par_iter()
+            .map(chunk_statistics)
+            .filter_map(Result::transpose)
+            .collect::<Result<BTreeMap<_, _>, _>>()?;
+        *cache.chunks.lock().unwrap_or_else(PoisonError::into_inner) = chunks;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        array::{ArrayBuilder, DataType, FillValue},
+        storage::store::MemoryStore,
+    };
+
+    use super::*;
+
+    #[test]
+    fn array_cached_statistics() {
+        let store = Arc::new(MemoryStore::new());
+        let array = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::Float32,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0.0f32),
+        )
+        .build(store.clone(), "/array")
+        .unwrap()
+        .with_cached_statistics(CachedStatisticsLevel::Chunks)
+        .unwrap();
+        assert_eq!(
+            array.cached_statistics_level(),
+            Some(CachedStatisticsLevel::Chunks)
+        );
+        assert_eq!(array.cached_statistics(), Some(CachedStatistics::default()));
+
+        array
+            .store_chunk_elements(&[0, 0], &[1.0f32, 2.0, f32::NAN, 4.0])
+            .unwrap();
+        array
+            .store_chunk_elements(&[1, 1], &[-5.0f32, 6.0, 7.0, 8.0])
+            .unwrap();
+        array
+            .store_array_subset_elements(&ArraySubset::new_with_ranges(&[3..4, 3..4]), &[9.0f32])
+            .unwrap();
+        assert_eq!(
+            array.cached_statistics(),
+            Some(CachedStatistics {
+                count: 7,
+                min: Some(-5.0),
+                max: Some(9.0),
+            })
+        );
+        assert_eq!(
+            array.cached_chunk_statistics(&[0, 0]),
+            Some(CachedStatistics {
+                count: 3,
+                min: Some(1.0),
+                max: Some(4.0),
+            })
+        );
+        array.erase_chunk(&[0, 0]).unwrap();
+        assert!(array.cached_chunk_statistics(&[0, 0]).is_none());
+        array.store_metadata().unwrap();
+
+        // Statistics are read from the attributes
+        let array = Array::open(store.clone(), "/array").unwrap();
+        assert!(array.cached_statistics_level().is_none());
+        let statistics = CachedStatistics {
+            count: 4,
+            min: Some(-5.0),
+            max: Some(9.0),
+        };
+        assert_eq!(array.cached_statistics(), Some(statistics));
+        assert_eq!(array.cached_chunk_statistics(&[1, 1]), Some(statistics));
+        assert!(array.cached_chunk_statistics(&[0, 1]).is_none());
+
+        // Chunk statistics are loaded from the attributes
+        let array = array
+            .with_cached_statistics(CachedStatisticsLevel::Array)
+            .unwrap();
+        assert_eq!(array.cached_statistics(), Some(statistics));
+        array.refresh_cached_statistics().unwrap();
+        assert_eq!(array.cached_statistics(), Some(statistics));
+        array.store_metadata().unwrap();
+        let array = Array::open(store, "/array").unwrap();
+        assert_eq!(array.cached_statistics(), Some(statistics));
+        assert!(array.cached_chunk_statistics(&[1, 1]).is_none());
+
+        let array = ArrayBuilder::new(
+            vec![4],
+            DataType::String,
+            vec![2].try_into().unwrap(),
+            FillValue::from(""),
+        )
+        .build(Arc::new(MemoryStore::new()), "/array")
+        .unwrap();
+        assert!(matches!(
+            array.with_cached_statistics(CachedStatisticsLevel::Array),
+            Err(ArrayError::IncompatibleElementType)
+        ));
+    }
+}
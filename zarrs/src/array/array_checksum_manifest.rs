@@ -0,0 +1,247 @@
+use std::{collections::BTreeMap, fmt::Write, sync::Arc};
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    array_subset::ArraySubset,
+    node::data_key,
+    storage::{
+        ReadableStorageTraits, ReadableWritableStorageTraits, StorageError, StorageHandle, StoreKey,
+    },
+};
+
+use super::{Array, ArrayError};
+
+/// The file name of the checksum manifest of an array, relative to the array path.
+pub const CHECKSUM_MANIFEST_FILE_NAME: &str = "zarrs_checksums.json";
+
+/// The digest algorithm of a [`ChecksumManifest`].
+const CHECKSUM_MANIFEST_ALGORITHM: &str = "sha256";
+
+/// A checksum manifest of the encoded chunks of an array.
+///
+/// The manifest maps each chunk key (relative to the array path) to the hex encoded SHA-256 digest of its encoded bytes.
+/// It is stored alongside the array metadata by [`Array::write_checksum_manifest`] and checked by [`Array::verify_checksum_manifest`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ChecksumManifest {
+    /// The digest algorithm. Always `sha256`.
+    pub algorithm: String,
+    /// The digest of each chunk, keyed by the chunk key.
+    pub checksums: BTreeMap<String, String>,
+}
+
+/// The result of [`Array::verify_checksum_manifest`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChecksumVerification {
+    /// The number of chunks with a digest matching the manifest.
+    pub num_verified: usize,
+    /// The chunk keys with a digest that does not match the manifest.
+    pub mismatched: Vec<String>,
+    /// The chunk keys in the manifest that do not exist in the store.
+    pub missing: Vec<String>,
+    /// The chunk keys that exist in the store but are not in the manifest.
+    pub unrecorded: Vec<String>,
+}
+
+impl ChecksumVerification {
+    /// Returns true if every chunk in the store matches the manifest and no chunks are missing.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.mismatched.is_empty() && self.missing.is_empty() && self.unrecorded.is_empty()
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
+    /// Return the store key of the checksum manifest of the array.
+    #[must_use]
+    pub fn checksum_manifest_key(&self) -> StoreKey {
+        let file_name = unsafe { StoreKey::new_unchecked(CHECKSUM_MANIFEST_FILE_NAME.to_string()) };
+        data_key(self.path(), &file_name)
+    }
+
+    /// Compute the [`ChecksumManifest`] of the encoded chunks of the array in parallel.
+    ///
+    /// Chunks are not decoded.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if there is an underlying store error.
+    pub fn checksum_manifest(&self) -> Result<ChecksumManifest, ArrayError> {
+        Ok(ChecksumManifest {
+            algorithm: CHECKSUM_MANIFEST_ALGORITHM.to_string(),
+            checksums: self.chunk_checksums()?,
+        })
+    }
+
+    /// Read the [`ChecksumManifest`] of the array, if it exists.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if the manifest is invalid or there is an underlying store error.
+    pub fn retrieve_checksum_manifest(&self) -> Result<Option<ChecksumManifest>, ArrayError> {
+        let key = self.checksum_manifest_key();
+        let Some(bytes) = self.storage.get(&key)? else {
+            return Ok(None);
+        };
+        let manifest: ChecksumManifest = serde_json::from_slice(&bytes)
+            .map_err(|err| ArrayError::InvalidChecksumManifest(err.to_string()))?;
+        if manifest.algorithm != CHECKSUM_MANIFEST_ALGORITHM {
+            return Err(ArrayError::InvalidChecksumManifest(format!(
+                "unsupported algorithm {}",
+                manifest.algorithm
+            )));
+        }
+        Ok(Some(manifest))
+    }
+
+    /// Verify the encoded chunks of the array against its stored [`ChecksumManifest`].
+    ///
+    /// Chunks are not decoded, so this only detects changes to the stored bytes of chunks.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if the manifest does not exist or is invalid, or there is an underlying store error.
+    pub fn verify_checksum_manifest(&self) -> Result<ChecksumVerification, ArrayError> {
+        let manifest = self.retrieve_checksum_manifest()?.ok_or_else(|| {
+            ArrayError::InvalidChecksumManifest(format!(
+                "{} does not exist",
+                self.checksum_manifest_key()
+            ))
+        })?;
+        let mut checksums = self.chunk_checksums()?;
+
+        let mut verification = ChecksumVerification::default();
+        for (chunk_key, expected) in manifest.checksums {
+            match checksums.remove(&chunk_key) {
+                Some(checksum) if checksum == expected => verification.num_verified += 1,
+                Some(_) => verification.mismatched.push(chunk_key),
+                None => verification.missing.push(chunk_key),
+            }
+        }
+        verification.unrecorded = checksums.into_keys().collect();
+        Ok(verification)
+    }
+
+    /// Return the digests of the encoded chunks of the array that exist in the store, keyed by chunk key.
+    fn chunk_checksums(&self) -> Result<BTreeMap<String, String>, ArrayError> {
+        let Some(chunk_grid_shape) = self.chunk_grid_shape() else {
+            return Err(ArrayError::InvalidArraySubset(
+                self.subset_all(),
+                self.shape().to_vec(),
+            ));
+        };
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_readable_transformer(storage_handle)?;
+
+        let checksum = |chunk_indices: Vec<u64>| -> Result<Option<(String, String)>, StorageError> {
+            let Some(bytes) = storage_transformer.get(&self.chunk_key(&chunk_indices))? else {
+                return Ok(None);
+            };
+            let checksum = Sha256::digest(&bytes).iter().fold(
+                String::with_capacity(64),
+                |mut checksum, byte| {
+                    let _ = write!(checksum, "{byte:02x}");
+                    checksum
+                },
+            );
+            let chunk_key = self.chunk_key_encoding().encode(&chunk_indices);
+            Ok(Some((chunk_key.as_str().to_string(), checksum)))
+        };
+        let checksums = ArraySubset::new_with_shape(chunk_grid_shape)
+            .indices()
+            .into_par_iter()
+            .map(checksum)
+            .filter_map(Result::transpose)
+            .collect::<Result<BTreeMap<_, _>, _>>()?;
+        Ok(checksums)
+    }
+}
+
+impl<TStorage: ?Sized + ReadableWritableStorageTraits + 'static> Array<TStorage> {
+    /// Compute the [`ChecksumManifest`] of the encoded chunks of the array and store it alongside the array metadata.
+    ///
+    /// The manifest is stored at [`checksum_manifest_key`](Array::checksum_manifest_key) and can later be checked with [`verify_checksum_manifest`](Array::verify_checksum_manifest).
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if there is an underlying store error.
+    pub fn write_checksum_manifest(&self) -> Result<ChecksumManifest, ArrayError> {
+        let manifest = self.checksum_manifest()?;
+        let key = self.checksum_manifest_key();
+        let json = serde_json::to_vec_pretty(&manifest)
+            .map_err(|err| StorageError::InvalidMetadata(key.clone(), err.to_string()))?;
+        self.storage.set(&key, json.into())?;
+        Ok(manifest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        array::{ArrayBuilder, DataType, FillValue},
+        storage::{store::MemoryStore, WritableStorageTraits},
+    };
+
+    use super::*;
+
+    #[test]
+    fn array_checksum_manifest() {
+        let store = Arc::new(MemoryStore::new());
+        let array = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::UInt8,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .build(store.clone(), "/array")
+        .unwrap();
+        array.store_metadata().unwrap();
+        array
+            .store_chunk_elements(&[0, 0], &[1u8, 2, 3, 4])
+            .unwrap();
+        array
+            .store_chunk_elements(&[1, 1], &[5u8, 6, 7, 8])
+            .unwrap();
+        assert!(array.retrieve_checksum_manifest().unwrap().is_none());
+        assert!(matches!(
+            array.verify_checksum_manifest(),
+            Err(ArrayError::InvalidChecksumManifest(_))
+        ));
+
+        let manifest = array.write_checksum_manifest().unwrap();
+        assert_eq!(manifest.algorithm, "sha256");
+        assert_eq!(
+            manifest.checksums.keys().collect::<Vec<_>>(),
+            vec!["c/0/0", "c/1/1"]
+        );
+        assert_eq!(
+            array.retrieve_checksum_manifest().unwrap(),
+            Some(manifest.clone())
+        );
+        assert!(store
+            .get(&StoreKey::new("array/zarrs_checksums.json").unwrap())
+            .unwrap()
+            .is_some());
+        let verification = array.verify_checksum_manifest().unwrap();
+        assert!(verification.is_valid());
+        assert_eq!(verification.num_verified, 2);
+
+        // Tamper with the store
+        store
+            .set(&array.chunk_key(&[0, 0]), vec![0u8; 4].into())
+            .unwrap();
+        store.erase(&array.chunk_key(&[1, 1])).unwrap();
+        array.store_chunk_elements(&[0, 1], &[9u8; 4]).unwrap();
+        let verification = array.verify_checksum_manifest().unwrap();
+        assert!(!verification.is_valid());
+        assert_eq!(
+            verification,
+            ChecksumVerification {
+                num_verified: 0,
+                mismatched: vec!["c/0/0".to_string()],
+                missing: vec!["c/1/1".to_string()],
+                unrecorded: vec!["c/0/1".to_string()],
+            }
+        );
+    }
+}
@@ -0,0 +1,336 @@
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use rayon_iter_concurrent_limit::iter_concurrent_limit;
+
+use crate::{array_subset::ArraySubset, storage::ReadableStorageTraits};
+
+use super::{
+    codec::CodecOptions, concurrency::concurrency_chunks_and_codec, element::elements_to_f64,
+    Array, ArrayBytes, ArrayError, ArrayIndices, DataType,
+};
+
+/// Options for [`compare`].
+///
+/// By default, elements are compared exactly by their bytes.
+/// A tolerance can be set to compare the elements of numeric arrays as [`f64`] within an absolute and relative tolerance.
+#[derive(Debug, Clone, Default)]
+pub struct ArrayCompareOptions {
+    tolerance: Option<(f64, f64)>,
+    codec_options: CodecOptions,
+}
+
+impl ArrayCompareOptions {
+    /// Return the absolute and relative tolerance, if set.
+    #[must_use]
+    pub fn tolerance(&self) -> Option<(f64, f64)> {
+        self.tolerance
+    }
+
+    /// Set the absolute and relative tolerance.
+    ///
+    /// Elements `a` and `b` are equal if `|a - b| <= absolute + relative * |b|`, or they are both NaN.
+    #[must_use]
+    pub fn with_tolerance(mut self, absolute: f64, relative: f64) -> Self {
+        self.tolerance = Some((absolute, relative));
+        self
+    }
+
+    /// Set the absolute and relative tolerance.
+    ///
+    /// See [`with_tolerance`](ArrayCompareOptions::with_tolerance).
+    pub fn set_tolerance(&mut self, absolute: f64, relative: f64) -> &mut Self {
+        self.tolerance = Some((absolute, relative));
+        self
+    }
+
+    /// Return the codec options.
+    #[must_use]
+    pub fn codec_options(&self) -> &CodecOptions {
+        &self.codec_options
+    }
+
+    /// Set the codec options.
+    #[must_use]
+    pub fn with_codec_options(mut self, codec_options: CodecOptions) -> Self {
+        self.codec_options = codec_options;
+        self
+    }
+
+    /// Set the codec options.
+    pub fn set_codec_options(&mut self, codec_options: CodecOptions) -> &mut Self {
+        self.codec_options = codec_options;
+        self
+    }
+}
+
+/// The result of [`compare`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ArrayComparison {
+    num_chunks: u64,
+    num_elements: u64,
+    num_differing_elements: u64,
+    differing_chunks: Vec<ArrayIndices>,
+    max_absolute_difference: Option<f64>,
+}
+
+impl ArrayComparison {
+    /// Returns true if the arrays are equal.
+    #[must_use]
+    pub fn is_equal(&self) -> bool {
+        self.differing_chunks.is_empty()
+    }
+
+    /// Return the number of chunks compared.
+    #[must_use]
+    pub const fn num_chunks(&self) -> u64 {
+        self.num_chunks
+    }
+
+    /// Return the number of elements compared.
+    #[must_use]
+    pub const fn num_elements(&self) -> u64 {
+        self.num_elements
+    }
+
+    /// Return the number of elements that differ.
+    #[must_use]
+    pub const fn num_differing_elements(&self) -> u64 {
+        self.num_differing_elements
+    }
+
+    /// Return the indices of the chunks of the first array that differ, in C order.
+    #[must_use]
+    pub fn differing_chunks(&self) -> &[ArrayIndices] {
+        &self.differing_chunks
+    }
+
+    /// Return the maximum absolute difference of elements that are not NaN.
+    ///
+    /// This is only computed if a tolerance is set and is [`None`] if no elements were compared.
+    #[must_use]
+    pub const fn max_absolute_difference(&self) -> Option<f64> {
+        self.max_absolute_difference
+    }
+
+    /// Merge the comparison of disjoint chunks.
+    fn merge(mut self, other: Self) -> Self {
+        self.num_chunks += other.num_chunks;
+        self.num_elements += other.num_elements;
+        self.num_differing_elements += other.num_differing_elements;
+        self.differing_chunks.extend(other.differing_chunks);
+        self.max_absolute_difference =
+            match (self.max_absolute_difference, other.max_absolute_difference) {
+                (Some(a), Some(b)) => Some(a.max(b)),
+                (a, b) => a.or(b),
+            };
+        self
+    }
+}
+
+/// Compare the elements of arrays `a` and `b` chunk-by-chunk.
+///
+/// The arrays must have the same shape and data type, but they may have different chunk grids and codecs.
+/// The chunks of `a` are compared with the corresponding subsets of `b` in parallel, so the arrays are never held in memory at once.
+/// This is useful for validating that a migration or recompression of an array preserved its data.
+///
+/// # Errors
+/// Returns an [`ArrayError`] if
+///  - the arrays have a different shape or data type,
+///  - a tolerance is set and the data type is not boolean, integer, or floating point,
+///  - there is a codec decoding error, or
+///  - an underlying store error.
+pub fn compare<TStorageA, TStorageB>(
+    a: &Array<TStorageA>,
+    b: &Array<TStorageB>,
+    options: &ArrayCompareOptions,
+) -> Result<ArrayComparison, ArrayError>
+where
+    TStorageA: ?Sized + ReadableStorageTraits + 'static,
+    TStorageB: ?Sized + ReadableStorageTraits + 'static,
+{
+    if a.shape() != b.shape() {
+        return Err(ArrayError::IncompatibleArrays(format!(
+            "shape {:?} does not match {:?}",
+            a.shape(),
+            b.shape()
+        )));
+    }
+    if a.data_type() != b.data_type() {
+        return Err(ArrayError::IncompatibleArrays(format!(
+            "data type {} does not match {}",
+            a.data_type(),
+            b.data_type()
+        )));
+    }
+    let Some(chunk_grid_shape) = a.chunk_grid_shape() else {
+        return Err(ArrayError::InvalidArraySubset(
+            a.subset_all(),
+            a.shape().to_vec(),
+        ));
+    };
+    let chunks = ArraySubset::new_with_shape(chunk_grid_shape);
+
+    // Calculate chunk/codec concurrency
+    let chunk_representation = a.chunk_array_representation(&vec![0; a.dimensionality()])?;
+    let codec_concurrency = a.recommended_codec_concurrency(&chunk_representation)?;
+    let (chunk_concurrent_limit, codec_options) = concurrency_chunks_and_codec(
+        options.codec_options().concurrent_target(),
+        chunks.num_elements_usize(),
+        options.codec_options(),
+        &codec_concurrency,
+    );
+
+    let compare_chunk = |chunk_indices: Vec<u64>| -> Result<ArrayComparison, ArrayError> {
+        let chunk_subset = a.chunk_subset_bounded(&chunk_indices)?;
+        let bytes_a = a.retrieve_array_subset_opt(&chunk_subset, &codec_options)?;
+        let bytes_b = b.retrieve_array_subset_opt(&chunk_subset, &codec_options)?;
+        let (num_differing_elements, max_absolute_difference) =
+            if let Some((absolute, relative)) = options.tolerance() {
+                compare_elements_tolerance(a.data_type(), bytes_a, bytes_b, absolute, relative)?
+            } else {
+                (
+                    compare_elements_exact(a.data_type(), &bytes_a, &bytes_b),
+                    None,
+                )
+            };
+        Ok(ArrayComparison {
+            num_chunks: 1,
+            num_elements: chunk_subset.num_elements(),
+            num_differing_elements,
+            differing_chunks: if num_differing_elements > 0 {
+                vec![chunk_indices]
+            } else {
+                vec![]
+            },
+            max_absolute_difference,
+        })
+    };
+    let indices = chunks.indices();
+    let mut comparison =
+        iter_concurrent_limit!(chunk_concurrent_limit, indices, map, compare_chunk)
+            .try_reduce(ArrayComparison::default, |a, b| Ok(a.merge(b)))?;
+    comparison.differing_chunks.sort();
+    Ok(comparison)
+}
+
+/// Return the number of elements of `bytes_a` and `bytes_b` with different bytes.
+fn compare_elements_exact(data_type: &DataType, bytes_a: &ArrayBytes, bytes_b: &ArrayBytes) -> u64 {
+    match (bytes_a, bytes_b) {
+        (ArrayBytes::Fixed(bytes_a), ArrayBytes::Fixed(bytes_b)) => {
+            let size = data_type.fixed_size().unwrap_or(1);
+            std::iter::zip(bytes_a.chunks_exact(size), bytes_b.chunks_exact(size))
+                .filter(|(a, b)| a != b)
+                .count() as u64
+        }
+        (ArrayBytes::Variable(bytes_a, offsets_a), ArrayBytes::Variable(bytes_b, offsets_b)) => {
+            std::iter::zip(offsets_a.windows(2), offsets_b.windows(2))
+                .filter(|(a, b)| bytes_a[a[0]..a[1]] != bytes_b[b[0]..b[1]])
+                .count() as u64
+        }
+        _ => unreachable!("arrays with the same data type have the same bytes representation"),
+    }
+}
+
+/// Return the number of elements of `bytes_a` and `bytes_b` outside of the tolerance and their maximum absolute difference.
+fn compare_elements_tolerance(
+    data_type: &DataType,
+    bytes_a: ArrayBytes,
+    bytes_b: ArrayBytes,
+    absolute: f64,
+    relative: f64,
+) -> Result<(u64, Option<f64>), ArrayError> {
+    let elements_a = elements_to_f64(data_type, bytes_a)?;
+    let elements_b = elements_to_f64(data_type, bytes_b)?;
+    let mut num_differing_elements = 0;
+    let mut max_absolute_difference: Option<f64> = None;
+    for (a, b) in std::iter::zip(elements_a, elements_b) {
+        if a.is_nan() || b.is_nan() {
+            if a.is_nan() != b.is_nan() {
+                num_differing_elements += 1;
+            }
+            continue;
+        }
+        #[allow(clippy::float_cmp)]
+        let difference = if a == b { 0.0 } else { (a - b).abs() };
+        if difference > absolute + relative * b.abs() {
+            num_differing_elements += 1;
+        }
+        max_absolute_difference =
+            Some(max_absolute_difference.map_or(difference, |max| max.max(difference)));
+    }
+    Ok((num_differing_elements, max_absolute_difference))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        array::{ArrayBuilder, DataType, FillValue},
+        storage::store::MemoryStore,
+    };
+
+    use super::*;
+
+    #[test]
+    fn array_compare() {
+        let store = Arc::new(MemoryStore::new());
+        let elements: Vec<f32> = (0..16u8).map(f32::from).collect();
+        let a = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::Float32,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0.0f32),
+        )
+        .build(store.clone(), "/a")
+        .unwrap();
+        a.store_array_subset_elements(&a.subset_all(), &elements)
+            .unwrap();
+        let b = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::Float32,
+            vec![4, 1].try_into().unwrap(),
+            FillValue::from(0.0f32),
+        )
+        .build(store.clone(), "/b")
+        .unwrap();
+        b.store_array_subset_elements(&b.subset_all(), &elements)
+            .unwrap();
+
+        let comparison = compare(&a, &b, &ArrayCompareOptions::default()).unwrap();
+        assert!(comparison.is_equal());
+        assert_eq!(comparison.num_chunks(), 4);
+        assert_eq!(comparison.num_elements(), 16);
+
+        b.store_array_subset_elements(
+            &ArraySubset::new_with_ranges(&[3..4, 0..2]),
+            &[12.5f32, 13.0],
+        )
+        .unwrap();
+        b.store_array_subset_elements(&ArraySubset::new_with_ranges(&[0..1, 3..4]), &[3.0001f32])
+            .unwrap();
+        let comparison = compare(&a, &b, &ArrayCompareOptions::default()).unwrap();
+        assert!(!comparison.is_equal());
+        assert_eq!(comparison.num_differing_elements(), 2);
+        assert_eq!(comparison.differing_chunks(), &[vec![0, 1], vec![1, 0]]);
+        assert_eq!(comparison.max_absolute_difference(), None);
+
+        let options = ArrayCompareOptions::default().with_tolerance(1e-3, 0.0);
+        let comparison = compare(&a, &b, &options).unwrap();
+        assert_eq!(comparison.num_differing_elements(), 1);
+        assert_eq!(comparison.differing_chunks(), &[vec![1, 0]]);
+        assert_eq!(comparison.max_absolute_difference(), Some(0.5));
+
+        let c = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::Float64,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0.0f64),
+        )
+        .build(store, "/c")
+        .unwrap();
+        assert!(matches!(
+            compare(&a, &c, &options),
+            Err(ArrayError::IncompatibleArrays(_))
+        ));
+    }
+}
@@ -0,0 +1,443 @@
+use std::{
+    ops::Range,
+    sync::{Arc, PoisonError},
+};
+
+use crate::{array_subset::ArraySubset, storage::ReadableStorageTraits};
+
+use super::{
+    element::elements_to_f64, Array, ArrayBytes, ArrayCreateError, ArrayError, ElementOwned,
+};
+
+impl<TStorage: ?Sized> Array<TStorage> {
+    /// Return the path of the coordinate array of the dimension named `dimension_name`.
+    ///
+    /// By the [xarray convention](https://docs.xarray.dev/en/stable/internals/zarr-encoding-spec.html), the coordinate array of a dimension is a one-dimensional array named after the dimension in the same group as the array.
+    /// Returns [`None`] if the array is the root node, which has no siblings.
+    #[must_use]
+    pub fn coordinate_array_path(&self, dimension_name: &str) -> Option<String> {
+        let path = self.path().as_str();
+        if path == "/" {
+            return None;
+        }
+        let (parent, _) = path.rsplit_once('/')?;
+        Some(format!("{parent}/{dimension_name}"))
+    }
+
+    /// Clear the cached coordinate array labels read by [`select`](Array::select).
+    ///
+    /// Call this if coordinate arrays have been modified since they were first read.
+    pub fn clear_coordinate_cache(&self) {
+        self.coordinate_labels
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
+    /// Open the coordinate array of the dimension named `dimension_name`.
+    ///
+    /// The coordinate array is at [`coordinate_array_path`](Array::coordinate_array_path) and must be one-dimensional with the length of the dimension.
+    /// Returns [`None`] if the coordinate array does not exist.
+    ///
+    /// # Errors
+    /// Returns
+    ///  - [`ArrayError::UnknownDimensionName`] if the array has no dimension named `dimension_name`, or
+    ///  - [`ArrayError::InvalidCoordinateArray`] if the coordinate array cannot be opened or its shape does not match the dimension.
+    pub fn coordinate_array(&self, dimension_name: &str) -> Result<Option<Self>, ArrayError> {
+        let dimension = self
+            .dimension_index(dimension_name)
+            .ok_or_else(|| ArrayError::UnknownDimensionName(dimension_name.to_string()))?;
+        let Some(path) = self.coordinate_array_path(dimension_name) else {
+            return Ok(None);
+        };
+        let invalid =
+            |reason: String| ArrayError::InvalidCoordinateArray(dimension_name.to_string(), reason);
+        let coordinate_array = match Self::open(self.storage.clone(), &path) {
+            Ok(coordinate_array) => coordinate_array,
+            Err(ArrayCreateError::MissingMetadata) => return Ok(None),
+            Err(err) => return Err(invalid(err.to_string())),
+        };
+        let length = self.shape()[dimension];
+        if coordinate_array.shape() != [length] {
+            return Err(invalid(format!(
+                "expected shape [{length}], got {:?}",
+                coordinate_array.shape()
+            )));
+        }
+        Ok(Some(coordinate_array))
+    }
+
+    /// Return the subset of the array with coordinates within `ranges` of labels for named dimensions.
+    ///
+    /// The labels of each dimension in `ranges` are read from its [`coordinate_array`](Array::coordinate_array), and the subset spans the indices with labels in the half-open range.
+    /// Coordinates are expected to be monotonic, otherwise the subset spans all indices with labels in the range.
+    /// Dimensions not in `ranges` are not restricted.
+    ///
+    /// For example, with coordinate arrays `lat` and `lon`:
+    /// ```rust,ignore
+    /// let subset = array.coordinate_range_subset(&[("lat", -10.0..10.0), ("lon", 100.0..120.0)])?;
+    /// let elements = array.retrieve_array_subset_elements::<f32>(&subset)?;
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - a dimension name is unknown or its coordinate array is missing or invalid,
+    ///  - `T` is incompatible with the data type of a coordinate array,
+    ///  - there is a codec decoding error, or
+    ///  - an underlying store error.
+    pub fn coordinate_range_subset<T: ElementOwned + PartialOrd>(
+        &self,
+        ranges: &[(&str, Range<T>)],
+    ) -> Result<ArraySubset, ArrayError> {
+        let mut subset_ranges: Vec<Range<u64>> =
+            self.shape().iter().map(|&length| 0..length).collect();
+        for (dimension_name, range) in ranges {
+            let coordinate_array = self.coordinate_array(dimension_name)?.ok_or_else(|| {
+                ArrayError::InvalidCoordinateArray(
+                    (*dimension_name).to_string(),
+                    "the coordinate array does not exist".to_string(),
+                )
+            })?;
+            let coordinates = coordinate_array
+                .retrieve_array_subset_elements::<T>(&coordinate_array.subset_all())?;
+            let dimension = self
+                .dimension_index(dimension_name)
+                .ok_or_else(|| ArrayError::UnknownDimensionName((*dimension_name).to_string()))?;
+            subset_ranges[dimension] = coordinate_indices(&coordinates, range);
+        }
+        Ok(ArraySubset::new_with_ranges(&subset_ranges))
+    }
+
+    /// Return the labels of the coordinate array of the dimension named `dimension_name` as [`f64`].
+    ///
+    /// Labels are read from the [`coordinate_array`](Array::coordinate_array) on first use and then cached.
+    /// The coordinate array must have a boolean, integer, or floating point data type.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - the dimension name is unknown or its coordinate array is missing or invalid,
+    ///  - there is a codec decoding error, or
+    ///  - an underlying store error.
+    pub fn coordinate_labels(&self, dimension_name: &str) -> Result<Arc<Vec<f64>>, ArrayError> {
+        if let Some(labels) = self
+            .coordinate_labels
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(dimension_name)
+        {
+            return Ok(labels.clone());
+        }
+
+        let coordinate_array = self.coordinate_array(dimension_name)?.ok_or_else(|| {
+            ArrayError::InvalidCoordinateArray(
+                dimension_name.to_string(),
+                "the coordinate array does not exist".to_string(),
+            )
+        })?;
+        let labels = Arc::new(retrieve_labels(dimension_name, &coordinate_array)?);
+        self.coordinate_labels
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(dimension_name.to_string(), labels.clone());
+        Ok(labels)
+    }
+
+    /// Return the subset of the array selected by ranges of labels for named dimensions.
+    ///
+    /// This is like [`coordinate_range_subset`](Array::coordinate_range_subset), except that labels are compared as [`f64`] and cached (see [`coordinate_labels`](Array::coordinate_labels)).
+    /// Thus, dimensions with coordinate arrays of different data types can be selected together.
+    ///
+    /// # Errors
+    /// See [`coordinate_labels`](Array::coordinate_labels).
+    pub fn select_subset(
+        &self,
+        selection: &[(&str, Range<f64>)],
+    ) -> Result<ArraySubset, ArrayError> {
+        let mut subset_ranges: Vec<Range<u64>> =
+            self.shape().iter().map(|&length| 0..length).collect();
+        for (dimension_name, range) in selection {
+            let labels = self.coordinate_labels(dimension_name)?;
+            let dimension = self
+                .dimension_index(dimension_name)
+                .ok_or_else(|| ArrayError::UnknownDimensionName((*dimension_name).to_string()))?;
+            subset_ranges[dimension] = coordinate_indices(&labels, range);
+        }
+        Ok(ArraySubset::new_with_ranges(&subset_ranges))
+    }
+
+    /// Read and decode the subset of the array selected by ranges of labels for named dimensions into its bytes.
+    ///
+    /// Labels are resolved to indices with [`select_subset`](Array::select_subset).
+    /// For example, with `time`, `lat`, and `lon` coordinate arrays:
+    /// ```rust,ignore
+    /// let bytes = array.select(&[("time", t0..t1), ("lat", 10.0..20.0)])?;
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - the selection cannot be resolved (see [`coordinate_labels`](Array::coordinate_labels)),
+    ///  - there is a codec decoding error, or
+    ///  - an underlying store error.
+    pub fn select(&self, selection: &[(&str, Range<f64>)]) -> Result<ArrayBytes<'_>, ArrayError> {
+        self.retrieve_array_subset(&self.select_subset(selection)?)
+    }
+
+    /// Read and decode the subset of the array selected by ranges of labels for named dimensions into a vector of its elements.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - the selection cannot be resolved (see [`coordinate_labels`](Array::coordinate_labels)),
+    ///  - the size of `T` does not match the data type size,
+    ///  - there is a codec decoding error, or
+    ///  - an underlying store error.
+    pub fn select_elements<T: ElementOwned>(
+        &self,
+        selection: &[(&str, Range<f64>)],
+    ) -> Result<Vec<T>, ArrayError> {
+        self.retrieve_array_subset_elements(&self.select_subset(selection)?)
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Read and decode the subset of the array selected by ranges of labels for named dimensions into an [`ndarray::ArrayD`].
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - the selection cannot be resolved (see [`coordinate_labels`](Array::coordinate_labels)),
+    ///  - the size of `T` does not match the data type size,
+    ///  - there is a codec decoding error, or
+    ///  - an underlying store error.
+    pub fn select_ndarray<T: ElementOwned>(
+        &self,
+        selection: &[(&str, Range<f64>)],
+    ) -> Result<ndarray::ArrayD<T>, ArrayError> {
+        self.retrieve_array_subset_ndarray(&self.select_subset(selection)?)
+    }
+}
+
+/// Retrieve the elements of a coordinate array as [`f64`].
+fn retrieve_labels<TStorage: ?Sized + ReadableStorageTraits + 'static>(
+    dimension_name: &str,
+    coordinate_array: &Array<TStorage>,
+) -> Result<Vec<f64>, ArrayError> {
+    let data_type = coordinate_array.data_type();
+    let bytes = coordinate_array.retrieve_array_subset(&coordinate_array.subset_all())?;
+    elements_to_f64(data_type, bytes).map_err(|err| match err {
+        ArrayError::IncompatibleElementType => ArrayError::InvalidCoordinateArray(
+            dimension_name.to_string(),
+            format!("unsupported data type {}", data_type.name()),
+        ),
+        err => err,
+    })
+}
+
+/// Return the range of indices spanning the `coordinates` within `range`.
+pub(super) fn coordinate_indices<T: PartialOrd>(coordinates: &[T], range: &Range<T>) -> Range<u64> {
+    let mut selected = coordinates
+        .iter()
+        .enumerate()
+        .filter(|(_, coordinate)| range.contains(*coordinate))
+        .map(|(index, _)| index as u64);
+    selected.next().map_or(0..0, |first| {
+        let last = selected.last().unwrap_or(first);
+        first..last + 1
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        array::{ArrayBuilder, ArrayMetadataV2, DataType, FillValue, ARRAY_DIMENSIONS_ATTRIBUTE},
+        metadata::v2::array::FillValueMetadataV2,
+        storage::store::MemoryStore,
+    };
+
+    use super::*;
+
+    #[test]
+    fn array_dimension_names() {
+        let store = Arc::new(MemoryStore::new());
+        let mut array = ArrayBuilder::new(
+            vec![4, 3],
+            DataType::Float32,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0.0f32),
+        )
+        .build(store.clone(), "/data")
+        .unwrap();
+        assert_eq!(array.dimension_index("y"), None);
+        assert!(array.set_dimension_names(Some(vec!["y".into()])).is_err());
+        array
+            .set_dimension_names(Some(vec!["y".into(), "x".into()]))
+            .unwrap();
+        assert_eq!(array.dimension_index("x"), Some(1));
+        array.store_metadata().unwrap();
+        let array = Array::open(store.clone(), "/data").unwrap();
+        assert_eq!(array.dimension_names(), &Some(vec!["y".into(), "x".into()]));
+
+        // Zarr V2 arrays store dimension names in the _ARRAY_DIMENSIONS attribute
+        let metadata = ArrayMetadataV2::new(
+            vec![4, 3],
+            vec![2, 2].try_into().unwrap(),
+            "<f4".into(),
+            FillValueMetadataV2::Number(0.into()),
+            None,
+            None,
+        );
+        let mut array =
+            Array::new_with_metadata(store.clone(), "/data_v2", metadata.into()).unwrap();
+        array
+            .set_dimension_names(Some(vec!["y".into(), "x".into()]))
+            .unwrap();
+        assert_eq!(
+            array.attributes()[ARRAY_DIMENSIONS_ATTRIBUTE],
+            serde_json::json!(["y", "x"])
+        );
+        array.store_metadata().unwrap();
+        let mut array = Array::open(store, "/data_v2").unwrap();
+        assert_eq!(array.dimension_index("x"), Some(1));
+        array.set_dimension_names(None).unwrap();
+        assert!(!array.attributes().contains_key(ARRAY_DIMENSIONS_ATTRIBUTE));
+    }
+
+    #[test]
+    fn array_coordinate_range_subset() {
+        let store = Arc::new(MemoryStore::new());
+        let array = ArrayBuilder::new(
+            vec![4, 3],
+            DataType::Float32,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0.0f32),
+        )
+        .dimension_names(["y", "x"].into())
+        .build(store.clone(), "/group/data")
+        .unwrap();
+        assert_eq!(
+            array.coordinate_array_path("y").as_deref(),
+            Some("/group/y")
+        );
+        assert!(matches!(
+            array.coordinate_array("z"),
+            Err(ArrayError::UnknownDimensionName(_))
+        ));
+        assert!(array.coordinate_array("y").unwrap().is_none());
+
+        let store_coordinates = |name: &str, coordinates: &[f64]| {
+            let coordinate_array = ArrayBuilder::new(
+                vec![coordinates.len() as u64],
+                DataType::Float64,
+                vec![2].try_into().unwrap(),
+                FillValue::from(0.0f64),
+            )
+            .dimension_names([name].into())
+            .build(store.clone(), &format!("/group/{name}"))
+            .unwrap();
+            coordinate_array.store_metadata().unwrap();
+            coordinate_array
+                .store_array_subset_elements(&coordinate_array.subset_all(), coordinates)
+                .unwrap();
+        };
+        store_coordinates("y", &[10.0, 20.0, 30.0, 40.0]);
+        store_coordinates("x", &[0.5, 0.0, -0.5]);
+        assert!(array.coordinate_array("y").unwrap().is_some());
+
+        assert_eq!(
+            array.coordinate_range_subset(&[("y", 15.0..35.0)]).unwrap(),
+            ArraySubset::new_with_ranges(&[1..3, 0..3])
+        );
+        assert_eq!(
+            array
+                .coordinate_range_subset(&[("y", 10.0..20.0), ("x", -0.5..0.25)])
+                .unwrap(),
+            ArraySubset::new_with_ranges(&[0..1, 1..3])
+        );
+        assert!(array
+            .coordinate_range_subset(&[("y", 50.0..60.0)])
+            .unwrap()
+            .is_empty());
+        assert!(array.coordinate_range_subset(&[("y", 0u8..1)]).is_err());
+
+        store_coordinates("x", &[0.0, 1.0]);
+        assert!(matches!(
+            array.coordinate_array("x"),
+            Err(ArrayError::InvalidCoordinateArray(_, _))
+        ));
+    }
+
+    #[test]
+    fn array_select() {
+        let store = Arc::new(MemoryStore::new());
+        let array = ArrayBuilder::new(
+            vec![4, 3],
+            DataType::Float32,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0.0f32),
+        )
+        .dimension_names(["time", "lat"].into())
+        .build(store.clone(), "/data")
+        .unwrap();
+        let elements: Vec<f32> = (0..12u8).map(f32::from).collect();
+        array
+            .store_array_subset_elements(&array.subset_all(), &elements)
+            .unwrap();
+        assert!(matches!(
+            array.select(&[("time", 0.0..1.0)]),
+            Err(ArrayError::InvalidCoordinateArray(_, _))
+        ));
+
+        let time = ArrayBuilder::new(
+            vec![4],
+            DataType::Int64,
+            vec![4].try_into().unwrap(),
+            FillValue::from(0i64),
+        )
+        .build(store.clone(), "/time")
+        .unwrap();
+        time.store_metadata().unwrap();
+        time.store_array_subset_elements(&time.subset_all(), &[0i64, 10, 20, 30])
+            .unwrap();
+        let lat = ArrayBuilder::new(
+            vec![3],
+            DataType::Float32,
+            vec![3].try_into().unwrap(),
+            FillValue::from(0.0f32),
+        )
+        .build(store.clone(), "/lat")
+        .unwrap();
+        lat.store_metadata().unwrap();
+        lat.store_array_subset_elements(&lat.subset_all(), &[10.0f32, 15.0, 20.0])
+            .unwrap();
+
+        let selection = [("time", 10.0..30.0), ("lat", 10.0..20.0)];
+        assert_eq!(
+            array.select_subset(&selection).unwrap(),
+            ArraySubset::new_with_ranges(&[1..3, 0..2])
+        );
+        assert_eq!(
+            array.select_elements::<f32>(&selection).unwrap(),
+            vec![3.0, 4.0, 6.0, 7.0]
+        );
+        assert_eq!(
+            array.select(&[("lat", 15.0..16.0)]).unwrap(),
+            array
+                .retrieve_array_subset(&ArraySubset::new_with_ranges(&[0..4, 1..2]))
+                .unwrap()
+        );
+
+        // Labels are cached until the cache is cleared
+        time.store_array_subset_elements(&time.subset_all(), &[10i64, 20, 30, 40])
+            .unwrap();
+        assert_eq!(
+            array.select_subset(&[("time", 10.0..30.0)]).unwrap(),
+            ArraySubset::new_with_ranges(&[1..3, 0..3])
+        );
+        array.clear_coordinate_cache();
+        assert_eq!(
+            array.select_subset(&[("time", 10.0..30.0)]).unwrap(),
+            ArraySubset::new_with_ranges(&[0..2, 0..3])
+        );
+    }
+}
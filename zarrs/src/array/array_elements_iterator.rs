@@ -0,0 +1,176 @@
+use crate::{array_subset::ArraySubset, storage::ReadableStorageTraits};
+
+use super::{codec::CodecOptions, unravel_index, Array, ArrayError, ArrayIndices, ElementOwned};
+
+/// An iterator over the elements of an array subset.
+///
+/// Elements are yielded in C-contiguous order.
+/// The array subset is retrieved in slabs spanning one chunk along the first dimension and the full extent of the array subset along every other dimension.
+/// At most one slab of elements is held in memory at a time, so peak memory scales with the extent of the array subset along the other dimensions rather than with a single chunk.
+/// A slab of a wide array subset may hold most of its elements.
+/// Slabs are retrieved and decoded as the iterator reaches them, rather than when it is created.
+///
+/// Created with [`Array::iter_elements`] or [`Array::iter_elements_opt`].
+#[derive(Debug)]
+pub struct ArrayElementsIterator<'a, TStorage: ?Sized, T> {
+    array: &'a Array<TStorage>,
+    array_subset: ArraySubset,
+    options: CodecOptions,
+    /// The slabs of the array subset that have not been retrieved.
+    slabs: std::vec::IntoIter<ArraySubset>,
+    /// The retrieved elements that have not been yielded.
+    elements: std::vec::IntoIter<T>,
+}
+
+impl<'a, TStorage: ?Sized + ReadableStorageTraits + 'static, T: ElementOwned>
+    ArrayElementsIterator<'a, TStorage, T>
+{
+    /// Return an iterator over the indices and elements of the array subset.
+    pub fn indexed(self) -> impl Iterator<Item = Result<(ArrayIndices, T), ArrayError>> + 'a
+    where
+        T: 'a,
+    {
+        let start = self.array_subset.start().to_vec();
+        let shape = self.array_subset.shape().to_vec();
+        std::iter::zip(0.., self).map(move |(position, element)| {
+            let indices = std::iter::zip(&start, unravel_index(position, &shape))
+                .map(|(start, position)| start + position)
+                .collect();
+            Ok((indices, element?))
+        })
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + 'static, T: ElementOwned> Iterator
+    for ArrayElementsIterator<'_, TStorage, T>
+{
+    type Item = Result<T, ArrayError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(element) = self.elements.next() {
+                return Some(Ok(element));
+            }
+            let slab = self.slabs.next()?;
+            match self
+                .array
+                .retrieve_array_subset_elements_opt(&slab, &self.options)
+            {
+                Ok(elements) => self.elements = elements.into_iter(),
+                Err(err) => {
+                    // Stop iterating after an error
+                    self.slabs = Vec::new().into_iter();
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
+    /// Return an iterator over the elements of the `array_subset` of the array.
+    ///
+    /// Chunks are retrieved and decoded one slab of chunks along the first dimension at a time, so arrays larger than memory can be scanned if a slab fits in memory.
+    /// See [`ArrayElementsIterator`] for the memory bound.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - `T` is incompatible with the data type of the array, or
+    ///  - `array_subset` is not within the bounds of the array.
+    pub fn iter_elements<T: ElementOwned>(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<ArrayElementsIterator<'_, TStorage, T>, ArrayError> {
+        self.iter_elements_opt(array_subset, &CodecOptions::default())
+    }
+
+    /// Explicit options version of [`iter_elements`](Array::iter_elements).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn iter_elements_opt<T: ElementOwned>(
+        &self,
+        array_subset: &ArraySubset,
+        options: &CodecOptions,
+    ) -> Result<ArrayElementsIterator<'_, TStorage, T>, ArrayError> {
+        T::validate_data_type(self.data_type())?;
+        if array_subset.dimensionality() != self.dimensionality()
+            || !array_subset.inbounds(self.shape())
+        {
+            return Err(ArrayError::InvalidArraySubset(
+                array_subset.clone(),
+                self.shape().to_vec(),
+            ));
+        }
+        Ok(ArrayElementsIterator {
+            array: self,
+            array_subset: array_subset.clone(),
+            options: options.clone(),
+            slabs: self.array_subset_slabs(array_subset)?.into_iter(),
+            elements: Vec::new().into_iter(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        array::{ArrayBuilder, DataType, FillValue},
+        storage::{
+            storage_adapter::performance_metrics::PerformanceMetricsStorageAdapter,
+            store::MemoryStore,
+        },
+    };
+
+    use super::*;
+
+    #[test]
+    fn array_iter_elements() {
+        let store = Arc::new(PerformanceMetricsStorageAdapter::new(Arc::new(
+            MemoryStore::new(),
+        )));
+        let array = ArrayBuilder::new(
+            vec![5, 4],
+            DataType::UInt8,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .build(store.clone(), "/")
+        .unwrap();
+        array
+            .store_array_subset_elements(&array.subset_all(), &(0..20).collect::<Vec<u8>>())
+            .unwrap();
+
+        assert!(array.iter_elements::<u16>(&array.subset_all()).is_err());
+        assert!(array
+            .iter_elements::<u8>(&ArraySubset::new_with_ranges(&[0..6, 0..4]))
+            .is_err());
+
+        // Chunks are not retrieved until they are reached
+        store.reset();
+        let array_subset = ArraySubset::new_with_ranges(&[1..5, 1..3]);
+        let mut elements = array.iter_elements::<u8>(&array_subset).unwrap();
+        assert_eq!(store.reads(), 0);
+        assert_eq!(elements.next().unwrap().unwrap(), 5);
+        assert_eq!(
+            elements.collect::<Result<Vec<_>, _>>().unwrap(),
+            vec![6, 9, 10, 13, 14, 17, 18]
+        );
+
+        let elements = array
+            .iter_elements::<u8>(&ArraySubset::new_with_ranges(&[3..5, 3..4]))
+            .unwrap()
+            .indexed()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(elements, vec![(vec![3, 3], 15), (vec![4, 3], 19)]);
+
+        assert_eq!(
+            array
+                .iter_elements::<u8>(&ArraySubset::new_with_ranges(&[2..2, 0..4]))
+                .unwrap()
+                .count(),
+            0
+        );
+    }
+}
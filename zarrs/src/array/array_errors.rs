@@ -61,6 +61,9 @@ pub enum ArrayCreateError {
     /// The Zarr V2 array is unsupported.
     #[error("unsupported Zarr V2 array: {_0}")]
     UnsupportedZarrV2Array(String),
+    /// The source of a broadcast array is invalid.
+    #[error("invalid broadcast source: {_0}")]
+    InvalidBroadcastSource(String),
 }
 
 /// Array errors.
@@ -102,6 +105,33 @@ pub enum ArrayError {
     /// Invalid data shape.
     #[error("data has shape {_0:?}, expected {_1:?}")]
     InvalidDataShape(Vec<usize>, Vec<usize>),
+    /// Invalid axis.
+    #[error("axis {_0} is out of bounds for an array with {_1} dimensions")]
+    InvalidAxis(usize, usize),
+    /// Invalid permutation of the array dimensions.
+    #[error("axes {_0:?} are not a permutation of the array dimensions")]
+    InvalidPermutation(Vec<usize>),
+    /// Unknown dimension name.
+    #[error("the array has no dimension named {_0}")]
+    UnknownDimensionName(String),
+    /// Invalid coordinate array.
+    #[error("invalid coordinate array for dimension {_0}: {_1}")]
+    InvalidCoordinateArray(String, String),
+    /// Invalid mask array.
+    #[error("mask array has shape {_0:?}, expected {_1:?}")]
+    InvalidMaskArray(ArrayShape, ArrayShape),
+    /// Incompatible arrays.
+    #[error("incompatible arrays: {_0}")]
+    IncompatibleArrays(String),
+    /// Invalid checksum manifest.
+    #[error("invalid checksum manifest: {_0}")]
+    InvalidChecksumManifest(String),
+    /// An Arrow conversion error.
+    #[error("arrow conversion error: {_0}")]
+    ArrowError(String),
+    /// Invalid histogram bin edges.
+    #[error("histogram bin edges {_0:?} must be at least two increasing values")]
+    InvalidHistogramBins(Vec<f64>),
     /// Invalid element value.
     ///
     /// For example
@@ -0,0 +1,152 @@
+use std::sync::Arc;
+
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+use crate::storage::{
+    ReadableListableStorageTraits, ReadableWritableListableStorageTraits, StorageError,
+    StorageHandle, StoreKey, StorePrefix,
+};
+
+use super::{Array, ArrayError, ArrayIndices};
+
+/// Chunks outside of the chunk grid of an array, found by [`Array::orphaned_chunks`] or erased by [`Array::erase_orphaned_chunks`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OrphanedChunks {
+    /// The indices of the orphaned chunks, in C order.
+    pub chunks: Vec<ArrayIndices>,
+    /// The total size of the encoded orphaned chunks in bytes.
+    pub num_bytes: u64,
+}
+
+impl<TStorage: ?Sized + ReadableListableStorageTraits + 'static> Array<TStorage> {
+    /// Find the chunks in the store that are outside of the chunk grid of the array.
+    ///
+    /// Chunks become orphaned if an array is shrunk or rechunked without erasing them.
+    /// Keys under the array prefix that are not chunk keys of the array are ignored.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if the chunk grid shape cannot be determined or there is an underlying store error.
+    pub fn orphaned_chunks(&self) -> Result<OrphanedChunks, ArrayError> {
+        let mut orphaned_chunks = OrphanedChunks::default();
+        for (_, chunk_indices, num_bytes) in self.orphaned_chunk_keys()? {
+            orphaned_chunks.chunks.push(chunk_indices);
+            orphaned_chunks.num_bytes += num_bytes;
+        }
+        Ok(orphaned_chunks)
+    }
+
+    /// Return the key, indices, and size of the chunks in the store that are outside of the chunk grid of the array, sorted by indices.
+    fn orphaned_chunk_keys(&self) -> Result<Vec<(StoreKey, ArrayIndices, u64)>, ArrayError> {
+        let Some(chunk_grid_shape) = self.chunk_grid_shape() else {
+            return Err(ArrayError::InvalidArraySubset(
+                self.subset_all(),
+                self.shape().to_vec(),
+            ));
+        };
+        let prefix: StorePrefix = self.path().try_into().map_err(StorageError::from)?;
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer_listable = self
+            .storage_transformers()
+            .create_listable_transformer(storage_handle.clone())?;
+        let storage_transformer = self
+            .storage_transformers()
+            .create_readable_transformer(storage_handle)?;
+
+        let mut orphaned_chunks = Vec::new();
+        for key in storage_transformer_listable.list_prefix(&prefix)? {
+            let Some(chunk_indices) = self.chunk_indices_from_key(&key) else {
+                continue;
+            };
+            if std::iter::zip(&chunk_indices, &chunk_grid_shape).all(|(index, shape)| index < shape)
+            {
+                continue;
+            }
+            let num_bytes = storage_transformer.size_key(&key)?.unwrap_or_default();
+            orphaned_chunks.push((key, chunk_indices, num_bytes));
+        }
+        orphaned_chunks.sort_by(|(_, a, _), (_, b, _)| a.cmp(b));
+        Ok(orphaned_chunks)
+    }
+}
+
+impl<
+        TStorage: ?Sized + ReadableWritableListableStorageTraits + ReadableListableStorageTraits + 'static,
+    > Array<TStorage>
+{
+    /// Erase the chunks in the store that are outside of the chunk grid of the array.
+    ///
+    /// This reclaims the space of chunks orphaned by shrinking or rechunking an array, see [`orphaned_chunks`](Array::orphaned_chunks).
+    /// Returns the erased chunks and the number of bytes reclaimed.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if the chunk grid shape cannot be determined or there is an underlying store error.
+    pub fn erase_orphaned_chunks(&self) -> Result<OrphanedChunks, ArrayError> {
+        let orphaned_chunk_keys = self.orphaned_chunk_keys()?;
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_writable_transformer(storage_handle)?;
+        orphaned_chunk_keys
+            .par_iter()
+            .try_for_each(|(key, chunk_indices, _)| {
+                storage_transformer.erase(key)?;
+                self.invalidate_chunk_cache(chunk_indices);
+                self.invalidate_cached_statistics(chunk_indices);
+                Ok::<_, StorageError>(())
+            })?;
+
+        let mut orphaned_chunks = OrphanedChunks::default();
+        for (_, chunk_indices, num_bytes) in orphaned_chunk_keys {
+            orphaned_chunks.chunks.push(chunk_indices);
+            orphaned_chunks.num_bytes += num_bytes;
+        }
+        Ok(orphaned_chunks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        array::{ArrayBuilder, DataType, FillValue},
+        storage::{store::MemoryStore, ReadableStorageTraits, WritableStorageTraits},
+    };
+
+    use super::*;
+
+    #[test]
+    fn array_erase_orphaned_chunks() {
+        let store = Arc::new(MemoryStore::new());
+        let mut array = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::UInt8,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .build(store.clone(), "/array")
+        .unwrap();
+        array.store_metadata().unwrap();
+        array
+            .store_array_subset_elements(&array.subset_all(), &[1u8; 16])
+            .unwrap();
+        store
+            .set(&StoreKey::new("array/other").unwrap(), vec![0u8; 8].into())
+            .unwrap();
+        assert_eq!(array.orphaned_chunks().unwrap(), OrphanedChunks::default());
+
+        // Shrink the array without erasing chunks
+        array.set_shape(vec![2, 3]);
+        let orphaned_chunks = array.orphaned_chunks().unwrap();
+        assert_eq!(orphaned_chunks.chunks, vec![vec![1, 0], vec![1, 1]]);
+        assert_eq!(orphaned_chunks.num_bytes, 8);
+
+        assert_eq!(array.erase_orphaned_chunks().unwrap(), orphaned_chunks);
+        assert!(store.get(&array.chunk_key(&[1, 0])).unwrap().is_none());
+        assert!(store.get(&array.chunk_key(&[1, 1])).unwrap().is_none());
+        assert!(store.get(&array.chunk_key(&[0, 1])).unwrap().is_some());
+        assert!(store
+            .get(&StoreKey::new("array/other").unwrap())
+            .unwrap()
+            .is_some());
+        assert_eq!(array.orphaned_chunks().unwrap(), OrphanedChunks::default());
+    }
+}
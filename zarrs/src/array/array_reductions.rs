@@ -0,0 +1,803 @@
+use std::{collections::HashMap, hash::Hash};
+
+use num::traits::AsPrimitive;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use rayon_iter_concurrent_limit::iter_concurrent_limit;
+
+use crate::{
+    array_subset::ArraySubset,
+    storage::{ReadableStorageTraits, ReadableWritableStorageTraits},
+};
+
+use super::{
+    codec::CodecOptions, concurrency::concurrency_chunks_and_codec, Array, ArrayError, Element,
+    ElementOwned,
+};
+
+/// Summary statistics of the elements of an array subset.
+///
+/// Computed by [`Array::statistics`] and [`Array::nanstatistics`].
+/// The sum, mean, and variance are accumulated in [`f64`] precision.
+///
+/// Statistics computed with [`Array::statistics`] are NaN if any element is NaN, whereas [`Array::nanstatistics`] ignores NaN elements.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ArrayStatistics<T> {
+    count: u64,
+    min: Option<T>,
+    max: Option<T>,
+    sum: f64,
+    mean: f64,
+    /// The sum of squared differences from the mean.
+    m2: f64,
+}
+
+impl<T> Default for ArrayStatistics<T> {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            min: None,
+            max: None,
+            sum: 0.0,
+            mean: 0.0,
+            m2: 0.0,
+        }
+    }
+}
+
+/// Returns true if `value` is NaN (i.e. it is not comparable with itself).
+fn is_nan<T: PartialOrd>(value: &T) -> bool {
+    value.partial_cmp(value).is_none()
+}
+
+impl<T: Copy + PartialOrd + AsPrimitive<f64>> ArrayStatistics<T> {
+    /// Compute the statistics of `elements`, ignoring NaN elements if `skip_nan` is true.
+    #[allow(clippy::cast_precision_loss)]
+    fn from_elements(elements: &[T], skip_nan: bool) -> Self {
+        let mut statistics = Self::default();
+        for &element in elements {
+            if is_nan(&element) {
+                if skip_nan {
+                    continue;
+                }
+                // NaN propagates to the minimum and maximum
+                statistics.min = Some(element);
+                statistics.max = Some(element);
+            } else {
+                if statistics.min.map_or(true, |min| element < min) {
+                    statistics.min = Some(element);
+                }
+                if statistics.max.map_or(true, |max| element > max) {
+                    statistics.max = Some(element);
+                }
+            }
+            statistics.count += 1;
+            statistics.sum += element.as_();
+        }
+        if statistics.count > 0 {
+            statistics.mean = statistics.sum / statistics.count as f64;
+            statistics.m2 = elements
+                .iter()
+                .filter(|element| !skip_nan || !is_nan(*element))
+                .map(|element| (element.as_() - statistics.mean).powi(2))
+                .sum();
+        }
+        statistics
+    }
+
+    /// Merge the statistics of two disjoint sets of elements.
+    #[allow(clippy::cast_precision_loss)]
+    fn merge(self, other: Self) -> Self {
+        if self.count == 0 {
+            return other;
+        } else if other.count == 0 {
+            return self;
+        }
+        let min = |a: T, b: T| if is_nan(&a) || b >= a { a } else { b };
+        let max = |a: T, b: T| if is_nan(&a) || b <= a { a } else { b };
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+        let weight = other.count as f64 / count as f64;
+        Self {
+            count,
+            min: self.min.zip(other.min).map(|(a, b)| min(a, b)),
+            max: self.max.zip(other.max).map(|(a, b)| max(a, b)),
+            sum: self.sum + other.sum,
+            mean: self.mean + delta * weight,
+            m2: self.m2 + other.m2 + delta * delta * self.count as f64 * weight,
+        }
+    }
+}
+
+impl<T: Copy> ArrayStatistics<T> {
+    /// Return the number of elements.
+    #[must_use]
+    pub const fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Return the minimum element, or [`None`] if there are no elements.
+    #[must_use]
+    pub const fn min(&self) -> Option<T> {
+        self.min
+    }
+
+    /// Return the maximum element, or [`None`] if there are no elements.
+    #[must_use]
+    pub const fn max(&self) -> Option<T> {
+        self.max
+    }
+
+    /// Return the sum of the elements.
+    #[must_use]
+    pub const fn sum(&self) -> f64 {
+        self.sum
+    }
+
+    /// Return the mean of the elements, or [`None`] if there are no elements.
+    #[must_use]
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.mean)
+    }
+
+    /// Return the population variance of the elements, or [`None`] if there are no elements.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn variance(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.m2 / self.count as f64)
+    }
+
+    /// Return the population standard deviation of the elements, or [`None`] if there are no elements.
+    #[must_use]
+    pub fn std(&self) -> Option<f64> {
+        self.variance().map(f64::sqrt)
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
+    /// Reduce the elements of `array_subset` of the array chunk-parallel.
+    ///
+    /// `fold` is called with the overlap of each chunk with `array_subset` and its elements, and the results are combined with `combine`.
+    /// Chunks are retrieved and reduced as they are reached, so `array_subset` is never held in memory at once.
+    pub(super) fn reduce_array_subset_elements_opt<T: ElementOwned, A: Send>(
+        &self,
+        array_subset: &ArraySubset,
+        identity: impl Fn() -> A + Send + Sync,
+        fold: impl Fn(&ArraySubset, Vec<T>) -> Result<A, ArrayError> + Send + Sync,
+        combine: impl Fn(A, A) -> A + Send + Sync,
+        options: &CodecOptions,
+    ) -> Result<A, ArrayError> {
+        T::validate_data_type(self.data_type())?;
+        if array_subset.dimensionality() != self.dimensionality()
+            || !array_subset.inbounds(self.shape())
+        {
+            return Err(ArrayError::InvalidArraySubset(
+                array_subset.clone(),
+                self.shape().to_vec(),
+            ));
+        }
+        if array_subset.is_empty() {
+            return Ok(identity());
+        }
+        let Some(chunks) = self.chunks_in_array_subset(array_subset)? else {
+            return Err(ArrayError::InvalidArraySubset(
+                array_subset.clone(),
+                self.shape().to_vec(),
+            ));
+        };
+
+        // Calculate chunk/codec concurrency
+        let chunk_representation =
+            self.chunk_array_representation(&vec![0; self.dimensionality()])?;
+        let codec_concurrency = self.recommended_codec_concurrency(&chunk_representation)?;
+        let (chunk_concurrent_limit, options) = concurrency_chunks_and_codec(
+            options.concurrent_target(),
+            chunks.num_elements_usize(),
+            options,
+            &codec_concurrency,
+        );
+
+        let reduce_chunk = |chunk_indices: Vec<u64>| -> Result<A, ArrayError> {
+            let chunk_subset = self.chunk_subset(&chunk_indices)?;
+            let overlap = unsafe { array_subset.overlap_unchecked(&chunk_subset) };
+            let elements = self.retrieve_array_subset_elements_opt(&overlap, &options)?;
+            fold(&overlap, elements)
+        };
+        let indices = chunks.indices();
+        iter_concurrent_limit!(chunk_concurrent_limit, indices, map, reduce_chunk)
+            .try_reduce(&identity, |a, b| Ok(combine(a, b)))
+    }
+
+    /// Compute the [`ArrayStatistics`] of the elements of `array_subset` of the array with default codec options.
+    ///
+    /// See [`statistics_opt`](Array::statistics_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn statistics<T: ElementOwned + Copy + PartialOrd + AsPrimitive<f64> + Send>(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<ArrayStatistics<T>, ArrayError> {
+        self.statistics_opt(array_subset, &CodecOptions::default())
+    }
+
+    /// Compute the [`ArrayStatistics`] of the elements of `array_subset` of the array.
+    ///
+    /// The statistics are computed for each chunk intersecting `array_subset` in parallel and then merged.
+    /// The statistics are NaN if any element is NaN.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - `T` is incompatible with the data type of the array,
+    ///  - `array_subset` is not within the bounds of the array,
+    ///  - there is a codec decoding error, or
+    ///  - an underlying store error.
+    pub fn statistics_opt<T: ElementOwned + Copy + PartialOrd + AsPrimitive<f64> + Send>(
+        &self,
+        array_subset: &ArraySubset,
+        options: &CodecOptions,
+    ) -> Result<ArrayStatistics<T>, ArrayError> {
+        self.reduce_array_subset_elements_opt(
+            array_subset,
+            ArrayStatistics::default,
+            |_, elements| Ok(ArrayStatistics::from_elements(&elements, false)),
+            ArrayStatistics::merge,
+            options,
+        )
+    }
+
+    /// Compute the [`ArrayStatistics`] of the elements of `array_subset` of the array ignoring NaN elements with default codec options.
+    ///
+    /// See [`nanstatistics_opt`](Array::nanstatistics_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn nanstatistics<T: ElementOwned + Copy + PartialOrd + AsPrimitive<f64> + Send>(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<ArrayStatistics<T>, ArrayError> {
+        self.nanstatistics_opt(array_subset, &CodecOptions::default())
+    }
+
+    /// Compute the [`ArrayStatistics`] of the elements of `array_subset` of the array ignoring NaN elements.
+    ///
+    /// See [`statistics_opt`](Array::statistics_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn nanstatistics_opt<T: ElementOwned + Copy + PartialOrd + AsPrimitive<f64> + Send>(
+        &self,
+        array_subset: &ArraySubset,
+        options: &CodecOptions,
+    ) -> Result<ArrayStatistics<T>, ArrayError> {
+        self.reduce_array_subset_elements_opt(
+            array_subset,
+            ArrayStatistics::default,
+            |_, elements| Ok(ArrayStatistics::from_elements(&elements, true)),
+            ArrayStatistics::merge,
+            options,
+        )
+    }
+
+    /// Return the minimum element of `array_subset` of the array, or [`None`] if it is empty.
+    ///
+    /// The minimum is NaN if any element is NaN.
+    /// Use [`statistics_opt`](Array::statistics_opt) to control codec options.
+    #[allow(clippy::missing_errors_doc)]
+    pub fn min<T: ElementOwned + Copy + PartialOrd + AsPrimitive<f64> + Send>(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<Option<T>, ArrayError> {
+        Ok(self.statistics(array_subset)?.min())
+    }
+
+    /// Return the maximum element of `array_subset` of the array, or [`None`] if it is empty.
+    ///
+    /// The maximum is NaN if any element is NaN.
+    /// Use [`statistics_opt`](Array::statistics_opt) to control codec options.
+    #[allow(clippy::missing_errors_doc)]
+    pub fn max<T: ElementOwned + Copy + PartialOrd + AsPrimitive<f64> + Send>(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<Option<T>, ArrayError> {
+        Ok(self.statistics(array_subset)?.max())
+    }
+
+    /// Return the sum of the elements of `array_subset` of the array.
+    ///
+    /// The sum is NaN if any element is NaN.
+    /// Use [`statistics_opt`](Array::statistics_opt) to control codec options.
+    #[allow(clippy::missing_errors_doc)]
+    pub fn sum<T: ElementOwned + Copy + PartialOrd + AsPrimitive<f64> + Send>(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<f64, ArrayError> {
+        Ok(self.statistics::<T>(array_subset)?.sum())
+    }
+
+    /// Return the mean of the elements of `array_subset` of the array, or [`None`] if it is empty.
+    ///
+    /// The mean is NaN if any element is NaN.
+    /// Use [`statistics_opt`](Array::statistics_opt) to control codec options.
+    #[allow(clippy::missing_errors_doc)]
+    pub fn mean<T: ElementOwned + Copy + PartialOrd + AsPrimitive<f64> + Send>(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<Option<f64>, ArrayError> {
+        Ok(self.statistics::<T>(array_subset)?.mean())
+    }
+
+    /// Return the population standard deviation of the elements of `array_subset` of the array, or [`None`] if it is empty.
+    ///
+    /// The standard deviation is NaN if any element is NaN.
+    /// Use [`statistics_opt`](Array::statistics_opt) to control codec options.
+    #[allow(clippy::missing_errors_doc)]
+    pub fn std<T: ElementOwned + Copy + PartialOrd + AsPrimitive<f64> + Send>(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<Option<f64>, ArrayError> {
+        Ok(self.statistics::<T>(array_subset)?.std())
+    }
+
+    /// Return the minimum element of `array_subset` of the array ignoring NaN elements, or [`None`] if there are none.
+    ///
+    /// Use [`nanstatistics_opt`](Array::nanstatistics_opt) to control codec options.
+    #[allow(clippy::missing_errors_doc)]
+    pub fn nanmin<T: ElementOwned + Copy + PartialOrd + AsPrimitive<f64> + Send>(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<Option<T>, ArrayError> {
+        Ok(self.nanstatistics(array_subset)?.min())
+    }
+
+    /// Return the maximum element of `array_subset` of the array ignoring NaN elements, or [`None`] if there are none.
+    ///
+    /// Use [`nanstatistics_opt`](Array::nanstatistics_opt) to control codec options.
+    #[allow(clippy::missing_errors_doc)]
+    pub fn nanmax<T: ElementOwned + Copy + PartialOrd + AsPrimitive<f64> + Send>(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<Option<T>, ArrayError> {
+        Ok(self.nanstatistics(array_subset)?.max())
+    }
+
+    /// Return the sum of the elements of `array_subset` of the array ignoring NaN elements.
+    ///
+    /// Use [`nanstatistics_opt`](Array::nanstatistics_opt) to control codec options.
+    #[allow(clippy::missing_errors_doc)]
+    pub fn nansum<T: ElementOwned + Copy + PartialOrd + AsPrimitive<f64> + Send>(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<f64, ArrayError> {
+        Ok(self.nanstatistics::<T>(array_subset)?.sum())
+    }
+
+    /// Return the mean of the elements of `array_subset` of the array ignoring NaN elements, or [`None`] if there are none.
+    ///
+    /// Use [`nanstatistics_opt`](Array::nanstatistics_opt) to control codec options.
+    #[allow(clippy::missing_errors_doc)]
+    pub fn nanmean<T: ElementOwned + Copy + PartialOrd + AsPrimitive<f64> + Send>(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<Option<f64>, ArrayError> {
+        Ok(self.nanstatistics::<T>(array_subset)?.mean())
+    }
+
+    /// Return the population standard deviation of the elements of `array_subset` of the array ignoring NaN elements, or [`None`] if there are none.
+    ///
+    /// Use [`nanstatistics_opt`](Array::nanstatistics_opt) to control codec options.
+    #[allow(clippy::missing_errors_doc)]
+    pub fn nanstd<T: ElementOwned + Copy + PartialOrd + AsPrimitive<f64> + Send>(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<Option<f64>, ArrayError> {
+        Ok(self.nanstatistics::<T>(array_subset)?.std())
+    }
+
+    /// Count the elements of `array_subset` of the array in each of the histogram bins with edges `bins` with default codec options.
+    ///
+    /// See [`histogram_opt`](Array::histogram_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn histogram<T: ElementOwned + Copy + AsPrimitive<f64>>(
+        &self,
+        bins: &[f64],
+        array_subset: &ArraySubset,
+    ) -> Result<Vec<u64>, ArrayError> {
+        self.histogram_opt::<T>(bins, array_subset, &CodecOptions::default())
+    }
+
+    /// Count the elements of `array_subset` of the array in each of the histogram bins with edges `bins`.
+    ///
+    /// `bins` are the increasing edges of the bins, so there is one fewer bin than edges.
+    /// Each bin includes its lower edge and excludes its upper edge, except the last bin which includes both.
+    /// Elements outside of the bins and NaN elements are not counted.
+    ///
+    /// The elements of each chunk intersecting `array_subset` are counted in parallel and then the counts are summed.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - `bins` has fewer than two edges or is not increasing,
+    ///  - `T` is incompatible with the data type of the array,
+    ///  - `array_subset` is not within the bounds of the array,
+    ///  - there is a codec decoding error, or
+    ///  - an underlying store error.
+    pub fn histogram_opt<T: ElementOwned + Copy + AsPrimitive<f64>>(
+        &self,
+        bins: &[f64],
+        array_subset: &ArraySubset,
+        options: &CodecOptions,
+    ) -> Result<Vec<u64>, ArrayError> {
+        let increasing = bins.windows(2).all(|edges| edges[0] < edges[1]);
+        if bins.len() < 2 || !increasing {
+            return Err(ArrayError::InvalidHistogramBins(bins.to_vec()));
+        }
+        let num_bins = bins.len() - 1;
+        let (first, last) = (bins[0], bins[num_bins]);
+        self.reduce_array_subset_elements_opt(
+            array_subset,
+            || vec![0; num_bins],
+            |_, elements: Vec<T>| {
+                let mut counts = vec![0; num_bins];
+                for element in elements {
+                    let value: f64 = element.as_();
+                    // NaN elements are outside of every bin
+                    if value >= first && value <= last {
+                        let bin = bins.partition_point(|&edge| edge <= value) - 1;
+                        counts[bin.min(num_bins - 1)] += 1;
+                    }
+                }
+                Ok(counts)
+            },
+            |mut a, b| {
+                std::iter::zip(&mut a, b).for_each(|(a, b)| *a += b);
+                a
+            },
+            options,
+        )
+    }
+
+    /// Count the occurrences of each element value of `array_subset` of the array with default codec options.
+    ///
+    /// See [`value_counts_opt`](Array::value_counts_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn value_counts<T: ElementOwned + Eq + Hash + Send>(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<HashMap<T, u64>, ArrayError> {
+        self.value_counts_opt(array_subset, &CodecOptions::default())
+    }
+
+    /// Count the occurrences of each element value of `array_subset` of the array.
+    ///
+    /// This is intended for integer or label arrays with relatively few distinct values.
+    /// The elements of each chunk intersecting `array_subset` are counted in parallel and then the counts are merged.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - `T` is incompatible with the data type of the array,
+    ///  - `array_subset` is not within the bounds of the array,
+    ///  - there is a codec decoding error, or
+    ///  - an underlying store error.
+    pub fn value_counts_opt<T: ElementOwned + Eq + Hash + Send>(
+        &self,
+        array_subset: &ArraySubset,
+        options: &CodecOptions,
+    ) -> Result<HashMap<T, u64>, ArrayError> {
+        self.reduce_array_subset_elements_opt(
+            array_subset,
+            HashMap::new,
+            |_, elements: Vec<T>| {
+                let mut counts = HashMap::new();
+                for element in elements {
+                    *counts.entry(element).or_insert(0) += 1;
+                }
+                Ok(counts)
+            },
+            |mut a, b| {
+                for (element, count) in b {
+                    *a.entry(element).or_insert(0) += count;
+                }
+                a
+            },
+            options,
+        )
+    }
+
+    /// Apply `f` to the elements of `array_subset` of the array and store the results in the same array subset of `output` with default codec options.
+    ///
+    /// See [`map_array_subset_into_opt`](Array::map_array_subset_into_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn map_array_subset_into<
+        T: ElementOwned,
+        U: Element,
+        TStorageOut: ?Sized + ReadableWritableStorageTraits + 'static,
+    >(
+        &self,
+        array_subset: &ArraySubset,
+        output: &Array<TStorageOut>,
+        f: impl Fn(T) -> U + Send + Sync,
+    ) -> Result<(), ArrayError> {
+        self.map_array_subset_into_opt(array_subset, output, f, &CodecOptions::default())
+    }
+
+    /// Apply `f` to the elements of `array_subset` of the array and store the results in the same array subset of `output`.
+    ///
+    /// The elements of each chunk intersecting `array_subset` are mapped in parallel, so `array_subset` is never held in memory at once.
+    /// `output` must have the shape of the array, but may have a different data type, chunk grid, or codecs.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - the shape of `output` does not match the shape of the array,
+    ///  - `T` or `U` is incompatible with the data type of the array or `output`,
+    ///  - `array_subset` is not within the bounds of the array,
+    ///  - there is a codec decoding or encoding error, or
+    ///  - an underlying store error.
+    ///
+    /// # Panics
+    /// Panics if a dimension of the array or `output` exceeds `usize::MAX`.
+    pub fn map_array_subset_into_opt<
+        T: ElementOwned,
+        U: Element,
+        TStorageOut: ?Sized + ReadableWritableStorageTraits + 'static,
+    >(
+        &self,
+        array_subset: &ArraySubset,
+        output: &Array<TStorageOut>,
+        f: impl Fn(T) -> U + Send + Sync,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        if output.shape() != self.shape() {
+            return Err(ArrayError::InvalidDataShape(
+                output
+                    .shape()
+                    .iter()
+                    .map(|&length| usize::try_from(length).unwrap())
+                    .collect(),
+                self.shape()
+                    .iter()
+                    .map(|&length| usize::try_from(length).unwrap())
+                    .collect(),
+            ));
+        }
+        U::validate_data_type(output.data_type())?;
+        self.reduce_array_subset_elements_opt(
+            array_subset,
+            || (),
+            |overlap, elements: Vec<T>| {
+                let elements: Vec<U> = elements.into_iter().map(&f).collect();
+                output.store_array_subset_elements_opt(overlap, &elements, options)
+            },
+            |(), ()| (),
+            options,
+        )
+    }
+}
+
+impl<TStorage: ?Sized + ReadableWritableStorageTraits + 'static> Array<TStorage> {
+    /// Apply `f` to the elements of `array_subset` of the array in place with default codec options.
+    ///
+    /// See [`map_array_subset_inplace_opt`](Array::map_array_subset_inplace_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn map_array_subset_inplace<T: ElementOwned + Copy>(
+        &self,
+        array_subset: &ArraySubset,
+        f: impl Fn(T) -> T + Send + Sync,
+    ) -> Result<(), ArrayError> {
+        self.map_array_subset_inplace_opt(array_subset, f, &CodecOptions::default())
+    }
+
+    /// Apply `f` to the elements of `array_subset` of the array in place.
+    ///
+    /// Each chunk intersecting `array_subset` is updated with [`update_array_subset_opt`](Array::update_array_subset_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn map_array_subset_inplace_opt<T: ElementOwned + Copy>(
+        &self,
+        array_subset: &ArraySubset,
+        f: impl Fn(T) -> T + Send + Sync,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        self.update_array_subset_opt(
+            array_subset,
+            |_, elements: &mut [T]| {
+                for element in elements {
+                    *element = f(*element);
+                }
+            },
+            options,
+        )
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        array::{ArrayBuilder, DataType, FillValue},
+        storage::store::MemoryStore,
+    };
+
+    use super::*;
+
+    #[test]
+    fn array_reductions() {
+        let store = Arc::new(MemoryStore::new());
+        let array = ArrayBuilder::new(
+            vec![5, 4],
+            DataType::Float32,
+            vec![2, 3].try_into().unwrap(),
+            FillValue::from(0.0f32),
+        )
+        .build(store.clone(), "/array")
+        .unwrap();
+        let elements: Vec<f32> = (0..20u8).map(f32::from).collect();
+        array
+            .store_array_subset_elements(&array.subset_all(), &elements)
+            .unwrap();
+
+        assert!(array.statistics::<u8>(&array.subset_all()).is_err());
+        assert!(array
+            .statistics::<f32>(&ArraySubset::new_with_ranges(&[0..6, 0..4]))
+            .is_err());
+
+        let statistics = array.statistics::<f32>(&array.subset_all()).unwrap();
+        assert_eq!(statistics.count(), 20);
+        assert_eq!(statistics.min(), Some(0.0));
+        assert_eq!(statistics.max(), Some(19.0));
+        assert_eq!(statistics.sum(), 190.0);
+        assert_eq!(statistics.mean(), Some(9.5));
+        assert!((statistics.variance().unwrap() - 33.25).abs() < 1e-9);
+
+        // Subsets spanning part of each chunk
+        let array_subset = ArraySubset::new_with_ranges(&[1..4, 2..4]);
+        assert_eq!(array.min::<f32>(&array_subset).unwrap(), Some(6.0));
+        assert_eq!(array.max::<f32>(&array_subset).unwrap(), Some(15.0));
+        assert_eq!(array.sum::<f32>(&array_subset).unwrap(), 63.0);
+        assert_eq!(array.mean::<f32>(&array_subset).unwrap(), Some(10.5));
+        assert!(
+            (array.std::<f32>(&array_subset).unwrap().unwrap() - (65.5f64 / 6.0).sqrt()).abs()
+                < 1e-9
+        );
+        assert_eq!(
+            array
+                .statistics::<f32>(&ArraySubset::new_with_ranges(&[1..1, 0..4]))
+                .unwrap(),
+            ArrayStatistics::default()
+        );
+
+        // NaN propagates unless it is ignored
+        array
+            .store_array_subset_elements(&ArraySubset::new_with_ranges(&[2..3, 1..2]), &[f32::NAN])
+            .unwrap();
+        assert_eq!(array.min::<f32>(&array_subset).unwrap(), Some(6.0));
+        assert!(array
+            .max::<f32>(&array.subset_all())
+            .unwrap()
+            .unwrap()
+            .is_nan());
+        assert!(array
+            .mean::<f32>(&array.subset_all())
+            .unwrap()
+            .unwrap()
+            .is_nan());
+        assert_eq!(array.nanmin::<f32>(&array.subset_all()).unwrap(), Some(0.0));
+        assert_eq!(
+            array.nanmax::<f32>(&array.subset_all()).unwrap(),
+            Some(19.0)
+        );
+        assert_eq!(array.nansum::<f32>(&array.subset_all()).unwrap(), 181.0);
+        assert_eq!(
+            array
+                .nanstatistics::<f32>(&array.subset_all())
+                .unwrap()
+                .count(),
+            19
+        );
+    }
+
+    #[test]
+    fn array_map_array_subset() {
+        let store = Arc::new(MemoryStore::new());
+        let array = ArrayBuilder::new(
+            vec![5, 4],
+            DataType::Float32,
+            vec![2, 3].try_into().unwrap(),
+            FillValue::from(0.0f32),
+        )
+        .build(store.clone(), "/array")
+        .unwrap();
+        let elements: Vec<f32> = (0..20u8).map(f32::from).collect();
+        array
+            .store_array_subset_elements(&array.subset_all(), &elements)
+            .unwrap();
+        array
+            .store_array_subset_elements(&ArraySubset::new_with_ranges(&[2..3, 2..3]), &[f32::NAN])
+            .unwrap();
+
+        let array_subset = ArraySubset::new_with_ranges(&[1..4, 2..4]);
+        let output = ArrayBuilder::new(
+            vec![5, 4],
+            DataType::UInt8,
+            vec![4, 4].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .build(store, "/output")
+        .unwrap();
+        array
+            .map_array_subset_into(&array_subset, &output, |element: f32| {
+                if element.is_nan() {
+                    u8::MAX
+                } else {
+                    num::cast(element * 2.0).unwrap()
+                }
+            })
+            .unwrap();
+        assert_eq!(
+            output
+                .retrieve_array_subset_elements::<u8>(&array_subset)
+                .unwrap(),
+            vec![12, 14, 255, 22, 28, 30]
+        );
+        let output_small = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::Float32,
+            vec![4, 4].try_into().unwrap(),
+            FillValue::from(0.0f32),
+        )
+        .build(Arc::new(MemoryStore::new()), "/")
+        .unwrap();
+        assert!(array
+            .map_array_subset_into(&array_subset, &output_small, |element: f32| element)
+            .is_err());
+
+        array
+            .map_array_subset_inplace(&array_subset, |element: f32| -element)
+            .unwrap();
+        assert_eq!(array.nansum::<f32>(&array_subset).unwrap(), -53.0);
+    }
+
+    #[test]
+    fn array_histogram_value_counts() {
+        let array = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::UInt16,
+            vec![3, 3].try_into().unwrap(),
+            FillValue::from(0u16),
+        )
+        .build(Arc::new(MemoryStore::new()), "/")
+        .unwrap();
+        let elements: Vec<u16> = (0..16).map(|i| i % 5).collect();
+        array
+            .store_array_subset_elements(&array.subset_all(), &elements)
+            .unwrap();
+
+        assert!(array.histogram::<u16>(&[0.0], &array.subset_all()).is_err());
+        assert!(array
+            .histogram::<u16>(&[0.0, 2.0, 2.0], &array.subset_all())
+            .is_err());
+        assert!(array
+            .histogram::<u8>(&[0.0, 1.0], &array.subset_all())
+            .is_err());
+
+        // The last bin includes its upper edge, and elements outside of the bins are not counted
+        assert_eq!(
+            array
+                .histogram::<u16>(&[1.0, 2.0, 3.5], &array.subset_all())
+                .unwrap(),
+            vec![3, 6]
+        );
+        assert_eq!(
+            array
+                .histogram::<u16>(
+                    &[0.0, 2.0, 4.0],
+                    &ArraySubset::new_with_ranges(&[1..3, 2..4])
+                )
+                .unwrap(),
+            vec![3, 1]
+        );
+
+        let value_counts = array.value_counts::<u16>(&array.subset_all()).unwrap();
+        assert_eq!(
+            value_counts,
+            HashMap::from([(0, 4), (1, 3), (2, 3), (3, 3), (4, 3)])
+        );
+        assert!(array
+            .value_counts::<u16>(&ArraySubset::new_with_ranges(&[0..0, 0..4]))
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -1,4 +1,7 @@
-use super::{codec::ShardingCodecConfiguration, Array, ArrayShape, ChunkGrid, ChunkShape};
+use super::{
+    codec::{CodecError, ShardingCodec, ShardingCodecConfiguration},
+    Array, ArrayShape, ChunkGrid, ChunkShape,
+};
 
 /// An [`Array`] extension trait to simplify working with arrays using the `sharding_indexed` codec.
 pub trait ArrayShardedExt {
@@ -86,3 +89,28 @@ impl<TStorage: ?Sized> ArrayShardedExt for Array<TStorage> {
         }
     }
 }
+
+/// Return the `sharding_indexed` codec of a sharded array.
+///
+/// The encoded shards of the array must not be encoded by any subsequent bytes to bytes codecs.
+pub(super) fn array_sharding_codec<TStorage: ?Sized>(
+    array: &Array<TStorage>,
+) -> Result<ShardingCodec, CodecError> {
+    if !array.is_sharded() {
+        return Err(CodecError::Other("the array is not sharded".to_string()));
+    }
+    if !array.codecs().bytes_to_bytes_codecs().is_empty() {
+        return Err(CodecError::Other(
+            "the array has bytes to bytes codecs after the sharding codec".to_string(),
+        ));
+    }
+    let sharding_configuration: ShardingCodecConfiguration = array
+        .codecs()
+        .array_to_bytes_codec()
+        .create_metadata()
+        .expect("the array to bytes codec should have metadata")
+        .to_configuration()
+        .map_err(|err| CodecError::Other(err.to_string()))?;
+    ShardingCodec::new_with_configuration(&sharding_configuration)
+        .map_err(|err| CodecError::Other(err.to_string()))
+}
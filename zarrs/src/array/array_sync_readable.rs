@@ -1,26 +1,32 @@
 use std::{borrow::Cow, sync::Arc};
 
-use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use rayon::{
+    iter::{IntoParallelIterator, ParallelIterator},
+    slice::ParallelSlice,
+};
 use rayon_iter_concurrent_limit::iter_concurrent_limit;
 use unsafe_cell_slice::UnsafeCellSlice;
 
 use crate::{
     array::{ArrayBytes, ArrayMetadataV2},
-    array_subset::ArraySubset,
+    array_subset::{ArraySlice, ArraySubset},
     config::MetadataRetrieveVersion,
-    node::{meta_key_v2_array, meta_key_v2_attributes, meta_key_v3, NodePath},
-    storage::{ReadableStorageTraits, StorageError, StorageHandle},
+    node::{
+        meta_key_v2_array, meta_key_v2_attributes, meta_key_v3, metadata_v2_from_slices, NodePath,
+    },
+    storage::{Bytes, MaybeBytes, ReadableStorageTraits, StorageError, StorageHandle, StoreKey},
 };
 
 use super::{
-    array_bytes::{copy_fill_value_into, merge_chunks_vlen},
+    array_bytes::{concatenate_array_bytes, merge_chunks_vlen, split_array_bytes_elements},
     codec::{
         options::CodecOptions, ArrayPartialDecoderTraits, ArrayToBytesCodecTraits,
-        StoragePartialDecoder,
+        CodecProfileOperation, StoragePartialDecoder,
     },
     concurrency::concurrency_chunks_and_codec,
     element::ElementOwned,
-    Array, ArrayCreateError, ArrayError, ArrayMetadata, ArrayMetadataV3, ArraySize, DataTypeSize,
+    update_array_bytes, Array, ArrayCreateError, ArrayError, ArrayIndices, ArrayMetadata,
+    ArrayMetadataV3, ArraySize, ChunkCache, ChunkCacheDecodedLruSizeLimit, DataTypeSize,
 };
 
 #[cfg(feature = "ndarray")]
@@ -51,29 +57,34 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
         if let MetadataRetrieveVersion::Default | MetadataRetrieveVersion::V3 = version {
             // Try V3
             let key_v3 = meta_key_v3(&node_path);
-            if let Some(metadata) = storage.get(&key_v3)? {
-                let metadata: ArrayMetadataV3 = serde_json::from_slice(&metadata)
-                    .map_err(|err| StorageError::InvalidMetadata(key_v3, err.to_string()))?;
-                return Self::new_with_metadata(storage, path, ArrayMetadata::V3(metadata));
+            if let (Some(metadata), etag) = storage.get_with_etag(&key_v3)? {
+                let metadata: ArrayMetadataV3 =
+                    serde_json::from_slice(&metadata).map_err(|err| {
+                        StorageError::InvalidMetadata(key_v3.clone(), err.to_string())
+                    })?;
+                let array = Self::new_with_metadata(storage, path, ArrayMetadata::V3(metadata))?;
+                array.set_metadata_etag(&key_v3, etag);
+                return Ok(array);
             }
         }
 
         if let MetadataRetrieveVersion::Default | MetadataRetrieveVersion::V2 = version {
             // Try V2
             let key_v2 = meta_key_v2_array(&node_path);
-            if let Some(metadata) = storage.get(&key_v2)? {
-                let mut metadata: ArrayMetadataV2 = serde_json::from_slice(&metadata)
-                    .map_err(|err| StorageError::InvalidMetadata(key_v2, err.to_string()))?;
-
+            if let (Some(metadata), etag) = storage.get_with_etag(&key_v2)? {
                 let attributes_key = meta_key_v2_attributes(&node_path);
-                let attributes = storage.get(&attributes_key)?;
-                if let Some(attributes) = attributes {
-                    metadata.attributes = serde_json::from_slice(&attributes).map_err(|err| {
-                        StorageError::InvalidMetadata(attributes_key, err.to_string())
-                    })?;
-                }
+                let (attributes, attributes_etag) = storage.get_with_etag(&attributes_key)?;
+                let metadata: ArrayMetadataV2 = metadata_v2_from_slices(
+                    &key_v2,
+                    &metadata,
+                    &attributes_key,
+                    attributes.as_deref(),
+                )?;
 
-                return Self::new_with_metadata(storage, path, ArrayMetadata::V2(metadata));
+                let array = Self::new_with_metadata(storage, path, ArrayMetadata::V2(metadata))?;
+                array.set_metadata_etag(&key_v2, etag);
+                array.set_metadata_etag(&attributes_key, attributes_etag);
+                return Ok(array);
             }
         }
 
@@ -142,14 +153,26 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
         &self,
         chunk_indices: &[u64],
     ) -> Result<Option<Vec<u8>>, StorageError> {
+        self.retrieve_encoded_chunk_bytes(chunk_indices)
+            .map(|maybe_bytes| maybe_bytes.map(|bytes| bytes.to_vec()))
+    }
+
+    /// Retrieve the encoded bytes of a chunk as [`Bytes`].
+    ///
+    /// Unlike [`retrieve_encoded_chunk`](Array::retrieve_encoded_chunk), the bytes retrieved from the store are not copied.
+    ///
+    /// # Errors
+    /// Returns an [`StorageError`] if there is an underlying store error.
+    pub fn retrieve_encoded_chunk_bytes(
+        &self,
+        chunk_indices: &[u64],
+    ) -> Result<MaybeBytes, StorageError> {
         let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
         let storage_transformer = self
             .storage_transformers()
             .create_readable_transformer(storage_handle)?;
 
-        storage_transformer
-            .get(&self.chunk_key(chunk_indices))
-            .map(|maybe_bytes| maybe_bytes.map(|bytes| bytes.to_vec()))
+        storage_transformer.get(&self.chunk_key(chunk_indices))
     }
 
     /// Read and decode the chunk at `chunk_indices` into its bytes or the fill value if it does not exist with default codec options.
@@ -166,6 +189,21 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
         self.retrieve_chunk_opt(chunk_indices, &CodecOptions::default())
     }
 
+    /// Read and decode the chunk at `chunk_indices` into [`Bytes`] or the fill value if it does not exist with default codec options.
+    ///
+    /// If the array has no chunk cache and its codecs do not transform the encoded bytes of the chunk (see [`ArrayToBytesCodecTraits::is_passthrough`]), the bytes retrieved from the store are returned without copying.
+    /// This is only possible for fixed size data types.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - `chunk_indices` are invalid,
+    ///  - the data type is not fixed size,
+    ///  - there is a codec decoding error, or
+    ///  - an underlying store error.
+    pub fn retrieve_chunk_bytes(&self, chunk_indices: &[u64]) -> Result<Bytes, ArrayError> {
+        self.retrieve_chunk_bytes_opt(chunk_indices, &CodecOptions::default())
+    }
+
     /// Read and decode the chunk at `chunk_indices` into a vector of its elements or the fill value if it does not exist.
     ///
     /// # Errors
@@ -213,25 +251,12 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
         chunks: &ArraySubset,
         options: &CodecOptions,
     ) -> Result<Vec<Option<Vec<u8>>>, StorageError> {
-        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
-        let storage_transformer = self
-            .storage_transformers()
-            .create_readable_transformer(storage_handle)?;
-
-        let retrieve_encoded_chunk = |chunk_indices: Vec<u64>| {
-            storage_transformer
-                .get(&self.chunk_key(&chunk_indices))
-                .map(|maybe_bytes| maybe_bytes.map(|bytes| bytes.to_vec()))
-        };
-
-        let indices = chunks.indices();
-        iter_concurrent_limit!(
-            options.concurrent_target(),
-            indices,
-            map,
-            retrieve_encoded_chunk
-        )
-        .collect()
+        let chunk_indices: Vec<ArrayIndices> = chunks.indices().into_iter().collect();
+        Ok(self
+            .retrieve_encoded_chunks_batched(&chunk_indices, options.concurrent_target())?
+            .into_iter()
+            .map(|maybe_bytes| maybe_bytes.map(|bytes| bytes.to_vec()))
+            .collect())
     }
 
     /// Read and decode the chunks at `chunks` into their bytes.
@@ -393,6 +418,209 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
         self.retrieve_array_subset_ndarray_opt(array_subset, &CodecOptions::default())
     }
 
+    #[cfg(feature = "ndarray")]
+    /// Read and decode the `array_subset` of array into the existing `output` view.
+    ///
+    /// `output` may have any memory layout (e.g. a transposed or strided view of a larger array), but its shape must match the shape of `array_subset`.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the shape of `output` does not match the shape of `array_subset`,
+    ///  - an array subset is invalid or out of bounds of the array,
+    ///  - there is a codec decoding error, or
+    ///  - an underlying store error.
+    ///
+    /// # Panics
+    /// Will panic if any dimension in `array_subset` is `usize::MAX` or larger.
+    pub fn retrieve_into_ndarray_view_mut<T: ElementOwned, D: ndarray::Dimension>(
+        &self,
+        array_subset: &ArraySubset,
+        output: ndarray::ArrayViewMut<T, D>,
+    ) -> Result<(), ArrayError> {
+        self.retrieve_into_ndarray_view_mut_opt(array_subset, output, &CodecOptions::default())
+    }
+
+    /// Read and decode the elements at `indices` of the array.
+    ///
+    /// `indices` is a list of element coordinates in the array, which may be in any order and may repeat.
+    /// The returned elements are in the order of `indices`.
+    /// Accesses are grouped by chunk, so each chunk intersecting `indices` is only retrieved once.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - an element of `indices` has an incorrect dimensionality or is out of bounds of the array,
+    ///  - there is a codec decoding error, or
+    ///  - an underlying store error.
+    pub fn retrieve_by_indices(
+        &self,
+        indices: &[ArrayIndices],
+    ) -> Result<ArrayBytes<'_>, ArrayError> {
+        self.retrieve_by_indices_opt(indices, &CodecOptions::default())
+    }
+
+    /// Read and decode the elements at `indices` of the array into a vector of its elements.
+    ///
+    /// See [`retrieve_by_indices`](Array::retrieve_by_indices).
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the size of `T` does not match the data type size,
+    ///  - the decoded bytes cannot be transmuted,
+    ///  - an element of `indices` has an incorrect dimensionality or is out of bounds of the array,
+    ///  - there is a codec decoding error, or
+    ///  - an underlying store error.
+    pub fn retrieve_elements_by_indices<T: ElementOwned>(
+        &self,
+        indices: &[ArrayIndices],
+    ) -> Result<Vec<T>, ArrayError> {
+        self.retrieve_elements_by_indices_opt(indices, &CodecOptions::default())
+    }
+
+    /// Read and decode the elements of the `array_subset` of array selected by `mask`.
+    ///
+    /// `mask` has an element for each element of `array_subset` in C order, and the elements where `mask` is [`true`] are returned in C order.
+    /// Only the chunks with a selected element are retrieved.
+    ///
+    /// An `ndarray` mask in standard layout can be supplied with [`as_slice`](https://docs.rs/ndarray/latest/ndarray/struct.ArrayBase.html#method.as_slice).
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the array subset is invalid or out of bounds of the array,
+    ///  - the length of `mask` does not match the number of elements in `array_subset`,
+    ///  - there is a codec decoding error, or
+    ///  - an underlying store error.
+    pub fn retrieve_array_subset_masked(
+        &self,
+        array_subset: &ArraySubset,
+        mask: &[bool],
+    ) -> Result<ArrayBytes<'_>, ArrayError> {
+        self.retrieve_array_subset_masked_opt(array_subset, mask, &CodecOptions::default())
+    }
+
+    /// Read and decode the elements of the `array_subset` of array selected by `mask` into a vector of its elements.
+    ///
+    /// See [`retrieve_array_subset_masked`](Array::retrieve_array_subset_masked).
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the size of `T` does not match the data type size,
+    ///  - the decoded bytes cannot be transmuted,
+    ///  - the array subset is invalid or out of bounds of the array,
+    ///  - the length of `mask` does not match the number of elements in `array_subset`,
+    ///  - there is a codec decoding error, or
+    ///  - an underlying store error.
+    pub fn retrieve_array_subset_masked_elements<T: ElementOwned>(
+        &self,
+        array_subset: &ArraySubset,
+        mask: &[bool],
+    ) -> Result<Vec<T>, ArrayError> {
+        self.retrieve_array_subset_masked_elements_opt(array_subset, mask, &CodecOptions::default())
+    }
+
+    /// Read and decode the `array_slice` of array.
+    ///
+    /// Only the elements of the array slice are decoded from each chunk it intersects, and chunks between the elements of the array slice are not retrieved.
+    /// The elements are returned in C order with the shape of the array slice.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the array slice is invalid or out of bounds of the array,
+    ///  - there is a codec decoding error, or
+    ///  - an underlying store error.
+    pub fn retrieve_array_slice(
+        &self,
+        array_slice: &ArraySlice,
+    ) -> Result<ArrayBytes<'_>, ArrayError> {
+        self.retrieve_array_slice_opt(array_slice, &CodecOptions::default())
+    }
+
+    /// Read and decode the `array_slice` of array into a vector of its elements.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the size of `T` does not match the data type size,
+    ///  - the decoded bytes cannot be transmuted,
+    ///  - the array slice is invalid or out of bounds of the array,
+    ///  - there is a codec decoding error, or
+    ///  - an underlying store error.
+    pub fn retrieve_array_slice_elements<T: ElementOwned>(
+        &self,
+        array_slice: &ArraySlice,
+    ) -> Result<Vec<T>, ArrayError> {
+        self.retrieve_array_slice_elements_opt(array_slice, &CodecOptions::default())
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Read and decode the `array_slice` of array into an [`ndarray::ArrayD`].
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the array slice is invalid or out of bounds of the array,
+    ///  - there is a codec decoding error, or
+    ///  - an underlying store error.
+    ///
+    /// # Panics
+    /// Will panic if any dimension in `array_slice` is `usize::MAX` or larger.
+    pub fn retrieve_array_slice_ndarray<T: ElementOwned>(
+        &self,
+        array_slice: &ArraySlice,
+    ) -> Result<ndarray::ArrayD<T>, ArrayError> {
+        self.retrieve_array_slice_ndarray_opt(array_slice, &CodecOptions::default())
+    }
+
+    /// Read and decode the chunks of the array in parallel and call `f` with the indices, array subset, and bytes of each chunk.
+    ///
+    /// The chunks and their array subsets are those of [`chunks`](Array::chunks), so elements of chunks outside of the array are excluded.
+    /// The number of chunks decoded concurrently is bounded by the concurrent target of the default [`CodecOptions`].
+    /// Chunks are visited in an arbitrary order.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - a chunk cannot be retrieved,
+    ///  - `f` returns an error, or
+    ///  - there is an underlying store error.
+    pub fn par_visit_chunks(
+        &self,
+        f: impl Fn(&[u64], &ArraySubset, ArrayBytes<'_>) -> Result<(), ArrayError> + Send + Sync,
+    ) -> Result<(), ArrayError> {
+        self.par_visit_chunks_opt(f, &CodecOptions::default())
+    }
+
+    /// Read and decode the chunks of the array in parallel and call `f` with the indices, array subset, and elements of each chunk.
+    ///
+    /// See [`par_visit_chunks`](Array::par_visit_chunks).
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - the size of `T` does not match the data type size,
+    ///  - a chunk cannot be retrieved,
+    ///  - `f` returns an error, or
+    ///  - there is an underlying store error.
+    pub fn par_visit_chunks_elements<T: ElementOwned>(
+        &self,
+        f: impl Fn(&[u64], &ArraySubset, Vec<T>) -> Result<(), ArrayError> + Send + Sync,
+    ) -> Result<(), ArrayError> {
+        self.par_visit_chunks_elements_opt(f, &CodecOptions::default())
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Read and decode the chunks of the array in parallel and call `f` with the indices, array subset, and [`ndarray::ArrayD`] of each chunk.
+    ///
+    /// See [`par_visit_chunks`](Array::par_visit_chunks).
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - the size of `T` does not match the data type size,
+    ///  - a chunk cannot be retrieved,
+    ///  - `f` returns an error, or
+    ///  - there is an underlying store error.
+    pub fn par_visit_chunks_ndarray<T: ElementOwned>(
+        &self,
+        f: impl Fn(&[u64], &ArraySubset, ndarray::ArrayD<T>) -> Result<(), ArrayError> + Send + Sync,
+    ) -> Result<(), ArrayError> {
+        self.par_visit_chunks_ndarray_opt(f, &CodecOptions::default())
+    }
+
     /// Initialises a partial decoder for the chunk at `chunk_indices`.
     ///
     /// # Errors
@@ -414,6 +642,42 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
         &self,
         chunk_indices: &[u64],
         options: &CodecOptions,
+    ) -> Result<Option<ArrayBytes<'_>>, ArrayError> {
+        if let Some(chunk_cache) = &self.chunk_cache {
+            Ok(self
+                .retrieve_chunk_cached(chunk_cache, chunk_indices, options)?
+                .map(Arc::unwrap_or_clone))
+        } else {
+            self.retrieve_chunk_if_exists_uncached(chunk_indices, options)
+        }
+    }
+
+    /// Retrieve the chunk at `chunk_indices` from `chunk_cache`.
+    ///
+    /// The chunk is retrieved, decoded, and inserted into `chunk_cache` if it is not cached.
+    fn retrieve_chunk_cached(
+        &self,
+        chunk_cache: &ChunkCacheDecodedLruSizeLimit,
+        chunk_indices: &[u64],
+        options: &CodecOptions,
+    ) -> Result<Option<Arc<ArrayBytes<'static>>>, ArrayError> {
+        if let Some(chunk) = chunk_cache.get(chunk_indices) {
+            return Ok(Some(chunk));
+        }
+        let chunk = self
+            .retrieve_chunk_if_exists_uncached(chunk_indices, options)?
+            .map(|chunk| Arc::new(chunk.into_owned()));
+        if let Some(chunk) = &chunk {
+            chunk_cache.insert(chunk_indices.to_vec(), chunk.clone());
+        }
+        Ok(chunk)
+    }
+
+    /// Retrieve the chunk at `chunk_indices` without the chunk cache.
+    fn retrieve_chunk_if_exists_uncached(
+        &self,
+        chunk_indices: &[u64],
+        options: &CodecOptions,
     ) -> Result<Option<ArrayBytes<'_>>, ArrayError> {
         if chunk_indices.len() != self.dimensionality() {
             return Err(ArrayError::InvalidChunkGridIndicesError(
@@ -424,9 +688,34 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
         let storage_transformer = self
             .storage_transformers()
             .create_readable_transformer(storage_handle)?;
+        if self.codecs().supports_streaming() && options.profiler().is_none() {
+            // Stream the encoded chunk through the bytes to bytes codecs rather than holding it in memory
+            let chunk_reader = storage_transformer
+                .get_reader(&self.chunk_key(chunk_indices))
+                .map_err(ArrayError::StorageError)?;
+            let Some(chunk_reader) = chunk_reader else {
+                return Ok(None);
+            };
+            let chunk_representation = self.chunk_array_representation(chunk_indices)?;
+            let bytes = self
+                .codecs()
+                .decode_reader(chunk_reader, &chunk_representation, options)
+                .map_err(ArrayError::CodecError)?;
+            return Ok(Some(bytes));
+        }
+        let start = std::time::Instant::now();
         let chunk_encoded = storage_transformer
             .get(&self.chunk_key(chunk_indices))
             .map_err(ArrayError::StorageError)?;
+        if let Some(profiler) = options.profiler() {
+            profiler.record(
+                "store",
+                CodecProfileOperation::StoreRetrieve,
+                start.elapsed(),
+                0,
+                chunk_encoded.as_ref().map_or(0, Bytes::len),
+            );
+        }
         if let Some(chunk_encoded) = chunk_encoded {
             let chunk_encoded: Vec<u8> = chunk_encoded.into();
             let chunk_representation = self.chunk_array_representation(chunk_indices)?;
@@ -458,6 +747,27 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
         }
     }
 
+    /// Explicit options version of [`retrieve_chunk_bytes`](Array::retrieve_chunk_bytes).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_chunk_bytes_opt(
+        &self,
+        chunk_indices: &[u64],
+        options: &CodecOptions,
+    ) -> Result<Bytes, ArrayError> {
+        let chunk_representation = self.chunk_array_representation(chunk_indices)?;
+        if self.chunk_cache.is_none() && self.codecs().is_passthrough(&chunk_representation) {
+            if let Some(chunk_encoded) = self.retrieve_encoded_chunk_bytes(chunk_indices)? {
+                ArrayBytes::new_flen(chunk_encoded.as_ref())
+                    .validate(chunk_representation.num_elements(), self.data_type().size())?;
+                return Ok(chunk_encoded);
+            }
+        }
+        let chunk_bytes = self
+            .retrieve_chunk_opt(chunk_indices, options)?
+            .into_fixed()?;
+        Ok(Bytes::from(chunk_bytes.into_owned()))
+    }
+
     unsafe fn retrieve_chunk_into(
         &self,
         chunk_indices: &[u64],
@@ -478,34 +788,45 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
         let chunk_encoded = storage_transformer
             .get(&self.chunk_key(chunk_indices))
             .map_err(ArrayError::StorageError)?;
-        if let Some(chunk_encoded) = chunk_encoded {
-            let chunk_encoded: Vec<u8> = chunk_encoded.into();
-            let chunk_representation = self.chunk_array_representation(chunk_indices)?;
-            unsafe {
-                self.codecs().decode_into(
-                    Cow::Owned(chunk_encoded),
-                    &chunk_representation,
-                    output,
-                    output_shape,
-                    output_subset,
-                    options,
-                )
-            }
-            .map_err(ArrayError::CodecError)
-        } else {
-            unsafe {
-                copy_fill_value_into(
-                    self.data_type(),
-                    self.fill_value(),
-                    output,
-                    output_shape,
-                    output_subset,
-                )
-            }
-            .map_err(ArrayError::CodecError)
+        unsafe {
+            self.decode_chunk_into(
+                chunk_indices,
+                chunk_encoded,
+                output,
+                output_shape,
+                output_subset,
+                options,
+            )
         }
     }
 
+    /// Retrieve the encoded bytes of the chunks at `chunk_indices` with [`get_many`](ReadableStorageTraits::get_many).
+    ///
+    /// The chunks are split into up to `concurrency` batches which are retrieved in parallel.
+    fn retrieve_encoded_chunks_batched(
+        &self,
+        chunk_indices: &[ArrayIndices],
+        concurrency: usize,
+    ) -> Result<Vec<MaybeBytes>, StorageError> {
+        if chunk_indices.is_empty() {
+            return Ok(vec![]);
+        }
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_readable_transformer(storage_handle)?;
+        let keys: Vec<StoreKey> = chunk_indices
+            .iter()
+            .map(|chunk_indices| self.chunk_key(chunk_indices))
+            .collect();
+        let batch_size = keys.len().div_ceil(concurrency.max(1));
+        let batches = keys
+            .par_chunks(batch_size)
+            .map(|keys| storage_transformer.get_many(keys))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(batches.into_iter().flatten().collect())
+    }
+
     /// Explicit options version of [`retrieve_chunk_elements_if_exists`](Array::retrieve_chunk_elements_if_exists).
     #[allow(clippy::missing_errors_doc)]
     pub fn retrieve_chunk_elements_if_exists_opt<T: ElementOwned>(
@@ -717,6 +1038,49 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
                         {
                             let output =
                                 UnsafeCellSlice::new_from_vec_with_spare_capacity(&mut output);
+
+                            // Retrieve the chunks entirely within the array subset together, then decode them
+                            //   Chunks are retrieved with one get_many per group of `chunk_concurrent_limit` chunks, so only the encoded bytes of a group are held in memory
+                            //   Chunks are retrieved individually if they may be in the chunk cache
+                            let (chunks_whole, chunks_part): (Vec<_>, Vec<_>) =
+                                chunks.indices().into_iter().partition(|chunk_indices| {
+                                    self.chunk_cache.is_none()
+                                        && self.chunk_subset(chunk_indices).is_ok_and(
+                                            |chunk_subset| {
+                                                chunk_subset
+                                                    .overlap(array_subset)
+                                                    .is_ok_and(|overlap| overlap == chunk_subset)
+                                            },
+                                        )
+                                });
+                            let decode_chunk =
+                                |(chunk_indices, chunk_encoded): (&ArrayIndices, MaybeBytes)| {
+                                    let chunk_subset = self.chunk_subset(chunk_indices)?;
+                                    unsafe {
+                                        self.decode_chunk_into(
+                                            chunk_indices,
+                                            chunk_encoded,
+                                            &output,
+                                            array_subset.shape(),
+                                            &chunk_subset.relative_to(array_subset.start())?,
+                                            &options,
+                                        )
+                                    }
+                                };
+                            for chunks_group in chunks_whole.chunks(chunk_concurrent_limit.max(1)) {
+                                let chunks_group_encoded =
+                                    self.retrieve_encoded_chunks_batched(chunks_group, 1)?;
+                                let chunks_group: Vec<_> =
+                                    chunks_group.iter().zip(chunks_group_encoded).collect();
+                                iter_concurrent_limit!(
+                                    chunk_concurrent_limit,
+                                    chunks_group,
+                                    try_for_each,
+                                    decode_chunk
+                                )?;
+                            }
+
+                            // Retrieve the subsets of the chunks partially within the array subset
                             let retrieve_chunk = |chunk_indices: Vec<u64>| {
                                 let chunk_subset = self.chunk_subset(&chunk_indices)?;
                                 let chunk_subset_overlap = chunk_subset.overlap(array_subset)?;
@@ -744,10 +1108,9 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
                                 // );
                                 Ok::<_, ArrayError>(())
                             };
-                            let indices = chunks.indices();
                             iter_concurrent_limit!(
                                 chunk_concurrent_limit,
-                                indices,
+                                chunks_part,
                                 try_for_each,
                                 retrieve_chunk
                             )?;
@@ -785,6 +1148,330 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
         elements_to_ndarray(array_subset.shape(), elements)
     }
 
+    #[cfg(feature = "ndarray")]
+    /// Explicit options version of [`retrieve_into_ndarray_view_mut`](Array::retrieve_into_ndarray_view_mut).
+    #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    pub fn retrieve_into_ndarray_view_mut_opt<T: ElementOwned, D: ndarray::Dimension>(
+        &self,
+        array_subset: &ArraySubset,
+        mut output: ndarray::ArrayViewMut<T, D>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        let subset_shape = array_subset.shape_usize();
+        if output.shape() != subset_shape.as_slice() {
+            return Err(ArrayError::InvalidDataShape(
+                output.shape().to_vec(),
+                subset_shape,
+            ));
+        }
+        let elements = self.retrieve_array_subset_elements_opt::<T>(array_subset, options)?;
+        if let Some(output) = output.as_slice_mut() {
+            output.clone_from_slice(&elements);
+        } else {
+            output.assign(&elements_to_ndarray(array_subset.shape(), elements)?);
+        }
+        Ok(())
+    }
+
+    /// Explicit options version of [`retrieve_by_indices`](Array::retrieve_by_indices).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_by_indices_opt(
+        &self,
+        indices: &[ArrayIndices],
+        options: &CodecOptions,
+    ) -> Result<ArrayBytes<'_>, ArrayError> {
+        let chunks = self.element_subsets_by_chunk(indices)?;
+
+        let retrieve_chunk_elements =
+            |(chunk_indices, elements): (ArrayIndices, Vec<(usize, ArraySubset)>)| {
+                let (positions, element_subsets): (Vec<_>, Vec<_>) = elements.into_iter().unzip();
+                let elements_bytes = self.retrieve_chunk_elements_by_subsets(
+                    &chunk_indices,
+                    &element_subsets,
+                    options,
+                )?;
+                Ok::<_, ArrayError>(std::iter::zip(positions, elements_bytes).collect::<Vec<_>>())
+            };
+        let chunks_elements_bytes = chunks
+            .into_par_iter()
+            .map(retrieve_chunk_elements)
+            .collect::<Result<Vec<_>, ArrayError>>()?;
+
+        // Reorder the elements to the order of indices
+        let mut elements_bytes = vec![None; indices.len()];
+        for (position, element_bytes) in chunks_elements_bytes.into_iter().flatten() {
+            elements_bytes[position] = Some(element_bytes);
+        }
+        let elements_bytes = elements_bytes.into_iter().map(Option::unwrap).collect();
+        Ok(concatenate_array_bytes(
+            elements_bytes,
+            self.data_type().size(),
+        )?)
+    }
+
+    /// Retrieve the single element `element_subsets` of the chunk at `chunk_indices`.
+    fn retrieve_chunk_elements_by_subsets(
+        &self,
+        chunk_indices: &[u64],
+        element_subsets: &[ArraySubset],
+        options: &CodecOptions,
+    ) -> Result<Vec<ArrayBytes<'static>>, ArrayError> {
+        let Some(chunk_cache) = &self.chunk_cache else {
+            return Ok(self
+                .partial_decoder_opt(chunk_indices, options)?
+                .partial_decode(element_subsets, options)?
+                .into_iter()
+                .map(ArrayBytes::into_owned)
+                .collect());
+        };
+        if let Some(chunk) = self.retrieve_chunk_cached(chunk_cache, chunk_indices, options)? {
+            let chunk_shape = self.chunk_array_representation(chunk_indices)?.shape_u64();
+            element_subsets
+                .iter()
+                .map(|element_subset| {
+                    Ok(chunk
+                        .extract_array_subset(element_subset, &chunk_shape, self.data_type())?
+                        .into_owned())
+                })
+                .collect()
+        } else {
+            let array_size = ArraySize::new(self.data_type().size(), 1);
+            let fill_value = ArrayBytes::new_fill_value(array_size, self.fill_value());
+            Ok(vec![fill_value; element_subsets.len()])
+        }
+    }
+
+    /// Explicit options version of [`retrieve_elements_by_indices`](Array::retrieve_elements_by_indices).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_elements_by_indices_opt<T: ElementOwned>(
+        &self,
+        indices: &[ArrayIndices],
+        options: &CodecOptions,
+    ) -> Result<Vec<T>, ArrayError> {
+        T::from_array_bytes(
+            self.data_type(),
+            self.retrieve_by_indices_opt(indices, options)?,
+        )
+    }
+
+    /// Explicit options version of [`retrieve_array_subset_masked`](Array::retrieve_array_subset_masked).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_array_subset_masked_opt(
+        &self,
+        array_subset: &ArraySubset,
+        mask: &[bool],
+        options: &CodecOptions,
+    ) -> Result<ArrayBytes<'_>, ArrayError> {
+        if !array_subset.inbounds(self.shape()) {
+            return Err(ArrayError::InvalidArraySubset(
+                array_subset.clone(),
+                self.shape().to_vec(),
+            ));
+        }
+        if mask.len() as u64 != array_subset.num_elements() {
+            return Err(ArrayError::InvalidDataShape(
+                vec![mask.len()],
+                vec![array_subset.num_elements_usize()],
+            ));
+        }
+        let indices: Vec<ArrayIndices> = std::iter::zip(&array_subset.indices(), mask)
+            .filter_map(|(indices, &selected)| selected.then_some(indices))
+            .collect();
+        self.retrieve_by_indices_opt(&indices, options)
+    }
+
+    /// Explicit options version of [`retrieve_array_subset_masked_elements`](Array::retrieve_array_subset_masked_elements).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_array_subset_masked_elements_opt<T: ElementOwned>(
+        &self,
+        array_subset: &ArraySubset,
+        mask: &[bool],
+        options: &CodecOptions,
+    ) -> Result<Vec<T>, ArrayError> {
+        T::from_array_bytes(
+            self.data_type(),
+            self.retrieve_array_subset_masked_opt(array_subset, mask, options)?,
+        )
+    }
+
+    /// Explicit options version of [`retrieve_array_slice`](Array::retrieve_array_slice).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_array_slice_opt(
+        &self,
+        array_slice: &ArraySlice,
+        options: &CodecOptions,
+    ) -> Result<ArrayBytes<'_>, ArrayError> {
+        let bounding_subset = array_slice.bounding_subset();
+        if array_slice.is_contiguous() {
+            return self.retrieve_array_subset_opt(&bounding_subset, options);
+        }
+        let Some(chunks) = self
+            .chunks_in_array_subset(&bounding_subset)?
+            .filter(|_| array_slice.inbounds(self.shape()))
+        else {
+            return Err(ArrayError::InvalidArraySubset(
+                bounding_subset,
+                self.shape().to_vec(),
+            ));
+        };
+
+        // Retrieve the elements of the array slice within each chunk and their positions in the array slice
+        let retrieve_chunk_slice = |chunk_indices: Vec<u64>| {
+            let chunk_subset = self.chunk_subset(&chunk_indices)?;
+            let positions = array_slice.overlap_positions(&chunk_subset)?;
+            if positions.is_empty() {
+                return Ok(None);
+            }
+            let bytes = self.retrieve_chunk_slice(
+                &chunk_indices,
+                &array_slice
+                    .subslice(&positions)?
+                    .relative_to(chunk_subset.start())?,
+                options,
+            )?;
+            Ok::<_, ArrayError>(Some((bytes, positions)))
+        };
+        let chunk_bytes_and_positions = chunks
+            .indices()
+            .into_par_iter()
+            .map(retrieve_chunk_slice)
+            .collect::<Result<Vec<_>, _>>()?;
+        let chunk_bytes_and_positions: Vec<_> =
+            chunk_bytes_and_positions.into_iter().flatten().collect();
+
+        match self.data_type().size() {
+            DataTypeSize::Variable => Ok(merge_chunks_vlen(
+                chunk_bytes_and_positions,
+                array_slice.shape(),
+            )?),
+            DataTypeSize::Fixed(data_type_size) => {
+                let mut bytes = ArrayBytes::new_flen(vec![
+                    0;
+                    array_slice.num_elements_usize()
+                        * data_type_size
+                ]);
+                for (chunk_bytes, positions) in chunk_bytes_and_positions {
+                    bytes = unsafe {
+                        update_array_bytes(
+                            bytes,
+                            array_slice.shape(),
+                            &positions,
+                            &chunk_bytes,
+                            self.data_type().size(),
+                        )
+                    };
+                }
+                Ok(bytes)
+            }
+        }
+    }
+
+    /// Retrieve the `chunk_slice` of the chunk at `chunk_indices`.
+    fn retrieve_chunk_slice(
+        &self,
+        chunk_indices: &[u64],
+        chunk_slice: &ArraySlice,
+        options: &CodecOptions,
+    ) -> Result<ArrayBytes<'static>, ArrayError> {
+        let chunk_slice_subset = chunk_slice.bounding_subset();
+        let bytes = self.retrieve_chunk_subset_opt(chunk_indices, &chunk_slice_subset, options)?;
+        let elements = split_array_bytes_elements(&bytes, self.data_type().size());
+        let chunk_slice = chunk_slice.relative_to(chunk_slice_subset.start())?;
+        let elements = chunk_slice
+            .linearised_indices(chunk_slice_subset.shape())
+            .map(|index| elements[usize::try_from(index).unwrap()].clone())
+            .collect();
+        Ok(concatenate_array_bytes(elements, self.data_type().size())?)
+    }
+
+    /// Explicit options version of [`retrieve_array_slice_elements`](Array::retrieve_array_slice_elements).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_array_slice_elements_opt<T: ElementOwned>(
+        &self,
+        array_slice: &ArraySlice,
+        options: &CodecOptions,
+    ) -> Result<Vec<T>, ArrayError> {
+        T::from_array_bytes(
+            self.data_type(),
+            self.retrieve_array_slice_opt(array_slice, options)?,
+        )
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Explicit options version of [`retrieve_array_slice_ndarray`](Array::retrieve_array_slice_ndarray).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_array_slice_ndarray_opt<T: ElementOwned>(
+        &self,
+        array_slice: &ArraySlice,
+        options: &CodecOptions,
+    ) -> Result<ndarray::ArrayD<T>, ArrayError> {
+        let elements = self.retrieve_array_slice_elements_opt::<T>(array_slice, options)?;
+        elements_to_ndarray(array_slice.shape(), elements)
+    }
+
+    /// Explicit options version of [`par_visit_chunks`](Array::par_visit_chunks).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn par_visit_chunks_opt(
+        &self,
+        f: impl Fn(&[u64], &ArraySubset, ArrayBytes<'_>) -> Result<(), ArrayError> + Send + Sync,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        let chunks: Vec<_> = self.chunks().collect();
+        if chunks.is_empty() {
+            return Ok(());
+        }
+
+        // Calculate chunk/codec concurrency
+        let chunk_representation =
+            self.chunk_array_representation(&vec![0; self.dimensionality()])?;
+        let codec_concurrency = self.recommended_codec_concurrency(&chunk_representation)?;
+        let (chunk_concurrent_limit, options) = concurrency_chunks_and_codec(
+            options.concurrent_target(),
+            chunks.len(),
+            options,
+            &codec_concurrency,
+        );
+
+        let visit_chunk = |(chunk_indices, chunk_subset): (ArrayIndices, ArraySubset)| {
+            let chunk_bytes = self.retrieve_array_subset_opt(&chunk_subset, &options)?;
+            f(&chunk_indices, &chunk_subset, chunk_bytes)
+        };
+        iter_concurrent_limit!(chunk_concurrent_limit, chunks, try_for_each, visit_chunk)
+    }
+
+    /// Explicit options version of [`par_visit_chunks_elements`](Array::par_visit_chunks_elements).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn par_visit_chunks_elements_opt<T: ElementOwned>(
+        &self,
+        f: impl Fn(&[u64], &ArraySubset, Vec<T>) -> Result<(), ArrayError> + Send + Sync,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        self.par_visit_chunks_opt(
+            |chunk_indices, chunk_subset, chunk_bytes| {
+                let elements = T::from_array_bytes(self.data_type(), chunk_bytes)?;
+                f(chunk_indices, chunk_subset, elements)
+            },
+            options,
+        )
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Explicit options version of [`par_visit_chunks_ndarray`](Array::par_visit_chunks_ndarray).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn par_visit_chunks_ndarray_opt<T: ElementOwned>(
+        &self,
+        f: impl Fn(&[u64], &ArraySubset, ndarray::ArrayD<T>) -> Result<(), ArrayError> + Send + Sync,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        self.par_visit_chunks_elements_opt(
+            |chunk_indices, chunk_subset, elements| {
+                let elements = elements_to_ndarray(chunk_subset.shape(), elements)?;
+                f(chunk_indices, chunk_subset, elements)
+            },
+            options,
+        )
+    }
+
     /// Explicit options version of [`retrieve_chunk_subset`](Array::retrieve_chunk_subset).
     #[allow(clippy::missing_errors_doc)]
     pub fn retrieve_chunk_subset_opt(
@@ -806,6 +1493,20 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
         {
             // Fast path if `chunk_subset` encompasses the whole chunk
             self.retrieve_chunk_opt(chunk_indices, options)?
+        } else if let Some(chunk_cache) = &self.chunk_cache {
+            if let Some(chunk) = self.retrieve_chunk_cached(chunk_cache, chunk_indices, options)? {
+                chunk
+                    .extract_array_subset(
+                        chunk_subset,
+                        &chunk_representation.shape_u64(),
+                        self.data_type(),
+                    )?
+                    .into_owned()
+            } else {
+                let array_size =
+                    ArraySize::new(self.data_type().size(), chunk_subset.num_elements());
+                ArrayBytes::new_fill_value(array_size, self.fill_value())
+            }
         } else {
             let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
             let storage_transformer = self
@@ -818,7 +1519,11 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
 
             self.codecs
                 .clone()
-                .partial_decoder(input_handle, &chunk_representation, options)?
+                .partial_decoder(
+                    input_handle,
+                    &chunk_representation,
+                    &self.partial_decoder_options(options),
+                )?
                 .partial_decode(&[chunk_subset.clone()], options)?
                 .remove(0)
                 .into_owned()
@@ -844,7 +1549,19 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
             ));
         }
 
-        if chunk_subset.start().iter().all(|&o| o == 0)
+        if let Some(chunk_cache) = &self.chunk_cache {
+            let chunk = self.retrieve_chunk_cached(chunk_cache, chunk_indices, options)?;
+            unsafe {
+                self.copy_cached_chunk_subset_into(
+                    chunk.as_deref(),
+                    &chunk_representation.shape_u64(),
+                    chunk_subset,
+                    output,
+                    output_shape,
+                    output_subset,
+                )
+            }
+        } else if chunk_subset.start().iter().all(|&o| o == 0)
             && chunk_subset.shape() == chunk_representation.shape_u64()
         {
             // Fast path if `chunk_subset` encompasses the whole chunk
@@ -870,7 +1587,11 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
             unsafe {
                 self.codecs
                     .clone()
-                    .partial_decoder(input_handle, &chunk_representation, options)?
+                    .partial_decoder(
+                        input_handle,
+                        &chunk_representation,
+                        &self.partial_decoder_options(options),
+                    )?
                     .partial_decode_into(
                         chunk_subset,
                         output,
@@ -927,9 +1648,10 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
             self.chunk_key(chunk_indices),
         ));
         let chunk_representation = self.chunk_array_representation(chunk_indices)?;
-        Ok(self
-            .codecs
-            .clone()
-            .partial_decoder(input_handle, &chunk_representation, options)?)
+        Ok(self.codecs.clone().partial_decoder(
+            input_handle,
+            &chunk_representation,
+            &self.partial_decoder_options(options),
+        )?)
     }
 }
@@ -1,11 +1,15 @@
-use std::sync::Arc;
+use std::{borrow::Cow, sync::Arc};
 
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
+#[cfg(feature = "sharding")]
+use super::ArrayShardedExt;
+
 use crate::{
-    array::ArrayBytes,
-    array_subset::ArraySubset,
-    storage::{ReadableWritableStorageTraits, StorageHandle},
+    array::{array_bytes::split_array_bytes_elements, ArrayBytes, ArrayIndices, ArrayShape},
+    array_subset::{ArraySlice, ArraySubset, IncompatibleDimensionalityError},
+    node::{update_attributes, AttributesUpdateOptions},
+    storage::{Bytes, ReadableWritableStorageTraits, StorageError, StorageHandle},
 };
 
 use super::{
@@ -14,19 +18,69 @@ use super::{
         StoragePartialDecoder, StoragePartialEncoder,
     },
     concurrency::concurrency_chunks_and_codec,
-    update_array_bytes, Array, ArrayError, Element,
+    update_array_bytes, Array, ArrayError, ArraySize, Element, ElementOwned,
 };
 
 impl<TStorage: ?Sized + ReadableWritableStorageTraits + 'static> Array<TStorage> {
+    /// Update the attributes with `update` and store them with default [`AttributesUpdateOptions`].
+    ///
+    /// See [`update_attributes_opt`](Array::update_attributes_opt).
+    ///
+    /// # Errors
+    /// Returns [`StorageError`] if there is an underlying store error or the stored metadata is invalid.
+    pub fn update_attributes(
+        &mut self,
+        update: impl FnOnce(&mut serde_json::Map<String, serde_json::Value>),
+    ) -> Result<(), StorageError> {
+        self.update_attributes_opt(update, &AttributesUpdateOptions::default())
+    }
+
+    /// Update the attributes with `update` and store them with non-default [`AttributesUpdateOptions`].
+    ///
+    /// `update` is applied to a copy of the attributes of the array.
+    /// The attributes it adds, modifies, or removes are merged into the attributes of the stored metadata, which is then written back.
+    /// Attributes modified by another writer are preserved unless they are also changed by `update`.
+    /// The attributes of the array are then set to the attributes that were written.
+    ///
+    /// Only the attributes of the stored metadata are changed, which is `zarr.json` for a Zarr V3 array or `.zattrs` for a Zarr V2 array.
+    /// If it has not been stored, it is created from the metadata of the array.
+    /// The store is not accessed if `update` does not change the attributes.
+    ///
+    /// With [optimistic concurrency](AttributesUpdateOptions::optimistic_concurrency), the metadata is only written if it was not modified by another writer since it was read, otherwise the update is retried.
+    ///
+    /// # Errors
+    /// Returns [`StorageError`] if there is an underlying store error or the stored metadata is invalid.
+    /// With optimistic concurrency, returns [`StorageError::PreconditionFailed`] if the metadata was modified by another writer on every attempt, or [`StorageError::Unsupported`] if the store does not support conditional writes.
+    pub fn update_attributes_opt(
+        &mut self,
+        update: impl FnOnce(&mut serde_json::Map<String, serde_json::Value>),
+        options: &AttributesUpdateOptions,
+    ) -> Result<(), StorageError> {
+        let (key, document, changes) = self.attributes_update(update)?;
+        if changes.is_empty() {
+            return Ok(());
+        }
+        let storage_handle = StorageHandle::new(self.storage.clone());
+        let (attributes, etag) =
+            update_attributes(&storage_handle, &key, &document, &changes, options)?;
+        *self.attributes_mut() = attributes;
+        self.set_metadata_etag(&key, etag);
+        Ok(())
+    }
+
     /// Encode `chunk_subset_bytes` and store in `chunk_subset` of the chunk at `chunk_indices` with default codec options.
     ///
     /// Use [`store_chunk_subset_opt`](Array::store_chunk_subset_opt) to control codec options.
     /// Prefer to use [`store_chunk`](Array::store_chunk) where possible, since this function may decode the chunk before updating it and reencoding it.
     ///
+    /// If the store supports conditional writes ([`set_if_match`](crate::storage::WritableStorageTraits::set_if_match)), a decoded chunk is only written if it has not been modified by another writer since it was read.
+    /// A chunk updated to be entirely the fill value is erased unconditionally.
+    ///
     /// # Errors
     /// Returns an [`ArrayError`] if
     ///  - `chunk_subset` is invalid or out of bounds of the chunk,
-    ///  - there is a codec encoding error, or
+    ///  - there is a codec encoding error,
+    ///  - the chunk was modified by another writer ([`StorageError::PreconditionFailed`](crate::storage::StorageError::PreconditionFailed)), or
     ///  - an underlying store error.
     ///
     /// # Panics
@@ -151,6 +205,54 @@ impl<TStorage: ?Sized + ReadableWritableStorageTraits + 'static> Array<TStorage>
         self.store_array_subset_ndarray_opt(subset_start, subset_array, &CodecOptions::default())
     }
 
+    #[cfg(feature = "ndarray")]
+    /// Encode `subset_array` and store in the array subset starting at `subset_start`.
+    ///
+    /// Use [`store_array_subset_ndarray_view_opt`](Array::store_array_subset_ndarray_view_opt) to control codec options.
+    /// Unlike [`store_array_subset_ndarray`](Array::store_array_subset_ndarray), `subset_array` is borrowed and may have any memory layout (e.g. a transposed or strided view).
+    /// A view in standard layout is stored without copying, otherwise its elements are streamed in logical order with [`store_array_subset_from_iter`](Array::store_array_subset_from_iter).
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - the array subset starting at `subset_start` with the shape of `subset_array` is not within the bounds of the array, or
+    ///  - a [`store_array_subset_elements`](Array::store_array_subset_elements) error condition is met.
+    pub fn store_array_subset_ndarray_view<T: Element, D: ndarray::Dimension>(
+        &self,
+        subset_start: &[u64],
+        subset_array: ndarray::ArrayView<T, D>,
+    ) -> Result<(), ArrayError> {
+        self.store_array_subset_ndarray_view_opt(
+            subset_start,
+            subset_array,
+            &CodecOptions::default(),
+        )
+    }
+
+    /// Encode the elements of `subset_elements` and store in `array_subset`, consuming `subset_elements` lazily.
+    ///
+    /// Use [`store_array_subset_from_iter_opt`](Array::store_array_subset_from_iter_opt) to control codec options.
+    /// `subset_elements` must yield the elements of `array_subset` in C-contiguous order.
+    /// The elements are buffered and stored in slabs spanning the chunks intersecting `array_subset` along the first dimension, so the elements of `array_subset` are never held in memory at once.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - `array_subset` is not within the bounds of the array,
+    ///  - `subset_elements` does not yield the number of elements in `array_subset`, or
+    ///  - a [`store_array_subset_elements`](Array::store_array_subset_elements) error condition is met.
+    ///
+    /// The slabs stored before an error occurs are not reverted.
+    pub fn store_array_subset_from_iter<T: Element>(
+        &self,
+        array_subset: &ArraySubset,
+        subset_elements: impl IntoIterator<Item = T>,
+    ) -> Result<(), ArrayError> {
+        self.store_array_subset_from_iter_opt(
+            array_subset,
+            subset_elements,
+            &CodecOptions::default(),
+        )
+    }
+
     /////////////////////////////////////////////////////////////////////////////
     // Advanced methods
     /////////////////////////////////////////////////////////////////////////////
@@ -186,14 +288,32 @@ impl<TStorage: ?Sized + ReadableWritableStorageTraits + 'static> Array<TStorage>
             chunk_subset_bytes.validate(chunk_subset.num_elements(), self.data_type().size())?;
 
             // Lock the chunk
-            // let key = self.chunk_key(chunk_indices);
-            // let mutex = self.storage.mutex(&key)?;
-            // let _lock = mutex.lock();
+            let _lock = self.storage.lock_key(&self.chunk_key(chunk_indices))?;
 
             if options.experimental_partial_encoding() {
                 let partial_encoder = self.partial_encoder(chunk_indices, options)?;
-                Ok(partial_encoder
-                    .partial_encode(&[(chunk_subset, chunk_subset_bytes)], options)?)
+                partial_encoder.partial_encode(&[(chunk_subset, chunk_subset_bytes)], options)?;
+                #[cfg(feature = "sharding")]
+                if let Some(threshold) = options.shard_compaction_threshold() {
+                    // Only the sharding partial encoder leaves unreferenced bytes in a shard
+                    if self.is_sharded() && self.codecs().bytes_to_bytes_codecs().is_empty() {
+                        self.compact_shard_if_exceeds(chunk_indices, threshold, options)?;
+                    }
+                }
+                self.invalidate_shard_index(chunk_indices);
+                self.invalidate_chunk_cache(chunk_indices);
+                Ok(())
+            } else if self.storage.supports_set_if_match() {
+                self.store_chunk_subset_if_match(
+                    chunk_indices,
+                    &chunk_shape,
+                    chunk_subset,
+                    &chunk_subset_bytes,
+                    options,
+                )?;
+                self.invalidate_shard_index(chunk_indices);
+                self.invalidate_chunk_cache(chunk_indices);
+                Ok(())
             } else {
                 // Decode the entire chunk
                 let chunk_bytes_old = self.retrieve_chunk_opt(chunk_indices, options)?;
@@ -216,6 +336,85 @@ impl<TStorage: ?Sized + ReadableWritableStorageTraits + 'static> Array<TStorage>
         }
     }
 
+    /// Update `chunk_subset` of the chunk at `chunk_indices` with a read-modify-write that is conditional on the entity tag of the chunk.
+    ///
+    /// A chunk that becomes entirely the fill value is written conditionally rather than erased, since stores do not support conditional erasure.
+    /// Falls back to an unconditional write (or erase) if the store does not support the entity tag.
+    fn store_chunk_subset_if_match(
+        &self,
+        chunk_indices: &[u64],
+        chunk_shape: &[u64],
+        chunk_subset: &ArraySubset,
+        chunk_subset_bytes: &ArrayBytes<'_>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer_read = self
+            .storage_transformers()
+            .create_readable_transformer(storage_handle.clone())?;
+        let storage_transformer_write = self
+            .storage_transformers()
+            .create_writable_transformer(storage_handle)?;
+        let chunk_key = self.chunk_key(chunk_indices);
+        let chunk_representation = self.chunk_array_representation(chunk_indices)?;
+
+        // Decode the entire chunk
+        let (chunk_encoded, etag) = storage_transformer_read.get_with_etag(&chunk_key)?;
+        let chunk_exists = chunk_encoded.is_some();
+        let chunk_bytes_old = if let Some(chunk_encoded) = chunk_encoded {
+            let chunk_encoded: Vec<u8> = chunk_encoded.into();
+            self.codecs()
+                .decode(Cow::Owned(chunk_encoded), &chunk_representation, options)
+                .map_err(ArrayError::CodecError)?
+        } else {
+            let array_size =
+                ArraySize::new(self.data_type().size(), chunk_representation.num_elements());
+            ArrayBytes::new_fill_value(array_size, self.fill_value())
+        };
+        chunk_bytes_old.validate(chunk_shape.iter().product(), self.data_type().size())?;
+
+        // Update the chunk
+        let chunk_bytes_new = unsafe {
+            update_array_bytes(
+                chunk_bytes_old,
+                chunk_shape,
+                chunk_subset,
+                chunk_subset_bytes,
+                self.data_type().size(),
+            )
+        };
+
+        // Store the updated chunk
+        let chunk_is_fill_value =
+            !options.store_empty_chunks() && chunk_bytes_new.is_fill_value(self.fill_value());
+        if chunk_is_fill_value && etag.is_none() {
+            // The entity tag of an existing chunk is unknown, so it is erased unconditionally
+            if chunk_exists {
+                self.erase_chunk(chunk_indices)?;
+            }
+            return Ok(());
+        }
+        let chunk_encoded = self
+            .codecs()
+            .encode(chunk_bytes_new, &chunk_representation, options)
+            .map_err(ArrayError::CodecError)?;
+        let chunk_encoded = Bytes::from(chunk_encoded.into_owned());
+        match storage_transformer_write.set_if_match(
+            &chunk_key,
+            chunk_encoded.clone(),
+            etag.as_ref(),
+        ) {
+            Ok(_) => Ok(()),
+            Err(StorageError::Unsupported(_)) if chunk_is_fill_value => {
+                Ok(self.erase_chunk(chunk_indices)?)
+            }
+            Err(StorageError::Unsupported(_)) => {
+                Ok(storage_transformer_write.set(&chunk_key, chunk_encoded)?)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
     /// Explicit options version of [`store_chunk_subset_elements`](Array::store_chunk_subset_elements).
     #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
     pub fn store_chunk_subset_elements_opt<T: Element>(
@@ -351,6 +550,51 @@ impl<TStorage: ?Sized + ReadableWritableStorageTraits + 'static> Array<TStorage>
         self.store_array_subset_opt(array_subset, subset_bytes, options)
     }
 
+    /// Explicit options version of [`store_array_subset_from_iter`](Array::store_array_subset_from_iter).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn store_array_subset_from_iter_opt<T: Element>(
+        &self,
+        array_subset: &ArraySubset,
+        subset_elements: impl IntoIterator<Item = T>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        T::validate_data_type(self.data_type())?;
+        if array_subset.dimensionality() != self.dimensionality()
+            || !array_subset.inbounds(self.shape())
+        {
+            return Err(ArrayError::InvalidArraySubset(
+                array_subset.clone(),
+                self.shape().to_vec(),
+            ));
+        }
+
+        let num_elements = array_subset.num_elements_usize();
+        let mut subset_elements = subset_elements.into_iter();
+        let mut num_consumed = 0;
+        for slab in self.array_subset_slabs(array_subset)? {
+            let slab_elements: Vec<T> = subset_elements
+                .by_ref()
+                .take(slab.num_elements_usize())
+                .collect();
+            num_consumed += slab_elements.len();
+            if slab_elements.len() != slab.num_elements_usize() {
+                return Err(ArrayError::InvalidDataShape(
+                    vec![num_consumed],
+                    vec![num_elements],
+                ));
+            }
+            self.store_array_subset_elements_opt(&slab, &slab_elements, options)?;
+        }
+        if subset_elements.next().is_some() {
+            // The remaining elements are not consumed
+            return Err(ArrayError::InvalidDataShape(
+                vec![num_elements + 1],
+                vec![num_elements],
+            ));
+        }
+        Ok(())
+    }
+
     #[cfg(feature = "ndarray")]
     /// Explicit options version of [`store_array_subset_ndarray`](Array::store_array_subset_ndarray).
     #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
@@ -369,6 +613,718 @@ impl<TStorage: ?Sized + ReadableWritableStorageTraits + 'static> Array<TStorage>
         self.store_array_subset_elements_opt(&subset, &subset_array, options)
     }
 
+    #[cfg(feature = "ndarray")]
+    /// Explicit options version of [`store_array_subset_ndarray_view`](Array::store_array_subset_ndarray_view).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn store_array_subset_ndarray_view_opt<T: Element, D: ndarray::Dimension>(
+        &self,
+        subset_start: &[u64],
+        subset_array: ndarray::ArrayView<T, D>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        let subset = ArraySubset::new_with_start_shape(
+            subset_start.to_vec(),
+            subset_array.shape().iter().map(|u| *u as u64).collect(),
+        )?;
+        if !subset.inbounds(self.shape()) {
+            return Err(ArrayError::InvalidArraySubset(
+                subset,
+                self.shape().to_vec(),
+            ));
+        }
+        if let Some(subset_elements) = subset_array.to_slice() {
+            self.store_array_subset_elements_opt(&subset, subset_elements, options)
+        } else {
+            self.store_array_subset_from_iter_opt(
+                &subset,
+                subset_array.into_iter().cloned(),
+                options,
+            )
+        }
+    }
+
+    /// Resize the array to `shape` with default codec options.
+    ///
+    /// See [`resize_opt`](Array::resize_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn resize(&mut self, shape: ArrayShape) -> Result<(), ArrayError> {
+        self.resize_opt(shape, &CodecOptions::default())
+    }
+
+    /// Resize the array to `shape` and store the updated metadata.
+    ///
+    /// Chunks entirely outside of `shape` are erased.
+    /// If the array shrinks, the elements of chunks straddling the new bounds that are outside of `shape` are reset to the fill value.
+    /// Otherwise, they would reappear if the array is later grown.
+    ///
+    /// Chunks are updated before the metadata is stored.
+    /// Concurrent reads or writes of the array may observe a partially resized array.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - the dimensionality of `shape` does not match the dimensionality of the array,
+    ///  - there is a codec decoding or encoding error, or
+    ///  - an underlying store error.
+    pub fn resize_opt(
+        &mut self,
+        shape: ArrayShape,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        if shape.len() != self.dimensionality() {
+            return Err(
+                IncompatibleDimensionalityError::new(shape.len(), self.dimensionality()).into(),
+            );
+        }
+
+        let shrinks = std::iter::zip(self.shape(), &shape).any(|(old, new)| new < old);
+        if shrinks {
+            let array_subset_old = self.subset_all();
+            let array_subset_new = ArraySubset::new_with_shape(shape.clone());
+            let Some(chunks) = self.chunks_in_array_subset(&array_subset_old)? else {
+                return Err(ArrayError::InvalidArraySubset(
+                    array_subset_old,
+                    self.shape().to_vec(),
+                ));
+            };
+
+            let resize_chunk = |chunk_indices: Vec<u64>| -> Result<(), ArrayError> {
+                let chunk_subset = self.chunk_subset(&chunk_indices)?;
+                let outside = std::iter::zip(chunk_subset.start(), array_subset_new.shape())
+                    .any(|(start, end)| start >= end);
+                if outside {
+                    // Erase chunks entirely outside of the new shape
+                    self.erase_chunk(&chunk_indices)?;
+                    return Ok(());
+                }
+                let overlap_old = chunk_subset.overlap(&array_subset_old)?;
+                let overlap_new = chunk_subset.overlap(&array_subset_new)?;
+                if overlap_new != overlap_old {
+                    // Reset elements outside of the new shape to the fill value
+                    let chunk_bytes = self.retrieve_chunk_opt(&chunk_indices, options)?;
+                    let overlap_new = overlap_new.relative_to(chunk_subset.start())?;
+                    let overlap_bytes = chunk_bytes.extract_array_subset(
+                        &overlap_new,
+                        chunk_subset.shape(),
+                        self.data_type(),
+                    )?;
+                    let chunk_bytes = ArrayBytes::new_fill_value(
+                        ArraySize::new(self.data_type().size(), chunk_subset.num_elements()),
+                        self.fill_value(),
+                    );
+                    let chunk_bytes = unsafe {
+                        update_array_bytes(
+                            chunk_bytes,
+                            chunk_subset.shape(),
+                            &overlap_new,
+                            &overlap_bytes,
+                            self.data_type().size(),
+                        )
+                    };
+                    self.store_chunk_opt(&chunk_indices, chunk_bytes, options)?;
+                }
+                Ok(())
+            };
+            chunks
+                .indices()
+                .into_par_iter()
+                .try_for_each(resize_chunk)?;
+        }
+
+        self.set_shape(shape);
+        self.store_metadata()?;
+        Ok(())
+    }
+
+    /// Append `data` to the array along `axis` with default codec options.
+    ///
+    /// See [`append_opt`](Array::append_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn append<'a>(
+        &mut self,
+        axis: usize,
+        data: impl Into<ArrayBytes<'a>>,
+    ) -> Result<ArraySubset, ArrayError> {
+        self.append_opt(axis, data, &CodecOptions::default())
+    }
+
+    /// Append `elements` to the array along `axis` with default codec options.
+    ///
+    /// See [`append_elements_opt`](Array::append_elements_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn append_elements<T: Element>(
+        &mut self,
+        axis: usize,
+        elements: &[T],
+    ) -> Result<ArraySubset, ArrayError> {
+        self.append_elements_opt(axis, elements, &CodecOptions::default())
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Append `array` to the array along `axis` with default codec options.
+    ///
+    /// See [`append_ndarray_opt`](Array::append_ndarray_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn append_ndarray<T: Element, D: ndarray::Dimension>(
+        &mut self,
+        axis: usize,
+        array: impl Into<ndarray::Array<T, D>>,
+    ) -> Result<ArraySubset, ArrayError> {
+        self.append_ndarray_opt(axis, array, &CodecOptions::default())
+    }
+
+    /// Append `data` to the array along `axis`, growing the array.
+    ///
+    /// `data` has the shape of the array except along `axis`, where its length is inferred from the number of elements in `data`.
+    /// The updated array metadata is stored and then `data` is stored.
+    ///
+    /// Returns the array subset of the appended data.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - `axis` is not less than the dimensionality of the array,
+    ///  - the number of elements in `data` is not a multiple of the number of elements in a non-empty slice of the array along `axis`,
+    ///  - there is a codec encoding error, or
+    ///  - an underlying store error.
+    ///
+    /// # Panics
+    /// Panics if a dimension of the array exceeds [`usize::MAX`].
+    pub fn append_opt<'a>(
+        &mut self,
+        axis: usize,
+        data: impl Into<ArrayBytes<'a>>,
+        options: &CodecOptions,
+    ) -> Result<ArraySubset, ArrayError> {
+        if axis >= self.dimensionality() {
+            return Err(ArrayError::InvalidAxis(axis, self.dimensionality()));
+        }
+        let data = data.into();
+        let num_elements = match &data {
+            ArrayBytes::Fixed(bytes) => self
+                .data_type()
+                .fixed_size()
+                .map_or(0, |data_type_size| bytes.len() / data_type_size),
+            ArrayBytes::Variable(_, offsets) => offsets.len().saturating_sub(1),
+        };
+        let num_elements = u64::try_from(num_elements).unwrap();
+
+        // Infer the length of the appended data along the axis
+        let mut subset_shape = self.shape().to_vec();
+        subset_shape[axis] = 1;
+        let num_elements_slice: u64 = subset_shape.iter().product();
+        if num_elements_slice == 0 || num_elements % num_elements_slice != 0 {
+            return Err(ArrayError::InvalidDataShape(
+                vec![usize::try_from(num_elements).unwrap()],
+                subset_shape
+                    .iter()
+                    .map(|length| usize::try_from(*length).unwrap())
+                    .collect(),
+            ));
+        }
+        subset_shape[axis] = num_elements / num_elements_slice;
+        let mut subset_start = vec![0; self.dimensionality()];
+        subset_start[axis] = self.shape()[axis];
+        let subset = ArraySubset::new_with_start_shape(subset_start, subset_shape)?;
+
+        let mut shape = self.shape().to_vec();
+        shape[axis] += subset.shape()[axis];
+        self.set_shape(shape);
+        self.store_metadata()?;
+        self.store_array_subset_opt(&subset, data, options)?;
+        Ok(subset)
+    }
+
+    /// Explicit options version of [`append_elements`](Array::append_elements).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn append_elements_opt<T: Element>(
+        &mut self,
+        axis: usize,
+        elements: &[T],
+        options: &CodecOptions,
+    ) -> Result<ArraySubset, ArrayError> {
+        let data = T::into_array_bytes(self.data_type(), elements)?;
+        self.append_opt(axis, data.into_owned(), options)
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Explicit options version of [`append_ndarray`](Array::append_ndarray).
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if the shape of `array` does not match the shape of the array except along `axis`, or [`append_opt`](Array::append_opt) fails.
+    ///
+    /// # Panics
+    /// Panics if a dimension of the array exceeds [`usize::MAX`].
+    pub fn append_ndarray_opt<T: Element, D: ndarray::Dimension>(
+        &mut self,
+        axis: usize,
+        array: impl Into<ndarray::Array<T, D>>,
+        options: &CodecOptions,
+    ) -> Result<ArraySubset, ArrayError> {
+        let array: ndarray::Array<T, D> = array.into();
+        let expected_shape: Vec<usize> = self
+            .shape()
+            .iter()
+            .enumerate()
+            .map(|(i, &length)| {
+                if i == axis {
+                    array.shape().get(axis).copied().unwrap_or_default()
+                } else {
+                    usize::try_from(length).unwrap()
+                }
+            })
+            .collect();
+        if array.shape() != expected_shape {
+            return Err(ArrayError::InvalidDataShape(
+                array.shape().to_vec(),
+                expected_shape,
+            ));
+        }
+        let elements = super::ndarray_into_vec(array);
+        self.append_elements_opt(axis, &elements, options)
+    }
+
+    /// Encode `data` and store it at the elements at `indices` of the array.
+    ///
+    /// `indices` is a list of element coordinates in the array, which may be in any order.
+    /// `data` holds the elements in the order of `indices`; if an element is repeated, the last of its elements is stored.
+    /// Updates are grouped by chunk, so each chunk intersecting `indices` is only retrieved and stored once.
+    ///
+    /// Prefer [`store_array_subset`](Array::store_array_subset) for dense subsets of the array.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - an element of `indices` has an incorrect dimensionality or is out of bounds of the array,
+    ///  - the length of `data` does not match the number of elements in `indices`,
+    ///  - there is a codec decoding or encoding error, or
+    ///  - an underlying store error.
+    pub fn store_by_indices<'a>(
+        &self,
+        indices: &[ArrayIndices],
+        data: impl Into<ArrayBytes<'a>>,
+    ) -> Result<(), ArrayError> {
+        self.store_by_indices_opt(indices, data, &CodecOptions::default())
+    }
+
+    /// Encode `elements` and store them at the elements at `indices` of the array.
+    ///
+    /// See [`store_by_indices`](Array::store_by_indices).
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if the size of `T` does not match the data type size or a [`store_by_indices`](Array::store_by_indices) error condition is met.
+    pub fn store_elements_by_indices<T: Element>(
+        &self,
+        indices: &[ArrayIndices],
+        elements: &[T],
+    ) -> Result<(), ArrayError> {
+        self.store_elements_by_indices_opt(indices, elements, &CodecOptions::default())
+    }
+
+    /// Explicit options version of [`store_by_indices`](Array::store_by_indices).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn store_by_indices_opt<'a>(
+        &self,
+        indices: &[ArrayIndices],
+        data: impl Into<ArrayBytes<'a>>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        let data = data.into();
+        data.validate(indices.len() as u64, self.data_type().size())?;
+        let elements_bytes = split_array_bytes_elements(&data, self.data_type().size());
+        let chunks = self.element_subsets_by_chunk(indices)?;
+
+        let store_chunk_elements =
+            |(chunk_indices, elements): (ArrayIndices, Vec<(usize, ArraySubset)>)| {
+                let chunk_shape = self.chunk_array_representation(&chunk_indices)?.shape_u64();
+                let mut chunk_bytes = self.retrieve_chunk_opt(&chunk_indices, options)?;
+                for (position, element_subset) in elements {
+                    chunk_bytes = unsafe {
+                        update_array_bytes(
+                            chunk_bytes,
+                            &chunk_shape,
+                            &element_subset,
+                            &elements_bytes[position],
+                            self.data_type().size(),
+                        )
+                    };
+                }
+                self.store_chunk_opt(&chunk_indices, chunk_bytes, options)
+            };
+        chunks.into_par_iter().try_for_each(store_chunk_elements)
+    }
+
+    /// Explicit options version of [`store_elements_by_indices`](Array::store_elements_by_indices).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn store_elements_by_indices_opt<T: Element>(
+        &self,
+        indices: &[ArrayIndices],
+        elements: &[T],
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        let data = T::into_array_bytes(self.data_type(), elements)?;
+        self.store_by_indices_opt(indices, data, options)
+    }
+
+    /// Encode `data` and store it in the `array_slice` of the array with default codec options.
+    ///
+    /// See [`store_array_slice_opt`](Array::store_array_slice_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn store_array_slice<'a>(
+        &self,
+        array_slice: &ArraySlice,
+        data: impl Into<ArrayBytes<'a>>,
+    ) -> Result<(), ArrayError> {
+        self.store_array_slice_opt(array_slice, data, &CodecOptions::default())
+    }
+
+    /// Encode `elements` and store them in the `array_slice` of the array with default codec options.
+    ///
+    /// See [`store_array_slice_elements_opt`](Array::store_array_slice_elements_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn store_array_slice_elements<T: Element>(
+        &self,
+        array_slice: &ArraySlice,
+        elements: &[T],
+    ) -> Result<(), ArrayError> {
+        self.store_array_slice_elements_opt(array_slice, elements, &CodecOptions::default())
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Encode `array` and store it in the `array_slice` of the array with default codec options.
+    ///
+    /// See [`store_array_slice_ndarray_opt`](Array::store_array_slice_ndarray_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn store_array_slice_ndarray<T: Element, D: ndarray::Dimension>(
+        &self,
+        array_slice: &ArraySlice,
+        array: impl Into<ndarray::Array<T, D>>,
+    ) -> Result<(), ArrayError> {
+        self.store_array_slice_ndarray_opt(array_slice, array, &CodecOptions::default())
+    }
+
+    /// Encode `data` and store it in the `array_slice` of the array.
+    ///
+    /// `data` holds the elements of the array slice in C order.
+    /// Each chunk intersecting an element of the array slice is retrieved, updated, and stored.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - the array slice is invalid or out of bounds of the array,
+    ///  - the length of `data` does not match the number of elements in `array_slice`,
+    ///  - there is a codec decoding or encoding error, or
+    ///  - an underlying store error.
+    pub fn store_array_slice_opt<'a>(
+        &self,
+        array_slice: &ArraySlice,
+        data: impl Into<ArrayBytes<'a>>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        let bounding_subset = array_slice.bounding_subset();
+        if array_slice.is_contiguous() {
+            return self.store_array_subset_opt(&bounding_subset, data, options);
+        }
+        let Some(chunks) = self
+            .chunks_in_array_subset(&bounding_subset)?
+            .filter(|_| array_slice.inbounds(self.shape()))
+        else {
+            return Err(ArrayError::InvalidArraySubset(
+                bounding_subset,
+                self.shape().to_vec(),
+            ));
+        };
+        let data = data.into();
+        data.validate(array_slice.num_elements(), self.data_type().size())?;
+
+        // Update the elements of the array slice within each chunk
+        let store_chunk_slice = |chunk_indices: Vec<u64>| {
+            let chunk_subset = self.chunk_subset(&chunk_indices)?;
+            let positions = array_slice.overlap_positions(&chunk_subset)?;
+            if positions.is_empty() {
+                return Ok(());
+            }
+            let chunk_slice_bytes =
+                data.extract_array_subset(&positions, array_slice.shape(), self.data_type())?;
+            let chunk_slice = array_slice
+                .subslice(&positions)?
+                .relative_to(chunk_subset.start())?;
+            let elements_bytes =
+                split_array_bytes_elements(&chunk_slice_bytes, self.data_type().size());
+            let mut chunk_bytes = self.retrieve_chunk_opt(&chunk_indices, options)?;
+            for (element_indices, element_bytes) in
+                std::iter::zip(chunk_slice.indices(), elements_bytes)
+            {
+                let element_subset = ArraySubset::new_with_start_shape(
+                    element_indices,
+                    vec![1; chunk_slice.dimensionality()],
+                )?;
+                chunk_bytes = unsafe {
+                    update_array_bytes(
+                        chunk_bytes,
+                        chunk_subset.shape(),
+                        &element_subset,
+                        &element_bytes,
+                        self.data_type().size(),
+                    )
+                };
+            }
+            self.store_chunk_opt(&chunk_indices, chunk_bytes, options)
+        };
+        chunks
+            .indices()
+            .into_par_iter()
+            .try_for_each(store_chunk_slice)
+    }
+
+    /// Explicit options version of [`store_array_slice_elements`](Array::store_array_slice_elements).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn store_array_slice_elements_opt<T: Element>(
+        &self,
+        array_slice: &ArraySlice,
+        elements: &[T],
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        let data = T::into_array_bytes(self.data_type(), elements)?;
+        self.store_array_slice_opt(array_slice, data, options)
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Explicit options version of [`store_array_slice_ndarray`](Array::store_array_slice_ndarray).
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if the shape of `array` does not match the shape of `array_slice`, or [`store_array_slice_opt`](Array::store_array_slice_opt) fails.
+    ///
+    /// # Panics
+    /// Panics if a dimension of `array_slice` exceeds [`usize::MAX`].
+    pub fn store_array_slice_ndarray_opt<T: Element, D: ndarray::Dimension>(
+        &self,
+        array_slice: &ArraySlice,
+        array: impl Into<ndarray::Array<T, D>>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        let array: ndarray::Array<T, D> = array.into();
+        let array_slice_shape: Vec<usize> = array_slice
+            .shape()
+            .iter()
+            .map(|length| usize::try_from(*length).unwrap())
+            .collect();
+        if array.shape() != array_slice_shape {
+            return Err(ArrayError::InvalidDataShape(
+                array.shape().to_vec(),
+                array_slice_shape,
+            ));
+        }
+        let elements = super::ndarray_into_vec(array);
+        self.store_array_slice_elements_opt(array_slice, &elements, options)
+    }
+
+    /// Update the elements of `array_subset` of the array in place with `update_fn` and default codec options.
+    ///
+    /// See [`update_array_subset_opt`](Array::update_array_subset_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn update_array_subset<T: ElementOwned>(
+        &self,
+        array_subset: &ArraySubset,
+        update_fn: impl Fn(&ArraySubset, &mut [T]) + Send + Sync,
+    ) -> Result<(), ArrayError> {
+        self.update_array_subset_opt(array_subset, update_fn, &CodecOptions::default())
+    }
+
+    /// Update the elements of `array_subset` of the array in place with `update_fn`.
+    ///
+    /// Each chunk intersecting `array_subset` is read, updated, and written back while its store key is locked with [`lock_key`](crate::storage::WritableStorageTraits::lock_key).
+    /// `update_fn` is called once per chunk with the overlap of the chunk and `array_subset` (in array coordinates) and its elements in C-contiguous order.
+    /// Chunks are updated in parallel, so `update_fn` may be called concurrently.
+    ///
+    /// This is a safe primitive for accumulation (e.g. incrementing the bins of a histogram) by concurrent writers, provided the store locks keys (e.g. it is wrapped in a [`LockingStorageAdapter`](crate::storage::storage_adapter::locking::LockingStorageAdapter)) and every writer uses the same locks.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - `T` is incompatible with the data type of the array,
+    ///  - `array_subset` is not within the bounds of the array,
+    ///  - a chunk lock cannot be acquired,
+    ///  - there is a codec decoding or encoding error, or
+    ///  - an underlying store error.
+    ///
+    /// The chunks updated before an error occurs are not reverted.
+    pub fn update_array_subset_opt<T: ElementOwned>(
+        &self,
+        array_subset: &ArraySubset,
+        update_fn: impl Fn(&ArraySubset, &mut [T]) + Send + Sync,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        T::validate_data_type(self.data_type())?;
+        if array_subset.dimensionality() != self.dimensionality()
+            || !array_subset.inbounds(self.shape())
+        {
+            return Err(ArrayError::InvalidArraySubset(
+                array_subset.clone(),
+                self.shape().to_vec(),
+            ));
+        }
+        if array_subset.is_empty() {
+            return Ok(());
+        }
+        let Some(chunks) = self.chunks_in_array_subset(array_subset)? else {
+            return Err(ArrayError::InvalidArraySubset(
+                array_subset.clone(),
+                self.shape().to_vec(),
+            ));
+        };
+
+        // Calculate chunk/codec concurrency
+        let chunk_representation =
+            self.chunk_array_representation(&vec![0; self.dimensionality()])?;
+        let codec_concurrency = self.recommended_codec_concurrency(&chunk_representation)?;
+        let (chunk_concurrent_limit, options) = concurrency_chunks_and_codec(
+            options.concurrent_target(),
+            chunks.num_elements_usize(),
+            options,
+            &codec_concurrency,
+        );
+
+        let update_chunk = |chunk_indices: Vec<u64>| -> Result<(), ArrayError> {
+            let chunk_subset_in_array = self.chunk_subset(&chunk_indices)?;
+            let overlap = unsafe { array_subset.overlap_unchecked(&chunk_subset_in_array) };
+            let overlap_in_chunk =
+                unsafe { overlap.relative_to_unchecked(chunk_subset_in_array.start()) };
+            let chunk_shape = chunk_subset_in_array.shape();
+
+            // Lock the chunk
+            let _lock = self.storage.lock_key(&self.chunk_key(&chunk_indices))?;
+
+            // Update the elements of the overlap
+            let chunk_bytes_old = self.retrieve_chunk_opt(&chunk_indices, &options)?;
+            let overlap_bytes = chunk_bytes_old.extract_array_subset(
+                &overlap_in_chunk,
+                chunk_shape,
+                self.data_type(),
+            )?;
+            let mut overlap_elements = T::from_array_bytes(self.data_type(), overlap_bytes)?;
+            update_fn(&overlap, &mut overlap_elements);
+            let overlap_bytes = T::into_array_bytes(self.data_type(), &overlap_elements)?;
+            overlap_bytes.validate(overlap.num_elements(), self.data_type().size())?;
+
+            // Store the updated chunk
+            let chunk_bytes_new = unsafe {
+                update_array_bytes(
+                    chunk_bytes_old,
+                    chunk_shape,
+                    &overlap_in_chunk,
+                    &overlap_bytes,
+                    self.data_type().size(),
+                )
+            };
+            self.store_chunk_opt(&chunk_indices, chunk_bytes_new, &options)
+        };
+
+        let indices = chunks.indices();
+        rayon_iter_concurrent_limit::iter_concurrent_limit!(
+            chunk_concurrent_limit,
+            indices,
+            try_for_each,
+            update_chunk
+        )
+    }
+
+    /// Compact the shard at `shard_indices` with default codec options.
+    ///
+    /// See [`compact_shard_opt`](Array::compact_shard_opt).
+    #[cfg(feature = "sharding")]
+    #[allow(clippy::missing_errors_doc)]
+    pub fn compact_shard(&self, shard_indices: &[u64]) -> Result<u64, ArrayError> {
+        self.compact_shard_opt(shard_indices, &CodecOptions::default())
+    }
+
+    /// Compact the shard at `shard_indices` by rewriting it without the bytes that are not referenced by its shard index.
+    ///
+    /// Partial encoding of a shard writes updated inner chunks after the existing inner chunks, so the bytes of the inner chunks they replace become unreferenced.
+    /// Compaction copies the referenced inner chunks into a new shard without decoding them.
+    /// Compaction can be triggered automatically after partial encoding with [`CodecOptions::set_shard_compaction_threshold`].
+    ///
+    /// Returns the number of bytes removed from the shard, which is zero if the shard does not exist or has no unreferenced bytes.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - the array is not sharded or has bytes to bytes codecs after the `sharding_indexed` codec,
+    ///  - `shard_indices` are invalid,
+    ///  - the shard index cannot be decoded or encoded, or
+    ///  - there is an underlying store error.
+    #[cfg(feature = "sharding")]
+    pub fn compact_shard_opt(
+        &self,
+        shard_indices: &[u64],
+        options: &CodecOptions,
+    ) -> Result<u64, ArrayError> {
+        let _lock = self.storage.lock_key(&self.chunk_key(shard_indices))?;
+        self.compact_shard_if_exceeds(shard_indices, 0.0, options)
+    }
+
+    /// Compact the shards in `shards` with default codec options.
+    ///
+    /// See [`compact_shards_opt`](Array::compact_shards_opt).
+    #[cfg(feature = "sharding")]
+    #[allow(clippy::missing_errors_doc)]
+    pub fn compact_shards(&self, shards: &ArraySubset) -> Result<u64, ArrayError> {
+        self.compact_shards_opt(shards, &CodecOptions::default())
+    }
+
+    /// Compact the shards in `shards`.
+    ///
+    /// Returns the total number of bytes removed from the shards.
+    /// See [`compact_shard_opt`](Array::compact_shard_opt).
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if compacting any shard fails.
+    #[cfg(feature = "sharding")]
+    pub fn compact_shards_opt(
+        &self,
+        shards: &ArraySubset,
+        options: &CodecOptions,
+    ) -> Result<u64, ArrayError> {
+        shards
+            .indices()
+            .into_par_iter()
+            .map(|shard_indices| self.compact_shard_opt(&shard_indices, options))
+            .try_reduce(|| 0, |a, b| Ok(a + b))
+    }
+
+    /// Compact the shard at `shard_indices` if the fraction of its bytes that are unreferenced exceeds `threshold`.
+    ///
+    /// The shard must be locked by the caller.
+    #[cfg(feature = "sharding")]
+    #[allow(clippy::cast_precision_loss)]
+    fn compact_shard_if_exceeds(
+        &self,
+        shard_indices: &[u64],
+        threshold: f64,
+        options: &CodecOptions,
+    ) -> Result<u64, ArrayError> {
+        let sharding_codec = super::array_sharded_ext::array_sharding_codec(self)?;
+        let Some(encoded_shard) = self.retrieve_encoded_chunk(shard_indices)? else {
+            return Ok(0);
+        };
+
+        // Get the representation of the shard input to the sharding codec
+        let mut shard_representation = self.chunk_array_representation(shard_indices)?;
+        for codec in self.codecs().array_to_array_codecs() {
+            shard_representation = codec.compute_encoded_size(&shard_representation)?;
+        }
+
+        let unreferenced_size = sharding_codec.encoded_shard_unreferenced_size(
+            &encoded_shard,
+            &shard_representation,
+            options,
+        )?;
+        if unreferenced_size == 0
+            || unreferenced_size as f64 <= threshold * encoded_shard.len() as f64
+        {
+            return Ok(0);
+        }
+        let compacted_shard =
+            sharding_codec.compact_encoded_shard(&encoded_shard, &shard_representation, options)?;
+        unsafe { self.store_encoded_chunk(shard_indices, Bytes::from(compacted_shard)) }?;
+        self.invalidate_shard_index(shard_indices);
+        Ok(unreferenced_size)
+    }
+
     /// Initialises a partial encoder for the chunk at `chunk_indices`.
     ///
     /// Only one partial encoder should be created for a chunk at a time because:
@@ -0,0 +1,163 @@
+use std::sync::Arc;
+
+use super::{
+    array_sharded_ext::array_sharding_codec,
+    codec::{CodecError, CodecOptions, ShardWriter},
+    Array, ArrayError,
+};
+use crate::storage::{StorageHandle, StoreValueWriter, WritableStorageTraits};
+
+/// An [`Array`] extension trait to efficiently write arrays using the `sharding_indexed` codec.
+pub trait ArrayShardedWritableExt<TStorage: ?Sized + WritableStorageTraits + 'static> {
+    /// Store the shard at `shard_indices` by writing its inner chunks with a [`ShardWriter`].
+    ///
+    /// `write_inner_chunks` writes the inner chunks of the shard with [`ShardWriter::write_inner_chunk`], where the inner chunk indices are relative to the shard.
+    /// The encoded shard is streamed to the store with [`WritableStorageTraits::set_writer`], so the inner chunks of the shard need not all be held in memory.
+    /// This is useful for converting a large dataset into a sharded array.
+    ///
+    /// The shard is stored even if all of its inner chunks are the fill value.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - the array is not sharded,
+    ///  - the array has array to array or bytes to bytes codecs in addition to the `sharding_indexed` codec,
+    ///  - `shard_indices` are invalid,
+    ///  - `write_inner_chunks` fails, or
+    ///  - there is an underlying store error.
+    fn store_shard_streaming_opt(
+        &self,
+        shard_indices: &[u64],
+        write_inner_chunks: impl FnOnce(
+            &mut ShardWriter<Box<dyn StoreValueWriter + '_>>,
+        ) -> Result<(), ArrayError>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError>;
+}
+
+impl<TStorage: ?Sized + WritableStorageTraits + 'static> ArrayShardedWritableExt<TStorage>
+    for Array<TStorage>
+{
+    fn store_shard_streaming_opt(
+        &self,
+        shard_indices: &[u64],
+        write_inner_chunks: impl FnOnce(
+            &mut ShardWriter<Box<dyn StoreValueWriter + '_>>,
+        ) -> Result<(), ArrayError>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        if !self.codecs().array_to_array_codecs().is_empty() {
+            return Err(CodecError::Other(
+                "streaming a shard is not supported for an array with array to array codecs"
+                    .to_string(),
+            )
+            .into());
+        }
+        let sharding_codec = array_sharding_codec(self)?;
+
+        let shard_representation = self.chunk_array_representation(shard_indices)?;
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_writable_transformer(storage_handle)?;
+        let shard_value_writer = storage_transformer.set_writer(&self.chunk_key(shard_indices))?;
+        let mut shard_writer =
+            sharding_codec.shard_writer(&shard_representation, shard_value_writer, options)?;
+        write_inner_chunks(&mut shard_writer)?;
+        shard_writer.finish()?.finish()?;
+        self.invalidate_shard_index(shard_indices);
+        self.invalidate_chunk_cache(shard_indices);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        array::{
+            codec::array_to_bytes::sharding::{ShardingCodecBuilder, ShardingIndexLocation},
+            ArrayBuilder, DataType, FillValue,
+        },
+        array_subset::ArraySubset,
+        storage::store::MemoryStore,
+    };
+
+    use super::*;
+
+    fn array_sharded_writable_ext_impl(
+        index_location: ShardingIndexLocation,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let store = Arc::new(MemoryStore::default());
+        let array = ArrayBuilder::new(
+            vec![8, 8], // array shape
+            DataType::UInt16,
+            vec![4, 4].try_into()?, // regular chunk shape
+            FillValue::from(0u16),
+        )
+        .array_to_bytes_codec(Arc::new(
+            ShardingCodecBuilder::new(vec![2, 2].try_into()?)
+                .bytes_to_bytes_codecs(vec![
+                    #[cfg(feature = "gzip")]
+                    Arc::new(crate::array::codec::GzipCodec::new(5)?),
+                ])
+                .index_location(index_location)
+                .build(),
+        ))
+        .build(store, "/array")?;
+
+        // Write the inner chunks of shard [0, 1] in reverse order, skipping inner chunk [1, 0]
+        array.store_shard_streaming_opt(
+            &[0, 1],
+            |shard_writer| {
+                assert_eq!(shard_writer.inner_chunk_grid_shape(), &[2, 2]);
+                for inner_chunk_indices in [[1, 1], [0, 1], [0, 0]] {
+                    let offset =
+                        u16::try_from(inner_chunk_indices[0] * 2 + inner_chunk_indices[1]).unwrap();
+                    let elements: Vec<u16> = (0..4).map(|i| offset * 4 + i).collect();
+                    shard_writer.write_inner_chunk(
+                        &inner_chunk_indices,
+                        crate::array::transmute_to_bytes_vec(elements).into(),
+                    )?;
+                }
+                // Inner chunks cannot be written twice
+                assert!(shard_writer
+                    .write_inner_chunk(&[0, 0], vec![0u8; 8].into())
+                    .is_err());
+                Ok(())
+            },
+            &CodecOptions::default(),
+        )?;
+
+        let shard = array.retrieve_chunk_elements::<u16>(&[0, 1])?;
+        assert_eq!(shard, [0, 1, 4, 5, 2, 3, 6, 7, 0, 0, 12, 13, 0, 0, 14, 15]);
+        let inner_chunk = array
+            .retrieve_array_subset_elements::<u16>(&ArraySubset::new_with_ranges(&[2..4, 6..8]))?;
+        assert_eq!(inner_chunk, [12, 13, 14, 15]);
+        Ok(())
+    }
+
+    #[test]
+    fn array_sharded_writable_ext_index_end() -> Result<(), Box<dyn std::error::Error>> {
+        array_sharded_writable_ext_impl(ShardingIndexLocation::End)
+    }
+
+    #[test]
+    fn array_sharded_writable_ext_index_start() -> Result<(), Box<dyn std::error::Error>> {
+        array_sharded_writable_ext_impl(ShardingIndexLocation::Start)
+    }
+
+    #[test]
+    fn array_sharded_writable_ext_unsharded() -> Result<(), Box<dyn std::error::Error>> {
+        let store = Arc::new(MemoryStore::default());
+        let array = ArrayBuilder::new(
+            vec![8, 8],
+            DataType::UInt16,
+            vec![4, 4].try_into()?,
+            FillValue::from(0u16),
+        )
+        .build(store, "/array")?;
+        assert!(array
+            .store_shard_streaming_opt(&[0, 0], |_| Ok(()), &CodecOptions::default())
+            .is_err());
+        Ok(())
+    }
+}
@@ -8,11 +8,11 @@ use crate::{
     array_subset::ArraySubset,
     config::{global_config, MetadataEraseVersion},
     node::{meta_key_v2_array, meta_key_v2_attributes, meta_key_v3},
-    storage::{Bytes, StorageError, StorageHandle, WritableStorageTraits},
+    storage::{Bytes, StorageError, StorageHandle, StoreKey, WritableStorageTraits},
 };
 
 use super::{
-    codec::{options::CodecOptions, ArrayToBytesCodecTraits},
+    codec::{options::CodecOptions, ArrayToBytesCodecTraits, CodecProfileOperation},
     concurrency::concurrency_chunks_and_codec,
     Array, ArrayError, ArrayMetadata, ArrayMetadataOptions, Element,
 };
@@ -32,8 +32,12 @@ impl<TStorage: ?Sized + WritableStorageTraits + 'static> Array<TStorage> {
     ///
     /// The metadata is created with [`Array::metadata_opt`].
     ///
+    /// If the store supports conditional writes ([`set_if_match`](WritableStorageTraits::set_if_match)) and the entity tag of a metadata key is known because the array was opened from the store or has stored its metadata before, the key is only written if it has not been modified by another writer since.
+    /// Otherwise, the metadata is written unconditionally.
+    ///
     /// # Errors
     /// Returns [`StorageError`] if there is an underlying store error.
+    /// Returns [`StorageError::PreconditionFailed`] if the metadata was modified by another writer.
     pub fn store_metadata_opt(&self, options: &ArrayMetadataOptions) -> Result<(), StorageError> {
         let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
         let storage_transformer = self
@@ -50,7 +54,7 @@ impl<TStorage: ?Sized + WritableStorageTraits + 'static> Array<TStorage> {
                 let key = meta_key_v3(path);
                 let json = serde_json::to_vec_pretty(&metadata)
                     .map_err(|err| StorageError::InvalidMetadata(key.clone(), err.to_string()))?;
-                storage_transformer.set(&key, json.into())
+                self.store_metadata_key(&*storage_transformer, &key, json.into())
             }
             ArrayMetadata::V2(metadata) => {
                 let mut metadata = metadata.clone();
@@ -61,7 +65,7 @@ impl<TStorage: ?Sized + WritableStorageTraits + 'static> Array<TStorage> {
                     let json = serde_json::to_vec_pretty(&metadata.attributes).map_err(|err| {
                         StorageError::InvalidMetadata(key.clone(), err.to_string())
                     })?;
-                    storage_transformer.set(&meta_key_v2_attributes(path), json.into())?;
+                    self.store_metadata_key(&*storage_transformer, &key, json.into())?;
 
                     metadata.attributes = serde_json::Map::default();
                 }
@@ -70,11 +74,40 @@ impl<TStorage: ?Sized + WritableStorageTraits + 'static> Array<TStorage> {
                 let key = meta_key_v2_array(path);
                 let json = serde_json::to_vec_pretty(&metadata)
                     .map_err(|err| StorageError::InvalidMetadata(key.clone(), err.to_string()))?;
-                storage_transformer.set(&key, json.into())
+                self.store_metadata_key(&*storage_transformer, &key, json.into())
             }
         }
     }
 
+    /// Store a metadata `value` at `key`, conditional on its entity tag if it is known and the store supports conditional writes.
+    ///
+    /// Falls back to an unconditional write if the store does not support the entity tag.
+    fn store_metadata_key(
+        &self,
+        storage: &dyn WritableStorageTraits,
+        key: &StoreKey,
+        value: Bytes,
+    ) -> Result<(), StorageError> {
+        if storage.supports_set_if_match() {
+            // If the entity tag is unknown, the key is only written if it does not exist so the entity tag of the initial write is known
+            let etag = self.metadata_etag(key);
+            match storage.set_if_match(key, value.clone(), etag.as_ref()) {
+                Ok(etag) => {
+                    self.set_metadata_etag(key, etag);
+                    return Ok(());
+                }
+                // The key exists but its entity tag is unknown, e.g. the array was created over existing metadata
+                Err(StorageError::PreconditionFailed(_)) if etag.is_none() => {}
+                Err(StorageError::Unsupported(_)) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        storage.set(key, value)?;
+        // The entity tag of an unconditional write is unknown
+        self.set_metadata_etag(key, None);
+        Ok(())
+    }
+
     /// Encode `chunk_bytes` and store at `chunk_indices`.
     ///
     /// Use [`store_chunk_opt`](Array::store_chunk_opt) to control codec options.
@@ -232,7 +265,11 @@ impl<TStorage: ?Sized + WritableStorageTraits + 'static> Array<TStorage> {
         let storage_transformer = self
             .storage_transformers()
             .create_writable_transformer(storage_handle)?;
-        storage_transformer.erase(&self.chunk_key(chunk_indices))
+        storage_transformer.erase(&self.chunk_key(chunk_indices))?;
+        self.invalidate_shard_index(chunk_indices);
+        self.invalidate_chunk_cache(chunk_indices);
+        self.invalidate_cached_statistics(chunk_indices);
+        Ok(())
     }
 
     /// Erase the chunks in `chunks`.
@@ -244,8 +281,13 @@ impl<TStorage: ?Sized + WritableStorageTraits + 'static> Array<TStorage> {
         let storage_transformer = self
             .storage_transformers()
             .create_writable_transformer(storage_handle)?;
-        let erase_chunk =
-            |chunk_indices: Vec<u64>| storage_transformer.erase(&self.chunk_key(&chunk_indices));
+        let erase_chunk = |chunk_indices: Vec<u64>| {
+            storage_transformer.erase(&self.chunk_key(&chunk_indices))?;
+            self.invalidate_shard_index(&chunk_indices);
+            self.invalidate_chunk_cache(&chunk_indices);
+            self.invalidate_cached_statistics(&chunk_indices);
+            Ok::<_, StorageError>(())
+        };
 
         chunks.indices().into_par_iter().try_for_each(erase_chunk)
     }
@@ -273,16 +315,47 @@ impl<TStorage: ?Sized + WritableStorageTraits + 'static> Array<TStorage> {
 
         let is_fill_value =
             !options.store_empty_chunks() && chunk_bytes.is_fill_value(self.fill_value());
+        if !is_fill_value {
+            self.update_cached_statistics(chunk_indices, &chunk_bytes)?;
+        }
         if is_fill_value {
             self.erase_chunk(chunk_indices)?;
+        } else if self.codecs().supports_streaming() && options.profiler().is_none() {
+            // Stream the encoded chunk to the store rather than holding it in memory
+            let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+            let storage_transformer = self
+                .storage_transformers()
+                .create_writable_transformer(storage_handle)?;
+            let chunk_writer = storage_transformer.set_writer(&self.chunk_key(chunk_indices))?;
+            self.codecs()
+                .encode_writer(
+                    chunk_bytes,
+                    &chunk_array_representation,
+                    chunk_writer,
+                    options,
+                )
+                .map_err(ArrayError::CodecError)?;
         } else {
             let chunk_encoded = self
                 .codecs()
                 .encode(chunk_bytes, &chunk_array_representation, options)
                 .map_err(ArrayError::CodecError)?;
             let chunk_encoded = Bytes::from(chunk_encoded.into_owned());
+            let chunk_encoded_size = chunk_encoded.len();
+            let start = std::time::Instant::now();
             unsafe { self.store_encoded_chunk(chunk_indices, chunk_encoded) }?;
+            if let Some(profiler) = options.profiler() {
+                profiler.record(
+                    "store",
+                    CodecProfileOperation::StoreStore,
+                    start.elapsed(),
+                    chunk_encoded_size,
+                    0,
+                );
+            }
         }
+        self.invalidate_shard_index(chunk_indices);
+        self.invalidate_chunk_cache(chunk_indices);
         Ok(())
     }
 
@@ -303,6 +376,8 @@ impl<TStorage: ?Sized + WritableStorageTraits + 'static> Array<TStorage> {
             .storage_transformers()
             .create_writable_transformer(storage_handle)?;
         storage_transformer.set(&self.chunk_key(chunk_indices), encoded_chunk_bytes)?;
+        self.invalidate_shard_index(chunk_indices);
+        self.invalidate_chunk_cache(chunk_indices);
 
         Ok(())
     }
@@ -0,0 +1,342 @@
+use std::sync::Arc;
+
+use crate::{
+    array_subset::ArraySubset,
+    storage::{ReadableStorageTraits, StorageHandle},
+};
+
+use super::{
+    codec::CodecOptions, Array, ArrayBytes, ArrayError, DataType, ElementOwned, FillValue,
+};
+
+/// The source of the validity mask of array elements.
+///
+/// Invalid elements are missing data, such as elements that were never written.
+#[derive(Debug)]
+pub enum ValiditySource<'a, TStorage: ?Sized> {
+    /// Elements equal to the fill value are invalid.
+    ///
+    /// If the fill value is NaN, then any NaN element is invalid.
+    FillValue,
+    /// Elements in chunks that do not exist in the store are invalid.
+    ///
+    /// This distinguishes elements that were never written from elements that were written with the fill value.
+    MissingChunks,
+    /// Elements are valid where an accompanying `bool` mask array with the same shape is [`true`].
+    MaskArray(&'a Array<TStorage>),
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
+    /// Read the validity mask of the `array_subset` of the array.
+    ///
+    /// The mask has an element for each element of `array_subset` in C order that is [`true`] where the element is valid.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - the array subset is invalid or out of bounds of the array,
+    ///  - the mask array of [`ValiditySource::MaskArray`] does not have the shape of the array or a `bool` data type,
+    ///  - there is a codec decoding error, or
+    ///  - an underlying store error.
+    pub fn retrieve_array_subset_validity(
+        &self,
+        array_subset: &ArraySubset,
+        source: &ValiditySource<TStorage>,
+    ) -> Result<Vec<bool>, ArrayError> {
+        self.retrieve_array_subset_validity_opt(array_subset, source, &CodecOptions::default())
+    }
+
+    /// Read and decode the `array_subset` of the array into a vector of its elements and its validity mask.
+    ///
+    /// See [`retrieve_array_subset_validity`](Array::retrieve_array_subset_validity).
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - the size of `T` does not match the data type size,
+    ///  - the array subset is invalid or out of bounds of the array,
+    ///  - the mask array of [`ValiditySource::MaskArray`] does not have the shape of the array or a `bool` data type,
+    ///  - there is a codec decoding error, or
+    ///  - an underlying store error.
+    pub fn retrieve_array_subset_elements_with_validity<T: ElementOwned>(
+        &self,
+        array_subset: &ArraySubset,
+        source: &ValiditySource<TStorage>,
+    ) -> Result<(Vec<T>, Vec<bool>), ArrayError> {
+        self.retrieve_array_subset_elements_with_validity_opt(
+            array_subset,
+            source,
+            &CodecOptions::default(),
+        )
+    }
+
+    /// Read and decode the `array_subset` of the array into a vector of its elements, which are [`None`] if invalid.
+    ///
+    /// See [`retrieve_array_subset_validity`](Array::retrieve_array_subset_validity).
+    ///
+    /// # Errors
+    /// See [`retrieve_array_subset_elements_with_validity`](Array::retrieve_array_subset_elements_with_validity).
+    pub fn retrieve_array_subset_elements_optional<T: ElementOwned>(
+        &self,
+        array_subset: &ArraySubset,
+        source: &ValiditySource<TStorage>,
+    ) -> Result<Vec<Option<T>>, ArrayError> {
+        self.retrieve_array_subset_elements_optional_opt(
+            array_subset,
+            source,
+            &CodecOptions::default(),
+        )
+    }
+
+    /// Explicit options version of [`retrieve_array_subset_validity`](Array::retrieve_array_subset_validity).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_array_subset_validity_opt(
+        &self,
+        array_subset: &ArraySubset,
+        source: &ValiditySource<TStorage>,
+        options: &CodecOptions,
+    ) -> Result<Vec<bool>, ArrayError> {
+        match source {
+            ValiditySource::FillValue => {
+                let bytes = self.retrieve_array_subset_opt(array_subset, options)?;
+                Ok(fill_value_validity(
+                    self.data_type(),
+                    self.fill_value(),
+                    &bytes,
+                ))
+            }
+            ValiditySource::MissingChunks => self.chunk_validity(array_subset),
+            ValiditySource::MaskArray(mask_array) => {
+                if mask_array.shape() != self.shape() {
+                    return Err(ArrayError::InvalidMaskArray(
+                        mask_array.shape().to_vec(),
+                        self.shape().to_vec(),
+                    ));
+                }
+                mask_array.retrieve_array_subset_elements_opt::<bool>(array_subset, options)
+            }
+        }
+    }
+
+    /// Explicit options version of [`retrieve_array_subset_elements_with_validity`](Array::retrieve_array_subset_elements_with_validity).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_array_subset_elements_with_validity_opt<T: ElementOwned>(
+        &self,
+        array_subset: &ArraySubset,
+        source: &ValiditySource<TStorage>,
+        options: &CodecOptions,
+    ) -> Result<(Vec<T>, Vec<bool>), ArrayError> {
+        let bytes = self.retrieve_array_subset_opt(array_subset, options)?;
+        let validity = if let ValiditySource::FillValue = source {
+            fill_value_validity(self.data_type(), self.fill_value(), &bytes)
+        } else {
+            self.retrieve_array_subset_validity_opt(array_subset, source, options)?
+        };
+        let elements = T::from_array_bytes(self.data_type(), bytes)?;
+        Ok((elements, validity))
+    }
+
+    /// Explicit options version of [`retrieve_array_subset_elements_optional`](Array::retrieve_array_subset_elements_optional).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_array_subset_elements_optional_opt<T: ElementOwned>(
+        &self,
+        array_subset: &ArraySubset,
+        source: &ValiditySource<TStorage>,
+        options: &CodecOptions,
+    ) -> Result<Vec<Option<T>>, ArrayError> {
+        let (elements, validity) =
+            self.retrieve_array_subset_elements_with_validity_opt(array_subset, source, options)?;
+        Ok(std::iter::zip(elements, validity)
+            .map(|(element, valid)| valid.then_some(element))
+            .collect())
+    }
+
+    /// Return the validity mask of `array_subset` where elements are valid if their chunk exists.
+    fn chunk_validity(&self, array_subset: &ArraySubset) -> Result<Vec<bool>, ArrayError> {
+        let Some(chunks) = self
+            .chunks_in_array_subset(array_subset)?
+            .filter(|_| array_subset.inbounds(self.shape()))
+        else {
+            return Err(ArrayError::InvalidArraySubset(
+                array_subset.clone(),
+                self.shape().to_vec(),
+            ));
+        };
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_readable_transformer(storage_handle)?;
+
+        let mut validity = vec![false; array_subset.num_elements_usize()];
+        for chunk_indices in &chunks.indices() {
+            if storage_transformer
+                .size_key(&self.chunk_key(&chunk_indices))?
+                .is_none()
+            {
+                continue;
+            }
+            let chunk_subset = self.chunk_subset(&chunk_indices)?;
+            let overlap = chunk_subset
+                .overlap(array_subset)?
+                .relative_to(array_subset.start())?;
+            // SAFETY: the overlap is within the array subset
+            let indices = unsafe { overlap.linearised_indices_unchecked(array_subset.shape()) };
+            for index in &indices {
+                validity[usize::try_from(index).unwrap()] = true;
+            }
+        }
+        Ok(validity)
+    }
+}
+
+/// Return the validity mask of `bytes`, where elements equal to the `fill_value` are invalid.
+fn fill_value_validity(
+    data_type: &DataType,
+    fill_value: &FillValue,
+    bytes: &ArrayBytes,
+) -> Vec<bool> {
+    let fill_value = fill_value.as_ne_bytes();
+    let fill_value_is_nan = is_nan(data_type, fill_value);
+    let is_valid = |element: &[u8]| {
+        element != fill_value && !(fill_value_is_nan && is_nan(data_type, element))
+    };
+    match bytes {
+        ArrayBytes::Fixed(bytes) => bytes.chunks_exact(fill_value.len()).map(is_valid).collect(),
+        ArrayBytes::Variable(bytes, offsets) => offsets
+            .windows(2)
+            .map(|range| is_valid(&bytes[range[0]..range[1]]))
+            .collect(),
+    }
+}
+
+/// Return true if `element` is NaN (or has a NaN component) for a floating point `data_type`.
+fn is_nan(data_type: &DataType, element: &[u8]) -> bool {
+    match data_type {
+        DataType::Float16 => {
+            <[u8; 2]>::try_from(element).is_ok_and(|bytes| half::f16::from_ne_bytes(bytes).is_nan())
+        }
+        DataType::BFloat16 => <[u8; 2]>::try_from(element)
+            .is_ok_and(|bytes| half::bf16::from_ne_bytes(bytes).is_nan()),
+        DataType::Float32 | DataType::Complex64 => element
+            .chunks_exact(4)
+            .any(|bytes| f32::from_ne_bytes(bytes.try_into().unwrap()).is_nan()),
+        DataType::Float64 | DataType::Complex128 => element
+            .chunks_exact(8)
+            .any(|bytes| f64::from_ne_bytes(bytes.try_into().unwrap()).is_nan()),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        array::{ArrayBuilder, DataType, FillValue},
+        storage::store::MemoryStore,
+    };
+
+    use super::*;
+
+    #[test]
+    fn array_validity() {
+        let store = Arc::new(MemoryStore::new());
+        let array = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::Float32,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(f32::NAN),
+        )
+        .build(store.clone(), "/data")
+        .unwrap();
+        array
+            .store_chunk_elements(&[0, 0], &[1.0f32, f32::NAN, 0.0, 2.0])
+            .unwrap();
+        array
+            .store_chunk_elements(&[1, 1], &[f32::NAN, 3.0, f32::NAN, f32::NAN])
+            .unwrap();
+        let subset = ArraySubset::new_with_ranges(&[1..3, 0..4]);
+
+        let validity = array
+            .retrieve_array_subset_validity(&subset, &ValiditySource::FillValue)
+            .unwrap();
+        assert_eq!(
+            validity,
+            vec![true, true, false, false, false, false, false, true]
+        );
+        let validity = array
+            .retrieve_array_subset_validity(&subset, &ValiditySource::MissingChunks)
+            .unwrap();
+        assert_eq!(
+            validity,
+            vec![true, true, false, false, false, false, true, true]
+        );
+
+        let (elements, validity) = array
+            .retrieve_array_subset_elements_with_validity::<f32>(
+                &ArraySubset::new_with_ranges(&[0..1, 0..2]),
+                &ValiditySource::FillValue,
+            )
+            .unwrap();
+        assert!((elements[0] - 1.0).abs() < f32::EPSILON);
+        assert!(elements[1].is_nan());
+        assert_eq!(validity, vec![true, false]);
+
+        let mask_array = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::Bool,
+            vec![4, 4].try_into().unwrap(),
+            FillValue::from(false),
+        )
+        .build(store.clone(), "/mask")
+        .unwrap();
+        mask_array
+            .store_array_subset_elements(
+                &ArraySubset::new_with_ranges(&[1..2, 0..4]),
+                &[false, true, true, false],
+            )
+            .unwrap();
+        let elements = array
+            .retrieve_array_subset_elements_optional::<f32>(
+                &ArraySubset::new_with_ranges(&[1..2, 0..2]),
+                &ValiditySource::MaskArray(&mask_array),
+            )
+            .unwrap();
+        assert_eq!(elements, vec![None, Some(2.0)]);
+
+        let mask_array = ArrayBuilder::new(
+            vec![2, 2],
+            DataType::Bool,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(false),
+        )
+        .build(store, "/mask_small")
+        .unwrap();
+        assert!(matches!(
+            array.retrieve_array_subset_validity(&subset, &ValiditySource::MaskArray(&mask_array)),
+            Err(ArrayError::InvalidMaskArray(_, _))
+        ));
+    }
+
+    #[test]
+    fn array_validity_fill_value_vlen() {
+        let store = Arc::new(MemoryStore::new());
+        let array = ArrayBuilder::new(
+            vec![3],
+            DataType::String,
+            vec![3].try_into().unwrap(),
+            FillValue::from(""),
+        )
+        .build(store, "/strings")
+        .unwrap();
+        array
+            .store_array_subset_elements(&array.subset_all(), &["a", "", "b"])
+            .unwrap();
+        let elements = array
+            .retrieve_array_subset_elements_optional::<String>(
+                &array.subset_all(),
+                &ValiditySource::FillValue,
+            )
+            .unwrap();
+        assert_eq!(
+            elements,
+            vec![Some("a".to_string()), None, Some("b".to_string())]
+        );
+    }
+}
@@ -0,0 +1,334 @@
+use std::sync::Arc;
+
+use itertools::izip;
+use num::traits::AsPrimitive;
+
+use crate::{
+    array_subset::{ArraySlice, ArraySubset},
+    storage::ReadableStorageTraits,
+};
+
+use super::{
+    codec::CodecOptions, ravel_indices, unravel_index, Array, ArrayError, ArrayShape, ElementOwned,
+};
+
+/// Retrieve the elements of an array slice in C-contiguous order.
+type RetrieveFn<'a, T> =
+    dyn Fn(&ArraySlice, &CodecOptions) -> Result<Vec<T>, ArrayError> + Send + Sync + 'a;
+
+/// A lazy view of an array.
+///
+/// An array view is a composition of slicing, transposition, data type casting, and element-wise maps of an [`Array`].
+/// The operations are not evaluated until a subset of the view is retrieved, and only the chunks of the array intersecting the retrieved subset are read and decoded.
+///
+/// ```rust
+/// # use std::sync::Arc;
+/// # use zarrs::array::{Array, ArrayBuilder, ArrayView, DataType, FillValue};
+/// # use zarrs::array_subset::ArraySubset;
+/// # use zarrs::storage::store::MemoryStore;
+/// # let array = ArrayBuilder::new(vec![4, 6], DataType::UInt8, vec![2, 2].try_into()?, FillValue::from(0u8))
+/// #     .build(Arc::new(MemoryStore::new()), "/")?;
+/// let view = ArrayView::<u8>::new(&array)?
+///     .subset(&ArraySubset::new_with_ranges(&[0..2, 2..6]))?
+///     .transpose(&[1, 0])?
+///     .cast::<f32>()
+///     .map(|x| x * 0.5);
+/// assert_eq!(view.shape(), &[4, 2]);
+/// let elements: Vec<f32> = view.retrieve_array_subset_elements(&ArraySubset::new_with_ranges(&[0..2, 0..2]))?;
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone)]
+pub struct ArrayView<'a, T> {
+    /// The slice of the array, in the order of the array dimensions.
+    array_slice: ArraySlice,
+    /// The array dimension of each view dimension.
+    axes: Vec<usize>,
+    /// Retrieve the mapped elements of a slice of the array.
+    retrieve: Arc<RetrieveFn<'a, T>>,
+}
+
+impl<T> std::fmt::Debug for ArrayView<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ArrayView")
+            .field("array_slice", &self.array_slice)
+            .field("axes", &self.axes)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, T: ElementOwned + 'a> ArrayView<'a, T> {
+    /// Create a view of all elements of `array`.
+    ///
+    /// # Errors
+    /// Returns [`ArrayError::IncompatibleElementType`] if `T` is incompatible with the data type of `array`.
+    pub fn new<TStorage: ?Sized + ReadableStorageTraits + 'static>(
+        array: &'a Array<TStorage>,
+    ) -> Result<Self, ArrayError> {
+        T::validate_data_type(array.data_type())?;
+        Ok(Self {
+            array_slice: ArraySlice::from(array.subset_all()),
+            axes: (0..array.dimensionality()).collect(),
+            retrieve: Arc::new(move |array_slice, options| {
+                array.retrieve_array_slice_elements_opt(array_slice, options)
+            }),
+        })
+    }
+}
+
+impl<'a, T: Clone + 'a> ArrayView<'a, T> {
+    /// Return the shape of the view.
+    #[must_use]
+    pub fn shape(&self) -> ArrayShape {
+        self.axes
+            .iter()
+            .map(|&axis| self.array_slice.shape()[axis])
+            .collect()
+    }
+
+    /// Return the dimensionality of the view.
+    #[must_use]
+    pub fn dimensionality(&self) -> usize {
+        self.axes.len()
+    }
+
+    /// Return the number of elements of the view.
+    #[must_use]
+    pub fn num_elements(&self) -> u64 {
+        self.array_slice.num_elements()
+    }
+
+    /// Return a view of the `array_slice` of this view.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if `array_slice` is incompatible with the shape of the view.
+    pub fn slice(self, array_slice: &ArraySlice) -> Result<Self, ArrayError> {
+        let shape = self.shape();
+        if array_slice.dimensionality() != self.dimensionality() || !array_slice.inbounds(&shape) {
+            return Err(ArrayError::InvalidArraySubset(
+                array_slice.bounding_subset(),
+                shape,
+            ));
+        }
+        let mut start = self.array_slice.start().to_vec();
+        let mut slice_shape = self.array_slice.shape().to_vec();
+        let mut step = self.array_slice.step().to_vec();
+        for (&axis, &view_start, &view_shape, &view_step) in izip!(
+            &self.axes,
+            array_slice.start(),
+            array_slice.shape(),
+            array_slice.step()
+        ) {
+            start[axis] += view_start * step[axis].get();
+            slice_shape[axis] = view_shape;
+            step[axis] = step[axis].saturating_mul(view_step);
+        }
+        Ok(Self {
+            array_slice: ArraySlice::new_with_start_shape_step(start, slice_shape, step)?,
+            ..self
+        })
+    }
+
+    /// Return a view of the `array_subset` of this view.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if `array_subset` is incompatible with the shape of the view.
+    pub fn subset(self, array_subset: &ArraySubset) -> Result<Self, ArrayError> {
+        self.slice(&ArraySlice::from(array_subset.clone()))
+    }
+
+    /// Return a view with its dimensions permuted.
+    ///
+    /// Dimension `i` of the returned view is dimension `axes[i]` of this view.
+    ///
+    /// # Errors
+    /// Returns [`ArrayError::InvalidPermutation`] if `axes` is not a permutation of the dimensions of the view.
+    pub fn transpose(self, axes: &[usize]) -> Result<Self, ArrayError> {
+        if axes.len() != self.dimensionality() {
+            return Err(ArrayError::InvalidPermutation(axes.to_vec()));
+        }
+        let mut permuted = vec![false; self.dimensionality()];
+        for &axis in axes {
+            match permuted.get_mut(axis) {
+                Some(permuted) if !*permuted => *permuted = true,
+                _ => return Err(ArrayError::InvalidPermutation(axes.to_vec())),
+            }
+        }
+        Ok(Self {
+            axes: axes.iter().map(|&axis| self.axes[axis]).collect(),
+            ..self
+        })
+    }
+
+    /// Return a view with `f` applied to each element.
+    #[must_use]
+    pub fn map<U>(self, f: impl Fn(T) -> U + Send + Sync + 'a) -> ArrayView<'a, U> {
+        let retrieve = self.retrieve;
+        ArrayView {
+            array_slice: self.array_slice,
+            axes: self.axes,
+            retrieve: Arc::new(move |array_slice, options| {
+                Ok(retrieve(array_slice, options)?
+                    .into_iter()
+                    .map(&f)
+                    .collect())
+            }),
+        }
+    }
+
+    /// Return a view with each element cast to `U`.
+    ///
+    /// Elements are cast with the semantics of the `as` operator.
+    #[must_use]
+    pub fn cast<U: Copy + 'static>(self) -> ArrayView<'a, U>
+    where
+        T: AsPrimitive<U>,
+    {
+        self.map(AsPrimitive::as_)
+    }
+
+    /// Evaluate the view and return the elements of its `array_subset`.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - `array_subset` is incompatible with the shape of the view, or
+    ///  - there is an underlying error with the retrieval.
+    pub fn retrieve_array_subset_elements(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<Vec<T>, ArrayError> {
+        self.retrieve_array_subset_elements_opt(array_subset, &CodecOptions::default())
+    }
+
+    /// Evaluate the view and return all of its elements.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if there is an underlying error with the retrieval.
+    pub fn retrieve_elements(&self) -> Result<Vec<T>, ArrayError> {
+        self.retrieve_array_subset_elements(&ArraySubset::new_with_shape(self.shape()))
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Evaluate the view and return its `array_subset` as an [`ndarray::ArrayD`].
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - `array_subset` is incompatible with the shape of the view, or
+    ///  - there is an underlying error with the retrieval.
+    pub fn retrieve_array_subset_ndarray(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<ndarray::ArrayD<T>, ArrayError> {
+        let elements = self.retrieve_array_subset_elements(array_subset)?;
+        super::elements_to_ndarray(array_subset.shape(), elements)
+    }
+
+    /// Explicit options version of [`retrieve_array_subset_elements`](ArrayView::retrieve_array_subset_elements).
+    #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    pub fn retrieve_array_subset_elements_opt(
+        &self,
+        array_subset: &ArraySubset,
+        options: &CodecOptions,
+    ) -> Result<Vec<T>, ArrayError> {
+        let view = self.clone().subset(array_subset)?;
+        let elements = (view.retrieve)(&view.array_slice, options)?;
+
+        // Permute the elements from the order of the array dimensions to the order of the view dimensions
+        if view.axes.windows(2).all(|axes| axes[0] < axes[1]) {
+            return Ok(elements);
+        }
+        let array_slice_shape = view.array_slice.shape();
+        let shape = view.shape();
+        let mut indices = vec![0; view.dimensionality()];
+        Ok((0..view.num_elements())
+            .map(|index| {
+                for (&axis, index) in std::iter::zip(&view.axes, unravel_index(index, &shape)) {
+                    indices[axis] = index;
+                }
+                elements[usize::try_from(ravel_indices(&indices, array_slice_shape)).unwrap()]
+                    .clone()
+            })
+            .collect())
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
+    /// Return a lazy [`ArrayView`] of all elements of the array.
+    ///
+    /// # Errors
+    /// Returns [`ArrayError::IncompatibleElementType`] if `T` is incompatible with the data type of the array.
+    pub fn view<'a, T: ElementOwned + 'a>(&'a self) -> Result<ArrayView<'a, T>, ArrayError> {
+        ArrayView::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{mem::size_of, num::NonZeroU64};
+
+    use crate::{
+        array::{ArrayBuilder, DataType, FillValue},
+        storage::{
+            storage_adapter::performance_metrics::PerformanceMetricsStorageAdapter,
+            store::MemoryStore,
+        },
+    };
+
+    use super::*;
+
+    #[test]
+    fn array_view() {
+        let store = Arc::new(PerformanceMetricsStorageAdapter::new(Arc::new(
+            MemoryStore::new(),
+        )));
+        let array = ArrayBuilder::new(
+            vec![4, 6],
+            DataType::UInt16,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u16),
+        )
+        .build(store.clone(), "/")
+        .unwrap();
+        array
+            .store_array_subset_elements(&array.subset_all(), &(0..24).collect::<Vec<u16>>())
+            .unwrap();
+
+        assert!(array.view::<u8>().is_err());
+        let view = array.view::<u16>().unwrap();
+        assert!(view.clone().transpose(&[0]).is_err());
+        assert!(view.clone().transpose(&[1, 1]).is_err());
+        assert!(view
+            .clone()
+            .subset(&ArraySubset::new_with_ranges(&[0..5, 0..6]))
+            .is_err());
+
+        // [[1, 3, 5], [13, 15, 17]]
+        let step = [NonZeroU64::new(2).unwrap(), NonZeroU64::new(2).unwrap()];
+        let view = view
+            .slice(&ArraySlice::new_with_ranges_step(&[0..4, 1..6], &step).unwrap())
+            .unwrap()
+            .subset(&ArraySubset::new_with_ranges(&[0..2, 0..3]))
+            .unwrap();
+        assert_eq!(view.shape(), vec![2, 3]);
+        assert_eq!(view.retrieve_elements().unwrap(), vec![1, 3, 5, 13, 15, 17]);
+
+        let view = view.transpose(&[1, 0]).unwrap();
+        assert_eq!(view.shape(), vec![3, 2]);
+        assert_eq!(view.retrieve_elements().unwrap(), vec![1, 13, 3, 15, 5, 17]);
+
+        let view = view
+            .cast::<f32>()
+            .map(|x| x * 0.5)
+            .subset(&ArraySubset::new_with_ranges(&[1..3, 1..2]))
+            .unwrap();
+        assert_eq!(view.retrieve_elements().unwrap(), vec![7.5, 8.5]);
+
+        // Only the chunk intersecting the retrieved subset are read
+        store.reset();
+        assert_eq!(
+            view.retrieve_array_subset_elements(&ArraySubset::new_with_ranges(&[1..2, 0..1]))
+                .unwrap(),
+            vec![8.5]
+        );
+        assert!(store.bytes_read() <= 2 * 2 * size_of::<u16>());
+    }
+}
@@ -22,6 +22,7 @@ use std::borrow::Cow;
 type ChunkIndices = ArrayIndices;
 
 /// A chunk cache with a fixed chunk capacity.
+#[derive(Debug)]
 pub struct ChunkCacheLruChunkLimit<T: ChunkCacheType> {
     cache: Cache<ChunkIndices, Arc<T>>,
 }
@@ -33,6 +34,7 @@ pub type ChunkCacheEncodedLruChunkLimit = ChunkCacheLruChunkLimit<ChunkCacheType
 pub type ChunkCacheDecodedLruChunkLimit = ChunkCacheLruChunkLimit<ChunkCacheTypeDecoded>;
 
 /// A chunk cache with a fixed size capacity.
+#[derive(Debug)]
 pub struct ChunkCacheLruSizeLimit<T: ChunkCacheType> {
     cache: Cache<ChunkIndices, Arc<T>>,
 }
@@ -93,6 +95,16 @@ impl<CT: ChunkCacheType> ChunkCacheLruSizeLimit<CT> {
             .build();
         Self { cache }
     }
+
+    /// Invalidate the cached chunk at `chunk_indices`.
+    pub fn invalidate(&self, chunk_indices: &[u64]) {
+        self.cache.invalidate(chunk_indices);
+    }
+
+    /// Invalidate all cached chunks.
+    pub fn clear(&self) {
+        self.cache.invalidate_all();
+    }
 }
 
 impl<CT: ChunkCacheType> ChunkCacheLruChunkLimitThreadLocal<CT> {
@@ -497,6 +509,73 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn array_with_chunk_cache() {
+        let store = Arc::new(MemoryStore::default());
+        let store = Arc::new(PerformanceMetricsStorageAdapter::new(store));
+        let chunk_size = 4 * 4 * size_of::<u8>();
+        let cache = Arc::new(ChunkCacheDecodedLruSizeLimit::new(2 * chunk_size as u64));
+        let array = ArrayBuilder::new(
+            vec![8, 8], // array shape
+            DataType::UInt8,
+            vec![4, 4].try_into().unwrap(), // regular chunk shape
+            FillValue::from(0u8),
+        )
+        .build(store.clone(), "/")
+        .unwrap()
+        .with_chunk_cache(cache.clone());
+        let data: Vec<u8> = (0..64).collect();
+        array
+            .store_array_subset_elements(&array.subset_all(), &data)
+            .unwrap();
+
+        // Intersected chunks are retrieved once, then retrieved from the cache
+        let subset = ArraySubset::new_with_ranges(&[3..5, 0..4]);
+        let expected = vec![24, 25, 26, 27, 32, 33, 34, 35];
+        assert_eq!(
+            array.retrieve_array_subset_elements::<u8>(&subset).unwrap(),
+            expected
+        );
+        assert_eq!(store.reads(), 2);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(
+            array.retrieve_array_subset_elements::<u8>(&subset).unwrap(),
+            expected
+        );
+        assert_eq!(
+            array
+                .retrieve_chunk_subset_elements::<u8>(
+                    &[0, 0],
+                    &ArraySubset::new_with_ranges(&[1..3, 1..3])
+                )
+                .unwrap(),
+            vec![9, 10, 17, 18]
+        );
+        assert_eq!(
+            array.retrieve_chunk_elements::<u8>(&[1, 0]).unwrap()[..4],
+            [32, 33, 34, 35]
+        );
+        assert_eq!(store.reads(), 2);
+
+        // Writing a chunk invalidates it
+        array.store_chunk_elements::<u8>(&[0, 0], &[1; 16]).unwrap();
+        assert!(cache.get(&[0, 0]).is_none());
+        assert_eq!(
+            array.retrieve_array_subset_elements::<u8>(&subset).unwrap(),
+            vec![1, 1, 1, 1, 32, 33, 34, 35]
+        );
+        assert_eq!(store.reads(), 3);
+
+        // Chunks that do not exist are not cached
+        array.erase_chunk(&[0, 0]).unwrap();
+        assert!(array.retrieve_chunk_if_exists(&[0, 0]).unwrap().is_none());
+        assert!(cache.get(&[0, 0]).is_none());
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
     #[test]
     #[cfg_attr(miri, ignore)]
     fn array_chunk_cache_encoded_chunks() {
@@ -2,8 +2,11 @@
 //!
 //! See <https://zarr-specs.readthedocs.io/en/latest/v3/core/v3.0.html#chunk-grids>.
 //!
-//! A [`ChunkGrid`] is a [`Box`] wrapped chunk grid which implements [`ChunkGridTraits`].
-//! Chunk grids are Zarr extension points and they can be registered through [`inventory`] as a [`ChunkGridPlugin`].
+//! A [`ChunkGrid`] is an [`Arc`] wrapped chunk grid which implements [`ChunkGridTraits`].
+//! Chunk grids are Zarr extension points and they can be registered through [`inventory`] or at runtime with [`chunk_grid_register`] as a [`ChunkGridPlugin`].
+//!
+//! A custom chunk grid implements [`ChunkGridTraits`].
+//! The chunk grid of an array maps array subsets to the chunks they intersect for all of its retrieve and store methods, and the chunk grid metadata is written to the array metadata.
 //!
 //! Includes a [`RegularChunkGrid`] and [`RectangularChunkGrid`] implementation.
 //!
@@ -28,7 +31,7 @@ use derive_more::{Deref, From};
 use crate::{
     array_subset::{ArraySubset, IncompatibleDimensionalityError},
     metadata::v3::MetadataV3,
-    plugin::{Plugin, PluginCreateError},
+    plugin::{Plugin, PluginCreateError, RuntimePluginRegistry},
 };
 
 use super::{ArrayIndices, ArrayShape, ChunkShape};
@@ -41,6 +44,31 @@ pub struct ChunkGrid(Arc<dyn ChunkGridTraits>);
 pub type ChunkGridPlugin = Plugin<ChunkGrid>;
 inventory::collect!(ChunkGridPlugin);
 
+/// Chunk grid plugins registered at runtime with [`chunk_grid_register`].
+static CHUNK_GRIDS_REGISTERED: RuntimePluginRegistry<ChunkGrid> = RuntimePluginRegistry::new();
+
+/// Register a chunk grid plugin at runtime.
+///
+/// Chunk grids are usually registered at compile time with [`inventory::submit!`], but this is not possible in some environments or if the chunk grid is only known at runtime.
+/// A registered chunk grid is picked up by [`ChunkGrid::from_metadata`] (e.g. when opening an array) and takes precedence over chunk grids registered at compile time.
+/// A registered chunk grid replaces any chunk grid previously registered with the same identifier.
+pub fn chunk_grid_register(plugin: ChunkGridPlugin) {
+    CHUNK_GRIDS_REGISTERED.register(plugin);
+}
+
+/// Unregister the chunk grid plugin registered with [`chunk_grid_register`] with `identifier`.
+///
+/// Returns true if a chunk grid was unregistered.
+pub fn chunk_grid_unregister(identifier: &str) -> bool {
+    CHUNK_GRIDS_REGISTERED.unregister(identifier)
+}
+
+/// Return the identifiers of the chunk grid plugins registered with [`chunk_grid_register`].
+#[must_use]
+pub fn chunk_grids_registered_identifiers() -> Vec<&'static str> {
+    CHUNK_GRIDS_REGISTERED.identifiers()
+}
+
 impl ChunkGrid {
     /// Create a chunk grid.
     pub fn new<T: ChunkGridTraits + 'static>(chunk_grid: T) -> Self {
@@ -53,7 +81,11 @@ impl ChunkGrid {
     /// # Errors
     ///
     /// Returns a [`PluginCreateError`] if the metadata is invalid or not associated with a registered chunk grid plugin.
+    /// Chunk grid plugins are registered at compile time with [`inventory::submit!`] or at runtime with [`chunk_grid_register`].
     pub fn from_metadata(metadata: &MetadataV3) -> Result<Self, PluginCreateError> {
+        if let Some(plugin) = CHUNK_GRIDS_REGISTERED.find(metadata.name()) {
+            return plugin.create(metadata);
+        }
         for plugin in inventory::iter::<ChunkGridPlugin> {
             if plugin.match_name(metadata.name()) {
                 return plugin.create(metadata);
@@ -493,8 +525,143 @@ pub trait ChunkGridTraits: core::fmt::Debug + Send + Sync {
 
 #[cfg(test)]
 mod tests {
+    use crate::{
+        array::{ArrayBuilder, DataType, FillValue},
+        plugin::PluginMetadataInvalidError,
+        storage::{store::MemoryStore, ReadableStorageTraits, StoreKey},
+    };
+
     use super::*;
 
+    const ROWS_IDENTIFIER: &str = "zarrs.test.rows";
+
+    /// A 2D chunk grid where each chunk is a row of the array.
+    #[derive(Debug)]
+    struct RowsChunkGrid;
+
+    impl ChunkGridTraits for RowsChunkGrid {
+        fn create_metadata(&self) -> MetadataV3 {
+            MetadataV3::new(ROWS_IDENTIFIER)
+        }
+
+        fn dimensionality(&self) -> usize {
+            2
+        }
+
+        unsafe fn grid_shape_unchecked(&self, array_shape: &[u64]) -> Option<ArrayShape> {
+            Some(vec![array_shape[0], 1])
+        }
+
+        unsafe fn chunk_shape_unchecked(
+            &self,
+            chunk_indices: &[u64],
+            array_shape: &[u64],
+        ) -> Option<ChunkShape> {
+            unsafe { self.chunk_shape_u64_unchecked(chunk_indices, array_shape) }
+                .and_then(|chunk_shape| chunk_shape.try_into().ok())
+        }
+
+        unsafe fn chunk_shape_u64_unchecked(
+            &self,
+            _chunk_indices: &[u64],
+            array_shape: &[u64],
+        ) -> Option<ArrayShape> {
+            Some(vec![1, array_shape[1]])
+        }
+
+        unsafe fn chunk_origin_unchecked(
+            &self,
+            chunk_indices: &[u64],
+            _array_shape: &[u64],
+        ) -> Option<ArrayIndices> {
+            Some(vec![chunk_indices[0], 0])
+        }
+
+        unsafe fn chunk_indices_unchecked(
+            &self,
+            array_indices: &[u64],
+            _array_shape: &[u64],
+        ) -> Option<ArrayIndices> {
+            Some(vec![array_indices[0], 0])
+        }
+
+        unsafe fn chunk_element_indices_unchecked(
+            &self,
+            array_indices: &[u64],
+            _array_shape: &[u64],
+        ) -> Option<ArrayIndices> {
+            Some(vec![0, array_indices[1]])
+        }
+    }
+
+    fn is_name_rows(name: &str) -> bool {
+        name == ROWS_IDENTIFIER
+    }
+
+    fn create_chunk_grid_rows(metadata: &MetadataV3) -> Result<ChunkGrid, PluginCreateError> {
+        if metadata.configuration_is_none_or_empty() {
+            Ok(ChunkGrid::new(RowsChunkGrid))
+        } else {
+            Err(
+                PluginMetadataInvalidError::new(ROWS_IDENTIFIER, "chunk grid", metadata.clone())
+                    .into(),
+            )
+        }
+    }
+
+    #[test]
+    fn chunk_grid_register_custom() {
+        let store = Arc::new(MemoryStore::new());
+        let array = ArrayBuilder::new(
+            vec![3, 4],
+            DataType::UInt8,
+            ChunkGrid::new(RowsChunkGrid),
+            FillValue::from(0u8),
+        )
+        .build(store.clone(), "/array")
+        .unwrap();
+        array.store_metadata().unwrap();
+        array
+            .store_array_subset_elements::<u8>(
+                &ArraySubset::new_with_ranges(&[1..3, 1..3]),
+                &[1, 2, 3, 4],
+            )
+            .unwrap();
+        assert_eq!(
+            store
+                .get(&StoreKey::new("array/c/2/0").unwrap())
+                .unwrap()
+                .unwrap()
+                .as_ref(),
+            &[0, 3, 4, 0]
+        );
+        assert!(store
+            .get(&StoreKey::new("array/c/0/0").unwrap())
+            .unwrap()
+            .is_none());
+
+        // The array cannot be opened until the chunk grid is registered
+        assert!(crate::array::Array::open(store.clone(), "/array").is_err());
+        chunk_grid_register(ChunkGridPlugin::new(
+            ROWS_IDENTIFIER,
+            is_name_rows,
+            create_chunk_grid_rows,
+        ));
+        assert!(chunk_grids_registered_identifiers().contains(&ROWS_IDENTIFIER));
+        let array = crate::array::Array::open(store.clone(), "/array").unwrap();
+        assert_eq!(array.chunk_grid_shape(), Some(vec![3, 1]));
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<u8>(&ArraySubset::new_with_ranges(&[0..3, 0..4]))
+                .unwrap(),
+            vec![0, 0, 0, 0, 0, 1, 2, 0, 0, 3, 4, 0]
+        );
+
+        assert!(chunk_grid_unregister(ROWS_IDENTIFIER));
+        assert!(!chunk_grid_unregister(ROWS_IDENTIFIER));
+        assert!(crate::array::Array::open(store, "/array").is_err());
+    }
+
     #[test]
     fn chunk_grid_configuration_regular() {
         let json = r#"
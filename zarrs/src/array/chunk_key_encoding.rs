@@ -1,24 +1,31 @@
-//! Zarr chunk key encodings. Includes a [default](default::DefaultChunkKeyEncoding) and [v2](v2::V2ChunkKeyEncoding) implementation.
+//! Zarr chunk key encodings. Includes a [default](default::DefaultChunkKeyEncoding), [v2](v2::V2ChunkKeyEncoding), and experimental [`hash_prefix`](hash_prefix::HashPrefixChunkKeyEncoding) implementation.
 //!
 //! See <https://zarr-specs.readthedocs.io/en/latest/v3/core/v3.0.html#chunk-key-encoding>.
+//!
+//! Custom chunk key encodings implement [`ChunkKeyEncodingTraits`] and are registered as a [`ChunkKeyEncodingPlugin`] at compile time with [`inventory::submit!`] or at runtime with [`chunk_key_encoding_register`].
+//! All chunk keys of an array are encoded and decoded with its chunk key encoding, and the chunk key encoding metadata is written to the array metadata.
 
 pub mod default;
+pub mod hash_prefix;
 pub mod v2;
 
 use std::sync::Arc;
 
 pub use crate::metadata::{
     v3::array::chunk_key_encoding::{
-        default::DefaultChunkKeyEncodingConfiguration, v2::V2ChunkKeyEncodingConfiguration,
+        default::DefaultChunkKeyEncodingConfiguration,
+        hash_prefix::HashPrefixChunkKeyEncodingConfiguration, v2::V2ChunkKeyEncodingConfiguration,
     },
     ChunkKeySeparator,
 };
 pub use default::DefaultChunkKeyEncoding;
+pub use hash_prefix::HashPrefixChunkKeyEncoding;
 pub use v2::V2ChunkKeyEncoding;
 
 use crate::{
+    array::ArrayIndices,
     metadata::v3::MetadataV3,
-    plugin::{Plugin, PluginCreateError},
+    plugin::{Plugin, PluginCreateError, RuntimePluginRegistry},
     storage::StoreKey,
 };
 
@@ -32,6 +39,32 @@ pub struct ChunkKeyEncoding(Arc<dyn ChunkKeyEncodingTraits>);
 pub type ChunkKeyEncodingPlugin = Plugin<ChunkKeyEncoding>;
 inventory::collect!(ChunkKeyEncodingPlugin);
 
+/// Chunk key encoding plugins registered at runtime with [`chunk_key_encoding_register`].
+static CHUNK_KEY_ENCODINGS_REGISTERED: RuntimePluginRegistry<ChunkKeyEncoding> =
+    RuntimePluginRegistry::new();
+
+/// Register a chunk key encoding plugin at runtime.
+///
+/// Chunk key encodings are usually registered at compile time with [`inventory::submit!`], but this is not possible in some environments or if the chunk key encoding is only known at runtime.
+/// A registered chunk key encoding is picked up by [`ChunkKeyEncoding::from_metadata`] (e.g. when opening an array) and takes precedence over chunk key encodings registered at compile time.
+/// A registered chunk key encoding replaces any chunk key encoding previously registered with the same identifier.
+pub fn chunk_key_encoding_register(plugin: ChunkKeyEncodingPlugin) {
+    CHUNK_KEY_ENCODINGS_REGISTERED.register(plugin);
+}
+
+/// Unregister the chunk key encoding plugin registered with [`chunk_key_encoding_register`] with `identifier`.
+///
+/// Returns true if a chunk key encoding was unregistered.
+pub fn chunk_key_encoding_unregister(identifier: &str) -> bool {
+    CHUNK_KEY_ENCODINGS_REGISTERED.unregister(identifier)
+}
+
+/// Return the identifiers of the chunk key encoding plugins registered with [`chunk_key_encoding_register`].
+#[must_use]
+pub fn chunk_key_encodings_registered_identifiers() -> Vec<&'static str> {
+    CHUNK_KEY_ENCODINGS_REGISTERED.identifiers()
+}
+
 impl ChunkKeyEncoding {
     /// Create a chunk key encoding.
     pub fn new<T: ChunkKeyEncodingTraits + 'static>(chunk_key_encoding: T) -> Self {
@@ -44,7 +77,11 @@ impl ChunkKeyEncoding {
     /// # Errors
     ///
     /// Returns [`PluginCreateError`] if the metadata is invalid or not associated with a registered chunk key encoding plugin.
+    /// Chunk key encoding plugins are registered at compile time with [`inventory::submit!`] or at runtime with [`chunk_key_encoding_register`].
     pub fn from_metadata(metadata: &MetadataV3) -> Result<Self, PluginCreateError> {
+        if let Some(plugin) = CHUNK_KEY_ENCODINGS_REGISTERED.find(metadata.name()) {
+            return plugin.create(metadata);
+        }
         for plugin in inventory::iter::<ChunkKeyEncodingPlugin> {
             if plugin.match_name(metadata.name()) {
                 return plugin.create(metadata);
@@ -60,6 +97,9 @@ impl ChunkKeyEncoding {
                 v2::IDENTIFIER => {
                     return v2::create_chunk_key_encoding_v2(metadata);
                 }
+                hash_prefix::IDENTIFIER => {
+                    return hash_prefix::create_chunk_key_encoding_hash_prefix(metadata);
+                }
                 _ => {}
             }
         }
@@ -86,4 +126,138 @@ pub trait ChunkKeyEncodingTraits: core::fmt::Debug + Send + Sync {
 
     /// Encode chunk grid indices (grid cell coordinates) into a store key.
     fn encode(&self, chunk_grid_indices: &[u64]) -> StoreKey;
+
+    /// Decode a store key into chunk grid indices (grid cell coordinates).
+    ///
+    /// Returns [`None`] if `chunk_key` is not a key produced by [`encode`](ChunkKeyEncodingTraits::encode).
+    /// The default implementation does not support decoding and always returns [`None`].
+    fn decode(&self, chunk_key: &StoreKey) -> Option<ArrayIndices> {
+        let _ = chunk_key;
+        None
+    }
+}
+
+/// Decode a chunk index encoded as an ASCII decimal string without leading zeros.
+fn decode_chunk_index(index: &str) -> Option<u64> {
+    if index.is_empty()
+        || (index.len() > 1 && index.starts_with('0'))
+        || !index.bytes().all(|byte| byte.is_ascii_digit())
+    {
+        None
+    } else {
+        index.parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        array::{ArrayBuilder, DataType, FillValue},
+        array_subset::ArraySubset,
+        plugin::PluginMetadataInvalidError,
+        storage::{store::MemoryStore, ReadableStorageTraits},
+    };
+
+    use super::*;
+
+    const UNDERSCORE_IDENTIFIER: &str = "zarrs.test.underscore";
+
+    /// A chunk key encoding with keys like `k_1_23`.
+    #[derive(Debug)]
+    struct UnderscoreChunkKeyEncoding;
+
+    impl ChunkKeyEncodingTraits for UnderscoreChunkKeyEncoding {
+        fn create_metadata(&self) -> MetadataV3 {
+            MetadataV3::new(UNDERSCORE_IDENTIFIER)
+        }
+
+        fn encode(&self, chunk_grid_indices: &[u64]) -> StoreKey {
+            let indices: Vec<String> = chunk_grid_indices.iter().map(u64::to_string).collect();
+            StoreKey::new(format!("k_{}", indices.join("_"))).unwrap()
+        }
+
+        fn decode(&self, chunk_key: &StoreKey) -> Option<ArrayIndices> {
+            chunk_key
+                .as_str()
+                .strip_prefix("k_")?
+                .split('_')
+                .map(decode_chunk_index)
+                .collect()
+        }
+    }
+
+    fn is_name_underscore(name: &str) -> bool {
+        name == UNDERSCORE_IDENTIFIER
+    }
+
+    fn create_chunk_key_encoding_underscore(
+        metadata: &MetadataV3,
+    ) -> Result<ChunkKeyEncoding, PluginCreateError> {
+        if metadata.configuration_is_none_or_empty() {
+            Ok(ChunkKeyEncoding::new(UnderscoreChunkKeyEncoding))
+        } else {
+            Err(PluginMetadataInvalidError::new(
+                UNDERSCORE_IDENTIFIER,
+                "chunk key encoding",
+                metadata.clone(),
+            )
+            .into())
+        }
+    }
+
+    #[test]
+    fn chunk_key_encoding_register_custom() {
+        let store = Arc::new(MemoryStore::new());
+        let array = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::UInt8,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .chunk_key_encoding(UnderscoreChunkKeyEncoding.into())
+        .build(store.clone(), "/array")
+        .unwrap();
+        array.store_metadata().unwrap();
+        array
+            .store_chunk_elements::<u8>(&[1, 0], &[1, 2, 3, 4])
+            .unwrap();
+        assert!(store
+            .get(&StoreKey::new("array/k_1_0").unwrap())
+            .unwrap()
+            .is_some());
+
+        // The chunk key encoding is emitted in the array metadata
+        let metadata = store
+            .get(&StoreKey::new("array/zarr.json").unwrap())
+            .unwrap()
+            .unwrap();
+        let metadata: serde_json::Value = serde_json::from_slice(&metadata).unwrap();
+        assert_eq!(metadata["chunk_key_encoding"], UNDERSCORE_IDENTIFIER);
+
+        // The array cannot be opened until the chunk key encoding is registered
+        assert!(crate::array::Array::open(store.clone(), "/array").is_err());
+        chunk_key_encoding_register(ChunkKeyEncodingPlugin::new(
+            UNDERSCORE_IDENTIFIER,
+            is_name_underscore,
+            create_chunk_key_encoding_underscore,
+        ));
+        assert!(chunk_key_encodings_registered_identifiers().contains(&UNDERSCORE_IDENTIFIER));
+        let array = crate::array::Array::open(store.clone(), "/array").unwrap();
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<u8>(&ArraySubset::new_with_ranges(&[2..4, 0..2]))
+                .unwrap(),
+            vec![1, 2, 3, 4]
+        );
+        assert_eq!(
+            array
+                .chunk_key_encoding()
+                .decode(&StoreKey::new("k_1_0").unwrap()),
+            Some(vec![1, 0])
+        );
+
+        assert!(chunk_key_encoding_unregister(UNDERSCORE_IDENTIFIER));
+        assert!(!chunk_key_encoding_unregister(UNDERSCORE_IDENTIFIER));
+        assert!(crate::array::Array::open(store, "/array").is_err());
+    }
 }
@@ -1,14 +1,14 @@
 //! The default chunk key encoding.
 
 use crate::{
-    array::chunk_key_encoding::ChunkKeyEncodingPlugin,
+    array::{chunk_key_encoding::ChunkKeyEncodingPlugin, ArrayIndices},
     metadata::v3::{array::chunk_key_encoding::default, MetadataV3},
     plugin::{PluginCreateError, PluginMetadataInvalidError},
     storage::StoreKey,
 };
 
 use super::{
-    ChunkKeyEncoding, ChunkKeyEncodingTraits, ChunkKeySeparator,
+    decode_chunk_index, ChunkKeyEncoding, ChunkKeyEncodingTraits, ChunkKeySeparator,
     DefaultChunkKeyEncodingConfiguration,
 };
 
@@ -100,6 +100,18 @@ impl ChunkKeyEncodingTraits for DefaultChunkKeyEncoding {
         }
         unsafe { StoreKey::new_unchecked(key) }
     }
+
+    fn decode(&self, chunk_key: &StoreKey) -> Option<ArrayIndices> {
+        let indices = chunk_key.as_str().strip_prefix('c')?;
+        if indices.is_empty() {
+            return Some(vec![]);
+        }
+        indices
+            .strip_prefix(self.separator.to_string().as_str())?
+            .split(&self.separator.to_string())
+            .map(decode_chunk_index)
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -122,6 +134,23 @@ mod tests {
         assert_eq!(key, StoreKey::new("c.1.23.45").unwrap());
     }
 
+    #[test]
+    fn decode() {
+        let chunk_key_encoding: ChunkKeyEncoding = DefaultChunkKeyEncoding::new_slash().into();
+        let decode = |key: &str| chunk_key_encoding.decode(&StoreKey::new(key).unwrap());
+        assert_eq!(decode("c/1/23/45"), Some(vec![1, 23, 45]));
+        assert_eq!(decode("c"), Some(vec![]));
+        assert_eq!(decode("c.1.23"), None);
+        assert_eq!(decode("c/01/2"), None);
+        assert_eq!(decode("c/1/x"), None);
+        assert_eq!(decode("d/1"), None);
+        let chunk_key_encoding: ChunkKeyEncoding = DefaultChunkKeyEncoding::new_dot().into();
+        assert_eq!(
+            chunk_key_encoding.decode(&StoreKey::new("c.1.23").unwrap()),
+            Some(vec![1, 23])
+        );
+    }
+
     #[test]
     fn slash_scalar() {
         let chunk_key_encoding: ChunkKeyEncoding = DefaultChunkKeyEncoding::new_slash().into();
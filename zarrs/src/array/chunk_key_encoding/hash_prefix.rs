@@ -0,0 +1,195 @@
+//! The `hash_prefix` chunk key encoding.
+//!
+//! <div class="warning">
+//! This chunk key encoding is experimental and may be incompatible with other Zarr V3 implementations.
+//! </div>
+
+use crate::{
+    array::{chunk_key_encoding::ChunkKeyEncodingPlugin, ArrayIndices},
+    metadata::v3::{array::chunk_key_encoding::hash_prefix, MetadataV3},
+    plugin::{PluginCreateError, PluginMetadataInvalidError},
+    storage::StoreKey,
+};
+
+use super::{
+    ChunkKeyEncoding, ChunkKeyEncodingTraits, ChunkKeySeparator, DefaultChunkKeyEncoding,
+    HashPrefixChunkKeyEncodingConfiguration,
+};
+
+pub use hash_prefix::IDENTIFIER;
+
+/// The maximum number of hexadecimal digits of the hash prefix.
+const MAX_PREFIX_LENGTH: u8 = 16;
+
+// Register the chunk key encoding.
+inventory::submit! {
+    ChunkKeyEncodingPlugin::new(
+        IDENTIFIER,
+        is_name_hash_prefix,
+        create_chunk_key_encoding_hash_prefix
+    )
+}
+
+fn is_name_hash_prefix(name: &str) -> bool {
+    name.eq(IDENTIFIER)
+}
+
+pub(crate) fn create_chunk_key_encoding_hash_prefix(
+    metadata: &MetadataV3,
+) -> Result<ChunkKeyEncoding, PluginCreateError> {
+    let configuration: HashPrefixChunkKeyEncodingConfiguration =
+        metadata.to_configuration().map_err(|_| {
+            PluginMetadataInvalidError::new(IDENTIFIER, "chunk key encoding", metadata.clone())
+        })?;
+    let hash_prefix =
+        HashPrefixChunkKeyEncoding::new(configuration.separator, configuration.prefix_length)?;
+    Ok(ChunkKeyEncoding::new(hash_prefix))
+}
+
+/// A `hash_prefix` chunk key encoding.
+///
+/// The key for a chunk is the key of the [`default`](DefaultChunkKeyEncoding) chunk key encoding prefixed by a short hash of the chunk grid indices and the separator (e.g. `302d/c/1/23/45`).
+/// The hash is the first `prefix_length` lowercase hexadecimal digits of the 64-bit FNV-1a hash of the chunk grid indices, each encoded as 8 little-endian bytes.
+///
+/// Object stores such as S3 and GCS partition keys by prefix, so sequential chunk keys concentrate massively parallel writes on few partitions.
+/// Hash prefixed keys spread neighbouring chunks uniformly across `16^prefix_length` prefixes.
+#[derive(Debug, Clone)]
+pub struct HashPrefixChunkKeyEncoding {
+    separator: ChunkKeySeparator,
+    prefix_length: u8,
+}
+
+impl HashPrefixChunkKeyEncoding {
+    /// Create a new `hash_prefix` chunk key encoding with separator `separator` and a hash prefix of `prefix_length` hexadecimal digits.
+    ///
+    /// # Errors
+    /// Returns a [`PluginCreateError`] if `prefix_length` is not between 1 and 16.
+    pub fn new(separator: ChunkKeySeparator, prefix_length: u8) -> Result<Self, PluginCreateError> {
+        if (1..=MAX_PREFIX_LENGTH).contains(&prefix_length) {
+            Ok(Self {
+                separator,
+                prefix_length,
+            })
+        } else {
+            Err(PluginCreateError::Other(format!(
+                "{IDENTIFIER} chunk key encoding prefix length {prefix_length} is not between 1 and {MAX_PREFIX_LENGTH}"
+            )))
+        }
+    }
+
+    /// Return the hash prefix of the chunk with `chunk_grid_indices`.
+    fn prefix(&self, chunk_grid_indices: &[u64]) -> String {
+        let hash = chunk_grid_indices
+            .iter()
+            .flat_map(|index| index.to_le_bytes())
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+            });
+        let mut prefix = format!("{hash:016x}");
+        prefix.truncate(usize::from(self.prefix_length));
+        prefix
+    }
+}
+
+impl Default for HashPrefixChunkKeyEncoding {
+    /// Create a `hash_prefix` chunk key encoding with default separator `/` and a 4 digit hash prefix.
+    fn default() -> Self {
+        Self {
+            separator: ChunkKeySeparator::Slash,
+            prefix_length: 4,
+        }
+    }
+}
+
+impl ChunkKeyEncodingTraits for HashPrefixChunkKeyEncoding {
+    fn create_metadata(&self) -> MetadataV3 {
+        let configuration = HashPrefixChunkKeyEncodingConfiguration {
+            separator: self.separator,
+            prefix_length: self.prefix_length,
+        };
+        MetadataV3::new_with_serializable_configuration(IDENTIFIER, &configuration).unwrap()
+    }
+
+    fn encode(&self, chunk_grid_indices: &[u64]) -> StoreKey {
+        let key = DefaultChunkKeyEncoding::new(self.separator).encode(chunk_grid_indices);
+        let key = self.prefix(chunk_grid_indices) + &self.separator.to_string() + key.as_str();
+        unsafe { StoreKey::new_unchecked(key) }
+    }
+
+    fn decode(&self, chunk_key: &StoreKey) -> Option<ArrayIndices> {
+        let (prefix, key) = chunk_key
+            .as_str()
+            .split_once(self.separator.to_string().as_str())?;
+        let chunk_grid_indices =
+            DefaultChunkKeyEncoding::new(self.separator).decode(&StoreKey::new(key).ok()?)?;
+        (prefix == self.prefix(&chunk_grid_indices)).then_some(chunk_grid_indices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::node::{data_key, NodePath};
+
+    use super::*;
+
+    #[test]
+    fn hash_prefix_slash_nd() {
+        let chunk_key_encoding: ChunkKeyEncoding = HashPrefixChunkKeyEncoding::default().into();
+        let key = data_key(&NodePath::root(), &chunk_key_encoding.encode(&[1, 23, 45]));
+        assert_eq!(key, StoreKey::new("302d/c/1/23/45").unwrap());
+        let key = data_key(&NodePath::root(), &chunk_key_encoding.encode(&[]));
+        assert_eq!(key, StoreKey::new("cbf2/c").unwrap());
+    }
+
+    #[test]
+    fn hash_prefix_dot_nd() {
+        let chunk_key_encoding: ChunkKeyEncoding =
+            HashPrefixChunkKeyEncoding::new(ChunkKeySeparator::Dot, 2)
+                .unwrap()
+                .into();
+        assert_eq!(
+            chunk_key_encoding.encode(&[0, 1]),
+            StoreKey::new("69.c.0.1").unwrap()
+        );
+        assert_eq!(
+            chunk_key_encoding.encode(&[1, 0]),
+            StoreKey::new("39.c.1.0").unwrap()
+        );
+        assert!(HashPrefixChunkKeyEncoding::new(ChunkKeySeparator::Dot, 0).is_err());
+        assert!(HashPrefixChunkKeyEncoding::new(ChunkKeySeparator::Dot, 17).is_err());
+    }
+
+    #[test]
+    fn hash_prefix_decode() {
+        let chunk_key_encoding: ChunkKeyEncoding = HashPrefixChunkKeyEncoding::default().into();
+        let decode = |key: &str| chunk_key_encoding.decode(&StoreKey::new(key).unwrap());
+        assert_eq!(decode("302d/c/1/23/45"), Some(vec![1, 23, 45]));
+        assert_eq!(decode("cbf2/c"), Some(vec![]));
+        assert_eq!(decode("302e/c/1/23/45"), None);
+        assert_eq!(decode("c/1/23/45"), None);
+        assert_eq!(decode("302d/c/1/23/x"), None);
+    }
+
+    #[test]
+    fn hash_prefix_metadata_round_trip() {
+        let chunk_key_encoding: ChunkKeyEncoding =
+            HashPrefixChunkKeyEncoding::new(ChunkKeySeparator::Dot, 6)
+                .unwrap()
+                .into();
+        let metadata = chunk_key_encoding.create_metadata();
+        assert_eq!(
+            metadata.to_string(),
+            r#"hash_prefix {"separator":".","prefix_length":6}"#
+        );
+        let chunk_key_encoding_round_trip = ChunkKeyEncoding::from_metadata(&metadata).unwrap();
+        assert_eq!(
+            chunk_key_encoding_round_trip.encode(&[1, 23, 45]),
+            StoreKey::new("302d4b.c.1.23.45").unwrap()
+        );
+        let metadata = MetadataV3::new_with_configuration(
+            IDENTIFIER,
+            serde_json::from_str(r#"{"prefix_length":32}"#).unwrap(),
+        );
+        assert!(ChunkKeyEncoding::from_metadata(&metadata).is_err());
+    }
+}
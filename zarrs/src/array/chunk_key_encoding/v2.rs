@@ -1,14 +1,15 @@
 //! The v2 chunk key encoding.
 
 use crate::{
-    array::chunk_key_encoding::ChunkKeyEncodingPlugin,
+    array::{chunk_key_encoding::ChunkKeyEncodingPlugin, ArrayIndices},
     metadata::v3::{array::chunk_key_encoding::v2, MetadataV3},
     plugin::{PluginCreateError, PluginMetadataInvalidError},
     storage::StoreKey,
 };
 
 use super::{
-    ChunkKeyEncoding, ChunkKeyEncodingTraits, ChunkKeySeparator, V2ChunkKeyEncodingConfiguration,
+    decode_chunk_index, ChunkKeyEncoding, ChunkKeyEncodingTraits, ChunkKeySeparator,
+    V2ChunkKeyEncodingConfiguration,
 };
 
 pub use v2::IDENTIFIER;
@@ -98,6 +99,14 @@ impl ChunkKeyEncodingTraits for V2ChunkKeyEncoding {
         };
         unsafe { StoreKey::new_unchecked(key) }
     }
+
+    fn decode(&self, chunk_key: &StoreKey) -> Option<ArrayIndices> {
+        chunk_key
+            .as_str()
+            .split(&self.separator.to_string())
+            .map(decode_chunk_index)
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -120,6 +129,16 @@ mod tests {
         assert_eq!(key, StoreKey::new("1.23.45").unwrap());
     }
 
+    #[test]
+    fn decode() {
+        let chunk_key_encoding: ChunkKeyEncoding = V2ChunkKeyEncoding::new_dot().into();
+        let decode = |key: &str| chunk_key_encoding.decode(&StoreKey::new(key).unwrap());
+        assert_eq!(decode("1.23.45"), Some(vec![1, 23, 45]));
+        assert_eq!(decode("0"), Some(vec![0]));
+        assert_eq!(decode("1/23"), None);
+        assert_eq!(decode("1..2"), None);
+    }
+
     #[test]
     fn slash_scalar() {
         let chunk_key_encoding: ChunkKeyEncoding = V2ChunkKeyEncoding::new_slash().into();
@@ -12,36 +12,73 @@
 
 pub mod array_to_array;
 pub mod array_to_bytes;
+pub mod backend;
 pub mod bytes_to_bytes;
 pub mod options;
+pub mod profiler;
 
+pub use backend::CodecBackend;
 pub use options::{CodecOptions, CodecOptionsBuilder};
+pub use profiler::{
+    CodecProfileOperation, CodecProfileRecord, CodecProfileReport, CodecProfileReportEntry,
+    CodecProfiler,
+};
 
 // Array to array
 #[cfg(feature = "bitround")]
 pub use array_to_array::bitround::{
-    BitroundCodec, BitroundCodecConfiguration, BitroundCodecConfigurationV1,
+    BitroundCodec, BitroundCodecConfiguration, BitroundCodecConfigurationV1, BitroundKeepbits,
+};
+#[cfg(feature = "delta")]
+pub use array_to_array::delta::{DeltaCodec, DeltaCodecConfiguration, DeltaCodecConfigurationV1};
+#[cfg(feature = "fixedscaleoffset")]
+pub use array_to_array::fixedscaleoffset::{
+    FixedScaleOffsetCodec, FixedScaleOffsetCodecConfiguration, FixedScaleOffsetCodecConfigurationV1,
 };
 #[cfg(feature = "transpose")]
 pub use array_to_array::transpose::{
-    TransposeCodec, TransposeCodecConfiguration, TransposeCodecConfigurationV1,
+    TransposeCodec, TransposeCodecConfiguration, TransposeCodecConfigurationV1, TransposeOrder,
 };
 
 // Array to bytes
+#[cfg(feature = "blosc2")]
+pub use array_to_bytes::blosc2::{
+    Blosc2Codec, Blosc2CodecConfiguration, Blosc2CodecConfigurationV1, Blosc2Compressor,
+};
 pub use array_to_bytes::bytes::{BytesCodec, BytesCodecConfiguration, BytesCodecConfigurationV1};
-pub use array_to_bytes::codec_chain::CodecChain;
+pub use array_to_bytes::codec_chain::{CodecChain, PartialDecoderCacheMode};
+#[cfg(feature = "jpegxl")]
+pub use array_to_bytes::jpegxl::{
+    JpegXlCodec, JpegXlCodecConfiguration, JpegXlCodecConfigurationV1,
+};
+#[cfg(feature = "packbits")]
+pub use array_to_bytes::packbits::{
+    PackBitsCodec, PackBitsCodecConfiguration, PackBitsCodecConfigurationV1,
+    PackBitsPaddingEncoding,
+};
 #[cfg(feature = "pcodec")]
 pub use array_to_bytes::pcodec::{
     PcodecCodec, PcodecCodecConfiguration, PcodecCodecConfigurationV1,
 };
+#[cfg(feature = "png")]
+pub use array_to_bytes::png::{PngCodec, PngCodecConfiguration, PngCodecConfigurationV1};
+#[cfg(feature = "rle")]
+pub use array_to_bytes::rle::{RleCodec, RleCodecConfiguration, RleCodecConfigurationV1};
 #[cfg(feature = "sharding")]
 pub use array_to_bytes::sharding::{
-    ShardingCodec, ShardingCodecConfiguration, ShardingCodecConfigurationV1,
+    ShardIndexCache, ShardWriter, ShardingCodec, ShardingCodecConfiguration,
+    ShardingCodecConfigurationV1,
 };
+#[cfg(feature = "sz3")]
+pub use array_to_bytes::sz3::{Sz3Codec, Sz3CodecConfiguration, Sz3CodecConfigurationV1};
 #[cfg(feature = "zfp")]
 pub use array_to_bytes::zfp::{ZfpCodec, ZfpCodecConfiguration, ZfpCodecConfigurationV1};
 
 // Bytes to bytes
+#[cfg(feature = "bitshuffle")]
+pub use bytes_to_bytes::bitshuffle::{
+    BitshuffleCodec, BitshuffleCodecConfiguration, BitshuffleCodecConfigurationV1,
+};
 #[cfg(feature = "blosc")]
 pub use bytes_to_bytes::blosc::{BloscCodec, BloscCodecConfiguration, BloscCodecConfigurationV1};
 #[cfg(feature = "bz2")]
@@ -50,8 +87,16 @@ pub use bytes_to_bytes::bz2::{Bz2Codec, Bz2CodecConfiguration, Bz2CodecConfigura
 pub use bytes_to_bytes::crc32c::{
     Crc32cCodec, Crc32cCodecConfiguration, Crc32cCodecConfigurationV1,
 };
+#[cfg(feature = "framed")]
+pub use bytes_to_bytes::framed::{
+    FramedCodec, FramedCodecConfiguration, FramedCodecConfigurationV1,
+};
 #[cfg(feature = "gzip")]
 pub use bytes_to_bytes::gzip::{GzipCodec, GzipCodecConfiguration, GzipCodecConfigurationV1};
+#[cfg(feature = "shuffle")]
+pub use bytes_to_bytes::shuffle::{
+    ShuffleCodec, ShuffleCodecConfiguration, ShuffleCodecConfigurationV1,
+};
 #[cfg(feature = "zstd")]
 pub use bytes_to_bytes::zstd::{ZstdCodec, ZstdCodecConfiguration, ZstdCodecConfigurationV1};
 
@@ -65,6 +110,9 @@ pub use bytes_partial_decoder_cache::BytesPartialDecoderCache;
 mod byte_interval_partial_decoder;
 pub use byte_interval_partial_decoder::ByteIntervalPartialDecoder;
 
+mod byte_range_coalesce;
+use byte_range_coalesce::CoalescedByteRanges;
+
 #[cfg(feature = "async")]
 pub use byte_interval_partial_decoder::AsyncByteIntervalPartialDecoder;
 use unsafe_cell_slice::UnsafeCellSlice;
@@ -72,25 +120,38 @@ use unsafe_cell_slice::UnsafeCellSlice;
 mod array_partial_encoder_default;
 pub use array_partial_encoder_default::ArrayPartialEncoderDefault;
 
+#[cfg(feature = "async")]
+pub use array_partial_encoder_default::AsyncArrayPartialEncoderDefault;
+
 mod array_to_array_partial_encoder_default;
 pub use array_to_array_partial_encoder_default::ArrayToArrayPartialEncoderDefault;
 
+#[cfg(feature = "async")]
+pub use array_to_array_partial_encoder_default::AsyncArrayToArrayPartialEncoderDefault;
+
 mod bytes_partial_encoder_default;
 pub use bytes_partial_encoder_default::BytesPartialEncoderDefault;
 
+#[cfg(feature = "async")]
+pub use bytes_partial_encoder_default::AsyncBytesPartialEncoderDefault;
+
+mod bytes_encode_writer_default;
+use bytes_encode_writer_default::BytesEncodeWriterDefault;
+
 use crate::storage::{StoreKeyOffsetValue, WritableStorage};
 use crate::{
     array_subset::{ArraySubset, IncompatibleArraySubsetAndShapeError},
     byte_range::{extract_byte_ranges_read_seek, ByteOffset, ByteRange, InvalidByteRangeError},
     metadata::v3::MetadataV3,
-    plugin::{Plugin, PluginCreateError},
-    storage::{ReadableStorage, StorageError, StoreKey},
+    plugin::{Plugin, PluginCreateError, RuntimePluginRegistry},
+    storage::{ReadableStorage, StorageError, StoreKey, StoreValueReader, StoreValueWriter},
 };
 
 #[cfg(feature = "async")]
-use crate::storage::AsyncReadableStorage;
+use crate::storage::{AsyncReadableStorage, AsyncWritableStorage};
 
 use std::borrow::Cow;
+use std::io::Read;
 use std::sync::Arc;
 
 use super::array_bytes::update_bytes_flen;
@@ -104,6 +165,33 @@ use super::{ArrayBytes, RawBytes};
 pub type CodecPlugin = Plugin<Codec>;
 inventory::collect!(CodecPlugin);
 
+/// Codec plugins registered at runtime with [`codec_register`].
+static CODECS_REGISTERED: RuntimePluginRegistry<Codec> = RuntimePluginRegistry::new();
+
+/// Register a codec plugin at runtime.
+///
+/// Codecs are usually registered at compile time with [`inventory::submit!`], but this is not possible in some environments or if the codec is only known at runtime.
+/// A registered codec is picked up by [`Codec::from_metadata`] (e.g. when opening an array) and takes precedence over codecs registered at compile time.
+/// A registered codec replaces any codec previously registered with the same identifier.
+///
+/// See the `custom_codec` example for an out-of-tree codec.
+pub fn codec_register(plugin: CodecPlugin) {
+    CODECS_REGISTERED.register(plugin);
+}
+
+/// Unregister the codec plugin registered with [`codec_register`] with `identifier`.
+///
+/// Returns true if a codec was unregistered.
+pub fn codec_unregister(identifier: &str) -> bool {
+    CODECS_REGISTERED.unregister(identifier)
+}
+
+/// Return the identifiers of the codec plugins registered with [`codec_register`].
+#[must_use]
+pub fn codecs_registered_identifiers() -> Vec<&'static str> {
+    CODECS_REGISTERED.identifiers()
+}
+
 /// A generic array to array, array to bytes, or bytes to bytes codec.
 #[derive(Debug)]
 pub enum Codec {
@@ -120,77 +208,126 @@ impl Codec {
     ///
     /// # Errors
     /// Returns [`PluginCreateError`] if the metadata is invalid or not associated with a registered codec plugin.
+    /// Codec plugins are registered at compile time with [`inventory::submit!`] or at runtime with [`codec_register`].
     pub fn from_metadata(metadata: &MetadataV3) -> Result<Self, PluginCreateError> {
-        for plugin in inventory::iter::<CodecPlugin> {
-            if plugin.match_name(metadata.name()) {
-                return plugin.create(metadata);
-            }
+        if let Some(codec) = Self::from_registered_plugin(metadata) {
+            return codec;
         }
         #[cfg(miri)]
-        {
-            // Inventory does not work in miri, so manually handle all known codecs
-            match metadata.name() {
-                #[cfg(feature = "transpose")]
-                array_to_array::transpose::IDENTIFIER => {
-                    return array_to_array::transpose::create_codec_transpose(metadata);
-                }
-                #[cfg(feature = "bitround")]
-                array_to_array::bitround::IDENTIFIER => {
-                    return array_to_array::bitround::create_codec_bitround(metadata);
-                }
-                array_to_bytes::bytes::IDENTIFIER => {
-                    return array_to_bytes::bytes::create_codec_bytes(metadata);
-                }
-                #[cfg(feature = "pcodec")]
-                array_to_bytes::pcodec::IDENTIFIER => {
-                    return array_to_bytes::pcodec::create_codec_pcodec(metadata);
-                }
-                #[cfg(feature = "sharding")]
-                array_to_bytes::sharding::IDENTIFIER => {
-                    return array_to_bytes::sharding::create_codec_sharding(metadata);
-                }
-                #[cfg(feature = "zfp")]
-                array_to_bytes::zfp::IDENTIFIER => {
-                    return array_to_bytes::zfp::create_codec_zfp(metadata);
-                }
-                array_to_bytes::vlen::IDENTIFIER => {
-                    return array_to_bytes::vlen::create_codec_vlen(metadata);
-                }
-                array_to_bytes::vlen_v2::IDENTIFIER => {
-                    return array_to_bytes::vlen_v2::create_codec_vlen_v2(metadata);
-                }
-                #[cfg(feature = "blosc")]
-                bytes_to_bytes::blosc::IDENTIFIER => {
-                    return bytes_to_bytes::blosc::create_codec_blosc(metadata);
-                }
-                #[cfg(feature = "bz2")]
-                bytes_to_bytes::bz2::IDENTIFIER => {
-                    return bytes_to_bytes::bz2::create_codec_bz2(metadata);
-                }
-                #[cfg(feature = "crc32c")]
-                bytes_to_bytes::crc32c::IDENTIFIER => {
-                    return bytes_to_bytes::crc32c::create_codec_crc32c(metadata);
-                }
-                #[cfg(feature = "gdeflate")]
-                bytes_to_bytes::gdeflate::IDENTIFIER => {
-                    return bytes_to_bytes::gdeflate::create_codec_gdeflate(metadata);
-                }
-                #[cfg(feature = "gzip")]
-                bytes_to_bytes::gzip::IDENTIFIER => {
-                    return bytes_to_bytes::gzip::create_codec_gzip(metadata);
-                }
-                #[cfg(feature = "zstd")]
-                bytes_to_bytes::zstd::IDENTIFIER => {
-                    return bytes_to_bytes::zstd::create_codec_zstd(metadata);
-                }
-                _ => {}
-            }
+        if let Some(codec) = Self::from_known_codec(metadata) {
+            return codec;
         }
         Err(PluginCreateError::Unsupported {
             name: metadata.name().to_string(),
             plugin_type: "codec".to_string(),
         })
     }
+
+    /// Create a codec from metadata with a codec plugin registered at runtime or compile time.
+    ///
+    /// Returns [`None`] if no registered codec plugin matches the name of the metadata.
+    fn from_registered_plugin(metadata: &MetadataV3) -> Option<Result<Self, PluginCreateError>> {
+        // The registry is not locked while creating the codec, since it may create nested codecs
+        if let Some(plugin) = CODECS_REGISTERED.find(metadata.name()) {
+            return Some(plugin.create(metadata));
+        }
+        inventory::iter::<CodecPlugin>
+            .into_iter()
+            .find(|plugin| plugin.match_name(metadata.name()))
+            .map(|plugin| plugin.create(metadata))
+    }
+
+    /// Create a codec from metadata by manually matching all known codecs, since inventory does not work in miri.
+    ///
+    /// Returns [`None`] if the name of the metadata does not match a known codec.
+    #[cfg(miri)]
+    fn from_known_codec(metadata: &MetadataV3) -> Option<Result<Self, PluginCreateError>> {
+        let codec = match metadata.name() {
+            #[cfg(feature = "transpose")]
+            array_to_array::transpose::IDENTIFIER => {
+                array_to_array::transpose::create_codec_transpose(metadata)
+            }
+            #[cfg(feature = "bitround")]
+            array_to_array::bitround::IDENTIFIER => {
+                array_to_array::bitround::create_codec_bitround(metadata)
+            }
+            #[cfg(feature = "delta")]
+            array_to_array::delta::IDENTIFIER => {
+                array_to_array::delta::create_codec_delta(metadata)
+            }
+            #[cfg(feature = "fixedscaleoffset")]
+            array_to_array::fixedscaleoffset::IDENTIFIER => {
+                array_to_array::fixedscaleoffset::create_codec_fixedscaleoffset(metadata)
+            }
+            array_to_bytes::bytes::IDENTIFIER => {
+                array_to_bytes::bytes::create_codec_bytes(metadata)
+            }
+            #[cfg(feature = "blosc2")]
+            array_to_bytes::blosc2::IDENTIFIER => {
+                array_to_bytes::blosc2::create_codec_blosc2(metadata)
+            }
+            #[cfg(feature = "jpegxl")]
+            array_to_bytes::jpegxl::IDENTIFIER => {
+                array_to_bytes::jpegxl::create_codec_jpegxl(metadata)
+            }
+            #[cfg(feature = "packbits")]
+            array_to_bytes::packbits::IDENTIFIER => {
+                array_to_bytes::packbits::create_codec_packbits(metadata)
+            }
+            #[cfg(feature = "pcodec")]
+            array_to_bytes::pcodec::IDENTIFIER => {
+                array_to_bytes::pcodec::create_codec_pcodec(metadata)
+            }
+            #[cfg(feature = "png")]
+            array_to_bytes::png::IDENTIFIER => array_to_bytes::png::create_codec_png(metadata),
+            #[cfg(feature = "rle")]
+            array_to_bytes::rle::IDENTIFIER => array_to_bytes::rle::create_codec_rle(metadata),
+            #[cfg(feature = "sharding")]
+            array_to_bytes::sharding::IDENTIFIER => {
+                array_to_bytes::sharding::create_codec_sharding(metadata)
+            }
+            #[cfg(feature = "sz3")]
+            array_to_bytes::sz3::IDENTIFIER => array_to_bytes::sz3::create_codec_sz3(metadata),
+            #[cfg(feature = "zfp")]
+            array_to_bytes::zfp::IDENTIFIER => array_to_bytes::zfp::create_codec_zfp(metadata),
+            array_to_bytes::vlen::IDENTIFIER => array_to_bytes::vlen::create_codec_vlen(metadata),
+            array_to_bytes::vlen_v2::IDENTIFIER => {
+                array_to_bytes::vlen_v2::create_codec_vlen_v2(metadata)
+            }
+            #[cfg(feature = "bitshuffle")]
+            bytes_to_bytes::bitshuffle::IDENTIFIER => {
+                bytes_to_bytes::bitshuffle::create_codec_bitshuffle(metadata)
+            }
+            #[cfg(feature = "blosc")]
+            bytes_to_bytes::blosc::IDENTIFIER => {
+                bytes_to_bytes::blosc::create_codec_blosc(metadata)
+            }
+            #[cfg(feature = "bz2")]
+            bytes_to_bytes::bz2::IDENTIFIER => bytes_to_bytes::bz2::create_codec_bz2(metadata),
+            #[cfg(feature = "crc32c")]
+            bytes_to_bytes::crc32c::IDENTIFIER => {
+                bytes_to_bytes::crc32c::create_codec_crc32c(metadata)
+            }
+            #[cfg(feature = "framed")]
+            bytes_to_bytes::framed::IDENTIFIER => {
+                bytes_to_bytes::framed::create_codec_framed(metadata)
+            }
+            #[cfg(feature = "gdeflate")]
+            bytes_to_bytes::gdeflate::IDENTIFIER => {
+                bytes_to_bytes::gdeflate::create_codec_gdeflate(metadata)
+            }
+            #[cfg(feature = "gzip")]
+            bytes_to_bytes::gzip::IDENTIFIER => bytes_to_bytes::gzip::create_codec_gzip(metadata),
+            #[cfg(feature = "shuffle")]
+            bytes_to_bytes::shuffle::IDENTIFIER => {
+                bytes_to_bytes::shuffle::create_codec_shuffle(metadata)
+            }
+            #[cfg(feature = "zstd")]
+            bytes_to_bytes::zstd::IDENTIFIER => bytes_to_bytes::zstd::create_codec_zstd(metadata),
+            _ => return None,
+        };
+        Some(codec)
+    }
 }
 
 /// Codec traits.
@@ -283,6 +420,13 @@ pub trait BytesPartialDecoderTraits: Send + Sync {
             .partial_decode(&[ByteRange::FromStart(0, None)], options)?
             .map(|mut v| v.remove(0)))
     }
+
+    /// Return the store key of the store value that is decoded, if the input is a store value.
+    ///
+    /// This is used to cache state associated with a store value, such as a shard index.
+    fn store_key(&self) -> Option<&StoreKey> {
+        None
+    }
 }
 
 #[cfg(feature = "async")]
@@ -330,6 +474,13 @@ pub trait AsyncBytesPartialDecoderTraits: Send + Sync {
             .await?
             .map(|mut v| v.remove(0)))
     }
+
+    /// Return the store key of the store value that is decoded, if the input is a store value.
+    ///
+    /// This is used to cache state associated with a store value, such as a shard index.
+    fn store_key(&self) -> Option<&StoreKey> {
+        None
+    }
 }
 
 /// Partial array decoder traits.
@@ -429,6 +580,62 @@ pub trait BytesPartialEncoderTraits: Send + Sync {
         offsets_and_bytes: &[(ByteOffset, crate::array::RawBytes<'_>)],
         options: &CodecOptions,
     ) -> Result<(), CodecError>;
+
+    /// Returns true if the partial encoder can encode byte ranges without decoding and encoding the entire value.
+    ///
+    /// This is used by array to bytes codecs to write byte ranges of a chunk directly to the partial encoder.
+    fn supports_partial_encode(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(feature = "async")]
+/// Asynchronous partial array encoder traits.
+#[async_trait::async_trait]
+pub trait AsyncArrayPartialEncoderTraits: Send + Sync {
+    /// Erase the chunk.
+    ///
+    /// # Errors
+    /// Returns an error if there is an underlying store error.
+    async fn erase(&self) -> Result<(), CodecError>;
+
+    /// Partially encode a chunk.
+    ///
+    /// # Errors
+    /// Returns [`CodecError`] if a codec fails or an array subset is invalid.
+    async fn partial_encode(
+        &self,
+        subsets_and_bytes: &[(&ArraySubset, ArrayBytes<'_>)],
+        options: &CodecOptions,
+    ) -> Result<(), CodecError>;
+}
+
+#[cfg(feature = "async")]
+/// Asynchronous partial bytes encoder traits.
+#[async_trait::async_trait]
+pub trait AsyncBytesPartialEncoderTraits: Send + Sync {
+    /// Erase the chunk.
+    ///
+    /// # Errors
+    /// Returns an error if there is an underlying store error.
+    async fn erase(&self) -> Result<(), CodecError>;
+
+    /// Partially encode a chunk.
+    ///
+    /// # Errors
+    /// Returns [`CodecError`] if a codec fails or an array subset is invalid.
+    async fn partial_encode(
+        &self,
+        offsets_and_bytes: &[(ByteOffset, crate::array::RawBytes<'_>)],
+        options: &CodecOptions,
+    ) -> Result<(), CodecError>;
+
+    /// Returns true if the partial encoder can encode byte ranges without decoding and encoding the entire value.
+    ///
+    /// See [`BytesPartialEncoderTraits::supports_partial_encode`].
+    fn supports_partial_encode(&self) -> bool {
+        false
+    }
 }
 
 #[cfg(feature = "async")]
@@ -496,8 +703,17 @@ impl BytesPartialDecoderTraits for StoragePartialDecoder {
     fn partial_decode(
         &self,
         decoded_regions: &[ByteRange],
-        _options: &CodecOptions,
+        options: &CodecOptions,
     ) -> Result<Option<Vec<RawBytes<'_>>>, CodecError> {
+        if let Some(gap) = options.byte_range_coalesce_gap() {
+            if decoded_regions.len() > 1 {
+                let coalesced = CoalescedByteRanges::new(decoded_regions, gap);
+                return Ok(self
+                    .storage
+                    .get_partial_values_key(&self.key, coalesced.byte_ranges())?
+                    .map(|vec_bytes| coalesced.split(&vec_bytes)));
+            }
+        }
         Ok(self
             .storage
             .get_partial_values_key(&self.key, decoded_regions)?
@@ -508,6 +724,10 @@ impl BytesPartialDecoderTraits for StoragePartialDecoder {
                     .collect()
             }))
     }
+
+    fn store_key(&self) -> Option<&StoreKey> {
+        Some(&self.key)
+    }
 }
 
 #[cfg(feature = "async")]
@@ -531,8 +751,18 @@ impl AsyncBytesPartialDecoderTraits for AsyncStoragePartialDecoder {
     async fn partial_decode(
         &self,
         decoded_regions: &[ByteRange],
-        _options: &CodecOptions,
+        options: &CodecOptions,
     ) -> Result<Option<Vec<RawBytes<'_>>>, CodecError> {
+        if let Some(gap) = options.byte_range_coalesce_gap() {
+            if decoded_regions.len() > 1 {
+                let coalesced = CoalescedByteRanges::new(decoded_regions, gap);
+                return Ok(self
+                    .storage
+                    .get_partial_values_key(&self.key, coalesced.byte_ranges())
+                    .await?
+                    .map(|vec_bytes| coalesced.split(&vec_bytes)));
+            }
+        }
         Ok(self
             .storage
             .get_partial_values_key(&self.key, decoded_regions)
@@ -544,6 +774,10 @@ impl AsyncBytesPartialDecoderTraits for AsyncStoragePartialDecoder {
                     .collect()
             }))
     }
+
+    fn store_key(&self) -> Option<&StoreKey> {
+        Some(&self.key)
+    }
 }
 
 /// A [`WritableStorage`] store value partial encoder.
@@ -575,6 +809,49 @@ impl BytesPartialEncoderTraits for StoragePartialEncoder {
             .collect::<Vec<_>>();
         Ok(self.storage.set_partial_values(&key_offset_values)?)
     }
+
+    fn supports_partial_encode(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(feature = "async")]
+/// An [`AsyncWritableStorage`] store value partial encoder.
+pub struct AsyncStoragePartialEncoder {
+    storage: AsyncWritableStorage,
+    key: StoreKey,
+}
+
+#[cfg(feature = "async")]
+impl AsyncStoragePartialEncoder {
+    /// Create a new storage partial encoder.
+    pub fn new(storage: AsyncWritableStorage, key: StoreKey) -> Self {
+        Self { storage, key }
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl AsyncBytesPartialEncoderTraits for AsyncStoragePartialEncoder {
+    async fn erase(&self) -> Result<(), CodecError> {
+        Ok(self.storage.erase(&self.key).await?)
+    }
+
+    async fn partial_encode(
+        &self,
+        offsets_and_bytes: &[(ByteOffset, crate::array::RawBytes<'_>)],
+        _options: &CodecOptions,
+    ) -> Result<(), CodecError> {
+        let key_offset_values = offsets_and_bytes
+            .iter()
+            .map(|(offset, bytes)| StoreKeyOffsetValue::new(self.key.clone(), *offset, bytes))
+            .collect::<Vec<_>>();
+        Ok(self.storage.set_partial_values(&key_offset_values).await?)
+    }
+
+    fn supports_partial_encode(&self) -> bool {
+        true
+    }
 }
 
 /// Traits for array to array codecs.
@@ -660,7 +937,27 @@ pub trait ArrayToArrayCodecTraits: ArrayCodecTraits + core::fmt::Debug {
         options: &CodecOptions,
     ) -> Result<Arc<dyn AsyncArrayPartialDecoderTraits>, CodecError>;
 
-    // TODO: async_partial_encoder
+    #[cfg(feature = "async")]
+    /// Initialise an asynchronous partial encoder.
+    ///
+    /// The default implementation returns an [`AsyncArrayToArrayPartialEncoderDefault`].
+    ///
+    /// # Errors
+    /// Returns a [`CodecError`] if initialisation fails.
+    async fn async_partial_encoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn AsyncArrayPartialDecoderTraits>,
+        output_handle: Arc<dyn AsyncArrayPartialEncoderTraits>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn AsyncArrayPartialEncoderTraits>, CodecError> {
+        Ok(Arc::new(AsyncArrayToArrayPartialEncoderDefault::new(
+            input_handle,
+            output_handle,
+            decoded_representation.clone(),
+            self.dynamic(),
+        )))
+    }
 }
 
 /// Traits for array to bytes codecs.
@@ -745,6 +1042,14 @@ pub trait ArrayToBytesCodecTraits: ArrayCodecTraits + core::fmt::Debug {
         Ok(())
     }
 
+    /// Returns true if the decoded bytes of a chunk with `decoded_representation` are its encoded bytes.
+    ///
+    /// If true, a chunk can be decoded without copying its encoded bytes.
+    /// The default implementation returns false.
+    fn is_passthrough(&self, _decoded_representation: &ChunkRepresentation) -> bool {
+        false
+    }
+
     /// Initialise a partial decoder.
     ///
     /// # Errors
@@ -780,7 +1085,27 @@ pub trait ArrayToBytesCodecTraits: ArrayCodecTraits + core::fmt::Debug {
         options: &CodecOptions,
     ) -> Result<Arc<dyn AsyncArrayPartialDecoderTraits>, CodecError>;
 
-    // TODO: Async partial encoder
+    #[cfg(feature = "async")]
+    /// Initialise an asynchronous partial encoder.
+    ///
+    /// The default implementation returns an [`AsyncArrayPartialEncoderDefault`].
+    ///
+    /// # Errors
+    /// Returns a [`CodecError`] if initialisation fails.
+    async fn async_partial_encoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+        output_handle: Arc<dyn AsyncBytesPartialEncoderTraits>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn AsyncArrayPartialEncoderTraits>, CodecError> {
+        Ok(Arc::new(AsyncArrayPartialEncoderDefault::new(
+            input_handle,
+            output_handle,
+            decoded_representation.clone(),
+            self.dynamic(),
+        )))
+    }
 }
 
 /// Traits for bytes to bytes codecs.
@@ -825,6 +1150,52 @@ pub trait BytesToBytesCodecTraits: CodecTraits + core::fmt::Debug {
         options: &CodecOptions,
     ) -> Result<RawBytes<'a>, CodecError>;
 
+    /// Indicates if [`decode_reader`](BytesToBytesCodecTraits::decode_reader) and [`encode_writer`](BytesToBytesCodecTraits::encode_writer) stream bytes incrementally.
+    ///
+    /// If false, these methods hold the entire value in memory.
+    fn supports_streaming(&self) -> bool {
+        false
+    }
+
+    /// Return a reader of the decoded bytes of an `encoded_value` reader.
+    ///
+    /// The default implementation reads and decodes the entire value with [`decode`](BytesToBytesCodecTraits::decode).
+    ///
+    /// # Errors
+    /// Returns [`CodecError`] if a codec fails.
+    fn decode_reader<'a>(
+        &self,
+        mut encoded_value: StoreValueReader<'a>,
+        decoded_representation: &BytesRepresentation,
+        options: &CodecOptions,
+    ) -> Result<StoreValueReader<'a>, CodecError> {
+        let mut encoded = Vec::new();
+        encoded_value.read_to_end(&mut encoded)?;
+        let decoded = self
+            .decode(Cow::Owned(encoded), decoded_representation, options)?
+            .into_owned();
+        Ok(Box::new(std::io::Cursor::new(decoded)))
+    }
+
+    /// Return a writer that encodes bytes to an `encoded_value` writer.
+    ///
+    /// Finishing the returned writer finishes `encoded_value`.
+    /// The default implementation buffers the entire value and encodes it with [`encode`](BytesToBytesCodecTraits::encode) when finished.
+    ///
+    /// # Errors
+    /// Returns [`CodecError`] if a codec fails.
+    fn encode_writer<'a>(
+        &'a self,
+        encoded_value: Box<dyn StoreValueWriter + 'a>,
+        options: &CodecOptions,
+    ) -> Result<Box<dyn StoreValueWriter + 'a>, CodecError> {
+        Ok(Box::new(BytesEncodeWriterDefault::new(
+            self,
+            encoded_value,
+            options,
+        )))
+    }
+
     /// Initialises a partial decoder.
     ///
     /// # Errors
@@ -860,7 +1231,27 @@ pub trait BytesToBytesCodecTraits: CodecTraits + core::fmt::Debug {
         options: &CodecOptions,
     ) -> Result<Arc<dyn AsyncBytesPartialDecoderTraits>, CodecError>;
 
-    // TODO: Async partial encoder
+    #[cfg(feature = "async")]
+    /// Initialise an asynchronous partial encoder.
+    ///
+    /// The default implementation returns an [`AsyncBytesPartialEncoderDefault`].
+    ///
+    /// # Errors
+    /// Returns a [`CodecError`] if initialisation fails.
+    async fn async_partial_encoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+        output_handle: Arc<dyn AsyncBytesPartialEncoderTraits>,
+        decoded_representation: &BytesRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn AsyncBytesPartialEncoderTraits>, CodecError> {
+        Ok(Arc::new(AsyncBytesPartialEncoderDefault::new(
+            input_handle,
+            output_handle,
+            *decoded_representation,
+            self.dynamic(),
+        )))
+    }
 }
 
 impl BytesPartialDecoderTraits for std::io::Cursor<&[u8]> {
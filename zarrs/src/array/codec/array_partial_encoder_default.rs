@@ -1,15 +1,84 @@
 use std::sync::Arc;
 
 use crate::{
-    array::{array_bytes::update_array_bytes, ArrayBytes, ArraySize, ChunkRepresentation},
+    array::{
+        array_bytes::update_array_bytes, ArrayBytes, ArraySize, ChunkRepresentation, RawBytes,
+    },
     array_subset::ArraySubset,
 };
 
 use super::{
     ArrayPartialEncoderTraits, ArrayToBytesCodecTraits, BytesPartialDecoderTraits,
-    BytesPartialEncoderTraits,
+    BytesPartialEncoderTraits, CodecError, CodecOptions,
 };
 
+#[cfg(feature = "async")]
+use super::{
+    AsyncArrayPartialEncoderTraits, AsyncBytesPartialDecoderTraits, AsyncBytesPartialEncoderTraits,
+};
+
+/// Decode `chunk_bytes`, update it with `subsets_and_bytes`, and encode it.
+///
+/// Returns [`None`] if the updated chunk is the fill value and empty chunks are not stored.
+fn update_chunk(
+    codec: &dyn ArrayToBytesCodecTraits,
+    decoded_representation: &ChunkRepresentation,
+    chunk_bytes: Option<RawBytes<'_>>,
+    subsets_and_bytes: &[(&ArraySubset, ArrayBytes<'_>)],
+    options: &CodecOptions,
+) -> Result<Option<RawBytes<'static>>, CodecError> {
+    let chunk_shape = decoded_representation.shape_u64();
+
+    // Handle a missing chunk
+    let mut chunk_bytes = if let Some(chunk_bytes) = chunk_bytes {
+        codec.decode(chunk_bytes, decoded_representation, options)?
+    } else {
+        let array_size = ArraySize::new(
+            decoded_representation.data_type().size(),
+            decoded_representation.num_elements(),
+        );
+        ArrayBytes::new_fill_value(array_size, decoded_representation.fill_value())
+    };
+
+    // Validate the bytes
+    chunk_bytes.validate(
+        decoded_representation.num_elements(),
+        decoded_representation.data_type().size(),
+    )?;
+
+    // Update the chunk
+    // TODO: More efficient update for multiple chunk subsets?
+    for (chunk_subset, chunk_subset_bytes) in subsets_and_bytes {
+        chunk_subset_bytes.validate(
+            chunk_subset.num_elements(),
+            decoded_representation.data_type().size(),
+        )?;
+
+        chunk_bytes = unsafe {
+            update_array_bytes(
+                chunk_bytes,
+                &chunk_shape,
+                chunk_subset,
+                chunk_subset_bytes,
+                decoded_representation.data_type().size(),
+            )
+        };
+    }
+
+    let is_fill_value = !options.store_empty_chunks()
+        && chunk_bytes.is_fill_value(decoded_representation.fill_value());
+    if is_fill_value {
+        Ok(None)
+    } else {
+        Ok(Some(
+            codec
+                .encode(chunk_bytes, decoded_representation, options)?
+                .into_owned()
+                .into(),
+        ))
+    }
+}
+
 /// The default array (chunk) partial encoder. Decodes the entire chunk, updates it, and writes the entire chunk.
 pub struct ArrayPartialEncoderDefault {
     input_handle: Arc<dyn BytesPartialDecoderTraits>,
@@ -47,57 +116,82 @@ impl ArrayPartialEncoderTraits for ArrayPartialEncoderDefault {
         options: &super::CodecOptions,
     ) -> Result<(), super::CodecError> {
         // Read the entire chunk
-        let chunk_shape = self.decoded_representation.shape_u64();
         let chunk_bytes = self.input_handle.decode(options)?;
+        let chunk_bytes = update_chunk(
+            &*self.codec,
+            &self.decoded_representation,
+            chunk_bytes,
+            subsets_and_bytes,
+            options,
+        )?;
 
-        // Handle a missing chunk
-        let mut chunk_bytes = if let Some(chunk_bytes) = chunk_bytes {
-            self.codec
-                .decode(chunk_bytes, &self.decoded_representation, options)?
+        if let Some(chunk_bytes) = chunk_bytes {
+            // Store the updated chunk
+            self.output_handle
+                .partial_encode(&[(0, chunk_bytes)], options)
         } else {
-            let array_size = ArraySize::new(
-                self.decoded_representation.data_type().size(),
-                self.decoded_representation.num_elements(),
-            );
-            ArrayBytes::new_fill_value(array_size, self.decoded_representation.fill_value())
-        };
+            self.output_handle.erase()
+        }
+    }
+}
 
-        // Validate the bytes
-        chunk_bytes.validate(
-            self.decoded_representation.num_elements(),
-            self.decoded_representation.data_type().size(),
-        )?;
+#[cfg(feature = "async")]
+/// The default asynchronous array (chunk) partial encoder. Decodes the entire chunk, updates it, and writes the entire chunk.
+pub struct AsyncArrayPartialEncoderDefault {
+    input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+    output_handle: Arc<dyn AsyncBytesPartialEncoderTraits>,
+    decoded_representation: ChunkRepresentation,
+    codec: Arc<dyn ArrayToBytesCodecTraits>,
+}
 
-        // Update the chunk
-        // TODO: More efficient update for multiple chunk subsets?
-        for (chunk_subset, chunk_subset_bytes) in subsets_and_bytes {
-            chunk_subset_bytes.validate(
-                chunk_subset.num_elements(),
-                self.decoded_representation.data_type().size(),
-            )?;
-
-            chunk_bytes = unsafe {
-                update_array_bytes(
-                    chunk_bytes,
-                    &chunk_shape,
-                    chunk_subset,
-                    chunk_subset_bytes,
-                    self.decoded_representation.data_type().size(),
-                )
-            };
+#[cfg(feature = "async")]
+impl AsyncArrayPartialEncoderDefault {
+    /// Create a new [`AsyncArrayPartialEncoderDefault`].
+    #[must_use]
+    pub fn new(
+        input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+        output_handle: Arc<dyn AsyncBytesPartialEncoderTraits>,
+        decoded_representation: ChunkRepresentation,
+        codec: Arc<dyn ArrayToBytesCodecTraits>,
+    ) -> Self {
+        Self {
+            input_handle,
+            output_handle,
+            decoded_representation,
+            codec,
         }
+    }
+}
 
-        let is_fill_value = !options.store_empty_chunks()
-            && chunk_bytes.is_fill_value(self.decoded_representation.fill_value());
-        if is_fill_value {
-            self.output_handle.erase()
-        } else {
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl AsyncArrayPartialEncoderTraits for AsyncArrayPartialEncoderDefault {
+    async fn erase(&self) -> Result<(), CodecError> {
+        self.output_handle.erase().await
+    }
+
+    async fn partial_encode(
+        &self,
+        subsets_and_bytes: &[(&ArraySubset, ArrayBytes<'_>)],
+        options: &CodecOptions,
+    ) -> Result<(), CodecError> {
+        // Read the entire chunk
+        let chunk_bytes = self.input_handle.decode(options).await?;
+        let chunk_bytes = update_chunk(
+            &*self.codec,
+            &self.decoded_representation,
+            chunk_bytes,
+            subsets_and_bytes,
+            options,
+        )?;
+
+        if let Some(chunk_bytes) = chunk_bytes {
             // Store the updated chunk
-            let chunk_bytes =
-                self.codec
-                    .encode(chunk_bytes, &self.decoded_representation, options)?;
             self.output_handle
                 .partial_encode(&[(0, chunk_bytes)], options)
+                .await
+        } else {
+            self.output_handle.erase().await
         }
     }
 }
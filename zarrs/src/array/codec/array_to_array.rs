@@ -2,5 +2,9 @@
 
 #[cfg(feature = "bitround")]
 pub mod bitround;
+#[cfg(feature = "delta")]
+pub mod delta;
+#[cfg(feature = "fixedscaleoffset")]
+pub mod fixedscaleoffset;
 #[cfg(feature = "transpose")]
 pub mod transpose;
@@ -2,6 +2,10 @@
 //!
 //! Rounds the mantissa of floating point data types to the specified number of bits.
 //! Rounds integers to the specified number of bits from the most significant set bit.
+//! Signed integers are rounded in magnitude, preserving their sign.
+//!
+//! The keepbits can differ for each index along an axis (e.g. for each channel of a multichannel array), see [`BitroundCodec::new_per_index`].
+//! Keepbits stored in an array attribute can be used with [`BitroundCodec::new_from_attribute`].
 //!
 //! <div class="warning">
 //! This codec is experimental and may be incompatible with other Zarr V3 implementations.
@@ -14,10 +18,10 @@
 mod bitround_codec;
 mod bitround_partial_decoder;
 
-use std::sync::Arc;
+use std::{mem::size_of, sync::Arc};
 
 pub use crate::metadata::v3::array::codec::bitround::{
-    BitroundCodecConfiguration, BitroundCodecConfigurationV1,
+    BitroundCodecConfiguration, BitroundCodecConfigurationV1, BitroundKeepbits,
 };
 pub use bitround_codec::BitroundCodec;
 
@@ -51,7 +55,7 @@ pub(crate) fn create_codec_bitround(metadata: &MetadataV3) -> Result<Codec, Plug
     let configuration: BitroundCodecConfiguration = metadata
         .to_configuration()
         .map_err(|_| PluginMetadataInvalidError::new(IDENTIFIER, "codec", metadata.clone()))?;
-    let codec = Arc::new(BitroundCodec::new_with_configuration(&configuration));
+    let codec = Arc::new(BitroundCodec::new_with_configuration(&configuration)?);
     Ok(Codec::ArrayToArray(codec))
 }
 
@@ -99,86 +103,108 @@ const fn round_bits64(mut input: u64, keepbits: u32, maxbits: u32) -> u64 {
     input
 }
 
+/// Round the magnitude of a signed integer with `bits` bits to `keepbits` significant bits, preserving its sign.
+///
+/// The magnitude is rounded towards zero instead if rounding to nearest overflows `bits` bits.
+fn round_bits_signed(input: i64, keepbits: u32, bits: u32) -> i64 {
+    let magnitude = input.unsigned_abs();
+    let maxbits = 64 - magnitude.leading_zeros();
+    let mut rounded = round_bits64(magnitude, keepbits, maxbits);
+    let limit = if input < 0 {
+        1 << (bits - 1)
+    } else {
+        (1 << (bits - 1)) - 1
+    };
+    if rounded > limit {
+        // Only reachable if keepbits < maxbits
+        let maskbits = maxbits - keepbits;
+        rounded = (magnitude >> maskbits) << maskbits;
+    }
+    if input < 0 {
+        0i64.wrapping_sub_unsigned(rounded)
+    } else {
+        0i64.wrapping_add_unsigned(rounded)
+    }
+}
+
+/// Apply `round` to each element of type `T` in `bytes`.
+fn round_elements<T: bytemuck::Pod>(bytes: &mut [u8], round: impl Fn(T) -> T) {
+    for chunk in bytes.chunks_exact_mut(size_of::<T>()) {
+        let element = round(bytemuck::pod_read_unaligned(chunk));
+        chunk.copy_from_slice(bytemuck::bytes_of(&element));
+    }
+}
+
+/// Round the magnitude of each signed integer element of type `T` in `bytes` to `keepbits` significant bits.
+fn round_signed<T>(bytes: &mut [u8], keepbits: u32)
+where
+    T: bytemuck::Pod + Into<i64> + TryFrom<i64>,
+    <T as TryFrom<i64>>::Error: std::fmt::Debug,
+{
+    let bits = u32::try_from(8 * size_of::<T>()).unwrap();
+    round_elements(bytes, |element: T| {
+        T::try_from(round_bits_signed(element.into(), keepbits, bits)).unwrap()
+    });
+}
+
 fn round_bytes(bytes: &mut [u8], data_type: &DataType, keepbits: u32) -> Result<(), CodecError> {
     match data_type {
-        DataType::UInt8 | DataType::Int8 => {
-            let round = |element: &mut u8| {
-                *element = round_bits8(*element, keepbits, 8 - element.leading_zeros());
-            };
-            bytes.iter_mut().for_each(round);
-            Ok(())
-        }
+        DataType::UInt8 => round_elements(bytes, |element: u8| {
+            round_bits8(element, keepbits, 8 - element.leading_zeros())
+        }),
+        DataType::UInt16 => round_elements(bytes, |element: u16| {
+            round_bits16(element, keepbits, 16 - element.leading_zeros())
+        }),
+        DataType::UInt32 => round_elements(bytes, |element: u32| {
+            round_bits32(element, keepbits, 32 - element.leading_zeros())
+        }),
+        DataType::UInt64 => round_elements(bytes, |element: u64| {
+            round_bits64(element, keepbits, 64 - element.leading_zeros())
+        }),
+        DataType::Int8 => round_signed::<i8>(bytes, keepbits),
+        DataType::Int16 => round_signed::<i16>(bytes, keepbits),
+        DataType::Int32 => round_signed::<i32>(bytes, keepbits),
+        DataType::Int64 => round_signed::<i64>(bytes, keepbits),
+        // Floating point elements are rounded in the mantissa bits of their bit representation
         DataType::Float16 | DataType::BFloat16 => {
-            let round = |chunk: &mut [u8]| {
-                let element = u16::from_ne_bytes(chunk.try_into().unwrap());
-                let element = u16::to_ne_bytes(round_bits16(element, keepbits, 10));
-                chunk.copy_from_slice(&element);
-            };
-            bytes.chunks_exact_mut(2).for_each(round);
-            Ok(())
-        }
-        DataType::UInt16 | DataType::Int16 => {
-            let round = |chunk: &mut [u8]| {
-                let element = u16::from_ne_bytes(chunk.try_into().unwrap());
-                let element = u16::to_ne_bytes(round_bits16(
-                    element,
-                    keepbits,
-                    16 - element.leading_zeros(),
-                ));
-                chunk.copy_from_slice(&element);
-            };
-            bytes.chunks_exact_mut(2).for_each(round);
-            Ok(())
+            round_elements(bytes, |element: u16| round_bits16(element, keepbits, 10));
         }
         DataType::Float32 | DataType::Complex64 => {
-            let round = |chunk: &mut [u8]| {
-                let element = u32::from_ne_bytes(chunk.try_into().unwrap());
-                let element = u32::to_ne_bytes(round_bits32(element, keepbits, 23));
-                chunk.copy_from_slice(&element);
-            };
-            bytes.chunks_exact_mut(4).for_each(round);
-            Ok(())
-        }
-        DataType::UInt32 | DataType::Int32 => {
-            let round = |chunk: &mut [u8]| {
-                let element = u32::from_ne_bytes(chunk.try_into().unwrap());
-                let element = u32::to_ne_bytes(round_bits32(
-                    element,
-                    keepbits,
-                    32 - element.leading_zeros(),
-                ));
-                chunk.copy_from_slice(&element);
-            };
-            bytes.chunks_exact_mut(4).for_each(round);
-            Ok(())
+            round_elements(bytes, |element: u32| round_bits32(element, keepbits, 23));
         }
         DataType::Float64 | DataType::Complex128 => {
-            let round = |chunk: &mut [u8]| {
-                let element = u64::from_ne_bytes(chunk.try_into().unwrap());
-                let element = u64::to_ne_bytes(round_bits64(element, keepbits, 52));
-                chunk.copy_from_slice(&element);
-            };
-            bytes.chunks_exact_mut(8).for_each(round);
-            Ok(())
+            round_elements(bytes, |element: u64| round_bits64(element, keepbits, 52));
         }
-        DataType::UInt64 | DataType::Int64 => {
-            let round = |chunk: &mut [u8]| {
-                let element = u64::from_ne_bytes(chunk.try_into().unwrap());
-                let element = u64::to_ne_bytes(round_bits64(
-                    element,
-                    keepbits,
-                    64 - element.leading_zeros(),
-                ));
-                chunk.copy_from_slice(&element);
-            };
-            bytes.chunks_exact_mut(8).for_each(round);
-            Ok(())
+        _ => {
+            return Err(CodecError::UnsupportedDataType(
+                data_type.clone(),
+                IDENTIFIER.to_string(),
+            ))
         }
-        _ => Err(CodecError::UnsupportedDataType(
-            data_type.clone(),
-            IDENTIFIER.to_string(),
-        )),
     }
+    Ok(())
+}
+
+/// Round `bytes` with a `shape` with the keepbits for each index along `axis`.
+fn round_bytes_per_index(
+    bytes: &mut [u8],
+    data_type: &DataType,
+    shape: &[u64],
+    axis: usize,
+    keepbits: &[u32],
+) -> Result<(), CodecError> {
+    let element_size = data_type.fixed_size().ok_or_else(|| {
+        CodecError::UnsupportedDataType(data_type.clone(), IDENTIFIER.to_string())
+    })?;
+    let inner_elements: u64 = shape[axis + 1..].iter().product();
+    let run_size = usize::try_from(inner_elements).unwrap() * element_size;
+    if run_size == 0 {
+        return Ok(());
+    }
+    for (run_index, run) in bytes.chunks_exact_mut(run_size).enumerate() {
+        round_bytes(run, data_type, keepbits[run_index % keepbits.len()])?;
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -191,14 +217,21 @@ mod tests {
     use crate::{
         array::{
             array_representation,
-            codec::{ArrayToArrayCodecTraits, ArrayToBytesCodecTraits, BytesCodec, CodecOptions},
-            ArrayBytes,
+            codec::{
+                ArrayToArrayCodecTraits, ArrayToBytesCodecTraits, BytesCodec, CodecOptions,
+                CodecTraits,
+            },
+            ArrayBytes, ArrayMetadataOptions,
         },
         array_subset::ArraySubset,
     };
 
     use super::*;
 
+    fn metadata_options() -> ArrayMetadataOptions {
+        ArrayMetadataOptions::default().with_experimental_codec_store_metadata_if_encode_only(true)
+    }
+
     #[test]
     fn codec_bitround_float() {
         // 1 sign bit, 8 exponent, 3 mantissa
@@ -226,7 +259,7 @@ mod tests {
         let bytes = ArrayBytes::from(bytes);
 
         let codec_configuration: BitroundCodecConfiguration = serde_json::from_str(JSON).unwrap();
-        let codec = BitroundCodec::new_with_configuration(&codec_configuration);
+        let codec = BitroundCodec::new_with_configuration(&codec_configuration).unwrap();
 
         let encoded = codec
             .encode(
@@ -258,7 +291,7 @@ mod tests {
         let bytes = ArrayBytes::from(bytes);
 
         let codec_configuration: BitroundCodecConfiguration = serde_json::from_str(JSON).unwrap();
-        let codec = BitroundCodec::new_with_configuration(&codec_configuration);
+        let codec = BitroundCodec::new_with_configuration(&codec_configuration).unwrap();
 
         let encoded = codec
             .encode(
@@ -296,7 +329,7 @@ mod tests {
         let bytes = ArrayBytes::from(bytes);
 
         let codec_configuration: BitroundCodecConfiguration = serde_json::from_str(JSON).unwrap();
-        let codec = BitroundCodec::new_with_configuration(&codec_configuration);
+        let codec = BitroundCodec::new_with_configuration(&codec_configuration).unwrap();
 
         let encoded = codec
             .encode(
@@ -317,11 +350,143 @@ mod tests {
         assert_eq!(decoded_elements, &[0, 3, 7, 16, 16, 56, 96, 128, 224]);
     }
 
+    #[test]
+    fn codec_bitround_int8() {
+        const JSON: &str = r#"{ "keepbits": 3 }"#;
+        let elements: Vec<i8> = vec![-128, -100, -17, -1, 0, 1, 17, 100, 127];
+        let chunk_representation = ChunkRepresentation::new(
+            vec![(elements.len() as u64).try_into().unwrap()],
+            DataType::Int8,
+            0i8.into(),
+        )
+        .unwrap();
+        let bytes = crate::array::transmute_to_bytes_vec(elements);
+        let bytes = ArrayBytes::from(bytes);
+
+        let codec_configuration: BitroundCodecConfiguration = serde_json::from_str(JSON).unwrap();
+        let codec = BitroundCodec::new_with_configuration(&codec_configuration).unwrap();
+
+        let encoded = codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let decoded = codec
+            .decode(encoded, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let decoded_elements = crate::array::transmute_from_bytes_vec::<i8>(
+            decoded.into_fixed().unwrap().into_owned(),
+        );
+        // 127 rounds to 128, which overflows, so it is rounded towards zero instead
+        assert_eq!(decoded_elements, &[-128, -96, -16, -1, 0, 1, 16, 96, 112]);
+    }
+
+    #[test]
+    fn codec_bitround_int64() {
+        const JSON: &str = r#"{ "keepbits": 3 }"#;
+        let elements: Vec<i64> = vec![i64::MIN, -1685, -1024, 0, 1685, i64::MAX];
+        let chunk_representation = ChunkRepresentation::new(
+            vec![(elements.len() as u64).try_into().unwrap()],
+            DataType::Int64,
+            0i64.into(),
+        )
+        .unwrap();
+        let bytes = crate::array::transmute_to_bytes_vec(elements);
+        let bytes = ArrayBytes::from(bytes);
+
+        let codec_configuration: BitroundCodecConfiguration = serde_json::from_str(JSON).unwrap();
+        let codec = BitroundCodec::new_with_configuration(&codec_configuration).unwrap();
+
+        let encoded = codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let decoded = codec
+            .decode(encoded, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let decoded_elements = crate::array::transmute_from_bytes_vec::<i64>(
+            decoded.into_fixed().unwrap().into_owned(),
+        );
+        assert_eq!(
+            decoded_elements,
+            &[i64::MIN, -1792, -1024, 0, 1792, 7 << 60]
+        );
+    }
+
+    #[test]
+    fn codec_bitround_per_index() {
+        const JSON: &str = r#"{ "keepbits": [7, 3], "axis": 1 }"#;
+        let chunk_representation = ChunkRepresentation::new(
+            vec![NonZeroU64::new(2).unwrap(), NonZeroU64::new(2).unwrap()],
+            DataType::UInt8,
+            0u8.into(),
+        )
+        .unwrap();
+        let elements: Vec<u8> = vec![89, 89, 255, 255];
+        let bytes = ArrayBytes::from(elements);
+
+        let codec_configuration: BitroundCodecConfiguration = serde_json::from_str(JSON).unwrap();
+        let codec = BitroundCodec::new_with_configuration(&codec_configuration).unwrap();
+        assert_eq!(
+            codec
+                .create_metadata_opt(&metadata_options())
+                .unwrap()
+                .to_configuration::<BitroundCodecConfiguration>()
+                .unwrap(),
+            codec_configuration
+        );
+
+        let encoded = codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let decoded = codec
+            .decode(encoded, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        assert_eq!(
+            decoded.into_fixed().unwrap().into_owned(),
+            &[89, 96, 254, 224]
+        );
+
+        // The chunk shape along the axis must match the number of keepbits
+        let chunk_representation = ChunkRepresentation::new(
+            vec![NonZeroU64::new(4).unwrap(), NonZeroU64::new(1).unwrap()],
+            DataType::UInt8,
+            0u8.into(),
+        )
+        .unwrap();
+        assert!(codec.compute_encoded_size(&chunk_representation).is_err());
+
+        // An axis is required
+        let codec_configuration: BitroundCodecConfiguration =
+            serde_json::from_str(r#"{ "keepbits": [7, 3] }"#).unwrap();
+        assert!(BitroundCodec::new_with_configuration(&codec_configuration).is_err());
+    }
+
+    #[test]
+    fn codec_bitround_from_attribute() {
+        let attributes: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(r#"{ "keepbits": [7, 3], "uniform": 3, "invalid": "3" }"#)
+                .unwrap();
+        let configuration = |codec: BitroundCodec| {
+            codec
+                .create_metadata_opt(&metadata_options())
+                .unwrap()
+                .to_configuration::<BitroundCodecConfigurationV1>()
+                .unwrap()
+        };
+        let codec = BitroundCodec::new_from_attribute(&attributes, "keepbits", 1).unwrap();
+        assert_eq!(
+            configuration(codec),
+            BitroundCodecConfigurationV1::new_per_index(1, vec![7, 3])
+        );
+        let codec = BitroundCodec::new_from_attribute(&attributes, "uniform", 1).unwrap();
+        assert_eq!(configuration(codec), BitroundCodecConfigurationV1::new(3));
+        assert!(BitroundCodec::new_from_attribute(&attributes, "invalid", 1).is_err());
+        assert!(BitroundCodec::new_from_attribute(&attributes, "missing", 1).is_err());
+    }
+
     #[test]
     fn codec_bitround_partial_decode() {
         const JSON: &'static str = r#"{ "keepbits": 2 }"#;
         let codec_configuration: BitroundCodecConfiguration = serde_json::from_str(JSON).unwrap();
-        let codec = Arc::new(BitroundCodec::new_with_configuration(&codec_configuration));
+        let codec = Arc::new(BitroundCodec::new_with_configuration(&codec_configuration).unwrap());
 
         let elements: Vec<f32> = (0..32).map(|i| i as f32).collect();
         let chunk_representation = ChunkRepresentation::new(
@@ -380,7 +545,7 @@ mod tests {
     async fn codec_bitround_async_partial_decode() {
         const JSON: &'static str = r#"{ "keepbits": 2 }"#;
         let codec_configuration: BitroundCodecConfiguration = serde_json::from_str(JSON).unwrap();
-        let codec = Arc::new(BitroundCodec::new_with_configuration(&codec_configuration));
+        let codec = Arc::new(BitroundCodec::new_with_configuration(&codec_configuration).unwrap());
 
         let elements: Vec<f32> = (0..32).map(|i| i as f32).collect();
         let chunk_representation = ChunkRepresentation::new(
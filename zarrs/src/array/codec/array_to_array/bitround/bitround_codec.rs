@@ -11,46 +11,116 @@ use crate::{
     },
     config::global_config,
     metadata::v3::MetadataV3,
+    plugin::PluginCreateError,
 };
 
 #[cfg(feature = "async")]
 use crate::array::codec::AsyncArrayPartialDecoderTraits;
 
 use super::{
-    bitround_partial_decoder, round_bytes, BitroundCodecConfiguration,
-    BitroundCodecConfigurationV1, IDENTIFIER,
+    bitround_partial_decoder, round_bytes, round_bytes_per_index, BitroundCodecConfiguration,
+    BitroundCodecConfigurationV1, BitroundKeepbits, IDENTIFIER,
 };
 
 /// A `bitround` codec implementation.
 #[derive(Clone, Debug, Default)]
 pub struct BitroundCodec {
     keepbits: u32,
+    keepbits_per_index: Option<(usize, Vec<u32>)>,
 }
 
 impl BitroundCodec {
     /// Create a new `bitround` codec.
     ///
-    /// `keepbits` is the number of bits to round to in the floating point mantissa.
+    /// `keepbits` is the number of bits to round to in the floating point mantissa, or the number of significant bits to round to for an integer.
     #[must_use]
     pub const fn new(keepbits: u32) -> Self {
-        Self { keepbits }
+        Self {
+            keepbits,
+            keepbits_per_index: None,
+        }
     }
 
-    /// Create a new `bitround` codec from a configuration.
+    /// Create a new `bitround` codec with `keepbits` for each index along `axis` (e.g. for each channel of a multichannel array).
+    ///
+    /// The codec is applied to each chunk independently, so the chunk shape along `axis` must equal the length of `keepbits`.
+    /// In other words, `axis` must not be chunked.
     #[must_use]
-    pub const fn new_with_configuration(configuration: &BitroundCodecConfiguration) -> Self {
-        let BitroundCodecConfiguration::V1(configuration) = configuration;
+    pub const fn new_per_index(axis: usize, keepbits: Vec<u32>) -> Self {
         Self {
-            keepbits: configuration.keepbits,
+            keepbits: 0,
+            keepbits_per_index: Some((axis, keepbits)),
+        }
+    }
+
+    /// Create a new `bitround` codec with keepbits read from the `attribute` of array `attributes`.
+    ///
+    /// The attribute must be an integer, or an array of integers with the keepbits for each index along `axis` (see [`BitroundCodec::new_per_index`]).
+    /// This is useful for keepbits computed by bitinformation analysis (e.g. `xbitinfo`) and stored with the array.
+    ///
+    /// # Errors
+    /// Returns a [`PluginCreateError`] if the attribute is missing or is not an integer or an array of integers.
+    pub fn new_from_attribute(
+        attributes: &serde_json::Map<String, serde_json::Value>,
+        attribute: &str,
+        axis: usize,
+    ) -> Result<Self, PluginCreateError> {
+        let value = attributes.get(attribute).ok_or_else(|| {
+            PluginCreateError::Other(format!("the keepbits attribute {attribute} is missing"))
+        })?;
+        let keepbits: BitroundKeepbits =
+            serde_json::from_value(value.clone()).map_err(|_| {
+                PluginCreateError::Other(format!(
+                    "the keepbits attribute {attribute} is not an integer or an array of integers: {value}"
+                ))
+            })?;
+        Ok(match keepbits {
+            BitroundKeepbits::Uniform(keepbits) => Self::new(keepbits),
+            BitroundKeepbits::PerIndex(keepbits) => Self::new_per_index(axis, keepbits),
+        })
+    }
+
+    /// Create a new `bitround` codec from a configuration.
+    ///
+    /// # Errors
+    /// Returns a [`PluginCreateError`] if the configuration has keepbits for each index without an `axis`.
+    pub fn new_with_configuration(
+        configuration: &BitroundCodecConfiguration,
+    ) -> Result<Self, PluginCreateError> {
+        let BitroundCodecConfiguration::V1(configuration) = configuration;
+        match (&configuration.keepbits, configuration.axis) {
+            (BitroundKeepbits::Uniform(keepbits), _) => Ok(Self::new(*keepbits)),
+            (BitroundKeepbits::PerIndex(keepbits), Some(axis)) => {
+                Ok(Self::new_per_index(axis, keepbits.clone()))
+            }
+            (BitroundKeepbits::PerIndex(_), None) => Err(PluginCreateError::Other(
+                "bitround codec keepbits for each index require an axis".to_string(),
+            )),
         }
     }
+
+    /// Validate the keepbits for each index against the chunk `shape`.
+    fn validate_per_index(&self, shape: &[u64]) -> Result<(), CodecError> {
+        if let Some((axis, keepbits)) = &self.keepbits_per_index {
+            if shape.get(*axis) != Some(&u64::try_from(keepbits.len()).unwrap()) {
+                return Err(CodecError::Other(format!(
+                    "bitround codec has {} keepbits along axis {axis}, which is incompatible with chunk shape {shape:?}",
+                    keepbits.len()
+                )));
+            }
+        }
+        Ok(())
+    }
 }
 
 impl CodecTraits for BitroundCodec {
     fn create_metadata_opt(&self, options: &ArrayMetadataOptions) -> Option<MetadataV3> {
         if options.experimental_codec_store_metadata_if_encode_only() {
-            let configuration = BitroundCodecConfigurationV1 {
-                keepbits: self.keepbits,
+            let configuration = match &self.keepbits_per_index {
+                Some((axis, keepbits)) => {
+                    BitroundCodecConfigurationV1::new_per_index(*axis, keepbits.clone())
+                }
+                None => BitroundCodecConfigurationV1::new(self.keepbits),
             };
             Some(
                 MetadataV3::new_with_serializable_configuration(
@@ -99,11 +169,23 @@ impl ArrayToArrayCodecTraits for BitroundCodec {
         _options: &CodecOptions,
     ) -> Result<ArrayBytes<'a>, CodecError> {
         let mut bytes = bytes.into_fixed()?;
-        round_bytes(
-            bytes.to_mut(),
-            decoded_representation.data_type(),
-            self.keepbits,
-        )?;
+        if let Some((axis, keepbits)) = &self.keepbits_per_index {
+            let shape = decoded_representation.shape_u64();
+            self.validate_per_index(&shape)?;
+            round_bytes_per_index(
+                bytes.to_mut(),
+                decoded_representation.data_type(),
+                &shape,
+                *axis,
+                keepbits,
+            )?;
+        } else {
+            round_bytes(
+                bytes.to_mut(),
+                decoded_representation.data_type(),
+                self.keepbits,
+            )?;
+        }
         Ok(ArrayBytes::from(bytes))
     }
 
@@ -122,6 +204,10 @@ impl ArrayToArrayCodecTraits for BitroundCodec {
         decoded_representation: &ChunkRepresentation,
         _options: &CodecOptions,
     ) -> Result<Arc<dyn ArrayPartialDecoderTraits>, CodecError> {
+        if self.keepbits_per_index.is_some() {
+            // Decoding is a no-op, the bytes were rounded on encoding
+            return Ok(input_handle);
+        }
         Ok(Arc::new(
             bitround_partial_decoder::BitroundPartialDecoder::new(
                 input_handle,
@@ -153,6 +239,10 @@ impl ArrayToArrayCodecTraits for BitroundCodec {
         decoded_representation: &ChunkRepresentation,
         _options: &CodecOptions,
     ) -> Result<Arc<dyn AsyncArrayPartialDecoderTraits>, CodecError> {
+        if self.keepbits_per_index.is_some() {
+            // Decoding is a no-op, the bytes were rounded on encoding
+            return Ok(input_handle);
+        }
         Ok(Arc::new(
             bitround_partial_decoder::AsyncBitroundPartialDecoder::new(
                 input_handle,
@@ -181,7 +271,10 @@ impl ArrayToArrayCodecTraits for BitroundCodec {
             | DataType::UInt64
             | DataType::Int64
             | DataType::Complex64
-            | DataType::Complex128 => Ok(decoded_representation.clone()),
+            | DataType::Complex128 => {
+                self.validate_per_index(&decoded_representation.shape_u64())?;
+                Ok(decoded_representation.clone())
+            }
             _ => Err(CodecError::UnsupportedDataType(
                 data_type.clone(),
                 IDENTIFIER.to_string(),
@@ -28,7 +28,9 @@ impl BitroundPartialDecoder {
         keepbits: u32,
     ) -> Result<Self, CodecError> {
         match data_type {
-            DataType::Float16
+            DataType::UInt8
+            | DataType::Int8
+            | DataType::Float16
             | DataType::BFloat16
             | DataType::UInt16
             | DataType::Int16
@@ -92,7 +94,9 @@ impl AsyncBitroundPartialDecoder {
         keepbits: u32,
     ) -> Result<Self, CodecError> {
         match data_type {
-            DataType::Float16
+            DataType::UInt8
+            | DataType::Int8
+            | DataType::Float16
             | DataType::BFloat16
             | DataType::UInt16
             | DataType::Int16
@@ -0,0 +1,503 @@
+//! The `delta` array to array codec.
+//!
+//! Encodes the difference between each element and its predecessor in the lexicographical order of the chunk.
+//! The first element is encoded as is.
+//! Deltas of integers wrap on overflow, so encoding integers is lossless.
+//! Encoding deltas with a narrower data type (`astype`) is lossless only if every delta is representable with that data type.
+//!
+//! This codec is compatible with the `numcodecs` `delta` filter of Zarr V2 arrays.
+//! It typically improves the compression of monotonic or slowly varying data.
+//!
+//! Decoding a subset of a chunk requires all elements that precede the subset in the chunk.
+//!
+//! <div class="warning">
+//! This codec is experimental and may be incompatible with other Zarr V3 implementations.
+//! </div>
+//!
+//! This codec requires the `delta` feature, which is disabled by default.
+//!
+//! See [`DeltaCodecConfigurationV1`] for example `JSON` metadata.
+
+mod delta_codec;
+mod delta_partial_decoder;
+
+use std::sync::Arc;
+
+use num::traits::AsPrimitive;
+
+pub use crate::metadata::v3::array::codec::delta::{
+    DeltaCodecConfiguration, DeltaCodecConfigurationV1,
+};
+pub use delta_codec::DeltaCodec;
+
+use crate::{
+    array::{
+        codec::{Codec, CodecError, CodecPlugin},
+        convert_from_bytes_slice, transmute_to_bytes_vec, DataType,
+    },
+    config::global_config,
+    metadata::v3::{array::codec::delta, MetadataV3},
+    plugin::{PluginCreateError, PluginMetadataInvalidError},
+};
+
+pub use delta::IDENTIFIER;
+
+// Register the codec.
+inventory::submit! {
+    CodecPlugin::new(IDENTIFIER, is_name_delta, create_codec_delta)
+}
+
+fn is_name_delta(name: &str) -> bool {
+    name.eq(IDENTIFIER)
+        || name
+            == global_config()
+                .experimental_codec_names()
+                .get(IDENTIFIER)
+                .expect("experimental codec identifier in global map")
+}
+
+pub(crate) fn create_codec_delta(metadata: &MetadataV3) -> Result<Codec, PluginCreateError> {
+    let configuration: DeltaCodecConfiguration = metadata
+        .to_configuration()
+        .map_err(|_| PluginMetadataInvalidError::new(IDENTIFIER, "codec", metadata.clone()))?;
+    let codec = Arc::new(
+        DeltaCodec::new_with_configuration(&configuration)
+            .map_err(|_| PluginMetadataInvalidError::new(IDENTIFIER, "codec", metadata.clone()))?,
+    );
+    Ok(Codec::ArrayToArray(codec))
+}
+
+trait DeltaElement: bytemuck::Pod {
+    fn delta(self, previous: Self) -> Self;
+
+    fn undelta(self, previous: Self) -> Self;
+}
+
+macro_rules! impl_delta_element_int {
+    ($($t:ty),*) => {
+        $(
+            impl DeltaElement for $t {
+                fn delta(self, previous: Self) -> Self {
+                    self.wrapping_sub(previous)
+                }
+
+                fn undelta(self, previous: Self) -> Self {
+                    self.wrapping_add(previous)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_delta_element_float {
+    ($($t:ty),*) => {
+        $(
+            impl DeltaElement for $t {
+                fn delta(self, previous: Self) -> Self {
+                    self - previous
+                }
+
+                fn undelta(self, previous: Self) -> Self {
+                    self + previous
+                }
+            }
+        )*
+    };
+}
+
+impl_delta_element_int!(i8, i16, i32, i64, u8, u16, u32, u64);
+impl_delta_element_float!(half::f16, half::bf16, f32, f64);
+
+fn delta_elements<T: DeltaElement>(elements: &mut [T]) {
+    for i in (1..elements.len()).rev() {
+        elements[i] = elements[i].delta(elements[i - 1]);
+    }
+}
+
+fn undelta_elements<T: DeltaElement>(elements: &mut [T]) {
+    for i in 1..elements.len() {
+        elements[i] = elements[i].undelta(elements[i - 1]);
+    }
+}
+
+/// Evaluate `$body` with `$t` as the element type of a data type convertible with [`AsPrimitive`], otherwise evaluate `$otherwise`.
+macro_rules! with_primitive_type {
+    ($data_type:expr, $t:ident => $body:expr, $otherwise:expr) => {
+        match $data_type {
+            DataType::Int8 => {
+                type $t = i8;
+                $body
+            }
+            DataType::Int16 => {
+                type $t = i16;
+                $body
+            }
+            DataType::Int32 => {
+                type $t = i32;
+                $body
+            }
+            DataType::Int64 => {
+                type $t = i64;
+                $body
+            }
+            DataType::UInt8 => {
+                type $t = u8;
+                $body
+            }
+            DataType::UInt16 => {
+                type $t = u16;
+                $body
+            }
+            DataType::UInt32 => {
+                type $t = u32;
+                $body
+            }
+            DataType::UInt64 => {
+                type $t = u64;
+                $body
+            }
+            DataType::Float32 => {
+                type $t = f32;
+                $body
+            }
+            DataType::Float64 => {
+                type $t = f64;
+                $body
+            }
+            _ => $otherwise,
+        }
+    };
+}
+
+/// Evaluate `$body` with `$t` as the element type of a data type supported by the `delta` codec, otherwise evaluate `$otherwise`.
+macro_rules! with_delta_type {
+    ($data_type:expr, $t:ident => $body:expr, $otherwise:expr) => {
+        match $data_type {
+            DataType::Float16 => { type $t = half::f16; $body }
+            DataType::BFloat16 => { type $t = half::bf16; $body }
+            data_type => with_primitive_type!(data_type, $t => $body, $otherwise),
+        }
+    };
+}
+
+fn unsupported_data_types(data_type: &DataType, astype: &DataType) -> CodecError {
+    CodecError::Other(format!(
+        "the {IDENTIFIER} codec does not support encoding {data_type} as {astype}"
+    ))
+}
+
+/// Check that the `delta` codec supports encoding `data_type` as `astype`.
+///
+/// Any integer or floating point data type is supported if `astype` is the same data type.
+/// Otherwise, both data types must be integer or 32/64-bit floating point data types.
+fn validate_data_types(data_type: &DataType, astype: &DataType) -> Result<(), CodecError> {
+    if data_type == astype {
+        with_delta_type!(
+            data_type,
+            _T => Ok(()),
+            Err(CodecError::UnsupportedDataType(
+                data_type.clone(),
+                IDENTIFIER.to_string(),
+            ))
+        )
+    } else {
+        with_primitive_type!(
+            data_type,
+            _T => with_primitive_type!(
+                astype,
+                _U => Ok(()),
+                Err(unsupported_data_types(data_type, astype))
+            ),
+            Err(unsupported_data_types(data_type, astype))
+        )
+    }
+}
+
+/// Delta encode `bytes` of `data_type` elements and convert the deltas to `astype`.
+fn delta_encode(
+    bytes: &[u8],
+    data_type: &DataType,
+    astype: &DataType,
+) -> Result<Vec<u8>, CodecError> {
+    if data_type == astype {
+        with_delta_type!(
+            data_type,
+            T => {
+                let mut elements = convert_from_bytes_slice::<T>(bytes);
+                delta_elements(&mut elements);
+                Ok(transmute_to_bytes_vec(elements))
+            },
+            Err(unsupported_data_types(data_type, astype))
+        )
+    } else {
+        with_primitive_type!(
+            data_type,
+            T => with_primitive_type!(
+                astype,
+                U => {
+                    let mut elements = convert_from_bytes_slice::<T>(bytes);
+                    delta_elements(&mut elements);
+                    let elements: Vec<U> = elements.into_iter().map(AsPrimitive::as_).collect();
+                    Ok(transmute_to_bytes_vec(elements))
+                },
+                Err(unsupported_data_types(data_type, astype))
+            ),
+            Err(unsupported_data_types(data_type, astype))
+        )
+    }
+}
+
+/// Convert `bytes` of `astype` deltas to `data_type` and delta decode them.
+fn delta_decode(
+    bytes: &[u8],
+    data_type: &DataType,
+    astype: &DataType,
+) -> Result<Vec<u8>, CodecError> {
+    if data_type == astype {
+        with_delta_type!(
+            data_type,
+            T => {
+                let mut elements = convert_from_bytes_slice::<T>(bytes);
+                undelta_elements(&mut elements);
+                Ok(transmute_to_bytes_vec(elements))
+            },
+            Err(unsupported_data_types(data_type, astype))
+        )
+    } else {
+        with_primitive_type!(
+            data_type,
+            T => with_primitive_type!(
+                astype,
+                U => {
+                    let mut elements: Vec<T> = convert_from_bytes_slice::<U>(bytes)
+                        .into_iter()
+                        .map(AsPrimitive::as_)
+                        .collect();
+                    undelta_elements(&mut elements);
+                    Ok(transmute_to_bytes_vec(elements))
+                },
+                Err(unsupported_data_types(data_type, astype))
+            ),
+            Err(unsupported_data_types(data_type, astype))
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroU64, sync::Arc};
+
+    use itertools::Itertools;
+
+    use crate::{
+        array::{
+            codec::{
+                ArrayToArrayCodecTraits, ArrayToBytesCodecTraits, BytesCodec, CodecOptions,
+                CodecTraits,
+            },
+            ArrayBytes, ChunkRepresentation, FillValue,
+        },
+        array_subset::ArraySubset,
+    };
+
+    use super::*;
+
+    fn chunk_representation(shape: &[u64], data_type: DataType) -> ChunkRepresentation {
+        let fill_value = FillValue::new(vec![0; data_type.fixed_size().unwrap()]);
+        ChunkRepresentation::new(
+            shape.iter().map(|&s| NonZeroU64::new(s).unwrap()).collect(),
+            data_type,
+            fill_value,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn codec_delta_round_trip() {
+        let chunk_representation = chunk_representation(&[6], DataType::Int32);
+        let elements: Vec<i32> = vec![10, 12, 15, 15, 11, i32::MIN];
+        let bytes = ArrayBytes::from(crate::array::transmute_to_bytes_vec(elements.clone()));
+
+        let configuration: DeltaCodecConfiguration = serde_json::from_str("{}").unwrap();
+        let codec = DeltaCodec::new_with_configuration(&configuration).unwrap();
+        let encoded = codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let encoded_elements = crate::array::transmute_from_bytes_vec::<i32>(
+            encoded.clone().into_fixed().unwrap().into_owned(),
+        );
+        assert_eq!(
+            encoded_elements,
+            &[10, 2, 3, 0, -4, i32::MIN.wrapping_sub(11)]
+        );
+        let decoded = codec
+            .decode(encoded, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let decoded_elements = crate::array::transmute_from_bytes_vec::<i32>(
+            decoded.into_fixed().unwrap().into_owned(),
+        );
+        assert_eq!(decoded_elements, elements);
+    }
+
+    #[test]
+    fn codec_delta_round_trip_float() {
+        let chunk_representation = chunk_representation(&[4], DataType::Float64);
+        let elements: Vec<f64> = vec![1.5, 2.0, 4.25, -1.0];
+        let bytes = ArrayBytes::from(crate::array::transmute_to_bytes_vec(elements.clone()));
+
+        let codec = DeltaCodec::new(None);
+        let encoded = codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let decoded = codec
+            .decode(encoded, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let decoded_elements = crate::array::transmute_from_bytes_vec::<f64>(
+            decoded.into_fixed().unwrap().into_owned(),
+        );
+        assert_eq!(decoded_elements, elements);
+    }
+
+    #[test]
+    fn codec_delta_astype() {
+        let chunk_representation = chunk_representation(&[4], DataType::Int64);
+        let elements: Vec<i64> = vec![1_000_000, 1_000_100, 1_000_050, 1_000_050];
+        let bytes = ArrayBytes::from(crate::array::transmute_to_bytes_vec(elements.clone()));
+
+        let configuration: DeltaCodecConfiguration =
+            serde_json::from_str(r#"{"astype":"int16"}"#).unwrap();
+        let codec = DeltaCodec::new_with_configuration(&configuration).unwrap();
+        let encoded_representation = codec.compute_encoded_size(&chunk_representation).unwrap();
+        assert_eq!(encoded_representation.data_type(), &DataType::Int16);
+        assert_eq!(
+            codec.create_metadata().unwrap().to_string(),
+            r#"https://codec.zarrs.dev/array_to_array/delta {"astype":"int16"}"#
+        );
+
+        let encoded = codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let encoded_elements = crate::array::transmute_from_bytes_vec::<i16>(
+            encoded.clone().into_fixed().unwrap().into_owned(),
+        );
+        assert_eq!(encoded_elements, &[16960, 100, -50, 0]);
+        let decoded = codec
+            .decode(encoded, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let decoded_elements = crate::array::transmute_from_bytes_vec::<i64>(
+            decoded.into_fixed().unwrap().into_owned(),
+        );
+        // The first element does not fit in the encoded data type
+        assert_eq!(decoded_elements, &[16960, 17060, 17010, 17010]);
+    }
+
+    #[test]
+    fn codec_delta_unsupported() {
+        let codec = DeltaCodec::new(None);
+        assert!(codec
+            .compute_encoded_size(&chunk_representation(&[4], DataType::Bool))
+            .is_err());
+        let codec = DeltaCodec::new(Some(DataType::Float32));
+        assert!(codec
+            .compute_encoded_size(&chunk_representation(&[4], DataType::Float16))
+            .is_err());
+        assert!(codec
+            .compute_encoded_size(&chunk_representation(&[4], DataType::Int32))
+            .is_ok());
+    }
+
+    #[test]
+    fn codec_delta_partial_decode() {
+        let codec = Arc::new(DeltaCodec::new(None));
+
+        let elements: Vec<u16> = (0..20).map(|i| i * i).collect();
+        let chunk_representation = chunk_representation(&[4, 5], DataType::UInt16);
+        let bytes: ArrayBytes = crate::array::transmute_to_bytes_vec(elements).into();
+
+        let encoded = codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .unwrap()
+            .into_owned();
+        let decoded_regions = [
+            ArraySubset::new_with_ranges(&[1..3, 1..3]),
+            ArraySubset::new_with_ranges(&[0..1, 4..5]),
+            ArraySubset::new_with_ranges(&[2..2, 0..5]),
+        ];
+        let input_handle = Arc::new(std::io::Cursor::new(encoded.into_fixed().unwrap()));
+        let bytes_codec = Arc::new(BytesCodec::default());
+        let input_handle = bytes_codec
+            .partial_decoder(
+                input_handle,
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        let partial_decoder = codec
+            .partial_decoder(
+                input_handle,
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        let decoded_partial_chunk = partial_decoder
+            .partial_decode(&decoded_regions, &CodecOptions::default())
+            .unwrap();
+        let decoded_partial_chunk = decoded_partial_chunk
+            .into_iter()
+            .map(|bytes| {
+                crate::array::transmute_from_bytes_vec::<u16>(
+                    bytes.into_fixed().unwrap().into_owned(),
+                )
+            })
+            .collect_vec();
+        let answer: &[Vec<u16>] = &[vec![36, 49, 121, 144], vec![16], vec![]];
+        assert_eq!(answer, decoded_partial_chunk);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn codec_delta_async_partial_decode() {
+        let codec = Arc::new(DeltaCodec::new(Some(DataType::Int16)));
+
+        let elements: Vec<i32> = (0..20).map(|i| 1000 - i * 3).collect();
+        let chunk_representation = chunk_representation(&[20], DataType::Int32);
+        let encoded_representation = codec.compute_encoded_size(&chunk_representation).unwrap();
+        let bytes: ArrayBytes = crate::array::transmute_to_bytes_vec(elements).into();
+
+        let encoded = codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .unwrap()
+            .into_owned();
+        let decoded_regions = [ArraySubset::new_with_start_shape(vec![5], vec![3]).unwrap()];
+        let input_handle = Arc::new(std::io::Cursor::new(encoded.into_fixed().unwrap()));
+        let bytes_codec = Arc::new(BytesCodec::default());
+        let input_handle = bytes_codec
+            .async_partial_decoder(
+                input_handle,
+                &encoded_representation,
+                &CodecOptions::default(),
+            )
+            .await
+            .unwrap();
+        let partial_decoder = codec
+            .async_partial_decoder(
+                input_handle,
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .await
+            .unwrap();
+        let decoded_partial_chunk = partial_decoder
+            .partial_decode(&decoded_regions, &CodecOptions::default())
+            .await
+            .unwrap();
+        let decoded_partial_chunk = crate::array::transmute_from_bytes_vec::<i32>(
+            decoded_partial_chunk[0]
+                .clone()
+                .into_fixed()
+                .unwrap()
+                .into_owned(),
+        );
+        assert_eq!(decoded_partial_chunk, &[985, 982, 979]);
+    }
+}
@@ -0,0 +1,214 @@
+use std::sync::Arc;
+
+use crate::{
+    array::{
+        codec::{
+            options::CodecOptions, ArrayBytes, ArrayCodecTraits, ArrayPartialDecoderTraits,
+            ArrayPartialEncoderTraits, ArrayToArrayCodecTraits, ArrayToArrayPartialEncoderDefault,
+            CodecError, CodecTraits, RecommendedConcurrency,
+        },
+        ArrayMetadataOptions, ChunkRepresentation, ChunkShape, DataType, FillValue,
+    },
+    config::global_config,
+    metadata::v3::MetadataV3,
+};
+
+#[cfg(feature = "async")]
+use crate::array::codec::AsyncArrayPartialDecoderTraits;
+
+use super::{
+    delta_decode, delta_encode, delta_partial_decoder, validate_data_types,
+    DeltaCodecConfiguration, DeltaCodecConfigurationV1,
+};
+
+/// A `delta` codec implementation.
+#[derive(Clone, Debug, Default)]
+pub struct DeltaCodec {
+    astype: Option<DataType>,
+}
+
+impl DeltaCodec {
+    /// Create a new `delta` codec.
+    ///
+    /// `astype` is the data type of the encoded deltas, which defaults to the decoded data type if [`None`].
+    #[must_use]
+    pub const fn new(astype: Option<DataType>) -> Self {
+        Self { astype }
+    }
+
+    /// Create a new `delta` codec from a configuration.
+    ///
+    /// # Errors
+    /// Returns an error if `astype` is not a supported data type.
+    pub fn new_with_configuration(
+        configuration: &DeltaCodecConfiguration,
+    ) -> Result<Self, CodecError> {
+        let DeltaCodecConfiguration::V1(configuration) = configuration;
+        let astype = configuration
+            .astype
+            .as_ref()
+            .map(DataType::from_metadata)
+            .transpose()
+            .map_err(|err| CodecError::Other(err.to_string()))?;
+        Ok(Self { astype })
+    }
+
+    /// Return the data type of the encoded deltas, or [`None`] if it is the decoded data type.
+    #[must_use]
+    pub const fn astype(&self) -> Option<&DataType> {
+        self.astype.as_ref()
+    }
+
+    fn encoded_data_type<'a>(&'a self, decoded_data_type: &'a DataType) -> &'a DataType {
+        self.astype.as_ref().unwrap_or(decoded_data_type)
+    }
+}
+
+impl CodecTraits for DeltaCodec {
+    fn create_metadata_opt(&self, _options: &ArrayMetadataOptions) -> Option<MetadataV3> {
+        let configuration = DeltaCodecConfigurationV1 {
+            astype: self.astype.as_ref().map(DataType::metadata),
+        };
+        Some(
+            MetadataV3::new_with_serializable_configuration(
+                global_config()
+                    .experimental_codec_names()
+                    .get(super::IDENTIFIER)
+                    .expect("experimental codec identifier in global map"),
+                &configuration,
+            )
+            .unwrap(),
+        )
+    }
+
+    fn partial_decoder_should_cache_input(&self) -> bool {
+        // Each partial decode decodes all elements preceding the requested subsets
+        true
+    }
+
+    fn partial_decoder_decodes_all(&self) -> bool {
+        false
+    }
+}
+
+impl ArrayCodecTraits for DeltaCodec {
+    fn recommended_concurrency(
+        &self,
+        _decoded_representation: &ChunkRepresentation,
+    ) -> Result<RecommendedConcurrency, CodecError> {
+        // Delta decoding is sequential
+        Ok(RecommendedConcurrency::new_maximum(1))
+    }
+}
+
+#[cfg_attr(feature = "async", async_trait::async_trait)]
+impl ArrayToArrayCodecTraits for DeltaCodec {
+    fn dynamic(self: Arc<Self>) -> Arc<dyn ArrayToArrayCodecTraits> {
+        self as Arc<dyn ArrayToArrayCodecTraits>
+    }
+
+    fn encode<'a>(
+        &self,
+        bytes: ArrayBytes<'a>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<ArrayBytes<'a>, CodecError> {
+        let data_type = decoded_representation.data_type();
+        let bytes = bytes.into_fixed()?;
+        let encoded = delta_encode(&bytes, data_type, self.encoded_data_type(data_type))?;
+        Ok(ArrayBytes::from(encoded))
+    }
+
+    fn decode<'a>(
+        &self,
+        bytes: ArrayBytes<'a>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<ArrayBytes<'a>, CodecError> {
+        let data_type = decoded_representation.data_type();
+        let bytes = bytes.into_fixed()?;
+        let decoded = delta_decode(&bytes, data_type, self.encoded_data_type(data_type))?;
+        Ok(ArrayBytes::from(decoded))
+    }
+
+    fn partial_decoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn ArrayPartialDecoderTraits>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn ArrayPartialDecoderTraits>, CodecError> {
+        let astype = self
+            .encoded_data_type(decoded_representation.data_type())
+            .clone();
+        Ok(Arc::new(delta_partial_decoder::DeltaPartialDecoder::new(
+            input_handle,
+            decoded_representation.clone(),
+            astype,
+        )?))
+    }
+
+    fn partial_encoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn ArrayPartialDecoderTraits>,
+        output_handle: Arc<dyn ArrayPartialEncoderTraits>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn ArrayPartialEncoderTraits>, CodecError> {
+        Ok(Arc::new(ArrayToArrayPartialEncoderDefault::new(
+            input_handle,
+            output_handle,
+            decoded_representation.clone(),
+            self,
+        )))
+    }
+
+    #[cfg(feature = "async")]
+    async fn async_partial_decoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn AsyncArrayPartialDecoderTraits>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn AsyncArrayPartialDecoderTraits>, CodecError> {
+        let astype = self
+            .encoded_data_type(decoded_representation.data_type())
+            .clone();
+        Ok(Arc::new(
+            delta_partial_decoder::AsyncDeltaPartialDecoder::new(
+                input_handle,
+                decoded_representation.clone(),
+                astype,
+            )?,
+        ))
+    }
+
+    fn compute_encoded_size(
+        &self,
+        decoded_representation: &ChunkRepresentation,
+    ) -> Result<ChunkRepresentation, CodecError> {
+        let data_type = decoded_representation.data_type();
+        let astype = self.encoded_data_type(data_type);
+        validate_data_types(data_type, astype)?;
+        if data_type == astype {
+            Ok(decoded_representation.clone())
+        } else {
+            // The encoded fill value is the fill value converted to the encoded data type
+            let fill_value = FillValue::new(delta_encode(
+                decoded_representation.fill_value().as_ne_bytes(),
+                data_type,
+                astype,
+            )?);
+            Ok(unsafe {
+                // SAFETY: the fill value has one element of the encoded data type
+                ChunkRepresentation::new_unchecked(
+                    decoded_representation.shape().to_vec(),
+                    astype.clone(),
+                    fill_value,
+                )
+            })
+        }
+    }
+
+    fn compute_decoded_shape(&self, encoded_shape: ChunkShape) -> Result<ChunkShape, CodecError> {
+        Ok(encoded_shape)
+    }
+}
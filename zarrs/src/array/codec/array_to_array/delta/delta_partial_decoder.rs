@@ -0,0 +1,204 @@
+use std::sync::Arc;
+
+use crate::{
+    array::{
+        codec::{ArrayBytes, ArrayPartialDecoderTraits, CodecError, CodecOptions},
+        ravel_indices, unravel_index, ArrayShape, ChunkRepresentation, DataType,
+    },
+    array_subset::{ArraySubset, IncompatibleArraySubsetAndShapeError},
+};
+
+#[cfg(feature = "async")]
+use crate::array::codec::AsyncArrayPartialDecoderTraits;
+
+use super::{delta_decode, validate_data_types};
+
+/// Partial decoder for the `delta` codec.
+pub(crate) struct DeltaPartialDecoder {
+    input_handle: Arc<dyn ArrayPartialDecoderTraits>,
+    decoded_representation: ChunkRepresentation,
+    astype: DataType,
+}
+
+impl DeltaPartialDecoder {
+    /// Create a new partial decoder for the `delta` codec.
+    pub(crate) fn new(
+        input_handle: Arc<dyn ArrayPartialDecoderTraits>,
+        decoded_representation: ChunkRepresentation,
+        astype: DataType,
+    ) -> Result<Self, CodecError> {
+        validate_data_types(decoded_representation.data_type(), &astype)?;
+        Ok(Self {
+            input_handle,
+            decoded_representation,
+            astype,
+        })
+    }
+}
+
+/// Return the subsets of the elements of a chunk with `chunk_shape` preceding and including the last element of `array_subsets`.
+///
+/// The subsets are in lexicographical order, so their concatenated elements are the leading elements of the chunk.
+/// Returns [`None`] if all `array_subsets` are empty.
+fn prefix_subsets(
+    array_subsets: &[ArraySubset],
+    chunk_shape: &[u64],
+) -> Result<Option<Vec<ArraySubset>>, CodecError> {
+    let mut last = None;
+    for array_subset in array_subsets {
+        if array_subset.dimensionality() != chunk_shape.len() {
+            return Err(CodecError::InvalidArraySubsetDimensionalityError(
+                array_subset.clone(),
+                chunk_shape.len(),
+            ));
+        }
+        if let Some(end_inc) = array_subset.end_inc() {
+            if !array_subset.inbounds(chunk_shape) {
+                return Err(CodecError::InvalidArraySubsetError(
+                    IncompatibleArraySubsetAndShapeError::new(
+                        array_subset.clone(),
+                        chunk_shape.to_vec(),
+                    ),
+                ));
+            }
+            let index = ravel_indices(&end_inc, chunk_shape);
+            last = Some(last.map_or(index, |last: u64| last.max(index)));
+        }
+    }
+    let Some(last) = last else {
+        return Ok(None);
+    };
+
+    // The elements preceding `last` along each dimension, then `last` itself
+    let last = unravel_index(last, chunk_shape);
+    let mut subsets = Vec::with_capacity(chunk_shape.len() + 1);
+    for dim in 0..chunk_shape.len() {
+        if last[dim] > 0 {
+            let ranges: Vec<_> = (0..chunk_shape.len())
+                .map(|i| match i.cmp(&dim) {
+                    std::cmp::Ordering::Less => last[i]..last[i] + 1,
+                    std::cmp::Ordering::Equal => 0..last[i],
+                    std::cmp::Ordering::Greater => 0..chunk_shape[i],
+                })
+                .collect();
+            subsets.push(ArraySubset::new_with_ranges(&ranges));
+        }
+    }
+    let ranges: Vec<_> = last.iter().map(|&i| i..i + 1).collect();
+    subsets.push(ArraySubset::new_with_ranges(&ranges));
+    Ok(Some(subsets))
+}
+
+/// Delta decode the leading elements of a chunk and extract `array_subsets`.
+fn decode_prefix(
+    prefix: Vec<ArrayBytes<'_>>,
+    array_subsets: &[ArraySubset],
+    decoded_representation: &ChunkRepresentation,
+    astype: &DataType,
+) -> Result<Vec<ArrayBytes<'static>>, CodecError> {
+    let data_type = decoded_representation.data_type();
+    let mut encoded = Vec::new();
+    for bytes in prefix {
+        encoded.extend_from_slice(&bytes.into_fixed()?);
+    }
+    let decoded = ArrayBytes::from(delta_decode(&encoded, data_type, astype)?);
+    let chunk_shape: ArrayShape = decoded_representation.shape_u64();
+    array_subsets
+        .iter()
+        .map(|array_subset| {
+            if array_subset.is_empty() {
+                Ok(ArrayBytes::new_flen(vec![]))
+            } else {
+                // The decoded elements include the last element of every subset
+                Ok(decoded
+                    .extract_array_subset(array_subset, &chunk_shape, data_type)?
+                    .into_owned())
+            }
+        })
+        .collect()
+}
+
+fn empty_array_bytes(array_subsets: &[ArraySubset]) -> Vec<ArrayBytes<'static>> {
+    array_subsets
+        .iter()
+        .map(|_| ArrayBytes::new_flen(vec![]))
+        .collect()
+}
+
+impl ArrayPartialDecoderTraits for DeltaPartialDecoder {
+    fn data_type(&self) -> &DataType {
+        self.decoded_representation.data_type()
+    }
+
+    fn partial_decode(
+        &self,
+        array_subsets: &[ArraySubset],
+        options: &CodecOptions,
+    ) -> Result<Vec<ArrayBytes<'_>>, CodecError> {
+        let chunk_shape = self.decoded_representation.shape_u64();
+        let Some(prefix_subsets) = prefix_subsets(array_subsets, &chunk_shape)? else {
+            return Ok(empty_array_bytes(array_subsets));
+        };
+        let prefix = self.input_handle.partial_decode(&prefix_subsets, options)?;
+        decode_prefix(
+            prefix,
+            array_subsets,
+            &self.decoded_representation,
+            &self.astype,
+        )
+    }
+}
+
+#[cfg(feature = "async")]
+/// Asynchronous partial decoder for the `delta` codec.
+pub(crate) struct AsyncDeltaPartialDecoder {
+    input_handle: Arc<dyn AsyncArrayPartialDecoderTraits>,
+    decoded_representation: ChunkRepresentation,
+    astype: DataType,
+}
+
+#[cfg(feature = "async")]
+impl AsyncDeltaPartialDecoder {
+    /// Create a new partial decoder for the `delta` codec.
+    pub(crate) fn new(
+        input_handle: Arc<dyn AsyncArrayPartialDecoderTraits>,
+        decoded_representation: ChunkRepresentation,
+        astype: DataType,
+    ) -> Result<Self, CodecError> {
+        validate_data_types(decoded_representation.data_type(), &astype)?;
+        Ok(Self {
+            input_handle,
+            decoded_representation,
+            astype,
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl AsyncArrayPartialDecoderTraits for AsyncDeltaPartialDecoder {
+    fn data_type(&self) -> &DataType {
+        self.decoded_representation.data_type()
+    }
+
+    async fn partial_decode(
+        &self,
+        array_subsets: &[ArraySubset],
+        options: &CodecOptions,
+    ) -> Result<Vec<ArrayBytes<'_>>, CodecError> {
+        let chunk_shape = self.decoded_representation.shape_u64();
+        let Some(prefix_subsets) = prefix_subsets(array_subsets, &chunk_shape)? else {
+            return Ok(empty_array_bytes(array_subsets));
+        };
+        let prefix = self
+            .input_handle
+            .partial_decode(&prefix_subsets, options)
+            .await?;
+        decode_prefix(
+            prefix,
+            array_subsets,
+            &self.decoded_representation,
+            &self.astype,
+        )
+    }
+}
@@ -0,0 +1,318 @@
+//! The `fixedscaleoffset` array to array codec.
+//!
+//! Encodes each element as `round((x - offset) * scale)` converted to the encoded data type (`astype`), and decodes each element as `x / scale + offset`.
+//! Rounding is to the nearest integer, with ties to even.
+//! Encoding is lossy unless every element is exactly representable after scaling.
+//!
+//! This codec is compatible with the `numcodecs` `fixedscaleoffset` filter of Zarr V2 arrays.
+//! It is typically used to store floating point data with a fixed precision as narrow integers.
+//!
+//! <div class="warning">
+//! This codec is experimental and may be incompatible with other Zarr V3 implementations.
+//! </div>
+//!
+//! This codec requires the `fixedscaleoffset` feature, which is disabled by default.
+//!
+//! See [`FixedScaleOffsetCodecConfigurationV1`] for example `JSON` metadata.
+
+mod fixedscaleoffset_codec;
+mod fixedscaleoffset_partial_decoder;
+
+use std::sync::Arc;
+
+use num::traits::AsPrimitive;
+
+pub use crate::metadata::v3::array::codec::fixedscaleoffset::{
+    FixedScaleOffsetCodecConfiguration, FixedScaleOffsetCodecConfigurationV1,
+};
+pub use fixedscaleoffset_codec::FixedScaleOffsetCodec;
+
+use crate::{
+    array::{
+        codec::{Codec, CodecError, CodecPlugin},
+        convert_from_bytes_slice, transmute_to_bytes_vec, DataType,
+    },
+    config::global_config,
+    metadata::v3::{array::codec::fixedscaleoffset, MetadataV3},
+    plugin::{PluginCreateError, PluginMetadataInvalidError},
+};
+
+pub use fixedscaleoffset::IDENTIFIER;
+
+// Register the codec.
+inventory::submit! {
+    CodecPlugin::new(IDENTIFIER, is_name_fixedscaleoffset, create_codec_fixedscaleoffset)
+}
+
+fn is_name_fixedscaleoffset(name: &str) -> bool {
+    name.eq(IDENTIFIER)
+        || name
+            == global_config()
+                .experimental_codec_names()
+                .get(IDENTIFIER)
+                .expect("experimental codec identifier in global map")
+}
+
+pub(crate) fn create_codec_fixedscaleoffset(
+    metadata: &MetadataV3,
+) -> Result<Codec, PluginCreateError> {
+    let configuration: FixedScaleOffsetCodecConfiguration = metadata
+        .to_configuration()
+        .map_err(|_| PluginMetadataInvalidError::new(IDENTIFIER, "codec", metadata.clone()))?;
+    let codec = Arc::new(
+        FixedScaleOffsetCodec::new_with_configuration(&configuration)
+            .map_err(|_| PluginMetadataInvalidError::new(IDENTIFIER, "codec", metadata.clone()))?,
+    );
+    Ok(Codec::ArrayToArray(codec))
+}
+
+/// Evaluate `$body` with `$t` as the element type of a data type supported by the `fixedscaleoffset` codec, otherwise evaluate `$otherwise`.
+macro_rules! with_fixedscaleoffset_type {
+    ($data_type:expr, $t:ident => $body:expr, $otherwise:expr) => {
+        match $data_type {
+            DataType::Int8 => {
+                type $t = i8;
+                $body
+            }
+            DataType::Int16 => {
+                type $t = i16;
+                $body
+            }
+            DataType::Int32 => {
+                type $t = i32;
+                $body
+            }
+            DataType::Int64 => {
+                type $t = i64;
+                $body
+            }
+            DataType::UInt8 => {
+                type $t = u8;
+                $body
+            }
+            DataType::UInt16 => {
+                type $t = u16;
+                $body
+            }
+            DataType::UInt32 => {
+                type $t = u32;
+                $body
+            }
+            DataType::UInt64 => {
+                type $t = u64;
+                $body
+            }
+            DataType::Float32 => {
+                type $t = f32;
+                $body
+            }
+            DataType::Float64 => {
+                type $t = f64;
+                $body
+            }
+            _ => $otherwise,
+        }
+    };
+}
+
+fn unsupported_data_types(data_type: &DataType, astype: &DataType) -> CodecError {
+    CodecError::Other(format!(
+        "the {IDENTIFIER} codec does not support encoding {data_type} as {astype}"
+    ))
+}
+
+/// Check that the `fixedscaleoffset` codec supports encoding `data_type` as `astype`.
+///
+/// Both data types must be integer or 32/64-bit floating point data types.
+fn validate_data_types(data_type: &DataType, astype: &DataType) -> Result<(), CodecError> {
+    with_fixedscaleoffset_type!(
+        data_type,
+        _T => with_fixedscaleoffset_type!(
+            astype,
+            _U => Ok(()),
+            Err(unsupported_data_types(data_type, astype))
+        ),
+        Err(CodecError::UnsupportedDataType(
+            data_type.clone(),
+            IDENTIFIER.to_string(),
+        ))
+    )
+}
+
+/// Scale and offset `bytes` of `data_type` elements and convert them to `astype`.
+fn fixedscaleoffset_encode(
+    bytes: &[u8],
+    data_type: &DataType,
+    astype: &DataType,
+    offset: f64,
+    scale: f64,
+) -> Result<Vec<u8>, CodecError> {
+    with_fixedscaleoffset_type!(
+        data_type,
+        T => with_fixedscaleoffset_type!(
+            astype,
+            U => {
+                let elements: Vec<U> = convert_from_bytes_slice::<T>(bytes)
+                    .into_iter()
+                    .map(|element| {
+                        let element: f64 = element.as_();
+                        ((element - offset) * scale).round_ties_even().as_()
+                    })
+                    .collect();
+                Ok(transmute_to_bytes_vec(elements))
+            },
+            Err(unsupported_data_types(data_type, astype))
+        ),
+        Err(unsupported_data_types(data_type, astype))
+    )
+}
+
+/// Convert `bytes` of `astype` elements to `data_type` and undo the scale and offset.
+fn fixedscaleoffset_decode(
+    bytes: &[u8],
+    data_type: &DataType,
+    astype: &DataType,
+    offset: f64,
+    scale: f64,
+) -> Result<Vec<u8>, CodecError> {
+    with_fixedscaleoffset_type!(
+        data_type,
+        T => with_fixedscaleoffset_type!(
+            astype,
+            U => {
+                let elements: Vec<T> = convert_from_bytes_slice::<U>(bytes)
+                    .into_iter()
+                    .map(|element| {
+                        let element: f64 = element.as_();
+                        (element / scale + offset).as_()
+                    })
+                    .collect();
+                Ok(transmute_to_bytes_vec(elements))
+            },
+            Err(unsupported_data_types(data_type, astype))
+        ),
+        Err(unsupported_data_types(data_type, astype))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroU64, sync::Arc};
+
+    use crate::{
+        array::{
+            codec::{
+                ArrayToArrayCodecTraits, ArrayToBytesCodecTraits, BytesCodec, CodecOptions,
+                CodecTraits,
+            },
+            ArrayBytes, ChunkRepresentation, FillValue,
+        },
+        array_subset::ArraySubset,
+    };
+
+    use super::*;
+
+    fn chunk_representation(shape: &[u64], data_type: DataType) -> ChunkRepresentation {
+        let fill_value = FillValue::new(vec![0; data_type.fixed_size().unwrap()]);
+        ChunkRepresentation::new(
+            shape.iter().map(|&s| NonZeroU64::new(s).unwrap()).collect(),
+            data_type,
+            fill_value,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn codec_fixedscaleoffset_round_trip() {
+        let chunk_representation = chunk_representation(&[5], DataType::Float64);
+        let elements: Vec<f64> = vec![1000.0, 1000.12, 1000.25, 1000.75, 1025.5];
+        let bytes = ArrayBytes::from(crate::array::transmute_to_bytes_vec(elements));
+
+        let configuration: FixedScaleOffsetCodecConfiguration =
+            serde_json::from_str(r#"{"offset":1000,"scale":10,"astype":"uint8"}"#).unwrap();
+        let codec = FixedScaleOffsetCodec::new_with_configuration(&configuration).unwrap();
+        let encoded_representation = codec.compute_encoded_size(&chunk_representation).unwrap();
+        assert_eq!(encoded_representation.data_type(), &DataType::UInt8);
+        assert_eq!(
+            codec.create_metadata().unwrap().to_string(),
+            r#"https://codec.zarrs.dev/array_to_array/fixedscaleoffset {"offset":1000.0,"scale":10.0,"astype":"uint8"}"#
+        );
+
+        let encoded = codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let encoded_elements = encoded.clone().into_fixed().unwrap().into_owned();
+        // Ties round to even, so 2.5 rounds to 2 and 7.5 rounds to 8
+        assert_eq!(encoded_elements, &[0, 1, 2, 8, 255]);
+        let decoded = codec
+            .decode(encoded, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let decoded_elements = crate::array::transmute_from_bytes_vec::<f64>(
+            decoded.into_fixed().unwrap().into_owned(),
+        );
+        assert_eq!(decoded_elements, &[1000.0, 1000.1, 1000.2, 1000.8, 1025.5]);
+    }
+
+    #[test]
+    fn codec_fixedscaleoffset_unsupported() {
+        let codec = FixedScaleOffsetCodec::new(0.0, 1.0, None);
+        assert!(codec
+            .compute_encoded_size(&chunk_representation(&[4], DataType::Bool))
+            .is_err());
+        let codec = FixedScaleOffsetCodec::new(0.0, 1.0, Some(DataType::Float16));
+        assert!(codec
+            .compute_encoded_size(&chunk_representation(&[4], DataType::Float32))
+            .is_err());
+        assert!(FixedScaleOffsetCodec::new(0.0, 1.0, Some(DataType::Int16))
+            .compute_encoded_size(&chunk_representation(&[4], DataType::Float32))
+            .is_ok());
+    }
+
+    #[test]
+    fn codec_fixedscaleoffset_partial_decode() {
+        let codec = Arc::new(FixedScaleOffsetCodec::new(-1.0, 4.0, Some(DataType::Int16)));
+
+        let elements: Vec<f32> = (0..20i16).map(|i| f32::from(i) * 0.25).collect();
+        let chunk_representation = chunk_representation(&[4, 5], DataType::Float32);
+        let encoded_representation = codec.compute_encoded_size(&chunk_representation).unwrap();
+        let bytes: ArrayBytes = crate::array::transmute_to_bytes_vec(elements).into();
+
+        let encoded = codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .unwrap()
+            .into_owned();
+        let decoded_regions = [
+            ArraySubset::new_with_ranges(&[1..3, 1..3]),
+            ArraySubset::new_with_ranges(&[0..1, 4..5]),
+        ];
+        let input_handle = Arc::new(std::io::Cursor::new(encoded.into_fixed().unwrap()));
+        let bytes_codec = Arc::new(BytesCodec::default());
+        let input_handle = bytes_codec
+            .partial_decoder(
+                input_handle,
+                &encoded_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        let partial_decoder = codec
+            .partial_decoder(
+                input_handle,
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        let decoded_partial_chunk = partial_decoder
+            .partial_decode(&decoded_regions, &CodecOptions::default())
+            .unwrap();
+        let decoded_partial_chunk: Vec<Vec<f32>> = decoded_partial_chunk
+            .into_iter()
+            .map(|bytes| {
+                crate::array::transmute_from_bytes_vec::<f32>(
+                    bytes.into_fixed().unwrap().into_owned(),
+                )
+            })
+            .collect();
+        let answer: &[Vec<f32>] = &[vec![1.5, 1.75, 2.75, 3.0], vec![1.0]];
+        assert_eq!(answer, decoded_partial_chunk);
+    }
+}
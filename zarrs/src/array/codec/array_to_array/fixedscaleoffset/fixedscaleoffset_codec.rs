@@ -0,0 +1,245 @@
+use std::sync::Arc;
+
+use crate::{
+    array::{
+        codec::{
+            options::CodecOptions, ArrayBytes, ArrayCodecTraits, ArrayPartialDecoderTraits,
+            ArrayPartialEncoderTraits, ArrayToArrayCodecTraits, ArrayToArrayPartialEncoderDefault,
+            CodecError, CodecTraits, RecommendedConcurrency,
+        },
+        ArrayMetadataOptions, ChunkRepresentation, ChunkShape, DataType, FillValue,
+    },
+    config::global_config,
+    metadata::v3::MetadataV3,
+};
+
+#[cfg(feature = "async")]
+use crate::array::codec::AsyncArrayPartialDecoderTraits;
+
+use super::{
+    fixedscaleoffset_decode, fixedscaleoffset_encode, fixedscaleoffset_partial_decoder,
+    validate_data_types, FixedScaleOffsetCodecConfiguration, FixedScaleOffsetCodecConfigurationV1,
+};
+
+/// A `fixedscaleoffset` codec implementation.
+#[derive(Clone, Debug)]
+pub struct FixedScaleOffsetCodec {
+    offset: f64,
+    scale: f64,
+    astype: Option<DataType>,
+}
+
+impl FixedScaleOffsetCodec {
+    /// Create a new `fixedscaleoffset` codec.
+    ///
+    /// Elements are encoded as `round((x - offset) * scale)`.
+    /// `astype` is the data type of the encoded elements, which defaults to the decoded data type if [`None`].
+    #[must_use]
+    pub const fn new(offset: f64, scale: f64, astype: Option<DataType>) -> Self {
+        Self {
+            offset,
+            scale,
+            astype,
+        }
+    }
+
+    /// Create a new `fixedscaleoffset` codec from a configuration.
+    ///
+    /// # Errors
+    /// Returns an error if `astype` is not a supported data type.
+    pub fn new_with_configuration(
+        configuration: &FixedScaleOffsetCodecConfiguration,
+    ) -> Result<Self, CodecError> {
+        let FixedScaleOffsetCodecConfiguration::V1(configuration) = configuration;
+        let astype = configuration
+            .astype
+            .as_ref()
+            .map(DataType::from_metadata)
+            .transpose()
+            .map_err(|err| CodecError::Other(err.to_string()))?;
+        Ok(Self::new(configuration.offset, configuration.scale, astype))
+    }
+
+    /// Return the offset.
+    #[must_use]
+    pub const fn offset(&self) -> f64 {
+        self.offset
+    }
+
+    /// Return the scale.
+    #[must_use]
+    pub const fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// Return the data type of the encoded elements, or [`None`] if it is the decoded data type.
+    #[must_use]
+    pub const fn astype(&self) -> Option<&DataType> {
+        self.astype.as_ref()
+    }
+
+    fn encoded_data_type<'a>(&'a self, decoded_data_type: &'a DataType) -> &'a DataType {
+        self.astype.as_ref().unwrap_or(decoded_data_type)
+    }
+}
+
+impl CodecTraits for FixedScaleOffsetCodec {
+    fn create_metadata_opt(&self, _options: &ArrayMetadataOptions) -> Option<MetadataV3> {
+        let configuration = FixedScaleOffsetCodecConfigurationV1 {
+            offset: self.offset,
+            scale: self.scale,
+            astype: self.astype.as_ref().map(DataType::metadata),
+        };
+        Some(
+            MetadataV3::new_with_serializable_configuration(
+                global_config()
+                    .experimental_codec_names()
+                    .get(super::IDENTIFIER)
+                    .expect("experimental codec identifier in global map"),
+                &configuration,
+            )
+            .unwrap(),
+        )
+    }
+
+    fn partial_decoder_should_cache_input(&self) -> bool {
+        false
+    }
+
+    fn partial_decoder_decodes_all(&self) -> bool {
+        false
+    }
+}
+
+impl ArrayCodecTraits for FixedScaleOffsetCodec {
+    fn recommended_concurrency(
+        &self,
+        _decoded_representation: &ChunkRepresentation,
+    ) -> Result<RecommendedConcurrency, CodecError> {
+        Ok(RecommendedConcurrency::new_maximum(1))
+    }
+}
+
+#[cfg_attr(feature = "async", async_trait::async_trait)]
+impl ArrayToArrayCodecTraits for FixedScaleOffsetCodec {
+    fn dynamic(self: Arc<Self>) -> Arc<dyn ArrayToArrayCodecTraits> {
+        self as Arc<dyn ArrayToArrayCodecTraits>
+    }
+
+    fn encode<'a>(
+        &self,
+        bytes: ArrayBytes<'a>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<ArrayBytes<'a>, CodecError> {
+        let data_type = decoded_representation.data_type();
+        let bytes = bytes.into_fixed()?;
+        let encoded = fixedscaleoffset_encode(
+            &bytes,
+            data_type,
+            self.encoded_data_type(data_type),
+            self.offset,
+            self.scale,
+        )?;
+        Ok(ArrayBytes::from(encoded))
+    }
+
+    fn decode<'a>(
+        &self,
+        bytes: ArrayBytes<'a>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<ArrayBytes<'a>, CodecError> {
+        let data_type = decoded_representation.data_type();
+        let bytes = bytes.into_fixed()?;
+        let decoded = fixedscaleoffset_decode(
+            &bytes,
+            data_type,
+            self.encoded_data_type(data_type),
+            self.offset,
+            self.scale,
+        )?;
+        Ok(ArrayBytes::from(decoded))
+    }
+
+    fn partial_decoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn ArrayPartialDecoderTraits>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn ArrayPartialDecoderTraits>, CodecError> {
+        let data_type = decoded_representation.data_type();
+        Ok(Arc::new(
+            fixedscaleoffset_partial_decoder::FixedScaleOffsetPartialDecoder::new(
+                input_handle,
+                data_type.clone(),
+                self.encoded_data_type(data_type).clone(),
+                self.offset,
+                self.scale,
+            )?,
+        ))
+    }
+
+    fn partial_encoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn ArrayPartialDecoderTraits>,
+        output_handle: Arc<dyn ArrayPartialEncoderTraits>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn ArrayPartialEncoderTraits>, CodecError> {
+        Ok(Arc::new(ArrayToArrayPartialEncoderDefault::new(
+            input_handle,
+            output_handle,
+            decoded_representation.clone(),
+            self,
+        )))
+    }
+
+    #[cfg(feature = "async")]
+    async fn async_partial_decoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn AsyncArrayPartialDecoderTraits>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn AsyncArrayPartialDecoderTraits>, CodecError> {
+        let data_type = decoded_representation.data_type();
+        Ok(Arc::new(
+            fixedscaleoffset_partial_decoder::AsyncFixedScaleOffsetPartialDecoder::new(
+                input_handle,
+                data_type.clone(),
+                self.encoded_data_type(data_type).clone(),
+                self.offset,
+                self.scale,
+            )?,
+        ))
+    }
+
+    fn compute_encoded_size(
+        &self,
+        decoded_representation: &ChunkRepresentation,
+    ) -> Result<ChunkRepresentation, CodecError> {
+        let data_type = decoded_representation.data_type();
+        let astype = self.encoded_data_type(data_type);
+        validate_data_types(data_type, astype)?;
+        // The encoded fill value is the encoded decoded fill value
+        let fill_value = FillValue::new(fixedscaleoffset_encode(
+            decoded_representation.fill_value().as_ne_bytes(),
+            data_type,
+            astype,
+            self.offset,
+            self.scale,
+        )?);
+        Ok(unsafe {
+            // SAFETY: the fill value has one element of the encoded data type
+            ChunkRepresentation::new_unchecked(
+                decoded_representation.shape().to_vec(),
+                astype.clone(),
+                fill_value,
+            )
+        })
+    }
+
+    fn compute_decoded_shape(&self, encoded_shape: ChunkShape) -> Result<ChunkShape, CodecError> {
+        Ok(encoded_shape)
+    }
+}
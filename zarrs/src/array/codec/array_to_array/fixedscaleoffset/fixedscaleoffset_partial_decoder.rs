@@ -0,0 +1,137 @@
+use std::sync::Arc;
+
+use crate::{
+    array::{
+        codec::{ArrayBytes, ArrayPartialDecoderTraits, CodecError, CodecOptions},
+        DataType,
+    },
+    array_subset::ArraySubset,
+};
+
+#[cfg(feature = "async")]
+use crate::array::codec::AsyncArrayPartialDecoderTraits;
+
+use super::{fixedscaleoffset_decode, validate_data_types};
+
+/// Partial decoder for the `fixedscaleoffset` codec.
+pub(crate) struct FixedScaleOffsetPartialDecoder {
+    input_handle: Arc<dyn ArrayPartialDecoderTraits>,
+    data_type: DataType,
+    astype: DataType,
+    offset: f64,
+    scale: f64,
+}
+
+impl FixedScaleOffsetPartialDecoder {
+    /// Create a new partial decoder for the `fixedscaleoffset` codec.
+    pub(crate) fn new(
+        input_handle: Arc<dyn ArrayPartialDecoderTraits>,
+        data_type: DataType,
+        astype: DataType,
+        offset: f64,
+        scale: f64,
+    ) -> Result<Self, CodecError> {
+        validate_data_types(&data_type, &astype)?;
+        Ok(Self {
+            input_handle,
+            data_type,
+            astype,
+            offset,
+            scale,
+        })
+    }
+}
+
+impl ArrayPartialDecoderTraits for FixedScaleOffsetPartialDecoder {
+    fn data_type(&self) -> &DataType {
+        &self.data_type
+    }
+
+    fn partial_decode(
+        &self,
+        array_subsets: &[ArraySubset],
+        options: &CodecOptions,
+    ) -> Result<Vec<ArrayBytes<'_>>, CodecError> {
+        let bytes = self.input_handle.partial_decode(array_subsets, options)?;
+
+        let mut bytes_out = Vec::with_capacity(bytes.len());
+        for bytes in bytes {
+            let bytes = bytes.into_fixed()?;
+            let decoded = fixedscaleoffset_decode(
+                &bytes,
+                &self.data_type,
+                &self.astype,
+                self.offset,
+                self.scale,
+            )?;
+            bytes_out.push(ArrayBytes::from(decoded));
+        }
+
+        Ok(bytes_out)
+    }
+}
+
+#[cfg(feature = "async")]
+/// Asynchronous partial decoder for the `fixedscaleoffset` codec.
+pub(crate) struct AsyncFixedScaleOffsetPartialDecoder {
+    input_handle: Arc<dyn AsyncArrayPartialDecoderTraits>,
+    data_type: DataType,
+    astype: DataType,
+    offset: f64,
+    scale: f64,
+}
+
+#[cfg(feature = "async")]
+impl AsyncFixedScaleOffsetPartialDecoder {
+    /// Create a new partial decoder for the `fixedscaleoffset` codec.
+    pub(crate) fn new(
+        input_handle: Arc<dyn AsyncArrayPartialDecoderTraits>,
+        data_type: DataType,
+        astype: DataType,
+        offset: f64,
+        scale: f64,
+    ) -> Result<Self, CodecError> {
+        validate_data_types(&data_type, &astype)?;
+        Ok(Self {
+            input_handle,
+            data_type,
+            astype,
+            offset,
+            scale,
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl AsyncArrayPartialDecoderTraits for AsyncFixedScaleOffsetPartialDecoder {
+    fn data_type(&self) -> &DataType {
+        &self.data_type
+    }
+
+    async fn partial_decode(
+        &self,
+        array_subsets: &[ArraySubset],
+        options: &CodecOptions,
+    ) -> Result<Vec<ArrayBytes<'_>>, CodecError> {
+        let bytes = self
+            .input_handle
+            .partial_decode(array_subsets, options)
+            .await?;
+
+        let mut bytes_out = Vec::with_capacity(bytes.len());
+        for bytes in bytes {
+            let bytes = bytes.into_fixed()?;
+            let decoded = fixedscaleoffset_decode(
+                &bytes,
+                &self.data_type,
+                &self.astype,
+                self.offset,
+                self.scale,
+            )?;
+            bytes_out.push(ArrayBytes::from(decoded));
+        }
+
+        Ok(bytes_out)
+    }
+}
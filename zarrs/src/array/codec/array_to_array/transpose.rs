@@ -2,10 +2,15 @@
 //!
 //! Permutes the dimensions of arrays.
 //!
+//! Partial encoding transposes the updated chunk subsets and passes them to the next codec, so the chunk is only entirely re-encoded if that codec does not support partial encoding.
+//!
+//! [`transpose_order_innermost`] and [`transpose_order_for_shuffle`] can be used to choose an order that improves the compression ratio of downstream codecs.
+//!
 //! See <https://zarr-specs.readthedocs.io/en/latest/v3/codecs/transpose/v1.0.html>.
 
 mod transpose_codec;
 mod transpose_partial_decoder;
+mod transpose_partial_encoder;
 
 use std::sync::Arc;
 
@@ -18,9 +23,10 @@ pub use transpose_codec::TransposeCodec;
 use crate::{
     array::{
         array_bytes::RawBytesOffsets,
-        codec::{Codec, CodecPlugin},
-        ArrayBytes, RawBytes,
+        codec::{Codec, CodecError, CodecPlugin},
+        ArrayBytes, ChunkRepresentation, RawBytes,
     },
+    array_subset::ArraySubset,
     metadata::v3::{array::codec::transpose, MetadataV3},
     plugin::{PluginCreateError, PluginMetadataInvalidError},
 };
@@ -44,6 +50,92 @@ pub(crate) fn create_codec_transpose(metadata: &MetadataV3) -> Result<Codec, Plu
     Ok(Codec::ArrayToArray(codec))
 }
 
+/// Return a transpose order that moves dimension `axis` to the innermost (fastest varying) dimension.
+///
+/// The relative order of the other dimensions is retained.
+/// Making the dimension along which elements are most similar the innermost dimension typically improves the compression ratio of a downstream `shuffle`, `bitshuffle`, or `blosc` codec.
+///
+/// # Errors
+/// Returns an [`InvalidPermutationError`] if `axis` is not less than `dimensionality`.
+pub fn transpose_order_innermost(
+    dimensionality: usize,
+    axis: usize,
+) -> Result<TransposeOrder, InvalidPermutationError> {
+    let order: Vec<usize> = (0..dimensionality)
+        .filter(|&i| i != axis)
+        .chain(std::iter::once(axis))
+        .collect();
+    if axis < dimensionality {
+        TransposeOrder::new(&order)
+    } else {
+        Err(InvalidPermutationError::from(order))
+    }
+}
+
+/// Return a transpose order that orders the dimensions of a chunk from roughest (outermost) to smoothest (innermost).
+///
+/// The roughness of a dimension is the mean number of bits that differ between elements that are adjacent along that dimension.
+/// Similar elements are adjacent in the encoded chunk with this order, which typically improves the compression ratio of a downstream `shuffle`, `bitshuffle`, or `blosc` codec.
+/// Dimensions with equal roughness retain their relative order, and dimensions with a length of 1 are outermost.
+///
+/// `bytes` are the decoded bytes of a representative chunk with `decoded_representation`.
+///
+/// # Errors
+/// Returns a [`CodecError`] if the data type is not fixed size or the length of `bytes` is incompatible with `decoded_representation`.
+///
+/// # Panics
+/// Panics if a dimension of `decoded_representation` exceeds [`usize::MAX`].
+pub fn transpose_order_for_shuffle(
+    bytes: &[u8],
+    decoded_representation: &ChunkRepresentation,
+) -> Result<TransposeOrder, CodecError> {
+    let data_type = decoded_representation.data_type();
+    let element_size = data_type.fixed_size().ok_or_else(|| {
+        CodecError::UnsupportedDataType(data_type.clone(), IDENTIFIER.to_string())
+    })?;
+    let expected_size = decoded_representation.num_elements() * element_size as u64;
+    if bytes.len() as u64 != expected_size {
+        return Err(CodecError::UnexpectedChunkDecodedSize(
+            bytes.len(),
+            expected_size,
+        ));
+    }
+
+    // The number of differing bits and adjacent element pairs along each dimension
+    let shape = decoded_representation.shape_u64();
+    let mut roughness: Vec<(usize, u64, u64)> = Vec::with_capacity(shape.len());
+    let mut stride = element_size;
+    for (axis, &size) in shape.iter().enumerate().rev() {
+        let block_size = stride * usize::try_from(size).unwrap();
+        let mut differing_bits = 0;
+        let mut pairs = 0;
+        for block in bytes.chunks_exact(block_size) {
+            let elements = block[..block_size - stride].chunks_exact(element_size);
+            let elements_next = block[stride..].chunks_exact(element_size);
+            for (element, element_next) in std::iter::zip(elements, elements_next) {
+                differing_bits += std::iter::zip(element, element_next)
+                    .map(|(a, b)| u64::from((a ^ b).count_ones()))
+                    .sum::<u64>();
+                pairs += 1;
+            }
+        }
+        roughness.push((axis, differing_bits, pairs));
+        stride = block_size;
+    }
+    roughness.reverse();
+
+    roughness.sort_by(|(_, bits_a, pairs_a), (_, bits_b, pairs_b)| {
+        let roughness_a = u128::from(*bits_a) * u128::from(*pairs_b);
+        let roughness_b = u128::from(*bits_b) * u128::from(*pairs_a);
+        (*pairs_a != 0)
+            .cmp(&(*pairs_b != 0))
+            .then(roughness_b.cmp(&roughness_a))
+    });
+    Ok(TransposeOrder(
+        roughness.into_iter().map(|(axis, _, _)| axis).collect(),
+    ))
+}
+
 fn calculate_order_encode(order: &TransposeOrder, array_dimensions: usize) -> Vec<usize> {
     assert_eq!(order.0.len(), array_dimensions);
     let mut permutation_encode = Vec::<usize>::with_capacity(array_dimensions + 1);
@@ -89,6 +181,21 @@ fn transpose_array(
     }
 }
 
+fn get_decoded_regions_transposed(
+    order: &TransposeOrder,
+    decoded_regions: &[ArraySubset],
+) -> Vec<ArraySubset> {
+    let mut decoded_regions_transposed = Vec::with_capacity(decoded_regions.len());
+    for decoded_region in decoded_regions {
+        let start = permute(decoded_region.start(), &order.0);
+        let size = permute(decoded_region.shape(), &order.0);
+        let decoded_region_transpose =
+            unsafe { ArraySubset::new_with_start_shape_unchecked(start, size) };
+        decoded_regions_transposed.push(decoded_region_transpose);
+    }
+    decoded_regions_transposed
+}
+
 fn permute<T: Copy>(v: &[T], order: &[usize]) -> Vec<T> {
     let mut vec = Vec::<T>::with_capacity(v.len());
     for axis in order {
@@ -197,6 +304,51 @@ mod tests {
         codec_transpose_round_trip_impl(JSON, DataType::UInt16, FillValue::from(0u16));
     }
 
+    #[test]
+    fn codec_transpose_order_innermost() {
+        assert_eq!(
+            transpose_order_innermost(3, 0).unwrap(),
+            TransposeOrder::new(&[1, 2, 0]).unwrap()
+        );
+        assert_eq!(
+            transpose_order_innermost(3, 2).unwrap(),
+            TransposeOrder::new(&[0, 1, 2]).unwrap()
+        );
+        assert!(transpose_order_innermost(3, 3).is_err());
+    }
+
+    #[test]
+    fn codec_transpose_order_for_shuffle() {
+        let chunk_representation = ChunkRepresentation::new(
+            vec![
+                NonZeroU64::new(1).unwrap(),
+                NonZeroU64::new(4).unwrap(),
+                NonZeroU64::new(8).unwrap(),
+            ],
+            DataType::UInt16,
+            FillValue::from(0u16),
+        )
+        .unwrap();
+
+        // Smooth along dimension 1
+        let elements: Vec<u16> = (0..32u16)
+            .map(|i| i / 8 + (i % 8).wrapping_mul(0x9E37))
+            .collect();
+        let bytes = crate::array::transmute_to_bytes_vec(elements);
+        let order = transpose_order_for_shuffle(&bytes, &chunk_representation).unwrap();
+        assert_eq!(order, TransposeOrder::new(&[0, 2, 1]).unwrap());
+
+        // Smooth along dimension 2
+        let elements: Vec<u16> = (0..32u16)
+            .map(|i| i % 8 + (i / 8).wrapping_mul(0x9E37))
+            .collect();
+        let bytes = crate::array::transmute_to_bytes_vec(elements);
+        let order = transpose_order_for_shuffle(&bytes, &chunk_representation).unwrap();
+        assert_eq!(order, TransposeOrder::new(&[0, 1, 2]).unwrap());
+
+        assert!(transpose_order_for_shuffle(&bytes[1..], &chunk_representation).is_err());
+    }
+
     #[test]
     fn codec_transpose_partial_decode() {
         let codec = Arc::new(TransposeCodec::new(TransposeOrder::new(&[1, 0]).unwrap()));
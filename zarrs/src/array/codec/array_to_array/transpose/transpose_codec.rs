@@ -4,8 +4,8 @@ use crate::{
     array::{
         codec::{
             options::CodecOptions, ArrayBytes, ArrayCodecTraits, ArrayPartialDecoderTraits,
-            ArrayPartialEncoderTraits, ArrayToArrayCodecTraits, ArrayToArrayPartialEncoderDefault,
-            CodecError, CodecTraits, RecommendedConcurrency,
+            ArrayPartialEncoderTraits, ArrayToArrayCodecTraits, CodecError, CodecTraits,
+            RecommendedConcurrency,
         },
         ArrayMetadataOptions, ChunkRepresentation, ChunkShape,
     },
@@ -199,17 +199,18 @@ impl ArrayToArrayCodecTraits for TransposeCodec {
 
     fn partial_encoder(
         self: Arc<Self>,
-        input_handle: Arc<dyn ArrayPartialDecoderTraits>,
+        _input_handle: Arc<dyn ArrayPartialDecoderTraits>,
         output_handle: Arc<dyn ArrayPartialEncoderTraits>,
         decoded_representation: &ChunkRepresentation,
         _options: &CodecOptions,
     ) -> Result<Arc<dyn ArrayPartialEncoderTraits>, CodecError> {
-        Ok(Arc::new(ArrayToArrayPartialEncoderDefault::new(
-            input_handle,
-            output_handle,
-            decoded_representation.clone(),
-            self,
-        )))
+        Ok(Arc::new(
+            super::transpose_partial_encoder::TransposePartialEncoder::new(
+                output_handle,
+                decoded_representation.clone(),
+                self.order.clone(),
+            ),
+        ))
     }
 
     fn compute_encoded_size(
@@ -1,6 +1,9 @@
 use std::sync::Arc;
 
-use super::{calculate_order_decode, permute, transpose_array, TransposeOrder};
+use super::{
+    calculate_order_decode, get_decoded_regions_transposed, permute, transpose_array,
+    TransposeOrder,
+};
 use crate::array::{
     codec::{ArrayBytes, ArrayPartialDecoderTraits, ArraySubset, CodecError, CodecOptions},
     ChunkRepresentation, DataType,
@@ -46,21 +49,6 @@ fn validate_regions(
     Ok(())
 }
 
-fn get_decoded_regions_transposed(
-    order: &TransposeOrder,
-    decoded_regions: &[ArraySubset],
-) -> Vec<ArraySubset> {
-    let mut decoded_regions_transposed = Vec::with_capacity(decoded_regions.len());
-    for decoded_region in decoded_regions {
-        let start = permute(decoded_region.start(), &order.0);
-        let size = permute(decoded_region.shape(), &order.0);
-        let decoded_region_transpose =
-            unsafe { ArraySubset::new_with_start_shape_unchecked(start, size) };
-        decoded_regions_transposed.push(decoded_region_transpose);
-    }
-    decoded_regions_transposed
-}
-
 /// Reverse the transpose on each subset
 fn do_transpose<'a>(
     encoded_value: Vec<ArrayBytes<'a>>,
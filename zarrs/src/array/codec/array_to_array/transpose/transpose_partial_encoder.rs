@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use super::{
+    calculate_order_encode, get_decoded_regions_transposed, transpose_array, transpose_vlen,
+    TransposeOrder,
+};
+use crate::{
+    array::{
+        codec::{ArrayBytes, ArrayPartialEncoderTraits, ArraySubset, CodecError, CodecOptions},
+        ChunkRepresentation,
+    },
+    array_subset::IncompatibleArraySubsetAndShapeError,
+};
+
+/// Partial encoder for the Transpose codec.
+///
+/// Transposes each chunk subset and its bytes, and passes them to the partial encoder of the next codec.
+/// The entire chunk is only re-encoded if the next codec does not support partial encoding.
+pub(crate) struct TransposePartialEncoder {
+    output_handle: Arc<dyn ArrayPartialEncoderTraits>,
+    decoded_representation: ChunkRepresentation,
+    order: TransposeOrder,
+}
+
+impl TransposePartialEncoder {
+    /// Create a new partial encoder for the Transpose codec.
+    pub(crate) fn new(
+        output_handle: Arc<dyn ArrayPartialEncoderTraits>,
+        decoded_representation: ChunkRepresentation,
+        order: TransposeOrder,
+    ) -> Self {
+        Self {
+            output_handle,
+            decoded_representation,
+            order,
+        }
+    }
+}
+
+/// Transpose the `bytes` of `chunk_subset`.
+fn transpose_subset_bytes<'a>(
+    bytes: &ArrayBytes<'_>,
+    chunk_subset: &ArraySubset,
+    order: &TransposeOrder,
+    decoded_representation: &ChunkRepresentation,
+) -> Result<ArrayBytes<'a>, CodecError> {
+    match bytes {
+        ArrayBytes::Variable(bytes, offsets) => Ok(transpose_vlen(
+            bytes,
+            offsets,
+            &chunk_subset.shape_usize(),
+            order.0.clone(),
+        )),
+        ArrayBytes::Fixed(bytes) => {
+            let order_encode = calculate_order_encode(order, chunk_subset.dimensionality());
+            let data_type_size = decoded_representation.data_type().fixed_size().unwrap();
+            let bytes = transpose_array(&order_encode, chunk_subset.shape(), data_type_size, bytes)
+                .map_err(|_| CodecError::Other("transpose_array error".to_string()))?;
+            Ok(ArrayBytes::from(bytes))
+        }
+    }
+}
+
+impl ArrayPartialEncoderTraits for TransposePartialEncoder {
+    fn erase(&self) -> Result<(), CodecError> {
+        self.output_handle.erase()
+    }
+
+    fn partial_encode(
+        &self,
+        subsets_and_bytes: &[(&ArraySubset, ArrayBytes<'_>)],
+        options: &CodecOptions,
+    ) -> Result<(), CodecError> {
+        let chunk_shape = self.decoded_representation.shape_u64();
+        let data_type_size = self.decoded_representation.data_type().size();
+
+        let mut chunk_subsets = Vec::with_capacity(subsets_and_bytes.len());
+        let mut chunk_subsets_bytes = Vec::with_capacity(subsets_and_bytes.len());
+        for (chunk_subset, chunk_subset_bytes) in subsets_and_bytes {
+            // Check the subset is within the chunk shape
+            if chunk_subset.dimensionality() != chunk_shape.len()
+                || chunk_subset
+                    .end_exc()
+                    .iter()
+                    .zip(&chunk_shape)
+                    .any(|(a, b)| a > b)
+            {
+                return Err(CodecError::InvalidArraySubsetError(
+                    IncompatibleArraySubsetAndShapeError::new((*chunk_subset).clone(), chunk_shape),
+                ));
+            }
+            chunk_subset_bytes.validate(chunk_subset.num_elements(), data_type_size)?;
+
+            chunk_subsets.push((*chunk_subset).clone());
+            chunk_subsets_bytes.push(transpose_subset_bytes(
+                chunk_subset_bytes,
+                chunk_subset,
+                &self.order,
+                &self.decoded_representation,
+            )?);
+        }
+
+        // Encode the transposed subsets of the transposed chunk
+        let chunk_subsets_transposed = get_decoded_regions_transposed(&self.order, &chunk_subsets);
+        let subsets_and_bytes_transposed: Vec<(&ArraySubset, ArrayBytes<'_>)> =
+            std::iter::zip(&chunk_subsets_transposed, chunk_subsets_bytes).collect();
+        self.output_handle
+            .partial_encode(&subsets_and_bytes_transposed, options)
+    }
+}
@@ -5,9 +5,21 @@ pub mod codec_chain;
 pub mod vlen;
 pub mod vlen_v2;
 
+#[cfg(feature = "blosc2")]
+pub mod blosc2;
+#[cfg(feature = "jpegxl")]
+pub mod jpegxl;
+#[cfg(feature = "packbits")]
+pub mod packbits;
 #[cfg(feature = "pcodec")]
 pub mod pcodec;
+#[cfg(feature = "png")]
+pub mod png;
+#[cfg(feature = "rle")]
+pub mod rle;
 #[cfg(feature = "sharding")]
 pub mod sharding;
+#[cfg(feature = "sz3")]
+pub mod sz3;
 #[cfg(feature = "zfp")]
 pub mod zfp;
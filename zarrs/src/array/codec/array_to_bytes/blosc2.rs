@@ -0,0 +1,653 @@
+//! The `blosc2` array to bytes codec.
+//!
+//! It uses the [blosc2](https://www.blosc.org/) `b2nd` N-dimensional array format.
+//! Each chunk is encoded as a contiguous `b2nd` frame with a single `blosc2` chunk, which is divided into independently compressed N-dimensional blocks.
+//! Elements are encoded in little endian byte order.
+//! All fixed size data types are supported.
+//!
+//! Partial decoding only decompresses the blocks intersecting the requested regions, unlike the `blosc` bytes to bytes codec which must decompress whole chunks for non-contiguous regions.
+//!
+//! If the `blockshape` is unspecified, the block shape is the chunk shape with the leading dimensions reduced so that a block is near 256 KiB.
+//!
+//! <div class="warning">
+//! This codec is experimental and may be incompatible with other Zarr V3 implementations.
+//! </div>
+//!
+//! This codec requires the `blosc2` feature, which is disabled by default.
+//!
+//! See [`Blosc2CodecConfigurationV1`] for example `JSON` metadata.
+
+mod blosc2_codec;
+mod blosc2_partial_decoder;
+
+use std::{
+    ffi::{c_char, c_void},
+    sync::{Arc, Once},
+};
+
+pub use crate::metadata::v3::array::codec::blosc2::{
+    Blosc2CodecConfiguration, Blosc2CodecConfigurationV1, Blosc2Compressor, BloscCompressionLevel,
+    BloscShuffleMode,
+};
+pub use blosc2_codec::Blosc2Codec;
+
+use blosc2_sys::{
+    b2nd_array_t, b2nd_context_t, b2nd_create_ctx, b2nd_free, b2nd_free_ctx, b2nd_from_cbuffer,
+    b2nd_from_cframe, b2nd_get_slice_cbuffer, b2nd_to_cbuffer, b2nd_to_cframe, blosc2_init,
+    blosc2_storage, B2ND_MAX_DIM, BLOSC2_CPARAMS_DEFAULTS, BLOSC2_DPARAMS_DEFAULTS,
+    BLOSC2_MAX_FILTERS, BLOSC2_STORAGE_DEFAULTS,
+};
+
+use crate::{
+    array::{
+        codec::{Codec, CodecError, CodecPlugin},
+        DataType, RawBytes,
+    },
+    array_subset::ArraySubset,
+    config::global_config,
+    metadata::v3::{array::codec::blosc2, MetadataV3},
+    plugin::{PluginCreateError, PluginMetadataInvalidError},
+};
+
+pub use blosc2::IDENTIFIER;
+
+// Register the codec.
+inventory::submit! {
+    CodecPlugin::new(IDENTIFIER, is_name_blosc2, create_codec_blosc2)
+}
+
+fn is_name_blosc2(name: &str) -> bool {
+    name.eq(IDENTIFIER)
+        || name
+            == global_config()
+                .experimental_codec_names()
+                .get(IDENTIFIER)
+                .expect("experimental codec identifier in global map")
+}
+
+pub(crate) fn create_codec_blosc2(metadata: &MetadataV3) -> Result<Codec, PluginCreateError> {
+    let configuration: Blosc2CodecConfiguration = metadata
+        .to_configuration()
+        .map_err(|_| PluginMetadataInvalidError::new(IDENTIFIER, "codec", metadata.clone()))?;
+    let codec = Arc::new(Blosc2Codec::new_with_configuration(&configuration));
+    Ok(Codec::ArrayToBytes(codec))
+}
+
+/// The target size of an automatically determined block in bytes.
+const BLOCK_SIZE_TARGET: u64 = 256 * 1024;
+
+static BLOSC2_INIT: Once = Once::new();
+
+/// Initialise the `blosc2` library, which registers its codecs and filters.
+fn blosc2_init_once() {
+    BLOSC2_INIT.call_once(|| unsafe { blosc2_init() });
+}
+
+#[allow(clippy::cast_possible_truncation)]
+const fn compressor_as_compcode(compressor: Blosc2Compressor) -> u8 {
+    match compressor {
+        Blosc2Compressor::BloscLZ => blosc2_sys::BLOSC_BLOSCLZ as u8,
+        Blosc2Compressor::LZ4 => blosc2_sys::BLOSC_LZ4 as u8,
+        Blosc2Compressor::LZ4HC => blosc2_sys::BLOSC_LZ4HC as u8,
+        Blosc2Compressor::Zlib => blosc2_sys::BLOSC_ZLIB as u8,
+        Blosc2Compressor::Zstd => blosc2_sys::BLOSC_ZSTD as u8,
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+const fn shuffle_mode_as_filter(shuffle_mode: BloscShuffleMode) -> u8 {
+    match shuffle_mode {
+        BloscShuffleMode::NoShuffle => blosc2_sys::BLOSC_NOSHUFFLE as u8,
+        BloscShuffleMode::Shuffle => blosc2_sys::BLOSC_SHUFFLE as u8,
+        BloscShuffleMode::BitShuffle => blosc2_sys::BLOSC_BITSHUFFLE as u8,
+    }
+}
+
+/// Return the size of an element of `data_type` if it is supported by the `blosc2` codec.
+fn blosc2_typesize(data_type: &DataType) -> Result<usize, CodecError> {
+    data_type
+        .fixed_size()
+        .ok_or_else(|| CodecError::UnsupportedDataType(data_type.clone(), IDENTIFIER.to_string()))
+}
+
+/// Return the block shape of a chunk with `chunk_shape` and elements of `typesize` bytes.
+///
+/// Uses `blockshape` if specified, otherwise the innermost dimensions are kept whole and the outer dimensions are reduced until a block is near [`BLOCK_SIZE_TARGET`].
+fn blosc2_blockshape(
+    blockshape: Option<&[u64]>,
+    chunk_shape: &[u64],
+    typesize: usize,
+) -> Result<Vec<u64>, CodecError> {
+    if let Some(blockshape) = blockshape {
+        if blockshape.len() != chunk_shape.len()
+            || std::iter::zip(blockshape, chunk_shape).any(|(block, chunk)| block > chunk)
+        {
+            return Err(CodecError::Other(format!(
+                "blosc2 block shape {blockshape:?} is incompatible with the chunk shape {chunk_shape:?}"
+            )));
+        }
+        return Ok(blockshape.to_vec());
+    }
+
+    let mut block_size = typesize as u64;
+    let mut blockshape = vec![1; chunk_shape.len()];
+    for (block, &chunk) in std::iter::zip(&mut blockshape, chunk_shape).rev() {
+        *block = (BLOCK_SIZE_TARGET / block_size).clamp(1, chunk);
+        block_size *= *block;
+        if *block < chunk {
+            break;
+        }
+    }
+    Ok(blockshape)
+}
+
+/// Convert the bytes of elements of `data_type` between the native and little endian byte order.
+fn swap_endianness_le<'a>(mut bytes: RawBytes<'a>, data_type: &DataType) -> RawBytes<'a> {
+    if cfg!(target_endian = "big") {
+        super::bytes::reverse_endianness(bytes.to_mut(), data_type);
+    }
+    bytes
+}
+
+/// Convert `values` to the [`i64`] values expected by `b2nd`.
+fn to_i64(values: &[u64]) -> Result<Vec<i64>, CodecError> {
+    values
+        .iter()
+        .map(|&value| i64::try_from(value))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| CodecError::Other("blosc2 shape exceeds i64::MAX".to_string()))
+}
+
+/// Convert `values` to the [`i32`] values expected by `b2nd`.
+fn to_i32(values: &[u64]) -> Result<Vec<i32>, CodecError> {
+    values
+        .iter()
+        .map(|&value| i32::try_from(value))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| CodecError::Other("blosc2 chunk shape exceeds i32::MAX".to_string()))
+}
+
+/// A `b2nd` array context, which is freed on drop.
+struct B2ndContext(*mut b2nd_context_t);
+
+impl Drop for B2ndContext {
+    fn drop(&mut self) {
+        unsafe {
+            b2nd_free_ctx(self.0);
+        }
+    }
+}
+
+/// A `b2nd` array, which is freed on drop.
+///
+/// The array may reference the encoded bytes it was created from.
+struct B2ndArray<'a> {
+    array: *mut b2nd_array_t,
+    _encoded: std::marker::PhantomData<&'a [u8]>,
+}
+
+impl Drop for B2ndArray<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            b2nd_free(self.array);
+        }
+    }
+}
+
+impl<'a> B2ndArray<'a> {
+    /// Create a `b2nd` array from an encoded contiguous frame of a chunk with `chunk_shape` and elements of `typesize` bytes.
+    fn from_cframe(
+        encoded_value: &'a [u8],
+        chunk_shape: &[u64],
+        typesize: usize,
+    ) -> Result<Self, CodecError> {
+        blosc2_init_once();
+        let mut array: *mut b2nd_array_t = std::ptr::null_mut();
+        // The frame is not copied, and it is only read by blosc2
+        let rc = unsafe {
+            b2nd_from_cframe(
+                encoded_value.as_ptr().cast_mut(),
+                i64::try_from(encoded_value.len()).unwrap(),
+                false,
+                std::ptr::addr_of_mut!(array),
+            )
+        };
+        if rc < 0 || array.is_null() {
+            return Err(CodecError::Other(format!(
+                "b2nd_from_cframe failed with error code {rc}"
+            )));
+        }
+        let array = Self {
+            array,
+            _encoded: std::marker::PhantomData,
+        };
+
+        // Validate the array against the chunk representation
+        let (ndim, shape, array_typesize) = unsafe {
+            let array = &*array.array;
+            (array.ndim, array.shape, (*array.sc).typesize)
+        };
+        let shape_matches = usize::try_from(ndim).is_ok_and(|ndim| {
+            ndim == chunk_shape.len()
+                && std::iter::zip(&shape[..ndim], chunk_shape)
+                    .all(|(&a, &b)| u64::try_from(a) == Ok(b))
+        });
+        if !shape_matches || usize::try_from(array_typesize) != Ok(typesize) {
+            return Err(CodecError::Other(
+                "blosc2 encoded array is incompatible with the chunk representation".to_string(),
+            ));
+        }
+        Ok(array)
+    }
+
+    /// Decode the entire array, which has `size` bytes.
+    fn decode(&self, size: usize) -> Result<Vec<u8>, CodecError> {
+        let mut decoded_value: Vec<u8> = Vec::with_capacity(size);
+        let rc = unsafe {
+            b2nd_to_cbuffer(
+                self.array,
+                decoded_value.as_mut_ptr().cast::<c_void>(),
+                i64::try_from(size).unwrap(),
+            )
+        };
+        if rc < 0 {
+            return Err(CodecError::Other(format!(
+                "b2nd_to_cbuffer failed with error code {rc}"
+            )));
+        }
+        unsafe { decoded_value.set_len(size) };
+        Ok(decoded_value)
+    }
+
+    /// Decode `array_subset` of the array, only decompressing the blocks it intersects.
+    fn decode_subset(
+        &self,
+        array_subset: &ArraySubset,
+        typesize: usize,
+    ) -> Result<Vec<u8>, CodecError> {
+        let start = to_i64(array_subset.start())?;
+        let stop = to_i64(&array_subset.end_exc())?;
+        let shape = to_i64(array_subset.shape())?;
+        let size = array_subset.num_elements_usize() * typesize;
+        if size == 0 {
+            return Ok(vec![]);
+        }
+        let mut decoded_value: Vec<u8> = Vec::with_capacity(size);
+        let rc = unsafe {
+            b2nd_get_slice_cbuffer(
+                self.array,
+                start.as_ptr(),
+                stop.as_ptr(),
+                decoded_value.as_mut_ptr().cast::<c_void>(),
+                shape.as_ptr(),
+                i64::try_from(size).unwrap(),
+            )
+        };
+        if rc < 0 {
+            return Err(CodecError::Other(format!(
+                "b2nd_get_slice_cbuffer failed with error code {rc}"
+            )));
+        }
+        unsafe { decoded_value.set_len(size) };
+        Ok(decoded_value)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn blosc2_encode(
+    decoded_value: &[u8],
+    chunk_shape: &[u64],
+    blockshape: &[u64],
+    typesize: usize,
+    compressor: Blosc2Compressor,
+    clevel: BloscCompressionLevel,
+    shuffle_mode: BloscShuffleMode,
+    nthreads: usize,
+) -> Result<Vec<u8>, CodecError> {
+    blosc2_init_once();
+    if chunk_shape.len() > B2ND_MAX_DIM as usize {
+        return Err(CodecError::Other(format!(
+            "blosc2 supports at most {B2ND_MAX_DIM} dimensions"
+        )));
+    }
+    let shape = to_i64(chunk_shape)?;
+    let chunkshape = to_i32(chunk_shape)?;
+    let blockshape = to_i32(blockshape)?;
+
+    let mut cparams = unsafe { BLOSC2_CPARAMS_DEFAULTS };
+    cparams.compcode = compressor_as_compcode(compressor);
+    cparams.clevel = clevel.into();
+    cparams.typesize = i32::try_from(typesize)
+        .map_err(|_| CodecError::Other("blosc2 typesize exceeds i32::MAX".to_string()))?;
+    cparams.nthreads = i16::try_from(nthreads).unwrap_or(i16::MAX);
+    // The last filter is the shuffle filter
+    cparams.filters[BLOSC2_MAX_FILTERS as usize - 1] = shuffle_mode_as_filter(shuffle_mode);
+    let mut dparams = unsafe { BLOSC2_DPARAMS_DEFAULTS };
+    let mut storage: blosc2_storage = unsafe { BLOSC2_STORAGE_DEFAULTS };
+    storage.contiguous = true;
+    storage.cparams = std::ptr::addr_of_mut!(cparams);
+    storage.dparams = std::ptr::addr_of_mut!(dparams);
+
+    let context = unsafe {
+        b2nd_create_ctx(
+            std::ptr::addr_of!(storage),
+            i8::try_from(shape.len()).unwrap(),
+            shape.as_ptr(),
+            chunkshape.as_ptr(),
+            blockshape.as_ptr(),
+            std::ptr::null_mut::<c_char>(),
+            0,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    if context.is_null() {
+        return Err(CodecError::Other("b2nd_create_ctx failed".to_string()));
+    }
+    let context = B2ndContext(context);
+
+    let mut array: *mut b2nd_array_t = std::ptr::null_mut();
+    let rc = unsafe {
+        b2nd_from_cbuffer(
+            context.0,
+            std::ptr::addr_of_mut!(array),
+            decoded_value.as_ptr().cast::<c_void>(),
+            i64::try_from(decoded_value.len()).unwrap(),
+        )
+    };
+    if rc < 0 || array.is_null() {
+        return Err(CodecError::Other(format!(
+            "b2nd_from_cbuffer failed with error code {rc}"
+        )));
+    }
+    let array = B2ndArray {
+        array,
+        _encoded: std::marker::PhantomData,
+    };
+
+    let mut cframe: *mut u8 = std::ptr::null_mut();
+    let mut cframe_len: i64 = 0;
+    let mut needs_free = false;
+    let rc = unsafe {
+        b2nd_to_cframe(
+            array.array,
+            std::ptr::addr_of_mut!(cframe),
+            std::ptr::addr_of_mut!(cframe_len),
+            std::ptr::addr_of_mut!(needs_free),
+        )
+    };
+    if rc < 0 || cframe.is_null() {
+        return Err(CodecError::Other(format!(
+            "b2nd_to_cframe failed with error code {rc}"
+        )));
+    }
+    let encoded_value = unsafe {
+        std::slice::from_raw_parts(cframe, usize::try_from(cframe_len).unwrap()).to_vec()
+    };
+    if needs_free {
+        unsafe { libc::free(cframe.cast::<c_void>()) };
+    }
+    Ok(encoded_value)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroU64, sync::Arc};
+
+    use crate::{
+        array::{
+            codec::{ArrayToBytesCodecTraits, CodecOptions, CodecTraits},
+            ArrayBytes, ChunkRepresentation, DataType, Element, ElementOwned, FillValue,
+        },
+        array_subset::ArraySubset,
+    };
+
+    use super::*;
+
+    const JSON_VALID: &str = r#"{
+        "cname": "zstd",
+        "clevel": 5,
+        "shuffle": "shuffle",
+        "blockshape": [2, 2, 3]
+    }"#;
+
+    fn chunk_representation(data_type: DataType, fill_value: FillValue) -> ChunkRepresentation {
+        ChunkRepresentation::new(
+            vec![
+                NonZeroU64::new(6).unwrap(),
+                NonZeroU64::new(5).unwrap(),
+                NonZeroU64::new(7).unwrap(),
+            ],
+            data_type,
+            fill_value,
+        )
+        .unwrap()
+    }
+
+    fn elements(num_elements: u64) -> Vec<u16> {
+        (0..num_elements)
+            .map(|i| u16::try_from(i % 100).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn codec_blosc2_configuration() {
+        let configuration: Blosc2CodecConfiguration = serde_json::from_str(JSON_VALID).unwrap();
+        let codec = Blosc2Codec::new_with_configuration(&configuration);
+        let metadata = codec.create_metadata().unwrap();
+        assert_eq!(
+            metadata
+                .to_configuration::<Blosc2CodecConfiguration>()
+                .unwrap(),
+            configuration
+        );
+    }
+
+    #[test]
+    fn codec_blosc2_blockshape() {
+        assert_eq!(
+            blosc2_blockshape(None, &[1000, 1000, 1000], 4).unwrap(),
+            vec![1, 65, 1000]
+        );
+        assert_eq!(blosc2_blockshape(None, &[10, 10], 8).unwrap(), vec![10, 10]);
+        assert_eq!(
+            blosc2_blockshape(Some(&[2, 3]), &[10, 10], 8).unwrap(),
+            vec![2, 3]
+        );
+        assert!(blosc2_blockshape(Some(&[2, 11]), &[10, 10], 8).is_err());
+        assert!(blosc2_blockshape(Some(&[2]), &[10, 10], 8).is_err());
+    }
+
+    #[test]
+    fn codec_blosc2_round_trip() {
+        let chunk_representation = chunk_representation(DataType::UInt16, FillValue::from(0u16));
+        let elements = elements(chunk_representation.num_elements());
+        let bytes = u16::into_array_bytes(&DataType::UInt16, &elements).unwrap();
+
+        let codec = Blosc2Codec::new_with_configuration(&serde_json::from_str(JSON_VALID).unwrap());
+        let encoded = codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let decoded = codec
+            .decode(encoded, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        assert_eq!(
+            elements,
+            u16::from_array_bytes(&DataType::UInt16, decoded).unwrap()
+        );
+    }
+
+    #[test]
+    fn codec_blosc2_round_trip_automatic_blockshape() {
+        let chunk_representation = chunk_representation(DataType::Float64, FillValue::from(0f64));
+        let elements: Vec<f64> = (0..chunk_representation.num_elements())
+            .map(|i| f64::from(u32::try_from(i).unwrap()) / 3.0)
+            .collect();
+        let bytes = f64::into_array_bytes(&DataType::Float64, &elements).unwrap();
+
+        let codec = Blosc2Codec::new(
+            Blosc2Compressor::LZ4,
+            BloscCompressionLevel::try_from(9u8).unwrap(),
+            BloscShuffleMode::BitShuffle,
+            None,
+        );
+        let encoded = codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let decoded = codec
+            .decode(encoded, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        assert_eq!(
+            elements,
+            f64::from_array_bytes(&DataType::Float64, decoded).unwrap()
+        );
+    }
+
+    #[test]
+    fn codec_blosc2_invalid() {
+        let chunk_representation = chunk_representation(DataType::UInt16, FillValue::from(0u16));
+        let codec = Blosc2Codec::new_with_configuration(&serde_json::from_str(JSON_VALID).unwrap());
+        assert!(codec
+            .decode(
+                vec![0; 64].into(),
+                &chunk_representation,
+                &CodecOptions::default()
+            )
+            .is_err());
+
+        // the encoded array shape does not match the chunk shape
+        let elements = elements(chunk_representation.num_elements());
+        let bytes = u16::into_array_bytes(&DataType::UInt16, &elements).unwrap();
+        let encoded = codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let chunk_representation_other = ChunkRepresentation::new(
+            vec![
+                NonZeroU64::new(5).unwrap(),
+                NonZeroU64::new(6).unwrap(),
+                NonZeroU64::new(7).unwrap(),
+            ],
+            DataType::UInt16,
+            FillValue::from(0u16),
+        )
+        .unwrap();
+        assert!(codec
+            .decode(
+                encoded,
+                &chunk_representation_other,
+                &CodecOptions::default()
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn codec_blosc2_unsupported_data_type() {
+        let chunk_representation = chunk_representation(DataType::String, FillValue::from(""));
+        let codec = Blosc2Codec::new_with_configuration(&serde_json::from_str(JSON_VALID).unwrap());
+        assert!(codec.compute_encoded_size(&chunk_representation).is_err());
+    }
+
+    #[test]
+    fn codec_blosc2_partial_decode() {
+        let chunk_representation = chunk_representation(DataType::UInt16, FillValue::from(0u16));
+        let elements = elements(chunk_representation.num_elements());
+        let bytes = u16::into_array_bytes(&DataType::UInt16, &elements)
+            .unwrap()
+            .into_owned();
+
+        let codec = Arc::new(Blosc2Codec::new_with_configuration(
+            &serde_json::from_str(JSON_VALID).unwrap(),
+        ));
+        let encoded = codec
+            .encode(
+                bytes.clone(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+
+        let decoded_regions = [
+            ArraySubset::new_with_ranges(&[1..3, 0..5, 2..3]),
+            ArraySubset::new_with_ranges(&[5..6, 4..5, 0..7]),
+            ArraySubset::new_with_ranges(&[0..6, 0..5, 0..7]),
+        ];
+        let input_handle = Arc::new(std::io::Cursor::new(encoded));
+        let partial_decoder = codec
+            .partial_decoder(
+                input_handle,
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        let decoded_partial_chunk = partial_decoder
+            .partial_decode(&decoded_regions, &CodecOptions::default())
+            .unwrap();
+        for (decoded_region, decoded_partial_chunk) in
+            decoded_regions.iter().zip(decoded_partial_chunk)
+        {
+            assert_eq!(
+                bytes
+                    .extract_array_subset(
+                        decoded_region,
+                        &chunk_representation.shape_u64(),
+                        chunk_representation.data_type()
+                    )
+                    .unwrap(),
+                decoded_partial_chunk
+            );
+        }
+
+        assert!(partial_decoder
+            .partial_decode(
+                &[ArraySubset::new_with_ranges(&[5..7, 0..5, 0..7])],
+                &CodecOptions::default()
+            )
+            .is_err());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn codec_blosc2_async_partial_decode() {
+        let chunk_representation = chunk_representation(DataType::UInt16, FillValue::from(0u16));
+        let elements = elements(chunk_representation.num_elements());
+        let bytes = u16::into_array_bytes(&DataType::UInt16, &elements)
+            .unwrap()
+            .into_owned();
+
+        let codec = Arc::new(Blosc2Codec::new_with_configuration(
+            &serde_json::from_str(JSON_VALID).unwrap(),
+        ));
+        let encoded = codec
+            .encode(
+                bytes.clone(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+
+        let decoded_regions = [ArraySubset::new_with_ranges(&[2..4, 1..3, 1..6])];
+        let input_handle = Arc::new(std::io::Cursor::new(encoded));
+        let partial_decoder = codec
+            .async_partial_decoder(
+                input_handle,
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .await
+            .unwrap();
+        let decoded_partial_chunk = partial_decoder
+            .partial_decode(&decoded_regions, &CodecOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            bytes
+                .extract_array_subset(
+                    &decoded_regions[0],
+                    &chunk_representation.shape_u64(),
+                    chunk_representation.data_type()
+                )
+                .unwrap(),
+            decoded_partial_chunk[0]
+        );
+    }
+}
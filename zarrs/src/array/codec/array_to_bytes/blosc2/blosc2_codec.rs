@@ -0,0 +1,225 @@
+use std::{num::NonZeroU64, sync::Arc};
+
+use crate::{
+    array::{
+        codec::{
+            ArrayBytes, ArrayCodecTraits, ArrayPartialDecoderTraits, ArrayPartialEncoderDefault,
+            ArrayPartialEncoderTraits, ArrayToBytesCodecTraits, BytesPartialDecoderTraits,
+            BytesPartialEncoderTraits, CodecError, CodecOptions, CodecTraits, RawBytes,
+            RecommendedConcurrency,
+        },
+        ArrayMetadataOptions, BytesRepresentation, ChunkRepresentation,
+    },
+    config::global_config,
+    metadata::v3::MetadataV3,
+};
+
+#[cfg(feature = "async")]
+use crate::array::codec::{AsyncArrayPartialDecoderTraits, AsyncBytesPartialDecoderTraits};
+
+use super::{
+    blosc2_blockshape, blosc2_encode, blosc2_partial_decoder, blosc2_typesize, swap_endianness_le,
+    B2ndArray, Blosc2CodecConfiguration, Blosc2CodecConfigurationV1, Blosc2Compressor,
+    BloscCompressionLevel, BloscShuffleMode, IDENTIFIER,
+};
+
+/// A `blosc2` codec implementation.
+#[derive(Clone, Debug)]
+pub struct Blosc2Codec {
+    cname: Blosc2Compressor,
+    clevel: BloscCompressionLevel,
+    shuffle_mode: BloscShuffleMode,
+    blockshape: Option<Vec<NonZeroU64>>,
+}
+
+impl Blosc2Codec {
+    /// Create a new `blosc2` codec.
+    ///
+    /// The block shape is chosen automatically if `blockshape` is [`None`].
+    #[must_use]
+    pub const fn new(
+        cname: Blosc2Compressor,
+        clevel: BloscCompressionLevel,
+        shuffle_mode: BloscShuffleMode,
+        blockshape: Option<Vec<NonZeroU64>>,
+    ) -> Self {
+        Self {
+            cname,
+            clevel,
+            shuffle_mode,
+            blockshape,
+        }
+    }
+
+    /// Create a new `blosc2` codec from configuration.
+    #[must_use]
+    pub fn new_with_configuration(configuration: &Blosc2CodecConfiguration) -> Self {
+        let Blosc2CodecConfiguration::V1(configuration) = configuration;
+        Self::new(
+            configuration.cname,
+            configuration.clevel,
+            configuration.shuffle,
+            configuration.blockshape.clone(),
+        )
+    }
+
+    /// Return the block shape of a chunk with `decoded_representation`.
+    fn blockshape(
+        &self,
+        decoded_representation: &ChunkRepresentation,
+    ) -> Result<Vec<u64>, CodecError> {
+        let blockshape = self
+            .blockshape
+            .as_ref()
+            .map(|blockshape| blockshape.iter().map(|b| b.get()).collect::<Vec<_>>());
+        blosc2_blockshape(
+            blockshape.as_deref(),
+            &decoded_representation.shape_u64(),
+            blosc2_typesize(decoded_representation.data_type())?,
+        )
+    }
+}
+
+impl CodecTraits for Blosc2Codec {
+    fn create_metadata_opt(&self, _options: &ArrayMetadataOptions) -> Option<MetadataV3> {
+        let configuration = Blosc2CodecConfiguration::V1(Blosc2CodecConfigurationV1 {
+            cname: self.cname,
+            clevel: self.clevel,
+            shuffle: self.shuffle_mode,
+            blockshape: self.blockshape.clone(),
+        });
+        Some(
+            MetadataV3::new_with_serializable_configuration(
+                global_config()
+                    .experimental_codec_names()
+                    .get(IDENTIFIER)
+                    .expect("experimental codec identifier in global map"),
+                &configuration,
+            )
+            .expect("blosc2 configuration is valid json"),
+        )
+    }
+
+    fn partial_decoder_should_cache_input(&self) -> bool {
+        false
+    }
+
+    fn partial_decoder_decodes_all(&self) -> bool {
+        false
+    }
+}
+
+impl ArrayCodecTraits for Blosc2Codec {
+    fn recommended_concurrency(
+        &self,
+        _decoded_representation: &ChunkRepresentation,
+    ) -> Result<RecommendedConcurrency, CodecError> {
+        // TODO: Dependent on the block shape, recommended concurrency could be > 1
+        Ok(RecommendedConcurrency::new_maximum(1))
+    }
+}
+
+#[cfg_attr(feature = "async", async_trait::async_trait)]
+impl ArrayToBytesCodecTraits for Blosc2Codec {
+    fn dynamic(self: Arc<Self>) -> Arc<dyn ArrayToBytesCodecTraits> {
+        self as Arc<dyn ArrayToBytesCodecTraits>
+    }
+
+    fn encode<'a>(
+        &self,
+        bytes: ArrayBytes<'a>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        let data_type = decoded_representation.data_type();
+        let typesize = blosc2_typesize(data_type)?;
+        let bytes = bytes.into_fixed()?;
+        let expected_size = decoded_representation.num_elements() * typesize as u64;
+        if bytes.len() as u64 != expected_size {
+            return Err(CodecError::UnexpectedChunkDecodedSize(
+                bytes.len(),
+                expected_size,
+            ));
+        }
+        let bytes = swap_endianness_le(bytes, data_type);
+
+        let n_threads = 1;
+        Ok(blosc2_encode(
+            &bytes,
+            &decoded_representation.shape_u64(),
+            &self.blockshape(decoded_representation)?,
+            typesize,
+            self.cname,
+            self.clevel,
+            self.shuffle_mode,
+            n_threads,
+        )?
+        .into())
+    }
+
+    fn decode<'a>(
+        &self,
+        bytes: RawBytes<'a>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<ArrayBytes<'a>, CodecError> {
+        let data_type = decoded_representation.data_type();
+        let typesize = blosc2_typesize(data_type)?;
+        let array = B2ndArray::from_cframe(&bytes, &decoded_representation.shape_u64(), typesize)?;
+        let decoded_value = array.decode(decoded_representation.num_elements_usize() * typesize)?;
+        Ok(ArrayBytes::from(swap_endianness_le(
+            decoded_value.into(),
+            data_type,
+        )))
+    }
+
+    fn partial_decoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn BytesPartialDecoderTraits>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn ArrayPartialDecoderTraits>, CodecError> {
+        Ok(Arc::new(blosc2_partial_decoder::Blosc2PartialDecoder::new(
+            input_handle,
+            decoded_representation.clone(),
+        )?))
+    }
+
+    fn partial_encoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn BytesPartialDecoderTraits>,
+        output_handle: Arc<dyn BytesPartialEncoderTraits>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn ArrayPartialEncoderTraits>, CodecError> {
+        Ok(Arc::new(ArrayPartialEncoderDefault::new(
+            input_handle,
+            output_handle,
+            decoded_representation.clone(),
+            self,
+        )))
+    }
+
+    #[cfg(feature = "async")]
+    async fn async_partial_decoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn AsyncArrayPartialDecoderTraits>, CodecError> {
+        Ok(Arc::new(
+            blosc2_partial_decoder::AsyncBlosc2PartialDecoder::new(
+                input_handle,
+                decoded_representation.clone(),
+            )?,
+        ))
+    }
+
+    fn compute_encoded_size(
+        &self,
+        decoded_representation: &ChunkRepresentation,
+    ) -> Result<BytesRepresentation, CodecError> {
+        blosc2_typesize(decoded_representation.data_type())?;
+        Ok(BytesRepresentation::UnboundedSize)
+    }
+}
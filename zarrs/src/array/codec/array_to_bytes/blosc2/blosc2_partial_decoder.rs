@@ -0,0 +1,154 @@
+use std::sync::Arc;
+
+use crate::{
+    array::{
+        codec::{
+            ArrayBytes, ArrayPartialDecoderTraits, BytesPartialDecoderTraits, CodecError,
+            CodecOptions,
+        },
+        ArraySize, ChunkRepresentation, DataType,
+    },
+    array_subset::{ArraySubset, IncompatibleArraySubsetAndShapeError},
+};
+
+#[cfg(feature = "async")]
+use crate::array::codec::{AsyncArrayPartialDecoderTraits, AsyncBytesPartialDecoderTraits};
+
+use super::{blosc2_typesize, swap_endianness_le, B2ndArray};
+
+fn validate_decoded_regions(
+    decoded_regions: &[ArraySubset],
+    decoded_representation: &ChunkRepresentation,
+) -> Result<(), CodecError> {
+    let chunk_shape = decoded_representation.shape_u64();
+    for array_subset in decoded_regions {
+        if array_subset.dimensionality() != decoded_representation.dimensionality() {
+            return Err(CodecError::InvalidArraySubsetDimensionalityError(
+                array_subset.clone(),
+                decoded_representation.dimensionality(),
+            ));
+        }
+        if !array_subset.inbounds(&chunk_shape) {
+            return Err(CodecError::InvalidArraySubsetError(
+                IncompatibleArraySubsetAndShapeError::new(array_subset.clone(), chunk_shape),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn do_partial_decode<'a>(
+    encoded_value: Option<&[u8]>,
+    decoded_regions: &[ArraySubset],
+    decoded_representation: &ChunkRepresentation,
+) -> Result<Vec<ArrayBytes<'a>>, CodecError> {
+    let data_type = decoded_representation.data_type();
+    let Some(encoded_value) = encoded_value else {
+        return Ok(decoded_regions
+            .iter()
+            .map(|array_subset| {
+                let array_size = ArraySize::new(data_type.size(), array_subset.num_elements());
+                ArrayBytes::new_fill_value(array_size, decoded_representation.fill_value())
+            })
+            .collect());
+    };
+
+    // Only the blocks intersecting each decoded region are decompressed
+    let typesize = blosc2_typesize(data_type)?;
+    let array =
+        B2ndArray::from_cframe(encoded_value, &decoded_representation.shape_u64(), typesize)?;
+    decoded_regions
+        .iter()
+        .map(|array_subset| {
+            let decoded = array.decode_subset(array_subset, typesize)?;
+            Ok(ArrayBytes::from(swap_endianness_le(
+                decoded.into(),
+                data_type,
+            )))
+        })
+        .collect()
+}
+
+/// Partial decoder for the `blosc2` codec.
+pub(crate) struct Blosc2PartialDecoder<'a> {
+    input_handle: Arc<dyn BytesPartialDecoderTraits + 'a>,
+    decoded_representation: ChunkRepresentation,
+}
+
+impl<'a> Blosc2PartialDecoder<'a> {
+    /// Create a new partial decoder for the `blosc2` codec.
+    pub(crate) fn new(
+        input_handle: Arc<dyn BytesPartialDecoderTraits + 'a>,
+        decoded_representation: ChunkRepresentation,
+    ) -> Result<Self, CodecError> {
+        blosc2_typesize(decoded_representation.data_type())?;
+        Ok(Self {
+            input_handle,
+            decoded_representation,
+        })
+    }
+}
+
+impl ArrayPartialDecoderTraits for Blosc2PartialDecoder<'_> {
+    fn data_type(&self) -> &DataType {
+        self.decoded_representation.data_type()
+    }
+
+    fn partial_decode(
+        &self,
+        decoded_regions: &[ArraySubset],
+        options: &CodecOptions,
+    ) -> Result<Vec<ArrayBytes<'_>>, CodecError> {
+        validate_decoded_regions(decoded_regions, &self.decoded_representation)?;
+        let encoded_value = self.input_handle.decode(options)?;
+        do_partial_decode(
+            encoded_value.as_deref(),
+            decoded_regions,
+            &self.decoded_representation,
+        )
+    }
+}
+
+#[cfg(feature = "async")]
+/// Asynchronous partial decoder for the `blosc2` codec.
+pub(crate) struct AsyncBlosc2PartialDecoder {
+    input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+    decoded_representation: ChunkRepresentation,
+}
+
+#[cfg(feature = "async")]
+impl AsyncBlosc2PartialDecoder {
+    /// Create a new partial decoder for the `blosc2` codec.
+    pub(crate) fn new(
+        input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+        decoded_representation: ChunkRepresentation,
+    ) -> Result<Self, CodecError> {
+        blosc2_typesize(decoded_representation.data_type())?;
+        Ok(Self {
+            input_handle,
+            decoded_representation,
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl AsyncArrayPartialDecoderTraits for AsyncBlosc2PartialDecoder {
+    fn data_type(&self) -> &DataType {
+        self.decoded_representation.data_type()
+    }
+
+    async fn partial_decode(
+        &self,
+        decoded_regions: &[ArraySubset],
+        options: &CodecOptions,
+    ) -> Result<Vec<ArrayBytes<'_>>, CodecError> {
+        validate_decoded_regions(decoded_regions, &self.decoded_representation)?;
+        let encoded_value = self.input_handle.decode(options).await?;
+        do_partial_decode(
+            encoded_value.as_deref(),
+            decoded_regions,
+            &self.decoded_representation,
+        )
+    }
+}
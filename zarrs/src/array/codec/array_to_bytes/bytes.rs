@@ -6,6 +6,7 @@
 
 mod bytes_codec;
 mod bytes_partial_decoder;
+mod bytes_partial_encoder;
 
 use std::sync::Arc;
 
@@ -20,8 +20,8 @@ use crate::{
 use crate::array::codec::{AsyncArrayPartialDecoderTraits, AsyncBytesPartialDecoderTraits};
 
 use super::{
-    bytes_partial_decoder, reverse_endianness, BytesCodecConfiguration, BytesCodecConfigurationV1,
-    Endianness,
+    bytes_partial_decoder, bytes_partial_encoder, reverse_endianness, BytesCodecConfiguration,
+    BytesCodecConfigurationV1, Endianness,
 };
 
 /// A `bytes` codec implementation.
@@ -172,6 +172,15 @@ impl ArrayToBytesCodecTraits for BytesCodec {
         ))
     }
 
+    fn is_passthrough(&self, decoded_representation: &ChunkRepresentation) -> bool {
+        match decoded_representation.data_type().size() {
+            DataTypeSize::Variable => false,
+            DataTypeSize::Fixed(data_type_size) => {
+                data_type_size == 1 || self.endian.is_some_and(Endianness::is_native)
+            }
+        }
+    }
+
     fn partial_decoder(
         self: Arc<Self>,
         input_handle: Arc<dyn BytesPartialDecoderTraits>,
@@ -192,12 +201,23 @@ impl ArrayToBytesCodecTraits for BytesCodec {
         decoded_representation: &ChunkRepresentation,
         _options: &CodecOptions,
     ) -> Result<Arc<dyn ArrayPartialEncoderTraits>, CodecError> {
-        Ok(Arc::new(ArrayPartialEncoderDefault::new(
-            input_handle,
-            output_handle,
+        let partial_encoder_default = ArrayPartialEncoderDefault::new(
+            input_handle.clone(),
+            output_handle.clone(),
             decoded_representation.clone(),
-            self,
-        )))
+            self.clone(),
+        );
+        if output_handle.supports_partial_encode() {
+            Ok(Arc::new(bytes_partial_encoder::BytesPartialEncoder::new(
+                input_handle,
+                output_handle,
+                decoded_representation.clone(),
+                self.endian,
+                partial_encoder_default,
+            )))
+        } else {
+            Ok(Arc::new(partial_encoder_default))
+        }
     }
 
     #[cfg(feature = "async")]
@@ -0,0 +1,109 @@
+use std::{borrow::Cow, sync::Arc};
+
+use crate::{
+    array::{
+        codec::{
+            ArrayPartialEncoderDefault, ArrayPartialEncoderTraits, ArraySubset,
+            BytesPartialDecoderTraits, BytesPartialEncoderTraits, CodecError, CodecOptions,
+        },
+        ArrayBytes, ChunkRepresentation, DataTypeSize,
+    },
+    byte_range::ByteRange,
+};
+
+use super::{reverse_endianness, Endianness};
+
+/// Partial encoder for the `bytes` codec.
+///
+/// The bytes of each updated array subset are written directly to the corresponding byte ranges of the output handle.
+/// The [`ArrayPartialEncoderDefault`] is used instead if the chunk does not exist or the updated elements are all the fill value, so that a missing chunk is initialised with the fill value and a chunk that may become entirely the fill value is erased.
+pub(crate) struct BytesPartialEncoder {
+    input_handle: Arc<dyn BytesPartialDecoderTraits>,
+    output_handle: Arc<dyn BytesPartialEncoderTraits>,
+    decoded_representation: ChunkRepresentation,
+    endian: Option<Endianness>,
+    partial_encoder_default: ArrayPartialEncoderDefault,
+}
+
+impl BytesPartialEncoder {
+    /// Create a new partial encoder for the `bytes` codec.
+    pub(crate) fn new(
+        input_handle: Arc<dyn BytesPartialDecoderTraits>,
+        output_handle: Arc<dyn BytesPartialEncoderTraits>,
+        decoded_representation: ChunkRepresentation,
+        endian: Option<Endianness>,
+        partial_encoder_default: ArrayPartialEncoderDefault,
+    ) -> Self {
+        Self {
+            input_handle,
+            output_handle,
+            decoded_representation,
+            endian,
+            partial_encoder_default,
+        }
+    }
+}
+
+impl ArrayPartialEncoderTraits for BytesPartialEncoder {
+    fn erase(&self) -> Result<(), CodecError> {
+        self.output_handle.erase()
+    }
+
+    fn partial_encode(
+        &self,
+        subsets_and_bytes: &[(&ArraySubset, ArrayBytes<'_>)],
+        options: &CodecOptions,
+    ) -> Result<(), CodecError> {
+        let data_type = self.decoded_representation.data_type();
+        let DataTypeSize::Fixed(data_type_size) = data_type.size() else {
+            return Err(CodecError::UnsupportedDataType(
+                data_type.clone(),
+                super::IDENTIFIER.to_string(),
+            ));
+        };
+
+        // Check if the chunk exists, unless the update could make it entirely the fill value
+        let fill_value = self.decoded_representation.fill_value();
+        let exists = !subsets_and_bytes
+            .iter()
+            .all(|(_, chunk_subset_bytes)| chunk_subset_bytes.is_fill_value(fill_value))
+            && self
+                .input_handle
+                .partial_decode(
+                    &[ByteRange::FromStart(0, Some(data_type_size as u64))],
+                    options,
+                )?
+                .is_some();
+        if !exists {
+            return self
+                .partial_encoder_default
+                .partial_encode(subsets_and_bytes, options);
+        }
+
+        // Write the bytes of each chunk subset
+        let chunk_shape = self.decoded_representation.shape_u64();
+        let chunk_size = self.decoded_representation.num_elements() * data_type_size as u64;
+        let mut offsets_and_bytes = Vec::new();
+        for (chunk_subset, chunk_subset_bytes) in subsets_and_bytes {
+            chunk_subset_bytes.validate(chunk_subset.num_elements(), data_type.size())?;
+            let mut chunk_subset_bytes = chunk_subset_bytes.clone().into_fixed()?;
+            if let Some(endian) = &self.endian {
+                if !endian.is_native() {
+                    reverse_endianness(chunk_subset_bytes.to_mut(), data_type);
+                }
+            }
+
+            let mut bytes_offset = 0;
+            for byte_range in chunk_subset.byte_ranges(&chunk_shape, data_type_size)? {
+                let length = usize::try_from(byte_range.length(chunk_size)).unwrap();
+                offsets_and_bytes.push((
+                    byte_range.start(chunk_size),
+                    Cow::Owned(chunk_subset_bytes[bytes_offset..bytes_offset + length].to_vec()),
+                ));
+                bytes_offset += length;
+            }
+        }
+        self.output_handle
+            .partial_encode(&offsets_and_bytes, options)
+    }
+}
@@ -1,6 +1,10 @@
 //! An array to bytes codec formed by joining an array to array sequence, array to bytes, and bytes to bytes sequence of codecs.
 
-use std::sync::Arc;
+use std::{
+    borrow::Cow,
+    io::{Read, Write},
+    sync::Arc,
+};
 
 use unsafe_cell_slice::UnsafeCellSlice;
 
@@ -8,6 +12,7 @@ use crate::{
     array::{
         array_bytes::update_bytes_flen,
         codec::{
+            profiler::{profile, CodecProfileOperation},
             ArrayCodecTraits, ArrayPartialDecoderCache, ArrayPartialDecoderTraits,
             ArrayPartialEncoderTraits, ArrayToArrayCodecTraits, ArrayToBytesCodecTraits,
             BytesPartialDecoderCache, BytesPartialDecoderTraits, BytesPartialEncoderTraits,
@@ -20,10 +25,14 @@ use crate::{
     array_subset::ArraySubset,
     metadata::v3::MetadataV3,
     plugin::PluginCreateError,
+    storage::{StoreValueReader, StoreValueWriter},
 };
 
 #[cfg(feature = "async")]
-use crate::array::codec::{AsyncArrayPartialDecoderTraits, AsyncBytesPartialDecoderTraits};
+use crate::array::codec::{
+    AsyncArrayPartialDecoderTraits, AsyncArrayPartialEncoderTraits, AsyncBytesPartialDecoderTraits,
+    AsyncBytesPartialEncoderTraits,
+};
 
 /// A codec chain is a sequence of array to array, a bytes to bytes, and a sequence of array to bytes codecs.
 ///
@@ -32,14 +41,36 @@ use crate::array::codec::{AsyncArrayPartialDecoderTraits, AsyncBytesPartialDecod
 /// If decoding (i.e. going backwards through a codec chain), then a cache may be inserted
 ///    - following the last codec with [`partial_decoder_decodes_all`](crate::array::codec::CodecTraits::partial_decoder_decodes_all) true, or
 ///    - preceding the first codec with [`partial_decoder_should_cache_input`](crate::array::codec::CodecTraits::partial_decoder_should_cache_input), whichever is further.
+///
+/// The cache location can be overridden with [`with_partial_decoder_cache_mode`](CodecChain::with_partial_decoder_cache_mode).
 #[derive(Debug, Clone)]
 pub struct CodecChain {
     array_to_array: Vec<Arc<dyn ArrayToArrayCodecTraits>>,
     array_to_bytes: Arc<dyn ArrayToBytesCodecTraits>,
     bytes_to_bytes: Vec<Arc<dyn BytesToBytesCodecTraits>>,
+    partial_decoder_cache_mode: PartialDecoderCacheMode,
     cache_index: Option<usize>, // for partial decoders
 }
 
+/// The cache inserted by a [`CodecChain`] partial decoder.
+///
+/// The optimal cache depends on the latency of the store relative to the cost of decoding.
+/// Caching the encoded input avoids repeated store requests, whereas caching the decoded output also avoids repeated decoding at the cost of memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PartialDecoderCacheMode {
+    /// Insert a cache where determined by the codecs (see [`CodecChain`]).
+    #[default]
+    Auto,
+    /// Do not insert a cache.
+    ///
+    /// Codecs that decode their entire input are re-run on every partial decode.
+    Disabled,
+    /// Cache the encoded input of the codec chain.
+    EncodedInput,
+    /// Cache the decoded output of the codec chain.
+    DecodedOutput,
+}
+
 impl CodecChain {
     /// Create a new codec chain.
     #[must_use]
@@ -48,6 +79,47 @@ impl CodecChain {
         array_to_bytes: Arc<dyn ArrayToBytesCodecTraits>,
         bytes_to_bytes: Vec<Arc<dyn BytesToBytesCodecTraits>>,
     ) -> Self {
+        let cache_index = Self::cache_index_auto(&array_to_array, &array_to_bytes, &bytes_to_bytes);
+        Self {
+            array_to_array,
+            array_to_bytes,
+            bytes_to_bytes,
+            partial_decoder_cache_mode: PartialDecoderCacheMode::Auto,
+            cache_index,
+        }
+    }
+
+    /// Return the codec chain with the partial decoder cache `mode`.
+    #[must_use]
+    pub fn with_partial_decoder_cache_mode(mut self, mode: PartialDecoderCacheMode) -> Self {
+        self.cache_index = match mode {
+            PartialDecoderCacheMode::Auto => Self::cache_index_auto(
+                &self.array_to_array,
+                &self.array_to_bytes,
+                &self.bytes_to_bytes,
+            ),
+            PartialDecoderCacheMode::Disabled => None,
+            PartialDecoderCacheMode::EncodedInput => Some(0),
+            PartialDecoderCacheMode::DecodedOutput => {
+                Some(self.bytes_to_bytes.len() + 1 + self.array_to_array.len())
+            }
+        };
+        self.partial_decoder_cache_mode = mode;
+        self
+    }
+
+    /// Return the partial decoder cache mode.
+    #[must_use]
+    pub const fn partial_decoder_cache_mode(&self) -> PartialDecoderCacheMode {
+        self.partial_decoder_cache_mode
+    }
+
+    /// Return the index of the partial decoder cache determined by the codecs.
+    fn cache_index_auto(
+        array_to_array: &[Arc<dyn ArrayToArrayCodecTraits>],
+        array_to_bytes: &Arc<dyn ArrayToBytesCodecTraits>,
+        bytes_to_bytes: &[Arc<dyn BytesToBytesCodecTraits>],
+    ) -> Option<usize> {
         let mut cache_index_must = None;
         let mut cache_index_should = None;
         let mut codec_index = 0;
@@ -79,7 +151,7 @@ impl CodecChain {
             codec_index += 1;
         }
 
-        let cache_index = if let (Some(cache_index_must), Some(cache_index_should)) =
+        if let (Some(cache_index_must), Some(cache_index_should)) =
             (cache_index_must, cache_index_should)
         {
             Some(std::cmp::max(cache_index_must, cache_index_should))
@@ -89,13 +161,6 @@ impl CodecChain {
             cache_index_should
         } else {
             None
-        };
-
-        Self {
-            array_to_array,
-            array_to_bytes,
-            bytes_to_bytes,
-            cache_index,
         }
     }
 
@@ -209,6 +274,142 @@ impl CodecChain {
         }
         Ok(bytes_representations)
     }
+
+    /// Decode `bytes` with the array to bytes and array to array codecs.
+    fn decode_array<'a>(
+        &self,
+        bytes: RawBytes<'a>,
+        array_representations: &[ChunkRepresentation],
+        options: &CodecOptions,
+    ) -> Result<ArrayBytes<'a>, CodecError> {
+        // bytes->array
+        let mut bytes = profile(
+            options,
+            self.array_to_bytes.as_ref(),
+            CodecProfileOperation::Decode,
+            bytes.len(),
+            || {
+                self.array_to_bytes
+                    .decode(bytes, array_representations.last().unwrap(), options)
+            },
+            ArrayBytes::size,
+        )?;
+
+        // array->array
+        for (codec, array_representation) in std::iter::zip(
+            self.array_to_array.iter().rev(),
+            array_representations.iter().rev().skip(1),
+        ) {
+            bytes = profile(
+                options,
+                codec.as_ref(),
+                CodecProfileOperation::Decode,
+                bytes.size(),
+                || codec.decode(bytes, array_representation, options),
+                ArrayBytes::size,
+            )?;
+        }
+
+        let decoded_representation = array_representations.first().unwrap();
+        bytes.validate(
+            decoded_representation.num_elements(),
+            decoded_representation.data_type().size(),
+        )?;
+        Ok(bytes)
+    }
+
+    /// Indicates if the codec chain has bytes to bytes codecs and they all [support streaming](BytesToBytesCodecTraits::supports_streaming).
+    ///
+    /// If true, [`decode_reader`](CodecChain::decode_reader) and [`encode_writer`](CodecChain::encode_writer) do not hold the encoded bytes in memory.
+    #[must_use]
+    pub fn supports_streaming(&self) -> bool {
+        !self.bytes_to_bytes.is_empty()
+            && self
+                .bytes_to_bytes
+                .iter()
+                .all(|codec| codec.supports_streaming())
+    }
+
+    /// Decode the encoded bytes of a chunk from an `encoded_value` reader.
+    ///
+    /// The encoded bytes are streamed through the bytes to bytes codecs with [`BytesToBytesCodecTraits::decode_reader`].
+    ///
+    /// # Errors
+    /// Returns [`CodecError`] if a codec fails or the encoded value cannot be read.
+    ///
+    /// # Panics
+    /// Panics if the size of the bytes input to the bytes to bytes codecs exceeds [`usize::MAX`].
+    pub fn decode_reader(
+        &self,
+        encoded_value: StoreValueReader<'_>,
+        decoded_representation: &ChunkRepresentation,
+        options: &CodecOptions,
+    ) -> Result<ArrayBytes<'static>, CodecError> {
+        let array_representations =
+            self.get_array_representations(decoded_representation.clone())?;
+        let bytes_representations =
+            self.get_bytes_representations(array_representations.last().unwrap())?;
+
+        // bytes->bytes
+        let mut reader = encoded_value;
+        for (codec, bytes_representation) in std::iter::zip(
+            self.bytes_to_bytes.iter().rev(),
+            bytes_representations.iter().rev().skip(1),
+        ) {
+            reader = codec.decode_reader(reader, bytes_representation, options)?;
+        }
+        let mut bytes = bytes_representations
+            .first()
+            .unwrap()
+            .size()
+            .map_or_else(Vec::new, |size| {
+                Vec::with_capacity(usize::try_from(size).unwrap())
+            });
+        reader.read_to_end(&mut bytes)?;
+
+        self.decode_array(Cow::Owned(bytes), &array_representations, options)
+    }
+
+    /// Encode the `bytes` of a chunk to an `encoded_value` writer and finish it.
+    ///
+    /// The output of the array to bytes codec is streamed through the bytes to bytes codecs with [`BytesToBytesCodecTraits::encode_writer`].
+    ///
+    /// # Errors
+    /// Returns [`CodecError`] if a codec fails or the encoded value cannot be written.
+    pub fn encode_writer(
+        &self,
+        bytes: ArrayBytes<'_>,
+        decoded_representation: &ChunkRepresentation,
+        encoded_value: Box<dyn StoreValueWriter + '_>,
+        options: &CodecOptions,
+    ) -> Result<(), CodecError> {
+        bytes.validate(
+            decoded_representation.num_elements(),
+            decoded_representation.data_type().size(),
+        )?;
+
+        // array->array
+        let mut bytes = bytes;
+        let mut decoded_representation = decoded_representation.clone();
+        for codec in &self.array_to_array {
+            bytes = codec.encode(bytes, &decoded_representation, options)?;
+            decoded_representation = codec.compute_encoded_size(&decoded_representation)?;
+        }
+
+        // array->bytes
+        let bytes = self
+            .array_to_bytes
+            .encode(bytes, &decoded_representation, options)?;
+
+        // bytes->bytes
+        let mut writer = encoded_value;
+        for codec in self.bytes_to_bytes.iter().rev() {
+            writer = codec.encode_writer(writer, options)?;
+        }
+        writer.write_all(&bytes)?;
+        writer.finish()?;
+        Ok(())
+    }
 }
 
 impl CodecTraits for CodecChain {
@@ -249,21 +450,43 @@ impl ArrayToBytesCodecTraits for CodecChain {
 
         // array->array
         for codec in &self.array_to_array {
-            bytes = codec.encode(bytes, &decoded_representation, options)?;
+            bytes = profile(
+                options,
+                codec.as_ref(),
+                CodecProfileOperation::Encode,
+                bytes.size(),
+                || codec.encode(bytes, &decoded_representation, options),
+                ArrayBytes::size,
+            )?;
             decoded_representation = codec.compute_encoded_size(&decoded_representation)?;
         }
 
         // array->bytes
-        let mut bytes = self
-            .array_to_bytes
-            .encode(bytes, &decoded_representation, options)?;
+        let mut bytes = profile(
+            options,
+            self.array_to_bytes.as_ref(),
+            CodecProfileOperation::Encode,
+            bytes.size(),
+            || {
+                self.array_to_bytes
+                    .encode(bytes, &decoded_representation, options)
+            },
+            |bytes| bytes.len(),
+        )?;
         let mut decoded_representation = self
             .array_to_bytes
             .compute_encoded_size(&decoded_representation)?;
 
         // bytes->bytes
         for codec in &self.bytes_to_bytes {
-            bytes = codec.encode(bytes, options)?;
+            bytes = profile(
+                options,
+                codec.as_ref(),
+                CodecProfileOperation::Encode,
+                bytes.len(),
+                || codec.encode(bytes, options),
+                |bytes| bytes.len(),
+            )?;
             decoded_representation = codec.compute_encoded_size(&decoded_representation);
         }
 
@@ -286,27 +509,17 @@ impl ArrayToBytesCodecTraits for CodecChain {
             self.bytes_to_bytes.iter().rev(),
             bytes_representations.iter().rev().skip(1),
         ) {
-            bytes = codec.decode(bytes, bytes_representation, options)?;
-        }
-
-        // bytes->array
-        let mut bytes =
-            self.array_to_bytes
-                .decode(bytes, array_representations.last().unwrap(), options)?;
-
-        // array->array
-        for (codec, array_representation) in std::iter::zip(
-            self.array_to_array.iter().rev(),
-            array_representations.iter().rev().skip(1),
-        ) {
-            bytes = codec.decode(bytes, array_representation, options)?;
+            bytes = profile(
+                options,
+                codec.as_ref(),
+                CodecProfileOperation::Decode,
+                bytes.len(),
+                || codec.decode(bytes, bytes_representation, options),
+                |bytes| bytes.len(),
+            )?;
         }
 
-        bytes.validate(
-            decoded_representation.num_elements(),
-            decoded_representation.data_type().size(),
-        )?;
-        Ok(bytes)
+        self.decode_array(bytes, &array_representations, options)
     }
 
     unsafe fn decode_into(
@@ -325,16 +538,24 @@ impl ArrayToBytesCodecTraits for CodecChain {
 
         if self.bytes_to_bytes.is_empty() && self.array_to_array.is_empty() {
             // Fast path if no bytes to bytes or array to array codecs
-            return unsafe {
-                self.array_to_bytes.decode_into(
-                    bytes,
-                    array_representations.last().unwrap(),
-                    output,
-                    output_shape,
-                    output_subset,
-                    options,
-                )
-            };
+            let array_representation = array_representations.last().unwrap();
+            return profile(
+                options,
+                self.array_to_bytes.as_ref(),
+                CodecProfileOperation::Decode,
+                bytes.len(),
+                || unsafe {
+                    self.array_to_bytes.decode_into(
+                        bytes,
+                        array_representation,
+                        output,
+                        output_shape,
+                        output_subset,
+                        options,
+                    )
+                },
+                |()| array_representation.fixed_size().unwrap_or_default(),
+            );
         }
 
         // bytes->bytes
@@ -342,34 +563,64 @@ impl ArrayToBytesCodecTraits for CodecChain {
             self.bytes_to_bytes.iter().rev(),
             bytes_representations.iter().rev().skip(1),
         ) {
-            bytes = codec.decode(bytes, bytes_representation, options)?;
+            bytes = profile(
+                options,
+                codec.as_ref(),
+                CodecProfileOperation::Decode,
+                bytes.len(),
+                || codec.decode(bytes, bytes_representation, options),
+                |bytes| bytes.len(),
+            )?;
         }
 
         if self.array_to_array.is_empty() {
             // Fast path if no array to array codecs
-            return unsafe {
-                self.array_to_bytes.decode_into(
-                    bytes,
-                    array_representations.last().unwrap(),
-                    output,
-                    output_shape,
-                    output_subset,
-                    options,
-                )
-            };
+            let array_representation = array_representations.last().unwrap();
+            return profile(
+                options,
+                self.array_to_bytes.as_ref(),
+                CodecProfileOperation::Decode,
+                bytes.len(),
+                || unsafe {
+                    self.array_to_bytes.decode_into(
+                        bytes,
+                        array_representation,
+                        output,
+                        output_shape,
+                        output_subset,
+                        options,
+                    )
+                },
+                |()| array_representation.fixed_size().unwrap_or_default(),
+            );
         }
 
         // bytes->array
-        let mut bytes =
-            self.array_to_bytes
-                .decode(bytes, array_representations.last().unwrap(), options)?;
+        let mut bytes = profile(
+            options,
+            self.array_to_bytes.as_ref(),
+            CodecProfileOperation::Decode,
+            bytes.len(),
+            || {
+                self.array_to_bytes
+                    .decode(bytes, array_representations.last().unwrap(), options)
+            },
+            ArrayBytes::size,
+        )?;
 
         // array->array
         for (codec, array_representation) in std::iter::zip(
             self.array_to_array.iter().rev(),
             array_representations.iter().rev().skip(1),
         ) {
-            bytes = codec.decode(bytes, array_representation, options)?;
+            bytes = profile(
+                options,
+                codec.as_ref(),
+                CodecProfileOperation::Decode,
+                bytes.size(),
+                || codec.decode(bytes, array_representation, options),
+                ArrayBytes::size,
+            )?;
         }
         bytes.validate(
             decoded_representation.num_elements(),
@@ -391,6 +642,12 @@ impl ArrayToBytesCodecTraits for CodecChain {
         Ok(())
     }
 
+    fn is_passthrough(&self, decoded_representation: &ChunkRepresentation) -> bool {
+        self.array_to_array.is_empty()
+            && self.bytes_to_bytes.is_empty()
+            && self.array_to_bytes.is_passthrough(decoded_representation)
+    }
+
     fn partial_decoder(
         self: Arc<Self>,
         mut input_handle: Arc<dyn BytesPartialDecoderTraits>,
@@ -603,6 +860,82 @@ impl ArrayToBytesCodecTraits for CodecChain {
         Ok(input_handle)
     }
 
+    #[cfg(feature = "async")]
+    async fn async_partial_encoder(
+        self: Arc<Self>,
+        mut input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+        mut output_handle: Arc<dyn AsyncBytesPartialEncoderTraits>,
+        decoded_representation: &ChunkRepresentation,
+        options: &CodecOptions,
+    ) -> Result<Arc<dyn AsyncArrayPartialEncoderTraits>, CodecError> {
+        let array_representations =
+            self.get_array_representations(decoded_representation.clone())?;
+        let bytes_representations =
+            self.get_bytes_representations(array_representations.last().unwrap())?;
+
+        for (codec, bytes_representation) in std::iter::zip(
+            self.bytes_to_bytes.iter().rev(),
+            bytes_representations.iter().rev().skip(1),
+        ) {
+            output_handle = Arc::clone(codec)
+                .async_partial_encoder(
+                    input_handle.clone(),
+                    output_handle,
+                    bytes_representation,
+                    options,
+                )
+                .await?;
+            input_handle = Arc::clone(codec)
+                .async_partial_decoder(input_handle, bytes_representation, options)
+                .await?;
+        }
+
+        let mut output_handle = self
+            .array_to_bytes
+            .clone()
+            .async_partial_encoder(
+                input_handle.clone(),
+                output_handle,
+                array_representations.last().unwrap(),
+                options,
+            )
+            .await?;
+
+        if self.array_to_array.is_empty() {
+            return Ok(output_handle);
+        }
+
+        let mut input_handle = self
+            .array_to_bytes
+            .clone()
+            .async_partial_decoder(input_handle, array_representations.last().unwrap(), options)
+            .await?;
+
+        let mut it = std::iter::zip(
+            self.array_to_array.iter().rev(),
+            array_representations.iter().rev().skip(1),
+        )
+        .peekable();
+        while let Some((codec, array_representation)) = it.next() {
+            output_handle = Arc::clone(codec)
+                .async_partial_encoder(
+                    input_handle.clone(),
+                    output_handle,
+                    array_representation,
+                    options,
+                )
+                .await?;
+
+            if it.peek().is_some() {
+                input_handle = Arc::clone(codec)
+                    .async_partial_decoder(input_handle, array_representation, options)
+                    .await?;
+            }
+        }
+
+        Ok(output_handle)
+    }
+
     fn compute_encoded_size(
         &self,
         decoded_representation: &ChunkRepresentation,
@@ -911,4 +1244,90 @@ mod tests {
             decoded_partial_chunk_true,
         );
     }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn codec_chain_profiler() {
+        use crate::array::codec::{CodecProfileOperation, CodecProfiler};
+
+        let chunk_representation = ChunkRepresentation::new(
+            vec![NonZeroU64::new(4).unwrap(); 2],
+            DataType::Float32,
+            FillValue::from(0f32),
+        )
+        .unwrap();
+        let elements: Vec<f32> = vec![1.0; 16];
+        let bytes: ArrayBytes = crate::array::transmute_to_bytes_vec(elements).into();
+
+        let codec_configurations: Vec<MetadataV3> = vec![
+            serde_json::from_str(JSON_BYTES).unwrap(),
+            serde_json::from_str(JSON_GZIP).unwrap(),
+        ];
+        let codec = CodecChain::from_metadata(&codec_configurations).unwrap();
+
+        let profiler = Arc::new(CodecProfiler::new());
+        let options = CodecOptions::builder()
+            .profiler(Some(profiler.clone()))
+            .build();
+        let encoded = codec
+            .encode(bytes.clone(), &chunk_representation, &options)
+            .unwrap();
+        let decoded = codec
+            .decode(encoded.clone(), &chunk_representation, &options)
+            .unwrap();
+        assert_eq!(bytes, decoded);
+
+        let records = profiler.records();
+        let records: Vec<_> = records
+            .iter()
+            .map(|record| (record.name(), record.operation()))
+            .collect();
+        assert_eq!(
+            records,
+            vec![
+                ("bytes", CodecProfileOperation::Encode),
+                ("gzip", CodecProfileOperation::Encode),
+                ("gzip", CodecProfileOperation::Decode),
+                ("bytes", CodecProfileOperation::Decode),
+            ]
+        );
+        let gzip_encode = &profiler.records()[1];
+        assert_eq!(gzip_encode.input_size(), 64);
+        assert_eq!(gzip_encode.output_size(), encoded.len());
+        assert_eq!(profiler.report().entries().len(), 4);
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn codec_chain_partial_decoder_cache_mode() {
+        let codec_chain = CodecChain::from_metadata(&[
+            serde_json::from_str(JSON_BYTES).unwrap(),
+            serde_json::from_str(JSON_GZIP).unwrap(),
+        ])
+        .unwrap();
+        assert_eq!(
+            codec_chain.partial_decoder_cache_mode(),
+            PartialDecoderCacheMode::Auto
+        );
+        let cache_index_auto = codec_chain.cache_index;
+        assert!(cache_index_auto.is_some());
+
+        let codec_chain =
+            codec_chain.with_partial_decoder_cache_mode(PartialDecoderCacheMode::Disabled);
+        assert_eq!(codec_chain.cache_index, None);
+        let codec_chain =
+            codec_chain.with_partial_decoder_cache_mode(PartialDecoderCacheMode::EncodedInput);
+        assert_eq!(codec_chain.cache_index, Some(0));
+        let codec_chain =
+            codec_chain.with_partial_decoder_cache_mode(PartialDecoderCacheMode::DecodedOutput);
+        assert_eq!(codec_chain.cache_index, Some(2));
+        let codec_chain =
+            codec_chain.with_partial_decoder_cache_mode(PartialDecoderCacheMode::Auto);
+        assert_eq!(codec_chain.cache_index, cache_index_auto);
+        assert_eq!(
+            codec_chain.partial_decoder_cache_mode(),
+            PartialDecoderCacheMode::Auto
+        );
+    }
 }
@@ -0,0 +1,488 @@
+//! The `jpegxl` array to bytes codec.
+//!
+//! Encodes a chunk as a [JPEG XL](https://jpeg.org/jpegxl/) image with lossless or lossy compression.
+//! Image codecs are well suited to microscopy and geospatial tiles, and JPEG XL encoded chunks can be displayed directly by supporting web browsers.
+//!
+//! The chunk shape must be `[height, width]` or `[height, width, channels]`, where the number of channels is in the range `[1, 4]`.
+//! A chunk with 1, 2, 3, or 4 channels is encoded as a greyscale, greyscale with alpha, RGB, or RGBA image respectively.
+//! The `uint8`, `uint16`, and `float32` data types are supported.
+//!
+//! <div class="warning">
+//! This codec is experimental and may be incompatible with other Zarr V3 implementations.
+//! </div>
+//!
+//! This codec requires the `jpegxl` feature, which is disabled by default.
+//!
+//! See [`JpegXlCodecConfigurationV1`] for example `JSON` metadata.
+
+mod jpegxl_codec;
+mod jpegxl_partial_decoder;
+
+use std::sync::Arc;
+
+use jpegxl_rs::{
+    decode::PixelFormat,
+    encode::{ColorEncoding, EncoderFrame, EncoderResult, EncoderSpeed},
+    Endianness,
+};
+
+pub use crate::metadata::v3::array::codec::jpegxl::{
+    JpegXlCodecConfiguration, JpegXlCodecConfigurationV1, JpegXlDistance, JpegXlEffort,
+};
+pub use jpegxl_codec::JpegXlCodec;
+
+use crate::{
+    array::{
+        codec::{Codec, CodecError, CodecPlugin},
+        convert_from_bytes_slice, transmute_to_bytes, ChunkRepresentation, DataType,
+    },
+    config::global_config,
+    metadata::v3::{array::codec::jpegxl, MetadataV3},
+    plugin::{PluginCreateError, PluginMetadataInvalidError},
+};
+
+pub use jpegxl::IDENTIFIER;
+
+// Register the codec.
+inventory::submit! {
+    CodecPlugin::new(IDENTIFIER, is_name_jpegxl, create_codec_jpegxl)
+}
+
+fn is_name_jpegxl(name: &str) -> bool {
+    name.eq(IDENTIFIER)
+        || name
+            == global_config()
+                .experimental_codec_names()
+                .get(IDENTIFIER)
+                .expect("experimental codec identifier in global map")
+}
+
+pub(crate) fn create_codec_jpegxl(metadata: &MetadataV3) -> Result<Codec, PluginCreateError> {
+    let configuration: JpegXlCodecConfiguration = metadata
+        .to_configuration()
+        .map_err(|_| PluginMetadataInvalidError::new(IDENTIFIER, "codec", metadata.clone()))?;
+    let codec = Arc::new(JpegXlCodec::new_with_configuration(&configuration));
+    Ok(Codec::ArrayToBytes(codec))
+}
+
+/// The layout of a chunk encoded as a JPEG XL image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct JpegXlImageLayout {
+    height: u32,
+    width: u32,
+    channels: u32,
+}
+
+impl JpegXlImageLayout {
+    /// Returns true if the image has an alpha channel.
+    const fn has_alpha(&self) -> bool {
+        self.channels == 2 || self.channels == 4
+    }
+
+    /// Returns true if the image is greyscale.
+    const fn is_grey(&self) -> bool {
+        self.channels <= 2
+    }
+}
+
+/// Return the image layout of a chunk with `decoded_representation`.
+///
+/// # Errors
+/// Returns a [`CodecError`] if the data type or chunk shape is not supported.
+fn jpegxl_image_layout(
+    decoded_representation: &ChunkRepresentation,
+) -> Result<JpegXlImageLayout, CodecError> {
+    match decoded_representation.data_type() {
+        DataType::UInt8 | DataType::UInt16 | DataType::Float32 => {}
+        data_type => {
+            return Err(CodecError::UnsupportedDataType(
+                data_type.clone(),
+                IDENTIFIER.to_string(),
+            ))
+        }
+    }
+    let shape = decoded_representation.shape_u64();
+    let (height, width, channels) = match shape.as_slice() {
+        [height, width] => (*height, *width, 1),
+        [height, width, channels] => (*height, *width, *channels),
+        _ => {
+            return Err(CodecError::Other(format!(
+                "the jpegxl codec requires a chunk shape of [height, width] or [height, width, channels], got {shape:?}"
+            )))
+        }
+    };
+    if !(1..=4).contains(&channels) {
+        return Err(CodecError::Other(format!(
+            "the jpegxl codec supports 1 to 4 channels, got {channels}"
+        )));
+    }
+    let dimension = |size: u64| {
+        u32::try_from(size).map_err(|_| {
+            CodecError::Other(format!(
+                "the jpegxl codec supports images up to {} pixels wide and high, got {shape:?}",
+                u32::MAX
+            ))
+        })
+    };
+    Ok(JpegXlImageLayout {
+        height: dimension(height)?,
+        width: dimension(width)?,
+        channels: dimension(channels)?,
+    })
+}
+
+/// Return the encoder speed corresponding to `effort`.
+const fn jpegxl_speed(effort: JpegXlEffort) -> EncoderSpeed {
+    match effort.get() {
+        1 => EncoderSpeed::Lightning,
+        2 => EncoderSpeed::Thunder,
+        3 => EncoderSpeed::Falcon,
+        4 => EncoderSpeed::Cheetah,
+        5 => EncoderSpeed::Hare,
+        6 => EncoderSpeed::Wombat,
+        7 => EncoderSpeed::Squirrel,
+        8 => EncoderSpeed::Kitten,
+        9 => EncoderSpeed::Tortoise,
+        _ => EncoderSpeed::Glacier,
+    }
+}
+
+fn jpegxl_encode(
+    decoded_value: &[u8],
+    decoded_representation: &ChunkRepresentation,
+    distance: JpegXlDistance,
+    effort: JpegXlEffort,
+) -> Result<Vec<u8>, CodecError> {
+    let layout = jpegxl_image_layout(decoded_representation)?;
+    let lossless = distance.is_lossless();
+    let mut encoder = jpegxl_rs::encoder_builder()
+        .has_alpha(layout.has_alpha())
+        .color_encoding(if layout.is_grey() {
+            ColorEncoding::SrgbLuma
+        } else {
+            ColorEncoding::Srgb
+        })
+        .lossless(lossless)
+        .uses_original_profile(lossless)
+        .quality(distance.get())
+        .speed(jpegxl_speed(effort))
+        .build()
+        .map_err(|err| CodecError::Other(err.to_string()))?;
+
+    macro_rules! jpegxl_encode {
+        ( $t:ty ) => {{
+            let elements = convert_from_bytes_slice::<$t>(decoded_value);
+            let frame = EncoderFrame::new(&elements).num_channels(layout.channels);
+            let result: EncoderResult<$t> = encoder
+                .encode_frame(&frame, layout.width, layout.height)
+                .map_err(|err| CodecError::Other(err.to_string()))?;
+            Ok(result.data)
+        }};
+    }
+
+    let data_type = decoded_representation.data_type();
+    match data_type {
+        DataType::UInt8 => jpegxl_encode!(u8),
+        DataType::UInt16 => jpegxl_encode!(u16),
+        DataType::Float32 => jpegxl_encode!(f32),
+        _ => Err(CodecError::UnsupportedDataType(
+            data_type.clone(),
+            IDENTIFIER.to_string(),
+        )),
+    }
+}
+
+fn jpegxl_decode(
+    encoded_value: &[u8],
+    decoded_representation: &ChunkRepresentation,
+) -> Result<Vec<u8>, CodecError> {
+    let layout = jpegxl_image_layout(decoded_representation)?;
+    let decoder = jpegxl_rs::decoder_builder()
+        .pixel_format(PixelFormat {
+            num_channels: layout.channels,
+            endianness: Endianness::Native,
+            align: 0,
+        })
+        .build()
+        .map_err(|err| CodecError::Other(err.to_string()))?;
+
+    macro_rules! jpegxl_decode {
+        ( $t:ty ) => {{
+            let (metadata, elements) = decoder
+                .decode_with::<$t>(encoded_value)
+                .map_err(|err| CodecError::Other(err.to_string()))?;
+            if metadata.width != layout.width || metadata.height != layout.height {
+                return Err(CodecError::Other(format!(
+                    "the decoded jpegxl image is {}x{}, expected {}x{}",
+                    metadata.width, metadata.height, layout.width, layout.height
+                )));
+            }
+            if elements.len() as u64 == decoded_representation.num_elements() {
+                Ok(transmute_to_bytes(&elements).to_vec())
+            } else {
+                Err(CodecError::UnexpectedChunkDecodedSize(
+                    elements.len() * std::mem::size_of::<$t>(),
+                    decoded_representation.num_elements() * std::mem::size_of::<$t>() as u64,
+                ))
+            }
+        }};
+    }
+
+    let data_type = decoded_representation.data_type();
+    match data_type {
+        DataType::UInt8 => jpegxl_decode!(u8),
+        DataType::UInt16 => jpegxl_decode!(u16),
+        DataType::Float32 => jpegxl_decode!(f32),
+        _ => Err(CodecError::UnsupportedDataType(
+            data_type.clone(),
+            IDENTIFIER.to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroU64, sync::Arc};
+
+    use crate::{
+        array::{
+            codec::{ArrayToBytesCodecTraits, CodecOptions, CodecTraits},
+            ArrayBytes, ChunkRepresentation, DataType, Element, ElementOwned, FillValue,
+        },
+        array_subset::ArraySubset,
+    };
+
+    use super::*;
+
+    const JSON_LOSSLESS: &str = r#"{
+        "effort": 3
+    }"#;
+
+    const JSON_LOSSY: &str = r#"{
+        "distance": 1.0,
+        "effort": 3
+    }"#;
+
+    fn chunk_representation(
+        shape: &[u64],
+        data_type: DataType,
+        fill_value: FillValue,
+    ) -> ChunkRepresentation {
+        ChunkRepresentation::new(
+            shape
+                .iter()
+                .map(|size| NonZeroU64::new(*size).unwrap())
+                .collect(),
+            data_type,
+            fill_value,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn codec_jpegxl_configuration() {
+        let configuration: JpegXlCodecConfiguration = serde_json::from_str(JSON_LOSSY).unwrap();
+        let codec = JpegXlCodec::new_with_configuration(&configuration);
+        assert_eq!(codec.distance(), JpegXlDistance::new(1.0).unwrap());
+        assert_eq!(codec.effort(), JpegXlEffort::new(3).unwrap());
+        let metadata = codec.create_metadata().unwrap();
+        assert_eq!(
+            metadata
+                .to_configuration::<JpegXlCodecConfiguration>()
+                .unwrap(),
+            configuration
+        );
+    }
+
+    #[test]
+    fn codec_jpegxl_round_trip_lossless_u8() {
+        for shape in [vec![16, 12], vec![16, 12, 2], vec![9, 14, 3], vec![8, 8, 4]] {
+            let chunk_representation =
+                chunk_representation(&shape, DataType::UInt8, FillValue::from(0u8));
+            let elements: Vec<u8> = (0..chunk_representation.num_elements())
+                .map(|i| (i * 7 % 256) as u8)
+                .collect();
+            let bytes = u8::into_array_bytes(&DataType::UInt8, &elements).unwrap();
+
+            let codec =
+                JpegXlCodec::new_with_configuration(&serde_json::from_str(JSON_LOSSLESS).unwrap());
+            let encoded = codec
+                .encode(bytes, &chunk_representation, &CodecOptions::default())
+                .unwrap();
+            let decoded = codec
+                .decode(encoded, &chunk_representation, &CodecOptions::default())
+                .unwrap();
+            assert_eq!(
+                elements,
+                u8::from_array_bytes(&DataType::UInt8, decoded).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn codec_jpegxl_round_trip_lossless_u16() {
+        let chunk_representation =
+            chunk_representation(&[20, 10], DataType::UInt16, FillValue::from(0u16));
+        let elements: Vec<u16> = (0..chunk_representation.num_elements())
+            .map(|i| (i * 263) as u16)
+            .collect();
+        let bytes = u16::into_array_bytes(&DataType::UInt16, &elements).unwrap();
+
+        let codec = JpegXlCodec::new(JpegXlDistance::LOSSLESS, JpegXlEffort::default());
+        let encoded = codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let decoded = codec
+            .decode(encoded, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        assert_eq!(
+            elements,
+            u16::from_array_bytes(&DataType::UInt16, decoded).unwrap()
+        );
+    }
+
+    #[test]
+    fn codec_jpegxl_round_trip_lossy_u8() {
+        let chunk_representation =
+            chunk_representation(&[32, 32, 3], DataType::UInt8, FillValue::from(0u8));
+        let elements: Vec<u8> = (0..chunk_representation.num_elements())
+            .map(|i| (i / 3 % 32 * 4) as u8)
+            .collect();
+        let bytes = u8::into_array_bytes(&DataType::UInt8, &elements).unwrap();
+
+        let codec = JpegXlCodec::new_with_configuration(&serde_json::from_str(JSON_LOSSY).unwrap());
+        let encoded = codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let decoded = codec
+            .decode(encoded, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let decoded_elements = u8::from_array_bytes(&DataType::UInt8, decoded).unwrap();
+        assert_eq!(elements.len(), decoded_elements.len());
+    }
+
+    #[test]
+    fn codec_jpegxl_unsupported() {
+        let codec =
+            JpegXlCodec::new_with_configuration(&serde_json::from_str(JSON_LOSSLESS).unwrap());
+        for chunk_representation in [
+            chunk_representation(&[4, 4], DataType::Int8, FillValue::from(0i8)),
+            chunk_representation(&[4], DataType::UInt8, FillValue::from(0u8)),
+            chunk_representation(&[4, 4, 5], DataType::UInt8, FillValue::from(0u8)),
+        ] {
+            let bytes = ArrayBytes::new_fill_value(
+                crate::array::ArraySize::new(
+                    chunk_representation.data_type().size(),
+                    chunk_representation.num_elements(),
+                ),
+                chunk_representation.fill_value(),
+            );
+            assert!(codec
+                .encode(bytes, &chunk_representation, &CodecOptions::default())
+                .is_err());
+            assert!(codec.compute_encoded_size(&chunk_representation).is_err());
+        }
+    }
+
+    #[test]
+    fn codec_jpegxl_partial_decode() {
+        let chunk_representation =
+            chunk_representation(&[6, 5, 3], DataType::UInt8, FillValue::from(0u8));
+        let elements: Vec<u8> = (0..chunk_representation.num_elements())
+            .map(|i| i as u8)
+            .collect();
+        let bytes = u8::into_array_bytes(&DataType::UInt8, &elements)
+            .unwrap()
+            .into_owned();
+
+        let codec = Arc::new(JpegXlCodec::new_with_configuration(
+            &serde_json::from_str(JSON_LOSSLESS).unwrap(),
+        ));
+        let encoded = codec
+            .encode(
+                bytes.clone(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+
+        let decoded_regions = [
+            ArraySubset::new_with_ranges(&[1..3, 0..5, 2..3]),
+            ArraySubset::new_with_ranges(&[5..6, 4..5, 0..3]),
+        ];
+        let input_handle = Arc::new(std::io::Cursor::new(encoded));
+        let partial_decoder = codec
+            .partial_decoder(
+                input_handle,
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        let decoded_partial_chunk = partial_decoder
+            .partial_decode(&decoded_regions, &CodecOptions::default())
+            .unwrap();
+        for (decoded_region, decoded_partial_chunk) in
+            decoded_regions.iter().zip(decoded_partial_chunk)
+        {
+            assert_eq!(
+                bytes
+                    .extract_array_subset(
+                        decoded_region,
+                        &chunk_representation.shape_u64(),
+                        chunk_representation.data_type()
+                    )
+                    .unwrap(),
+                decoded_partial_chunk
+            );
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn codec_jpegxl_async_partial_decode() {
+        let chunk_representation =
+            chunk_representation(&[6, 5], DataType::UInt16, FillValue::from(0u16));
+        let elements: Vec<u16> = (0..chunk_representation.num_elements())
+            .map(|i| (i * 1000) as u16)
+            .collect();
+        let bytes = u16::into_array_bytes(&DataType::UInt16, &elements)
+            .unwrap()
+            .into_owned();
+
+        let codec = Arc::new(JpegXlCodec::new_with_configuration(
+            &serde_json::from_str(JSON_LOSSLESS).unwrap(),
+        ));
+        let encoded = codec
+            .encode(
+                bytes.clone(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+
+        let decoded_regions = [ArraySubset::new_with_ranges(&[2..4, 1..3])];
+        let input_handle = Arc::new(std::io::Cursor::new(encoded));
+        let partial_decoder = codec
+            .async_partial_decoder(
+                input_handle,
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .await
+            .unwrap();
+        let decoded_partial_chunk = partial_decoder
+            .partial_decode(&decoded_regions, &CodecOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            bytes
+                .extract_array_subset(
+                    &decoded_regions[0],
+                    &chunk_representation.shape_u64(),
+                    chunk_representation.data_type()
+                )
+                .unwrap(),
+            decoded_partial_chunk[0]
+        );
+    }
+}
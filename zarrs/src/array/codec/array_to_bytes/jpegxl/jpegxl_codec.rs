@@ -0,0 +1,173 @@
+use std::sync::Arc;
+
+use crate::{
+    array::{
+        codec::{
+            ArrayBytes, ArrayCodecTraits, ArrayPartialDecoderTraits, ArrayPartialEncoderDefault,
+            ArrayPartialEncoderTraits, ArrayToBytesCodecTraits, BytesPartialDecoderTraits,
+            BytesPartialEncoderTraits, CodecError, CodecOptions, CodecTraits, RawBytes,
+            RecommendedConcurrency,
+        },
+        ArrayMetadataOptions, BytesRepresentation, ChunkRepresentation,
+    },
+    config::global_config,
+    metadata::v3::MetadataV3,
+};
+
+#[cfg(feature = "async")]
+use crate::array::codec::{AsyncArrayPartialDecoderTraits, AsyncBytesPartialDecoderTraits};
+
+use super::{
+    jpegxl_decode, jpegxl_encode, jpegxl_image_layout, jpegxl_partial_decoder,
+    JpegXlCodecConfiguration, JpegXlCodecConfigurationV1, JpegXlDistance, JpegXlEffort, IDENTIFIER,
+};
+
+/// A `jpegxl` codec implementation.
+#[derive(Clone, Copy, Debug)]
+pub struct JpegXlCodec {
+    distance: JpegXlDistance,
+    effort: JpegXlEffort,
+}
+
+impl JpegXlCodec {
+    /// Create a new `jpegxl` codec with a butteraugli distance and encoder effort.
+    #[must_use]
+    pub const fn new(distance: JpegXlDistance, effort: JpegXlEffort) -> Self {
+        Self { distance, effort }
+    }
+
+    /// Create a new `jpegxl` codec from configuration.
+    #[must_use]
+    pub const fn new_with_configuration(configuration: &JpegXlCodecConfiguration) -> Self {
+        let JpegXlCodecConfiguration::V1(configuration) = configuration;
+        Self::new(configuration.distance, configuration.effort)
+    }
+
+    /// Return the butteraugli distance.
+    #[must_use]
+    pub const fn distance(&self) -> JpegXlDistance {
+        self.distance
+    }
+
+    /// Return the encoder effort.
+    #[must_use]
+    pub const fn effort(&self) -> JpegXlEffort {
+        self.effort
+    }
+}
+
+impl CodecTraits for JpegXlCodec {
+    fn create_metadata_opt(&self, _options: &ArrayMetadataOptions) -> Option<MetadataV3> {
+        let configuration = JpegXlCodecConfiguration::V1(JpegXlCodecConfigurationV1 {
+            distance: self.distance,
+            effort: self.effort,
+        });
+        Some(
+            MetadataV3::new_with_serializable_configuration(
+                global_config()
+                    .experimental_codec_names()
+                    .get(IDENTIFIER)
+                    .expect("experimental codec identifier in global map"),
+                &configuration,
+            )
+            .expect("jpegxl configuration is valid json"),
+        )
+    }
+
+    fn partial_decoder_should_cache_input(&self) -> bool {
+        false
+    }
+
+    fn partial_decoder_decodes_all(&self) -> bool {
+        true
+    }
+}
+
+impl ArrayCodecTraits for JpegXlCodec {
+    fn recommended_concurrency(
+        &self,
+        _decoded_representation: &ChunkRepresentation,
+    ) -> Result<RecommendedConcurrency, CodecError> {
+        // the libjxl parallel runner is not used
+        Ok(RecommendedConcurrency::new_maximum(1))
+    }
+}
+
+#[cfg_attr(feature = "async", async_trait::async_trait)]
+impl ArrayToBytesCodecTraits for JpegXlCodec {
+    fn dynamic(self: Arc<Self>) -> Arc<dyn ArrayToBytesCodecTraits> {
+        self as Arc<dyn ArrayToBytesCodecTraits>
+    }
+
+    fn encode<'a>(
+        &self,
+        bytes: ArrayBytes<'a>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        let bytes = bytes.into_fixed()?;
+        Ok(jpegxl_encode(&bytes, decoded_representation, self.distance, self.effort)?.into())
+    }
+
+    fn decode<'a>(
+        &self,
+        bytes: RawBytes<'a>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<ArrayBytes<'a>, CodecError> {
+        Ok(ArrayBytes::from(jpegxl_decode(
+            &bytes,
+            decoded_representation,
+        )?))
+    }
+
+    fn partial_decoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn BytesPartialDecoderTraits>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn ArrayPartialDecoderTraits>, CodecError> {
+        Ok(Arc::new(jpegxl_partial_decoder::JpegXlPartialDecoder::new(
+            input_handle,
+            decoded_representation.clone(),
+        )?))
+    }
+
+    fn partial_encoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn BytesPartialDecoderTraits>,
+        output_handle: Arc<dyn BytesPartialEncoderTraits>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn ArrayPartialEncoderTraits>, CodecError> {
+        Ok(Arc::new(ArrayPartialEncoderDefault::new(
+            input_handle,
+            output_handle,
+            decoded_representation.clone(),
+            self,
+        )))
+    }
+
+    #[cfg(feature = "async")]
+    async fn async_partial_decoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn AsyncArrayPartialDecoderTraits>, CodecError> {
+        Ok(Arc::new(
+            jpegxl_partial_decoder::AsyncJpegXlPartialDecoder::new(
+                input_handle,
+                decoded_representation.clone(),
+            )?,
+        ))
+    }
+
+    fn compute_encoded_size(
+        &self,
+        decoded_representation: &ChunkRepresentation,
+    ) -> Result<BytesRepresentation, CodecError> {
+        jpegxl_image_layout(decoded_representation)?;
+        Ok(BytesRepresentation::UnboundedSize)
+    }
+}
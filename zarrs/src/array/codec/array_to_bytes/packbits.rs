@@ -0,0 +1,555 @@
+//! The `packbits` array to bytes codec.
+//!
+//! Packs the elements of a chunk (in C order) into a dense bitfield.
+//! `bool` elements are packed into 1 bit, and integer elements are packed into the bits `first_bit..=last_bit` of each element.
+//! This is well suited to masks and low-cardinality integer data, such as segmentation labels with few classes.
+//! The `bool` and integer data types are supported.
+//!
+//! ### Encoded Representation
+//! The packed bits of each element are written from the most significant bit (`last_bit`) to the least significant bit (`first_bit`).
+//! The bits of consecutive elements are concatenated, and each byte is filled from its most significant bit.
+//! The last byte is padded with zero bits.
+//!
+//! Depending on the `padding_encoding`, the number of padding bits is stored in a byte before (`first_byte`) or after (`last_byte`) the packed bits, or not at all (`none`).
+//! With the `first_byte` padding encoding, `bool` chunks are encoded identically to the `numcodecs` `packbits` codec.
+//! Zarr V2 arrays with a `packbits` filter are supported through this codec.
+//!
+//! Decoded signed integers are sign extended from `last_bit`.
+//!
+//! The encoded size is fixed, so partial decoding only retrieves the bytes holding the requested elements.
+//!
+//! <div class="warning">
+//! This codec is experimental and may be incompatible with other Zarr V3 implementations.
+//! </div>
+//!
+//! This codec requires the `packbits` feature, which is disabled by default.
+//!
+//! See [`PackBitsCodecConfigurationV1`] for example `JSON` metadata.
+
+mod packbits_codec;
+mod packbits_partial_decoder;
+
+use std::sync::Arc;
+
+pub use crate::metadata::v3::array::codec::packbits::{
+    PackBitsCodecConfiguration, PackBitsCodecConfigurationV1, PackBitsPaddingEncoding,
+};
+pub use packbits_codec::PackBitsCodec;
+
+use crate::{
+    array::{
+        codec::{Codec, CodecError, CodecPlugin},
+        DataType,
+    },
+    config::global_config,
+    metadata::v3::{array::codec::packbits, MetadataV3},
+    plugin::{PluginCreateError, PluginMetadataInvalidError},
+};
+
+pub use packbits::IDENTIFIER;
+
+// Register the codec.
+inventory::submit! {
+    CodecPlugin::new(IDENTIFIER, is_name_packbits, create_codec_packbits)
+}
+
+fn is_name_packbits(name: &str) -> bool {
+    name.eq(IDENTIFIER)
+        || name
+            == global_config()
+                .experimental_codec_names()
+                .get(IDENTIFIER)
+                .expect("experimental codec identifier in global map")
+}
+
+pub(crate) fn create_codec_packbits(metadata: &MetadataV3) -> Result<Codec, PluginCreateError> {
+    let configuration: PackBitsCodecConfiguration = metadata
+        .to_configuration()
+        .map_err(|_| PluginMetadataInvalidError::new(IDENTIFIER, "codec", metadata.clone()))?;
+    let codec = Arc::new(PackBitsCodec::new_with_configuration(&configuration));
+    Ok(Codec::ArrayToBytes(codec))
+}
+
+/// The packing of the elements of a chunk with the `packbits` codec.
+#[derive(Clone, Copy, Debug)]
+struct PackBitsLayout {
+    /// The size of a decoded element in bytes.
+    element_size: usize,
+    /// Whether decoded elements are sign extended.
+    signed: bool,
+    /// The first (least significant) packed bit of each element.
+    first_bit: u64,
+    /// The last (most significant) packed bit of each element.
+    last_bit: u64,
+    /// The encoding of the number of padding bits.
+    padding_encoding: PackBitsPaddingEncoding,
+}
+
+impl PackBitsLayout {
+    /// Create a new layout for elements of `data_type`.
+    ///
+    /// Returns an error if the data type is unsupported, or if the packed bits are invalid for the data type.
+    fn new(
+        data_type: &DataType,
+        padding_encoding: PackBitsPaddingEncoding,
+        first_bit: Option<u64>,
+        last_bit: Option<u64>,
+    ) -> Result<Self, CodecError> {
+        let (element_size, signed, num_bits) = match data_type {
+            DataType::Bool => (1, false, 1),
+            DataType::Int8 => (1, true, 8),
+            DataType::UInt8 => (1, false, 8),
+            DataType::Int16 => (2, true, 16),
+            DataType::UInt16 => (2, false, 16),
+            DataType::Int32 => (4, true, 32),
+            DataType::UInt32 => (4, false, 32),
+            DataType::Int64 => (8, true, 64),
+            DataType::UInt64 => (8, false, 64),
+            _ => {
+                return Err(CodecError::UnsupportedDataType(
+                    data_type.clone(),
+                    IDENTIFIER.to_string(),
+                ))
+            }
+        };
+        let first_bit = first_bit.unwrap_or(0);
+        let last_bit = last_bit.unwrap_or(num_bits - 1);
+        if first_bit > last_bit || last_bit >= num_bits {
+            return Err(CodecError::Other(format!(
+                "packbits first_bit {first_bit} and last_bit {last_bit} are invalid for data type {data_type}"
+            )));
+        }
+        Ok(Self {
+            element_size,
+            signed,
+            first_bit,
+            last_bit,
+            padding_encoding,
+        })
+    }
+
+    /// Return the number of packed bits of each element.
+    const fn bits_per_element(&self) -> u64 {
+        self.last_bit - self.first_bit + 1
+    }
+
+    /// Return the size of the packed bits of `num_elements` elements in bytes.
+    const fn packed_size(&self, num_elements: u64) -> u64 {
+        (num_elements * self.bits_per_element()).div_ceil(8)
+    }
+
+    /// Return the number of padding bits in the last packed byte of `num_elements` elements.
+    fn padding_bits(&self, num_elements: u64) -> u8 {
+        u8::try_from(self.packed_size(num_elements) * 8 - num_elements * self.bits_per_element())
+            .unwrap()
+    }
+
+    /// Return the offset of the packed bits in the encoded representation.
+    const fn packed_offset(&self) -> u64 {
+        match self.padding_encoding {
+            PackBitsPaddingEncoding::FirstByte => 1,
+            PackBitsPaddingEncoding::None | PackBitsPaddingEncoding::LastByte => 0,
+        }
+    }
+
+    /// Return the size of the encoded representation of `num_elements` elements.
+    const fn encoded_size(&self, num_elements: u64) -> u64 {
+        let padding_size = match self.padding_encoding {
+            PackBitsPaddingEncoding::None => 0,
+            PackBitsPaddingEncoding::FirstByte | PackBitsPaddingEncoding::LastByte => 1,
+        };
+        self.packed_size(num_elements) + padding_size
+    }
+
+    /// Unpack `num_elements` elements starting at bit `bit_offset` of `packed` and append them to `output`.
+    fn unpack_elements(
+        &self,
+        packed: &[u8],
+        bit_offset: u64,
+        num_elements: u64,
+        output: &mut Vec<u8>,
+    ) {
+        let mut bit = bit_offset;
+        for _ in 0..num_elements {
+            let mut value: u64 = 0;
+            for _ in 0..self.bits_per_element() {
+                let byte = packed[usize::try_from(bit / 8).unwrap()];
+                value = (value << 1) | u64::from((byte >> (7 - bit % 8)) & 1);
+                bit += 1;
+            }
+            value <<= self.first_bit;
+            if self.signed && (value >> self.last_bit) & 1 == 1 {
+                value |= u64::MAX << self.last_bit;
+            }
+            output.extend_from_slice(&value.to_ne_bytes()[..self.element_size]);
+        }
+    }
+}
+
+/// Convert a native endian element of up to 8 bytes to a [`u64`].
+fn element_to_u64(element: &[u8]) -> u64 {
+    match element.len() {
+        1 => u64::from(element[0]),
+        2 => u64::from(u16::from_ne_bytes(element.try_into().unwrap())),
+        4 => u64::from(u32::from_ne_bytes(element.try_into().unwrap())),
+        8 => u64::from_ne_bytes(element.try_into().unwrap()),
+        _ => unreachable!("packbits element sizes are 1, 2, 4, or 8"),
+    }
+}
+
+fn packbits_encode(
+    decoded_value: &[u8],
+    num_elements: u64,
+    layout: &PackBitsLayout,
+) -> Result<Vec<u8>, CodecError> {
+    let expected_size = num_elements * layout.element_size as u64;
+    if decoded_value.len() as u64 != expected_size {
+        return Err(CodecError::UnexpectedChunkDecodedSize(
+            decoded_value.len(),
+            expected_size,
+        ));
+    }
+
+    let packed_size = usize::try_from(layout.packed_size(num_elements)).unwrap();
+    let mut packed = vec![0u8; packed_size];
+    let mut bit: usize = 0;
+    for element in decoded_value.chunks_exact(layout.element_size) {
+        let value = element_to_u64(element) >> layout.first_bit;
+        for i in (0..layout.bits_per_element()).rev() {
+            if (value >> i) & 1 == 1 {
+                packed[bit / 8] |= 0x80 >> (bit % 8);
+            }
+            bit += 1;
+        }
+    }
+
+    let padding_bits = layout.padding_bits(num_elements);
+    Ok(match layout.padding_encoding {
+        PackBitsPaddingEncoding::None => packed,
+        PackBitsPaddingEncoding::FirstByte => {
+            let mut encoded_value = Vec::with_capacity(packed_size + 1);
+            encoded_value.push(padding_bits);
+            encoded_value.extend_from_slice(&packed);
+            encoded_value
+        }
+        PackBitsPaddingEncoding::LastByte => {
+            packed.push(padding_bits);
+            packed
+        }
+    })
+}
+
+fn packbits_decode(
+    encoded_value: &[u8],
+    num_elements: u64,
+    layout: &PackBitsLayout,
+) -> Result<Vec<u8>, CodecError> {
+    if encoded_value.len() as u64 != layout.encoded_size(num_elements) {
+        return Err(CodecError::Other(
+            "packbits encoded value has an unexpected length".to_string(),
+        ));
+    }
+    let padding_bits = match layout.padding_encoding {
+        PackBitsPaddingEncoding::None => None,
+        PackBitsPaddingEncoding::FirstByte => encoded_value.first(),
+        PackBitsPaddingEncoding::LastByte => encoded_value.last(),
+    };
+    if padding_bits.is_some_and(|&padding_bits| padding_bits != layout.padding_bits(num_elements)) {
+        return Err(CodecError::Other(
+            "packbits encoded number of padding bits is invalid".to_string(),
+        ));
+    }
+
+    let packed_offset = usize::try_from(layout.packed_offset()).unwrap();
+    let packed_size = usize::try_from(layout.packed_size(num_elements)).unwrap();
+    let packed = &encoded_value[packed_offset..packed_offset + packed_size];
+    let mut decoded_value =
+        Vec::with_capacity(usize::try_from(num_elements).unwrap() * layout.element_size);
+    layout.unpack_elements(packed, 0, num_elements, &mut decoded_value);
+    Ok(decoded_value)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroU64, sync::Arc};
+
+    use crate::{
+        array::{
+            codec::{ArrayToBytesCodecTraits, CodecOptions, CodecTraits},
+            ArrayBytes, ChunkRepresentation, DataType, Element, ElementOwned, FillValue,
+        },
+        array_subset::ArraySubset,
+    };
+
+    use super::*;
+
+    const JSON_VALID: &str = r#"{
+        "padding_encoding": "last_byte",
+        "first_bit": 1,
+        "last_bit": 3
+    }"#;
+
+    fn chunk_representation(data_type: DataType, fill_value: FillValue) -> ChunkRepresentation {
+        ChunkRepresentation::new(
+            vec![
+                NonZeroU64::new(6).unwrap(),
+                NonZeroU64::new(5).unwrap(),
+                NonZeroU64::new(3).unwrap(),
+            ],
+            data_type,
+            fill_value,
+        )
+        .unwrap()
+    }
+
+    /// Labels with a low cardinality in bits 1 to 3.
+    fn labels(num_elements: u64) -> Vec<u16> {
+        (0..num_elements)
+            .map(|i| u16::try_from((i % 7) << 1).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn codec_packbits_configuration() {
+        let configuration: PackBitsCodecConfiguration = serde_json::from_str(JSON_VALID).unwrap();
+        let codec = PackBitsCodec::new_with_configuration(&configuration);
+        let metadata = codec.create_metadata().unwrap();
+        assert_eq!(
+            metadata
+                .to_configuration::<PackBitsCodecConfiguration>()
+                .unwrap(),
+            configuration
+        );
+    }
+
+    #[test]
+    fn codec_packbits_encoded_numcodecs() {
+        // Matches numcodecs.PackBits().encode(...)
+        let chunk_representation = ChunkRepresentation::new(
+            vec![NonZeroU64::new(10).unwrap()],
+            DataType::Bool,
+            FillValue::from(false),
+        )
+        .unwrap();
+        let elements = vec![
+            true, false, true, true, false, false, false, false, true, true,
+        ];
+        let bytes = bool::into_array_bytes(&DataType::Bool, &elements).unwrap();
+        let codec = PackBitsCodec::new(PackBitsPaddingEncoding::FirstByte, None, None);
+        let encoded = codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        assert_eq!(encoded.to_vec(), vec![6, 0b1011_0000, 0b1100_0000]);
+        let decoded = codec
+            .decode(encoded, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        assert_eq!(
+            elements,
+            bool::from_array_bytes(&DataType::Bool, decoded).unwrap()
+        );
+    }
+
+    #[test]
+    fn codec_packbits_round_trip() {
+        let chunk_representation = chunk_representation(DataType::UInt16, FillValue::from(0u16));
+        let elements = labels(chunk_representation.num_elements());
+        let bytes = u16::into_array_bytes(&DataType::UInt16, &elements).unwrap();
+
+        let codec =
+            PackBitsCodec::new_with_configuration(&serde_json::from_str(JSON_VALID).unwrap());
+        let encoded = codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        // 90 elements * 3 bits = 34 bytes, including 2 padding bits
+        assert_eq!(encoded.len(), 34 + 1);
+        assert_eq!(encoded.last(), Some(&2));
+        let decoded = codec
+            .decode(encoded, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        assert_eq!(
+            elements,
+            u16::from_array_bytes(&DataType::UInt16, decoded).unwrap()
+        );
+    }
+
+    #[test]
+    fn codec_packbits_round_trip_signed() {
+        let chunk_representation = chunk_representation(DataType::Int32, FillValue::from(0i32));
+        let elements: Vec<i32> = (0..chunk_representation.num_elements())
+            .map(|i| i32::try_from(i % 9).unwrap() - 4)
+            .collect();
+        let bytes = i32::into_array_bytes(&DataType::Int32, &elements).unwrap();
+
+        let codec = PackBitsCodec::new(PackBitsPaddingEncoding::None, None, Some(3));
+        let encoded = codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        assert_eq!(encoded.len(), 45);
+        let decoded = codec
+            .decode(encoded, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        assert_eq!(
+            elements,
+            i32::from_array_bytes(&DataType::Int32, decoded).unwrap()
+        );
+    }
+
+    #[test]
+    fn codec_packbits_invalid() {
+        let chunk_representation = chunk_representation(DataType::UInt8, FillValue::from(0u8));
+        let bytes = ArrayBytes::new_fill_value(
+            crate::array::ArraySize::new(
+                chunk_representation.data_type().size(),
+                chunk_representation.num_elements(),
+            ),
+            chunk_representation.fill_value(),
+        );
+
+        // the last bit exceeds the data type
+        let codec = PackBitsCodec::new(PackBitsPaddingEncoding::None, None, Some(8));
+        assert!(codec
+            .encode(
+                bytes.clone(),
+                &chunk_representation,
+                &CodecOptions::default()
+            )
+            .is_err());
+        // the first bit is after the last bit
+        let codec = PackBitsCodec::new(PackBitsPaddingEncoding::None, Some(4), Some(3));
+        assert!(codec.compute_encoded_size(&chunk_representation).is_err());
+
+        let codec = PackBitsCodec::new(PackBitsPaddingEncoding::FirstByte, None, Some(0));
+        let mut encoded = codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .unwrap()
+            .to_vec();
+        // the encoded value is truncated
+        assert!(codec
+            .decode(
+                encoded[..encoded.len() - 1].to_vec().into(),
+                &chunk_representation,
+                &CodecOptions::default()
+            )
+            .is_err());
+        // the number of padding bits is incorrect
+        encoded[0] = 7;
+        assert!(codec
+            .decode(
+                encoded.into(),
+                &chunk_representation,
+                &CodecOptions::default()
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn codec_packbits_unsupported_data_type() {
+        let chunk_representation = chunk_representation(DataType::Float32, FillValue::from(0f32));
+        let codec = PackBitsCodec::default();
+        assert!(codec.compute_encoded_size(&chunk_representation).is_err());
+    }
+
+    #[test]
+    fn codec_packbits_partial_decode() {
+        let chunk_representation = chunk_representation(DataType::UInt16, FillValue::from(0u16));
+        let elements = labels(chunk_representation.num_elements());
+        let bytes = u16::into_array_bytes(&DataType::UInt16, &elements)
+            .unwrap()
+            .into_owned();
+
+        let codec = Arc::new(PackBitsCodec::new_with_configuration(
+            &serde_json::from_str(JSON_VALID).unwrap(),
+        ));
+        let encoded = codec
+            .encode(
+                bytes.clone(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+
+        let decoded_regions = [
+            ArraySubset::new_with_ranges(&[1..3, 0..5, 2..3]),
+            ArraySubset::new_with_ranges(&[5..6, 4..5, 0..3]),
+            ArraySubset::new_with_ranges(&[0..6, 0..5, 0..3]),
+        ];
+        let input_handle = Arc::new(std::io::Cursor::new(encoded));
+        let partial_decoder = codec
+            .partial_decoder(
+                input_handle,
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        let decoded_partial_chunk = partial_decoder
+            .partial_decode(&decoded_regions, &CodecOptions::default())
+            .unwrap();
+        for (decoded_region, decoded_partial_chunk) in
+            decoded_regions.iter().zip(decoded_partial_chunk)
+        {
+            assert_eq!(
+                bytes
+                    .extract_array_subset(
+                        decoded_region,
+                        &chunk_representation.shape_u64(),
+                        chunk_representation.data_type()
+                    )
+                    .unwrap(),
+                decoded_partial_chunk
+            );
+        }
+
+        assert!(partial_decoder
+            .partial_decode(
+                &[ArraySubset::new_with_ranges(&[5..7, 0..5, 0..3])],
+                &CodecOptions::default()
+            )
+            .is_err());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn codec_packbits_async_partial_decode() {
+        let chunk_representation = chunk_representation(DataType::UInt16, FillValue::from(0u16));
+        let elements = labels(chunk_representation.num_elements());
+        let bytes = u16::into_array_bytes(&DataType::UInt16, &elements)
+            .unwrap()
+            .into_owned();
+
+        let codec = Arc::new(PackBitsCodec::new_with_configuration(
+            &serde_json::from_str(JSON_VALID).unwrap(),
+        ));
+        let encoded = codec
+            .encode(
+                bytes.clone(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+
+        let decoded_regions = [ArraySubset::new_with_ranges(&[2..4, 1..3, 1..3])];
+        let input_handle = Arc::new(std::io::Cursor::new(encoded));
+        let partial_decoder = codec
+            .async_partial_decoder(
+                input_handle,
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .await
+            .unwrap();
+        let decoded_partial_chunk = partial_decoder
+            .partial_decode(&decoded_regions, &CodecOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            bytes
+                .extract_array_subset(
+                    &decoded_regions[0],
+                    &chunk_representation.shape_u64(),
+                    chunk_representation.data_type()
+                )
+                .unwrap(),
+            decoded_partial_chunk[0]
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use std::sync::Arc;
+
+use crate::{
+    array::{
+        codec::{
+            ArrayBytes, ArrayCodecTraits, ArrayPartialDecoderTraits, ArrayPartialEncoderDefault,
+            ArrayPartialEncoderTraits, ArrayToBytesCodecTraits, BytesPartialDecoderTraits,
+            BytesPartialEncoderTraits, CodecError, CodecOptions, CodecTraits, RawBytes,
+            RecommendedConcurrency,
+        },
+        ArrayMetadataOptions, BytesRepresentation, ChunkRepresentation, DataType,
+    },
+    config::global_config,
+    metadata::v3::MetadataV3,
+};
+
+#[cfg(feature = "async")]
+use crate::array::codec::{AsyncArrayPartialDecoderTraits, AsyncBytesPartialDecoderTraits};
+
+use super::{
+    packbits_decode, packbits_encode, packbits_partial_decoder, PackBitsCodecConfiguration,
+    PackBitsCodecConfigurationV1, PackBitsLayout, PackBitsPaddingEncoding, IDENTIFIER,
+};
+
+/// A `packbits` codec implementation.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PackBitsCodec {
+    padding_encoding: PackBitsPaddingEncoding,
+    first_bit: Option<u64>,
+    last_bit: Option<u64>,
+}
+
+impl PackBitsCodec {
+    /// Create a new `packbits` codec.
+    ///
+    /// `first_bit` and `last_bit` default to the first and last bit of the data type if [`None`].
+    #[must_use]
+    pub const fn new(
+        padding_encoding: PackBitsPaddingEncoding,
+        first_bit: Option<u64>,
+        last_bit: Option<u64>,
+    ) -> Self {
+        Self {
+            padding_encoding,
+            first_bit,
+            last_bit,
+        }
+    }
+
+    /// Create a new `packbits` codec from configuration.
+    #[must_use]
+    pub const fn new_with_configuration(configuration: &PackBitsCodecConfiguration) -> Self {
+        let PackBitsCodecConfiguration::V1(configuration) = configuration;
+        Self::new(
+            configuration.padding_encoding,
+            configuration.first_bit,
+            configuration.last_bit,
+        )
+    }
+
+    fn layout(&self, data_type: &DataType) -> Result<PackBitsLayout, CodecError> {
+        PackBitsLayout::new(
+            data_type,
+            self.padding_encoding,
+            self.first_bit,
+            self.last_bit,
+        )
+    }
+}
+
+impl CodecTraits for PackBitsCodec {
+    fn create_metadata_opt(&self, _options: &ArrayMetadataOptions) -> Option<MetadataV3> {
+        let configuration = PackBitsCodecConfiguration::V1(PackBitsCodecConfigurationV1 {
+            padding_encoding: self.padding_encoding,
+            first_bit: self.first_bit,
+            last_bit: self.last_bit,
+        });
+        Some(
+            MetadataV3::new_with_serializable_configuration(
+                global_config()
+                    .experimental_codec_names()
+                    .get(IDENTIFIER)
+                    .expect("experimental codec identifier in global map"),
+                &configuration,
+            )
+            .expect("packbits configuration is valid json"),
+        )
+    }
+
+    fn partial_decoder_should_cache_input(&self) -> bool {
+        false
+    }
+
+    fn partial_decoder_decodes_all(&self) -> bool {
+        false
+    }
+}
+
+impl ArrayCodecTraits for PackBitsCodec {
+    fn recommended_concurrency(
+        &self,
+        _decoded_representation: &ChunkRepresentation,
+    ) -> Result<RecommendedConcurrency, CodecError> {
+        Ok(RecommendedConcurrency::new_maximum(1))
+    }
+}
+
+#[cfg_attr(feature = "async", async_trait::async_trait)]
+impl ArrayToBytesCodecTraits for PackBitsCodec {
+    fn dynamic(self: Arc<Self>) -> Arc<dyn ArrayToBytesCodecTraits> {
+        self as Arc<dyn ArrayToBytesCodecTraits>
+    }
+
+    fn encode<'a>(
+        &self,
+        bytes: ArrayBytes<'a>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        let layout = self.layout(decoded_representation.data_type())?;
+        let bytes = bytes.into_fixed()?;
+        Ok(packbits_encode(&bytes, decoded_representation.num_elements(), &layout)?.into())
+    }
+
+    fn decode<'a>(
+        &self,
+        bytes: RawBytes<'a>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<ArrayBytes<'a>, CodecError> {
+        let layout = self.layout(decoded_representation.data_type())?;
+        Ok(ArrayBytes::from(packbits_decode(
+            &bytes,
+            decoded_representation.num_elements(),
+            &layout,
+        )?))
+    }
+
+    fn partial_decoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn BytesPartialDecoderTraits>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn ArrayPartialDecoderTraits>, CodecError> {
+        let layout = self.layout(decoded_representation.data_type())?;
+        Ok(Arc::new(
+            packbits_partial_decoder::PackBitsPartialDecoder::new(
+                input_handle,
+                decoded_representation.clone(),
+                layout,
+            ),
+        ))
+    }
+
+    fn partial_encoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn BytesPartialDecoderTraits>,
+        output_handle: Arc<dyn BytesPartialEncoderTraits>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn ArrayPartialEncoderTraits>, CodecError> {
+        Ok(Arc::new(ArrayPartialEncoderDefault::new(
+            input_handle,
+            output_handle,
+            decoded_representation.clone(),
+            self,
+        )))
+    }
+
+    #[cfg(feature = "async")]
+    async fn async_partial_decoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn AsyncArrayPartialDecoderTraits>, CodecError> {
+        let layout = self.layout(decoded_representation.data_type())?;
+        Ok(Arc::new(
+            packbits_partial_decoder::AsyncPackBitsPartialDecoder::new(
+                input_handle,
+                decoded_representation.clone(),
+                layout,
+            ),
+        ))
+    }
+
+    fn compute_encoded_size(
+        &self,
+        decoded_representation: &ChunkRepresentation,
+    ) -> Result<BytesRepresentation, CodecError> {
+        let layout = self.layout(decoded_representation.data_type())?;
+        Ok(BytesRepresentation::FixedSize(
+            layout.encoded_size(decoded_representation.num_elements()),
+        ))
+    }
+}
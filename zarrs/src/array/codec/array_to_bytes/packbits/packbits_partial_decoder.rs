@@ -0,0 +1,237 @@
+use std::{ops::Range, sync::Arc};
+
+use crate::{
+    array::{
+        codec::{
+            ArrayBytes, ArrayPartialDecoderTraits, BytesPartialDecoderTraits, CodecError,
+            CodecOptions,
+        },
+        ArraySize, ChunkRepresentation, DataType, RawBytes,
+    },
+    array_subset::ArraySubset,
+    byte_range::ByteRange,
+};
+
+#[cfg(feature = "async")]
+use crate::array::codec::{AsyncArrayPartialDecoderTraits, AsyncBytesPartialDecoderTraits};
+
+use super::PackBitsLayout;
+
+/// The packed elements of a decoded region.
+struct PackBitsRegion {
+    /// The linearised element ranges of the region.
+    elements: Vec<Range<u64>>,
+    /// The number of elements in the region.
+    num_elements: u64,
+}
+
+impl PackBitsRegion {
+    fn new(array_subset: &ArraySubset, chunk_shape: &[u64]) -> Result<Self, CodecError> {
+        let contiguous_indices = array_subset.contiguous_linearised_indices(chunk_shape)?;
+        let contiguous_elements = contiguous_indices.contiguous_elements();
+        let elements: Vec<Range<u64>> = contiguous_indices
+            .iter()
+            .map(|start| start..start + contiguous_elements)
+            .filter(|elements| !elements.is_empty())
+            .collect();
+        Ok(Self {
+            elements,
+            num_elements: array_subset.num_elements(),
+        })
+    }
+
+    /// Return the encoded byte ranges of the packed bits of each element range of the region.
+    fn byte_ranges(&self, layout: &PackBitsLayout) -> impl Iterator<Item = ByteRange> + '_ {
+        let bits_per_element = layout.bits_per_element();
+        let packed_offset = layout.packed_offset();
+        self.elements.iter().map(move |elements| {
+            let start = elements.start * bits_per_element / 8;
+            let end = (elements.end * bits_per_element).div_ceil(8);
+            ByteRange::FromStart(packed_offset + start, Some(end - start))
+        })
+    }
+
+    /// Decode the region from the `packed` bytes of each of its element ranges.
+    fn decode(&self, layout: &PackBitsLayout, packed: &[RawBytes<'_>]) -> ArrayBytes<'static> {
+        let bits_per_element = layout.bits_per_element();
+        let mut decoded =
+            Vec::with_capacity(usize::try_from(self.num_elements).unwrap() * layout.element_size);
+        for (elements, packed) in self.elements.iter().zip(packed) {
+            layout.unpack_elements(
+                packed,
+                (elements.start * bits_per_element) % 8,
+                elements.end - elements.start,
+                &mut decoded,
+            );
+        }
+        ArrayBytes::from(decoded)
+    }
+}
+
+fn validate_decoded_regions(
+    decoded_regions: &[ArraySubset],
+    decoded_representation: &ChunkRepresentation,
+) -> Result<(), CodecError> {
+    for array_subset in decoded_regions {
+        if array_subset.dimensionality() != decoded_representation.dimensionality() {
+            return Err(CodecError::InvalidArraySubsetDimensionalityError(
+                array_subset.clone(),
+                decoded_representation.dimensionality(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn fill_value_regions(
+    decoded_regions: &[ArraySubset],
+    decoded_representation: &ChunkRepresentation,
+) -> Vec<ArrayBytes<'static>> {
+    decoded_regions
+        .iter()
+        .map(|array_subset| {
+            let array_size = ArraySize::new(
+                decoded_representation.data_type().size(),
+                array_subset.num_elements(),
+            );
+            ArrayBytes::new_fill_value(array_size, decoded_representation.fill_value())
+        })
+        .collect()
+}
+
+/// Return the decoded regions and the encoded byte ranges of their packed bits.
+fn packbits_regions(
+    decoded_regions: &[ArraySubset],
+    decoded_representation: &ChunkRepresentation,
+    layout: &PackBitsLayout,
+) -> Result<(Vec<PackBitsRegion>, Vec<ByteRange>), CodecError> {
+    let chunk_shape = decoded_representation.shape_u64();
+    let regions = decoded_regions
+        .iter()
+        .map(|array_subset| PackBitsRegion::new(array_subset, &chunk_shape))
+        .collect::<Result<Vec<_>, _>>()?;
+    let byte_ranges = regions
+        .iter()
+        .flat_map(|region| region.byte_ranges(layout))
+        .collect();
+    Ok((regions, byte_ranges))
+}
+
+fn decode_regions(
+    regions: &[PackBitsRegion],
+    layout: &PackBitsLayout,
+    packed: &[RawBytes<'_>],
+) -> Vec<ArrayBytes<'static>> {
+    let mut offset = 0;
+    regions
+        .iter()
+        .map(|region| {
+            let num_ranges = region.elements.len();
+            let decoded = region.decode(layout, &packed[offset..offset + num_ranges]);
+            offset += num_ranges;
+            decoded
+        })
+        .collect()
+}
+
+/// Partial decoder for the `packbits` codec.
+pub(crate) struct PackBitsPartialDecoder<'a> {
+    input_handle: Arc<dyn BytesPartialDecoderTraits + 'a>,
+    decoded_representation: ChunkRepresentation,
+    layout: PackBitsLayout,
+}
+
+impl<'a> PackBitsPartialDecoder<'a> {
+    /// Create a new partial decoder for the `packbits` codec.
+    pub(crate) fn new(
+        input_handle: Arc<dyn BytesPartialDecoderTraits + 'a>,
+        decoded_representation: ChunkRepresentation,
+        layout: PackBitsLayout,
+    ) -> Self {
+        Self {
+            input_handle,
+            decoded_representation,
+            layout,
+        }
+    }
+}
+
+impl ArrayPartialDecoderTraits for PackBitsPartialDecoder<'_> {
+    fn data_type(&self) -> &DataType {
+        self.decoded_representation.data_type()
+    }
+
+    fn partial_decode(
+        &self,
+        decoded_regions: &[ArraySubset],
+        options: &CodecOptions,
+    ) -> Result<Vec<ArrayBytes<'_>>, CodecError> {
+        validate_decoded_regions(decoded_regions, &self.decoded_representation)?;
+
+        // Only read the bytes holding the packed bits of the decoded regions
+        let (regions, byte_ranges) =
+            packbits_regions(decoded_regions, &self.decoded_representation, &self.layout)?;
+        let Some(packed) = self.input_handle.partial_decode(&byte_ranges, options)? else {
+            return Ok(fill_value_regions(
+                decoded_regions,
+                &self.decoded_representation,
+            ));
+        };
+        Ok(decode_regions(&regions, &self.layout, &packed))
+    }
+}
+
+#[cfg(feature = "async")]
+/// Asynchronous partial decoder for the `packbits` codec.
+pub(crate) struct AsyncPackBitsPartialDecoder {
+    input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+    decoded_representation: ChunkRepresentation,
+    layout: PackBitsLayout,
+}
+
+#[cfg(feature = "async")]
+impl AsyncPackBitsPartialDecoder {
+    /// Create a new partial decoder for the `packbits` codec.
+    pub(crate) fn new(
+        input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+        decoded_representation: ChunkRepresentation,
+        layout: PackBitsLayout,
+    ) -> Self {
+        Self {
+            input_handle,
+            decoded_representation,
+            layout,
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl AsyncArrayPartialDecoderTraits for AsyncPackBitsPartialDecoder {
+    fn data_type(&self) -> &DataType {
+        self.decoded_representation.data_type()
+    }
+
+    async fn partial_decode(
+        &self,
+        decoded_regions: &[ArraySubset],
+        options: &CodecOptions,
+    ) -> Result<Vec<ArrayBytes<'_>>, CodecError> {
+        validate_decoded_regions(decoded_regions, &self.decoded_representation)?;
+
+        // Only read the bytes holding the packed bits of the decoded regions
+        let (regions, byte_ranges) =
+            packbits_regions(decoded_regions, &self.decoded_representation, &self.layout)?;
+        let Some(packed) = self
+            .input_handle
+            .partial_decode(&byte_ranges, options)
+            .await?
+        else {
+            return Ok(fill_value_regions(
+                decoded_regions,
+                &self.decoded_representation,
+            ));
+        };
+        Ok(decode_regions(&regions, &self.layout, &packed))
+    }
+}
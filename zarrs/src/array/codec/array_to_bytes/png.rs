@@ -0,0 +1,426 @@
+//! The `png` array to bytes codec.
+//!
+//! Encodes a chunk as a [PNG](https://www.w3.org/TR/png/) image with lossless compression.
+//! Image codecs are well suited to microscopy and geospatial tiles, and PNG encoded chunks can be displayed directly by web browsers.
+//!
+//! The chunk shape must be `[height, width]` or `[height, width, channels]`, where the number of channels is in the range `[1, 4]`.
+//! A chunk with 1, 2, 3, or 4 channels is encoded as a greyscale, greyscale with alpha, RGB, or RGBA image respectively.
+//! The `uint8` and `uint16` data types are supported.
+//!
+//! <div class="warning">
+//! This codec is experimental and may be incompatible with other Zarr V3 implementations.
+//! </div>
+//!
+//! This codec requires the `png` feature, which is disabled by default.
+//!
+//! See [`PngCodecConfigurationV1`] for example `JSON` metadata.
+
+mod png_codec;
+mod png_partial_decoder;
+
+use std::sync::Arc;
+
+pub use crate::metadata::v3::array::codec::png::{
+    PngCodecConfiguration, PngCodecConfigurationV1, PngCompression,
+};
+pub use png_codec::PngCodec;
+
+use crate::{
+    array::{
+        codec::{Codec, CodecError, CodecPlugin},
+        ChunkRepresentation, DataType,
+    },
+    config::global_config,
+    metadata::v3::{array::codec::png, MetadataV3},
+    plugin::{PluginCreateError, PluginMetadataInvalidError},
+};
+
+pub use png::IDENTIFIER;
+
+// Register the codec.
+inventory::submit! {
+    CodecPlugin::new(IDENTIFIER, is_name_png, create_codec_png)
+}
+
+fn is_name_png(name: &str) -> bool {
+    name.eq(IDENTIFIER)
+        || name
+            == global_config()
+                .experimental_codec_names()
+                .get(IDENTIFIER)
+                .expect("experimental codec identifier in global map")
+}
+
+pub(crate) fn create_codec_png(metadata: &MetadataV3) -> Result<Codec, PluginCreateError> {
+    let configuration: PngCodecConfiguration = metadata
+        .to_configuration()
+        .map_err(|_| PluginMetadataInvalidError::new(IDENTIFIER, "codec", metadata.clone()))?;
+    let codec = Arc::new(PngCodec::new_with_configuration(&configuration));
+    Ok(Codec::ArrayToBytes(codec))
+}
+
+/// The layout of a chunk encoded as a PNG image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct PngImageLayout {
+    height: u32,
+    width: u32,
+    color_type: ::png::ColorType,
+    bit_depth: ::png::BitDepth,
+}
+
+/// Return the image layout of a chunk with `decoded_representation`.
+///
+/// # Errors
+/// Returns a [`CodecError`] if the data type or chunk shape is not supported.
+fn png_image_layout(
+    decoded_representation: &ChunkRepresentation,
+) -> Result<PngImageLayout, CodecError> {
+    let bit_depth = match decoded_representation.data_type() {
+        DataType::UInt8 => ::png::BitDepth::Eight,
+        DataType::UInt16 => ::png::BitDepth::Sixteen,
+        data_type => {
+            return Err(CodecError::UnsupportedDataType(
+                data_type.clone(),
+                IDENTIFIER.to_string(),
+            ))
+        }
+    };
+    let shape = decoded_representation.shape_u64();
+    let (height, width, channels) = match shape.as_slice() {
+        [height, width] => (*height, *width, 1),
+        [height, width, channels] => (*height, *width, *channels),
+        _ => {
+            return Err(CodecError::Other(format!(
+                "the png codec requires a chunk shape of [height, width] or [height, width, channels], got {shape:?}"
+            )))
+        }
+    };
+    let color_type = match channels {
+        1 => ::png::ColorType::Grayscale,
+        2 => ::png::ColorType::GrayscaleAlpha,
+        3 => ::png::ColorType::Rgb,
+        4 => ::png::ColorType::Rgba,
+        _ => {
+            return Err(CodecError::Other(format!(
+                "the png codec supports 1 to 4 channels, got {channels}"
+            )))
+        }
+    };
+    let dimension = |size: u64| {
+        u32::try_from(size).map_err(|_| {
+            CodecError::Other(format!(
+                "the png codec supports images up to {} pixels wide and high, got {shape:?}",
+                u32::MAX
+            ))
+        })
+    };
+    Ok(PngImageLayout {
+        height: dimension(height)?,
+        width: dimension(width)?,
+        color_type,
+        bit_depth,
+    })
+}
+
+/// Convert the bytes of `uint16` elements between the native and big endian (PNG) byte order.
+fn swap_u16_be(bytes: &mut [u8]) {
+    if cfg!(target_endian = "little") {
+        for element in bytes.chunks_exact_mut(2) {
+            element.swap(0, 1);
+        }
+    }
+}
+
+fn png_encode(
+    decoded_value: &[u8],
+    decoded_representation: &ChunkRepresentation,
+    compression: PngCompression,
+) -> Result<Vec<u8>, CodecError> {
+    let layout = png_image_layout(decoded_representation)?;
+
+    let mut encoded_value = Vec::new();
+    let mut encoder = ::png::Encoder::new(&mut encoded_value, layout.width, layout.height);
+    encoder.set_color(layout.color_type);
+    encoder.set_depth(layout.bit_depth);
+    encoder.set_compression(match compression {
+        PngCompression::Fast => ::png::Compression::Fast,
+        PngCompression::Default => ::png::Compression::Default,
+        PngCompression::Best => ::png::Compression::Best,
+    });
+    let mut writer = encoder
+        .write_header()
+        .map_err(|err| CodecError::Other(err.to_string()))?;
+    if layout.bit_depth == ::png::BitDepth::Sixteen {
+        let mut data = decoded_value.to_vec();
+        swap_u16_be(&mut data);
+        writer.write_image_data(&data)
+    } else {
+        writer.write_image_data(decoded_value)
+    }
+    .map_err(|err| CodecError::Other(err.to_string()))?;
+    writer
+        .finish()
+        .map_err(|err| CodecError::Other(err.to_string()))?;
+    Ok(encoded_value)
+}
+
+fn png_decode(
+    encoded_value: &[u8],
+    decoded_representation: &ChunkRepresentation,
+) -> Result<Vec<u8>, CodecError> {
+    let layout = png_image_layout(decoded_representation)?;
+
+    let mut decoder = ::png::Decoder::new(encoded_value);
+    decoder.set_transformations(::png::Transformations::IDENTITY);
+    let mut reader = decoder
+        .read_info()
+        .map_err(|err| CodecError::Other(err.to_string()))?;
+    let mut decoded_value = vec![0; reader.output_buffer_size()];
+    let info = reader
+        .next_frame(&mut decoded_value)
+        .map_err(|err| CodecError::Other(err.to_string()))?;
+    let decoded_layout = PngImageLayout {
+        height: info.height,
+        width: info.width,
+        color_type: info.color_type,
+        bit_depth: info.bit_depth,
+    };
+    if decoded_layout != layout {
+        return Err(CodecError::Other(format!(
+            "the decoded png image {decoded_layout:?} does not match the chunk representation {layout:?}"
+        )));
+    }
+    decoded_value.truncate(info.buffer_size());
+    if layout.bit_depth == ::png::BitDepth::Sixteen {
+        swap_u16_be(&mut decoded_value);
+    }
+    Ok(decoded_value)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroU64, sync::Arc};
+
+    use crate::{
+        array::{
+            codec::{ArrayToBytesCodecTraits, CodecOptions, CodecTraits},
+            ArrayBytes, ChunkRepresentation, DataType, Element, ElementOwned, FillValue,
+        },
+        array_subset::ArraySubset,
+    };
+
+    use super::*;
+
+    const JSON_VALID: &str = r#"{
+        "compression": "fast"
+    }"#;
+
+    fn chunk_representation(
+        shape: &[u64],
+        data_type: DataType,
+        fill_value: FillValue,
+    ) -> ChunkRepresentation {
+        ChunkRepresentation::new(
+            shape
+                .iter()
+                .map(|size| NonZeroU64::new(*size).unwrap())
+                .collect(),
+            data_type,
+            fill_value,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn codec_png_configuration() {
+        let configuration: PngCodecConfiguration = serde_json::from_str(JSON_VALID).unwrap();
+        let codec = PngCodec::new_with_configuration(&configuration);
+        assert_eq!(codec.compression(), PngCompression::Fast);
+        let metadata = codec.create_metadata().unwrap();
+        assert_eq!(
+            metadata
+                .to_configuration::<PngCodecConfiguration>()
+                .unwrap(),
+            configuration
+        );
+    }
+
+    #[test]
+    fn codec_png_round_trip_u8() {
+        for shape in [
+            vec![13, 7],
+            vec![13, 7, 1],
+            vec![5, 9, 2],
+            vec![4, 6, 3],
+            vec![8, 3, 4],
+        ] {
+            let chunk_representation =
+                chunk_representation(&shape, DataType::UInt8, FillValue::from(0u8));
+            let elements: Vec<u8> = (0..chunk_representation.num_elements())
+                .map(|i| u8::try_from(i * 7 % 256).unwrap())
+                .collect();
+            let bytes = u8::into_array_bytes(&DataType::UInt8, &elements).unwrap();
+
+            let codec =
+                PngCodec::new_with_configuration(&serde_json::from_str(JSON_VALID).unwrap());
+            let encoded = codec
+                .encode(bytes, &chunk_representation, &CodecOptions::default())
+                .unwrap();
+            assert_eq!(&encoded[..8], b"\x89PNG\r\n\x1a\n");
+            let decoded = codec
+                .decode(encoded, &chunk_representation, &CodecOptions::default())
+                .unwrap();
+            assert_eq!(
+                elements,
+                u8::from_array_bytes(&DataType::UInt8, decoded).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn codec_png_round_trip_u16() {
+        let chunk_representation =
+            chunk_representation(&[10, 20, 3], DataType::UInt16, FillValue::from(0u16));
+        let elements: Vec<u16> = (0..chunk_representation.num_elements())
+            .map(|i| u16::try_from(i * 263 % 65536).unwrap())
+            .collect();
+        let bytes = u16::into_array_bytes(&DataType::UInt16, &elements).unwrap();
+
+        let codec = PngCodec::new(PngCompression::Best);
+        let encoded = codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let decoded = codec
+            .decode(encoded, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        assert_eq!(
+            elements,
+            u16::from_array_bytes(&DataType::UInt16, decoded).unwrap()
+        );
+    }
+
+    #[test]
+    fn codec_png_unsupported() {
+        let codec = PngCodec::new_with_configuration(&serde_json::from_str(JSON_VALID).unwrap());
+        for chunk_representation in [
+            chunk_representation(&[4, 4], DataType::Float32, FillValue::from(0f32)),
+            chunk_representation(&[4], DataType::UInt8, FillValue::from(0u8)),
+            chunk_representation(&[4, 4, 5], DataType::UInt8, FillValue::from(0u8)),
+            chunk_representation(&[2, 4, 4, 1], DataType::UInt8, FillValue::from(0u8)),
+        ] {
+            let bytes = ArrayBytes::new_fill_value(
+                crate::array::ArraySize::new(
+                    chunk_representation.data_type().size(),
+                    chunk_representation.num_elements(),
+                ),
+                chunk_representation.fill_value(),
+            );
+            assert!(codec
+                .encode(bytes, &chunk_representation, &CodecOptions::default())
+                .is_err());
+            assert!(codec.compute_encoded_size(&chunk_representation).is_err());
+        }
+    }
+
+    #[test]
+    fn codec_png_partial_decode() {
+        let chunk_representation =
+            chunk_representation(&[6, 5, 3], DataType::UInt8, FillValue::from(0u8));
+        let elements: Vec<u8> = (0..chunk_representation.num_elements())
+            .map(|i| u8::try_from(i).unwrap())
+            .collect();
+        let bytes = u8::into_array_bytes(&DataType::UInt8, &elements)
+            .unwrap()
+            .into_owned();
+
+        let codec = Arc::new(PngCodec::new_with_configuration(
+            &serde_json::from_str(JSON_VALID).unwrap(),
+        ));
+        let encoded = codec
+            .encode(
+                bytes.clone(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+
+        let decoded_regions = [
+            ArraySubset::new_with_ranges(&[1..3, 0..5, 2..3]),
+            ArraySubset::new_with_ranges(&[5..6, 4..5, 0..3]),
+        ];
+        let input_handle = Arc::new(std::io::Cursor::new(encoded));
+        let partial_decoder = codec
+            .partial_decoder(
+                input_handle,
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        let decoded_partial_chunk = partial_decoder
+            .partial_decode(&decoded_regions, &CodecOptions::default())
+            .unwrap();
+        for (decoded_region, decoded_partial_chunk) in
+            decoded_regions.iter().zip(decoded_partial_chunk)
+        {
+            assert_eq!(
+                bytes
+                    .extract_array_subset(
+                        decoded_region,
+                        &chunk_representation.shape_u64(),
+                        chunk_representation.data_type()
+                    )
+                    .unwrap(),
+                decoded_partial_chunk
+            );
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn codec_png_async_partial_decode() {
+        let chunk_representation =
+            chunk_representation(&[6, 5], DataType::UInt16, FillValue::from(0u16));
+        let elements: Vec<u16> = (0..chunk_representation.num_elements())
+            .map(|i| u16::try_from(i * 1000).unwrap())
+            .collect();
+        let bytes = u16::into_array_bytes(&DataType::UInt16, &elements)
+            .unwrap()
+            .into_owned();
+
+        let codec = Arc::new(PngCodec::new_with_configuration(
+            &serde_json::from_str(JSON_VALID).unwrap(),
+        ));
+        let encoded = codec
+            .encode(
+                bytes.clone(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+
+        let decoded_regions = [ArraySubset::new_with_ranges(&[2..4, 1..3])];
+        let input_handle = Arc::new(std::io::Cursor::new(encoded));
+        let partial_decoder = codec
+            .async_partial_decoder(
+                input_handle,
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .await
+            .unwrap();
+        let decoded_partial_chunk = partial_decoder
+            .partial_decode(&decoded_regions, &CodecOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            bytes
+                .extract_array_subset(
+                    &decoded_regions[0],
+                    &chunk_representation.shape_u64(),
+                    chunk_representation.data_type()
+                )
+                .unwrap(),
+            decoded_partial_chunk[0]
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use std::sync::Arc;
+
+use crate::{
+    array::{
+        codec::{
+            ArrayBytes, ArrayCodecTraits, ArrayPartialDecoderTraits, ArrayPartialEncoderDefault,
+            ArrayPartialEncoderTraits, ArrayToBytesCodecTraits, BytesPartialDecoderTraits,
+            BytesPartialEncoderTraits, CodecError, CodecOptions, CodecTraits, RawBytes,
+            RecommendedConcurrency,
+        },
+        ArrayMetadataOptions, BytesRepresentation, ChunkRepresentation,
+    },
+    config::global_config,
+    metadata::v3::MetadataV3,
+};
+
+#[cfg(feature = "async")]
+use crate::array::codec::{AsyncArrayPartialDecoderTraits, AsyncBytesPartialDecoderTraits};
+
+use super::{
+    png_decode, png_encode, png_image_layout, png_partial_decoder, PngCodecConfiguration,
+    PngCodecConfigurationV1, PngCompression, IDENTIFIER,
+};
+
+/// A `png` codec implementation.
+#[derive(Clone, Copy, Debug)]
+pub struct PngCodec {
+    compression: PngCompression,
+}
+
+impl PngCodec {
+    /// Create a new `png` codec with a compression.
+    #[must_use]
+    pub const fn new(compression: PngCompression) -> Self {
+        Self { compression }
+    }
+
+    /// Create a new `png` codec from configuration.
+    #[must_use]
+    pub const fn new_with_configuration(configuration: &PngCodecConfiguration) -> Self {
+        let PngCodecConfiguration::V1(configuration) = configuration;
+        Self::new(configuration.compression)
+    }
+
+    /// Return the compression.
+    #[must_use]
+    pub const fn compression(&self) -> PngCompression {
+        self.compression
+    }
+}
+
+impl CodecTraits for PngCodec {
+    fn create_metadata_opt(&self, _options: &ArrayMetadataOptions) -> Option<MetadataV3> {
+        let configuration = PngCodecConfiguration::V1(PngCodecConfigurationV1 {
+            compression: self.compression,
+        });
+        Some(
+            MetadataV3::new_with_serializable_configuration(
+                global_config()
+                    .experimental_codec_names()
+                    .get(IDENTIFIER)
+                    .expect("experimental codec identifier in global map"),
+                &configuration,
+            )
+            .expect("png configuration is valid json"),
+        )
+    }
+
+    fn partial_decoder_should_cache_input(&self) -> bool {
+        false
+    }
+
+    fn partial_decoder_decodes_all(&self) -> bool {
+        true
+    }
+}
+
+impl ArrayCodecTraits for PngCodec {
+    fn recommended_concurrency(
+        &self,
+        _decoded_representation: &ChunkRepresentation,
+    ) -> Result<RecommendedConcurrency, CodecError> {
+        // png does not support parallel encode or decode
+        Ok(RecommendedConcurrency::new_maximum(1))
+    }
+}
+
+#[cfg_attr(feature = "async", async_trait::async_trait)]
+impl ArrayToBytesCodecTraits for PngCodec {
+    fn dynamic(self: Arc<Self>) -> Arc<dyn ArrayToBytesCodecTraits> {
+        self as Arc<dyn ArrayToBytesCodecTraits>
+    }
+
+    fn encode<'a>(
+        &self,
+        bytes: ArrayBytes<'a>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        let bytes = bytes.into_fixed()?;
+        Ok(png_encode(&bytes, decoded_representation, self.compression)?.into())
+    }
+
+    fn decode<'a>(
+        &self,
+        bytes: RawBytes<'a>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<ArrayBytes<'a>, CodecError> {
+        Ok(ArrayBytes::from(png_decode(
+            &bytes,
+            decoded_representation,
+        )?))
+    }
+
+    fn partial_decoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn BytesPartialDecoderTraits>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn ArrayPartialDecoderTraits>, CodecError> {
+        Ok(Arc::new(png_partial_decoder::PngPartialDecoder::new(
+            input_handle,
+            decoded_representation.clone(),
+        )?))
+    }
+
+    fn partial_encoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn BytesPartialDecoderTraits>,
+        output_handle: Arc<dyn BytesPartialEncoderTraits>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn ArrayPartialEncoderTraits>, CodecError> {
+        Ok(Arc::new(ArrayPartialEncoderDefault::new(
+            input_handle,
+            output_handle,
+            decoded_representation.clone(),
+            self,
+        )))
+    }
+
+    #[cfg(feature = "async")]
+    async fn async_partial_decoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn AsyncArrayPartialDecoderTraits>, CodecError> {
+        Ok(Arc::new(png_partial_decoder::AsyncPngPartialDecoder::new(
+            input_handle,
+            decoded_representation.clone(),
+        )?))
+    }
+
+    fn compute_encoded_size(
+        &self,
+        decoded_representation: &ChunkRepresentation,
+    ) -> Result<BytesRepresentation, CodecError> {
+        png_image_layout(decoded_representation)?;
+        Ok(BytesRepresentation::UnboundedSize)
+    }
+}
@@ -0,0 +1,497 @@
+//! The `rle` array to bytes codec.
+//!
+//! Encodes the elements of a chunk (in C order) as runs of identical values.
+//! It is optimised for integer segmentation/label volumes with long constant runs, where it compresses better than general purpose compression codecs and decodes much faster.
+//! The `bool` and integer data types are supported.
+//!
+//! ### Encoded Representation
+//! The encoded representation is
+//!  - the number of runs `n` as a little endian `u64`,
+//!  - the run index: the exclusive end element index of each run as `n` little endian `u64`s, and
+//!  - the value of each run as `n` little endian elements.
+//!
+//! Partial decoding reads the run index, and then only retrieves the values of the runs intersecting the requested regions.
+//!
+//! <div class="warning">
+//! This codec is experimental and may be incompatible with other Zarr V3 implementations.
+//! </div>
+//!
+//! This codec requires the `rle` feature, which is disabled by default.
+//!
+//! See [`RleCodecConfigurationV1`] for example `JSON` metadata.
+
+mod rle_codec;
+mod rle_partial_decoder;
+
+use std::sync::Arc;
+
+pub use crate::metadata::v3::array::codec::rle::{RleCodecConfiguration, RleCodecConfigurationV1};
+pub use rle_codec::RleCodec;
+
+use crate::{
+    array::{
+        codec::{Codec, CodecError, CodecPlugin},
+        ChunkRepresentation, DataType,
+    },
+    config::global_config,
+    metadata::v3::{array::codec::rle, MetadataV3},
+    plugin::{PluginCreateError, PluginMetadataInvalidError},
+};
+
+pub use rle::IDENTIFIER;
+
+// Register the codec.
+inventory::submit! {
+    CodecPlugin::new(IDENTIFIER, is_name_rle, create_codec_rle)
+}
+
+fn is_name_rle(name: &str) -> bool {
+    name.eq(IDENTIFIER)
+        || name
+            == global_config()
+                .experimental_codec_names()
+                .get(IDENTIFIER)
+                .expect("experimental codec identifier in global map")
+}
+
+pub(crate) fn create_codec_rle(metadata: &MetadataV3) -> Result<Codec, PluginCreateError> {
+    let configuration: RleCodecConfiguration = metadata
+        .to_configuration()
+        .map_err(|_| PluginMetadataInvalidError::new(IDENTIFIER, "codec", metadata.clone()))?;
+    let codec = Arc::new(RleCodec::new_with_configuration(&configuration));
+    Ok(Codec::ArrayToBytes(codec))
+}
+
+/// The size of the number of runs and each run end in the encoded representation.
+const RUN_END_SIZE: usize = std::mem::size_of::<u64>();
+
+/// Return the size of an element of `data_type` if it is supported by the `rle` codec.
+fn rle_element_size(data_type: &DataType) -> Result<usize, CodecError> {
+    match data_type {
+        DataType::Bool | DataType::Int8 | DataType::UInt8 => Ok(1),
+        DataType::Int16 | DataType::UInt16 => Ok(2),
+        DataType::Int32 | DataType::UInt32 => Ok(4),
+        DataType::Int64 | DataType::UInt64 => Ok(8),
+        _ => Err(CodecError::UnsupportedDataType(
+            data_type.clone(),
+            IDENTIFIER.to_string(),
+        )),
+    }
+}
+
+/// Convert the bytes of elements of `element_size` bytes between the native and little endian byte order.
+fn swap_endianness_le(bytes: &mut [u8], element_size: usize) {
+    if cfg!(target_endian = "big") && element_size > 1 {
+        for element in bytes.chunks_exact_mut(element_size) {
+            element.reverse();
+        }
+    }
+}
+
+fn rle_encode(
+    decoded_value: &[u8],
+    decoded_representation: &ChunkRepresentation,
+) -> Result<Vec<u8>, CodecError> {
+    let element_size = rle_element_size(decoded_representation.data_type())?;
+    let expected_size = decoded_representation.num_elements() * element_size as u64;
+    if decoded_value.len() as u64 != expected_size {
+        return Err(CodecError::UnexpectedChunkDecodedSize(
+            decoded_value.len(),
+            expected_size,
+        ));
+    }
+
+    let mut run_ends: Vec<u64> = Vec::new();
+    let mut values: Vec<u8> = Vec::new();
+    let mut elements = decoded_value.chunks_exact(element_size);
+    if let Some(first) = elements.next() {
+        let mut value = first;
+        for (i, element) in elements.enumerate() {
+            if element != value {
+                run_ends.push(i as u64 + 1);
+                values.extend_from_slice(value);
+                value = element;
+            }
+        }
+        run_ends.push(decoded_representation.num_elements());
+        values.extend_from_slice(value);
+    }
+    swap_endianness_le(&mut values, element_size);
+
+    let mut encoded_value = Vec::with_capacity(RUN_END_SIZE * (run_ends.len() + 1) + values.len());
+    encoded_value.extend_from_slice(&(run_ends.len() as u64).to_le_bytes());
+    for run_end in run_ends {
+        encoded_value.extend_from_slice(&run_end.to_le_bytes());
+    }
+    encoded_value.extend_from_slice(&values);
+    Ok(encoded_value)
+}
+
+/// Decode the number of runs from the first bytes of an encoded value.
+fn rle_num_runs(encoded_header: &[u8]) -> Result<usize, CodecError> {
+    let num_runs: [u8; RUN_END_SIZE] = encoded_header
+        .get(..RUN_END_SIZE)
+        .and_then(|num_runs| num_runs.try_into().ok())
+        .ok_or_else(|| CodecError::Other("rle encoded value is too short".to_string()))?;
+    usize::try_from(u64::from_le_bytes(num_runs))
+        .map_err(|_| CodecError::Other("rle number of runs exceeds usize::MAX".to_string()))
+}
+
+/// Decode and validate the run index of a chunk with `num_elements` elements.
+///
+/// The run ends must be strictly increasing, and the last run must end at `num_elements`.
+fn rle_run_ends(encoded_run_ends: &[u8], num_elements: u64) -> Result<Vec<u64>, CodecError> {
+    let run_ends: Vec<u64> = encoded_run_ends
+        .chunks_exact(RUN_END_SIZE)
+        .map(|run_end| u64::from_le_bytes(run_end.try_into().unwrap()))
+        .collect();
+    let increasing = run_ends
+        .iter()
+        .try_fold(0, |start, &end| (end > start).then_some(end));
+    if increasing == Some(num_elements) {
+        Ok(run_ends)
+    } else {
+        Err(CodecError::Other("rle run index is invalid".to_string()))
+    }
+}
+
+/// Append the decoded `elements` to `output`.
+///
+/// `values` are the native endian values of the runs starting at run `first_run`, which must include the runs intersecting `elements`.
+fn rle_decode_elements(
+    run_ends: &[u64],
+    first_run: usize,
+    values: &[u8],
+    element_size: usize,
+    elements: std::ops::Range<u64>,
+    output: &mut Vec<u8>,
+) {
+    let mut run = first_run + run_ends[first_run..].partition_point(|&end| end <= elements.start);
+    let mut start = elements.start;
+    while start < elements.end {
+        let end = run_ends[run].min(elements.end);
+        let offset = (run - first_run) * element_size;
+        let value = &values[offset..offset + element_size];
+        for _ in start..end {
+            output.extend_from_slice(value);
+        }
+        start = end;
+        run += 1;
+    }
+}
+
+fn rle_decode(
+    encoded_value: &[u8],
+    decoded_representation: &ChunkRepresentation,
+) -> Result<Vec<u8>, CodecError> {
+    let element_size = rle_element_size(decoded_representation.data_type())?;
+    let num_elements = decoded_representation.num_elements();
+    let num_runs = rle_num_runs(encoded_value)?;
+    let values_offset = RUN_END_SIZE * (num_runs + 1);
+    if encoded_value.len() != values_offset + num_runs * element_size {
+        return Err(CodecError::Other(
+            "rle encoded value has an unexpected length".to_string(),
+        ));
+    }
+    let run_ends = rle_run_ends(&encoded_value[RUN_END_SIZE..values_offset], num_elements)?;
+    let mut values = encoded_value[values_offset..].to_vec();
+    swap_endianness_le(&mut values, element_size);
+
+    let mut decoded_value =
+        Vec::with_capacity(decoded_representation.num_elements_usize() * element_size);
+    rle_decode_elements(
+        &run_ends,
+        0,
+        &values,
+        element_size,
+        0..num_elements,
+        &mut decoded_value,
+    );
+    Ok(decoded_value)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroU64, sync::Arc};
+
+    use crate::{
+        array::{
+            codec::{ArrayToBytesCodecTraits, CodecOptions, CodecTraits},
+            ArrayBytes, ChunkRepresentation, DataType, Element, ElementOwned, FillValue,
+        },
+        array_subset::ArraySubset,
+    };
+
+    use super::*;
+
+    const JSON_VALID: &str = r"{}";
+
+    fn chunk_representation(data_type: DataType, fill_value: FillValue) -> ChunkRepresentation {
+        ChunkRepresentation::new(
+            vec![
+                NonZeroU64::new(6).unwrap(),
+                NonZeroU64::new(5).unwrap(),
+                NonZeroU64::new(4).unwrap(),
+            ],
+            data_type,
+            fill_value,
+        )
+        .unwrap()
+    }
+
+    /// Labels with runs of various lengths.
+    fn labels(num_elements: u64) -> Vec<u32> {
+        (0..num_elements)
+            .map(|i| u32::try_from(i / 7 + i / 50).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn codec_rle_configuration() {
+        let configuration: RleCodecConfiguration = serde_json::from_str(JSON_VALID).unwrap();
+        let codec = RleCodec::new_with_configuration(&configuration);
+        let metadata = codec.create_metadata().unwrap();
+        assert_eq!(
+            metadata
+                .to_configuration::<RleCodecConfiguration>()
+                .unwrap(),
+            configuration
+        );
+    }
+
+    #[test]
+    fn codec_rle_encoded() {
+        let chunk_representation = ChunkRepresentation::new(
+            vec![NonZeroU64::new(6).unwrap()],
+            DataType::UInt16,
+            FillValue::from(0u16),
+        )
+        .unwrap();
+        let elements: Vec<u16> = vec![3, 3, 3, 0x0102, 0x0102, 3];
+        let bytes = u16::into_array_bytes(&DataType::UInt16, &elements).unwrap();
+        let codec = RleCodec::new();
+        let encoded = codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let mut expected: Vec<u8> = vec![];
+        for value in [3u64, 3, 5, 6] {
+            expected.extend(value.to_le_bytes());
+        }
+        expected.extend([3, 0, 2, 1, 3, 0]);
+        assert_eq!(encoded.to_vec(), expected);
+        let decoded = codec
+            .decode(encoded, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        assert_eq!(
+            elements,
+            u16::from_array_bytes(&DataType::UInt16, decoded).unwrap()
+        );
+    }
+
+    #[test]
+    fn codec_rle_round_trip() {
+        let chunk_representation = chunk_representation(DataType::UInt32, FillValue::from(0u32));
+        let elements = labels(chunk_representation.num_elements());
+        let bytes = u32::into_array_bytes(&DataType::UInt32, &elements).unwrap();
+
+        let codec = RleCodec::new_with_configuration(&serde_json::from_str(JSON_VALID).unwrap());
+        let encoded = codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        assert!(encoded.len() < elements.len() * std::mem::size_of::<u32>());
+        let decoded = codec
+            .decode(encoded, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        assert_eq!(
+            elements,
+            u32::from_array_bytes(&DataType::UInt32, decoded).unwrap()
+        );
+    }
+
+    #[test]
+    fn codec_rle_round_trip_bool() {
+        let chunk_representation = chunk_representation(DataType::Bool, FillValue::from(false));
+        let elements: Vec<bool> = (0..chunk_representation.num_elements())
+            .map(|i| i % 11 < 4)
+            .collect();
+        let bytes = bool::into_array_bytes(&DataType::Bool, &elements).unwrap();
+
+        let codec = RleCodec::new();
+        let encoded = codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let decoded = codec
+            .decode(encoded, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        assert_eq!(
+            elements,
+            bool::from_array_bytes(&DataType::Bool, decoded).unwrap()
+        );
+    }
+
+    #[test]
+    fn codec_rle_invalid() {
+        let chunk_representation = chunk_representation(DataType::UInt8, FillValue::from(0u8));
+        let codec = RleCodec::new();
+        let mut encoded: Vec<u8> = vec![];
+        for value in [2u64, 100, 110] {
+            encoded.extend(value.to_le_bytes());
+        }
+        encoded.extend([1, 2]);
+        // the last run does not end at the number of elements
+        assert!(codec
+            .decode(
+                encoded.clone().into(),
+                &chunk_representation,
+                &CodecOptions::default()
+            )
+            .is_err());
+        // the encoded value has trailing bytes
+        let mut valid = encoded.clone();
+        valid[2 * RUN_END_SIZE..3 * RUN_END_SIZE].copy_from_slice(&120u64.to_le_bytes());
+        assert!(codec
+            .decode(
+                valid.clone().into(),
+                &chunk_representation,
+                &CodecOptions::default()
+            )
+            .is_ok());
+        valid.push(0);
+        assert!(codec
+            .decode(
+                valid.into(),
+                &chunk_representation,
+                &CodecOptions::default()
+            )
+            .is_err());
+        // the encoded value is truncated
+        assert!(codec
+            .decode(
+                encoded[..RUN_END_SIZE].to_vec().into(),
+                &chunk_representation,
+                &CodecOptions::default()
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn codec_rle_unsupported_data_type() {
+        let chunk_representation = chunk_representation(DataType::Float32, FillValue::from(0f32));
+        let bytes = ArrayBytes::new_fill_value(
+            crate::array::ArraySize::new(
+                chunk_representation.data_type().size(),
+                chunk_representation.num_elements(),
+            ),
+            chunk_representation.fill_value(),
+        );
+        let codec = RleCodec::new();
+        assert!(codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .is_err());
+        assert!(codec.compute_encoded_size(&chunk_representation).is_err());
+    }
+
+    #[test]
+    fn codec_rle_partial_decode() {
+        let chunk_representation = chunk_representation(DataType::UInt32, FillValue::from(0u32));
+        let elements = labels(chunk_representation.num_elements());
+        let bytes = u32::into_array_bytes(&DataType::UInt32, &elements)
+            .unwrap()
+            .into_owned();
+
+        let codec = Arc::new(RleCodec::new_with_configuration(
+            &serde_json::from_str(JSON_VALID).unwrap(),
+        ));
+        let encoded = codec
+            .encode(
+                bytes.clone(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+
+        let decoded_regions = [
+            ArraySubset::new_with_ranges(&[1..3, 0..5, 2..3]),
+            ArraySubset::new_with_ranges(&[5..6, 4..5, 0..4]),
+            ArraySubset::new_with_ranges(&[0..6, 0..5, 0..4]),
+        ];
+        let input_handle = Arc::new(std::io::Cursor::new(encoded));
+        let partial_decoder = codec
+            .partial_decoder(
+                input_handle,
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        let decoded_partial_chunk = partial_decoder
+            .partial_decode(&decoded_regions, &CodecOptions::default())
+            .unwrap();
+        for (decoded_region, decoded_partial_chunk) in
+            decoded_regions.iter().zip(decoded_partial_chunk)
+        {
+            assert_eq!(
+                bytes
+                    .extract_array_subset(
+                        decoded_region,
+                        &chunk_representation.shape_u64(),
+                        chunk_representation.data_type()
+                    )
+                    .unwrap(),
+                decoded_partial_chunk
+            );
+        }
+
+        assert!(partial_decoder
+            .partial_decode(
+                &[ArraySubset::new_with_ranges(&[5..7, 0..5, 0..4])],
+                &CodecOptions::default()
+            )
+            .is_err());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn codec_rle_async_partial_decode() {
+        let chunk_representation = chunk_representation(DataType::UInt32, FillValue::from(0u32));
+        let elements = labels(chunk_representation.num_elements());
+        let bytes = u32::into_array_bytes(&DataType::UInt32, &elements)
+            .unwrap()
+            .into_owned();
+
+        let codec = Arc::new(RleCodec::new_with_configuration(
+            &serde_json::from_str(JSON_VALID).unwrap(),
+        ));
+        let encoded = codec
+            .encode(
+                bytes.clone(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+
+        let decoded_regions = [ArraySubset::new_with_ranges(&[2..4, 1..3, 0..4])];
+        let input_handle = Arc::new(std::io::Cursor::new(encoded));
+        let partial_decoder = codec
+            .async_partial_decoder(
+                input_handle,
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .await
+            .unwrap();
+        let decoded_partial_chunk = partial_decoder
+            .partial_decode(&decoded_regions, &CodecOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            bytes
+                .extract_array_subset(
+                    &decoded_regions[0],
+                    &chunk_representation.shape_u64(),
+                    chunk_representation.data_type()
+                )
+                .unwrap(),
+            decoded_partial_chunk[0]
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use std::sync::Arc;
+
+use crate::{
+    array::{
+        codec::{
+            ArrayBytes, ArrayCodecTraits, ArrayPartialDecoderTraits, ArrayPartialEncoderDefault,
+            ArrayPartialEncoderTraits, ArrayToBytesCodecTraits, BytesPartialDecoderTraits,
+            BytesPartialEncoderTraits, CodecError, CodecOptions, CodecTraits, RawBytes,
+            RecommendedConcurrency,
+        },
+        ArrayMetadataOptions, BytesRepresentation, ChunkRepresentation,
+    },
+    config::global_config,
+    metadata::v3::MetadataV3,
+};
+
+#[cfg(feature = "async")]
+use crate::array::codec::{AsyncArrayPartialDecoderTraits, AsyncBytesPartialDecoderTraits};
+
+use super::{
+    rle_decode, rle_element_size, rle_encode, rle_partial_decoder, RleCodecConfiguration,
+    RleCodecConfigurationV1, IDENTIFIER, RUN_END_SIZE,
+};
+
+/// A `rle` codec implementation.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RleCodec;
+
+impl RleCodec {
+    /// Create a new `rle` codec.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+
+    /// Create a new `rle` codec from configuration.
+    #[must_use]
+    pub const fn new_with_configuration(_configuration: &RleCodecConfiguration) -> Self {
+        Self
+    }
+}
+
+impl CodecTraits for RleCodec {
+    fn create_metadata_opt(&self, _options: &ArrayMetadataOptions) -> Option<MetadataV3> {
+        let configuration = RleCodecConfiguration::V1(RleCodecConfigurationV1::new());
+        Some(
+            MetadataV3::new_with_serializable_configuration(
+                global_config()
+                    .experimental_codec_names()
+                    .get(IDENTIFIER)
+                    .expect("experimental codec identifier in global map"),
+                &configuration,
+            )
+            .expect("rle configuration is valid json"),
+        )
+    }
+
+    fn partial_decoder_should_cache_input(&self) -> bool {
+        false
+    }
+
+    fn partial_decoder_decodes_all(&self) -> bool {
+        false
+    }
+}
+
+impl ArrayCodecTraits for RleCodec {
+    fn recommended_concurrency(
+        &self,
+        _decoded_representation: &ChunkRepresentation,
+    ) -> Result<RecommendedConcurrency, CodecError> {
+        Ok(RecommendedConcurrency::new_maximum(1))
+    }
+}
+
+#[cfg_attr(feature = "async", async_trait::async_trait)]
+impl ArrayToBytesCodecTraits for RleCodec {
+    fn dynamic(self: Arc<Self>) -> Arc<dyn ArrayToBytesCodecTraits> {
+        self as Arc<dyn ArrayToBytesCodecTraits>
+    }
+
+    fn encode<'a>(
+        &self,
+        bytes: ArrayBytes<'a>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        let bytes = bytes.into_fixed()?;
+        Ok(rle_encode(&bytes, decoded_representation)?.into())
+    }
+
+    fn decode<'a>(
+        &self,
+        bytes: RawBytes<'a>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<ArrayBytes<'a>, CodecError> {
+        Ok(ArrayBytes::from(rle_decode(
+            &bytes,
+            decoded_representation,
+        )?))
+    }
+
+    fn partial_decoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn BytesPartialDecoderTraits>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn ArrayPartialDecoderTraits>, CodecError> {
+        Ok(Arc::new(rle_partial_decoder::RlePartialDecoder::new(
+            input_handle,
+            decoded_representation.clone(),
+        )?))
+    }
+
+    fn partial_encoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn BytesPartialDecoderTraits>,
+        output_handle: Arc<dyn BytesPartialEncoderTraits>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn ArrayPartialEncoderTraits>, CodecError> {
+        Ok(Arc::new(ArrayPartialEncoderDefault::new(
+            input_handle,
+            output_handle,
+            decoded_representation.clone(),
+            self,
+        )))
+    }
+
+    #[cfg(feature = "async")]
+    async fn async_partial_decoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn AsyncArrayPartialDecoderTraits>, CodecError> {
+        Ok(Arc::new(rle_partial_decoder::AsyncRlePartialDecoder::new(
+            input_handle,
+            decoded_representation.clone(),
+        )?))
+    }
+
+    fn compute_encoded_size(
+        &self,
+        decoded_representation: &ChunkRepresentation,
+    ) -> Result<BytesRepresentation, CodecError> {
+        // Every element is a run in the worst case
+        let element_size = rle_element_size(decoded_representation.data_type())?;
+        let num_elements = decoded_representation.num_elements();
+        Ok(BytesRepresentation::BoundedSize(
+            RUN_END_SIZE as u64 + num_elements * (RUN_END_SIZE + element_size) as u64,
+        ))
+    }
+}
@@ -0,0 +1,285 @@
+use std::{ops::Range, sync::Arc};
+
+use crate::{
+    array::{
+        codec::{
+            ArrayBytes, ArrayPartialDecoderTraits, BytesPartialDecoderTraits, CodecError,
+            CodecOptions,
+        },
+        ArraySize, ChunkRepresentation, DataType, RawBytes,
+    },
+    array_subset::ArraySubset,
+    byte_range::ByteRange,
+};
+
+#[cfg(feature = "async")]
+use crate::array::codec::{AsyncArrayPartialDecoderTraits, AsyncBytesPartialDecoderTraits};
+
+use super::{
+    rle_decode_elements, rle_element_size, rle_num_runs, rle_run_ends, swap_endianness_le,
+    RUN_END_SIZE,
+};
+
+/// The encoded byte range of the number of runs.
+const NUM_RUNS_BYTE_RANGE: ByteRange = ByteRange::FromStart(0, Some(RUN_END_SIZE as u64));
+
+/// Return the encoded byte range of the run index with `num_runs` runs.
+const fn run_ends_byte_range(num_runs: usize) -> ByteRange {
+    ByteRange::FromStart(RUN_END_SIZE as u64, Some((RUN_END_SIZE * num_runs) as u64))
+}
+
+/// The runs intersecting a decoded region.
+struct RleRegion {
+    /// The linearised element ranges of the region.
+    elements: Vec<Range<u64>>,
+    /// The runs intersecting the region.
+    runs: Range<usize>,
+}
+
+impl RleRegion {
+    fn new(
+        array_subset: &ArraySubset,
+        chunk_shape: &[u64],
+        run_ends: &[u64],
+    ) -> Result<Self, CodecError> {
+        let contiguous_indices = array_subset.contiguous_linearised_indices(chunk_shape)?;
+        let contiguous_elements = contiguous_indices.contiguous_elements();
+        let elements: Vec<Range<u64>> = contiguous_indices
+            .iter()
+            .map(|start| start..start + contiguous_elements)
+            .filter(|elements| !elements.is_empty())
+            .collect();
+        let runs = match (elements.first(), elements.last()) {
+            (Some(first), Some(last)) => {
+                run_ends.partition_point(|&end| end <= first.start)
+                    ..run_ends.partition_point(|&end| end < last.end) + 1
+            }
+            _ => 0..0,
+        };
+        Ok(Self { elements, runs })
+    }
+
+    /// Return the encoded byte range of the values of the runs intersecting the region.
+    fn values_byte_range(&self, num_runs: usize, element_size: usize) -> ByteRange {
+        let values_offset = RUN_END_SIZE * (num_runs + 1);
+        ByteRange::FromStart(
+            (values_offset + self.runs.start * element_size) as u64,
+            Some((self.runs.len() * element_size) as u64),
+        )
+    }
+
+    /// Decode the region from the little endian `values` of its runs.
+    fn decode(&self, run_ends: &[u64], values: &[u8], element_size: usize) -> ArrayBytes<'static> {
+        let mut values = values.to_vec();
+        swap_endianness_le(&mut values, element_size);
+        let num_elements: u64 = self
+            .elements
+            .iter()
+            .map(|elements| elements.end - elements.start)
+            .sum();
+        let mut decoded = Vec::with_capacity(usize::try_from(num_elements).unwrap() * element_size);
+        for elements in &self.elements {
+            rle_decode_elements(
+                run_ends,
+                self.runs.start,
+                &values,
+                element_size,
+                elements.clone(),
+                &mut decoded,
+            );
+        }
+        ArrayBytes::from(decoded)
+    }
+}
+
+fn validate_decoded_regions(
+    decoded_regions: &[ArraySubset],
+    decoded_representation: &ChunkRepresentation,
+) -> Result<(), CodecError> {
+    for array_subset in decoded_regions {
+        if array_subset.dimensionality() != decoded_representation.dimensionality() {
+            return Err(CodecError::InvalidArraySubsetDimensionalityError(
+                array_subset.clone(),
+                decoded_representation.dimensionality(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn fill_value_regions(
+    decoded_regions: &[ArraySubset],
+    decoded_representation: &ChunkRepresentation,
+) -> Vec<ArrayBytes<'static>> {
+    decoded_regions
+        .iter()
+        .map(|array_subset| {
+            let array_size = ArraySize::new(
+                decoded_representation.data_type().size(),
+                array_subset.num_elements(),
+            );
+            ArrayBytes::new_fill_value(array_size, decoded_representation.fill_value())
+        })
+        .collect()
+}
+
+/// Return the decoded regions and the encoded byte ranges of their run values.
+fn rle_regions(
+    decoded_regions: &[ArraySubset],
+    decoded_representation: &ChunkRepresentation,
+    run_ends: &[u64],
+) -> Result<(Vec<RleRegion>, Vec<ByteRange>), CodecError> {
+    let element_size = rle_element_size(decoded_representation.data_type())?;
+    let chunk_shape = decoded_representation.shape_u64();
+    let regions = decoded_regions
+        .iter()
+        .map(|array_subset| RleRegion::new(array_subset, &chunk_shape, run_ends))
+        .collect::<Result<Vec<_>, _>>()?;
+    let byte_ranges = regions
+        .iter()
+        .map(|region| region.values_byte_range(run_ends.len(), element_size))
+        .collect();
+    Ok((regions, byte_ranges))
+}
+
+fn decode_regions(
+    regions: &[RleRegion],
+    run_ends: &[u64],
+    values: &[RawBytes<'_>],
+    element_size: usize,
+) -> Vec<ArrayBytes<'static>> {
+    regions
+        .iter()
+        .zip(values)
+        .map(|(region, values)| region.decode(run_ends, values, element_size))
+        .collect()
+}
+
+/// Partial decoder for the `rle` codec.
+pub(crate) struct RlePartialDecoder<'a> {
+    input_handle: Arc<dyn BytesPartialDecoderTraits + 'a>,
+    decoded_representation: ChunkRepresentation,
+}
+
+impl<'a> RlePartialDecoder<'a> {
+    /// Create a new partial decoder for the `rle` codec.
+    pub(crate) fn new(
+        input_handle: Arc<dyn BytesPartialDecoderTraits + 'a>,
+        decoded_representation: ChunkRepresentation,
+    ) -> Result<Self, CodecError> {
+        rle_element_size(decoded_representation.data_type())?;
+        Ok(Self {
+            input_handle,
+            decoded_representation,
+        })
+    }
+}
+
+impl ArrayPartialDecoderTraits for RlePartialDecoder<'_> {
+    fn data_type(&self) -> &DataType {
+        self.decoded_representation.data_type()
+    }
+
+    fn partial_decode(
+        &self,
+        decoded_regions: &[ArraySubset],
+        options: &CodecOptions,
+    ) -> Result<Vec<ArrayBytes<'_>>, CodecError> {
+        validate_decoded_regions(decoded_regions, &self.decoded_representation)?;
+        let element_size = rle_element_size(self.decoded_representation.data_type())?;
+
+        // Read the number of runs and the run index
+        let Some(num_runs) = self
+            .input_handle
+            .partial_decode(&[NUM_RUNS_BYTE_RANGE], options)?
+        else {
+            return Ok(fill_value_regions(
+                decoded_regions,
+                &self.decoded_representation,
+            ));
+        };
+        let num_runs = rle_num_runs(&num_runs[0])?;
+        let run_ends = self
+            .input_handle
+            .partial_decode(&[run_ends_byte_range(num_runs)], options)?
+            .ok_or_else(|| CodecError::Other("rle run index is missing".to_string()))?;
+        let run_ends = rle_run_ends(&run_ends[0], self.decoded_representation.num_elements())?;
+
+        // Only read the values of the runs intersecting the decoded regions
+        let (regions, byte_ranges) =
+            rle_regions(decoded_regions, &self.decoded_representation, &run_ends)?;
+        let values = self
+            .input_handle
+            .partial_decode(&byte_ranges, options)?
+            .ok_or_else(|| CodecError::Other("rle run values are missing".to_string()))?;
+        Ok(decode_regions(&regions, &run_ends, &values, element_size))
+    }
+}
+
+#[cfg(feature = "async")]
+/// Asynchronous partial decoder for the `rle` codec.
+pub(crate) struct AsyncRlePartialDecoder {
+    input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+    decoded_representation: ChunkRepresentation,
+}
+
+#[cfg(feature = "async")]
+impl AsyncRlePartialDecoder {
+    /// Create a new partial decoder for the `rle` codec.
+    pub(crate) fn new(
+        input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+        decoded_representation: ChunkRepresentation,
+    ) -> Result<Self, CodecError> {
+        rle_element_size(decoded_representation.data_type())?;
+        Ok(Self {
+            input_handle,
+            decoded_representation,
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl AsyncArrayPartialDecoderTraits for AsyncRlePartialDecoder {
+    fn data_type(&self) -> &DataType {
+        self.decoded_representation.data_type()
+    }
+
+    async fn partial_decode(
+        &self,
+        decoded_regions: &[ArraySubset],
+        options: &CodecOptions,
+    ) -> Result<Vec<ArrayBytes<'_>>, CodecError> {
+        validate_decoded_regions(decoded_regions, &self.decoded_representation)?;
+        let element_size = rle_element_size(self.decoded_representation.data_type())?;
+
+        // Read the number of runs and the run index
+        let Some(num_runs) = self
+            .input_handle
+            .partial_decode(&[NUM_RUNS_BYTE_RANGE], options)
+            .await?
+        else {
+            return Ok(fill_value_regions(
+                decoded_regions,
+                &self.decoded_representation,
+            ));
+        };
+        let num_runs = rle_num_runs(&num_runs[0])?;
+        let run_ends = self
+            .input_handle
+            .partial_decode(&[run_ends_byte_range(num_runs)], options)
+            .await?
+            .ok_or_else(|| CodecError::Other("rle run index is missing".to_string()))?;
+        let run_ends = rle_run_ends(&run_ends[0], self.decoded_representation.num_elements())?;
+
+        // Only read the values of the runs intersecting the decoded regions
+        let (regions, byte_ranges) =
+            rle_regions(decoded_regions, &self.decoded_representation, &run_ends)?;
+        let values = self
+            .input_handle
+            .partial_decode(&byte_ranges, options)
+            .await?
+            .ok_or_else(|| CodecError::Other("rle run values are missing".to_string()))?;
+        Ok(decode_regions(&regions, &run_ends, &values, element_size))
+    }
+}
@@ -9,11 +9,15 @@
 //!
 //! See [`ShardingCodecConfigurationV1`] for example `JSON` metadata.
 //! The [`ShardingCodecBuilder`] can help with creating a [`ShardingCodec`].
+//! A [`ShardWriter`] writes a shard in a single pass, without holding all of its inner chunks in memory.
+//! A [`ShardIndexCache`] caches decoded shard indices across repeated partial reads of the same shard.
 
 mod sharding_codec;
 mod sharding_codec_builder;
+mod sharding_index_cache;
 mod sharding_partial_decoder;
 mod sharding_partial_encoder;
+mod sharding_writer;
 
 use std::{borrow::Cow, num::NonZeroU64, sync::Arc};
 
@@ -23,12 +27,14 @@ pub use crate::metadata::v3::array::codec::sharding::{
 
 pub use sharding_codec::ShardingCodec;
 pub use sharding_codec_builder::ShardingCodecBuilder;
+pub use sharding_index_cache::ShardIndexCache;
+pub use sharding_writer::ShardWriter;
 
 use crate::{
     array::{
         codec::{
             ArrayToBytesCodecTraits, BytesPartialDecoderTraits, Codec, CodecError, CodecOptions,
-            CodecPlugin,
+            CodecPlugin, CodecProfileOperation,
         },
         BytesRepresentation, ChunkRepresentation, ChunkShape, CodecChain, DataType, FillValue,
     },
@@ -96,19 +102,53 @@ fn compute_index_encoded_size(
     }
 }
 
+/// Check that `index_codecs` can encode the shard index of a shard with `dimensionality` dimensions.
+///
+/// The shard index must have a fixed encoded size, so that it can be located in a shard without decoding the shard.
+/// This rules out index codecs with a variable size output (e.g. compressors).
+fn validate_index_codecs(
+    index_codecs: &CodecChain,
+    dimensionality: usize,
+) -> Result<(), CodecError> {
+    let chunks_per_shard = vec![NonZeroU64::MIN; dimensionality];
+    let index_array_representation = sharding_index_decoded_representation(&chunks_per_shard);
+    compute_index_encoded_size(index_codecs, &index_array_representation).map(|_| ())
+}
+
 fn decode_shard_index(
     encoded_shard_index: &[u8],
     index_array_representation: &ChunkRepresentation,
     index_codecs: &dyn ArrayToBytesCodecTraits,
     options: &CodecOptions,
 ) -> Result<Vec<u64>, CodecError> {
-    // Decode the shard index
-    let decoded_shard_index = index_codecs.decode(
-        Cow::Borrowed(encoded_shard_index),
-        index_array_representation,
-        options,
-    )?;
+    // Decode the shard index, profiling it as a whole rather than its individual codecs
+    let profiler = options.profiler().cloned();
+    let start = std::time::Instant::now();
+    let decoded_shard_index = if profiler.is_some() {
+        let mut options = options.clone();
+        options.set_profiler(None);
+        index_codecs.decode(
+            Cow::Borrowed(encoded_shard_index),
+            index_array_representation,
+            &options,
+        )?
+    } else {
+        index_codecs.decode(
+            Cow::Borrowed(encoded_shard_index),
+            index_array_representation,
+            options,
+        )?
+    };
     let decoded_shard_index = decoded_shard_index.into_fixed()?;
+    if let Some(profiler) = profiler {
+        profiler.record(
+            IDENTIFIER,
+            CodecProfileOperation::DecodeShardIndex,
+            start.elapsed(),
+            encoded_shard_index.len(),
+            decoded_shard_index.len(),
+        );
+    }
     Ok(decoded_shard_index
         .chunks_exact(core::mem::size_of::<u64>())
         .map(|v| u64::from_ne_bytes(v.try_into().unwrap() /* safe */))
@@ -700,4 +740,110 @@ mod tests {
         let answer: Vec<u8> = vec![4, 8];
         assert_eq!(answer, decoded_partial_chunk);
     }
+
+    #[cfg(feature = "transpose")]
+    #[cfg(feature = "crc32c")]
+    #[test]
+    fn codec_sharding_index_codecs() {
+        use crate::array::codec::{
+            BytesCodec, CodecTraits, Crc32cCodec, TransposeCodec, TransposeOrder,
+        };
+
+        let chunk_representation = ChunkRepresentation::new(
+            ChunkShape::try_from(vec![4, 4]).unwrap().into(),
+            DataType::UInt8,
+            FillValue::from(0u8),
+        )
+        .unwrap();
+        let elements: Vec<u8> = (0..16).collect();
+        let bytes: ArrayBytes = elements.into();
+
+        for index_location in [ShardingIndexLocation::Start, ShardingIndexLocation::End] {
+            let codec = ShardingCodecBuilder::new(vec![2, 2].try_into().unwrap())
+                .index_array_to_array_codecs(vec![Arc::new(TransposeCodec::new(
+                    TransposeOrder::new(&[2, 0, 1]).unwrap(),
+                ))])
+                .index_array_to_bytes_codec(Arc::new(BytesCodec::big()))
+                .index_bytes_to_bytes_codecs(vec![Arc::new(Crc32cCodec::new())])
+                .index_location(index_location)
+                .try_build_arc()
+                .unwrap();
+
+            // Round trip the index codecs through metadata
+            let metadata = codec.create_metadata().unwrap();
+            let configuration: ShardingCodecConfiguration = metadata.to_configuration().unwrap();
+            let ShardingCodecConfiguration::V1(configuration_v1) = &configuration;
+            let index_codec_names: Vec<_> = configuration_v1
+                .index_codecs
+                .iter()
+                .map(MetadataV3::name)
+                .collect();
+            assert_eq!(index_codec_names, ["transpose", "bytes", "crc32c"]);
+            let codec_from_metadata =
+                Arc::new(ShardingCodec::new_with_configuration(&configuration).unwrap());
+            assert_eq!(codec_from_metadata.create_metadata(), Some(metadata));
+
+            let encoded = codec
+                .encode(
+                    bytes.clone(),
+                    &chunk_representation,
+                    &CodecOptions::default(),
+                )
+                .unwrap();
+            let decoded = codec_from_metadata
+                .decode(
+                    encoded.clone(),
+                    &chunk_representation,
+                    &CodecOptions::default(),
+                )
+                .unwrap();
+            assert_eq!(bytes, decoded);
+
+            let input_handle = Arc::new(std::io::Cursor::new(encoded));
+            let partial_decoder = codec_from_metadata
+                .partial_decoder(
+                    input_handle,
+                    &chunk_representation,
+                    &CodecOptions::default(),
+                )
+                .unwrap();
+            let decoded_partial_chunk = partial_decoder
+                .partial_decode(
+                    &[ArraySubset::new_with_ranges(&[1..3, 0..1])],
+                    &CodecOptions::default(),
+                )
+                .unwrap();
+            assert_eq!(
+                decoded_partial_chunk[0]
+                    .clone()
+                    .into_fixed()
+                    .unwrap()
+                    .to_vec(),
+                vec![4u8, 8]
+            );
+        }
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn codec_sharding_index_codecs_variable_size() {
+        use crate::array::codec::GzipCodec;
+
+        const JSON_VARIABLE_SIZE_INDEX: &str = r#"{
+    "chunk_shape": [2, 2],
+    "codecs": [{ "name": "bytes", "configuration": { "endian": "little" } }],
+    "index_codecs": [
+        { "name": "bytes", "configuration": { "endian": "little" } },
+        { "name": "gzip", "configuration": { "level": 5 } }
+    ]
+}"#;
+
+        let mut builder = ShardingCodecBuilder::new(vec![2, 2].try_into().unwrap());
+        builder.index_bytes_to_bytes_codecs(vec![Arc::new(GzipCodec::new(5).unwrap())]);
+        assert!(builder.try_build().is_err());
+
+        let configuration: ShardingCodecConfiguration =
+            serde_json::from_str(JSON_VARIABLE_SIZE_INDEX).unwrap();
+        assert!(ShardingCodec::new_with_configuration(&configuration).is_err());
+    }
 }
@@ -1,5 +1,6 @@
 use std::{
     borrow::Cow,
+    io::Write,
     num::NonZeroU64,
     sync::{atomic::AtomicUsize, Arc},
 };
@@ -23,12 +24,16 @@ use crate::{
 };
 
 #[cfg(feature = "async")]
-use crate::array::codec::{AsyncArrayPartialDecoderTraits, AsyncBytesPartialDecoderTraits};
+use crate::array::codec::{
+    AsyncArrayPartialDecoderTraits, AsyncArrayPartialEncoderTraits, AsyncBytesPartialDecoderTraits,
+    AsyncBytesPartialEncoderTraits,
+};
 
 use super::{
     calculate_chunks_per_shard, compute_index_encoded_size, decode_shard_index,
     sharding_index_decoded_representation, sharding_partial_decoder, sharding_partial_encoder,
-    ShardingCodecConfiguration, ShardingCodecConfigurationV1, ShardingIndexLocation, IDENTIFIER,
+    validate_index_codecs, ShardWriter, ShardingCodecConfiguration, ShardingCodecConfigurationV1,
+    ShardingIndexLocation, IDENTIFIER,
 };
 
 use rayon::prelude::*;
@@ -68,13 +73,15 @@ impl ShardingCodec {
     ///
     /// # Errors
     ///
-    /// Returns [`PluginCreateError`] if there is a configuration issue.
+    /// Returns [`PluginCreateError`] if there is a configuration issue, including if the index codecs do not have a fixed size output.
     pub fn new_with_configuration(
         configuration: &ShardingCodecConfiguration,
     ) -> Result<Self, PluginCreateError> {
         let ShardingCodecConfiguration::V1(configuration) = configuration;
         let inner_codecs = Arc::new(CodecChain::from_metadata(&configuration.codecs)?);
         let index_codecs = Arc::new(CodecChain::from_metadata(&configuration.index_codecs)?);
+        validate_index_codecs(&index_codecs, configuration.chunk_shape.len())
+            .map_err(|err| PluginCreateError::Other(format!("invalid index codecs: {err}")))?;
         Ok(Self::new(
             configuration.chunk_shape.clone(),
             inner_codecs,
@@ -84,6 +91,124 @@ impl ShardingCodec {
     }
 }
 
+impl ShardingCodec {
+    /// Create a [`ShardWriter`] that writes a shard with `shard_representation` to `writer`.
+    ///
+    /// # Errors
+    /// Returns a [`CodecError`] if the inner chunk shape does not evenly divide the shard shape or the index codecs do not have a fixed size output.
+    pub fn shard_writer<W: Write>(
+        &self,
+        shard_representation: &ChunkRepresentation,
+        writer: W,
+        options: &CodecOptions,
+    ) -> Result<ShardWriter<W>, CodecError> {
+        ShardWriter::new(
+            writer,
+            &self.chunk_shape,
+            self.inner_codecs.clone(),
+            self.index_codecs.clone(),
+            self.index_location,
+            shard_representation,
+            options,
+        )
+    }
+
+    /// Return the number of bytes of `encoded_shard` that are not referenced by its shard index.
+    ///
+    /// Unreferenced bytes are left in a shard when its inner chunks are updated by partial encoding.
+    ///
+    /// # Errors
+    /// Returns a [`CodecError`] if the shard index cannot be decoded.
+    pub fn encoded_shard_unreferenced_size(
+        &self,
+        encoded_shard: &[u8],
+        shard_representation: &ChunkRepresentation,
+        options: &CodecOptions,
+    ) -> Result<u64, CodecError> {
+        let chunks_per_shard =
+            calculate_chunks_per_shard(shard_representation.shape(), self.chunk_shape.as_slice())?;
+        let shard_index = self.decode_index(encoded_shard, chunks_per_shard.as_slice(), options)?;
+        let index_encoded_size = compute_index_encoded_size(
+            self.index_codecs.as_ref(),
+            &sharding_index_decoded_representation(chunks_per_shard.as_slice()),
+        )?;
+        let referenced_size: u64 = shard_index
+            .chunks_exact(2)
+            .filter(|offset_size| **offset_size != [u64::MAX, u64::MAX])
+            .map(|offset_size| offset_size[1])
+            .sum();
+        Ok((encoded_shard.len() as u64).saturating_sub(index_encoded_size + referenced_size))
+    }
+
+    /// Compact `encoded_shard` by removing the bytes that are not referenced by its shard index.
+    ///
+    /// The encoded inner chunks are copied in the order of the shard index without being decoded.
+    ///
+    /// # Errors
+    /// Returns a [`CodecError`] if the shard index cannot be decoded or encoded, or if it references bytes beyond the end of `encoded_shard`.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn compact_encoded_shard(
+        &self,
+        encoded_shard: &[u8],
+        shard_representation: &ChunkRepresentation,
+        options: &CodecOptions,
+    ) -> Result<Vec<u8>, CodecError> {
+        let chunks_per_shard =
+            calculate_chunks_per_shard(shard_representation.shape(), self.chunk_shape.as_slice())?;
+        let mut shard_index =
+            self.decode_index(encoded_shard, chunks_per_shard.as_slice(), options)?;
+        let index_decoded_representation =
+            sharding_index_decoded_representation(chunks_per_shard.as_slice());
+        let index_encoded_size =
+            compute_index_encoded_size(self.index_codecs.as_ref(), &index_decoded_representation)?;
+
+        // Copy the referenced inner chunks and update their offsets
+        let mut encoded_chunks = Vec::with_capacity(encoded_shard.len());
+        let mut offset = match self.index_location {
+            ShardingIndexLocation::Start => index_encoded_size,
+            ShardingIndexLocation::End => 0,
+        };
+        for offset_size in shard_index.chunks_exact_mut(2) {
+            if *offset_size == [u64::MAX, u64::MAX] {
+                continue;
+            }
+            let encoded_chunk = offset_size[0]
+                .checked_add(offset_size[1])
+                .and_then(|end| {
+                    let start = usize::try_from(offset_size[0]).ok()?;
+                    let end = usize::try_from(end).ok()?;
+                    encoded_shard.get(start..end)
+                })
+                .ok_or_else(|| {
+                    CodecError::Other(
+                        "the shard index references bytes beyond the end of the encoded shard"
+                            .to_string(),
+                    )
+                })?;
+            encoded_chunks.extend_from_slice(encoded_chunk);
+            offset_size[0] = offset;
+            offset += offset_size[1];
+        }
+
+        // Encode the shard index and write the compacted shard
+        let shard_index_bytes: RawBytes = transmute_to_bytes_vec(shard_index).into();
+        let encoded_shard_index = self.index_codecs.encode(
+            shard_index_bytes.into(),
+            &index_decoded_representation,
+            options,
+        )?;
+        Ok(match self.index_location {
+            ShardingIndexLocation::Start => {
+                [&encoded_shard_index[..], &encoded_chunks[..]].concat()
+            }
+            ShardingIndexLocation::End => {
+                encoded_chunks.extend_from_slice(&encoded_shard_index);
+                encoded_chunks
+            }
+        })
+    }
+}
+
 impl CodecTraits for ShardingCodec {
     fn create_metadata_opt(&self, _options: &ArrayMetadataOptions) -> Option<MetadataV3> {
         let configuration = ShardingCodecConfigurationV1 {
@@ -552,6 +677,29 @@ impl ArrayToBytesCodecTraits for ShardingCodec {
         ))
     }
 
+    #[cfg(feature = "async")]
+    async fn async_partial_encoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+        output_handle: Arc<dyn AsyncBytesPartialEncoderTraits>,
+        decoded_representation: &ChunkRepresentation,
+        options: &CodecOptions,
+    ) -> Result<Arc<dyn AsyncArrayPartialEncoderTraits>, CodecError> {
+        Ok(Arc::new(
+            sharding_partial_encoder::AsyncShardingPartialEncoder::new(
+                input_handle,
+                output_handle,
+                decoded_representation.clone(),
+                self.chunk_shape.clone(),
+                self.inner_codecs.clone(),
+                self.index_codecs.clone(),
+                self.index_location,
+                options,
+            )
+            .await?,
+        ))
+    }
+
     fn compute_encoded_size(
         &self,
         decoded_representation: &ChunkRepresentation,
@@ -3,21 +3,27 @@ use std::sync::Arc;
 use codec::CodecChain;
 
 use crate::array::{
-    codec::{self, ArrayToArrayCodecTraits, ArrayToBytesCodecTraits, BytesToBytesCodecTraits},
+    codec::{
+        self, ArrayToArrayCodecTraits, ArrayToBytesCodecTraits, BytesToBytesCodecTraits, CodecError,
+    },
     ChunkShape,
 };
 
-use super::{ShardingCodec, ShardingIndexLocation};
+use super::{validate_index_codecs, ShardingCodec, ShardingIndexLocation};
 
 /// A [`ShardingCodec`] builder.
 ///
 /// By default, both the inner chunks and the index are encoded with the `bytes` codec with native endian encoding.
 /// The index is additionally encoded with the `crc32c checksum` codec (if supported).
 ///
+/// The index can be encoded with any array to array, array to bytes, and bytes to bytes codecs, provided that the encoded index has a fixed size.
+/// [`try_build`](ShardingCodecBuilder::try_build) validates this, whereas [`build`](ShardingCodecBuilder::build) defers any error until the codec is first used.
+///
 /// Use the methods in the `sharding` codec builder to change the configuration away from these defaults, and then build the `sharding` codec with [`build`](ShardingCodecBuilder::build).
 #[derive(Debug)]
 pub struct ShardingCodecBuilder {
     inner_chunk_shape: ChunkShape,
+    index_array_to_array_codecs: Vec<Arc<dyn ArrayToArrayCodecTraits>>,
     index_array_to_bytes_codec: Arc<dyn ArrayToBytesCodecTraits>,
     index_bytes_to_bytes_codecs: Vec<Arc<dyn BytesToBytesCodecTraits>>,
     array_to_array_codecs: Vec<Arc<dyn ArrayToArrayCodecTraits>>,
@@ -32,6 +38,7 @@ impl ShardingCodecBuilder {
     pub fn new(inner_chunk_shape: ChunkShape) -> Self {
         Self {
             inner_chunk_shape,
+            index_array_to_array_codecs: Vec::default(),
             index_array_to_bytes_codec: Arc::<codec::BytesCodec>::default(),
             index_bytes_to_bytes_codecs: vec![
                 #[cfg(feature = "crc32c")]
@@ -44,6 +51,17 @@ impl ShardingCodecBuilder {
         }
     }
 
+    /// Set the index array to array codecs.
+    ///
+    /// If left unmodified, no array to array codecs will be applied for the index.
+    pub fn index_array_to_array_codecs(
+        &mut self,
+        index_array_to_array_codecs: Vec<Arc<dyn ArrayToArrayCodecTraits>>,
+    ) -> &mut Self {
+        self.index_array_to_array_codecs = index_array_to_array_codecs;
+        self
+    }
+
     /// Set the index array to bytes codec.
     ///
     /// If left unmodified, the index will be encoded with the `bytes` codec with native endian encoding.
@@ -107,6 +125,14 @@ impl ShardingCodecBuilder {
         self
     }
 
+    fn index_codecs(&self) -> CodecChain {
+        CodecChain::new(
+            self.index_array_to_array_codecs.clone(),
+            self.index_array_to_bytes_codec.clone(),
+            self.index_bytes_to_bytes_codecs.clone(),
+        )
+    }
+
     /// Build into a [`ShardingCodec`].
     #[must_use]
     pub fn build(&self) -> ShardingCodec {
@@ -115,15 +141,10 @@ impl ShardingCodecBuilder {
             self.array_to_bytes_codec.clone(),
             self.bytes_to_bytes_codecs.clone(),
         ));
-        let index_codecs = Arc::new(CodecChain::new(
-            vec![],
-            self.index_array_to_bytes_codec.clone(),
-            self.index_bytes_to_bytes_codecs.clone(),
-        ));
         ShardingCodec::new(
             self.inner_chunk_shape.clone(),
             inner_codecs,
-            index_codecs,
+            Arc::new(self.index_codecs()),
             self.index_location,
         )
     }
@@ -133,4 +154,21 @@ impl ShardingCodecBuilder {
     pub fn build_arc(&self) -> Arc<ShardingCodec> {
         Arc::new(self.build())
     }
+
+    /// Build into a [`ShardingCodec`], validating the index codecs.
+    ///
+    /// # Errors
+    /// Returns a [`CodecError`] if the index codecs are incompatible with the shard index or do not have a fixed size output.
+    pub fn try_build(&self) -> Result<ShardingCodec, CodecError> {
+        validate_index_codecs(&self.index_codecs(), self.inner_chunk_shape.len())?;
+        Ok(self.build())
+    }
+
+    /// Build into an [`Arc<ShardingCodec>`], validating the index codecs.
+    ///
+    /// # Errors
+    /// Returns a [`CodecError`] if the index codecs are incompatible with the shard index or do not have a fixed size output.
+    pub fn try_build_arc(&self) -> Result<Arc<ShardingCodec>, CodecError> {
+        Ok(Arc::new(self.try_build()?))
+    }
 }
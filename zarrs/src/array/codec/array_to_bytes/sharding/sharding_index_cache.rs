@@ -0,0 +1,162 @@
+use std::sync::Arc;
+
+use moka::{
+    policy::EvictionPolicy,
+    sync::{Cache, CacheBuilder},
+};
+
+use crate::storage::StoreKey;
+
+/// An LRU (least recently used) cache of decoded shard indices with a fixed shard capacity.
+///
+/// Partial decoding of a shard must first retrieve and decode its shard index.
+/// A shard index cache avoids repeating this for repeated partial reads of the same shard, such as many small reads of inner chunks of a remote array.
+/// It is attached to an array with [`Array::with_shard_index_cache`](crate::array::Array::with_shard_index_cache).
+///
+/// Shard indices are keyed by the store key of their shard, so a cache must not be shared between arrays.
+/// A shard index is only cached if the shard is partially decoded directly from a store, i.e. there are no bytes to bytes codecs after the `sharding_indexed` codec.
+///
+/// The cached shard index of a shard is invalidated when the shard is written or erased by the array.
+/// Writes by other means, such as another [`Array`](crate::array::Array) or process, are not observed.
+/// Use [`invalidate`](ShardIndexCache::invalidate) or [`clear`](ShardIndexCache::clear) if a shard may have been modified elsewhere.
+#[derive(Debug)]
+pub struct ShardIndexCache {
+    cache: Cache<StoreKey, Arc<Vec<u64>>>,
+}
+
+impl ShardIndexCache {
+    /// Create a new [`ShardIndexCache`] with a capacity in shards of `shard_capacity`.
+    #[must_use]
+    pub fn new(shard_capacity: u64) -> Self {
+        let cache = CacheBuilder::new(shard_capacity)
+            .eviction_policy(EvictionPolicy::lru())
+            .build();
+        Self { cache }
+    }
+
+    /// Return the cached shard index of the shard at `key`, if any.
+    #[must_use]
+    pub fn get(&self, key: &StoreKey) -> Option<Arc<Vec<u64>>> {
+        self.cache.get(key)
+    }
+
+    /// Insert the shard index of the shard at `key`.
+    pub fn insert(&self, key: StoreKey, shard_index: Arc<Vec<u64>>) {
+        self.cache.insert(key, shard_index);
+    }
+
+    /// Invalidate the cached shard index of the shard at `key`.
+    pub fn invalidate(&self, key: &StoreKey) {
+        self.cache.invalidate(key);
+    }
+
+    /// Invalidate all cached shard indices.
+    pub fn clear(&self) {
+        self.cache.invalidate_all();
+    }
+
+    /// Return the number of cached shard indices.
+    #[allow(clippy::missing_panics_doc)]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.cache.run_pending_tasks();
+        usize::try_from(self.cache.entry_count()).unwrap()
+    }
+
+    /// Returns true if the cache contains no shard indices.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        array::{
+            codec::array_to_bytes::sharding::ShardingCodecBuilder, Array, ArrayBuilder, DataType,
+            FillValue,
+        },
+        array_subset::ArraySubset,
+        storage::{
+            storage_adapter::performance_metrics::PerformanceMetricsStorageAdapter,
+            store::MemoryStore,
+        },
+    };
+
+    use super::*;
+
+    #[test]
+    fn shard_index_cache() -> Result<(), Box<dyn std::error::Error>> {
+        let store = Arc::new(MemoryStore::default());
+        let store = Arc::new(PerformanceMetricsStorageAdapter::new(store));
+        let array = ArrayBuilder::new(
+            vec![8, 8], // array shape
+            DataType::UInt16,
+            vec![4, 4].try_into()?, // regular chunk shape
+            FillValue::from(0u16),
+        )
+        .array_to_bytes_codec(Arc::new(
+            ShardingCodecBuilder::new(vec![2, 2].try_into()?).build(),
+        ))
+        .build(store.clone(), "/array")?;
+        array.store_metadata()?;
+        let data: Vec<u16> = (0..64).collect();
+        array.store_array_subset_elements(&array.subset_all(), &data)?;
+
+        let cache = Arc::new(ShardIndexCache::new(4));
+        let array = array.with_shard_index_cache(cache.clone());
+        let inner_chunk_0 = ArraySubset::new_with_ranges(&[0..2, 0..2]);
+        let inner_chunk_1 = ArraySubset::new_with_ranges(&[2..4, 2..4]);
+
+        // The first partial read of a shard reads the shard index and the inner chunk
+        store.reset();
+        assert!(cache.is_empty());
+        let elements = array.retrieve_array_subset_elements::<u16>(&inner_chunk_0)?;
+        assert_eq!(elements, [0, 1, 8, 9]);
+        assert_eq!(store.reads(), 2);
+        assert_eq!(cache.len(), 1);
+
+        // Later partial reads of the shard only read the inner chunk
+        let elements = array.retrieve_array_subset_elements::<u16>(&inner_chunk_1)?;
+        assert_eq!(elements, [18, 19, 26, 27]);
+        assert_eq!(store.reads(), 3);
+
+        // Writing the shard invalidates its shard index
+        array.store_chunk_elements::<u16>(&[0, 0], &[1; 16])?;
+        assert!(cache.is_empty());
+        store.reset();
+        let elements = array.retrieve_array_subset_elements::<u16>(&inner_chunk_1)?;
+        assert_eq!(elements, [1, 1, 1, 1]);
+        assert_eq!(store.reads(), 2);
+
+        // Storing encoded shard bytes invalidates its shard index
+        let encoded_shard = array.retrieve_encoded_chunk(&[0, 1])?.unwrap();
+        array.retrieve_array_subset_elements::<u16>(&inner_chunk_0)?;
+        assert_eq!(cache.len(), 1);
+        unsafe { array.store_encoded_chunk(&[0, 0], encoded_shard.into()) }?;
+        assert!(cache.is_empty());
+        let elements = array.retrieve_array_subset_elements::<u16>(&inner_chunk_0)?;
+        assert_eq!(elements, [4, 5, 12, 13]);
+
+        // Erasing the shard invalidates its shard index
+        array.erase_chunk(&[0, 0])?;
+        assert!(cache.is_empty());
+        let elements = array.retrieve_array_subset_elements::<u16>(&inner_chunk_0)?;
+        assert_eq!(elements, [0, 0, 0, 0]);
+
+        // Without a cache, each partial read decodes the shard index
+        let array = Array::open(store.clone(), "/array")?;
+        assert!(array.shard_index_cache().is_none());
+        store.reset();
+        array
+            .retrieve_array_subset_elements::<u16>(&ArraySubset::new_with_ranges(&[0..2, 4..6]))?;
+        array
+            .retrieve_array_subset_elements::<u16>(&ArraySubset::new_with_ranges(&[2..4, 6..8]))?;
+        assert_eq!(store.reads(), 4);
+
+        cache.clear();
+        assert!(cache.is_empty());
+        Ok(())
+    }
+}
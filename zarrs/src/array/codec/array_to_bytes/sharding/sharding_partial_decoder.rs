@@ -1,5 +1,6 @@
-use std::sync::Arc;
+use std::{collections::HashMap, io::Cursor, sync::Arc};
 
+use itertools::Itertools;
 use rayon::prelude::*;
 use unsafe_cell_slice::UnsafeCellSlice;
 
@@ -13,6 +14,7 @@ use crate::array::{
     concurrency::{calc_concurrency_outer_inner, RecommendedConcurrency},
     ravel_indices, ArrayBytes, ArraySize, ChunkRepresentation, ChunkShape, DataType, DataTypeSize,
 };
+use crate::{array_subset::iterators::Chunks, byte_range::ByteRange};
 
 #[cfg(feature = "async")]
 use crate::array::codec::{
@@ -22,13 +24,106 @@ use crate::array::codec::{
 
 use super::{calculate_chunks_per_shard, ShardingIndexLocation};
 
+/// Return the unique offsets and sizes of the encoded inner chunks of `chunks` that are not empty.
+fn inner_chunk_offsets_sizes(
+    shard_index: &[u64],
+    chunks: &Chunks,
+    chunks_per_shard: &[u64],
+) -> Vec<(u64, u64)> {
+    chunks
+        .iter()
+        .filter_map(|(chunk_indices, _)| {
+            let chunk_index =
+                usize::try_from(ravel_indices(&chunk_indices, chunks_per_shard)).unwrap();
+            let offset = shard_index[chunk_index * 2];
+            let size = shard_index[chunk_index * 2 + 1];
+            if offset == u64::MAX && size == u64::MAX {
+                None
+            } else {
+                Some((offset, size))
+            }
+        })
+        .unique()
+        .collect()
+}
+
+/// Partial decoders of encoded inner chunks keyed by their offset and size.
+type InnerChunkDecoders<T> = HashMap<(u64, u64), Arc<T>>;
+
+/// Retrieve the encoded inner chunks of `chunks` with coalesced byte ranges if [`CodecOptions::byte_range_coalesce_gap`] is set.
+///
+/// Returns partial decoders of the retrieved inner chunks keyed by their offset and size.
+/// This is empty if byte ranges are not coalesced.
+fn retrieve_inner_chunks(
+    input_handle: &dyn BytesPartialDecoderTraits,
+    shard_index: &[u64],
+    chunks: &Chunks,
+    chunks_per_shard: &[u64],
+    options: &CodecOptions,
+) -> Result<InnerChunkDecoders<dyn BytesPartialDecoderTraits>, CodecError> {
+    if options.byte_range_coalesce_gap().is_none() {
+        return Ok(HashMap::new());
+    }
+    let offsets_sizes = inner_chunk_offsets_sizes(shard_index, chunks, chunks_per_shard);
+    if offsets_sizes.len() < 2 {
+        return Ok(HashMap::new());
+    }
+    let byte_ranges: Vec<ByteRange> = offsets_sizes
+        .iter()
+        .map(|(offset, size)| ByteRange::FromStart(*offset, Some(*size)))
+        .collect();
+    let Some(encoded_inner_chunks) = input_handle.partial_decode(&byte_ranges, options)? else {
+        return Ok(HashMap::new());
+    };
+    Ok(std::iter::zip(offsets_sizes, encoded_inner_chunks)
+        .map(|(offset_size, encoded_inner_chunk)| {
+            let input_handle: Arc<dyn BytesPartialDecoderTraits> =
+                Arc::new(Cursor::new(encoded_inner_chunk.into_owned()));
+            (offset_size, input_handle)
+        })
+        .collect())
+}
+
+#[cfg(feature = "async")]
+/// Asynchronous variant of [`retrieve_inner_chunks`].
+async fn async_retrieve_inner_chunks(
+    input_handle: &dyn AsyncBytesPartialDecoderTraits,
+    shard_index: &[u64],
+    chunks: &Chunks,
+    chunks_per_shard: &[u64],
+    options: &CodecOptions,
+) -> Result<InnerChunkDecoders<dyn AsyncBytesPartialDecoderTraits>, CodecError> {
+    if options.byte_range_coalesce_gap().is_none() {
+        return Ok(HashMap::new());
+    }
+    let offsets_sizes = inner_chunk_offsets_sizes(shard_index, chunks, chunks_per_shard);
+    if offsets_sizes.len() < 2 {
+        return Ok(HashMap::new());
+    }
+    let byte_ranges: Vec<ByteRange> = offsets_sizes
+        .iter()
+        .map(|(offset, size)| ByteRange::FromStart(*offset, Some(*size)))
+        .collect();
+    let Some(encoded_inner_chunks) = input_handle.partial_decode(&byte_ranges, options).await?
+    else {
+        return Ok(HashMap::new());
+    };
+    Ok(std::iter::zip(offsets_sizes, encoded_inner_chunks)
+        .map(|(offset_size, encoded_inner_chunk)| {
+            let input_handle: Arc<dyn AsyncBytesPartialDecoderTraits> =
+                Arc::new(Cursor::new(encoded_inner_chunk.into_owned()));
+            (offset_size, input_handle)
+        })
+        .collect())
+}
+
 /// Partial decoder for the sharding codec.
 pub(crate) struct ShardingPartialDecoder {
     input_handle: Arc<dyn BytesPartialDecoderTraits>,
     decoded_representation: ChunkRepresentation,
     chunk_shape: ChunkShape,
     inner_codecs: Arc<CodecChain>,
-    shard_index: Option<Vec<u64>>,
+    shard_index: Option<Arc<Vec<u64>>>,
 }
 
 impl ShardingPartialDecoder {
@@ -42,14 +137,25 @@ impl ShardingPartialDecoder {
         index_location: ShardingIndexLocation,
         options: &CodecOptions,
     ) -> Result<Self, CodecError> {
-        let shard_index = super::decode_shard_index_partial_decoder(
-            &*input_handle,
-            index_codecs,
-            index_location,
-            chunk_shape.as_slice(),
-            &decoded_representation,
-            options,
-        )?;
+        let shard_index_cache = options.shard_index_cache().zip(input_handle.store_key());
+        let cached_shard_index = shard_index_cache.and_then(|(cache, key)| cache.get(key));
+        let shard_index = if cached_shard_index.is_some() {
+            cached_shard_index
+        } else {
+            let shard_index = super::decode_shard_index_partial_decoder(
+                &*input_handle,
+                index_codecs,
+                index_location,
+                chunk_shape.as_slice(),
+                &decoded_representation,
+                options,
+            )?
+            .map(Arc::new);
+            if let (Some((cache, key)), Some(shard_index)) = (shard_index_cache, &shard_index) {
+                cache.insert(key.clone(), shard_index.clone());
+            }
+            shard_index
+        };
         Ok(Self {
             input_handle,
             decoded_representation,
@@ -127,6 +233,13 @@ impl ArrayPartialDecoderTraits for ShardingPartialDecoder {
         let mut out = Vec::with_capacity(array_subsets.len());
         for array_subset in array_subsets {
             let chunks = unsafe { array_subset.chunks_unchecked(chunk_representation.shape()) };
+            let inner_chunks_encoded = retrieve_inner_chunks(
+                &*self.input_handle,
+                shard_index,
+                &chunks,
+                &chunks_per_shard,
+                &options,
+            )?;
 
             match self.decoded_representation.element_size() {
                 DataTypeSize::Variable => {
@@ -155,12 +268,20 @@ impl ArrayPartialDecoderTraits for ShardingPartialDecoder {
                             )
                         } else {
                             // Partially decode the inner chunk
+                            let input_handle: Arc<dyn BytesPartialDecoderTraits> =
+                                if let Some(input_handle) =
+                                    inner_chunks_encoded.get(&(offset, size))
+                                {
+                                    input_handle.clone()
+                                } else {
+                                    Arc::new(ByteIntervalPartialDecoder::new(
+                                        self.input_handle.clone(),
+                                        offset,
+                                        size,
+                                    ))
+                                };
                             let partial_decoder = self.inner_codecs.clone().partial_decoder(
-                                Arc::new(ByteIntervalPartialDecoder::new(
-                                    self.input_handle.clone(),
-                                    offset,
-                                    size,
-                                )),
+                                input_handle,
                                 &chunk_representation,
                                 &options,
                             )
@@ -236,12 +357,20 @@ impl ArrayPartialDecoderTraits for ShardingPartialDecoder {
                             )
                         } else {
                             // Partially decode the inner chunk
+                            let input_handle: Arc<dyn BytesPartialDecoderTraits> =
+                                if let Some(input_handle) =
+                                    inner_chunks_encoded.get(&(offset, size))
+                                {
+                                    input_handle.clone()
+                                } else {
+                                    Arc::new(ByteIntervalPartialDecoder::new(
+                                        self.input_handle.clone(),
+                                        offset,
+                                        size,
+                                    ))
+                                };
                             let partial_decoder = self.inner_codecs.clone().partial_decoder(
-                                Arc::new(ByteIntervalPartialDecoder::new(
-                                    self.input_handle.clone(),
-                                    offset,
-                                    size,
-                                )),
+                                input_handle,
                                 &chunk_representation,
                                 &options,
                             )
@@ -297,7 +426,7 @@ pub(crate) struct AsyncShardingPartialDecoder {
     decoded_representation: ChunkRepresentation,
     chunk_shape: ChunkShape,
     inner_codecs: Arc<CodecChain>,
-    shard_index: Option<Vec<u64>>,
+    shard_index: Option<Arc<Vec<u64>>>,
 }
 
 #[cfg(feature = "async")]
@@ -312,15 +441,26 @@ impl AsyncShardingPartialDecoder {
         index_location: ShardingIndexLocation,
         options: &CodecOptions,
     ) -> Result<AsyncShardingPartialDecoder, CodecError> {
-        let shard_index = super::decode_shard_index_async_partial_decoder(
-            &*input_handle,
-            index_codecs,
-            index_location,
-            chunk_shape.as_slice(),
-            &decoded_representation,
-            options,
-        )
-        .await?;
+        let shard_index_cache = options.shard_index_cache().zip(input_handle.store_key());
+        let cached_shard_index = shard_index_cache.and_then(|(cache, key)| cache.get(key));
+        let shard_index = if cached_shard_index.is_some() {
+            cached_shard_index
+        } else {
+            let shard_index = super::decode_shard_index_async_partial_decoder(
+                &*input_handle,
+                index_codecs,
+                index_location,
+                chunk_shape.as_slice(),
+                &decoded_representation,
+                options,
+            )
+            .await?
+            .map(Arc::new);
+            if let (Some((cache, key)), Some(shard_index)) = (shard_index_cache, &shard_index) {
+                cache.insert(key.clone(), shard_index.clone());
+            }
+            shard_index
+        };
         Ok(Self {
             input_handle,
             decoded_representation,
@@ -381,11 +521,18 @@ impl AsyncArrayPartialDecoderTraits for AsyncShardingPartialDecoder {
         let mut out = Vec::with_capacity(array_subsets.len());
         // TODO: Could go parallel here?
         for array_subset in array_subsets {
+            let chunks = unsafe { array_subset.chunks_unchecked(chunk_representation.shape()) };
+            let inner_chunks_encoded = async_retrieve_inner_chunks(
+                &*self.input_handle,
+                shard_index,
+                &chunks,
+                &chunks_per_shard,
+                options,
+            )
+            .await?;
+            let inner_chunks_encoded = &inner_chunks_encoded;
             match self.decoded_representation.element_size() {
                 DataTypeSize::Variable => {
-                    let chunks =
-                        unsafe { array_subset.chunks_unchecked(chunk_representation.shape()) };
-
                     let decode_inner_chunk_subset = |(chunk_indices, chunk_subset): (
                         Vec<u64>,
                         _,
@@ -413,12 +560,20 @@ impl AsyncArrayPartialDecoderTraits for AsyncShardingPartialDecoder {
                                 )
                             } else {
                                 // Partially decode the inner chunk
+                                let input_handle: Arc<dyn AsyncBytesPartialDecoderTraits> =
+                                    if let Some(input_handle) =
+                                        inner_chunks_encoded.get(&(offset, size))
+                                    {
+                                        input_handle.clone()
+                                    } else {
+                                        Arc::new(AsyncByteIntervalPartialDecoder::new(
+                                            self.input_handle.clone(),
+                                            offset,
+                                            size,
+                                        ))
+                                    };
                                 let partial_decoder = self.inner_codecs.clone().async_partial_decoder(
-                                    Arc::new(AsyncByteIntervalPartialDecoder::new(
-                                        self.input_handle.clone(),
-                                        offset,
-                                        size,
-                                    )),
+                                    input_handle,
                                     &chunk_representation,
                                     options,
                                 ).await
@@ -461,25 +616,24 @@ impl AsyncArrayPartialDecoderTraits for AsyncShardingPartialDecoder {
                 }
                 DataTypeSize::Fixed(data_type_size) => {
                     // Find filled / non filled chunks
-                    let chunk_info =
-                        unsafe { array_subset.chunks_unchecked(chunk_representation.shape()) }
-                            .into_iter()
-                            .map(|(chunk_indices, chunk_subset)| {
-                                let chunk_index = ravel_indices(&chunk_indices, &chunks_per_shard);
-                                let chunk_index = usize::try_from(chunk_index).unwrap();
-
-                                // Read the offset/size
-                                let offset = shard_index[chunk_index * 2];
-                                let size = shard_index[chunk_index * 2 + 1];
-                                if offset == u64::MAX && size == u64::MAX {
-                                    (chunk_subset, None)
-                                } else {
-                                    let offset: usize = offset.try_into().unwrap();
-                                    let size: usize = size.try_into().unwrap();
-                                    (chunk_subset, Some((offset, size)))
-                                }
-                            })
-                            .collect::<Vec<_>>();
+                    let chunk_info = chunks
+                        .iter()
+                        .map(|(chunk_indices, chunk_subset)| {
+                            let chunk_index = ravel_indices(&chunk_indices, &chunks_per_shard);
+                            let chunk_index = usize::try_from(chunk_index).unwrap();
+
+                            // Read the offset/size
+                            let offset = shard_index[chunk_index * 2];
+                            let size = shard_index[chunk_index * 2 + 1];
+                            if offset == u64::MAX && size == u64::MAX {
+                                (chunk_subset, None)
+                            } else {
+                                let offset: usize = offset.try_into().unwrap();
+                                let size: usize = size.try_into().unwrap();
+                                (chunk_subset, Some((offset, size)))
+                            }
+                        })
+                        .collect::<Vec<_>>();
 
                     let shard_size = array_subset.num_elements_usize() * data_type_size;
                     let mut shard = Vec::with_capacity(shard_size);
@@ -497,15 +651,23 @@ impl AsyncArrayPartialDecoderTraits for AsyncShardingPartialDecoder {
                             .map(|(chunk_subset, (offset, size))| {
                                 let chunk_representation = chunk_representation.clone();
                                 async move {
+                                let offset = u64::try_from(*offset).unwrap();
+                                let size = u64::try_from(*size).unwrap();
+                                let input_handle: Arc<dyn AsyncBytesPartialDecoderTraits> =
+                                    if let Some(input_handle) = inner_chunks_encoded.get(&(offset, size)) {
+                                        input_handle.clone()
+                                    } else {
+                                        Arc::new(AsyncByteIntervalPartialDecoder::new(
+                                            self.input_handle.clone(),
+                                            offset,
+                                            size,
+                                        ))
+                                    };
                                 let partial_decoder = self
                                     .inner_codecs
                                     .clone()
                                     .async_partial_decoder(
-                                        Arc::new(AsyncByteIntervalPartialDecoder::new(
-                                            self.input_handle.clone(),
-                                            u64::try_from(*offset).unwrap(),
-                                            u64::try_from(*size).unwrap(),
-                                        )),
+                                        input_handle,
                                         &chunk_representation,
                                         options, // TODO: Adjust options for partial decoding?
                                     )
@@ -8,6 +8,7 @@ use itertools::Itertools;
 use rayon::iter::{
     IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
 };
+use zarrs_storage::byte_range::ByteOffset;
 
 use crate::{
     array::{
@@ -18,43 +19,60 @@ use crate::{
             ArrayPartialEncoderTraits, ArrayToBytesCodecTraits, BytesPartialDecoderTraits,
             BytesPartialEncoderTraits, CodecError, CodecOptions,
         },
-        ravel_indices, transmute_to_bytes, ArrayBytes, ArraySize, ChunkRepresentation, ChunkShape,
-        CodecChain, RawBytes,
+        ravel_indices, transmute_to_bytes, ArrayBytes, ArrayShape, ArraySize, ChunkRepresentation,
+        ChunkShape, CodecChain, RawBytes,
     },
     array_subset::{ArraySubset, IncompatibleArraySubsetAndShapeError},
     byte_range::ByteRange,
 };
 
+#[cfg(feature = "async")]
+use crate::array::codec::{
+    AsyncArrayPartialEncoderTraits, AsyncBytesPartialDecoderTraits, AsyncBytesPartialEncoderTraits,
+};
+
 use super::{sharding_index_decoded_representation, ShardingIndexLocation};
 
-pub(crate) struct ShardingPartialEncoder {
-    input_handle: Arc<dyn BytesPartialDecoderTraits>,
-    output_handle: Arc<dyn BytesPartialEncoderTraits>,
+/// The inner chunks of a shard updated by a partial encode.
+struct InnerChunksUpdate {
+    /// The indices of all inner chunks intersecting the updated subsets.
+    intersected: HashSet<u64>,
+    /// The indices of existing inner chunks that straddle the updated subsets and must be retrieved.
+    retrieved: Vec<u64>,
+    /// The byte ranges of the retrieved inner chunks.
+    byte_ranges: Vec<ByteRange>,
+}
+
+/// The writes to a shard for a partial encode.
+struct ShardWrites {
+    /// The shard is erased before writing.
+    erase: bool,
+    /// The bytes written to the shard at each offset.
+    offsets_and_bytes: Vec<(ByteOffset, RawBytes<'static>)>,
+}
+
+/// The components of the sharding partial encoders that are independent of the input and output handles.
+struct ShardingPartialEncoderInner {
     decoded_representation: ChunkRepresentation,
     chunk_grid: RegularChunkGrid,
+    chunks_per_shard: ArrayShape,
     inner_codecs: Arc<CodecChain>,
     index_codecs: Arc<CodecChain>,
     index_location: ShardingIndexLocation,
     index_decoded_representation: ChunkRepresentation,
     inner_chunk_representation: ChunkRepresentation,
-    shard_index: Arc<Mutex<Vec<u64>>>,
 }
 
-impl ShardingPartialEncoder {
-    /// Create a new partial encoder for the sharding codec.
-    #[allow(clippy::too_many_arguments)]
-    pub(crate) fn new(
-        input_handle: Arc<dyn BytesPartialDecoderTraits>,
-        output_handle: Arc<dyn BytesPartialEncoderTraits>,
+impl ShardingPartialEncoderInner {
+    fn new(
         decoded_representation: ChunkRepresentation,
         chunk_shape: ChunkShape,
         inner_codecs: Arc<CodecChain>,
         index_codecs: Arc<CodecChain>,
         index_location: ShardingIndexLocation,
-        options: &CodecOptions,
     ) -> Result<Self, CodecError> {
         let chunks_per_shard =
-            &calculate_chunks_per_shard(decoded_representation.shape(), &chunk_shape)?;
+            calculate_chunks_per_shard(decoded_representation.shape(), &chunk_shape)?;
         let index_decoded_representation =
             sharding_index_decoded_representation(chunks_per_shard.as_slice());
         let inner_chunk_representation = ChunkRepresentation::new(
@@ -64,93 +82,45 @@ impl ShardingPartialEncoder {
         )
         .map_err(|_| CodecError::Other("Fill value and data type are incompatible?".to_string()))?;
 
-        // Decode the index
-        let shard_index = super::decode_shard_index_partial_decoder(
-            &*input_handle,
-            &index_codecs,
-            index_location,
-            inner_chunk_representation.shape(),
-            &decoded_representation,
-            options,
-        )?
-        .unwrap_or_else(|| {
-            let num_chunks =
-                usize::try_from(chunks_per_shard.iter().map(|x| x.get()).product::<u64>()).unwrap();
-            vec![u64::MAX; num_chunks * 2]
-        });
-
         Ok(Self {
-            input_handle,
-            output_handle,
             decoded_representation,
             chunk_grid: RegularChunkGrid::new(chunk_shape),
+            chunks_per_shard: chunks_per_shard.to_array_shape(),
             inner_codecs,
             index_codecs,
             index_location,
             index_decoded_representation,
             inner_chunk_representation,
-            shard_index: Arc::new(Mutex::new(shard_index)),
         })
     }
-}
 
-impl ArrayPartialEncoderTraits for ShardingPartialEncoder {
-    fn erase(&self) -> Result<(), super::CodecError> {
-        self.output_handle.erase()
+    /// Return the shard index of an empty shard.
+    fn empty_shard_index(&self) -> Vec<u64> {
+        let num_chunks = usize::try_from(self.chunks_per_shard.iter().product::<u64>()).unwrap();
+        vec![u64::MAX; num_chunks * 2]
     }
 
-    #[allow(clippy::too_many_lines)]
-    #[allow(clippy::similar_names)]
-    fn partial_encode(
-        &self,
-        subsets_and_bytes: &[(&ArraySubset, ArrayBytes<'_>)],
-        options: &super::CodecOptions,
-    ) -> Result<(), super::CodecError> {
-        let mut shard_index = self.shard_index.lock().unwrap();
-
-        let chunks_per_shard = calculate_chunks_per_shard(
-            self.decoded_representation.shape(),
-            self.inner_chunk_representation.shape(),
-        )?;
-        let chunks_per_shard = chunks_per_shard.to_array_shape();
-
-        // Get the maximum offset of existing encoded chunks
-        let max_data_offset = shard_index
-            .iter()
-            .tuples()
-            .map(|(&offset, &size)| {
-                if offset == u64::MAX && size == u64::MAX {
-                    0
-                } else {
-                    offset + size
-                }
+    /// Return the inner chunks intersecting `chunk_subset`.
+    fn inner_chunks(&self, chunk_subset: &ArraySubset) -> Result<ArraySubset, CodecError> {
+        self.chunk_grid
+            .chunks_in_array_subset(chunk_subset, &self.chunks_per_shard)
+            .map_err(|_| {
+                CodecError::InvalidArraySubsetError(IncompatibleArraySubsetAndShapeError::new(
+                    chunk_subset.clone(),
+                    self.chunks_per_shard.clone(),
+                ))
+            })?
+            .ok_or_else(|| {
+                CodecError::Other("Cannot determine the inner chunk of a chunk subset".to_string())
             })
-            .max()
-            .expect("shards cannot be empty");
-
-        let get_inner_chunks = |chunk_subset| {
-            self.chunk_grid
-                .chunks_in_array_subset(chunk_subset, &chunks_per_shard)
-                .map_err(|_| {
-                    CodecError::InvalidArraySubsetError(IncompatibleArraySubsetAndShapeError::new(
-                        (*chunk_subset).clone(),
-                        chunks_per_shard.clone(),
-                    ))
-                })?
-                .ok_or_else(|| {
-                    CodecError::Other(
-                        "Cannot determine the inner chunk of a chunk subset".to_string(),
-                    )
-                })
-        };
-        let inner_chunk_fill_value = || {
-            let array_size = ArraySize::new(
-                self.inner_chunk_representation.data_type().size(),
-                self.inner_chunk_representation.num_elements(),
-            );
-            ArrayBytes::new_fill_value(array_size, self.inner_chunk_representation.fill_value())
-        };
+    }
 
+    /// Return the inner chunks intersected by `subsets_and_bytes` and the byte ranges of the inner chunks that must be retrieved.
+    fn inner_chunks_update(
+        &self,
+        shard_index: &[u64],
+        subsets_and_bytes: &[(&ArraySubset, ArrayBytes<'_>)],
+    ) -> Result<InnerChunksUpdate, CodecError> {
         // Get all the inner chunks that need to be retrieved
         //   This only includes chunks that straddle chunk subsets.
         //   Chunks that are entirely within a chunk subset are entirely replaced and are not read.
@@ -176,15 +146,13 @@ impl ArrayPartialEncoderTraits for ShardingPartialEncoder {
             }
 
             // Get the iterator over the inner chunks
-            let inner_chunks = get_inner_chunks(chunk_subset)?;
+            let inner_chunks = self.inner_chunks(chunk_subset)?;
             let inner_chunks = inner_chunks.indices();
 
             // Get all the inner chunks intersected
-            inner_chunks_intersected.extend(
-                inner_chunks.into_iter().map(|inner_chunk_indices| {
-                    ravel_indices(&inner_chunk_indices, &chunks_per_shard)
-                }),
-            );
+            inner_chunks_intersected.extend(inner_chunks.into_iter().map(|inner_chunk_indices| {
+                ravel_indices(&inner_chunk_indices, &self.chunks_per_shard)
+            }));
 
             // Get all the inner chunks that need to be updated
             inner_chunks_indices.extend(inner_chunks.into_iter().filter_map(
@@ -208,7 +176,7 @@ impl ArrayPartialEncoderTraits for ShardingPartialEncoder {
                             .any(|(a, b)| *a > b)
                     {
                         let inner_chunk_index =
-                            ravel_indices(&inner_chunk_indices, &chunks_per_shard);
+                            ravel_indices(&inner_chunk_indices, &self.chunks_per_shard);
                         Some(inner_chunk_index)
                     } else {
                         None
@@ -235,11 +203,51 @@ impl ArrayPartialEncoderTraits for ShardingPartialEncoder {
             .sorted_by_key(|(_, byte_range)| *byte_range)
             .unzip();
 
-        // Read the straddling inner chunks
-        let inner_chunks_encoded = self
-            .input_handle
-            .partial_decode(&byte_ranges, options)?
-            .map(|bytes| bytes.into_iter().map(Cow::into_owned).collect::<Vec<_>>());
+        Ok(InnerChunksUpdate {
+            intersected: inner_chunks_intersected,
+            retrieved: inner_chunks_indices,
+            byte_ranges,
+        })
+    }
+
+    /// Update the retrieved inner chunks and `shard_index`, and return the writes to the shard.
+    #[allow(clippy::too_many_lines)]
+    #[allow(clippy::similar_names)]
+    fn shard_writes(
+        &self,
+        shard_index: &mut [u64],
+        subsets_and_bytes: &[(&ArraySubset, ArrayBytes<'_>)],
+        inner_chunks_update: InnerChunksUpdate,
+        inner_chunks_encoded: Option<Vec<Vec<u8>>>,
+        options: &CodecOptions,
+    ) -> Result<ShardWrites, CodecError> {
+        let InnerChunksUpdate {
+            intersected: inner_chunks_intersected,
+            retrieved: inner_chunks_indices,
+            byte_ranges: _,
+        } = inner_chunks_update;
+
+        // Get the maximum offset of existing encoded chunks
+        let max_data_offset = shard_index
+            .iter()
+            .tuples()
+            .map(|(&offset, &size)| {
+                if offset == u64::MAX && size == u64::MAX {
+                    0
+                } else {
+                    offset + size
+                }
+            })
+            .max()
+            .expect("shards cannot be empty");
+
+        let inner_chunk_fill_value = || {
+            let array_size = ArraySize::new(
+                self.inner_chunk_representation.data_type().size(),
+                self.inner_chunk_representation.num_elements(),
+            );
+            ArrayBytes::new_fill_value(array_size, self.inner_chunk_representation.fill_value())
+        };
 
         // Decode the straddling inner chunks
         let inner_chunks_decoded: HashMap<_, _> =
@@ -267,17 +275,18 @@ impl ArrayPartialEncoderTraits for ShardingPartialEncoder {
         //   This loop is intentionally not run in parallel so that overapping subset updates are applied incrementally rather than having a non deterministic output.
         let inner_chunks_decoded = Arc::new(Mutex::new(inner_chunks_decoded));
         for (chunk_subset, chunk_subset_bytes) in subsets_and_bytes {
-            let inner_chunks = get_inner_chunks(chunk_subset)?;
+            let inner_chunks = self.inner_chunks(chunk_subset)?;
 
             inner_chunks
                 .indices()
                 .into_par_iter()
                 .try_for_each(|inner_chunk_indices| {
                     // Extract the inner chunk bytes that overlap with the chunk subset
-                    let inner_chunk_index = ravel_indices(&inner_chunk_indices, &chunks_per_shard);
+                    let inner_chunk_index =
+                        ravel_indices(&inner_chunk_indices, &self.chunks_per_shard);
                     let inner_chunk_subset = self
                         .chunk_grid
-                        .subset(&inner_chunk_indices, &chunks_per_shard)
+                        .subset(&inner_chunk_indices, &self.chunks_per_shard)
                         .expect("already validated")
                         .expect("regular grid");
                     let inner_chunk_subset_overlap =
@@ -353,11 +362,10 @@ impl ArrayPartialEncoderTraits for ShardingPartialEncoder {
             shard_index[usize::try_from(inner_chunk_index * 2).unwrap()] = u64::MAX;
             shard_index[usize::try_from(inner_chunk_index * 2 + 1).unwrap()] = u64::MAX;
         }
-        let max_data_offset = if shard_index.par_iter().all(|&x| x == u64::MAX) {
-            self.output_handle.erase()?;
-            0
+        let (erase, max_data_offset) = if shard_index.par_iter().all(|&x| x == u64::MAX) {
+            (true, 0)
         } else {
-            max_data_offset
+            (false, max_data_offset)
         };
 
         // Get the offset for new data
@@ -388,58 +396,237 @@ impl ArrayPartialEncoderTraits for ShardingPartialEncoder {
 
         if shard_index.par_iter().all(|&x| x == u64::MAX) {
             // Erase the shard if all chunks are empty
-            self.output_handle.erase()?;
-        } else {
-            // Encode the updated shard index
-            let shard_index_bytes: RawBytes = transmute_to_bytes(shard_index.as_slice()).into();
-            let encoded_array_index = self
-                .index_codecs
-                .encode(
-                    shard_index_bytes.into(),
-                    &self.index_decoded_representation,
-                    options,
-                )?
-                .into_owned();
-
-            // Get the total size of the encoded inner chunks
-            let encoded_inner_chunks_size = updated_inner_chunks
-                .iter()
-                .filter_map(|(_, inner_chunk_encoded)| inner_chunk_encoded.as_ref().map(Vec::len))
-                .sum::<usize>();
+            return Ok(ShardWrites {
+                erase: true,
+                offsets_and_bytes: vec![],
+            });
+        }
 
-            // Get the suffix write size
-            let suffix_write_size = match self.index_location {
-                ShardingIndexLocation::Start => encoded_inner_chunks_size,
-                ShardingIndexLocation::End => encoded_inner_chunks_size + encoded_array_index.len(),
-            };
+        // Encode the updated shard index
+        let shard_index_bytes: RawBytes = transmute_to_bytes(&*shard_index).into();
+        let encoded_array_index = self
+            .index_codecs
+            .encode(
+                shard_index_bytes.into(),
+                &self.index_decoded_representation,
+                options,
+            )?
+            .into_owned();
+
+        // Get the total size of the encoded inner chunks
+        let encoded_inner_chunks_size = updated_inner_chunks
+            .iter()
+            .filter_map(|(_, inner_chunk_encoded)| inner_chunk_encoded.as_ref().map(Vec::len))
+            .sum::<usize>();
 
-            // Concatenate the updated inner chunks
-            let mut encoded_output = Vec::with_capacity(suffix_write_size);
-            for (_, inner_chunk_encoded) in updated_inner_chunks {
-                if let Some(inner_chunk_encoded) = inner_chunk_encoded {
-                    encoded_output.extend(inner_chunk_encoded);
-                }
+        // Get the suffix write size
+        let suffix_write_size = match self.index_location {
+            ShardingIndexLocation::Start => encoded_inner_chunks_size,
+            ShardingIndexLocation::End => encoded_inner_chunks_size + encoded_array_index.len(),
+        };
+
+        // Concatenate the updated inner chunks
+        let mut encoded_output = Vec::with_capacity(suffix_write_size);
+        for (_, inner_chunk_encoded) in updated_inner_chunks {
+            if let Some(inner_chunk_encoded) = inner_chunk_encoded {
+                encoded_output.extend(inner_chunk_encoded);
             }
+        }
 
-            // Write the encoded index and updated inner chunks
-            match self.index_location {
-                ShardingIndexLocation::Start => {
-                    self.output_handle.partial_encode(
-                        &[
-                            (0, Cow::Owned(encoded_array_index)),
-                            (offset_new_chunks, Cow::Owned(encoded_output)),
-                        ],
-                        options,
-                    )?;
-                }
-                ShardingIndexLocation::End => {
-                    encoded_output.extend(encoded_array_index);
-                    self.output_handle.partial_encode(
-                        &[(offset_new_chunks, Cow::Owned(encoded_output))],
-                        options,
-                    )?;
-                }
+        // Get the encoded index and updated inner chunks to write
+        let offsets_and_bytes = match self.index_location {
+            ShardingIndexLocation::Start => vec![
+                (0, Cow::Owned(encoded_array_index)),
+                (offset_new_chunks, Cow::Owned(encoded_output)),
+            ],
+            ShardingIndexLocation::End => {
+                encoded_output.extend(encoded_array_index);
+                vec![(offset_new_chunks, Cow::Owned(encoded_output))]
             }
+        };
+        Ok(ShardWrites {
+            erase,
+            offsets_and_bytes,
+        })
+    }
+}
+
+pub(crate) struct ShardingPartialEncoder {
+    input_handle: Arc<dyn BytesPartialDecoderTraits>,
+    output_handle: Arc<dyn BytesPartialEncoderTraits>,
+    inner: ShardingPartialEncoderInner,
+    shard_index: Arc<Mutex<Vec<u64>>>,
+}
+
+impl ShardingPartialEncoder {
+    /// Create a new partial encoder for the sharding codec.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        input_handle: Arc<dyn BytesPartialDecoderTraits>,
+        output_handle: Arc<dyn BytesPartialEncoderTraits>,
+        decoded_representation: ChunkRepresentation,
+        chunk_shape: ChunkShape,
+        inner_codecs: Arc<CodecChain>,
+        index_codecs: Arc<CodecChain>,
+        index_location: ShardingIndexLocation,
+        options: &CodecOptions,
+    ) -> Result<Self, CodecError> {
+        let inner = ShardingPartialEncoderInner::new(
+            decoded_representation,
+            chunk_shape,
+            inner_codecs,
+            index_codecs,
+            index_location,
+        )?;
+
+        // Decode the index
+        let shard_index = super::decode_shard_index_partial_decoder(
+            &*input_handle,
+            &inner.index_codecs,
+            index_location,
+            inner.inner_chunk_representation.shape(),
+            &inner.decoded_representation,
+            options,
+        )?
+        .unwrap_or_else(|| inner.empty_shard_index());
+
+        Ok(Self {
+            input_handle,
+            output_handle,
+            inner,
+            shard_index: Arc::new(Mutex::new(shard_index)),
+        })
+    }
+}
+
+impl ArrayPartialEncoderTraits for ShardingPartialEncoder {
+    fn erase(&self) -> Result<(), super::CodecError> {
+        self.output_handle.erase()
+    }
+
+    fn partial_encode(
+        &self,
+        subsets_and_bytes: &[(&ArraySubset, ArrayBytes<'_>)],
+        options: &super::CodecOptions,
+    ) -> Result<(), super::CodecError> {
+        let mut shard_index = self.shard_index.lock().unwrap();
+
+        // Read the straddling inner chunks
+        let inner_chunks_update = self
+            .inner
+            .inner_chunks_update(&shard_index, subsets_and_bytes)?;
+        let inner_chunks_encoded = self
+            .input_handle
+            .partial_decode(&inner_chunks_update.byte_ranges, options)?
+            .map(|bytes| bytes.into_iter().map(Cow::into_owned).collect::<Vec<_>>());
+
+        let shard_writes = self.inner.shard_writes(
+            &mut shard_index,
+            subsets_and_bytes,
+            inner_chunks_update,
+            inner_chunks_encoded,
+            options,
+        )?;
+        if shard_writes.erase {
+            self.output_handle.erase()?;
+        }
+        if !shard_writes.offsets_and_bytes.is_empty() {
+            self.output_handle
+                .partial_encode(&shard_writes.offsets_and_bytes, options)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+pub(crate) struct AsyncShardingPartialEncoder {
+    input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+    output_handle: Arc<dyn AsyncBytesPartialEncoderTraits>,
+    inner: ShardingPartialEncoderInner,
+    shard_index: futures::lock::Mutex<Vec<u64>>,
+}
+
+#[cfg(feature = "async")]
+impl AsyncShardingPartialEncoder {
+    /// Create a new asynchronous partial encoder for the sharding codec.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn new(
+        input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+        output_handle: Arc<dyn AsyncBytesPartialEncoderTraits>,
+        decoded_representation: ChunkRepresentation,
+        chunk_shape: ChunkShape,
+        inner_codecs: Arc<CodecChain>,
+        index_codecs: Arc<CodecChain>,
+        index_location: ShardingIndexLocation,
+        options: &CodecOptions,
+    ) -> Result<Self, CodecError> {
+        let inner = ShardingPartialEncoderInner::new(
+            decoded_representation,
+            chunk_shape,
+            inner_codecs,
+            index_codecs,
+            index_location,
+        )?;
+
+        // Decode the index
+        let shard_index = super::decode_shard_index_async_partial_decoder(
+            &*input_handle,
+            &inner.index_codecs,
+            index_location,
+            inner.inner_chunk_representation.shape(),
+            &inner.decoded_representation,
+            options,
+        )
+        .await?
+        .unwrap_or_else(|| inner.empty_shard_index());
+
+        Ok(Self {
+            input_handle,
+            output_handle,
+            inner,
+            shard_index: futures::lock::Mutex::new(shard_index),
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl AsyncArrayPartialEncoderTraits for AsyncShardingPartialEncoder {
+    async fn erase(&self) -> Result<(), CodecError> {
+        self.output_handle.erase().await
+    }
+
+    async fn partial_encode(
+        &self,
+        subsets_and_bytes: &[(&ArraySubset, ArrayBytes<'_>)],
+        options: &CodecOptions,
+    ) -> Result<(), CodecError> {
+        let mut shard_index = self.shard_index.lock().await;
+
+        // Read the straddling inner chunks
+        let inner_chunks_update = self
+            .inner
+            .inner_chunks_update(&shard_index, subsets_and_bytes)?;
+        let inner_chunks_encoded = self
+            .input_handle
+            .partial_decode(&inner_chunks_update.byte_ranges, options)
+            .await?
+            .map(|bytes| bytes.into_iter().map(Cow::into_owned).collect::<Vec<_>>());
+
+        let shard_writes = self.inner.shard_writes(
+            &mut shard_index,
+            subsets_and_bytes,
+            inner_chunks_update,
+            inner_chunks_encoded,
+            options,
+        )?;
+        if shard_writes.erase {
+            self.output_handle.erase().await?;
+        }
+        if !shard_writes.offsets_and_bytes.is_empty() {
+            self.output_handle
+                .partial_encode(&shard_writes.offsets_and_bytes, options)
+                .await?;
         }
         Ok(())
     }
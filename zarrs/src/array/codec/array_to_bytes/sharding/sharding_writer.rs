@@ -0,0 +1,165 @@
+use std::{io::Write, sync::Arc};
+
+use crate::array::{
+    codec::{ArrayToBytesCodecTraits, CodecChain, CodecError, CodecOptions},
+    ravel_indices, transmute_to_bytes_vec, ArrayBytes, ChunkRepresentation, ChunkShape, RawBytes,
+};
+
+use super::{
+    calculate_chunks_per_shard, compute_index_encoded_size, sharding_index_decoded_representation,
+    ShardingIndexLocation,
+};
+
+/// An append-only writer of an encoded shard.
+///
+/// A shard writer encodes inner chunks as they are written and writes them to the underlying writer in a single pass, so the inner chunks of a shard need not all be held in memory.
+/// The shard index is encoded and written when the shard writer is [finished](ShardWriter::finish).
+/// If the shard index is located at the start of the shard, the encoded inner chunks are buffered in memory until the shard writer is finished.
+///
+/// Each inner chunk can be written at most once, in any order.
+/// Inner chunks that are not written, or are entirely the fill value, are not stored in the shard.
+///
+/// Create a shard writer with [`ShardingCodec::shard_writer`](super::ShardingCodec::shard_writer).
+pub struct ShardWriter<W: Write> {
+    writer: W,
+    inner_codecs: Arc<CodecChain>,
+    index_codecs: Arc<CodecChain>,
+    index_location: ShardingIndexLocation,
+    chunk_representation: ChunkRepresentation,
+    chunks_per_shard: Vec<u64>,
+    index_decoded_representation: ChunkRepresentation,
+    shard_index: Vec<u64>,
+    written: Vec<bool>,
+    /// The offset of the next inner chunk in the shard.
+    offset: u64,
+    /// The encoded inner chunks, if the shard index is located at the start of the shard.
+    buffer: Vec<u8>,
+    options: CodecOptions,
+}
+
+impl<W: Write> ShardWriter<W> {
+    pub(super) fn new(
+        writer: W,
+        chunk_shape: &ChunkShape,
+        inner_codecs: Arc<CodecChain>,
+        index_codecs: Arc<CodecChain>,
+        index_location: ShardingIndexLocation,
+        shard_representation: &ChunkRepresentation,
+        options: &CodecOptions,
+    ) -> Result<Self, CodecError> {
+        let chunk_representation = unsafe {
+            ChunkRepresentation::new_unchecked(
+                chunk_shape.as_slice().to_vec(),
+                shard_representation.data_type().clone(),
+                shard_representation.fill_value().clone(),
+            )
+        };
+        let chunks_per_shard =
+            calculate_chunks_per_shard(shard_representation.shape(), chunk_shape.as_slice())?;
+        let index_decoded_representation =
+            sharding_index_decoded_representation(chunks_per_shard.as_slice());
+        let index_encoded_size =
+            compute_index_encoded_size(index_codecs.as_ref(), &index_decoded_representation)?;
+        let num_chunks = index_decoded_representation.num_elements_usize() / 2;
+        Ok(Self {
+            writer,
+            inner_codecs,
+            index_codecs,
+            index_location,
+            chunk_representation,
+            chunks_per_shard: chunks_per_shard.to_array_shape(),
+            index_decoded_representation,
+            shard_index: vec![u64::MAX; num_chunks * 2],
+            written: vec![false; num_chunks],
+            offset: match index_location {
+                ShardingIndexLocation::Start => index_encoded_size,
+                ShardingIndexLocation::End => 0,
+            },
+            buffer: Vec::new(),
+            options: options.clone(),
+        })
+    }
+
+    /// Return the representation of the inner chunks.
+    #[must_use]
+    pub fn inner_chunk_representation(&self) -> &ChunkRepresentation {
+        &self.chunk_representation
+    }
+
+    /// Return the number of inner chunks along each dimension of the shard.
+    #[must_use]
+    pub fn inner_chunk_grid_shape(&self) -> &[u64] {
+        &self.chunks_per_shard
+    }
+
+    /// Encode and write the inner chunk at `inner_chunk_indices` in the shard.
+    ///
+    /// # Errors
+    /// Returns a [`CodecError`] if
+    ///  - `inner_chunk_indices` are invalid or the inner chunk has already been written,
+    ///  - the length of `bytes` is not compatible with the inner chunk representation,
+    ///  - the inner chunk cannot be encoded, or
+    ///  - there is an error writing the encoded inner chunk.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn write_inner_chunk(
+        &mut self,
+        inner_chunk_indices: &[u64],
+        bytes: ArrayBytes<'_>,
+    ) -> Result<(), CodecError> {
+        if inner_chunk_indices.len() != self.chunks_per_shard.len()
+            || std::iter::zip(inner_chunk_indices, &self.chunks_per_shard).any(|(i, n)| i >= n)
+        {
+            return Err(CodecError::Other(format!(
+                "inner chunk indices {inner_chunk_indices:?} are invalid for a shard with {:?} inner chunks",
+                self.chunks_per_shard
+            )));
+        }
+        let chunk_index =
+            usize::try_from(ravel_indices(inner_chunk_indices, &self.chunks_per_shard)).unwrap();
+        if self.written[chunk_index] {
+            return Err(CodecError::Other(format!(
+                "inner chunk {inner_chunk_indices:?} has already been written"
+            )));
+        }
+        bytes.validate(
+            self.chunk_representation.num_elements(),
+            self.chunk_representation.data_type().size(),
+        )?;
+        self.written[chunk_index] = true;
+        if bytes.is_fill_value(self.chunk_representation.fill_value()) {
+            return Ok(());
+        }
+
+        let chunk_encoded =
+            self.inner_codecs
+                .encode(bytes, &self.chunk_representation, &self.options)?;
+        match self.index_location {
+            ShardingIndexLocation::Start => self.buffer.extend_from_slice(&chunk_encoded),
+            ShardingIndexLocation::End => self.writer.write_all(&chunk_encoded)?,
+        }
+        let chunk_encoded_size = u64::try_from(chunk_encoded.len()).unwrap();
+        self.shard_index[chunk_index * 2] = self.offset;
+        self.shard_index[chunk_index * 2 + 1] = chunk_encoded_size;
+        self.offset += chunk_encoded_size;
+        Ok(())
+    }
+
+    /// Encode and write the shard index, and return the underlying writer.
+    ///
+    /// # Errors
+    /// Returns a [`CodecError`] if the shard index cannot be encoded or there is an error writing the shard.
+    pub fn finish(mut self) -> Result<W, CodecError> {
+        let shard_index_bytes: RawBytes = transmute_to_bytes_vec(self.shard_index).into();
+        let encoded_shard_index = self.index_codecs.encode(
+            shard_index_bytes.into(),
+            &self.index_decoded_representation,
+            &self.options,
+        )?;
+        self.writer.write_all(&encoded_shard_index)?;
+        if self.index_location == ShardingIndexLocation::Start {
+            self.writer.write_all(&self.buffer)?;
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
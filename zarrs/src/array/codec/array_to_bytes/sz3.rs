@@ -0,0 +1,387 @@
+//! The `sz3` array to bytes codec.
+//!
+//! [SZ3](https://github.com/szcompressor/SZ3) is an error-bounded lossy compressor for scientific data.
+//! The decoded value of every element is within an absolute and/or value range relative error bound of its original value.
+//! 32/64-bit floating point and integer data types are supported.
+//!
+//! <div class="warning">
+//! This codec is experimental and may be incompatible with other Zarr V3 implementations.
+//! </div>
+//!
+//! This codec requires the `sz3` feature, which is disabled by default.
+//!
+//! See [`Sz3CodecConfigurationV1`] for example `JSON` metadata.
+
+mod sz3_codec;
+mod sz3_partial_decoder;
+
+use std::sync::Arc;
+
+pub use crate::metadata::v3::array::codec::sz3::{
+    Sz3CodecConfiguration, Sz3CodecConfigurationV1, Sz3ErrorBound,
+};
+pub use sz3_codec::Sz3Codec;
+
+use crate::{
+    array::{
+        codec::{Codec, CodecError, CodecPlugin},
+        convert_from_bytes_slice, transmute_to_bytes, ChunkRepresentation, DataType,
+    },
+    config::global_config,
+    metadata::v3::{array::codec::sz3, MetadataV3},
+    plugin::{PluginCreateError, PluginMetadataInvalidError},
+};
+
+pub use sz3::IDENTIFIER;
+
+// Register the codec.
+inventory::submit! {
+    CodecPlugin::new(IDENTIFIER, is_name_sz3, create_codec_sz3)
+}
+
+fn is_name_sz3(name: &str) -> bool {
+    name.eq(IDENTIFIER)
+        || name
+            == global_config()
+                .experimental_codec_names()
+                .get(IDENTIFIER)
+                .expect("experimental codec identifier in global map")
+}
+
+pub(crate) fn create_codec_sz3(metadata: &MetadataV3) -> Result<Codec, PluginCreateError> {
+    let configuration: Sz3CodecConfiguration = metadata
+        .to_configuration()
+        .map_err(|_| PluginMetadataInvalidError::new(IDENTIFIER, "codec", metadata.clone()))?;
+    let codec = Arc::new(Sz3Codec::new_with_configuration(&configuration));
+    Ok(Codec::ArrayToBytes(codec))
+}
+
+const fn sz3_error_bound(error_bound: &Sz3ErrorBound) -> ::sz3::ErrorBound {
+    match *error_bound {
+        Sz3ErrorBound::Absolute { absolute } => ::sz3::ErrorBound::Absolute(absolute),
+        Sz3ErrorBound::Relative { relative } => ::sz3::ErrorBound::Relative(relative),
+        Sz3ErrorBound::AbsoluteAndRelative { absolute, relative } => {
+            ::sz3::ErrorBound::AbsoluteAndRelative {
+                absolute_bound: absolute,
+                relative_bound: relative,
+            }
+        }
+        Sz3ErrorBound::AbsoluteOrRelative { absolute, relative } => {
+            ::sz3::ErrorBound::AbsoluteOrRelative {
+                absolute_bound: absolute,
+                relative_bound: relative,
+            }
+        }
+    }
+}
+
+fn sz3_encode(
+    decoded_value: &[u8],
+    decoded_representation: &ChunkRepresentation,
+    error_bound: &Sz3ErrorBound,
+) -> Result<Vec<u8>, CodecError> {
+    macro_rules! sz3_encode {
+        ( $t:ty ) => {{
+            let elements = convert_from_bytes_slice::<$t>(decoded_value);
+            let mut data = ::sz3::DimensionedData::build(&elements);
+            for dim in decoded_representation.shape() {
+                data = data
+                    .dim(usize::try_from(dim.get()).unwrap())
+                    .map_err(|err| CodecError::Other(err.to_string()))?;
+            }
+            let data = data
+                .finish()
+                .map_err(|err| CodecError::Other(err.to_string()))?;
+            ::sz3::compress(&data, sz3_error_bound(error_bound))
+                .map_err(|err| CodecError::Other(err.to_string()))
+        }};
+    }
+
+    let data_type = decoded_representation.data_type();
+    match data_type {
+        DataType::Int32 => sz3_encode!(i32),
+        DataType::Int64 => sz3_encode!(i64),
+        DataType::Float32 => sz3_encode!(f32),
+        DataType::Float64 => sz3_encode!(f64),
+        _ => Err(CodecError::UnsupportedDataType(
+            data_type.clone(),
+            IDENTIFIER.to_string(),
+        )),
+    }
+}
+
+fn sz3_decode(
+    encoded_value: &[u8],
+    decoded_representation: &ChunkRepresentation,
+) -> Result<Vec<u8>, CodecError> {
+    macro_rules! sz3_decode {
+        ( $t:ty ) => {{
+            let (_config, data) = ::sz3::decompress::<$t, _>(encoded_value)
+                .map_err(|err| CodecError::Other(err.to_string()))?;
+            let elements = data.data();
+            if elements.len() as u64 == decoded_representation.num_elements() {
+                Ok(transmute_to_bytes(elements).to_vec())
+            } else {
+                Err(CodecError::UnexpectedChunkDecodedSize(
+                    elements.len() * std::mem::size_of::<$t>(),
+                    decoded_representation.num_elements() * std::mem::size_of::<$t>() as u64,
+                ))
+            }
+        }};
+    }
+
+    let data_type = decoded_representation.data_type();
+    match data_type {
+        DataType::Int32 => sz3_decode!(i32),
+        DataType::Int64 => sz3_decode!(i64),
+        DataType::Float32 => sz3_decode!(f32),
+        DataType::Float64 => sz3_decode!(f64),
+        _ => Err(CodecError::UnsupportedDataType(
+            data_type.clone(),
+            IDENTIFIER.to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroU64, sync::Arc};
+
+    use crate::{
+        array::{
+            codec::{ArrayToBytesCodecTraits, CodecOptions, CodecTraits},
+            ArrayBytes, ChunkRepresentation, DataType, Element, ElementOwned, FillValue,
+        },
+        array_subset::ArraySubset,
+    };
+
+    use super::*;
+
+    const JSON_ABSOLUTE: &str = r#"{
+        "error_bound": "absolute",
+        "absolute": 0.01
+    }"#;
+
+    const JSON_RELATIVE: &str = r#"{
+        "error_bound": "relative",
+        "relative": 0.001
+    }"#;
+
+    fn chunk_representation(data_type: DataType, fill_value: FillValue) -> ChunkRepresentation {
+        ChunkRepresentation::new(
+            vec![
+                NonZeroU64::new(6).unwrap(),
+                NonZeroU64::new(5).unwrap(),
+                NonZeroU64::new(4).unwrap(),
+            ],
+            data_type,
+            fill_value,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn codec_sz3_configuration() {
+        let configuration: Sz3CodecConfiguration = serde_json::from_str(JSON_ABSOLUTE).unwrap();
+        let codec = Sz3Codec::new_with_configuration(&configuration);
+        assert_eq!(
+            codec.error_bound(),
+            &Sz3ErrorBound::Absolute { absolute: 0.01 }
+        );
+        let metadata = codec.create_metadata().unwrap();
+        assert_eq!(
+            metadata
+                .to_configuration::<Sz3CodecConfiguration>()
+                .unwrap(),
+            configuration
+        );
+    }
+
+    #[test]
+    fn codec_sz3_round_trip_f64() {
+        let chunk_representation = chunk_representation(DataType::Float64, FillValue::from(0f64));
+        let elements: Vec<f64> = (0..chunk_representation.num_elements())
+            .map(|i| (i as f64 / 10.0).sin())
+            .collect();
+        let bytes = f64::into_array_bytes(&DataType::Float64, &elements).unwrap();
+
+        let codec = Sz3Codec::new_with_configuration(&serde_json::from_str(JSON_ABSOLUTE).unwrap());
+        let encoded = codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let decoded = codec
+            .decode(encoded, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let decoded_elements = f64::from_array_bytes(&DataType::Float64, decoded).unwrap();
+        assert_eq!(elements.len(), decoded_elements.len());
+        for (element, decoded_element) in elements.iter().zip(&decoded_elements) {
+            assert!((element - decoded_element).abs() <= 0.01);
+        }
+    }
+
+    #[test]
+    fn codec_sz3_round_trip_f32() {
+        let chunk_representation = chunk_representation(DataType::Float32, FillValue::from(0f32));
+        let elements: Vec<f32> = (0..chunk_representation.num_elements())
+            .map(|i| (i as f32 / 10.0).cos() * 100.0)
+            .collect();
+        let bytes = f32::into_array_bytes(&DataType::Float32, &elements).unwrap();
+
+        // The value range is 200, so the absolute error bound is 0.2
+        let codec = Sz3Codec::new_with_configuration(&serde_json::from_str(JSON_RELATIVE).unwrap());
+        let encoded = codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let decoded = codec
+            .decode(encoded, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let decoded_elements = f32::from_array_bytes(&DataType::Float32, decoded).unwrap();
+        assert_eq!(elements.len(), decoded_elements.len());
+        for (element, decoded_element) in elements.iter().zip(&decoded_elements) {
+            assert!((element - decoded_element).abs() <= 0.2);
+        }
+    }
+
+    #[test]
+    fn codec_sz3_round_trip_i32() {
+        let chunk_representation = chunk_representation(DataType::Int32, FillValue::from(0i32));
+        let elements: Vec<i32> = (0..chunk_representation.num_elements() as i32).collect();
+        let bytes = i32::into_array_bytes(&DataType::Int32, &elements).unwrap();
+
+        let codec = Sz3Codec::new(Sz3ErrorBound::Absolute { absolute: 1.0 });
+        let encoded = codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let decoded = codec
+            .decode(encoded, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let decoded_elements = i32::from_array_bytes(&DataType::Int32, decoded).unwrap();
+        assert_eq!(elements.len(), decoded_elements.len());
+        for (element, decoded_element) in elements.iter().zip(&decoded_elements) {
+            assert!((element - decoded_element).abs() <= 1);
+        }
+    }
+
+    #[test]
+    fn codec_sz3_unsupported_data_type() {
+        let chunk_representation = chunk_representation(DataType::UInt8, FillValue::from(0u8));
+        let bytes = ArrayBytes::new_fill_value(
+            crate::array::ArraySize::new(
+                chunk_representation.data_type().size(),
+                chunk_representation.num_elements(),
+            ),
+            chunk_representation.fill_value(),
+        );
+        let codec = Sz3Codec::new_with_configuration(&serde_json::from_str(JSON_ABSOLUTE).unwrap());
+        assert!(codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .is_err());
+        assert!(codec.compute_encoded_size(&chunk_representation).is_err());
+    }
+
+    #[test]
+    fn codec_sz3_partial_decode() {
+        let chunk_representation = chunk_representation(DataType::Float32, FillValue::from(0f32));
+        let elements: Vec<f32> = (0..chunk_representation.num_elements())
+            .map(|i| i as f32)
+            .collect();
+        let bytes = f32::into_array_bytes(&DataType::Float32, &elements)
+            .unwrap()
+            .into_owned();
+
+        let codec = Arc::new(Sz3Codec::new_with_configuration(
+            &serde_json::from_str(JSON_ABSOLUTE).unwrap(),
+        ));
+        let encoded = codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let decoded = codec
+            .decode(
+                encoded.clone(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+
+        let decoded_regions = [
+            ArraySubset::new_with_ranges(&[1..3, 0..5, 2..3]),
+            ArraySubset::new_with_ranges(&[5..6, 4..5, 0..4]),
+        ];
+        let input_handle = Arc::new(std::io::Cursor::new(encoded));
+        let partial_decoder = codec
+            .partial_decoder(
+                input_handle,
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        let decoded_partial_chunk = partial_decoder
+            .partial_decode(&decoded_regions, &CodecOptions::default())
+            .unwrap();
+        for (decoded_region, decoded_partial_chunk) in
+            decoded_regions.iter().zip(decoded_partial_chunk)
+        {
+            assert_eq!(
+                decoded
+                    .extract_array_subset(
+                        decoded_region,
+                        &chunk_representation.shape_u64(),
+                        chunk_representation.data_type()
+                    )
+                    .unwrap(),
+                decoded_partial_chunk
+            );
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn codec_sz3_async_partial_decode() {
+        let chunk_representation = chunk_representation(DataType::Float64, FillValue::from(0f64));
+        let elements: Vec<f64> = (0..chunk_representation.num_elements())
+            .map(|i| i as f64)
+            .collect();
+        let bytes = f64::into_array_bytes(&DataType::Float64, &elements)
+            .unwrap()
+            .into_owned();
+
+        let codec = Arc::new(Sz3Codec::new_with_configuration(
+            &serde_json::from_str(JSON_ABSOLUTE).unwrap(),
+        ));
+        let encoded = codec
+            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let decoded = codec
+            .decode(
+                encoded.clone(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+
+        let decoded_regions = [ArraySubset::new_with_ranges(&[2..4, 1..3, 0..4])];
+        let input_handle = Arc::new(std::io::Cursor::new(encoded));
+        let partial_decoder = codec
+            .async_partial_decoder(
+                input_handle,
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .await
+            .unwrap();
+        let decoded_partial_chunk = partial_decoder
+            .partial_decode(&decoded_regions, &CodecOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            decoded
+                .extract_array_subset(
+                    &decoded_regions[0],
+                    &chunk_representation.shape_u64(),
+                    chunk_representation.data_type()
+                )
+                .unwrap(),
+            decoded_partial_chunk[0]
+        );
+    }
+}
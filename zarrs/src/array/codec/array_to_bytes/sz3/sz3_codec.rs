@@ -0,0 +1,171 @@
+use std::sync::Arc;
+
+use crate::{
+    array::{
+        codec::{
+            ArrayBytes, ArrayCodecTraits, ArrayPartialDecoderTraits, ArrayPartialEncoderDefault,
+            ArrayPartialEncoderTraits, ArrayToBytesCodecTraits, BytesPartialDecoderTraits,
+            BytesPartialEncoderTraits, CodecError, CodecOptions, CodecTraits, RawBytes,
+            RecommendedConcurrency,
+        },
+        ArrayMetadataOptions, BytesRepresentation, ChunkRepresentation, DataType,
+    },
+    config::global_config,
+    metadata::v3::MetadataV3,
+};
+
+#[cfg(feature = "async")]
+use crate::array::codec::{AsyncArrayPartialDecoderTraits, AsyncBytesPartialDecoderTraits};
+
+use super::{
+    sz3_decode, sz3_encode, sz3_partial_decoder, Sz3CodecConfiguration, Sz3CodecConfigurationV1,
+    Sz3ErrorBound, IDENTIFIER,
+};
+
+/// A `sz3` codec implementation.
+#[derive(Clone, Copy, Debug)]
+pub struct Sz3Codec {
+    error_bound: Sz3ErrorBound,
+}
+
+impl Sz3Codec {
+    /// Create a new `sz3` codec with an error bound.
+    #[must_use]
+    pub const fn new(error_bound: Sz3ErrorBound) -> Self {
+        Self { error_bound }
+    }
+
+    /// Create a new `sz3` codec from configuration.
+    #[must_use]
+    pub const fn new_with_configuration(configuration: &Sz3CodecConfiguration) -> Self {
+        let Sz3CodecConfiguration::V1(configuration) = configuration;
+        Self::new(configuration.error_bound)
+    }
+
+    /// Return the error bound.
+    #[must_use]
+    pub const fn error_bound(&self) -> &Sz3ErrorBound {
+        &self.error_bound
+    }
+}
+
+impl CodecTraits for Sz3Codec {
+    fn create_metadata_opt(&self, _options: &ArrayMetadataOptions) -> Option<MetadataV3> {
+        let configuration = Sz3CodecConfiguration::V1(Sz3CodecConfigurationV1 {
+            error_bound: self.error_bound,
+        });
+        Some(
+            MetadataV3::new_with_serializable_configuration(
+                global_config()
+                    .experimental_codec_names()
+                    .get(IDENTIFIER)
+                    .expect("experimental codec identifier in global map"),
+                &configuration,
+            )
+            .expect("sz3 configuration is valid json"),
+        )
+    }
+
+    fn partial_decoder_should_cache_input(&self) -> bool {
+        false
+    }
+
+    fn partial_decoder_decodes_all(&self) -> bool {
+        true
+    }
+}
+
+impl ArrayCodecTraits for Sz3Codec {
+    fn recommended_concurrency(
+        &self,
+        _decoded_representation: &ChunkRepresentation,
+    ) -> Result<RecommendedConcurrency, CodecError> {
+        // sz3 does not support parallel encode or decode
+        Ok(RecommendedConcurrency::new_maximum(1))
+    }
+}
+
+#[cfg_attr(feature = "async", async_trait::async_trait)]
+impl ArrayToBytesCodecTraits for Sz3Codec {
+    fn dynamic(self: Arc<Self>) -> Arc<dyn ArrayToBytesCodecTraits> {
+        self as Arc<dyn ArrayToBytesCodecTraits>
+    }
+
+    fn encode<'a>(
+        &self,
+        bytes: ArrayBytes<'a>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        let bytes = bytes.into_fixed()?;
+        Ok(sz3_encode(&bytes, decoded_representation, &self.error_bound)?.into())
+    }
+
+    fn decode<'a>(
+        &self,
+        bytes: RawBytes<'a>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<ArrayBytes<'a>, CodecError> {
+        Ok(ArrayBytes::from(sz3_decode(
+            &bytes,
+            decoded_representation,
+        )?))
+    }
+
+    fn partial_decoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn BytesPartialDecoderTraits>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn ArrayPartialDecoderTraits>, CodecError> {
+        Ok(Arc::new(sz3_partial_decoder::Sz3PartialDecoder::new(
+            input_handle,
+            decoded_representation.clone(),
+        )?))
+    }
+
+    fn partial_encoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn BytesPartialDecoderTraits>,
+        output_handle: Arc<dyn BytesPartialEncoderTraits>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn ArrayPartialEncoderTraits>, CodecError> {
+        Ok(Arc::new(ArrayPartialEncoderDefault::new(
+            input_handle,
+            output_handle,
+            decoded_representation.clone(),
+            self,
+        )))
+    }
+
+    #[cfg(feature = "async")]
+    async fn async_partial_decoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn AsyncArrayPartialDecoderTraits>, CodecError> {
+        Ok(Arc::new(sz3_partial_decoder::AsyncSz3PartialDecoder::new(
+            input_handle,
+            decoded_representation.clone(),
+        )?))
+    }
+
+    fn compute_encoded_size(
+        &self,
+        decoded_representation: &ChunkRepresentation,
+    ) -> Result<BytesRepresentation, CodecError> {
+        let data_type = decoded_representation.data_type();
+        match data_type {
+            DataType::Int32 | DataType::Int64 | DataType::Float32 | DataType::Float64 => {
+                Ok(BytesRepresentation::UnboundedSize)
+            }
+            _ => Err(CodecError::UnsupportedDataType(
+                data_type.clone(),
+                IDENTIFIER.to_string(),
+            )),
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use std::sync::Arc;
+
+use crate::{
+    array::{
+        codec::{
+            ArrayBytes, ArrayPartialDecoderTraits, BytesPartialDecoderTraits, CodecError,
+            CodecOptions, RawBytes,
+        },
+        ArraySize, ChunkRepresentation, DataType,
+    },
+    array_subset::ArraySubset,
+};
+
+#[cfg(feature = "async")]
+use crate::array::codec::{AsyncArrayPartialDecoderTraits, AsyncBytesPartialDecoderTraits};
+
+use super::{sz3_decode, IDENTIFIER};
+
+fn validate_data_type(decoded_representation: &ChunkRepresentation) -> Result<(), CodecError> {
+    match decoded_representation.data_type() {
+        DataType::Int32 | DataType::Int64 | DataType::Float32 | DataType::Float64 => Ok(()),
+        data_type => Err(CodecError::UnsupportedDataType(
+            data_type.clone(),
+            IDENTIFIER.to_string(),
+        )),
+    }
+}
+
+fn do_partial_decode(
+    encoded_value: Option<RawBytes<'_>>,
+    decoded_regions: &[ArraySubset],
+    decoded_representation: &ChunkRepresentation,
+) -> Result<Vec<ArrayBytes<'static>>, CodecError> {
+    for array_subset in decoded_regions {
+        if array_subset.dimensionality() != decoded_representation.dimensionality() {
+            return Err(CodecError::InvalidArraySubsetDimensionalityError(
+                array_subset.clone(),
+                decoded_representation.dimensionality(),
+            ));
+        }
+    }
+
+    match encoded_value {
+        Some(encoded_value) => {
+            let decoded_value: ArrayBytes =
+                sz3_decode(&encoded_value, decoded_representation)?.into();
+            let chunk_shape = decoded_representation.shape_u64();
+            decoded_regions
+                .iter()
+                .map(|array_subset| {
+                    Ok(decoded_value
+                        .extract_array_subset(
+                            array_subset,
+                            &chunk_shape,
+                            decoded_representation.data_type(),
+                        )?
+                        .into_owned())
+                })
+                .collect()
+        }
+        None => Ok(decoded_regions
+            .iter()
+            .map(|array_subset| {
+                let array_size = ArraySize::new(
+                    decoded_representation.data_type().size(),
+                    array_subset.num_elements(),
+                );
+                ArrayBytes::new_fill_value(array_size, decoded_representation.fill_value())
+            })
+            .collect()),
+    }
+}
+
+/// Partial decoder for the `sz3` codec.
+pub(crate) struct Sz3PartialDecoder<'a> {
+    input_handle: Arc<dyn BytesPartialDecoderTraits + 'a>,
+    decoded_representation: ChunkRepresentation,
+}
+
+impl<'a> Sz3PartialDecoder<'a> {
+    /// Create a new partial decoder for the `sz3` codec.
+    pub(crate) fn new(
+        input_handle: Arc<dyn BytesPartialDecoderTraits + 'a>,
+        decoded_representation: ChunkRepresentation,
+    ) -> Result<Self, CodecError> {
+        validate_data_type(&decoded_representation)?;
+        Ok(Self {
+            input_handle,
+            decoded_representation,
+        })
+    }
+}
+
+impl ArrayPartialDecoderTraits for Sz3PartialDecoder<'_> {
+    fn data_type(&self) -> &DataType {
+        self.decoded_representation.data_type()
+    }
+
+    fn partial_decode(
+        &self,
+        decoded_regions: &[ArraySubset],
+        options: &CodecOptions,
+    ) -> Result<Vec<ArrayBytes<'_>>, CodecError> {
+        let encoded_value = self.input_handle.decode(options)?;
+        do_partial_decode(encoded_value, decoded_regions, &self.decoded_representation)
+    }
+}
+
+#[cfg(feature = "async")]
+/// Asynchronous partial decoder for the `sz3` codec.
+pub(crate) struct AsyncSz3PartialDecoder {
+    input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+    decoded_representation: ChunkRepresentation,
+}
+
+#[cfg(feature = "async")]
+impl AsyncSz3PartialDecoder {
+    /// Create a new partial decoder for the `sz3` codec.
+    pub(crate) fn new(
+        input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+        decoded_representation: ChunkRepresentation,
+    ) -> Result<Self, CodecError> {
+        validate_data_type(&decoded_representation)?;
+        Ok(Self {
+            input_handle,
+            decoded_representation,
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl AsyncArrayPartialDecoderTraits for AsyncSz3PartialDecoder {
+    fn data_type(&self) -> &DataType {
+        self.decoded_representation.data_type()
+    }
+
+    async fn partial_decode(
+        &self,
+        decoded_regions: &[ArraySubset],
+        options: &CodecOptions,
+    ) -> Result<Vec<ArrayBytes<'_>>, CodecError> {
+        let encoded_value = self.input_handle.decode(options).await?;
+        do_partial_decode(encoded_value, decoded_regions, &self.decoded_representation)
+    }
+}
@@ -3,6 +3,10 @@
 //! [zfp](https://zfp.io/) is a compressed number format for 1D to 4D arrays of 32/64-bit floating point or integer data.
 //! 8/16-bit integer types are supported through promotion to 32-bit in accordance with the [zfp utility functions](https://zfp.readthedocs.io/en/release1.0.1/low-level-api.html#utility-functions).
 //!
+//! In fixed rate mode, every block of 4^d elements is encoded with the same number of bits.
+//! Partial decoding of a fixed rate chunk only retrieves and decodes the blocks overlapping the requested regions.
+//! Partial decoding in other modes decodes the entire chunk.
+//!
 //! <div class="warning">
 //! This codec is experimental and may be incompatible with other Zarr V3 implementations.
 //! </div>
@@ -15,6 +19,7 @@ mod zfp_array;
 mod zfp_bitstream;
 mod zfp_codec;
 mod zfp_field;
+mod zfp_fixed_rate;
 mod zfp_partial_decoder;
 mod zfp_stream;
 
@@ -159,10 +164,10 @@ fn promote_before_zfp_encoding(
 }
 
 fn init_zfp_decoding_output(
-    decoded_representation: &ChunkRepresentation,
+    data_type: &DataType,
+    num_elements: usize,
 ) -> Result<ZfpArray, CodecError> {
-    let num_elements = decoded_representation.num_elements_usize();
-    match decoded_representation.data_type() {
+    match data_type {
         DataType::Int8
         | DataType::UInt8
         | DataType::Int16
@@ -173,18 +178,15 @@ fn init_zfp_decoding_output(
         DataType::Float32 => Ok(ZfpArray::Float(vec![0.0; num_elements])),
         DataType::Float64 => Ok(ZfpArray::Double(vec![0.0; num_elements])),
         _ => Err(CodecError::UnsupportedDataType(
-            decoded_representation.data_type().clone(),
+            data_type.clone(),
             IDENTIFIER.to_string(),
         )),
     }
 }
 
-fn demote_after_zfp_decoding(
-    array: ZfpArray,
-    decoded_representation: &ChunkRepresentation,
-) -> Result<Vec<u8>, CodecError> {
+fn demote_after_zfp_decoding(array: ZfpArray, data_type: &DataType) -> Result<Vec<u8>, CodecError> {
     #[allow(clippy::cast_sign_loss)]
-    match (decoded_representation.data_type(), array) {
+    match (data_type, array) {
         (DataType::Int32, ZfpArray::Int32(vec)) => Ok(transmute_to_bytes_vec(vec)),
         (DataType::UInt32, ZfpArray::Int32(vec)) => {
             let vec = vec
@@ -224,7 +226,7 @@ fn demote_after_zfp_decoding(
                 .collect(),
         )),
         _ => Err(CodecError::UnsupportedDataType(
-            decoded_representation.data_type().clone(),
+            data_type.clone(),
             IDENTIFIER.to_string(),
         )),
     }
@@ -237,7 +239,10 @@ fn zfp_decode(
     decoded_representation: &ChunkRepresentation,
     parallel: bool,
 ) -> Result<Vec<u8>, CodecError> {
-    let mut array = init_zfp_decoding_output(decoded_representation)?;
+    let mut array = init_zfp_decoding_output(
+        decoded_representation.data_type(),
+        decoded_representation.num_elements_usize(),
+    )?;
     let zfp_type = array.zfp_type();
     let Some(stream) = ZfpStream::new(zfp_mode, zfp_type) else {
         return Err(CodecError::from("failed to create zfp stream"));
@@ -285,7 +290,7 @@ fn zfp_decode(
         }
     }
 
-    demote_after_zfp_decoding(array, decoded_representation)
+    demote_after_zfp_decoding(array, decoded_representation.data_type())
 }
 
 #[cfg(test)]
@@ -295,7 +300,7 @@ mod tests {
 
     use crate::{
         array::{
-            codec::{ArrayToBytesCodecTraits, CodecOptions},
+            codec::{ArrayToBytesCodecTraits, CodecOptions, CodecTraits},
             element::ElementOwned,
             ArrayBytes,
         },
@@ -563,6 +568,64 @@ mod tests {
         assert_eq!(answer, decoded_partial_chunk);
     }
 
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn codec_zfp_partial_decode_fixed_rate() {
+        let chunk_shape = vec![NonZeroU64::new(7).unwrap(), NonZeroU64::new(9).unwrap()];
+        let chunk_representation =
+            ChunkRepresentation::new(chunk_shape, DataType::Float64, 0.0f64.into()).unwrap();
+        let elements: Vec<f64> = (0..63).map(|i| f64::from(i).sin()).collect();
+        let bytes: ArrayBytes = crate::array::transmute_to_bytes_vec(elements).into();
+
+        for write_header in [true, false] {
+            let codec = Arc::new(ZfpCodec::new_fixed_rate(8.0, write_header));
+            assert!(!codec.partial_decoder_decodes_all());
+            let encoded = codec
+                .encode(
+                    bytes.clone(),
+                    &chunk_representation,
+                    &CodecOptions::default(),
+                )
+                .unwrap();
+            let decoded = codec
+                .decode(
+                    encoded.clone(),
+                    &chunk_representation,
+                    &CodecOptions::default(),
+                )
+                .unwrap();
+
+            let decoded_regions = [
+                ArraySubset::new_with_ranges(&[0..7, 0..9]),
+                ArraySubset::new_with_ranges(&[5..7, 3..8]),
+                ArraySubset::new_with_ranges(&[3..4, 0..0]),
+                ArraySubset::new_with_ranges(&[1..6, 8..9]),
+            ];
+            let input_handle = Arc::new(std::io::Cursor::new(encoded));
+            let partial_decoder = codec
+                .partial_decoder(
+                    input_handle,
+                    &chunk_representation,
+                    &CodecOptions::default(),
+                )
+                .unwrap();
+            let decoded_partial_chunk = partial_decoder
+                .partial_decode(&decoded_regions, &CodecOptions::default())
+                .unwrap();
+            for (decoded_region, decoded_partial) in
+                std::iter::zip(&decoded_regions, decoded_partial_chunk)
+            {
+                let expected = decoded
+                    .extract_array_subset(decoded_region, &[7, 9], &DataType::Float64)
+                    .unwrap();
+                assert_eq!(
+                    expected.into_fixed().unwrap(),
+                    decoded_partial.into_fixed().unwrap()
+                );
+            }
+        }
+    }
+
     #[cfg(feature = "async")]
     #[tokio::test]
     #[cfg_attr(miri, ignore)]
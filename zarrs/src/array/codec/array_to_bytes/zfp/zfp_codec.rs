@@ -144,7 +144,8 @@ impl CodecTraits for ZfpCodec {
     }
 
     fn partial_decoder_decodes_all(&self) -> bool {
-        true
+        // Fixed rate streams are partially decoded at the block granularity
+        !matches!(self.mode, ZfpMode::FixedRate { .. })
     }
 }
 
@@ -0,0 +1,324 @@
+use std::collections::HashMap;
+
+use zfp_sys::{
+    stream_rseek, zfp_decode_block_double_1, zfp_decode_block_double_2, zfp_decode_block_double_3,
+    zfp_decode_block_double_4, zfp_decode_block_float_1, zfp_decode_block_float_2,
+    zfp_decode_block_float_3, zfp_decode_block_float_4, zfp_decode_block_int32_1,
+    zfp_decode_block_int32_2, zfp_decode_block_int32_3, zfp_decode_block_int32_4,
+    zfp_decode_block_int64_1, zfp_decode_block_int64_2, zfp_decode_block_int64_3,
+    zfp_decode_block_int64_4, zfp_stream_params, zfp_stream_rewind, zfp_stream_set_bit_stream,
+    zfp_type, zfp_write_header,
+};
+
+use crate::{
+    array::{
+        codec::{ArrayBytes, CodecError},
+        ravel_indices, ArrayIndices, ArrayShape, ChunkRepresentation, DataType,
+    },
+    array_subset::{ArraySubset, IncompatibleArraySubsetAndShapeError},
+    byte_range::ByteRange,
+};
+
+use super::{
+    demote_after_zfp_decoding, init_zfp_decoding_output, zarr_to_zfp_data_type,
+    zfp_bitstream::ZfpBitstream, zfp_field::ZfpField, zfp_stream::ZfpStream, ZfpArray, ZfpMode,
+};
+
+/// The length of a `zfp` block along each dimension.
+const BLOCK_LENGTH: u64 = 4;
+
+/// The layout of the blocks of a `zfp` fixed rate stream.
+///
+/// In fixed rate mode, every block of 4^d elements is encoded with the same number of bits, so the blocks overlapping a subset can be located and decoded without decoding the rest of the stream.
+pub(super) struct ZfpFixedRateBlocks {
+    mode: ZfpMode,
+    data_type: DataType,
+    zfp_type: zfp_type,
+    chunk_shape: ArrayShape,
+    grid_shape: ArrayShape,
+    header_bits: u64,
+    block_bits: u64,
+}
+
+impl ZfpFixedRateBlocks {
+    /// Create the block layout of a `zfp` stream.
+    ///
+    /// Returns [`None`] if the blocks of the stream cannot be located, such as if `mode` is not fixed rate.
+    pub(super) fn new(
+        mode: &ZfpMode,
+        write_header: bool,
+        decoded_representation: &ChunkRepresentation,
+    ) -> Option<Self> {
+        // Bit offsets map to byte offsets only if the words of the stream are little endian
+        if !matches!(mode, ZfpMode::FixedRate { .. }) || cfg!(target_endian = "big") {
+            return None;
+        }
+        let chunk_shape = decoded_representation.shape_u64();
+        if !(1..=4).contains(&chunk_shape.len()) {
+            return None;
+        }
+        let zfp_type = zarr_to_zfp_data_type(decoded_representation.data_type())?;
+        let stream = ZfpStream::new(mode, zfp_type)?;
+
+        let (mut minbits, mut maxbits, mut maxprec, mut minexp) = (0, 0, 0, 0);
+        unsafe {
+            zfp_stream_params(
+                stream.as_zfp_stream(),
+                &mut minbits,
+                &mut maxbits,
+                &mut maxprec,
+                &mut minexp,
+            );
+        }
+        if minbits != maxbits || maxbits == 0 {
+            return None;
+        }
+
+        let header_bits = if write_header {
+            let chunk_shape_usize = decoded_representation
+                .shape()
+                .iter()
+                .map(|u| usize::try_from(u.get()).unwrap())
+                .collect::<Vec<usize>>();
+            // SAFETY: zfp_write_header does not use the data in the field, so it can be empty
+            let field = unsafe { ZfpField::new_empty(zfp_type, &chunk_shape_usize) }?;
+            // The header is at most ZFP_HEADER_MAX_BITS (148) bits
+            let mut buffer = [0u64; 4];
+            let bitstream = ZfpBitstream::new(bytemuck::cast_slice_mut(&mut buffer))?;
+            let header_bits = unsafe {
+                zfp_stream_set_bit_stream(stream.as_zfp_stream(), bitstream.as_bitstream());
+                zfp_stream_rewind(stream.as_zfp_stream());
+                zfp_write_header(
+                    stream.as_zfp_stream(),
+                    field.as_zfp_field(),
+                    zfp_sys::ZFP_HEADER_FULL,
+                )
+            };
+            if header_bits == 0 {
+                return None;
+            }
+            u64::try_from(header_bits).ok()?
+        } else {
+            0
+        };
+
+        let grid_shape = chunk_shape
+            .iter()
+            .map(|&shape| shape.div_ceil(BLOCK_LENGTH))
+            .collect();
+        Some(Self {
+            mode: *mode,
+            data_type: decoded_representation.data_type().clone(),
+            zfp_type,
+            chunk_shape,
+            grid_shape,
+            header_bits,
+            block_bits: u64::from(maxbits),
+        })
+    }
+
+    /// Return the linearised indices of the blocks overlapping `array_subsets`.
+    ///
+    /// # Errors
+    /// Returns a [`CodecError`] if a subset is out of bounds of the chunk.
+    pub(super) fn blocks(&self, array_subsets: &[ArraySubset]) -> Result<Vec<u64>, CodecError> {
+        let mut blocks = Vec::new();
+        for array_subset in array_subsets {
+            if !array_subset.inbounds(&self.chunk_shape) {
+                return Err(IncompatibleArraySubsetAndShapeError::new(
+                    array_subset.clone(),
+                    self.chunk_shape.clone(),
+                )
+                .into());
+            }
+            if array_subset.is_empty() {
+                continue;
+            }
+            for block_indices in &block_grid_subset(array_subset).indices() {
+                blocks.push(ravel_indices(&block_indices, &self.grid_shape));
+            }
+        }
+        blocks.sort_unstable();
+        blocks.dedup();
+        Ok(blocks)
+    }
+
+    /// Return the byte range of the stream containing `block`, and the bit offset of the block in that byte range.
+    pub(super) fn block_byte_range(&self, block: u64) -> (ByteRange, u64) {
+        let start_bit = self.header_bits + block * self.block_bits;
+        let end_bit = start_bit + self.block_bits;
+        let start_byte = start_bit / 8;
+        let end_byte = end_bit.div_ceil(8);
+        (
+            ByteRange::FromStart(start_byte, Some(end_byte - start_byte)),
+            start_bit - start_byte * 8,
+        )
+    }
+
+    /// Decode a block from `bytes`, starting `bit_offset` bits into `bytes`.
+    fn decode_block(&self, bytes: &[u8], bit_offset: u64) -> Result<ZfpArray, CodecError> {
+        let dimensionality = self.chunk_shape.len();
+        let block_elements = 1 << (2 * dimensionality);
+        let mut block = match &self.data_type {
+            DataType::Int64 | DataType::UInt64 => ZfpArray::Int64(vec![0; block_elements]),
+            DataType::Float32 => ZfpArray::Float(vec![0.0; block_elements]),
+            DataType::Float64 => ZfpArray::Double(vec![0.0; block_elements]),
+            _ => ZfpArray::Int32(vec![0; block_elements]),
+        };
+
+        // The stream is read in words, so copy the bytes to an aligned buffer with a trailing word of padding
+        let mut buffer = vec![0u64; bytes.len().div_ceil(8) + 1];
+        bytemuck::cast_slice_mut::<u64, u8>(&mut buffer)[..bytes.len()].copy_from_slice(bytes);
+        let Some(stream) = ZfpStream::new(&self.mode, self.zfp_type) else {
+            return Err(CodecError::from("failed to create zfp stream"));
+        };
+        let Some(bitstream) = ZfpBitstream::new(bytemuck::cast_slice_mut(&mut buffer)) else {
+            return Err(CodecError::from("failed to create zfp bitstream"));
+        };
+
+        let bits = unsafe {
+            zfp_stream_set_bit_stream(stream.as_zfp_stream(), bitstream.as_bitstream());
+            stream_rseek(bitstream.as_bitstream(), bit_offset);
+            let stream = stream.as_zfp_stream();
+            match (&mut block, dimensionality) {
+                (ZfpArray::Int32(block), 1) => zfp_decode_block_int32_1(stream, block.as_mut_ptr()),
+                (ZfpArray::Int32(block), 2) => zfp_decode_block_int32_2(stream, block.as_mut_ptr()),
+                (ZfpArray::Int32(block), 3) => zfp_decode_block_int32_3(stream, block.as_mut_ptr()),
+                (ZfpArray::Int32(block), _) => zfp_decode_block_int32_4(stream, block.as_mut_ptr()),
+                (ZfpArray::Int64(block), 1) => zfp_decode_block_int64_1(stream, block.as_mut_ptr()),
+                (ZfpArray::Int64(block), 2) => zfp_decode_block_int64_2(stream, block.as_mut_ptr()),
+                (ZfpArray::Int64(block), 3) => zfp_decode_block_int64_3(stream, block.as_mut_ptr()),
+                (ZfpArray::Int64(block), _) => zfp_decode_block_int64_4(stream, block.as_mut_ptr()),
+                (ZfpArray::Float(block), 1) => zfp_decode_block_float_1(stream, block.as_mut_ptr()),
+                (ZfpArray::Float(block), 2) => zfp_decode_block_float_2(stream, block.as_mut_ptr()),
+                (ZfpArray::Float(block), 3) => zfp_decode_block_float_3(stream, block.as_mut_ptr()),
+                (ZfpArray::Float(block), _) => zfp_decode_block_float_4(stream, block.as_mut_ptr()),
+                (ZfpArray::Double(block), 1) => {
+                    zfp_decode_block_double_1(stream, block.as_mut_ptr())
+                }
+                (ZfpArray::Double(block), 2) => {
+                    zfp_decode_block_double_2(stream, block.as_mut_ptr())
+                }
+                (ZfpArray::Double(block), 3) => {
+                    zfp_decode_block_double_3(stream, block.as_mut_ptr())
+                }
+                (ZfpArray::Double(block), _) => {
+                    zfp_decode_block_double_4(stream, block.as_mut_ptr())
+                }
+            }
+        };
+        if bits == 0 {
+            return Err(CodecError::from("zfp block decompression failed"));
+        }
+        Ok(block)
+    }
+
+    /// Decode `array_subsets` from the encoded `blocks` and their `encoded_blocks`.
+    ///
+    /// `blocks` and `encoded_blocks` must correspond to the blocks and byte ranges returned by [`blocks`](Self::blocks) and [`block_byte_range`](Self::block_byte_range).
+    ///
+    /// # Errors
+    /// Returns a [`CodecError`] if a block cannot be decoded.
+    pub(super) fn decode(
+        &self,
+        array_subsets: &[ArraySubset],
+        blocks: &[u64],
+        encoded_blocks: &[impl AsRef<[u8]>],
+    ) -> Result<Vec<ArrayBytes<'static>>, CodecError> {
+        let mut decoded_blocks = HashMap::with_capacity(blocks.len());
+        for (&block, encoded_block) in std::iter::zip(blocks, encoded_blocks) {
+            let (_, bit_offset) = self.block_byte_range(block);
+            decoded_blocks.insert(
+                block,
+                self.decode_block(encoded_block.as_ref(), bit_offset)?,
+            );
+        }
+
+        let block_shape = vec![BLOCK_LENGTH; self.chunk_shape.len()];
+        let mut out = Vec::with_capacity(array_subsets.len());
+        for array_subset in array_subsets {
+            let mut decoded =
+                init_zfp_decoding_output(&self.data_type, array_subset.num_elements_usize())?;
+            if !array_subset.is_empty() {
+                for block_indices in &block_grid_subset(array_subset).indices() {
+                    let block = &decoded_blocks[&ravel_indices(&block_indices, &self.grid_shape)];
+                    let block_start: ArrayIndices =
+                        block_indices.iter().map(|i| i * BLOCK_LENGTH).collect();
+                    let block_subset = unsafe {
+                        // SAFETY: the block start and shape have the dimensionality of the subset
+                        ArraySubset::new_with_start_shape_unchecked(
+                            block_start.clone(),
+                            block_shape.clone(),
+                        )
+                    };
+                    let overlap = unsafe { array_subset.overlap_unchecked(&block_subset) };
+                    copy_overlap(
+                        block,
+                        &block_start,
+                        &block_shape,
+                        &mut decoded,
+                        array_subset,
+                        &overlap,
+                    );
+                }
+            }
+            out.push(ArrayBytes::from(demote_after_zfp_decoding(
+                decoded,
+                &self.data_type,
+            )?));
+        }
+        Ok(out)
+    }
+}
+
+/// Return the subset of the block grid overlapping `array_subset`.
+fn block_grid_subset(array_subset: &ArraySubset) -> ArraySubset {
+    let ranges: Vec<_> = std::iter::zip(array_subset.start(), array_subset.end_exc())
+        .map(|(&start, end)| start / BLOCK_LENGTH..end.div_ceil(BLOCK_LENGTH))
+        .collect();
+    ArraySubset::new_with_ranges(&ranges)
+}
+
+/// Copy the `overlap` of a decoded `block` and `array_subset` into `decoded`, the elements of `array_subset`.
+fn copy_overlap(
+    block: &ZfpArray,
+    block_start: &[u64],
+    block_shape: &[u64],
+    decoded: &mut ZfpArray,
+    array_subset: &ArraySubset,
+    overlap: &ArraySubset,
+) {
+    let dimensionality = overlap.dimensionality();
+    let row_length = usize::try_from(overlap.shape()[dimensionality - 1]).unwrap();
+    let mut rows_shape = overlap.shape().to_vec();
+    rows_shape[dimensionality - 1] = 1;
+    let rows = unsafe {
+        // SAFETY: the rows shape has the dimensionality of the overlap
+        ArraySubset::new_with_start_shape_unchecked(overlap.start().to_vec(), rows_shape)
+    };
+    for row_start in &rows.indices() {
+        let src_indices: Vec<u64> = std::iter::zip(&row_start, block_start)
+            .map(|(i, start)| i - start)
+            .collect();
+        let dst_indices: Vec<u64> = std::iter::zip(&row_start, array_subset.start())
+            .map(|(i, start)| i - start)
+            .collect();
+        let src = usize::try_from(ravel_indices(&src_indices, block_shape)).unwrap();
+        let dst = usize::try_from(ravel_indices(&dst_indices, array_subset.shape())).unwrap();
+        match (block, &mut *decoded) {
+            (ZfpArray::Int32(block), ZfpArray::Int32(decoded)) => {
+                decoded[dst..dst + row_length].copy_from_slice(&block[src..src + row_length]);
+            }
+            (ZfpArray::Int64(block), ZfpArray::Int64(decoded)) => {
+                decoded[dst..dst + row_length].copy_from_slice(&block[src..src + row_length]);
+            }
+            (ZfpArray::Float(block), ZfpArray::Float(decoded)) => {
+                decoded[dst..dst + row_length].copy_from_slice(&block[src..src + row_length]);
+            }
+            (ZfpArray::Double(block), ZfpArray::Double(decoded)) => {
+                decoded[dst..dst + row_length].copy_from_slice(&block[src..src + row_length]);
+            }
+            _ => unreachable!("decoded blocks and subsets have the same zfp type"),
+        }
+    }
+}
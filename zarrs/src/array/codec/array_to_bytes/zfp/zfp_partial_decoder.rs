@@ -15,7 +15,23 @@ use crate::{
 #[cfg(feature = "async")]
 use crate::array::codec::{AsyncArrayPartialDecoderTraits, AsyncBytesPartialDecoderTraits};
 
-use super::{zarr_to_zfp_data_type, zfp_decode, ZfpMode};
+use super::{zarr_to_zfp_data_type, zfp_decode, zfp_fixed_rate::ZfpFixedRateBlocks, ZfpMode};
+
+fn fill_value_regions(
+    decoded_regions: &[ArraySubset],
+    decoded_representation: &ChunkRepresentation,
+) -> Vec<ArrayBytes<'static>> {
+    decoded_regions
+        .iter()
+        .map(|decoded_region| {
+            let array_size = ArraySize::new(
+                decoded_representation.data_type().size(),
+                decoded_region.num_elements(),
+            );
+            ArrayBytes::new_fill_value(array_size, decoded_representation.fill_value())
+        })
+        .collect()
+}
 
 /// Partial decoder for the `zfp` codec.
 pub(crate) struct ZfpPartialDecoder<'a> {
@@ -23,6 +39,7 @@ pub(crate) struct ZfpPartialDecoder<'a> {
     decoded_representation: ChunkRepresentation,
     mode: ZfpMode,
     write_header: bool,
+    fixed_rate_blocks: Option<ZfpFixedRateBlocks>,
 }
 
 impl<'a> ZfpPartialDecoder<'a> {
@@ -39,6 +56,11 @@ impl<'a> ZfpPartialDecoder<'a> {
                 decoded_representation: decoded_representation.clone(),
                 mode,
                 write_header,
+                fixed_rate_blocks: ZfpFixedRateBlocks::new(
+                    &mode,
+                    write_header,
+                    decoded_representation,
+                ),
             })
         } else {
             Err(CodecError::from(
@@ -70,6 +92,22 @@ impl ArrayPartialDecoderTraits for ZfpPartialDecoder<'_> {
             }
         }
 
+        if let Some(fixed_rate_blocks) = &self.fixed_rate_blocks {
+            // Only decode the blocks overlapping the decoded regions
+            let blocks = fixed_rate_blocks.blocks(decoded_regions)?;
+            let byte_ranges: Vec<_> = blocks
+                .iter()
+                .map(|&block| fixed_rate_blocks.block_byte_range(block).0)
+                .collect();
+            if let Some(encoded_blocks) = self.input_handle.partial_decode(&byte_ranges, options)? {
+                return fixed_rate_blocks.decode(decoded_regions, &blocks, &encoded_blocks);
+            }
+            return Ok(fill_value_regions(
+                decoded_regions,
+                &self.decoded_representation,
+            ));
+        }
+
         let encoded_value = self.input_handle.decode(options)?;
         let mut out = Vec::with_capacity(decoded_regions.len());
         let chunk_shape = self.decoded_representation.shape_u64();
@@ -92,17 +130,7 @@ impl ArrayPartialDecoderTraits for ZfpPartialDecoder<'_> {
                 }
             }
             None => {
-                for decoded_region in decoded_regions {
-                    let array_size = ArraySize::new(
-                        self.decoded_representation.data_type().size(),
-                        decoded_region.num_elements(),
-                    );
-                    let fill_value = ArrayBytes::new_fill_value(
-                        array_size,
-                        self.decoded_representation.fill_value(),
-                    );
-                    out.push(fill_value);
-                }
+                out = fill_value_regions(decoded_regions, &self.decoded_representation);
             }
         }
         Ok(out)
@@ -116,6 +144,7 @@ pub(crate) struct AsyncZfpPartialDecoder {
     decoded_representation: ChunkRepresentation,
     mode: ZfpMode,
     write_header: bool,
+    fixed_rate_blocks: Option<ZfpFixedRateBlocks>,
 }
 
 #[cfg(feature = "async")]
@@ -133,6 +162,11 @@ impl AsyncZfpPartialDecoder {
                 decoded_representation: decoded_representation.clone(),
                 mode,
                 write_header,
+                fixed_rate_blocks: ZfpFixedRateBlocks::new(
+                    &mode,
+                    write_header,
+                    decoded_representation,
+                ),
             })
         } else {
             Err(CodecError::from(
@@ -166,6 +200,26 @@ impl AsyncArrayPartialDecoderTraits for AsyncZfpPartialDecoder {
             }
         }
 
+        if let Some(fixed_rate_blocks) = &self.fixed_rate_blocks {
+            // Only decode the blocks overlapping the decoded regions
+            let blocks = fixed_rate_blocks.blocks(decoded_regions)?;
+            let byte_ranges: Vec<_> = blocks
+                .iter()
+                .map(|&block| fixed_rate_blocks.block_byte_range(block).0)
+                .collect();
+            if let Some(encoded_blocks) = self
+                .input_handle
+                .partial_decode(&byte_ranges, options)
+                .await?
+            {
+                return fixed_rate_blocks.decode(decoded_regions, &blocks, &encoded_blocks);
+            }
+            return Ok(fill_value_regions(
+                decoded_regions,
+                &self.decoded_representation,
+            ));
+        }
+
         let encoded_value = self.input_handle.decode(options).await?;
         let chunk_shape = self.decoded_representation.shape_u64();
         let mut out = Vec::with_capacity(decoded_regions.len());
@@ -188,17 +242,7 @@ impl AsyncArrayPartialDecoderTraits for AsyncZfpPartialDecoder {
                 }
             }
             None => {
-                for decoded_region in decoded_regions {
-                    let array_size = ArraySize::new(
-                        self.decoded_representation.data_type().size(),
-                        decoded_region.num_elements(),
-                    );
-                    let fill_value = ArrayBytes::new_fill_value(
-                        array_size,
-                        self.decoded_representation.fill_value(),
-                    );
-                    out.push(fill_value);
-                }
+                out = fill_value_regions(decoded_regions, &self.decoded_representation);
             }
         }
         Ok(out)
@@ -0,0 +1,245 @@
+//! Codec backends.
+//!
+//! [`CodecBackend`] is an extension point for offloading the encoding and decoding of supported codecs to an accelerator, such as a GPU with [nvCOMP](https://developer.nvidia.com/nvcomp).
+//! This is useful in pipelines where codec throughput is the bottleneck, like on GPU nodes.
+//!
+//! A backend is selected with [`CodecOptions::set_backend`] or [`CodecOptionsBuilder::backend`](crate::array::codec::CodecOptionsBuilder::backend).
+//! Codecs that support a backend consult it before their CPU implementation.
+//! The CPU implementation is used automatically if:
+//!  - no backend is selected, or
+//!  - the backend does not support the codec or its configuration (i.e. it returns [`None`]).
+//!
+//! A backend error (e.g. a corrupt input) is returned from the codec.
+//! A backend that should fall back to the CPU implementation in other circumstances (e.g. a GPU is not available or is out of memory) can return [`None`].
+//!
+//! The encoded output of a backend must be interchangeable with that of the CPU implementation of the codec.
+//!
+//! Only the `zstd` and `gdeflate` codecs consult the backend, when encoding and decoding a complete chunk.
+//! Partial decoding, streaming and partial encoding always use the CPU implementation.
+//! `LZ4` is only supported as a `blosc`/`blosc2` internal compressor, which does not consult the backend.
+//!
+//! `zarrs` does not include any backend implementations, GPU or otherwise.
+//! A backend (e.g. with nvCOMP bindings) must be implemented in a downstream crate.
+
+use std::borrow::Cow;
+
+use crate::{
+    array::{BytesRepresentation, RawBytes},
+    metadata::v3::MetadataV3,
+};
+
+use super::{CodecError, CodecOptions, CodecTraits};
+
+/// A codec backend.
+///
+/// See the [module documentation](self).
+pub trait CodecBackend: Send + Sync + core::fmt::Debug {
+    /// Return the name of the backend (e.g. `nvcomp`).
+    fn name(&self) -> &str;
+
+    /// Encode `decoded_value` with the codec described by `codec`.
+    ///
+    /// Return [`None`] if the backend does not support the codec or its configuration, or the CPU implementation should be used instead.
+    ///
+    /// # Errors
+    /// Returns a [`CodecError`] if the backend fails to encode.
+    fn encode(
+        &self,
+        codec: &MetadataV3,
+        decoded_value: &[u8],
+    ) -> Result<Option<Vec<u8>>, CodecError>;
+
+    /// Decode `encoded_value` with the codec described by `codec`.
+    ///
+    /// Return [`None`] if the backend does not support the codec or its configuration, or the CPU implementation should be used instead.
+    ///
+    /// # Errors
+    /// Returns a [`CodecError`] if the backend fails to decode.
+    fn decode(
+        &self,
+        codec: &MetadataV3,
+        encoded_value: &[u8],
+        decoded_representation: &BytesRepresentation,
+    ) -> Result<Option<Vec<u8>>, CodecError>;
+}
+
+/// Encode `decoded_value` with the backend in `options`.
+///
+/// Returns [`None`] if the CPU implementation of `codec` should be used instead.
+///
+/// # Errors
+/// Returns a [`CodecError`] if the backend fails to encode.
+pub(crate) fn backend_encode(
+    codec: &dyn CodecTraits,
+    decoded_value: &[u8],
+    options: &CodecOptions,
+) -> Result<Option<RawBytes<'static>>, CodecError> {
+    let (Some(backend), Some(metadata)) = (options.backend(), codec.create_metadata()) else {
+        return Ok(None);
+    };
+    Ok(backend.encode(&metadata, decoded_value)?.map(Cow::Owned))
+}
+
+/// Decode `encoded_value` with the backend in `options`.
+///
+/// Returns [`None`] if the CPU implementation of `codec` should be used instead.
+///
+/// # Errors
+/// Returns a [`CodecError`] if the backend fails to decode or the decoded value does not match `decoded_representation`.
+pub(crate) fn backend_decode(
+    codec: &dyn CodecTraits,
+    encoded_value: &[u8],
+    decoded_representation: &BytesRepresentation,
+    options: &CodecOptions,
+) -> Result<Option<RawBytes<'static>>, CodecError> {
+    let (Some(backend), Some(metadata)) = (options.backend(), codec.create_metadata()) else {
+        return Ok(None);
+    };
+    let Some(decoded_value) = backend.decode(&metadata, encoded_value, decoded_representation)?
+    else {
+        return Ok(None);
+    };
+    match decoded_representation.size() {
+        Some(size) if size != decoded_value.len() as u64 => Err(
+            CodecError::UnexpectedChunkDecodedSize(decoded_value.len(), size),
+        ),
+        _ => Ok(Some(Cow::Owned(decoded_value))),
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "zstd")]
+mod tests {
+    use std::{
+        borrow::Cow,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    use crate::array::codec::{BytesToBytesCodecTraits, ZstdCodec};
+
+    use super::*;
+
+    /// A backend that supports `zstd` on the CPU and counts its invocations.
+    ///
+    /// The backend fails if `fail` is set, or declines to encode and decode if `decline` is set.
+    #[derive(Debug, Default)]
+    struct CountingBackend {
+        fail: bool,
+        decline: bool,
+        encodes: AtomicUsize,
+        decodes: AtomicUsize,
+    }
+
+    impl CodecBackend for CountingBackend {
+        fn name(&self) -> &'static str {
+            "counting"
+        }
+
+        fn encode(
+            &self,
+            codec: &MetadataV3,
+            decoded_value: &[u8],
+        ) -> Result<Option<Vec<u8>>, CodecError> {
+            if codec.name() != "zstd" {
+                return Ok(None);
+            }
+            self.encodes.fetch_add(1, Ordering::Relaxed);
+            if self.fail {
+                return Err(CodecError::Other("backend failure".to_string()));
+            } else if self.decline {
+                return Ok(None);
+            }
+            Ok(Some(zstd::encode_all(decoded_value, 1)?))
+        }
+
+        fn decode(
+            &self,
+            codec: &MetadataV3,
+            encoded_value: &[u8],
+            _decoded_representation: &BytesRepresentation,
+        ) -> Result<Option<Vec<u8>>, CodecError> {
+            if codec.name() != "zstd" {
+                return Ok(None);
+            }
+            self.decodes.fetch_add(1, Ordering::Relaxed);
+            if self.fail {
+                return Err(CodecError::Other("backend failure".to_string()));
+            } else if self.decline {
+                return Ok(None);
+            }
+            Ok(Some(zstd::decode_all(encoded_value)?))
+        }
+    }
+
+    fn round_trip(backend: &Arc<CountingBackend>) {
+        let bytes: Vec<u8> = (0..255).collect();
+        let bytes_representation = BytesRepresentation::FixedSize(bytes.len() as u64);
+        let codec = ZstdCodec::new(5, false);
+        let options = CodecOptions::builder()
+            .backend(Some(backend.clone() as Arc<dyn CodecBackend>))
+            .build();
+        assert_eq!(options.backend().unwrap().name(), "counting");
+
+        let encoded = codec.encode(Cow::Borrowed(&bytes), &options).unwrap();
+        let decoded = codec
+            .decode(encoded.clone(), &bytes_representation, &options)
+            .unwrap();
+        assert_eq!(bytes, decoded.to_vec());
+
+        // Encoded values are interchangeable with the CPU implementation
+        let decoded = codec
+            .decode(encoded, &bytes_representation, &CodecOptions::default())
+            .unwrap();
+        assert_eq!(bytes, decoded.to_vec());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn codec_backend_zstd() {
+        let backend = Arc::new(CountingBackend::default());
+        round_trip(&backend);
+        assert_eq!(backend.encodes.load(Ordering::Relaxed), 1);
+        assert_eq!(backend.decodes.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn codec_backend_fallback() {
+        let backend = Arc::new(CountingBackend {
+            decline: true,
+            ..Default::default()
+        });
+        round_trip(&backend);
+        assert_eq!(backend.encodes.load(Ordering::Relaxed), 1);
+        assert_eq!(backend.decodes.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn codec_backend_error() {
+        let bytes: Vec<u8> = (0..255).collect();
+        let bytes_representation = BytesRepresentation::FixedSize(bytes.len() as u64);
+        let codec = ZstdCodec::new(5, false);
+        let backend = Arc::new(CountingBackend {
+            fail: true,
+            ..Default::default()
+        });
+        let options = CodecOptions::builder()
+            .backend(Some(backend.clone() as Arc<dyn CodecBackend>))
+            .build();
+
+        // Backend errors are not hidden by falling back to the CPU implementation
+        assert!(codec.encode(Cow::Borrowed(&bytes), &options).is_err());
+        let encoded = codec
+            .encode(Cow::Borrowed(&bytes), &CodecOptions::default())
+            .unwrap();
+        assert!(codec
+            .decode(encoded, &bytes_representation, &options)
+            .is_err());
+        assert_eq!(backend.encodes.load(Ordering::Relaxed), 1);
+        assert_eq!(backend.decodes.load(Ordering::Relaxed), 1);
+    }
+}
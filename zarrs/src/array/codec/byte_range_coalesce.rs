@@ -0,0 +1,139 @@
+use std::{borrow::Cow, ops::Range};
+
+use crate::{array::RawBytes, byte_range::ByteRange};
+
+/// Byte ranges coalesced into fewer byte ranges.
+///
+/// Bounded byte ranges separated by no more than a gap are merged into a single byte range.
+/// Byte ranges to the end of a value and suffix byte ranges are not coalesced.
+pub(crate) struct CoalescedByteRanges {
+    byte_ranges: Vec<ByteRange>,
+    /// The index of the coalesced byte range containing each byte range and its range within the coalesced bytes.
+    ///
+    /// The range is [`None`] if the byte range is not coalesced.
+    locations: Vec<(usize, Option<Range<usize>>)>,
+}
+
+impl CoalescedByteRanges {
+    /// Coalesce `byte_ranges` separated by no more than `gap` bytes.
+    pub(crate) fn new(byte_ranges: &[ByteRange], gap: u64) -> Self {
+        let mut coalesced = Vec::new();
+        let mut locations = vec![(0, None); byte_ranges.len()];
+
+        // Sort the bounded byte ranges by their start
+        let mut bounded = Vec::with_capacity(byte_ranges.len());
+        for (i, byte_range) in byte_ranges.iter().enumerate() {
+            if let ByteRange::FromStart(offset, Some(length)) = byte_range {
+                bounded.push((i, *offset, offset + length));
+            } else {
+                locations[i] = (coalesced.len(), None);
+                coalesced.push(*byte_range);
+            }
+        }
+        bounded.sort_by_key(|(_, start, _)| *start);
+
+        // Merge byte ranges separated by no more than gap bytes
+        // Each group is the start and end of a coalesced byte range and the indices of its members in `bounded`
+        let mut groups: Vec<(u64, u64, Vec<usize>)> = Vec::new();
+        for (member, &(_, start, end)) in bounded.iter().enumerate() {
+            match groups.last_mut() {
+                Some((_, group_end, members)) if start <= group_end.saturating_add(gap) => {
+                    *group_end = (*group_end).max(end);
+                    members.push(member);
+                }
+                _ => groups.push((start, end, vec![member])),
+            }
+        }
+        for (group_start, group_end, members) in groups {
+            for member in members {
+                let (i, start, end) = bounded[member];
+                locations[i] = (
+                    coalesced.len(),
+                    Some(
+                        usize::try_from(start - group_start).unwrap()
+                            ..usize::try_from(end - group_start).unwrap(),
+                    ),
+                );
+            }
+            coalesced.push(ByteRange::FromStart(
+                group_start,
+                Some(group_end - group_start),
+            ));
+        }
+
+        Self {
+            byte_ranges: coalesced,
+            locations,
+        }
+    }
+
+    /// Return the coalesced byte ranges.
+    pub(crate) fn byte_ranges(&self) -> &[ByteRange] {
+        &self.byte_ranges
+    }
+
+    /// Split the bytes of the coalesced byte ranges into the bytes of the original byte ranges.
+    pub(crate) fn split<'a>(&self, coalesced_bytes: &[impl AsRef<[u8]>]) -> Vec<RawBytes<'a>> {
+        self.locations
+            .iter()
+            .map(|(index, range)| {
+                let bytes = coalesced_bytes[*index].as_ref();
+                let bytes = range.as_ref().map_or(bytes, |range| &bytes[range.clone()]);
+                Cow::Owned(bytes.to_vec())
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coalesce_byte_ranges() {
+        let byte_ranges = [
+            ByteRange::FromStart(10, Some(2)),
+            ByteRange::FromStart(0, Some(4)),
+            ByteRange::Suffix(1),
+            ByteRange::FromStart(5, Some(2)),
+            ByteRange::FromStart(20, Some(1)),
+            ByteRange::FromStart(2, Some(1)),
+        ];
+        let coalesced = CoalescedByteRanges::new(&byte_ranges, 1);
+        assert_eq!(
+            coalesced.byte_ranges(),
+            &[
+                ByteRange::Suffix(1),
+                ByteRange::FromStart(0, Some(7)),
+                ByteRange::FromStart(10, Some(2)),
+                ByteRange::FromStart(20, Some(1)),
+            ]
+        );
+
+        let bytes: Vec<u8> = (0..21).collect();
+        let coalesced_bytes: Vec<Vec<u8>> = coalesced
+            .byte_ranges()
+            .iter()
+            .map(|byte_range| {
+                let start = usize::try_from(byte_range.start(21)).unwrap();
+                let end = usize::try_from(byte_range.end(21)).unwrap();
+                bytes[start..end].to_vec()
+            })
+            .collect();
+        let split = coalesced.split(&coalesced_bytes);
+        assert_eq!(
+            split,
+            vec![
+                vec![10, 11],
+                vec![0, 1, 2, 3],
+                vec![20],
+                vec![5, 6],
+                vec![20],
+                vec![2],
+            ]
+        );
+
+        let coalesced = CoalescedByteRanges::new(&byte_ranges, 0);
+        assert_eq!(coalesced.byte_ranges().len(), 5);
+    }
+}
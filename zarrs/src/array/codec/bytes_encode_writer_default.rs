@@ -0,0 +1,53 @@
+use std::{borrow::Cow, io::Write};
+
+use zarrs_storage::{StorageError, StoreValueWriter};
+
+use super::{BytesToBytesCodecTraits, CodecOptions};
+
+/// The default bytes to bytes codec encode writer. Buffers the entire value and encodes it when finished.
+pub(crate) struct BytesEncodeWriterDefault<'a, TCodec: ?Sized> {
+    codec: &'a TCodec,
+    encoded_value: Box<dyn StoreValueWriter + 'a>,
+    options: CodecOptions,
+    buffer: Vec<u8>,
+}
+
+impl<'a, TCodec: ?Sized> BytesEncodeWriterDefault<'a, TCodec> {
+    /// Create a new [`BytesEncodeWriterDefault`] encoding with `codec` to `encoded_value`.
+    pub(crate) fn new(
+        codec: &'a TCodec,
+        encoded_value: Box<dyn StoreValueWriter + 'a>,
+        options: &CodecOptions,
+    ) -> Self {
+        Self {
+            codec,
+            encoded_value,
+            options: options.clone(),
+            buffer: Vec::new(),
+        }
+    }
+}
+
+impl<TCodec: ?Sized> Write for BytesEncodeWriterDefault<'_, TCodec> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<TCodec: ?Sized + BytesToBytesCodecTraits> StoreValueWriter
+    for BytesEncodeWriterDefault<'_, TCodec>
+{
+    fn finish(mut self: Box<Self>) -> Result<(), StorageError> {
+        let buffer = std::mem::take(&mut self.buffer);
+        let encoded = self
+            .codec
+            .encode(Cow::Owned(buffer), &self.options)
+            .map_err(|err| StorageError::Other(err.to_string()))?;
+        self.encoded_value.write_all(&encoded)?;
+        self.encoded_value.finish()
+    }
+}
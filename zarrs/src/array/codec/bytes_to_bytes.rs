@@ -1,15 +1,21 @@
 //! Bytes to bytes codecs.
 
+#[cfg(feature = "bitshuffle")]
+pub mod bitshuffle;
 #[cfg(feature = "blosc")]
 pub mod blosc;
 #[cfg(feature = "bz2")]
 pub mod bz2;
 #[cfg(feature = "crc32c")]
 pub mod crc32c;
+#[cfg(feature = "framed")]
+pub mod framed;
 #[cfg(feature = "gdeflate")]
 pub mod gdeflate;
 #[cfg(feature = "gzip")]
 pub mod gzip;
+#[cfg(feature = "shuffle")]
+pub mod shuffle;
 #[cfg(feature = "zstd")]
 pub mod zstd;
 
@@ -0,0 +1,485 @@
+//! The `bitshuffle` bytes to bytes codec.
+//!
+//! [Bitshuffle](https://github.com/kiyo-masui/bitshuffle) rearranges the bits of a sequence of fixed size elements, such that the same bit of every element in a block is stored contiguously.
+//! It is a lossless size-preserving transform that improves the compression ratio of data with slowly varying values when followed by a compression codec.
+//!
+//! This codec is compatible with the `bshuf_bitshuffle` function of the `bitshuffle` library (as used by the HDF5 bitshuffle filter without compression), and the `imagecodecs_bitshuffle` codec of Zarr V2 arrays.
+//! It is independent of the bitshuffle option of the `blosc` codec.
+//!
+//! The elements are bitshuffled in blocks.
+//! Only the blocks that intersect the requested byte ranges are retrieved and unshuffled in partial decoding.
+//! The bit transpose is accelerated with SSE2 on `x86_64`.
+//!
+//! <div class="warning">
+//! This codec is experimental and may be incompatible with other Zarr V3 implementations.
+//! </div>
+//!
+//! This codec requires the `bitshuffle` feature, which is disabled by default.
+//!
+//! See [`BitshuffleCodecConfigurationV1`] for example `JSON` metadata.
+
+mod bitshuffle_codec;
+mod bitshuffle_partial_decoder;
+
+use std::{ops::Range, sync::Arc};
+
+use crate::{
+    array::codec::{Codec, CodecPlugin},
+    config::global_config,
+    metadata::v3::{array::codec::bitshuffle, MetadataV3},
+    plugin::{PluginCreateError, PluginMetadataInvalidError},
+};
+
+pub use crate::metadata::v3::array::codec::bitshuffle::{
+    BitshuffleBlockSize, BitshuffleCodecConfiguration, BitshuffleCodecConfigurationV1,
+};
+
+pub use self::bitshuffle_codec::BitshuffleCodec;
+
+pub use bitshuffle::IDENTIFIER;
+
+// Register the codec.
+inventory::submit! {
+    CodecPlugin::new(IDENTIFIER, is_name_bitshuffle, create_codec_bitshuffle)
+}
+
+fn is_name_bitshuffle(name: &str) -> bool {
+    name.eq(IDENTIFIER)
+        || name
+            == global_config()
+                .experimental_codec_names()
+                .get(IDENTIFIER)
+                .expect("experimental codec identifier in global map")
+}
+
+pub(crate) fn create_codec_bitshuffle(metadata: &MetadataV3) -> Result<Codec, PluginCreateError> {
+    let configuration: BitshuffleCodecConfiguration = metadata
+        .to_configuration()
+        .map_err(|_| PluginMetadataInvalidError::new(IDENTIFIER, "codec", metadata.clone()))?;
+    let codec = Arc::new(BitshuffleCodec::new_with_configuration(&configuration));
+    Ok(Codec::BytesToBytes(codec))
+}
+
+/// The number of elements in a block must be a multiple of this.
+const BLOCKED_MULT: usize = 8;
+
+/// The target size of a block in bytes with the default block size.
+const TARGET_BLOCK_SIZE_BYTES: usize = 8192;
+
+/// The minimum number of elements in a block with the default block size.
+const MIN_RECOMMEND_BLOCK: usize = 128;
+
+/// Return the default number of elements in a block of `element_size` byte elements.
+///
+/// This matches `bshuf_default_block_size` of the `bitshuffle` library.
+const fn default_block_size(element_size: usize) -> usize {
+    let block_size = TARGET_BLOCK_SIZE_BYTES / element_size / BLOCKED_MULT * BLOCKED_MULT;
+    if block_size > MIN_RECOMMEND_BLOCK {
+        block_size
+    } else {
+        MIN_RECOMMEND_BLOCK
+    }
+}
+
+/// A block of bitshuffled bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct BitshuffleBlock {
+    /// The byte range of the block, which is the same in the encoded and decoded bytes.
+    byte_range: Range<usize>,
+    /// The number of elements in the block, or zero if the bytes are stored verbatim.
+    num_elements: usize,
+}
+
+/// Return the blocks of `size` bytes of `element_size` byte elements with `block_size` elements per block.
+///
+/// The elements after the last complete block are bitshuffled as a final block, excluding up to 7 trailing elements and any trailing bytes of an incomplete element.
+/// These trailing bytes are stored verbatim.
+fn bitshuffle_blocks(size: usize, element_size: usize, block_size: usize) -> Vec<BitshuffleBlock> {
+    let num_elements = size / element_size;
+    let mut blocks = Vec::with_capacity(num_elements / block_size + 2);
+    let mut offset = 0;
+    let mut push_block = |num_elements: usize, length: usize| {
+        if length > 0 {
+            blocks.push(BitshuffleBlock {
+                byte_range: offset..offset + length,
+                num_elements,
+            });
+            offset += length;
+        }
+    };
+    for _ in 0..num_elements / block_size {
+        push_block(block_size, block_size * element_size);
+    }
+    let last_block_size = num_elements % block_size;
+    let last_block_size = last_block_size - last_block_size % BLOCKED_MULT;
+    let verbatim_size =
+        size - (num_elements - num_elements % block_size + last_block_size) * element_size;
+    push_block(last_block_size, last_block_size * element_size);
+    push_block(0, verbatim_size);
+    blocks
+}
+
+/// Transpose the 8x8 bit matrix `x`, where byte `i` of `x` is row `i` and bit `j` of a row is column `j`.
+const fn transpose_bits_8x8(mut x: u64) -> u64 {
+    let mut t = (x ^ (x >> 7)) & 0x00AA_00AA_00AA_00AA;
+    x = x ^ t ^ (t << 7);
+    t = (x ^ (x >> 14)) & 0x0000_CCCC_0000_CCCC;
+    x = x ^ t ^ (t << 14);
+    t = (x ^ (x >> 28)) & 0x0000_0000_F0F0_F0F0;
+    x ^ t ^ (t << 28)
+}
+
+/// Transpose the 8x8 bit matrices of each 8 bytes of `input`, where byte `i` of a matrix is row `i`.
+///
+/// Row `k` of the transpose of matrix `m` is written to `out[k * row_stride + m * matrix_stride]`.
+fn transpose_bits(input: &[u8], out: &mut [u8], row_stride: usize, matrix_stride: usize) {
+    let num_matrices = input.len() / 8;
+    let mut m = 0;
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        use std::arch::x86_64::{__m128i, _mm_loadu_si128, _mm_movemask_epi8, _mm_slli_epi16};
+        while m + 2 <= num_matrices {
+            // SAFETY: SSE2 is always available on x86_64, and the 16 bytes read are within `input`
+            // `_mm_loadu_si128` does not require the pointer to be aligned
+            #[allow(clippy::cast_ptr_alignment)]
+            let mut v = unsafe { _mm_loadu_si128(input.as_ptr().add(8 * m).cast::<__m128i>()) };
+            for k in (0..8).rev() {
+                // Gather the most significant bit of each byte, which is bit k before shifting
+                #[allow(clippy::cast_sign_loss)]
+                let bits = unsafe { _mm_movemask_epi8(v) as u32 }.to_le_bytes();
+                out[k * row_stride + m * matrix_stride] = bits[0];
+                out[k * row_stride + (m + 1) * matrix_stride] = bits[1];
+                v = unsafe { _mm_slli_epi16(v, 1) };
+            }
+            m += 2;
+        }
+    }
+
+    while m < num_matrices {
+        let x = u64::from_le_bytes(input[8 * m..8 * (m + 1)].try_into().unwrap());
+        for (k, row) in transpose_bits_8x8(x).to_le_bytes().into_iter().enumerate() {
+            out[k * row_stride + m * matrix_stride] = row;
+        }
+        m += 1;
+    }
+}
+
+/// Bitshuffle a block of `num_elements` elements of `element_size` bytes.
+///
+/// The output is `element_size * 8` bit rows of `num_elements / 8` bytes, where bit `i` of byte `m` of the bit row `8 * j + k` is bit `k` of byte `j` of element `8 * m + i`.
+fn bitshuffle_block(input: &[u8], output: &mut [u8], element_size: usize, num_elements: usize) {
+    // Transpose the bytes, so the `j`th bytes of all elements are contiguous
+    let mut bytes = vec![0u8; input.len()];
+    for (i, element) in input.chunks_exact(element_size).enumerate() {
+        for (j, byte) in element.iter().enumerate() {
+            bytes[j * num_elements + i] = *byte;
+        }
+    }
+
+    // Transpose the bits of each byte row
+    let stride = num_elements / 8;
+    for (j, row) in bytes.chunks_exact(num_elements).enumerate() {
+        transpose_bits(row, &mut output[8 * j * stride..], stride, 1);
+    }
+}
+
+/// Reverse [`bitshuffle_block`].
+fn bitunshuffle_block(input: &[u8], output: &mut [u8], element_size: usize, num_elements: usize) {
+    // Gather the 8 bit rows of each byte, so each group of 8 bytes is an 8x8 bit matrix
+    let stride = num_elements / 8;
+    let mut matrices = vec![0u8; input.len()];
+    for j in 0..element_size {
+        let bit_rows = &input[8 * j * stride..8 * (j + 1) * stride];
+        for (k, bit_row) in bit_rows.chunks_exact(stride).enumerate() {
+            for (m, byte) in bit_row.iter().enumerate() {
+                matrices[(j * stride + m) * 8 + k] = *byte;
+            }
+        }
+    }
+
+    // Transpose the bit matrices to recover the byte rows, then transpose the bytes back into elements
+    let mut bytes = vec![0u8; input.len()];
+    transpose_bits(&matrices, &mut bytes, 1, 8);
+    for (j, row) in bytes.chunks_exact(num_elements).enumerate() {
+        for (i, byte) in row.iter().enumerate() {
+            output[i * element_size + j] = *byte;
+        }
+    }
+}
+
+/// Apply `transform` to the bytes of each block in `blocks`, where `bytes` starts at the first block.
+fn transform_blocks(
+    bytes: &[u8],
+    blocks: &[BitshuffleBlock],
+    element_size: usize,
+    transform: fn(&[u8], &mut [u8], usize, usize),
+) -> Vec<u8> {
+    let mut out = vec![0u8; bytes.len()];
+    let Some(start) = blocks.first().map(|block| block.byte_range.start) else {
+        return out;
+    };
+    for block in blocks {
+        let byte_range = block.byte_range.start - start..block.byte_range.end - start;
+        let input = &bytes[byte_range.clone()];
+        let output = &mut out[byte_range];
+        if block.num_elements == 0 {
+            output.copy_from_slice(input);
+        } else {
+            transform(input, output, element_size, block.num_elements);
+        }
+    }
+    out
+}
+
+/// Bitshuffle `bytes` of `element_size` byte elements in blocks of `block_size` elements.
+fn bitshuffle(bytes: &[u8], element_size: usize, block_size: usize) -> Vec<u8> {
+    let blocks = bitshuffle_blocks(bytes.len(), element_size, block_size);
+    transform_blocks(bytes, &blocks, element_size, bitshuffle_block)
+}
+
+/// Reverse [`bitshuffle`].
+fn bitunshuffle(bytes: &[u8], element_size: usize, block_size: usize) -> Vec<u8> {
+    let blocks = bitshuffle_blocks(bytes.len(), element_size, block_size);
+    transform_blocks(bytes, &blocks, element_size, bitunshuffle_block)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{borrow::Cow, num::NonZeroUsize, sync::Arc};
+
+    use crate::{
+        array::{
+            codec::{BytesToBytesCodecTraits, CodecOptions, CodecTraits},
+            BytesRepresentation,
+        },
+        byte_range::ByteRange,
+    };
+
+    use super::*;
+
+    const JSON_VALID: &str = r#"{
+        "element_size": 4,
+        "block_size": 16
+    }"#;
+
+    /// A bit by bit reference implementation of [`bitshuffle_block`].
+    fn bitshuffle_block_reference(input: &[u8], element_size: usize) -> Vec<u8> {
+        let num_elements = input.len() / element_size;
+        let mut output = vec![0u8; input.len()];
+        for i in 0..num_elements {
+            for j in 0..element_size {
+                for k in 0..8 {
+                    let bit = (input[i * element_size + j] >> k) & 1;
+                    let out_bit = (8 * j + k) * num_elements + i;
+                    output[out_bit / 8] |= bit << (out_bit % 8);
+                }
+            }
+        }
+        output
+    }
+
+    fn test_bytes(size: usize) -> Vec<u8> {
+        (0..size)
+            .map(|i| u8::try_from((i * 7919 + i / 3) % 256).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn codec_bitshuffle_transpose_bits_8x8() {
+        let x = u64::from_le_bytes(test_bytes(8).try_into().unwrap());
+        let t = transpose_bits_8x8(x);
+        for i in 0..8 {
+            for j in 0..8 {
+                assert_eq!((x >> (8 * i + j)) & 1, (t >> (8 * j + i)) & 1);
+            }
+        }
+        assert_eq!(transpose_bits_8x8(t), x);
+    }
+
+    #[test]
+    fn codec_bitshuffle_block_reference() {
+        for element_size in [1, 2, 3, 4, 8] {
+            for num_elements in [8, 16, 24, 40, 128] {
+                let input = test_bytes(element_size * num_elements);
+                let mut output = vec![0u8; input.len()];
+                bitshuffle_block(&input, &mut output, element_size, num_elements);
+                assert_eq!(output, bitshuffle_block_reference(&input, element_size));
+                let mut unshuffled = vec![0u8; input.len()];
+                bitunshuffle_block(&output, &mut unshuffled, element_size, num_elements);
+                assert_eq!(input, unshuffled);
+            }
+        }
+    }
+
+    #[test]
+    fn codec_bitshuffle_blocks() {
+        // 2 full blocks of 16 elements, a final block of 8 elements, 3 trailing elements and 1 trailing byte
+        let blocks = bitshuffle_blocks(4 * (16 + 16 + 8 + 3) + 1, 4, 16);
+        assert_eq!(
+            blocks,
+            vec![
+                BitshuffleBlock {
+                    byte_range: 0..64,
+                    num_elements: 16
+                },
+                BitshuffleBlock {
+                    byte_range: 64..128,
+                    num_elements: 16
+                },
+                BitshuffleBlock {
+                    byte_range: 128..160,
+                    num_elements: 8
+                },
+                BitshuffleBlock {
+                    byte_range: 160..173,
+                    num_elements: 0
+                },
+            ]
+        );
+        assert_eq!(default_block_size(1), 8192);
+        assert_eq!(default_block_size(4), 2048);
+        assert_eq!(default_block_size(3), 2728);
+        assert_eq!(default_block_size(128), 128);
+    }
+
+    #[test]
+    fn codec_bitshuffle_configuration() {
+        let configuration: BitshuffleCodecConfiguration = serde_json::from_str(JSON_VALID).unwrap();
+        let codec = BitshuffleCodec::new_with_configuration(&configuration);
+        assert_eq!(codec.element_size(), 4);
+        assert_eq!(codec.block_size(), 16);
+        let metadata = codec.create_metadata().unwrap();
+        assert_eq!(
+            metadata
+                .to_configuration::<BitshuffleCodecConfiguration>()
+                .unwrap(),
+            configuration
+        );
+
+        let codec = BitshuffleCodec::new(NonZeroUsize::new(4).unwrap(), None);
+        assert_eq!(codec.block_size(), 2048);
+    }
+
+    #[test]
+    fn codec_bitshuffle_round_trip() {
+        for (element_size, block_size) in [(1, None), (2, None), (4, Some(16)), (8, Some(64))] {
+            let codec = BitshuffleCodec::new(
+                NonZeroUsize::new(element_size).unwrap(),
+                block_size.and_then(BitshuffleBlockSize::new),
+            );
+            for size in [0, 7, 100, 4096, 20000, 65537] {
+                let bytes = test_bytes(size);
+                let bytes_representation = BytesRepresentation::FixedSize(size as u64);
+                let encoded = codec
+                    .encode(Cow::Borrowed(&bytes), &CodecOptions::default())
+                    .unwrap();
+                assert_eq!(encoded.len(), size);
+                let decoded = codec
+                    .decode(encoded, &bytes_representation, &CodecOptions::default())
+                    .unwrap();
+                assert_eq!(bytes, decoded.to_vec());
+            }
+        }
+    }
+
+    #[test]
+    fn codec_bitshuffle_bitshuffled_u16() {
+        // Values less than 64 only use the first 6 bits, so the remaining 10 bit rows of 8 bytes are zero
+        let elements: Vec<u16> = (0..64).collect();
+        let bytes = crate::array::transmute_to_bytes_vec(elements);
+        let encoded = bitshuffle(&bytes, 2, 64);
+        assert_eq!(&encoded[..8], &[0xAA; 8]);
+        assert_eq!(&encoded[8..16], &[0xCC; 8]);
+        assert_eq!(&encoded[16..24], &[0xF0; 8]);
+        for k in 3..6 {
+            for m in 0..8 {
+                let expected = if (m >> (k - 3)) & 1 == 1 { 0xFF } else { 0x00 };
+                assert_eq!(encoded[8 * k + m], expected);
+            }
+        }
+        assert!(encoded[48..].iter().all(|byte| *byte == 0));
+        assert_eq!(bitunshuffle(&encoded, 2, 64), bytes);
+    }
+
+    #[test]
+    fn codec_bitshuffle_partial_decode() {
+        let codec = Arc::new(BitshuffleCodec::new_with_configuration(
+            &serde_json::from_str(JSON_VALID).unwrap(),
+        ));
+        let size = 4 * (16 * 3 + 8 + 5) + 2;
+        let bytes = test_bytes(size);
+        let bytes_representation = BytesRepresentation::FixedSize(size as u64);
+        let encoded = codec
+            .encode(Cow::Borrowed(&bytes), &CodecOptions::default())
+            .unwrap()
+            .to_vec();
+
+        let decoded_regions = [
+            ByteRange::FromStart(0, Some(1)),
+            ByteRange::FromStart(60, Some(10)),
+            ByteRange::FromStart(100, None),
+            ByteRange::Suffix(30),
+            ByteRange::FromStart(size as u64, Some(0)),
+        ];
+        let input_handle = Arc::new(std::io::Cursor::new(encoded));
+        let partial_decoder = codec
+            .partial_decoder(
+                input_handle,
+                &bytes_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        let decoded_partial_chunk = partial_decoder
+            .partial_decode(&decoded_regions, &CodecOptions::default())
+            .unwrap()
+            .unwrap();
+        for (decoded_region, decoded_partial_chunk) in
+            decoded_regions.iter().zip(decoded_partial_chunk)
+        {
+            assert_eq!(
+                &bytes[decoded_region.to_range_usize(size as u64)],
+                decoded_partial_chunk.as_ref()
+            );
+        }
+
+        assert!(partial_decoder
+            .partial_decode(
+                &[ByteRange::FromStart(size as u64 - 1, Some(2))],
+                &CodecOptions::default()
+            )
+            .is_err());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn codec_bitshuffle_async_partial_decode() {
+        let codec = Arc::new(BitshuffleCodec::new_with_configuration(
+            &serde_json::from_str(JSON_VALID).unwrap(),
+        ));
+        let size = 4 * 100;
+        let bytes = test_bytes(size);
+        let bytes_representation = BytesRepresentation::FixedSize(size as u64);
+        let encoded = codec
+            .encode(Cow::Borrowed(&bytes), &CodecOptions::default())
+            .unwrap()
+            .to_vec();
+
+        let decoded_regions = [ByteRange::FromStart(70, Some(130))];
+        let input_handle = Arc::new(std::io::Cursor::new(encoded));
+        let partial_decoder = codec
+            .async_partial_decoder(
+                input_handle,
+                &bytes_representation,
+                &CodecOptions::default(),
+            )
+            .await
+            .unwrap();
+        let decoded_partial_chunk = partial_decoder
+            .partial_decode(&decoded_regions, &CodecOptions::default())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&bytes[70..200], decoded_partial_chunk[0].as_ref());
+    }
+}
@@ -0,0 +1,184 @@
+use std::{borrow::Cow, num::NonZeroUsize, sync::Arc};
+
+use crate::{
+    array::{
+        codec::{
+            BytesPartialDecoderTraits, BytesPartialEncoderDefault, BytesPartialEncoderTraits,
+            BytesToBytesCodecTraits, CodecError, CodecOptions, CodecTraits, RecommendedConcurrency,
+        },
+        ArrayMetadataOptions, BytesRepresentation, RawBytes,
+    },
+    config::global_config,
+    metadata::v3::MetadataV3,
+};
+
+#[cfg(feature = "async")]
+use crate::array::codec::AsyncBytesPartialDecoderTraits;
+
+use super::{
+    bitshuffle, bitshuffle_partial_decoder, bitunshuffle, default_block_size, BitshuffleBlockSize,
+    BitshuffleCodecConfiguration, BitshuffleCodecConfigurationV1, IDENTIFIER,
+};
+
+/// A `bitshuffle` codec implementation.
+#[derive(Clone, Debug)]
+pub struct BitshuffleCodec {
+    element_size: NonZeroUsize,
+    block_size: Option<BitshuffleBlockSize>,
+}
+
+impl BitshuffleCodec {
+    /// Create a new `bitshuffle` codec for elements of `element_size` bytes.
+    ///
+    /// If `block_size` is [`None`], the block size is chosen automatically like the `bitshuffle` library.
+    #[must_use]
+    pub const fn new(element_size: NonZeroUsize, block_size: Option<BitshuffleBlockSize>) -> Self {
+        Self {
+            element_size,
+            block_size,
+        }
+    }
+
+    /// Create a new `bitshuffle` codec from configuration.
+    #[must_use]
+    pub const fn new_with_configuration(configuration: &BitshuffleCodecConfiguration) -> Self {
+        let BitshuffleCodecConfiguration::V1(configuration) = configuration;
+        Self::new(configuration.element_size, configuration.block_size)
+    }
+
+    /// Return the size of an element in bytes.
+    #[must_use]
+    pub const fn element_size(&self) -> usize {
+        self.element_size.get()
+    }
+
+    /// Return the number of elements in a block.
+    #[must_use]
+    pub const fn block_size(&self) -> usize {
+        match self.block_size {
+            Some(block_size) => block_size.get(),
+            None => default_block_size(self.element_size.get()),
+        }
+    }
+}
+
+impl CodecTraits for BitshuffleCodec {
+    fn create_metadata_opt(&self, _options: &ArrayMetadataOptions) -> Option<MetadataV3> {
+        let configuration = BitshuffleCodecConfigurationV1 {
+            element_size: self.element_size,
+            block_size: self.block_size,
+        };
+        Some(
+            MetadataV3::new_with_serializable_configuration(
+                global_config()
+                    .experimental_codec_names()
+                    .get(IDENTIFIER)
+                    .expect("experimental codec identifier in global map"),
+                &configuration,
+            )
+            .unwrap(),
+        )
+    }
+
+    fn partial_decoder_should_cache_input(&self) -> bool {
+        false
+    }
+
+    fn partial_decoder_decodes_all(&self) -> bool {
+        false
+    }
+}
+
+#[cfg_attr(feature = "async", async_trait::async_trait)]
+impl BytesToBytesCodecTraits for BitshuffleCodec {
+    fn dynamic(self: Arc<Self>) -> Arc<dyn BytesToBytesCodecTraits> {
+        self as Arc<dyn BytesToBytesCodecTraits>
+    }
+
+    fn recommended_concurrency(
+        &self,
+        _decoded_representation: &BytesRepresentation,
+    ) -> Result<RecommendedConcurrency, CodecError> {
+        Ok(RecommendedConcurrency::new_maximum(1))
+    }
+
+    fn encode<'a>(
+        &self,
+        decoded_value: RawBytes<'a>,
+        _options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        Ok(Cow::Owned(bitshuffle(
+            &decoded_value,
+            self.element_size(),
+            self.block_size(),
+        )))
+    }
+
+    fn decode<'a>(
+        &self,
+        encoded_value: RawBytes<'a>,
+        _decoded_representation: &BytesRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        Ok(Cow::Owned(bitunshuffle(
+            &encoded_value,
+            self.element_size(),
+            self.block_size(),
+        )))
+    }
+
+    fn partial_decoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn BytesPartialDecoderTraits>,
+        decoded_representation: &BytesRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn BytesPartialDecoderTraits>, CodecError> {
+        Ok(Arc::new(
+            bitshuffle_partial_decoder::BitshufflePartialDecoder::new(
+                input_handle,
+                decoded_representation.size(),
+                self.element_size(),
+                self.block_size(),
+            ),
+        ))
+    }
+
+    fn partial_encoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn BytesPartialDecoderTraits>,
+        output_handle: Arc<dyn BytesPartialEncoderTraits>,
+        decoded_representation: &BytesRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn BytesPartialEncoderTraits>, CodecError> {
+        Ok(Arc::new(BytesPartialEncoderDefault::new(
+            input_handle,
+            output_handle,
+            *decoded_representation,
+            self,
+        )))
+    }
+
+    #[cfg(feature = "async")]
+    async fn async_partial_decoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+        decoded_representation: &BytesRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn AsyncBytesPartialDecoderTraits>, CodecError> {
+        Ok(Arc::new(
+            bitshuffle_partial_decoder::AsyncBitshufflePartialDecoder::new(
+                input_handle,
+                decoded_representation.size(),
+                self.element_size(),
+                self.block_size(),
+            ),
+        ))
+    }
+
+    fn compute_encoded_size(
+        &self,
+        decoded_representation: &BytesRepresentation,
+    ) -> BytesRepresentation {
+        *decoded_representation
+    }
+}
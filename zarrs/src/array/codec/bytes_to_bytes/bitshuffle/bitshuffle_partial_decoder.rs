@@ -0,0 +1,246 @@
+use std::{borrow::Cow, ops::Range, sync::Arc};
+
+use crate::{
+    array::{
+        codec::{BytesPartialDecoderTraits, CodecError, CodecOptions},
+        RawBytes,
+    },
+    byte_range::{extract_byte_ranges, ByteRange, InvalidByteRangeError},
+};
+
+#[cfg(feature = "async")]
+use crate::array::codec::AsyncBytesPartialDecoderTraits;
+
+use super::{
+    bitshuffle_blocks, bitunshuffle, bitunshuffle_block, transform_blocks, BitshuffleBlock,
+};
+
+/// Return the indices of the `blocks` intersecting each of the `decoded_regions` of a `size` byte value.
+fn intersecting_blocks(
+    blocks: &[BitshuffleBlock],
+    decoded_regions: &[ByteRange],
+    size: u64,
+) -> Result<Vec<Range<usize>>, CodecError> {
+    decoded_regions
+        .iter()
+        .map(|decoded_region| {
+            let valid = match decoded_region {
+                ByteRange::FromStart(offset, length) => offset + length.unwrap_or(0) <= size,
+                ByteRange::Suffix(length) => *length <= size,
+            };
+            if !valid {
+                return Err(CodecError::InvalidByteRangeError(
+                    InvalidByteRangeError::new(*decoded_region, size),
+                ));
+            }
+            let range = decoded_region.to_range_usize(size);
+            let first = blocks.partition_point(|block| block.byte_range.end <= range.start);
+            let last = blocks.partition_point(|block| block.byte_range.start < range.end);
+            Ok(first..last.max(first))
+        })
+        .collect()
+}
+
+/// Return the encoded byte range spanning `blocks`.
+///
+/// This is the same as the decoded byte range, since bitshuffling does not change the size of a block.
+fn blocks_byte_range(blocks: &[BitshuffleBlock]) -> ByteRange {
+    match (blocks.first(), blocks.last()) {
+        (Some(first), Some(last)) => ByteRange::FromStart(
+            first.byte_range.start as u64,
+            Some((last.byte_range.end - first.byte_range.start) as u64),
+        ),
+        _ => ByteRange::FromStart(0, Some(0)),
+    }
+}
+
+/// Bitunshuffle the `encoded_blocks` spanning `blocks`, and extract `decoded_region` of a `size` byte value.
+fn decode_blocks(
+    encoded_blocks: &[u8],
+    blocks: &[BitshuffleBlock],
+    element_size: usize,
+    decoded_region: &ByteRange,
+    size: u64,
+) -> Vec<u8> {
+    let Some(offset) = blocks.first().map(|block| block.byte_range.start) else {
+        return vec![];
+    };
+    let decoded = transform_blocks(encoded_blocks, blocks, element_size, bitunshuffle_block);
+    let range = decoded_region.to_range_usize(size);
+    decoded[range.start - offset..range.end - offset].to_vec()
+}
+
+/// Bitunshuffle the entire `encoded_value`, and extract `decoded_regions`.
+fn decode_all<'a>(
+    encoded_value: &[u8],
+    decoded_regions: &[ByteRange],
+    element_size: usize,
+    block_size: usize,
+) -> Result<Vec<RawBytes<'a>>, CodecError> {
+    let decoded_value = bitunshuffle(encoded_value, element_size, block_size);
+    Ok(extract_byte_ranges(&decoded_value, decoded_regions)
+        .map_err(CodecError::InvalidByteRangeError)?
+        .into_iter()
+        .map(Cow::Owned)
+        .collect())
+}
+
+/// Partial decoder for the `bitshuffle` codec.
+pub(crate) struct BitshufflePartialDecoder<'a> {
+    input_handle: Arc<dyn BytesPartialDecoderTraits + 'a>,
+    decoded_size: Option<u64>,
+    element_size: usize,
+    block_size: usize,
+}
+
+impl<'a> BitshufflePartialDecoder<'a> {
+    /// Create a new partial decoder for the `bitshuffle` codec.
+    pub(crate) fn new(
+        input_handle: Arc<dyn BytesPartialDecoderTraits + 'a>,
+        decoded_size: Option<u64>,
+        element_size: usize,
+        block_size: usize,
+    ) -> Self {
+        Self {
+            input_handle,
+            decoded_size,
+            element_size,
+            block_size,
+        }
+    }
+}
+
+impl BytesPartialDecoderTraits for BitshufflePartialDecoder<'_> {
+    fn partial_decode(
+        &self,
+        decoded_regions: &[ByteRange],
+        options: &CodecOptions,
+    ) -> Result<Option<Vec<RawBytes<'_>>>, CodecError> {
+        let Some(size) = self.decoded_size else {
+            let Some(encoded_value) = self.input_handle.decode(options)? else {
+                return Ok(None);
+            };
+            return Ok(Some(decode_all(
+                &encoded_value,
+                decoded_regions,
+                self.element_size,
+                self.block_size,
+            )?));
+        };
+
+        // Only retrieve and bitunshuffle the blocks intersecting the decoded regions
+        let blocks = bitshuffle_blocks(
+            usize::try_from(size).unwrap(),
+            self.element_size,
+            self.block_size,
+        );
+        let region_blocks = intersecting_blocks(&blocks, decoded_regions, size)?;
+        let byte_ranges: Vec<ByteRange> = region_blocks
+            .iter()
+            .map(|region_blocks| blocks_byte_range(&blocks[region_blocks.clone()]))
+            .collect();
+        let Some(encoded_blocks) = self.input_handle.partial_decode(&byte_ranges, options)? else {
+            return Ok(None);
+        };
+        Ok(Some(
+            encoded_blocks
+                .iter()
+                .zip(region_blocks)
+                .zip(decoded_regions)
+                .map(|((encoded_blocks, region_blocks), decoded_region)| {
+                    Cow::Owned(decode_blocks(
+                        encoded_blocks,
+                        &blocks[region_blocks],
+                        self.element_size,
+                        decoded_region,
+                        size,
+                    ))
+                })
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(feature = "async")]
+/// Asynchronous partial decoder for the `bitshuffle` codec.
+pub(crate) struct AsyncBitshufflePartialDecoder {
+    input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+    decoded_size: Option<u64>,
+    element_size: usize,
+    block_size: usize,
+}
+
+#[cfg(feature = "async")]
+impl AsyncBitshufflePartialDecoder {
+    /// Create a new partial decoder for the `bitshuffle` codec.
+    pub(crate) fn new(
+        input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+        decoded_size: Option<u64>,
+        element_size: usize,
+        block_size: usize,
+    ) -> Self {
+        Self {
+            input_handle,
+            decoded_size,
+            element_size,
+            block_size,
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl AsyncBytesPartialDecoderTraits for AsyncBitshufflePartialDecoder {
+    async fn partial_decode(
+        &self,
+        decoded_regions: &[ByteRange],
+        options: &CodecOptions,
+    ) -> Result<Option<Vec<RawBytes<'_>>>, CodecError> {
+        let Some(size) = self.decoded_size else {
+            let Some(encoded_value) = self.input_handle.decode(options).await? else {
+                return Ok(None);
+            };
+            return Ok(Some(decode_all(
+                &encoded_value,
+                decoded_regions,
+                self.element_size,
+                self.block_size,
+            )?));
+        };
+
+        // Only retrieve and bitunshuffle the blocks intersecting the decoded regions
+        let blocks = bitshuffle_blocks(
+            usize::try_from(size).unwrap(),
+            self.element_size,
+            self.block_size,
+        );
+        let region_blocks = intersecting_blocks(&blocks, decoded_regions, size)?;
+        let byte_ranges: Vec<ByteRange> = region_blocks
+            .iter()
+            .map(|region_blocks| blocks_byte_range(&blocks[region_blocks.clone()]))
+            .collect();
+        let Some(encoded_blocks) = self
+            .input_handle
+            .partial_decode(&byte_ranges, options)
+            .await?
+        else {
+            return Ok(None);
+        };
+        Ok(Some(
+            encoded_blocks
+                .iter()
+                .zip(region_blocks)
+                .zip(decoded_regions)
+                .map(|((encoded_blocks, region_blocks), decoded_region)| {
+                    Cow::Owned(decode_blocks(
+                        encoded_blocks,
+                        &blocks[region_blocks],
+                        self.element_size,
+                        decoded_region,
+                        size,
+                    ))
+                })
+                .collect(),
+        ))
+    }
+}
@@ -2,10 +2,13 @@
 //!
 //! Appends a CRC32C checksum of the input bytestream.
 //!
+//! With [experimental partial encoding](crate::config::Config::experimental_partial_encoding), the checksum is updated incrementally from the bytes replaced by an update rather than recomputed from the entire input bytestream.
+//!
 //! See <https://zarr-specs.readthedocs.io/en/latest/v3/codecs/crc32c/v1.0.html>.
 
 mod crc32c_codec;
 mod crc32c_partial_decoder;
+mod crc32c_partial_encoder;
 
 use std::sync::Arc;
 
@@ -41,6 +44,20 @@ pub(crate) fn create_codec_crc32c(metadata: &MetadataV3) -> Result<Codec, Plugin
 
 const CHECKSUM_SIZE: usize = core::mem::size_of::<u32>();
 
+/// Return the CRC32C `checksum` of a `size` byte value updated for the replacement of `bytes_old` at `offset` with `bytes_new`.
+///
+/// A CRC is linear in the value apart from a term that only depends on the value length.
+/// So the checksum changes by the CRC (without the initial and final XOR) of the difference of the old and new bytes, shifted by the number of bytes that follow them.
+fn crc32c_update(checksum: u32, size: u64, offset: u64, bytes_old: &[u8], bytes_new: &[u8]) -> u32 {
+    let difference: Vec<u8> = std::iter::zip(bytes_old, bytes_new)
+        .map(|(byte_old, byte_new)| byte_old ^ byte_new)
+        .collect();
+    let difference_crc =
+        ::crc32c::crc32c(&difference) ^ ::crc32c::crc32c(&vec![0; difference.len()]);
+    let trailing = usize::try_from(size - offset - difference.len() as u64).unwrap();
+    checksum ^ ::crc32c::crc32c_combine(difference_crc, 0, trailing)
+}
+
 #[cfg(test)]
 mod tests {
     use std::{borrow::Cow, sync::Arc};
@@ -48,9 +65,14 @@ mod tests {
     use crate::{
         array::{
             codec::{BytesToBytesCodecTraits, CodecOptions, CodecTraits},
-            BytesRepresentation,
+            ArrayBuilder, BytesRepresentation, DataType, FillValue,
         },
+        array_subset::ArraySubset,
         byte_range::ByteRange,
+        storage::{
+            storage_adapter::performance_metrics::PerformanceMetricsStorageAdapter,
+            store::MemoryStore,
+        },
     };
 
     use super::*;
@@ -133,6 +155,65 @@ mod tests {
         );
     }
 
+    #[test]
+    fn codec_crc32c_update() {
+        let mut bytes: Vec<u8> = (0..=255).collect();
+        let mut checksum = ::crc32c::crc32c(&bytes);
+        for (offset, bytes_new) in [(0, vec![7; 3]), (100, vec![1, 2, 3, 4]), (250, vec![0; 6])] {
+            let range = offset..offset + bytes_new.len();
+            checksum = crc32c_update(
+                checksum,
+                bytes.len() as u64,
+                offset as u64,
+                &bytes[range.clone()],
+                &bytes_new,
+            );
+            bytes[range].copy_from_slice(&bytes_new);
+            assert_eq!(checksum, ::crc32c::crc32c(&bytes));
+        }
+    }
+
+    #[test]
+    fn codec_crc32c_array_partial_encode() -> Result<(), Box<dyn std::error::Error>> {
+        let store = Arc::new(MemoryStore::default());
+        let store_perf = Arc::new(PerformanceMetricsStorageAdapter::new(store));
+        let array = ArrayBuilder::new(
+            vec![8, 8], // array shape
+            DataType::UInt16,
+            vec![8, 8].try_into()?, // regular chunk shape
+            FillValue::from(0u16),
+        )
+        .bytes_to_bytes_codecs(vec![Arc::new(Crc32cCodec::new())])
+        .build(store_perf.clone(), "/array")?;
+        let options = CodecOptions::builder()
+            .experimental_partial_encoding(true)
+            .build();
+        let mut elements: Vec<u16> = (0..64).collect();
+        array.store_array_subset_elements_opt(&array.subset_all(), &elements, &options)?;
+
+        // Only the updated elements and the checksum are written
+        store_perf.reset();
+        array.store_array_subset_elements_opt::<u16>(
+            &ArraySubset::new_with_ranges(&[2..4, 3..5]),
+            &[100, 101, 102, 103],
+            &options,
+        )?;
+        for (i, element) in [(19, 100), (20, 101), (27, 102), (28, 103)] {
+            elements[i] = element;
+        }
+        assert_eq!(
+            store_perf.bytes_written(),
+            4 * core::mem::size_of::<u16>() + CHECKSUM_SIZE
+        );
+
+        // The checksum is validated when the chunk is retrieved
+        assert_eq!(
+            array.retrieve_array_subset_elements::<u16>(&array.subset_all())?,
+            elements
+        );
+        Ok(())
+    }
+
     #[cfg(feature = "async")]
     #[tokio::test]
     async fn codec_crc32c_async_partial_decode() {
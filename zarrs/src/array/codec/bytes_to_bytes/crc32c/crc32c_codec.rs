@@ -15,8 +15,8 @@ use crate::{
 use crate::array::codec::AsyncBytesPartialDecoderTraits;
 
 use super::{
-    crc32c_partial_decoder, Crc32cCodecConfiguration, Crc32cCodecConfigurationV1, CHECKSUM_SIZE,
-    IDENTIFIER,
+    crc32c_partial_decoder, crc32c_partial_encoder, Crc32cCodecConfiguration,
+    Crc32cCodecConfigurationV1, CHECKSUM_SIZE, IDENTIFIER,
 };
 
 /// A `crc32c` (CRC32C checksum) codec implementation.
@@ -118,11 +118,17 @@ impl BytesToBytesCodecTraits for Crc32cCodec {
         decoded_representation: &BytesRepresentation,
         _options: &CodecOptions,
     ) -> Result<Arc<dyn BytesPartialEncoderTraits>, CodecError> {
-        Ok(Arc::new(BytesPartialEncoderDefault::new(
+        let partial_encoder_default = BytesPartialEncoderDefault::new(
+            input_handle.clone(),
+            output_handle.clone(),
+            *decoded_representation,
+            self,
+        );
+        Ok(Arc::new(crc32c_partial_encoder::Crc32cPartialEncoder::new(
             input_handle,
             output_handle,
             *decoded_representation,
-            self,
+            partial_encoder_default,
         )))
     }
 
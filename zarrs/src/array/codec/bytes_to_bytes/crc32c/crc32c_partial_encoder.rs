@@ -0,0 +1,128 @@
+use std::{borrow::Cow, sync::Arc};
+
+use zarrs_storage::byte_range::ByteOffset;
+
+use crate::{
+    array::{
+        codec::{
+            BytesPartialDecoderTraits, BytesPartialEncoderDefault, BytesPartialEncoderTraits,
+            CodecError, CodecOptions,
+        },
+        BytesRepresentation, RawBytes,
+    },
+    byte_range::ByteRange,
+};
+
+use super::{crc32c_update, CHECKSUM_SIZE};
+
+/// Partial encoder for the `crc32c` (CRC32C checksum) codec.
+///
+/// The checksum is updated incrementally from the bytes replaced by each update, so only the updated byte ranges and the checksum are read and written.
+/// The existing checksum is not validated.
+///
+/// The [`BytesPartialEncoderDefault`] is used instead if
+///  - the decoded size is not fixed,
+///  - the output handle does not support partial encoding,
+///  - the update extends the value, or
+///  - the value does not exist.
+pub(crate) struct Crc32cPartialEncoder {
+    input_handle: Arc<dyn BytesPartialDecoderTraits>,
+    output_handle: Arc<dyn BytesPartialEncoderTraits>,
+    decoded_representation: BytesRepresentation,
+    partial_encoder_default: BytesPartialEncoderDefault,
+}
+
+impl Crc32cPartialEncoder {
+    /// Create a new partial encoder for the `crc32c` codec.
+    pub(crate) fn new(
+        input_handle: Arc<dyn BytesPartialDecoderTraits>,
+        output_handle: Arc<dyn BytesPartialEncoderTraits>,
+        decoded_representation: BytesRepresentation,
+        partial_encoder_default: BytesPartialEncoderDefault,
+    ) -> Self {
+        Self {
+            input_handle,
+            output_handle,
+            decoded_representation,
+            partial_encoder_default,
+        }
+    }
+}
+
+impl BytesPartialEncoderTraits for Crc32cPartialEncoder {
+    fn erase(&self) -> Result<(), CodecError> {
+        self.output_handle.erase()
+    }
+
+    fn partial_encode(
+        &self,
+        offsets_and_bytes: &[(ByteOffset, RawBytes<'_>)],
+        options: &CodecOptions,
+    ) -> Result<(), CodecError> {
+        let BytesRepresentation::FixedSize(size) = self.decoded_representation else {
+            return self
+                .partial_encoder_default
+                .partial_encode(offsets_and_bytes, options);
+        };
+        let extends = offsets_and_bytes
+            .iter()
+            .any(|(offset, bytes)| offset + bytes.len() as u64 > size);
+        if extends || !self.output_handle.supports_partial_encode() {
+            return self
+                .partial_encoder_default
+                .partial_encode(offsets_and_bytes, options);
+        }
+
+        // Retrieve the bytes to be replaced and the checksum
+        let mut byte_ranges: Vec<ByteRange> = offsets_and_bytes
+            .iter()
+            .map(|(offset, bytes)| ByteRange::FromStart(*offset, Some(bytes.len() as u64)))
+            .collect();
+        byte_ranges.push(ByteRange::FromStart(size, Some(CHECKSUM_SIZE as u64)));
+        let Some(mut bytes_old) = self.input_handle.partial_decode(&byte_ranges, options)? else {
+            return self
+                .partial_encoder_default
+                .partial_encode(offsets_and_bytes, options);
+        };
+        let checksum = bytes_old.pop().unwrap();
+        let checksum: [u8; CHECKSUM_SIZE] = checksum
+            .as_ref()
+            .try_into()
+            .map_err(|_| CodecError::Other("crc32c decoder expects a 32 bit input".to_string()))?;
+        let mut checksum = u32::from_le_bytes(checksum);
+
+        // Update the checksum, where later updates replace the bytes of earlier updates
+        for (i, ((offset, bytes), bytes_old)) in
+            std::iter::zip(offsets_and_bytes, bytes_old).enumerate()
+        {
+            let mut bytes_old = bytes_old.into_owned();
+            let end = offset + bytes.len() as u64;
+            for (offset_prev, bytes_prev) in &offsets_and_bytes[..i] {
+                let start_overlap = (*offset).max(*offset_prev);
+                let end_overlap = end.min(offset_prev + bytes_prev.len() as u64);
+                if start_overlap < end_overlap {
+                    bytes_old[usize::try_from(start_overlap - offset).unwrap()
+                        ..usize::try_from(end_overlap - offset).unwrap()]
+                        .copy_from_slice(
+                            &bytes_prev[usize::try_from(start_overlap - offset_prev).unwrap()
+                                ..usize::try_from(end_overlap - offset_prev).unwrap()],
+                        );
+                }
+            }
+            checksum = crc32c_update(checksum, size, *offset, &bytes_old, bytes);
+        }
+
+        // Write the updated byte ranges and the checksum
+        let mut offsets_and_bytes_encoded: Vec<(ByteOffset, RawBytes<'_>)> = offsets_and_bytes
+            .iter()
+            .map(|(offset, bytes)| (*offset, Cow::Borrowed(bytes.as_ref())))
+            .collect();
+        offsets_and_bytes_encoded.push((size, Cow::Owned(checksum.to_le_bytes().to_vec())));
+        self.output_handle
+            .partial_encode(&offsets_and_bytes_encoded, options)
+    }
+
+    fn supports_partial_encode(&self) -> bool {
+        self.output_handle.supports_partial_encode()
+    }
+}
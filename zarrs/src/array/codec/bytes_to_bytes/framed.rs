@@ -0,0 +1,624 @@
+//! The `framed` bytes to bytes codec.
+//!
+//! The `framed` codec splits a value into frames of a fixed decoded size that are independently encoded by a sequence of bytes to bytes codecs (e.g. `gzip` or `zstd`).
+//! An index of the encoded frames is appended to the encoded frames.
+//!
+//! Only the frames intersecting the requested byte ranges are retrieved and decoded in partial decoding.
+//! With [experimental partial encoding](crate::config::Config::experimental_partial_encoding), only the frames intersecting the updated byte ranges are decoded, updated, and encoded.
+//! The updated frames and the index are appended to the encoded value, so the bytes of the frames they replace become unreferenced.
+//! This allows the partial encoding of compressed chunks of an unsharded array with the `bytes` codec, for example.
+//!
+//! The encoded representation is
+//! ```text
+//! | frame 0 | frame 1 | ... | frame n-1 | index | decoded size |
+//! ```
+//! where
+//!  - the index is a (offset, size) pair of `u64` values for each of the `n` encoded frames, where the offset is relative to the start of the encoded value,
+//!  - the decoded size is the size of the decoded value in bytes as a `u64`, and
+//!  - `n` is the decoded size divided by the frame size, rounded up.
+//!
+//! All `u64` values are little endian.
+//!
+//! <div class="warning">
+//! This codec is experimental and may be incompatible with other Zarr V3 implementations.
+//! </div>
+//!
+//! This codec requires the `framed` feature, which is disabled by default.
+//!
+//! See [`FramedCodecConfigurationV1`] for example `JSON` metadata.
+
+mod framed_codec;
+mod framed_partial_decoder;
+mod framed_partial_encoder;
+
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+    ops::Range,
+    sync::Arc,
+};
+
+use crate::{
+    array::{
+        codec::{
+            BytesPartialDecoderTraits, BytesToBytesCodecTraits, Codec, CodecError, CodecOptions,
+            CodecPlugin,
+        },
+        BytesRepresentation, RawBytes,
+    },
+    byte_range::{ByteRange, InvalidByteRangeError},
+    config::global_config,
+    metadata::v3::{array::codec::framed, MetadataV3},
+    plugin::{PluginCreateError, PluginMetadataInvalidError},
+};
+
+#[cfg(feature = "async")]
+use crate::array::codec::AsyncBytesPartialDecoderTraits;
+
+pub use crate::metadata::v3::array::codec::framed::{
+    FramedCodecConfiguration, FramedCodecConfigurationV1,
+};
+
+pub use self::framed_codec::FramedCodec;
+
+pub use framed::IDENTIFIER;
+
+// Register the codec.
+inventory::submit! {
+    CodecPlugin::new(IDENTIFIER, is_name_framed, create_codec_framed)
+}
+
+fn is_name_framed(name: &str) -> bool {
+    name.eq(IDENTIFIER)
+        || name
+            == global_config()
+                .experimental_codec_names()
+                .get(IDENTIFIER)
+                .expect("experimental codec identifier in global map")
+}
+
+pub(crate) fn create_codec_framed(metadata: &MetadataV3) -> Result<Codec, PluginCreateError> {
+    let configuration: FramedCodecConfiguration = metadata
+        .to_configuration()
+        .map_err(|_| PluginMetadataInvalidError::new(IDENTIFIER, "codec", metadata.clone()))?;
+    let codec = Arc::new(FramedCodec::new_with_configuration(&configuration)?);
+    Ok(Codec::BytesToBytes(codec))
+}
+
+/// The size of an encoded (offset, size) pair of the frame index.
+const FRAME_INDEX_ENTRY_SIZE: u64 = 2 * core::mem::size_of::<u64>() as u64;
+
+/// The size of the encoded decoded size at the end of an encoded value.
+const DECODED_SIZE_SIZE: u64 = core::mem::size_of::<u64>() as u64;
+
+/// Return the size of the encoded frame index (including the decoded size) of a `decoded_size` byte value.
+fn frame_index_encoded_size(decoded_size: u64, frame_size: u64) -> u64 {
+    decoded_size.div_ceil(frame_size) * FRAME_INDEX_ENTRY_SIZE + DECODED_SIZE_SIZE
+}
+
+/// The index of the encoded frames of a value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct FrameIndex {
+    /// The size of the decoded value in bytes.
+    decoded_size: u64,
+    /// The (offset, size) of each encoded frame.
+    frames: Vec<(u64, u64)>,
+}
+
+impl FrameIndex {
+    /// Decode a frame index from the end of `encoded_index`, which must include the decoded size.
+    fn decode(encoded_index: &[u8], frame_size: u64) -> Result<Self, CodecError> {
+        let invalid = || CodecError::Other("the framed codec index is invalid".to_string());
+        let (encoded_frames, encoded_decoded_size) = encoded_index
+            .split_last_chunk::<8>()
+            .map(|(frames, decoded_size)| (frames, *decoded_size))
+            .ok_or_else(invalid)?;
+        let decoded_size = u64::from_le_bytes(encoded_decoded_size);
+        let index_size = frame_index_encoded_size(decoded_size, frame_size);
+        let encoded_frames_size = usize::try_from(index_size - DECODED_SIZE_SIZE).unwrap();
+        if encoded_frames.len() < encoded_frames_size {
+            return Err(invalid());
+        }
+        let frames = encoded_frames[encoded_frames.len() - encoded_frames_size..]
+            .chunks_exact(16)
+            .map(|entry| {
+                let (offset, size) = entry.split_at(8);
+                (
+                    u64::from_le_bytes(offset.try_into().unwrap()),
+                    u64::from_le_bytes(size.try_into().unwrap()),
+                )
+            })
+            .collect();
+        Ok(Self {
+            decoded_size,
+            frames,
+        })
+    }
+
+    /// Encode the frame index, including the decoded size.
+    fn encode(&self) -> Vec<u8> {
+        let mut encoded_index = Vec::with_capacity(
+            self.frames.len() * usize::try_from(FRAME_INDEX_ENTRY_SIZE).unwrap()
+                + usize::try_from(DECODED_SIZE_SIZE).unwrap(),
+        );
+        for (offset, size) in &self.frames {
+            encoded_index.extend_from_slice(&offset.to_le_bytes());
+            encoded_index.extend_from_slice(&size.to_le_bytes());
+        }
+        encoded_index.extend_from_slice(&self.decoded_size.to_le_bytes());
+        encoded_index
+    }
+
+    /// Return the end of the encoded frames, which is the offset of the frame index in the encoded value.
+    fn frames_end(&self) -> u64 {
+        self.frames
+            .iter()
+            .map(|(offset, size)| offset + size)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Return the encoded byte range of `frame`.
+    fn frame_byte_range(&self, frame: usize) -> ByteRange {
+        let (offset, size) = self.frames[frame];
+        ByteRange::FromStart(offset, Some(size))
+    }
+
+    /// Return the decoded byte range of `frame`.
+    fn frame_decoded_range(&self, frame: usize, frame_size: u64) -> Range<u64> {
+        let start = frame as u64 * frame_size;
+        start..(start + frame_size).min(self.decoded_size)
+    }
+
+    /// Return the range of frames intersecting the decoded byte `range`.
+    fn frames_intersecting(range: &Range<u64>, frame_size: u64) -> Range<usize> {
+        if range.is_empty() {
+            return 0..0;
+        }
+        usize::try_from(range.start / frame_size).unwrap()
+            ..usize::try_from(range.end.div_ceil(frame_size)).unwrap()
+    }
+}
+
+/// Return the representation of the output of each of `codecs` for a `decoded_size` byte frame, starting with the frame itself.
+fn frame_representations(
+    codecs: &[Arc<dyn BytesToBytesCodecTraits>],
+    decoded_size: u64,
+) -> Vec<BytesRepresentation> {
+    let mut representations = Vec::with_capacity(codecs.len() + 1);
+    representations.push(BytesRepresentation::FixedSize(decoded_size));
+    for codec in codecs {
+        representations.push(codec.compute_encoded_size(representations.last().unwrap()));
+    }
+    representations
+}
+
+/// Encode `frame` with `codecs`.
+fn encode_frame(
+    codecs: &[Arc<dyn BytesToBytesCodecTraits>],
+    frame: &[u8],
+    options: &CodecOptions,
+) -> Result<Vec<u8>, CodecError> {
+    let mut encoded_frame = Cow::Borrowed(frame);
+    for codec in codecs {
+        encoded_frame = codec.encode(encoded_frame, options)?;
+    }
+    Ok(encoded_frame.into_owned())
+}
+
+/// Decode `encoded_frame` with `codecs`, where the decoded frame has `decoded_size` bytes.
+fn decode_frame(
+    codecs: &[Arc<dyn BytesToBytesCodecTraits>],
+    encoded_frame: RawBytes<'_>,
+    decoded_size: u64,
+    options: &CodecOptions,
+) -> Result<Vec<u8>, CodecError> {
+    let representations = frame_representations(codecs, decoded_size);
+    let mut decoded_frame = encoded_frame;
+    for (codec, representation) in
+        std::iter::zip(codecs.iter().rev(), representations.iter().rev().skip(1))
+    {
+        decoded_frame = codec.decode(decoded_frame, representation, options)?;
+    }
+    if decoded_frame.len() as u64 != decoded_size {
+        return Err(CodecError::UnexpectedChunkDecodedSize(
+            decoded_frame.len(),
+            decoded_size,
+        ));
+    }
+    Ok(decoded_frame.into_owned())
+}
+
+/// Encode `decoded_value` in frames of `frame_size` bytes.
+fn encode_framed(
+    codecs: &[Arc<dyn BytesToBytesCodecTraits>],
+    frame_size: u64,
+    decoded_value: &[u8],
+    options: &CodecOptions,
+) -> Result<(Vec<u8>, FrameIndex), CodecError> {
+    let mut encoded_value = Vec::new();
+    let mut frames = Vec::with_capacity(
+        decoded_value
+            .len()
+            .div_ceil(usize::try_from(frame_size).unwrap()),
+    );
+    for frame in decoded_value.chunks(usize::try_from(frame_size).unwrap()) {
+        let encoded_frame = encode_frame(codecs, frame, options)?;
+        frames.push((encoded_value.len() as u64, encoded_frame.len() as u64));
+        encoded_value.extend_from_slice(&encoded_frame);
+    }
+    let frame_index = FrameIndex {
+        decoded_size: decoded_value.len() as u64,
+        frames,
+    };
+    encoded_value.extend_from_slice(&frame_index.encode());
+    Ok((encoded_value, frame_index))
+}
+
+/// Decode the entire `encoded_value` with frames of `frame_size` bytes.
+fn decode_framed(
+    codecs: &[Arc<dyn BytesToBytesCodecTraits>],
+    frame_size: u64,
+    encoded_value: &[u8],
+    options: &CodecOptions,
+) -> Result<Vec<u8>, CodecError> {
+    let frame_index = FrameIndex::decode(encoded_value, frame_size)?;
+    let mut decoded_value = Vec::with_capacity(usize::try_from(frame_index.decoded_size).unwrap());
+    for frame in 0..frame_index.frames.len() {
+        let encoded_frame = frame_index
+            .frame_byte_range(frame)
+            .to_range_usize(encoded_value.len() as u64);
+        if encoded_frame.end > encoded_value.len() {
+            return Err(CodecError::Other(
+                "the framed codec index is invalid".to_string(),
+            ));
+        }
+        let decoded_range = frame_index.frame_decoded_range(frame, frame_size);
+        decoded_value.extend(decode_frame(
+            codecs,
+            Cow::Borrowed(&encoded_value[encoded_frame]),
+            decoded_range.end - decoded_range.start,
+            options,
+        )?);
+    }
+    Ok(decoded_value)
+}
+
+/// Return the byte range of the encoded frame index of a `decoded_size` byte value, if known.
+fn frame_index_byte_range(decoded_size: Option<u64>, frame_size: u64) -> ByteRange {
+    ByteRange::Suffix(decoded_size.map_or(DECODED_SIZE_SIZE, |decoded_size| {
+        frame_index_encoded_size(decoded_size, frame_size)
+    }))
+}
+
+/// Return the decoded size of an encoded frame index that only includes the decoded size.
+fn encoded_decoded_size(encoded_index: &[u8]) -> Result<u64, CodecError> {
+    encoded_index
+        .last_chunk::<8>()
+        .map(|decoded_size| u64::from_le_bytes(*decoded_size))
+        .ok_or_else(|| CodecError::Other("the framed codec index is invalid".to_string()))
+}
+
+/// Read the frame index from `input_handle`.
+///
+/// Returns [`None`] if there is no encoded value.
+fn read_frame_index(
+    input_handle: &dyn BytesPartialDecoderTraits,
+    decoded_size: Option<u64>,
+    frame_size: u64,
+    options: &CodecOptions,
+) -> Result<Option<FrameIndex>, CodecError> {
+    let decoded_size = if let Some(decoded_size) = decoded_size {
+        decoded_size
+    } else {
+        let Some(encoded_index) = input_handle
+            .partial_decode(&[frame_index_byte_range(None, frame_size)], options)?
+            .map(|mut encoded_index| encoded_index.remove(0))
+        else {
+            return Ok(None);
+        };
+        encoded_decoded_size(&encoded_index)?
+    };
+    input_handle
+        .partial_decode(
+            &[frame_index_byte_range(Some(decoded_size), frame_size)],
+            options,
+        )?
+        .map(|mut encoded_index| FrameIndex::decode(&encoded_index.remove(0), frame_size))
+        .transpose()
+}
+
+#[cfg(feature = "async")]
+/// Asynchronously read the frame index from `input_handle`.
+///
+/// Returns [`None`] if there is no encoded value.
+async fn async_read_frame_index(
+    input_handle: &dyn AsyncBytesPartialDecoderTraits,
+    decoded_size: Option<u64>,
+    frame_size: u64,
+    options: &CodecOptions,
+) -> Result<Option<FrameIndex>, CodecError> {
+    let decoded_size = if let Some(decoded_size) = decoded_size {
+        decoded_size
+    } else {
+        let Some(encoded_index) = input_handle
+            .partial_decode(&[frame_index_byte_range(None, frame_size)], options)
+            .await?
+            .map(|mut encoded_index| encoded_index.remove(0))
+        else {
+            return Ok(None);
+        };
+        encoded_decoded_size(&encoded_index)?
+    };
+    input_handle
+        .partial_decode(
+            &[frame_index_byte_range(Some(decoded_size), frame_size)],
+            options,
+        )
+        .await?
+        .map(|mut encoded_index| FrameIndex::decode(&encoded_index.remove(0), frame_size))
+        .transpose()
+}
+
+/// Return the decoded byte range of each of `decoded_regions` of a `decoded_size` byte value.
+fn decoded_regions_ranges(
+    decoded_regions: &[ByteRange],
+    decoded_size: u64,
+) -> Result<Vec<Range<u64>>, CodecError> {
+    decoded_regions
+        .iter()
+        .map(|decoded_region| {
+            let valid = match decoded_region {
+                ByteRange::FromStart(offset, length) => {
+                    offset + length.unwrap_or(0) <= decoded_size
+                }
+                ByteRange::Suffix(length) => *length <= decoded_size,
+            };
+            if valid {
+                Ok(decoded_region.to_range(decoded_size))
+            } else {
+                Err(CodecError::InvalidByteRangeError(
+                    InvalidByteRangeError::new(*decoded_region, decoded_size),
+                ))
+            }
+        })
+        .collect()
+}
+
+/// Return the fixed decoded size of `decoded_representation`, if known.
+const fn fixed_decoded_size(decoded_representation: &BytesRepresentation) -> Option<u64> {
+    match decoded_representation {
+        BytesRepresentation::FixedSize(size) => Some(*size),
+        BytesRepresentation::BoundedSize(_) | BytesRepresentation::UnboundedSize => None,
+    }
+}
+
+/// Return the sorted indices of the frames intersecting any of the decoded byte `ranges`.
+fn frames_intersecting_ranges(ranges: &[Range<u64>], frame_size: u64) -> Vec<usize> {
+    ranges
+        .iter()
+        .flat_map(|range| FrameIndex::frames_intersecting(range, frame_size))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Decode the `encoded_frames` of `frames`.
+fn decode_frames(
+    codecs: &[Arc<dyn BytesToBytesCodecTraits>],
+    frame_index: &FrameIndex,
+    frame_size: u64,
+    frames: &[usize],
+    encoded_frames: Vec<RawBytes<'_>>,
+    options: &CodecOptions,
+) -> Result<BTreeMap<usize, Vec<u8>>, CodecError> {
+    std::iter::zip(frames, encoded_frames)
+        .map(|(&frame, encoded_frame)| {
+            let decoded_range = frame_index.frame_decoded_range(frame, frame_size);
+            let decoded_frame = decode_frame(
+                codecs,
+                encoded_frame,
+                decoded_range.end - decoded_range.start,
+                options,
+            )?;
+            Ok((frame, decoded_frame))
+        })
+        .collect()
+}
+
+/// Extract the decoded byte `range` from `decoded_frames`, which must include all frames intersecting `range`.
+fn extract_decoded_range(
+    decoded_frames: &BTreeMap<usize, Vec<u8>>,
+    range: &Range<u64>,
+    frame_size: u64,
+) -> Vec<u8> {
+    let mut decoded = Vec::with_capacity(usize::try_from(range.end - range.start).unwrap());
+    for frame in FrameIndex::frames_intersecting(range, frame_size) {
+        let decoded_frame = &decoded_frames[&frame];
+        let frame_start = frame as u64 * frame_size;
+        let start = usize::try_from(range.start.saturating_sub(frame_start)).unwrap();
+        let end = usize::try_from(range.end - frame_start)
+            .unwrap()
+            .min(decoded_frame.len());
+        decoded.extend_from_slice(&decoded_frame[start..end]);
+    }
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU64;
+
+    use crate::{
+        array::{codec::CodecTraits, ArrayBuilder, DataType, FillValue},
+        array_subset::ArraySubset,
+        storage::{
+            storage_adapter::performance_metrics::PerformanceMetricsStorageAdapter,
+            store::MemoryStore, ReadableStorageTraits,
+        },
+    };
+
+    use super::*;
+
+    fn framed_codec(frame_size: u64) -> FramedCodec {
+        FramedCodec::new(
+            NonZeroU64::new(frame_size).unwrap(),
+            vec![
+                #[cfg(feature = "gzip")]
+                Arc::new(crate::array::codec::GzipCodec::new(5).unwrap()),
+            ],
+        )
+    }
+
+    #[test]
+    #[cfg(feature = "gzip")]
+    fn codec_framed_configuration() {
+        const JSON_GZIP: &str = r#"{
+            "frame_size": 100,
+            "codecs": [{"name": "gzip", "configuration": {"level": 5}}]
+        }"#;
+        let configuration: FramedCodecConfiguration = serde_json::from_str(JSON_GZIP).unwrap();
+        let codec = FramedCodec::new_with_configuration(&configuration).unwrap();
+        assert_eq!(codec.frame_size(), 100);
+        assert_eq!(codec.codecs().len(), 1);
+        let metadata = codec.create_metadata().unwrap();
+        assert_eq!(
+            metadata
+                .to_configuration::<FramedCodecConfiguration>()
+                .unwrap(),
+            configuration
+        );
+    }
+
+    #[test]
+    fn codec_framed_configuration_invalid() {
+        let configuration: FramedCodecConfiguration = serde_json::from_str(
+            r#"{
+            "frame_size": 100,
+            "codecs": [{"name": "bytes", "configuration": {"endian": "little"}}]
+        }"#,
+        )
+        .unwrap();
+        assert!(FramedCodec::new_with_configuration(&configuration).is_err());
+    }
+
+    #[test]
+    fn codec_framed_round_trip() {
+        let codec = framed_codec(100);
+        for size in [0, 1, 100, 250] {
+            let bytes: Vec<u8> = (0..size).map(|i| u8::try_from(i % 7).unwrap()).collect();
+            let bytes_representation = BytesRepresentation::FixedSize(size);
+            let encoded = codec
+                .encode(Cow::Borrowed(&bytes), &CodecOptions::default())
+                .unwrap();
+            let frame_index = FrameIndex::decode(&encoded, 100).unwrap();
+            assert_eq!(frame_index.decoded_size, size);
+            assert_eq!(frame_index.frames.len() as u64, size.div_ceil(100));
+            let decoded = codec
+                .decode(encoded, &bytes_representation, &CodecOptions::default())
+                .unwrap();
+            assert_eq!(bytes, decoded.to_vec());
+        }
+    }
+
+    #[test]
+    fn codec_framed_partial_decode() {
+        let codec = Arc::new(framed_codec(100));
+        let bytes: Vec<u8> = (0..250).map(|i| u8::try_from(i % 251).unwrap()).collect();
+        let encoded = codec
+            .encode(Cow::Borrowed(&bytes), &CodecOptions::default())
+            .unwrap();
+        let decoded_regions = [
+            ByteRange::FromStart(90, Some(20)),
+            ByteRange::FromStart(200, None),
+            ByteRange::Suffix(10),
+            ByteRange::FromStart(5, Some(0)),
+        ];
+        for decoded_representation in [
+            BytesRepresentation::FixedSize(250),
+            BytesRepresentation::UnboundedSize,
+        ] {
+            let input_handle = Arc::new(std::io::Cursor::new(encoded.to_vec()));
+            let partial_decoder = codec
+                .clone()
+                .partial_decoder(
+                    input_handle,
+                    &decoded_representation,
+                    &CodecOptions::default(),
+                )
+                .unwrap();
+            let decoded = partial_decoder
+                .partial_decode(&decoded_regions, &CodecOptions::default())
+                .unwrap()
+                .unwrap();
+            assert_eq!(decoded[0].as_ref(), &bytes[90..110]);
+            assert_eq!(decoded[1].as_ref(), &bytes[200..]);
+            assert_eq!(decoded[2].as_ref(), &bytes[240..]);
+            assert!(decoded[3].is_empty());
+            assert!(partial_decoder
+                .partial_decode(
+                    &[ByteRange::FromStart(240, Some(20))],
+                    &CodecOptions::default()
+                )
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn codec_framed_array_partial_encode() -> Result<(), Box<dyn std::error::Error>> {
+        let store = Arc::new(MemoryStore::default());
+        let store_perf = Arc::new(PerformanceMetricsStorageAdapter::new(store.clone()));
+        let array = ArrayBuilder::new(
+            vec![16, 16], // array shape
+            DataType::UInt16,
+            vec![16, 16].try_into()?, // regular chunk shape
+            FillValue::from(0u16),
+        )
+        .bytes_to_bytes_codecs(vec![Arc::new(framed_codec(64))])
+        .build(store_perf.clone(), "/array")?;
+        let key = array.chunk_key(&[0, 0]);
+        let options = CodecOptions::builder()
+            .experimental_partial_encoding(true)
+            .build();
+
+        // Each 64 byte frame holds 2 rows of the chunk
+        let mut elements: Vec<u16> = (0..256).collect();
+        array.store_array_subset_elements_opt(&array.subset_all(), &elements, &options)?;
+        let encoded_size = store.get(&key)?.unwrap().len();
+
+        // Only the updated frame and the frame index are appended to the chunk
+        store_perf.reset();
+        array.store_array_subset_elements_opt::<u16>(
+            &ArraySubset::new_with_ranges(&[5..6, 3..5]),
+            &[1000, 1001],
+            &options,
+        )?;
+        elements[5 * 16 + 3] = 1000;
+        elements[5 * 16 + 4] = 1001;
+        assert_eq!(store_perf.writes(), 1);
+        let encoded = store.get(&key)?.unwrap();
+        let frame_index = FrameIndex::decode(&encoded, 64)?;
+        assert_eq!(
+            encoded.len(),
+            encoded_size + usize::try_from(frame_index.frames[2].1).unwrap()
+        );
+        assert_eq!(
+            array.retrieve_array_subset_elements::<u16>(&array.subset_all())?,
+            elements
+        );
+
+        // The chunk is erased if it becomes entirely the fill value
+        array.store_array_subset_elements_opt::<u16>(
+            &ArraySubset::new_with_ranges(&[0..16, 0..8]),
+            &[0; 128],
+            &options,
+        )?;
+        array.store_array_subset_elements_opt::<u16>(
+            &ArraySubset::new_with_ranges(&[0..16, 8..16]),
+            &[0; 128],
+            &options,
+        )?;
+        assert!(store.get(&key)?.is_none());
+        Ok(())
+    }
+}
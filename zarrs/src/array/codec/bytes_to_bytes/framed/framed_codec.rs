@@ -0,0 +1,236 @@
+use std::{borrow::Cow, num::NonZeroU64, sync::Arc};
+
+use crate::{
+    array::{
+        codec::{
+            BytesPartialDecoderTraits, BytesPartialEncoderTraits, BytesToBytesCodecTraits, Codec,
+            CodecError, CodecOptions, CodecTraits, RecommendedConcurrency,
+        },
+        ArrayMetadataOptions, BytesRepresentation, RawBytes,
+    },
+    config::global_config,
+    metadata::v3::MetadataV3,
+    plugin::PluginCreateError,
+};
+
+#[cfg(feature = "async")]
+use crate::array::codec::AsyncBytesPartialDecoderTraits;
+
+use super::{
+    decode_framed, encode_framed, frame_index_encoded_size, frame_representations,
+    framed_partial_decoder, framed_partial_encoder, FramedCodecConfiguration,
+    FramedCodecConfigurationV1, IDENTIFIER,
+};
+
+/// A `framed` codec implementation.
+#[derive(Clone, Debug)]
+pub struct FramedCodec {
+    frame_size: NonZeroU64,
+    codecs: Vec<Arc<dyn BytesToBytesCodecTraits>>,
+}
+
+impl FramedCodec {
+    /// Create a new `framed` codec that encodes frames of `frame_size` bytes with `codecs`.
+    #[must_use]
+    pub fn new(frame_size: NonZeroU64, codecs: Vec<Arc<dyn BytesToBytesCodecTraits>>) -> Self {
+        Self { frame_size, codecs }
+    }
+
+    /// Create a new `framed` codec from configuration.
+    ///
+    /// # Errors
+    /// Returns a [`PluginCreateError`] if a codec of the configuration cannot be created or is not a bytes to bytes codec.
+    pub fn new_with_configuration(
+        configuration: &FramedCodecConfiguration,
+    ) -> Result<Self, PluginCreateError> {
+        let FramedCodecConfiguration::V1(configuration) = configuration;
+        let codecs = configuration
+            .codecs
+            .iter()
+            .map(|metadata| match Codec::from_metadata(metadata)? {
+                Codec::BytesToBytes(codec) => Ok(codec),
+                _ => Err(PluginCreateError::Other(format!(
+                    "the {} codec of the framed codec is not a bytes to bytes codec",
+                    metadata.name()
+                ))),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::new(configuration.frame_size, codecs))
+    }
+
+    /// Return the decoded size of a frame in bytes.
+    #[must_use]
+    pub const fn frame_size(&self) -> u64 {
+        self.frame_size.get()
+    }
+
+    /// Return the codecs that encode each frame.
+    #[must_use]
+    pub fn codecs(&self) -> &[Arc<dyn BytesToBytesCodecTraits>] {
+        &self.codecs
+    }
+}
+
+impl CodecTraits for FramedCodec {
+    fn create_metadata_opt(&self, options: &ArrayMetadataOptions) -> Option<MetadataV3> {
+        let configuration = FramedCodecConfigurationV1 {
+            frame_size: self.frame_size,
+            codecs: self
+                .codecs
+                .iter()
+                .filter_map(|codec| codec.create_metadata_opt(options))
+                .collect(),
+        };
+        Some(
+            MetadataV3::new_with_serializable_configuration(
+                global_config()
+                    .experimental_codec_names()
+                    .get(IDENTIFIER)
+                    .expect("experimental codec identifier in global map"),
+                &configuration,
+            )
+            .unwrap(),
+        )
+    }
+
+    fn partial_decoder_should_cache_input(&self) -> bool {
+        false
+    }
+
+    fn partial_decoder_decodes_all(&self) -> bool {
+        false
+    }
+}
+
+#[cfg_attr(feature = "async", async_trait::async_trait)]
+impl BytesToBytesCodecTraits for FramedCodec {
+    fn dynamic(self: Arc<Self>) -> Arc<dyn BytesToBytesCodecTraits> {
+        self as Arc<dyn BytesToBytesCodecTraits>
+    }
+
+    fn recommended_concurrency(
+        &self,
+        _decoded_representation: &BytesRepresentation,
+    ) -> Result<RecommendedConcurrency, CodecError> {
+        Ok(RecommendedConcurrency::new_maximum(1))
+    }
+
+    fn encode<'a>(
+        &self,
+        decoded_value: RawBytes<'a>,
+        options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        let (encoded_value, _frame_index) =
+            encode_framed(&self.codecs, self.frame_size(), &decoded_value, options)?;
+        Ok(Cow::Owned(encoded_value))
+    }
+
+    fn decode<'a>(
+        &self,
+        encoded_value: RawBytes<'a>,
+        _decoded_representation: &BytesRepresentation,
+        options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        Ok(Cow::Owned(decode_framed(
+            &self.codecs,
+            self.frame_size(),
+            &encoded_value,
+            options,
+        )?))
+    }
+
+    fn partial_decoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn BytesPartialDecoderTraits>,
+        decoded_representation: &BytesRepresentation,
+        options: &CodecOptions,
+    ) -> Result<Arc<dyn BytesPartialDecoderTraits>, CodecError> {
+        Ok(Arc::new(framed_partial_decoder::FramedPartialDecoder::new(
+            input_handle,
+            decoded_representation,
+            self.frame_size(),
+            self.codecs.clone(),
+            options,
+        )?))
+    }
+
+    fn partial_encoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn BytesPartialDecoderTraits>,
+        output_handle: Arc<dyn BytesPartialEncoderTraits>,
+        decoded_representation: &BytesRepresentation,
+        options: &CodecOptions,
+    ) -> Result<Arc<dyn BytesPartialEncoderTraits>, CodecError> {
+        Ok(Arc::new(framed_partial_encoder::FramedPartialEncoder::new(
+            input_handle,
+            output_handle,
+            decoded_representation,
+            self.frame_size(),
+            self.codecs.clone(),
+            options,
+        )?))
+    }
+
+    #[cfg(feature = "async")]
+    async fn async_partial_decoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+        decoded_representation: &BytesRepresentation,
+        options: &CodecOptions,
+    ) -> Result<Arc<dyn AsyncBytesPartialDecoderTraits>, CodecError> {
+        Ok(Arc::new(
+            framed_partial_decoder::AsyncFramedPartialDecoder::new(
+                input_handle,
+                decoded_representation,
+                self.frame_size(),
+                self.codecs.clone(),
+                options,
+            )
+            .await?,
+        ))
+    }
+
+    fn compute_encoded_size(
+        &self,
+        decoded_representation: &BytesRepresentation,
+    ) -> BytesRepresentation {
+        let frame_size = self.frame_size();
+        let Some(decoded_size) = decoded_representation.size() else {
+            return BytesRepresentation::UnboundedSize;
+        };
+        let num_frames = decoded_size.div_ceil(frame_size);
+        let last_frame_size = decoded_size - num_frames.saturating_sub(1) * frame_size;
+        let frame_encoded_size = |decoded_size: u64| {
+            frame_representations(&self.codecs, decoded_size)
+                .last()
+                .copied()
+                .unwrap()
+        };
+        let frame_encoded_sizes = [
+            (num_frames.saturating_sub(1), frame_encoded_size(frame_size)),
+            (
+                u64::from(num_frames > 0),
+                frame_encoded_size(last_frame_size),
+            ),
+        ];
+        let mut encoded_size = frame_index_encoded_size(decoded_size, frame_size);
+        let mut fixed = matches!(decoded_representation, BytesRepresentation::FixedSize(_));
+        for (count, frame_encoded_size) in frame_encoded_sizes {
+            match frame_encoded_size {
+                BytesRepresentation::FixedSize(size) => encoded_size += count * size,
+                BytesRepresentation::BoundedSize(size) => {
+                    fixed = false;
+                    encoded_size += count * size;
+                }
+                BytesRepresentation::UnboundedSize => {
+                    return BytesRepresentation::UnboundedSize;
+                }
+            }
+        }
+        if fixed {
+            BytesRepresentation::FixedSize(encoded_size)
+        } else {
+            BytesRepresentation::BoundedSize(encoded_size)
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use std::{borrow::Cow, sync::Arc};
+
+use crate::{
+    array::{
+        codec::{BytesPartialDecoderTraits, BytesToBytesCodecTraits, CodecError, CodecOptions},
+        BytesRepresentation, RawBytes,
+    },
+    byte_range::ByteRange,
+};
+
+#[cfg(feature = "async")]
+use crate::array::codec::AsyncBytesPartialDecoderTraits;
+
+#[cfg(feature = "async")]
+use super::async_read_frame_index;
+use super::{
+    decode_frames, decoded_regions_ranges, extract_decoded_range, fixed_decoded_size,
+    frames_intersecting_ranges, read_frame_index, FrameIndex,
+};
+
+/// Decode the `encoded_frames` of `frames` and extract `decoded_regions`.
+fn decode_regions<'a>(
+    codecs: &[Arc<dyn BytesToBytesCodecTraits>],
+    frame_index: &FrameIndex,
+    frame_size: u64,
+    decoded_regions: &[ByteRange],
+    frames: &[usize],
+    encoded_frames: Vec<RawBytes<'_>>,
+    options: &CodecOptions,
+) -> Result<Vec<RawBytes<'a>>, CodecError> {
+    let ranges = decoded_regions_ranges(decoded_regions, frame_index.decoded_size)?;
+    let decoded_frames = decode_frames(
+        codecs,
+        frame_index,
+        frame_size,
+        frames,
+        encoded_frames,
+        options,
+    )?;
+    Ok(ranges
+        .iter()
+        .map(|range| Cow::Owned(extract_decoded_range(&decoded_frames, range, frame_size)))
+        .collect())
+}
+
+/// Return the frames intersecting `decoded_regions` and their encoded byte ranges.
+fn regions_frames(
+    frame_index: &FrameIndex,
+    frame_size: u64,
+    decoded_regions: &[ByteRange],
+) -> Result<(Vec<usize>, Vec<ByteRange>), CodecError> {
+    let ranges = decoded_regions_ranges(decoded_regions, frame_index.decoded_size)?;
+    let frames = frames_intersecting_ranges(&ranges, frame_size);
+    let byte_ranges = frames
+        .iter()
+        .map(|&frame| frame_index.frame_byte_range(frame))
+        .collect();
+    Ok((frames, byte_ranges))
+}
+
+/// Partial decoder for the `framed` codec.
+pub(crate) struct FramedPartialDecoder {
+    input_handle: Arc<dyn BytesPartialDecoderTraits>,
+    frame_size: u64,
+    codecs: Vec<Arc<dyn BytesToBytesCodecTraits>>,
+    frame_index: Option<FrameIndex>,
+}
+
+impl FramedPartialDecoder {
+    /// Create a new partial decoder for the `framed` codec.
+    pub(crate) fn new(
+        input_handle: Arc<dyn BytesPartialDecoderTraits>,
+        decoded_representation: &BytesRepresentation,
+        frame_size: u64,
+        codecs: Vec<Arc<dyn BytesToBytesCodecTraits>>,
+        options: &CodecOptions,
+    ) -> Result<Self, CodecError> {
+        let frame_index = read_frame_index(
+            &*input_handle,
+            fixed_decoded_size(decoded_representation),
+            frame_size,
+            options,
+        )?;
+        Ok(Self {
+            input_handle,
+            frame_size,
+            codecs,
+            frame_index,
+        })
+    }
+}
+
+impl BytesPartialDecoderTraits for FramedPartialDecoder {
+    fn partial_decode(
+        &self,
+        decoded_regions: &[ByteRange],
+        options: &CodecOptions,
+    ) -> Result<Option<Vec<RawBytes<'_>>>, CodecError> {
+        let Some(frame_index) = &self.frame_index else {
+            return Ok(None);
+        };
+
+        // Only retrieve and decode the frames intersecting the decoded regions
+        let (frames, byte_ranges) = regions_frames(frame_index, self.frame_size, decoded_regions)?;
+        let Some(encoded_frames) = self.input_handle.partial_decode(&byte_ranges, options)? else {
+            return Ok(None);
+        };
+        Ok(Some(decode_regions(
+            &self.codecs,
+            frame_index,
+            self.frame_size,
+            decoded_regions,
+            &frames,
+            encoded_frames,
+            options,
+        )?))
+    }
+}
+
+#[cfg(feature = "async")]
+/// Asynchronous partial decoder for the `framed` codec.
+pub(crate) struct AsyncFramedPartialDecoder {
+    input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+    frame_size: u64,
+    codecs: Vec<Arc<dyn BytesToBytesCodecTraits>>,
+    frame_index: Option<FrameIndex>,
+}
+
+#[cfg(feature = "async")]
+impl AsyncFramedPartialDecoder {
+    /// Create a new partial decoder for the `framed` codec.
+    pub(crate) async fn new(
+        input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+        decoded_representation: &BytesRepresentation,
+        frame_size: u64,
+        codecs: Vec<Arc<dyn BytesToBytesCodecTraits>>,
+        options: &CodecOptions,
+    ) -> Result<Self, CodecError> {
+        let frame_index = async_read_frame_index(
+            &*input_handle,
+            fixed_decoded_size(decoded_representation),
+            frame_size,
+            options,
+        )
+        .await?;
+        Ok(Self {
+            input_handle,
+            frame_size,
+            codecs,
+            frame_index,
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl AsyncBytesPartialDecoderTraits for AsyncFramedPartialDecoder {
+    async fn partial_decode(
+        &self,
+        decoded_regions: &[ByteRange],
+        options: &CodecOptions,
+    ) -> Result<Option<Vec<RawBytes<'_>>>, CodecError> {
+        let Some(frame_index) = &self.frame_index else {
+            return Ok(None);
+        };
+
+        // Only retrieve and decode the frames intersecting the decoded regions
+        let (frames, byte_ranges) = regions_frames(frame_index, self.frame_size, decoded_regions)?;
+        let Some(encoded_frames) = self
+            .input_handle
+            .partial_decode(&byte_ranges, options)
+            .await?
+        else {
+            return Ok(None);
+        };
+        Ok(Some(decode_regions(
+            &self.codecs,
+            frame_index,
+            self.frame_size,
+            decoded_regions,
+            &frames,
+            encoded_frames,
+            options,
+        )?))
+    }
+}
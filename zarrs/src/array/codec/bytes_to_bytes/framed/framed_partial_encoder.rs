@@ -0,0 +1,207 @@
+use std::{
+    borrow::Cow,
+    ops::Range,
+    sync::{Arc, Mutex},
+};
+
+use zarrs_storage::byte_range::ByteOffset;
+
+use crate::array::{
+    codec::{
+        BytesPartialDecoderTraits, BytesPartialEncoderTraits, BytesToBytesCodecTraits, CodecError,
+        CodecOptions,
+    },
+    BytesRepresentation, RawBytes,
+};
+
+use super::{
+    decode_framed, decode_frames, encode_frame, encode_framed, fixed_decoded_size,
+    frames_intersecting_ranges, read_frame_index, FrameIndex,
+};
+
+/// Partial encoder for the `framed` codec.
+///
+/// Only the frames intersecting the updated byte ranges are decoded, updated, and encoded.
+/// The updated frames and frame index are appended to the encoded value.
+/// The entire value is encoded and written instead if
+///  - it does not exist,
+///  - the update extends it,
+///  - the update intersects all of its frames, or
+///  - more than half of the encoded value would be unreferenced frames.
+pub(crate) struct FramedPartialEncoder {
+    input_handle: Arc<dyn BytesPartialDecoderTraits>,
+    output_handle: Arc<dyn BytesPartialEncoderTraits>,
+    frame_size: u64,
+    codecs: Vec<Arc<dyn BytesToBytesCodecTraits>>,
+    frame_index: Mutex<Option<FrameIndex>>,
+}
+
+impl FramedPartialEncoder {
+    /// Create a new partial encoder for the `framed` codec.
+    pub(crate) fn new(
+        input_handle: Arc<dyn BytesPartialDecoderTraits>,
+        output_handle: Arc<dyn BytesPartialEncoderTraits>,
+        decoded_representation: &BytesRepresentation,
+        frame_size: u64,
+        codecs: Vec<Arc<dyn BytesToBytesCodecTraits>>,
+        options: &CodecOptions,
+    ) -> Result<Self, CodecError> {
+        let frame_index = read_frame_index(
+            &*input_handle,
+            fixed_decoded_size(decoded_representation),
+            frame_size,
+            options,
+        )?;
+        Ok(Self {
+            input_handle,
+            output_handle,
+            frame_size,
+            codecs,
+            frame_index: Mutex::new(frame_index),
+        })
+    }
+
+    /// Decode the entire value, update it, and encode and write the entire value.
+    fn partial_encode_all(
+        &self,
+        frame_index: &mut Option<FrameIndex>,
+        offsets_and_bytes: &[(ByteOffset, RawBytes<'_>)],
+        options: &CodecOptions,
+    ) -> Result<(), CodecError> {
+        let mut decoded_value = if frame_index.is_some() {
+            let encoded_value = self.input_handle.decode(options)?.ok_or_else(|| {
+                CodecError::Other("the framed codec encoded value is missing".to_string())
+            })?;
+            decode_framed(&self.codecs, self.frame_size, &encoded_value, options)?
+        } else {
+            vec![]
+        };
+
+        // The decoded value must be resized to the maximum byte range end
+        let decoded_value_len = offsets_and_bytes
+            .iter()
+            .map(|(offset, bytes)| usize::try_from(offset + bytes.len() as u64).unwrap())
+            .max()
+            .unwrap_or_default()
+            .max(decoded_value.len());
+        decoded_value.resize(decoded_value_len, 0);
+        for (offset, bytes) in offsets_and_bytes {
+            let start = usize::try_from(*offset).unwrap();
+            decoded_value[start..start + bytes.len()].copy_from_slice(bytes);
+        }
+
+        let (encoded_value, frame_index_new) =
+            encode_framed(&self.codecs, self.frame_size, &decoded_value, options)?;
+        if frame_index.is_some() {
+            // The existing value may be longer than the encoded value
+            self.output_handle.erase()?;
+        }
+        self.output_handle
+            .partial_encode(&[(0, Cow::Owned(encoded_value))], options)?;
+        *frame_index = Some(frame_index_new);
+        Ok(())
+    }
+}
+
+impl BytesPartialEncoderTraits for FramedPartialEncoder {
+    fn erase(&self) -> Result<(), CodecError> {
+        let mut frame_index = self
+            .frame_index
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        self.output_handle.erase()?;
+        *frame_index = None;
+        Ok(())
+    }
+
+    fn partial_encode(
+        &self,
+        offsets_and_bytes: &[(ByteOffset, RawBytes<'_>)],
+        options: &CodecOptions,
+    ) -> Result<(), CodecError> {
+        let mut frame_index_guard = self
+            .frame_index
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let ranges: Vec<Range<u64>> = offsets_and_bytes
+            .iter()
+            .map(|(offset, bytes)| *offset..offset + bytes.len() as u64)
+            .collect();
+        let frames = frames_intersecting_ranges(&ranges, self.frame_size);
+
+        let Some(frame_index) = frame_index_guard.as_ref() else {
+            return self.partial_encode_all(&mut frame_index_guard, offsets_and_bytes, options);
+        };
+        let extends = ranges
+            .iter()
+            .any(|range| range.end > frame_index.decoded_size);
+        if extends
+            || frames.len() == frame_index.frames.len()
+            || !self.output_handle.supports_partial_encode()
+        {
+            return self.partial_encode_all(&mut frame_index_guard, offsets_and_bytes, options);
+        }
+
+        // Only retrieve and decode the frames intersecting the updated byte ranges
+        let byte_ranges: Vec<_> = frames
+            .iter()
+            .map(|&frame| frame_index.frame_byte_range(frame))
+            .collect();
+        let encoded_frames = self
+            .input_handle
+            .partial_decode(&byte_ranges, options)?
+            .ok_or_else(|| {
+                CodecError::Other("the framed codec encoded value is missing".to_string())
+            })?;
+        let mut decoded_frames = decode_frames(
+            &self.codecs,
+            frame_index,
+            self.frame_size,
+            &frames,
+            encoded_frames,
+            options,
+        )?;
+
+        // Update the frames
+        for (range, (_offset, bytes)) in std::iter::zip(&ranges, offsets_and_bytes) {
+            for frame in FrameIndex::frames_intersecting(range, self.frame_size) {
+                let decoded_frame = decoded_frames.get_mut(&frame).unwrap();
+                let frame_start = frame as u64 * self.frame_size;
+                let start = range.start.max(frame_start);
+                let end = range.end.min(frame_start + decoded_frame.len() as u64);
+                let frame_range = usize::try_from(start - frame_start).unwrap()
+                    ..usize::try_from(end - frame_start).unwrap();
+                let bytes_range = usize::try_from(start - range.start).unwrap()
+                    ..usize::try_from(end - range.start).unwrap();
+                decoded_frame[frame_range].copy_from_slice(&bytes[bytes_range]);
+            }
+        }
+
+        // Append the updated frames and the updated frame index
+        let offset = frame_index.frames_end();
+        let mut frame_index_new = frame_index.clone();
+        let mut appended = Vec::new();
+        for (frame, decoded_frame) in decoded_frames {
+            let encoded_frame = encode_frame(&self.codecs, &decoded_frame, options)?;
+            frame_index_new.frames[frame] =
+                (offset + appended.len() as u64, encoded_frame.len() as u64);
+            appended.extend_from_slice(&encoded_frame);
+        }
+
+        // Encode the entire value instead if most of it would be unreferenced
+        let referenced: u64 = frame_index_new.frames.iter().map(|(_, size)| size).sum();
+        if offset + appended.len() as u64 > 2 * referenced {
+            return self.partial_encode_all(&mut frame_index_guard, offsets_and_bytes, options);
+        }
+
+        appended.extend_from_slice(&frame_index_new.encode());
+        self.output_handle
+            .partial_encode(&[(offset, Cow::Owned(appended))], options)?;
+        *frame_index_guard = Some(frame_index_new);
+        Ok(())
+    }
+
+    fn supports_partial_encode(&self) -> bool {
+        true
+    }
+}
@@ -4,6 +4,7 @@ use std::{borrow::Cow, sync::Arc};
 use crate::{
     array::{
         codec::{
+            backend::{backend_decode, backend_encode},
             BytesPartialDecoderTraits, BytesPartialEncoderDefault, BytesPartialEncoderTraits,
             BytesToBytesCodecTraits, CodecError, CodecOptions, CodecTraits,
         },
@@ -80,8 +81,12 @@ impl BytesToBytesCodecTraits for GDeflateCodec {
     fn encode<'a>(
         &self,
         decoded_value: RawBytes<'a>,
-        _options: &CodecOptions,
+        options: &CodecOptions,
     ) -> Result<RawBytes<'a>, CodecError> {
+        if let Some(encoded_value) = backend_encode(self, &decoded_value, options)? {
+            return Ok(encoded_value);
+        }
+
         let compressor = GDeflateCompressor::new(self.compression_level)
             .map_err(|err| CodecError::Other(err.to_string()))?;
         let (page_sizes, encoded_bytes) = compressor
@@ -112,9 +117,15 @@ impl BytesToBytesCodecTraits for GDeflateCodec {
     fn decode<'a>(
         &self,
         encoded_value: RawBytes<'a>,
-        _decoded_representation: &BytesRepresentation,
-        _options: &CodecOptions,
+        decoded_representation: &BytesRepresentation,
+        options: &CodecOptions,
     ) -> Result<RawBytes<'a>, CodecError> {
+        if let Some(decoded_value) =
+            backend_decode(self, &encoded_value, decoded_representation, options)?
+        {
+            return Ok(decoded_value);
+        }
+
         Ok(Cow::Owned(gdeflate_decode(&encoded_value)?))
     }
 
@@ -97,6 +97,53 @@ mod tests {
         assert_eq!(bytes, decoded.to_vec());
     }
 
+    #[test]
+    fn codec_gzip_round_trip_streaming() {
+        use std::io::{Read, Write};
+
+        use crate::storage::{store::MemoryStore, ReadableStorageTraits, WritableStorageTraits};
+
+        let elements: Vec<u16> = (0..32).collect();
+        let bytes = crate::array::transmute_to_bytes_vec(elements);
+        let bytes_representation = BytesRepresentation::FixedSize(bytes.len() as u64);
+
+        let configuration: GzipCodecConfiguration = serde_json::from_str(JSON_VALID).unwrap();
+        let codec = GzipCodec::new_with_configuration(&configuration);
+        assert!(codec.supports_streaming());
+
+        let store = MemoryStore::new();
+        let key = "c".try_into().unwrap();
+        let mut writer = codec
+            .encode_writer(store.set_writer(&key).unwrap(), &CodecOptions::default())
+            .unwrap();
+        for chunk in bytes.chunks(7) {
+            writer.write_all(chunk).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let encoded = store.get(&key).unwrap().unwrap();
+        let decoded = codec
+            .decode(
+                Cow::Borrowed(&encoded),
+                &bytes_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(bytes, decoded.to_vec());
+
+        let mut decoded = Vec::new();
+        codec
+            .decode_reader(
+                store.get_reader(&key).unwrap().unwrap(),
+                &bytes_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap()
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(bytes, decoded);
+    }
+
     #[test]
     fn codec_gzip_partial_decode() {
         let elements: Vec<u16> = (0..8).collect();
@@ -1,6 +1,6 @@
 use std::{
     borrow::Cow,
-    io::{Cursor, Read},
+    io::{Cursor, Read, Write},
     sync::Arc,
 };
 
@@ -15,6 +15,7 @@ use crate::{
         ArrayMetadataOptions, BytesRepresentation, RawBytes,
     },
     metadata::v3::MetadataV3,
+    storage::{StorageError, StoreValueReader, StoreValueWriter},
 };
 
 #[cfg(feature = "async")]
@@ -107,6 +108,30 @@ impl BytesToBytesCodecTraits for GzipCodec {
         Ok(Cow::Owned(out))
     }
 
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    fn decode_reader<'a>(
+        &self,
+        encoded_value: StoreValueReader<'a>,
+        _decoded_representation: &BytesRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<StoreValueReader<'a>, CodecError> {
+        Ok(Box::new(flate2::read::GzDecoder::new(encoded_value)))
+    }
+
+    fn encode_writer<'a>(
+        &'a self,
+        encoded_value: Box<dyn StoreValueWriter + 'a>,
+        _options: &CodecOptions,
+    ) -> Result<Box<dyn StoreValueWriter + 'a>, CodecError> {
+        Ok(Box::new(GzipEncodeWriter(flate2::write::GzEncoder::new(
+            encoded_value,
+            flate2::Compression::new(self.compression_level.as_u32()),
+        ))))
+    }
+
     fn partial_decoder(
         self: Arc<Self>,
         r: Arc<dyn BytesPartialDecoderTraits>,
@@ -159,3 +184,22 @@ impl BytesToBytesCodecTraits for GzipCodec {
             })
     }
 }
+
+/// A [`StoreValueWriter`] that streams bytes through a `gzip` encoder.
+struct GzipEncodeWriter<'a>(flate2::write::GzEncoder<Box<dyn StoreValueWriter + 'a>>);
+
+impl Write for GzipEncodeWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl StoreValueWriter for GzipEncodeWriter<'_> {
+    fn finish(self: Box<Self>) -> Result<(), StorageError> {
+        self.0.finish()?.finish()
+    }
+}
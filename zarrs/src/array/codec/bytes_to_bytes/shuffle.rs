@@ -0,0 +1,250 @@
+//! The `shuffle` bytes to bytes codec.
+//!
+//! Rearranges the bytes of a sequence of fixed size elements, such that the `j`th bytes of every element are stored contiguously.
+//! It is a lossless size-preserving transform that improves the compression ratio of numerical data when followed by a compression codec, such as `gzip` or `zstd`.
+//!
+//! This codec is compatible with the `numcodecs` `shuffle` codec of Zarr V2 arrays, which can be a filter or a compressor.
+//! It is independent of the shuffle option of the `blosc` codec.
+//!
+//! Any trailing bytes of an incomplete element are stored verbatim.
+//! Only the bytes of the elements that intersect the requested byte ranges are retrieved in partial decoding.
+//!
+//! <div class="warning">
+//! This codec is experimental and may be incompatible with other Zarr V3 implementations.
+//! </div>
+//!
+//! This codec requires the `shuffle` feature, which is disabled by default.
+//!
+//! See [`ShuffleCodecConfigurationV1`] for example `JSON` metadata.
+
+mod shuffle_codec;
+mod shuffle_partial_decoder;
+
+use std::sync::Arc;
+
+use crate::{
+    array::codec::{Codec, CodecPlugin},
+    config::global_config,
+    metadata::v3::{array::codec::shuffle, MetadataV3},
+    plugin::{PluginCreateError, PluginMetadataInvalidError},
+};
+
+pub use crate::metadata::v3::array::codec::shuffle::{
+    ShuffleCodecConfiguration, ShuffleCodecConfigurationV1,
+};
+
+pub use self::shuffle_codec::ShuffleCodec;
+
+pub use shuffle::IDENTIFIER;
+
+// Register the codec.
+inventory::submit! {
+    CodecPlugin::new(IDENTIFIER, is_name_shuffle, create_codec_shuffle)
+}
+
+fn is_name_shuffle(name: &str) -> bool {
+    name.eq(IDENTIFIER)
+        || name
+            == global_config()
+                .experimental_codec_names()
+                .get(IDENTIFIER)
+                .expect("experimental codec identifier in global map")
+}
+
+pub(crate) fn create_codec_shuffle(metadata: &MetadataV3) -> Result<Codec, PluginCreateError> {
+    let configuration: ShuffleCodecConfiguration = metadata
+        .to_configuration()
+        .map_err(|_| PluginMetadataInvalidError::new(IDENTIFIER, "codec", metadata.clone()))?;
+    let codec = Arc::new(ShuffleCodec::new_with_configuration(&configuration));
+    Ok(Codec::BytesToBytes(codec))
+}
+
+/// Shuffle `bytes` of `element_size` byte elements.
+///
+/// Byte `j` of element `i` is moved to `j * num_elements + i`.
+fn shuffle(bytes: &[u8], element_size: usize) -> Vec<u8> {
+    let num_elements = bytes.len() / element_size;
+    let shuffled_size = num_elements * element_size;
+    let mut out = vec![0u8; bytes.len()];
+    for (i, element) in bytes[..shuffled_size]
+        .chunks_exact(element_size)
+        .enumerate()
+    {
+        for (j, byte) in element.iter().enumerate() {
+            out[j * num_elements + i] = *byte;
+        }
+    }
+    out[shuffled_size..].copy_from_slice(&bytes[shuffled_size..]);
+    out
+}
+
+/// Reverse [`shuffle`].
+fn unshuffle(bytes: &[u8], element_size: usize) -> Vec<u8> {
+    let num_elements = bytes.len() / element_size;
+    let shuffled_size = num_elements * element_size;
+    let mut out = vec![0u8; bytes.len()];
+    if num_elements > 0 {
+        for (j, row) in bytes[..shuffled_size]
+            .chunks_exact(num_elements)
+            .enumerate()
+        {
+            for (i, byte) in row.iter().enumerate() {
+                out[i * element_size + j] = *byte;
+            }
+        }
+    }
+    out[shuffled_size..].copy_from_slice(&bytes[shuffled_size..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{borrow::Cow, num::NonZeroUsize, sync::Arc};
+
+    use crate::{
+        array::{
+            codec::{BytesToBytesCodecTraits, CodecOptions, CodecTraits},
+            BytesRepresentation,
+        },
+        byte_range::ByteRange,
+    };
+
+    use super::*;
+
+    const JSON_VALID: &str = r#"{
+        "element_size": 4
+    }"#;
+
+    fn test_bytes(size: usize) -> Vec<u8> {
+        (0..size)
+            .map(|i| u8::try_from((i * 7919 + i / 3) % 256).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn codec_shuffle_configuration() {
+        let configuration: ShuffleCodecConfiguration = serde_json::from_str(JSON_VALID).unwrap();
+        let codec = ShuffleCodec::new_with_configuration(&configuration);
+        assert_eq!(codec.element_size(), 4);
+        let metadata = codec.create_metadata().unwrap();
+        assert_eq!(
+            metadata
+                .to_configuration::<ShuffleCodecConfiguration>()
+                .unwrap(),
+            configuration
+        );
+    }
+
+    #[test]
+    fn codec_shuffle_shuffled_u32() {
+        let elements: Vec<u32> = vec![0x0403_0201, 0x0807_0605, 0x0C0B_0A09];
+        let mut bytes = crate::array::transmute_to_bytes_vec(elements);
+        bytes.extend([0xFE, 0xFF]);
+        let encoded = shuffle(&bytes, 4);
+        assert_eq!(
+            encoded,
+            vec![1, 5, 9, 2, 6, 10, 3, 7, 11, 4, 8, 12, 0xFE, 0xFF]
+        );
+        assert_eq!(unshuffle(&encoded, 4), bytes);
+    }
+
+    #[test]
+    fn codec_shuffle_round_trip() {
+        for element_size in [1, 2, 3, 4, 8, 16] {
+            let codec = ShuffleCodec::new(NonZeroUsize::new(element_size).unwrap());
+            for size in [0, 1, 7, 100, 4096, 65537] {
+                let bytes = test_bytes(size);
+                let bytes_representation = BytesRepresentation::FixedSize(size as u64);
+                let encoded = codec
+                    .encode(Cow::Borrowed(&bytes), &CodecOptions::default())
+                    .unwrap();
+                assert_eq!(encoded.len(), size);
+                let decoded = codec
+                    .decode(encoded, &bytes_representation, &CodecOptions::default())
+                    .unwrap();
+                assert_eq!(bytes, decoded.to_vec());
+            }
+        }
+    }
+
+    #[test]
+    fn codec_shuffle_partial_decode() {
+        let codec = Arc::new(ShuffleCodec::new_with_configuration(
+            &serde_json::from_str(JSON_VALID).unwrap(),
+        ));
+        let size = 4 * 25 + 3;
+        let bytes = test_bytes(size);
+        let bytes_representation = BytesRepresentation::FixedSize(size as u64);
+        let encoded = codec
+            .encode(Cow::Borrowed(&bytes), &CodecOptions::default())
+            .unwrap()
+            .to_vec();
+
+        let decoded_regions = [
+            ByteRange::FromStart(0, Some(1)),
+            ByteRange::FromStart(5, Some(10)),
+            ByteRange::FromStart(60, None),
+            ByteRange::Suffix(2),
+            ByteRange::FromStart(size as u64, Some(0)),
+        ];
+        let input_handle = Arc::new(std::io::Cursor::new(encoded));
+        let partial_decoder = codec
+            .partial_decoder(
+                input_handle,
+                &bytes_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        let decoded_partial_chunk = partial_decoder
+            .partial_decode(&decoded_regions, &CodecOptions::default())
+            .unwrap()
+            .unwrap();
+        for (decoded_region, decoded_partial_chunk) in
+            decoded_regions.iter().zip(decoded_partial_chunk)
+        {
+            assert_eq!(
+                &bytes[decoded_region.to_range_usize(size as u64)],
+                decoded_partial_chunk.as_ref()
+            );
+        }
+
+        assert!(partial_decoder
+            .partial_decode(
+                &[ByteRange::FromStart(size as u64 - 1, Some(2))],
+                &CodecOptions::default()
+            )
+            .is_err());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn codec_shuffle_async_partial_decode() {
+        let codec = Arc::new(ShuffleCodec::new_with_configuration(
+            &serde_json::from_str(JSON_VALID).unwrap(),
+        ));
+        let size = 4 * 100;
+        let bytes = test_bytes(size);
+        let bytes_representation = BytesRepresentation::FixedSize(size as u64);
+        let encoded = codec
+            .encode(Cow::Borrowed(&bytes), &CodecOptions::default())
+            .unwrap()
+            .to_vec();
+
+        let decoded_regions = [ByteRange::FromStart(70, Some(130))];
+        let input_handle = Arc::new(std::io::Cursor::new(encoded));
+        let partial_decoder = codec
+            .async_partial_decoder(
+                input_handle,
+                &bytes_representation,
+                &CodecOptions::default(),
+            )
+            .await
+            .unwrap();
+        let decoded_partial_chunk = partial_decoder
+            .partial_decode(&decoded_regions, &CodecOptions::default())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&bytes[70..200], decoded_partial_chunk[0].as_ref());
+    }
+}
@@ -0,0 +1,164 @@
+use std::{borrow::Cow, num::NonZeroUsize, sync::Arc};
+
+use crate::{
+    array::{
+        codec::{
+            BytesPartialDecoderTraits, BytesPartialEncoderDefault, BytesPartialEncoderTraits,
+            BytesToBytesCodecTraits, CodecError, CodecOptions, CodecTraits, RecommendedConcurrency,
+        },
+        ArrayMetadataOptions, BytesRepresentation, RawBytes,
+    },
+    config::global_config,
+    metadata::v3::MetadataV3,
+};
+
+#[cfg(feature = "async")]
+use crate::array::codec::AsyncBytesPartialDecoderTraits;
+
+use super::{
+    shuffle, shuffle_partial_decoder, unshuffle, ShuffleCodecConfiguration,
+    ShuffleCodecConfigurationV1, IDENTIFIER,
+};
+
+/// A `shuffle` codec implementation.
+#[derive(Clone, Debug)]
+pub struct ShuffleCodec {
+    element_size: NonZeroUsize,
+}
+
+impl ShuffleCodec {
+    /// Create a new `shuffle` codec for elements of `element_size` bytes.
+    #[must_use]
+    pub const fn new(element_size: NonZeroUsize) -> Self {
+        Self { element_size }
+    }
+
+    /// Create a new `shuffle` codec from configuration.
+    #[must_use]
+    pub const fn new_with_configuration(configuration: &ShuffleCodecConfiguration) -> Self {
+        let ShuffleCodecConfiguration::V1(configuration) = configuration;
+        Self::new(configuration.element_size)
+    }
+
+    /// Return the size of an element in bytes.
+    #[must_use]
+    pub const fn element_size(&self) -> usize {
+        self.element_size.get()
+    }
+}
+
+impl CodecTraits for ShuffleCodec {
+    fn create_metadata_opt(&self, _options: &ArrayMetadataOptions) -> Option<MetadataV3> {
+        let configuration = ShuffleCodecConfigurationV1 {
+            element_size: self.element_size,
+        };
+        Some(
+            MetadataV3::new_with_serializable_configuration(
+                global_config()
+                    .experimental_codec_names()
+                    .get(IDENTIFIER)
+                    .expect("experimental codec identifier in global map"),
+                &configuration,
+            )
+            .unwrap(),
+        )
+    }
+
+    fn partial_decoder_should_cache_input(&self) -> bool {
+        false
+    }
+
+    fn partial_decoder_decodes_all(&self) -> bool {
+        false
+    }
+}
+
+#[cfg_attr(feature = "async", async_trait::async_trait)]
+impl BytesToBytesCodecTraits for ShuffleCodec {
+    fn dynamic(self: Arc<Self>) -> Arc<dyn BytesToBytesCodecTraits> {
+        self as Arc<dyn BytesToBytesCodecTraits>
+    }
+
+    fn recommended_concurrency(
+        &self,
+        _decoded_representation: &BytesRepresentation,
+    ) -> Result<RecommendedConcurrency, CodecError> {
+        Ok(RecommendedConcurrency::new_maximum(1))
+    }
+
+    fn encode<'a>(
+        &self,
+        decoded_value: RawBytes<'a>,
+        _options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        if self.element_size() == 1 {
+            return Ok(decoded_value);
+        }
+        Ok(Cow::Owned(shuffle(&decoded_value, self.element_size())))
+    }
+
+    fn decode<'a>(
+        &self,
+        encoded_value: RawBytes<'a>,
+        _decoded_representation: &BytesRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        if self.element_size() == 1 {
+            return Ok(encoded_value);
+        }
+        Ok(Cow::Owned(unshuffle(&encoded_value, self.element_size())))
+    }
+
+    fn partial_decoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn BytesPartialDecoderTraits>,
+        decoded_representation: &BytesRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn BytesPartialDecoderTraits>, CodecError> {
+        Ok(Arc::new(
+            shuffle_partial_decoder::ShufflePartialDecoder::new(
+                input_handle,
+                decoded_representation.size(),
+                self.element_size(),
+            ),
+        ))
+    }
+
+    fn partial_encoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn BytesPartialDecoderTraits>,
+        output_handle: Arc<dyn BytesPartialEncoderTraits>,
+        decoded_representation: &BytesRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn BytesPartialEncoderTraits>, CodecError> {
+        Ok(Arc::new(BytesPartialEncoderDefault::new(
+            input_handle,
+            output_handle,
+            *decoded_representation,
+            self,
+        )))
+    }
+
+    #[cfg(feature = "async")]
+    async fn async_partial_decoder(
+        self: Arc<Self>,
+        input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+        decoded_representation: &BytesRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Arc<dyn AsyncBytesPartialDecoderTraits>, CodecError> {
+        Ok(Arc::new(
+            shuffle_partial_decoder::AsyncShufflePartialDecoder::new(
+                input_handle,
+                decoded_representation.size(),
+                self.element_size(),
+            ),
+        ))
+    }
+
+    fn compute_encoded_size(
+        &self,
+        decoded_representation: &BytesRepresentation,
+    ) -> BytesRepresentation {
+        *decoded_representation
+    }
+}
@@ -0,0 +1,258 @@
+use std::{borrow::Cow, ops::Range, sync::Arc};
+
+use crate::{
+    array::{
+        codec::{BytesPartialDecoderTraits, CodecError, CodecOptions},
+        RawBytes,
+    },
+    byte_range::{extract_byte_ranges, ByteRange, InvalidByteRangeError},
+};
+
+#[cfg(feature = "async")]
+use crate::array::codec::AsyncBytesPartialDecoderTraits;
+
+use super::unshuffle;
+
+/// The encoded bytes required to decode a decoded region.
+struct ShuffleRegion {
+    /// The decoded byte range.
+    byte_range: Range<usize>,
+    /// The indices of the shuffled elements intersecting the decoded byte range.
+    elements: Range<usize>,
+    /// The byte range of the verbatim trailing bytes intersecting the decoded byte range.
+    verbatim: Range<usize>,
+}
+
+impl ShuffleRegion {
+    /// Return the encoded byte ranges of the region in a value with `num_elements` elements of `element_size` bytes.
+    ///
+    /// These are the byte ranges of each of the `element_size` shuffled byte rows, followed by the verbatim trailing bytes.
+    fn encoded_byte_ranges(&self, num_elements: usize, element_size: usize) -> Vec<ByteRange> {
+        let mut byte_ranges = Vec::with_capacity(element_size + 1);
+        if !self.elements.is_empty() {
+            for j in 0..element_size {
+                byte_ranges.push(ByteRange::FromStart(
+                    (j * num_elements + self.elements.start) as u64,
+                    Some(self.elements.len() as u64),
+                ));
+            }
+        }
+        if !self.verbatim.is_empty() {
+            byte_ranges.push(ByteRange::FromStart(
+                self.verbatim.start as u64,
+                Some(self.verbatim.len() as u64),
+            ));
+        }
+        byte_ranges
+    }
+
+    /// Decode the region from the bytes of its [`encoded_byte_ranges`](Self::encoded_byte_ranges).
+    fn decode(&self, encoded: &[RawBytes<'_>], element_size: usize) -> Vec<u8> {
+        let mut decoded = Vec::with_capacity(self.byte_range.len());
+        let mut encoded = encoded.iter();
+        if !self.elements.is_empty() {
+            let mut elements = vec![0u8; self.elements.len() * element_size];
+            for (j, row) in encoded.by_ref().take(element_size).enumerate() {
+                for (i, byte) in row.iter().enumerate() {
+                    elements[i * element_size + j] = *byte;
+                }
+            }
+            let offset = self.elements.start * element_size;
+            let end = self.byte_range.end.min(self.elements.end * element_size);
+            decoded.extend_from_slice(&elements[self.byte_range.start - offset..end - offset]);
+        }
+        if let Some(verbatim) = encoded.next() {
+            decoded.extend_from_slice(verbatim);
+        }
+        decoded
+    }
+}
+
+/// Return the [`ShuffleRegion`] of each of the `decoded_regions` of a `size` byte value of `element_size` byte elements.
+fn shuffle_regions(
+    decoded_regions: &[ByteRange],
+    size: u64,
+    element_size: usize,
+) -> Result<Vec<ShuffleRegion>, CodecError> {
+    let shuffled_size = usize::try_from(size).unwrap() / element_size * element_size;
+    decoded_regions
+        .iter()
+        .map(|decoded_region| {
+            let valid = match decoded_region {
+                ByteRange::FromStart(offset, length) => offset + length.unwrap_or(0) <= size,
+                ByteRange::Suffix(length) => *length <= size,
+            };
+            if !valid {
+                return Err(CodecError::InvalidByteRangeError(
+                    InvalidByteRangeError::new(*decoded_region, size),
+                ));
+            }
+            let byte_range = decoded_region.to_range_usize(size);
+            let elements = byte_range.start.min(shuffled_size) / element_size
+                ..byte_range.end.min(shuffled_size).div_ceil(element_size);
+            let verbatim = byte_range.start.max(shuffled_size)..byte_range.end.max(shuffled_size);
+            Ok(ShuffleRegion {
+                byte_range,
+                elements,
+                verbatim,
+            })
+        })
+        .collect()
+}
+
+/// Unshuffle the entire `encoded_value`, and extract `decoded_regions`.
+fn decode_all<'a>(
+    encoded_value: &[u8],
+    decoded_regions: &[ByteRange],
+    element_size: usize,
+) -> Result<Vec<RawBytes<'a>>, CodecError> {
+    let decoded_value = unshuffle(encoded_value, element_size);
+    Ok(extract_byte_ranges(&decoded_value, decoded_regions)
+        .map_err(CodecError::InvalidByteRangeError)?
+        .into_iter()
+        .map(Cow::Owned)
+        .collect())
+}
+
+/// Decode each of the `regions` from the `encoded` bytes of their concatenated encoded byte ranges.
+fn decode_regions<'a>(
+    regions: &[ShuffleRegion],
+    encoded: &[RawBytes<'_>],
+    num_elements: usize,
+    element_size: usize,
+) -> Vec<RawBytes<'a>> {
+    let mut offset = 0;
+    regions
+        .iter()
+        .map(|region| {
+            let count = region.encoded_byte_ranges(num_elements, element_size).len();
+            let decoded = region.decode(&encoded[offset..offset + count], element_size);
+            offset += count;
+            Cow::Owned(decoded)
+        })
+        .collect()
+}
+
+/// Partial decoder for the `shuffle` codec.
+pub(crate) struct ShufflePartialDecoder<'a> {
+    input_handle: Arc<dyn BytesPartialDecoderTraits + 'a>,
+    decoded_size: Option<u64>,
+    element_size: usize,
+}
+
+impl<'a> ShufflePartialDecoder<'a> {
+    /// Create a new partial decoder for the `shuffle` codec.
+    pub(crate) fn new(
+        input_handle: Arc<dyn BytesPartialDecoderTraits + 'a>,
+        decoded_size: Option<u64>,
+        element_size: usize,
+    ) -> Self {
+        Self {
+            input_handle,
+            decoded_size,
+            element_size,
+        }
+    }
+}
+
+impl BytesPartialDecoderTraits for ShufflePartialDecoder<'_> {
+    fn partial_decode(
+        &self,
+        decoded_regions: &[ByteRange],
+        options: &CodecOptions,
+    ) -> Result<Option<Vec<RawBytes<'_>>>, CodecError> {
+        let Some(size) = self.decoded_size else {
+            let Some(encoded_value) = self.input_handle.decode(options)? else {
+                return Ok(None);
+            };
+            return Ok(Some(decode_all(
+                &encoded_value,
+                decoded_regions,
+                self.element_size,
+            )?));
+        };
+
+        // Only retrieve the bytes of the elements intersecting the decoded regions
+        let num_elements = usize::try_from(size).unwrap() / self.element_size;
+        let regions = shuffle_regions(decoded_regions, size, self.element_size)?;
+        let byte_ranges: Vec<ByteRange> = regions
+            .iter()
+            .flat_map(|region| region.encoded_byte_ranges(num_elements, self.element_size))
+            .collect();
+        let Some(encoded) = self.input_handle.partial_decode(&byte_ranges, options)? else {
+            return Ok(None);
+        };
+        Ok(Some(decode_regions(
+            &regions,
+            &encoded,
+            num_elements,
+            self.element_size,
+        )))
+    }
+}
+
+#[cfg(feature = "async")]
+/// Asynchronous partial decoder for the `shuffle` codec.
+pub(crate) struct AsyncShufflePartialDecoder {
+    input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+    decoded_size: Option<u64>,
+    element_size: usize,
+}
+
+#[cfg(feature = "async")]
+impl AsyncShufflePartialDecoder {
+    /// Create a new partial decoder for the `shuffle` codec.
+    pub(crate) fn new(
+        input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+        decoded_size: Option<u64>,
+        element_size: usize,
+    ) -> Self {
+        Self {
+            input_handle,
+            decoded_size,
+            element_size,
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl AsyncBytesPartialDecoderTraits for AsyncShufflePartialDecoder {
+    async fn partial_decode(
+        &self,
+        decoded_regions: &[ByteRange],
+        options: &CodecOptions,
+    ) -> Result<Option<Vec<RawBytes<'_>>>, CodecError> {
+        let Some(size) = self.decoded_size else {
+            let Some(encoded_value) = self.input_handle.decode(options).await? else {
+                return Ok(None);
+            };
+            return Ok(Some(decode_all(
+                &encoded_value,
+                decoded_regions,
+                self.element_size,
+            )?));
+        };
+
+        // Only retrieve the bytes of the elements intersecting the decoded regions
+        let num_elements = usize::try_from(size).unwrap() / self.element_size;
+        let regions = shuffle_regions(decoded_regions, size, self.element_size)?;
+        let byte_ranges: Vec<ByteRange> = regions
+            .iter()
+            .flat_map(|region| region.encoded_byte_ranges(num_elements, self.element_size))
+            .collect();
+        let Some(encoded) = self
+            .input_handle
+            .partial_decode(&byte_ranges, options)
+            .await?
+        else {
+            return Ok(None);
+        };
+        Ok(Some(decode_regions(
+            &regions,
+            &encoded,
+            num_elements,
+            self.element_size,
+        )))
+    }
+}
@@ -7,16 +7,26 @@
 //! </div>
 //!
 //! See <https://github.com/zarr-developers/zarr-specs/pull/256>.
+//!
+//! ### Dictionaries
+//! A [`ZstdDictionary`] can greatly improve the compression ratio of many small chunks with similar content.
+//! A dictionary is trained from samples with [`ZstdDictionary::train`] or from the chunks of an existing array with [`ZstdDictionary::train_from_array`].
+//!
+//! A dictionary is either embedded in the codec metadata ([`ZstdCodec::with_dictionary`]), or externally referenced by its dictionary ID ([`ZstdCodec::with_external_dictionary`]).
+//! An externally referenced dictionary must be registered with [`zstd_dictionary_register`] before opening an array that uses it.
+//! The `dictionary` configuration field is a `zarrs` extension.
 
 mod zstd_codec;
+mod zstd_dictionary;
 mod zstd_partial_decoder;
 
 use std::sync::Arc;
 
 pub use crate::metadata::v3::array::codec::zstd::{
-    ZstdCodecConfiguration, ZstdCodecConfigurationV1, ZstdCompressionLevel,
+    ZstdCodecConfiguration, ZstdCodecConfigurationV1, ZstdCodecDictionary, ZstdCompressionLevel,
 };
 pub use zstd_codec::ZstdCodec;
+pub use zstd_dictionary::{zstd_dictionary_register, zstd_dictionary_registered, ZstdDictionary};
 
 use crate::{
     array::codec::{Codec, CodecPlugin},
@@ -39,7 +49,7 @@ pub(crate) fn create_codec_zstd(metadata: &MetadataV3) -> Result<Codec, PluginCr
     let configuration: ZstdCodecConfiguration = metadata
         .to_configuration()
         .map_err(|_| PluginMetadataInvalidError::new(IDENTIFIER, "codec", metadata.clone()))?;
-    let codec = Arc::new(ZstdCodec::new_with_configuration(&configuration));
+    let codec = Arc::new(ZstdCodec::new_with_configuration(&configuration)?);
     Ok(Codec::BytesToBytes(codec))
 }
 
@@ -49,9 +59,10 @@ mod tests {
 
     use crate::{
         array::{
-            codec::{BytesToBytesCodecTraits, CodecOptions},
+            codec::{BytesToBytesCodecTraits, CodecOptions, CodecTraits},
             BytesRepresentation,
         },
+        array_subset::ArraySubset,
         byte_range::ByteRange,
     };
 
@@ -70,7 +81,7 @@ mod tests {
         let bytes_representation = BytesRepresentation::FixedSize(bytes.len() as u64);
 
         let configuration: ZstdCodecConfiguration = serde_json::from_str(JSON_VALID).unwrap();
-        let codec = ZstdCodec::new_with_configuration(&configuration);
+        let codec = ZstdCodec::new_with_configuration(&configuration).unwrap();
 
         let encoded = codec
             .encode(Cow::Borrowed(&bytes), &CodecOptions::default())
@@ -81,6 +92,221 @@ mod tests {
         assert_eq!(bytes, decoded.to_vec());
     }
 
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn codec_zstd_round_trip_streaming() {
+        use std::io::{Read, Write};
+
+        use crate::storage::{store::MemoryStore, ReadableStorageTraits, WritableStorageTraits};
+
+        let elements: Vec<u16> = (0..32).collect();
+        let bytes = crate::array::transmute_to_bytes_vec(elements);
+        let bytes_representation = BytesRepresentation::FixedSize(bytes.len() as u64);
+
+        let configuration: ZstdCodecConfiguration = serde_json::from_str(JSON_VALID).unwrap();
+        let codec = ZstdCodec::new_with_configuration(&configuration).unwrap();
+        assert!(codec.supports_streaming());
+
+        let store = MemoryStore::new();
+        let key = "c".try_into().unwrap();
+        let mut writer = codec
+            .encode_writer(store.set_writer(&key).unwrap(), &CodecOptions::default())
+            .unwrap();
+        for chunk in bytes.chunks(7) {
+            writer.write_all(chunk).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let encoded = store.get(&key).unwrap().unwrap();
+        let decoded = codec
+            .decode(
+                Cow::Borrowed(&encoded),
+                &bytes_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(bytes, decoded.to_vec());
+
+        let mut decoded = Vec::new();
+        codec
+            .decode_reader(
+                store.get_reader(&key).unwrap().unwrap(),
+                &bytes_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap()
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(bytes, decoded);
+    }
+
+    /// Small similar values for training dictionaries.
+    fn dictionary_samples() -> Vec<Vec<u8>> {
+        (0..512)
+            .map(|i| {
+                format!(
+                    r#"{{"station": "station_{}", "index": {i}, "quality": "good", "values": [{}, {}, {}]}}"#,
+                    i % 13,
+                    i % 7,
+                    i % 5,
+                    i % 3
+                )
+                .into_bytes()
+            })
+            .collect()
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn codec_zstd_dictionary_round_trip() {
+        let samples = dictionary_samples();
+        let dictionary = ZstdDictionary::train(&samples, 1024).unwrap();
+        assert!(dictionary.id().is_some());
+        let bytes = samples[100].clone();
+        let bytes_representation = BytesRepresentation::FixedSize(bytes.len() as u64);
+
+        let codec = ZstdCodec::new(5, true);
+        let codec_dictionary = ZstdCodec::new(5, true).with_dictionary(dictionary.clone());
+        assert_eq!(codec_dictionary.dictionary(), Some(&dictionary));
+        let encoded = codec
+            .encode(Cow::Borrowed(&bytes), &CodecOptions::default())
+            .unwrap();
+        let encoded_dictionary = codec_dictionary
+            .encode(Cow::Borrowed(&bytes), &CodecOptions::default())
+            .unwrap()
+            .into_owned();
+        assert!(encoded_dictionary.len() < encoded.len());
+
+        // The dictionary is embedded in the metadata
+        let metadata = codec_dictionary.create_metadata().unwrap();
+        let configuration: ZstdCodecConfiguration = metadata.to_configuration().unwrap();
+        let ZstdCodecConfiguration::V1(configuration_v1) = &configuration;
+        assert!(matches!(
+            configuration_v1.dictionary,
+            Some(ZstdCodecDictionary::Embedded { .. })
+        ));
+        let codec_metadata = ZstdCodec::new_with_configuration(&configuration).unwrap();
+        assert_eq!(codec_metadata.dictionary(), Some(&dictionary));
+        let decoded = codec_metadata
+            .decode(
+                Cow::Borrowed(&encoded_dictionary),
+                &bytes_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(bytes, decoded.to_vec());
+
+        // Values encoded without a dictionary can be decoded
+        let decoded = codec_metadata
+            .decode(encoded, &bytes_representation, &CodecOptions::default())
+            .unwrap();
+        assert_eq!(bytes, decoded.to_vec());
+
+        // Streaming
+        let mut decoded = Vec::new();
+        std::io::Read::read_to_end(
+            &mut codec_dictionary
+                .decode_reader(
+                    Box::new(std::io::Cursor::new(encoded_dictionary.clone())),
+                    &bytes_representation,
+                    &CodecOptions::default(),
+                )
+                .unwrap(),
+            &mut decoded,
+        )
+        .unwrap();
+        assert_eq!(bytes, decoded);
+
+        // Partial decoding
+        let partial_decoder = Arc::new(codec_dictionary)
+            .partial_decoder(
+                Arc::new(std::io::Cursor::new(encoded_dictionary)),
+                &bytes_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        let decoded_partial = partial_decoder
+            .partial_decode_concat(
+                &[ByteRange::FromStart(2, Some(7))],
+                &CodecOptions::default(),
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(&bytes[2..9], decoded_partial.as_ref());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn codec_zstd_external_dictionary() {
+        let samples = dictionary_samples();
+        let dictionary = ZstdDictionary::train(&samples, 2048).unwrap();
+        let id = dictionary.id().unwrap();
+        let bytes = samples[7].clone();
+        let bytes_representation = BytesRepresentation::FixedSize(bytes.len() as u64);
+
+        let codec = ZstdCodec::new(5, false)
+            .with_external_dictionary(dictionary.clone())
+            .unwrap();
+        let encoded = codec
+            .encode(Cow::Borrowed(&bytes), &CodecOptions::default())
+            .unwrap();
+        let metadata = codec.create_metadata().unwrap();
+        let configuration: ZstdCodecConfiguration = metadata.to_configuration().unwrap();
+        let ZstdCodecConfiguration::V1(configuration_v1) = &configuration;
+        assert_eq!(
+            configuration_v1.dictionary,
+            Some(ZstdCodecDictionary::External { id })
+        );
+
+        // An externally referenced dictionary must be registered
+        assert!(ZstdCodec::new_with_configuration(&configuration).is_err());
+        assert_eq!(zstd_dictionary_register(dictionary.clone()).unwrap(), id);
+        assert_eq!(zstd_dictionary_registered(id), Some(dictionary));
+        let codec_metadata = ZstdCodec::new_with_configuration(&configuration).unwrap();
+        let decoded = codec_metadata
+            .decode(encoded, &bytes_representation, &CodecOptions::default())
+            .unwrap();
+        assert_eq!(bytes, decoded.to_vec());
+
+        // Raw content dictionaries have no dictionary ID
+        let raw = ZstdDictionary::new(samples[0].clone());
+        assert!(raw.id().is_none());
+        assert!(zstd_dictionary_register(raw.clone()).is_err());
+        assert!(ZstdCodec::new(5, false)
+            .with_external_dictionary(raw)
+            .is_err());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn codec_zstd_dictionary_train_from_array() {
+        use crate::array::{ArrayBuilder, DataType, FillValue};
+        use crate::storage::store::MemoryStore;
+
+        let store = Arc::new(MemoryStore::new());
+        let array = ArrayBuilder::new(
+            vec![64, 96],
+            DataType::UInt16,
+            vec![8, 8].try_into().unwrap(),
+            FillValue::from(0u16),
+        )
+        .build(store, "/")
+        .unwrap();
+        let chunk_grid = ArraySubset::new_with_shape(array.chunk_grid_shape().unwrap());
+        for chunk_indices in &chunk_grid.indices() {
+            let elements: Vec<u16> = (0..64)
+                .map(|i| (i % 8) * 100 + u16::try_from(chunk_indices[0] % 3).unwrap())
+                .collect();
+            array
+                .store_chunk_elements(&chunk_indices, &elements)
+                .unwrap();
+        }
+        let dictionary =
+            ZstdDictionary::train_from_array(&array, 512, 64, &CodecOptions::default()).unwrap();
+        assert!(dictionary.id().is_some());
+        assert!(dictionary.as_bytes().len() <= 512);
+    }
+
     #[test]
     #[cfg_attr(miri, ignore)]
     fn codec_zstd_partial_decode() {
@@ -89,7 +315,7 @@ mod tests {
         let bytes_representation = BytesRepresentation::FixedSize(bytes.len() as u64);
 
         let configuration: ZstdCodecConfiguration = serde_json::from_str(JSON_VALID).unwrap();
-        let codec = Arc::new(ZstdCodec::new_with_configuration(&configuration));
+        let codec = Arc::new(ZstdCodec::new_with_configuration(&configuration).unwrap());
 
         let encoded = codec
             .encode(Cow::Owned(bytes), &CodecOptions::default())
@@ -130,7 +356,7 @@ mod tests {
         let bytes_representation = BytesRepresentation::FixedSize(bytes.len() as u64);
 
         let configuration: ZstdCodecConfiguration = serde_json::from_str(JSON_VALID).unwrap();
-        let codec = Arc::new(ZstdCodec::new_with_configuration(&configuration));
+        let codec = Arc::new(ZstdCodec::new_with_configuration(&configuration).unwrap());
 
         let encoded = codec
             .encode(Cow::Owned(bytes), &CodecOptions::default())
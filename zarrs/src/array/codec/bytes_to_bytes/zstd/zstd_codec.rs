@@ -1,28 +1,62 @@
-use std::{borrow::Cow, sync::Arc};
+use std::{
+    borrow::Cow,
+    io::{BufReader, Read, Write},
+    sync::Arc,
+};
 
-use zstd::zstd_safe;
+use base64::{prelude::BASE64_STANDARD, Engine};
+use zstd::{
+    dict::{DecoderDictionary, EncoderDictionary},
+    zstd_safe,
+};
 
 use crate::{
     array::{
         codec::{
+            backend::{backend_decode, backend_encode},
             BytesPartialDecoderTraits, BytesPartialEncoderDefault, BytesPartialEncoderTraits,
             BytesToBytesCodecTraits, CodecError, CodecOptions, CodecTraits, RecommendedConcurrency,
         },
         ArrayMetadataOptions, BytesRepresentation, RawBytes,
     },
     metadata::v3::MetadataV3,
+    plugin::PluginCreateError,
+    storage::{StorageError, StoreValueReader, StoreValueWriter},
 };
 
 #[cfg(feature = "async")]
 use crate::array::codec::AsyncBytesPartialDecoderTraits;
 
-use super::{zstd_partial_decoder, ZstdCodecConfiguration, ZstdCodecConfigurationV1, IDENTIFIER};
+use super::{
+    zstd_dictionary_registered, zstd_partial_decoder, ZstdCodecConfiguration,
+    ZstdCodecConfigurationV1, ZstdCodecDictionary, ZstdDictionary, IDENTIFIER,
+};
 
 /// A `zstd` codec implementation.
 #[derive(Clone, Debug)]
 pub struct ZstdCodec {
     compression: zstd_safe::CompressionLevel,
     checksum: bool,
+    dictionary: Option<ZstdPreparedDictionary>,
+}
+
+/// A `zstd` dictionary prepared for compression and decompression.
+#[derive(Clone)]
+struct ZstdPreparedDictionary {
+    dictionary: ZstdDictionary,
+    external: bool,
+    encoder: Arc<EncoderDictionary<'static>>,
+    decoder: Arc<DecoderDictionary<'static>>,
+}
+
+impl core::fmt::Debug for ZstdPreparedDictionary {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ZstdPreparedDictionary")
+            .field("id", &self.dictionary.id())
+            .field("size", &self.dictionary.as_bytes().len())
+            .field("external", &self.external)
+            .finish_non_exhaustive()
+    }
 }
 
 impl ZstdCodec {
@@ -32,26 +66,123 @@ impl ZstdCodec {
         Self {
             compression,
             checksum,
+            dictionary: None,
         }
     }
 
     /// Create a new `Zstd` codec from configuration.
-    #[must_use]
-    pub fn new_with_configuration(configuration: &ZstdCodecConfiguration) -> Self {
+    ///
+    /// # Errors
+    /// Returns [`PluginCreateError`] if an embedded dictionary is not valid base64, or an externally referenced dictionary is not registered with [`zstd_dictionary_register`](super::zstd_dictionary_register).
+    pub fn new_with_configuration(
+        configuration: &ZstdCodecConfiguration,
+    ) -> Result<Self, PluginCreateError> {
         let ZstdCodecConfiguration::V1(configuration) = configuration;
-        Self {
-            compression: configuration.level.clone().into(),
-            checksum: configuration.checksum,
+        let codec = Self::new(configuration.level.clone().into(), configuration.checksum);
+        match &configuration.dictionary {
+            None => Ok(codec),
+            Some(ZstdCodecDictionary::Embedded { data }) => {
+                let dictionary = BASE64_STANDARD.decode(data).map_err(|err| {
+                    PluginCreateError::Other(format!("invalid embedded zstd dictionary: {err}"))
+                })?;
+                Ok(codec.with_dictionary(ZstdDictionary::new(dictionary)))
+            }
+            Some(ZstdCodecDictionary::External { id }) => {
+                let dictionary = zstd_dictionary_registered(*id).ok_or_else(|| {
+                    PluginCreateError::Other(format!("the zstd dictionary {id} is not registered"))
+                })?;
+                codec
+                    .with_external_dictionary(dictionary)
+                    .map_err(|err| PluginCreateError::Other(err.to_string()))
+            }
+        }
+    }
+
+    /// Compress and decompress with `dictionary`, which is embedded in the codec metadata.
+    #[must_use]
+    pub fn with_dictionary(mut self, dictionary: ZstdDictionary) -> Self {
+        self.dictionary = Some(self.prepare_dictionary(dictionary, false));
+        self
+    }
+
+    /// Compress and decompress with `dictionary`, which is referenced by its dictionary ID in the codec metadata.
+    ///
+    /// The dictionary must be registered with [`zstd_dictionary_register`](super::zstd_dictionary_register) to create the codec from its metadata (e.g. when opening an array).
+    ///
+    /// # Errors
+    /// Returns a [`CodecError`] if the dictionary has no dictionary ID (i.e. it is raw content).
+    pub fn with_external_dictionary(
+        mut self,
+        dictionary: ZstdDictionary,
+    ) -> Result<Self, CodecError> {
+        if dictionary.id().is_none() {
+            return Err(CodecError::Other(
+                "an externally referenced zstd dictionary must have a dictionary ID".to_string(),
+            ));
         }
+        self.dictionary = Some(self.prepare_dictionary(dictionary, true));
+        Ok(self)
+    }
+
+    /// Return the dictionary, if any.
+    #[must_use]
+    pub fn dictionary(&self) -> Option<&ZstdDictionary> {
+        self.dictionary
+            .as_ref()
+            .map(|dictionary| &dictionary.dictionary)
+    }
+
+    fn prepare_dictionary(
+        &self,
+        dictionary: ZstdDictionary,
+        external: bool,
+    ) -> ZstdPreparedDictionary {
+        ZstdPreparedDictionary {
+            encoder: Arc::new(EncoderDictionary::copy(
+                dictionary.as_bytes(),
+                self.compression,
+            )),
+            decoder: Arc::new(DecoderDictionary::copy(dictionary.as_bytes())),
+            dictionary,
+            external,
+        }
+    }
+
+    fn decoder_dictionary(&self) -> Option<Arc<DecoderDictionary<'static>>> {
+        self.dictionary
+            .as_ref()
+            .map(|dictionary| dictionary.decoder.clone())
+    }
+}
+
+/// Decompress all frames of `encoded_value`, with a dictionary if it is not [`None`].
+pub(super) fn zstd_decode_all(
+    encoded_value: &[u8],
+    dictionary: Option<&DecoderDictionary<'_>>,
+) -> std::io::Result<Vec<u8>> {
+    if let Some(dictionary) = dictionary {
+        let mut decoder = zstd::Decoder::with_prepared_dictionary(encoded_value, dictionary)?;
+        let mut decoded_value = Vec::new();
+        decoder.read_to_end(&mut decoded_value)?;
+        Ok(decoded_value)
+    } else {
+        zstd::decode_all(encoded_value)
     }
 }
 
 impl CodecTraits for ZstdCodec {
     fn create_metadata_opt(&self, _options: &ArrayMetadataOptions) -> Option<MetadataV3> {
-        let configuration = ZstdCodecConfigurationV1 {
-            level: self.compression.into(),
-            checksum: self.checksum,
-        };
+        let dictionary =
+            self.dictionary
+                .as_ref()
+                .map(|dictionary| match dictionary.dictionary.id() {
+                    Some(id) if dictionary.external => ZstdCodecDictionary::External { id },
+                    _ => ZstdCodecDictionary::Embedded {
+                        data: BASE64_STANDARD.encode(dictionary.dictionary.as_bytes()),
+                    },
+                });
+        let configuration = ZstdCodecConfigurationV1::new(self.compression.into(), self.checksum)
+            .with_dictionary(dictionary);
         Some(MetadataV3::new_with_serializable_configuration(IDENTIFIER, &configuration).unwrap())
     }
 
@@ -81,10 +212,18 @@ impl BytesToBytesCodecTraits for ZstdCodec {
     fn encode<'a>(
         &self,
         decoded_value: RawBytes<'a>,
-        _options: &CodecOptions,
+        options: &CodecOptions,
     ) -> Result<RawBytes<'a>, CodecError> {
+        if let Some(encoded_value) = backend_encode(self, &decoded_value, options)? {
+            return Ok(encoded_value);
+        }
+
         let mut result = Vec::<u8>::new();
-        let mut encoder = zstd::Encoder::new(&mut result, self.compression)?;
+        let mut encoder = if let Some(dictionary) = &self.dictionary {
+            zstd::Encoder::with_prepared_dictionary(&mut result, &dictionary.encoder)?
+        } else {
+            zstd::Encoder::new(&mut result, self.compression)?
+        };
         encoder.include_checksum(self.checksum)?;
         // if parallel {
         //     let n_threads = std::thread::available_parallelism().unwrap().get();
@@ -98,21 +237,68 @@ impl BytesToBytesCodecTraits for ZstdCodec {
     fn decode<'a>(
         &self,
         encoded_value: RawBytes<'a>,
-        _decoded_representation: &BytesRepresentation,
-        _options: &CodecOptions,
+        decoded_representation: &BytesRepresentation,
+        options: &CodecOptions,
     ) -> Result<RawBytes<'a>, CodecError> {
-        zstd::decode_all(std::io::Cursor::new(&encoded_value))
+        if let Some(decoded_value) =
+            backend_decode(self, &encoded_value, decoded_representation, options)?
+        {
+            return Ok(decoded_value);
+        }
+
+        let dictionary = self
+            .dictionary
+            .as_ref()
+            .map(|dictionary| &*dictionary.decoder);
+        zstd_decode_all(&encoded_value, dictionary)
             .map_err(CodecError::IOError)
             .map(Cow::Owned)
     }
 
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    fn decode_reader<'a>(
+        &self,
+        encoded_value: StoreValueReader<'a>,
+        _decoded_representation: &BytesRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<StoreValueReader<'a>, CodecError> {
+        if let Some(dictionary) = &self.dictionary {
+            Ok(Box::new(zstd::Decoder::with_dictionary(
+                BufReader::new(encoded_value),
+                dictionary.dictionary.as_bytes(),
+            )?))
+        } else {
+            Ok(Box::new(zstd::Decoder::new(encoded_value)?))
+        }
+    }
+
+    fn encode_writer<'a>(
+        &'a self,
+        encoded_value: Box<dyn StoreValueWriter + 'a>,
+        _options: &CodecOptions,
+    ) -> Result<Box<dyn StoreValueWriter + 'a>, CodecError> {
+        let mut encoder = if let Some(dictionary) = &self.dictionary {
+            zstd::Encoder::with_prepared_dictionary(encoded_value, &dictionary.encoder)?
+        } else {
+            zstd::Encoder::new(encoded_value, self.compression)?
+        };
+        encoder.include_checksum(self.checksum)?;
+        Ok(Box::new(ZstdEncodeWriter(encoder)))
+    }
+
     fn partial_decoder(
         self: Arc<Self>,
         r: Arc<dyn BytesPartialDecoderTraits>,
         _decoded_representation: &BytesRepresentation,
         _options: &CodecOptions,
     ) -> Result<Arc<dyn BytesPartialDecoderTraits>, CodecError> {
-        Ok(Arc::new(zstd_partial_decoder::ZstdPartialDecoder::new(r)))
+        Ok(Arc::new(zstd_partial_decoder::ZstdPartialDecoder::new(
+            r,
+            self.decoder_dictionary(),
+        )))
     }
 
     fn partial_encoder(
@@ -138,7 +324,7 @@ impl BytesToBytesCodecTraits for ZstdCodec {
         _options: &CodecOptions,
     ) -> Result<Arc<dyn AsyncBytesPartialDecoderTraits>, CodecError> {
         Ok(Arc::new(
-            zstd_partial_decoder::AsyncZstdPartialDecoder::new(r),
+            zstd_partial_decoder::AsyncZstdPartialDecoder::new(r, self.decoder_dictionary()),
         ))
     }
 
@@ -159,3 +345,22 @@ impl BytesToBytesCodecTraits for ZstdCodec {
             })
     }
 }
+
+/// A [`StoreValueWriter`] that streams bytes through a `zstd` encoder.
+struct ZstdEncodeWriter<'a>(zstd::Encoder<'a, Box<dyn StoreValueWriter + 'a>>);
+
+impl Write for ZstdEncodeWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl StoreValueWriter for ZstdEncodeWriter<'_> {
+    fn finish(self: Box<Self>) -> Result<(), StorageError> {
+        self.0.finish()?.finish()
+    }
+}
@@ -0,0 +1,130 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock, RwLock},
+};
+
+use zstd::zstd_safe;
+
+use crate::{
+    array::{
+        codec::{ArrayToBytesCodecTraits, CodecChain, CodecError, CodecOptions},
+        Array, ArrayError,
+    },
+    array_subset::ArraySubset,
+    storage::ReadableStorageTraits,
+};
+
+/// A `zstd` dictionary.
+///
+/// A dictionary is trained on samples of the values to be compressed, and is used for both compression and decompression.
+/// Dictionaries can greatly improve the compression ratio of small values with similar content, such as the chunks of a large collection of small similar chunks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ZstdDictionary(Arc<[u8]>);
+
+impl ZstdDictionary {
+    /// Create a new `zstd` dictionary from its bytes.
+    ///
+    /// The bytes are either a dictionary in the `zstd` dictionary format (e.g. trained with [`ZstdDictionary::train`] or `zstd --train`), or raw content.
+    #[must_use]
+    pub fn new(dictionary: impl Into<Arc<[u8]>>) -> Self {
+        Self(dictionary.into())
+    }
+
+    /// Train a `zstd` dictionary of at most `max_size` bytes from `samples`.
+    ///
+    /// A good dictionary size is typically about 100 times smaller than the total size of the samples.
+    ///
+    /// # Errors
+    /// Returns a [`CodecError`] if training fails, such as if there are too few samples.
+    pub fn train<S: AsRef<[u8]>>(samples: &[S], max_size: usize) -> Result<Self, CodecError> {
+        Ok(Self::new(zstd::dict::from_samples(samples, max_size)?))
+    }
+
+    /// Train a `zstd` dictionary of at most `max_size` bytes from up to `max_chunks` chunks of `array`.
+    ///
+    /// The samples are the chunks that exist in the store, encoded with the array to array and array to bytes codecs of `array`.
+    /// These are the values compressed by a `zstd` codec that is the first bytes to bytes codec of an array with the same codecs.
+    /// The chunks of an array with the `sharding_indexed` codec are entire shards, so the samples are not the inner chunks of the shards.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if a chunk cannot be retrieved or encoded, or training fails.
+    pub fn train_from_array<TStorage: ?Sized + ReadableStorageTraits + 'static>(
+        array: &Array<TStorage>,
+        max_size: usize,
+        max_chunks: usize,
+        options: &CodecOptions,
+    ) -> Result<Self, ArrayError> {
+        let codecs = CodecChain::new(
+            array.codecs().array_to_array_codecs().to_vec(),
+            array.codecs().array_to_bytes_codec().clone(),
+            vec![],
+        );
+        let chunk_grid_shape = array
+            .chunk_grid_shape()
+            .ok_or_else(|| CodecError::Other("the array chunk grid is unbounded".to_string()))?;
+        let mut samples = Vec::new();
+        for chunk_indices in &ArraySubset::new_with_shape(chunk_grid_shape).indices() {
+            if samples.len() >= max_chunks {
+                break;
+            }
+            let Some(chunk_bytes) = array.retrieve_chunk_if_exists_opt(&chunk_indices, options)?
+            else {
+                continue;
+            };
+            let chunk_representation = array.chunk_array_representation(&chunk_indices)?;
+            let sample = codecs.encode(chunk_bytes, &chunk_representation, options)?;
+            samples.push(sample.into_owned());
+        }
+        Ok(Self::train(&samples, max_size)?)
+    }
+
+    /// Return the dictionary ID, or [`None`] if the dictionary is raw content.
+    #[must_use]
+    pub fn id(&self) -> Option<u32> {
+        zstd_safe::get_dict_id(&self.0).map(std::num::NonZeroU32::get)
+    }
+
+    /// Return the bytes of the dictionary.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+type ZstdDictionaries = HashMap<u32, ZstdDictionary>;
+
+static ZSTD_DICTIONARIES: OnceLock<RwLock<ZstdDictionaries>> = OnceLock::new();
+
+fn zstd_dictionaries() -> &'static RwLock<ZstdDictionaries> {
+    ZSTD_DICTIONARIES.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Register a `zstd` dictionary for `zstd` codecs referencing it by its dictionary ID, and return its ID.
+///
+/// Externally referenced dictionaries are not stored in array metadata, so they must be registered before creating an array that uses them.
+/// A registered dictionary replaces any dictionary previously registered with the same ID.
+///
+/// # Errors
+/// Returns a [`CodecError`] if the dictionary has no dictionary ID (i.e. it is raw content).
+pub fn zstd_dictionary_register(dictionary: ZstdDictionary) -> Result<u32, CodecError> {
+    let id = dictionary.id().ok_or_else(|| {
+        CodecError::Other(
+            "a zstd dictionary without a dictionary ID cannot be registered".to_string(),
+        )
+    })?;
+    zstd_dictionaries()
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .insert(id, dictionary);
+    Ok(id)
+}
+
+/// Return the `zstd` dictionary registered with dictionary ID `id`.
+#[must_use]
+pub fn zstd_dictionary_registered(id: u32) -> Option<ZstdDictionary> {
+    zstd_dictionaries()
+        .read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .get(&id)
+        .cloned()
+}
@@ -1,6 +1,6 @@
 use std::{borrow::Cow, sync::Arc};
 
-use bytes::Buf;
+use zstd::dict::DecoderDictionary;
 
 use crate::{
     array::{
@@ -13,15 +13,24 @@ use crate::{
 #[cfg(feature = "async")]
 use crate::array::codec::AsyncBytesPartialDecoderTraits;
 
+use super::zstd_codec::zstd_decode_all;
+
 /// Partial decoder for the `zstd` codec.
 pub(crate) struct ZstdPartialDecoder<'a> {
     input_handle: Arc<dyn BytesPartialDecoderTraits + 'a>,
+    dictionary: Option<Arc<DecoderDictionary<'static>>>,
 }
 
 impl<'a> ZstdPartialDecoder<'a> {
     /// Create a new partial decoder for the `zstd` codec.
-    pub(crate) fn new(input_handle: Arc<dyn BytesPartialDecoderTraits + 'a>) -> Self {
-        Self { input_handle }
+    pub(crate) fn new(
+        input_handle: Arc<dyn BytesPartialDecoderTraits + 'a>,
+        dictionary: Option<Arc<DecoderDictionary<'static>>>,
+    ) -> Self {
+        Self {
+            input_handle,
+            dictionary,
+        }
     }
 }
 
@@ -36,7 +45,8 @@ impl BytesPartialDecoderTraits for ZstdPartialDecoder<'_> {
             return Ok(None);
         };
 
-        let decompressed = zstd::decode_all(encoded_value.reader()).map_err(CodecError::IOError)?;
+        let decompressed = zstd_decode_all(&encoded_value, self.dictionary.as_deref())
+            .map_err(CodecError::IOError)?;
 
         Ok(Some(
             extract_byte_ranges(&decompressed, decoded_regions)
@@ -52,13 +62,20 @@ impl BytesPartialDecoderTraits for ZstdPartialDecoder<'_> {
 /// Asynchronous partial decoder for the `zstd` codec.
 pub(crate) struct AsyncZstdPartialDecoder {
     input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+    dictionary: Option<Arc<DecoderDictionary<'static>>>,
 }
 
 #[cfg(feature = "async")]
 impl AsyncZstdPartialDecoder {
     /// Create a new partial decoder for the `zstd` codec.
-    pub(crate) fn new(input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>) -> Self {
-        Self { input_handle }
+    pub(crate) fn new(
+        input_handle: Arc<dyn AsyncBytesPartialDecoderTraits>,
+        dictionary: Option<Arc<DecoderDictionary<'static>>>,
+    ) -> Self {
+        Self {
+            input_handle,
+            dictionary,
+        }
     }
 }
 
@@ -75,7 +92,8 @@ impl AsyncBytesPartialDecoderTraits for AsyncZstdPartialDecoder {
             return Ok(None);
         };
 
-        let decompressed = zstd::decode_all(encoded_value.reader()).map_err(CodecError::IOError)?;
+        let decompressed = zstd_decode_all(&encoded_value, self.dictionary.as_deref())
+            .map_err(CodecError::IOError)?;
 
         Ok(Some(
             extract_byte_ranges(&decompressed, decoded_regions)
@@ -1,7 +1,14 @@
 //! Codec options for encoding and decoding.
 
+use std::sync::Arc;
+
 use crate::config::global_config;
 
+use super::{CodecBackend, CodecProfiler};
+
+#[cfg(feature = "sharding")]
+use super::ShardIndexCache;
+
 /// Codec options for encoding/decoding.
 ///
 /// Default values for these options are set by the global [`Config`](crate::config::Config).
@@ -11,6 +18,12 @@ pub struct CodecOptions {
     store_empty_chunks: bool,
     concurrent_target: usize,
     experimental_partial_encoding: bool,
+    backend: Option<Arc<dyn CodecBackend>>,
+    profiler: Option<Arc<CodecProfiler>>,
+    shard_compaction_threshold: Option<f64>,
+    #[cfg(feature = "sharding")]
+    shard_index_cache: Option<Arc<ShardIndexCache>>,
+    byte_range_coalesce_gap: Option<u64>,
 }
 
 impl Default for CodecOptions {
@@ -20,6 +33,12 @@ impl Default for CodecOptions {
             store_empty_chunks: global_config().store_empty_chunks(),
             concurrent_target: global_config().codec_concurrent_target(),
             experimental_partial_encoding: global_config().experimental_partial_encoding(),
+            backend: None,
+            profiler: None,
+            shard_compaction_threshold: None,
+            #[cfg(feature = "sharding")]
+            shard_index_cache: None,
+            byte_range_coalesce_gap: None,
         }
     }
 }
@@ -39,6 +58,12 @@ impl CodecOptions {
             store_empty_chunks: self.store_empty_chunks,
             concurrent_target: self.concurrent_target,
             experimental_partial_encoding: self.experimental_partial_encoding,
+            backend: self.backend.clone(),
+            profiler: self.profiler.clone(),
+            shard_compaction_threshold: self.shard_compaction_threshold,
+            #[cfg(feature = "sharding")]
+            shard_index_cache: self.shard_index_cache.clone(),
+            byte_range_coalesce_gap: self.byte_range_coalesce_gap,
         }
     }
 
@@ -92,6 +117,92 @@ impl CodecOptions {
         self.experimental_partial_encoding = experimental_partial_encoding;
         self
     }
+
+    /// Return the codec backend, if any.
+    #[must_use]
+    pub fn backend(&self) -> Option<&Arc<dyn CodecBackend>> {
+        self.backend.as_ref()
+    }
+
+    /// Set the codec backend.
+    ///
+    /// Codecs use their CPU implementation if the backend is [`None`] or does not support a codec.
+    /// See [`codec::backend`](crate::array::codec::backend).
+    pub fn set_backend(&mut self, backend: Option<Arc<dyn CodecBackend>>) -> &mut Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Return the codec pipeline profiler, if any.
+    #[must_use]
+    pub fn profiler(&self) -> Option<&Arc<CodecProfiler>> {
+        self.profiler.as_ref()
+    }
+
+    /// Set the codec pipeline profiler.
+    ///
+    /// If set, the wall time and input/output sizes of codec operations are recorded.
+    /// See [`codec::profiler`](crate::array::codec::profiler).
+    pub fn set_profiler(&mut self, profiler: Option<Arc<CodecProfiler>>) -> &mut Self {
+        self.profiler = profiler;
+        self
+    }
+
+    /// Return the shard compaction threshold, if any.
+    #[must_use]
+    pub fn shard_compaction_threshold(&self) -> Option<f64> {
+        self.shard_compaction_threshold
+    }
+
+    /// Set the shard compaction threshold.
+    ///
+    /// If set, a shard is compacted after a partial encode if the fraction of its bytes that are unreferenced by its shard index exceeds the threshold.
+    /// See [`Array::compact_shard_opt`](crate::array::Array::compact_shard_opt).
+    pub fn set_shard_compaction_threshold(
+        &mut self,
+        shard_compaction_threshold: Option<f64>,
+    ) -> &mut Self {
+        self.shard_compaction_threshold = shard_compaction_threshold;
+        self
+    }
+
+    /// Return the shard index cache, if any.
+    #[cfg(feature = "sharding")]
+    #[must_use]
+    pub(crate) fn shard_index_cache(&self) -> Option<&Arc<ShardIndexCache>> {
+        self.shard_index_cache.as_ref()
+    }
+
+    /// Set the shard index cache.
+    ///
+    /// This is set by an [`Array`](crate::array::Array) with a shard index cache when it creates a partial decoder.
+    #[cfg(feature = "sharding")]
+    pub(crate) fn set_shard_index_cache(
+        &mut self,
+        shard_index_cache: Option<Arc<ShardIndexCache>>,
+    ) -> &mut Self {
+        self.shard_index_cache = shard_index_cache;
+        self
+    }
+
+    /// Return the byte range coalescing gap, if any.
+    #[must_use]
+    pub fn byte_range_coalesce_gap(&self) -> Option<u64> {
+        self.byte_range_coalesce_gap
+    }
+
+    /// Set the byte range coalescing gap.
+    ///
+    /// If set, byte ranges requested by a partial decoder that are separated by no more than `byte_range_coalesce_gap` bytes are retrieved with a single byte range.
+    /// This retrieves some unrequested bytes, but can substantially reduce the number of requests to high latency stores (e.g. HTTP stores).
+    /// The sharding codec partial decoder also retrieves the intersected inner chunks with coalesced byte ranges.
+    pub fn set_byte_range_coalesce_gap(
+        &mut self,
+        byte_range_coalesce_gap: Option<u64>,
+    ) -> &mut Self {
+        self.byte_range_coalesce_gap = byte_range_coalesce_gap;
+        self
+    }
 }
 
 /// Builder for [`CodecOptions`].
@@ -103,6 +214,12 @@ pub struct CodecOptionsBuilder {
     store_empty_chunks: bool,
     concurrent_target: usize,
     experimental_partial_encoding: bool,
+    backend: Option<Arc<dyn CodecBackend>>,
+    profiler: Option<Arc<CodecProfiler>>,
+    shard_compaction_threshold: Option<f64>,
+    #[cfg(feature = "sharding")]
+    shard_index_cache: Option<Arc<ShardIndexCache>>,
+    byte_range_coalesce_gap: Option<u64>,
 }
 
 impl Default for CodecOptionsBuilder {
@@ -120,6 +237,12 @@ impl CodecOptionsBuilder {
             store_empty_chunks: global_config().store_empty_chunks(),
             concurrent_target: global_config().codec_concurrent_target(),
             experimental_partial_encoding: global_config().experimental_partial_encoding(),
+            backend: None,
+            profiler: None,
+            shard_compaction_threshold: None,
+            #[cfg(feature = "sharding")]
+            shard_index_cache: None,
+            byte_range_coalesce_gap: None,
         }
     }
 
@@ -131,6 +254,12 @@ impl CodecOptionsBuilder {
             store_empty_chunks: self.store_empty_chunks,
             concurrent_target: self.concurrent_target,
             experimental_partial_encoding: self.experimental_partial_encoding,
+            backend: self.backend.clone(),
+            profiler: self.profiler.clone(),
+            shard_compaction_threshold: self.shard_compaction_threshold,
+            #[cfg(feature = "sharding")]
+            shard_index_cache: self.shard_index_cache.clone(),
+            byte_range_coalesce_gap: self.byte_range_coalesce_gap,
         }
     }
 
@@ -161,4 +290,45 @@ impl CodecOptionsBuilder {
         self.experimental_partial_encoding = experimental_partial_encoding;
         self
     }
+
+    /// Set the codec backend.
+    ///
+    /// Codecs use their CPU implementation if the backend is [`None`] or does not support a codec.
+    /// See [`codec::backend`](crate::array::codec::backend).
+    #[must_use]
+    pub fn backend(mut self, backend: Option<Arc<dyn CodecBackend>>) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Set the codec pipeline profiler.
+    ///
+    /// If set, the wall time and input/output sizes of codec operations are recorded.
+    /// See [`codec::profiler`](crate::array::codec::profiler).
+    #[must_use]
+    pub fn profiler(mut self, profiler: Option<Arc<CodecProfiler>>) -> Self {
+        self.profiler = profiler;
+        self
+    }
+
+    /// Set the shard compaction threshold.
+    ///
+    /// If set, a shard is compacted after a partial encode if the fraction of its bytes that are unreferenced by its shard index exceeds the threshold.
+    /// See [`Array::compact_shard_opt`](crate::array::Array::compact_shard_opt).
+    #[must_use]
+    pub fn shard_compaction_threshold(mut self, shard_compaction_threshold: Option<f64>) -> Self {
+        self.shard_compaction_threshold = shard_compaction_threshold;
+        self
+    }
+
+    /// Set the byte range coalescing gap.
+    ///
+    /// If set, byte ranges requested by a partial decoder that are separated by no more than `byte_range_coalesce_gap` bytes are retrieved with a single byte range.
+    /// This retrieves some unrequested bytes, but can substantially reduce the number of requests to high latency stores (e.g. HTTP stores).
+    /// The sharding codec partial decoder also retrieves the intersected inner chunks with coalesced byte ranges.
+    #[must_use]
+    pub fn byte_range_coalesce_gap(mut self, byte_range_coalesce_gap: Option<u64>) -> Self {
+        self.byte_range_coalesce_gap = byte_range_coalesce_gap;
+        self
+    }
 }
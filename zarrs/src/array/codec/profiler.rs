@@ -0,0 +1,343 @@
+//! Codec pipeline profiling.
+//!
+//! A [`CodecProfiler`] records the wall time and input/output sizes of each codec encode/decode of each chunk.
+//! It is enabled with [`CodecOptions::set_profiler`] or [`CodecOptionsBuilder::profiler`](crate::array::codec::CodecOptionsBuilder::profiler).
+//!
+//! The following are recorded:
+//!  - the encode/decode of each codec in a [`CodecChain`](crate::array::codec::CodecChain), including the inner codecs of the `sharding_indexed` codec,
+//!  - the decoding of a `sharding_indexed` shard index, and
+//!  - the retrieval/storage of encoded chunks from/to the store in [`Array::retrieve_chunk_opt`](crate::array::Array::retrieve_chunk_opt) and [`Array::store_chunk_opt`](crate::array::Array::store_chunk_opt) (and the methods that call them).
+//!
+//! Chunks are not streamed through the codecs while profiling, and partial decoders and partial encoders are not profiled.
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use zarrs::array::codec::{CodecOptions, CodecProfiler};
+//! let profiler = Arc::new(CodecProfiler::new());
+//! let options = CodecOptions::builder().profiler(Some(profiler.clone())).build();
+//! // array.retrieve_chunk_opt(&[0, 0], &options)?;
+//! println!("{}", profiler.report());
+//! ```
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use super::{CodecError, CodecOptions, CodecTraits};
+
+/// A profiled operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CodecProfileOperation {
+    /// A codec encode.
+    Encode,
+    /// A codec decode.
+    Decode,
+    /// A `sharding_indexed` shard index decode.
+    DecodeShardIndex,
+    /// The retrieval of an encoded chunk from the store.
+    StoreRetrieve,
+    /// The storage of an encoded chunk in the store.
+    StoreStore,
+}
+
+impl core::fmt::Display for CodecProfileOperation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.pad(match self {
+            Self::Encode => "encode",
+            Self::Decode => "decode",
+            Self::DecodeShardIndex => "decode shard index",
+            Self::StoreRetrieve => "store retrieve",
+            Self::StoreStore => "store store",
+        })
+    }
+}
+
+/// A record of a profiled operation.
+#[derive(Debug, Clone)]
+pub struct CodecProfileRecord {
+    name: String,
+    operation: CodecProfileOperation,
+    duration: Duration,
+    input_size: usize,
+    output_size: usize,
+}
+
+impl CodecProfileRecord {
+    /// Return the name of the codec (e.g. `blosc`), or `store` for store operations.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Return the operation.
+    #[must_use]
+    pub const fn operation(&self) -> CodecProfileOperation {
+        self.operation
+    }
+
+    /// Return the wall time of the operation.
+    #[must_use]
+    pub const fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Return the size of the input in bytes.
+    #[must_use]
+    pub const fn input_size(&self) -> usize {
+        self.input_size
+    }
+
+    /// Return the size of the output in bytes.
+    #[must_use]
+    pub const fn output_size(&self) -> usize {
+        self.output_size
+    }
+}
+
+/// A codec pipeline profiler.
+///
+/// See the [module documentation](self).
+#[derive(Debug, Default)]
+pub struct CodecProfiler {
+    records: Mutex<Vec<CodecProfileRecord>>,
+}
+
+impl CodecProfiler {
+    /// Create a new codec profiler.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an operation.
+    pub fn record(
+        &self,
+        name: impl Into<String>,
+        operation: CodecProfileOperation,
+        duration: Duration,
+        input_size: usize,
+        output_size: usize,
+    ) {
+        let record = CodecProfileRecord {
+            name: name.into(),
+            operation,
+            duration,
+            input_size,
+            output_size,
+        };
+        self.records
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .push(record);
+    }
+
+    /// Return the records of each operation in the order they completed.
+    #[must_use]
+    pub fn records(&self) -> Vec<CodecProfileRecord> {
+        self.records
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Clear the records.
+    pub fn clear(&self) {
+        self.records
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clear();
+    }
+
+    /// Return a report summarising the records by name and operation.
+    #[must_use]
+    pub fn report(&self) -> CodecProfileReport {
+        let mut entries =
+            HashMap::<(String, CodecProfileOperation), CodecProfileReportEntry>::new();
+        for record in self
+            .records
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .iter()
+        {
+            let entry = entries
+                .entry((record.name.clone(), record.operation))
+                .or_insert_with(|| CodecProfileReportEntry {
+                    name: record.name.clone(),
+                    operation: record.operation,
+                    count: 0,
+                    duration: Duration::ZERO,
+                    input_size: 0,
+                    output_size: 0,
+                });
+            entry.count += 1;
+            entry.duration += record.duration;
+            entry.input_size += record.input_size;
+            entry.output_size += record.output_size;
+        }
+        let mut entries: Vec<_> = entries.into_values().collect();
+        entries.sort_by(|a, b| {
+            b.duration
+                .cmp(&a.duration)
+                .then_with(|| (&a.name, a.operation).cmp(&(&b.name, b.operation)))
+        });
+        CodecProfileReport { entries }
+    }
+}
+
+/// An entry of a [`CodecProfileReport`], summarising the records with the same name and operation.
+#[derive(Debug, Clone)]
+pub struct CodecProfileReportEntry {
+    name: String,
+    operation: CodecProfileOperation,
+    count: usize,
+    duration: Duration,
+    input_size: usize,
+    output_size: usize,
+}
+
+impl CodecProfileReportEntry {
+    /// Return the name of the codec, or `store` for store operations.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Return the operation.
+    #[must_use]
+    pub const fn operation(&self) -> CodecProfileOperation {
+        self.operation
+    }
+
+    /// Return the number of records.
+    #[must_use]
+    pub const fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Return the total wall time of the records.
+    #[must_use]
+    pub const fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Return the total input size of the records in bytes.
+    #[must_use]
+    pub const fn input_size(&self) -> usize {
+        self.input_size
+    }
+
+    /// Return the total output size of the records in bytes.
+    #[must_use]
+    pub const fn output_size(&self) -> usize {
+        self.output_size
+    }
+}
+
+/// A codec pipeline profile report, retrieved with [`CodecProfiler::report`].
+///
+/// Entries are sorted by descending total wall time.
+/// Note that the wall time of concurrent operations overlaps.
+#[derive(Debug, Clone)]
+pub struct CodecProfileReport {
+    entries: Vec<CodecProfileReportEntry>,
+}
+
+impl CodecProfileReport {
+    /// Return the entries of the report.
+    #[must_use]
+    pub fn entries(&self) -> &[CodecProfileReportEntry] {
+        &self.entries
+    }
+}
+
+impl core::fmt::Display for CodecProfileReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        writeln!(
+            f,
+            "{:<24} {:<20} {:>8} {:>14} {:>14} {:>14}",
+            "name", "operation", "count", "duration", "input size", "output size"
+        )?;
+        for entry in &self.entries {
+            let duration = format!("{:.3?}", entry.duration);
+            writeln!(
+                f,
+                "{:<24} {:<20} {:>8} {:>14} {:>14} {:>14}",
+                entry.name,
+                entry.operation,
+                entry.count,
+                duration,
+                entry.input_size,
+                entry.output_size
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Run `f` and record it with the profiler in `options`, if any.
+pub(crate) fn profile<T, C: CodecTraits + ?Sized>(
+    options: &CodecOptions,
+    codec: &C,
+    operation: CodecProfileOperation,
+    input_size: usize,
+    f: impl FnOnce() -> Result<T, CodecError>,
+    output_size: impl FnOnce(&T) -> usize,
+) -> Result<T, CodecError> {
+    let Some(profiler) = options.profiler() else {
+        return f();
+    };
+    let start = Instant::now();
+    let output = f()?;
+    let duration = start.elapsed();
+    if let Some(metadata) = codec.create_metadata() {
+        profiler.record(
+            metadata.name(),
+            operation,
+            duration,
+            input_size,
+            output_size(&output),
+        );
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codec_profiler_report() {
+        let profiler = CodecProfiler::new();
+        let millis = Duration::from_millis;
+        profiler.record("bytes", CodecProfileOperation::Decode, millis(1), 10, 10);
+        profiler.record("blosc", CodecProfileOperation::Decode, millis(5), 4, 10);
+        profiler.record("blosc", CodecProfileOperation::Decode, millis(3), 6, 10);
+        profiler.record(
+            "store",
+            CodecProfileOperation::StoreRetrieve,
+            millis(2),
+            0,
+            10,
+        );
+        assert_eq!(profiler.records().len(), 4);
+        assert_eq!(profiler.records()[1].name(), "blosc");
+
+        let report = profiler.report();
+        let entries = report.entries();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].name(), "blosc");
+        assert_eq!(entries[0].operation(), CodecProfileOperation::Decode);
+        assert_eq!(entries[0].count(), 2);
+        assert_eq!(entries[0].duration(), millis(8));
+        assert_eq!(entries[0].input_size(), 10);
+        assert_eq!(entries[0].output_size(), 20);
+        assert_eq!(entries[1].name(), "store");
+        assert_eq!(entries[2].name(), "bytes");
+        assert!(report.to_string().contains("store retrieve"));
+
+        profiler.clear();
+        assert!(profiler.records().is_empty());
+    }
+}
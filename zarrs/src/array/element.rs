@@ -265,3 +265,38 @@ impl ElementOwned for Vec<u8> {
         Ok(elements)
     }
 }
+
+/// Convert the elements of `bytes` with a boolean, integer, or floating point `data_type` to [`f64`].
+///
+/// # Errors
+/// Returns [`ArrayError::IncompatibleElementType`] if the data type is not supported.
+#[allow(clippy::cast_precision_loss)]
+pub(super) fn elements_to_f64(
+    data_type: &DataType,
+    bytes: ArrayBytes<'_>,
+) -> Result<Vec<f64>, ArrayError> {
+    macro_rules! to_f64 {
+        ( $t:ty, $to_f64:expr ) => {
+            <$t>::from_array_bytes(data_type, bytes)?
+                .into_iter()
+                .map($to_f64)
+                .collect()
+        };
+    }
+    Ok(match data_type {
+        DataType::Bool => to_f64!(bool, f64::from),
+        DataType::Int8 => to_f64!(i8, f64::from),
+        DataType::Int16 => to_f64!(i16, f64::from),
+        DataType::Int32 => to_f64!(i32, f64::from),
+        DataType::Int64 => to_f64!(i64, |element| element as f64),
+        DataType::UInt8 => to_f64!(u8, f64::from),
+        DataType::UInt16 => to_f64!(u16, f64::from),
+        DataType::UInt32 => to_f64!(u32, f64::from),
+        DataType::UInt64 => to_f64!(u64, |element| element as f64),
+        DataType::Float16 => to_f64!(half::f16, f64::from),
+        DataType::BFloat16 => to_f64!(half::bf16, f64::from),
+        DataType::Float32 => to_f64!(f32, f64::from),
+        DataType::Float64 => f64::from_array_bytes(data_type, bytes)?,
+        _ => return Err(IET),
+    })
+}
@@ -0,0 +1,353 @@
+use std::marker::PhantomData;
+
+use derive_more::Deref;
+
+use crate::{
+    array_subset::ArraySubset,
+    storage::{ReadableStorageTraits, ReadableWritableStorageTraits, WritableStorageTraits},
+};
+
+use super::{codec::CodecOptions, Array, ArrayError, Element, ElementOwned};
+
+/// An array with elements of type `T`.
+///
+/// The data type of the array is checked against `T` when the typed array is created, so the element methods of a typed array do not need an element type parameter.
+/// A typed array dereferences to its [`Array`], so all other array methods remain available.
+///
+/// ```rust
+/// # use std::sync::Arc;
+/// # use zarrs::array::{ArrayBuilder, DataType, FillValue, TypedArray};
+/// # use zarrs::storage::store::MemoryStore;
+/// # let array = ArrayBuilder::new(vec![4, 4], DataType::Float32, vec![2, 2].try_into()?, FillValue::from(0.0f32))
+/// #     .build(Arc::new(MemoryStore::new()), "/")?;
+/// let array = TypedArray::<_, f32>::new(array)?;
+/// array.store_chunk(&[0, 0], &[1.0, 2.0, 3.0, 4.0])?;
+/// let elements: Vec<f32> = array.retrieve_chunk(&[0, 0])?;
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Deref)]
+pub struct TypedArray<TStorage: ?Sized, T> {
+    #[deref]
+    array: Array<TStorage>,
+    element_type: PhantomData<fn() -> T>,
+}
+
+impl<TStorage: ?Sized, T: Element> TypedArray<TStorage, T> {
+    /// Create a typed array from `array`.
+    ///
+    /// # Errors
+    /// Returns [`ArrayError::IncompatibleElementType`] if `T` is incompatible with the data type of `array`.
+    pub fn new(array: Array<TStorage>) -> Result<Self, ArrayError> {
+        T::validate_data_type(array.data_type())?;
+        Ok(Self {
+            array,
+            element_type: PhantomData,
+        })
+    }
+
+    /// Return the underlying array.
+    #[must_use]
+    pub fn array(&self) -> &Array<TStorage> {
+        &self.array
+    }
+
+    /// Consume the typed array and return the underlying array.
+    #[must_use]
+    pub fn into_inner(self) -> Array<TStorage> {
+        self.array
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + 'static, T: ElementOwned> TypedArray<TStorage, T> {
+    /// Read and decode the chunk at `chunk_indices` into a vector of its elements.
+    ///
+    /// See [`Array::retrieve_chunk_elements`].
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if the chunk cannot be retrieved.
+    pub fn retrieve_chunk(&self, chunk_indices: &[u64]) -> Result<Vec<T>, ArrayError> {
+        self.array.retrieve_chunk_elements(chunk_indices)
+    }
+
+    /// Read and decode the chunks at `chunks` into a vector of their elements.
+    ///
+    /// See [`Array::retrieve_chunks_elements`].
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if the chunks cannot be retrieved.
+    pub fn retrieve_chunks(&self, chunks: &ArraySubset) -> Result<Vec<T>, ArrayError> {
+        self.array.retrieve_chunks_elements(chunks)
+    }
+
+    /// Read and decode the `chunk_subset` of the chunk at `chunk_indices` into a vector of its elements.
+    ///
+    /// See [`Array::retrieve_chunk_subset_elements`].
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if the chunk subset cannot be retrieved.
+    pub fn retrieve_chunk_subset(
+        &self,
+        chunk_indices: &[u64],
+        chunk_subset: &ArraySubset,
+    ) -> Result<Vec<T>, ArrayError> {
+        self.array
+            .retrieve_chunk_subset_elements(chunk_indices, chunk_subset)
+    }
+
+    /// Read and decode the `array_subset` of the array into a vector of its elements.
+    ///
+    /// See [`Array::retrieve_array_subset_elements`].
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if the array subset cannot be retrieved.
+    pub fn retrieve_array_subset(&self, array_subset: &ArraySubset) -> Result<Vec<T>, ArrayError> {
+        self.array.retrieve_array_subset_elements(array_subset)
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Read and decode the `array_subset` of the array into an [`ndarray::ArrayD`].
+    ///
+    /// See [`Array::retrieve_array_subset_ndarray`].
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if the array subset cannot be retrieved.
+    pub fn retrieve_array_subset_ndarray(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<ndarray::ArrayD<T>, ArrayError> {
+        self.array.retrieve_array_subset_ndarray(array_subset)
+    }
+
+    /// Explicit options version of [`retrieve_chunk`](TypedArray::retrieve_chunk).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_chunk_opt(
+        &self,
+        chunk_indices: &[u64],
+        options: &CodecOptions,
+    ) -> Result<Vec<T>, ArrayError> {
+        self.array
+            .retrieve_chunk_elements_opt(chunk_indices, options)
+    }
+
+    /// Explicit options version of [`retrieve_chunks`](TypedArray::retrieve_chunks).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_chunks_opt(
+        &self,
+        chunks: &ArraySubset,
+        options: &CodecOptions,
+    ) -> Result<Vec<T>, ArrayError> {
+        self.array.retrieve_chunks_elements_opt(chunks, options)
+    }
+
+    /// Explicit options version of [`retrieve_chunk_subset`](TypedArray::retrieve_chunk_subset).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_chunk_subset_opt(
+        &self,
+        chunk_indices: &[u64],
+        chunk_subset: &ArraySubset,
+        options: &CodecOptions,
+    ) -> Result<Vec<T>, ArrayError> {
+        self.array
+            .retrieve_chunk_subset_elements_opt(chunk_indices, chunk_subset, options)
+    }
+
+    /// Explicit options version of [`retrieve_array_subset`](TypedArray::retrieve_array_subset).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_array_subset_opt(
+        &self,
+        array_subset: &ArraySubset,
+        options: &CodecOptions,
+    ) -> Result<Vec<T>, ArrayError> {
+        self.array
+            .retrieve_array_subset_elements_opt(array_subset, options)
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Explicit options version of [`retrieve_array_subset_ndarray`](TypedArray::retrieve_array_subset_ndarray).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_array_subset_ndarray_opt(
+        &self,
+        array_subset: &ArraySubset,
+        options: &CodecOptions,
+    ) -> Result<ndarray::ArrayD<T>, ArrayError> {
+        self.array
+            .retrieve_array_subset_ndarray_opt(array_subset, options)
+    }
+}
+
+impl<TStorage: ?Sized + WritableStorageTraits + 'static, T: Element> TypedArray<TStorage, T> {
+    /// Encode `chunk_elements` and store at `chunk_indices`.
+    ///
+    /// See [`Array::store_chunk_elements`].
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if the chunk cannot be stored.
+    pub fn store_chunk(
+        &self,
+        chunk_indices: &[u64],
+        chunk_elements: &[T],
+    ) -> Result<(), ArrayError> {
+        self.array
+            .store_chunk_elements(chunk_indices, chunk_elements)
+    }
+
+    /// Encode `chunks_elements` and store at the chunks with indices represented by the `chunks` array subset.
+    ///
+    /// See [`Array::store_chunks_elements`].
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if the chunks cannot be stored.
+    pub fn store_chunks(
+        &self,
+        chunks: &ArraySubset,
+        chunks_elements: &[T],
+    ) -> Result<(), ArrayError> {
+        self.array.store_chunks_elements(chunks, chunks_elements)
+    }
+
+    /// Explicit options version of [`store_chunk`](TypedArray::store_chunk).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn store_chunk_opt(
+        &self,
+        chunk_indices: &[u64],
+        chunk_elements: &[T],
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        self.array
+            .store_chunk_elements_opt(chunk_indices, chunk_elements, options)
+    }
+
+    /// Explicit options version of [`store_chunks`](TypedArray::store_chunks).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn store_chunks_opt(
+        &self,
+        chunks: &ArraySubset,
+        chunks_elements: &[T],
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        self.array
+            .store_chunks_elements_opt(chunks, chunks_elements, options)
+    }
+}
+
+impl<TStorage: ?Sized + ReadableWritableStorageTraits + 'static, T: Element>
+    TypedArray<TStorage, T>
+{
+    /// Encode `chunk_subset_elements` and store in `chunk_subset` of the chunk at `chunk_indices`.
+    ///
+    /// See [`Array::store_chunk_subset_elements`].
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if the chunk subset cannot be stored.
+    pub fn store_chunk_subset(
+        &self,
+        chunk_indices: &[u64],
+        chunk_subset: &ArraySubset,
+        chunk_subset_elements: &[T],
+    ) -> Result<(), ArrayError> {
+        self.array
+            .store_chunk_subset_elements(chunk_indices, chunk_subset, chunk_subset_elements)
+    }
+
+    /// Encode `subset_elements` and store in `array_subset`.
+    ///
+    /// See [`Array::store_array_subset_elements`].
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if the array subset cannot be stored.
+    pub fn store_array_subset(
+        &self,
+        array_subset: &ArraySubset,
+        subset_elements: &[T],
+    ) -> Result<(), ArrayError> {
+        self.array
+            .store_array_subset_elements(array_subset, subset_elements)
+    }
+
+    /// Explicit options version of [`store_chunk_subset`](TypedArray::store_chunk_subset).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn store_chunk_subset_opt(
+        &self,
+        chunk_indices: &[u64],
+        chunk_subset: &ArraySubset,
+        chunk_subset_elements: &[T],
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        self.array.store_chunk_subset_elements_opt(
+            chunk_indices,
+            chunk_subset,
+            chunk_subset_elements,
+            options,
+        )
+    }
+
+    /// Explicit options version of [`store_array_subset`](TypedArray::store_array_subset).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn store_array_subset_opt(
+        &self,
+        array_subset: &ArraySubset,
+        subset_elements: &[T],
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        self.array
+            .store_array_subset_elements_opt(array_subset, subset_elements, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        array::{ArrayBuilder, DataType, FillValue},
+        storage::store::MemoryStore,
+    };
+
+    use super::*;
+
+    #[test]
+    fn typed_array() {
+        let array = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::Int16,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0i16),
+        )
+        .build(Arc::new(MemoryStore::new()), "/")
+        .unwrap();
+        assert!(matches!(
+            TypedArray::<_, u16>::new(array),
+            Err(ArrayError::IncompatibleElementType)
+        ));
+
+        let array = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::Int16,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0i16),
+        )
+        .build(Arc::new(MemoryStore::new()), "/")
+        .unwrap();
+        let array = TypedArray::<_, i16>::new(array).unwrap();
+        array.store_chunk(&[0, 0], &[1, 2, 3, 4]).unwrap();
+        array
+            .store_array_subset(&ArraySubset::new_with_ranges(&[1..3, 1..3]), &[-1; 4])
+            .unwrap();
+        assert_eq!(array.retrieve_chunk(&[0, 0]).unwrap(), vec![1, 2, 3, -1]);
+        assert_eq!(
+            array
+                .retrieve_array_subset(&ArraySubset::new_with_ranges(&[2..3, 0..4]))
+                .unwrap(),
+            vec![0, -1, -1, 0]
+        );
+        assert_eq!(
+            array
+                .retrieve_chunk_subset(&[1, 1], &ArraySubset::new_with_ranges(&[0..1, 0..2]))
+                .unwrap(),
+            vec![-1, 0]
+        );
+        // The typed array dereferences to the array
+        assert_eq!(array.shape(), &[4, 4]);
+        assert_eq!(array.into_inner().data_type(), &DataType::Int16);
+    }
+}
@@ -0,0 +1,270 @@
+use std::sync::Arc;
+
+use crate::{
+    byte_range::{extract_byte_ranges, ByteRange},
+    storage::{store::MemoryStore, Bytes, ReadableStorageTraits, StorageError, StoreKey},
+};
+
+use super::{
+    array_bytes::{concatenate_array_bytes, split_array_bytes_elements},
+    codec::{ArrayToBytesCodecTraits, CodecOptions},
+    ravel_indices, Array, ArrayBuilder, ArrayBytes, ArrayCreateError, ArrayError, ArrayIndices,
+    ArrayShape, ArraySize, ChunkGrid, DataType, FillValue,
+};
+
+/// A read-only store of the chunks of a virtual array.
+///
+/// The chunks of a virtual array are synthesized from its fill value or a broadcasted source array rather than retrieved from storage.
+/// A virtual array is created with [`Array::new_constant`] or [`Array::new_broadcast`] and supports all of the array retrieve methods.
+///
+/// The store does not hold the array metadata.
+/// Chunks of a constant array do not exist, so they are never synthesized.
+#[derive(Debug)]
+pub struct VirtualStore {
+    array: Array<MemoryStore>,
+    source: Option<(ArrayBytes<'static>, ArrayShape)>,
+}
+
+impl VirtualStore {
+    /// Return the chunk indices of a chunk `key` of the virtual array.
+    ///
+    /// The virtual array is at the root of the store and uses the default chunk key encoding.
+    fn chunk_indices(&self, key: &StoreKey) -> Option<ArrayIndices> {
+        let key = key.as_str().strip_prefix('c')?;
+        if !key.is_empty() && !key.starts_with('/') {
+            return None;
+        }
+        let chunk_indices = key
+            .split('/')
+            .skip(1)
+            .map(str::parse)
+            .collect::<Result<ArrayIndices, _>>()
+            .ok()?;
+        (chunk_indices.len() == self.array.dimensionality()
+            && self
+                .array
+                .chunk_grid()
+                .chunk_indices_inbounds(&chunk_indices, self.array.shape()))
+        .then_some(chunk_indices)
+    }
+
+    /// Synthesize the chunk at `chunk_indices` from `source` with `source_shape`.
+    ///
+    /// Elements of the chunk outside of the array are the fill value.
+    fn chunk_bytes(
+        &self,
+        chunk_indices: &[u64],
+        source: &ArrayBytes<'_>,
+        source_shape: &[u64],
+    ) -> Result<ArrayBytes<'static>, ArrayError> {
+        let data_type_size = self.array.data_type().size();
+        let chunk_subset = self.array.chunk_subset(chunk_indices)?;
+        let source_elements = split_array_bytes_elements(source, data_type_size);
+        let fill_value =
+            ArrayBytes::new_fill_value(ArraySize::new(data_type_size, 1), self.array.fill_value());
+        let broadcast_dimensions = self.array.dimensionality() - source_shape.len();
+        let elements = chunk_subset
+            .indices()
+            .iter()
+            .map(|indices| {
+                if std::iter::zip(&indices, self.array.shape()).all(|(i, shape)| i < shape) {
+                    let source_indices: ArrayIndices =
+                        std::iter::zip(&indices[broadcast_dimensions..], source_shape)
+                            .map(|(&i, &shape)| if shape == 1 { 0 } else { i })
+                            .collect();
+                    let source_index = ravel_indices(&source_indices, source_shape);
+                    source_elements[usize::try_from(source_index).unwrap()].clone()
+                } else {
+                    fill_value.clone()
+                }
+            })
+            .collect();
+        Ok(concatenate_array_bytes(elements, data_type_size)?)
+    }
+
+    /// Synthesize and encode the chunk at `key`.
+    fn chunk_encoded(&self, key: &StoreKey) -> Result<Option<Vec<u8>>, StorageError> {
+        let Some((source, source_shape)) = &self.source else {
+            return Ok(None);
+        };
+        let Some(chunk_indices) = self.chunk_indices(key) else {
+            return Ok(None);
+        };
+        let encode = || {
+            let chunk_representation = self.array.chunk_array_representation(&chunk_indices)?;
+            let chunk_bytes = self.chunk_bytes(&chunk_indices, source, source_shape)?;
+            Ok::<_, ArrayError>(
+                self.array
+                    .codecs()
+                    .encode(chunk_bytes, &chunk_representation, &CodecOptions::default())?
+                    .into_owned(),
+            )
+        };
+        encode()
+            .map(Some)
+            .map_err(|err| StorageError::Other(err.to_string()))
+    }
+}
+
+impl ReadableStorageTraits for VirtualStore {
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        let Some(chunk_encoded) = self.chunk_encoded(key)? else {
+            return Ok(None);
+        };
+        Ok(Some(
+            extract_byte_ranges(&chunk_encoded, byte_ranges)?
+                .into_iter()
+                .map(Bytes::from)
+                .collect(),
+        ))
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        Ok(self
+            .chunk_encoded(key)?
+            .map(|chunk_encoded| chunk_encoded.len() as u64))
+    }
+}
+
+impl Array<VirtualStore> {
+    /// Create a virtual array with every element equal to `fill_value`.
+    ///
+    /// The chunks of the array do not exist, so retrieving them does not read from storage.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayCreateError`] if the array cannot be created, such as if the dimensionality of `chunk_grid` does not match `shape`.
+    pub fn new_constant(
+        shape: ArrayShape,
+        data_type: DataType,
+        chunk_grid: ChunkGrid,
+        fill_value: FillValue,
+    ) -> Result<Self, ArrayCreateError> {
+        Self::new_virtual(shape, data_type, chunk_grid, fill_value, None)
+    }
+
+    /// Create a virtual array of `source` with `source_shape` broadcast to `shape`.
+    ///
+    /// The chunks of the array are synthesized from `source` when retrieved.
+    /// Broadcasting follows the `NumPy` rules: `source_shape` is aligned with the trailing dimensions of `shape`, and each dimension of `source_shape` must equal the corresponding dimension of `shape` or one.
+    /// Elements of chunks outside of `shape` are `fill_value`.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayCreateError`] if
+    ///  - `source_shape` cannot be broadcast to `shape`,
+    ///  - `source` is incompatible with `source_shape` and `data_type`, or
+    ///  - the array cannot be created, such as if the dimensionality of `chunk_grid` does not match `shape`.
+    pub fn new_broadcast<'a>(
+        shape: ArrayShape,
+        data_type: DataType,
+        chunk_grid: ChunkGrid,
+        fill_value: FillValue,
+        source: impl Into<ArrayBytes<'a>>,
+        source_shape: &[u64],
+    ) -> Result<Self, ArrayCreateError> {
+        let broadcastable = source_shape.len() <= shape.len()
+            && std::iter::zip(source_shape.iter().rev(), shape.iter().rev())
+                .all(|(&source_shape, &shape)| source_shape == shape || source_shape == 1);
+        if !broadcastable {
+            return Err(ArrayCreateError::InvalidBroadcastSource(format!(
+                "shape {source_shape:?} cannot be broadcast to shape {shape:?}"
+            )));
+        }
+        let source = source.into();
+        source
+            .validate(source_shape.iter().product(), data_type.size())
+            .map_err(|err| ArrayCreateError::InvalidBroadcastSource(err.to_string()))?;
+        Self::new_virtual(
+            shape,
+            data_type,
+            chunk_grid,
+            fill_value,
+            Some((source.into_owned(), source_shape.to_vec())),
+        )
+    }
+
+    fn new_virtual(
+        shape: ArrayShape,
+        data_type: DataType,
+        chunk_grid: ChunkGrid,
+        fill_value: FillValue,
+        source: Option<(ArrayBytes<'static>, ArrayShape)>,
+    ) -> Result<Self, ArrayCreateError> {
+        let array = ArrayBuilder::new(shape, data_type, chunk_grid, fill_value)
+            .build(Arc::new(MemoryStore::new()), "/")?;
+        let metadata = array.metadata().clone();
+        let storage = Arc::new(VirtualStore { array, source });
+        Self::new_with_metadata(storage, "/", metadata)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::array_subset::ArraySubset;
+
+    use super::*;
+
+    #[test]
+    fn array_virtual_constant() {
+        let array = Array::new_constant(
+            vec![4, 5],
+            DataType::UInt8,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(7u8),
+        )
+        .unwrap();
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<u8>(&ArraySubset::new_with_ranges(&[1..3, 3..5]))
+                .unwrap(),
+            vec![7; 4]
+        );
+        assert!(array.retrieve_chunk_if_exists(&[0, 0]).unwrap().is_none());
+    }
+
+    #[test]
+    fn array_virtual_broadcast() {
+        assert!(Array::new_broadcast(
+            vec![3, 4],
+            DataType::UInt8,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u8),
+            vec![1, 2, 3],
+            &[3],
+        )
+        .is_err());
+        assert!(Array::new_broadcast(
+            vec![3, 4],
+            DataType::UInt8,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u8),
+            vec![1, 2, 3],
+            &[4],
+        )
+        .is_err());
+
+        let array = Array::new_broadcast(
+            vec![3, 4],
+            DataType::UInt8,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u8),
+            vec![1, 2, 3, 4],
+            &[1, 4],
+        )
+        .unwrap();
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<u8>(&array.subset_all())
+                .unwrap(),
+            vec![1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4]
+        );
+        // The chunk beyond the array shape is padded with the fill value
+        assert_eq!(
+            array.retrieve_chunk_elements::<u8>(&[1, 1]).unwrap(),
+            vec![3, 4, 0, 0]
+        );
+    }
+}
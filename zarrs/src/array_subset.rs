@@ -1,6 +1,7 @@
 //! Array subsets.
 //!
 //! An [`ArraySubset`] represents a subset of an array or chunk.
+//! An [`ArraySlice`] represents a strided subset of an array or chunk.
 //!
 //! Many [`Array`](crate::array::Array) store and retrieve methods have an [`ArraySubset`] parameter.
 //! [`iterators`] includes various types of [`ArraySubset`] iterators.
@@ -8,8 +9,11 @@
 //! This module also provides convenience functions for:
 //!  - computing the byte ranges of array subsets within an array with a fixed element size.
 
+mod array_slice;
 pub mod iterators;
 
+pub use array_slice::ArraySlice;
+
 use std::{fmt::Debug, num::NonZeroU64, ops::Range};
 
 use iterators::{
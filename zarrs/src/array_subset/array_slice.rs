@@ -0,0 +1,325 @@
+use std::{num::NonZeroU64, ops::Range};
+
+use itertools::izip;
+
+use crate::array::{ravel_indices, unravel_index, ArrayIndices, ArrayShape};
+
+use super::{ArraySubset, IncompatibleDimensionalityError};
+
+/// An array slice.
+///
+/// A strided selection of an array or chunk, with a start, a number of selected elements, and a step for each dimension.
+/// For example, the slice `[1..8;3, 0..4;2]` selects the elements at `[1, 4, 7]` of the first dimension and `[0, 2]` of the second dimension.
+///
+/// An [`ArraySubset`] is an array slice with a step of one in every dimension.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct ArraySlice {
+    /// The start of the array slice.
+    start: ArrayIndices,
+    /// The number of elements selected in each dimension.
+    shape: ArrayShape,
+    /// The step of the array slice.
+    step: Vec<NonZeroU64>,
+}
+
+impl ArraySlice {
+    /// Create a new array slice from a list of [`Range`]s and a step for each dimension.
+    ///
+    /// The elements from the start of each range with a spacing of the step that are less than the end of the range are selected.
+    ///
+    /// # Errors
+    /// Returns [`IncompatibleDimensionalityError`] if the length of `ranges` and `step` do not match.
+    pub fn new_with_ranges_step(
+        ranges: &[Range<u64>],
+        step: &[NonZeroU64],
+    ) -> Result<Self, IncompatibleDimensionalityError> {
+        if ranges.len() != step.len() {
+            return Err(IncompatibleDimensionalityError::new(
+                step.len(),
+                ranges.len(),
+            ));
+        }
+        let start = ranges.iter().map(|range| range.start).collect();
+        let shape = std::iter::zip(ranges, step)
+            .map(|(range, step)| range.end.saturating_sub(range.start).div_ceil(step.get()))
+            .collect();
+        Ok(Self {
+            start,
+            shape,
+            step: step.to_vec(),
+        })
+    }
+
+    /// Create a new array slice from a start, the number of selected elements, and a step for each dimension.
+    ///
+    /// # Errors
+    /// Returns [`IncompatibleDimensionalityError`] if the length of `start`, `shape`, and `step` do not match.
+    pub fn new_with_start_shape_step(
+        start: ArrayIndices,
+        shape: ArrayShape,
+        step: Vec<NonZeroU64>,
+    ) -> Result<Self, IncompatibleDimensionalityError> {
+        if start.len() != shape.len() {
+            Err(IncompatibleDimensionalityError::new(
+                shape.len(),
+                start.len(),
+            ))
+        } else if start.len() != step.len() {
+            Err(IncompatibleDimensionalityError::new(
+                step.len(),
+                start.len(),
+            ))
+        } else {
+            Ok(Self { start, shape, step })
+        }
+    }
+
+    /// Return the start of the array slice.
+    #[must_use]
+    pub fn start(&self) -> &[u64] {
+        &self.start
+    }
+
+    /// Return the number of elements selected in each dimension.
+    #[must_use]
+    pub fn shape(&self) -> &[u64] {
+        &self.shape
+    }
+
+    /// Return the step of the array slice.
+    #[must_use]
+    pub fn step(&self) -> &[NonZeroU64] {
+        &self.step
+    }
+
+    /// Return the dimensionality of the array slice.
+    #[must_use]
+    pub fn dimensionality(&self) -> usize {
+        self.start.len()
+    }
+
+    /// Returns if the array slice is empty (i.e. has a zero element in its shape).
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.shape.iter().any(|i| i == &0)
+    }
+
+    /// Returns [`true`] if the step of the array slice is one in every dimension.
+    #[must_use]
+    pub fn is_contiguous(&self) -> bool {
+        self.step.iter().all(|step| step.get() == 1)
+    }
+
+    /// Return the number of elements of the array slice.
+    ///
+    /// Equal to the product of the components of its shape.
+    #[must_use]
+    pub fn num_elements(&self) -> u64 {
+        self.shape.iter().product()
+    }
+
+    /// Return the number of elements of the array slice as a `usize`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`num_elements()`](Self::num_elements()) is greater than [`usize::MAX`].
+    #[must_use]
+    pub fn num_elements_usize(&self) -> usize {
+        usize::try_from(self.num_elements()).unwrap()
+    }
+
+    /// Return the smallest array subset containing the elements of the array slice.
+    #[must_use]
+    pub fn bounding_subset(&self) -> ArraySubset {
+        let shape = izip!(&self.shape, &self.step)
+            .map(|(&shape, step)| {
+                if shape == 0 {
+                    0
+                } else {
+                    (shape - 1) * step.get() + 1
+                }
+            })
+            .collect();
+        unsafe { ArraySubset::new_with_start_shape_unchecked(self.start.clone(), shape) }
+    }
+
+    /// Returns true if the array slice is within the bounds of `array_shape`.
+    #[must_use]
+    pub fn inbounds(&self, array_shape: &[u64]) -> bool {
+        self.bounding_subset().inbounds(array_shape)
+    }
+
+    /// Return the positions within the array slice of its elements within `array_subset`.
+    ///
+    /// # Errors
+    /// Returns [`IncompatibleDimensionalityError`] if the dimensionality of `array_subset` does not match the dimensionality of this array slice.
+    pub fn overlap_positions(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<ArraySubset, IncompatibleDimensionalityError> {
+        if array_subset.dimensionality() != self.dimensionality() {
+            return Err(IncompatibleDimensionalityError::new(
+                array_subset.dimensionality(),
+                self.dimensionality(),
+            ));
+        }
+        let ranges: Vec<Range<u64>> = izip!(
+            &self.start,
+            &self.shape,
+            &self.step,
+            array_subset.start(),
+            array_subset.end_exc()
+        )
+        .map(|(&start, &shape, step, &subset_start, subset_end)| {
+            let step = step.get();
+            let position_start = subset_start.saturating_sub(start).div_ceil(step);
+            let position_end = subset_end.saturating_sub(start).div_ceil(step).min(shape);
+            position_start.min(position_end)..position_end
+        })
+        .collect();
+        Ok(ArraySubset::new_with_ranges(&ranges))
+    }
+
+    /// Return the array slice of the elements at `positions` within this array slice.
+    ///
+    /// # Errors
+    /// Returns [`IncompatibleDimensionalityError`] if the dimensionality of `positions` does not match the dimensionality of this array slice.
+    pub fn subslice(
+        &self,
+        positions: &ArraySubset,
+    ) -> Result<Self, IncompatibleDimensionalityError> {
+        if positions.dimensionality() != self.dimensionality() {
+            return Err(IncompatibleDimensionalityError::new(
+                positions.dimensionality(),
+                self.dimensionality(),
+            ));
+        }
+        let start = izip!(&self.start, &self.step, positions.start())
+            .map(|(start, step, position)| start + position * step.get())
+            .collect();
+        Ok(Self {
+            start,
+            shape: positions.shape().to_vec(),
+            step: self.step.clone(),
+        })
+    }
+
+    /// Return the array slice relative to `start`.
+    ///
+    /// Creates an array slice starting at [`ArraySlice::start()`] - `start`.
+    ///
+    /// # Errors
+    /// Returns [`IncompatibleDimensionalityError`] if the length of `start` does not match the dimensionality of this array slice.
+    pub fn relative_to(&self, start: &[u64]) -> Result<Self, IncompatibleDimensionalityError> {
+        if start.len() != self.dimensionality() {
+            return Err(IncompatibleDimensionalityError::new(
+                start.len(),
+                self.dimensionality(),
+            ));
+        }
+        Ok(Self {
+            start: std::iter::zip(&self.start, start)
+                .map(|(a, b)| a - b)
+                .collect(),
+            shape: self.shape.clone(),
+            step: self.step.clone(),
+        })
+    }
+
+    /// Return an iterator over the indices of the elements of the array slice.
+    ///
+    /// Iterates over the last dimension fastest (i.e. C-contiguous order).
+    pub fn indices(&self) -> impl Iterator<Item = ArrayIndices> + '_ {
+        (0..self.num_elements()).map(|position| {
+            let position = unravel_index(position, &self.shape);
+            izip!(&self.start, &self.step, position)
+                .map(|(start, step, position)| start + position * step.get())
+                .collect()
+        })
+    }
+
+    /// Return an iterator over the linearised indices of the elements of the array slice in an array with `array_shape`.
+    ///
+    /// Iterates over the last dimension fastest (i.e. C-contiguous order).
+    /// The array slice must be within the bounds of `array_shape`.
+    pub fn linearised_indices<'a>(
+        &'a self,
+        array_shape: &'a [u64],
+    ) -> impl Iterator<Item = u64> + 'a {
+        self.indices()
+            .map(|indices| ravel_indices(&indices, array_shape))
+    }
+}
+
+impl From<ArraySubset> for ArraySlice {
+    fn from(array_subset: ArraySubset) -> Self {
+        let step = vec![NonZeroU64::new(1).unwrap(); array_subset.dimensionality()];
+        Self {
+            start: array_subset.start,
+            shape: array_subset.shape,
+            step,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn array_slice() {
+        let step = [NonZeroU64::new(3).unwrap(), NonZeroU64::new(2).unwrap()];
+        assert!(ArraySlice::new_with_ranges_step(&[1..8, 0..4], &step[..1]).is_err());
+        let array_slice = ArraySlice::new_with_ranges_step(&[1..8, 0..4], &step).unwrap();
+        assert_eq!(array_slice.start(), &[1, 0]);
+        assert_eq!(array_slice.shape(), &[3, 2]);
+        assert_eq!(array_slice.num_elements(), 6);
+        assert!(!array_slice.is_contiguous());
+        assert_eq!(
+            array_slice.bounding_subset(),
+            ArraySubset::new_with_ranges(&[1..8, 0..3])
+        );
+        assert!(array_slice.inbounds(&[8, 3]));
+        assert!(!array_slice.inbounds(&[7, 3]));
+        assert_eq!(
+            array_slice.indices().collect::<Vec<_>>(),
+            vec![
+                vec![1, 0],
+                vec![1, 2],
+                vec![4, 0],
+                vec![4, 2],
+                vec![7, 0],
+                vec![7, 2]
+            ]
+        );
+        assert_eq!(
+            array_slice.linearised_indices(&[8, 3]).collect::<Vec<_>>(),
+            vec![3, 5, 12, 14, 21, 23]
+        );
+
+        let positions = array_slice
+            .overlap_positions(&ArraySubset::new_with_ranges(&[2..5, 1..4]))
+            .unwrap();
+        assert_eq!(positions, ArraySubset::new_with_ranges(&[1..2, 1..2]));
+        assert_eq!(
+            array_slice
+                .subslice(&positions)
+                .unwrap()
+                .indices()
+                .collect::<Vec<_>>(),
+            vec![vec![4, 2]]
+        );
+        assert!(array_slice
+            .overlap_positions(&ArraySubset::new_with_ranges(&[2..4, 1..2]))
+            .unwrap()
+            .is_empty());
+        assert_eq!(array_slice.relative_to(&[1, 0]).unwrap().start(), &[0, 0]);
+
+        let array_slice = ArraySlice::from(ArraySubset::new_with_ranges(&[1..3, 2..4]));
+        assert!(array_slice.is_contiguous());
+        assert_eq!(
+            array_slice.bounding_subset(),
+            ArraySubset::new_with_ranges(&[1..3, 2..4])
+        );
+    }
+}
@@ -100,10 +100,13 @@ use crate::array::{codec::CodecOptions, ArrayMetadataOptions};
 /// ### Experimental Partial Encoding
 /// > default: [`false`]
 ///
-/// If `true`, [`Array::store_chunk_subset`](crate::array::Array::store_chunk_subset) and [`Array::store_array_subset`](crate::array::Array::store_array_subset) and variants can use partial encoding.
+/// If `true`, [`Array::store_chunk_subset`](crate::array::Array::store_chunk_subset) and [`Array::store_array_subset`](crate::array::Array::store_array_subset) and variants (including async variants) can use partial encoding.
 /// This is relevant when using the sharding codec, as it enables inner chunks to be written without reading and writing entire shards.
+/// It also enables chunks encoded with the `bytes` codec and no bytes to bytes codecs (or only the experimental `framed` codec) to be partially written without reading and writing entire chunks.
 ///
-/// This is an experimental feature for now until it has more comprehensively tested and support is added in the async API.
+/// The async API only supports efficient partial encoding with the sharding codec, and does not apply [`CodecOptions::shard_compaction_threshold`](crate::array::codec::CodecOptions::shard_compaction_threshold).
+///
+/// This is an experimental feature for now until it has more comprehensively tested.
 #[derive(Debug)]
 #[allow(clippy::struct_excessive_bools)]
 pub struct Config {
@@ -127,16 +130,38 @@ impl Default for Config {
             // Array to array
             #[cfg(feature = "bitround")]
             (codec::bitround::IDENTIFIER.to_string(), "https://codec.zarrs.dev/array_to_array/bitround".to_string()),
+            #[cfg(feature = "delta")]
+            (codec::delta::IDENTIFIER.to_string(), "https://codec.zarrs.dev/array_to_array/delta".to_string()),
+            #[cfg(feature = "fixedscaleoffset")]
+            (codec::fixedscaleoffset::IDENTIFIER.to_string(), "https://codec.zarrs.dev/array_to_array/fixedscaleoffset".to_string()),
             // Array to bytes
             #[cfg(feature = "zfp")]
             (codec::zfp::IDENTIFIER.to_string(), "https://codec.zarrs.dev/array_to_bytes/zfp".to_string()),
             #[cfg(feature = "pcodec")]
             (codec::pcodec::IDENTIFIER.to_string(), "https://codec.zarrs.dev/array_to_bytes/pcodec".to_string()),
+            #[cfg(feature = "blosc2")]
+            (codec::blosc2::IDENTIFIER.to_string(), "https://codec.zarrs.dev/array_to_bytes/blosc2".to_string()),
+            #[cfg(feature = "png")]
+            (codec::png::IDENTIFIER.to_string(), "https://codec.zarrs.dev/array_to_bytes/png".to_string()),
+            #[cfg(feature = "jpegxl")]
+            (codec::jpegxl::IDENTIFIER.to_string(), "https://codec.zarrs.dev/array_to_bytes/jpegxl".to_string()),
+            #[cfg(feature = "rle")]
+            (codec::rle::IDENTIFIER.to_string(), "https://codec.zarrs.dev/array_to_bytes/rle".to_string()),
+            #[cfg(feature = "packbits")]
+            (codec::packbits::IDENTIFIER.to_string(), "https://codec.zarrs.dev/array_to_bytes/packbits".to_string()),
+            #[cfg(feature = "sz3")]
+            (codec::sz3::IDENTIFIER.to_string(), "https://codec.zarrs.dev/array_to_bytes/sz3".to_string()),
             (codec::vlen::IDENTIFIER.to_string(), "https://codec.zarrs.dev/array_to_bytes/vlen".to_string()),
             (codec::vlen_v2::IDENTIFIER.to_string(), "https://codec.zarrs.dev/array_to_bytes/vlen_v2".to_string()),
             // Bytes to bytes
+            #[cfg(feature = "bitshuffle")]
+            (codec::bitshuffle::IDENTIFIER.to_string(), "https://codec.zarrs.dev/bytes_to_bytes/bitshuffle".to_string()),
             #[cfg(feature = "bz2")]
             (codec::bz2::IDENTIFIER.to_string(), "https://codec.zarrs.dev/bytes_to_bytes/bz2".to_string()),
+            #[cfg(feature = "framed")]
+            (codec::framed::IDENTIFIER.to_string(), "https://codec.zarrs.dev/bytes_to_bytes/framed".to_string()),
+            #[cfg(feature = "shuffle")]
+            (codec::shuffle::IDENTIFIER.to_string(), "https://codec.zarrs.dev/bytes_to_bytes/shuffle".to_string()),
         ]);
 
         Self {
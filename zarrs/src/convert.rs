@@ -0,0 +1,371 @@
+//! Conversion of Zarr hierarchies between Zarr versions.
+//!
+//! [`migrate_v2_to_v3`] converts the Zarr V2 arrays and groups of a hierarchy to Zarr V3 in place.
+//! The metadata of each node is converted as if it were stored with [`MetadataConvertVersion::V3`], written to `zarr.json`, and the Zarr V2 metadata (`.zarray`, `.zgroup`, `.zattrs`, and `.zmetadata`) is erased.
+//!
+//! A converted array keeps the `v2` chunk key encoding of the Zarr V2 array by default, so its chunks do not need to be moved.
+//! With [`MigrationOptions::with_rekey_chunks`], the chunk key encoding is changed to the `default` chunk key encoding with a `/` separator and every chunk is moved to its new key.
+//!
+//! All nodes are converted before the store is modified, so an array that cannot be converted to Zarr V3 leaves the hierarchy untouched.
+//! A dry run ([`MigrationOptions::with_dry_run`]) reports the changes that would be made without modifying the store.
+
+use std::sync::Arc;
+
+use thiserror::Error;
+
+use crate::{
+    array::{
+        chunk_key_encoding::{ChunkKeyEncodingTraits, DefaultChunkKeyEncoding},
+        Array, ArrayCreateError, ArrayMetadata, ArrayMetadataOptions,
+    },
+    config::MetadataConvertVersion,
+    group::{Group, GroupCreateError, GroupMetadata, GroupMetadataOptions},
+    node::{
+        meta_key_v2_array, meta_key_v2_attributes, meta_key_v2_consolidated, meta_key_v2_group,
+        meta_key_v3, Node, NodeCreateError, NodeMetadata,
+    },
+    storage::{Bytes, ReadableWritableListableStorageTraits, StorageError, StoreKey, StorePrefix},
+};
+
+/// Options for [`migrate_v2_to_v3`].
+#[derive(Debug, Clone, Default)]
+pub struct MigrationOptions {
+    dry_run: bool,
+    rekey_chunks: bool,
+}
+
+impl MigrationOptions {
+    /// Get the dry run configuration. Defaults to false.
+    ///
+    /// If enabled, the changes that would be made are reported but the store is not modified.
+    #[must_use]
+    pub fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Set the dry run configuration.
+    #[must_use]
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Set the dry run configuration.
+    pub fn set_dry_run(&mut self, dry_run: bool) -> &mut Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Get the rekey chunks configuration. Defaults to false.
+    ///
+    /// If enabled, converted arrays use the `default` chunk key encoding with a `/` separator, and their chunks are moved to their new keys.
+    /// Otherwise, converted arrays use the `v2` chunk key encoding and their chunks are not moved.
+    #[must_use]
+    pub fn rekey_chunks(&self) -> bool {
+        self.rekey_chunks
+    }
+
+    /// Set the rekey chunks configuration.
+    #[must_use]
+    pub fn with_rekey_chunks(mut self, rekey_chunks: bool) -> Self {
+        self.rekey_chunks = rekey_chunks;
+        self
+    }
+
+    /// Set the rekey chunks configuration.
+    pub fn set_rekey_chunks(&mut self, rekey_chunks: bool) -> &mut Self {
+        self.rekey_chunks = rekey_chunks;
+        self
+    }
+}
+
+/// A change made to a store by [`migrate_v2_to_v3`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MigrationAction {
+    /// Zarr V3 metadata is stored at the key.
+    StoreMetadata(StoreKey),
+    /// The Zarr V2 metadata at the key is erased.
+    EraseMetadata(StoreKey),
+    /// A chunk is moved from the first key to the second key.
+    MoveChunk(StoreKey, StoreKey),
+}
+
+impl core::fmt::Display for MigrationAction {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::StoreMetadata(key) => write!(f, "store {key}"),
+            Self::EraseMetadata(key) => write!(f, "erase {key}"),
+            Self::MoveChunk(from, to) => write!(f, "move {from} to {to}"),
+        }
+    }
+}
+
+/// The result of [`migrate_v2_to_v3`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MigrationReport {
+    /// The number of converted groups.
+    pub num_groups: usize,
+    /// The number of converted arrays.
+    pub num_arrays: usize,
+    /// The changes made to the store, or that would be made in a dry run.
+    pub actions: Vec<MigrationAction>,
+}
+
+/// A migration error.
+#[derive(Debug, Error)]
+pub enum MigrationError {
+    /// A storage error.
+    #[error(transparent)]
+    StorageError(#[from] StorageError),
+    /// The hierarchy could not be opened.
+    #[error(transparent)]
+    NodeCreateError(#[from] NodeCreateError),
+    /// An array could not be converted.
+    #[error(transparent)]
+    ArrayCreateError(#[from] ArrayCreateError),
+    /// A group could not be converted.
+    #[error(transparent)]
+    GroupCreateError(#[from] GroupCreateError),
+}
+
+/// Convert the Zarr V2 arrays and groups of the hierarchy at `path` in `storage` to Zarr V3 in place.
+///
+/// Nodes that are already Zarr V3 are left as is.
+/// See the [module documentation](crate::convert).
+///
+/// The chunks of an array are moved by copying them to their new keys before the Zarr V2 metadata is erased, and erasing them afterwards.
+///
+/// # Errors
+/// Returns a [`MigrationError`] if there is an underlying store error, the hierarchy cannot be opened, or an array cannot be converted to Zarr V3 (e.g. it uses a Zarr V2 codec without a Zarr V3 equivalent).
+pub fn migrate_v2_to_v3<TStorage: ?Sized + ReadableWritableListableStorageTraits>(
+    storage: &Arc<TStorage>,
+    path: &str,
+    options: &MigrationOptions,
+) -> Result<MigrationReport, MigrationError> {
+    let hierarchy = Node::open(storage, path)?;
+    let array_options =
+        ArrayMetadataOptions::default().with_metadata_convert_version(MetadataConvertVersion::V3);
+    let group_options =
+        GroupMetadataOptions::default().with_metadata_convert_version(MetadataConvertVersion::V3);
+
+    let mut report = MigrationReport::default();
+    let mut metadata_v3: Vec<(StoreKey, Bytes)> = Vec::new();
+    for node in &hierarchy {
+        let node_path = node.path();
+        let key = meta_key_v3(node_path);
+        let (json, v2_keys) = match node.metadata() {
+            NodeMetadata::Array(metadata @ ArrayMetadata::V2(_)) => {
+                let array_v2 = Array::new_with_metadata(
+                    storage.clone(),
+                    node_path.as_str(),
+                    metadata.clone(),
+                )?;
+                let mut metadata = array_v2.metadata_opt(&array_options);
+                if options.rekey_chunks() {
+                    if let ArrayMetadata::V3(metadata) = &mut metadata {
+                        metadata.chunk_key_encoding =
+                            DefaultChunkKeyEncoding::default().create_metadata();
+                    }
+                }
+                let array_v3 =
+                    Array::new_with_metadata(storage.clone(), node_path.as_str(), metadata)?;
+
+                // Move chunks with a different key
+                let prefix: StorePrefix = node_path.try_into().map_err(StorageError::from)?;
+                for chunk_key in storage.list_prefix(&prefix)? {
+                    if let Some(chunk_indices) = array_v2.chunk_indices_from_key(&chunk_key) {
+                        let chunk_key_v3 = array_v3.chunk_key(&chunk_indices);
+                        if chunk_key_v3 != chunk_key {
+                            report
+                                .actions
+                                .push(MigrationAction::MoveChunk(chunk_key, chunk_key_v3));
+                        }
+                    }
+                }
+
+                report.num_arrays += 1;
+                let json = serde_json::to_vec_pretty(array_v3.metadata())
+                    .map_err(|err| StorageError::InvalidMetadata(key.clone(), err.to_string()))?;
+                let v2_keys = vec![
+                    meta_key_v2_array(node_path),
+                    meta_key_v2_attributes(node_path),
+                ];
+                (json, v2_keys)
+            }
+            NodeMetadata::Group(metadata @ GroupMetadata::V2(_)) => {
+                let group = Group::new_with_metadata(
+                    storage.clone(),
+                    node_path.as_str(),
+                    metadata.clone(),
+                )?;
+                report.num_groups += 1;
+                let json = serde_json::to_vec_pretty(&group.metadata_opt(&group_options))
+                    .map_err(|err| StorageError::InvalidMetadata(key.clone(), err.to_string()))?;
+                let v2_keys = vec![
+                    meta_key_v2_group(node_path),
+                    meta_key_v2_attributes(node_path),
+                    meta_key_v2_consolidated(node_path),
+                ];
+                (json, v2_keys)
+            }
+            NodeMetadata::Array(ArrayMetadata::V3(_))
+            | NodeMetadata::Group(GroupMetadata::V3(_)) => {
+                continue;
+            }
+        };
+
+        report
+            .actions
+            .push(MigrationAction::StoreMetadata(key.clone()));
+        metadata_v3.push((key, json.into()));
+        for key in v2_keys {
+            if storage.size_key(&key)?.is_some() {
+                report.actions.push(MigrationAction::EraseMetadata(key));
+            }
+        }
+    }
+
+    if !options.dry_run() {
+        // Copy chunks to their new keys, so that the hierarchy remains readable if the migration is interrupted
+        for action in &report.actions {
+            if let MigrationAction::MoveChunk(from, to) = action {
+                if let Some(bytes) = storage.get(from)? {
+                    storage.set(to, bytes)?;
+                }
+            }
+        }
+
+        // Zarr V3 metadata takes precedence over Zarr V2 metadata when opening a node
+        for (key, json) in metadata_v3 {
+            storage.set(&key, json)?;
+        }
+
+        for action in &report.actions {
+            match action {
+                MigrationAction::EraseMetadata(key) | MigrationAction::MoveChunk(key, _) => {
+                    storage.erase(key)?;
+                }
+                MigrationAction::StoreMetadata(_) => {}
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        array_subset::ArraySubset,
+        storage::{store::MemoryStore, ListableStorageTraits, WritableStorageTraits},
+    };
+
+    use super::*;
+
+    fn v2_hierarchy() -> Arc<MemoryStore> {
+        let store = Arc::new(MemoryStore::new());
+        let set = |key: &str, value: &[u8]| {
+            store
+                .set(&StoreKey::new(key).unwrap(), value.to_vec().into())
+                .unwrap();
+        };
+        set(".zgroup", br#"{"zarr_format": 2}"#);
+        set(".zattrs", br#"{"title": "test"}"#);
+        set("group/.zgroup", br#"{"zarr_format": 2}"#);
+        set(
+            "group/array/.zarray",
+            br#"{
+                "zarr_format": 2,
+                "shape": [4, 4],
+                "chunks": [2, 2],
+                "dtype": "|u1",
+                "compressor": null,
+                "fill_value": 0,
+                "order": "C",
+                "filters": null,
+                "dimension_separator": "."
+            }"#,
+        );
+        set(
+            "group/array/.zattrs",
+            br#"{"_ARRAY_DIMENSIONS": ["y", "x"]}"#,
+        );
+        set("group/array/0.0", &[1, 2, 3, 4]);
+        set("group/array/1.1", &[5, 6, 7, 8]);
+        store
+    }
+
+    #[test]
+    fn migrate_v2_to_v3_dry_run() {
+        let store = v2_hierarchy();
+        let keys = store.list().unwrap();
+        let options = MigrationOptions::default()
+            .with_dry_run(true)
+            .with_rekey_chunks(true);
+        let report = migrate_v2_to_v3(&store, "/", &options).unwrap();
+        assert_eq!(report.num_groups, 2);
+        assert_eq!(report.num_arrays, 1);
+        let key = |key: &str| StoreKey::new(key).unwrap();
+        assert_eq!(
+            report.actions,
+            vec![
+                MigrationAction::StoreMetadata(key("zarr.json")),
+                MigrationAction::EraseMetadata(key(".zgroup")),
+                MigrationAction::EraseMetadata(key(".zattrs")),
+                MigrationAction::StoreMetadata(key("group/zarr.json")),
+                MigrationAction::EraseMetadata(key("group/.zgroup")),
+                MigrationAction::MoveChunk(key("group/array/0.0"), key("group/array/c/0/0")),
+                MigrationAction::MoveChunk(key("group/array/1.1"), key("group/array/c/1/1")),
+                MigrationAction::StoreMetadata(key("group/array/zarr.json")),
+                MigrationAction::EraseMetadata(key("group/array/.zarray")),
+                MigrationAction::EraseMetadata(key("group/array/.zattrs")),
+            ]
+        );
+        assert_eq!(store.list().unwrap(), keys);
+    }
+
+    #[test]
+    fn migrate_v2_to_v3_rekey() {
+        for rekey_chunks in [false, true] {
+            let store = v2_hierarchy();
+            let options = MigrationOptions::default().with_rekey_chunks(rekey_chunks);
+            migrate_v2_to_v3(&store, "/", &options).unwrap();
+
+            let group = Group::open(store.clone(), "/").unwrap();
+            assert!(matches!(group.metadata(), GroupMetadata::V3(_)));
+            assert_eq!(group.attributes()["title"], "test");
+
+            let array = Array::open(store.clone(), "/group/array").unwrap();
+            assert!(matches!(array.metadata(), ArrayMetadata::V3(_)));
+            assert_eq!(array.dimension_names().as_ref().unwrap().len(), 2);
+            assert_eq!(
+                array.chunk_key(&[1, 1]).as_str(),
+                if rekey_chunks {
+                    "group/array/c/1/1"
+                } else {
+                    "group/array/1.1"
+                }
+            );
+            assert_eq!(
+                array
+                    .retrieve_array_subset_elements::<u8>(&ArraySubset::new_with_ranges(&[
+                        0..4,
+                        0..4
+                    ]))
+                    .unwrap(),
+                vec![1, 2, 0, 0, 3, 4, 0, 0, 0, 0, 5, 6, 0, 0, 7, 8]
+            );
+            assert!(store
+                .list()
+                .unwrap()
+                .iter()
+                .all(|key| !key.as_str().ends_with(".zarray")
+                    && !key.as_str().ends_with(".zgroup")
+                    && !key.as_str().ends_with(".zattrs")));
+
+            // A migrated hierarchy is not modified again
+            let report = migrate_v2_to_v3(&store, "/", &options).unwrap();
+            assert_eq!(report, MigrationReport::default());
+        }
+    }
+}
@@ -29,6 +29,7 @@ mod group_metadata_options;
 use std::sync::Arc;
 
 use derive_more::Display;
+use serde::{de::DeserializeOwned, Serialize};
 use thiserror::Error;
 use zarrs_metadata::NodeMetadata;
 use zarrs_storage::ListableStorageTraits;
@@ -39,22 +40,29 @@ use crate::{
         global_config, MetadataConvertVersion, MetadataEraseVersion, MetadataRetrieveVersion,
     },
     metadata::{
+        nczarr::NCZarrMetadata,
+        ome::{OmeMetadata, OmeMetadataError},
         v2::GroupMetadataV2,
         v2_to_v3::group_metadata_v2_to_v3,
         v3::{AdditionalFields, UnsupportedAdditionalFieldError},
     },
     node::{
-        _get_child_nodes, meta_key_v2_attributes, meta_key_v2_group, meta_key_v3, Node, NodePath,
-        NodePathError,
+        _get_child_nodes, meta_key_v2_attributes, meta_key_v2_group, meta_key_v3,
+        metadata_v2_from_slices, update_attributes, AttributesChanges, AttributesDocument,
+        AttributesUpdateOptions, Node, NodePath, NodePathError,
+    },
+    storage::{
+        ReadableStorageTraits, ReadableWritableStorageTraits, StorageError, StorageHandle,
+        StoreKey, StorePrefix, WritableStorageTraits,
     },
-    storage::{ReadableStorageTraits, StorageError, StorageHandle, WritableStorageTraits},
 };
 
 #[cfg(feature = "async")]
-use crate::node::_async_get_child_nodes;
+use crate::node::{_async_get_child_nodes, async_update_attributes};
 #[cfg(feature = "async")]
 use crate::storage::{
-    AsyncListableStorageTraits, AsyncReadableStorageTraits, AsyncWritableStorageTraits,
+    AsyncListableStorageTraits, AsyncReadableStorageTraits, AsyncReadableWritableStorageTraits,
+    AsyncWritableStorageTraits,
 };
 
 pub use self::group_builder::GroupBuilder;
@@ -69,7 +77,6 @@ pub use group_metadata_options::GroupMetadataOptions;
 )]
 pub struct Group<TStorage: ?Sized> {
     /// The storage.
-    #[allow(dead_code)]
     storage: Arc<TStorage>,
     /// The path of the group in the store.
     #[allow(dead_code)]
@@ -104,6 +111,11 @@ impl<TStorage: ?Sized> Group<TStorage> {
         &self.path
     }
 
+    /// Get the storage.
+    pub(crate) const fn storage(&self) -> &Arc<TStorage> {
+        &self.storage
+    }
+
     /// Get attributes.
     #[must_use]
     pub const fn attributes(&self) -> &serde_json::Map<String, serde_json::Value> {
@@ -122,6 +134,66 @@ impl<TStorage: ?Sized> Group<TStorage> {
         }
     }
 
+    /// Get the OME-NGFF metadata in the attributes.
+    ///
+    /// OME-NGFF attributes that are absent are [`None`].
+    ///
+    /// # Errors
+    /// Returns an [`OmeMetadataError`] if the OME-NGFF attributes cannot be deserialized or are invalid.
+    pub fn ome_metadata(&self) -> Result<OmeMetadata, OmeMetadataError> {
+        OmeMetadata::from_attributes(self.attributes())
+    }
+
+    /// Set the OME-NGFF metadata in the attributes.
+    ///
+    /// OME-NGFF attributes that are [`None`] in `ome_metadata` are removed, and other attributes are left untouched.
+    ///
+    /// # Errors
+    /// Returns an [`OmeMetadataError`] if `ome_metadata` is invalid.
+    pub fn set_ome_metadata(
+        &mut self,
+        ome_metadata: &OmeMetadata,
+    ) -> Result<&mut Self, OmeMetadataError> {
+        ome_metadata.to_attributes(self.attributes_mut())?;
+        Ok(self)
+    }
+
+    /// Get the `NCZarr` metadata in the attributes.
+    ///
+    /// `NCZarr` attributes that are absent are [`None`].
+    ///
+    /// # Errors
+    /// Returns a [`serde_json::Error`] if the `NCZarr` attributes cannot be deserialized.
+    pub fn nczarr_metadata(&self) -> Result<NCZarrMetadata, serde_json::Error> {
+        NCZarrMetadata::from_attributes(self.attributes())
+    }
+
+    /// Deserialize the attributes into `T`.
+    ///
+    /// Attributes that are not fields of `T` are ignored, unless `T` denies unknown fields.
+    ///
+    /// # Errors
+    /// Returns a [`serde_json::Error`] if the attributes cannot be deserialized into `T`.
+    pub fn attributes_as<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_value(serde_json::Value::Object(self.attributes().clone()))
+    }
+
+    /// Serialize `value` and set it as the attribute `key`, replacing any existing attribute with the same key.
+    ///
+    /// This does **not** write to the store, use [`update_attributes`](Group::update_attributes) or [`store_metadata`](Group::store_metadata) to write the attributes to the store.
+    ///
+    /// # Errors
+    /// Returns a [`serde_json::Error`] if `value` cannot be serialized.
+    pub fn set_attribute<T: Serialize>(
+        &mut self,
+        key: &str,
+        value: T,
+    ) -> Result<&mut Self, serde_json::Error> {
+        let value = serde_json::to_value(value)?;
+        self.attributes_mut().insert(key.to_string(), value);
+        Ok(self)
+    }
+
     /// Get additional fields.
     #[must_use]
     pub const fn additional_fields(&self) -> &AdditionalFields {
@@ -161,6 +233,29 @@ impl<TStorage: ?Sized> Group<TStorage> {
         }
     }
 
+    /// Return the key and document of the metadata holding the attributes, and the changes made to the attributes by `update`.
+    fn attributes_update(
+        &self,
+        update: impl FnOnce(&mut serde_json::Map<String, serde_json::Value>),
+    ) -> Result<(StoreKey, AttributesDocument, AttributesChanges), StorageError> {
+        let mut attributes = self.attributes().clone();
+        update(&mut attributes);
+        let changes = AttributesChanges::new(self.attributes(), &attributes);
+        match &self.metadata {
+            GroupMetadata::V3(metadata) => {
+                let key = meta_key_v3(self.path());
+                let document = serde_json::to_value(metadata)
+                    .map_err(|err| StorageError::InvalidMetadata(key.clone(), err.to_string()))?;
+                Ok((key, AttributesDocument::V3(document), changes))
+            }
+            GroupMetadata::V2(metadata) => Ok((
+                meta_key_v2_attributes(self.path()),
+                AttributesDocument::V2(metadata.attributes.clone()),
+                changes,
+            )),
+        }
+    }
+
     /// Convert the group to Zarr V3.
     ///
     /// If the group is already Zarr V3, this is a no-op.
@@ -215,15 +310,14 @@ impl<TStorage: ?Sized + ReadableStorageTraits> Group<TStorage> {
             // Try Zarr V2
             let key_v2 = meta_key_v2_group(&node_path);
             if let Some(metadata) = storage.get(&key_v2)? {
-                let mut metadata: GroupMetadataV2 = serde_json::from_slice(&metadata)
-                    .map_err(|err| StorageError::InvalidMetadata(key_v2, err.to_string()))?;
                 let attributes_key = meta_key_v2_attributes(&node_path);
                 let attributes = storage.get(&attributes_key)?;
-                if let Some(attributes) = attributes {
-                    metadata.attributes = serde_json::from_slice(&attributes).map_err(|err| {
-                        StorageError::InvalidMetadata(attributes_key, err.to_string())
-                    })?;
-                }
+                let metadata: GroupMetadataV2 = metadata_v2_from_slices(
+                    &key_v2,
+                    &metadata,
+                    &attributes_key,
+                    attributes.as_deref(),
+                )?;
                 return Self::new_with_metadata(storage, path, GroupMetadata::V2(metadata));
             }
         }
@@ -319,15 +413,14 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits> Group<TStorage> {
             // Try Zarr V2
             let key_v2 = meta_key_v2_group(&node_path);
             if let Some(metadata) = storage.get(&key_v2).await? {
-                let mut metadata: GroupMetadataV2 = serde_json::from_slice(&metadata)
-                    .map_err(|err| StorageError::InvalidMetadata(key_v2, err.to_string()))?;
                 let attributes_key = meta_key_v2_attributes(&node_path);
                 let attributes = storage.get(&attributes_key).await?;
-                if let Some(attributes) = attributes {
-                    metadata.attributes = serde_json::from_slice(&attributes).map_err(|err| {
-                        StorageError::InvalidMetadata(attributes_key, err.to_string())
-                    })?;
-                }
+                let metadata: GroupMetadataV2 = metadata_v2_from_slices(
+                    &key_v2,
+                    &metadata,
+                    &attributes_key,
+                    attributes.as_deref(),
+                )?;
                 return Self::new_with_metadata(storage, path, GroupMetadata::V2(metadata));
             }
         }
@@ -508,6 +601,96 @@ impl<TStorage: ?Sized + WritableStorageTraits> Group<TStorage> {
             }
         }
     }
+
+    /// Erase the group and all of its descendants, including their metadata and chunks.
+    ///
+    /// All keys under the group prefix are erased, so deleting the root group erases the entire store.
+    /// Use [`move_node`](crate::node::move_node) to move a group rather than delete it.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if there is an underlying store error.
+    pub fn delete_recursive(&self) -> Result<(), StorageError> {
+        let prefix: StorePrefix = self.path().try_into()?;
+        self.storage.erase_prefix(&prefix)
+    }
+}
+
+impl<TStorage: ?Sized + ReadableWritableStorageTraits> Group<TStorage> {
+    /// Update the attributes with `update` and store them with default [`AttributesUpdateOptions`].
+    ///
+    /// See [`update_attributes_opt`](Group::update_attributes_opt).
+    ///
+    /// # Errors
+    /// Returns [`StorageError`] if there is an underlying store error or the stored metadata is invalid.
+    pub fn update_attributes(
+        &mut self,
+        update: impl FnOnce(&mut serde_json::Map<String, serde_json::Value>),
+    ) -> Result<(), StorageError> {
+        self.update_attributes_opt(update, &AttributesUpdateOptions::default())
+    }
+
+    /// Update the attributes with `update` and store them with non-default [`AttributesUpdateOptions`].
+    ///
+    /// `update` is applied to a copy of the attributes of the group.
+    /// The attributes it adds, modifies, or removes are merged into the attributes of the stored metadata, which is then written back.
+    /// Attributes modified by another writer are preserved unless they are also changed by `update`.
+    /// The attributes of the group are then set to the attributes that were written.
+    ///
+    /// Only the attributes of the stored metadata are changed, which is `zarr.json` for a Zarr V3 group or `.zattrs` for a Zarr V2 group.
+    /// If it has not been stored, it is created from the metadata of the group.
+    /// The store is not accessed if `update` does not change the attributes.
+    ///
+    /// With [optimistic concurrency](AttributesUpdateOptions::optimistic_concurrency), the metadata is only written if it was not modified by another writer since it was read, otherwise the update is retried.
+    ///
+    /// # Errors
+    /// Returns [`StorageError`] if there is an underlying store error or the stored metadata is invalid.
+    /// With optimistic concurrency, returns [`StorageError::PreconditionFailed`] if the metadata was modified by another writer on every attempt, or [`StorageError::Unsupported`] if the store does not support conditional writes.
+    pub fn update_attributes_opt(
+        &mut self,
+        update: impl FnOnce(&mut serde_json::Map<String, serde_json::Value>),
+        options: &AttributesUpdateOptions,
+    ) -> Result<(), StorageError> {
+        let (key, document, changes) = self.attributes_update(update)?;
+        if changes.is_empty() {
+            return Ok(());
+        }
+        let storage_handle = StorageHandle::new(self.storage.clone());
+        let (attributes, _etag) =
+            update_attributes(&storage_handle, &key, &document, &changes, options)?;
+        *self.attributes_mut() = attributes;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<TStorage: ?Sized + AsyncReadableWritableStorageTraits> Group<TStorage> {
+    /// Async variant of [`update_attributes`](Group::update_attributes).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_update_attributes(
+        &mut self,
+        update: impl FnOnce(&mut serde_json::Map<String, serde_json::Value>),
+    ) -> Result<(), StorageError> {
+        self.async_update_attributes_opt(update, &AttributesUpdateOptions::default())
+            .await
+    }
+
+    /// Async variant of [`update_attributes_opt`](Group::update_attributes_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_update_attributes_opt(
+        &mut self,
+        update: impl FnOnce(&mut serde_json::Map<String, serde_json::Value>),
+        options: &AttributesUpdateOptions,
+    ) -> Result<(), StorageError> {
+        let (key, document, changes) = self.attributes_update(update)?;
+        if changes.is_empty() {
+            return Ok(());
+        }
+        let storage_handle = StorageHandle::new(self.storage.clone());
+        let (attributes, _etag) =
+            async_update_attributes(&storage_handle, &key, &document, &changes, options).await?;
+        *self.attributes_mut() = attributes;
+        Ok(())
+    }
 }
 
 #[cfg(feature = "async")]
@@ -609,11 +792,18 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits> Group<TStorage> {
             }
         }
     }
+
+    /// Async variant of [`delete_recursive`](Group::delete_recursive).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_delete_recursive(&self) -> Result<(), StorageError> {
+        let prefix: StorePrefix = self.path().try_into()?;
+        self.storage.erase_prefix(&prefix).await
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::storage::{store::MemoryStore, StoreKey};
+    use crate::storage::store::MemoryStore;
 
     use super::*;
 
@@ -755,6 +945,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn group_ome_metadata() {
+        use crate::metadata::ome::{OmeAxis, OmeAxisType, OmeDataset, OmeMultiscale};
+
+        let store = std::sync::Arc::new(MemoryStore::new());
+        let mut group = GroupBuilder::new().build(store.clone(), "/image").unwrap();
+        group
+            .attributes_mut()
+            .insert("spam".to_string(), "ham".into());
+        assert_eq!(group.ome_metadata().unwrap(), OmeMetadata::default());
+
+        let multiscale = OmeMultiscale::new(
+            vec![
+                OmeAxis::new("y", Some(OmeAxisType::Space)).with_unit("micrometer"),
+                OmeAxis::new("x", Some(OmeAxisType::Space)).with_unit("micrometer"),
+            ],
+            vec![
+                OmeDataset::new("0", vec![0.5, 0.5]),
+                OmeDataset::new("1", vec![1.0, 1.0]),
+            ],
+        );
+        let ome_metadata = OmeMetadata {
+            multiscales: Some(vec![multiscale]),
+            labels: Some(vec!["cells".to_string()]),
+            ..Default::default()
+        };
+        group.set_ome_metadata(&ome_metadata).unwrap();
+        group.store_metadata().unwrap();
+
+        let mut group = Group::open(store, "/image").unwrap();
+        assert_eq!(group.attributes()["spam"], "ham");
+        assert_eq!(group.ome_metadata().unwrap(), ome_metadata);
+
+        let mut invalid = ome_metadata;
+        invalid.multiscales.as_mut().unwrap()[0].axes.truncate(1);
+        assert!(group.set_ome_metadata(&invalid).is_err());
+    }
+
     #[cfg(feature = "async")]
     #[tokio::test]
     async fn group_metadata_write_read_async() {
@@ -778,4 +1006,150 @@ mod tests {
         let group_path = "/group";
         assert!(Group::open(store, group_path).is_err());
     }
+
+    fn group_move_delete<
+        TStorage: ?Sized + crate::storage::ReadableWritableListableStorageTraits + 'static,
+    >(
+        store: &Arc<TStorage>,
+    ) {
+        use crate::{
+            array::{ArrayBuilder, DataType, FillValue},
+            node::{move_node, node_exists, NodeMoveError},
+        };
+
+        GroupBuilder::new()
+            .build(store.clone(), "/a")
+            .unwrap()
+            .store_metadata()
+            .unwrap();
+        let array = ArrayBuilder::new(
+            vec![4],
+            DataType::UInt8,
+            vec![2].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .build(store.clone(), "/a/array")
+        .unwrap();
+        array.store_metadata().unwrap();
+        array
+            .store_array_subset_elements(&array.subset_all(), &[1u8, 2, 3, 4])
+            .unwrap();
+
+        let path = |path: &str| NodePath::new(path).unwrap();
+        assert!(matches!(
+            move_node(store, &path("/a"), &path("/a/b")),
+            Err(NodeMoveError::InvalidPaths(_, _))
+        ));
+        assert!(matches!(
+            move_node(store, &path("/c"), &path("/d")),
+            Err(NodeMoveError::MissingNode(_))
+        ));
+        move_node(store, &path("/a"), &path("/b/c")).unwrap();
+        assert!(!node_exists(store, &path("/a")).unwrap());
+        let array = Array::open(store.clone(), "/b/c/array").unwrap();
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<u8>(&array.subset_all())
+                .unwrap(),
+            vec![1, 2, 3, 4]
+        );
+
+        let group = Group::open(store.clone(), "/b/c").unwrap();
+        group.delete_recursive().unwrap();
+        assert!(!node_exists(store, &path("/b/c")).unwrap());
+        assert!(!node_exists(store, &path("/b/c/array")).unwrap());
+    }
+
+    #[test]
+    fn group_move_delete_rename_prefix() {
+        let store = Arc::new(MemoryStore::new());
+        assert!(store.supports_rename_prefix());
+        group_move_delete(&store);
+    }
+
+    #[test]
+    fn group_move_delete_copy() {
+        use crate::storage::storage_adapter::performance_metrics::PerformanceMetricsStorageAdapter;
+        let store = Arc::new(PerformanceMetricsStorageAdapter::new(Arc::new(
+            MemoryStore::new(),
+        )));
+        assert!(!store.supports_rename_prefix());
+        group_move_delete(&store);
+    }
+
+    #[test]
+    fn group_attributes_as() {
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct Attributes {
+            title: String,
+            #[serde(default)]
+            keywords: Vec<String>,
+        }
+
+        let store = Arc::new(MemoryStore::new());
+        let mut group = GroupBuilder::new().build(store, "/group").unwrap();
+        assert!(group.attributes_as::<Attributes>().is_err());
+        group
+            .set_attribute("title", "sea surface temperature")
+            .unwrap();
+        assert_eq!(
+            group.attributes_as::<Attributes>().unwrap(),
+            Attributes {
+                title: "sea surface temperature".to_string(),
+                keywords: vec![],
+            }
+        );
+        group.set_attribute("keywords", ["ocean", "sst"]).unwrap();
+        assert_eq!(
+            group.attributes_as::<Attributes>().unwrap().keywords,
+            vec!["ocean", "sst"]
+        );
+    }
+
+    #[test]
+    fn group_update_attributes_v2() {
+        let store = Arc::new(MemoryStore::new());
+        let mut attributes = serde_json::Map::new();
+        attributes.insert("a".to_string(), 1.into());
+        let metadata = GroupMetadataV2::new().with_attributes(attributes);
+        Group::new_with_metadata(store.clone(), "/group", metadata.into())
+            .unwrap()
+            .store_metadata()
+            .unwrap();
+
+        let mut group_a = Group::open(store.clone(), "/group").unwrap();
+        let mut group_b = Group::open(store.clone(), "/group").unwrap();
+        group_a
+            .update_attributes(|attributes| {
+                attributes.insert("b".to_string(), 2.into());
+            })
+            .unwrap();
+        group_b
+            .update_attributes(|attributes| {
+                attributes.insert("a".to_string(), 10.into());
+                attributes.insert("c".to_string(), 3.into());
+            })
+            .unwrap();
+        let expected = serde_json::json!({"a": 10, "b": 2, "c": 3});
+        assert_eq!(
+            serde_json::Value::Object(group_b.attributes().clone()),
+            expected
+        );
+
+        // The attributes are stored in .zattrs
+        let group = Group::open(store.clone(), "/group").unwrap();
+        assert!(matches!(group.metadata(), GroupMetadata::V2(_)));
+        assert_eq!(
+            serde_json::Value::Object(group.attributes().clone()),
+            expected
+        );
+        let zattrs: serde_json::Value = serde_json::from_slice(
+            &store
+                .get(&meta_key_v2_attributes(group.path()))
+                .unwrap()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(zattrs, expected);
+    }
 }
@@ -26,6 +26,7 @@
 //! An existing V2 or V3 array can be opened with [`Array::open`](crate::array::Array::open).
 //! A new array can be created from V2 or V3 metadata with [`Array::new_with_metadata`](crate::array::Array::new_with_metadata).
 //! The [`ArrayBuilder`](crate::array::ArrayBuilder) only supports V3 array creation.
+//! Zarr V2 data written by `netcdf-c` in the [NCZarr](https://docs.unidata.ucar.edu/netcdf-c/current/nczarr.html) layout is supported, and a V2 hierarchy can be opened from consolidated metadata (`.zmetadata`) with [`Node::open_consolidated`](crate::node::Node::open_consolidated).
 //!
 //! `zarrs` supports forward conversion of Zarr V2 data to V3.
 //! See ["Metadata Convert Version"](crate::config::Config#metadata-convert-version) and ["Metadata Erase Version"](crate::config::Config#metadata-erase-version) for information about manipulating the version of array/group metadata.
@@ -171,7 +172,10 @@
 //!  - `async`: an **experimental** asynchronous API for [`stores`](storage), [`Array`](crate::array::Array), and [`Group`](group::Group).
 //!    - The async API is runtime-agnostic. This has some limitations that are detailed in the [`Array`](crate::array::Array) docs.
 //!    - The async API is not as performant as the sync API.
-//!  - Codecs: `bitround`, `bz2`, `pcodec`, `zfp`, `zstd`.
+//!  - `arrow`: [`Array`](crate::array::Array) methods to convert 1-dimensional arrays to and from Apache Arrow arrays, and an [`ArrayReader`](crate::array::ArrayReader) of Arrow record batches.
+//!  - `checksum_manifest`: [`Array`](crate::array::Array) methods to write and verify a SHA-256 checksum manifest of encoded chunks.
+//!  - `npy`: [`.npy` and `.npz`](crate::npy) import and export for `NumPy`.
+//!  - Codecs: `bitround`, `bitshuffle`, `blosc2`, `bz2`, `delta`, `framed`, `jpegxl`, `packbits`, `pcodec`, `png`, `rle`, `shuffle`, `sz3`, `zfp`, `zstd`.
 //!
 //! ## `zarrs` Ecosystem
 #![doc = include_str!("../doc/ecosystem.md")]
@@ -187,9 +191,14 @@
 pub mod array;
 pub mod array_subset;
 pub mod config;
+pub mod convert;
 pub mod group;
+pub mod multiscale;
 pub mod node;
+#[cfg(feature = "npy")]
+pub mod npy;
 pub mod plugin;
+pub mod validate;
 pub mod version;
 
 pub use zarrs_metadata as metadata;
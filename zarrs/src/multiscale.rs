@@ -0,0 +1,494 @@
+//! Multiscale image pyramids.
+//!
+//! A multiscale pyramid is a group of arrays holding the same image at successively lower resolutions.
+//! A [`MultiscaleBuilder`] downsamples a base [`Array`] into the levels of a pyramid in a [`Group`], and adds [OME-NGFF](https://ngff.openmicroscopy.org/0.4/#multiscale-md) `multiscales` metadata to the group attributes.
+//! Viewers that understand OME-Zarr (e.g. `napari`, `neuroglancer`, `vizarr`) can then display the pyramid directly.
+//!
+//! ```rust
+//! # use std::sync::Arc;
+//! # use zarrs::array::{ArrayBuilder, DataType, FillValue};
+//! # use zarrs::group::GroupBuilder;
+//! # use zarrs::multiscale::{DownsampleMethod, MultiscaleBuilder};
+//! # let store = Arc::new(zarrs::storage::store::MemoryStore::new());
+//! let mut group = GroupBuilder::new().build(store.clone(), "/image")?;
+//! let array = ArrayBuilder::new(
+//!     vec![64, 64],
+//!     DataType::UInt8,
+//!     vec![16, 16].try_into()?,
+//!     FillValue::from(0u8),
+//! )
+//! .dimension_names(["y", "x"].into())
+//! .build(store.clone(), "/image/0")?;
+//! array.store_metadata()?;
+//! # array.store_array_subset_elements(&array.subset_all(), &vec![1u8; 64 * 64])?;
+//!
+//! // Write levels "1" and "2" at 1/2 and 1/4 resolution
+//! let levels = MultiscaleBuilder::new(vec![2, 2], 2)
+//!     .method(DownsampleMethod::Mean)
+//!     .build::<u8, _>(&array, &mut group)?;
+//! assert_eq!(levels[1].shape(), &[16, 16]);
+//! assert!(group.attributes().contains_key("multiscales"));
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
+use num::traits::AsPrimitive;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use thiserror::Error;
+
+use crate::{
+    array::{
+        codec::CodecOptions, unravel_index, Array, ArrayBuilder, ArrayCreateError, ArrayError,
+        ChunkGrid, ElementOwned,
+    },
+    array_subset::ArraySubset,
+    group::Group,
+    metadata::ome::{OmeAxis, OmeAxisType, OmeDataset, OmeMetadataError, OmeMultiscale},
+    storage::{ReadableWritableStorageTraits, StorageError},
+};
+
+/// The method used to downsample a window of elements into a single element.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DownsampleMethod {
+    /// The mean of the elements.
+    ///
+    /// The mean is accumulated in [`f64`] precision and converted to the element type with an `as` cast, so integer means are truncated.
+    #[default]
+    Mean,
+    /// The most frequent element, or the first of the most frequent elements if there is a tie.
+    ///
+    /// This is suited to label images, where averaging would create labels that do not exist.
+    Mode,
+    /// The first element (i.e. nearest-neighbour decimation).
+    Stride,
+}
+
+impl DownsampleMethod {
+    /// Return the name of the method, which is written as the `type` of the `multiscales` metadata.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Mean => "mean",
+            Self::Mode => "mode",
+            Self::Stride => "stride",
+        }
+    }
+
+    /// Downsample a window of `elements`.
+    fn downsample<T: Copy + PartialEq + AsPrimitive<f64>>(self, elements: &[T]) -> T
+    where
+        f64: AsPrimitive<T>,
+    {
+        match self {
+            Self::Mean => {
+                #[allow(clippy::cast_precision_loss)]
+                let count = elements.len() as f64;
+                let sum: f64 = elements.iter().map(|element| element.as_()).sum();
+                (sum / count).as_()
+            }
+            Self::Mode => {
+                let mut counts: Vec<(T, usize)> = Vec::new();
+                for &element in elements {
+                    if let Some((_, count)) = counts.iter_mut().find(|(value, _)| *value == element)
+                    {
+                        *count += 1;
+                    } else {
+                        counts.push((element, 1));
+                    }
+                }
+                // max_by_key returns the last maximum, so search in reverse to prefer the first
+                counts
+                    .into_iter()
+                    .rev()
+                    .max_by_key(|(_, count)| *count)
+                    .map_or(elements[0], |(value, _)| value)
+            }
+            Self::Stride => elements[0],
+        }
+    }
+}
+
+/// A multiscale error.
+#[derive(Debug, Error)]
+pub enum MultiscaleError {
+    /// An array error.
+    #[error(transparent)]
+    ArrayError(#[from] ArrayError),
+    /// An array creation error.
+    #[error(transparent)]
+    ArrayCreateError(#[from] ArrayCreateError),
+    /// A storage error.
+    #[error(transparent)]
+    StorageError(#[from] StorageError),
+    /// Invalid downsampling factors.
+    #[error(
+        "downsampling factors {_0:?} must be non-zero and match the array dimensionality {_1}"
+    )]
+    InvalidFactors(Vec<u64>, usize),
+    /// Invalid OME-NGFF metadata, such as an unsupported number of axes.
+    #[error(transparent)]
+    OmeMetadataError(#[from] OmeMetadataError),
+    /// The base array is not a child of the group.
+    #[error("array at {_0} is not a child of the group at {_1}")]
+    NotInGroup(String, String),
+}
+
+/// A builder for the levels and metadata of a multiscale pyramid.
+///
+/// See the [module documentation](self).
+#[derive(Clone, Debug)]
+pub struct MultiscaleBuilder {
+    factors: Vec<u64>,
+    num_levels: usize,
+    method: DownsampleMethod,
+    name: Option<String>,
+}
+
+impl MultiscaleBuilder {
+    /// Create a new multiscale builder for `num_levels` downsampled levels.
+    ///
+    /// Each level is downsampled from the previous level by `factors` in each dimension.
+    /// A factor of 1 leaves a dimension (e.g. channels or time) at full resolution.
+    #[must_use]
+    pub fn new(factors: Vec<u64>, num_levels: usize) -> Self {
+        Self {
+            factors,
+            num_levels,
+            method: DownsampleMethod::default(),
+            name: None,
+        }
+    }
+
+    /// Set the downsampling method. Defaults to [`DownsampleMethod::Mean`].
+    pub fn method(&mut self, method: DownsampleMethod) -> &mut Self {
+        self.method = method;
+        self
+    }
+
+    /// Set the `name` of the `multiscales` metadata.
+    pub fn name(&mut self, name: impl Into<String>) -> &mut Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Build the multiscale pyramid of `base` in `group` with default codec options.
+    ///
+    /// See [`build_opt`](MultiscaleBuilder::build_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn build<T, TStorage>(
+        &self,
+        base: &Array<TStorage>,
+        group: &mut Group<TStorage>,
+    ) -> Result<Vec<Array<TStorage>>, MultiscaleError>
+    where
+        T: ElementOwned + Copy + PartialEq + AsPrimitive<f64> + Send + Sync,
+        f64: AsPrimitive<T>,
+        TStorage: ?Sized + ReadableWritableStorageTraits + 'static,
+    {
+        self.build_opt::<T, TStorage>(base, group, &CodecOptions::default())
+    }
+
+    /// Build the multiscale pyramid of `base` in `group`.
+    ///
+    /// `base` is the full resolution level of the pyramid and must be a child of `group`.
+    /// The downsampled levels are written to the arrays `1`, `2`, ... in `group`, with each level downsampled from the previous level.
+    /// Each level has the configuration of `base` (e.g. data type and codecs), and a regular chunk grid with the shape of the first chunk of `base`.
+    /// The chunks of each level are downsampled in parallel.
+    ///
+    /// The `multiscales` attribute of `group` is set and the metadata of the levels and `group` is stored.
+    /// The axes are named with the dimension names of `base`, if any, and conventional names (e.g. `t`, `c`, `z`, `y`, `x`) are given an axis type.
+    ///
+    /// Returns the downsampled levels.
+    ///
+    /// # Errors
+    /// Returns a [`MultiscaleError`] if
+    ///  - the factors are zero or do not match the dimensionality of `base`,
+    ///  - `base` is not a child of `group`,
+    ///  - the axes of `base` are not valid OME-NGFF axes (e.g. there are fewer than 2 or more than 5),
+    ///  - `T` is incompatible with the data type of `base`,
+    ///  - there is a codec decoding or encoding error, or
+    ///  - an underlying store error.
+    pub fn build_opt<T, TStorage>(
+        &self,
+        base: &Array<TStorage>,
+        group: &mut Group<TStorage>,
+        options: &CodecOptions,
+    ) -> Result<Vec<Array<TStorage>>, MultiscaleError>
+    where
+        T: ElementOwned + Copy + PartialEq + AsPrimitive<f64> + Send + Sync,
+        f64: AsPrimitive<T>,
+        TStorage: ?Sized + ReadableWritableStorageTraits + 'static,
+    {
+        if self.factors.len() != base.dimensionality() || self.factors.contains(&0) {
+            return Err(MultiscaleError::InvalidFactors(
+                self.factors.clone(),
+                base.dimensionality(),
+            ));
+        }
+        let base_path = relative_path(group, base)?;
+        let axes: Vec<OmeAxis> = (0..base.dimensionality())
+            .map(|i| {
+                let name = base
+                    .dimension_names()
+                    .as_ref()
+                    .and_then(|dimension_names| dimension_names[i].as_str());
+                name.map_or_else(
+                    || OmeAxis::new(format!("dim_{i}"), None),
+                    |name| OmeAxis::new(name, axis_type(name)),
+                )
+            })
+            .collect();
+        let mut scale = vec![1.0; base.dimensionality()];
+        let mut datasets = vec![OmeDataset::new(base_path, scale.clone())];
+        for level in 1..=self.num_levels {
+            for (scale, factor) in std::iter::zip(&mut scale, &self.factors) {
+                #[allow(clippy::cast_precision_loss)]
+                let factor = *factor as f64;
+                *scale *= factor;
+            }
+            datasets.push(OmeDataset::new(level.to_string(), scale.clone()));
+        }
+        let mut multiscale = OmeMultiscale::new(axes, datasets);
+        multiscale.name.clone_from(&self.name);
+        multiscale.downscaling_type = Some(self.method.name().to_string());
+        // Check the metadata before writing any levels
+        multiscale.validate()?;
+
+        let chunk_shape = base.chunk_shape(&vec![0; base.dimensionality()])?;
+        let mut builder = ArrayBuilder::from_array(base);
+        builder.chunk_grid(ChunkGrid::from(chunk_shape));
+
+        let mut levels: Vec<Array<TStorage>> = Vec::with_capacity(self.num_levels);
+        for level in 1..=self.num_levels {
+            let input = levels.last().unwrap_or(base);
+            let shape = std::iter::zip(input.shape(), &self.factors)
+                .map(|(length, factor)| length.div_ceil(*factor))
+                .collect();
+            let output = builder.shape(shape).build(
+                group.storage().clone(),
+                &child_path(group, &level.to_string()),
+            )?;
+            output.store_metadata()?;
+            self.downsample_level::<T, TStorage>(input, &output, options)?;
+            levels.push(output);
+        }
+
+        let multiscales = serde_json::to_value(vec![multiscale]).map_err(OmeMetadataError::from)?;
+        group
+            .attributes_mut()
+            .insert("multiscales".to_string(), multiscales);
+        group.store_metadata()?;
+
+        Ok(levels)
+    }
+
+    /// Downsample `input` into `output` chunk-parallel.
+    fn downsample_level<T, TStorage>(
+        &self,
+        input: &Array<TStorage>,
+        output: &Array<TStorage>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError>
+    where
+        T: ElementOwned + Copy + PartialEq + AsPrimitive<f64> + Send + Sync,
+        f64: AsPrimitive<T>,
+        TStorage: ?Sized + ReadableWritableStorageTraits + 'static,
+    {
+        let chunks: Vec<_> = output.chunks().collect();
+        chunks.into_par_iter().try_for_each(|(_, output_subset)| {
+            let input_start: Vec<u64> = std::iter::zip(output_subset.start(), &self.factors)
+                .map(|(start, factor)| start * factor)
+                .collect();
+            let input_end: Vec<u64> =
+                itertools::izip!(output_subset.end_exc(), &self.factors, input.shape())
+                    .map(|(end, factor, length)| (end * factor).min(*length))
+                    .collect();
+            // SAFETY: the start and end have the dimensionality of the input
+            let input_subset =
+                unsafe { ArraySubset::new_with_start_end_exc_unchecked(input_start, input_end) };
+            let input_elements: Vec<T> =
+                input.retrieve_array_subset_elements_opt(&input_subset, options)?;
+            let output_elements = downsample_elements(
+                self.method,
+                &input_elements,
+                input_subset.shape(),
+                output_subset.shape(),
+                &self.factors,
+            );
+            output.store_array_subset_elements_opt(&output_subset, &output_elements, options)
+        })
+    }
+}
+
+/// Downsample the `elements` with `shape` into elements with `output_shape` by `factors` in each dimension.
+fn downsample_elements<T: Copy + PartialEq + AsPrimitive<f64>>(
+    method: DownsampleMethod,
+    elements: &[T],
+    shape: &[u64],
+    output_shape: &[u64],
+    factors: &[u64],
+) -> Vec<T>
+where
+    f64: AsPrimitive<T>,
+{
+    let num_elements: u64 = output_shape.iter().product();
+    let mut window_elements = Vec::new();
+    (0..num_elements)
+        .map(|index| {
+            let output_indices = unravel_index(index, output_shape);
+            let start: Vec<u64> = std::iter::zip(&output_indices, factors)
+                .map(|(index, factor)| index * factor)
+                .collect();
+            let end: Vec<u64> = itertools::izip!(&start, factors, shape)
+                .map(|(start, factor, length)| (start + factor).min(*length))
+                .collect();
+            // SAFETY: the window is within `shape`
+            let window = unsafe { ArraySubset::new_with_start_end_exc_unchecked(start, end) };
+            let window_indices = unsafe { window.linearised_indices_unchecked(shape) };
+            window_elements.clear();
+            window_elements.extend(
+                window_indices
+                    .iter()
+                    .map(|index| elements[usize::try_from(index).unwrap()]),
+            );
+            method.downsample(&window_elements)
+        })
+        .collect()
+}
+
+/// Return the path of `array` relative to `group`.
+fn relative_path<TStorage: ?Sized>(
+    group: &Group<TStorage>,
+    array: &Array<TStorage>,
+) -> Result<String, MultiscaleError> {
+    let group_path = group.path().as_str();
+    let array_path = array.path().as_str();
+    let not_in_group =
+        || MultiscaleError::NotInGroup(array_path.to_string(), group_path.to_string());
+    let relative = array_path
+        .strip_prefix(group_path.trim_end_matches('/'))
+        .and_then(|path| path.strip_prefix('/'))
+        .ok_or_else(not_in_group)?;
+    if relative.is_empty() {
+        Err(not_in_group())
+    } else {
+        Ok(relative.to_string())
+    }
+}
+
+/// Return the path of the child `name` of `group`.
+fn child_path<TStorage: ?Sized>(group: &Group<TStorage>, name: &str) -> String {
+    format!("{}/{name}", group.path().as_str().trim_end_matches('/'))
+}
+
+/// Return the OME-NGFF axis type of a dimension named `name`, if it is a conventional name.
+fn axis_type(name: &str) -> Option<OmeAxisType> {
+    match name.to_lowercase().as_str() {
+        "x" | "y" | "z" => Some(OmeAxisType::Space),
+        "t" | "time" => Some(OmeAxisType::Time),
+        "c" | "channel" => Some(OmeAxisType::Channel),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        array::{DataType, FillValue},
+        group::GroupBuilder,
+        storage::store::MemoryStore,
+    };
+
+    use super::*;
+
+    #[test]
+    fn multiscale_downsample_elements() {
+        let elements: Vec<u8> = vec![
+            0, 1, 2, 3, 4, //
+            5, 6, 7, 8, 9, //
+            1, 1, 2, 2, 7, //
+        ];
+        assert_eq!(
+            downsample_elements(DownsampleMethod::Mean, &elements, &[3, 5], &[2, 3], &[2, 2]),
+            vec![3, 5, 6, 1, 2, 7]
+        );
+        assert_eq!(
+            downsample_elements(
+                DownsampleMethod::Stride,
+                &elements,
+                &[3, 5],
+                &[2, 3],
+                &[2, 2]
+            ),
+            vec![0, 2, 4, 1, 2, 7]
+        );
+        assert_eq!(
+            downsample_elements(DownsampleMethod::Mode, &elements, &[3, 5], &[2, 3], &[2, 2]),
+            vec![0, 2, 4, 1, 2, 7]
+        );
+        assert_eq!(
+            downsample_elements(DownsampleMethod::Mode, &[1u8, 2, 2, 1, 2], &[5], &[1], &[5]),
+            vec![2]
+        );
+    }
+
+    #[test]
+    fn multiscale_build() {
+        let store = Arc::new(MemoryStore::new());
+        let mut group = GroupBuilder::new().build(store.clone(), "/image").unwrap();
+        let array = ArrayBuilder::new(
+            vec![8, 6],
+            DataType::Float32,
+            vec![4, 4].try_into().unwrap(),
+            FillValue::from(0.0f32),
+        )
+        .dimension_names(["y", "x"].into())
+        .build(store.clone(), "/image/0")
+        .unwrap();
+        array.store_metadata().unwrap();
+        let elements: Vec<f32> = (0..48u8).map(f32::from).collect();
+        array
+            .store_array_subset_elements(&array.subset_all(), &elements)
+            .unwrap();
+
+        assert!(MultiscaleBuilder::new(vec![2], 1)
+            .build::<f32, _>(&array, &mut group)
+            .is_err());
+        assert!(MultiscaleBuilder::new(vec![2, 0], 1)
+            .build::<f32, _>(&array, &mut group)
+            .is_err());
+
+        let levels = MultiscaleBuilder::new(vec![2, 2], 2)
+            .name("image")
+            .build::<f32, _>(&array, &mut group)
+            .unwrap();
+        assert_eq!(levels.len(), 2);
+        assert_eq!(levels[0].path().as_str(), "/image/1");
+        assert_eq!(levels[0].shape(), &[4, 3]);
+        assert_eq!(levels[1].shape(), &[2, 2]);
+        assert_eq!(
+            levels[0]
+                .retrieve_array_subset_elements::<f32>(&ArraySubset::new_with_ranges(&[0..1, 0..3]))
+                .unwrap(),
+            vec![3.5, 5.5, 7.5]
+        );
+        let level = Array::open(store.clone(), "/image/2").unwrap();
+        assert_eq!(
+            level
+                .retrieve_array_subset_elements::<f32>(&level.subset_all())
+                .unwrap(),
+            vec![10.5, 13.5, 34.5, 37.5]
+        );
+
+        let group = Group::open(store, "/image").unwrap();
+        let multiscales = group.ome_metadata().unwrap().multiscales.unwrap();
+        let multiscale = &multiscales[0];
+        assert_eq!(multiscale.name.as_deref(), Some("image"));
+        assert_eq!(multiscale.downscaling_type.as_deref(), Some("mean"));
+        assert_eq!(multiscale.axes[1].name, "x");
+        assert_eq!(multiscale.axes[1].axis_type, Some(OmeAxisType::Space));
+        assert_eq!(multiscale.datasets[0].path, "0");
+        assert_eq!(multiscale.datasets[2], OmeDataset::new("2", vec![4.0, 4.0]));
+    }
+}
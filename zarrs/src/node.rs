@@ -5,6 +5,10 @@
 //! A [`Node`] has an associated [`NodePath`], [`NodeMetadata`], and children.
 //!
 //! The [`Node::hierarchy_tree`] function can be used to create a string representation of a the hierarchy below a node.
+//!
+//! [`Node::tree`] opens the entire hierarchy of a store.
+//! The nodes of a hierarchy can be traversed depth-first with [`Node::iter`], or filtered by a [`NodePathGlob`] with [`Node::iter_glob`].
+//! The [`Display`](std::fmt::Display) implementation of a [`Node`] pretty-prints the hierarchy below it.
 
 mod node_name;
 pub use node_name::{NodeName, NodeNameError};
@@ -14,11 +18,21 @@ pub use node_path::{NodePath, NodePathError};
 
 mod node_sync;
 pub(crate) use node_sync::_get_child_nodes;
-pub use node_sync::{get_child_nodes, node_exists, node_exists_listable};
+pub use node_sync::{get_child_nodes, move_node, node_exists, node_exists_listable};
+
+mod node_tree;
+pub use node_tree::{NodeIter, NodePathGlob};
+
+mod node_attributes;
+#[cfg(feature = "async")]
+pub(crate) use node_attributes::async_update_attributes;
+pub use node_attributes::AttributesUpdateOptions;
+pub(crate) use node_attributes::{update_attributes, AttributesChanges, AttributesDocument};
 
 mod key;
 pub use key::{
-    data_key, meta_key, meta_key_v2_array, meta_key_v2_attributes, meta_key_v2_group, meta_key_v3,
+    data_key, meta_key, meta_key_v2_array, meta_key_v2_attributes, meta_key_v2_consolidated,
+    meta_key_v2_group, meta_key_v3,
 };
 
 #[cfg(feature = "async")]
@@ -26,21 +40,25 @@ mod node_async;
 #[cfg(feature = "async")]
 pub(crate) use node_async::_async_get_child_nodes;
 #[cfg(feature = "async")]
-pub use node_async::{async_get_child_nodes, async_node_exists, async_node_exists_listable};
+pub use node_async::{
+    async_get_child_nodes, async_move_node, async_node_exists, async_node_exists_listable,
+};
 
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 pub use crate::metadata::NodeMetadata;
+use serde::de::DeserializeOwned;
 use thiserror::Error;
 
 use crate::{
     array::ArrayMetadata,
     config::MetadataRetrieveVersion,
     metadata::{
-        v2::{ArrayMetadataV2, GroupMetadataV2},
+        nczarr::take_nczarr_keys,
+        v2::{ArrayMetadataV2, ConsolidatedMetadataV2, GroupMetadataV2},
         GroupMetadata,
     },
-    storage::{ListableStorageTraits, ReadableStorageTraits, StorageError},
+    storage::{ListableStorageTraits, ReadableStorageTraits, StorageError, StoreKey},
 };
 
 #[cfg(feature = "async")]
@@ -84,6 +102,23 @@ pub enum NodeCreateError {
     MissingMetadata,
 }
 
+/// A node move error.
+#[derive(Debug, Error)]
+pub enum NodeMoveError {
+    /// A storage error.
+    #[error(transparent)]
+    StorageError(#[from] StorageError),
+    /// There is no node at the source path.
+    #[error("there is no node at {_0}")]
+    MissingNode(NodePath),
+    /// The destination path is not empty.
+    #[error("the destination {_0} is not empty")]
+    DestinationExists(NodePath),
+    /// The source path is the root, or the destination path is the source path or a descendant of it.
+    #[error("cannot move node {_0} to {_1}")]
+    InvalidPaths(NodePath, NodePath),
+}
+
 // FIXME: Remove in the next breaking release
 impl From<NodeCreateError> for StorageError {
     fn from(value: NodeCreateError) -> Self {
@@ -100,6 +135,64 @@ impl From<NodeCreateError> for StorageError {
     }
 }
 
+/// Zarr V2 metadata with attributes that are stored separately in `.zattrs`.
+pub(crate) trait MetadataV2WithAttributes: DeserializeOwned {
+    /// Mutably borrow the attributes.
+    fn attributes_mut(&mut self) -> &mut serde_json::Map<String, serde_json::Value>;
+}
+
+impl MetadataV2WithAttributes for ArrayMetadataV2 {
+    fn attributes_mut(&mut self) -> &mut serde_json::Map<String, serde_json::Value> {
+        &mut self.attributes
+    }
+}
+
+impl MetadataV2WithAttributes for GroupMetadataV2 {
+    fn attributes_mut(&mut self) -> &mut serde_json::Map<String, serde_json::Value> {
+        &mut self.attributes
+    }
+}
+
+/// Deserialize Zarr V2 `.zarray`/`.zgroup` `metadata` and `.zattrs` `attributes`.
+///
+/// `NCZarr` keys (`_nczarr_*`) written alongside the `.zarray`/`.zgroup` metadata by older versions of `netcdf-c` are moved to the attributes.
+fn metadata_v2_from_values<T: MetadataV2WithAttributes>(
+    mut metadata: serde_json::Value,
+    attributes: Option<serde_json::Value>,
+) -> Result<T, serde_json::Error> {
+    let nczarr_keys = match &mut metadata {
+        serde_json::Value::Object(metadata) => take_nczarr_keys(metadata),
+        _ => serde_json::Map::new(),
+    };
+    let mut metadata: T = serde_json::from_value(metadata)?;
+    if let Some(attributes) = attributes {
+        *metadata.attributes_mut() = serde_json::from_value(attributes)?;
+    }
+    for (key, value) in nczarr_keys {
+        metadata.attributes_mut().entry(key).or_insert(value);
+    }
+    Ok(metadata)
+}
+
+/// Deserialize Zarr V2 `.zarray`/`.zgroup` `metadata` and `.zattrs` `attributes` retrieved from the store.
+///
+/// See [`metadata_v2_from_values`].
+pub(crate) fn metadata_v2_from_slices<T: MetadataV2WithAttributes>(
+    metadata_key: &StoreKey,
+    metadata: &[u8],
+    attributes_key: &StoreKey,
+    attributes: Option<&[u8]>,
+) -> Result<T, StorageError> {
+    let metadata: serde_json::Value = serde_json::from_slice(metadata)
+        .map_err(|err| StorageError::InvalidMetadata(metadata_key.clone(), err.to_string()))?;
+    let attributes: Option<serde_json::Value> = attributes
+        .map(serde_json::from_slice)
+        .transpose()
+        .map_err(|err| StorageError::InvalidMetadata(attributes_key.clone(), err.to_string()))?;
+    metadata_v2_from_values(metadata, attributes)
+        .map_err(|err| StorageError::InvalidMetadata(metadata_key.clone(), err.to_string()))
+}
+
 impl Node {
     fn get_metadata<TStorage: ?Sized + ReadableStorageTraits + ListableStorageTraits>(
         storage: &Arc<TStorage>,
@@ -128,28 +221,26 @@ impl Node {
             let array_key = meta_key_v2_array(path);
             let attributes_key = meta_key_v2_attributes(path);
             if let Some(metadata) = storage.get(&array_key)? {
-                let mut metadata: ArrayMetadataV2 = serde_json::from_slice(&metadata)
-                    .map_err(|err| StorageError::InvalidMetadata(array_key, err.to_string()))?;
                 let attributes = storage.get(&attributes_key)?;
-                if let Some(attributes) = attributes {
-                    metadata.attributes = serde_json::from_slice(&attributes).map_err(|err| {
-                        StorageError::InvalidMetadata(attributes_key, err.to_string())
-                    })?;
-                }
+                let metadata: ArrayMetadataV2 = metadata_v2_from_slices(
+                    &array_key,
+                    &metadata,
+                    &attributes_key,
+                    attributes.as_deref(),
+                )?;
                 return Ok(NodeMetadata::Array(ArrayMetadata::V2(metadata)));
             }
 
             // Try a Zarr V2 group
             let group_key = meta_key_v2_group(path);
             if let Some(metadata) = storage.get(&group_key)? {
-                let mut metadata: GroupMetadataV2 = serde_json::from_slice(&metadata)
-                    .map_err(|err| StorageError::InvalidMetadata(group_key, err.to_string()))?;
                 let attributes = storage.get(&attributes_key)?;
-                if let Some(attributes) = attributes {
-                    metadata.attributes = serde_json::from_slice(&attributes).map_err(|err| {
-                        StorageError::InvalidMetadata(attributes_key, err.to_string())
-                    })?;
-                }
+                let metadata: GroupMetadataV2 = metadata_v2_from_slices(
+                    &group_key,
+                    &metadata,
+                    &attributes_key,
+                    attributes.as_deref(),
+                )?;
                 return Ok(NodeMetadata::Group(GroupMetadata::V2(metadata)));
             }
         }
@@ -190,28 +281,26 @@ impl Node {
             let array_key = meta_key_v2_array(path);
             let attributes_key = meta_key_v2_attributes(path);
             if let Some(metadata) = storage.get(&array_key).await? {
-                let mut metadata: ArrayMetadataV2 = serde_json::from_slice(&metadata)
-                    .map_err(|err| StorageError::InvalidMetadata(array_key, err.to_string()))?;
                 let attributes = storage.get(&attributes_key).await?;
-                if let Some(attributes) = attributes {
-                    metadata.attributes = serde_json::from_slice(&attributes).map_err(|err| {
-                        StorageError::InvalidMetadata(attributes_key, err.to_string())
-                    })?;
-                }
+                let metadata: ArrayMetadataV2 = metadata_v2_from_slices(
+                    &array_key,
+                    &metadata,
+                    &attributes_key,
+                    attributes.as_deref(),
+                )?;
                 return Ok(NodeMetadata::Array(ArrayMetadata::V2(metadata)));
             }
 
             // Try a Zarr V2 group
             let group_key = meta_key_v2_group(path);
             if let Some(metadata) = storage.get(&group_key).await? {
-                let mut metadata: GroupMetadataV2 = serde_json::from_slice(&metadata)
-                    .map_err(|err| StorageError::InvalidMetadata(group_key, err.to_string()))?;
                 let attributes = storage.get(&attributes_key).await?;
-                if let Some(attributes) = attributes {
-                    metadata.attributes = serde_json::from_slice(&attributes).map_err(|err| {
-                        StorageError::InvalidMetadata(attributes_key, err.to_string())
-                    })?;
-                }
+                let metadata: GroupMetadataV2 = metadata_v2_from_slices(
+                    &group_key,
+                    &metadata,
+                    &attributes_key,
+                    attributes.as_deref(),
+                )?;
                 return Ok(NodeMetadata::Group(GroupMetadata::V2(metadata)));
             }
         }
@@ -294,6 +383,141 @@ impl Node {
         Ok(node)
     }
 
+    /// Open a node at `path` and read the metadata of it and its descendants from Zarr V2 consolidated metadata (`.zmetadata`) in `storage`.
+    ///
+    /// Consolidated metadata is written by `zarr-python`, `xarray`, and `netcdf-c` (`NCZarr`).
+    /// It enables a hierarchy to be opened with a single read rather than by listing the store, which is slow for many remote stores.
+    /// Nodes in the consolidated metadata without a parent group are ignored.
+    ///
+    /// Arrays can then be opened from the metadata of a node without further reads with [`Array::new_with_metadata`](crate::array::Array::new_with_metadata).
+    ///
+    /// # Errors
+    /// Returns [`NodeCreateError::MissingMetadata`] if there is no consolidated metadata at `path` or it does not include the node at `path`.
+    /// Returns a [`NodeCreateError`] if the consolidated metadata is invalid or there is an underlying store error.
+    pub fn open_consolidated<TStorage: ?Sized + ReadableStorageTraits>(
+        storage: &Arc<TStorage>,
+        path: &str,
+    ) -> Result<Self, NodeCreateError> {
+        let path: NodePath = path.try_into()?;
+        let key = meta_key_v2_consolidated(&path);
+        let Some(consolidated_metadata) = storage.get(&key)? else {
+            return Err(NodeCreateError::MissingMetadata);
+        };
+        Self::from_consolidated_metadata(path, &key, &consolidated_metadata)
+    }
+
+    #[cfg(feature = "async")]
+    /// Asynchronously open a node at `path` and read the metadata of it and its descendants from Zarr V2 consolidated metadata (`.zmetadata`) in `storage`.
+    ///
+    /// See [`Node::open_consolidated`].
+    ///
+    /// # Errors
+    /// Returns [`NodeCreateError::MissingMetadata`] if there is no consolidated metadata at `path` or it does not include the node at `path`.
+    /// Returns a [`NodeCreateError`] if the consolidated metadata is invalid or there is an underlying store error.
+    pub async fn async_open_consolidated<TStorage: ?Sized + AsyncReadableStorageTraits>(
+        storage: Arc<TStorage>,
+        path: &str,
+    ) -> Result<Self, NodeCreateError> {
+        let path: NodePath = path.try_into()?;
+        let key = meta_key_v2_consolidated(&path);
+        let Some(consolidated_metadata) = storage.get(&key).await? else {
+            return Err(NodeCreateError::MissingMetadata);
+        };
+        Self::from_consolidated_metadata(path, &key, &consolidated_metadata)
+    }
+
+    /// Create a node at `path` from the encoded consolidated metadata at `key`.
+    fn from_consolidated_metadata(
+        path: NodePath,
+        key: &StoreKey,
+        consolidated_metadata: &[u8],
+    ) -> Result<Self, NodeCreateError> {
+        fn parent(relative_path: &str) -> Option<&str> {
+            if relative_path.is_empty() {
+                None
+            } else {
+                Some(
+                    relative_path
+                        .rsplit_once('/')
+                        .map_or("", |(parent, _)| parent),
+                )
+            }
+        }
+
+        fn new_node(
+            path: NodePath,
+            relative_path: &str,
+            metadata: NodeMetadata,
+            nodes: &mut BTreeMap<&str, NodeMetadata>,
+        ) -> Result<Node, NodePathError> {
+            let mut children = Vec::new();
+            if let NodeMetadata::Group(_) = metadata {
+                let child_relative_paths: Vec<&str> = nodes
+                    .keys()
+                    .copied()
+                    .filter(|child| parent(child) == Some(relative_path))
+                    .collect();
+                for child_relative_path in child_relative_paths {
+                    let name = child_relative_path
+                        .rsplit_once('/')
+                        .map_or(child_relative_path, |(_, name)| name);
+                    let child_path = if path.as_str() == "/" {
+                        NodePath::new(&format!("/{name}"))?
+                    } else {
+                        NodePath::new(&format!("{path}/{name}"))?
+                    };
+                    if let Some(child_metadata) = nodes.remove(child_relative_path) {
+                        children.push(new_node(
+                            child_path,
+                            child_relative_path,
+                            child_metadata,
+                            nodes,
+                        )?);
+                    }
+                }
+            }
+            Ok(Node::new_with_metadata(path, metadata, children))
+        }
+
+        let invalid_metadata =
+            |err: serde_json::Error| StorageError::InvalidMetadata(key.clone(), err.to_string());
+        let consolidated_metadata: ConsolidatedMetadataV2 =
+            serde_json::from_slice(consolidated_metadata).map_err(invalid_metadata)?;
+
+        let mut nodes: BTreeMap<&str, NodeMetadata> = BTreeMap::new();
+        for relative_path in consolidated_metadata.node_paths() {
+            let prefix = if relative_path.is_empty() {
+                String::new()
+            } else {
+                format!("{relative_path}/")
+            };
+            let attributes = consolidated_metadata
+                .get(&format!("{prefix}.zattrs"))
+                .cloned();
+            let metadata = if let Some(metadata) =
+                consolidated_metadata.get(&format!("{prefix}.zarray"))
+            {
+                NodeMetadata::Array(ArrayMetadata::V2(
+                    metadata_v2_from_values(metadata.clone(), attributes)
+                        .map_err(invalid_metadata)?,
+                ))
+            } else if let Some(metadata) = consolidated_metadata.get(&format!("{prefix}.zgroup")) {
+                NodeMetadata::Group(GroupMetadata::V2(
+                    metadata_v2_from_values(metadata.clone(), attributes)
+                        .map_err(invalid_metadata)?,
+                ))
+            } else {
+                continue;
+            };
+            nodes.insert(relative_path, metadata);
+        }
+
+        let Some(metadata) = nodes.remove("") else {
+            return Err(NodeCreateError::MissingMetadata);
+        };
+        Ok(new_node(path, "", metadata, &mut nodes)?)
+    }
+
     /// Create a new node at `path` with `metadata` and `children`.
     #[must_use]
     pub fn new_with_metadata(path: NodePath, metadata: NodeMetadata, children: Vec<Self>) -> Self {
@@ -540,6 +764,109 @@ mod tests {
         );
     }
 
+    const NCZARR_ZGROUP: &str = r#"{
+        "zarr_format": 2,
+        "_nczarr_superblock": {"version": "2.0.0"},
+        "_nczarr_group": {"dims": {"lat": 2, "lon": 3}, "vars": ["sst"], "groups": []}
+    }"#;
+
+    const NCZARR_ZARRAY: &str = r#"{
+        "zarr_format": 2,
+        "shape": [2, 3],
+        "chunks": [2, 3],
+        "dtype": "<f4",
+        "compressor": null,
+        "fill_value": 0.0,
+        "filters": null,
+        "order": "C",
+        "_nczarr_array": {"dimrefs": ["/lat", "/lon"], "storage": "chunked"}
+    }"#;
+
+    #[test]
+    fn node_nczarr() {
+        let store: std::sync::Arc<MemoryStore> = std::sync::Arc::new(MemoryStore::new());
+        store
+            .set(&StoreKey::new(".zgroup").unwrap(), NCZARR_ZGROUP.into())
+            .unwrap();
+        store
+            .set(&StoreKey::new("sst/.zarray").unwrap(), NCZARR_ZARRAY.into())
+            .unwrap();
+        store
+            .set(
+                &StoreKey::new("sst/.zattrs").unwrap(),
+                r#"{"units": "K"}"#.into(),
+            )
+            .unwrap();
+
+        let node = Node::open(&store, "/").unwrap();
+        assert_eq!(node.children().len(), 1);
+
+        let group = crate::group::Group::open(store.clone(), "/").unwrap();
+        let nczarr_metadata = group.nczarr_metadata().unwrap();
+        assert_eq!(nczarr_metadata.superblock.unwrap().version, "2.0.0");
+        assert_eq!(nczarr_metadata.group.unwrap().dims["lon"].size(), 3);
+
+        let array = crate::array::Array::open(store.clone(), "/sst").unwrap();
+        assert_eq!(
+            array.dimension_names(),
+            &Some(vec!["lat".into(), "lon".into()])
+        );
+        assert_eq!(array.attributes()["units"], "K");
+        assert!(array.nczarr_metadata().unwrap().array.is_some());
+    }
+
+    #[test]
+    fn node_consolidated() {
+        let consolidated = format!(
+            r#"{{
+                "metadata": {{
+                    ".zgroup": {NCZARR_ZGROUP},
+                    "forecast/.zgroup": {{"zarr_format": 2}},
+                    "forecast/sst/.zarray": {NCZARR_ZARRAY},
+                    "sst/.zarray": {NCZARR_ZARRAY},
+                    "sst/.zattrs": {{"units": "K"}}
+                }},
+                "zarr_consolidated_format": 1
+            }}"#
+        );
+        let store: std::sync::Arc<MemoryStore> = std::sync::Arc::new(MemoryStore::new());
+        assert!(matches!(
+            Node::open_consolidated(&store, "/"),
+            Err(NodeCreateError::MissingMetadata)
+        ));
+        store
+            .set(&StoreKey::new(".zmetadata").unwrap(), consolidated.into())
+            .unwrap();
+
+        let node = Node::open_consolidated(&store, "/").unwrap();
+        let paths: Vec<&str> = node
+            .children()
+            .iter()
+            .map(|child| child.path().as_str())
+            .collect();
+        assert_eq!(paths, vec!["/forecast", "/sst"]);
+        assert_eq!(
+            node.children()[0].children()[0].path().as_str(),
+            "/forecast/sst"
+        );
+        let sst = &node.children()[1];
+        assert_eq!(sst.path().as_str(), "/sst");
+        let NodeMetadata::Array(metadata) = sst.metadata().clone() else {
+            panic!("expected array metadata")
+        };
+        let array =
+            crate::array::Array::new_with_metadata(store.clone(), sst.path().as_str(), metadata)
+                .unwrap();
+        assert_eq!(
+            array.dimension_names(),
+            &Some(vec!["lat".into(), "lon".into()])
+        );
+        assert_eq!(array.attributes()["units"], "K");
+
+        let node = Node::open_consolidated(&store, "/forecast");
+        assert!(matches!(node, Err(NodeCreateError::MissingMetadata)));
+    }
+
     #[test]
     fn node_root() {
         let node = Node::new_with_metadata(
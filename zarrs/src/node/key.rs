@@ -45,6 +45,12 @@ pub fn meta_key_v2_attributes(path: &NodePath) -> StoreKey {
     meta_key_any(path, ".zattrs")
 }
 
+/// Return the Zarr V2 consolidated metadata key (.zmetadata) given a node path.
+#[must_use]
+pub fn meta_key_v2_consolidated(path: &NodePath) -> StoreKey {
+    meta_key_any(path, ".zmetadata")
+}
+
 /// Return the data key given a node path and a `chunk_key` of an array.
 ///
 /// A chunk key is computed with the `encode` method of a chunk key encoder.
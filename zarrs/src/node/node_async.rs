@@ -4,12 +4,13 @@ use crate::{
     config::MetadataRetrieveVersion,
     storage::{
         async_discover_children, AsyncListableStorageTraits, AsyncReadableStorageTraits,
-        StorageError, StorePrefix,
+        AsyncReadableWritableListableStorageTraits, StorageError, StorePrefix,
     },
 };
 
 use super::{
-    meta_key_v2_array, meta_key_v2_group, meta_key_v3, Node, NodeMetadata, NodePath, NodePathError,
+    meta_key_v2_array, meta_key_v2_group, meta_key_v3, node_sync::moved_key, Node, NodeMetadata,
+    NodeMoveError, NodePath, NodePathError,
 };
 
 // TODO: Replace async_get_child_nodes with this method in the next breaking release
@@ -92,3 +93,44 @@ pub async fn async_node_exists_listable<TStorage: ?Sized + AsyncListableStorageT
             | keys.contains(&meta_key_v2_group(path))
     })
 }
+
+/// Asynchronously move the node at `from` and all of its descendants to `to`.
+///
+/// See [`move_node`](crate::node::move_node).
+///
+/// # Errors
+/// Returns a [`NodeMoveError`] if
+///  - there is no node at `from`,
+///  - there are keys under `to`,
+///  - `from` is the root or `to` is `from` or a descendant of it, or
+///  - there is an underlying store error.
+pub async fn async_move_node<TStorage: ?Sized + AsyncReadableWritableListableStorageTraits>(
+    storage: &Arc<TStorage>,
+    from: &NodePath,
+    to: &NodePath,
+) -> Result<(), NodeMoveError> {
+    let from_prefix: StorePrefix = from.try_into().map_err(StorageError::from)?;
+    let to_prefix: StorePrefix = to.try_into().map_err(StorageError::from)?;
+    if from_prefix.as_str().is_empty() || to_prefix.as_str().starts_with(from_prefix.as_str()) {
+        return Err(NodeMoveError::InvalidPaths(from.clone(), to.clone()));
+    }
+    if !async_node_exists(storage, from).await? {
+        return Err(NodeMoveError::MissingNode(from.clone()));
+    }
+    if !storage.list_prefix(&to_prefix).await?.is_empty() {
+        return Err(NodeMoveError::DestinationExists(to.clone()));
+    }
+
+    if storage.supports_rename_prefix() {
+        storage.rename_prefix(&from_prefix, &to_prefix).await?;
+    } else {
+        for key in storage.list_prefix(&from_prefix).await? {
+            let to_key = moved_key(&key, &from_prefix, &to_prefix)?;
+            if let Some(value) = storage.get(&key).await? {
+                storage.set(&to_key, value).await?;
+            }
+        }
+        storage.erase_prefix(&from_prefix).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,281 @@
+use crate::storage::{
+    Bytes, ReadableWritableStorageTraits, StorageError, StoreKey, StoreValueValidator,
+};
+
+#[cfg(feature = "async")]
+use crate::storage::AsyncReadableWritableStorageTraits;
+
+/// Options for updating the attributes of an array or group.
+///
+/// See [`Array::update_attributes_opt`](crate::array::Array::update_attributes_opt) and [`Group::update_attributes_opt`](crate::group::Group::update_attributes_opt).
+#[derive(Debug, Clone)]
+pub struct AttributesUpdateOptions {
+    optimistic_concurrency: bool,
+    max_retries: usize,
+}
+
+impl Default for AttributesUpdateOptions {
+    fn default() -> Self {
+        Self {
+            optimistic_concurrency: false,
+            max_retries: 8,
+        }
+    }
+}
+
+impl AttributesUpdateOptions {
+    /// Get the optimistic concurrency configuration. Defaults to false.
+    ///
+    /// If enabled, updated attributes are written with [`set_if_match`](crate::storage::WritableStorageTraits::set_if_match) so that they are only written if the metadata has not been modified by another writer since it was read.
+    /// If the metadata was modified, the update is applied to the modified metadata and retried up to [`max_retries`](AttributesUpdateOptions::max_retries) times.
+    #[must_use]
+    pub fn optimistic_concurrency(&self) -> bool {
+        self.optimistic_concurrency
+    }
+
+    /// Set the optimistic concurrency configuration.
+    #[must_use]
+    pub fn with_optimistic_concurrency(mut self, optimistic_concurrency: bool) -> Self {
+        self.optimistic_concurrency = optimistic_concurrency;
+        self
+    }
+
+    /// Set the optimistic concurrency configuration.
+    pub fn set_optimistic_concurrency(&mut self, optimistic_concurrency: bool) -> &mut Self {
+        self.optimistic_concurrency = optimistic_concurrency;
+        self
+    }
+
+    /// Get the maximum number of retries if the metadata is modified by another writer during an update with optimistic concurrency. Defaults to 8.
+    #[must_use]
+    pub fn max_retries(&self) -> usize {
+        self.max_retries
+    }
+
+    /// Set the maximum number of retries if the metadata is modified by another writer during an update with optimistic concurrency.
+    #[must_use]
+    pub fn with_max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Set the maximum number of retries if the metadata is modified by another writer during an update with optimistic concurrency.
+    pub fn set_max_retries(&mut self, max_retries: usize) -> &mut Self {
+        self.max_retries = max_retries;
+        self
+    }
+}
+
+/// The key-level changes made to attributes by an update.
+#[derive(Debug, Clone)]
+pub(crate) struct AttributesChanges {
+    /// Attributes that were added or modified.
+    set: serde_json::Map<String, serde_json::Value>,
+    /// Attributes that were removed.
+    removed: Vec<String>,
+}
+
+impl AttributesChanges {
+    /// Return the changes that turn the `before` attributes into the `after` attributes.
+    pub(crate) fn new(
+        before: &serde_json::Map<String, serde_json::Value>,
+        after: &serde_json::Map<String, serde_json::Value>,
+    ) -> Self {
+        let set = after
+            .iter()
+            .filter(|(key, value)| before.get(*key) != Some(*value))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        let removed = before
+            .keys()
+            .filter(|key| !after.contains_key(*key))
+            .cloned()
+            .collect();
+        Self { set, removed }
+    }
+
+    /// Returns true if there are no changes.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.set.is_empty() && self.removed.is_empty()
+    }
+
+    /// Apply the changes to `attributes`.
+    ///
+    /// Attributes that were not changed are left as is.
+    pub(crate) fn apply(&self, attributes: &mut serde_json::Map<String, serde_json::Value>) {
+        for key in &self.removed {
+            attributes.remove(key);
+        }
+        for (key, value) in &self.set {
+            attributes.insert(key.clone(), value.clone());
+        }
+    }
+}
+
+/// A metadata document holding the attributes of an array or group.
+#[derive(Debug, Clone)]
+pub(crate) enum AttributesDocument {
+    /// A Zarr V3 `zarr.json` document with an `attributes` field.
+    ///
+    /// Holds the document that is used if it has not been stored.
+    V3(serde_json::Value),
+    /// A Zarr V2 `.zattrs` document.
+    ///
+    /// Holds the attributes that are used if they have not been stored.
+    V2(serde_json::Map<String, serde_json::Value>),
+}
+
+impl AttributesDocument {
+    /// Apply `changes` to the attributes of the document stored at `key`, or the default document if `bytes` is [`None`].
+    ///
+    /// Returns the encoded updated document and its attributes.
+    fn update(
+        &self,
+        key: &StoreKey,
+        bytes: Option<&[u8]>,
+        changes: &AttributesChanges,
+    ) -> Result<(Bytes, serde_json::Map<String, serde_json::Value>), StorageError> {
+        let invalid_metadata =
+            |err: serde_json::Error| StorageError::InvalidMetadata(key.clone(), err.to_string());
+        match self {
+            Self::V3(document) => {
+                let document = match bytes {
+                    Some(bytes) => serde_json::from_slice(bytes).map_err(invalid_metadata)?,
+                    None => document.clone(),
+                };
+                let serde_json::Value::Object(mut document) = document else {
+                    return Err(StorageError::InvalidMetadata(
+                        key.clone(),
+                        "metadata must be an object".to_string(),
+                    ));
+                };
+                let mut attributes = match document.remove("attributes") {
+                    Some(serde_json::Value::Object(attributes)) => attributes,
+                    None => serde_json::Map::default(),
+                    Some(_) => {
+                        return Err(StorageError::InvalidMetadata(
+                            key.clone(),
+                            "attributes must be an object".to_string(),
+                        ))
+                    }
+                };
+                changes.apply(&mut attributes);
+                if !attributes.is_empty() {
+                    document.insert(
+                        "attributes".to_string(),
+                        serde_json::Value::Object(attributes.clone()),
+                    );
+                }
+                let json = serde_json::to_vec_pretty(&document).map_err(invalid_metadata)?;
+                Ok((json.into(), attributes))
+            }
+            Self::V2(attributes) => {
+                let mut attributes = match bytes {
+                    Some(bytes) => serde_json::from_slice(bytes).map_err(invalid_metadata)?,
+                    None => attributes.clone(),
+                };
+                changes.apply(&mut attributes);
+                let json = serde_json::to_vec_pretty(&attributes).map_err(invalid_metadata)?;
+                Ok((json.into(), attributes))
+            }
+        }
+    }
+}
+
+/// Read the attributes `document` at `key`, apply `changes`, and write it back.
+///
+/// Returns the updated attributes and the entity tag of the written document if known.
+pub(crate) fn update_attributes<TStorage: ?Sized + ReadableWritableStorageTraits>(
+    storage: &TStorage,
+    key: &StoreKey,
+    document: &AttributesDocument,
+    changes: &AttributesChanges,
+    options: &AttributesUpdateOptions,
+) -> Result<
+    (
+        serde_json::Map<String, serde_json::Value>,
+        Option<StoreValueValidator>,
+    ),
+    StorageError,
+> {
+    let mut retries = 0;
+    loop {
+        let (bytes, etag) = storage.get_with_etag(key)?;
+        let (value, attributes) = document.update(key, bytes.as_deref(), changes)?;
+        if !options.optimistic_concurrency() {
+            storage.set(key, value)?;
+            // The entity tag of an unconditional write is unknown
+            return Ok((attributes, None));
+        }
+        match storage.set_if_match(key, value, etag.as_ref()) {
+            Ok(etag) => return Ok((attributes, etag)),
+            Err(StorageError::PreconditionFailed(_)) if retries < options.max_retries() => {
+                retries += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+/// Asynchronously read the attributes `document` at `key`, apply `changes`, and write it back.
+///
+/// Returns the updated attributes and the entity tag of the written document if known.
+pub(crate) async fn async_update_attributes<
+    TStorage: ?Sized + AsyncReadableWritableStorageTraits,
+>(
+    storage: &TStorage,
+    key: &StoreKey,
+    document: &AttributesDocument,
+    changes: &AttributesChanges,
+    options: &AttributesUpdateOptions,
+) -> Result<
+    (
+        serde_json::Map<String, serde_json::Value>,
+        Option<StoreValueValidator>,
+    ),
+    StorageError,
+> {
+    let mut retries = 0;
+    loop {
+        let (bytes, etag) = storage.get_with_etag(key).await?;
+        let (value, attributes) = document.update(key, bytes.as_deref(), changes)?;
+        if !options.optimistic_concurrency() {
+            storage.set(key, value).await?;
+            // The entity tag of an unconditional write is unknown
+            return Ok((attributes, None));
+        }
+        match storage.set_if_match(key, value, etag.as_ref()).await {
+            Ok(etag) => return Ok((attributes, etag)),
+            Err(StorageError::PreconditionFailed(_)) if retries < options.max_retries() => {
+                retries += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attributes_changes() {
+        let before: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(r#"{"a": 1, "b": 2, "c": 3}"#).unwrap();
+        let after: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(r#"{"a": 1, "b": 20, "d": 4}"#).unwrap();
+        let changes = AttributesChanges::new(&before, &after);
+        assert!(!changes.is_empty());
+        assert!(AttributesChanges::new(&before, &before).is_empty());
+
+        // Concurrently modified attributes that were not changed are preserved
+        let mut stored: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(r#"{"a": 10, "b": 2, "c": 3, "e": 5}"#).unwrap();
+        changes.apply(&mut stored);
+        assert_eq!(
+            serde_json::Value::Object(stored),
+            serde_json::json!({"a": 10, "b": 20, "d": 4, "e": 5})
+        );
+    }
+}
@@ -3,12 +3,14 @@ use std::sync::Arc;
 use crate::{
     config::MetadataRetrieveVersion,
     storage::{
-        discover_children, ListableStorageTraits, ReadableStorageTraits, StorageError, StorePrefix,
+        discover_children, ListableStorageTraits, ReadableStorageTraits,
+        ReadableWritableListableStorageTraits, StorageError, StoreKey, StorePrefix,
     },
 };
 
 use super::{
-    meta_key_v2_array, meta_key_v2_group, meta_key_v3, Node, NodeMetadata, NodePath, NodePathError,
+    meta_key_v2_array, meta_key_v2_group, meta_key_v3, Node, NodeMetadata, NodeMoveError, NodePath,
+    NodePathError,
 };
 
 // TODO: Replace get_child_nodes with this method in the next breaking release
@@ -84,3 +86,58 @@ pub fn node_exists_listable<TStorage: ?Sized + ListableStorageTraits>(
             | keys.contains(&meta_key_v2_group(path))
     })
 }
+
+/// Move the node at `from` and all of its descendants to `to`.
+///
+/// Node metadata does not encode the node path, so a node is moved by moving the keys of its metadata and chunks (and those of its descendants) to be under `to`.
+/// If the store [supports renaming a prefix](crate::storage::WritableStorageTraits::supports_rename_prefix) (e.g. a filesystem store), the keys are moved with a single rename.
+/// Otherwise, each key is copied to `to` and then the keys under `from` are erased.
+///
+/// Any [`Array`](crate::array::Array) or [`Group`](crate::group::Group) opened at or below `from` must be reopened at the new path.
+///
+/// # Errors
+/// Returns a [`NodeMoveError`] if
+///  - there is no node at `from`,
+///  - there are keys under `to`,
+///  - `from` is the root or `to` is `from` or a descendant of it, or
+///  - there is an underlying store error.
+pub fn move_node<TStorage: ?Sized + ReadableWritableListableStorageTraits>(
+    storage: &Arc<TStorage>,
+    from: &NodePath,
+    to: &NodePath,
+) -> Result<(), NodeMoveError> {
+    let from_prefix: StorePrefix = from.try_into().map_err(StorageError::from)?;
+    let to_prefix: StorePrefix = to.try_into().map_err(StorageError::from)?;
+    if from_prefix.as_str().is_empty() || to_prefix.as_str().starts_with(from_prefix.as_str()) {
+        return Err(NodeMoveError::InvalidPaths(from.clone(), to.clone()));
+    }
+    if !node_exists(storage, from)? {
+        return Err(NodeMoveError::MissingNode(from.clone()));
+    }
+    if !storage.list_prefix(&to_prefix)?.is_empty() {
+        return Err(NodeMoveError::DestinationExists(to.clone()));
+    }
+
+    if storage.supports_rename_prefix() {
+        storage.rename_prefix(&from_prefix, &to_prefix)?;
+    } else {
+        for key in storage.list_prefix(&from_prefix)? {
+            let to_key = moved_key(&key, &from_prefix, &to_prefix)?;
+            if let Some(value) = storage.get(&key)? {
+                storage.set(&to_key, value)?;
+            }
+        }
+        storage.erase_prefix(&from_prefix)?;
+    }
+    Ok(())
+}
+
+/// Return `key` moved from under `from` to under `to`.
+pub(crate) fn moved_key(
+    key: &StoreKey,
+    from: &StorePrefix,
+    to: &StorePrefix,
+) -> Result<StoreKey, StorageError> {
+    let suffix = key.as_str().strip_prefix(from.as_str()).unwrap_or_default();
+    Ok(StoreKey::new(format!("{}{suffix}", to.as_str()))?)
+}
@@ -0,0 +1,313 @@
+use std::sync::Arc;
+
+use crate::{
+    array::ArrayMetadata,
+    metadata::v2::array::DataTypeMetadataV2,
+    storage::{ListableStorageTraits, ReadableStorageTraits},
+};
+
+#[cfg(feature = "async")]
+use crate::storage::{AsyncListableStorageTraits, AsyncReadableStorageTraits};
+
+use super::{Node, NodeCreateError, NodeMetadata, NodePath};
+
+impl Node {
+    /// Open the hierarchy in `storage` as a tree of nodes below the root node.
+    ///
+    /// The children of each group are sorted by name.
+    /// The tree can be traversed with [`iter`](Node::iter) and [`iter_glob`](Node::iter_glob), and pretty-printed with [`Display`](std::fmt::Display).
+    ///
+    /// # Errors
+    /// Returns [`NodeCreateError`] if metadata is invalid or there is a failure to list child nodes.
+    pub fn tree<TStorage: ?Sized + ReadableStorageTraits + ListableStorageTraits>(
+        storage: &Arc<TStorage>,
+    ) -> Result<Self, NodeCreateError> {
+        let mut node = Self::open(storage, "/")?;
+        node.sort_children();
+        Ok(node)
+    }
+
+    #[cfg(feature = "async")]
+    /// Asynchronously open the hierarchy in `storage` as a tree of nodes below the root node.
+    ///
+    /// See [`Node::tree`].
+    ///
+    /// # Errors
+    /// Returns [`NodeCreateError`] if metadata is invalid or there is a failure to list child nodes.
+    pub async fn async_tree<
+        TStorage: ?Sized + AsyncReadableStorageTraits + AsyncListableStorageTraits,
+    >(
+        storage: Arc<TStorage>,
+    ) -> Result<Self, NodeCreateError> {
+        let mut node = Self::async_open(storage, "/").await?;
+        node.sort_children();
+        Ok(node)
+    }
+
+    /// Sort the children of the node and its descendants by name.
+    fn sort_children(&mut self) {
+        self.children
+            .sort_by(|a, b| a.path.as_str().cmp(b.path.as_str()));
+        for child in &mut self.children {
+            child.sort_children();
+        }
+    }
+
+    /// Returns true if the node is an array.
+    #[must_use]
+    pub fn is_array(&self) -> bool {
+        matches!(self.metadata, NodeMetadata::Array(_))
+    }
+
+    /// Returns true if the node is a group.
+    #[must_use]
+    pub fn is_group(&self) -> bool {
+        matches!(self.metadata, NodeMetadata::Group(_))
+    }
+
+    /// Return a depth-first (pre-order) iterator over the node and its descendants.
+    #[must_use]
+    pub fn iter(&self) -> NodeIter<'_> {
+        NodeIter { stack: vec![self] }
+    }
+
+    /// Return a depth-first (pre-order) iterator over the node and its descendants with a path matching `glob`.
+    pub fn iter_glob<'a>(&'a self, glob: &'a NodePathGlob) -> impl Iterator<Item = &'a Self> + 'a {
+        self.iter().filter(move |node| glob.matches(node.path()))
+    }
+
+    /// Return a short description of the node for a hierarchy tree.
+    ///
+    /// Arrays are annotated with their shape and data type.
+    fn tree_label(&self) -> String {
+        let name = if self.is_root() {
+            "/".to_string()
+        } else {
+            self.name().to_string()
+        };
+        match &self.metadata {
+            NodeMetadata::Array(ArrayMetadata::V3(metadata)) => {
+                format!("{name} {:?} {}", metadata.shape, metadata.data_type)
+            }
+            NodeMetadata::Array(ArrayMetadata::V2(metadata)) => match &metadata.dtype {
+                DataTypeMetadataV2::Simple(dtype) => {
+                    format!("{name} {:?} {dtype}", metadata.shape)
+                }
+                DataTypeMetadataV2::Structured(dtype) => {
+                    format!("{name} {:?} {dtype:?}", metadata.shape)
+                }
+            },
+            NodeMetadata::Group(_) => name,
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Node {
+    type Item = &'a Node;
+    type IntoIter = NodeIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// A depth-first (pre-order) iterator over a [`Node`] and its descendants.
+///
+/// See [`Node::iter`].
+#[derive(Debug, Clone)]
+pub struct NodeIter<'a> {
+    stack: Vec<&'a Node>,
+}
+
+impl<'a> Iterator for NodeIter<'a> {
+    type Item = &'a Node;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.stack.extend(node.children.iter().rev());
+        Some(node)
+    }
+}
+
+impl std::iter::FusedIterator for NodeIter<'_> {}
+
+/// Pretty-print the hierarchy below a node.
+///
+/// Arrays are annotated with their shape and data type.
+/// For example:
+/// ```text
+/// /
+/// ├── a
+/// │   ├── baz [10000, 1000] float64
+/// │   └── foo [10000, 1000] float64
+/// └── b
+/// ```
+impl std::fmt::Display for Node {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn write_children(
+            f: &mut std::fmt::Formatter<'_>,
+            children: &[Node],
+            prefix: &str,
+        ) -> std::fmt::Result {
+            for (i, child) in children.iter().enumerate() {
+                let last = i + 1 == children.len();
+                let (branch, indent) = if last {
+                    ("└── ", "    ")
+                } else {
+                    ("├── ", "│   ")
+                };
+                writeln!(f, "{prefix}{branch}{}", child.tree_label())?;
+                write_children(f, &child.children, &format!("{prefix}{indent}"))?;
+            }
+            Ok(())
+        }
+
+        writeln!(f, "{}", self.tree_label())?;
+        write_children(f, &self.children, "")
+    }
+}
+
+/// A glob pattern that matches node paths.
+///
+/// A pattern is a `/` separated sequence of components, where
+///  - `?` matches any single character,
+///  - `*` matches any sequence of characters within a component, and
+///  - a `**` component matches any number of components, including none.
+///
+/// The leading `/` of a pattern is optional.
+/// For example, `/**/temperature` matches `/temperature` and `/2024/01/temperature`, and `/*/t?` matches `/a/t0` but not `/a/b/t0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodePathGlob {
+    components: Vec<Vec<char>>,
+}
+
+impl NodePathGlob {
+    /// Create a new node path glob from `pattern`.
+    #[must_use]
+    pub fn new(pattern: &str) -> Self {
+        Self {
+            components: path_components(pattern)
+                .map(|component| component.chars().collect())
+                .collect(),
+        }
+    }
+
+    /// Returns true if `path` matches the glob.
+    #[must_use]
+    pub fn matches(&self, path: &NodePath) -> bool {
+        let path: Vec<Vec<char>> = path_components(path.as_str())
+            .map(|component| component.chars().collect())
+            .collect();
+        match_components(&self.components, &path)
+    }
+}
+
+/// Return the components of a node path or glob pattern.
+fn path_components(path: &str) -> impl Iterator<Item = &str> {
+    let path = path.strip_prefix('/').unwrap_or(path);
+    path.split('/').filter(|component| !component.is_empty())
+}
+
+fn match_components(pattern: &[Vec<char>], path: &[Vec<char>]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((component, pattern)) if component.as_slice() == ['*', '*'] => {
+            (0..=path.len()).any(|i| match_components(pattern, &path[i..]))
+        }
+        Some((component_pattern, pattern)) => {
+            path.split_first().is_some_and(|(component, path)| {
+                match_component(component_pattern, component) && match_components(pattern, path)
+            })
+        }
+    }
+}
+
+fn match_component(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', pattern)) => (0..=name.len()).any(|i| match_component(pattern, &name[i..])),
+        Some(('?', pattern)) => name
+            .split_first()
+            .is_some_and(|(_, name)| match_component(pattern, name)),
+        Some((c, pattern)) => name
+            .split_first()
+            .is_some_and(|(n, name)| n == c && match_component(pattern, name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        array::{ArrayBuilder, DataType, FillValue},
+        group::GroupBuilder,
+        storage::store::MemoryStore,
+    };
+
+    use super::*;
+
+    #[test]
+    fn node_path_glob() {
+        let path = |path: &str| NodePath::new(path).unwrap();
+        assert!(NodePathGlob::new("/**/temperature").matches(&path("/temperature")));
+        assert!(NodePathGlob::new("**/temperature").matches(&path("/2024/01/temperature")));
+        assert!(!NodePathGlob::new("/**/temperature").matches(&path("/temperature/0")));
+        assert!(NodePathGlob::new("/*/t?").matches(&path("/a/t0")));
+        assert!(!NodePathGlob::new("/*/t?").matches(&path("/a/b/t0")));
+        assert!(!NodePathGlob::new("/*/t?").matches(&path("/a/t")));
+        assert!(NodePathGlob::new("/a*c").matches(&path("/abbc")));
+        assert!(NodePathGlob::new("/").matches(&path("/")));
+        assert!(NodePathGlob::new("/**").matches(&path("/")));
+        assert!(NodePathGlob::new("/**").matches(&path("/a/b")));
+        assert!(!NodePathGlob::new("/*").matches(&path("/")));
+    }
+
+    #[test]
+    fn node_tree() {
+        let store = Arc::new(MemoryStore::new());
+        for path in ["/", "/b", "/a"] {
+            GroupBuilder::new()
+                .build(store.clone(), path)
+                .unwrap()
+                .store_metadata()
+                .unwrap();
+        }
+        for path in ["/a/foo", "/a/baz"] {
+            ArrayBuilder::new(
+                vec![10, 4],
+                DataType::Float32,
+                vec![5, 2].try_into().unwrap(),
+                FillValue::from(0.0f32),
+            )
+            .build(store.clone(), path)
+            .unwrap()
+            .store_metadata()
+            .unwrap();
+        }
+
+        let tree = Node::tree(&store).unwrap();
+        let paths: Vec<&str> = tree.iter().map(|node| node.path().as_str()).collect();
+        assert_eq!(paths, vec!["/", "/a", "/a/baz", "/a/foo", "/b"]);
+        assert_eq!(tree.iter().filter(|node| node.is_array()).count(), 2);
+        assert_eq!(
+            (&tree).into_iter().filter(|node| node.is_group()).count(),
+            3
+        );
+
+        let glob = NodePathGlob::new("/a/*");
+        let paths: Vec<&str> = tree
+            .iter_glob(&glob)
+            .map(|node| node.path().as_str())
+            .collect();
+        assert_eq!(paths, vec!["/a/baz", "/a/foo"]);
+
+        assert_eq!(
+            tree.to_string(),
+            "/
+├── a
+│   ├── baz [10, 4] float32
+│   └── foo [10, 4] float32
+└── b
+"
+        );
+    }
+}
@@ -0,0 +1,730 @@
+//! `NumPy` `.npy` and `.npz` import and export.
+//!
+//! The [`.npy` format](https://numpy.org/doc/stable/reference/generated/numpy.lib.format.html) stores a single array as a short header followed by its raw elements.
+//! A `.npz` file is a zip archive of `.npy` files.
+//!
+//! Arrays are imported and exported in slabs that span the chunks of the array along one dimension, so the elements of an array are never held in memory at once.
+//! Data types map as follows:
+//!
+//! | `NumPy`                   | Zarr                          |
+//! |---------------------------|-------------------------------|
+//! | `bool`                    | `bool`                        |
+//! | `int8` ... `int64`        | `int8` ... `int64`            |
+//! | `uint8` ... `uint64`      | `uint8` ... `uint64`          |
+//! | `float16` ... `float64`   | `float16` ... `float64`       |
+//! | `complex64`, `complex128` | `complex64`, `complex128`     |
+//! | `V<n>` (void)             | `r<8n>` (raw bits)            |
+//!
+//! Both little and big endian `.npy` files can be imported, and Fortran order `.npy` files are transposed on import.
+//! Exported `.npy` files are in native endianness and C order.
+//!
+//! ```rust
+//! # use std::sync::Arc;
+//! # use zarrs::array::{ArrayBuilder, DataType, FillValue};
+//! # use zarrs::storage::store::MemoryStore;
+//! # use zarrs::npy::{create_array_from_npy, write_npy};
+//! # let store = Arc::new(MemoryStore::new());
+//! let array = ArrayBuilder::new(
+//!     vec![4, 4],
+//!     DataType::Float32,
+//!     vec![2, 2].try_into()?,
+//!     FillValue::from(0.0f32),
+//! )
+//! .build(store.clone(), "/array")?;
+//! array.store_array_subset_elements(&array.subset_all(), &[1.0f32; 16])?;
+//!
+//! let mut npy = Vec::new();
+//! write_npy(&array, &array.subset_all(), &mut npy)?;
+//!
+//! let copy = create_array_from_npy(store, "/copy", npy.as_slice(), vec![4, 1].try_into()?)?;
+//! assert_eq!(copy.retrieve_array_subset_elements::<f32>(&copy.subset_all())?, vec![1.0f32; 16]);
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
+use std::{
+    io::{Read, Seek, Write},
+    sync::Arc,
+};
+
+use thiserror::Error;
+
+use crate::{
+    array::{
+        codec::array_to_bytes::bytes::reverse_endianness, Array, ArrayBuilder, ArrayBytesView,
+        ArrayCreateError, ArrayError, ArrayShape, ChunkGrid, DataType, Endianness, FillValue,
+    },
+    array_subset::ArraySubset,
+    storage::{ReadableStorageTraits, ReadableWritableStorageTraits, StorageError},
+};
+
+/// The magic string at the start of a `.npy` file.
+const NPY_MAGIC: &[u8] = b"\x93NUMPY";
+
+/// The alignment of the data of a `.npy` file.
+const NPY_ALIGNMENT: usize = 64;
+
+/// A `.npy` error.
+#[derive(Debug, Error)]
+pub enum NpyError {
+    /// An IO error.
+    #[error(transparent)]
+    IOError(#[from] std::io::Error),
+    /// An invalid `.npy` header.
+    #[error("invalid npy header: {_0}")]
+    InvalidHeader(String),
+    /// A data type that cannot be represented in both `NumPy` and Zarr.
+    #[error("unsupported npy data type {_0}")]
+    UnsupportedDataType(String),
+    /// The `.npy` data is incompatible with the array.
+    #[error("npy data with data type {_0} and shape {_1:?} is incompatible with array with data type {_2} and shape {_3:?}")]
+    IncompatibleArray(DataType, ArrayShape, DataType, ArrayShape),
+    /// An array error.
+    #[error(transparent)]
+    ArrayError(#[from] ArrayError),
+    /// An array creation error.
+    #[error(transparent)]
+    ArrayCreateError(#[from] ArrayCreateError),
+    /// A store error.
+    #[error(transparent)]
+    StorageError(#[from] StorageError),
+    /// A zip error.
+    #[error(transparent)]
+    ZipError(#[from] zip::result::ZipError),
+}
+
+/// Return the `NumPy` array protocol type string of a data type in native endianness, e.g. `<f8`.
+///
+/// # Errors
+/// Returns [`NpyError::UnsupportedDataType`] if the data type is not supported by `NumPy` (e.g. `bfloat16`, `string`, `bytes`).
+pub fn data_type_to_npy_descr(data_type: &DataType) -> Result<String, NpyError> {
+    let endianness = match Endianness::native() {
+        Endianness::Little => '<',
+        Endianness::Big => '>',
+    };
+    Ok(match data_type {
+        DataType::Bool => "|b1".to_string(),
+        DataType::Int8 => "|i1".to_string(),
+        DataType::UInt8 => "|u1".to_string(),
+        DataType::Int16 => format!("{endianness}i2"),
+        DataType::Int32 => format!("{endianness}i4"),
+        DataType::Int64 => format!("{endianness}i8"),
+        DataType::UInt16 => format!("{endianness}u2"),
+        DataType::UInt32 => format!("{endianness}u4"),
+        DataType::UInt64 => format!("{endianness}u8"),
+        DataType::Float16 => format!("{endianness}f2"),
+        DataType::Float32 => format!("{endianness}f4"),
+        DataType::Float64 => format!("{endianness}f8"),
+        DataType::Complex64 => format!("{endianness}c8"),
+        DataType::Complex128 => format!("{endianness}c16"),
+        DataType::RawBits(size) => format!("|V{size}"),
+        DataType::BFloat16 | DataType::String | DataType::Binary => {
+            return Err(NpyError::UnsupportedDataType(data_type.to_string()));
+        }
+    })
+}
+
+/// Return the data type and endianness of a `NumPy` array protocol type string, e.g. `<f8`.
+///
+/// Single byte data types and `|` (not applicable) or `=` (native) byte orders are reported as native endianness.
+///
+/// # Errors
+/// Returns [`NpyError::UnsupportedDataType`] if the type string is not supported.
+pub fn data_type_from_npy_descr(descr: &str) -> Result<(DataType, Endianness), NpyError> {
+    let unsupported = || NpyError::UnsupportedDataType(descr.to_string());
+    let mut chars = descr.chars();
+    let endianness = match chars.next() {
+        Some('<') => Endianness::Little,
+        Some('>') => Endianness::Big,
+        Some('|' | '=') => Endianness::native(),
+        _ => return Err(unsupported()),
+    };
+    let kind = chars.next().ok_or_else(unsupported)?;
+    let size: usize = chars.as_str().parse().map_err(|_| unsupported())?;
+    let data_type = match (kind, size) {
+        ('b', 1) => DataType::Bool,
+        ('i', 1) => DataType::Int8,
+        ('i', 2) => DataType::Int16,
+        ('i', 4) => DataType::Int32,
+        ('i', 8) => DataType::Int64,
+        ('u', 1) => DataType::UInt8,
+        ('u', 2) => DataType::UInt16,
+        ('u', 4) => DataType::UInt32,
+        ('u', 8) => DataType::UInt64,
+        ('f', 2) => DataType::Float16,
+        ('f', 4) => DataType::Float32,
+        ('f', 8) => DataType::Float64,
+        ('c', 8) => DataType::Complex64,
+        ('c', 16) => DataType::Complex128,
+        ('V', size) if size > 0 => DataType::RawBits(size),
+        _ => return Err(unsupported()),
+    };
+    Ok((data_type, endianness))
+}
+
+/// The header of a `.npy` file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NpyHeader {
+    /// The data type of the elements.
+    pub data_type: DataType,
+    /// The endianness of the elements.
+    pub endianness: Endianness,
+    /// True if the elements are in Fortran order rather than C order.
+    pub fortran_order: bool,
+    /// The shape of the array.
+    pub shape: ArrayShape,
+}
+
+impl NpyHeader {
+    /// Create a new header for elements of `data_type` in native endianness and C order.
+    #[must_use]
+    pub fn new(data_type: DataType, shape: ArrayShape) -> Self {
+        Self {
+            data_type,
+            endianness: Endianness::native(),
+            fortran_order: false,
+            shape,
+        }
+    }
+
+    /// Read a header from the start of a `.npy` file, leaving `reader` at the start of the elements.
+    ///
+    /// # Errors
+    /// Returns an [`NpyError`] if the header is invalid or unsupported, or there is an IO error.
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, NpyError> {
+        let mut preamble = [0u8; 8];
+        reader.read_exact(&mut preamble)?;
+        if &preamble[..6] != NPY_MAGIC {
+            return Err(NpyError::InvalidHeader("missing magic string".to_string()));
+        }
+        let header_len = match preamble[6] {
+            1 => {
+                let mut header_len = [0u8; 2];
+                reader.read_exact(&mut header_len)?;
+                usize::from(u16::from_le_bytes(header_len))
+            }
+            2 | 3 => {
+                let mut header_len = [0u8; 4];
+                reader.read_exact(&mut header_len)?;
+                usize::try_from(u32::from_le_bytes(header_len))
+                    .map_err(|_| NpyError::InvalidHeader("header is too long".to_string()))?
+            }
+            version => {
+                return Err(NpyError::InvalidHeader(format!(
+                    "unsupported version {version}"
+                )))
+            }
+        };
+        let mut header = vec![0u8; header_len];
+        reader.read_exact(&mut header)?;
+        let header = String::from_utf8(header)
+            .map_err(|_| NpyError::InvalidHeader("header is not valid utf-8".to_string()))?;
+
+        let descr = header_value(&header, "descr")?
+            .strip_prefix('\'')
+            .and_then(|value| value.split('\'').next())
+            .ok_or_else(|| NpyError::InvalidHeader("invalid descr".to_string()))?;
+        let (data_type, endianness) = data_type_from_npy_descr(descr)?;
+        let fortran_order = header_value(&header, "fortran_order")?;
+        let fortran_order = if fortran_order.starts_with("True") {
+            true
+        } else if fortran_order.starts_with("False") {
+            false
+        } else {
+            return Err(NpyError::InvalidHeader("invalid fortran_order".to_string()));
+        };
+        let shape = header_value(&header, "shape")?
+            .strip_prefix('(')
+            .and_then(|value| value.split(')').next())
+            .ok_or_else(|| NpyError::InvalidHeader("invalid shape".to_string()))?
+            .split(',')
+            .map(str::trim)
+            .filter(|dimension| !dimension.is_empty())
+            .map(str::parse)
+            .collect::<Result<ArrayShape, _>>()
+            .map_err(|_| NpyError::InvalidHeader("invalid shape".to_string()))?;
+        Ok(Self {
+            data_type,
+            endianness,
+            fortran_order,
+            shape,
+        })
+    }
+
+    /// Write the header to the start of a `.npy` file.
+    ///
+    /// # Errors
+    /// Returns an [`NpyError`] if the data type is unsupported or there is an IO error.
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<(), NpyError> {
+        let descr = data_type_to_npy_descr(&self.data_type)?;
+        let descr = match self.endianness {
+            Endianness::Little => descr.replace('>', "<"),
+            Endianness::Big => descr.replace('<', ">"),
+        };
+        let shape = match self.shape.as_slice() {
+            [dimension] => format!("({dimension},)"),
+            shape => format!(
+                "({})",
+                shape
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+        let fortran_order = if self.fortran_order { "True" } else { "False" };
+        let mut header =
+            format!("{{'descr': '{descr}', 'fortran_order': {fortran_order}, 'shape': {shape}, }}");
+
+        // Version 2.0 supports headers longer than u16::MAX
+        let version: u8 = if header.len() + NPY_ALIGNMENT < usize::from(u16::MAX) {
+            1
+        } else {
+            2
+        };
+        let preamble_len = if version == 1 { 10 } else { 12 };
+
+        // Pad the header with spaces and a newline so the elements are aligned
+        let padding =
+            (NPY_ALIGNMENT - (preamble_len + header.len() + 1) % NPY_ALIGNMENT) % NPY_ALIGNMENT;
+        header.extend(std::iter::repeat(' ').take(padding));
+        header.push('\n');
+
+        writer.write_all(NPY_MAGIC)?;
+        writer.write_all(&[version, 0])?;
+        if version == 1 {
+            let header_len = u16::try_from(header.len()).unwrap_or_default();
+            writer.write_all(&header_len.to_le_bytes())?;
+        } else {
+            let header_len = u32::try_from(header.len())
+                .map_err(|_| NpyError::InvalidHeader("header is too long".to_string()))?;
+            writer.write_all(&header_len.to_le_bytes())?;
+        }
+        writer.write_all(header.as_bytes())?;
+        Ok(())
+    }
+
+    /// Create an [`ArrayBuilder`] for an array with the data type and shape of the header, `chunk_grid`, and a zero fill value.
+    #[must_use]
+    pub fn array_builder(&self, chunk_grid: ChunkGrid) -> ArrayBuilder {
+        let fill_value = FillValue::new(vec![0; self.data_type.fixed_size().unwrap_or_default()]);
+        ArrayBuilder::new(
+            self.shape.clone(),
+            self.data_type.clone(),
+            chunk_grid,
+            fill_value,
+        )
+    }
+}
+
+/// Return the value of `key` in a `.npy` header dictionary, up to the end of the header.
+fn header_value<'a>(header: &'a str, key: &str) -> Result<&'a str, NpyError> {
+    [format!("'{key}':"), format!("\"{key}\":")]
+        .iter()
+        .find_map(|pattern| {
+            header
+                .find(pattern.as_str())
+                .map(|start| header[start + pattern.len()..].trim_start())
+        })
+        .ok_or_else(|| NpyError::InvalidHeader(format!("missing {key}")))
+}
+
+/// Split `array_subset` into slabs along `axis` at the chunk boundaries of `array`.
+///
+/// Each slab spans `array_subset` in all other dimensions.
+fn array_subset_slabs<TStorage: ?Sized>(
+    array: &Array<TStorage>,
+    array_subset: &ArraySubset,
+    axis: usize,
+) -> Result<Vec<ArraySubset>, ArrayError> {
+    if array_subset.dimensionality() == 0 {
+        return Ok(vec![array_subset.clone()]);
+    } else if array_subset.num_elements() == 0 {
+        return Ok(vec![]);
+    }
+
+    let mut slabs = Vec::new();
+    let mut slab_start = array_subset.start().to_vec();
+    let end = array_subset.end_exc()[axis];
+    while slab_start[axis] < end {
+        // The slab ends at the end of the chunks containing its first element along the axis
+        let mut array_indices = vec![0; array.dimensionality()];
+        array_indices[axis] = slab_start[axis];
+        let slab_end = array
+            .chunk_grid()
+            .chunk_indices(&array_indices, array.shape())?
+            .map(|chunk_indices| array.chunk_subset(&chunk_indices))
+            .transpose()?
+            .map_or(slab_start[axis] + 1, |chunk_subset| {
+                chunk_subset.end_exc()[axis]
+            })
+            .min(end);
+        let mut slab_shape = array_subset.shape().to_vec();
+        slab_shape[axis] = slab_end - slab_start[axis];
+        slabs.push(ArraySubset::new_with_start_shape(
+            slab_start.clone(),
+            slab_shape,
+        )?);
+        slab_start[axis] = slab_end;
+    }
+    Ok(slabs)
+}
+
+/// Read the elements of a `.npy` file after its `header` from `reader` and store them in `array`.
+fn store_npy_elements<TStorage: ?Sized + ReadableWritableStorageTraits + 'static>(
+    array: &Array<TStorage>,
+    header: &NpyHeader,
+    mut reader: impl Read,
+) -> Result<(), NpyError> {
+    if &header.data_type != array.data_type() || header.shape != array.shape() {
+        return Err(NpyError::IncompatibleArray(
+            header.data_type.clone(),
+            header.shape.clone(),
+            array.data_type().clone(),
+            array.shape().to_vec(),
+        ));
+    }
+    let data_type_size = header.data_type.fixed_size().unwrap_or_default();
+
+    // Fortran order elements are read in slabs along the last dimension, which are contiguous
+    let axis = if header.fortran_order {
+        array.dimensionality().saturating_sub(1)
+    } else {
+        0
+    };
+    for slab in array_subset_slabs(array, &array.subset_all(), axis)? {
+        let mut bytes = vec![0u8; slab.num_elements_usize() * data_type_size];
+        reader.read_exact(&mut bytes)?;
+        if !header.endianness.is_native() {
+            reverse_endianness(&mut bytes, &header.data_type);
+        }
+        let bytes = if header.fortran_order {
+            let shape = slab.shape().iter().rev().copied().collect();
+            let permutation = (0..slab.dimensionality()).rev().collect::<Vec<_>>();
+            ArrayBytesView::new(bytes, shape, header.data_type.clone())?
+                .transpose(&permutation)?
+                .into_bytes()
+                .into_owned()
+        } else {
+            bytes
+        };
+        array.store_array_subset(&slab, bytes)?;
+    }
+    Ok(())
+}
+
+/// Read a `.npy` file from `reader` and store its elements in `array`.
+///
+/// The elements are read and stored in slabs spanning the chunks of `array` along the first dimension (or the last dimension if the elements are in Fortran order).
+///
+/// # Errors
+/// Returns an [`NpyError`] if
+///  - the header is invalid,
+///  - the data type or shape of the `.npy` file does not match `array`, or
+///  - there is an IO error or an underlying array error.
+pub fn store_npy<TStorage: ?Sized + ReadableWritableStorageTraits + 'static>(
+    array: &Array<TStorage>,
+    mut reader: impl Read,
+) -> Result<(), NpyError> {
+    let header = NpyHeader::read(&mut reader)?;
+    store_npy_elements(array, &header, reader)
+}
+
+/// Create an array at `path` from a `.npy` file read from `reader`.
+///
+/// The array has the data type and shape of the `.npy` file, `chunk_grid`, a zero fill value, and default codecs.
+/// Its metadata is stored and the elements are stored as in [`store_npy`].
+///
+/// # Errors
+/// Returns an [`NpyError`] if the array cannot be created or a [`store_npy`] error condition is met.
+pub fn create_array_from_npy<TStorage: ?Sized + ReadableWritableStorageTraits + 'static>(
+    storage: Arc<TStorage>,
+    path: &str,
+    mut reader: impl Read,
+    chunk_grid: ChunkGrid,
+) -> Result<Array<TStorage>, NpyError> {
+    let header = NpyHeader::read(&mut reader)?;
+    let array = header.array_builder(chunk_grid).build(storage, path)?;
+    array.store_metadata()?;
+    store_npy_elements(&array, &header, reader)?;
+    Ok(array)
+}
+
+/// Write the `array_subset` of `array` to `writer` as a `.npy` file.
+///
+/// The elements are retrieved and written in slabs spanning the chunks of `array` along the first dimension.
+///
+/// # Errors
+/// Returns an [`NpyError`] if
+///  - the data type of the array is not supported by `NumPy`,
+///  - `array_subset` is not within the bounds of the array, or
+///  - there is an IO error or an underlying array error.
+pub fn write_npy<TStorage: ?Sized + ReadableStorageTraits + 'static>(
+    array: &Array<TStorage>,
+    array_subset: &ArraySubset,
+    mut writer: impl Write,
+) -> Result<(), NpyError> {
+    if array_subset.dimensionality() != array.dimensionality()
+        || !array_subset.inbounds(array.shape())
+    {
+        return Err(
+            ArrayError::InvalidArraySubset(array_subset.clone(), array.shape().to_vec()).into(),
+        );
+    }
+    NpyHeader::new(array.data_type().clone(), array_subset.shape().to_vec()).write(&mut writer)?;
+    for slab in array_subset_slabs(array, array_subset, 0)? {
+        let bytes = array
+            .retrieve_array_subset(&slab)?
+            .into_fixed()
+            .map_err(ArrayError::from)?;
+        writer.write_all(&bytes)?;
+    }
+    Ok(())
+}
+
+/// Write `arrays` to `writer` as a `.npz` file.
+///
+/// Each array is written in full as in [`write_npy`] to `<name>.npy` in the archive, without compression.
+///
+/// # Errors
+/// Returns an [`NpyError`] if there is a zip error or a [`write_npy`] error condition is met.
+pub fn write_npz<TStorage: ?Sized + ReadableStorageTraits + 'static>(
+    arrays: &[(&str, &Array<TStorage>)],
+    writer: impl Write + Seek,
+) -> Result<(), NpyError> {
+    let mut zip = zip::ZipWriter::new(writer);
+    for (name, array) in arrays {
+        let num_bytes = array.data_type().fixed_size().map_or(0, |size| {
+            array.shape().iter().product::<u64>() * size as u64
+        });
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored)
+            .large_file(num_bytes > u64::from(u32::MAX));
+        zip.start_file(format!("{name}.npy"), options)?;
+        write_npy(array, &array.subset_all(), &mut zip)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+/// Create an array under `group_path` for each `.npy` file in a `.npz` file read from `reader`.
+///
+/// The array for `<name>.npy` is created at `<group_path>/<name>` as in [`create_array_from_npy`], with the chunk grid returned by `chunk_grid`.
+/// The group at `group_path` is not created.
+///
+/// # Errors
+/// Returns an [`NpyError`] if there is a zip error or a [`create_array_from_npy`] error condition is met.
+pub fn create_arrays_from_npz<TStorage: ?Sized + ReadableWritableStorageTraits + 'static>(
+    storage: &Arc<TStorage>,
+    group_path: &str,
+    reader: impl Read + Seek,
+    chunk_grid: impl Fn(&NpyHeader) -> ChunkGrid,
+) -> Result<Vec<Array<TStorage>>, NpyError> {
+    let mut archive = zip::ZipArchive::new(reader)?;
+    let mut arrays = Vec::with_capacity(archive.len());
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index)?;
+        let Some(name) = file.name().strip_suffix(".npy") else {
+            continue;
+        };
+        let path = format!("{}/{name}", group_path.trim_end_matches('/'));
+        let header = NpyHeader::read(&mut file)?;
+        let array = header
+            .array_builder(chunk_grid(&header))
+            .build(storage.clone(), &path)?;
+        array.store_metadata()?;
+        store_npy_elements(&array, &header, &mut file)?;
+        arrays.push(array);
+    }
+    Ok(arrays)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::storage::store::MemoryStore;
+
+    use super::*;
+
+    #[test]
+    fn npy_descr() {
+        for data_type in [
+            DataType::Bool,
+            DataType::Int8,
+            DataType::UInt16,
+            DataType::Float64,
+            DataType::Complex128,
+            DataType::RawBits(4),
+        ] {
+            let descr = data_type_to_npy_descr(&data_type).unwrap();
+            assert_eq!(
+                data_type_from_npy_descr(&descr).unwrap(),
+                (data_type, Endianness::native())
+            );
+        }
+        assert_eq!(
+            data_type_from_npy_descr(">i4").unwrap(),
+            (DataType::Int32, Endianness::Big)
+        );
+        assert!(data_type_to_npy_descr(&DataType::String).is_err());
+        assert!(data_type_from_npy_descr("<U8").is_err());
+        assert!(data_type_from_npy_descr("<f3").is_err());
+    }
+
+    #[test]
+    fn npy_header() {
+        for shape in [vec![], vec![3], vec![2, 3, 4]] {
+            let header = NpyHeader::new(DataType::Float32, shape);
+            let mut bytes = Vec::new();
+            header.write(&mut bytes).unwrap();
+            assert_eq!(bytes.len() % NPY_ALIGNMENT, 0);
+            assert_eq!(NpyHeader::read(&mut bytes.as_slice()).unwrap(), header);
+        }
+
+        // A header written by NumPy
+        let mut bytes = b"\x93NUMPY\x01\x00\x76\x00".to_vec();
+        let dict = "{'descr': '>i2', 'fortran_order': True, 'shape': (2, 3), }";
+        bytes.extend_from_slice(dict.as_bytes());
+        bytes.extend(std::iter::repeat(b' ').take(118 - dict.len() - 1));
+        bytes.push(b'\n');
+        let header = NpyHeader::read(&mut bytes.as_slice()).unwrap();
+        assert_eq!(
+            header,
+            NpyHeader {
+                data_type: DataType::Int16,
+                endianness: Endianness::Big,
+                fortran_order: true,
+                shape: vec![2, 3],
+            }
+        );
+        assert!(NpyHeader::read(&mut &b"NUMPY"[..]).is_err());
+    }
+
+    #[test]
+    fn npy_import_export() {
+        let store = Arc::new(MemoryStore::new());
+        let array = ArrayBuilder::new(
+            vec![5, 3],
+            DataType::UInt16,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u16),
+        )
+        .build(store.clone(), "/array")
+        .unwrap();
+        let elements: Vec<u16> = (0..15).collect();
+        array
+            .store_array_subset_elements(&array.subset_all(), &elements)
+            .unwrap();
+
+        let mut npy = Vec::new();
+        write_npy(&array, &array.subset_all(), &mut npy).unwrap();
+        let copy = create_array_from_npy(
+            store.clone(),
+            "/copy",
+            npy.as_slice(),
+            vec![3, 3].try_into().unwrap(),
+        )
+        .unwrap();
+        assert_eq!(copy.shape(), &[5, 3]);
+        assert_eq!(
+            copy.retrieve_array_subset_elements::<u16>(&copy.subset_all())
+                .unwrap(),
+            elements
+        );
+
+        // Subset export
+        let mut npy = Vec::new();
+        let subset = ArraySubset::new_with_ranges(&[1..4, 1..3]);
+        write_npy(&array, &subset, &mut npy).unwrap();
+        let mut reader = npy.as_slice();
+        assert_eq!(NpyHeader::read(&mut reader).unwrap().shape, vec![3, 2]);
+        assert_eq!(
+            bytemuck::pod_collect_to_vec::<u8, u16>(reader),
+            vec![4, 5, 7, 8, 10, 11]
+        );
+        assert!(write_npy(
+            &array,
+            &ArraySubset::new_with_ranges(&[0..6, 0..3]),
+            &mut Vec::new()
+        )
+        .is_err());
+        assert!(store_npy(&copy, npy.as_slice()).is_err());
+
+        // Fortran order, big endian import
+        let header = NpyHeader {
+            data_type: DataType::UInt16,
+            endianness: Endianness::Big,
+            fortran_order: true,
+            shape: vec![5, 3],
+        };
+        let mut npy = Vec::new();
+        header.write(&mut npy).unwrap();
+        for column in 0..3u16 {
+            for row in 0..5u16 {
+                npy.extend_from_slice(&(row * 3 + column).to_be_bytes());
+            }
+        }
+        let fortran = ArrayBuilder::new(
+            vec![5, 3],
+            DataType::UInt16,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u16),
+        )
+        .build(store, "/fortran")
+        .unwrap();
+        store_npy(&fortran, npy.as_slice()).unwrap();
+        assert_eq!(
+            fortran
+                .retrieve_array_subset_elements::<u16>(&fortran.subset_all())
+                .unwrap(),
+            elements
+        );
+    }
+
+    #[test]
+    fn npz_import_export() {
+        let store = Arc::new(MemoryStore::new());
+        let a = ArrayBuilder::new(
+            vec![4],
+            DataType::Float32,
+            vec![2].try_into().unwrap(),
+            FillValue::from(0.0f32),
+        )
+        .build(store.clone(), "/a")
+        .unwrap();
+        a.store_array_subset_elements(&a.subset_all(), &[1.0f32, 2.0, 3.0, 4.0])
+            .unwrap();
+        let b = ArrayBuilder::new(
+            vec![2, 2],
+            DataType::Int64,
+            vec![1, 2].try_into().unwrap(),
+            FillValue::from(0i64),
+        )
+        .build(store.clone(), "/b")
+        .unwrap();
+        b.store_array_subset_elements(&b.subset_all(), &[1i64, -2, 3, -4])
+            .unwrap();
+
+        let mut npz = Cursor::new(Vec::new());
+        write_npz(&[("a", &a), ("b", &b)], &mut npz).unwrap();
+        let arrays = create_arrays_from_npz(&store, "/group", npz, |header| {
+            vec![1; header.shape.len()].try_into().unwrap()
+        })
+        .unwrap();
+        assert_eq!(arrays.len(), 2);
+        assert_eq!(arrays[0].path().as_str(), "/group/a");
+        assert_eq!(
+            arrays[0]
+                .retrieve_array_subset_elements::<f32>(&arrays[0].subset_all())
+                .unwrap(),
+            vec![1.0, 2.0, 3.0, 4.0]
+        );
+        assert_eq!(arrays[1].path().as_str(), "/group/b");
+        assert_eq!(
+            arrays[1]
+                .retrieve_array_subset_elements::<i64>(&arrays[1].subset_all())
+                .unwrap(),
+            vec![1, -2, 3, -4]
+        );
+    }
+}
@@ -6,9 +6,12 @@
 //! [Data types](`crate::array::data_type`) are not currently supported as an extension point.
 //!
 //! Plugins are registered at compile time using the [inventory] crate.
+//! Codec, chunk grid, and chunk key encoding plugins can also be registered at runtime with [`codec_register`](crate::array::codec::codec_register), [`chunk_grid_register`](crate::array::chunk_grid::chunk_grid_register), and [`chunk_key_encoding_register`](crate::array::chunk_key_encoding::chunk_key_encoding_register).
 //! At runtime, a name matching function is applied to identify which registered plugin is associated with the metadata.
 //! If a match is found, the plugin is created from the metadata.
 
+use std::sync::{PoisonError, RwLock};
+
 use thiserror::Error;
 
 use crate::metadata::v3::MetadataV3;
@@ -23,6 +26,14 @@ pub struct Plugin<TPlugin> {
     create_fn: fn(metadata: &MetadataV3) -> Result<TPlugin, PluginCreateError>,
 }
 
+impl<TPlugin> Clone for Plugin<TPlugin> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<TPlugin> Copy for Plugin<TPlugin> {}
+
 /// An invalid plugin metadata error.
 #[derive(Debug, Error)]
 #[error("{plugin_type} {identifier} is unsupported with metadata: {metadata}")]
@@ -108,3 +119,58 @@ impl<TPlugin> Plugin<TPlugin> {
         self.identifier
     }
 }
+
+/// Plugins registered at runtime for an extension point.
+///
+/// A plugin replaces any plugin previously registered with the same identifier.
+pub(crate) struct RuntimePluginRegistry<TPlugin> {
+    plugins: RwLock<Vec<Plugin<TPlugin>>>,
+}
+
+impl<TPlugin> RuntimePluginRegistry<TPlugin> {
+    /// Create a new empty registry.
+    pub(crate) const fn new() -> Self {
+        Self {
+            plugins: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Register `plugin`, replacing any plugin with the same identifier.
+    pub(crate) fn register(&self, plugin: Plugin<TPlugin>) {
+        let mut plugins = self.plugins.write().unwrap_or_else(PoisonError::into_inner);
+        plugins.retain(|registered| registered.identifier() != plugin.identifier());
+        plugins.push(plugin);
+    }
+
+    /// Unregister the plugin with `identifier`.
+    ///
+    /// Returns true if a plugin was unregistered.
+    pub(crate) fn unregister(&self, identifier: &str) -> bool {
+        let mut plugins = self.plugins.write().unwrap_or_else(PoisonError::into_inner);
+        let len = plugins.len();
+        plugins.retain(|registered| registered.identifier() != identifier);
+        plugins.len() != len
+    }
+
+    /// Return the identifiers of the registered plugins.
+    pub(crate) fn identifiers(&self) -> Vec<&'static str> {
+        self.plugins
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(Plugin::identifier)
+            .collect()
+    }
+
+    /// Return the first registered plugin associated with `name`.
+    ///
+    /// The plugin is copied out of the registry, so the registry is not locked while the plugin creates an object.
+    pub(crate) fn find(&self, name: &str) -> Option<Plugin<TPlugin>> {
+        self.plugins
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .find(|plugin| plugin.match_name(name))
+            .copied()
+    }
+}
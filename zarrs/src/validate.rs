@@ -0,0 +1,579 @@
+//! Validation of a Zarr hierarchy.
+//!
+//! [`validate`] walks every key in a store and reports the problems it finds in a [`ValidationReport`], much like `fsck` for a file system.
+//! It checks that
+//!  - all node metadata is valid and only uses supported extensions,
+//!  - a node does not have both Zarr V3 and Zarr V2 metadata, or both array and group metadata,
+//!  - `.zattrs` accompany a `.zarray` or `.zgroup`,
+//!  - the parent of every node is a group,
+//!  - every other key is a chunk key of an array within the bounds of its chunk grid.
+//!
+//! The root of the hierarchy is not required to be a node.
+//!
+//! ```rust
+//! # use std::sync::Arc;
+//! # use zarrs::array::{ArrayBuilder, DataType, FillValue};
+//! # use zarrs::storage::{store::MemoryStore, StoreKey, WritableStorageTraits};
+//! # use zarrs::validate::{validate, ValidationIssueKind};
+//! # let store = Arc::new(MemoryStore::new());
+//! let array = ArrayBuilder::new(
+//!     vec![4, 4],
+//!     DataType::UInt8,
+//!     vec![2, 2].try_into()?,
+//!     FillValue::from(0u8),
+//! )
+//! .build(store.clone(), "/array")?;
+//! array.store_metadata()?;
+//! array.store_chunk_elements(&[0, 0], &[1u8; 4])?;
+//! store.set(&StoreKey::new("array/c/2/0")?, vec![0u8; 4].into())?;
+//!
+//! let report = validate(&store)?;
+//! assert_eq!(report.num_arrays, 1);
+//! assert_eq!(report.num_chunks, 1);
+//! assert_eq!(
+//!     report.issues[0].kind,
+//!     ValidationIssueKind::OrphanedChunk(vec![2, 0])
+//! );
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use crate::{
+    array::{Array, ArrayCreateError, ArrayIndices, ArrayMetadata},
+    metadata::{
+        v2::{ArrayMetadataV2, GroupMetadataV2},
+        v3::{ArrayMetadataV3, GroupMetadataV3},
+    },
+    node::NodePath,
+    plugin::PluginCreateError,
+    storage::{ListableStorageTraits, ReadableStorageTraits, StorageError, StoreKey},
+};
+
+/// The known fields of Zarr V3 array metadata.
+const ARRAY_METADATA_V3_FIELDS: &[&str] = &[
+    "zarr_format",
+    "node_type",
+    "shape",
+    "data_type",
+    "chunk_grid",
+    "chunk_key_encoding",
+    "fill_value",
+    "codecs",
+    "attributes",
+    "storage_transformers",
+    "dimension_names",
+];
+
+/// The known fields of Zarr V3 group metadata.
+const GROUP_METADATA_V3_FIELDS: &[&str] = &["zarr_format", "node_type", "attributes"];
+
+/// Keys that are not metadata or chunks but are expected in a hierarchy.
+const IGNORED_FILE_NAMES: &[&str] = &[
+    ".zmetadata",
+    #[cfg(feature = "checksum_manifest")]
+    crate::array::CHECKSUM_MANIFEST_FILE_NAME,
+];
+
+/// The kind of a [`ValidationIssue`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationIssueKind {
+    /// The metadata is not valid JSON or is not valid Zarr metadata.
+    InvalidMetadata(String),
+    /// The metadata is valid, but uses an extension or feature that is not supported.
+    UnsupportedExtension(String),
+    /// The node has metadata for more than one node type or Zarr version.
+    ConflictingMetadata,
+    /// The `.zattrs` do not accompany a `.zarray` or `.zgroup`.
+    OrphanedAttributes,
+    /// The parent of the node is not a node.
+    MissingParentGroup,
+    /// The node is inside an array.
+    NodeInsideArray,
+    /// The chunk key of an array is outside the bounds of its chunk grid.
+    OrphanedChunk(ArrayIndices),
+    /// The key is not metadata or a chunk key of an array.
+    UnknownKey,
+}
+
+impl core::fmt::Display for ValidationIssueKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidMetadata(err) => write!(f, "invalid metadata: {err}"),
+            Self::UnsupportedExtension(err) => write!(f, "unsupported extension: {err}"),
+            Self::ConflictingMetadata => write!(f, "conflicting node metadata"),
+            Self::OrphanedAttributes => write!(f, "attributes without array or group metadata"),
+            Self::MissingParentGroup => write!(f, "the parent group does not exist"),
+            Self::NodeInsideArray => write!(f, "node inside an array"),
+            Self::OrphanedChunk(chunk_indices) => {
+                write!(f, "chunk {chunk_indices:?} is outside the chunk grid")
+            }
+            Self::UnknownKey => write!(f, "unknown key"),
+        }
+    }
+}
+
+/// An issue found by [`validate`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationIssue {
+    /// The store key with the issue.
+    pub key: StoreKey,
+    /// The kind of issue.
+    pub kind: ValidationIssueKind,
+}
+
+impl core::fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}: {}", self.key, self.kind)
+    }
+}
+
+/// The result of [`validate`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// The number of groups with valid metadata.
+    pub num_groups: usize,
+    /// The number of arrays with valid metadata.
+    pub num_arrays: usize,
+    /// The number of chunks within the chunk grid of an array with valid metadata.
+    pub num_chunks: usize,
+    /// The issues, sorted by key.
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// Returns true if there are no issues.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    fn push(&mut self, key: StoreKey, kind: ValidationIssueKind) {
+        self.issues.push(ValidationIssue { key, kind });
+    }
+}
+
+/// The metadata keys of a node.
+#[derive(Default)]
+struct NodeKeys {
+    zarr_json: Option<StoreKey>,
+    zarray: Option<StoreKey>,
+    zgroup: Option<StoreKey>,
+    zattrs: Option<StoreKey>,
+}
+
+/// A node found by [`validate`].
+///
+/// An array is [`None`] if its metadata is invalid.
+enum ValidatedNode<TStorage: ?Sized> {
+    Group,
+    Array(Option<Box<Array<TStorage>>>),
+}
+
+/// Validate the Zarr hierarchy in `storage`.
+///
+/// Every key in the store is listed and checked, see the [module documentation](crate::validate).
+/// Chunks are not retrieved or decoded.
+///
+/// # Errors
+/// Returns a [`StorageError`] if there is an underlying error with the store.
+#[allow(clippy::too_many_lines)]
+pub fn validate<TStorage: ?Sized + ReadableStorageTraits + ListableStorageTraits + 'static>(
+    storage: &Arc<TStorage>,
+) -> Result<ValidationReport, StorageError> {
+    let mut report = ValidationReport::default();
+
+    // Sort the keys into node metadata and other keys
+    let mut node_keys: BTreeMap<String, NodeKeys> = BTreeMap::new();
+    let mut other_keys = Vec::new();
+    for key in storage.list()? {
+        let (parent, file_name) = key.as_str().rsplit_once('/').unwrap_or(("", key.as_str()));
+        if !matches!(file_name, "zarr.json" | ".zarray" | ".zgroup" | ".zattrs") {
+            if !IGNORED_FILE_NAMES.contains(&file_name) {
+                other_keys.push(key);
+            }
+            continue;
+        }
+        let keys = node_keys.entry(format!("/{parent}")).or_default();
+        let metadata_key = match file_name {
+            "zarr.json" => &mut keys.zarr_json,
+            ".zarray" => &mut keys.zarray,
+            ".zgroup" => &mut keys.zgroup,
+            _ => &mut keys.zattrs,
+        };
+        *metadata_key = Some(key);
+    }
+
+    // Validate the node metadata
+    let mut nodes: BTreeMap<String, (StoreKey, ValidatedNode<TStorage>)> = BTreeMap::new();
+    for (path, node_keys) in node_keys {
+        let NodeKeys {
+            zarr_json,
+            zarray,
+            zgroup,
+            zattrs,
+        } = node_keys;
+        if let Some(key) = zattrs {
+            if zarray.is_none() && zgroup.is_none() {
+                report.push(key, ValidationIssueKind::OrphanedAttributes);
+            } else if let Some(bytes) = storage.get(&key)? {
+                if let Err(err) =
+                    serde_json::from_slice::<serde_json::Map<String, serde_json::Value>>(&bytes)
+                {
+                    report.push(key, ValidationIssueKind::InvalidMetadata(err.to_string()));
+                }
+            }
+        }
+        let metadata_keys = [&zarr_json, &zarray, &zgroup];
+        let Some(key) = metadata_keys.into_iter().flatten().next().cloned() else {
+            continue;
+        };
+        if metadata_keys.into_iter().flatten().count() > 1 {
+            report.push(key.clone(), ValidationIssueKind::ConflictingMetadata);
+        }
+        let node_path = match NodePath::new(&path) {
+            Ok(node_path) => node_path,
+            Err(err) => {
+                report.push(key, ValidationIssueKind::InvalidMetadata(err.to_string()));
+                continue;
+            }
+        };
+        let Some(bytes) = storage.get(&key)? else {
+            continue;
+        };
+        let node = if zarr_json.is_some() {
+            validate_node_v3(storage, &node_path, &key, &bytes, &mut report)
+        } else if zarray.is_some() {
+            Some(match serde_json::from_slice::<ArrayMetadataV2>(&bytes) {
+                Ok(metadata) => ValidatedNode::Array(validate_array(
+                    storage,
+                    &node_path,
+                    ArrayMetadata::V2(metadata),
+                    &key,
+                    &mut report,
+                )),
+                Err(err) => {
+                    report.push(
+                        key.clone(),
+                        ValidationIssueKind::InvalidMetadata(err.to_string()),
+                    );
+                    ValidatedNode::Array(None)
+                }
+            })
+        } else {
+            if let Err(err) = serde_json::from_slice::<GroupMetadataV2>(&bytes) {
+                report.push(
+                    key.clone(),
+                    ValidationIssueKind::InvalidMetadata(err.to_string()),
+                );
+            } else {
+                report.num_groups += 1;
+            }
+            Some(ValidatedNode::Group)
+        };
+        if let Some(node) = node {
+            nodes.insert(path, (key, node));
+        }
+    }
+
+    // Check the parent of each node
+    for (path, (key, _)) in &nodes {
+        let (parent, _) = path.rsplit_once('/').unwrap_or_default();
+        if ancestors(path)
+            .any(|ancestor| matches!(nodes.get(ancestor), Some((_, ValidatedNode::Array(_)))))
+        {
+            report.push(key.clone(), ValidationIssueKind::NodeInsideArray);
+        } else if !parent.is_empty() && !nodes.contains_key(parent) {
+            report.push(key.clone(), ValidationIssueKind::MissingParentGroup);
+        }
+    }
+
+    // Check that other keys are chunks within the chunk grid of an array
+    for key in other_keys {
+        let path = format!("/{}", key.as_str());
+        let node = ancestors(&path).find_map(|ancestor| nodes.get(ancestor));
+        match node {
+            Some((_, ValidatedNode::Array(Some(array)))) => {
+                match array.chunk_indices_from_key(&key) {
+                    Some(chunk_indices) => {
+                        let in_bounds = array.chunk_grid_shape().is_some_and(|chunk_grid_shape| {
+                            std::iter::zip(&chunk_indices, &chunk_grid_shape)
+                                .all(|(index, shape)| index < shape)
+                        });
+                        if in_bounds {
+                            report.num_chunks += 1;
+                        } else {
+                            report.push(key, ValidationIssueKind::OrphanedChunk(chunk_indices));
+                        }
+                    }
+                    None => report.push(key, ValidationIssueKind::UnknownKey),
+                }
+            }
+            // The chunks of an array with invalid metadata cannot be checked
+            Some((_, ValidatedNode::Array(None))) => {}
+            Some((_, ValidatedNode::Group)) | None => {
+                report.push(key, ValidationIssueKind::UnknownKey);
+            }
+        }
+    }
+
+    report.issues.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(report)
+}
+
+/// Validate the Zarr V3 metadata `bytes` of the node at `node_path`.
+fn validate_node_v3<TStorage: ?Sized>(
+    storage: &Arc<TStorage>,
+    node_path: &NodePath,
+    key: &StoreKey,
+    bytes: &[u8],
+    report: &mut ValidationReport,
+) -> Option<ValidatedNode<TStorage>> {
+    let metadata = match serde_json::from_slice::<serde_json::Map<String, serde_json::Value>>(bytes)
+    {
+        Ok(metadata) => metadata,
+        Err(err) => {
+            report.push(
+                key.clone(),
+                ValidationIssueKind::InvalidMetadata(err.to_string()),
+            );
+            return None;
+        }
+    };
+
+    let (node, fields) = match metadata
+        .get("node_type")
+        .and_then(|node_type| node_type.as_str())
+    {
+        Some("array") => (ValidatedNode::Array(None), ARRAY_METADATA_V3_FIELDS),
+        Some("group") => (ValidatedNode::Group, GROUP_METADATA_V3_FIELDS),
+        _ => {
+            report.push(
+                key.clone(),
+                ValidationIssueKind::InvalidMetadata(
+                    "node_type must be array or group".to_string(),
+                ),
+            );
+            return None;
+        }
+    };
+
+    // Additional fields must have "must_understand": false
+    let unsupported_fields = metadata
+        .iter()
+        .filter(|(name, value)| {
+            !fields.contains(&name.as_str())
+                && value.get("must_understand") != Some(&serde_json::Value::Bool(false))
+        })
+        .map(|(name, _)| name.as_str())
+        .collect::<BTreeSet<_>>();
+    if !unsupported_fields.is_empty() {
+        report.push(
+            key.clone(),
+            ValidationIssueKind::UnsupportedExtension(format!(
+                "unsupported additional fields {unsupported_fields:?}"
+            )),
+        );
+        return Some(node);
+    }
+
+    let metadata = serde_json::Value::Object(metadata);
+    Some(match node {
+        ValidatedNode::Array(_) => match serde_json::from_value::<ArrayMetadataV3>(metadata) {
+            Ok(metadata) => ValidatedNode::Array(validate_array(
+                storage,
+                node_path,
+                ArrayMetadata::V3(metadata),
+                key,
+                report,
+            )),
+            Err(err) => {
+                report.push(
+                    key.clone(),
+                    ValidationIssueKind::InvalidMetadata(err.to_string()),
+                );
+                ValidatedNode::Array(None)
+            }
+        },
+        ValidatedNode::Group => {
+            if let Err(err) = serde_json::from_value::<GroupMetadataV3>(metadata) {
+                report.push(
+                    key.clone(),
+                    ValidationIssueKind::InvalidMetadata(err.to_string()),
+                );
+            } else {
+                report.num_groups += 1;
+            }
+            ValidatedNode::Group
+        }
+    })
+}
+
+/// Create the array at `node_path` from its `metadata`, reporting an issue if it is unsupported or invalid.
+fn validate_array<TStorage: ?Sized>(
+    storage: &Arc<TStorage>,
+    node_path: &NodePath,
+    metadata: ArrayMetadata,
+    key: &StoreKey,
+    report: &mut ValidationReport,
+) -> Option<Box<Array<TStorage>>> {
+    match Array::new_with_metadata(storage.clone(), node_path.as_str(), metadata) {
+        Ok(array) => {
+            report.num_arrays += 1;
+            Some(Box::new(array))
+        }
+        Err(err) => {
+            let kind = match err {
+                ArrayCreateError::DataTypeCreateError(_)
+                | ArrayCreateError::UnsupportedAdditionalFieldError(_)
+                | ArrayCreateError::UnsupportedZarrV2Array(_)
+                | ArrayCreateError::CodecsCreateError(PluginCreateError::Unsupported { .. })
+                | ArrayCreateError::StorageTransformersCreateError(
+                    PluginCreateError::Unsupported { .. },
+                )
+                | ArrayCreateError::ChunkGridCreateError(PluginCreateError::Unsupported {
+                    ..
+                })
+                | ArrayCreateError::ChunkKeyEncodingCreateError(PluginCreateError::Unsupported {
+                    ..
+                }) => ValidationIssueKind::UnsupportedExtension(err.to_string()),
+                _ => ValidationIssueKind::InvalidMetadata(err.to_string()),
+            };
+            report.push(key.clone(), kind);
+            None
+        }
+    }
+}
+
+/// Return the ancestors of `path` from deepest to the root, excluding `path`.
+fn ancestors(path: &str) -> impl Iterator<Item = &str> {
+    std::iter::successors(Some(path), |&path| {
+        let (parent, _) = path.rsplit_once('/').filter(|_| path != "/")?;
+        Some(if parent.is_empty() { "/" } else { parent })
+    })
+    .skip(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        array::{ArrayBuilder, DataType, FillValue},
+        group::GroupBuilder,
+        storage::{store::MemoryStore, WritableStorageTraits},
+    };
+
+    use super::*;
+
+    #[test]
+    fn ancestors_of_path() {
+        assert_eq!(
+            ancestors("/a/b/c").collect::<Vec<_>>(),
+            vec!["/a/b", "/a", "/"]
+        );
+        assert_eq!(ancestors("/a").collect::<Vec<_>>(), vec!["/"]);
+        assert!(ancestors("/").next().is_none());
+    }
+
+    #[test]
+    fn validate_hierarchy() {
+        let store = Arc::new(MemoryStore::new());
+        GroupBuilder::new()
+            .build(store.clone(), "/")
+            .unwrap()
+            .store_metadata()
+            .unwrap();
+        GroupBuilder::new()
+            .build(store.clone(), "/group")
+            .unwrap()
+            .store_metadata()
+            .unwrap();
+        let array = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::UInt8,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .build(store.clone(), "/group/array")
+        .unwrap();
+        array.store_metadata().unwrap();
+        array
+            .store_chunk_elements(&[0, 0], &[1u8, 2, 3, 4])
+            .unwrap();
+        array
+            .store_chunk_elements(&[1, 1], &[5u8, 6, 7, 8])
+            .unwrap();
+
+        let report = validate(&store).unwrap();
+        assert!(report.is_valid());
+        assert_eq!(report.num_groups, 2);
+        assert_eq!(report.num_arrays, 1);
+        assert_eq!(report.num_chunks, 2);
+
+        let set = |key: &str, value: &str| {
+            store
+                .set(
+                    &StoreKey::new(key).unwrap(),
+                    value.as_bytes().to_vec().into(),
+                )
+                .unwrap();
+        };
+        set("group/array/c/2/0", "");
+        set("group/array/c/0", "");
+        set("group/array/other", "");
+        set(
+            "group/array/nested/zarr.json",
+            r#"{"zarr_format":3,"node_type":"group"}"#,
+        );
+        set("group/.zgroup", r#"{"zarr_format":2}"#);
+        set("orphan/.zattrs", "{}");
+        set(
+            "missing/parent/zarr.json",
+            r#"{"zarr_format":3,"node_type":"group"}"#,
+        );
+        set(
+            "invalid/zarr.json",
+            r#"{"zarr_format":3,"node_type":"array"}"#,
+        );
+        set("invalid/c/0", "");
+        set(
+            "extension/zarr.json",
+            r#"{"zarr_format":3,"node_type":"group","unknown":{"must_understand":true}}"#,
+        );
+        set(
+            "understood/zarr.json",
+            r#"{"zarr_format":3,"node_type":"group","known":{"must_understand":false}}"#,
+        );
+
+        let report = validate(&store).unwrap();
+        assert!(!report.is_valid());
+        assert_eq!(report.num_groups, 5);
+        assert_eq!(report.num_arrays, 1);
+        assert_eq!(report.num_chunks, 2);
+        let issues = report
+            .issues
+            .iter()
+            .map(|issue| (issue.key.as_str(), &issue.kind))
+            .collect::<Vec<_>>();
+        assert!(matches!(
+            issues.as_slice(),
+            [
+                ("extension/zarr.json", ValidationIssueKind::UnsupportedExtension(_)),
+                ("group/array/c/0", ValidationIssueKind::UnknownKey),
+                ("group/array/c/2/0", ValidationIssueKind::OrphanedChunk(chunk_indices)),
+                ("group/array/nested/zarr.json", ValidationIssueKind::NodeInsideArray),
+                ("group/array/other", ValidationIssueKind::UnknownKey),
+                ("group/zarr.json", ValidationIssueKind::ConflictingMetadata),
+                ("invalid/zarr.json", ValidationIssueKind::InvalidMetadata(_)),
+                ("missing/parent/zarr.json", ValidationIssueKind::MissingParentGroup),
+                ("orphan/.zattrs", ValidationIssueKind::OrphanedAttributes),
+            ] if *chunk_indices == [2, 0]
+        ));
+        assert_eq!(
+            report.issues[5].to_string(),
+            "group/zarr.json: conflicting node metadata"
+        );
+    }
+}
@@ -318,3 +318,79 @@ async fn array_str_async_sharded_transpose() -> Result<(), Box<dyn std::error::E
     let array = builder.build(store, array_path).unwrap();
     array_str_impl(array).await
 }
+
+#[cfg(feature = "sharding")]
+#[tokio::test]
+async fn array_async_partial_encode_sharding() -> Result<(), Box<dyn std::error::Error>> {
+    use core::mem::size_of;
+    use zarrs::array::codec::CodecOptionsBuilder;
+    use zarrs::storage::AsyncReadableStorageTraits;
+
+    let opt = CodecOptionsBuilder::new()
+        .experimental_partial_encoding(true)
+        .build();
+
+    let store = std::sync::Arc::new(zarrs_object_store::AsyncObjectStore::new(InMemory::new()));
+    let array_path = "/array";
+    let array = ArrayBuilder::new(
+        vec![4, 4], // array shape
+        DataType::UInt16,
+        vec![2, 2].try_into().unwrap(), // regular chunk shape
+        FillValue::from(0u16),
+    )
+    .array_to_bytes_codec(Arc::new(
+        zarrs::array::codec::array_to_bytes::sharding::ShardingCodecBuilder::new(
+            vec![1, 1].try_into().unwrap(),
+        )
+        .index_bytes_to_bytes_codecs(vec![])
+        .build(),
+    ))
+    .bytes_to_bytes_codecs(vec![])
+    .build(store.clone(), array_path)?;
+    let key_0_0 = array.chunk_key(&[0, 0]);
+    let shard_index_size = size_of::<u64>() * 2 * 4;
+
+    // [1, 0]
+    // [0, 0]
+    array
+        .async_store_array_subset_elements_opt::<u16>(
+            &ArraySubset::new_with_ranges(&[0..1, 0..1]),
+            &[1],
+            &opt,
+        )
+        .await?;
+    assert_eq!(
+        store.get(&key_0_0).await?.unwrap().len(),
+        shard_index_size + size_of::<u16>()
+    );
+
+    // The inner chunk is appended: [1, 2]
+    //                              [0, 0]
+    array
+        .async_store_array_subset_elements_opt::<u16>(
+            &ArraySubset::new_with_ranges(&[0..1, 1..2]),
+            &[2],
+            &opt,
+        )
+        .await?;
+    assert_eq!(
+        store.get(&key_0_0).await?.unwrap().len(),
+        shard_index_size + 2 * size_of::<u16>()
+    );
+    assert_eq!(
+        array.async_retrieve_chunk_elements::<u16>(&[0, 0]).await?,
+        vec![1, 2, 0, 0]
+    );
+
+    // The shard is erased once it is entirely the fill value
+    array
+        .async_store_array_subset_elements_opt::<u16>(
+            &ArraySubset::new_with_ranges(&[0..1, 0..2]),
+            &[0, 0],
+            &opt,
+        )
+        .await?;
+    assert!(store.get(&key_0_0).await?.is_none());
+
+    Ok(())
+}
@@ -215,6 +215,100 @@ fn array_partial_encode_sharding_index_end() {
     array_partial_encode_sharding(ShardingIndexLocation::End, vec![]).unwrap();
 }
 
+fn array_partial_encode_sharding_compaction(
+    sharding_index_location: ShardingIndexLocation,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let store = std::sync::Arc::new(MemoryStore::default());
+    let mut builder = ArrayBuilder::new(
+        vec![4, 4], // array shape
+        DataType::UInt16,
+        vec![2, 2].try_into().unwrap(), // regular chunk shape
+        FillValue::from(0u16),
+    );
+    builder.array_to_bytes_codec(Arc::new(
+        ShardingCodecBuilder::new(vec![1, 1].try_into().unwrap())
+            .index_bytes_to_bytes_codecs(vec![])
+            .index_location(sharding_index_location)
+            .build(),
+    ));
+    let array = builder.build(store.clone(), "/").unwrap();
+
+    let get_bytes_0_0 = || {
+        let key = array.chunk_key_encoding().encode(&[0, 0]);
+        store.get(&key)
+    };
+    let shard_index_size = size_of::<u64>() * 2 * 2 * 2;
+
+    // Partially encode the same inner chunk repeatedly, leaving stale inner chunks in the shard
+    let opt = CodecOptionsBuilder::new()
+        .experimental_partial_encoding(true)
+        .build();
+    array.store_array_subset_elements_opt::<u16>(
+        &ArraySubset::new_with_ranges(&[0..1, 0..2]),
+        &[1, 4],
+        &opt,
+    )?;
+    for value in [11, 12, 13] {
+        array.store_array_subset_elements_opt::<u16>(
+            &ArraySubset::new_with_ranges(&[0..1, 0..1]),
+            &[value],
+            &opt,
+        )?;
+    }
+    assert_eq!(
+        get_bytes_0_0()?.unwrap().len(),
+        shard_index_size + size_of::<u16>() * 5 // 3 stale inner chunks + 2 inner chunks
+    );
+
+    // Compact the shard
+    assert_eq!(
+        array.compact_shards(&ArraySubset::new_with_shape(vec![2, 2]))?,
+        size_of::<u16>() as u64 * 3
+    );
+    assert_eq!(
+        get_bytes_0_0()?.unwrap().len(),
+        shard_index_size + size_of::<u16>() * 2
+    );
+    assert_eq!(
+        array.retrieve_chunk_elements::<u16>(&[0, 0])?,
+        vec![13, 4, 0, 0]
+    );
+    assert_eq!(array.compact_shard(&[0, 0])?, 0);
+
+    // Compact the shard automatically when more than 2% of its bytes are unreferenced
+    let opt = CodecOptionsBuilder::new()
+        .experimental_partial_encoding(true)
+        .shard_compaction_threshold(Some(0.02))
+        .build();
+    for value in [1, 2, 3] {
+        array.store_array_subset_elements_opt::<u16>(
+            &ArraySubset::new_with_ranges(&[0..1, 0..1]),
+            &[value],
+            &opt,
+        )?;
+        assert_eq!(
+            get_bytes_0_0()?.unwrap().len(),
+            shard_index_size + size_of::<u16>() * 2
+        );
+    }
+    assert_eq!(
+        array.retrieve_chunk_elements::<u16>(&[0, 0])?,
+        vec![3, 4, 0, 0]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn array_partial_encode_sharding_compaction_index_start() {
+    array_partial_encode_sharding_compaction(ShardingIndexLocation::Start).unwrap();
+}
+
+#[test]
+fn array_partial_encode_sharding_compaction_index_end() {
+    array_partial_encode_sharding_compaction(ShardingIndexLocation::End).unwrap();
+}
+
 #[cfg(all(
     feature = "gzip",
     feature = "bz2",
@@ -257,3 +351,65 @@ fn array_partial_encode_sharding_index_compressed() {
         .unwrap();
     }
 }
+
+#[cfg(feature = "transpose")]
+#[test]
+fn array_partial_encode_transpose_sharding() -> Result<(), Box<dyn std::error::Error>> {
+    use zarrs::array::codec::{TransposeCodec, TransposeOrder};
+
+    let opt = CodecOptionsBuilder::new()
+        .experimental_partial_encoding(true)
+        .build();
+
+    let store = std::sync::Arc::new(MemoryStore::default());
+    let store_perf = Arc::new(PerformanceMetricsStorageAdapter::new(store.clone()));
+
+    let array_path = "/";
+    let mut builder = ArrayBuilder::new(
+        vec![4, 4], // array shape
+        DataType::UInt16,
+        vec![2, 4].try_into().unwrap(), // regular chunk shape
+        FillValue::from(0u16),
+    );
+    builder
+        .array_to_array_codecs(vec![Arc::new(TransposeCodec::new(
+            TransposeOrder::new(&[1, 0]).unwrap(),
+        ))])
+        .array_to_bytes_codec(Arc::new(
+            ShardingCodecBuilder::new(vec![1, 1].try_into().unwrap())
+                .index_bytes_to_bytes_codecs(vec![])
+                .index_location(ShardingIndexLocation::End)
+                .build(),
+        ))
+        .bytes_to_bytes_codecs(vec![]);
+    let array = builder.build(store_perf.clone(), array_path).unwrap();
+
+    // [0, 1, 0, 0]
+    // [0, 0, 0, 0]
+    array.store_array_subset_elements_opt::<u16>(
+        &ArraySubset::new_with_ranges(&[0..1, 1..2]),
+        &[1],
+        &opt,
+    )?;
+    assert_eq!(store_perf.reads(), 2); // index (sharding partial decoder and encoder)
+    assert_eq!(store_perf.writes(), 1);
+    store_perf.reset();
+
+    // [0, 1, 0, 0]
+    // [2, 3, 4, 0]
+    array.store_array_subset_elements_opt::<u16>(
+        &ArraySubset::new_with_ranges(&[1..2, 0..3]),
+        &[2, 3, 4],
+        &opt,
+    )?;
+    assert_eq!(store_perf.reads(), 2); // index (sharding partial decoder and encoder)
+    assert_eq!(store_perf.writes(), 1);
+    store_perf.reset();
+
+    assert_eq!(
+        array.retrieve_chunk_elements::<u16>(&[0, 0])?,
+        vec![0, 1, 0, 0, 2, 3, 4, 0]
+    );
+
+    Ok(())
+}
@@ -377,3 +377,61 @@ fn array_binary() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn array_sync_ndarray_view() -> Result<(), Box<dyn std::error::Error>> {
+    let store = Arc::new(MemoryStore::new());
+    let array = ArrayBuilder::new(
+        vec![4, 4],
+        DataType::UInt8,
+        vec![2, 2].try_into().unwrap(),
+        FillValue::from(0u8),
+    )
+    .build(store, "/")?;
+
+    // Transposed (non-standard layout) view
+    let data = ndarray::array![[1u8, 2], [3, 4]];
+    array.store_array_subset_ndarray_view(&[0, 0], data.t())?;
+    // Standard layout view
+    array.store_array_subset_ndarray_view(&[0, 2], data.view())?;
+    // Strided view
+    let data = ndarray::array![[5u8, 0, 6], [0, 0, 0], [7, 0, 8]];
+    array.store_array_subset_ndarray_view(&[2, 1], data.slice(ndarray::s![..;2, ..;2]))?;
+    assert!(array
+        .store_array_subset_ndarray_view(&[3, 3], data.view())
+        .is_err());
+    assert_eq!(
+        array.retrieve_array_subset_ndarray::<u8>(&array.subset_all())?,
+        ndarray::array![[1, 3, 1, 2], [2, 4, 3, 4], [0, 5, 6, 0], [0, 7, 8, 0]].into_dyn()
+    );
+
+    // Retrieve into a transposed view
+    let mut output = ndarray::Array2::<u8>::zeros((2, 2));
+    array.retrieve_into_ndarray_view_mut(
+        &ArraySubset::new_with_ranges(&[0..2, 0..2]),
+        output.view_mut().reversed_axes(),
+    )?;
+    assert_eq!(output, ndarray::array![[1, 2], [3, 4]]);
+    // Retrieve into a strided view
+    let mut output = ndarray::Array2::<u8>::zeros((3, 3));
+    array.retrieve_into_ndarray_view_mut(
+        &ArraySubset::new_with_ranges(&[2..4, 1..3]),
+        output.slice_mut(ndarray::s![..;2, ..;2]),
+    )?;
+    assert_eq!(output, ndarray::array![[5, 0, 6], [0, 0, 0], [7, 0, 8]]);
+    // Retrieve into a standard layout view
+    let mut output = ndarray::Array2::<u8>::zeros((2, 2));
+    array.retrieve_into_ndarray_view_mut(
+        &ArraySubset::new_with_ranges(&[0..2, 2..4]),
+        output.view_mut(),
+    )?;
+    assert_eq!(output, ndarray::array![[1, 2], [3, 4]]);
+    assert!(array
+        .retrieve_into_ndarray_view_mut(
+            &ArraySubset::new_with_ranges(&[0..1, 0..2]),
+            output.view_mut(),
+        )
+        .is_err());
+
+    Ok(())
+}
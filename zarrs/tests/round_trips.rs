@@ -4,7 +4,9 @@ use std::{error::Error, path::Path};
 
 use zarrs::{
     array::{
-        chunk_key_encoding::{DefaultChunkKeyEncoding, V2ChunkKeyEncoding},
+        chunk_key_encoding::{
+            DefaultChunkKeyEncoding, HashPrefixChunkKeyEncoding, V2ChunkKeyEncoding,
+        },
         ChunkKeyEncoding,
     },
     node::{data_key, meta_key_v3},
@@ -120,3 +122,15 @@ fn chunk_round_trip_filesystem_key_encoding_v2_slash() -> Result<(), Box<dyn Err
     assert!(path_expect.is_file());
     Ok(())
 }
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn chunk_round_trip_filesystem_key_encoding_hash_prefix() -> Result<(), Box<dyn Error>> {
+    let path = tempfile::TempDir::new()?;
+    let chunk_key_encoding = ChunkKeyEncoding::new(HashPrefixChunkKeyEncoding::default());
+    filesystem_chunk_round_trip_impl(path.path(), &chunk_key_encoding)?;
+    let mut path_expect = path.path().to_owned();
+    path_expect.push("group/array/81d2/c/0/0/0");
+    assert!(path_expect.is_file());
+    Ok(())
+}
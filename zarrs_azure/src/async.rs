@@ -0,0 +1,475 @@
+use futures::{StreamExt, TryStreamExt};
+use reqwest::{header, Client, Method, StatusCode, Url};
+
+use zarrs_storage::{
+    async_store_set_partial_values,
+    byte_range::{ByteRange, InvalidByteRangeError},
+    AsyncBytes, AsyncListableStorageTraits, AsyncReadableStorageTraits, AsyncWritableStorageTraits,
+    MaybeAsyncBytes, StorageError, StoreKey, StoreKeyOffsetValue, StoreKeys, StoreKeysPrefixes,
+    StorePrefix,
+};
+
+use crate::{
+    block_id, block_list_xml, block_ranges, byte_range_to_http_range, content_range_size,
+    credential::Authoriser, error_for_status, handle_reqwest_error, normalise_prefix,
+    AzureCredential, EnumerationResults, AZURE_STORAGE_VERSION, DEFAULT_BLOCK_SIZE,
+    DEFAULT_SINGLE_PUT_THRESHOLD, MAXIMUM_BLOCKS,
+};
+
+/// An asynchronous Azure Blob Storage store.
+///
+/// Use [`AzureBlobStoreBuilder`](crate::AzureBlobStoreBuilder) to create a store with account, credential, and endpoint configuration, or [`AsyncAzureBlobStore::new`] with an existing [`reqwest::Client`].
+#[derive(Debug, Clone)]
+pub struct AsyncAzureBlobStore {
+    client: Client,
+    container_url: Url,
+    prefix: String,
+    authoriser: Authoriser,
+    single_put_threshold: u64,
+    block_size: u64,
+}
+
+/// The blobs and blob prefixes returned by a `List Blobs` request.
+type BlobsAndPrefixes = (Vec<(String, u64)>, Vec<String>);
+
+impl AsyncAzureBlobStore {
+    /// Create a new Azure Blob Storage store for the root of the container at `container_url` (e.g. `https://<account>.blob.core.windows.net/<container>`).
+    #[must_use]
+    pub fn new(client: Client, container_url: Url, credential: AzureCredential) -> Self {
+        Self {
+            client,
+            container_url,
+            prefix: String::new(),
+            authoriser: Authoriser::new(credential),
+            single_put_threshold: DEFAULT_SINGLE_PUT_THRESHOLD,
+            block_size: DEFAULT_BLOCK_SIZE,
+        }
+    }
+
+    /// Set the prefix of the store root within the container.
+    #[must_use]
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.prefix = normalise_prefix(prefix);
+        self
+    }
+
+    /// Set the size threshold and block size in bytes of staged block uploads.
+    ///
+    /// The block size must be non-zero and at most [`MAXIMUM_BLOCK_SIZE`](crate::MAXIMUM_BLOCK_SIZE), otherwise uploads will fail.
+    #[must_use]
+    pub fn with_block_upload(mut self, single_put_threshold: u64, block_size: u64) -> Self {
+        self.single_put_threshold = single_put_threshold;
+        self.block_size = block_size;
+        self
+    }
+
+    /// Return the underlying [`reqwest::Client`].
+    #[must_use]
+    pub const fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Return the container URL.
+    #[must_use]
+    pub const fn container_url(&self) -> &Url {
+        &self.container_url
+    }
+
+    /// Return the credential.
+    #[must_use]
+    pub const fn credential(&self) -> &AzureCredential {
+        self.authoriser.credential()
+    }
+
+    /// Maps a [`StoreKey`] to a blob name.
+    #[must_use]
+    pub fn key_to_blob_name(&self, key: &StoreKey) -> String {
+        format!("{}{}", self.prefix, key.as_str())
+    }
+
+    fn prefix_to_blob_prefix(&self, prefix: &StorePrefix) -> String {
+        format!("{}{}", self.prefix, prefix.as_str())
+    }
+
+    fn blob_name_to_key(&self, blob_name: &str) -> Result<StoreKey, StorageError> {
+        let key = blob_name.strip_prefix(&self.prefix).unwrap_or(blob_name);
+        Ok(StoreKey::new(key)?)
+    }
+
+    /// Create the URL of a blob (or the container if `blob_name` is [`None`]) with query parameters.
+    fn url(&self, blob_name: Option<&str>, query: &[(&str, &str)]) -> Url {
+        let mut url = self.container_url.clone();
+        if let Some(blob_name) = blob_name {
+            if let Ok(mut segments) = url.path_segments_mut() {
+                segments.pop_if_empty().extend(blob_name.split('/'));
+            }
+        }
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+        self.authoriser.authorise_url(&mut url);
+        url
+    }
+
+    /// Send an authorised request.
+    async fn send(
+        &self,
+        method: Method,
+        url: Url,
+        build: impl FnOnce(reqwest::RequestBuilder) -> reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, StorageError> {
+        let request = self
+            .client
+            .request(method, url)
+            .header("x-ms-version", AZURE_STORAGE_VERSION);
+        let request = self
+            .authoriser
+            .authorise_request(&self.client, build(request))
+            .await?;
+        request.send().await.map_err(handle_reqwest_error)
+    }
+
+    /// Retrieve a single byte range of a value.
+    ///
+    /// Returns [`None`] if the key is not found.
+    async fn get_range(
+        &self,
+        key: &StoreKey,
+        byte_range: &ByteRange,
+    ) -> Result<MaybeAsyncBytes, StorageError> {
+        let (offset, length) = match byte_range {
+            ByteRange::FromStart(offset, length) => (*offset, *length),
+            ByteRange::Suffix(length) => {
+                // Azure Blob Storage does not support suffix ranges, so get the size first
+                let Some(size) = self.size_key(key).await? else {
+                    return Ok(None);
+                };
+                if *length > size {
+                    return Err(InvalidByteRangeError::new(*byte_range, size).into());
+                }
+                (size - length, Some(*length))
+            }
+        };
+        let Some(range) = byte_range_to_http_range(offset, length) else {
+            // An empty range cannot be requested, so check the key exists instead
+            return Ok(self.size_key(key).await?.map(|_| AsyncBytes::new()));
+        };
+
+        let url = self.url(Some(&self.key_to_blob_name(key)), &[]);
+        let response = self
+            .send(Method::GET, url, |request| {
+                request.header(header::RANGE, range)
+            })
+            .await?;
+        match response.status() {
+            StatusCode::NOT_FOUND => return Ok(None),
+            StatusCode::RANGE_NOT_SATISFIABLE => {
+                // The range is not satisfiable, this is valid only for an empty range at the end of the value
+                return if let Some(size) = self.size_key(key).await? {
+                    if byte_range.start(size) == size && byte_range.end(size) == size {
+                        Ok(Some(AsyncBytes::new()))
+                    } else {
+                        Err(InvalidByteRangeError::new(*byte_range, size).into())
+                    }
+                } else {
+                    Ok(None)
+                };
+            }
+            _ => {}
+        }
+
+        let response = error_for_status(response).await?;
+        let size = response
+            .headers()
+            .get(header::CONTENT_RANGE)
+            .and_then(|content_range| content_range.to_str().ok())
+            .and_then(content_range_size);
+        let bytes = response.bytes().await.map_err(handle_reqwest_error)?;
+        if let (Some(size), Some(length)) = (size, length) {
+            // Azure Blob Storage truncates ranges that extend beyond the end of the value
+            if bytes.len() as u64 != length {
+                return Err(InvalidByteRangeError::new(*byte_range, size).into());
+            }
+        }
+        Ok(Some(bytes))
+    }
+
+    /// Write a value as a block blob with parallel staged `Put Block` uploads and a `Put Block List`.
+    ///
+    /// Uncommitted blocks of a failed upload are discarded by Azure Blob Storage after a week.
+    async fn put_block_list(&self, key: &StoreKey, value: AsyncBytes) -> Result<(), StorageError> {
+        let blob_name = self.key_to_blob_name(key);
+        let block_size = usize::try_from(self.block_size).unwrap();
+        let ranges = block_ranges(value.len(), block_size);
+        if ranges.len() > MAXIMUM_BLOCKS {
+            return Err(StorageError::Other(format!(
+                "value of {} bytes exceeds the maximum of {MAXIMUM_BLOCKS} blocks of {block_size} bytes",
+                value.len()
+            )));
+        }
+
+        let block_ids =
+            futures::future::try_join_all(ranges.into_iter().enumerate().map(|(i, range)| {
+                let block_id = block_id(i);
+                let url = self.url(
+                    Some(&blob_name),
+                    &[("comp", "block"), ("blockid", &block_id)],
+                );
+                let body = value.slice(range);
+                async move {
+                    let response = self
+                        .send(Method::PUT, url, |request| request.body(body))
+                        .await?;
+                    error_for_status(response).await?;
+                    Ok::<_, StorageError>(block_id)
+                }
+            }))
+            .await?;
+
+        let url = self.url(Some(&blob_name), &[("comp", "blocklist")]);
+        let body = block_list_xml(&block_ids);
+        let response = self
+            .send(Method::PUT, url, |request| {
+                request
+                    .header(header::CONTENT_TYPE, "application/xml")
+                    .body(body)
+            })
+            .await?;
+        error_for_status(response).await?;
+        Ok(())
+    }
+
+    /// List blobs with a blob name prefix, returning the blob names and sizes, and the blob prefixes if `delimited`.
+    async fn list_blobs(
+        &self,
+        blob_prefix: &str,
+        delimited: bool,
+    ) -> Result<BlobsAndPrefixes, StorageError> {
+        let mut blobs = Vec::new();
+        let mut prefixes = Vec::new();
+        let mut marker: Option<String> = None;
+        loop {
+            let mut query = vec![
+                ("restype", "container"),
+                ("comp", "list"),
+                ("prefix", blob_prefix),
+            ];
+            if delimited {
+                query.push(("delimiter", "/"));
+            }
+            if let Some(marker) = &marker {
+                query.push(("marker", marker));
+            }
+            let url = self.url(None, &query);
+            let response = self.send(Method::GET, url, |request| request).await?;
+            let xml = error_for_status(response)
+                .await?
+                .text()
+                .await
+                .map_err(handle_reqwest_error)?;
+            let results = EnumerationResults::parse(&xml)?;
+            let next_marker = results.next_marker().map(str::to_string);
+            blobs.extend(
+                results
+                    .blobs
+                    .blobs
+                    .into_iter()
+                    .map(|blob| (blob.name, blob.properties.content_length)),
+            );
+            prefixes.extend(
+                results
+                    .blobs
+                    .blob_prefixes
+                    .into_iter()
+                    .map(|prefix| prefix.name),
+            );
+            if next_marker.is_none() {
+                break;
+            }
+            marker = next_marker;
+        }
+        Ok((blobs, prefixes))
+    }
+
+    /// Delete blobs concurrently, ignoring blobs that do not exist.
+    async fn delete_blobs(&self, blob_names: Vec<String>) -> Result<(), StorageError> {
+        futures::stream::iter(blob_names)
+            .map(Ok)
+            .try_for_each_concurrent(None, |blob_name| async move {
+                let url = self.url(Some(&blob_name), &[]);
+                let response = self.send(Method::DELETE, url, |request| request).await?;
+                if response.status() != StatusCode::NOT_FOUND {
+                    error_for_status(response).await?;
+                }
+                Ok(())
+            })
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncReadableStorageTraits for AsyncAzureBlobStore {
+    async fn get(&self, key: &StoreKey) -> Result<MaybeAsyncBytes, StorageError> {
+        let url = self.url(Some(&self.key_to_blob_name(key)), &[]);
+        let response = self.send(Method::GET, url, |request| request).await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        Ok(Some(
+            error_for_status(response)
+                .await?
+                .bytes()
+                .await
+                .map_err(handle_reqwest_error)?,
+        ))
+    }
+
+    async fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<AsyncBytes>>, StorageError> {
+        let values = futures::future::try_join_all(
+            byte_ranges
+                .iter()
+                .map(|byte_range| self.get_range(key, byte_range)),
+        )
+        .await?;
+        Ok(values.into_iter().collect())
+    }
+
+    async fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        let url = self.url(Some(&self.key_to_blob_name(key)), &[]);
+        let response = self.send(Method::HEAD, url, |request| request).await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let response = error_for_status(response).await?;
+        Ok(Some(
+            response
+                .headers()
+                .get(header::CONTENT_LENGTH)
+                .and_then(|length| length.to_str().ok())
+                .and_then(|length| length.parse().ok())
+                .unwrap_or_default(),
+        ))
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncWritableStorageTraits for AsyncAzureBlobStore {
+    async fn set(&self, key: &StoreKey, value: AsyncBytes) -> Result<(), StorageError> {
+        if value.len() as u64 > self.single_put_threshold {
+            self.put_block_list(key, value).await
+        } else {
+            let url = self.url(Some(&self.key_to_blob_name(key)), &[]);
+            let response = self
+                .send(Method::PUT, url, |request| {
+                    request.header("x-ms-blob-type", "BlockBlob").body(value)
+                })
+                .await?;
+            error_for_status(response).await?;
+            Ok(())
+        }
+    }
+
+    async fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        async_store_set_partial_values(self, key_offset_values).await
+    }
+
+    async fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.delete_blobs(vec![self.key_to_blob_name(key)]).await
+    }
+
+    async fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        self.delete_blobs(keys.iter().map(|key| self.key_to_blob_name(key)).collect())
+            .await
+    }
+
+    async fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        let (blobs, _) = self
+            .list_blobs(&self.prefix_to_blob_prefix(prefix), false)
+            .await?;
+        self.delete_blobs(blobs.into_iter().map(|(blob_name, _)| blob_name).collect())
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncListableStorageTraits for AsyncAzureBlobStore {
+    async fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.list_prefix(&StorePrefix::root()).await
+    }
+
+    async fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        let (blobs, _) = self
+            .list_blobs(&self.prefix_to_blob_prefix(prefix), false)
+            .await?;
+        let mut keys = blobs
+            .into_iter()
+            .map(|(blob_name, _)| self.blob_name_to_key(&blob_name))
+            .collect::<Result<Vec<_>, _>>()?;
+        keys.sort();
+        Ok(keys)
+    }
+
+    async fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        let (blobs, blob_prefixes) = self
+            .list_blobs(&self.prefix_to_blob_prefix(prefix), true)
+            .await?;
+        let mut keys = blobs
+            .into_iter()
+            .map(|(blob_name, _)| self.blob_name_to_key(&blob_name))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut prefixes = blob_prefixes
+            .iter()
+            .map(|blob_prefix| {
+                let prefix = blob_prefix
+                    .strip_prefix(&self.prefix)
+                    .unwrap_or(blob_prefix);
+                Ok(StorePrefix::new(prefix)?)
+            })
+            .collect::<Result<Vec<_>, StorageError>>()?;
+        keys.sort();
+        prefixes.sort();
+        Ok(StoreKeysPrefixes::new(keys, prefixes))
+    }
+
+    async fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        let (blobs, _) = self
+            .list_blobs(&self.prefix_to_blob_prefix(prefix), false)
+            .await?;
+        Ok(blobs.into_iter().map(|(_, size)| size).sum())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn azure_blob_url() {
+        let store = AsyncAzureBlobStore::new(
+            Client::new(),
+            Url::parse("https://account.blob.core.windows.net/container").unwrap(),
+            AzureCredential::sas_token("sig=abc"),
+        )
+        .with_prefix("root");
+        let key = StoreKey::new("a b/c/zarr.json").unwrap();
+        assert_eq!(
+            store
+                .url(Some(&store.key_to_blob_name(&key)), &[("comp", "block")])
+                .as_str(),
+            "https://account.blob.core.windows.net/container/root/a%20b/c/zarr.json?comp=block&sig=abc"
+        );
+        assert_eq!(
+            store
+                .url(None, &[("restype", "container"), ("prefix", "root/")])
+                .as_str(),
+            "https://account.blob.core.windows.net/container?restype=container&prefix=root%2F&sig=abc"
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::Deserialize;
+use zarrs_storage::StorageError;
+
+use crate::{error_for_status, handle_reqwest_error};
+
+/// The Azure Instance Metadata Service (IMDS) managed identity token endpoint.
+const IMDS_TOKEN_ENDPOINT: &str = "http://169.254.169.254/metadata/identity/oauth2/token";
+
+/// The resource for which managed identity access tokens are requested.
+const STORAGE_RESOURCE: &str = "https://storage.azure.com/";
+
+/// Managed identity access tokens are refreshed if they expire within this duration.
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(300);
+
+/// An Azure Blob Storage credential.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AzureCredential {
+    /// Anonymous access, for containers with public read access.
+    Anonymous,
+    /// A shared access signature (SAS) token, appended to the query of every request.
+    SasToken(String),
+    /// A managed identity, with access tokens retrieved from the Azure Instance Metadata Service.
+    ///
+    /// `client_id` selects a user-assigned identity, otherwise the system-assigned identity is used.
+    ManagedIdentity {
+        /// The client ID of a user-assigned identity.
+        client_id: Option<String>,
+    },
+}
+
+impl AzureCredential {
+    /// Create a shared access signature (SAS) token credential.
+    ///
+    /// A leading `?` is ignored.
+    #[must_use]
+    pub fn sas_token(sas_token: impl Into<String>) -> Self {
+        let sas_token: String = sas_token.into();
+        Self::SasToken(sas_token.trim_start_matches('?').to_string())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct AccessToken {
+    token: String,
+    expires_on: SystemTime,
+}
+
+#[derive(Deserialize)]
+struct ImdsTokenResponse {
+    access_token: String,
+    #[serde(deserialize_with = "deserialize_expires_on")]
+    expires_on: u64,
+}
+
+/// IMDS returns `expires_on` as a string of seconds since the Unix epoch.
+fn deserialize_expires_on<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<u64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum ExpiresOn {
+        String(String),
+        Number(u64),
+    }
+    match ExpiresOn::deserialize(deserializer)? {
+        ExpiresOn::String(expires_on) => expires_on.parse().map_err(serde::de::Error::custom),
+        ExpiresOn::Number(expires_on) => Ok(expires_on),
+    }
+}
+
+/// Authorises requests with an [`AzureCredential`], caching managed identity access tokens.
+#[derive(Debug, Clone)]
+pub(crate) struct Authoriser {
+    credential: AzureCredential,
+    token: Arc<tokio::sync::Mutex<Option<AccessToken>>>,
+}
+
+impl Authoriser {
+    pub(crate) fn new(credential: AzureCredential) -> Self {
+        Self {
+            credential,
+            token: Arc::default(),
+        }
+    }
+
+    pub(crate) const fn credential(&self) -> &AzureCredential {
+        &self.credential
+    }
+
+    /// Append the SAS token (if any) to the query of `url`.
+    pub(crate) fn authorise_url(&self, url: &mut reqwest::Url) {
+        if let AzureCredential::SasToken(sas_token) = &self.credential {
+            let query = match url.query() {
+                Some(query) if !query.is_empty() => format!("{query}&{sas_token}"),
+                _ => sas_token.clone(),
+            };
+            url.set_query(Some(&query));
+        }
+    }
+
+    /// Add an `Authorization` header (if any) to a request.
+    pub(crate) async fn authorise_request(
+        &self,
+        client: &reqwest::Client,
+        request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::RequestBuilder, StorageError> {
+        if let AzureCredential::ManagedIdentity { client_id } = &self.credential {
+            let token = self.access_token(client, client_id.as_deref()).await?;
+            Ok(request.bearer_auth(token))
+        } else {
+            Ok(request)
+        }
+    }
+
+    async fn access_token(
+        &self,
+        client: &reqwest::Client,
+        client_id: Option<&str>,
+    ) -> Result<String, StorageError> {
+        let mut token = self.token.lock().await;
+        if let Some(token) = token.as_ref() {
+            if token.expires_on > SystemTime::now() + TOKEN_REFRESH_MARGIN {
+                return Ok(token.token.clone());
+            }
+        }
+
+        let mut query = vec![
+            ("api-version", "2018-02-01"),
+            ("resource", STORAGE_RESOURCE),
+        ];
+        if let Some(client_id) = client_id {
+            query.push(("client_id", client_id));
+        }
+        let response = client
+            .get(IMDS_TOKEN_ENDPOINT)
+            .query(&query)
+            .header("Metadata", "true")
+            .send()
+            .await
+            .map_err(handle_reqwest_error)?;
+        let response = error_for_status(response)
+            .await?
+            .bytes()
+            .await
+            .map_err(handle_reqwest_error)?;
+        let response: ImdsTokenResponse = serde_json::from_slice(&response).map_err(|err| {
+            StorageError::Other(format!("invalid managed identity token response: {err}"))
+        })?;
+
+        let access_token = AccessToken {
+            token: response.access_token,
+            expires_on: UNIX_EPOCH + Duration::from_secs(response.expires_on),
+        };
+        let result = access_token.token.clone();
+        *token = Some(access_token);
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn azure_sas_token() {
+        let authoriser = Authoriser::new(AzureCredential::sas_token("?sv=1&sig=abc"));
+        let mut url = reqwest::Url::parse("https://account.blob.core.windows.net/c/k").unwrap();
+        authoriser.authorise_url(&mut url);
+        assert_eq!(url.query(), Some("sv=1&sig=abc"));
+
+        let mut url =
+            reqwest::Url::parse("https://account.blob.core.windows.net/c?comp=list").unwrap();
+        authoriser.authorise_url(&mut url);
+        assert_eq!(url.query(), Some("comp=list&sv=1&sig=abc"));
+    }
+
+    #[test]
+    fn azure_imds_token_response() {
+        let response: ImdsTokenResponse =
+            serde_json::from_str(r#"{"access_token":"token","expires_on":"1700000000"}"#).unwrap();
+        assert_eq!(response.access_token, "token");
+        assert_eq!(response.expires_on, 1_700_000_000);
+    }
+}
@@ -0,0 +1,483 @@
+//! An Azure Blob Storage store for the [`zarrs`](https://docs.rs/zarrs/latest/zarrs/index.html) crate.
+//!
+//! The store is backed by the [Blob service REST API](https://learn.microsoft.com/en-us/rest/api/storageservices/blob-service-rest-api).
+//! It:
+//! - retrieves multiple byte ranges of a value with parallel ranged `Get Blob` requests,
+//! - writes values over a configurable size threshold as block blobs with parallel staged `Put Block` uploads, and
+//! - lists directories with delimiter-based `List Blobs` requests.
+//!
+//! ```no_run
+//! # use std::sync::Arc;
+//! use zarrs_storage::AsyncReadableWritableListableStorage;
+//! use zarrs_azure::AzureBlobStoreBuilder;
+//!
+//! # fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let store: AsyncReadableWritableListableStorage = Arc::new(
+//!     AzureBlobStoreBuilder::new("account", "container")
+//!         .sas_token("sv=...&sig=...")
+//!         .prefix("path/to/hierarchy.zarr")
+//!         .build_async()?,
+//! );
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! A synchronous [`AzureBlobStore`] can be created with [`AzureBlobStoreBuilder::build`].
+//!
+//! ## Licence
+//! `zarrs_azure` is licensed under either of
+//! - the Apache License, Version 2.0 [LICENSE-APACHE](https://docs.rs/crate/zarrs_azure/latest/source/LICENCE-APACHE) or <http://www.apache.org/licenses/LICENSE-2.0> or
+//! - the MIT license [LICENSE-MIT](https://docs.rs/crate/zarrs_azure/latest/source/LICENCE-MIT) or <http://opensource.org/licenses/MIT>, at your option.
+
+mod r#async;
+mod credential;
+mod sync;
+
+pub use credential::AzureCredential;
+pub use r#async::AsyncAzureBlobStore;
+pub use sync::AzureBlobStore;
+
+use base64::Engine;
+use serde::Deserialize;
+use thiserror::Error;
+use zarrs_storage::StorageError;
+
+/// The version of the Blob service REST API used by the store.
+const AZURE_STORAGE_VERSION: &str = "2021-08-06";
+
+/// The default size threshold above which values are written with a staged block upload (16 MiB).
+pub const DEFAULT_SINGLE_PUT_THRESHOLD: u64 = 16 * 1024 * 1024;
+
+/// The default block size of a staged block upload (8 MiB).
+pub const DEFAULT_BLOCK_SIZE: u64 = 8 * 1024 * 1024;
+
+/// The maximum block size supported by Azure Blob Storage (4000 MiB).
+pub const MAXIMUM_BLOCK_SIZE: u64 = 4000 * 1024 * 1024;
+
+/// The maximum number of blocks in a block blob.
+const MAXIMUM_BLOCKS: usize = 50_000;
+
+/// A builder for an [`AsyncAzureBlobStore`] or [`AzureBlobStore`].
+///
+/// The store uses anonymous access unless a SAS token or managed identity is set.
+#[derive(Debug, Clone)]
+pub struct AzureBlobStoreBuilder {
+    account: String,
+    container: String,
+    prefix: String,
+    credential: AzureCredential,
+    endpoint_url: Option<String>,
+    single_put_threshold: u64,
+    block_size: u64,
+}
+
+impl AzureBlobStoreBuilder {
+    /// Create a new Azure Blob Storage store builder for `container` in the storage `account`.
+    #[must_use]
+    pub fn new(account: impl Into<String>, container: impl Into<String>) -> Self {
+        Self {
+            account: account.into(),
+            container: container.into(),
+            prefix: String::new(),
+            credential: AzureCredential::Anonymous,
+            endpoint_url: None,
+            single_put_threshold: DEFAULT_SINGLE_PUT_THRESHOLD,
+            block_size: DEFAULT_BLOCK_SIZE,
+        }
+    }
+
+    /// Set the prefix of the store root within the container.
+    ///
+    /// Defaults to the container root.
+    #[must_use]
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Set the credential.
+    ///
+    /// Defaults to [`AzureCredential::Anonymous`].
+    #[must_use]
+    pub fn credential(mut self, credential: AzureCredential) -> Self {
+        self.credential = credential;
+        self
+    }
+
+    /// Authenticate with a shared access signature (SAS) token.
+    ///
+    /// A leading `?` is ignored.
+    #[must_use]
+    pub fn sas_token(self, sas_token: impl Into<String>) -> Self {
+        self.credential(AzureCredential::sas_token(sas_token))
+    }
+
+    /// Authenticate with a managed identity.
+    ///
+    /// `client_id` selects a user-assigned identity, otherwise the system-assigned identity is used.
+    #[must_use]
+    pub fn managed_identity(self, client_id: Option<String>) -> Self {
+        self.credential(AzureCredential::ManagedIdentity { client_id })
+    }
+
+    /// Set the endpoint URL of the storage account, e.g. `http://127.0.0.1:10000/devstoreaccount1` for the Azurite emulator.
+    ///
+    /// Defaults to `https://<account>.blob.core.windows.net`.
+    #[must_use]
+    pub fn endpoint_url(mut self, endpoint_url: impl Into<String>) -> Self {
+        self.endpoint_url = Some(endpoint_url.into());
+        self
+    }
+
+    /// Set the size threshold in bytes above which values are written with a staged block upload.
+    ///
+    /// Defaults to [`DEFAULT_SINGLE_PUT_THRESHOLD`].
+    #[must_use]
+    pub const fn single_put_threshold(mut self, single_put_threshold: u64) -> Self {
+        self.single_put_threshold = single_put_threshold;
+        self
+    }
+
+    /// Set the block size in bytes of staged block uploads.
+    ///
+    /// Defaults to [`DEFAULT_BLOCK_SIZE`].
+    /// Must be non-zero and at most [`MAXIMUM_BLOCK_SIZE`].
+    #[must_use]
+    pub const fn block_size(mut self, block_size: u64) -> Self {
+        self.block_size = block_size;
+        self
+    }
+
+    fn validate(&self) -> Result<reqwest::Url, AzureBlobStoreCreateError> {
+        if self.account.is_empty() {
+            return Err(AzureBlobStoreCreateError::InvalidAccount(
+                self.account.clone(),
+            ));
+        }
+        if self.container.is_empty() || self.container.contains('/') {
+            return Err(AzureBlobStoreCreateError::InvalidContainer(
+                self.container.clone(),
+            ));
+        }
+        if self.block_size == 0 || self.block_size > MAXIMUM_BLOCK_SIZE {
+            return Err(AzureBlobStoreCreateError::InvalidBlockSize(self.block_size));
+        }
+        let endpoint_url = self
+            .endpoint_url
+            .clone()
+            .unwrap_or_else(|| format!("https://{}.blob.core.windows.net", self.account));
+        let mut url = reqwest::Url::parse(&endpoint_url)
+            .map_err(|_| AzureBlobStoreCreateError::InvalidEndpointUrl(endpoint_url.clone()))?;
+        url.path_segments_mut()
+            .map_err(|()| AzureBlobStoreCreateError::InvalidEndpointUrl(endpoint_url))?
+            .pop_if_empty()
+            .push(&self.container);
+        Ok(url)
+    }
+
+    /// Build an [`AsyncAzureBlobStore`].
+    ///
+    /// # Errors
+    /// Returns an [`AzureBlobStoreCreateError`] if the account, container, endpoint URL, or block size is invalid.
+    pub fn build_async(self) -> Result<AsyncAzureBlobStore, AzureBlobStoreCreateError> {
+        let container_url = self.validate()?;
+        let client = reqwest::Client::builder().build()?;
+        Ok(
+            AsyncAzureBlobStore::new(client, container_url, self.credential)
+                .with_prefix(&self.prefix)
+                .with_block_upload(self.single_put_threshold, self.block_size),
+        )
+    }
+
+    /// Build a synchronous [`AzureBlobStore`].
+    ///
+    /// The store owns a multi-threaded `tokio` runtime.
+    ///
+    /// # Errors
+    /// Returns an [`AzureBlobStoreCreateError`] if the account, container, endpoint URL, or block size is invalid, or the runtime cannot be created.
+    pub fn build(self) -> Result<AzureBlobStore, AzureBlobStoreCreateError> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+        let store = self.build_async()?;
+        Ok(AzureBlobStore::new_with_runtime(store, runtime))
+    }
+}
+
+/// An Azure Blob Storage store creation error.
+#[derive(Debug, Error)]
+pub enum AzureBlobStoreCreateError {
+    /// An IO error.
+    #[error(transparent)]
+    IOError(#[from] std::io::Error),
+    /// A HTTP client error.
+    #[error(transparent)]
+    ReqwestError(#[from] reqwest::Error),
+    /// The storage account name is not valid.
+    #[error("storage account {0} is not valid")]
+    InvalidAccount(String),
+    /// The container name is not valid.
+    #[error("container {0} is not valid")]
+    InvalidContainer(String),
+    /// The endpoint URL is not valid.
+    #[error("endpoint URL {0} is not valid")]
+    InvalidEndpointUrl(String),
+    /// The block size is zero or greater than [`MAXIMUM_BLOCK_SIZE`].
+    #[error("block size {0} must be non-zero and at most {MAXIMUM_BLOCK_SIZE}")]
+    InvalidBlockSize(u64),
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn handle_reqwest_error(err: reqwest::Error) -> StorageError {
+    StorageError::Other(err.to_string())
+}
+
+/// Return an error if a response does not have a success status code.
+async fn error_for_status(response: reqwest::Response) -> Result<reqwest::Response, StorageError> {
+    let status = response.status();
+    if status.is_success() {
+        Ok(response)
+    } else {
+        let body = response.text().await.unwrap_or_default();
+        Err(StorageError::Other(format!(
+            "Azure Blob Storage request failed with status {status}: {body}"
+        )))
+    }
+}
+
+/// Normalise a store prefix within a container so that it is empty or ends with `/`.
+fn normalise_prefix(prefix: &str) -> String {
+    let prefix = prefix.trim_matches('/');
+    if prefix.is_empty() {
+        String::new()
+    } else {
+        format!("{prefix}/")
+    }
+}
+
+/// Convert a byte range from `offset` with an optional `length` to a HTTP `Range` header value.
+///
+/// Returns [`None`] if the byte range is empty.
+fn byte_range_to_http_range(offset: u64, length: Option<u64>) -> Option<String> {
+    match length {
+        None => Some(format!("bytes={offset}-")),
+        Some(0) => None,
+        Some(length) => Some(format!("bytes={offset}-{}", offset + length - 1)),
+    }
+}
+
+/// Parse the complete length of a value from a `Content-Range` header value (e.g. `bytes 0-3/4`).
+fn content_range_size(content_range: &str) -> Option<u64> {
+    content_range
+        .rsplit_once('/')
+        .and_then(|(_, size)| size.parse().ok())
+}
+
+/// Split `size` bytes into staged block upload ranges of `block_size` bytes.
+fn block_ranges(size: usize, block_size: usize) -> Vec<std::ops::Range<usize>> {
+    (0..size)
+        .step_by(block_size)
+        .map(|start| start..usize::min(start + block_size, size))
+        .collect()
+}
+
+/// Create a block ID for the block at `index`.
+///
+/// All block IDs within a blob must have the same length.
+fn block_id(index: usize) -> String {
+    base64::engine::general_purpose::STANDARD.encode(format!("{index:08}"))
+}
+
+/// Create a `Put Block List` request body committing `block_ids` in order.
+fn block_list_xml(block_ids: &[String]) -> String {
+    let mut xml = String::from(r#"<?xml version="1.0" encoding="utf-8"?><BlockList>"#);
+    for block_id in block_ids {
+        xml.push_str("<Latest>");
+        xml.push_str(block_id);
+        xml.push_str("</Latest>");
+    }
+    xml.push_str("</BlockList>");
+    xml
+}
+
+/// A `List Blobs` response.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct EnumerationResults {
+    #[serde(default)]
+    blobs: ListBlobs,
+    #[serde(default)]
+    next_marker: Option<String>,
+}
+
+impl EnumerationResults {
+    fn parse(xml: &str) -> Result<Self, StorageError> {
+        quick_xml::de::from_str(xml).map_err(|err| StorageError::Other(err.to_string()))
+    }
+
+    /// Return the marker of the next page, if any.
+    fn next_marker(&self) -> Option<&str> {
+        self.next_marker
+            .as_deref()
+            .filter(|marker| !marker.is_empty())
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ListBlobs {
+    #[serde(rename = "Blob", default)]
+    blobs: Vec<ListBlob>,
+    #[serde(rename = "BlobPrefix", default)]
+    blob_prefixes: Vec<ListBlobPrefix>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ListBlob {
+    name: String,
+    properties: ListBlobProperties,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListBlobProperties {
+    #[serde(rename = "Content-Length")]
+    content_length: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ListBlobPrefix {
+    name: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn azure_byte_range_to_http_range() {
+        assert_eq!(
+            byte_range_to_http_range(0, None).as_deref(),
+            Some("bytes=0-")
+        );
+        assert_eq!(
+            byte_range_to_http_range(2, Some(3)).as_deref(),
+            Some("bytes=2-4")
+        );
+        assert!(byte_range_to_http_range(2, Some(0)).is_none());
+    }
+
+    #[test]
+    fn azure_content_range_size() {
+        assert_eq!(content_range_size("bytes 0-3/4"), Some(4));
+        assert_eq!(content_range_size("bytes 0-3/*"), None);
+        assert_eq!(content_range_size("invalid"), None);
+    }
+
+    #[test]
+    fn azure_prefix() {
+        assert_eq!(normalise_prefix(""), "");
+        assert_eq!(normalise_prefix("/"), "");
+        assert_eq!(normalise_prefix("a/b"), "a/b/");
+        assert_eq!(normalise_prefix("/a/b/"), "a/b/");
+    }
+
+    #[test]
+    fn azure_block_ranges() {
+        assert_eq!(block_ranges(0, 4), vec![]);
+        assert_eq!(block_ranges(4, 4), vec![0..4]);
+        assert_eq!(block_ranges(10, 4), vec![0..4, 4..8, 8..10]);
+    }
+
+    #[test]
+    fn azure_block_list() {
+        let block_ids = vec![block_id(0), block_id(12345)];
+        assert_eq!(block_ids[0].len(), block_ids[1].len());
+        assert_eq!(
+            block_list_xml(&block_ids),
+            r#"<?xml version="1.0" encoding="utf-8"?><BlockList><Latest>MDAwMDAwMDA=</Latest><Latest>MDAwMTIzNDU=</Latest></BlockList>"#
+        );
+    }
+
+    #[test]
+    fn azure_list_blobs_parse() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<EnumerationResults ServiceEndpoint="https://account.blob.core.windows.net/" ContainerName="container">
+  <Prefix>root/</Prefix>
+  <Delimiter>/</Delimiter>
+  <Blobs>
+    <Blob>
+      <Name>root/zarr.json</Name>
+      <Properties>
+        <Content-Length>123</Content-Length>
+        <BlobType>BlockBlob</BlobType>
+      </Properties>
+    </Blob>
+    <BlobPrefix>
+      <Name>root/a/</Name>
+    </BlobPrefix>
+    <Blob>
+      <Name>root/b</Name>
+      <Properties>
+        <Content-Length>0</Content-Length>
+      </Properties>
+    </Blob>
+    <BlobPrefix>
+      <Name>root/c/</Name>
+    </BlobPrefix>
+  </Blobs>
+  <NextMarker>marker</NextMarker>
+</EnumerationResults>"#;
+        let results = EnumerationResults::parse(xml).unwrap();
+        assert_eq!(results.blobs.blobs.len(), 2);
+        assert_eq!(results.blobs.blobs[0].name, "root/zarr.json");
+        assert_eq!(results.blobs.blobs[0].properties.content_length, 123);
+        assert_eq!(results.blobs.blobs[1].name, "root/b");
+        assert_eq!(results.blobs.blob_prefixes.len(), 2);
+        assert_eq!(results.blobs.blob_prefixes[1].name, "root/c/");
+        assert_eq!(results.next_marker(), Some("marker"));
+
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<EnumerationResults ContainerName="container">
+  <Blobs />
+  <NextMarker />
+</EnumerationResults>"#;
+        let results = EnumerationResults::parse(xml).unwrap();
+        assert!(results.blobs.blobs.is_empty());
+        assert!(results.blobs.blob_prefixes.is_empty());
+        assert_eq!(results.next_marker(), None);
+    }
+
+    #[test]
+    fn azure_builder_validate() {
+        assert!(AzureBlobStoreBuilder::new("", "container")
+            .validate()
+            .is_err());
+        assert!(AzureBlobStoreBuilder::new("account", "")
+            .validate()
+            .is_err());
+        assert!(AzureBlobStoreBuilder::new("account", "container")
+            .block_size(0)
+            .validate()
+            .is_err());
+        assert!(AzureBlobStoreBuilder::new("account", "container")
+            .endpoint_url("not a url")
+            .validate()
+            .is_err());
+        assert_eq!(
+            AzureBlobStoreBuilder::new("account", "container")
+                .validate()
+                .unwrap()
+                .as_str(),
+            "https://account.blob.core.windows.net/container"
+        );
+        assert_eq!(
+            AzureBlobStoreBuilder::new("devstoreaccount1", "container")
+                .endpoint_url("http://127.0.0.1:10000/devstoreaccount1/")
+                .validate()
+                .unwrap()
+                .as_str(),
+            "http://127.0.0.1:10000/devstoreaccount1/container"
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use zarrs_storage::{
+    byte_range::ByteRange, AsyncListableStorageTraits, AsyncReadableStorageTraits,
+    AsyncWritableStorageTraits, Bytes, ListableStorageTraits, MaybeBytes, ReadableStorageTraits,
+    StorageError, StoreKey, StoreKeyOffsetValue, StoreKeyRange, StoreKeys, StoreKeysPrefixes,
+    StorePrefix, WritableStorageTraits,
+};
+
+use crate::AsyncAzureBlobStore;
+
+/// A synchronous Azure Blob Storage store.
+///
+/// This store wraps an [`AsyncAzureBlobStore`] and a `tokio` runtime that drives its requests.
+/// Requests for multiple byte ranges or keys are still issued in parallel.
+///
+/// An [`AzureBlobStore`] will panic if called within an asynchronous execution context!
+#[derive(Debug)]
+pub struct AzureBlobStore {
+    store: AsyncAzureBlobStore,
+    runtime: tokio::runtime::Runtime,
+}
+
+impl AzureBlobStore {
+    /// Create a new synchronous Azure Blob Storage store from an [`AsyncAzureBlobStore`] and the `tokio` runtime to drive it.
+    #[must_use]
+    pub fn new_with_runtime(store: AsyncAzureBlobStore, runtime: tokio::runtime::Runtime) -> Self {
+        Self { store, runtime }
+    }
+
+    /// Return the underlying [`AsyncAzureBlobStore`].
+    #[must_use]
+    pub const fn async_store(&self) -> &AsyncAzureBlobStore {
+        &self.store
+    }
+
+    fn block_on<F: core::future::Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+}
+
+impl ReadableStorageTraits for AzureBlobStore {
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        self.block_on(self.store.get(key))
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        self.block_on(self.store.get_partial_values_key(key, byte_ranges))
+    }
+
+    fn get_partial_values(
+        &self,
+        key_ranges: &[StoreKeyRange],
+    ) -> Result<Vec<MaybeBytes>, StorageError> {
+        self.block_on(self.store.get_partial_values(key_ranges))
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        self.block_on(self.store.size_key(key))
+    }
+}
+
+impl WritableStorageTraits for AzureBlobStore {
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        self.block_on(self.store.set(key, value))
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        self.block_on(self.store.set_partial_values(key_offset_values))
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.block_on(self.store.erase(key))
+    }
+
+    fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        self.block_on(self.store.erase_values(keys))
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.block_on(self.store.erase_prefix(prefix))
+    }
+}
+
+impl ListableStorageTraits for AzureBlobStore {
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.block_on(self.store.list())
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.block_on(self.store.list_prefix(prefix))
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.block_on(self.store.list_dir(prefix))
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.block_on(self.store.size_prefix(prefix))
+    }
+}
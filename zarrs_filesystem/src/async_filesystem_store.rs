@@ -0,0 +1,503 @@
+//! An asynchronous file system store.
+
+use std::{
+    collections::HashMap,
+    io::SeekFrom,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use futures::{StreamExt, TryStreamExt};
+use tokio::{
+    fs::{File, OpenOptions},
+    io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt},
+    sync::{Mutex, RwLock},
+};
+use zarrs_storage::{
+    async_store_set_partial_values,
+    byte_range::{ByteOffset, ByteRange},
+    AsyncBytes, AsyncListableStorageTraits, AsyncReadableStorageTraits, AsyncStoreValueStream,
+    AsyncWritableStorageTraits, StorageError, StoreKey, StoreKeyError, StoreKeyOffsetValue,
+    StoreKeys, StoreKeysPrefixes, StorePrefix, StorePrefixes,
+};
+
+#[cfg(all(feature = "io_uring", target_os = "linux"))]
+use crate::io_uring::IoUringReader;
+use crate::FilesystemStoreCreateError;
+#[cfg(all(feature = "io_uring", target_os = "linux"))]
+use zarrs_storage::byte_range::InvalidByteRangeError;
+
+/// The size of the chunks of a value stream.
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+/// An asynchronous file system store.
+///
+/// File operations use the [`tokio::fs`] API and must run within a [`tokio`] runtime.
+/// Note that [`tokio::fs`] runs blocking file operations on the blocking thread pool of the runtime.
+///
+/// On Linux with the `io_uring` feature, `AsyncFilesystemStore::with_io_uring` reads values and partial values with `io_uring` instead.
+/// Many reads can then be in flight at once without occupying a blocking thread each.
+/// Other operations still use [`tokio::fs`].
+///
+/// See <https://zarr-specs.readthedocs.io/en/latest/v3/stores/filesystem/v1.0.html>.
+#[derive(Debug)]
+pub struct AsyncFilesystemStore {
+    base_path: PathBuf,
+    sort: bool,
+    readonly: bool,
+    files: Mutex<HashMap<StoreKey, Arc<RwLock<()>>>>,
+    #[cfg(all(feature = "io_uring", target_os = "linux"))]
+    io_uring: Option<IoUringReader>,
+}
+
+impl AsyncFilesystemStore {
+    /// Create a new asynchronous file system store at a given `base_path`.
+    ///
+    /// # Errors
+    /// Returns a [`FilesystemStoreCreateError`] if `base_directory`:
+    ///   - is not valid, or
+    ///   - it points to an existing file rather than a directory.
+    pub fn new<P: AsRef<Path>>(base_path: P) -> Result<Self, FilesystemStoreCreateError> {
+        let base_path = base_path.as_ref().to_path_buf();
+        if base_path.to_str().is_none() {
+            return Err(FilesystemStoreCreateError::InvalidBasePath(base_path));
+        }
+
+        let readonly = if base_path.exists() {
+            // the path already exists, check if it is read only
+            let md = std::fs::metadata(&base_path).map_err(FilesystemStoreCreateError::IOError)?;
+            md.permissions().readonly()
+        } else {
+            // the path does not exist, so try and create it. If this succeeds, the filesystem is not read only
+            std::fs::create_dir_all(&base_path).map_err(FilesystemStoreCreateError::IOError)?;
+            std::fs::remove_dir(&base_path)?;
+            false
+        };
+
+        Ok(Self {
+            base_path,
+            sort: false,
+            readonly,
+            files: Mutex::default(),
+            #[cfg(all(feature = "io_uring", target_os = "linux"))]
+            io_uring: None,
+        })
+    }
+
+    /// Makes the store sort directories/files when listing a directory.
+    #[must_use]
+    pub const fn sorted(mut self) -> Self {
+        self.sort = true;
+        self
+    }
+
+    /// Makes the store read values and partial values with `io_uring`, with a submission queue depth of `entries`.
+    ///
+    /// Reads are submitted by a dedicated driver thread, so many reads can be in flight at once.
+    ///
+    /// # Errors
+    /// Returns a [`FilesystemStoreCreateError`] if `io_uring` is not supported or permitted, in which case the store without `io_uring` can be used instead.
+    #[cfg(all(feature = "io_uring", target_os = "linux"))]
+    pub fn with_io_uring(mut self, entries: u32) -> Result<Self, FilesystemStoreCreateError> {
+        self.io_uring = Some(IoUringReader::new(entries)?);
+        Ok(self)
+    }
+
+    /// Maps a [`StoreKey`] to a filesystem [`PathBuf`].
+    #[must_use]
+    pub fn key_to_fspath(&self, key: &StoreKey) -> PathBuf {
+        let mut path = self.base_path.clone();
+        if !key.as_str().is_empty() {
+            path.push(key.as_str().strip_prefix('/').unwrap_or(key.as_str()));
+        }
+        path
+    }
+
+    /// Maps a filesystem [`PathBuf`] to a [`StoreKey`].
+    fn fspath_to_key(&self, path: &std::path::Path) -> Result<StoreKey, StoreKeyError> {
+        let path = pathdiff::diff_paths(path, &self.base_path)
+            .ok_or_else(|| StoreKeyError::from(path.to_str().unwrap_or_default().to_string()))?;
+        let path_str = path.to_string_lossy();
+        #[cfg(target_os = "windows")]
+        {
+            StoreKey::new(path_str.replace("\\", "/"))
+        }
+        #[cfg(not(target_os = "windows"))]
+        {
+            StoreKey::new(path_str)
+        }
+    }
+
+    /// Maps a store [`StorePrefix`] to a filesystem [`PathBuf`].
+    #[must_use]
+    pub fn prefix_to_fs_path(&self, prefix: &StorePrefix) -> PathBuf {
+        let mut path = self.base_path.clone();
+        path.push(prefix.as_str());
+        path
+    }
+
+    async fn get_file_mutex(&self, key: &StoreKey) -> Arc<RwLock<()>> {
+        let mut files = self.files.lock().await;
+        let file = files
+            .entry(key.clone())
+            .or_insert_with(|| Arc::new(RwLock::default()))
+            .clone();
+        drop(files);
+        file
+    }
+
+    async fn set_impl(
+        &self,
+        key: &StoreKey,
+        value: &[u8],
+        offset: ByteOffset,
+        truncate: bool,
+    ) -> Result<(), StorageError> {
+        let file = self.get_file_mutex(key).await;
+        let _lock = file.write().await;
+
+        // Create directories
+        let key_path = self.key_to_fspath(key);
+        if let Some(parent) = key_path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(truncate)
+            .open(key_path)
+            .await?;
+        file.seek(SeekFrom::Start(offset)).await?;
+        file.write_all(value).await?;
+        file.flush().await?;
+
+        Ok(())
+    }
+
+    /// Recursively list the files under `path`.
+    async fn list_path(&self, path: PathBuf) -> Result<StoreKeys, StorageError> {
+        let mut keys = StoreKeys::new();
+        let mut dirs = vec![path];
+        while let Some(dir) = dirs.pop() {
+            let mut entries = match tokio::fs::read_dir(&dir).await {
+                Ok(entries) => entries,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err.into()),
+            };
+            while let Some(entry) = entries.next_entry().await? {
+                if entry.file_type().await?.is_dir() {
+                    dirs.push(entry.path());
+                } else if let Ok(key) = self.fspath_to_key(&entry.path()) {
+                    keys.push(key);
+                }
+            }
+        }
+        keys.sort();
+        Ok(keys)
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncReadableStorageTraits for AsyncFilesystemStore {
+    /// Return a stream of the file at `key`.
+    ///
+    /// The file is read lazily, and writes to it are blocked until the stream is dropped.
+    async fn get_stream(
+        &self,
+        key: &StoreKey,
+    ) -> Result<Option<AsyncStoreValueStream<'_>>, StorageError> {
+        let lock = self.get_file_mutex(key).await.read_owned().await;
+        let file = match File::open(self.key_to_fspath(key)).await {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let stream = futures::stream::try_unfold((file, lock), |(mut file, lock)| async move {
+            let mut buffer = vec![0; STREAM_CHUNK_SIZE];
+            let length = file.read(&mut buffer).await?;
+            if length == 0 {
+                Ok(None)
+            } else {
+                buffer.truncate(length);
+                Ok(Some((AsyncBytes::from(buffer), (file, lock))))
+            }
+        });
+        Ok(Some(stream.boxed()))
+    }
+
+    async fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<AsyncBytes>>, StorageError> {
+        let file = self.get_file_mutex(key).await;
+        let _lock = file.read().await;
+
+        let mut file = match File::open(self.key_to_fspath(key)).await {
+            Ok(file) => file,
+            Err(err) => {
+                if err.kind() == std::io::ErrorKind::NotFound {
+                    return Ok(None);
+                }
+                return Err(err.into());
+            }
+        };
+
+        #[cfg(all(feature = "io_uring", target_os = "linux"))]
+        if let Some(io_uring) = &self.io_uring {
+            let size = file.metadata().await?.len();
+            let file = Arc::new(file.into_std().await);
+            let reads = byte_ranges.iter().map(|byte_range| {
+                let valid = match byte_range {
+                    ByteRange::FromStart(offset, length) => offset + length.unwrap_or(0) <= size,
+                    ByteRange::Suffix(length) => *length <= size,
+                };
+                let file = file.clone();
+                async move {
+                    if !valid {
+                        return Err(InvalidByteRangeError::new(*byte_range, size).into());
+                    }
+                    let length = usize::try_from(byte_range.length(size)).unwrap();
+                    let bytes = io_uring.read(file, byte_range.start(size), length).await?;
+                    Ok::<_, StorageError>(AsyncBytes::from(bytes))
+                }
+            });
+            return Ok(Some(futures::future::try_join_all(reads).await?));
+        }
+
+        let mut out = Vec::with_capacity(byte_ranges.len());
+        for byte_range in byte_ranges {
+            let bytes = {
+                // Seek
+                match byte_range {
+                    ByteRange::FromStart(offset, _) => file.seek(SeekFrom::Start(*offset)).await,
+                    ByteRange::Suffix(length) => {
+                        file.seek(SeekFrom::End(-(i64::try_from(*length).unwrap())))
+                            .await
+                    }
+                }?;
+
+                // Read
+                match byte_range {
+                    ByteRange::FromStart(_, None) => {
+                        let mut buffer = Vec::new();
+                        file.read_to_end(&mut buffer).await?;
+                        buffer
+                    }
+                    ByteRange::FromStart(_, Some(length)) | ByteRange::Suffix(length) => {
+                        let length = usize::try_from(*length).unwrap();
+                        let mut buffer = vec![0; length];
+                        file.read_exact(&mut buffer).await?;
+                        buffer
+                    }
+                }
+            };
+            out.push(AsyncBytes::from(bytes));
+        }
+
+        Ok(Some(out))
+    }
+
+    async fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        let key_path = self.key_to_fspath(key);
+        tokio::fs::metadata(key_path)
+            .await
+            .map_or_else(|_| Ok(None), |metadata| Ok(Some(metadata.len())))
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncWritableStorageTraits for AsyncFilesystemStore {
+    async fn set(&self, key: &StoreKey, value: AsyncBytes) -> Result<(), StorageError> {
+        if self.readonly {
+            Err(StorageError::ReadOnly)
+        } else {
+            self.set_impl(key, &value, 0, true).await
+        }
+    }
+
+    /// Write a stream to the file at `key`.
+    ///
+    /// The file is truncated and written incrementally, and other reads and writes of it are blocked until the stream is exhausted.
+    async fn set_stream<'a>(
+        &self,
+        key: &StoreKey,
+        mut value: AsyncStoreValueStream<'a>,
+    ) -> Result<(), StorageError> {
+        if self.readonly {
+            return Err(StorageError::ReadOnly);
+        }
+
+        let file = self.get_file_mutex(key).await;
+        let _lock = file.write().await;
+
+        let key_path = self.key_to_fspath(key);
+        if let Some(parent) = key_path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(key_path)
+            .await?;
+        while let Some(bytes) = value.try_next().await? {
+            file.write_all(&bytes).await?;
+        }
+        file.flush().await?;
+
+        Ok(())
+    }
+
+    async fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        if self.readonly {
+            return Err(StorageError::ReadOnly);
+        }
+
+        async_store_set_partial_values(self, key_offset_values).await
+    }
+
+    async fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        if self.readonly {
+            return Err(StorageError::ReadOnly);
+        }
+
+        let file = self.get_file_mutex(key).await;
+        let _lock = file.write().await;
+
+        let key_path = self.key_to_fspath(key);
+        let result = tokio::fs::remove_file(key_path).await;
+        if let Err(err) = result {
+            match err.kind() {
+                std::io::ErrorKind::NotFound => Ok(()),
+                _ => Err(err.into()),
+            }
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        if self.readonly {
+            return Err(StorageError::ReadOnly);
+        }
+
+        let _lock = self.files.lock().await; // lock all operations
+
+        let prefix_path = self.prefix_to_fs_path(prefix);
+        let result = tokio::fs::remove_dir_all(prefix_path).await;
+        if let Err(err) = result {
+            match err.kind() {
+                std::io::ErrorKind::NotFound => Ok(()),
+                _ => Err(err.into()),
+            }
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncListableStorageTraits for AsyncFilesystemStore {
+    async fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.list_path(self.base_path.clone()).await
+    }
+
+    async fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.list_path(self.prefix_to_fs_path(prefix)).await
+    }
+
+    async fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        let prefix_path = self.prefix_to_fs_path(prefix);
+        let mut keys: StoreKeys = vec![];
+        let mut prefixes: StorePrefixes = vec![];
+        if let Ok(mut dir) = tokio::fs::read_dir(prefix_path).await {
+            while let Some(entry) = dir.next_entry().await? {
+                let fs_path = entry.path();
+                let path = fs_path.file_name().unwrap();
+                if entry.file_type().await?.is_dir() {
+                    prefixes.push(StorePrefix::new(
+                        prefix.as_str().to_string() + path.to_str().unwrap() + "/",
+                    )?);
+                } else {
+                    keys.push(StoreKey::new(
+                        prefix.as_str().to_owned() + path.to_str().unwrap(),
+                    )?);
+                }
+            }
+        }
+        if self.sort {
+            keys.sort();
+            prefixes.sort();
+        }
+
+        Ok(StoreKeysPrefixes::new(keys, prefixes))
+    }
+
+    async fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        let mut size = 0;
+        for key in self.list_prefix(prefix).await? {
+            if let Some(size_key) = self.size_key(&key).await? {
+                size += size_key;
+            }
+        }
+        Ok(size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore)]
+    async fn async_filesystem() -> Result<(), Box<dyn Error>> {
+        let path = tempfile::TempDir::new()?;
+        let store = AsyncFilesystemStore::new(path.path())?.sorted();
+        zarrs_storage::store_test::async_store_write(&store).await?;
+        zarrs_storage::store_test::async_store_read(&store).await?;
+        zarrs_storage::store_test::async_store_list(&store).await?;
+        Ok(())
+    }
+
+    #[cfg(all(feature = "io_uring", target_os = "linux"))]
+    #[tokio::test]
+    #[cfg_attr(miri, ignore)]
+    async fn async_filesystem_io_uring() -> Result<(), Box<dyn Error>> {
+        let path = tempfile::TempDir::new()?;
+        let store = AsyncFilesystemStore::new(path.path())?
+            .sorted()
+            .with_io_uring(8)?;
+        zarrs_storage::store_test::async_store_write(&store).await?;
+        zarrs_storage::store_test::async_store_read(&store).await?;
+        zarrs_storage::store_test::async_store_list(&store).await?;
+
+        // More concurrent reads than the submission queue depth
+        let key = StoreKey::new("large")?;
+        let value: Vec<u8> = (0..1_000_000u32).map(|i| (i % 251) as u8).collect();
+        store.set(&key, value.clone().into()).await?;
+        let byte_ranges: Vec<_> = (0..100)
+            .map(|i| ByteRange::FromStart(i * 9_000, Some(10_000)))
+            .chain([ByteRange::Suffix(123), ByteRange::FromStart(999_000, None)])
+            .collect();
+        let values = store
+            .get_partial_values_key(&key, &byte_ranges)
+            .await?
+            .unwrap();
+        for (byte_range, bytes) in byte_ranges.iter().zip(values) {
+            assert_eq!(bytes, value[byte_range.to_range_usize(1_000_000)]);
+        }
+        assert_eq!(store.get(&key).await?.unwrap(), value);
+        assert!(store
+            .get_partial_values_key(&key, &[ByteRange::FromStart(999_999, Some(2))])
+            .await
+            .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use std::{
+    fs::OpenOptions,
+    os::unix::io::AsRawFd,
+    path::{Path, PathBuf},
+};
+
+use zarrs_storage::{
+    store_lock::{StoreKeyLockGuard, StoreLocks},
+    StorageError, StoreKey,
+};
+
+/// File store key locks.
+///
+/// Coordinates the processes of a single host with advisory file locks (`flock`).
+/// The lock of a store key is held on the lock file `{key}.lock` in the lock directory, which is created if it does not exist.
+/// Lock files are not removed when unlocked.
+///
+/// Advisory locks are only respected by processes that lock the same lock files, and may not be supported by network filesystems.
+/// The lock directory should not be inside a store, otherwise lock files are listed as store keys.
+#[derive(Debug, Clone)]
+pub struct FileStoreLocks {
+    directory: PathBuf,
+}
+
+impl FileStoreLocks {
+    /// Create new file store locks with lock files in `directory`.
+    #[must_use]
+    pub fn new(directory: impl AsRef<Path>) -> Self {
+        Self {
+            directory: directory.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Return the lock directory.
+    #[must_use]
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    fn lock_path(&self, key: &StoreKey) -> PathBuf {
+        self.directory.join(format!("{}.lock", key.as_str()))
+    }
+}
+
+impl StoreLocks for FileStoreLocks {
+    fn lock(&self, key: &StoreKey) -> Result<StoreKeyLockGuard<'_>, StorageError> {
+        let path = self.lock_path(key);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        loop {
+            // SAFETY: the file descriptor is valid for the lifetime of `file`
+            if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } == 0 {
+                break;
+            }
+            let err = std::io::Error::last_os_error();
+            if err.kind() != std::io::ErrorKind::Interrupted {
+                return Err(err.into());
+            }
+        }
+        // The lock is released when the file is closed
+        Ok(StoreKeyLockGuard::new(file))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use zarrs_storage::{
+        storage_adapter::locking::LockingStorageAdapter, ReadableStorageTraits,
+        WritableStorageTraits,
+    };
+
+    use crate::FilesystemStore;
+
+    use super::*;
+
+    #[test]
+    fn file_store_locks() -> Result<(), Box<dyn std::error::Error>> {
+        let path = tempfile::TempDir::new()?;
+        let locks_path = tempfile::TempDir::new()?;
+        let store = Arc::new(FilesystemStore::new(path.path())?);
+        let key = StoreKey::new("c/0")?;
+        store.set(&key, vec![0].into())?;
+
+        // Separate lock instances share the lock files, as would separate processes
+        let updates = AtomicUsize::new(0);
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                let store = LockingStorageAdapter::new(
+                    store.clone(),
+                    Arc::new(FileStoreLocks::new(locks_path.path())),
+                );
+                let (key, updates) = (&key, &updates);
+                scope.spawn(move || {
+                    for _ in 0..10 {
+                        let lock = store.lock_key(key).unwrap();
+                        assert!(lock.is_locked());
+                        let value = store.get(key).unwrap().unwrap();
+                        std::thread::yield_now();
+                        store.set(key, vec![value[0] + 1].into()).unwrap();
+                        updates.fetch_add(1, Ordering::Relaxed);
+                    }
+                });
+            }
+        });
+        assert_eq!(updates.load(Ordering::Relaxed), 40);
+        assert_eq!(store.get(&key)?.unwrap(), vec![40]);
+        assert!(locks_path.path().join("c/0.lock").is_file());
+        Ok(())
+    }
+}
@@ -0,0 +1,341 @@
+//! An [`io_uring`](https://man7.org/linux/man-pages/man7/io_uring.7.html) file reader for the [`AsyncFilesystemStore`](crate::AsyncFilesystemStore).
+//!
+//! A single driver thread owns the ring.
+//! Reads are sent to it over a channel, submitted in batches of up to the submission queue depth, and their results are returned through [`oneshot`] channels.
+//! The driver only blocks in the kernel, waiting for completions, so many reads can be in flight without occupying a thread each.
+
+use std::{
+    collections::VecDeque,
+    ffi::c_void,
+    fs::File,
+    io,
+    mem::size_of,
+    os::fd::{AsRawFd, OwnedFd},
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        mpsc, Arc,
+    },
+};
+
+use rustix::{
+    io::Errno,
+    io_uring::{
+        io_uring_cqe, io_uring_enter, io_uring_params, io_uring_ptr, io_uring_setup, io_uring_sqe,
+        io_uring_user_data, IoringEnterFlags, IoringOp, IORING_OFF_CQ_RING, IORING_OFF_SQES,
+        IORING_OFF_SQ_RING,
+    },
+    mm::{mmap, munmap, MapFlags, ProtFlags},
+};
+use tokio::sync::oneshot;
+
+/// A read of a file that has been requested but not yet completed.
+struct Read {
+    file: Arc<File>,
+    offset: u64,
+    buffer: Vec<u8>,
+    filled: usize,
+    sender: oneshot::Sender<io::Result<Vec<u8>>>,
+}
+
+impl Read {
+    /// Create a submission queue entry reading the unfilled part of the buffer.
+    fn sqe(&mut self, user_data: u64) -> io_uring_sqe {
+        let remaining = &mut self.buffer[self.filled..];
+        let mut sqe = io_uring_sqe {
+            opcode: IoringOp::Read,
+            fd: self.file.as_raw_fd(),
+            user_data: io_uring_user_data::from_u64(user_data),
+            ..Default::default()
+        };
+        sqe.off_or_addr2.off = self.offset + self.filled as u64;
+        sqe.addr_or_splice_off_in.addr = io_uring_ptr::new(remaining.as_mut_ptr().cast());
+        sqe.len.len = u32::try_from(remaining.len()).unwrap_or(u32::MAX);
+        sqe
+    }
+}
+
+/// A memory mapped region of an `io_uring` instance.
+struct Mmap {
+    ptr: *mut c_void,
+    len: usize,
+}
+
+impl Mmap {
+    fn new(fd: &OwnedFd, len: usize, offset: u64) -> io::Result<Self> {
+        // SAFETY: A new shared mapping of the ring is created, it does not alias any existing memory.
+        let ptr = unsafe {
+            mmap(
+                std::ptr::null_mut(),
+                len,
+                ProtFlags::READ | ProtFlags::WRITE,
+                MapFlags::SHARED | MapFlags::POPULATE,
+                fd,
+                offset,
+            )
+        }?;
+        Ok(Self { ptr, len })
+    }
+
+    /// Return a pointer to the `T` at a byte `offset` of the mapping.
+    fn at<T>(&self, offset: usize) -> *mut T {
+        debug_assert!(offset + size_of::<T>() <= self.len);
+        // SAFETY: The offset is within the mapping.
+        unsafe { self.ptr.cast::<u8>().add(offset).cast() }
+    }
+
+    /// Return the ring value at a byte `offset` of the mapping.
+    fn atomic(&self, offset: u32) -> &AtomicU32 {
+        // SAFETY: The kernel places aligned `u32` ring values at the offsets it reports, and they live as long as the mapping.
+        unsafe { &*self.at::<AtomicU32>(offset as usize) }
+    }
+}
+
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        // SAFETY: The mapping was created by `mmap` with this length and is not referenced after it is dropped.
+        let _ = unsafe { munmap(self.ptr, self.len) };
+    }
+}
+
+/// The submission and completion queues of an `io_uring` instance.
+struct Ring {
+    params: io_uring_params,
+    sq: Mmap,
+    cq: Mmap,
+    sqes: Mmap,
+    fd: OwnedFd,
+}
+
+// SAFETY: The ring is only accessed by the thread that owns it.
+unsafe impl Send for Ring {}
+
+impl Ring {
+    fn new(entries: u32) -> io::Result<Self> {
+        let mut params = io_uring_params::default();
+        // SAFETY: `params` is a valid, zeroed `io_uring_params`.
+        let fd = unsafe { io_uring_setup(entries, &mut params) }?;
+        let sq_entries = params.sq_entries as usize;
+        let cq_entries = params.cq_entries as usize;
+        let sq = Mmap::new(
+            &fd,
+            params.sq_off.array as usize + sq_entries * size_of::<u32>(),
+            IORING_OFF_SQ_RING,
+        )?;
+        let cq = Mmap::new(
+            &fd,
+            params.cq_off.cqes as usize + cq_entries * size_of::<io_uring_cqe>(),
+            IORING_OFF_CQ_RING,
+        )?;
+        let sqes = Mmap::new(&fd, sq_entries * size_of::<io_uring_sqe>(), IORING_OFF_SQES)?;
+        Ok(Self {
+            params,
+            sq,
+            cq,
+            sqes,
+            fd,
+        })
+    }
+
+    /// The number of entries in the submission queue.
+    fn sq_entries(&self) -> u32 {
+        self.params.sq_entries
+    }
+
+    /// Push an entry onto the submission queue.
+    ///
+    /// The caller must not push more entries than the submission queue depth before they are submitted with [`Ring::enter`].
+    fn push(&mut self, sqe: io_uring_sqe) {
+        let tail = self.sq.atomic(self.params.sq_off.tail);
+        let mask = self
+            .sq
+            .atomic(self.params.sq_off.ring_mask)
+            .load(Ordering::Relaxed);
+        let position = tail.load(Ordering::Relaxed);
+        let index = position & mask;
+        // SAFETY: `index` is within the submission queue, and the entry at it is not in use by the kernel because at most `sq_entries` entries are pushed before they are submitted.
+        unsafe {
+            self.sqes
+                .at::<io_uring_sqe>(index as usize * size_of::<io_uring_sqe>())
+                .write(sqe);
+            self.sq
+                .at::<u32>(self.params.sq_off.array as usize + index as usize * size_of::<u32>())
+                .write(index);
+        }
+        tail.store(position.wrapping_add(1), Ordering::Release);
+    }
+
+    /// Submit `to_submit` entries and wait for at least `min_complete` completions.
+    ///
+    /// Returns the number of submitted entries.
+    fn enter(&self, to_submit: u32, min_complete: u32) -> io::Result<u32> {
+        loop {
+            // SAFETY: The submitted entries reference buffers and files that are kept alive until they complete.
+            match unsafe {
+                io_uring_enter(
+                    &self.fd,
+                    to_submit,
+                    min_complete,
+                    IoringEnterFlags::GETEVENTS,
+                )
+            } {
+                Err(Errno::INTR) => {}
+                result => return Ok(result?),
+            }
+        }
+    }
+
+    /// Pop the `user_data` and result of an entry from the completion queue.
+    fn pop(&mut self) -> Option<(u64, i32)> {
+        let head = self.cq.atomic(self.params.cq_off.head);
+        let position = head.load(Ordering::Relaxed);
+        if position
+            == self
+                .cq
+                .atomic(self.params.cq_off.tail)
+                .load(Ordering::Acquire)
+        {
+            return None;
+        }
+        let mask = self
+            .cq
+            .atomic(self.params.cq_off.ring_mask)
+            .load(Ordering::Relaxed);
+        let index = (position & mask) as usize;
+        // SAFETY: The entry at `index` has been completed by the kernel and is not reused until the head is advanced.
+        let cqe = unsafe {
+            self.cq
+                .at::<io_uring_cqe>(
+                    self.params.cq_off.cqes as usize + index * size_of::<io_uring_cqe>(),
+                )
+                .read()
+        };
+        head.store(position.wrapping_add(1), Ordering::Release);
+        Some((cqe.user_data.u64_(), cqe.res))
+    }
+}
+
+/// The error returned if the driver thread is no longer running.
+fn stopped() -> io::Error {
+    io::Error::new(io::ErrorKind::BrokenPipe, "the io_uring driver has stopped")
+}
+
+/// Submit requested reads and complete them until all senders have been dropped.
+fn drive(mut ring: Ring, receiver: &mpsc::Receiver<Read>) {
+    let depth = ring.sq_entries() as usize;
+    let mut in_flight: Vec<Option<Read>> = (0..depth).map(|_| None).collect();
+    let mut in_flight_count = 0;
+    let mut queued = VecDeque::new();
+    let mut unsubmitted = 0;
+    loop {
+        // Only block on the channel if there is nothing else to do
+        if in_flight_count == 0 && queued.is_empty() {
+            match receiver.recv() {
+                Ok(read) => queued.push_back(read),
+                Err(mpsc::RecvError) => return,
+            }
+        }
+        queued.extend(receiver.try_iter());
+
+        while in_flight_count < depth {
+            let Some(mut read) = queued.pop_front() else {
+                break;
+            };
+            let slot = in_flight.iter().position(Option::is_none).unwrap();
+            ring.push(read.sqe(slot as u64));
+            in_flight[slot] = Some(read);
+            in_flight_count += 1;
+            unsubmitted += 1;
+        }
+
+        match ring.enter(unsubmitted, 1) {
+            Ok(submitted) => unsubmitted -= submitted,
+            Err(err) if matches!(err.raw_os_error(), Some(libc::EAGAIN | libc::EBUSY)) => {}
+            Err(err) => {
+                // The kernel may still reference the buffers of in flight reads, so they are leaked
+                for read in in_flight.into_iter().flatten() {
+                    let Read { buffer, sender, .. } = read;
+                    std::mem::forget(buffer);
+                    let _ = sender.send(Err(io::Error::new(err.kind(), err.to_string())));
+                }
+                for read in queued {
+                    let _ = read
+                        .sender
+                        .send(Err(io::Error::new(err.kind(), err.to_string())));
+                }
+                return;
+            }
+        }
+
+        while let Some((user_data, res)) = ring.pop() {
+            let slot = usize::try_from(user_data).unwrap();
+            let mut read = in_flight[slot].take().unwrap();
+            in_flight_count -= 1;
+            match res.cmp(&0) {
+                std::cmp::Ordering::Less => {
+                    let _ = read.sender.send(Err(io::Error::from_raw_os_error(-res)));
+                }
+                std::cmp::Ordering::Equal => {
+                    let _ = read
+                        .sender
+                        .send(Err(io::Error::from(io::ErrorKind::UnexpectedEof)));
+                }
+                std::cmp::Ordering::Greater => {
+                    read.filled += usize::try_from(res).unwrap();
+                    if read.filled < read.buffer.len() {
+                        // Short read, read the remainder
+                        queued.push_front(read);
+                    } else {
+                        let _ = read.sender.send(Ok(read.buffer));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Reads files with `io_uring`.
+#[derive(Debug)]
+pub(crate) struct IoUringReader {
+    sender: mpsc::Sender<Read>,
+}
+
+impl IoUringReader {
+    /// Create a new `io_uring` instance with a submission queue depth of `entries` and spawn its driver thread.
+    ///
+    /// # Errors
+    /// Returns an [`io::Error`] if `io_uring` is not supported or permitted, or the driver thread cannot be spawned.
+    pub(crate) fn new(entries: u32) -> io::Result<Self> {
+        let ring = Ring::new(entries)?;
+        let (sender, receiver) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("zarrs_io_uring".to_string())
+            .spawn(move || drive(ring, &receiver))?;
+        Ok(Self { sender })
+    }
+
+    /// Read `length` bytes of `file` at `offset`.
+    ///
+    /// # Errors
+    /// Returns an [`io::Error`] if the read fails or extends beyond the end of the file.
+    pub(crate) async fn read(
+        &self,
+        file: Arc<File>,
+        offset: u64,
+        length: usize,
+    ) -> io::Result<Vec<u8>> {
+        if length == 0 {
+            return Ok(Vec::new());
+        }
+        let (sender, receiver) = oneshot::channel();
+        self.sender
+            .send(Read {
+                file,
+                offset,
+                buffer: vec![0; length],
+                filled: 0,
+                sender,
+            })
+            .map_err(|_| stopped())?;
+        receiver.await.map_err(|_| stopped())?
+    }
+}
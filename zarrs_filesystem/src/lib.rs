@@ -2,6 +2,11 @@
 //!
 //! This implementation is conformant with the filesystem store defined in the Zarr V3 specification: <https://zarr-specs.readthedocs.io/en/latest/v3/stores/filesystem/v1.0.html>.
 //!
+//! The synchronous [`FilesystemStore`] is always available.
+//! The asynchronous [`AsyncFilesystemStore`] built on [`tokio::fs`](https://docs.rs/tokio/latest/tokio/fs/index.html) is enabled by the `async` feature.
+//! On Linux, the `io_uring` feature enables reading files with `io_uring` in the [`AsyncFilesystemStore`] with `AsyncFilesystemStore::with_io_uring`.
+//! On unix, [`FileStoreLocks`] provide store key locks for coordinating writes from multiple processes.
+//!
 //! ## Licence
 //! `zarrs_filesystem` is licensed under either of
 //! - the Apache License, Version 2.0 [LICENSE-APACHE](https://docs.rs/crate/zarrs_filesystem/latest/source/LICENCE-APACHE) or <http://www.apache.org/licenses/LICENSE-2.0> or
@@ -9,24 +14,48 @@
 
 use zarrs_storage::{
     byte_range::{ByteOffset, ByteRange},
-    store_set_partial_values, Bytes, ListableStorageTraits, ReadableStorageTraits, StorageError,
-    StoreKey, StoreKeyError, StoreKeyOffsetValue, StoreKeys, StoreKeysPrefixes, StorePrefix,
-    StorePrefixes, WritableStorageTraits,
+    store_set_partial_values, Bytes, ListableStorageTraits, MaybeBytes, ReadableStorageTraits,
+    StorageError, StoreKey, StoreKeyError, StoreKeyOffsetValue, StoreKeys, StoreKeysPrefixes,
+    StorePrefix, StorePrefixes, StoreValueReader, StoreValueValidator, StoreValueWriter,
+    WritableStorageTraits,
 };
 
-use bytes::BytesMut;
-use parking_lot::RwLock;
+use itertools::Itertools;
+use parking_lot::{
+    lock_api::{ArcRwLockReadGuard, ArcRwLockWriteGuard},
+    RawRwLock, RwLock,
+};
 use thiserror::Error;
 use walkdir::WalkDir;
 
 use std::{
     collections::HashMap,
     fs::{File, OpenOptions},
-    io::{Read, Seek, SeekFrom, Write},
+    io::{BufWriter, Read, Seek, SeekFrom, Write},
+    num::NonZeroUsize,
     path::{Path, PathBuf},
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::UNIX_EPOCH,
 };
 
+#[cfg(unix)]
+mod file_store_locks;
+#[cfg(unix)]
+pub use file_store_locks::FileStoreLocks;
+
+#[cfg(feature = "async")]
+mod async_filesystem_store;
+#[cfg(feature = "async")]
+pub use async_filesystem_store::AsyncFilesystemStore;
+
+#[cfg(all(feature = "io_uring", target_os = "linux"))]
+mod io_uring;
+
+#[cfg(target_os = "linux")]
+use bytes::BytesMut;
 #[cfg(target_os = "linux")]
 use libc::O_DIRECT;
 #[cfg(target_os = "linux")]
@@ -53,8 +82,13 @@ use std::os::unix::fs::OpenOptionsExt;
 //     FilesystemStore::new(path).map_err(|e| StorePluginCreateError::Other(e.to_string()))
 // }
 
+/// The maximum size of the aligned buffer used to stage unaligned data for direct I/O writes (4 MiB).
+#[cfg(target_os = "linux")]
+const DIRECT_IO_BUFFER_SIZE: usize = 4 * 1024 * 1024;
+
 /// For `O_DIRECT`, we need a buffer that is aligned to the page size and is a
 /// multiple of the page size.
+#[cfg(target_os = "linux")]
 fn bytes_aligned(size: usize) -> BytesMut {
     let align = page_size::get();
     let mut bytes = BytesMut::with_capacity(size + 2 * align);
@@ -62,20 +96,138 @@ fn bytes_aligned(size: usize) -> BytesMut {
     bytes.split_off(offset)
 }
 
+/// Write `value` to a `file` opened for direct I/O.
+///
+/// Page-aligned data is written directly from `value`.
+/// The remainder is staged through a bounded page-aligned buffer, padded to a multiple of the page size, and the file is then truncated to the length of `value`.
+#[cfg(target_os = "linux")]
+fn write_direct(file: &mut File, value: &[u8]) -> Result<(), StorageError> {
+    let page_size = page_size::get();
+    let (aligned, unaligned) = if value.as_ptr().align_offset(page_size) == 0 {
+        value.split_at(value.len() - value.len() % page_size)
+    } else {
+        value.split_at(0)
+    };
+    file.write_all(aligned)?;
+
+    if !unaligned.is_empty() {
+        let buffer_size = usize::min(
+            unaligned.len().next_multiple_of(page_size),
+            DIRECT_IO_BUFFER_SIZE.next_multiple_of(page_size),
+        );
+        let mut buf = bytes_aligned(buffer_size);
+        for chunk in unaligned.chunks(buffer_size) {
+            buf.clear();
+            buf.extend_from_slice(chunk);
+
+            // Pad to page size
+            let pad_size = buf.len().next_multiple_of(page_size) - buf.len();
+            buf.extend(std::iter::repeat(0).take(pad_size));
+
+            file.write_all(&buf)?;
+        }
+    }
+
+    // Truncate again to requested size
+    file.set_len(value.len() as u64)?;
+    Ok(())
+}
+
+/// The file name suffix of the temporary files of atomic writes.
+///
+/// Files with this suffix are not listed, so temporary files left behind by a crash are not mistaken for keys.
+const TEMP_FILE_SUFFIX: &str = ".zarrs-tmp";
+
+/// Returns true if `path` is a temporary file of an atomic write.
+fn is_temp_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|file_name| file_name.to_str())
+        .is_some_and(|file_name| file_name.ends_with(TEMP_FILE_SUFFIX))
+}
+
+/// Returns a unique temporary path for an atomic write to `key_path`.
+///
+/// The temporary file is in the same directory as `key_path`, so that it can be renamed atomically.
+fn temp_path(key_path: &Path) -> PathBuf {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let file_name = key_path.file_name().unwrap_or_default().to_string_lossy();
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+    key_path.with_file_name(format!(
+        ".{file_name}.{}.{count}{TEMP_FILE_SUFFIX}",
+        std::process::id()
+    ))
+}
+
+/// Returns the entity tag of a file, derived from its modification time, length, and (on unix) inode number.
+///
+/// The inode number changes when a file is replaced by a rename, which distinguishes writes within the resolution of the modification time.
+fn file_etag(metadata: &std::fs::Metadata) -> StoreValueValidator {
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |modified| modified.as_nanos());
+    #[cfg(unix)]
+    let etag = {
+        use std::os::unix::fs::MetadataExt;
+        format!("{modified:x}-{:x}-{:x}", metadata.len(), metadata.ino())
+    };
+    #[cfg(not(unix))]
+    let etag = format!("{modified:x}-{:x}", metadata.len());
+    StoreValueValidator::EntityTag(etag)
+}
+
+/// Synchronise the parent directory of `path`, so that a file created or renamed in it is durable.
+fn sync_parent_dir(path: &Path) -> Result<(), StorageError> {
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        File::open(parent)?.sync_all()?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
 /// Options for use with [`FilesystemStore`]
 #[non_exhaustive]
 #[derive(Debug, Clone, Default)]
 pub struct FilesystemStoreOptions {
     direct_io: bool,
+    atomic_writes: bool,
+    fsync: bool,
 }
 
 impl FilesystemStoreOptions {
     /// Set whether or not to enable direct I/O. Needs support from the
     /// operating system (currently only Linux) and file system.
+    ///
+    /// Direct I/O (`O_DIRECT`) bypasses the page cache when writing complete values, which can improve the throughput of large sequential writes to fast storage devices.
+    /// Partial writes, and writes on file systems that do not support direct I/O, fall back to buffered I/O.
     pub fn direct_io(&mut self, direct_io: bool) -> &mut Self {
         self.direct_io = direct_io;
         self
     }
+
+    /// Set whether or not to write values atomically.
+    ///
+    /// Defaults to false.
+    /// If enabled, a value is written to a temporary file in the same directory which is then renamed to the file of the key.
+    /// Other processes, and readers after a crash, observe either the previous or the new value and never a partially written value.
+    /// Temporary files use the suffix `.zarrs-tmp` and are excluded from listing.
+    pub fn atomic_writes(&mut self, atomic_writes: bool) -> &mut Self {
+        self.atomic_writes = atomic_writes;
+        self
+    }
+
+    /// Set whether or not to synchronise written values to the storage device.
+    ///
+    /// Defaults to false.
+    /// If enabled, a write calls `fsync` on the file and its parent directory before returning, so the value is durable once the write succeeds.
+    /// This makes writes substantially slower on most file systems.
+    pub fn fsync(&mut self, fsync: bool) -> &mut Self {
+        self.fsync = fsync;
+        self
+    }
 }
 
 /// A synchronous file system store.
@@ -187,6 +339,16 @@ impl FilesystemStore {
         file
     }
 
+    /// Create the parent directories of the file at `key_path`.
+    fn create_parent_dirs(key_path: &Path) -> Result<(), StorageError> {
+        if let Some(parent) = key_path.parent() {
+            if !parent.exists() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        Ok(())
+    }
+
     fn set_impl(
         &self,
         key: &StoreKey,
@@ -199,57 +361,200 @@ impl FilesystemStore {
 
         // Create directories
         let key_path = self.key_to_fspath(key);
-        if let Some(parent) = key_path.parent() {
-            if !parent.exists() {
-                std::fs::create_dir_all(parent)?;
+        Self::create_parent_dirs(&key_path)?;
+
+        if self.options.atomic_writes && truncate && offset == 0 {
+            let temp_path = temp_path(&key_path);
+            let written = self.write_file(&temp_path, value, offset, truncate);
+            match written.and_then(|()| self.rename_temp_file(&temp_path, &key_path)) {
+                Ok(()) => Ok(()),
+                Err(err) => {
+                    let _ = std::fs::remove_file(&temp_path);
+                    Err(err)
+                }
             }
+        } else {
+            self.write_file(&key_path, value, offset, truncate)?;
+            if self.options.fsync {
+                sync_parent_dir(&key_path)?;
+            }
+            Ok(())
         }
+    }
 
+    /// Write `value` at `offset` to the file at `path`, synchronising it if [`fsync`](FilesystemStoreOptions::fsync) is enabled.
+    fn write_file(
+        &self,
+        path: &Path,
+        value: &[u8],
+        offset: ByteOffset,
+        truncate: bool,
+    ) -> Result<(), StorageError> {
         let mut flags = OpenOptions::new();
         flags.write(true).create(true).truncate(truncate);
 
         // TODO: for now, only Linux support; also no support for `offset != 0`
-        let enable_direct =
-            cfg!(target_os = "linux") && self.options.direct_io && offset == 0 && !value.is_empty();
+        #[cfg(target_os = "linux")]
+        if self.options.direct_io && offset == 0 && !value.is_empty() {
+            let mut direct_flags = flags.clone();
+            direct_flags.custom_flags(O_DIRECT);
+            match direct_flags.open(path) {
+                Ok(mut file) => {
+                    write_direct(&mut file, value)?;
+                    return self.sync_file(&file);
+                }
+                Err(err) if err.raw_os_error() == Some(libc::EINVAL) => {
+                    // The file system does not support direct I/O, fall back to buffered I/O
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
 
-        // If `value` is already page-size aligned, we don't need to copy.
-        let need_copy = value.as_ptr().align_offset(page_size::get()) != 0
-            || value.len() % page_size::get() != 0;
+        let mut file = flags.open(path)?;
+        file.seek(SeekFrom::Start(offset))?;
+        file.write_all(value)?;
+        self.sync_file(&file)
+    }
 
-        #[cfg(target_os = "linux")]
-        if enable_direct {
-            flags.custom_flags(O_DIRECT);
+    /// Synchronise `file` if [`fsync`](FilesystemStoreOptions::fsync) is enabled.
+    fn sync_file(&self, file: &File) -> Result<(), StorageError> {
+        if self.options.fsync {
+            file.sync_all()?;
         }
+        Ok(())
+    }
 
-        let mut file = flags.open(key_path)?;
+    /// Rename the temporary file of an atomic write at `temp_path` to `key_path`.
+    fn rename_temp_file(&self, temp_path: &Path, key_path: &Path) -> Result<(), StorageError> {
+        std::fs::rename(temp_path, key_path)?;
+        if self.options.fsync {
+            sync_parent_dir(key_path)?;
+        }
+        Ok(())
+    }
+}
 
-        // Write
-        if enable_direct {
-            if need_copy {
-                let mut buf = bytes_aligned(value.len());
-                buf.extend_from_slice(value);
+/// A [`StoreValueReader`] of a file that holds a read lock on the file.
+struct FilesystemValueReader {
+    file: File,
+    _lock: ArcRwLockReadGuard<RawRwLock, ()>,
+}
 
-                // Pad to page size
-                let pad_size = buf.len().next_multiple_of(page_size::get()) - buf.len();
-                buf.extend(std::iter::repeat(0).take(pad_size));
+impl Read for FilesystemValueReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.file.read(buf)
+    }
+}
 
-                file.write_all(&buf)?;
-            } else {
-                file.write_all(value)?;
-            }
+/// A [`StoreValueWriter`] of a file that holds a write lock on the file until finished.
+///
+/// With atomic writes, the value is written to a temporary file which is renamed to the file of the key when finished, or removed if the writer is dropped.
+struct FilesystemValueWriter<'a> {
+    store: &'a FilesystemStore,
+    file: BufWriter<File>,
+    key_path: PathBuf,
+    temp_path: Option<PathBuf>,
+    _lock: ArcRwLockWriteGuard<RawRwLock, ()>,
+}
 
-            // Truncate again to requested size
-            file.set_len(value.len() as u64)?;
-        } else {
-            file.seek(SeekFrom::Start(offset))?;
-            file.write_all(value)?;
+impl Drop for FilesystemValueWriter<'_> {
+    fn drop(&mut self) {
+        if let Some(temp_path) = self.temp_path.take() {
+            let _ = std::fs::remove_file(temp_path);
         }
+    }
+}
+
+impl Write for FilesystemValueWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.file.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.file.flush()
+    }
+}
 
+impl StoreValueWriter for FilesystemValueWriter<'_> {
+    fn finish(mut self: Box<Self>) -> Result<(), StorageError> {
+        self.file.flush()?;
+        self.store.sync_file(self.file.get_ref())?;
+        if let Some(temp_path) = &self.temp_path {
+            self.store.rename_temp_file(temp_path, &self.key_path)?;
+            self.temp_path = None;
+        } else if self.store.options.fsync {
+            sync_parent_dir(&self.key_path)?;
+        }
         Ok(())
     }
 }
 
 impl ReadableStorageTraits for FilesystemStore {
+    /// Return the values of the files at `keys`.
+    ///
+    /// The files are split into contiguous groups which are read concurrently on up to [`available_parallelism`](std::thread::available_parallelism) threads.
+    fn get_many(&self, keys: &[StoreKey]) -> Result<Vec<MaybeBytes>, StorageError> {
+        let concurrency = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        if keys.len() <= 1 || concurrency == 1 {
+            return keys.iter().map(|key| self.get(key)).collect();
+        }
+        let group_size = keys.len().div_ceil(concurrency);
+        std::thread::scope(|scope| {
+            let groups: Vec<_> = keys
+                .chunks(group_size)
+                .map(|keys| {
+                    scope.spawn(move || {
+                        keys.iter()
+                            .map(|key| self.get(key))
+                            .collect::<Result<Vec<_>, _>>()
+                    })
+                })
+                .collect();
+            groups
+                .into_iter()
+                .map(|group| {
+                    group
+                        .join()
+                        .unwrap_or_else(|err| std::panic::resume_unwind(err))
+                })
+                .flatten_ok()
+                .collect()
+        })
+    }
+
+    /// Return a reader of the file at `key`.
+    ///
+    /// The file is read lazily, and writes to it are blocked until the reader is dropped.
+    fn get_reader(&self, key: &StoreKey) -> Result<Option<StoreValueReader<'_>>, StorageError> {
+        let lock = self.get_file_mutex(key).read_arc();
+        match File::open(self.key_to_fspath(key)) {
+            Ok(file) => Ok(Some(Box::new(FilesystemValueReader { file, _lock: lock }))),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Return the value of the file at `key` and its entity tag.
+    ///
+    /// The entity tag is derived from the modification time, length, and (on unix) inode number of the file.
+    fn get_with_etag(
+        &self,
+        key: &StoreKey,
+    ) -> Result<(MaybeBytes, Option<StoreValueValidator>), StorageError> {
+        let file = self.get_file_mutex(key);
+        let _lock = file.read();
+        let mut file = match File::open(self.key_to_fspath(key)) {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok((None, None)),
+            Err(err) => return Err(err.into()),
+        };
+        // The entity tag is retrieved first, so it cannot match if the file is modified while it is read
+        let etag = file_etag(&file.metadata()?);
+        let mut value = Vec::new();
+        file.read_to_end(&mut value)?;
+        Ok((Some(value.into()), Some(etag)))
+    }
+
     fn get_partial_values_key(
         &self,
         key: &StoreKey,
@@ -315,6 +620,83 @@ impl WritableStorageTraits for FilesystemStore {
         }
     }
 
+    /// Return a writer of the file at `key`.
+    ///
+    /// The file is truncated and written incrementally, and other reads and writes of it are blocked until the writer is finished or dropped.
+    /// With [atomic writes](FilesystemStoreOptions::atomic_writes), a temporary file is written and renamed when the writer is finished.
+    /// Direct I/O is not used.
+    fn set_writer(&self, key: &StoreKey) -> Result<Box<dyn StoreValueWriter + '_>, StorageError> {
+        if self.readonly {
+            return Err(StorageError::ReadOnly);
+        }
+
+        let lock = self.get_file_mutex(key).write_arc();
+        let key_path = self.key_to_fspath(key);
+        Self::create_parent_dirs(&key_path)?;
+        let temp_path = self.options.atomic_writes.then(|| temp_path(&key_path));
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(temp_path.as_ref().unwrap_or(&key_path))?;
+        Ok(Box::new(FilesystemValueWriter {
+            store: self,
+            file: BufWriter::new(file),
+            key_path,
+            temp_path,
+            _lock: lock,
+        }))
+    }
+
+    fn supports_set_if_match(&self) -> bool {
+        true
+    }
+
+    /// Store `value` at `key` if the entity tag of its file matches `etag`.
+    ///
+    /// The value is written to a temporary file which is renamed to the file of the key.
+    /// The check and write are atomic with respect to other operations of this store, but not other processes.
+    /// Processes writing to the same store can be coordinated with store key locks (e.g. [`FileStoreLocks`] on unix).
+    fn set_if_match(
+        &self,
+        key: &StoreKey,
+        value: Bytes,
+        etag: Option<&StoreValueValidator>,
+    ) -> Result<Option<StoreValueValidator>, StorageError> {
+        if self.readonly {
+            return Err(StorageError::ReadOnly);
+        }
+
+        let file = self.get_file_mutex(key);
+        let _lock = file.write();
+
+        let key_path = self.key_to_fspath(key);
+        let current_etag = match std::fs::metadata(&key_path) {
+            Ok(metadata) => Some(file_etag(&metadata)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err.into()),
+        };
+        if current_etag.as_ref() != etag {
+            return Err(StorageError::PreconditionFailed(key.clone()));
+        }
+
+        Self::create_parent_dirs(&key_path)?;
+        let temp_path = temp_path(&key_path);
+        let written = self.write_file(&temp_path, &value, 0, true).and_then(|()| {
+            // A rename preserves the modification time, length, and inode of the file
+            let etag = file_etag(&std::fs::metadata(&temp_path)?);
+            self.rename_temp_file(&temp_path, &key_path)?;
+            Ok(etag)
+        });
+        match written {
+            Ok(etag) => Ok(Some(etag)),
+            Err(err) => {
+                let _ = std::fs::remove_file(&temp_path);
+                Err(err)
+            }
+        }
+    }
+
     fn set_partial_values(
         &self,
         key_offset_values: &[StoreKeyOffsetValue],
@@ -364,6 +746,29 @@ impl WritableStorageTraits for FilesystemStore {
             Ok(())
         }
     }
+
+    fn supports_rename_prefix(&self) -> bool {
+        !self.readonly
+    }
+
+    fn rename_prefix(&self, from: &StorePrefix, to: &StorePrefix) -> Result<(), StorageError> {
+        if self.readonly {
+            return Err(StorageError::ReadOnly);
+        }
+
+        let _lock = self.files.lock(); // lock all operations
+
+        let from_path = self.prefix_to_fs_path(from);
+        if !from_path.exists() {
+            return Ok(());
+        }
+        let to_path = self.prefix_to_fs_path(to);
+        if let Some(parent) = to_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::rename(from_path, to_path)?;
+        Ok(())
+    }
 }
 
 impl ListableStorageTraits for FilesystemStore {
@@ -372,7 +777,7 @@ impl ListableStorageTraits for FilesystemStore {
             .sort_by_file_name()
             .into_iter()
             .filter_map(std::result::Result::ok)
-            .filter(|v| v.path().is_file())
+            .filter(|v| v.path().is_file() && !is_temp_file(v.path()))
             .filter_map(|v| self.fspath_to_key(v.path()).ok())
             .collect())
     }
@@ -382,7 +787,7 @@ impl ListableStorageTraits for FilesystemStore {
             .sort_by_file_name()
             .into_iter()
             .filter_map(std::result::Result::ok)
-            .filter(|v| v.path().is_file())
+            .filter(|v| v.path().is_file() && !is_temp_file(v.path()))
             .filter_map(|v| self.fspath_to_key(v.path()).ok())
             .collect())
     }
@@ -397,6 +802,9 @@ impl ListableStorageTraits for FilesystemStore {
                 let entry = entry?;
                 let fs_path = entry.path();
                 let path = fs_path.file_name().unwrap();
+                if is_temp_file(&fs_path) {
+                    continue;
+                }
                 if fs_path.is_dir() {
                     prefixes.push(StorePrefix::new(
                         prefix.as_str().to_string() + path.to_str().unwrap() + "/",
@@ -421,7 +829,7 @@ impl ListableStorageTraits for FilesystemStore {
             .into_iter()
             .filter_map(std::result::Result::ok)
             .filter_map(|v| {
-                if v.path().is_file() {
+                if v.path().is_file() && !is_temp_file(v.path()) {
                     Some(std::fs::metadata(v.path()).unwrap().len())
                 } else {
                     None
@@ -466,6 +874,38 @@ mod tests {
         zarrs_storage::store_test::store_write(&store)?;
         zarrs_storage::store_test::store_read(&store)?;
         zarrs_storage::store_test::store_list(&store)?;
+        zarrs_storage::store_test::store_set_if_match(&store)?;
+        zarrs_storage::store_test::store_rename_prefix(&store)?;
+        Ok(())
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn atomic_writes() -> Result<(), Box<dyn Error>> {
+        let path = tempfile::TempDir::new()?;
+        let mut opts = FilesystemStoreOptions::default();
+        opts.atomic_writes(true).fsync(true);
+
+        let store = FilesystemStore::new_with_options(path.path(), opts)?.sorted();
+        zarrs_storage::store_test::store_write(&store)?;
+        zarrs_storage::store_test::store_read(&store)?;
+
+        // A temporary file left behind by a crash is not listed
+        std::fs::write(
+            path.path()
+                .join("a")
+                .join(format!(".c.0.0{TEMP_FILE_SUFFIX}")),
+            [1],
+        )?;
+        zarrs_storage::store_test::store_list(&store)?;
+
+        // An unfinished writer does not modify the value
+        let key = "a/c".try_into()?;
+        let mut writer = store.set_writer(&key)?;
+        writer.write_all(&[1, 2, 3])?;
+        drop(writer);
+        assert_eq!(store.get(&key)?.unwrap(), vec![0]);
+        assert_eq!(std::fs::read_dir(path.path().join("a"))?.count(), 5);
         Ok(())
     }
 
@@ -483,4 +923,31 @@ mod tests {
         zarrs_storage::store_test::store_list(&store)?;
         Ok(())
     }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn direct_io_large() -> Result<(), Box<dyn Error>> {
+        let path = tempfile::TempDir::new()?;
+        let mut opts = FilesystemStoreOptions::default();
+        opts.direct_io(true);
+        let store = FilesystemStore::new_with_options(path.path(), opts)?;
+
+        // Unaligned data larger than the staging buffer
+        let value: Vec<u8> = (0..2 * DIRECT_IO_BUFFER_SIZE + 123)
+            .map(|i| u8::try_from(i % 251).unwrap())
+            .collect();
+        store.set(&"unaligned".try_into()?, value.clone().into())?;
+        assert_eq!(store.get(&"unaligned".try_into()?)?.unwrap(), value);
+
+        // Page-aligned data with an unaligned tail
+        let mut aligned = bytes_aligned(value.len());
+        aligned.extend_from_slice(&value);
+        store.set(&"aligned".try_into()?, aligned.freeze())?;
+        assert_eq!(store.get(&"aligned".try_into()?)?.unwrap(), value);
+        assert_eq!(
+            store.size_key(&"aligned".try_into()?)?,
+            Some(value.len() as u64)
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,375 @@
+//! An asynchronous HTTP store.
+
+use futures::{StreamExt, TryStreamExt};
+use reqwest::{
+    header::{ACCEPT_ENCODING, AUTHORIZATION, RANGE},
+    Url,
+};
+use zarrs_storage::{
+    byte_range::ByteRange, AsyncBytes, AsyncReadableStorageTraits, AsyncStoreValueStream,
+    MaybeAsyncBytes, MaybeModifiedBytes, StorageError, StoreKey, StoreValueValidator,
+};
+
+use crate::{
+    byte_ranges_response::{byte_ranges_response, range_header},
+    collect_byte_ranges, concat_segments, conditional_header, get_if_modified_response,
+    get_response, get_response_found, handle_reqwest_error, handle_url_error, key_to_url,
+    parse_base_url, size_response, HTTPCredential, HTTPRetry, HTTPSegmentedDownload,
+    HTTPStoreCreateError, HTTPStoreOptions, DEFAULT_GET_MANY_CONCURRENCY, IDENTITY,
+};
+
+/// Send a request, retrying transient errors and refreshing credentials and retrying once if they are rejected.
+pub(crate) async fn send(
+    credential: &HTTPCredential,
+    retry: &HTTPRetry,
+    request: impl Fn() -> reqwest::RequestBuilder + Send + Sync,
+) -> Result<reqwest::Response, StorageError> {
+    let send = || async {
+        let mut retries = 0;
+        loop {
+            let mut request = request();
+            if let Some(authorization) = credential.authorization() {
+                request = request.header(AUTHORIZATION, authorization);
+            }
+            let response = request.send().await;
+            let result = response.as_ref().map(reqwest::Response::status);
+            match retry.backoff(retries, result) {
+                Some(backoff) => tokio::time::sleep(backoff).await,
+                None => return response.map_err(handle_reqwest_error),
+            }
+            retries += 1;
+        }
+    };
+    let response = send().await?;
+    if credential.refresh(response.status())? {
+        send().await
+    } else {
+        Ok(response)
+    }
+}
+
+/// An asynchronous HTTP store.
+///
+/// All requests share a single [`reqwest::Client`], which pools and keeps alive connections.
+/// See [`HTTPStoreOptions`] for configuring the client, default headers, and authentication.
+#[derive(Debug)]
+pub struct AsyncHTTPStore {
+    base_url: Url,
+    batch_range_requests: bool,
+    client: reqwest::Client,
+    credential: HTTPCredential,
+    retry: HTTPRetry,
+    segmented_download: Option<HTTPSegmentedDownload>,
+    get_many_concurrency: usize,
+}
+
+impl AsyncHTTPStore {
+    /// Create a new asynchronous HTTP store at a given `base_url`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`HTTPStoreCreateError`] if `base_url` is not a valid URL.
+    pub fn new(base_url: &str) -> Result<Self, HTTPStoreCreateError> {
+        Self::new_with_options(base_url, &HTTPStoreOptions::default())
+    }
+
+    /// Create a new asynchronous HTTP store at a given `base_url` with a client configured by `options`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`HTTPStoreCreateError`] if `base_url` is not a valid URL or the client cannot be created.
+    pub fn new_with_options(
+        base_url: &str,
+        options: &HTTPStoreOptions,
+    ) -> Result<Self, HTTPStoreCreateError> {
+        let base_url = parse_base_url(base_url)?;
+        let client = options.client()?;
+        Ok(Self {
+            base_url,
+            batch_range_requests: true,
+            client,
+            credential: options.credential(),
+            retry: options.retry.clone(),
+            segmented_download: options.segmented_download,
+            get_many_concurrency: options.get_many_concurrency_or_default(),
+        })
+    }
+
+    /// Create a new asynchronous HTTP store at a given `base_url` with an existing `client`.
+    ///
+    /// Clones of a [`reqwest::Client`] share a connection pool, so a client can be shared by many stores.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`HTTPStoreCreateError`] if `base_url` is not a valid URL.
+    pub fn new_with_client(
+        base_url: &str,
+        client: reqwest::Client,
+    ) -> Result<Self, HTTPStoreCreateError> {
+        Ok(Self {
+            base_url: parse_base_url(base_url)?,
+            batch_range_requests: true,
+            client,
+            credential: HTTPCredential::default(),
+            retry: HTTPRetry::default(),
+            segmented_download: None,
+            get_many_concurrency: DEFAULT_GET_MANY_CONCURRENCY,
+        })
+    }
+
+    /// Return the client.
+    #[must_use]
+    pub const fn client(&self) -> &reqwest::Client {
+        &self.client
+    }
+
+    /// Set whether to batch range requests.
+    ///
+    /// Defaults to true.
+    /// Byte ranges missing from the response to a batched range request are requested individually.
+    pub fn set_batch_range_requests(&mut self, batch_range_requests: bool) {
+        self.batch_range_requests = batch_range_requests;
+    }
+
+    /// Maps a [`StoreKey`] to a HTTP [`Url`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URL is invalid.
+    pub fn key_to_url(&self, key: &StoreKey) -> Result<Url, url::ParseError> {
+        key_to_url(&self.base_url, key)
+    }
+
+    /// Send a request, retrying transient errors and refreshing credentials and retrying once if they are rejected.
+    async fn send(
+        &self,
+        request: impl Fn() -> reqwest::RequestBuilder + Send + Sync,
+    ) -> Result<reqwest::Response, StorageError> {
+        send(&self.credential, &self.retry, request).await
+    }
+
+    /// Request `byte_ranges` of the value at `url` with `size`.
+    async fn get_byte_ranges(
+        &self,
+        url: &Url,
+        byte_ranges: &[ByteRange],
+        size: u64,
+    ) -> Result<Vec<Option<AsyncBytes>>, StorageError> {
+        let range = range_header(byte_ranges, size);
+        let response = self
+            .send(|| {
+                self.client
+                    .get(url.clone())
+                    .header(RANGE, range.clone())
+                    .header(ACCEPT_ENCODING, IDENTITY)
+            })
+            .await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let bytes = response.bytes().await.map_err(handle_reqwest_error)?;
+        byte_ranges_response(status, &headers, bytes, byte_ranges, size)
+    }
+
+    /// Retrieve the segments with `byte_ranges` of the value at `url` with `size` concurrently.
+    async fn get_segmented(
+        &self,
+        url: &Url,
+        byte_ranges: &[ByteRange],
+        size: u64,
+        concurrency: usize,
+    ) -> Result<AsyncBytes, StorageError> {
+        let segments: Vec<AsyncBytes> = futures::stream::iter(byte_ranges.iter().copied())
+            .map(|byte_range| async move {
+                let segment = self.get_byte_ranges(url, &[byte_range], size).await?;
+                Ok::<_, StorageError>(collect_byte_ranges(segment)?.remove(0))
+            })
+            .buffered(concurrency)
+            .try_collect()
+            .await?;
+        concat_segments(&segments, size)
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncReadableStorageTraits for AsyncHTTPStore {
+    async fn get(&self, key: &StoreKey) -> Result<MaybeAsyncBytes, StorageError> {
+        let url = self.key_to_url(key).map_err(handle_url_error)?;
+        if let Some(segmented_download) = self.segmented_download {
+            let Some(size) = self.size_key(key).await? else {
+                return Ok(None);
+            };
+            if let Some(byte_ranges) = segmented_download.byte_ranges(size) {
+                return self
+                    .get_segmented(&url, &byte_ranges, size, segmented_download.concurrency)
+                    .await
+                    .map(Some);
+            }
+        }
+        let response = self.send(|| self.client.get(url.clone())).await?;
+        let status = response.status();
+        get_response(
+            status,
+            response.bytes().await.map_err(handle_reqwest_error)?,
+        )
+    }
+
+    async fn get_many(&self, keys: &[StoreKey]) -> Result<Vec<MaybeAsyncBytes>, StorageError> {
+        let futures = keys.iter().map(|key| self.get(key)).collect::<Vec<_>>();
+        futures::stream::iter(futures)
+            .buffered(self.get_many_concurrency)
+            .try_collect()
+            .await
+    }
+
+    /// Return a stream of the body of the response to a `GET` request of the value at `key`.
+    ///
+    /// The body is streamed as it is received, so a segmented download is not used.
+    async fn get_stream(
+        &self,
+        key: &StoreKey,
+    ) -> Result<Option<AsyncStoreValueStream<'_>>, StorageError> {
+        let url = self.key_to_url(key).map_err(handle_url_error)?;
+        let response = self.send(|| self.client.get(url.clone())).await?;
+        if !get_response_found(response.status())? {
+            return Ok(None);
+        }
+        let stream = futures::stream::try_unfold(response, |mut response| async move {
+            let chunk = response.chunk().await.map_err(handle_reqwest_error)?;
+            Ok(chunk.map(|chunk| (chunk, response)))
+        });
+        Ok(Some(stream.boxed()))
+    }
+
+    async fn get_if_modified(
+        &self,
+        key: &StoreKey,
+        validator: Option<&StoreValueValidator>,
+    ) -> Result<MaybeModifiedBytes, StorageError> {
+        let url = self.key_to_url(key).map_err(handle_url_error)?;
+        let conditional_header = validator.and_then(conditional_header);
+        let response = self
+            .send(|| {
+                let request = self.client.get(url.clone());
+                if let Some((name, value)) = &conditional_header {
+                    request.header(name, value)
+                } else {
+                    request
+                }
+            })
+            .await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let bytes = response.bytes().await.map_err(handle_reqwest_error)?;
+        get_if_modified_response(status, &headers, bytes)
+    }
+
+    async fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<AsyncBytes>>, StorageError> {
+        let url = self.key_to_url(key).map_err(handle_url_error)?;
+        let Some(size) = self.size_key(key).await? else {
+            return Ok(None);
+        };
+        let mut out = if self.batch_range_requests {
+            self.get_byte_ranges(&url, byte_ranges, size).await?
+        } else {
+            vec![None; byte_ranges.len()]
+        };
+        for (byte_range, bytes) in byte_ranges.iter().zip(&mut out) {
+            if bytes.is_none() {
+                // Request byte ranges missing from a batched response individually
+                *bytes = self
+                    .get_byte_ranges(&url, std::slice::from_ref(byte_range), size)
+                    .await?
+                    .remove(0);
+            }
+        }
+        collect_byte_ranges(out).map(Some)
+    }
+
+    async fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        let url = self.key_to_url(key).map_err(handle_url_error)?;
+        let response = self
+            .send(|| {
+                self.client
+                    .head(url.clone())
+                    .header(ACCEPT_ENCODING, IDENTITY)
+            })
+            .await?;
+        size_response(response.status(), response.headers())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    const HTTP_TEST_PATH_REF: &str =
+        "https://raw.githubusercontent.com/LDeakin/zarrs/main/zarrs/tests/data/store";
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore)]
+    async fn async_http_store() -> Result<(), Box<dyn Error>> {
+        let store = AsyncHTTPStore::new(HTTP_TEST_PATH_REF).unwrap();
+        zarrs_storage::store_test::async_store_read(&store).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore)]
+    async fn async_http_store_get_many() -> Result<(), Box<dyn Error>> {
+        let url = crate::tests::serve_values(crate::tests::GET_MANY_VALUES, 4);
+        let mut options = HTTPStoreOptions::default();
+        options.get_many_concurrency(2);
+        let store = AsyncHTTPStore::new_with_options(&url, &options)?;
+        let keys = [
+            "c/d".try_into()?,
+            "a".try_into()?,
+            "e".try_into()?,
+            "b".try_into()?,
+        ];
+        assert_eq!(
+            store.get_many(&keys).await?,
+            [
+                Some(b"345".as_slice().into()),
+                Some(b"0".as_slice().into()),
+                None,
+                Some(b"12".as_slice().into())
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore)]
+    async fn async_http_store_get_stream() -> Result<(), Box<dyn Error>> {
+        let url = crate::tests::serve_values(crate::tests::GET_MANY_VALUES, 2);
+        let store = AsyncHTTPStore::new(&url)?;
+        let value: Vec<AsyncBytes> = store
+            .get_stream(&"c/d".try_into()?)
+            .await?
+            .unwrap()
+            .try_collect()
+            .await?;
+        assert_eq!(value.concat(), b"345");
+        assert!(store.get_stream(&"e".try_into()?).await?.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore)]
+    async fn async_http_store_segmented_download() -> Result<(), Box<dyn Error>> {
+        let url = crate::tests::serve_first_byte_range(b"0123456789", 5);
+        let mut options = HTTPStoreOptions::default();
+        options.segmented_download(3, 2);
+        let store = AsyncHTTPStore::new_with_options(&url, &options)?;
+        assert_eq!(
+            store.get(&"a".try_into()?).await?.unwrap(),
+            b"0123456789".as_slice()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,251 @@
+//! An asynchronous store of presigned URLs.
+
+use futures::{StreamExt, TryStreamExt};
+use reqwest::{
+    header::{ACCEPT_ENCODING, RANGE},
+    Method, Url,
+};
+use zarrs_storage::{
+    async_store_set_partial_values, byte_range::ByteRange, AsyncBytes, AsyncReadableStorageTraits,
+    AsyncWritableStorageTraits, MaybeAsyncBytes, StorageError, StoreKey, StoreKeyOffsetValue,
+    StorePrefix,
+};
+
+use crate::{
+    async_http_store::send,
+    byte_ranges_response::{byte_ranges_response, range_header},
+    collect_byte_ranges, get_response, handle_reqwest_error,
+    presigned_url_store::{delete_response, erase_prefix_unsupported, put_response},
+    size_response, HTTPCredential, HTTPPresign, HTTPRetry, HTTPStoreCreateError, HTTPStoreOptions,
+    DEFAULT_GET_MANY_CONCURRENCY, IDENTITY,
+};
+
+/// An asynchronous store of presigned URLs.
+///
+/// See [`PresignedURLStore`](crate::PresignedURLStore).
+pub struct AsyncPresignedURLStore {
+    presign: HTTPPresign,
+    writable: bool,
+    client: reqwest::Client,
+    credential: HTTPCredential,
+    retry: HTTPRetry,
+    get_many_concurrency: usize,
+}
+
+impl core::fmt::Debug for AsyncPresignedURLStore {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("AsyncPresignedURLStore")
+            .field("writable", &self.writable)
+            .field("client", &self.client)
+            .field("retry", &self.retry)
+            .field("get_many_concurrency", &self.get_many_concurrency)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AsyncPresignedURLStore {
+    /// Create a new read-only asynchronous presigned URL store with URLs returned by `presign`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`HTTPStoreCreateError`] if the client cannot be created.
+    pub fn new(presign: HTTPPresign) -> Result<Self, HTTPStoreCreateError> {
+        Self::new_with_options(presign, &HTTPStoreOptions::default())
+    }
+
+    /// Create a new read-only asynchronous presigned URL store with URLs returned by `presign` and a client configured by `options`.
+    ///
+    /// Authentication options are not usually needed, since presigned URLs embed their authorisation.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`HTTPStoreCreateError`] if the client cannot be created.
+    pub fn new_with_options(
+        presign: HTTPPresign,
+        options: &HTTPStoreOptions,
+    ) -> Result<Self, HTTPStoreCreateError> {
+        Ok(Self {
+            presign,
+            writable: false,
+            client: options.client()?,
+            credential: options.credential(),
+            retry: options.retry.clone(),
+            get_many_concurrency: options.get_many_concurrency_or_default(),
+        })
+    }
+
+    /// Create a new read-only asynchronous presigned URL store with URLs returned by `presign` and an existing `client`.
+    #[must_use]
+    pub fn new_with_client(presign: HTTPPresign, client: reqwest::Client) -> Self {
+        Self {
+            presign,
+            writable: false,
+            client,
+            credential: HTTPCredential::default(),
+            retry: HTTPRetry::default(),
+            get_many_concurrency: DEFAULT_GET_MANY_CONCURRENCY,
+        }
+    }
+
+    /// Makes the store writable, with values written with `PUT` and erased with `DELETE` requests.
+    #[must_use]
+    pub const fn writable(mut self) -> Self {
+        self.writable = true;
+        self
+    }
+
+    /// Return the client.
+    #[must_use]
+    pub const fn client(&self) -> &reqwest::Client {
+        &self.client
+    }
+
+    /// Returns the presigned URL of a `method` on `key`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URL cannot be presigned.
+    pub fn presign(&self, key: &StoreKey, method: &Method) -> Result<Url, StorageError> {
+        (self.presign)(key, method)
+    }
+
+    async fn send(
+        &self,
+        request: impl Fn() -> reqwest::RequestBuilder + Send + Sync,
+    ) -> Result<reqwest::Response, StorageError> {
+        send(&self.credential, &self.retry, request).await
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncReadableStorageTraits for AsyncPresignedURLStore {
+    async fn get(&self, key: &StoreKey) -> Result<MaybeAsyncBytes, StorageError> {
+        let url = self.presign(key, &Method::GET)?;
+        let response = self.send(|| self.client.get(url.clone())).await?;
+        let status = response.status();
+        get_response(
+            status,
+            response.bytes().await.map_err(handle_reqwest_error)?,
+        )
+    }
+
+    async fn get_many(&self, keys: &[StoreKey]) -> Result<Vec<MaybeAsyncBytes>, StorageError> {
+        let futures = keys.iter().map(|key| self.get(key)).collect::<Vec<_>>();
+        futures::stream::iter(futures)
+            .buffered(self.get_many_concurrency)
+            .try_collect()
+            .await
+    }
+
+    async fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<AsyncBytes>>, StorageError> {
+        let Some(size) = self.size_key(key).await? else {
+            return Ok(None);
+        };
+        let url = self.presign(key, &Method::GET)?;
+        let mut out = Vec::with_capacity(byte_ranges.len());
+        for byte_range in byte_ranges {
+            let byte_range = std::slice::from_ref(byte_range);
+            let range = range_header(byte_range, size);
+            let response = self
+                .send(|| {
+                    self.client
+                        .get(url.clone())
+                        .header(RANGE, range.clone())
+                        .header(ACCEPT_ENCODING, IDENTITY)
+                })
+                .await?;
+            let status = response.status();
+            let headers = response.headers().clone();
+            let bytes = response.bytes().await.map_err(handle_reqwest_error)?;
+            let bytes = byte_ranges_response(status, &headers, bytes, byte_range, size)?;
+            out.extend(collect_byte_ranges(bytes)?);
+        }
+        Ok(Some(out))
+    }
+
+    async fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        let url = self.presign(key, &Method::HEAD)?;
+        let response = self
+            .send(|| {
+                self.client
+                    .head(url.clone())
+                    .header(ACCEPT_ENCODING, IDENTITY)
+            })
+            .await?;
+        size_response(response.status(), response.headers())
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncWritableStorageTraits for AsyncPresignedURLStore {
+    async fn set(&self, key: &StoreKey, value: AsyncBytes) -> Result<(), StorageError> {
+        if !self.writable {
+            return Err(StorageError::ReadOnly);
+        }
+        let url = self.presign(key, &Method::PUT)?;
+        let response = self
+            .send(|| self.client.put(url.clone()).body(value.clone()))
+            .await?;
+        put_response(response.status())
+    }
+
+    async fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        if !self.writable {
+            return Err(StorageError::ReadOnly);
+        }
+        async_store_set_partial_values(self, key_offset_values).await
+    }
+
+    async fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        if !self.writable {
+            return Err(StorageError::ReadOnly);
+        }
+        let url = self.presign(key, &Method::DELETE)?;
+        let response = self.send(|| self.client.delete(url.clone())).await?;
+        delete_response(response.status())
+    }
+
+    async fn erase_prefix(&self, _prefix: &StorePrefix) -> Result<(), StorageError> {
+        if !self.writable {
+            return Err(StorageError::ReadOnly);
+        }
+        Err(erase_prefix_unsupported())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::presigned_url_store::tests::{presign, serve_presigned};
+    use std::error::Error;
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore)]
+    async fn async_presigned_url_store() -> Result<(), Box<dyn Error>> {
+        let store = AsyncPresignedURLStore::new(presign(serve_presigned()))?.writable();
+        store
+            .set(&"a/b".try_into()?, vec![255, 255, 255].into())
+            .await?;
+        store
+            .set_partial_values(&[StoreKeyOffsetValue::new("a/b".try_into()?, 0, &[0, 1])])
+            .await?;
+        store
+            .set_partial_values(&[StoreKeyOffsetValue::new("a/b".try_into()?, 2, &[2, 3])])
+            .await?;
+        store.set(&"a/c".try_into()?, vec![0].into()).await?;
+        store.set(&"i/j/k".try_into()?, vec![0, 1].into()).await?;
+        store.set(&"erase".try_into()?, vec![].into()).await?;
+        store.erase(&"erase".try_into()?).await?;
+        store.erase(&"erase".try_into()?).await?; // succeeds
+        zarrs_storage::store_test::async_store_read(&store).await?;
+        assert!(store.erase_prefix(&StorePrefix::root()).await.is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,315 @@
+//! Parsing of responses to byte range requests.
+//!
+//! See <https://httpwg.org/specs/rfc9110.html#range.requests>.
+
+use itertools::Itertools;
+use reqwest::{
+    header::{HeaderMap, HeaderValue, CONTENT_RANGE, CONTENT_TYPE},
+    StatusCode,
+};
+use zarrs_storage::{byte_range::ByteRange, Bytes, StorageError};
+
+/// Returns the `RANGE` header value of a request for `byte_ranges` of a value with `size`.
+pub(crate) fn range_header(byte_ranges: &[ByteRange], size: u64) -> HeaderValue {
+    let bytes_strs = byte_ranges
+        .iter()
+        .map(|byte_range| format!("{}-{}", byte_range.start(size), byte_range.end(size) - 1))
+        .join(", ");
+    HeaderValue::from_str(&format!("bytes={bytes_strs}")).unwrap()
+}
+
+/// A contiguous part of a value starting at a byte offset.
+struct Part {
+    start: u64,
+    bytes: Bytes,
+}
+
+impl Part {
+    /// Returns the bytes of `byte_range` if they are within this part.
+    fn get(&self, byte_range: &ByteRange, size: u64) -> Option<Bytes> {
+        let start = byte_range.start(size).checked_sub(self.start)?;
+        let end = byte_range.end(size).checked_sub(self.start)?;
+        let start = usize::try_from(start).ok()?;
+        let end = usize::try_from(end).ok()?;
+        (start <= end && end <= self.bytes.len()).then(|| self.bytes.slice(start..end))
+    }
+}
+
+/// Parses a `Content-Range` header value (e.g. `bytes 0-49/1270`), returning the inclusive start and end.
+fn parse_content_range(content_range: &str) -> Option<(u64, u64)> {
+    let (unit, range) = content_range.trim().split_once(' ')?;
+    if !unit.eq_ignore_ascii_case("bytes") {
+        return None;
+    }
+    let (range, _size) = range.trim().split_once('/')?;
+    let (start, end) = range.split_once('-')?;
+    let (start, end) = (start.trim().parse().ok()?, end.trim().parse().ok()?);
+    (start <= end).then_some((start, end))
+}
+
+/// Returns the boundary of a `multipart/byteranges` `Content-Type` header value.
+fn multipart_byteranges_boundary(content_type: &str) -> Option<&str> {
+    let mut params = content_type.split(';').map(str::trim);
+    if !params.next()?.eq_ignore_ascii_case("multipart/byteranges") {
+        return None;
+    }
+    params.find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case("boundary")
+            .then(|| value.trim().trim_matches('"'))
+    })
+}
+
+/// Returns the position of `needle` in `haystack` at or after `from`.
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    haystack
+        .get(from..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|position| position + from)
+}
+
+/// Parses the body of a `multipart/byteranges` response with `boundary`.
+///
+/// The length of each part is taken from its `Content-Range` header, so part bodies may contain the boundary.
+fn parse_multipart_byteranges(bytes: &Bytes, boundary: &str) -> Result<Vec<Part>, StorageError> {
+    let invalid = || StorageError::from("http multipart/byteranges response is invalid");
+    let delimiter = format!("--{boundary}");
+    let delimiter = delimiter.as_bytes();
+
+    let mut parts = Vec::new();
+    let mut position = find(bytes, delimiter, 0).ok_or_else(invalid)?;
+    loop {
+        position += delimiter.len();
+        if bytes[position..].starts_with(b"--") {
+            // The closing delimiter
+            break;
+        }
+
+        // Skip the remainder of the delimiter line, then parse headers terminated by an empty line
+        position = find(bytes, b"\n", position).ok_or_else(invalid)? + 1;
+        let mut content_range = None;
+        loop {
+            let line_end = find(bytes, b"\n", position).ok_or_else(invalid)?;
+            let line = std::str::from_utf8(&bytes[position..line_end]).map_err(|_| invalid())?;
+            position = line_end + 1;
+            let line = line.trim_end_matches('\r');
+            if line.is_empty() {
+                break;
+            }
+            let (name, value) = line.split_once(':').ok_or_else(invalid)?;
+            if name.trim().eq_ignore_ascii_case(CONTENT_RANGE.as_str()) {
+                content_range = Some(parse_content_range(value).ok_or_else(invalid)?);
+            }
+        }
+        let (start, end) = content_range.ok_or_else(invalid)?;
+
+        let length = usize::try_from(end - start + 1).map_err(|_| invalid())?;
+        let body_end = position.checked_add(length).ok_or_else(invalid)?;
+        if body_end > bytes.len() {
+            return Err(invalid());
+        }
+        parts.push(Part {
+            start,
+            bytes: bytes.slice(position..body_end),
+        });
+        position = find(bytes, delimiter, body_end).ok_or_else(invalid)?;
+    }
+    Ok(parts)
+}
+
+/// Splits the response to a byte range request for `byte_ranges` of a value with `size`.
+///
+/// Supports `multipart/byteranges` and single part partial content responses, and full content responses from servers that ignore the `RANGE` header.
+/// A [`None`] is returned for each byte range that is not included in the response, as servers may respond with a subset of the requested byte ranges.
+pub(crate) fn byte_ranges_response(
+    status: StatusCode,
+    headers: &HeaderMap,
+    bytes: Bytes,
+    byte_ranges: &[ByteRange],
+    size: u64,
+) -> Result<Vec<Option<Bytes>>, StorageError> {
+    let parts = match status {
+        StatusCode::NOT_FOUND => return Err(StorageError::from("the http server returned a NOT FOUND status for the byte range request, but returned a non zero size for CONTENT_LENGTH")),
+        StatusCode::PARTIAL_CONTENT => {
+            let header_str = |name| headers.get(name).and_then(|value: &HeaderValue| value.to_str().ok());
+            if let Some(boundary) = header_str(CONTENT_TYPE).and_then(multipart_byteranges_boundary) {
+                parse_multipart_byteranges(&bytes, boundary)?
+            } else if let Some(content_range) = header_str(CONTENT_RANGE) {
+                let (start, end) = parse_content_range(content_range).ok_or_else(|| {
+                    StorageError::from("http partial content response has an invalid content range")
+                })?;
+                if bytes.len() as u64 != end - start + 1 {
+                    return Err(StorageError::from(
+                        "http partial content response length does not match its content range",
+                    ));
+                }
+                vec![Part { start, bytes }]
+            } else if bytes.len() as u64
+                == byte_ranges
+                    .iter()
+                    .map(|byte_range| byte_range.length(size))
+                    .sum::<u64>()
+            {
+                // Without a content range, assume the byte ranges are concatenated
+                let mut offset = 0;
+                byte_ranges
+                    .iter()
+                    .map(|byte_range| {
+                        let length = usize::try_from(byte_range.length(size)).unwrap();
+                        let part = Part {
+                            start: byte_range.start(size),
+                            bytes: bytes.slice(offset..offset + length),
+                        };
+                        offset += length;
+                        part
+                    })
+                    .collect()
+            } else {
+                vec![]
+            }
+        }
+        StatusCode::OK => {
+            // Received all bytes
+            vec![Part { start: 0, bytes }]
+        }
+        _ => {
+            return Err(StorageError::from(format!(
+                "the http server responded with status {status} for the byte range request"
+            )))
+        }
+    };
+
+    Ok(byte_ranges
+        .iter()
+        .map(|byte_range| parts.iter().find_map(|part| part.get(byte_range, size)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    fn partial_content_headers(content_type: &str, content_range: Option<&str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_str(content_type).unwrap());
+        if let Some(content_range) = content_range {
+            headers.insert(CONTENT_RANGE, HeaderValue::from_str(content_range).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn http_byte_ranges_response() -> Result<(), Box<dyn Error>> {
+        let byte_ranges = [ByteRange::FromStart(1, Some(2)), ByteRange::Suffix(1)];
+        assert_eq!(range_header(&byte_ranges, 5), "bytes=1-2, 4-4");
+        let partial = byte_ranges_response(
+            StatusCode::PARTIAL_CONTENT,
+            &HeaderMap::new(),
+            Bytes::from(vec![1, 2, 4]),
+            &byte_ranges,
+            5,
+        )?;
+        assert_eq!(partial, vec![Some(vec![1, 2].into()), Some(vec![4].into())]);
+        let full = byte_ranges_response(
+            StatusCode::OK,
+            &HeaderMap::new(),
+            Bytes::from(vec![0, 1, 2, 3, 4]),
+            &byte_ranges,
+            5,
+        )?;
+        assert_eq!(full, partial);
+        assert_eq!(
+            byte_ranges_response(
+                StatusCode::PARTIAL_CONTENT,
+                &HeaderMap::new(),
+                Bytes::from(vec![1]),
+                &byte_ranges,
+                5
+            )?,
+            vec![None, None]
+        );
+        assert!(byte_ranges_response(
+            StatusCode::NOT_FOUND,
+            &HeaderMap::new(),
+            Bytes::new(),
+            &byte_ranges,
+            5
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn http_byte_ranges_response_single_part() -> Result<(), Box<dyn Error>> {
+        let byte_ranges = [ByteRange::FromStart(1, Some(2)), ByteRange::Suffix(1)];
+        let headers = partial_content_headers("application/octet-stream", Some("bytes 1-2/5"));
+        let partial = byte_ranges_response(
+            StatusCode::PARTIAL_CONTENT,
+            &headers,
+            Bytes::from(vec![1, 2]),
+            &byte_ranges,
+            5,
+        )?;
+        assert_eq!(partial, vec![Some(vec![1, 2].into()), None]);
+        assert!(byte_ranges_response(
+            StatusCode::PARTIAL_CONTENT,
+            &headers,
+            Bytes::from(vec![1]),
+            &byte_ranges,
+            5
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn http_byte_ranges_response_multipart() -> Result<(), Box<dyn Error>> {
+        let byte_ranges = [
+            ByteRange::FromStart(0, Some(2)),
+            ByteRange::FromStart(5, Some(3)),
+            ByteRange::Suffix(2),
+        ];
+        let headers =
+            partial_content_headers("multipart/byteranges; boundary=\"3d6b6a416f9b5\"", None);
+        // The second part body contains the delimiter, and the last byte range is not included
+        let body = b"--3d6b6a416f9b5\r\n\
+            Content-Type: application/octet-stream\r\n\
+            Content-Range: bytes 0-1/10\r\n\
+            \r\n\
+            ab\r\n\
+            --3d6b6a416f9b5\r\n\
+            content-range: bytes 4-23/30\r\n\
+            \r\n\
+            x--3d6b6a416f9b5yyyy\r\n\
+            --3d6b6a416f9b5--\r\n";
+        let partial = byte_ranges_response(
+            StatusCode::PARTIAL_CONTENT,
+            &headers,
+            Bytes::from_static(body),
+            &byte_ranges,
+            30,
+        )?;
+        assert_eq!(
+            partial,
+            vec![
+                Some(b"ab".to_vec().into()),
+                Some(b"--3".to_vec().into()),
+                None
+            ]
+        );
+
+        // Truncated
+        assert!(byte_ranges_response(
+            StatusCode::PARTIAL_CONTENT,
+            &headers,
+            Bytes::from_static(&body[..60]),
+            &byte_ranges,
+            30
+        )
+        .is_err());
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-//! A synchronous `http` store for the [`zarrs`](https://docs.rs/zarrs/latest/zarrs/index.html) crate.
+//! A `http` store for the [`zarrs`](https://docs.rs/zarrs/latest/zarrs/index.html) crate.
 //!
 //! ```rust
 //! # use std::sync::Arc;
@@ -9,29 +9,530 @@
 //! # Ok::<_, Box<dyn std::error::Error>>(())
 //! ```
 //!
+//! The asynchronous `AsyncHTTPStore` is enabled by the `async` feature.
+//!
+//! A [`PresignedURLStore`] (or `AsyncPresignedURLStore`) requests presigned URLs for each key, so clients can access a store without embedding credentials.
+//! The client of a store can be configured with [`HTTPStoreOptions`] (e.g. timeouts and connection pooling) or shared between stores.
+//!
+//! The `gzip` and `zstd` features enable accepting compressed responses, which are transparently decompressed.
+//! Many static hosts serve metadata compressed.
+//! Size and range requests always request the uncompressed (identity) encoding, since sizes and byte ranges refer to the encoded representation.
+//!
 //! ## Licence
 //! `zarrs_http` is licensed under either of
 //! - the Apache License, Version 2.0 [LICENSE-APACHE](https://docs.rs/crate/zarrs_http/latest/source/LICENCE-APACHE) or <http://www.apache.org/licenses/LICENSE-2.0> or
 //! - the MIT license [LICENSE-MIT](https://docs.rs/crate/zarrs_http/latest/source/LICENCE-MIT) or <http://opensource.org/licenses/MIT>, at your option.
 
 use zarrs_storage::{
-    byte_range::ByteRange, Bytes, MaybeBytes, ReadableStorageTraits, StorageError, StoreKey,
+    byte_range::ByteRange, Bytes, MaybeBytes, MaybeModifiedBytes, ReadableStorageTraits,
+    StorageError, StoreKey, StoreValueReader, StoreValueValidator,
 };
 
 use itertools::Itertools;
 use reqwest::{
-    header::{HeaderValue, CONTENT_LENGTH, RANGE},
+    header::{
+        HeaderMap, HeaderName, HeaderValue, ACCEPT_ENCODING, AUTHORIZATION, CONTENT_LENGTH, ETAG,
+        IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, RANGE,
+    },
     StatusCode, Url,
 };
-use std::str::FromStr;
+use std::{
+    str::FromStr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, RwLock,
+    },
+    time::Duration,
+};
 use thiserror::Error;
 
+mod byte_ranges_response;
+mod presigned_url_store;
+use byte_ranges_response::{byte_ranges_response, range_header};
+pub use presigned_url_store::{HTTPPresign, PresignedURLStore};
+
+#[cfg(feature = "async")]
+mod async_http_store;
+#[cfg(feature = "async")]
+pub use async_http_store::AsyncHTTPStore;
+#[cfg(feature = "async")]
+mod async_presigned_url_store;
+#[cfg(feature = "async")]
+pub use async_presigned_url_store::AsyncPresignedURLStore;
+
+/// A callback returning a fresh `AUTHORIZATION` header value, such as a renewed bearer token.
+///
+/// See [`HTTPStoreOptions::credential_refresh`].
+pub type HTTPCredentialRefresh = Arc<dyn Fn() -> Result<HeaderValue, StorageError> + Send + Sync>;
+
+/// The redirect policy of a HTTP store.
+///
+/// A response with a redirect status that is not followed is an error.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HTTPRedirectPolicy {
+    /// Do not follow redirects.
+    None,
+    /// Follow up to a maximum number of redirects.
+    Limited(usize),
+    /// Follow up to a maximum number of redirects to the same origin (scheme, host, and port) as the request.
+    SameOrigin(usize),
+}
+
+impl Default for HTTPRedirectPolicy {
+    fn default() -> Self {
+        Self::Limited(10)
+    }
+}
+
+impl HTTPRedirectPolicy {
+    fn policy(self) -> reqwest::redirect::Policy {
+        match self {
+            Self::None => reqwest::redirect::Policy::none(),
+            Self::Limited(max) => reqwest::redirect::Policy::limited(max),
+            Self::SameOrigin(max) => reqwest::redirect::Policy::custom(move |attempt| {
+                if attempt.previous().len() > max {
+                    attempt.error("too many redirects")
+                } else if attempt
+                    .previous()
+                    .first()
+                    .is_some_and(|url| url.origin() != attempt.url().origin())
+                {
+                    attempt.stop()
+                } else {
+                    attempt.follow()
+                }
+            }),
+        }
+    }
+}
+
+/// Options for the HTTP client of a [`HTTPStore`] or `AsyncHTTPStore`.
+///
+/// Connections are pooled and kept alive by the client and shared by all requests of a store.
+/// To share a client between stores, create a store with a client instead (e.g. [`HTTPStore::new_with_client`]).
+///
+/// Default headers (e.g. a bearer token, API key, or cookie) are sent with every request, enabling access to datasets behind authenticated endpoints.
+///
+/// Requests failing with a transient error (a server error status, a timeout, or a connection error) can be retried with exponential backoff.
+///
+/// ```rust
+/// # use std::{sync::Arc, time::Duration};
+/// # use reqwest::header::{HeaderName, HeaderValue};
+/// use zarrs_http::{HTTPRedirectPolicy, HTTPStore, HTTPStoreOptions};
+/// let mut options = HTTPStoreOptions::default();
+/// options
+///     .bearer_token("token")
+///     .header(HeaderName::from_static("x-api-key"), HeaderValue::from_static("key"))
+///     .credential_refresh(Arc::new(|| Ok(HeaderValue::from_static("Bearer renewed"))))
+///     .max_retries(5)
+///     .retry_backoff(Duration::from_millis(200))
+///     .redirect_policy(HTTPRedirectPolicy::SameOrigin(5));
+/// let store = HTTPStore::new_with_options("http://...", &options)?;
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[non_exhaustive]
+#[derive(Clone, Default)]
+pub struct HTTPStoreOptions {
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    pool_idle_timeout: Option<Duration>,
+    pool_max_idle_per_host: Option<usize>,
+    tcp_keepalive: Option<Duration>,
+    headers: HeaderMap,
+    bearer_token: Option<String>,
+    credential_refresh: Option<HTTPCredentialRefresh>,
+    retry: HTTPRetry,
+    redirect_policy: HTTPRedirectPolicy,
+    segmented_download: Option<HTTPSegmentedDownload>,
+    get_many_concurrency: Option<usize>,
+}
+
+impl core::fmt::Debug for HTTPStoreOptions {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("HTTPStoreOptions")
+            .field("timeout", &self.timeout)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("pool_idle_timeout", &self.pool_idle_timeout)
+            .field("pool_max_idle_per_host", &self.pool_max_idle_per_host)
+            .field("tcp_keepalive", &self.tcp_keepalive)
+            .field("headers", &self.headers)
+            .field(
+                "bearer_token",
+                &self.bearer_token.as_ref().map(|_| "Sensitive"),
+            )
+            .field("credential_refresh", &self.credential_refresh.is_some())
+            .field("retry", &self.retry)
+            .field("redirect_policy", &self.redirect_policy)
+            .field("segmented_download", &self.segmented_download)
+            .field("get_many_concurrency", &self.get_many_concurrency)
+            .finish()
+    }
+}
+
+impl HTTPStoreOptions {
+    /// Set the total timeout of each request, from connecting until the response body has been read.
+    ///
+    /// Defaults to 30 seconds for a [`HTTPStore`], and no timeout for an `AsyncHTTPStore`.
+    pub fn timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the timeout of the connect phase of each request.
+    ///
+    /// Defaults to no timeout.
+    pub fn connect_timeout(&mut self, connect_timeout: Duration) -> &mut Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    /// Set the timeout after which idle pooled connections are closed.
+    ///
+    /// Defaults to 90 seconds.
+    pub fn pool_idle_timeout(&mut self, pool_idle_timeout: Duration) -> &mut Self {
+        self.pool_idle_timeout = Some(pool_idle_timeout);
+        self
+    }
+
+    /// Set the maximum number of idle pooled connections per host.
+    ///
+    /// Defaults to no limit.
+    pub fn pool_max_idle_per_host(&mut self, pool_max_idle_per_host: usize) -> &mut Self {
+        self.pool_max_idle_per_host = Some(pool_max_idle_per_host);
+        self
+    }
+
+    /// Set the interval of TCP keep-alive probes on connections.
+    ///
+    /// Defaults to disabled.
+    pub fn tcp_keepalive(&mut self, tcp_keepalive: Duration) -> &mut Self {
+        self.tcp_keepalive = Some(tcp_keepalive);
+        self
+    }
+
+    /// Add a default header sent with every request, such as an API key or a cookie.
+    ///
+    /// Sensitive values should be marked with [`HeaderValue::set_sensitive`].
+    pub fn header(&mut self, name: HeaderName, value: HeaderValue) -> &mut Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    /// Set a bearer token sent in the `AUTHORIZATION` header of every request.
+    pub fn bearer_token(&mut self, token: impl Into<String>) -> &mut Self {
+        self.bearer_token = Some(token.into());
+        self
+    }
+
+    /// Set a callback which refreshes credentials if the server responds with an `UNAUTHORIZED` or `FORBIDDEN` status.
+    ///
+    /// The returned value replaces the `AUTHORIZATION` header of subsequent requests, and the failed request is retried once.
+    pub fn credential_refresh(&mut self, credential_refresh: HTTPCredentialRefresh) -> &mut Self {
+        self.credential_refresh = Some(credential_refresh);
+        self
+    }
+
+    /// Set the maximum number of retries of a request failing with a transient error.
+    ///
+    /// Defaults to 0.
+    pub fn max_retries(&mut self, max_retries: usize) -> &mut Self {
+        self.retry.max_retries = max_retries;
+        self
+    }
+
+    /// Set the backoff before the first retry of a request, which doubles with each subsequent retry.
+    ///
+    /// Defaults to 100 milliseconds.
+    pub fn retry_backoff(&mut self, retry_backoff: Duration) -> &mut Self {
+        self.retry.backoff = retry_backoff;
+        self
+    }
+
+    /// Set the maximum backoff between retries of a request.
+    ///
+    /// Defaults to 10 seconds.
+    pub fn max_retry_backoff(&mut self, max_retry_backoff: Duration) -> &mut Self {
+        self.retry.max_backoff = max_retry_backoff;
+        self
+    }
+
+    /// Set the redirect policy.
+    ///
+    /// Defaults to [`HTTPRedirectPolicy::Limited`] with a maximum of 10 redirects.
+    pub fn redirect_policy(&mut self, redirect_policy: HTTPRedirectPolicy) -> &mut Self {
+        self.redirect_policy = redirect_policy;
+        self
+    }
+
+    /// Enable segmented download of values larger than `segment_size` bytes.
+    ///
+    /// Retrieving such a value with `get` splits it into segments retrieved with up to `concurrency` concurrent range requests, which are reassembled in order.
+    /// This can saturate high-bandwidth links when retrieving large values, such as shards.
+    /// The size of a value is requested before retrieving it.
+    ///
+    /// Defaults to disabled.
+    pub fn segmented_download(&mut self, segment_size: u64, concurrency: usize) -> &mut Self {
+        self.segmented_download = Some(HTTPSegmentedDownload {
+            segment_size: segment_size.max(1),
+            concurrency: concurrency.max(1),
+        });
+        self
+    }
+
+    /// Set the maximum number of concurrent requests retrieving the values of multiple keys with `get_many`.
+    ///
+    /// Defaults to 16.
+    pub fn get_many_concurrency(&mut self, concurrency: usize) -> &mut Self {
+        self.get_many_concurrency = Some(concurrency.max(1));
+        self
+    }
+
+    fn get_many_concurrency_or_default(&self) -> usize {
+        self.get_many_concurrency
+            .unwrap_or(DEFAULT_GET_MANY_CONCURRENCY)
+    }
+
+    fn default_headers(&self) -> Result<HeaderMap, HTTPStoreCreateError> {
+        let mut headers = self.headers.clone();
+        if let Some(token) = &self.bearer_token {
+            let mut value = HeaderValue::from_str(&format!("Bearer {token}"))
+                .map_err(|_| HTTPStoreCreateError::InvalidHeader(AUTHORIZATION.to_string()))?;
+            value.set_sensitive(true);
+            headers.insert(AUTHORIZATION, value);
+        }
+        Ok(headers)
+    }
+
+    fn credential(&self) -> HTTPCredential {
+        HTTPCredential {
+            refresh: self.credential_refresh.clone(),
+            authorization: RwLock::default(),
+        }
+    }
+
+    fn blocking_client(&self) -> Result<reqwest::blocking::Client, HTTPStoreCreateError> {
+        let mut builder = reqwest::blocking::Client::builder()
+            .tcp_keepalive(self.tcp_keepalive)
+            .redirect(self.redirect_policy.policy())
+            .default_headers(self.default_headers()?);
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(connect_timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+        if let Some(pool_idle_timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(pool_idle_timeout);
+        }
+        if let Some(pool_max_idle_per_host) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(pool_max_idle_per_host);
+        }
+        Ok(builder.build()?)
+    }
+
+    #[cfg(feature = "async")]
+    fn client(&self) -> Result<reqwest::Client, HTTPStoreCreateError> {
+        let mut builder = reqwest::Client::builder()
+            .tcp_keepalive(self.tcp_keepalive)
+            .redirect(self.redirect_policy.policy())
+            .default_headers(self.default_headers()?);
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(connect_timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+        if let Some(pool_idle_timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(pool_idle_timeout);
+        }
+        if let Some(pool_max_idle_per_host) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(pool_max_idle_per_host);
+        }
+        Ok(builder.build()?)
+    }
+}
+
+/// The default maximum number of concurrent requests of `get_many`.
+const DEFAULT_GET_MANY_CONCURRENCY: usize = 16;
+
+/// Apply `f` to `items` on up to `concurrency` scoped threads, returning the outputs in the order of `items`.
+///
+/// The remaining items are skipped after an error.
+fn concurrent_map<T: Sync, R: Send>(
+    items: &[T],
+    concurrency: usize,
+    f: impl Fn(&T) -> Result<R, StorageError> + Sync,
+) -> Result<Vec<R>, StorageError> {
+    let next = AtomicUsize::new(0);
+    let worker = || {
+        let mut outputs = Vec::new();
+        loop {
+            let index = next.fetch_add(1, Ordering::Relaxed);
+            let Some(item) = items.get(index) else {
+                return Ok(outputs);
+            };
+            match f(item) {
+                Ok(output) => outputs.push((index, output)),
+                Err(err) => {
+                    // Stop the other workers
+                    next.store(items.len(), Ordering::Relaxed);
+                    return Err(err);
+                }
+            }
+        }
+    };
+    let mut outputs = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..concurrency.min(items.len()))
+            .map(|_| scope.spawn(worker))
+            .collect();
+        workers
+            .into_iter()
+            .map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|err| std::panic::resume_unwind(err))
+            })
+            .flatten_ok()
+            .collect::<Result<Vec<_>, StorageError>>()
+    })?;
+    outputs.sort_by_key(|(index, _)| *index);
+    Ok(outputs.into_iter().map(|(_, output)| output).collect())
+}
+
+/// The segmented download configuration of a store.
+#[derive(Debug, Clone, Copy)]
+struct HTTPSegmentedDownload {
+    segment_size: u64,
+    concurrency: usize,
+}
+
+impl HTTPSegmentedDownload {
+    /// Returns the byte ranges of the segments of a value with `size`, or [`None`] if it should not be segmented.
+    fn byte_ranges(self, size: u64) -> Option<Vec<ByteRange>> {
+        (size > self.segment_size).then(|| {
+            (0..size)
+                .step_by(usize::try_from(self.segment_size).unwrap_or(usize::MAX))
+                .map(|offset| {
+                    ByteRange::FromStart(offset, Some(self.segment_size.min(size - offset)))
+                })
+                .collect()
+        })
+    }
+}
+
+/// Concatenate `segments` of a value with `size`.
+fn concat_segments(segments: &[Bytes], size: u64) -> Result<Bytes, StorageError> {
+    let mut value = Vec::with_capacity(usize::try_from(size).unwrap_or_default());
+    for segment in segments {
+        value.extend_from_slice(segment);
+    }
+    if value.len() as u64 == size {
+        Ok(value.into())
+    } else {
+        Err(StorageError::from(
+            "http segmented download did not match the size of the value",
+        ))
+    }
+}
+
+/// The retry policy of a store for requests failing with a transient error.
+#[derive(Debug, Clone)]
+struct HTTPRetry {
+    max_retries: usize,
+    backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl Default for HTTPRetry {
+    fn default() -> Self {
+        Self {
+            max_retries: 0,
+            backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+impl HTTPRetry {
+    /// Returns the backoff before retrying a request that has been retried `retries` times and responded with `result`.
+    ///
+    /// Returns [`None`] if the request should not be retried.
+    fn backoff(
+        &self,
+        retries: usize,
+        result: Result<StatusCode, &reqwest::Error>,
+    ) -> Option<Duration> {
+        let transient = match result {
+            Ok(status) => status.is_server_error(),
+            Err(err) => err.is_timeout() || err.is_connect(),
+        };
+        (transient && retries < self.max_retries).then(|| {
+            let factor = u32::try_from(retries)
+                .ok()
+                .and_then(|retries| 1u32.checked_shl(retries))
+                .unwrap_or(u32::MAX);
+            self.backoff.saturating_mul(factor).min(self.max_backoff)
+        })
+    }
+}
+
+/// The refreshable credentials of a store.
+#[derive(Default)]
+struct HTTPCredential {
+    refresh: Option<HTTPCredentialRefresh>,
+    /// The latest refreshed `AUTHORIZATION` header value, which overrides the default headers.
+    authorization: RwLock<Option<HeaderValue>>,
+}
+
+impl core::fmt::Debug for HTTPCredential {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "credential refresh: {}", self.refresh.is_some())
+    }
+}
+
+impl HTTPCredential {
+    fn authorization(&self) -> Option<HeaderValue> {
+        self.authorization
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Refresh the credential if the response `status` indicates it was rejected.
+    ///
+    /// Returns true if the credential was refreshed and the request should be retried.
+    fn refresh(&self, status: StatusCode) -> Result<bool, StorageError> {
+        match &self.refresh {
+            Some(refresh)
+                if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN =>
+            {
+                let mut authorization = refresh()?;
+                authorization.set_sensitive(true);
+                *self
+                    .authorization
+                    .write()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(authorization);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
 /// A synchronous HTTP store.
+///
+/// All requests share a single [`reqwest::blocking::Client`], which pools and keeps alive connections.
+/// See [`HTTPStoreOptions`] for configuring the client, default headers, and authentication.
 #[derive(Debug)]
 pub struct HTTPStore {
     base_url: Url,
     batch_range_requests: bool,
     client: reqwest::blocking::Client,
+    credential: HTTPCredential,
+    retry: HTTPRetry,
+    segmented_download: Option<HTTPSegmentedDownload>,
+    get_many_concurrency: usize,
 }
 
 #[allow(clippy::needless_pass_by_value)]
@@ -43,6 +544,140 @@ fn handle_url_error(err: url::ParseError) -> StorageError {
     StorageError::Other(err.to_string())
 }
 
+fn parse_base_url(base_url: &str) -> Result<Url, HTTPStoreCreateError> {
+    Url::from_str(base_url).map_err(|_| HTTPStoreCreateError::InvalidBaseURL(base_url.into()))
+}
+
+/// Maps a [`StoreKey`] to a HTTP [`Url`] under `base_url`.
+fn key_to_url(base_url: &Url, key: &StoreKey) -> Result<Url, url::ParseError> {
+    let mut url = base_url.as_str().to_string();
+    if !key.as_str().is_empty() {
+        url += ("/".to_string() + key.as_str().strip_prefix('/').unwrap_or(key.as_str())).as_str();
+    }
+    Url::parse(&url)
+}
+
+/// The uncompressed `ACCEPT_ENCODING` of size and range requests.
+///
+/// The content length and byte ranges of a response refer to its encoded representation, so these requests must not be compressed.
+/// Responses to other requests are compressed if supported by the server and enabled by the `gzip` or `zstd` features.
+const IDENTITY: HeaderValue = HeaderValue::from_static("identity");
+
+/// Returns true if the `status` of the response to a `GET` request indicates the value was found.
+fn get_response_found(status: StatusCode) -> Result<bool, StorageError> {
+    match status {
+        StatusCode::OK => Ok(true),
+        StatusCode::NOT_FOUND => Ok(false),
+        _ => Err(StorageError::from(format!(
+            "http unexpected status code: {status}"
+        ))),
+    }
+}
+
+/// Returns the response to a `GET` request.
+fn get_response(status: StatusCode, bytes: Bytes) -> Result<MaybeBytes, StorageError> {
+    Ok(get_response_found(status)?.then_some(bytes))
+}
+
+/// Returns the header of a conditional request for a value that does not match `validator`.
+fn conditional_header(validator: &StoreValueValidator) -> Option<(HeaderName, HeaderValue)> {
+    let (name, value) = match validator {
+        StoreValueValidator::EntityTag(etag) => (IF_NONE_MATCH, etag),
+        StoreValueValidator::LastModified(last_modified) => (IF_MODIFIED_SINCE, last_modified),
+        _ => return None,
+    };
+    Some((name, HeaderValue::from_str(value).ok()?))
+}
+
+/// Returns the response to a conditional `GET` request.
+///
+/// The `ETAG` of the response is preferred as its validator, otherwise its `LAST_MODIFIED` date.
+fn get_if_modified_response(
+    status: StatusCode,
+    headers: &HeaderMap,
+    bytes: Bytes,
+) -> Result<MaybeModifiedBytes, StorageError> {
+    if status == StatusCode::NOT_MODIFIED {
+        return Ok(MaybeModifiedBytes::NotModified);
+    }
+    let header_str = |name| {
+        headers
+            .get(name)
+            .and_then(|value: &HeaderValue| value.to_str().ok())
+            .map(str::to_string)
+    };
+    let validator = header_str(ETAG)
+        .map(StoreValueValidator::EntityTag)
+        .or_else(|| header_str(LAST_MODIFIED).map(StoreValueValidator::LastModified));
+    let value = get_response(status, bytes)?;
+    Ok(MaybeModifiedBytes::Modified(
+        value.clone(),
+        value.and(validator),
+    ))
+}
+
+/// Send a blocking request, retrying transient errors and refreshing credentials and retrying once if they are rejected.
+fn send_blocking(
+    credential: &HTTPCredential,
+    retry: &HTTPRetry,
+    request: impl Fn() -> reqwest::blocking::RequestBuilder,
+) -> Result<reqwest::blocking::Response, StorageError> {
+    let send = || {
+        let mut retries = 0;
+        loop {
+            let mut request = request();
+            if let Some(authorization) = credential.authorization() {
+                request = request.header(AUTHORIZATION, authorization);
+            }
+            let response = request.send();
+            let result = response.as_ref().map(reqwest::blocking::Response::status);
+            match retry.backoff(retries, result) {
+                Some(backoff) => std::thread::sleep(backoff),
+                None => return response.map_err(handle_reqwest_error),
+            }
+            retries += 1;
+        }
+    };
+    let response = send()?;
+    if credential.refresh(response.status())? {
+        send()
+    } else {
+        Ok(response)
+    }
+}
+
+/// Collect the bytes of each requested byte range, which must all be present.
+fn collect_byte_ranges(byte_ranges: Vec<Option<Bytes>>) -> Result<Vec<Bytes>, StorageError> {
+    byte_ranges
+        .into_iter()
+        .map(|bytes| {
+            bytes.ok_or_else(|| {
+                StorageError::from(
+                    "http partial content response did not include all requested byte ranges",
+                )
+            })
+        })
+        .collect()
+}
+
+/// Returns the size from the response to a `HEAD` request.
+fn size_response(status: StatusCode, headers: &HeaderMap) -> Result<Option<u64>, StorageError> {
+    match status {
+        StatusCode::OK => {
+            let length = headers
+                .get(CONTENT_LENGTH)
+                .and_then(|header_value| header_value.to_str().ok())
+                .and_then(|header_str| u64::from_str(header_str).ok())
+                .ok_or_else(|| StorageError::from("content length response is invalid"))?;
+            Ok(Some(length))
+        }
+        StatusCode::NOT_FOUND => Ok(None),
+        _ => Err(StorageError::from(format!(
+            "http size_key has status code {status}"
+        ))),
+    }
+}
+
 impl HTTPStore {
     /// Create a new HTTP store at a given `base_url`.
     ///
@@ -50,19 +685,63 @@ impl HTTPStore {
     ///
     /// Returns a [`HTTPStoreCreateError`] if `base_url` is not a valid URL.
     pub fn new(base_url: &str) -> Result<Self, HTTPStoreCreateError> {
-        let base_url = Url::from_str(base_url)
-            .map_err(|_| HTTPStoreCreateError::InvalidBaseURL(base_url.into()))?;
-        let client = reqwest::blocking::Client::new();
+        Self::new_with_options(base_url, &HTTPStoreOptions::default())
+    }
+
+    /// Create a new HTTP store at a given `base_url` with a client configured by `options`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`HTTPStoreCreateError`] if `base_url` is not a valid URL or the client cannot be created.
+    pub fn new_with_options(
+        base_url: &str,
+        options: &HTTPStoreOptions,
+    ) -> Result<Self, HTTPStoreCreateError> {
+        let base_url = parse_base_url(base_url)?;
+        let client = options.blocking_client()?;
         Ok(Self {
             base_url,
             batch_range_requests: true,
             client,
+            credential: options.credential(),
+            retry: options.retry.clone(),
+            segmented_download: options.segmented_download,
+            get_many_concurrency: options.get_many_concurrency_or_default(),
+        })
+    }
+
+    /// Create a new HTTP store at a given `base_url` with an existing `client`.
+    ///
+    /// Clones of a [`reqwest::blocking::Client`] share a connection pool, so a client can be shared by many stores.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`HTTPStoreCreateError`] if `base_url` is not a valid URL.
+    pub fn new_with_client(
+        base_url: &str,
+        client: reqwest::blocking::Client,
+    ) -> Result<Self, HTTPStoreCreateError> {
+        Ok(Self {
+            base_url: parse_base_url(base_url)?,
+            batch_range_requests: true,
+            client,
+            credential: HTTPCredential::default(),
+            retry: HTTPRetry::default(),
+            segmented_download: None,
+            get_many_concurrency: DEFAULT_GET_MANY_CONCURRENCY,
         })
     }
 
+    /// Return the client.
+    #[must_use]
+    pub const fn client(&self) -> &reqwest::blocking::Client {
+        &self.client
+    }
+
     /// Set whether to batch range requests.
     ///
     /// Defaults to true.
+    /// Byte ranges missing from the response to a batched range request are requested individually.
     /// Some servers do not fully support multipart ranges and might return an entire resource given such a request.
     /// It may be preferable to disable batched range requests in this case, so that each range request is a single part range.
     pub fn set_batch_range_requests(&mut self, batch_range_requests: bool) {
@@ -75,29 +754,111 @@ impl HTTPStore {
     ///
     /// Returns an error if the URL is invalid.
     pub fn key_to_url(&self, key: &StoreKey) -> Result<Url, url::ParseError> {
-        let mut url = self.base_url.as_str().to_string();
-        if !key.as_str().is_empty() {
-            url +=
-                ("/".to_string() + key.as_str().strip_prefix('/').unwrap_or(key.as_str())).as_str();
-        }
-        Url::parse(&url)
+        key_to_url(&self.base_url, key)
+    }
+
+    /// Send a request, retrying transient errors and refreshing credentials and retrying once if they are rejected.
+    fn send(
+        &self,
+        request: impl Fn() -> reqwest::blocking::RequestBuilder,
+    ) -> Result<reqwest::blocking::Response, StorageError> {
+        send_blocking(&self.credential, &self.retry, request)
+    }
+
+    /// Request `byte_ranges` of the value at `url` with `size`.
+    fn get_byte_ranges(
+        &self,
+        url: &Url,
+        byte_ranges: &[ByteRange],
+        size: u64,
+    ) -> Result<Vec<Option<Bytes>>, StorageError> {
+        let range = range_header(byte_ranges, size);
+        let response = self.send(|| {
+            self.client
+                .get(url.clone())
+                .header(RANGE, range.clone())
+                .header(ACCEPT_ENCODING, IDENTITY)
+        })?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let bytes = response.bytes().map_err(handle_reqwest_error)?;
+        byte_ranges_response(status, &headers, bytes, byte_ranges, size)
+    }
+
+    /// Retrieve the segments with `byte_ranges` of the value at `url` with `size` concurrently.
+    fn get_segmented(
+        &self,
+        url: &Url,
+        byte_ranges: &[ByteRange],
+        size: u64,
+        concurrency: usize,
+    ) -> Result<Bytes, StorageError> {
+        let segments = concurrent_map(byte_ranges, concurrency, |byte_range| {
+            let mut segment = self
+                .get_byte_ranges(url, std::slice::from_ref(byte_range), size)
+                .and_then(collect_byte_ranges)?;
+            Ok(segment.remove(0))
+        })?;
+        concat_segments(&segments, size)
     }
 }
 
 impl ReadableStorageTraits for HTTPStore {
     fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
         let url = self.key_to_url(key).map_err(handle_url_error)?;
-        let response = self.client.get(url).send().map_err(handle_reqwest_error)?;
-        match response.status() {
-            StatusCode::OK => Ok(Some(response.bytes().map_err(handle_reqwest_error)?)),
-            StatusCode::NOT_FOUND => Ok(None),
-            _ => Err(StorageError::from(format!(
-                "http unexpected status code: {}",
-                response.status()
-            ))),
+        if let Some(segmented_download) = self.segmented_download {
+            let Some(size) = self.size_key(key)? else {
+                return Ok(None);
+            };
+            if let Some(byte_ranges) = segmented_download.byte_ranges(size) {
+                return self
+                    .get_segmented(&url, &byte_ranges, size, segmented_download.concurrency)
+                    .map(Some);
+            }
+        }
+        let response = self.send(|| self.client.get(url.clone()))?;
+        let status = response.status();
+        get_response(status, response.bytes().map_err(handle_reqwest_error)?)
+    }
+
+    fn get_many(&self, keys: &[StoreKey]) -> Result<Vec<MaybeBytes>, StorageError> {
+        concurrent_map(keys, self.get_many_concurrency, |key| self.get(key))
+    }
+
+    /// Return a reader of the body of the response to a `GET` request of the value at `key`.
+    ///
+    /// The body is read as it is received, so a segmented download is not used.
+    fn get_reader(&self, key: &StoreKey) -> Result<Option<StoreValueReader<'_>>, StorageError> {
+        let url = self.key_to_url(key).map_err(handle_url_error)?;
+        let response = self.send(|| self.client.get(url.clone()))?;
+        if get_response_found(response.status())? {
+            Ok(Some(Box::new(response)))
+        } else {
+            Ok(None)
         }
     }
 
+    fn get_if_modified(
+        &self,
+        key: &StoreKey,
+        validator: Option<&StoreValueValidator>,
+    ) -> Result<MaybeModifiedBytes, StorageError> {
+        let url = self.key_to_url(key).map_err(handle_url_error)?;
+        let conditional_header = validator.and_then(conditional_header);
+        let response = self.send(|| {
+            let request = self.client.get(url.clone());
+            if let Some((name, value)) = &conditional_header {
+                request.header(name, value)
+            } else {
+                request
+            }
+        })?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let bytes = response.bytes().map_err(handle_reqwest_error)?;
+        get_if_modified_response(status, &headers, bytes)
+    }
+
     fn get_partial_values_key(
         &self,
         key: &StoreKey,
@@ -107,80 +868,30 @@ impl ReadableStorageTraits for HTTPStore {
         let Some(size) = self.size_key(key)? else {
             return Ok(None);
         };
-        let bytes_strs = byte_ranges
-            .iter()
-            .map(|byte_range| format!("{}-{}", byte_range.start(size), byte_range.end(size) - 1))
-            .join(", ");
-
-        let range = HeaderValue::from_str(&format!("bytes={bytes_strs}")).unwrap();
-        let response = self
-            .client
-            .get(url)
-            .header(RANGE, range)
-            .send()
-            .map_err(handle_reqwest_error)?;
-
-        match response.status() {
-            StatusCode::NOT_FOUND => Err(StorageError::from("the http server returned a NOT FOUND status for the byte range request, but returned a non zero size for CONTENT_LENGTH")),
-            StatusCode::PARTIAL_CONTENT => {
-                // TODO: Gracefully handle a response from the server which does not include all requested by ranges
-                let mut bytes = response.bytes().map_err(handle_reqwest_error)?;
-                if bytes.len() as u64
-                    == byte_ranges
-                        .iter()
-                        .map(|byte_range| byte_range.length(size))
-                        .sum::<u64>()
-                {
-                    let mut out = Vec::with_capacity(byte_ranges.len());
-                    for byte_range in byte_ranges {
-                        let bytes_range =
-                            bytes.split_to(usize::try_from(byte_range.length(size)).unwrap());
-                        out.push(bytes_range);
-                    }
-                    Ok(Some(out))
-                } else {
-                    Err(StorageError::from(
-                        "http partial content response did not include all requested byte ranges",
-                    ))
-                }
-            }
-            StatusCode::OK => {
-                // Received all bytes
-                let bytes = response.bytes().map_err(handle_reqwest_error)?;
-                let mut out = Vec::with_capacity(byte_ranges.len());
-                for byte_range in byte_ranges {
-                    let start = usize::try_from(byte_range.start(size)).unwrap();
-                    let end = usize::try_from(byte_range.end(size)).unwrap();
-                    out.push(bytes.slice(start..end));
-                }
-                Ok(Some(out))
+        let mut out = if self.batch_range_requests {
+            self.get_byte_ranges(&url, byte_ranges, size)?
+        } else {
+            vec![None; byte_ranges.len()]
+        };
+        for (byte_range, bytes) in byte_ranges.iter().zip(&mut out) {
+            if bytes.is_none() {
+                // Request byte ranges missing from a batched response individually
+                *bytes = self
+                    .get_byte_ranges(&url, std::slice::from_ref(byte_range), size)?
+                    .remove(0);
             }
-            _ => Err(StorageError::from(format!(
-                "the http server responded with status {} for the byte range request",
-                response.status()
-            ))),
         }
+        collect_byte_ranges(out).map(Some)
     }
 
     fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
         let url = self.key_to_url(key).map_err(handle_url_error)?;
-        let response = self.client.head(url).send().map_err(handle_reqwest_error)?;
-        match response.status() {
-            StatusCode::OK => {
-                let length = response
-                    .headers()
-                    .get(CONTENT_LENGTH)
-                    .and_then(|header_value| header_value.to_str().ok())
-                    .and_then(|header_str| u64::from_str(header_str).ok())
-                    .ok_or_else(|| StorageError::from("content length response is invalid"))?;
-                Ok(Some(length))
-            }
-            StatusCode::NOT_FOUND => Ok(None),
-            _ => Err(StorageError::from(format!(
-                "http size_key has status code {}",
-                response.status()
-            ))),
-        }
+        let response = self.send(|| {
+            self.client
+                .head(url.clone())
+                .header(ACCEPT_ENCODING, IDENTITY)
+        })?;
+        size_response(response.status(), response.headers())
     }
 }
 
@@ -193,6 +904,12 @@ pub enum HTTPStoreCreateError {
     /// The URL is not valid.
     #[error("base URL {0} is not valid")]
     InvalidBaseURL(String),
+    /// A header value is not valid.
+    #[error("the value of header {0} is not valid")]
+    InvalidHeader(String),
+    /// The HTTP client could not be created.
+    #[error(transparent)]
+    ClientError(#[from] reqwest::Error),
 }
 
 #[cfg(test)]
@@ -210,4 +927,450 @@ mod tests {
         zarrs_storage::store_test::store_read(&store)?;
         Ok(())
     }
+
+    #[test]
+    fn http_store_options() -> Result<(), Box<dyn Error>> {
+        let mut options = HTTPStoreOptions::default();
+        options
+            .timeout(Duration::from_secs(10))
+            .connect_timeout(Duration::from_secs(1))
+            .pool_idle_timeout(Duration::from_secs(60))
+            .pool_max_idle_per_host(4)
+            .tcp_keepalive(Duration::from_secs(30));
+        let store = HTTPStore::new_with_options("http://localhost:8080/store", &options)?;
+        assert_eq!(
+            store.key_to_url(&"a/b".try_into()?)?.as_str(),
+            "http://localhost:8080/store/a/b"
+        );
+        let shared =
+            HTTPStore::new_with_client("http://localhost:8080/other", store.client().clone())?;
+        assert_eq!(
+            shared.key_to_url(&"c".try_into()?)?.as_str(),
+            "http://localhost:8080/other/c"
+        );
+        assert!(HTTPStore::new("not a url").is_err());
+        Ok(())
+    }
+
+    /// Serve `responses` requests on a local port, responding `OK` with the request headers if `authorization` is present and `UNAUTHORIZED` otherwise.
+    fn serve_authorized(authorization: &'static str, responses: usize) -> String {
+        use std::io::{BufRead, BufReader, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().take(responses) {
+                let mut stream = stream.unwrap();
+                let mut headers = String::new();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    headers += &line.to_lowercase();
+                }
+                let response = if headers.contains(authorization) {
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{headers}",
+                        headers.len()
+                    )
+                } else {
+                    "HTTP/1.1 401 Unauthorized\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                        .to_string()
+                };
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        format!("http://{address}")
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn http_store_authentication() -> Result<(), Box<dyn Error>> {
+        let url = serve_authorized("authorization: bearer token", 1);
+        let mut options = HTTPStoreOptions::default();
+        options.bearer_token("token").header(
+            HeaderName::from_static("x-api-key"),
+            HeaderValue::from_static("key"),
+        );
+        let store = HTTPStore::new_with_options(&url, &options)?;
+        let headers = store.get(&"zarr.json".try_into()?)?.unwrap();
+        assert!(String::from_utf8(headers.to_vec())?.contains("x-api-key: key"));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn http_store_credential_refresh() -> Result<(), Box<dyn Error>> {
+        let url = serve_authorized("authorization: bearer renewed", 3);
+        let refreshes = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let mut options = HTTPStoreOptions::default();
+        options
+            .bearer_token("expired")
+            .credential_refresh(Arc::new({
+                let refreshes = refreshes.clone();
+                move || {
+                    refreshes.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    Ok(HeaderValue::from_static("Bearer renewed"))
+                }
+            }));
+        let store = HTTPStore::new_with_options(&url, &options)?;
+        assert!(store.get(&"zarr.json".try_into()?)?.is_some());
+        assert!(store.get(&"zarr.json".try_into()?)?.is_some());
+        assert_eq!(refreshes.load(std::sync::atomic::Ordering::Relaxed), 1);
+        Ok(())
+    }
+
+    /// Serve `value` with an `ETAG`, responding with `NOT_MODIFIED` to requests with a matching `IF_NONE_MATCH` header.
+    fn serve_etag(value: &'static str, responses: usize) -> String {
+        use std::io::{BufRead, BufReader, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().take(responses) {
+                let mut stream = stream.unwrap();
+                let mut headers = String::new();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    headers += &line.to_lowercase();
+                }
+                let response = if headers.contains("if-none-match: \"v1\"") {
+                    "HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n"
+                        .to_string()
+                } else {
+                    format!(
+                        "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{value}",
+                        value.len()
+                    )
+                };
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        format!("http://{address}")
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn http_store_get_if_modified() -> Result<(), Box<dyn Error>> {
+        let url = serve_etag("{}", 4);
+        let store = Arc::new(HTTPStore::new(&url)?);
+        let key = "zarr.json".try_into()?;
+        let etag = StoreValueValidator::EntityTag("\"v1\"".to_string());
+        assert_eq!(
+            store.get_if_modified(&key, None)?,
+            MaybeModifiedBytes::Modified(Some(b"{}".to_vec().into()), Some(etag.clone()))
+        );
+        assert_eq!(
+            store.get_if_modified(&key, Some(&etag))?,
+            MaybeModifiedBytes::NotModified
+        );
+
+        // Expired cached values are revalidated
+        let cache = zarrs_storage::storage_adapter::cache::CacheStorageAdapter::new(store)
+            .with_ttl(Duration::ZERO);
+        assert_eq!(cache.get(&key)?.unwrap(), b"{}".as_slice());
+        std::thread::sleep(Duration::from_millis(1));
+        assert_eq!(cache.get(&key)?.unwrap(), b"{}".as_slice());
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+        Ok(())
+    }
+
+    /// Serve a sequence of raw `responses`, one per connection.
+    fn serve_responses(responses: Vec<&'static str>) -> String {
+        use std::io::{BufRead, BufReader, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for (stream, response) in listener.incoming().zip(responses) {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                }
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        format!("http://{address}")
+    }
+
+    const UNAVAILABLE: &str =
+        "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+    const REDIRECT: &str =
+        "HTTP/1.1 302 Found\r\nLocation: /b\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+    const OK: &str = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok";
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn http_store_retry() -> Result<(), Box<dyn Error>> {
+        let mut options = HTTPStoreOptions::default();
+        options
+            .max_retries(2)
+            .retry_backoff(Duration::from_millis(1));
+        let url = serve_responses(vec![UNAVAILABLE, UNAVAILABLE, OK]);
+        let store = HTTPStore::new_with_options(&url, &options)?;
+        assert_eq!(store.get(&"a".try_into()?)?.unwrap(), b"ok".as_slice());
+
+        let url = serve_responses(vec![UNAVAILABLE, UNAVAILABLE, UNAVAILABLE, OK]);
+        let store = HTTPStore::new_with_options(&url, &options)?;
+        assert!(store.get(&"a".try_into()?).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn http_store_retry_backoff() {
+        let mut options = HTTPStoreOptions::default();
+        options
+            .max_retries(10)
+            .retry_backoff(Duration::from_secs(1))
+            .max_retry_backoff(Duration::from_secs(5));
+        let backoff = |retries| options.retry.backoff(retries, Ok(StatusCode::BAD_GATEWAY));
+        assert_eq!(backoff(0), Some(Duration::from_secs(1)));
+        assert_eq!(backoff(2), Some(Duration::from_secs(4)));
+        assert_eq!(backoff(3), Some(Duration::from_secs(5)));
+        assert_eq!(backoff(10), None);
+        assert_eq!(options.retry.backoff(0, Ok(StatusCode::NOT_FOUND)), None);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn http_store_redirect_policy() -> Result<(), Box<dyn Error>> {
+        let url = serve_responses(vec![REDIRECT, OK]);
+        let store = HTTPStore::new(&url)?;
+        assert_eq!(store.get(&"a".try_into()?)?.unwrap(), b"ok".as_slice());
+
+        let mut options = HTTPStoreOptions::default();
+        options.redirect_policy(HTTPRedirectPolicy::None);
+        let url = serve_responses(vec![REDIRECT, OK]);
+        let store = HTTPStore::new_with_options(&url, &options)?;
+        assert!(store.get(&"a".try_into()?).is_err());
+
+        options.redirect_policy(HTTPRedirectPolicy::SameOrigin(1));
+        let url = serve_responses(vec![REDIRECT, REDIRECT, OK]);
+        let store = HTTPStore::new_with_options(&url, &options)?;
+        assert!(store.get(&"a".try_into()?).is_err());
+        Ok(())
+    }
+
+    /// Serve `value`, responding to range requests with only the first requested byte range.
+    pub(crate) fn serve_first_byte_range(value: &'static [u8], responses: usize) -> String {
+        use std::io::{BufRead, BufReader, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().take(responses) {
+                let mut stream = stream.unwrap();
+                let mut range = None;
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some(ranges) = line.to_lowercase().strip_prefix("range: bytes=") {
+                        let (start, end) =
+                            ranges.split(',').next().unwrap().split_once('-').unwrap();
+                        range = Some((
+                            start.trim().parse::<usize>().unwrap(),
+                            end.trim().parse::<usize>().unwrap(),
+                        ));
+                    }
+                }
+                let mut response = if let Some((start, end)) = range {
+                    format!(
+                        "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\nContent-Range: bytes {start}-{end}/{}\r\nConnection: close\r\n\r\n",
+                        end - start + 1,
+                        value.len()
+                    )
+                    .into_bytes()
+                } else {
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        value.len()
+                    )
+                    .into_bytes()
+                };
+                if let Some((start, end)) = range {
+                    response.extend_from_slice(&value[start..=end]);
+                }
+                stream.write_all(&response).unwrap();
+            }
+        });
+        format!("http://{address}")
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn http_store_byte_ranges_subset() -> Result<(), Box<dyn Error>> {
+        // A size request, a batched range request, then a range request for the missing byte range
+        let url = serve_first_byte_range(b"0123456789", 3);
+        let store = HTTPStore::new(&url)?;
+        let byte_ranges = [ByteRange::FromStart(1, Some(2)), ByteRange::Suffix(3)];
+        assert_eq!(
+            store.get_partial_values_key(&"a".try_into()?, &byte_ranges)?,
+            Some(vec![b"12".to_vec().into(), b"789".to_vec().into()])
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn http_store_segmented_download() -> Result<(), Box<dyn Error>> {
+        // A size request, then a range request for each segment
+        let url = serve_first_byte_range(b"0123456789", 5);
+        let mut options = HTTPStoreOptions::default();
+        options.segmented_download(3, 2);
+        let store = HTTPStore::new_with_options(&url, &options)?;
+        assert_eq!(
+            store.get(&"a".try_into()?)?.unwrap(),
+            b"0123456789".as_slice()
+        );
+        Ok(())
+    }
+
+    /// Serve `responses` requests on a local port, responding with the value of the requested key in `values` or `NOT_FOUND`.
+    pub(crate) fn serve_values(
+        values: &'static [(&'static str, &'static str)],
+        responses: usize,
+    ) -> String {
+        use std::io::{BufRead, BufReader, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().take(responses) {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request = String::new();
+                reader.read_line(&mut request).unwrap();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                }
+                let path = request.split_whitespace().nth(1).unwrap();
+                let response = values
+                    .iter()
+                    .find(|(key, _)| path.trim_start_matches('/') == *key)
+                    .map_or_else(
+                        || "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string(),
+                        |(_, value)| {
+                            format!(
+                                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{value}",
+                                value.len()
+                            )
+                        },
+                    );
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        format!("http://{address}")
+    }
+
+    pub(crate) const GET_MANY_VALUES: &[(&str, &str)] = &[("a", "0"), ("b", "12"), ("c/d", "345")];
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn http_store_get_many() -> Result<(), Box<dyn Error>> {
+        let url = serve_values(GET_MANY_VALUES, 4);
+        let mut options = HTTPStoreOptions::default();
+        options.get_many_concurrency(2);
+        let store = HTTPStore::new_with_options(&url, &options)?;
+        let keys = [
+            "c/d".try_into()?,
+            "a".try_into()?,
+            "e".try_into()?,
+            "b".try_into()?,
+        ];
+        assert_eq!(
+            store.get_many(&keys)?,
+            [
+                Some(b"345".as_slice().into()),
+                Some(b"0".as_slice().into()),
+                None,
+                Some(b"12".as_slice().into())
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn http_store_get_reader() -> Result<(), Box<dyn Error>> {
+        let url = serve_values(GET_MANY_VALUES, 2);
+        let store = HTTPStore::new(&url)?;
+        let mut value = String::new();
+        store
+            .get_reader(&"c/d".try_into()?)?
+            .unwrap()
+            .read_to_string(&mut value)?;
+        assert_eq!(value, "345");
+        assert!(store.get_reader(&"e".try_into()?)?.is_none());
+        Ok(())
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn http_store_gzip() -> Result<(), Box<dyn Error>> {
+        use std::io::{BufRead, BufReader, Write};
+        const VALUE: &[u8] = br#"{"a":1}"#;
+        const VALUE_GZIP: &[u8] = &[
+            31, 139, 8, 0, 0, 0, 0, 0, 2, 3, 171, 86, 74, 84, 178, 50, 172, 5, 0, 175, 172, 27, 86,
+            7, 0, 0, 0,
+        ];
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().take(2) {
+                let mut stream = stream.unwrap();
+                let mut headers = String::new();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    headers += &line.to_lowercase();
+                }
+                let accept_encoding: Vec<&str> = headers
+                    .lines()
+                    .find_map(|line| line.strip_prefix("accept-encoding:"))
+                    .unwrap()
+                    .split(',')
+                    .map(str::trim)
+                    .collect();
+                let (encoding, length, body) = if headers.starts_with("head") {
+                    assert_eq!(accept_encoding, ["identity"]);
+                    ("identity", VALUE.len(), [].as_slice())
+                } else {
+                    assert!(accept_encoding.contains(&"gzip"));
+                    ("gzip", VALUE_GZIP.len(), VALUE_GZIP)
+                };
+                let mut response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Encoding: {encoding}\r\nContent-Length: {length}\r\nConnection: close\r\n\r\n"
+                )
+                .into_bytes();
+                response.extend_from_slice(body);
+                stream.write_all(&response).unwrap();
+            }
+        });
+        let store = HTTPStore::new(&format!("http://{address}"))?;
+        let key = "zarr.json".try_into()?;
+        assert_eq!(store.size_key(&key)?, Some(VALUE.len() as u64));
+        assert_eq!(store.get(&key)?.unwrap(), VALUE);
+        Ok(())
+    }
 }
@@ -0,0 +1,387 @@
+//! A store of presigned URLs.
+
+use std::sync::Arc;
+
+use reqwest::{
+    header::{ACCEPT_ENCODING, RANGE},
+    Method, StatusCode, Url,
+};
+use zarrs_storage::{
+    byte_range::ByteRange, store_set_partial_values, Bytes, MaybeBytes, ReadableStorageTraits,
+    StorageError, StoreKey, StoreKeyOffsetValue, StorePrefix, WritableStorageTraits,
+};
+
+use crate::{
+    byte_ranges_response::{byte_ranges_response, range_header},
+    collect_byte_ranges, concurrent_map, get_response, handle_reqwest_error, send_blocking,
+    size_response, HTTPCredential, HTTPRetry, HTTPStoreCreateError, HTTPStoreOptions,
+    DEFAULT_GET_MANY_CONCURRENCY, IDENTITY,
+};
+
+/// A function returning a presigned URL for a HTTP [`Method`] (`GET`, `HEAD`, `PUT`, or `DELETE`) on a [`StoreKey`].
+///
+/// See [`PresignedURLStore`].
+pub type HTTPPresign = Arc<dyn Fn(&StoreKey, &Method) -> Result<Url, StorageError> + Send + Sync>;
+
+/// A synchronous store of presigned URLs.
+///
+/// Each request is sent to a URL returned by a [`HTTPPresign`] function for the key and HTTP method of the request.
+/// This supports S3-compatible presigned URLs and other capability URLs, so that clients (e.g. browsers and edge workers) can access a store without embedding credentials.
+///
+/// The store is read-only unless created [`writable`](PresignedURLStore::writable), in which case values are written with `PUT` and erased with `DELETE` requests.
+/// Byte ranges are requested individually, since S3-compatible stores do not support multipart range requests.
+/// A prefix cannot be erased, since presigned URLs cannot list keys.
+///
+/// ```rust
+/// # use std::sync::Arc;
+/// use zarrs_http::PresignedURLStore;
+/// let store = PresignedURLStore::new(Arc::new(|key, method| {
+///     // Request a presigned URL from a capability-based service
+///     Ok(format!("https://bucket.s3.amazonaws.com/{key}?method={method}&X-Amz-Signature=...").parse().unwrap())
+/// }))?
+/// .writable();
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct PresignedURLStore {
+    presign: HTTPPresign,
+    writable: bool,
+    client: reqwest::blocking::Client,
+    credential: HTTPCredential,
+    retry: HTTPRetry,
+    get_many_concurrency: usize,
+}
+
+impl core::fmt::Debug for PresignedURLStore {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PresignedURLStore")
+            .field("writable", &self.writable)
+            .field("client", &self.client)
+            .field("retry", &self.retry)
+            .field("get_many_concurrency", &self.get_many_concurrency)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns the response to a `PUT` request.
+pub(crate) fn put_response(status: StatusCode) -> Result<(), StorageError> {
+    if status.is_success() {
+        Ok(())
+    } else {
+        Err(StorageError::from(format!(
+            "http put has status code {status}"
+        )))
+    }
+}
+
+/// Returns the response to a `DELETE` request, which succeeds if the key does not exist.
+pub(crate) fn delete_response(status: StatusCode) -> Result<(), StorageError> {
+    if status.is_success() || status == StatusCode::NOT_FOUND {
+        Ok(())
+    } else {
+        Err(StorageError::from(format!(
+            "http delete has status code {status}"
+        )))
+    }
+}
+
+/// Returns the error of erasing a prefix of a presigned URL store.
+pub(crate) fn erase_prefix_unsupported() -> StorageError {
+    StorageError::Unsupported("a presigned URL store cannot erase a prefix".to_string())
+}
+
+impl PresignedURLStore {
+    /// Create a new read-only presigned URL store with URLs returned by `presign`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`HTTPStoreCreateError`] if the client cannot be created.
+    pub fn new(presign: HTTPPresign) -> Result<Self, HTTPStoreCreateError> {
+        Self::new_with_options(presign, &HTTPStoreOptions::default())
+    }
+
+    /// Create a new read-only presigned URL store with URLs returned by `presign` and a client configured by `options`.
+    ///
+    /// Authentication options are not usually needed, since presigned URLs embed their authorisation.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`HTTPStoreCreateError`] if the client cannot be created.
+    pub fn new_with_options(
+        presign: HTTPPresign,
+        options: &HTTPStoreOptions,
+    ) -> Result<Self, HTTPStoreCreateError> {
+        Ok(Self {
+            presign,
+            writable: false,
+            client: options.blocking_client()?,
+            credential: options.credential(),
+            retry: options.retry.clone(),
+            get_many_concurrency: options.get_many_concurrency_or_default(),
+        })
+    }
+
+    /// Create a new read-only presigned URL store with URLs returned by `presign` and an existing `client`.
+    #[must_use]
+    pub fn new_with_client(presign: HTTPPresign, client: reqwest::blocking::Client) -> Self {
+        Self {
+            presign,
+            writable: false,
+            client,
+            credential: HTTPCredential::default(),
+            retry: HTTPRetry::default(),
+            get_many_concurrency: DEFAULT_GET_MANY_CONCURRENCY,
+        }
+    }
+
+    /// Makes the store writable, with values written with `PUT` and erased with `DELETE` requests.
+    #[must_use]
+    pub const fn writable(mut self) -> Self {
+        self.writable = true;
+        self
+    }
+
+    /// Return the client.
+    #[must_use]
+    pub const fn client(&self) -> &reqwest::blocking::Client {
+        &self.client
+    }
+
+    /// Returns the presigned URL of a `method` on `key`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URL cannot be presigned.
+    pub fn presign(&self, key: &StoreKey, method: &Method) -> Result<Url, StorageError> {
+        (self.presign)(key, method)
+    }
+
+    fn send(
+        &self,
+        request: impl Fn() -> reqwest::blocking::RequestBuilder,
+    ) -> Result<reqwest::blocking::Response, StorageError> {
+        send_blocking(&self.credential, &self.retry, request)
+    }
+}
+
+impl ReadableStorageTraits for PresignedURLStore {
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        let url = self.presign(key, &Method::GET)?;
+        let response = self.send(|| self.client.get(url.clone()))?;
+        let status = response.status();
+        get_response(status, response.bytes().map_err(handle_reqwest_error)?)
+    }
+
+    fn get_many(&self, keys: &[StoreKey]) -> Result<Vec<MaybeBytes>, StorageError> {
+        concurrent_map(keys, self.get_many_concurrency, |key| self.get(key))
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        let Some(size) = self.size_key(key)? else {
+            return Ok(None);
+        };
+        let url = self.presign(key, &Method::GET)?;
+        let mut out = Vec::with_capacity(byte_ranges.len());
+        for byte_range in byte_ranges {
+            let byte_range = std::slice::from_ref(byte_range);
+            let range = range_header(byte_range, size);
+            let response = self.send(|| {
+                self.client
+                    .get(url.clone())
+                    .header(RANGE, range.clone())
+                    .header(ACCEPT_ENCODING, IDENTITY)
+            })?;
+            let status = response.status();
+            let headers = response.headers().clone();
+            let bytes = response.bytes().map_err(handle_reqwest_error)?;
+            let bytes = byte_ranges_response(status, &headers, bytes, byte_range, size)?;
+            out.extend(collect_byte_ranges(bytes)?);
+        }
+        Ok(Some(out))
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        let url = self.presign(key, &Method::HEAD)?;
+        let response = self.send(|| {
+            self.client
+                .head(url.clone())
+                .header(ACCEPT_ENCODING, IDENTITY)
+        })?;
+        size_response(response.status(), response.headers())
+    }
+}
+
+impl WritableStorageTraits for PresignedURLStore {
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        if !self.writable {
+            return Err(StorageError::ReadOnly);
+        }
+        let url = self.presign(key, &Method::PUT)?;
+        let response = self.send(|| self.client.put(url.clone()).body(value.clone()))?;
+        put_response(response.status())
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        if !self.writable {
+            return Err(StorageError::ReadOnly);
+        }
+        store_set_partial_values(self, key_offset_values)
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        if !self.writable {
+            return Err(StorageError::ReadOnly);
+        }
+        let url = self.presign(key, &Method::DELETE)?;
+        let response = self.send(|| self.client.delete(url.clone()))?;
+        delete_response(response.status())
+    }
+
+    fn erase_prefix(&self, _prefix: &StorePrefix) -> Result<(), StorageError> {
+        if !self.writable {
+            return Err(StorageError::ReadOnly);
+        }
+        Err(erase_prefix_unsupported())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::{
+        collections::HashMap,
+        error::Error,
+        io::{BufRead, BufReader, Read, Write},
+    };
+
+    /// Serve an in-memory store of values at URLs "presigned" with a `method` query parameter matching the request method.
+    pub(crate) fn serve_presigned() -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let mut values: HashMap<String, Vec<u8>> = HashMap::new();
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut request_line = request_line.split_whitespace();
+                let method = request_line.next().unwrap().to_string();
+                let (path, query) = request_line.next().unwrap().split_once('?').unwrap();
+                let (mut length, mut range) = (0, None);
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let line = line.trim().to_lowercase();
+                    if line.is_empty() {
+                        break;
+                    }
+                    if let Some(value) = line.strip_prefix("content-length: ") {
+                        length = value.parse().unwrap();
+                    } else if let Some(value) = line.strip_prefix("range: bytes=") {
+                        let (start, end) = value.split_once('-').unwrap();
+                        range = Some((
+                            start.parse::<usize>().unwrap(),
+                            end.parse::<usize>().unwrap(),
+                        ));
+                    }
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+
+                let (status, headers, body) = if query == format!("method={method}") {
+                    match (method.as_str(), values.get(path), range) {
+                        ("PUT", _, _) => {
+                            values.insert(path.to_string(), body);
+                            ("200 OK", String::new(), vec![])
+                        }
+                        ("DELETE", value, _) => {
+                            let status = if value.is_some() {
+                                "204 No Content"
+                            } else {
+                                "404 Not Found"
+                            };
+                            values.remove(path);
+                            (status, String::new(), vec![])
+                        }
+                        ("HEAD", Some(value), _) => (
+                            "200 OK",
+                            format!("Content-Length: {}\r\n", value.len()),
+                            vec![],
+                        ),
+                        ("GET", Some(value), None) => ("200 OK", String::new(), value.clone()),
+                        ("GET", Some(value), Some((_, end))) if end >= value.len() => {
+                            ("416 Range Not Satisfiable", String::new(), vec![])
+                        }
+                        ("GET", Some(value), Some((start, end))) => (
+                            "206 Partial Content",
+                            format!("Content-Range: bytes {start}-{end}/{}\r\n", value.len()),
+                            value[start..=end].to_vec(),
+                        ),
+                        _ => ("404 Not Found", String::new(), vec![]),
+                    }
+                } else {
+                    ("403 Forbidden", String::new(), vec![])
+                };
+                let content_length = if method == "HEAD" {
+                    String::new()
+                } else {
+                    format!("Content-Length: {}\r\n", body.len())
+                };
+                let mut response = format!(
+                    "HTTP/1.1 {status}\r\n{headers}{content_length}Connection: close\r\n\r\n"
+                )
+                .into_bytes();
+                response.extend_from_slice(&body);
+                stream.write_all(&response).unwrap();
+            }
+        });
+        format!("http://{address}")
+    }
+
+    /// Presign URLs of a server started with [`serve_presigned`].
+    pub(crate) fn presign(url: String) -> HTTPPresign {
+        Arc::new(move |key, method| {
+            Url::parse(&format!("{url}/{key}?method={method}"))
+                .map_err(|err| StorageError::Other(err.to_string()))
+        })
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn presigned_url_store() -> Result<(), Box<dyn Error>> {
+        let url = serve_presigned();
+        let store = PresignedURLStore::new(presign(url.clone()))?.writable();
+        store.set(&"a/b".try_into()?, vec![255, 255, 255].into())?;
+        store.set_partial_values(&[StoreKeyOffsetValue::new("a/b".try_into()?, 0, &[0, 1])])?;
+        store.set_partial_values(&[StoreKeyOffsetValue::new("a/b".try_into()?, 2, &[2, 3])])?;
+        store.set(&"a/c".try_into()?, vec![0].into())?;
+        store.set(&"i/j/k".try_into()?, vec![0, 1].into())?;
+        store.set(&"erase".try_into()?, vec![].into())?;
+        store.erase(&"erase".try_into()?)?;
+        store.erase(&"erase".try_into()?)?; // succeeds
+        zarrs_storage::store_test::store_read(&store)?;
+        assert!(store.erase_prefix(&StorePrefix::root()).is_err());
+
+        // Read-only
+        let store = PresignedURLStore::new(presign(url.clone()))?;
+        assert_eq!(store.get(&"a/c".try_into()?)?, Some(vec![0].into()));
+        assert!(matches!(
+            store.set(&"a/c".try_into()?, vec![1].into()),
+            Err(StorageError::ReadOnly)
+        ));
+
+        // Invalid signatures are rejected
+        let store = PresignedURLStore::new(Arc::new(move |key, _method| {
+            Ok(Url::parse(&format!("{url}/{key}?method=GET")).unwrap())
+        }))?;
+        assert!(store.size_key(&"a/c".try_into()?).is_err());
+        Ok(())
+    }
+}
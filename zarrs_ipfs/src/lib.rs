@@ -0,0 +1,687 @@
+//! A read-only [IPFS](https://ipfs.tech/) store for the [`zarrs`](https://docs.rs/zarrs/latest/zarrs/index.html) crate.
+//!
+//! Store keys are resolved under a content identifier (CID) root via either an IPFS path gateway or the RPC API of a local IPFS daemon (e.g. Kubo).
+//! Content under a CID is immutable, so the store is only readable.
+//!
+//! ```rust
+//! # use std::sync::Arc;
+//! use zarrs_storage::ReadableStorage;
+//! use zarrs_ipfs::IpfsStore;
+//!
+//! // Via a gateway
+//! let store: ReadableStorage = Arc::new(IpfsStore::new_gateway(
+//!     "https://ipfs.io",
+//!     "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi/hierarchy.zarr",
+//! )?);
+//!
+//! // Via a local daemon
+//! let store: ReadableStorage = Arc::new(IpfsStore::new_daemon(
+//!     "http://127.0.0.1:5001",
+//!     "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi",
+//! )?);
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! ## Licence
+//! `zarrs_ipfs` is licensed under either of
+//! - the Apache License, Version 2.0 [LICENSE-APACHE](https://docs.rs/crate/zarrs_ipfs/latest/source/LICENCE-APACHE) or <http://www.apache.org/licenses/LICENSE-2.0> or
+//! - the MIT license [LICENSE-MIT](https://docs.rs/crate/zarrs_ipfs/latest/source/LICENCE-MIT) or <http://opensource.org/licenses/MIT>, at your option.
+
+use zarrs_storage::{
+    byte_range::{extract_byte_ranges, ByteRange, InvalidByteRangeError},
+    Bytes, MaybeBytes, ReadableStorageTraits, StorageError, StoreKey,
+};
+
+use reqwest::{
+    blocking::{Client, RequestBuilder, Response},
+    header::{CONTENT_LENGTH, CONTENT_RANGE, RANGE},
+    StatusCode, Url,
+};
+use serde::Deserialize;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// An IPFS endpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IpfsEndpoint {
+    /// An IPFS path gateway (e.g. `https://ipfs.io`).
+    ///
+    /// A key is retrieved from `{gateway}/ipfs/{root}/{key}`, and byte ranges are retrieved with `Range` requests.
+    Gateway(Url),
+    /// The RPC API of an IPFS daemon (e.g. `http://127.0.0.1:5001` for Kubo).
+    ///
+    /// A key is retrieved with `/api/v0/cat`, and its size with `/api/v0/files/stat`.
+    Daemon(Url),
+}
+
+impl IpfsEndpoint {
+    const fn url(&self) -> &Url {
+        match self {
+            Self::Gateway(url) | Self::Daemon(url) => url,
+        }
+    }
+}
+
+/// A synchronous read-only IPFS store.
+#[derive(Debug)]
+pub struct IpfsStore {
+    endpoint: IpfsEndpoint,
+    root: String,
+    client: Client,
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn handle_reqwest_error(err: reqwest::Error) -> StorageError {
+    StorageError::Other(err.to_string())
+}
+
+/// Normalise a CID root, such as `ipfs://{cid}/path/`, `/ipfs/{cid}/path/` or `{cid}/path`, to `{cid}/path`.
+fn normalise_root(root: &str) -> Result<String, IpfsStoreCreateError> {
+    let normalised = root
+        .strip_prefix("ipfs://")
+        .or_else(|| root.strip_prefix("/ipfs/"))
+        .unwrap_or(root)
+        .trim_matches('/');
+    let cid = normalised.split('/').next().unwrap_or_default();
+    if cid.is_empty() || !cid.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(IpfsStoreCreateError::InvalidRoot(root.to_string()));
+    }
+    Ok(normalised.to_string())
+}
+
+/// Return the value of a HTTP `Range` header for a byte range.
+///
+/// Returns [`None`] if the byte range is empty, since an empty range cannot be expressed in a `Range` header.
+fn byte_range_to_http_range(byte_range: &ByteRange) -> Option<String> {
+    match byte_range {
+        ByteRange::FromStart(_, Some(0)) | ByteRange::Suffix(0) => None,
+        ByteRange::FromStart(offset, None) => Some(format!("bytes={offset}-")),
+        ByteRange::FromStart(offset, Some(length)) => {
+            Some(format!("bytes={offset}-{}", offset + length - 1))
+        }
+        ByteRange::Suffix(length) => Some(format!("bytes=-{length}")),
+    }
+}
+
+/// Return the complete length from a HTTP `Content-Range` header, e.g. `bytes 0-3/4` or `bytes */4`.
+fn content_range_size(content_range: &str) -> Option<u64> {
+    let (_, size) = content_range.rsplit_once('/')?;
+    u64::from_str(size).ok()
+}
+
+/// Return true if an IPFS daemon error message indicates that a path does not exist.
+fn is_not_found_message(message: &str) -> bool {
+    let message = message.to_lowercase();
+    message.contains("no link named")
+        || message.contains("not found")
+        || message.contains("no such file")
+}
+
+/// An IPFS daemon RPC API error.
+#[derive(Deserialize)]
+struct DaemonError {
+    #[serde(rename = "Message")]
+    message: String,
+}
+
+/// The response of the `files/stat` IPFS daemon RPC API command.
+#[derive(Deserialize)]
+struct DaemonStat {
+    #[serde(rename = "Size")]
+    size: u64,
+    #[serde(rename = "Type")]
+    r#type: String,
+}
+
+impl IpfsStore {
+    /// Create a new IPFS store for the content under `root` retrieved via `endpoint`.
+    ///
+    /// `root` is a CID with an optional path, such as `{cid}`, `{cid}/path/to/hierarchy.zarr`, `/ipfs/{cid}/...` or `ipfs://{cid}/...`.
+    ///
+    /// # Errors
+    /// Returns a [`IpfsStoreCreateError`] if the endpoint URL or `root` is not valid.
+    pub fn new(endpoint: IpfsEndpoint, root: &str) -> Result<Self, IpfsStoreCreateError> {
+        let url = endpoint.url();
+        if !matches!(url.scheme(), "http" | "https") || url.cannot_be_a_base() {
+            return Err(IpfsStoreCreateError::InvalidEndpointURL(url.to_string()));
+        }
+        Ok(Self {
+            endpoint,
+            root: normalise_root(root)?,
+            client: Client::new(),
+        })
+    }
+
+    /// Create a new IPFS store for the content under `root` retrieved via a path gateway at `gateway_url`.
+    ///
+    /// # Errors
+    /// Returns a [`IpfsStoreCreateError`] if `gateway_url` or `root` is not valid.
+    pub fn new_gateway(gateway_url: &str, root: &str) -> Result<Self, IpfsStoreCreateError> {
+        let url = Url::from_str(gateway_url)
+            .map_err(|_| IpfsStoreCreateError::InvalidEndpointURL(gateway_url.into()))?;
+        Self::new(IpfsEndpoint::Gateway(url), root)
+    }
+
+    /// Create a new IPFS store for the content under `root` retrieved via the RPC API of an IPFS daemon at `api_url`.
+    ///
+    /// # Errors
+    /// Returns a [`IpfsStoreCreateError`] if `api_url` or `root` is not valid.
+    pub fn new_daemon(api_url: &str, root: &str) -> Result<Self, IpfsStoreCreateError> {
+        let url = Url::from_str(api_url)
+            .map_err(|_| IpfsStoreCreateError::InvalidEndpointURL(api_url.into()))?;
+        Self::new(IpfsEndpoint::Daemon(url), root)
+    }
+
+    /// Return the endpoint.
+    #[must_use]
+    pub const fn endpoint(&self) -> &IpfsEndpoint {
+        &self.endpoint
+    }
+
+    /// Return the normalised CID root, e.g. `{cid}/path/to/hierarchy.zarr`.
+    #[must_use]
+    pub fn root(&self) -> &str {
+        &self.root
+    }
+
+    /// Return the IPFS path of a key, e.g. `/ipfs/{cid}/path/to/hierarchy.zarr/zarr.json`.
+    #[must_use]
+    pub fn key_to_ipfs_path(&self, key: &StoreKey) -> String {
+        if key.as_str().is_empty() {
+            format!("/ipfs/{}", self.root)
+        } else {
+            format!("/ipfs/{}/{}", self.root, key.as_str())
+        }
+    }
+
+    /// Return the URL of `segments` under the endpoint URL.
+    fn endpoint_url<'a>(&self, segments: impl Iterator<Item = &'a str>) -> Url {
+        let mut url = self.endpoint.url().clone();
+        url.path_segments_mut()
+            .expect("the endpoint URL can be a base")
+            .pop_if_empty()
+            .extend(segments);
+        url
+    }
+
+    fn gateway_request(&self, method: reqwest::Method, key: &StoreKey) -> RequestBuilder {
+        let root = self.root.split('/');
+        let key = key
+            .as_str()
+            .split('/')
+            .filter(|segment| !segment.is_empty());
+        let url = self.endpoint_url(std::iter::once("ipfs").chain(root).chain(key));
+        self.client.request(method, url)
+    }
+
+    fn daemon_request(&self, command: &str, key: &StoreKey) -> RequestBuilder {
+        let url = self.endpoint_url(["api", "v0"].into_iter().chain(command.split('/')));
+        self.client
+            .post(url)
+            .query(&[("arg", self.key_to_ipfs_path(key))])
+    }
+
+    /// Send an IPFS daemon RPC API request, returning [`None`] if the path does not exist.
+    fn daemon_send(request: RequestBuilder) -> Result<Option<Response>, StorageError> {
+        let response = request.send().map_err(handle_reqwest_error)?;
+        if response.status().is_success() {
+            return Ok(Some(response));
+        }
+        let status = response.status();
+        let body = response.bytes().map_err(handle_reqwest_error)?;
+        let message = serde_json::from_slice::<DaemonError>(&body).map_or_else(
+            |_| String::from_utf8_lossy(&body).to_string(),
+            |err| err.message,
+        );
+        if is_not_found_message(&message) {
+            Ok(None)
+        } else {
+            Err(StorageError::from(format!(
+                "ipfs daemon responded with status {status}: {message}"
+            )))
+        }
+    }
+
+    fn daemon_cat(
+        &self,
+        key: &StoreKey,
+        offset: u64,
+        length: Option<u64>,
+    ) -> Result<MaybeBytes, StorageError> {
+        let mut request = self.daemon_request("cat", key);
+        if offset > 0 {
+            request = request.query(&[("offset", offset)]);
+        }
+        if let Some(length) = length {
+            request = request.query(&[("length", length)]);
+        }
+        Self::daemon_send(request)?
+            .map(|response| response.bytes().map_err(handle_reqwest_error))
+            .transpose()
+    }
+
+    fn gateway_get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        let mut out = Vec::with_capacity(byte_ranges.len());
+        for (i, byte_range) in byte_ranges.iter().enumerate() {
+            let Some(range) = byte_range_to_http_range(byte_range) else {
+                out.push(Bytes::new());
+                continue;
+            };
+            let response = self
+                .gateway_request(reqwest::Method::GET, key)
+                .header(RANGE, range)
+                .send()
+                .map_err(handle_reqwest_error)?;
+            match response.status() {
+                StatusCode::PARTIAL_CONTENT => {
+                    let size = response
+                        .headers()
+                        .get(CONTENT_RANGE)
+                        .and_then(|header_value| header_value.to_str().ok())
+                        .and_then(content_range_size);
+                    let bytes = response.bytes().map_err(handle_reqwest_error)?;
+                    let expected_length = match (byte_range, size) {
+                        (_, Some(size)) => {
+                            if byte_range.end(size) > size {
+                                return Err(InvalidByteRangeError::new(*byte_range, size).into());
+                            }
+                            Some(byte_range.length(size))
+                        }
+                        (ByteRange::FromStart(_, length), None) => *length,
+                        (ByteRange::Suffix(length), None) => Some(*length),
+                    };
+                    if expected_length.is_some_and(|length| length != bytes.len() as u64) {
+                        return Err(StorageError::from(format!(
+                            "ipfs gateway returned {} bytes for byte range {byte_range}",
+                            bytes.len()
+                        )));
+                    }
+                    out.push(bytes);
+                }
+                StatusCode::OK => {
+                    // The gateway ignored the range request and returned the entire value
+                    let bytes = response.bytes().map_err(handle_reqwest_error)?;
+                    out.extend(
+                        extract_byte_ranges(&bytes, &byte_ranges[i..])?
+                            .into_iter()
+                            .map(Bytes::from),
+                    );
+                    break;
+                }
+                StatusCode::RANGE_NOT_SATISFIABLE => {
+                    return Err(match self.size_key(key)? {
+                        Some(size) => InvalidByteRangeError::new(*byte_range, size).into(),
+                        None => StorageError::from(format!(
+                            "ipfs gateway could not satisfy byte range {byte_range}"
+                        )),
+                    });
+                }
+                StatusCode::NOT_FOUND => return Ok(None),
+                status => {
+                    return Err(StorageError::from(format!(
+                        "ipfs gateway responded with status {status} for the byte range request"
+                    )))
+                }
+            }
+        }
+        Ok(Some(out))
+    }
+
+    fn daemon_get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        let Some(size) = self.size_key(key)? else {
+            return Ok(None);
+        };
+        let mut out = Vec::with_capacity(byte_ranges.len());
+        for byte_range in byte_ranges {
+            if byte_range.end(size) > size {
+                return Err(InvalidByteRangeError::new(*byte_range, size).into());
+            }
+            let length = byte_range.length(size);
+            if length == 0 {
+                out.push(Bytes::new());
+                continue;
+            }
+            let Some(bytes) = self.daemon_cat(key, byte_range.start(size), Some(length))? else {
+                return Ok(None);
+            };
+            if bytes.len() as u64 != length {
+                return Err(StorageError::from(format!(
+                    "ipfs daemon returned {} bytes for byte range {byte_range}",
+                    bytes.len()
+                )));
+            }
+            out.push(bytes);
+        }
+        Ok(Some(out))
+    }
+}
+
+impl ReadableStorageTraits for IpfsStore {
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        match &self.endpoint {
+            IpfsEndpoint::Gateway(_) => {
+                let response = self
+                    .gateway_request(reqwest::Method::GET, key)
+                    .send()
+                    .map_err(handle_reqwest_error)?;
+                match response.status() {
+                    StatusCode::OK => Ok(Some(response.bytes().map_err(handle_reqwest_error)?)),
+                    StatusCode::NOT_FOUND => Ok(None),
+                    status => Err(StorageError::from(format!(
+                        "ipfs gateway unexpected status code: {status}"
+                    ))),
+                }
+            }
+            IpfsEndpoint::Daemon(_) => self.daemon_cat(key, 0, None),
+        }
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        match &self.endpoint {
+            IpfsEndpoint::Gateway(_) => self.gateway_get_partial_values_key(key, byte_ranges),
+            IpfsEndpoint::Daemon(_) => self.daemon_get_partial_values_key(key, byte_ranges),
+        }
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        match &self.endpoint {
+            IpfsEndpoint::Gateway(_) => {
+                let response = self
+                    .gateway_request(reqwest::Method::HEAD, key)
+                    .send()
+                    .map_err(handle_reqwest_error)?;
+                match response.status() {
+                    StatusCode::OK => {
+                        let length = response
+                            .headers()
+                            .get(CONTENT_LENGTH)
+                            .and_then(|header_value| header_value.to_str().ok())
+                            .and_then(|header_str| u64::from_str(header_str).ok())
+                            .ok_or_else(|| {
+                                StorageError::from("content length response is invalid")
+                            })?;
+                        Ok(Some(length))
+                    }
+                    StatusCode::NOT_FOUND => Ok(None),
+                    status => Err(StorageError::from(format!(
+                        "ipfs gateway size_key has status code {status}"
+                    ))),
+                }
+            }
+            IpfsEndpoint::Daemon(_) => {
+                let Some(response) = Self::daemon_send(self.daemon_request("files/stat", key))?
+                else {
+                    return Ok(None);
+                };
+                let body = response.bytes().map_err(handle_reqwest_error)?;
+                let stat: DaemonStat = serde_json::from_slice(&body).map_err(|err| {
+                    StorageError::Other(format!("invalid ipfs daemon stat response: {err}"))
+                })?;
+                Ok((stat.r#type == "file").then_some(stat.size))
+            }
+        }
+    }
+}
+
+/// An IPFS store creation error.
+#[derive(Debug, Error)]
+pub enum IpfsStoreCreateError {
+    /// The endpoint URL is not valid.
+    #[error("endpoint URL {0} is not valid")]
+    InvalidEndpointURL(String),
+    /// The CID root is not valid.
+    #[error("CID root {0} is not valid")]
+    InvalidRoot(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        collections::BTreeMap,
+        error::Error,
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+    };
+    use zarrs_storage::{store::MemoryStore, ListableStorageTraits, ReadableStorageTraits};
+
+    const CID: &str = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+
+    #[test]
+    fn ipfs_normalise_root() {
+        assert_eq!(normalise_root(CID).unwrap(), CID);
+        assert_eq!(
+            normalise_root(&format!("ipfs://{CID}/a/b/")).unwrap(),
+            format!("{CID}/a/b")
+        );
+        assert_eq!(
+            normalise_root(&format!("/ipfs/{CID}/a")).unwrap(),
+            format!("{CID}/a")
+        );
+        assert!(normalise_root("").is_err());
+        assert!(normalise_root("ipfs://").is_err());
+        assert!(normalise_root("not-a-cid/a").is_err());
+    }
+
+    #[test]
+    fn ipfs_byte_range_to_http_range() {
+        assert_eq!(
+            byte_range_to_http_range(&ByteRange::FromStart(1, None)).as_deref(),
+            Some("bytes=1-")
+        );
+        assert_eq!(
+            byte_range_to_http_range(&ByteRange::FromStart(1, Some(2))).as_deref(),
+            Some("bytes=1-2")
+        );
+        assert_eq!(
+            byte_range_to_http_range(&ByteRange::FromStart(1, Some(0))),
+            None
+        );
+        assert_eq!(
+            byte_range_to_http_range(&ByteRange::Suffix(3)).as_deref(),
+            Some("bytes=-3")
+        );
+        assert_eq!(byte_range_to_http_range(&ByteRange::Suffix(0)), None);
+        assert_eq!(content_range_size("bytes 0-3/4"), Some(4));
+        assert_eq!(content_range_size("bytes */4"), Some(4));
+        assert_eq!(content_range_size("bytes 0-3/*"), None);
+    }
+
+    #[test]
+    fn ipfs_urls() -> Result<(), Box<dyn Error>> {
+        let store = IpfsStore::new_gateway("https://ipfs.io/", &format!("{CID}/a.zarr"))?;
+        let key = StoreKey::new("c/0/1")?;
+        assert_eq!(
+            store
+                .gateway_request(reqwest::Method::GET, &key)
+                .build()?
+                .url()
+                .as_str(),
+            format!("https://ipfs.io/ipfs/{CID}/a.zarr/c/0/1")
+        );
+        assert_eq!(
+            store.key_to_ipfs_path(&key),
+            format!("/ipfs/{CID}/a.zarr/c/0/1")
+        );
+
+        let store = IpfsStore::new_daemon("http://127.0.0.1:5001", CID)?;
+        assert_eq!(
+            store
+                .daemon_request("files/stat", &key)
+                .build()?
+                .url()
+                .as_str(),
+            format!("http://127.0.0.1:5001/api/v0/files/stat?arg=%2Fipfs%2F{CID}%2Fc%2F0%2F1")
+        );
+
+        assert!(IpfsStore::new_gateway("ftp://ipfs.io", CID).is_err());
+        assert!(IpfsStore::new_gateway("not a url", CID).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn ipfs_daemon_not_found() {
+        assert!(is_not_found_message(&format!(
+            "no link named \"notfound\" under {CID}"
+        )));
+        assert!(!is_not_found_message("context deadline exceeded"));
+    }
+
+    /// Serve the values of `store` as an IPFS path gateway and daemon RPC API under [`CID`] from a background thread.
+    #[allow(clippy::too_many_lines)]
+    fn serve(store: &MemoryStore) -> Result<String, Box<dyn Error>> {
+        let mut values = BTreeMap::new();
+        for key in store.list()? {
+            values.insert(
+                format!("/ipfs/{CID}/{}", key.as_str()),
+                store.get(&key)?.unwrap(),
+            );
+        }
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}", listener.local_addr()?);
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(&stream);
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut range = None;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = header.split_once(':') {
+                        if name.eq_ignore_ascii_case("range") {
+                            range = Some(value.trim().to_string());
+                        }
+                    }
+                }
+                let mut parts = request_line.split_whitespace();
+                let method = parts.next().unwrap().to_string();
+                let target =
+                    Url::parse(&format!("http://localhost{}", parts.next().unwrap())).unwrap();
+                let query: BTreeMap<String, String> = target.query_pairs().into_owned().collect();
+
+                let (status, headers, body) = match target.path() {
+                    "/api/v0/cat" | "/api/v0/files/stat" => match values.get(&query["arg"]) {
+                        Some(value) if target.path() == "/api/v0/cat" => {
+                            let offset: usize =
+                                query.get("offset").map_or(0, |o| o.parse().unwrap());
+                            let length: usize = query
+                                .get("length")
+                                .map_or(value.len(), |l| l.parse().unwrap());
+                            let end = (offset + length).min(value.len());
+                            (
+                                "200 OK",
+                                String::new(),
+                                value[offset.min(end)..end].to_vec(),
+                            )
+                        }
+                        Some(value) => {
+                            let stat = format!(r#"{{"Size":{},"Type":"file"}}"#, value.len());
+                            ("200 OK", String::new(), stat.into_bytes())
+                        }
+                        None => {
+                            let err = r#"{"Message":"no link named \"notfound\"","Code":0,"Type":"error"}"#;
+                            (
+                                "500 Internal Server Error",
+                                String::new(),
+                                err.as_bytes().to_vec(),
+                            )
+                        }
+                    },
+                    path => match (values.get(path), range) {
+                        (None, _) => ("404 Not Found", String::new(), vec![]),
+                        (Some(value), None) => ("200 OK", String::new(), value.to_vec()),
+                        (Some(value), Some(range)) => {
+                            let size = value.len();
+                            let (start, end) = range
+                                .strip_prefix("bytes=")
+                                .unwrap()
+                                .split_once('-')
+                                .unwrap();
+                            let (start, end) = if start.is_empty() {
+                                (size.saturating_sub(end.parse().unwrap()), size)
+                            } else if end.is_empty() {
+                                (start.parse().unwrap(), size)
+                            } else {
+                                (
+                                    start.parse().unwrap(),
+                                    (end.parse::<usize>().unwrap() + 1).min(size),
+                                )
+                            };
+                            if start >= size {
+                                (
+                                    "416 Range Not Satisfiable",
+                                    format!("Content-Range: bytes */{size}\r\n"),
+                                    vec![],
+                                )
+                            } else {
+                                let content_range =
+                                    format!("Content-Range: bytes {start}-{}/{size}\r\n", end - 1);
+                                (
+                                    "206 Partial Content",
+                                    content_range,
+                                    value[start..end].to_vec(),
+                                )
+                            }
+                        }
+                    },
+                };
+                write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\n{headers}Content-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                )
+                .unwrap();
+                if method != "HEAD" {
+                    stream.write_all(&body).unwrap();
+                }
+            }
+        });
+        Ok(url)
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn ipfs_store() -> Result<(), Box<dyn Error>> {
+        let memory_store = MemoryStore::new();
+        zarrs_storage::store_test::store_write(&memory_store)?;
+        let url = serve(&memory_store)?;
+
+        let key = "a/b".try_into()?;
+        let byte_ranges = [
+            ByteRange::FromStart(1, Some(0)),
+            ByteRange::FromStart(1, Some(2)),
+        ];
+
+        let store = IpfsStore::new_gateway(&url, CID)?;
+        zarrs_storage::store_test::store_read(&store)?;
+        assert_eq!(
+            store.get_partial_values_key(&key, &byte_ranges)?,
+            Some(vec![Bytes::new(), vec![1, 2].into()])
+        );
+
+        let store = IpfsStore::new_daemon(&url, &format!("ipfs://{CID}"))?;
+        zarrs_storage::store_test::store_read(&store)?;
+        assert_eq!(
+            store.get_partial_values_key(&key, &byte_ranges)?,
+            Some(vec![Bytes::new(), vec![1, 2].into()])
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,381 @@
+//! A [kerchunk](https://fsspec.github.io/kerchunk/) reference store for the [`zarrs`](https://docs.rs/zarrs/latest/zarrs/index.html) crate.
+//!
+//! A kerchunk reference file maps store keys to inline values or to byte ranges of other files, such as the chunks of `NetCDF` or `HDF5` files.
+//! A [`ReferenceStore`] serves these keys by reading the referenced byte ranges, enabling virtual Zarr views over existing file collections.
+//!
+//! ```no_run
+//! # use std::sync::Arc;
+//! use zarrs_storage::ReadableListableStorage;
+//! use zarrs_kerchunk::ReferenceStore;
+//!
+//! let store: ReadableListableStorage = Arc::new(ReferenceStore::open("/path/to/references.json")?);
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! Referenced URLs can be `http://` or `https://` URLs, `file://` URLs, or local paths.
+//! Relative local paths are resolved against the directory of the reference file.
+//!
+//! Both version 0 and version 1 reference specifications are supported, including templates.
+//! Version 1 generators are not supported.
+//!
+//! ## Licence
+//! `zarrs_kerchunk` is licensed under either of
+//! - the Apache License, Version 2.0 [LICENSE-APACHE](https://docs.rs/crate/zarrs_kerchunk/latest/source/LICENCE-APACHE) or <http://www.apache.org/licenses/LICENSE-2.0> or
+//! - the MIT license [LICENSE-MIT](https://docs.rs/crate/zarrs_kerchunk/latest/source/LICENCE-MIT) or <http://opensource.org/licenses/MIT>, at your option.
+
+mod references;
+
+pub use references::{Reference, References};
+
+use std::{
+    collections::BTreeSet,
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use reqwest::{
+    header::{CONTENT_LENGTH, RANGE},
+    StatusCode,
+};
+use thiserror::Error;
+use zarrs_storage::{
+    byte_range::{extract_byte_ranges, ByteRange, InvalidByteRangeError},
+    Bytes, ListableStorageTraits, ReadableStorageTraits, StorageError, StoreKey, StoreKeys,
+    StoreKeysPrefixes, StorePrefix,
+};
+
+/// The target of a referenced URL.
+enum Target<'a> {
+    File(PathBuf),
+    Http(&'a str),
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn handle_reqwest_error(err: reqwest::Error) -> StorageError {
+    StorageError::Other(err.to_string())
+}
+
+/// A synchronous read-only kerchunk reference store.
+#[derive(Debug)]
+pub struct ReferenceStore {
+    references: References,
+    base_path: PathBuf,
+    client: reqwest::blocking::Client,
+}
+
+impl ReferenceStore {
+    /// Create a new reference store from `references`.
+    ///
+    /// Relative local paths are resolved against the current working directory.
+    #[must_use]
+    pub fn new(references: References) -> Self {
+        Self {
+            references,
+            base_path: PathBuf::new(),
+            client: reqwest::blocking::Client::new(),
+        }
+    }
+
+    /// Create a new reference store from the JSON of a kerchunk reference file.
+    ///
+    /// Relative local paths are resolved against the current working directory.
+    ///
+    /// # Errors
+    /// Returns a [`ReferenceStoreCreateError`] if the references are not valid.
+    pub fn from_json(json: &[u8]) -> Result<Self, ReferenceStoreCreateError> {
+        Ok(Self::new(references::parse_references(json)?))
+    }
+
+    /// Open a kerchunk reference file at `path`.
+    ///
+    /// Relative local paths are resolved against the directory of `path`.
+    ///
+    /// # Errors
+    /// Returns a [`ReferenceStoreCreateError`] if the file cannot be read or the references are not valid.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, ReferenceStoreCreateError> {
+        let path = path.as_ref();
+        let json = std::fs::read(path)?;
+        let base_path = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Ok(Self::from_json(&json)?.with_base_path(base_path))
+    }
+
+    /// Set the directory that relative local paths are resolved against.
+    #[must_use]
+    pub fn with_base_path(mut self, base_path: impl Into<PathBuf>) -> Self {
+        self.base_path = base_path.into();
+        self
+    }
+
+    /// Return the references.
+    #[must_use]
+    pub const fn references(&self) -> &References {
+        &self.references
+    }
+
+    fn target<'a>(&self, url: &'a str) -> Result<Target<'a>, StorageError> {
+        if url.starts_with("http://") || url.starts_with("https://") {
+            Ok(Target::Http(url))
+        } else if let Some(path) = url.strip_prefix("file://") {
+            Ok(Target::File(PathBuf::from(path)))
+        } else if url.contains("://") {
+            Err(StorageError::Unsupported(format!(
+                "the reference URL {url} has an unsupported protocol"
+            )))
+        } else {
+            Ok(Target::File(self.base_path.join(url)))
+        }
+    }
+
+    /// Return the size of the file at `url`.
+    fn url_size(&self, url: &str) -> Result<u64, StorageError> {
+        match self.target(url)? {
+            Target::File(path) => Ok(std::fs::metadata(path)?.len()),
+            Target::Http(url) => {
+                let response = self.client.head(url).send().map_err(handle_reqwest_error)?;
+                if response.status() != StatusCode::OK {
+                    return Err(StorageError::from(format!(
+                        "reference URL {url} responded with status {}",
+                        response.status()
+                    )));
+                }
+                response
+                    .headers()
+                    .get(CONTENT_LENGTH)
+                    .and_then(|header_value| header_value.to_str().ok())
+                    .and_then(|header_str| u64::from_str(header_str).ok())
+                    .ok_or_else(|| StorageError::from("content length response is invalid"))
+            }
+        }
+    }
+
+    /// Read `length` bytes at `offset` of the file at `url`.
+    fn read_url(&self, url: &str, offset: u64, length: u64) -> Result<Bytes, StorageError> {
+        let length_usize = usize::try_from(length).unwrap();
+        match self.target(url)? {
+            Target::File(path) => {
+                let mut file = File::open(path)?;
+                file.seek(SeekFrom::Start(offset))?;
+                let mut bytes = vec![0; length_usize];
+                file.read_exact(&mut bytes)?;
+                Ok(bytes.into())
+            }
+            Target::Http(_) if length == 0 => Ok(Bytes::new()),
+            Target::Http(url) => {
+                let response = self
+                    .client
+                    .get(url)
+                    .header(RANGE, format!("bytes={offset}-{}", offset + length - 1))
+                    .send()
+                    .map_err(handle_reqwest_error)?;
+                let bytes = match response.status() {
+                    StatusCode::PARTIAL_CONTENT => {
+                        response.bytes().map_err(handle_reqwest_error)?
+                    }
+                    StatusCode::OK => {
+                        // The server ignored the range request and returned the entire file
+                        let bytes = response.bytes().map_err(handle_reqwest_error)?;
+                        let start = usize::try_from(offset).unwrap().min(bytes.len());
+                        let end = (start + length_usize).min(bytes.len());
+                        bytes.slice(start..end)
+                    }
+                    status => {
+                        return Err(StorageError::from(format!(
+                            "reference URL {url} responded with status {status} for the byte range request"
+                        )))
+                    }
+                };
+                if bytes.len() == length_usize {
+                    Ok(bytes)
+                } else {
+                    Err(StorageError::from(format!(
+                        "reference URL {url} returned {} bytes, expected {length}",
+                        bytes.len()
+                    )))
+                }
+            }
+        }
+    }
+
+    /// Read `byte_ranges` of a value of `length` bytes at `offset` of the file at `url`.
+    fn read_url_byte_ranges(
+        &self,
+        url: &str,
+        offset: u64,
+        length: u64,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Vec<Bytes>, StorageError> {
+        byte_ranges
+            .iter()
+            .map(|byte_range| {
+                if byte_range.end(length) > length {
+                    return Err(InvalidByteRangeError::new(*byte_range, length).into());
+                }
+                self.read_url(
+                    url,
+                    offset + byte_range.start(length),
+                    byte_range.length(length),
+                )
+            })
+            .collect()
+    }
+}
+
+impl ReadableStorageTraits for ReferenceStore {
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        let Some(reference) = self.references.get(key) else {
+            return Ok(None);
+        };
+        let values = match reference {
+            Reference::Inline(value) => extract_byte_ranges(value, byte_ranges)?
+                .into_iter()
+                .map(Bytes::from)
+                .collect(),
+            Reference::Url(url) => {
+                let length = self.url_size(url)?;
+                self.read_url_byte_ranges(url, 0, length, byte_ranges)?
+            }
+            Reference::Range {
+                url,
+                offset,
+                length,
+            } => self.read_url_byte_ranges(url, *offset, *length, byte_ranges)?,
+        };
+        Ok(Some(values))
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        match self.references.get(key) {
+            None => Ok(None),
+            Some(Reference::Inline(value)) => Ok(Some(value.len() as u64)),
+            Some(Reference::Url(url)) => Ok(Some(self.url_size(url)?)),
+            Some(Reference::Range { length, .. }) => Ok(Some(*length)),
+        }
+    }
+}
+
+impl ListableStorageTraits for ReferenceStore {
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        Ok(self.references.keys().cloned().collect())
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        Ok(self
+            .references
+            .keys()
+            .filter(|&key| key.has_prefix(prefix))
+            .cloned()
+            .collect())
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        let mut keys: StoreKeys = vec![];
+        let mut prefixes: BTreeSet<StorePrefix> = BTreeSet::default();
+        for key in self.references.keys() {
+            if let Some(child) = key.as_str().strip_prefix(prefix.as_str()) {
+                if let Some((child, _)) = child.split_once('/') {
+                    prefixes.insert(StorePrefix::new(format!("{}{child}/", prefix.as_str()))?);
+                } else {
+                    keys.push(key.clone());
+                }
+            }
+        }
+        Ok(StoreKeysPrefixes::new(keys, prefixes.into_iter().collect()))
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        let mut size = 0;
+        for key in self.list_prefix(prefix)? {
+            size += self.size_key(&key)?.unwrap_or_default();
+        }
+        Ok(size)
+    }
+}
+
+/// A reference store creation error.
+#[derive(Debug, Error)]
+pub enum ReferenceStoreCreateError {
+    /// An IO error.
+    #[error(transparent)]
+    IOError(#[from] std::io::Error),
+    /// An error parsing the reference file JSON.
+    #[error(transparent)]
+    JSONError(#[from] serde_json::Error),
+    /// The reference file is not valid.
+    #[error("invalid reference file: {0}")]
+    InvalidReferenceFile(String),
+    /// The reference of a key is not valid.
+    #[error("invalid reference for key {0}: {1}")]
+    InvalidReference(String, String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{error::Error, io::Write};
+    use zarrs_storage::store::MemoryStore;
+
+    /// Write the values of `store` to a single data file with a reference file pointing to them.
+    fn write_references(store: &MemoryStore, dir: &Path) -> Result<PathBuf, Box<dyn Error>> {
+        let mut data = File::create(dir.join("data.bin"))?;
+        let mut refs = serde_json::Map::new();
+        let mut offset = 0;
+        for key in store.list()? {
+            let value = store.get(&key)?.unwrap();
+            data.write_all(&value)?;
+            refs.insert(
+                key.as_str().to_string(),
+                serde_json::json!(["{{d}}", offset, value.len()]),
+            );
+            offset += value.len();
+        }
+        let json = serde_json::json!({"version": 1, "templates": {"d": "data.bin"}, "refs": refs});
+        let path = dir.join("references.json");
+        std::fs::write(&path, serde_json::to_vec(&json)?)?;
+        Ok(path)
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn kerchunk_reference_store() -> Result<(), Box<dyn Error>> {
+        let memory_store = MemoryStore::new();
+        zarrs_storage::store_test::store_write(&memory_store)?;
+        let dir = tempfile::TempDir::new()?;
+        let path = write_references(&memory_store, dir.path())?;
+
+        let store = ReferenceStore::open(path)?;
+        zarrs_storage::store_test::store_read(&store)?;
+        zarrs_storage::store_test::store_list(&store)?;
+        Ok(())
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn kerchunk_reference_store_inline_url() -> Result<(), Box<dyn Error>> {
+        let dir = tempfile::TempDir::new()?;
+        std::fs::write(dir.path().join("whole.bin"), [0, 1, 2, 3])?;
+        let store = ReferenceStore::from_json(
+            br#"{".zgroup": "{\"zarr_format\":2}", "a": "base64:AAEC", "b": ["whole.bin"], "c": ["s3://bucket/c"]}"#,
+        )?
+        .with_base_path(dir.path());
+
+        assert_eq!(
+            store.get(&".zgroup".try_into()?)?.unwrap(),
+            br#"{"zarr_format":2}"#.to_vec()
+        );
+        assert_eq!(
+            store.get_partial_values_key(&"a".try_into()?, &[ByteRange::Suffix(1)])?,
+            Some(vec![vec![2].into()])
+        );
+        assert_eq!(store.size_key(&"b".try_into()?)?, Some(4));
+        assert_eq!(store.get(&"b".try_into()?)?.unwrap(), vec![0, 1, 2, 3]);
+        assert!(store.get(&"c".try_into()?).is_err());
+        assert!(store.get(&"d".try_into()?)?.is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,236 @@
+//! Kerchunk references.
+
+use std::collections::{BTreeMap, HashMap};
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde_json::Value;
+use zarrs_storage::{Bytes, StoreKey};
+
+use crate::ReferenceStoreCreateError;
+
+/// A kerchunk reference to the value of a store key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reference {
+    /// An inline value.
+    Inline(Bytes),
+    /// An entire file at a URL.
+    Url(String),
+    /// A byte range of a file at a URL.
+    Range {
+        /// The URL.
+        url: String,
+        /// The offset of the value in the file.
+        offset: u64,
+        /// The length of the value.
+        length: u64,
+    },
+}
+
+/// Kerchunk references of store keys.
+pub type References = BTreeMap<StoreKey, Reference>;
+
+fn invalid(key: &str, reason: impl Into<String>) -> ReferenceStoreCreateError {
+    ReferenceStoreCreateError::InvalidReference(key.to_string(), reason.into())
+}
+
+/// Substitute `{{name}}` templates in `url`.
+fn expand_templates(
+    key: &str,
+    url: &str,
+    templates: &HashMap<String, String>,
+) -> Result<String, ReferenceStoreCreateError> {
+    let mut expanded = String::with_capacity(url.len());
+    let mut rest = url;
+    while let Some(start) = rest.find("{{") {
+        let end = rest[start..]
+            .find("}}")
+            .ok_or_else(|| invalid(key, format!("unterminated template in {url}")))?;
+        let name = rest[start + 2..start + end].trim();
+        let value = templates
+            .get(name)
+            .ok_or_else(|| invalid(key, format!("unknown template {name}")))?;
+        expanded.push_str(&rest[..start]);
+        expanded.push_str(value);
+        rest = &rest[start + end + 2..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+fn parse_u64(key: &str, value: &Value) -> Result<u64, ReferenceStoreCreateError> {
+    value
+        .as_u64()
+        .ok_or_else(|| invalid(key, format!("{value} is not a valid offset or length")))
+}
+
+fn parse_reference(
+    key: &str,
+    value: &Value,
+    templates: &HashMap<String, String>,
+) -> Result<Reference, ReferenceStoreCreateError> {
+    match value {
+        Value::String(value) => {
+            if let Some(base64) = value.strip_prefix("base64:") {
+                let value = BASE64_STANDARD
+                    .decode(base64)
+                    .map_err(|err| invalid(key, err.to_string()))?;
+                Ok(Reference::Inline(value.into()))
+            } else {
+                Ok(Reference::Inline(Bytes::copy_from_slice(value.as_bytes())))
+            }
+        }
+        Value::Array(values) => {
+            let url = values
+                .first()
+                .and_then(Value::as_str)
+                .ok_or_else(|| invalid(key, "the first element must be a URL"))?;
+            let url = expand_templates(key, url, templates)?;
+            match values.as_slice() {
+                [_] => Ok(Reference::Url(url)),
+                [_, offset, length] => Ok(Reference::Range {
+                    url,
+                    offset: parse_u64(key, offset)?,
+                    length: parse_u64(key, length)?,
+                }),
+                _ => Err(invalid(key, "expected [url] or [url, offset, length]")),
+            }
+        }
+        _ => Err(invalid(key, "expected a string or an array")),
+    }
+}
+
+/// Parse a kerchunk reference file.
+///
+/// Both version 0 (a mapping of keys to references) and version 1 (with `refs` and `templates`) reference specifications are supported.
+/// Version 1 generators (`gen`) are not supported.
+pub(crate) fn parse_references(json: &[u8]) -> Result<References, ReferenceStoreCreateError> {
+    let value: Value = serde_json::from_slice(json)?;
+    let Value::Object(mut object) = value else {
+        return Err(ReferenceStoreCreateError::InvalidReferenceFile(
+            "expected a JSON object".to_string(),
+        ));
+    };
+
+    let (refs, templates) = if object.contains_key("version") {
+        if object
+            .get("gen")
+            .and_then(Value::as_array)
+            .is_some_and(|gen| !gen.is_empty())
+        {
+            return Err(ReferenceStoreCreateError::InvalidReferenceFile(
+                "generators (gen) are not supported".to_string(),
+            ));
+        }
+        let templates = match object.remove("templates") {
+            Some(Value::Object(templates)) => templates
+                .into_iter()
+                .map(|(name, value)| match value {
+                    Value::String(value) => Ok((name, value)),
+                    _ => Err(ReferenceStoreCreateError::InvalidReferenceFile(format!(
+                        "template {name} is not a string"
+                    ))),
+                })
+                .collect::<Result<HashMap<_, _>, _>>()?,
+            None | Some(Value::Null) => HashMap::new(),
+            Some(_) => {
+                return Err(ReferenceStoreCreateError::InvalidReferenceFile(
+                    "templates must be an object".to_string(),
+                ))
+            }
+        };
+        let Some(Value::Object(refs)) = object.remove("refs") else {
+            return Err(ReferenceStoreCreateError::InvalidReferenceFile(
+                "refs must be an object".to_string(),
+            ));
+        };
+        (refs, templates)
+    } else {
+        (object, HashMap::new())
+    };
+
+    refs.iter()
+        .map(|(key, value)| {
+            let store_key =
+                StoreKey::new(key.as_str()).map_err(|err| invalid(key, err.to_string()))?;
+            Ok((store_key, parse_reference(key, value, &templates)?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kerchunk_references_v0() {
+        let references = parse_references(
+            br#"{
+                ".zgroup": "{\"zarr_format\":2}",
+                "a/0": ["data.nc", 10, 20],
+                "a/1": ["data.nc"],
+                "b": "base64:AAEC"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            references[&StoreKey::new(".zgroup").unwrap()],
+            Reference::Inline(br#"{"zarr_format":2}"#.to_vec().into())
+        );
+        assert_eq!(
+            references[&StoreKey::new("a/0").unwrap()],
+            Reference::Range {
+                url: "data.nc".to_string(),
+                offset: 10,
+                length: 20
+            }
+        );
+        assert_eq!(
+            references[&StoreKey::new("a/1").unwrap()],
+            Reference::Url("data.nc".to_string())
+        );
+        assert_eq!(
+            references[&StoreKey::new("b").unwrap()],
+            Reference::Inline(vec![0, 1, 2].into())
+        );
+    }
+
+    #[test]
+    fn kerchunk_references_v1() {
+        let references = parse_references(
+            br#"{
+                "version": 1,
+                "templates": {"u": "https://example.com/data"},
+                "refs": {
+                    "a/0": ["{{u}}/0.nc", 0, 4],
+                    "a/1": ["{{ u }}/1.nc"]
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            references[&StoreKey::new("a/0").unwrap()],
+            Reference::Range {
+                url: "https://example.com/data/0.nc".to_string(),
+                offset: 0,
+                length: 4
+            }
+        );
+        assert_eq!(
+            references[&StoreKey::new("a/1").unwrap()],
+            Reference::Url("https://example.com/data/1.nc".to_string())
+        );
+    }
+
+    #[test]
+    fn kerchunk_references_invalid() {
+        assert!(parse_references(b"[]").is_err());
+        assert!(parse_references(br#"{"a": 1}"#).is_err());
+        assert!(parse_references(br#"{"a": ["url", 1]}"#).is_err());
+        assert!(parse_references(br#"{"a": ["url", -1, 2]}"#).is_err());
+        assert!(parse_references(br#"{"version": 1, "refs": {"a": ["{{u}}"]}}"#).is_err());
+        assert!(parse_references(
+            br#"{"version": 1, "gen": [{"key": "a/{{i}}", "url": "u", "dimensions": {"i": 2}}], "refs": {}}"#
+        )
+        .is_err());
+    }
+}
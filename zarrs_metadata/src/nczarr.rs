@@ -0,0 +1,274 @@
+//! `NCZarr` metadata.
+//!
+//! See <https://docs.unidata.ucar.edu/netcdf-c/current/nczarr.html>.
+//!
+//! `NCZarr` is the Zarr V2 layout written by `netcdf-c`.
+//! It extends Zarr V2 metadata with `_nczarr_*` keys that hold the netCDF data model, such as the shared dimensions of a group and the dimensions referenced by an array.
+//! Newer versions of `netcdf-c` write these keys in the `.zattrs` attributes, whereas older versions write them alongside the `.zarray` and `.zgroup` metadata.
+//!
+//! [`NCZarrMetadata`] holds the `NCZarr` metadata in the attributes of an array or group.
+//! Unrelated attributes are ignored.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::DimensionName;
+
+/// The prefix of `NCZarr` keys.
+pub const NCZARR_KEY_PREFIX: &str = "_nczarr_";
+
+/// The `NCZarr` superblock key, in the root group.
+pub const NCZARR_SUPERBLOCK_KEY: &str = "_nczarr_superblock";
+
+/// The `NCZarr` group key.
+pub const NCZARR_GROUP_KEY: &str = "_nczarr_group";
+
+/// The `NCZarr` array key.
+pub const NCZARR_ARRAY_KEY: &str = "_nczarr_array";
+
+/// The `NCZarr` attribute types key.
+pub const NCZARR_ATTR_KEY: &str = "_nczarr_attr";
+
+/// `NCZarr` metadata in the attributes of an array or group.
+///
+/// An example of `NCZarr` root group attributes:
+/// ```json
+/// {
+///     "title": "sea surface temperature",
+///     "_nczarr_superblock": {"version": "2.0.0"},
+///     "_nczarr_group": {"dims": {"lat": 180, "lon": 360}, "vars": ["sst"], "groups": []},
+///     "_nczarr_attr": {"types": {"title": ">S1"}}
+/// }
+/// ```
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct NCZarrMetadata {
+    /// The superblock of the root group.
+    #[serde(
+        default,
+        rename = "_nczarr_superblock",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub superblock: Option<NCZarrSuperblock>,
+    /// The dimensions, variables, and subgroups of a group.
+    #[serde(
+        default,
+        rename = "_nczarr_group",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub group: Option<NCZarrGroup>,
+    /// The dimensions referenced by an array.
+    #[serde(
+        default,
+        rename = "_nczarr_array",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub array: Option<NCZarrArray>,
+    /// The netCDF types of the attributes.
+    #[serde(
+        default,
+        rename = "_nczarr_attr",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub attr: Option<NCZarrAttr>,
+}
+
+impl NCZarrMetadata {
+    /// Read the `NCZarr` metadata from array or group `attributes`.
+    ///
+    /// Attributes that are not `NCZarr` metadata are ignored.
+    ///
+    /// # Errors
+    /// Returns a [`serde_json::Error`] if the `NCZarr` attributes cannot be deserialized.
+    pub fn from_attributes(
+        attributes: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<Self, serde_json::Error> {
+        let attributes = attributes
+            .iter()
+            .filter(|(key, _)| is_nczarr_key(key))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        serde_json::from_value(serde_json::Value::Object(attributes))
+    }
+
+    /// Returns true if there is no `NCZarr` metadata.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.superblock.is_none()
+            && self.group.is_none()
+            && self.array.is_none()
+            && self.attr.is_none()
+    }
+}
+
+/// Returns true if `key` is an `NCZarr` key (i.e. it starts with `_nczarr_`).
+#[must_use]
+pub fn is_nczarr_key(key: &str) -> bool {
+    key.starts_with(NCZARR_KEY_PREFIX)
+}
+
+/// Remove the `NCZarr` keys from Zarr V2 `.zarray` or `.zgroup` `metadata` and return them.
+///
+/// Older versions of `netcdf-c` write the `NCZarr` keys alongside the Zarr V2 metadata.
+/// These keys are not valid [additional fields](crate::v3::AdditionalFields), so they should be removed before deserializing the metadata and moved to the attributes.
+pub fn take_nczarr_keys(
+    metadata: &mut serde_json::Map<String, serde_json::Value>,
+) -> serde_json::Map<String, serde_json::Value> {
+    let keys: Vec<String> = metadata
+        .keys()
+        .filter(|key| is_nczarr_key(key))
+        .cloned()
+        .collect();
+    keys.into_iter()
+        .filter_map(|key| metadata.remove(&key).map(|value| (key, value)))
+        .collect()
+}
+
+/// The `NCZarr` superblock of the root group.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct NCZarrSuperblock {
+    /// The `NCZarr` version, e.g. `2.0.0`.
+    pub version: String,
+    /// The `NCZarr` format.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<u64>,
+}
+
+/// The dimensions, variables, and subgroups of an `NCZarr` group.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Default)]
+pub struct NCZarrGroup {
+    /// The shared dimensions defined in the group.
+    #[serde(default)]
+    pub dims: BTreeMap<String, NCZarrDimension>,
+    /// The names of the variables (arrays) in the group.
+    #[serde(default)]
+    pub vars: Vec<String>,
+    /// The names of the subgroups of the group.
+    #[serde(default)]
+    pub groups: Vec<String>,
+}
+
+/// An `NCZarr` shared dimension.
+///
+/// Older versions of `netcdf-c` write the size of a dimension, whereas newer versions write an object with the size and whether the dimension is unlimited.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(untagged)]
+pub enum NCZarrDimension {
+    /// The size of the dimension.
+    Size(u64),
+    /// The size of the dimension and whether it is unlimited.
+    Object {
+        /// The size of the dimension.
+        size: u64,
+        /// Non-zero if the dimension is unlimited.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        unlimited: Option<u64>,
+    },
+}
+
+impl NCZarrDimension {
+    /// Return the size of the dimension.
+    #[must_use]
+    pub const fn size(&self) -> u64 {
+        match self {
+            Self::Size(size) | Self::Object { size, .. } => *size,
+        }
+    }
+
+    /// Returns true if the dimension is unlimited.
+    #[must_use]
+    pub const fn is_unlimited(&self) -> bool {
+        matches!(self, Self::Object { unlimited: Some(unlimited), .. } if *unlimited != 0)
+    }
+}
+
+/// The dimensions referenced by an `NCZarr` array.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Default)]
+pub struct NCZarrArray {
+    /// The fully qualified names of the dimensions of the array, e.g. `/lat` or `/group/time`.
+    #[serde(default)]
+    pub dimrefs: Vec<String>,
+    /// The storage layout of the array, e.g. `chunked`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage: Option<String>,
+}
+
+impl NCZarrArray {
+    /// Return the dimension names of the array.
+    ///
+    /// A dimension name is the last component of a fully qualified dimension reference, so `/group/time` becomes `time`.
+    #[must_use]
+    pub fn dimension_names(&self) -> Vec<DimensionName> {
+        self.dimrefs
+            .iter()
+            .map(|dimref| {
+                let name = dimref.rsplit('/').next().unwrap_or(dimref);
+                DimensionName::new(name)
+            })
+            .collect()
+    }
+}
+
+/// The netCDF types of the attributes of an `NCZarr` array or group.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Default)]
+pub struct NCZarrAttr {
+    /// The Zarr V2 data type of each attribute, e.g. `<i4` or `>S1` for a character attribute.
+    #[serde(default)]
+    pub types: BTreeMap<String, String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JSON_GROUP: &str = r#"{
+        "title": "sea surface temperature",
+        "_nczarr_superblock": {"version": "2.0.0"},
+        "_nczarr_group": {"dims": {"lat": 180, "time": {"size": 4, "unlimited": 1}}, "vars": ["sst"], "groups": ["forecast"]},
+        "_nczarr_attr": {"types": {"title": ">S1"}}
+    }"#;
+
+    #[test]
+    fn nczarr_group() {
+        let attributes: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(JSON_GROUP).unwrap();
+        let metadata = NCZarrMetadata::from_attributes(&attributes).unwrap();
+        assert_eq!(metadata.superblock.unwrap().version, "2.0.0");
+        let group = metadata.group.unwrap();
+        assert_eq!(group.dims["lat"].size(), 180);
+        assert!(!group.dims["lat"].is_unlimited());
+        assert_eq!(group.dims["time"].size(), 4);
+        assert!(group.dims["time"].is_unlimited());
+        assert_eq!(group.vars, vec!["sst"]);
+        assert_eq!(group.groups, vec!["forecast"]);
+        assert_eq!(metadata.attr.unwrap().types["title"], ">S1");
+        assert!(metadata.array.is_none());
+    }
+
+    #[test]
+    fn nczarr_array() {
+        let mut metadata: serde_json::Map<String, serde_json::Value> = serde_json::from_str(
+            r#"{
+                "zarr_format": 2,
+                "shape": [180, 360],
+                "_nczarr_array": {"dimrefs": ["/lat", "/forecast/lon"], "storage": "chunked"}
+            }"#,
+        )
+        .unwrap();
+        let nczarr_keys = take_nczarr_keys(&mut metadata);
+        assert_eq!(metadata.len(), 2);
+        assert_eq!(nczarr_keys.len(), 1);
+
+        let metadata = NCZarrMetadata::from_attributes(&nczarr_keys).unwrap();
+        let array = metadata.array.unwrap();
+        assert_eq!(
+            array.dimension_names(),
+            vec![DimensionName::new("lat"), DimensionName::new("lon")]
+        );
+        assert_eq!(array.storage.as_deref(), Some("chunked"));
+
+        assert!(NCZarrMetadata::from_attributes(&serde_json::Map::new())
+            .unwrap()
+            .is_empty());
+    }
+}
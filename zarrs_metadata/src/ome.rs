@@ -0,0 +1,585 @@
+//! OME-NGFF (OME-Zarr) metadata.
+//!
+//! See <https://ngff.openmicroscopy.org/0.4/>.
+//!
+//! [`OmeMetadata`] holds the OME-NGFF metadata in the attributes of a group, such as the [`multiscales`](OmeMultiscale) of an image, its [`omero`](OmeOmero) rendering settings, the [`labels`](OmeMetadata::labels) of an image, or the [`image-label`](OmeImageLabel) metadata of a label image.
+//! Unrelated attributes are ignored when reading and left untouched when writing.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The OME-NGFF version supported by this module.
+pub const OME_NGFF_VERSION: &str = "0.4";
+
+/// OME-NGFF metadata in the attributes of a group.
+///
+/// An example of OME-NGFF group attributes:
+/// ```json
+/// {
+///     "multiscales": [{
+///         "version": "0.4",
+///         "axes": [{"name": "y", "type": "space", "unit": "micrometer"}, {"name": "x", "type": "space", "unit": "micrometer"}],
+///         "datasets": [
+///             {"path": "0", "coordinateTransformations": [{"type": "scale", "scale": [1.0, 1.0]}]},
+///             {"path": "1", "coordinateTransformations": [{"type": "scale", "scale": [2.0, 2.0]}]}
+///         ]
+///     }]
+/// }
+/// ```
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct OmeMetadata {
+    /// The multiscale images.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multiscales: Option<Vec<OmeMultiscale>>,
+    /// The rendering settings of the image.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub omero: Option<OmeOmero>,
+    /// The paths of the label images of the image, relative to the `labels` group.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub labels: Option<Vec<String>>,
+    /// The metadata of a label image.
+    #[serde(
+        default,
+        rename = "image-label",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub image_label: Option<OmeImageLabel>,
+}
+
+/// An OME-NGFF metadata error.
+#[derive(Debug, Error)]
+pub enum OmeMetadataError {
+    /// Serialization/deserialization error.
+    #[error("JSON serialization or deserialization error: {_0}")]
+    SerdeError(#[from] serde_json::Error),
+    /// The number of axes is not between 2 and 5.
+    #[error("multiscales must have 2 to 5 axes, got {_0}")]
+    InvalidNumberOfAxes(usize),
+    /// An axis name is duplicated.
+    #[error("axis name {_0} is duplicated")]
+    DuplicateAxisName(String),
+    /// Invalid axes, such as more than 3 space axes or axes in the wrong order.
+    #[error("invalid axes: {_0}")]
+    InvalidAxes(String),
+    /// A multiscale has no datasets.
+    #[error("multiscales must have at least one dataset")]
+    MissingDatasets,
+    /// Invalid coordinate transformations.
+    #[error("invalid coordinate transformations: {_0}")]
+    InvalidCoordinateTransformations(String),
+}
+
+impl OmeMetadata {
+    /// Read the OME-NGFF metadata from group `attributes`.
+    ///
+    /// Attributes that are not OME-NGFF metadata are ignored.
+    ///
+    /// # Errors
+    /// Returns an [`OmeMetadataError`] if the OME-NGFF attributes cannot be deserialized or are invalid.
+    pub fn from_attributes(
+        attributes: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<Self, OmeMetadataError> {
+        let metadata: Self = serde_json::from_value(serde_json::Value::Object(attributes.clone()))?;
+        metadata.validate()?;
+        Ok(metadata)
+    }
+
+    /// Write the OME-NGFF metadata into group `attributes`.
+    ///
+    /// The OME-NGFF attributes that are [`None`] are removed, and other attributes are left untouched.
+    ///
+    /// # Errors
+    /// Returns an [`OmeMetadataError`] if the metadata is invalid or cannot be serialized.
+    pub fn to_attributes(
+        &self,
+        attributes: &mut serde_json::Map<String, serde_json::Value>,
+    ) -> Result<(), OmeMetadataError> {
+        self.validate()?;
+        let serde_json::Value::Object(metadata) = serde_json::to_value(self)? else {
+            unreachable!("OmeMetadata serializes to an object")
+        };
+        for key in ["multiscales", "omero", "labels", "image-label"] {
+            attributes.remove(key);
+        }
+        attributes.extend(metadata);
+        Ok(())
+    }
+
+    /// Validate the OME-NGFF metadata.
+    ///
+    /// # Errors
+    /// Returns an [`OmeMetadataError`] if any [`OmeMultiscale`] is invalid.
+    pub fn validate(&self) -> Result<(), OmeMetadataError> {
+        for multiscale in self.multiscales.iter().flatten() {
+            multiscale.validate()?;
+        }
+        Ok(())
+    }
+}
+
+/// The type of an [`OmeAxis`].
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum OmeAxisType {
+    /// A space axis.
+    Space,
+    /// A time axis.
+    Time,
+    /// A channel axis.
+    Channel,
+    /// A custom axis type.
+    #[serde(untagged)]
+    Custom(String),
+}
+
+/// An axis of an [`OmeMultiscale`].
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct OmeAxis {
+    /// The name of the axis.
+    pub name: String,
+    /// The type of the axis.
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub axis_type: Option<OmeAxisType>,
+    /// The unit of the axis, e.g. `micrometer` or `second`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+}
+
+impl OmeAxis {
+    /// Create a new axis with `name` and an optional `axis_type`.
+    #[must_use]
+    pub fn new(name: impl Into<String>, axis_type: Option<OmeAxisType>) -> Self {
+        Self {
+            name: name.into(),
+            axis_type,
+            unit: None,
+        }
+    }
+
+    /// Set the unit.
+    #[must_use]
+    pub fn with_unit(mut self, unit: impl Into<String>) -> Self {
+        self.unit = Some(unit.into());
+        self
+    }
+}
+
+/// A coordinate transformation of an [`OmeDataset`] or [`OmeMultiscale`].
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum OmeCoordinateTransformation {
+    /// The identity transformation.
+    Identity,
+    /// A scale for each axis.
+    Scale {
+        /// The scale.
+        scale: Vec<f64>,
+    },
+    /// A translation for each axis.
+    Translation {
+        /// The translation.
+        translation: Vec<f64>,
+    },
+}
+
+/// A dataset (i.e. an array) of an [`OmeMultiscale`].
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OmeDataset {
+    /// The path of the array relative to the group.
+    pub path: String,
+    /// The coordinate transformations of the array.
+    ///
+    /// This must be a scale, optionally followed by a translation.
+    pub coordinate_transformations: Vec<OmeCoordinateTransformation>,
+}
+
+impl OmeDataset {
+    /// Create a new dataset at `path` with a `scale` coordinate transformation.
+    #[must_use]
+    pub fn new(path: impl Into<String>, scale: Vec<f64>) -> Self {
+        Self {
+            path: path.into(),
+            coordinate_transformations: vec![OmeCoordinateTransformation::Scale { scale }],
+        }
+    }
+}
+
+/// A multiscale image.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OmeMultiscale {
+    /// The OME-NGFF version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// The name of the multiscale image.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The axes of the multiscale image.
+    pub axes: Vec<OmeAxis>,
+    /// The datasets of the multiscale image, from the highest to the lowest resolution.
+    pub datasets: Vec<OmeDataset>,
+    /// The coordinate transformations applied to all datasets.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coordinate_transformations: Option<Vec<OmeCoordinateTransformation>>,
+    /// The type of downscaling method used to generate the datasets.
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub downscaling_type: Option<String>,
+    /// Additional information about the downscaling method.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+}
+
+impl OmeMultiscale {
+    /// Create a new multiscale image with `axes` and `datasets`.
+    #[must_use]
+    pub fn new(axes: Vec<OmeAxis>, datasets: Vec<OmeDataset>) -> Self {
+        Self {
+            version: Some(OME_NGFF_VERSION.to_string()),
+            name: None,
+            axes,
+            datasets,
+            coordinate_transformations: None,
+            downscaling_type: None,
+            metadata: None,
+        }
+    }
+
+    /// Validate the multiscale image against the OME-NGFF specification.
+    ///
+    /// Checks that
+    ///  - there are 2 to 5 axes with unique names,
+    ///  - there are at most 3 space axes, and at most one time axis and channel axis,
+    ///  - the axes are ordered by time, then channel or custom, then space,
+    ///  - there is at least one dataset, and
+    ///  - the coordinate transformations of each dataset are a scale optionally followed by a translation, matching the number of axes.
+    ///
+    /// # Errors
+    /// Returns an [`OmeMetadataError`] if the multiscale image is invalid.
+    pub fn validate(&self) -> Result<(), OmeMetadataError> {
+        let num_axes = self.axes.len();
+        if !(2..=5).contains(&num_axes) {
+            return Err(OmeMetadataError::InvalidNumberOfAxes(num_axes));
+        }
+        let mut names = HashSet::new();
+        for axis in &self.axes {
+            if !names.insert(axis.name.as_str()) {
+                return Err(OmeMetadataError::DuplicateAxisName(axis.name.clone()));
+            }
+        }
+        let count = |axis_type: OmeAxisType| {
+            self.axes
+                .iter()
+                .filter(|axis| axis.axis_type.as_ref() == Some(&axis_type))
+                .count()
+        };
+        if count(OmeAxisType::Space) > 3 {
+            return Err(OmeMetadataError::InvalidAxes(
+                "there must be at most 3 space axes".to_string(),
+            ));
+        }
+        if count(OmeAxisType::Time) > 1 || count(OmeAxisType::Channel) > 1 {
+            return Err(OmeMetadataError::InvalidAxes(
+                "there must be at most one time and channel axis".to_string(),
+            ));
+        }
+        let order = |axis: &OmeAxis| match axis.axis_type {
+            Some(OmeAxisType::Time) => 0,
+            Some(OmeAxisType::Space) => 2,
+            _ => 1,
+        };
+        if self
+            .axes
+            .windows(2)
+            .any(|axes| order(&axes[0]) > order(&axes[1]))
+        {
+            return Err(OmeMetadataError::InvalidAxes(
+                "axes must be ordered by time, then channel or custom, then space".to_string(),
+            ));
+        }
+
+        if self.datasets.is_empty() {
+            return Err(OmeMetadataError::MissingDatasets);
+        }
+        for dataset in &self.datasets {
+            validate_coordinate_transformations(
+                &dataset.coordinate_transformations,
+                num_axes,
+                true,
+            )
+            .map_err(|err| {
+                OmeMetadataError::InvalidCoordinateTransformations(format!(
+                    "dataset {}: {err}",
+                    dataset.path
+                ))
+            })?;
+        }
+        if let Some(coordinate_transformations) = &self.coordinate_transformations {
+            validate_coordinate_transformations(coordinate_transformations, num_axes, false)
+                .map_err(OmeMetadataError::InvalidCoordinateTransformations)?;
+        }
+        Ok(())
+    }
+}
+
+/// Validate that `coordinate_transformations` are a scale optionally followed by a translation with `num_axes` values.
+///
+/// If `require_scale` is false, a lone identity transformation is also valid.
+fn validate_coordinate_transformations(
+    coordinate_transformations: &[OmeCoordinateTransformation],
+    num_axes: usize,
+    require_scale: bool,
+) -> Result<(), String> {
+    use OmeCoordinateTransformation as CT;
+    let check_len = |values: &[f64]| {
+        if values.len() == num_axes {
+            Ok(())
+        } else {
+            Err(format!("expected {num_axes} values, got {}", values.len()))
+        }
+    };
+    match coordinate_transformations {
+        [CT::Identity] if !require_scale => Ok(()),
+        [CT::Scale { scale }] => check_len(scale),
+        [CT::Scale { scale }, CT::Translation { translation }] => {
+            check_len(scale)?;
+            check_len(translation)
+        }
+        _ => Err("expected a scale optionally followed by a translation".to_string()),
+    }
+}
+
+/// The rendering settings of an image.
+///
+/// See <https://ngff.openmicroscopy.org/0.4/#omero-md>.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct OmeOmero {
+    /// The image identifier.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    /// The image name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The OMERO metadata version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// The rendering settings of each channel.
+    #[serde(default)]
+    pub channels: Vec<OmeOmeroChannel>,
+    /// The rendering definitions, such as the default time point and z section.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rdefs: Option<serde_json::Value>,
+}
+
+/// The rendering settings of a channel of an [`OmeOmero`].
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct OmeOmeroChannel {
+    /// Whether the channel is displayed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active: Option<bool>,
+    /// The channel coefficient.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coefficient: Option<f64>,
+    /// The channel colour as a hex RGB string, e.g. `00FF00`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// The mapping family, e.g. `linear`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family: Option<String>,
+    /// Whether the colour map is inverted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inverted: Option<bool>,
+    /// The channel label.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// The display window of the channel.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window: Option<OmeOmeroWindow>,
+}
+
+/// The display window of an [`OmeOmeroChannel`].
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct OmeOmeroWindow {
+    /// The start of the display window.
+    pub start: f64,
+    /// The end of the display window.
+    pub end: f64,
+    /// The minimum value of the channel.
+    pub min: f64,
+    /// The maximum value of the channel.
+    pub max: f64,
+}
+
+/// The metadata of a label image.
+///
+/// See <https://ngff.openmicroscopy.org/0.4/#label-md>.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct OmeImageLabel {
+    /// The OME-NGFF version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// The display colours of the label values.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub colors: Option<Vec<OmeLabelColor>>,
+    /// The properties of the label values.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub properties: Option<Vec<serde_json::Map<String, serde_json::Value>>>,
+    /// The source image of the label image.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<OmeLabelSource>,
+}
+
+/// The display colour of a label value of an [`OmeImageLabel`].
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct OmeLabelColor {
+    /// The label value.
+    #[serde(rename = "label-value")]
+    pub label_value: u64,
+    /// The RGBA colour.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rgba: Option<[u8; 4]>,
+}
+
+/// The source image of an [`OmeImageLabel`].
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct OmeLabelSource {
+    /// The path of the source image relative to the label image.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JSON_VALID: &str = r#"{
+        "spam": "ham",
+        "multiscales": [{
+            "version": "0.4",
+            "name": "example",
+            "axes": [
+                {"name": "t", "type": "time", "unit": "millisecond"},
+                {"name": "c", "type": "channel"},
+                {"name": "z", "type": "space", "unit": "micrometer"},
+                {"name": "y", "type": "space", "unit": "micrometer"},
+                {"name": "x", "type": "space", "unit": "micrometer"}
+            ],
+            "datasets": [
+                {"path": "0", "coordinateTransformations": [{"type": "scale", "scale": [1.0, 1.0, 0.5, 0.5, 0.5]}]},
+                {"path": "1", "coordinateTransformations": [{"type": "scale", "scale": [1.0, 1.0, 1.0, 1.0, 1.0]}, {"type": "translation", "translation": [0.0, 0.0, 0.25, 0.25, 0.25]}]}
+            ],
+            "coordinateTransformations": [{"type": "scale", "scale": [0.1, 1.0, 1.0, 1.0, 1.0]}],
+            "type": "gaussian"
+        }],
+        "omero": {
+            "channels": [{"active": true, "color": "00FF00", "label": "GFP", "window": {"start": 0, "end": 1500, "min": 0, "max": 65535}}]
+        },
+        "labels": ["cells"]
+    }"#;
+
+    #[test]
+    fn ome_metadata_valid() {
+        let attributes: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(JSON_VALID).unwrap();
+        let metadata = OmeMetadata::from_attributes(&attributes).unwrap();
+        let multiscales = metadata.multiscales.as_ref().unwrap();
+        assert_eq!(multiscales[0].axes[0].axis_type, Some(OmeAxisType::Time));
+        assert_eq!(multiscales[0].axes[4].unit.as_deref(), Some("micrometer"));
+        let OmeCoordinateTransformation::Translation { translation } =
+            &multiscales[0].datasets[1].coordinate_transformations[1]
+        else {
+            panic!("expected a translation")
+        };
+        assert_eq!(translation.len(), 5);
+        for (parsed, expected) in std::iter::zip(translation, [0.0, 0.0, 0.25, 0.25, 0.25]) {
+            assert!((parsed - expected).abs() < f64::EPSILON);
+        }
+        assert_eq!(multiscales[0].downscaling_type.as_deref(), Some("gaussian"));
+        let window = metadata.omero.as_ref().unwrap().channels[0].window.unwrap();
+        assert!((window.end - 1500.0).abs() < f64::EPSILON);
+        assert_eq!(metadata.labels, Some(vec!["cells".to_string()]));
+
+        let mut attributes_out = serde_json::Map::new();
+        attributes_out.insert("spam".to_string(), "ham".into());
+        attributes_out.insert("labels".to_string(), serde_json::json!(["removed"]));
+        let mut metadata_out = metadata;
+        metadata_out.labels = None;
+        metadata_out.to_attributes(&mut attributes_out).unwrap();
+        assert_eq!(attributes_out["spam"], "ham");
+        assert!(!attributes_out.contains_key("labels"));
+        assert_eq!(
+            OmeMetadata::from_attributes(&attributes_out).unwrap(),
+            metadata_out
+        );
+    }
+
+    #[test]
+    fn ome_metadata_invalid() {
+        let valid = OmeMultiscale::new(
+            vec![
+                OmeAxis::new("y", Some(OmeAxisType::Space)),
+                OmeAxis::new("x", Some(OmeAxisType::Space)),
+            ],
+            vec![OmeDataset::new("0", vec![1.0, 1.0])],
+        );
+        assert!(valid.validate().is_ok());
+
+        let mut multiscale = valid.clone();
+        multiscale.axes.truncate(1);
+        assert!(matches!(
+            multiscale.validate(),
+            Err(OmeMetadataError::InvalidNumberOfAxes(1))
+        ));
+
+        let mut multiscale = valid.clone();
+        multiscale.axes[1].name = "y".to_string();
+        assert!(matches!(
+            multiscale.validate(),
+            Err(OmeMetadataError::DuplicateAxisName(_))
+        ));
+
+        let mut multiscale = valid.clone();
+        multiscale.axes[1].axis_type = Some(OmeAxisType::Time);
+        assert!(matches!(
+            multiscale.validate(),
+            Err(OmeMetadataError::InvalidAxes(_))
+        ));
+
+        let mut multiscale = valid.clone();
+        multiscale.datasets.clear();
+        assert!(matches!(
+            multiscale.validate(),
+            Err(OmeMetadataError::MissingDatasets)
+        ));
+
+        let mut multiscale = valid.clone();
+        multiscale.datasets[0].coordinate_transformations =
+            vec![OmeCoordinateTransformation::Scale { scale: vec![1.0] }];
+        assert!(matches!(
+            multiscale.validate(),
+            Err(OmeMetadataError::InvalidCoordinateTransformations(_))
+        ));
+
+        let mut multiscale = valid;
+        multiscale.datasets[0].coordinate_transformations = vec![
+            OmeCoordinateTransformation::Translation {
+                translation: vec![0.0, 0.0],
+            },
+            OmeCoordinateTransformation::Scale {
+                scale: vec![1.0, 1.0],
+            },
+        ];
+        assert!(matches!(
+            multiscale.validate(),
+            Err(OmeMetadataError::InvalidCoordinateTransformations(_))
+        ));
+
+        let axis: OmeAxis = serde_json::from_str(r#"{"name": "a", "type": "angle"}"#).unwrap();
+        assert_eq!(
+            axis.axis_type,
+            Some(OmeAxisType::Custom("angle".to_string()))
+        );
+    }
+}
@@ -4,7 +4,11 @@ pub mod group;
 /// Zarr V2 array metadata.
 pub mod array;
 
+/// Zarr V2 consolidated metadata.
+pub mod consolidated;
+
 pub use array::ArrayMetadataV2;
+pub use consolidated::ConsolidatedMetadataV2;
 pub use group::GroupMetadataV2;
 
 mod metadata;
@@ -28,9 +32,16 @@ mod tests {
 
     use crate::{
         v2_to_v3::{array_metadata_v2_to_v3, data_type_metadata_v2_to_v3_data_type},
-        v3::array::codec::{
-            blosc::{self, BloscCodecConfigurationV1},
-            transpose::{self, TransposeCodecConfigurationV1},
+        v3::{
+            array::{
+                codec::{
+                    blosc::{self, BloscCodecConfigurationV1},
+                    fixedscaleoffset::FixedScaleOffsetCodecConfigurationV1,
+                    transpose::{self, TransposeCodecConfigurationV1},
+                },
+                data_type::DataTypeMetadataV3,
+            },
+            MetadataV3,
         },
         ChunkKeySeparator, ChunkShape, Endianness,
     };
@@ -100,4 +111,110 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn array_v2_dimension_names() -> Result<(), Box<dyn std::error::Error>> {
+        let json = r#"
+            {
+                "chunks": [10, 10],
+                "compressor": null,
+                "dtype": "<f4",
+                "fill_value": 0.0,
+                "filters": null,
+                "order": "C",
+                "shape": [20, 20],
+                "zarr_format": 2
+            }"#;
+        let mut array_metadata_v2: crate::v2::ArrayMetadataV2 = serde_json::from_str(json)?;
+        array_metadata_v2.attributes.insert(
+            array::ARRAY_DIMENSIONS_ATTRIBUTE.to_string(),
+            serde_json::json!(["y", "x"]),
+        );
+        let array_metadata_v3 = array_metadata_v2_to_v3(&array_metadata_v2)?;
+        assert_eq!(
+            array_metadata_v3.dimension_names,
+            Some(vec!["y".into(), "x".into()])
+        );
+        assert!(array_metadata_v3.attributes.is_empty());
+
+        // Dimension names that do not match the dimensionality are left as attributes
+        array_metadata_v2.attributes.insert(
+            array::ARRAY_DIMENSIONS_ATTRIBUTE.to_string(),
+            serde_json::json!(["x"]),
+        );
+        let array_metadata_v3 = array_metadata_v2_to_v3(&array_metadata_v2)?;
+        assert_eq!(array_metadata_v3.dimension_names, None);
+        assert!(array_metadata_v3
+            .attributes
+            .contains_key(array::ARRAY_DIMENSIONS_ATTRIBUTE));
+
+        // Dimension names can also come from the NCZarr `_nczarr_array` attribute
+        array_metadata_v2
+            .attributes
+            .remove(array::ARRAY_DIMENSIONS_ATTRIBUTE);
+        array_metadata_v2.attributes.insert(
+            crate::nczarr::NCZARR_ARRAY_KEY.to_string(),
+            serde_json::json!({"dimrefs": ["/y", "/group/x"], "storage": "chunked"}),
+        );
+        let array_metadata_v3 = array_metadata_v2_to_v3(&array_metadata_v2)?;
+        assert_eq!(
+            array_metadata_v3.dimension_names,
+            Some(vec!["y".into(), "x".into()])
+        );
+        assert!(array_metadata_v3.attributes.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn array_v2_filters() -> Result<(), Box<dyn std::error::Error>> {
+        let json = r#"
+            {
+                "chunks": [10],
+                "compressor": {"id": "zstd", "level": 1},
+                "dtype": "<f8",
+                "fill_value": 0.0,
+                "filters": [
+                    {"id": "fixedscaleoffset", "offset": 1000, "scale": 10, "dtype": "<f8", "astype": "|u1"},
+                    {"id": "shuffle", "elementsize": 1}
+                ],
+                "order": "C",
+                "shape": [20],
+                "zarr_format": 2
+            }"#;
+        let array_metadata_v2: crate::v2::ArrayMetadataV2 = serde_json::from_str(json)?;
+        let array_metadata_v3 = array_metadata_v2_to_v3(&array_metadata_v2)?;
+        let names: Vec<&str> = array_metadata_v3
+            .codecs
+            .iter()
+            .map(MetadataV3::name)
+            .collect();
+        assert_eq!(names, vec!["fixedscaleoffset", "bytes", "shuffle", "zstd"]);
+        let configuration = array_metadata_v3.codecs[0]
+            .to_configuration::<FixedScaleOffsetCodecConfigurationV1>()
+            .unwrap();
+        assert_eq!(configuration.astype, Some(DataTypeMetadataV3::UInt8));
+
+        // Unsupported filters are listed
+        let json = r#"
+            {
+                "chunks": [10],
+                "compressor": null,
+                "dtype": "<f8",
+                "fill_value": 0.0,
+                "filters": [
+                    {"id": "quantize", "digits": 2, "dtype": "<f8"},
+                    {"id": "delta", "dtype": "<f8"},
+                    {"id": "categorize", "labels": ["a"], "dtype": "<f8"}
+                ],
+                "order": "C",
+                "shape": [20],
+                "zarr_format": 2
+            }"#;
+        let array_metadata_v2: crate::v2::ArrayMetadataV2 = serde_json::from_str(json)?;
+        let err = array_metadata_v2_to_v3(&array_metadata_v2).unwrap_err();
+        assert_eq!(err.to_string(), "unsupported filters: quantize, categorize");
+
+        Ok(())
+    }
 }
@@ -10,12 +10,22 @@ use crate::{
 pub mod codec {
     /// `bitround` codec metadata.
     pub mod bitround;
+    /// `imagecodecs_bitshuffle` codec metadata.
+    pub mod bitshuffle;
     /// `blosc` codec metadata.
     pub mod blosc;
     /// `bz2` codec metadata.
     pub mod bz2;
+    /// `delta` codec metadata.
+    pub mod delta;
+    /// `fixedscaleoffset` codec metadata.
+    pub mod fixedscaleoffset;
     /// `gzip` codec metadata.
     pub mod gzip;
+    /// `packbits` codec metadata.
+    pub mod packbits;
+    /// `shuffle` codec metadata.
+    pub mod shuffle;
     /// `vlen-array` codec metadata.
     pub mod vlen_array;
     /// `vlen-bytes` codec metadata.
@@ -28,6 +38,9 @@ pub mod codec {
     pub mod zstd;
 }
 
+/// The attribute holding the dimension names of a Zarr V2 array by the [xarray convention](https://docs.xarray.dev/en/stable/internals/zarr-encoding-spec.html).
+pub const ARRAY_DIMENSIONS_ATTRIBUTE: &str = "_ARRAY_DIMENSIONS";
+
 /// Zarr array metadata (storage specification v2).
 ///
 /// An example `JSON` document for a Zarr V2 array:
@@ -57,7 +70,6 @@ pub mod codec {
 /// }
 /// ```
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Display)]
-#[serde(tag = "node_type", rename = "array")]
 #[display("{}", serde_json::to_string(self).unwrap_or_default())]
 pub struct ArrayMetadataV2 {
     /// An integer defining the version of the storage specification to which the array adheres. Must be `2`.
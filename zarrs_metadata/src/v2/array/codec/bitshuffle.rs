@@ -0,0 +1,88 @@
+use std::num::NonZeroUsize;
+
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    v2_to_v3::ArrayMetadataV2ToV3ConversionError,
+    v3::array::codec::bitshuffle::{
+        BitshuffleBlockSize, BitshuffleCodecConfiguration, BitshuffleCodecConfigurationV1,
+    },
+};
+
+/// The identifier for the `imagecodecs_bitshuffle` codec.
+pub const IDENTIFIER: &str = "imagecodecs_bitshuffle";
+
+/// Configuration parameters for the `imagecodecs_bitshuffle` codec (imagecodecs).
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Display)]
+#[display("{}", serde_json::to_string(self).unwrap_or_default())]
+pub struct BitshuffleCodecConfigurationImagecodecs {
+    /// The size of an element in bytes.
+    #[serde(default = "default_itemsize")]
+    pub itemsize: NonZeroUsize,
+    /// The number of elements in a block, or 0 for the default block size.
+    #[serde(default)]
+    pub blocksize: usize,
+}
+
+const fn default_itemsize() -> NonZeroUsize {
+    NonZeroUsize::MIN
+}
+
+/// Convert [`BitshuffleCodecConfigurationImagecodecs`] to [`BitshuffleCodecConfiguration`].
+///
+/// # Errors
+/// Returns an error if `blocksize` is not zero or a multiple of 8.
+pub fn codec_bitshuffle_v2_imagecodecs_to_v3(
+    bitshuffle: &BitshuffleCodecConfigurationImagecodecs,
+) -> Result<BitshuffleCodecConfiguration, ArrayMetadataV2ToV3ConversionError> {
+    let block_size = if bitshuffle.blocksize == 0 {
+        None
+    } else {
+        Some(
+            BitshuffleBlockSize::new(bitshuffle.blocksize).ok_or_else(|| {
+                ArrayMetadataV2ToV3ConversionError::Other(format!(
+                    "bitshuffle block size {} is not a multiple of 8",
+                    bitshuffle.blocksize
+                ))
+            })?,
+        )
+    };
+    Ok(BitshuffleCodecConfiguration::V1(
+        BitshuffleCodecConfigurationV1 {
+            element_size: bitshuffle.itemsize,
+            block_size,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codec_bitshuffle_imagecodecs() {
+        let v2 = serde_json::from_str::<BitshuffleCodecConfigurationImagecodecs>(
+            r#"{"itemsize": 2, "blocksize": 0}"#,
+        )
+        .unwrap();
+        let BitshuffleCodecConfiguration::V1(v3) =
+            codec_bitshuffle_v2_imagecodecs_to_v3(&v2).unwrap();
+        assert_eq!(v3.element_size.get(), 2);
+        assert_eq!(v3.block_size, None);
+
+        let v2 = serde_json::from_str::<BitshuffleCodecConfigurationImagecodecs>(
+            r#"{"itemsize": 4, "blocksize": 2048}"#,
+        )
+        .unwrap();
+        let BitshuffleCodecConfiguration::V1(v3) =
+            codec_bitshuffle_v2_imagecodecs_to_v3(&v2).unwrap();
+        assert_eq!(v3.block_size, BitshuffleBlockSize::new(2048));
+
+        let v2 = serde_json::from_str::<BitshuffleCodecConfigurationImagecodecs>(
+            r#"{"itemsize": 4, "blocksize": 100}"#,
+        )
+        .unwrap();
+        assert!(codec_bitshuffle_v2_imagecodecs_to_v3(&v2).is_err());
+    }
+}
@@ -0,0 +1,86 @@
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    v2::array::DataTypeMetadataV2,
+    v2_to_v3::{data_type_metadata_v2_to_v3_data_type, ArrayMetadataV2ToV3ConversionError},
+    v3::array::codec::delta::{DeltaCodecConfiguration, DeltaCodecConfigurationV1},
+};
+
+/// The identifier for the `delta` codec.
+pub const IDENTIFIER: &str = "delta";
+
+/// Configuration parameters for the `delta` codec (numcodecs).
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Display)]
+#[serde(deny_unknown_fields)]
+#[display("{}", serde_json::to_string(self).unwrap_or_default())]
+pub struct DeltaCodecConfigurationNumcodecs {
+    /// The data type of the decoded array.
+    pub dtype: DataTypeMetadataV2,
+    /// The data type of the encoded deltas.
+    ///
+    /// Defaults to `dtype`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub astype: Option<DataTypeMetadataV2>,
+}
+
+/// Convert [`DeltaCodecConfigurationNumcodecs`] to [`DeltaCodecConfiguration`].
+///
+/// The `dtype` is not retained, since it is the data type of the array.
+///
+/// # Errors
+/// Returns an error if `dtype` or `astype` are not supported data types.
+pub fn codec_delta_v2_numcodecs_to_v3(
+    delta: &DeltaCodecConfigurationNumcodecs,
+) -> Result<DeltaCodecConfiguration, ArrayMetadataV2ToV3ConversionError> {
+    let to_v3 = |data_type: &DataTypeMetadataV2| {
+        data_type_metadata_v2_to_v3_data_type(data_type).map_err(|_| {
+            ArrayMetadataV2ToV3ConversionError::UnsupportedDataType(format!("{data_type:?}"))
+        })
+    };
+    let dtype = to_v3(&delta.dtype)?;
+    let astype = delta.astype.as_ref().map(to_v3).transpose()?;
+    Ok(DeltaCodecConfiguration::V1(DeltaCodecConfigurationV1 {
+        astype: astype.filter(|astype| astype != &dtype),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v3::array::data_type::DataTypeMetadataV3;
+
+    use super::*;
+
+    #[test]
+    fn codec_delta_numcodecs() {
+        let v2 = serde_json::from_str::<DeltaCodecConfigurationNumcodecs>(
+            r#"{"dtype": "<i4", "astype": "<i2"}"#,
+        )
+        .unwrap();
+        let v3 = codec_delta_v2_numcodecs_to_v3(&v2).unwrap();
+        assert_eq!(
+            v3,
+            DeltaCodecConfiguration::V1(DeltaCodecConfigurationV1 {
+                astype: Some(DataTypeMetadataV3::Int16)
+            })
+        );
+
+        let v2 = serde_json::from_str::<DeltaCodecConfigurationNumcodecs>(
+            r#"{"dtype": "<f8", "astype": "<f8"}"#,
+        )
+        .unwrap();
+        let v3 = codec_delta_v2_numcodecs_to_v3(&v2).unwrap();
+        assert_eq!(
+            v3,
+            DeltaCodecConfiguration::V1(DeltaCodecConfigurationV1 { astype: None })
+        );
+
+        let v2 = serde_json::from_str::<DeltaCodecConfigurationNumcodecs>(r#"{"dtype": "<u2"}"#)
+            .unwrap();
+        let v3 = codec_delta_v2_numcodecs_to_v3(&v2).unwrap();
+        assert_eq!(
+            v3,
+            DeltaCodecConfiguration::V1(DeltaCodecConfigurationV1 { astype: None })
+        );
+    }
+}
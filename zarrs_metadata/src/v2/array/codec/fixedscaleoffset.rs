@@ -0,0 +1,94 @@
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    v2::array::DataTypeMetadataV2,
+    v2_to_v3::{data_type_metadata_v2_to_v3_data_type, ArrayMetadataV2ToV3ConversionError},
+    v3::array::codec::fixedscaleoffset::{
+        FixedScaleOffsetCodecConfiguration, FixedScaleOffsetCodecConfigurationV1,
+    },
+};
+
+/// The identifier for the `fixedscaleoffset` codec.
+pub const IDENTIFIER: &str = "fixedscaleoffset";
+
+/// Configuration parameters for the `fixedscaleoffset` codec (numcodecs).
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Display)]
+#[serde(deny_unknown_fields)]
+#[display("{}", serde_json::to_string(self).unwrap_or_default())]
+pub struct FixedScaleOffsetCodecConfigurationNumcodecs {
+    /// The value subtracted from each element before scaling.
+    pub offset: f64,
+    /// The value each element is multiplied by after subtracting the offset.
+    pub scale: f64,
+    /// The data type of the decoded array.
+    pub dtype: DataTypeMetadataV2,
+    /// The data type of the encoded elements.
+    ///
+    /// Defaults to `dtype`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub astype: Option<DataTypeMetadataV2>,
+}
+
+/// Convert [`FixedScaleOffsetCodecConfigurationNumcodecs`] to [`FixedScaleOffsetCodecConfiguration`].
+///
+/// The `dtype` is not retained, since it is the data type of the array.
+///
+/// # Errors
+/// Returns an error if `dtype` or `astype` are not supported data types.
+pub fn codec_fixedscaleoffset_v2_numcodecs_to_v3(
+    fixedscaleoffset: &FixedScaleOffsetCodecConfigurationNumcodecs,
+) -> Result<FixedScaleOffsetCodecConfiguration, ArrayMetadataV2ToV3ConversionError> {
+    let to_v3 = |data_type: &DataTypeMetadataV2| {
+        data_type_metadata_v2_to_v3_data_type(data_type).map_err(|_| {
+            ArrayMetadataV2ToV3ConversionError::UnsupportedDataType(format!("{data_type:?}"))
+        })
+    };
+    let dtype = to_v3(&fixedscaleoffset.dtype)?;
+    let astype = fixedscaleoffset.astype.as_ref().map(to_v3).transpose()?;
+    Ok(FixedScaleOffsetCodecConfiguration::V1(
+        FixedScaleOffsetCodecConfigurationV1 {
+            offset: fixedscaleoffset.offset,
+            scale: fixedscaleoffset.scale,
+            astype: astype.filter(|astype| astype != &dtype),
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v3::array::data_type::DataTypeMetadataV3;
+
+    use super::*;
+
+    #[test]
+    fn codec_fixedscaleoffset_numcodecs() {
+        let v2 = serde_json::from_str::<FixedScaleOffsetCodecConfigurationNumcodecs>(
+            r#"{"offset": 1000, "scale": 10, "dtype": "<f8", "astype": "|u1"}"#,
+        )
+        .unwrap();
+        let v3 = codec_fixedscaleoffset_v2_numcodecs_to_v3(&v2).unwrap();
+        assert_eq!(
+            v3,
+            FixedScaleOffsetCodecConfiguration::V1(FixedScaleOffsetCodecConfigurationV1 {
+                offset: 1000.0,
+                scale: 10.0,
+                astype: Some(DataTypeMetadataV3::UInt8)
+            })
+        );
+
+        let v2 = serde_json::from_str::<FixedScaleOffsetCodecConfigurationNumcodecs>(
+            r#"{"offset": 0.5, "scale": 2.0, "dtype": "<i4"}"#,
+        )
+        .unwrap();
+        let v3 = codec_fixedscaleoffset_v2_numcodecs_to_v3(&v2).unwrap();
+        assert_eq!(
+            v3,
+            FixedScaleOffsetCodecConfiguration::V1(FixedScaleOffsetCodecConfigurationV1 {
+                offset: 0.5,
+                scale: 2.0,
+                astype: None
+            })
+        );
+    }
+}
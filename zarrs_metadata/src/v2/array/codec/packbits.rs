@@ -0,0 +1,41 @@
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+
+use crate::v3::array::codec::packbits::{
+    PackBitsCodecConfiguration, PackBitsCodecConfigurationV1, PackBitsPaddingEncoding,
+};
+
+/// The identifier for the `packbits` codec.
+pub const IDENTIFIER: &str = "packbits";
+
+/// Configuration parameters for the `packbits` codec (numcodecs).
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Display, Default)]
+#[serde(deny_unknown_fields)]
+#[display("{}", serde_json::to_string(self).unwrap_or_default())]
+pub struct PackBitsCodecConfigurationNumcodecs {}
+
+/// Convert [`PackBitsCodecConfigurationNumcodecs`] to [`PackBitsCodecConfiguration`].
+///
+/// The `numcodecs` `packbits` codec encodes the number of padding bits in the first byte.
+#[must_use]
+pub fn codec_packbits_v2_numcodecs_to_v3(
+    _packbits: &PackBitsCodecConfigurationNumcodecs,
+) -> PackBitsCodecConfiguration {
+    PackBitsCodecConfiguration::V1(PackBitsCodecConfigurationV1 {
+        padding_encoding: PackBitsPaddingEncoding::FirstByte,
+        first_bit: None,
+        last_bit: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codec_packbits_numcodecs() {
+        let v2 = serde_json::from_str::<PackBitsCodecConfigurationNumcodecs>(r"{}").unwrap();
+        let PackBitsCodecConfiguration::V1(v3) = codec_packbits_v2_numcodecs_to_v3(&v2);
+        assert_eq!(v3.padding_encoding, PackBitsPaddingEncoding::FirstByte);
+    }
+}
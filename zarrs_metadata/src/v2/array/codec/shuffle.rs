@@ -0,0 +1,58 @@
+use std::num::NonZeroUsize;
+
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+
+use crate::v3::array::codec::shuffle::{ShuffleCodecConfiguration, ShuffleCodecConfigurationV1};
+
+/// The identifier for the `shuffle` codec.
+pub const IDENTIFIER: &str = "shuffle";
+
+/// Configuration parameters for the `shuffle` codec (numcodecs).
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Display)]
+#[serde(deny_unknown_fields)]
+#[display("{}", serde_json::to_string(self).unwrap_or_default())]
+pub struct ShuffleCodecConfigurationNumcodecs {
+    /// The size of an element in bytes.
+    #[serde(default = "default_elementsize")]
+    pub elementsize: NonZeroUsize,
+}
+
+const fn default_elementsize() -> NonZeroUsize {
+    // numcodecs defaults to 4 byte elements
+    // SAFETY: 4 is non-zero
+    unsafe { NonZeroUsize::new_unchecked(4) }
+}
+
+/// Convert [`ShuffleCodecConfigurationNumcodecs`] to [`ShuffleCodecConfiguration`].
+#[must_use]
+pub fn codec_shuffle_v2_numcodecs_to_v3(
+    shuffle: &ShuffleCodecConfigurationNumcodecs,
+) -> ShuffleCodecConfiguration {
+    ShuffleCodecConfiguration::V1(ShuffleCodecConfigurationV1 {
+        element_size: shuffle.elementsize,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codec_shuffle_numcodecs() {
+        let v2 =
+            serde_json::from_str::<ShuffleCodecConfigurationNumcodecs>(r#"{"elementsize": 2}"#)
+                .unwrap();
+        let ShuffleCodecConfiguration::V1(v3) = codec_shuffle_v2_numcodecs_to_v3(&v2);
+        assert_eq!(v3.element_size.get(), 2);
+
+        let v2 = serde_json::from_str::<ShuffleCodecConfigurationNumcodecs>(r"{}").unwrap();
+        let ShuffleCodecConfiguration::V1(v3) = codec_shuffle_v2_numcodecs_to_v3(&v2);
+        assert_eq!(v3.element_size.get(), 4);
+
+        assert!(serde_json::from_str::<ShuffleCodecConfigurationNumcodecs>(
+            r#"{"elementsize": 0}"#
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+
+/// The key of Zarr V2 consolidated metadata relative to a group.
+pub const CONSOLIDATED_METADATA_KEY: &str = ".zmetadata";
+
+/// Zarr V2 consolidated metadata (`.zmetadata`).
+///
+/// Consolidated metadata holds the `.zgroup`, `.zarray`, and `.zattrs` documents of a hierarchy in a single document, so that a hierarchy can be opened without listing the store.
+/// It is written by `zarr-python` (`zarr.consolidate_metadata`), `xarray`, and `netcdf-c`.
+///
+/// An example of consolidated metadata:
+/// ```json
+/// {
+///     "metadata": {
+///         ".zgroup": {"zarr_format": 2},
+///         "sst/.zarray": {"zarr_format": 2, "shape": [180, 360], "...": "..."},
+///         "sst/.zattrs": {"_ARRAY_DIMENSIONS": ["lat", "lon"]}
+///     },
+///     "zarr_consolidated_format": 1
+/// }
+/// ```
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ConsolidatedMetadataV2 {
+    /// The metadata documents, keyed by their store key relative to the consolidated metadata.
+    pub metadata: serde_json::Map<String, serde_json::Value>,
+    /// An integer defining the version of the consolidated metadata format. Must be `1`.
+    pub zarr_consolidated_format: monostate::MustBe!(1u64),
+}
+
+impl Default for ConsolidatedMetadataV2 {
+    fn default() -> Self {
+        Self::new(serde_json::Map::new())
+    }
+}
+
+impl ConsolidatedMetadataV2 {
+    /// Create Zarr V2 consolidated metadata from metadata documents keyed by their relative store key.
+    #[must_use]
+    pub fn new(metadata: serde_json::Map<String, serde_json::Value>) -> Self {
+        Self {
+            metadata,
+            zarr_consolidated_format: monostate::MustBe!(1u64),
+        }
+    }
+
+    /// Return the metadata document with the relative store `key` (e.g. `sst/.zarray`) if it exists.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&serde_json::Value> {
+        self.metadata.get(key)
+    }
+
+    /// Return the relative paths of the arrays and groups in the consolidated metadata, sorted.
+    ///
+    /// The path of the node holding the consolidated metadata is an empty string.
+    #[must_use]
+    pub fn node_paths(&self) -> Vec<&str> {
+        let mut paths: Vec<&str> = self
+            .metadata
+            .keys()
+            .filter_map(|key| {
+                [".zarray", ".zgroup"].iter().find_map(|name| {
+                    key.strip_suffix(name)
+                        .filter(|path| path.is_empty() || path.ends_with('/'))
+                        .map(|path| path.trim_end_matches('/'))
+                })
+            })
+            .collect();
+        paths.sort_unstable();
+        paths.dedup();
+        paths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn consolidated_metadata_v2() {
+        let json = r#"{
+            "metadata": {
+                ".zgroup": {"zarr_format": 2},
+                ".zattrs": {"title": "test"},
+                "forecast/.zgroup": {"zarr_format": 2},
+                "forecast/sst/.zarray": {"zarr_format": 2},
+                "sst/.zarray": {"zarr_format": 2},
+                "sst/.zattrs": {"_ARRAY_DIMENSIONS": ["lat", "lon"]}
+            },
+            "zarr_consolidated_format": 1
+        }"#;
+        let consolidated: ConsolidatedMetadataV2 = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            consolidated.node_paths(),
+            vec!["", "forecast", "forecast/sst", "sst"]
+        );
+        assert_eq!(
+            consolidated.get("sst/.zattrs").unwrap()["_ARRAY_DIMENSIONS"][0],
+            "lat"
+        );
+        assert!(consolidated.get("missing/.zarray").is_none());
+
+        let json = r#"{"metadata": {}, "zarr_consolidated_format": 2}"#;
+        assert!(serde_json::from_str::<ConsolidatedMetadataV2>(json).is_err());
+    }
+}
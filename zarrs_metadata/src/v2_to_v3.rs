@@ -1,14 +1,28 @@
 use thiserror::Error;
 
 use crate::{
+    nczarr::{NCZarrArray, NCZARR_ARRAY_KEY},
     v2::{
         array::{
             codec::{
+                bitshuffle::{
+                    codec_bitshuffle_v2_imagecodecs_to_v3, BitshuffleCodecConfigurationImagecodecs,
+                },
                 blosc::{codec_blosc_v2_numcodecs_to_v3, BloscCodecConfigurationNumcodecs},
+                delta::{codec_delta_v2_numcodecs_to_v3, DeltaCodecConfigurationNumcodecs},
+                fixedscaleoffset::{
+                    codec_fixedscaleoffset_v2_numcodecs_to_v3,
+                    FixedScaleOffsetCodecConfigurationNumcodecs,
+                },
+                packbits::{
+                    codec_packbits_v2_numcodecs_to_v3, PackBitsCodecConfigurationNumcodecs,
+                },
+                shuffle::{codec_shuffle_v2_numcodecs_to_v3, ShuffleCodecConfigurationNumcodecs},
                 zfpy::{codec_zfpy_v2_numcodecs_to_v3, ZfpyCodecConfigurationNumcodecs},
             },
             data_type_metadata_v2_to_endianness, ArrayMetadataV2Order, DataTypeMetadataV2,
             DataTypeMetadataV2InvalidEndiannessError, FillValueMetadataV2,
+            ARRAY_DIMENSIONS_ATTRIBUTE,
         },
         ArrayMetadataV2, GroupMetadataV2,
     },
@@ -27,7 +41,7 @@ use crate::{
     },
 };
 
-use super::v3::array::data_type::DataTypeMetadataV3;
+use super::{v3::array::data_type::DataTypeMetadataV3, DimensionName};
 
 /// Convert Zarr V2 group metadata to V3.
 #[allow(clippy::too_many_lines)]
@@ -50,6 +64,9 @@ pub enum ArrayMetadataV2ToV3ConversionError {
     /// An unsupported codec.
     #[error("unsupported codec {_0} with configuration {_1:?}")]
     UnsupportedCodec(String, serde_json::Map<String, serde_json::Value>),
+    /// Unsupported filters, identified by their `id`.
+    #[error("unsupported filters: {}", .0.join(", "))]
+    UnsupportedFilters(Vec<String>),
     /// An unsupported fill value.
     #[error("unsupported fill value {_1:?} for data type {_0}")]
     UnsupportedFillValue(String, FillValueMetadataV2),
@@ -140,8 +157,20 @@ pub fn array_metadata_v2_to_v3(
 
     // Filters (array to array or array to bytes codecs)
     let mut has_array_to_bytes = false;
+    // Filters that are bytes to bytes codecs, which follow the array to bytes codec
+    let mut bytes_to_bytes_filters = vec![];
+    // Filters without a V3 equivalent, which are reported together
+    let mut unsupported_filters = vec![];
     if let Some(filters) = &array_metadata_v2.filters {
         for filter in filters {
+            if !bytes_to_bytes_filters.is_empty()
+                && filter.id() != crate::v2::array::codec::shuffle::IDENTIFIER
+            {
+                return Err(ArrayMetadataV2ToV3ConversionError::Other(format!(
+                    "the {} filter must not follow a shuffle filter",
+                    filter.id()
+                )));
+            }
             // TODO: Add a V2 registry with V2 to V3 conversion functions
             match filter.id() {
                 crate::v2::array::codec::vlen_array::IDENTIFIER
@@ -154,15 +183,75 @@ pub fn array_metadata_v2_to_v3(
                     )?;
                     codecs.push(vlen_v2_metadata);
                 }
-                _ => {
+                crate::v2::array::codec::delta::IDENTIFIER => {
+                    let delta_v2_metadata =
+                        serde_json::from_value::<DeltaCodecConfigurationNumcodecs>(
+                            serde_json::to_value(filter.configuration())?,
+                        )?;
+                    let configuration = codec_delta_v2_numcodecs_to_v3(&delta_v2_metadata)?;
+                    let delta_v3_metadata = MetadataV3::new_with_serializable_configuration(
+                        crate::v3::array::codec::delta::IDENTIFIER,
+                        &configuration,
+                    )?;
+                    codecs.push(delta_v3_metadata);
+                }
+                crate::v2::array::codec::fixedscaleoffset::IDENTIFIER => {
+                    let fixedscaleoffset_v2_metadata =
+                        serde_json::from_value::<FixedScaleOffsetCodecConfigurationNumcodecs>(
+                            serde_json::to_value(filter.configuration())?,
+                        )?;
+                    let configuration =
+                        codec_fixedscaleoffset_v2_numcodecs_to_v3(&fixedscaleoffset_v2_metadata)?;
+                    let fixedscaleoffset_v3_metadata =
+                        MetadataV3::new_with_serializable_configuration(
+                            crate::v3::array::codec::fixedscaleoffset::IDENTIFIER,
+                            &configuration,
+                        )?;
+                    codecs.push(fixedscaleoffset_v3_metadata);
+                }
+                crate::v3::array::codec::bitround::IDENTIFIER => {
+                    // bitround is v2/v3 compatible
                     codecs.push(MetadataV3::new_with_configuration(
                         filter.id(),
                         filter.configuration().clone(),
                     ));
                 }
+                crate::v2::array::codec::packbits::IDENTIFIER => {
+                    has_array_to_bytes = true;
+                    let packbits_v2_metadata =
+                        serde_json::from_value::<PackBitsCodecConfigurationNumcodecs>(
+                            serde_json::to_value(filter.configuration())?,
+                        )?;
+                    let configuration = codec_packbits_v2_numcodecs_to_v3(&packbits_v2_metadata);
+                    let packbits_v3_metadata = MetadataV3::new_with_serializable_configuration(
+                        crate::v3::array::codec::packbits::IDENTIFIER,
+                        &configuration,
+                    )?;
+                    codecs.push(packbits_v3_metadata);
+                }
+                crate::v2::array::codec::shuffle::IDENTIFIER => {
+                    let shuffle_v2_metadata =
+                        serde_json::from_value::<ShuffleCodecConfigurationNumcodecs>(
+                            serde_json::to_value(filter.configuration())?,
+                        )?;
+                    let configuration = codec_shuffle_v2_numcodecs_to_v3(&shuffle_v2_metadata);
+                    let shuffle_v3_metadata = MetadataV3::new_with_serializable_configuration(
+                        crate::v3::array::codec::shuffle::IDENTIFIER,
+                        &configuration,
+                    )?;
+                    bytes_to_bytes_filters.push(shuffle_v3_metadata);
+                }
+                _ => {
+                    unsupported_filters.push(filter.id().to_string());
+                }
             }
         }
     }
+    if !unsupported_filters.is_empty() {
+        return Err(ArrayMetadataV2ToV3ConversionError::UnsupportedFilters(
+            unsupported_filters,
+        ));
+    }
 
     // Compressor (array to bytes codec)
     if let Some(compressor) = &array_metadata_v2.compressor {
@@ -200,6 +289,8 @@ pub fn array_metadata_v2_to_v3(
         codecs.push(bytes_metadata);
     }
 
+    codecs.extend(bytes_to_bytes_filters);
+
     // Compressor (bytes to bytes codec)
     if let Some(compressor) = &array_metadata_v2.compressor {
         match compressor.id() {
@@ -217,6 +308,26 @@ pub fn array_metadata_v2_to_v3(
                     &configuration,
                 )?);
             }
+            crate::v2::array::codec::bitshuffle::IDENTIFIER => {
+                let bitshuffle = serde_json::from_value::<BitshuffleCodecConfigurationImagecodecs>(
+                    serde_json::to_value(compressor.configuration())?,
+                )?;
+                let configuration = codec_bitshuffle_v2_imagecodecs_to_v3(&bitshuffle)?;
+                codecs.push(MetadataV3::new_with_serializable_configuration(
+                    crate::v3::array::codec::bitshuffle::IDENTIFIER,
+                    &configuration,
+                )?);
+            }
+            crate::v2::array::codec::shuffle::IDENTIFIER => {
+                let shuffle = serde_json::from_value::<ShuffleCodecConfigurationNumcodecs>(
+                    serde_json::to_value(compressor.configuration())?,
+                )?;
+                let configuration = codec_shuffle_v2_numcodecs_to_v3(&shuffle);
+                codecs.push(MetadataV3::new_with_serializable_configuration(
+                    crate::v3::array::codec::shuffle::IDENTIFIER,
+                    &configuration,
+                )?);
+            }
             _ => codecs.push(MetadataV3::new_with_configuration(
                 compressor.id(),
                 compressor.configuration().clone(),
@@ -231,11 +342,35 @@ pub fn array_metadata_v2_to_v3(
         },
     )?;
 
-    let attributes = array_metadata_v2.attributes.clone();
+    // Dimension names in the xarray `_ARRAY_DIMENSIONS` attribute become V3 dimension names
+    let mut attributes = array_metadata_v2.attributes.clone();
+    let dimension_names = attributes
+        .get(ARRAY_DIMENSIONS_ATTRIBUTE)
+        .and_then(|dimension_names| {
+            serde_json::from_value::<Vec<DimensionName>>(dimension_names.clone()).ok()
+        })
+        .filter(|dimension_names| dimension_names.len() == shape.len());
+    if dimension_names.is_some() {
+        attributes.remove(ARRAY_DIMENSIONS_ATTRIBUTE);
+    }
+
+    // Otherwise, the dimensions referenced by the NCZarr `_nczarr_array` attribute become V3 dimension names
+    let dimension_names = dimension_names.or_else(|| {
+        let dimension_names = attributes
+            .get(NCZARR_ARRAY_KEY)
+            .and_then(|nczarr_array| {
+                serde_json::from_value::<NCZarrArray>(nczarr_array.clone()).ok()
+            })
+            .map(|nczarr_array| nczarr_array.dimension_names())
+            .filter(|dimension_names| dimension_names.len() == shape.len())?;
+        attributes.remove(NCZARR_ARRAY_KEY);
+        Some(dimension_names)
+    });
 
     Ok(
         ArrayMetadataV3::new(shape, chunk_grid, data_type, fill_value, codecs)
             .with_attributes(attributes)
+            .with_dimension_names(dimension_names)
             .with_additional_fields(array_metadata_v2.additional_fields.clone())
             .with_chunk_key_encoding(chunk_key_encoding),
     )
@@ -14,8 +14,12 @@ pub mod data_type;
 pub mod codec {
     /// `bitround` codec metadata.
     pub mod bitround;
+    /// `bitshuffle` codec metadata.
+    pub mod bitshuffle;
     /// `blosc` codec metadata.
     pub mod blosc;
+    /// `blosc2` codec metadata.
+    pub mod blosc2;
     /// `bytes` codec metadata.
     pub mod bytes;
 
@@ -23,14 +27,32 @@ pub mod codec {
     pub mod bz2;
     /// `crc32c` codec metadata.
     pub mod crc32c;
+    /// `delta` codec metadata.
+    pub mod delta;
+    /// `fixedscaleoffset` codec metadata.
+    pub mod fixedscaleoffset;
+    /// `framed` codec metadata.
+    pub mod framed;
     /// `gdeflate` codec metadata.
     pub mod gdeflate;
     /// `gzip` codec metadata.
     pub mod gzip;
+    /// `jpegxl` codec metadata.
+    pub mod jpegxl;
+    /// `packbits` codec metadata.
+    pub mod packbits;
     /// `pcodec` codec metadata.
     pub mod pcodec;
+    /// `png` codec metadata.
+    pub mod png;
+    /// `rle` codec metadata.
+    pub mod rle;
     /// `sharding` codec metadata.
     pub mod sharding;
+    /// `shuffle` codec metadata.
+    pub mod shuffle;
+    /// `sz3` codec metadata.
+    pub mod sz3;
     /// `transpose` codec metadata.
     pub mod transpose;
     /// `vlen` codec metadata.
@@ -55,6 +77,8 @@ pub mod chunk_grid {
 pub mod chunk_key_encoding {
     /// `default` chunk key encoding metadata.
     pub mod default;
+    /// `hash_prefix` chunk key encoding metadata.
+    pub mod hash_prefix;
     /// `v2` chunk key encoding metadata.
     pub mod v2;
 }
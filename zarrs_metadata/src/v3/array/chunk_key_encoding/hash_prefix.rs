@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+
+use derive_more::Display;
+
+use crate::ChunkKeySeparator;
+
+/// The identifier for the `hash_prefix` chunk key encoding.
+// TODO: ZEP for hash_prefix
+pub const IDENTIFIER: &str = "hash_prefix";
+
+/// A `hash_prefix` chunk key encoding configuration.
+///
+/// The key for a chunk is the key of the `default` chunk key encoding prefixed by a hash of the chunk grid indices and the separator.
+/// The hash is the first `prefix_length` lowercase hexadecimal digits of the 64-bit FNV-1a hash of the chunk grid indices, each encoded as 8 little-endian bytes.
+///
+/// ### Example
+/// ```rust
+/// # let JSON = r#"
+/// {
+///     "separator": "/",
+///     "prefix_length": 4
+/// }
+/// # "#;
+/// # use zarrs_metadata::v3::array::chunk_key_encoding::hash_prefix::HashPrefixChunkKeyEncodingConfiguration;
+/// # let configuration: HashPrefixChunkKeyEncodingConfiguration = serde_json::from_str(JSON).unwrap();
+/// ```
+/// The key for the chunk with grid indices `[1, 23, 45]` is `302d/c/1/23/45`.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Display)]
+#[serde(deny_unknown_fields)]
+#[display("{}", serde_json::to_string(self).unwrap_or_default())]
+pub struct HashPrefixChunkKeyEncodingConfiguration {
+    /// The chunk key separator.
+    #[serde(default = "default_separator")]
+    pub separator: ChunkKeySeparator,
+    /// The number of hexadecimal digits of the hash prefix, from 1 to 16.
+    #[serde(default = "default_prefix_length")]
+    pub prefix_length: u8,
+}
+
+const fn default_separator() -> ChunkKeySeparator {
+    ChunkKeySeparator::Slash
+}
+
+const fn default_prefix_length() -> u8 {
+    4
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunk_key_encoding_hash_prefix_config() {
+        let configuration =
+            serde_json::from_str::<HashPrefixChunkKeyEncodingConfiguration>("{}").unwrap();
+        assert_eq!(
+            configuration,
+            HashPrefixChunkKeyEncodingConfiguration {
+                separator: ChunkKeySeparator::Slash,
+                prefix_length: 4
+            }
+        );
+        let configuration = serde_json::from_str::<HashPrefixChunkKeyEncodingConfiguration>(
+            r#"{"separator":".","prefix_length":2}"#,
+        )
+        .unwrap();
+        assert_eq!(configuration.separator, ChunkKeySeparator::Dot);
+        assert_eq!(configuration.prefix_length, 2);
+        assert!(
+            serde_json::from_str::<HashPrefixChunkKeyEncodingConfiguration>(r#"{"hash":"md5"}"#)
+                .is_err()
+        );
+    }
+}
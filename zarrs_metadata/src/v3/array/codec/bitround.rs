@@ -25,11 +25,57 @@ pub enum BitroundCodecConfiguration {
 /// # use zarrs_metadata::v3::array::codec::bitround::BitroundCodecConfigurationV1;
 /// # let configuration: BitroundCodecConfigurationV1 = serde_json::from_str(JSON).unwrap();
 /// ```
+///
+/// ### Example: Keep 10, 7, and 4 bits of the mantissa for each index along axis 2
+/// ```rust
+/// # let JSON = r#"
+/// {
+///     "keepbits": [10, 7, 4],
+///     "axis": 2
+/// }
+/// # "#;
+/// # use zarrs_metadata::v3::array::codec::bitround::BitroundCodecConfigurationV1;
+/// # let configuration: BitroundCodecConfigurationV1 = serde_json::from_str(JSON).unwrap();
+/// ```
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Display)]
 #[serde(deny_unknown_fields)]
+#[display("{}", serde_json::to_string(self).unwrap_or_default())]
 pub struct BitroundCodecConfigurationV1 {
-    /// The number of mantissa bits to keep for a floating point data type.
-    pub keepbits: u32,
+    /// The number of mantissa bits to keep for a floating point data type, or the number of significant bits to keep for an integer data type.
+    pub keepbits: BitroundKeepbits,
+    /// The axis indexed by [`BitroundKeepbits::PerIndex`] keepbits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub axis: Option<usize>,
+}
+
+impl BitroundCodecConfigurationV1 {
+    /// Create a new `bitround` codec configuration with the same `keepbits` for all elements.
+    #[must_use]
+    pub const fn new(keepbits: u32) -> Self {
+        Self {
+            keepbits: BitroundKeepbits::Uniform(keepbits),
+            axis: None,
+        }
+    }
+
+    /// Create a new `bitround` codec configuration with `keepbits` for each index along `axis`.
+    #[must_use]
+    pub const fn new_per_index(axis: usize, keepbits: Vec<u32>) -> Self {
+        Self {
+            keepbits: BitroundKeepbits::PerIndex(keepbits),
+            axis: Some(axis),
+        }
+    }
+}
+
+/// The `keepbits` of the `bitround` codec.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, From)]
+#[serde(untagged)]
+pub enum BitroundKeepbits {
+    /// The same keepbits for all elements.
+    Uniform(u32),
+    /// The keepbits for each index along an axis (e.g. for each channel of a multichannel array).
+    PerIndex(Vec<u32>),
 }
 
 #[cfg(test)]
@@ -60,4 +106,27 @@ mod tests {
         )
         .unwrap();
     }
+
+    #[test]
+    fn codec_bitround_config_per_index() {
+        let configuration = serde_json::from_str::<BitroundCodecConfigurationV1>(
+            r#"{
+                "keepbits": [10, 7, 4],
+                "axis": 2
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            configuration,
+            BitroundCodecConfigurationV1::new_per_index(2, vec![10, 7, 4])
+        );
+        assert_eq!(
+            configuration.to_string(),
+            r#"{"keepbits":[10,7,4],"axis":2}"#
+        );
+        assert_eq!(
+            BitroundCodecConfigurationV1::new(10).to_string(),
+            r#"{"keepbits":10}"#
+        );
+    }
 }
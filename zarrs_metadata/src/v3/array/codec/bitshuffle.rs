@@ -0,0 +1,141 @@
+use std::num::NonZeroUsize;
+
+use derive_more::{Display, From};
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// The identifier for the `bitshuffle` codec.
+// TODO: ZEP for bitshuffle
+pub const IDENTIFIER: &str = "bitshuffle";
+
+/// A wrapper to handle various versions of `bitshuffle` codec configuration parameters.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Display, From)]
+#[serde(untagged)]
+pub enum BitshuffleCodecConfiguration {
+    /// Version 1.0 draft.
+    V1(BitshuffleCodecConfigurationV1),
+}
+
+/// `bitshuffle` codec configuration parameters (version 1.0 draft).
+///
+/// ### Example: Bitshuffle 4 byte elements in blocks of the default size
+/// ```rust
+/// # let JSON = r#"
+/// {
+///     "element_size": 4
+/// }
+/// # "#;
+/// # use zarrs_metadata::v3::array::codec::bitshuffle::BitshuffleCodecConfigurationV1;
+/// # let configuration: BitshuffleCodecConfigurationV1 = serde_json::from_str(JSON).unwrap();
+/// ```
+///
+/// ### Example: Bitshuffle 2 byte elements in blocks of 1024 elements
+/// ```rust
+/// # let JSON = r#"
+/// {
+///     "element_size": 2,
+///     "block_size": 1024
+/// }
+/// # "#;
+/// # use zarrs_metadata::v3::array::codec::bitshuffle::BitshuffleCodecConfigurationV1;
+/// # let configuration: BitshuffleCodecConfigurationV1 = serde_json::from_str(JSON).unwrap();
+/// ```
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Display)]
+#[serde(deny_unknown_fields)]
+#[display("{}", serde_json::to_string(self).unwrap_or_default())]
+pub struct BitshuffleCodecConfigurationV1 {
+    /// The size of an element in bytes.
+    pub element_size: NonZeroUsize,
+    /// The number of elements in a block.
+    ///
+    /// Defaults to the `bitshuffle` library default, which targets blocks of 8 KiB.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_size: Option<BitshuffleBlockSize>,
+}
+
+/// The number of elements in a `bitshuffle` block. A non-zero multiple of 8.
+#[derive(Serialize, Copy, Clone, Debug, Eq, PartialEq)]
+pub struct BitshuffleBlockSize(NonZeroUsize);
+
+impl<'de> Deserialize<'de> for BitshuffleBlockSize {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let block_size = usize::deserialize(d)?;
+        Self::new(block_size).ok_or_else(|| {
+            serde::de::Error::custom("bitshuffle block size must be a non-zero multiple of 8")
+        })
+    }
+}
+
+impl BitshuffleBlockSize {
+    /// Create a new block size.
+    ///
+    /// Returns [`None`] if `block_size` is not a non-zero multiple of 8.
+    #[must_use]
+    pub const fn new(block_size: usize) -> Option<Self> {
+        if block_size % 8 == 0 {
+            if let Some(block_size) = NonZeroUsize::new(block_size) {
+                return Some(Self(block_size));
+            }
+        }
+        None
+    }
+
+    /// The number of elements in a block.
+    #[must_use]
+    pub const fn get(&self) -> usize {
+        self.0.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codec_bitshuffle_valid() {
+        let configuration = serde_json::from_str::<BitshuffleCodecConfiguration>(
+            r#"{
+            "element_size": 2,
+            "block_size": 1024
+        }"#,
+        )
+        .unwrap();
+        let BitshuffleCodecConfiguration::V1(configuration) = configuration;
+        assert_eq!(configuration.element_size.get(), 2);
+        assert_eq!(configuration.block_size.unwrap().get(), 1024);
+    }
+
+    #[test]
+    fn codec_bitshuffle_valid_default_block_size() {
+        let configuration = serde_json::from_str::<BitshuffleCodecConfiguration>(
+            r#"{
+            "element_size": 4
+        }"#,
+        )
+        .unwrap();
+        assert_eq!(configuration.to_string(), r#"{"element_size":4}"#);
+    }
+
+    #[test]
+    fn codec_bitshuffle_invalid() {
+        assert!(serde_json::from_str::<BitshuffleCodecConfiguration>(
+            r#"{
+            "element_size": 0
+        }"#,
+        )
+        .is_err());
+        assert!(serde_json::from_str::<BitshuffleCodecConfiguration>(
+            r#"{
+            "element_size": 4,
+            "block_size": 100
+        }"#,
+        )
+        .is_err());
+        assert!(serde_json::from_str::<BitshuffleCodecConfiguration>(
+            r#"{
+            "element_size": 4,
+            "block_size": 0
+        }"#,
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,128 @@
+use std::num::NonZeroU64;
+
+use derive_more::{Display, From};
+use serde::{Deserialize, Serialize};
+
+pub use super::blosc::{BloscCompressionLevel, BloscShuffleMode};
+
+/// The identifier for the `blosc2` codec.
+// TODO: ZEP for blosc2
+pub const IDENTIFIER: &str = "blosc2";
+
+/// The `blosc2` compressor.
+///
+/// See <https://www.blosc.org/pages/>.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Blosc2Compressor {
+    /// [BloscLZ](https://github.com/Blosc/c-blosc2/blob/main/blosc/blosclz.h): blosc default compressor, heavily based on [FastLZ](http://fastlz.org/).
+    BloscLZ,
+    /// [LZ4](http://fastcompression.blogspot.com/p/lz4.html): a compact, very popular and fast compressor.
+    LZ4,
+    /// [LZ4HC](http://fastcompression.blogspot.com/p/lz4.html): a tweaked version of LZ4, produces better compression ratios at the expense of speed.
+    LZ4HC,
+    /// [Zlib](http://www.zlib.net/): a classic; somewhat slower than the previous ones, but achieving better compression ratios.
+    Zlib,
+    /// [Zstd](http://www.zstd.net/): an extremely well balanced codec; it provides the best compression ratios among the others above, and at reasonably fast speed.
+    Zstd,
+}
+
+/// A wrapper to handle various versions of `blosc2` codec configuration parameters.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Display, From)]
+#[serde(untagged)]
+pub enum Blosc2CodecConfiguration {
+    /// Version 1.0 draft.
+    V1(Blosc2CodecConfigurationV1),
+}
+
+/// `blosc2` codec configuration parameters (version 1.0 draft).
+///
+/// ### Example: Zstd compression with 16x16 blocks
+/// ```rust
+/// # let JSON = r#"
+/// {
+///     "cname": "zstd",
+///     "clevel": 5,
+///     "shuffle": "shuffle",
+///     "blockshape": [16, 16]
+/// }
+/// # "#;
+/// # use zarrs_metadata::v3::array::codec::blosc2::Blosc2CodecConfigurationV1;
+/// # let configuration: Blosc2CodecConfigurationV1 = serde_json::from_str(JSON).unwrap();
+/// ```
+///
+/// ### Example: LZ4 compression with an automatic block shape
+/// ```rust
+/// # let JSON = r#"
+/// {
+///     "cname": "lz4",
+///     "clevel": 9
+/// }
+/// # "#;
+/// # use zarrs_metadata::v3::array::codec::blosc2::Blosc2CodecConfigurationV1;
+/// # let configuration: Blosc2CodecConfigurationV1 = serde_json::from_str(JSON).unwrap();
+/// ```
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Display)]
+#[serde(deny_unknown_fields)]
+#[display("{}", serde_json::to_string(self).unwrap_or_default())]
+pub struct Blosc2CodecConfigurationV1 {
+    /// The compressor.
+    pub cname: Blosc2Compressor,
+    /// The compression level.
+    pub clevel: BloscCompressionLevel,
+    /// The shuffle mode.
+    ///
+    /// Defaults to noshuffle if unspecified.
+    #[serde(default)]
+    pub shuffle: BloscShuffleMode,
+    /// The shape of the independently compressed blocks of a chunk.
+    ///
+    /// Automatically determined if unspecified.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blockshape: Option<Vec<NonZeroU64>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codec_blosc2_valid() {
+        let configuration = serde_json::from_str::<Blosc2CodecConfiguration>(
+            r#"
+        {
+            "cname": "zstd",
+            "clevel": 5,
+            "shuffle": "bitshuffle",
+            "blockshape": [4, 8]
+        }"#,
+        )
+        .unwrap();
+        let Blosc2CodecConfiguration::V1(configuration) = configuration;
+        assert_eq!(configuration.shuffle, BloscShuffleMode::BitShuffle);
+        assert_eq!(
+            configuration.blockshape,
+            Some(vec![
+                NonZeroU64::new(4).unwrap(),
+                NonZeroU64::new(8).unwrap()
+            ])
+        );
+    }
+
+    #[test]
+    fn codec_blosc2_invalid() {
+        // snappy is not supported by blosc2
+        assert!(serde_json::from_str::<Blosc2CodecConfiguration>(
+            r#"{"cname": "snappy", "clevel": 5}"#
+        )
+        .is_err());
+        assert!(serde_json::from_str::<Blosc2CodecConfiguration>(
+            r#"{"cname": "lz4", "clevel": 10}"#
+        )
+        .is_err());
+        assert!(serde_json::from_str::<Blosc2CodecConfiguration>(
+            r#"{"cname": "lz4", "clevel": 5, "blockshape": [0, 8]}"#
+        )
+        .is_err());
+    }
+}
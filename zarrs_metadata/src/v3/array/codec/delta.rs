@@ -0,0 +1,90 @@
+use derive_more::{Display, From};
+use serde::{Deserialize, Serialize};
+
+use crate::v3::array::data_type::DataTypeMetadataV3;
+
+/// The identifier for the `delta` codec.
+// TODO: ZEP for delta
+pub const IDENTIFIER: &str = "delta";
+
+/// A wrapper to handle various versions of `delta` codec configuration parameters.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Display, From)]
+#[serde(untagged)]
+pub enum DeltaCodecConfiguration {
+    /// Version 1.0 draft.
+    V1(DeltaCodecConfigurationV1),
+}
+
+/// `delta` codec configuration parameters (version 1.0 draft).
+///
+/// ### Example: Encode deltas with the decoded data type
+/// ```rust
+/// # let JSON = r#"
+/// {}
+/// # "#;
+/// # use zarrs_metadata::v3::array::codec::delta::DeltaCodecConfigurationV1;
+/// # let configuration: DeltaCodecConfigurationV1 = serde_json::from_str(JSON).unwrap();
+/// ```
+///
+/// ### Example: Encode deltas as 16-bit integers
+/// ```rust
+/// # let JSON = r#"
+/// {
+///     "astype": "int16"
+/// }
+/// # "#;
+/// # use zarrs_metadata::v3::array::codec::delta::DeltaCodecConfigurationV1;
+/// # let configuration: DeltaCodecConfigurationV1 = serde_json::from_str(JSON).unwrap();
+/// ```
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Display, Default)]
+#[serde(deny_unknown_fields)]
+#[display("{}", serde_json::to_string(self).unwrap_or_default())]
+pub struct DeltaCodecConfigurationV1 {
+    /// The data type of the encoded deltas.
+    ///
+    /// Defaults to the decoded data type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub astype: Option<DataTypeMetadataV3>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v3::MetadataV3;
+
+    use super::*;
+
+    #[test]
+    fn codec_delta_metadata() {
+        serde_json::from_str::<MetadataV3>(
+            r#"{
+            "name": "delta",
+            "configuration": {
+                "astype": "int16"
+            }
+        }"#,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn codec_delta_config() {
+        let configuration = serde_json::from_str::<DeltaCodecConfiguration>("{}").unwrap();
+        assert_eq!(
+            configuration,
+            DeltaCodecConfiguration::V1(DeltaCodecConfigurationV1 { astype: None })
+        );
+        let configuration =
+            serde_json::from_str::<DeltaCodecConfiguration>(r#"{"astype":"uint8"}"#).unwrap();
+        assert_eq!(
+            configuration,
+            DeltaCodecConfiguration::V1(DeltaCodecConfigurationV1 {
+                astype: Some(DataTypeMetadataV3::UInt8)
+            })
+        );
+    }
+
+    #[test]
+    fn codec_delta_config_invalid() {
+        assert!(serde_json::from_str::<DeltaCodecConfiguration>(r#"{"dtype":"uint8"}"#).is_err());
+    }
+}
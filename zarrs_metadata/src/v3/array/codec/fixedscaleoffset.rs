@@ -0,0 +1,104 @@
+use derive_more::{Display, From};
+use serde::{Deserialize, Serialize};
+
+use crate::v3::array::data_type::DataTypeMetadataV3;
+
+/// The identifier for the `fixedscaleoffset` codec.
+// TODO: ZEP for fixedscaleoffset
+pub const IDENTIFIER: &str = "fixedscaleoffset";
+
+/// A wrapper to handle various versions of `fixedscaleoffset` codec configuration parameters.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Display, From)]
+#[serde(untagged)]
+pub enum FixedScaleOffsetCodecConfiguration {
+    /// Version 1.0 draft.
+    V1(FixedScaleOffsetCodecConfigurationV1),
+}
+
+/// `fixedscaleoffset` codec configuration parameters (version 1.0 draft).
+///
+/// Elements are encoded as `round((x - offset) * scale)` and decoded as `x / scale + offset`.
+///
+/// ### Example: Encode with the decoded data type
+/// ```rust
+/// # let JSON = r#"
+/// {
+///     "offset": 1000,
+///     "scale": 10
+/// }
+/// # "#;
+/// # use zarrs_metadata::v3::array::codec::fixedscaleoffset::FixedScaleOffsetCodecConfigurationV1;
+/// # let configuration: FixedScaleOffsetCodecConfigurationV1 = serde_json::from_str(JSON).unwrap();
+/// ```
+///
+/// ### Example: Encode as 8-bit unsigned integers
+/// ```rust
+/// # let JSON = r#"
+/// {
+///     "offset": 1000,
+///     "scale": 10,
+///     "astype": "uint8"
+/// }
+/// # "#;
+/// # use zarrs_metadata::v3::array::codec::fixedscaleoffset::FixedScaleOffsetCodecConfigurationV1;
+/// # let configuration: FixedScaleOffsetCodecConfigurationV1 = serde_json::from_str(JSON).unwrap();
+/// ```
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Display)]
+#[serde(deny_unknown_fields)]
+#[display("{}", serde_json::to_string(self).unwrap_or_default())]
+pub struct FixedScaleOffsetCodecConfigurationV1 {
+    /// The value subtracted from each element before scaling.
+    pub offset: f64,
+    /// The value each element is multiplied by after subtracting the offset.
+    pub scale: f64,
+    /// The data type of the encoded elements.
+    ///
+    /// Defaults to the decoded data type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub astype: Option<DataTypeMetadataV3>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v3::MetadataV3;
+
+    use super::*;
+
+    #[test]
+    fn codec_fixedscaleoffset_metadata() {
+        serde_json::from_str::<MetadataV3>(
+            r#"{
+            "name": "fixedscaleoffset",
+            "configuration": {
+                "offset": 1000,
+                "scale": 10,
+                "astype": "uint8"
+            }
+        }"#,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn codec_fixedscaleoffset_config() {
+        let configuration = serde_json::from_str::<FixedScaleOffsetCodecConfiguration>(
+            r#"{"offset":-1.5,"scale":100}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            configuration,
+            FixedScaleOffsetCodecConfiguration::V1(FixedScaleOffsetCodecConfigurationV1 {
+                offset: -1.5,
+                scale: 100.0,
+                astype: None
+            })
+        );
+    }
+
+    #[test]
+    fn codec_fixedscaleoffset_config_invalid() {
+        assert!(
+            serde_json::from_str::<FixedScaleOffsetCodecConfiguration>(r#"{"offset":0}"#).is_err()
+        );
+    }
+}
@@ -0,0 +1,92 @@
+use std::num::NonZeroU64;
+
+use derive_more::{Display, From};
+use serde::{Deserialize, Serialize};
+
+use crate::v3::MetadataV3;
+
+/// The identifier for the `framed` codec.
+// TODO: ZEP for framed
+pub const IDENTIFIER: &str = "framed";
+
+/// A wrapper to handle various versions of `framed` codec configuration parameters.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Display, From)]
+#[serde(untagged)]
+pub enum FramedCodecConfiguration {
+    /// Version 1.0 draft.
+    V1(FramedCodecConfigurationV1),
+}
+
+/// `framed` codec configuration parameters (version 1.0 draft).
+///
+/// ### Example: Compress frames of 64 KiB with `zstd`
+/// ```rust
+/// # let JSON = r#"
+/// {
+///     "frame_size": 65536,
+///     "codecs": [
+///         {
+///             "name": "zstd",
+///             "configuration": {
+///                 "level": 1,
+///                 "checksum": false
+///             }
+///         }
+///     ]
+/// }
+/// # "#;
+/// # use zarrs_metadata::v3::array::codec::framed::FramedCodecConfigurationV1;
+/// # let configuration: FramedCodecConfigurationV1 = serde_json::from_str(JSON).unwrap();
+/// ```
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Display)]
+#[serde(deny_unknown_fields)]
+#[display("{}", serde_json::to_string(self).unwrap_or_default())]
+pub struct FramedCodecConfigurationV1 {
+    /// The size of a decoded frame in bytes.
+    ///
+    /// The last frame is smaller if the size of the decoded value is not a multiple of the frame size.
+    pub frame_size: NonZeroU64,
+    /// A list of bytes to bytes codecs to be used for encoding and decoding each frame.
+    pub codecs: Vec<MetadataV3>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codec_framed_valid() {
+        let configuration = serde_json::from_str::<FramedCodecConfiguration>(
+            r#"{
+            "frame_size": 1024,
+            "codecs": [{"name": "gzip", "configuration": {"level": 5}}]
+        }"#,
+        )
+        .unwrap();
+        let FramedCodecConfiguration::V1(configuration) = &configuration;
+        assert_eq!(configuration.frame_size.get(), 1024);
+        assert_eq!(configuration.codecs.len(), 1);
+        assert_eq!(configuration.codecs[0].name(), "gzip");
+        assert_eq!(
+            configuration.to_string(),
+            r#"{"frame_size":1024,"codecs":[{"name":"gzip","configuration":{"level":5}}]}"#
+        );
+    }
+
+    #[test]
+    fn codec_framed_invalid() {
+        assert!(serde_json::from_str::<FramedCodecConfiguration>(
+            r#"{
+            "frame_size": 0,
+            "codecs": []
+        }"#,
+        )
+        .is_err());
+        assert!(serde_json::from_str::<FramedCodecConfiguration>(
+            r#"{
+            "frame_size": 1024
+        }"#,
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,164 @@
+use derive_more::{Display, From};
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// The identifier for the `jpegxl` codec.
+// TODO: ZEP for jpegxl
+pub const IDENTIFIER: &str = "jpegxl";
+
+/// A wrapper to handle various versions of `jpegxl` codec configuration parameters.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Display, From)]
+#[serde(untagged)]
+pub enum JpegXlCodecConfiguration {
+    /// Version 1.0 draft.
+    V1(JpegXlCodecConfigurationV1),
+}
+
+/// `jpegxl` codec configuration parameters (version 1.0 draft).
+///
+/// Further information on the meaning of these parameters can be found in the [libjxl documentation](https://github.com/libjxl/libjxl/blob/main/doc/encode_effort.md).
+///
+/// ### Example: Lossless encoding with the default effort
+/// ```rust
+/// # let JSON = r#"
+/// {}
+/// # "#;
+/// # use zarrs_metadata::v3::array::codec::jpegxl::JpegXlCodecConfigurationV1;
+/// # let configuration: JpegXlCodecConfigurationV1 = serde_json::from_str(JSON).unwrap();
+/// ```
+///
+/// ### Example: Visually lossless encoding with a low effort
+/// ```rust
+/// # let JSON = r#"
+/// {
+///     "distance": 1.0,
+///     "effort": 3
+/// }
+/// # "#;
+/// # use zarrs_metadata::v3::array::codec::jpegxl::JpegXlCodecConfigurationV1;
+/// # let configuration: JpegXlCodecConfigurationV1 = serde_json::from_str(JSON).unwrap();
+/// ```
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Display, Default)]
+#[serde(deny_unknown_fields)]
+#[display("{}", serde_json::to_string(self).unwrap_or_default())]
+pub struct JpegXlCodecConfigurationV1 {
+    /// The butteraugli distance, where 0 is lossless.
+    ///
+    /// Defaults to 0.
+    #[serde(default, skip_serializing_if = "JpegXlDistance::is_lossless")]
+    pub distance: JpegXlDistance,
+    /// The encoder effort, where a higher effort is slower and compresses better.
+    ///
+    /// Defaults to 7.
+    #[serde(default)]
+    pub effort: JpegXlEffort,
+}
+
+/// The `jpegxl` butteraugli distance. Must be in the range `[0, 25]`.
+///
+/// A distance of 0 is lossless, and a distance of 1 is visually lossless.
+#[derive(Serialize, Copy, Clone, Debug, PartialEq, Default)]
+pub struct JpegXlDistance(f32);
+
+impl<'de> Deserialize<'de> for JpegXlDistance {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let distance = f32::deserialize(d)?;
+        Self::new(distance)
+            .ok_or_else(|| serde::de::Error::custom("jpegxl distance must be in the range [0, 25]"))
+    }
+}
+
+impl JpegXlDistance {
+    /// The lossless distance.
+    pub const LOSSLESS: Self = Self(0.0);
+
+    /// Create a new distance.
+    ///
+    /// Returns [`None`] if `distance` is not in the range `[0, 25]`.
+    #[must_use]
+    pub fn new(distance: f32) -> Option<Self> {
+        (0.0..=25.0).contains(&distance).then_some(Self(distance))
+    }
+
+    /// The distance.
+    #[must_use]
+    pub const fn get(&self) -> f32 {
+        self.0
+    }
+
+    /// Returns true if the distance is lossless.
+    #[must_use]
+    pub fn is_lossless(&self) -> bool {
+        self.0 == 0.0
+    }
+}
+
+/// The `jpegxl` encoder effort. Must be in the range `[1, 10]`.
+#[derive(Serialize, Copy, Clone, Debug, Eq, PartialEq)]
+pub struct JpegXlEffort(u8);
+
+impl<'de> Deserialize<'de> for JpegXlEffort {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let effort = u8::deserialize(d)?;
+        Self::new(effort)
+            .ok_or_else(|| serde::de::Error::custom("jpegxl effort must be in the range [1, 10]"))
+    }
+}
+
+impl Default for JpegXlEffort {
+    fn default() -> Self {
+        Self(7)
+    }
+}
+
+impl JpegXlEffort {
+    /// Create a new effort.
+    ///
+    /// Returns [`None`] if `effort` is not in the range `[1, 10]`.
+    #[must_use]
+    pub const fn new(effort: u8) -> Option<Self> {
+        if matches!(effort, 1..=10) {
+            Some(Self(effort))
+        } else {
+            None
+        }
+    }
+
+    /// The effort.
+    #[must_use]
+    pub const fn get(&self) -> u8 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codec_jpegxl_valid() {
+        let configuration = serde_json::from_str::<JpegXlCodecConfiguration>(r"{}").unwrap();
+        assert_eq!(configuration.to_string(), r#"{"effort":7}"#);
+        let JpegXlCodecConfiguration::V1(configuration) = configuration;
+        assert!(configuration.distance.is_lossless());
+        assert_eq!(configuration.effort.get(), 7);
+
+        let configuration = serde_json::from_str::<JpegXlCodecConfiguration>(
+            r#"{
+            "distance": 1.5,
+            "effort": 3
+        }"#,
+        )
+        .unwrap();
+        let JpegXlCodecConfiguration::V1(configuration) = configuration;
+        assert_eq!(configuration.distance, JpegXlDistance::new(1.5).unwrap());
+        assert_eq!(configuration.effort.get(), 3);
+    }
+
+    #[test]
+    fn codec_jpegxl_invalid() {
+        assert!(serde_json::from_str::<JpegXlCodecConfiguration>(r#"{"distance": -1.0}"#).is_err());
+        assert!(serde_json::from_str::<JpegXlCodecConfiguration>(r#"{"distance": 26.0}"#).is_err());
+        assert!(serde_json::from_str::<JpegXlCodecConfiguration>(r#"{"effort": 0}"#).is_err());
+        assert!(serde_json::from_str::<JpegXlCodecConfiguration>(r#"{"effort": 11}"#).is_err());
+    }
+}
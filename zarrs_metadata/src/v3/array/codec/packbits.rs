@@ -0,0 +1,108 @@
+use derive_more::{Display, From};
+use serde::{Deserialize, Serialize};
+
+/// The identifier for the `packbits` codec.
+// TODO: ZEP for packbits
+pub const IDENTIFIER: &str = "packbits";
+
+/// A wrapper to handle various versions of `packbits` codec configuration parameters.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Display, From)]
+#[serde(untagged)]
+pub enum PackBitsCodecConfiguration {
+    /// Version 1.0 draft.
+    V1(PackBitsCodecConfigurationV1),
+}
+
+/// `packbits` codec configuration parameters (version 1.0 draft).
+///
+/// ### Example: Pack `bool` elements with a leading padding byte (compatible with `numcodecs` `packbits`)
+/// ```rust
+/// # let JSON = r#"
+/// {
+///     "padding_encoding": "first_byte"
+/// }
+/// # "#;
+/// # use zarrs_metadata::v3::array::codec::packbits::PackBitsCodecConfigurationV1;
+/// # let configuration: PackBitsCodecConfigurationV1 = serde_json::from_str(JSON).unwrap();
+/// ```
+///
+/// ### Example: Pack the 4 least significant bits of each element
+/// ```rust
+/// # let JSON = r#"
+/// {
+///     "first_bit": 0,
+///     "last_bit": 3
+/// }
+/// # "#;
+/// # use zarrs_metadata::v3::array::codec::packbits::PackBitsCodecConfigurationV1;
+/// # let configuration: PackBitsCodecConfigurationV1 = serde_json::from_str(JSON).unwrap();
+/// ```
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Display, Default)]
+#[serde(deny_unknown_fields)]
+#[display("{}", serde_json::to_string(self).unwrap_or_default())]
+pub struct PackBitsCodecConfigurationV1 {
+    /// The encoding of the number of padding bits in the last byte.
+    #[serde(default)]
+    pub padding_encoding: PackBitsPaddingEncoding,
+    /// The index of the first (least significant) bit of each element to pack.
+    ///
+    /// Defaults to 0.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_bit: Option<u64>,
+    /// The index of the last (most significant) bit of each element to pack.
+    ///
+    /// Defaults to the most significant bit of the data type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_bit: Option<u64>,
+}
+
+/// The `packbits` codec padding encoding.
+#[derive(Serialize, Deserialize, Clone, Copy, Eq, PartialEq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PackBitsPaddingEncoding {
+    /// The number of padding bits is not encoded.
+    #[default]
+    None,
+    /// The number of padding bits is encoded in a byte before the packed bits.
+    FirstByte,
+    /// The number of padding bits is encoded in a byte after the packed bits.
+    LastByte,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codec_packbits_valid() {
+        let configuration = serde_json::from_str::<PackBitsCodecConfiguration>(r"{}").unwrap();
+        assert_eq!(configuration.to_string(), r#"{"padding_encoding":"none"}"#);
+
+        let configuration = serde_json::from_str::<PackBitsCodecConfiguration>(
+            r#"{
+            "padding_encoding": "last_byte",
+            "first_bit": 2,
+            "last_bit": 5
+        }"#,
+        )
+        .unwrap();
+        let PackBitsCodecConfiguration::V1(configuration) = configuration;
+        assert_eq!(
+            configuration.padding_encoding,
+            PackBitsPaddingEncoding::LastByte
+        );
+        assert_eq!(configuration.first_bit, Some(2));
+        assert_eq!(configuration.last_bit, Some(5));
+    }
+
+    #[test]
+    fn codec_packbits_invalid() {
+        assert!(serde_json::from_str::<PackBitsCodecConfiguration>(
+            r#"{"padding_encoding": "middle_byte"}"#
+        )
+        .is_err());
+        assert!(
+            serde_json::from_str::<PackBitsCodecConfiguration>(r#"{"first_bit": -1}"#).is_err()
+        );
+    }
+}
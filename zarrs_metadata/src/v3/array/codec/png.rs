@@ -0,0 +1,85 @@
+use derive_more::{Display, From};
+use serde::{Deserialize, Serialize};
+
+/// The identifier for the `png` codec.
+// TODO: ZEP for png
+pub const IDENTIFIER: &str = "png";
+
+/// A wrapper to handle various versions of `png` codec configuration parameters.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Display, From)]
+#[serde(untagged)]
+pub enum PngCodecConfiguration {
+    /// Version 1.0 draft.
+    V1(PngCodecConfigurationV1),
+}
+
+/// `png` codec configuration parameters (version 1.0 draft).
+///
+/// ### Example: Encode with the default compression
+/// ```rust
+/// # let JSON = r#"
+/// {}
+/// # "#;
+/// # use zarrs_metadata::v3::array::codec::png::PngCodecConfigurationV1;
+/// # let configuration: PngCodecConfigurationV1 = serde_json::from_str(JSON).unwrap();
+/// ```
+///
+/// ### Example: Encode with the best compression
+/// ```rust
+/// # let JSON = r#"
+/// {
+///     "compression": "best"
+/// }
+/// # "#;
+/// # use zarrs_metadata::v3::array::codec::png::PngCodecConfigurationV1;
+/// # let configuration: PngCodecConfigurationV1 = serde_json::from_str(JSON).unwrap();
+/// ```
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Display, Default)]
+#[serde(deny_unknown_fields)]
+#[display("{}", serde_json::to_string(self).unwrap_or_default())]
+pub struct PngCodecConfigurationV1 {
+    /// The compression.
+    #[serde(default)]
+    pub compression: PngCompression,
+}
+
+/// The `png` codec compression.
+///
+/// The compression does not affect decoding.
+#[derive(Serialize, Deserialize, Clone, Copy, Eq, PartialEq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PngCompression {
+    /// Fast compression.
+    Fast,
+    /// The default compression.
+    #[default]
+    Default,
+    /// The best compression.
+    Best,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codec_png_valid() {
+        let configuration = serde_json::from_str::<PngCodecConfiguration>(r"{}").unwrap();
+        let PngCodecConfiguration::V1(configuration) = configuration;
+        assert_eq!(configuration.compression, PngCompression::Default);
+
+        let configuration =
+            serde_json::from_str::<PngCodecConfiguration>(r#"{"compression": "fast"}"#).unwrap();
+        let PngCodecConfiguration::V1(configuration) = &configuration;
+        assert_eq!(configuration.compression, PngCompression::Fast);
+        assert_eq!(configuration.to_string(), r#"{"compression":"fast"}"#);
+    }
+
+    #[test]
+    fn codec_png_invalid() {
+        assert!(
+            serde_json::from_str::<PngCodecConfiguration>(r#"{"compression": "fastest"}"#).is_err()
+        );
+        assert!(serde_json::from_str::<PngCodecConfiguration>(r#"{"level": 1}"#).is_err());
+    }
+}
@@ -0,0 +1,71 @@
+use std::num::NonZeroUsize;
+
+use derive_more::{Display, From};
+use serde::{Deserialize, Serialize};
+
+/// The identifier for the `shuffle` codec.
+// TODO: ZEP for shuffle
+pub const IDENTIFIER: &str = "shuffle";
+
+/// A wrapper to handle various versions of `shuffle` codec configuration parameters.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Display, From)]
+#[serde(untagged)]
+pub enum ShuffleCodecConfiguration {
+    /// Version 1.0 draft.
+    V1(ShuffleCodecConfigurationV1),
+}
+
+/// `shuffle` codec configuration parameters (version 1.0 draft).
+///
+/// ### Example: Shuffle 4 byte elements
+/// ```rust
+/// # let JSON = r#"
+/// {
+///     "element_size": 4
+/// }
+/// # "#;
+/// # use zarrs_metadata::v3::array::codec::shuffle::ShuffleCodecConfigurationV1;
+/// # let configuration: ShuffleCodecConfigurationV1 = serde_json::from_str(JSON).unwrap();
+/// ```
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Display)]
+#[serde(deny_unknown_fields)]
+#[display("{}", serde_json::to_string(self).unwrap_or_default())]
+pub struct ShuffleCodecConfigurationV1 {
+    /// The size of an element in bytes.
+    pub element_size: NonZeroUsize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codec_shuffle_valid() {
+        let configuration = serde_json::from_str::<ShuffleCodecConfiguration>(
+            r#"{
+            "element_size": 8
+        }"#,
+        )
+        .unwrap();
+        let ShuffleCodecConfiguration::V1(configuration) = &configuration;
+        assert_eq!(configuration.element_size.get(), 8);
+        assert_eq!(configuration.to_string(), r#"{"element_size":8}"#);
+    }
+
+    #[test]
+    fn codec_shuffle_invalid() {
+        assert!(serde_json::from_str::<ShuffleCodecConfiguration>(
+            r#"{
+            "element_size": 0
+        }"#,
+        )
+        .is_err());
+        assert!(serde_json::from_str::<ShuffleCodecConfiguration>(
+            r#"{
+            "element_size": 4,
+            "unknown": 1
+        }"#,
+        )
+        .is_err());
+    }
+}
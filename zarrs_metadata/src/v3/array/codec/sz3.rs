@@ -0,0 +1,155 @@
+use derive_more::From;
+use serde::{Deserialize, Serialize};
+
+/// The identifier for the `sz3` codec.
+// TODO: ZEP for sz3
+pub const IDENTIFIER: &str = "sz3";
+
+/// A wrapper to handle various versions of `sz3` codec configuration parameters.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, From)]
+#[serde(untagged)]
+pub enum Sz3CodecConfiguration {
+    /// Version 1.0 draft.
+    V1(Sz3CodecConfigurationV1),
+}
+
+/// Configuration parameters for the `sz3` codec (version 1.0 draft).
+///
+/// Further information on the meaning of these parameters can be found in the [SZ3 documentation](https://github.com/szcompressor/SZ3).
+///
+/// Valid examples:
+///
+/// ### Encode with an absolute error bound of 0.01
+/// ```rust
+/// # let JSON = r#"
+/// {
+///     "error_bound": "absolute",
+///     "absolute": 0.01
+/// }
+/// # "#;
+/// # use zarrs_metadata::v3::array::codec::sz3::Sz3CodecConfigurationV1;
+/// # let configuration: Sz3CodecConfigurationV1 = serde_json::from_str(JSON).unwrap();
+/// ```
+///
+/// ### Encode with a value range relative error bound of 0.001
+/// ```rust
+/// # let JSON = r#"
+/// {
+///     "error_bound": "relative",
+///     "relative": 0.001
+/// }
+/// # "#;
+/// # use zarrs_metadata::v3::array::codec::sz3::Sz3CodecConfigurationV1;
+/// # let configuration: Sz3CodecConfigurationV1 = serde_json::from_str(JSON).unwrap();
+/// ```
+///
+/// ### Encode with both an absolute and a value range relative error bound
+/// ```rust
+/// # let JSON = r#"
+/// {
+///     "error_bound": "absolute_and_relative",
+///     "absolute": 0.01,
+///     "relative": 0.001
+/// }
+/// # "#;
+/// # use zarrs_metadata::v3::array::codec::sz3::Sz3CodecConfigurationV1;
+/// # let configuration: Sz3CodecConfigurationV1 = serde_json::from_str(JSON).unwrap();
+/// ```
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Sz3CodecConfigurationV1 {
+    /// The error bound.
+    #[serde(flatten)]
+    pub error_bound: Sz3ErrorBound,
+}
+
+/// The `sz3` error bound.
+///
+/// A relative error bound is relative to the value range (maximum minus minimum) of the chunk.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(tag = "error_bound", rename_all = "snake_case")]
+pub enum Sz3ErrorBound {
+    /// The absolute error of each element is at most `absolute`.
+    Absolute {
+        /// The absolute error bound.
+        absolute: f64,
+    },
+    /// The absolute error of each element is at most `relative` times the value range.
+    Relative {
+        /// The value range relative error bound.
+        relative: f64,
+    },
+    /// Both the absolute and the value range relative error bounds are satisfied.
+    AbsoluteAndRelative {
+        /// The absolute error bound.
+        absolute: f64,
+        /// The value range relative error bound.
+        relative: f64,
+    },
+    /// Either the absolute or the value range relative error bound is satisfied.
+    AbsoluteOrRelative {
+        /// The absolute error bound.
+        absolute: f64,
+        /// The value range relative error bound.
+        relative: f64,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codec_sz3_absolute() {
+        let configuration = serde_json::from_str::<Sz3CodecConfiguration>(
+            r#"{
+            "error_bound": "absolute",
+            "absolute": 0.01
+        }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            configuration,
+            Sz3CodecConfiguration::V1(Sz3CodecConfigurationV1 {
+                error_bound: Sz3ErrorBound::Absolute { absolute: 0.01 }
+            })
+        );
+    }
+
+    #[test]
+    fn codec_sz3_absolute_or_relative() {
+        let configuration = serde_json::from_str::<Sz3CodecConfiguration>(
+            r#"{
+            "error_bound": "absolute_or_relative",
+            "absolute": 0.01,
+            "relative": 0.001
+        }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            serde_json::to_value(configuration).unwrap(),
+            serde_json::json!({
+                "error_bound": "absolute_or_relative",
+                "absolute": 0.01,
+                "relative": 0.001
+            })
+        );
+    }
+
+    #[test]
+    fn codec_sz3_invalid() {
+        assert!(serde_json::from_str::<Sz3CodecConfiguration>(
+            r#"{
+            "error_bound": "relative",
+            "absolute": 0.01
+        }"#,
+        )
+        .is_err());
+        assert!(serde_json::from_str::<Sz3CodecConfiguration>(
+            r#"{
+            "error_bound": "psnr",
+            "psnr": 0.01
+        }"#,
+        )
+        .is_err());
+    }
+}
@@ -21,16 +21,48 @@ pub struct ZstdCodecConfigurationV1 {
     pub level: ZstdCompressionLevel,
     /// A boolean that indicates whether to store a checksum when writing that will be verified when reading.
     pub checksum: bool,
+    /// An optional dictionary used for compression and decompression.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dictionary: Option<ZstdCodecDictionary>,
 }
 
 impl ZstdCodecConfigurationV1 {
     /// Create a new `zstd` codec configuration given a [`ZstdCompressionLevel`].
     #[must_use]
     pub const fn new(level: ZstdCompressionLevel, checksum: bool) -> Self {
-        Self { level, checksum }
+        Self {
+            level,
+            checksum,
+            dictionary: None,
+        }
+    }
+
+    /// Set the dictionary.
+    #[must_use]
+    pub fn with_dictionary(mut self, dictionary: Option<ZstdCodecDictionary>) -> Self {
+        self.dictionary = dictionary;
+        self
     }
 }
 
+/// A `zstd` dictionary referenced by the `zstd` codec configuration.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug)]
+#[serde(untagged)]
+pub enum ZstdCodecDictionary {
+    /// A dictionary embedded in the configuration.
+    Embedded {
+        /// The base64 encoded dictionary.
+        data: String,
+    },
+    /// An externally referenced dictionary, identified by its dictionary ID.
+    ///
+    /// The dictionary is not stored in the array metadata, so it must be provided to an implementation separately.
+    External {
+        /// The dictionary ID.
+        id: u32,
+    },
+}
+
 /// A `Zstd` compression level. An integer from -131072 to 22 which controls the speed and level of compression (has no impact on decoding).
 ///
 /// A value of 0 indicates to use the default compression level.
@@ -86,6 +118,40 @@ mod tests {
         serde_json::from_str::<ZstdCodecConfiguration>(JSON_VALID).unwrap();
     }
 
+    #[test]
+    fn codec_zstd_configuration_dictionary() {
+        const JSON_EMBEDDED: &str = r#"{
+        "level": 22,
+        "checksum": false,
+        "dictionary": {"data": "N6QwBw=="}
+    }"#;
+        const JSON_EXTERNAL: &str = r#"{
+        "level": 22,
+        "checksum": false,
+        "dictionary": {"id": 1234}
+    }"#;
+
+        let ZstdCodecConfiguration::V1(configuration) =
+            serde_json::from_str::<ZstdCodecConfiguration>(JSON_EMBEDDED).unwrap();
+        assert_eq!(
+            configuration.dictionary,
+            Some(ZstdCodecDictionary::Embedded {
+                data: "N6QwBw==".to_string()
+            })
+        );
+
+        let ZstdCodecConfiguration::V1(configuration) =
+            serde_json::from_str::<ZstdCodecConfiguration>(JSON_EXTERNAL).unwrap();
+        assert_eq!(
+            configuration.dictionary,
+            Some(ZstdCodecDictionary::External { id: 1234 })
+        );
+        assert_eq!(
+            serde_json::to_string(&configuration).unwrap(),
+            r#"{"level":22,"checksum":false,"dictionary":{"id":1234}}"#
+        );
+    }
+
     #[test]
     fn codec_zstd_configuration_invalid1() {
         const JSON_INVALID1: &str = r#"{
@@ -15,6 +15,20 @@
 //! # Ok::<_, Box<dyn std::error::Error>>(())
 //! ```
 //!
+//! An existing `Arc<dyn ObjectStore>` client (e.g. one registered with `arrow` or `datafusion`) can be shared directly:
+//! ```
+//! # use std::sync::Arc;
+//! use object_store::ObjectStore;
+//! use zarrs_storage::AsyncReadableWritableListableStorage;
+//! use zarrs_object_store::AsyncObjectStore;
+//!
+//! let client: Arc<dyn ObjectStore> = Arc::new(object_store::memory::InMemory::new());
+//! let store: AsyncReadableWritableListableStorage =
+//!     Arc::new(AsyncObjectStore::new(client.clone()));
+//! ```
+//!
+//! Values larger than a threshold are written with a multipart upload, see [`AsyncObjectStore::with_multipart_upload`].
+//!
 //! ## Version Compatibility Matrix
 //!
 #![doc = include_str!("../doc/version_compatibility_matrix.md")]
@@ -37,12 +51,12 @@
 pub use object_store;
 
 use futures::{StreamExt, TryStreamExt};
-use object_store::path::Path;
+use object_store::{path::Path, ObjectMeta, PutMode, PutPayload, UpdateVersion};
 
 use zarrs_storage::{
     async_store_set_partial_values, byte_range::ByteRange, AsyncBytes, AsyncListableStorageTraits,
     AsyncReadableStorageTraits, AsyncWritableStorageTraits, MaybeAsyncBytes, StorageError,
-    StoreKey, StoreKeyOffsetValue, StoreKeys, StoreKeysPrefixes, StorePrefix,
+    StoreKey, StoreKeyOffsetValue, StoreKeys, StoreKeysPrefixes, StorePrefix, StoreValueValidator,
 };
 
 /// Maps a [`StoreKey`] to an [`object_store`] path.
@@ -70,9 +84,37 @@ fn handle_result<T>(result: Result<T, object_store::Error>) -> Result<T, Storage
     result.map_err(|err| StorageError::Other(err.to_string()))
 }
 
+/// Maps an object version, or otherwise an entity tag, to a [`StoreValueValidator`].
+///
+/// Conditional puts match on the version in preference to the entity tag if both are available.
+fn object_validator(e_tag: Option<String>, version: Option<String>) -> Option<StoreValueValidator> {
+    version
+        .map(StoreValueValidator::Generation)
+        .or_else(|| e_tag.map(StoreValueValidator::EntityTag))
+}
+
+/// Maps the location of an [`ObjectMeta`] to a [`StoreKey`].
+fn object_meta_to_key(object_meta: &ObjectMeta) -> Result<StoreKey, StorageError> {
+    let path: &str = object_meta.location.as_ref();
+    Ok(StoreKey::try_from(path)?)
+}
+
+/// The default size threshold in bytes above which values are written with a multipart upload (16 MiB).
+pub const DEFAULT_MULTIPART_THRESHOLD: usize = 16 * 1024 * 1024;
+
+/// The default part size in bytes of multipart uploads (8 MiB).
+pub const DEFAULT_MULTIPART_PART_SIZE: usize = 8 * 1024 * 1024;
+
+/// The maximum number of parts of a multipart upload that are uploaded concurrently.
+const MULTIPART_CONCURRENCY: usize = 8;
+
 /// An asynchronous store backed by an [`object_store::ObjectStore`].
+///
+/// Any [`object_store::ObjectStore`] is supported, including an `Arc<dyn ObjectStore>` shared with other crates.
 pub struct AsyncObjectStore<T> {
     object_store: T,
+    multipart_threshold: usize,
+    multipart_part_size: usize,
     // locks: AsyncStoreLocks,
 }
 
@@ -80,7 +122,80 @@ impl<T: object_store::ObjectStore> AsyncObjectStore<T> {
     /// Create a new [`AsyncObjectStore`].
     #[must_use]
     pub fn new(object_store: T) -> Self {
-        Self { object_store }
+        Self {
+            object_store,
+            multipart_threshold: DEFAULT_MULTIPART_THRESHOLD,
+            multipart_part_size: DEFAULT_MULTIPART_PART_SIZE,
+        }
+    }
+
+    /// Set the size threshold and part size in bytes of multipart uploads.
+    ///
+    /// Values larger than `multipart_threshold` are split into parts of `multipart_part_size` bytes (the last part may be smaller).
+    /// Note that some object stores impose a minimum part size, such as 5 MiB for Amazon S3.
+    #[must_use]
+    pub fn with_multipart_upload(
+        mut self,
+        multipart_threshold: usize,
+        multipart_part_size: usize,
+    ) -> Self {
+        self.multipart_threshold = multipart_threshold;
+        self.multipart_part_size = multipart_part_size.max(1);
+        self
+    }
+
+    /// Return the underlying [`object_store::ObjectStore`].
+    #[must_use]
+    pub const fn object_store(&self) -> &T {
+        &self.object_store
+    }
+
+    /// Write `value` to `path` with a multipart upload, aborting the upload on failure.
+    async fn put_multipart(&self, path: &Path, value: AsyncBytes) -> Result<(), StorageError> {
+        let mut upload = handle_result(self.object_store.put_multipart(path).await)?;
+        let part_size = self.multipart_part_size;
+        let parts = futures::stream::iter((0..value.len()).step_by(part_size))
+            .map(|start| {
+                let end = (start + part_size).min(value.len());
+                upload.put_part(PutPayload::from(value.slice(start..end)))
+            })
+            .buffer_unordered(MULTIPART_CONCURRENCY)
+            .try_collect::<Vec<()>>()
+            .await;
+        let result = match parts {
+            Ok(_) => upload.complete().await.map(|_| ()),
+            Err(err) => Err(err),
+        };
+        if let Err(err) = result {
+            // The upload error takes precedence over an abort error
+            let _ = upload.abort().await;
+            return Err(StorageError::Other(err.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Collect the keys of a stream of objects with an optional `prefix`, sorted.
+    async fn list_keys(&self, prefix: Option<&Path>) -> Result<StoreKeys, StorageError> {
+        let mut keys: StoreKeys = self
+            .object_store
+            .list(prefix)
+            .map_err(|err| StorageError::Other(err.to_string()))
+            .and_then(|object_meta| futures::future::ready(object_meta_to_key(&object_meta)))
+            .try_collect()
+            .await?;
+        keys.sort();
+        Ok(keys)
+    }
+
+    /// Sum the sizes of a stream of objects with an optional `prefix`.
+    async fn size_objects(&self, prefix: Option<&Path>) -> Result<u64, StorageError> {
+        self.object_store
+            .list(prefix)
+            .map_err(|err| StorageError::Other(err.to_string()))
+            .try_fold(0, |size, object_meta| async move {
+                Ok(size + object_meta.size as u64)
+            })
+            .await
     }
 }
 
@@ -96,6 +211,20 @@ impl<T: object_store::ObjectStore> AsyncReadableStorageTraits for AsyncObjectSto
         }
     }
 
+    async fn get_with_etag(
+        &self,
+        key: &StoreKey,
+    ) -> Result<(MaybeAsyncBytes, Option<StoreValueValidator>), StorageError> {
+        let get = handle_result_notfound(self.object_store.get(&key_to_path(key)).await)?;
+        if let Some(get) = get {
+            let etag = object_validator(get.meta.e_tag.clone(), get.meta.version.clone());
+            let bytes = handle_result(get.bytes().await)?;
+            Ok((Some(bytes), etag))
+        } else {
+            Ok((None, None))
+        }
+    }
+
     async fn get_partial_values_key(
         &self,
         key: &StoreKey,
@@ -149,8 +278,60 @@ impl<T: object_store::ObjectStore> AsyncReadableStorageTraits for AsyncObjectSto
 #[async_trait::async_trait]
 impl<T: object_store::ObjectStore> AsyncWritableStorageTraits for AsyncObjectStore<T> {
     async fn set(&self, key: &StoreKey, value: AsyncBytes) -> Result<(), StorageError> {
-        handle_result(self.object_store.put(&key_to_path(key), value.into()).await)?;
-        Ok(())
+        let path = key_to_path(key);
+        if value.len() > self.multipart_threshold {
+            self.put_multipart(&path, value).await
+        } else {
+            handle_result(self.object_store.put(&path, value.into()).await)?;
+            Ok(())
+        }
+    }
+
+    fn supports_set_if_match(&self) -> bool {
+        true
+    }
+
+    /// Store `value` at `key` with a conditional put ([`PutMode::Create`] or [`PutMode::Update`]).
+    ///
+    /// The value is never written with a multipart upload.
+    /// Returns [`StorageError::Unsupported`] if the underlying object store does not support conditional puts.
+    async fn set_if_match(
+        &self,
+        key: &StoreKey,
+        value: AsyncBytes,
+        etag: Option<&StoreValueValidator>,
+    ) -> Result<Option<StoreValueValidator>, StorageError> {
+        let mode = match etag {
+            None => PutMode::Create,
+            Some(StoreValueValidator::EntityTag(entity_tag)) => PutMode::Update(UpdateVersion {
+                e_tag: Some(entity_tag.clone()),
+                version: None,
+            }),
+            Some(StoreValueValidator::Generation(version)) => PutMode::Update(UpdateVersion {
+                e_tag: None,
+                version: Some(version.clone()),
+            }),
+            Some(etag) => {
+                return Err(StorageError::Unsupported(format!(
+                    "object store conditional writes require an entity tag or version, not {etag:?}"
+                )))
+            }
+        };
+        let result = self
+            .object_store
+            .put_opts(&key_to_path(key), value.into(), mode.into())
+            .await;
+        match result {
+            Ok(result) => Ok(object_validator(result.e_tag, result.version)),
+            Err(
+                object_store::Error::Precondition { .. }
+                | object_store::Error::AlreadyExists { .. },
+            ) => Err(StorageError::PreconditionFailed(key.clone())),
+            Err(object_store::Error::NotImplemented) => Err(StorageError::Unsupported(
+                "conditional writes are not supported by this object store".to_string(),
+            )),
+            Err(err) => Err(StorageError::Other(err.to_string())),
+        }
     }
 
     async fn set_partial_values(
@@ -185,43 +366,12 @@ impl<T: object_store::ObjectStore> AsyncWritableStorageTraits for AsyncObjectSto
 #[async_trait::async_trait]
 impl<T: object_store::ObjectStore> AsyncListableStorageTraits for AsyncObjectStore<T> {
     async fn list(&self) -> Result<StoreKeys, StorageError> {
-        let mut list = handle_result(
-            self.object_store
-                .list(None)
-                .collect::<Vec<_>>()
-                .await
-                .into_iter()
-                .map(|object_meta| {
-                    object_meta.map(|object_meta| {
-                        let path: &str = object_meta.location.as_ref();
-                        StoreKey::try_from(path).unwrap() // FIXME
-                    })
-                })
-                .collect::<Result<Vec<_>, _>>(),
-        )?;
-        list.sort();
-        Ok(list)
+        self.list_keys(None).await
     }
 
     async fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
-        // TODO: Check if this is outputting everything under prefix, or just one level under
         let path: object_store::path::Path = prefix.as_str().into();
-        let mut list = handle_result(
-            self.object_store
-                .list(Some(&path))
-                .collect::<Vec<_>>()
-                .await
-                .into_iter()
-                .map(|object_meta| {
-                    object_meta.map(|object_meta| {
-                        let path: &str = object_meta.location.as_ref();
-                        StoreKey::try_from(path).unwrap() // FIXME
-                    })
-                })
-                .collect::<Result<Vec<_>, _>>(),
-        )?;
-        list.sort();
-        Ok(list)
+        self.list_keys(Some(&path)).await
     }
 
     async fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
@@ -238,10 +388,7 @@ impl<T: object_store::ObjectStore> AsyncListableStorageTraits for AsyncObjectSto
         let mut keys = list_result
             .objects
             .iter()
-            .map(|object_meta| {
-                let path: &str = object_meta.location.as_ref();
-                StoreKey::try_from(path)
-            })
+            .map(object_meta_to_key)
             .collect::<Result<Vec<_>, _>>()?;
         keys.sort();
         prefixes.sort();
@@ -250,23 +397,11 @@ impl<T: object_store::ObjectStore> AsyncListableStorageTraits for AsyncObjectSto
 
     async fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
         let prefix: object_store::path::Path = prefix.as_str().into();
-        let mut locations = self.object_store.list(Some(&prefix));
-        let mut size = 0;
-        while let Some(item) = locations.next().await {
-            let meta = handle_result(item)?;
-            size += u64::try_from(meta.size).unwrap();
-        }
-        Ok(size)
+        self.size_objects(Some(&prefix)).await
     }
 
     async fn size(&self) -> Result<u64, StorageError> {
-        let mut locations = self.object_store.list(None);
-        let mut size = 0;
-        while let Some(item) = locations.next().await {
-            let meta = handle_result(item)?;
-            size += u64::try_from(meta.size).unwrap();
-        }
-        Ok(size)
+        self.size_objects(None).await
     }
 }
 
@@ -281,6 +416,7 @@ mod tests {
         zarrs_storage::store_test::async_store_write(&store).await?;
         zarrs_storage::store_test::async_store_read(&store).await?;
         zarrs_storage::store_test::async_store_list(&store).await?;
+        zarrs_storage::store_test::async_store_set_if_match(&store).await?;
         Ok(())
     }
 
@@ -296,4 +432,36 @@ mod tests {
         zarrs_storage::store_test::async_store_list(&store).await?;
         Ok(())
     }
+
+    #[tokio::test]
+    async fn memory_shared() -> Result<(), Box<dyn Error>> {
+        let client: std::sync::Arc<dyn object_store::ObjectStore> =
+            std::sync::Arc::new(object_store::memory::InMemory::new());
+        let store = AsyncObjectStore::new(client.clone());
+        zarrs_storage::store_test::async_store_write(&store).await?;
+        zarrs_storage::store_test::async_store_read(&store).await?;
+        zarrs_storage::store_test::async_store_list(&store).await?;
+        assert!(client.head(&Path::from("a/b")).await.is_ok());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore)]
+    async fn multipart() -> Result<(), Box<dyn Error>> {
+        let path = tempfile::TempDir::new()?;
+        let store = AsyncObjectStore::new(object_store::local::LocalFileSystem::new_with_prefix(
+            path.path(),
+        )?)
+        .with_multipart_upload(4, 3);
+        zarrs_storage::store_test::async_store_write(&store).await?;
+        zarrs_storage::store_test::async_store_read(&store).await?;
+        zarrs_storage::store_test::async_store_list(&store).await?;
+
+        let key = StoreKey::new("multipart")?;
+        let value: Vec<u8> = (0..100).collect();
+        store.set(&key, value.clone().into()).await?;
+        assert_eq!(store.get(&key).await?.unwrap(), value);
+        assert_eq!(store.size_key(&key).await?, Some(100));
+        Ok(())
+    }
 }
@@ -0,0 +1,343 @@
+//! A remote store client.
+
+use reqwest::{
+    blocking::{Client, RequestBuilder},
+    StatusCode, Url,
+};
+use zarrs_storage::{
+    byte_range::ByteRange, Bytes, ListableStorageTraits, MaybeBytes, ReadableStorageTraits,
+    StorageError, StoreKey, StoreKeyOffsetValue, StoreKeys, StoreKeysPrefixes, StorePrefix,
+    StorePrefixes, WritableStorageTraits,
+};
+
+use crate::{
+    protocol::{
+        decode_maybe_values, decode_values, encode_values, ByteRangeMessage, ListDirMessage,
+        PATH_PREFIX,
+    },
+    RemoteStoreCreateError,
+};
+
+/// A synchronous remote store client.
+///
+/// Storage operations are sent to a store served with [`serve_store`](crate::serve_store) or [`serve_readable_store`](crate::serve_readable_store).
+#[derive(Debug)]
+pub struct RemoteStoreClient {
+    base_url: Url,
+    client: Client,
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn handle_reqwest_error(err: reqwest::Error) -> StorageError {
+    StorageError::Other(err.to_string())
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn handle_json_error(err: serde_json::Error) -> StorageError {
+    StorageError::Other(format!("invalid remote store response: {err}"))
+}
+
+/// Send a request, returning its body or [`None`] if the key is not found.
+fn send(request: RequestBuilder) -> Result<Option<Bytes>, StorageError> {
+    let response = request.send().map_err(handle_reqwest_error)?;
+    let status = response.status();
+    let bytes = response.bytes().map_err(handle_reqwest_error)?;
+    match status {
+        StatusCode::OK | StatusCode::NO_CONTENT => Ok(Some(bytes)),
+        StatusCode::NOT_FOUND => Ok(None),
+        StatusCode::FORBIDDEN => Err(StorageError::ReadOnly),
+        _ => Err(StorageError::Other(format!(
+            "the remote store responded with status {status}: {}",
+            String::from_utf8_lossy(&bytes)
+        ))),
+    }
+}
+
+/// Send a request, returning its body.
+fn send_expect(request: RequestBuilder) -> Result<Bytes, StorageError> {
+    send(request)?.ok_or_else(|| {
+        StorageError::Other("the remote store responded with status 404 Not Found".into())
+    })
+}
+
+impl RemoteStoreClient {
+    /// Create a new remote store client for a server at `url` (e.g. `http://127.0.0.1:8000`).
+    ///
+    /// # Errors
+    /// Returns a [`RemoteStoreCreateError`] if `url` is not a valid HTTP URL.
+    pub fn new(url: &str) -> Result<Self, RemoteStoreCreateError> {
+        Self::new_with_client(url, Client::new())
+    }
+
+    /// Create a new remote store client for a server at `url` with an existing `client`.
+    ///
+    /// # Errors
+    /// Returns a [`RemoteStoreCreateError`] if `url` is not a valid HTTP URL.
+    pub fn new_with_client(url: &str, client: Client) -> Result<Self, RemoteStoreCreateError> {
+        let base_url = Url::parse(url)
+            .ok()
+            .filter(|url| matches!(url.scheme(), "http" | "https") && !url.cannot_be_a_base())
+            .ok_or_else(|| RemoteStoreCreateError::InvalidURL(url.to_string()))?;
+        Ok(Self { base_url, client })
+    }
+
+    /// Return the client.
+    #[must_use]
+    pub const fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Returns the URL of an `operation` with query `params`.
+    fn url(&self, operation: &str, params: &[(&str, &str)]) -> Url {
+        let mut url = self.base_url.clone();
+        url.set_path(&format!(
+            "{}{PATH_PREFIX}{operation}",
+            self.base_url.path().trim_end_matches('/')
+        ));
+        if !params.is_empty() {
+            url.query_pairs_mut().extend_pairs(params);
+        }
+        url
+    }
+
+    fn list_response(bytes: &Bytes) -> Result<StoreKeys, StorageError> {
+        let keys: Vec<String> = serde_json::from_slice(bytes).map_err(handle_json_error)?;
+        Ok(keys
+            .into_iter()
+            .map(StoreKey::new)
+            .collect::<Result<_, _>>()?)
+    }
+}
+
+impl ReadableStorageTraits for RemoteStoreClient {
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        send(self.client.get(self.url("get", &[("key", key.as_str())])))
+    }
+
+    fn get_many(&self, keys: &[StoreKey]) -> Result<Vec<MaybeBytes>, StorageError> {
+        if keys.is_empty() {
+            return Ok(vec![]);
+        }
+        let keys: Vec<&str> = keys.iter().map(StoreKey::as_str).collect();
+        let body = serde_json::to_vec(&keys).map_err(handle_json_error)?;
+        let bytes = send_expect(self.client.post(self.url("get_many", &[])).body(body))?;
+        decode_maybe_values(&bytes)
+            .filter(|values| values.len() == keys.len())
+            .ok_or_else(|| StorageError::Other("invalid remote store values".into()))
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        let byte_ranges: Vec<ByteRangeMessage> =
+            byte_ranges.iter().copied().map(Into::into).collect();
+        let body = serde_json::to_vec(&byte_ranges).map_err(handle_json_error)?;
+        let Some(bytes) = send(
+            self.client
+                .post(self.url("get_partial_values_key", &[("key", key.as_str())]))
+                .body(body),
+        )?
+        else {
+            return Ok(None);
+        };
+        decode_values(&bytes)
+            .filter(|values| values.len() == byte_ranges.len())
+            .map(Some)
+            .ok_or_else(|| StorageError::Other("invalid remote store partial values".into()))
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        send(
+            self.client
+                .get(self.url("size_key", &[("key", key.as_str())])),
+        )?
+        .map(|bytes| serde_json::from_slice(&bytes).map_err(handle_json_error))
+        .transpose()
+    }
+}
+
+impl WritableStorageTraits for RemoteStoreClient {
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        send_expect(
+            self.client
+                .put(self.url("set", &[("key", key.as_str())]))
+                .body(value),
+        )?;
+        Ok(())
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        if key_offset_values.is_empty() {
+            return Ok(());
+        }
+        let offsets = key_offset_values
+            .iter()
+            .map(|key_offset_value| key_offset_value.offset().to_string())
+            .collect::<Vec<_>>();
+        let params = key_offset_values
+            .iter()
+            .zip(&offsets)
+            .flat_map(|(key_offset_value, offset)| {
+                [
+                    ("key", key_offset_value.key().as_str()),
+                    ("offset", offset.as_str()),
+                ]
+            })
+            .collect::<Vec<_>>();
+        let values = key_offset_values
+            .iter()
+            .map(StoreKeyOffsetValue::value)
+            .collect::<Vec<_>>();
+        send_expect(
+            self.client
+                .put(self.url("set_partial_values", &params))
+                .body(encode_values(&values)),
+        )?;
+        Ok(())
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        send_expect(
+            self.client
+                .delete(self.url("erase", &[("key", key.as_str())])),
+        )?;
+        Ok(())
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        send_expect(
+            self.client
+                .delete(self.url("erase_prefix", &[("prefix", prefix.as_str())])),
+        )?;
+        Ok(())
+    }
+}
+
+impl ListableStorageTraits for RemoteStoreClient {
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        Self::list_response(&send_expect(self.client.get(self.url("list", &[])))?)
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        Self::list_response(&send_expect(
+            self.client
+                .get(self.url("list_prefix", &[("prefix", prefix.as_str())])),
+        )?)
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        let bytes = send_expect(
+            self.client
+                .get(self.url("list_dir", &[("prefix", prefix.as_str())])),
+        )?;
+        let ListDirMessage { keys, prefixes } =
+            serde_json::from_slice(&bytes).map_err(handle_json_error)?;
+        let keys = keys
+            .into_iter()
+            .map(StoreKey::new)
+            .collect::<Result<StoreKeys, _>>()?;
+        let prefixes = prefixes
+            .into_iter()
+            .map(StorePrefix::new)
+            .collect::<Result<StorePrefixes, _>>()?;
+        Ok(StoreKeysPrefixes::new(keys, prefixes))
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        let bytes = send_expect(
+            self.client
+                .get(self.url("size_prefix", &[("prefix", prefix.as_str())])),
+        )?;
+        serde_json::from_slice(&bytes).map_err(handle_json_error)
+    }
+
+    fn size(&self) -> Result<u64, StorageError> {
+        let bytes = send_expect(self.client.get(self.url("size", &[])))?;
+        serde_json::from_slice(&bytes).map_err(handle_json_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, net::TcpListener, sync::Arc};
+
+    use zarrs_storage::store::MemoryStore;
+
+    use super::*;
+    use crate::{serve_readable_store, serve_store};
+
+    /// Serve a new memory store on a local port, returning its URL.
+    fn serve_memory_store(readonly: bool) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let store = Arc::new(MemoryStore::new());
+        std::thread::spawn(move || {
+            if readonly {
+                serve_readable_store(listener, store)
+            } else {
+                serve_store(listener, store)
+            }
+        });
+        url
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn remote_store() -> Result<(), Box<dyn Error>> {
+        let store = RemoteStoreClient::new(&serve_memory_store(false))?;
+        zarrs_storage::store_test::store_write(&store)?;
+        zarrs_storage::store_test::store_read(&store)?;
+        zarrs_storage::store_test::store_list(&store)?;
+        Ok(())
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn remote_store_get_many() -> Result<(), Box<dyn Error>> {
+        let store = RemoteStoreClient::new(&serve_memory_store(false))?;
+        store.set(&"a".try_into()?, vec![0, 1].into())?;
+        store.set(&"b/c".try_into()?, vec![].into())?;
+        assert_eq!(
+            store.get_many(&["b/c".try_into()?, "d".try_into()?, "a".try_into()?])?,
+            [Some(vec![].into()), None, Some(vec![0, 1].into())]
+        );
+        assert!(store.get_many(&[])?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn remote_store_readonly() -> Result<(), Box<dyn Error>> {
+        let store = RemoteStoreClient::new(&serve_memory_store(true))?;
+        assert!(store.get(&"a".try_into()?)?.is_none());
+        assert_eq!(store.get_many(&["a".try_into()?])?, [None]);
+        assert!(store.size_key(&"a".try_into()?)?.is_none());
+        assert!(matches!(
+            store.set(&"a".try_into()?, vec![0].into()),
+            Err(StorageError::ReadOnly)
+        ));
+        assert!(matches!(
+            store.erase_prefix(&StorePrefix::root()),
+            Err(StorageError::ReadOnly)
+        ));
+        assert!(store.list()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn remote_store_url() -> Result<(), Box<dyn Error>> {
+        let store = RemoteStoreClient::new("http://localhost:8000/store/")?;
+        assert_eq!(
+            store.url("get", &[("key", "a/b c")]).as_str(),
+            "http://localhost:8000/store/v1/get?key=a%2Fb+c"
+        );
+        assert!(RemoteStoreClient::new("file:///store").is_err());
+        assert!(RemoteStoreClient::new("not a url").is_err());
+        Ok(())
+    }
+}
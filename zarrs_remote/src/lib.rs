@@ -0,0 +1,73 @@
+//! A remote store client and server for the [`zarrs`](https://docs.rs/zarrs/latest/zarrs/index.html) crate.
+//!
+//! [`serve_store`] exposes any local store over the network, and a [`RemoteStoreClient`] is a store for a served store.
+//! This enables thin compute clients to read and write chunks held by a central chunk server.
+//!
+//! ```rust
+//! # use std::{net::TcpListener, sync::Arc};
+//! use zarrs_storage::{store::MemoryStore, ReadableWritableListableStorage};
+//! use zarrs_remote::{serve_store, RemoteStoreClient};
+//!
+//! // Server
+//! let listener = TcpListener::bind("127.0.0.1:0")?;
+//! let url = format!("http://{}", listener.local_addr()?);
+//! let store: ReadableWritableListableStorage = Arc::new(MemoryStore::new());
+//! std::thread::spawn(move || serve_store(listener, store));
+//!
+//! // Client
+//! let store: ReadableWritableListableStorage = Arc::new(RemoteStoreClient::new(&url)?);
+//! store.set(&"zarr.json".try_into()?, br#"{"zarr_format":3}"#.to_vec().into())?;
+//! assert_eq!(store.list()?, ["zarr.json".try_into()?]);
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! ## Protocol
+//! The storage traits are mapped to HTTP/1.1 requests under `/v1/`.
+//! Keys and prefixes are passed as `key` and `prefix` query parameters.
+//!
+//! | Operation                | Request                                                  | Response                                        |
+//! | ------------------------ | -------------------------------------------------------- | ----------------------------------------------- |
+//! | `get`                    | `GET /v1/get?key=`                                       | The value                                       |
+//! | `get_many`               | `POST /v1/get_many` with a JSON array of keys            | The values<sup>2</sup>                          |
+//! | `get_partial_values_key` | `POST /v1/get_partial_values_key?key=`<sup>1</sup>       | The values of the byte ranges<sup>2</sup>       |
+//! | `size_key`               | `GET /v1/size_key?key=`                                  | The size as JSON                                |
+//! | `set`                    | `PUT /v1/set?key=` with the value                        | `204 No Content`                                |
+//! | `set_partial_values`     | `PUT /v1/set_partial_values?key=&offset=...`<sup>3</sup> | `204 No Content`                                |
+//! | `erase`                  | `DELETE /v1/erase?key=`                                  | `204 No Content`                                |
+//! | `erase_prefix`           | `DELETE /v1/erase_prefix?prefix=`                        | `204 No Content`                                |
+//! | `list`                   | `GET /v1/list`                                           | A JSON array of keys                            |
+//! | `list_prefix`            | `GET /v1/list_prefix?prefix=`                            | A JSON array of keys                            |
+//! | `list_dir`               | `GET /v1/list_dir?prefix=`                               | A JSON object with `keys` and `prefixes` arrays |
+//! | `size_prefix`            | `GET /v1/size_prefix?prefix=`                            | The size as JSON                                |
+//! | `size`                   | `GET /v1/size`                                           | The size as JSON                                |
+//!
+//! <sup>1</sup> With a JSON array of byte ranges, such as `[{"from_start":[0,10]},{"from_start":[10,null]},{"suffix":4}]`.
+//!
+//! <sup>2</sup> Values are encoded as a sequence of little-endian `u64` lengths each followed by the bytes of a value.
+//! The values of `get_many` encode a missing key with a length of `u64::MAX`.
+//!
+//! <sup>3</sup> With a `key` and `offset` query parameter for each value, and the values encoded as in <sup>2</sup>.
+//!
+//! A missing key responds with `404 Not Found`, a write to a read-only store with `403 Forbidden`, an invalid request with `400 Bad Request`, and a storage error with `500 Internal Server Error` and the error message.
+//!
+//! ## Licence
+//! `zarrs_remote` is licensed under either of
+//! - the Apache License, Version 2.0 [LICENSE-APACHE](https://docs.rs/crate/zarrs_remote/latest/source/LICENCE-APACHE) or <http://www.apache.org/licenses/LICENSE-2.0> or
+//! - the MIT license [LICENSE-MIT](https://docs.rs/crate/zarrs_remote/latest/source/LICENCE-MIT) or <http://opensource.org/licenses/MIT>, at your option.
+
+mod client;
+mod protocol;
+mod server;
+
+pub use client::RemoteStoreClient;
+pub use server::{serve_readable_store, serve_store};
+
+use thiserror::Error;
+
+/// A remote store creation error.
+#[derive(Debug, Error)]
+pub enum RemoteStoreCreateError {
+    /// The URL is not a valid HTTP URL.
+    #[error("url {0} is not valid")]
+    InvalidURL(String),
+}
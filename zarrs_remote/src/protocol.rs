@@ -0,0 +1,153 @@
+//! The remote store protocol.
+//!
+//! See the [crate documentation](crate) for the operations of the protocol.
+
+use serde::{Deserialize, Serialize};
+use zarrs_storage::{byte_range::ByteRange, Bytes};
+
+/// The path prefix of the operations of this version of the protocol.
+pub(crate) const PATH_PREFIX: &str = "/v1/";
+
+/// Operations which modify the store.
+pub(crate) const WRITE_OPERATIONS: [&str; 4] =
+    ["set", "set_partial_values", "erase", "erase_prefix"];
+
+/// A serialisable [`ByteRange`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ByteRangeMessage {
+    FromStart(u64, Option<u64>),
+    Suffix(u64),
+}
+
+impl From<ByteRange> for ByteRangeMessage {
+    fn from(byte_range: ByteRange) -> Self {
+        match byte_range {
+            ByteRange::FromStart(offset, length) => Self::FromStart(offset, length),
+            ByteRange::Suffix(length) => Self::Suffix(length),
+        }
+    }
+}
+
+impl From<ByteRangeMessage> for ByteRange {
+    fn from(byte_range: ByteRangeMessage) -> Self {
+        match byte_range {
+            ByteRangeMessage::FromStart(offset, length) => Self::FromStart(offset, length),
+            ByteRangeMessage::Suffix(length) => Self::Suffix(length),
+        }
+    }
+}
+
+/// The response to a `list_dir` operation.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct ListDirMessage {
+    pub(crate) keys: Vec<String>,
+    pub(crate) prefixes: Vec<String>,
+}
+
+/// The encoded length of a missing value.
+const MISSING_LENGTH: u64 = u64::MAX;
+
+/// Encodes `values` as a sequence of little-endian `u64` lengths each followed by the bytes of a value.
+///
+/// A missing value is encoded as a length of [`u64::MAX`] without any bytes.
+fn encode<'a>(values: impl Iterator<Item = Option<&'a [u8]>> + Clone) -> Vec<u8> {
+    let length = values
+        .clone()
+        .map(|value| value.map_or(0, <[u8]>::len) + std::mem::size_of::<u64>())
+        .sum();
+    let mut bytes = Vec::with_capacity(length);
+    for value in values {
+        if let Some(value) = value {
+            bytes.extend_from_slice(&(value.len() as u64).to_le_bytes());
+            bytes.extend_from_slice(value);
+        } else {
+            bytes.extend_from_slice(&MISSING_LENGTH.to_le_bytes());
+        }
+    }
+    bytes
+}
+
+/// Decodes values encoded with [`encode`].
+///
+/// Returns [`None`] if `bytes` is not a valid encoding.
+fn decode(bytes: &Bytes) -> Option<Vec<Option<Bytes>>> {
+    let mut values = Vec::new();
+    let mut position = 0;
+    while position < bytes.len() {
+        let length_end = position.checked_add(std::mem::size_of::<u64>())?;
+        let length = u64::from_le_bytes(bytes.get(position..length_end)?.try_into().ok()?);
+        if length == MISSING_LENGTH {
+            values.push(None);
+            position = length_end;
+            continue;
+        }
+        let value_end = length_end.checked_add(usize::try_from(length).ok()?)?;
+        if value_end > bytes.len() {
+            return None;
+        }
+        values.push(Some(bytes.slice(length_end..value_end)));
+        position = value_end;
+    }
+    Some(values)
+}
+
+/// Encodes `values` as a sequence of little-endian `u64` lengths each followed by the bytes of a value.
+pub(crate) fn encode_values<T: AsRef<[u8]>>(values: &[T]) -> Vec<u8> {
+    encode(values.iter().map(|value| Some(value.as_ref())))
+}
+
+/// Decodes values encoded with [`encode_values`].
+///
+/// Returns [`None`] if `bytes` is not a valid encoding.
+pub(crate) fn decode_values(bytes: &Bytes) -> Option<Vec<Bytes>> {
+    decode(bytes)?.into_iter().collect()
+}
+
+/// Encodes `values` as with [`encode_values`], with a length of [`u64::MAX`] for a missing value.
+pub(crate) fn encode_maybe_values<T: AsRef<[u8]>>(values: &[Option<T>]) -> Vec<u8> {
+    encode(values.iter().map(|value| value.as_ref().map(AsRef::as_ref)))
+}
+
+/// Decodes values encoded with [`encode_maybe_values`].
+///
+/// Returns [`None`] if `bytes` is not a valid encoding.
+pub(crate) fn decode_maybe_values(bytes: &Bytes) -> Option<Vec<Option<Bytes>>> {
+    decode(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remote_values_encoding() {
+        let values = [vec![0, 1, 2], vec![], vec![3]];
+        let bytes = Bytes::from(encode_values(&values));
+        assert_eq!(bytes.len(), 3 * 8 + 4);
+        assert_eq!(
+            decode_values(&bytes).unwrap(),
+            vec![
+                Bytes::from(vec![0, 1, 2]),
+                Bytes::new(),
+                Bytes::from(vec![3])
+            ]
+        );
+        assert!(decode_values(&bytes.slice(..bytes.len() - 1)).is_none());
+        assert!(decode_values(&bytes.slice(..4)).is_none());
+        assert_eq!(decode_values(&Bytes::new()).unwrap(), Vec::<Bytes>::new());
+    }
+
+    #[test]
+    fn remote_maybe_values_encoding() {
+        let values = [Some(vec![0, 1, 2]), None, Some(vec![])];
+        let bytes = Bytes::from(encode_maybe_values(&values));
+        assert_eq!(bytes.len(), 3 * 8 + 3);
+        assert_eq!(
+            decode_maybe_values(&bytes).unwrap(),
+            vec![Some(Bytes::from(vec![0, 1, 2])), None, Some(Bytes::new())]
+        );
+        assert!(decode_values(&bytes).is_none());
+        assert!(decode_maybe_values(&bytes.slice(..bytes.len() - 1)).is_none());
+    }
+}
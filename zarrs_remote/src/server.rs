@@ -0,0 +1,383 @@
+//! A remote store server.
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::Arc,
+    time::Duration,
+};
+
+use url::Url;
+use zarrs_storage::{
+    byte_range::ByteRange, Bytes, ListableStorageTraits, ReadableListableStorage,
+    ReadableStorageTraits, ReadableWritableListableStorage, StorageError, StoreKey,
+    StoreKeyOffsetValue, StorePrefix, WritableStorageTraits,
+};
+
+use crate::protocol::{
+    decode_values, encode_maybe_values, encode_values, ByteRangeMessage, ListDirMessage,
+    PATH_PREFIX, WRITE_OPERATIONS,
+};
+
+/// The maximum size of the request line and headers of a request.
+const MAX_HEADER_SIZE: usize = 64 * 1024;
+
+/// The duration after which an idle connection is closed.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// A store served by [`serve_store`] or [`serve_readable_store`].
+enum ServedStore {
+    Readable(ReadableListableStorage),
+    Writable(ReadableWritableListableStorage),
+}
+
+/// Serve a readable, writable, and listable `store` to [`RemoteStoreClient`](crate::RemoteStoreClient)s connecting to `listener`.
+///
+/// Each connection is handled on its own thread.
+/// This function blocks while accepting connections, so it is typically called on a dedicated thread.
+///
+/// The server does not authenticate or encrypt connections.
+/// It should only listen on a trusted network, or behind a reverse proxy which does.
+///
+/// # Errors
+/// Returns an [`std::io::Error`] if accepting a connection fails.
+#[allow(clippy::needless_pass_by_value)]
+pub fn serve_store(
+    listener: TcpListener,
+    store: ReadableWritableListableStorage,
+) -> std::io::Result<()> {
+    serve(&listener, ServedStore::Writable(store))
+}
+
+/// Serve a readable and listable `store` to [`RemoteStoreClient`](crate::RemoteStoreClient)s connecting to `listener`.
+///
+/// Write operations are rejected, and return [`StorageError::ReadOnly`] on the client.
+/// See [`serve_store`].
+///
+/// # Errors
+/// Returns an [`std::io::Error`] if accepting a connection fails.
+#[allow(clippy::needless_pass_by_value)]
+pub fn serve_readable_store(
+    listener: TcpListener,
+    store: ReadableListableStorage,
+) -> std::io::Result<()> {
+    serve(&listener, ServedStore::Readable(store))
+}
+
+fn serve(listener: &TcpListener, store: ServedStore) -> std::io::Result<()> {
+    let store = Arc::new(store);
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let store = store.clone();
+        std::thread::spawn(move || {
+            // A failed connection does not affect other connections
+            let _ = serve_connection(stream, &store);
+        });
+    }
+    Ok(())
+}
+
+/// Handle requests on a connection until it is closed.
+fn serve_connection(stream: TcpStream, store: &ServedStore) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(IDLE_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+    while let Some(request) = read_request(&mut reader)? {
+        let response = handle_request(store, &request).unwrap_or_else(|response| response);
+        response.write(&mut writer, request.close)?;
+        if request.close {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// A HTTP request.
+struct Request {
+    method: String,
+    url: Url,
+    body: Bytes,
+    close: bool,
+}
+
+impl Request {
+    /// Returns the values of the query parameter `name`.
+    fn params<'a>(&'a self, name: &'a str) -> impl Iterator<Item = String> + 'a {
+        self.url
+            .query_pairs()
+            .filter(move |(param, _)| param == name)
+            .map(|(_, value)| value.into_owned())
+    }
+
+    /// Returns the value of the query parameter `name`.
+    fn param(&self, name: &str) -> Result<String, Response> {
+        self.params(name).next().ok_or_else(|| {
+            Response::new(400, format!("missing query parameter {name}").into_bytes())
+        })
+    }
+
+    fn key(&self) -> Result<StoreKey, Response> {
+        Ok(StoreKey::new(self.param("key")?).map_err(StorageError::from)?)
+    }
+
+    fn prefix(&self) -> Result<StorePrefix, Response> {
+        Ok(StorePrefix::new(self.param("prefix")?).map_err(StorageError::from)?)
+    }
+}
+
+fn invalid_data(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+/// Read a request from a connection.
+///
+/// Returns [`None`] if the connection was closed before a request.
+fn read_request(reader: &mut impl BufRead) -> std::io::Result<Option<Request>> {
+    let mut head = Vec::new();
+    loop {
+        let read = reader
+            .by_ref()
+            .take((MAX_HEADER_SIZE - head.len()) as u64)
+            .read_until(b'\n', &mut head)?;
+        if read == 0 {
+            return if head.is_empty() {
+                Ok(None)
+            } else {
+                Err(invalid_data("incomplete request"))
+            };
+        }
+        if head.ends_with(b"\r\n\r\n") || head.ends_with(b"\n\n") {
+            break;
+        }
+        if head.len() >= MAX_HEADER_SIZE {
+            return Err(invalid_data("request header too large"));
+        }
+    }
+    let head = std::str::from_utf8(&head).map_err(|_| invalid_data("invalid request header"))?;
+
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(target), Some(version)) = (
+        request_line.next(),
+        request_line.next(),
+        request_line.next(),
+    ) else {
+        return Err(invalid_data("invalid request line"));
+    };
+    let url = Url::parse("http://localhost")
+        .and_then(|base| base.join(target))
+        .map_err(|_| invalid_data("invalid request target"))?;
+
+    let mut content_length = 0;
+    let mut close = version == "HTTP/1.0";
+    for line in lines.filter(|line| !line.is_empty()) {
+        let (name, value) = line
+            .split_once(':')
+            .ok_or_else(|| invalid_data("invalid request header"))?;
+        let (name, value) = (name.trim(), value.trim());
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = value
+                .parse()
+                .map_err(|_| invalid_data("invalid content length"))?;
+        } else if name.eq_ignore_ascii_case("transfer-encoding") {
+            return Err(invalid_data("transfer encodings are not supported"));
+        } else if name.eq_ignore_ascii_case("connection") {
+            close = value.eq_ignore_ascii_case("close");
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    Ok(Some(Request {
+        method: method.to_string(),
+        url,
+        body: body.into(),
+        close,
+    }))
+}
+
+/// A HTTP response.
+struct Response {
+    status: u16,
+    body: Vec<u8>,
+}
+
+impl Response {
+    const fn new(status: u16, body: Vec<u8>) -> Self {
+        Self { status, body }
+    }
+
+    const fn ok(body: Vec<u8>) -> Self {
+        Self::new(200, body)
+    }
+
+    const fn no_content() -> Self {
+        Self::new(204, vec![])
+    }
+
+    const fn not_found() -> Self {
+        Self::new(404, vec![])
+    }
+
+    fn json(value: &impl serde::Serialize) -> Result<Self, Self> {
+        serde_json::to_vec(value)
+            .map(Self::ok)
+            .map_err(|err| Self::new(500, err.to_string().into_bytes()))
+    }
+
+    fn write(&self, writer: &mut impl Write, close: bool) -> std::io::Result<()> {
+        let reason = match self.status {
+            200 => "OK",
+            204 => "No Content",
+            400 => "Bad Request",
+            403 => "Forbidden",
+            404 => "Not Found",
+            501 => "Not Implemented",
+            _ => "Internal Server Error",
+        };
+        let connection = if close { "close" } else { "keep-alive" };
+        write!(
+            writer,
+            "HTTP/1.1 {} {reason}\r\nContent-Length: {}\r\nConnection: {connection}\r\n\r\n",
+            self.status,
+            self.body.len()
+        )?;
+        writer.write_all(&self.body)?;
+        writer.flush()
+    }
+}
+
+impl From<StorageError> for Response {
+    fn from(err: StorageError) -> Self {
+        let status = match err {
+            StorageError::ReadOnly => 403,
+            StorageError::InvalidStoreKey(_) | StorageError::StorePrefixError(_) => 400,
+            _ => 500,
+        };
+        Self::new(status, err.to_string().into_bytes())
+    }
+}
+
+fn handle_request(store: &ServedStore, request: &Request) -> Result<Response, Response> {
+    let operation = request
+        .url
+        .path()
+        .strip_prefix(PATH_PREFIX)
+        .ok_or_else(|| Response::new(501, b"unsupported protocol version".to_vec()))?;
+    match store {
+        ServedStore::Readable(_) if WRITE_OPERATIONS.contains(&operation) => {
+            Err(StorageError::ReadOnly.into())
+        }
+        ServedStore::Readable(store) => handle_read(&**store, operation, request),
+        ServedStore::Writable(store) if WRITE_OPERATIONS.contains(&operation) => {
+            handle_write(&**store, operation, request)
+        }
+        ServedStore::Writable(store) => handle_read(&**store, operation, request),
+    }
+}
+
+fn unsupported_operation(request: &Request) -> Response {
+    Response::new(
+        501,
+        format!(
+            "unsupported operation {} {}",
+            request.method,
+            request.url.path()
+        )
+        .into_bytes(),
+    )
+}
+
+fn key_strings(keys: &[StoreKey]) -> Vec<String> {
+    keys.iter().map(|key| key.as_str().to_string()).collect()
+}
+
+fn handle_read<TStorage: ?Sized + ReadableStorageTraits + ListableStorageTraits>(
+    store: &TStorage,
+    operation: &str,
+    request: &Request,
+) -> Result<Response, Response> {
+    match (request.method.as_str(), operation) {
+        ("GET", "get") => Ok(store
+            .get(&request.key()?)?
+            .map_or_else(Response::not_found, |value| Response::ok(value.to_vec()))),
+        ("POST", "get_many") => {
+            let keys: Vec<String> = serde_json::from_slice(&request.body)
+                .map_err(|err| Response::new(400, err.to_string().into_bytes()))?;
+            let keys = keys
+                .into_iter()
+                .map(StoreKey::new)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(StorageError::from)?;
+            Ok(Response::ok(encode_maybe_values(&store.get_many(&keys)?)))
+        }
+        ("POST", "get_partial_values_key") => {
+            let byte_ranges: Vec<ByteRangeMessage> = serde_json::from_slice(&request.body)
+                .map_err(|err| Response::new(400, err.to_string().into_bytes()))?;
+            let byte_ranges: Vec<ByteRange> = byte_ranges.into_iter().map(Into::into).collect();
+            Ok(store
+                .get_partial_values_key(&request.key()?, &byte_ranges)?
+                .map_or_else(Response::not_found, |values| {
+                    Response::ok(encode_values(&values))
+                }))
+        }
+        ("GET", "size_key") => match store.size_key(&request.key()?)? {
+            Some(size) => Response::json(&size),
+            None => Ok(Response::not_found()),
+        },
+        ("GET", "list") => Response::json(&key_strings(&store.list()?)),
+        ("GET", "list_prefix") => {
+            Response::json(&key_strings(&store.list_prefix(&request.prefix()?)?))
+        }
+        ("GET", "list_dir") => {
+            let keys_prefixes = store.list_dir(&request.prefix()?)?;
+            Response::json(&ListDirMessage {
+                keys: key_strings(keys_prefixes.keys()),
+                prefixes: keys_prefixes
+                    .prefixes()
+                    .iter()
+                    .map(|prefix| prefix.as_str().to_string())
+                    .collect(),
+            })
+        }
+        ("GET", "size_prefix") => Response::json(&store.size_prefix(&request.prefix()?)?),
+        ("GET", "size") => Response::json(&store.size()?),
+        _ => Err(unsupported_operation(request)),
+    }
+}
+
+fn handle_write<TStorage: ?Sized + WritableStorageTraits>(
+    store: &TStorage,
+    operation: &str,
+    request: &Request,
+) -> Result<Response, Response> {
+    match (request.method.as_str(), operation) {
+        ("PUT", "set") => store.set(&request.key()?, request.body.clone())?,
+        ("PUT", "set_partial_values") => {
+            let keys = request
+                .params("key")
+                .map(StoreKey::new)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(StorageError::from)?;
+            let offsets = request
+                .params("offset")
+                .map(|offset| offset.parse::<u64>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err| Response::new(400, err.to_string().into_bytes()))?;
+            let values = decode_values(&request.body)
+                .filter(|values| values.len() == keys.len() && values.len() == offsets.len())
+                .ok_or_else(|| Response::new(400, b"invalid partial values".to_vec()))?;
+            let key_offset_values = keys
+                .into_iter()
+                .zip(offsets)
+                .zip(&values)
+                .map(|((key, offset), value)| StoreKeyOffsetValue::new(key, offset, value))
+                .collect::<Vec<_>>();
+            store.set_partial_values(&key_offset_values)?;
+        }
+        ("DELETE", "erase") => store.erase(&request.key()?)?,
+        ("DELETE", "erase_prefix") => store.erase_prefix(&request.prefix()?)?,
+        _ => return Err(unsupported_operation(request)),
+    }
+    Ok(Response::no_content())
+}
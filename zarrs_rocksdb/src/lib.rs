@@ -0,0 +1,358 @@
+//! A [RocksDB](https://rocksdb.org/) store for the [`zarrs`](https://docs.rs/zarrs/latest/zarrs/index.html) crate.
+//!
+//! Storing keys in a single database rather than as individual files avoids inode exhaustion for hierarchies with millions of small chunks.
+//!
+//! ```no_run
+//! # use std::sync::Arc;
+//! use zarrs_storage::ReadableWritableListableStorage;
+//! use zarrs_rocksdb::RocksDBStore;
+//!
+//! let store: ReadableWritableListableStorage =
+//!     Arc::new(RocksDBStore::open("/path/to/hierarchy.rocksdb")?);
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! ## Licence
+//! `zarrs_rocksdb` is licensed under either of
+//! - the Apache License, Version 2.0 [LICENSE-APACHE](https://docs.rs/crate/zarrs_rocksdb/latest/source/LICENCE-APACHE) or <http://www.apache.org/licenses/LICENSE-2.0> or
+//! - the MIT license [LICENSE-MIT](https://docs.rs/crate/zarrs_rocksdb/latest/source/LICENCE-MIT) or <http://opensource.org/licenses/MIT>, at your option.
+
+pub use rocksdb;
+
+use std::{collections::BTreeMap, path::Path, sync::Mutex};
+
+use rocksdb::{Options, WriteBatch, DB};
+use thiserror::Error;
+use zarrs_storage::{
+    byte_range::{extract_byte_ranges, ByteRange},
+    Bytes, ListableStorageTraits, ReadableStorageTraits, StorageError, StoreKey,
+    StoreKeyOffsetValue, StoreKeys, StoreKeysPrefixes, StorePrefix, WritableStorageTraits,
+};
+
+/// A synchronous `RocksDB` store.
+///
+/// Store keys are the keys of the database.
+/// Multi-key updates ([`set_partial_values`](WritableStorageTraits::set_partial_values) and [`erase_values`](WritableStorageTraits::erase_values)) are applied atomically in a single write batch.
+pub struct RocksDBStore {
+    db: DB,
+    readonly: bool,
+    /// Serialises read-modify-write updates of [`set_partial_values`](WritableStorageTraits::set_partial_values).
+    partial_write: Mutex<()>,
+}
+
+impl std::fmt::Debug for RocksDBStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RocksDBStore")
+            .field("path", &self.db.path())
+            .field("readonly", &self.readonly)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RocksDBStore {
+    /// Open a `RocksDB` database at `path` as a store, creating it if it does not exist.
+    ///
+    /// # Errors
+    /// Returns a [`RocksDBStoreCreateError`] if the database cannot be opened.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, RocksDBStoreCreateError> {
+        let mut options = Options::default();
+        options.create_if_missing(true);
+        Self::open_with_options(path, &options)
+    }
+
+    /// Open a `RocksDB` database at `path` as a store with `options`.
+    ///
+    /// # Errors
+    /// Returns a [`RocksDBStoreCreateError`] if the database cannot be opened.
+    pub fn open_with_options<P: AsRef<Path>>(
+        path: P,
+        options: &Options,
+    ) -> Result<Self, RocksDBStoreCreateError> {
+        Ok(Self::new(DB::open(options, path)?, false))
+    }
+
+    /// Open an existing `RocksDB` database at `path` as a read-only store.
+    ///
+    /// # Errors
+    /// Returns a [`RocksDBStoreCreateError`] if the database cannot be opened.
+    pub fn open_read_only<P: AsRef<Path>>(path: P) -> Result<Self, RocksDBStoreCreateError> {
+        Ok(Self::new(
+            DB::open_for_read_only(&Options::default(), path, false)?,
+            true,
+        ))
+    }
+
+    fn new(db: DB, readonly: bool) -> Self {
+        Self {
+            db,
+            readonly,
+            partial_write: Mutex::default(),
+        }
+    }
+
+    /// Return the underlying [`DB`].
+    #[must_use]
+    pub const fn db(&self) -> &DB {
+        &self.db
+    }
+
+    /// Iterate over the keys and value sizes of the database with `prefix`.
+    fn for_each_prefix(
+        &self,
+        prefix: &StorePrefix,
+        mut f: impl FnMut(&str, u64) -> Result<(), StorageError>,
+    ) -> Result<(), StorageError> {
+        let prefix = prefix.as_str().as_bytes();
+        let mut iter = self.db.raw_iterator();
+        iter.seek(prefix);
+        while let (Some(key), Some(value)) = (iter.key(), iter.value()) {
+            if !key.starts_with(prefix) {
+                break;
+            }
+            f(key_to_str(key)?, value.len() as u64)?;
+            iter.next();
+        }
+        iter.status().map_err(handle_rocksdb_error)
+    }
+
+    fn check_writable(&self) -> Result<(), StorageError> {
+        if self.readonly {
+            Err(StorageError::ReadOnly)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn handle_rocksdb_error(err: rocksdb::Error) -> StorageError {
+    StorageError::Other(err.into_string())
+}
+
+fn key_to_str(key: &[u8]) -> Result<&str, StorageError> {
+    std::str::from_utf8(key).map_err(|err| StorageError::Other(err.to_string()))
+}
+
+/// Return the smallest key greater than all keys starting with `prefix`, or [`None`] if there is no such key.
+fn prefix_upper_bound(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut upper_bound = prefix.to_vec();
+    while let Some(last) = upper_bound.pop() {
+        if last < u8::MAX {
+            upper_bound.push(last + 1);
+            return Some(upper_bound);
+        }
+    }
+    None
+}
+
+impl ReadableStorageTraits for RocksDBStore {
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        let Some(value) = self
+            .db
+            .get_pinned(key.as_str())
+            .map_err(handle_rocksdb_error)?
+        else {
+            return Ok(None);
+        };
+        Ok(Some(
+            extract_byte_ranges(&value, byte_ranges)?
+                .into_iter()
+                .map(Bytes::from)
+                .collect(),
+        ))
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        Ok(self
+            .db
+            .get_pinned(key.as_str())
+            .map_err(handle_rocksdb_error)?
+            .map(|value| value.len() as u64))
+    }
+}
+
+impl WritableStorageTraits for RocksDBStore {
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        self.check_writable()?;
+        self.db
+            .put(key.as_str(), value)
+            .map_err(handle_rocksdb_error)
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        self.check_writable()?;
+        let _lock = self.partial_write.lock().unwrap();
+
+        // Apply the updates to each value in memory
+        let mut values: BTreeMap<&StoreKey, Vec<u8>> = BTreeMap::new();
+        for key_offset_value in key_offset_values {
+            let value = match values.entry(key_offset_value.key()) {
+                std::collections::btree_map::Entry::Occupied(entry) => entry.into_mut(),
+                std::collections::btree_map::Entry::Vacant(entry) => entry.insert(
+                    self.db
+                        .get(key_offset_value.key().as_str())
+                        .map_err(handle_rocksdb_error)?
+                        .unwrap_or_default(),
+                ),
+            };
+            let start = usize::try_from(key_offset_value.offset()).unwrap();
+            let end = start + key_offset_value.value().len();
+            if value.len() < end {
+                value.resize(end, 0);
+            }
+            value[start..end].copy_from_slice(key_offset_value.value());
+        }
+
+        // Write the updated values in a single batch
+        let mut batch = WriteBatch::default();
+        for (key, value) in values {
+            batch.put(key.as_str(), value);
+        }
+        self.db.write(batch).map_err(handle_rocksdb_error)
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.check_writable()?;
+        self.db.delete(key.as_str()).map_err(handle_rocksdb_error)
+    }
+
+    fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        self.check_writable()?;
+        let mut batch = WriteBatch::default();
+        for key in keys {
+            batch.delete(key.as_str());
+        }
+        self.db.write(batch).map_err(handle_rocksdb_error)
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.check_writable()?;
+        let mut batch = WriteBatch::default();
+        if let Some(upper_bound) = prefix_upper_bound(prefix.as_str().as_bytes()) {
+            batch.delete_range(prefix.as_str().as_bytes(), upper_bound.as_slice());
+        } else {
+            // The root prefix has no upper bound, so delete every key
+            self.for_each_prefix(prefix, |key, _| {
+                batch.delete(key);
+                Ok(())
+            })?;
+        }
+        self.db.write(batch).map_err(handle_rocksdb_error)
+    }
+}
+
+impl ListableStorageTraits for RocksDBStore {
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.list_prefix(&StorePrefix::root())
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        let mut keys = StoreKeys::new();
+        self.for_each_prefix(prefix, |key, _| {
+            keys.push(StoreKey::new(key)?);
+            Ok(())
+        })?;
+        Ok(keys)
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        let mut keys = StoreKeys::new();
+        let mut prefixes = Vec::new();
+        let mut iter = self.db.raw_iterator();
+        iter.seek(prefix.as_str());
+        while let Some(key) = iter.key() {
+            let key = key_to_str(key)?;
+            let Some(child) = key.strip_prefix(prefix.as_str()) else {
+                break;
+            };
+            if let Some((child, _)) = child.split_once('/') {
+                // Skip past all keys of the child prefix
+                let child_prefix = format!("{}{child}/", prefix.as_str());
+                let upper_bound = prefix_upper_bound(child_prefix.as_bytes());
+                prefixes.push(StorePrefix::new(child_prefix)?);
+                match upper_bound {
+                    Some(upper_bound) => iter.seek(upper_bound),
+                    None => break,
+                }
+            } else {
+                keys.push(StoreKey::new(key)?);
+                iter.next();
+            }
+        }
+        iter.status().map_err(handle_rocksdb_error)?;
+        Ok(StoreKeysPrefixes::new(keys, prefixes))
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        let mut size = 0;
+        self.for_each_prefix(prefix, |_, size_key| {
+            size += size_key;
+            Ok(())
+        })?;
+        Ok(size)
+    }
+}
+
+/// A `RocksDB` store creation error.
+#[derive(Debug, Error)]
+pub enum RocksDBStoreCreateError {
+    /// A `RocksDB` error.
+    #[error(transparent)]
+    RocksDBError(#[from] rocksdb::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn rocksdb_prefix_upper_bound() {
+        assert_eq!(prefix_upper_bound(b""), None);
+        assert_eq!(prefix_upper_bound(b"a/"), Some(b"a0".to_vec()));
+        assert_eq!(prefix_upper_bound(b"a\xff"), Some(b"b".to_vec()));
+        assert_eq!(prefix_upper_bound(b"\xff\xff"), None);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn rocksdb() -> Result<(), Box<dyn Error>> {
+        let path = tempfile::TempDir::new()?;
+        let store = RocksDBStore::open(path.path())?;
+        zarrs_storage::store_test::store_write(&store)?;
+        zarrs_storage::store_test::store_read(&store)?;
+        zarrs_storage::store_test::store_list(&store)?;
+        drop(store);
+
+        let store = RocksDBStore::open_read_only(path.path())?;
+        zarrs_storage::store_test::store_read(&store)?;
+        zarrs_storage::store_test::store_list(&store)?;
+        assert!(store.set(&"a/b".try_into()?, vec![].into()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn rocksdb_batched() -> Result<(), Box<dyn Error>> {
+        let path = tempfile::TempDir::new()?;
+        let store = RocksDBStore::open(path.path())?;
+        store.set_partial_values(&[
+            StoreKeyOffsetValue::new("a".try_into()?, 2, &[2, 3]),
+            StoreKeyOffsetValue::new("b".try_into()?, 0, &[4]),
+            StoreKeyOffsetValue::new("a".try_into()?, 0, &[0, 1]),
+        ])?;
+        assert_eq!(store.get(&"a".try_into()?)?.unwrap(), vec![0, 1, 2, 3]);
+        assert_eq!(store.get(&"b".try_into()?)?.unwrap(), vec![4]);
+
+        store.erase_values(&["a".try_into()?, "b".try_into()?])?;
+        assert!(store.list()?.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,537 @@
+use aws_sdk_s3::{
+    operation::get_object::GetObjectError,
+    primitives::ByteStream,
+    types::{CompletedMultipartUpload, CompletedPart, Delete, ObjectIdentifier},
+    Client,
+};
+use futures::{StreamExt, TryStreamExt};
+
+use zarrs_storage::{
+    async_store_set_partial_values,
+    byte_range::{ByteRange, InvalidByteRangeError},
+    AsyncBytes, AsyncListableStorageTraits, AsyncReadableStorageTraits, AsyncWritableStorageTraits,
+    MaybeAsyncBytes, StorageError, StoreKey, StoreKeyOffsetValue, StoreKeys, StoreKeysPrefixes,
+    StorePrefix, StoreValueValidator,
+};
+
+use crate::{
+    byte_range_to_http_range, content_range_size, handle_sdk_error, multipart_ranges,
+    normalise_prefix, DEFAULT_MULTIPART_PART_SIZE, DEFAULT_MULTIPART_THRESHOLD,
+    DELETE_OBJECTS_MAX_KEYS, GET_MANY_MAX_CONCURRENT_REQUESTS,
+};
+
+/// An asynchronous Amazon S3 store.
+///
+/// Use [`S3StoreBuilder`](crate::S3StoreBuilder) to create a store with region, credential, and endpoint configuration, or [`AsyncS3Store::new`] with an existing [`aws_sdk_s3::Client`].
+#[derive(Debug, Clone)]
+pub struct AsyncS3Store {
+    client: Client,
+    bucket: String,
+    prefix: String,
+    multipart_threshold: u64,
+    multipart_part_size: u64,
+}
+
+impl AsyncS3Store {
+    /// Create a new S3 store for the root of `bucket` from an existing [`aws_sdk_s3::Client`].
+    #[must_use]
+    pub fn new(client: Client, bucket: impl Into<String>) -> Self {
+        Self {
+            client,
+            bucket: bucket.into(),
+            prefix: String::new(),
+            multipart_threshold: DEFAULT_MULTIPART_THRESHOLD,
+            multipart_part_size: DEFAULT_MULTIPART_PART_SIZE,
+        }
+    }
+
+    /// Set the prefix of the store root within the bucket.
+    #[must_use]
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.prefix = normalise_prefix(prefix);
+        self
+    }
+
+    /// Set the size threshold and part size in bytes of multipart uploads.
+    ///
+    /// The part size should be at least [`MINIMUM_MULTIPART_PART_SIZE`](crate::MINIMUM_MULTIPART_PART_SIZE), otherwise S3 will reject uploads.
+    #[must_use]
+    pub fn with_multipart(mut self, multipart_threshold: u64, multipart_part_size: u64) -> Self {
+        self.multipart_threshold = multipart_threshold;
+        self.multipart_part_size = multipart_part_size;
+        self
+    }
+
+    /// Return the underlying [`aws_sdk_s3::Client`].
+    #[must_use]
+    pub const fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Return the bucket.
+    #[must_use]
+    pub fn bucket(&self) -> &str {
+        &self.bucket
+    }
+
+    /// Maps a [`StoreKey`] to an S3 object key.
+    #[must_use]
+    pub fn key_to_object_key(&self, key: &StoreKey) -> String {
+        format!("{}{}", self.prefix, key.as_str())
+    }
+
+    fn prefix_to_object_prefix(&self, prefix: &StorePrefix) -> String {
+        format!("{}{}", self.prefix, prefix.as_str())
+    }
+
+    fn object_key_to_key(&self, object_key: &str) -> Result<StoreKey, StorageError> {
+        let key = object_key.strip_prefix(&self.prefix).unwrap_or(object_key);
+        Ok(StoreKey::new(key)?)
+    }
+
+    /// Retrieve a single byte range of a value.
+    ///
+    /// Returns [`None`] if the key is not found.
+    async fn get_range(
+        &self,
+        key: &StoreKey,
+        byte_range: &ByteRange,
+    ) -> Result<MaybeAsyncBytes, StorageError> {
+        let Some(range) = byte_range_to_http_range(byte_range) else {
+            // S3 cannot satisfy an empty range, so check the key exists instead
+            return Ok(self.size_key(key).await?.map(|_| AsyncBytes::new()));
+        };
+        let output = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(self.key_to_object_key(key))
+            .range(range)
+            .send()
+            .await;
+        let output = match output {
+            Ok(output) => output,
+            Err(err) => {
+                let service_error = err.as_service_error();
+                if service_error.is_some_and(GetObjectError::is_no_such_key) {
+                    return Ok(None);
+                } else if err.raw_response().map(|r| r.status().as_u16()) == Some(416) {
+                    // The range is not satisfiable, this is valid only for an empty range at the end of the value
+                    return if let Some(size) = self.size_key(key).await? {
+                        if byte_range.start(size) == size && byte_range.end(size) == size {
+                            Ok(Some(AsyncBytes::new()))
+                        } else {
+                            Err(InvalidByteRangeError::new(*byte_range, size).into())
+                        }
+                    } else {
+                        Ok(None)
+                    };
+                }
+                return Err(handle_sdk_error(err));
+            }
+        };
+
+        let size = output.content_range().and_then(content_range_size);
+        let bytes = output
+            .body
+            .collect()
+            .await
+            .map_err(handle_sdk_error)?
+            .into_bytes();
+        if let (Some(size), ByteRange::FromStart(_, Some(length))) = (size, byte_range) {
+            // S3 truncates ranges that extend beyond the end of the value
+            if bytes.len() as u64 != *length {
+                return Err(InvalidByteRangeError::new(*byte_range, size).into());
+            }
+        }
+        Ok(Some(bytes))
+    }
+
+    async fn put_multipart(&self, key: &StoreKey, value: AsyncBytes) -> Result<(), StorageError> {
+        let object_key = self.key_to_object_key(key);
+        let upload = self
+            .client
+            .create_multipart_upload()
+            .bucket(&self.bucket)
+            .key(&object_key)
+            .send()
+            .await
+            .map_err(handle_sdk_error)?;
+        let upload_id = upload
+            .upload_id()
+            .ok_or_else(|| StorageError::from("S3 did not return a multipart upload id"))?;
+
+        let part_size = usize::try_from(self.multipart_part_size).unwrap();
+        let parts = futures::future::try_join_all(
+            multipart_ranges(value.len(), part_size)
+                .into_iter()
+                .enumerate()
+                .map(|(i, range)| {
+                    let part_number = i32::try_from(i + 1).unwrap();
+                    let body = ByteStream::from(value.slice(range));
+                    let object_key = &object_key;
+                    async move {
+                        let output = self
+                            .client
+                            .upload_part()
+                            .bucket(&self.bucket)
+                            .key(object_key)
+                            .upload_id(upload_id)
+                            .part_number(part_number)
+                            .body(body)
+                            .send()
+                            .await
+                            .map_err(handle_sdk_error)?;
+                        Ok::<_, StorageError>(
+                            CompletedPart::builder()
+                                .set_e_tag(output.e_tag().map(str::to_string))
+                                .part_number(part_number)
+                                .build(),
+                        )
+                    }
+                }),
+        )
+        .await;
+
+        let result = match parts {
+            Ok(parts) => self
+                .client
+                .complete_multipart_upload()
+                .bucket(&self.bucket)
+                .key(&object_key)
+                .upload_id(upload_id)
+                .multipart_upload(
+                    CompletedMultipartUpload::builder()
+                        .set_parts(Some(parts))
+                        .build(),
+                )
+                .send()
+                .await
+                .map(|_| ())
+                .map_err(handle_sdk_error),
+            Err(err) => Err(err),
+        };
+
+        if result.is_err() {
+            // Best effort cleanup, the original error is more informative
+            let _ = self
+                .client
+                .abort_multipart_upload()
+                .bucket(&self.bucket)
+                .key(&object_key)
+                .upload_id(upload_id)
+                .send()
+                .await;
+        }
+        result
+    }
+
+    /// List all objects with an object key prefix, returning the object keys and sizes.
+    async fn list_objects(&self, object_prefix: &str) -> Result<Vec<(String, u64)>, StorageError> {
+        let mut pages = self
+            .client
+            .list_objects_v2()
+            .bucket(&self.bucket)
+            .prefix(object_prefix)
+            .into_paginator()
+            .send();
+        let mut objects = Vec::new();
+        while let Some(page) = pages.next().await {
+            let page = page.map_err(handle_sdk_error)?;
+            for object in page.contents() {
+                if let Some(key) = object.key() {
+                    let size = object.size().unwrap_or_default();
+                    objects.push((key.to_string(), u64::try_from(size).unwrap_or_default()));
+                }
+            }
+        }
+        Ok(objects)
+    }
+
+    /// Delete objects in batches with `DeleteObjects`.
+    async fn delete_objects(&self, object_keys: Vec<String>) -> Result<(), StorageError> {
+        futures::stream::iter(object_keys.chunks(DELETE_OBJECTS_MAX_KEYS))
+            .map(Ok)
+            .try_for_each_concurrent(None, |object_keys| async move {
+                let objects = object_keys
+                    .iter()
+                    .map(|key| ObjectIdentifier::builder().key(key).build())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(handle_sdk_error)?;
+                let delete = Delete::builder()
+                    .set_objects(Some(objects))
+                    .quiet(true)
+                    .build()
+                    .map_err(handle_sdk_error)?;
+                let output = self
+                    .client
+                    .delete_objects()
+                    .bucket(&self.bucket)
+                    .delete(delete)
+                    .send()
+                    .await
+                    .map_err(handle_sdk_error)?;
+                if let Some(error) = output.errors().first() {
+                    return Err(StorageError::Other(format!(
+                        "failed to delete {}: {}",
+                        error.key().unwrap_or_default(),
+                        error.message().unwrap_or_default()
+                    )));
+                }
+                Ok(())
+            })
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncReadableStorageTraits for AsyncS3Store {
+    async fn get(&self, key: &StoreKey) -> Result<MaybeAsyncBytes, StorageError> {
+        Ok(self.get_with_etag(key).await?.0)
+    }
+
+    async fn get_with_etag(
+        &self,
+        key: &StoreKey,
+    ) -> Result<(MaybeAsyncBytes, Option<StoreValueValidator>), StorageError> {
+        let output = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(self.key_to_object_key(key))
+            .send()
+            .await;
+        match output {
+            Ok(output) => {
+                let etag = output
+                    .e_tag()
+                    .map(|etag| StoreValueValidator::EntityTag(etag.to_string()));
+                let value = output
+                    .body
+                    .collect()
+                    .await
+                    .map_err(handle_sdk_error)?
+                    .into_bytes();
+                Ok((Some(value), etag))
+            }
+            Err(err) => {
+                if err
+                    .as_service_error()
+                    .is_some_and(GetObjectError::is_no_such_key)
+                {
+                    Ok((None, None))
+                } else {
+                    Err(handle_sdk_error(err))
+                }
+            }
+        }
+    }
+
+    async fn get_many(&self, keys: &[StoreKey]) -> Result<Vec<MaybeAsyncBytes>, StorageError> {
+        // S3 has no batched GetObject, so limit the number of requests in flight instead
+        let futures = keys.iter().map(|key| self.get(key)).collect::<Vec<_>>();
+        futures::stream::iter(futures)
+            .buffered(GET_MANY_MAX_CONCURRENT_REQUESTS)
+            .try_collect()
+            .await
+    }
+
+    async fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<AsyncBytes>>, StorageError> {
+        let values = futures::future::try_join_all(
+            byte_ranges
+                .iter()
+                .map(|byte_range| self.get_range(key, byte_range)),
+        )
+        .await?;
+        Ok(values.into_iter().collect())
+    }
+
+    async fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        let output = self
+            .client
+            .head_object()
+            .bucket(&self.bucket)
+            .key(self.key_to_object_key(key))
+            .send()
+            .await;
+        match output {
+            Ok(output) => Ok(Some(
+                output
+                    .content_length()
+                    .and_then(|length| u64::try_from(length).ok())
+                    .unwrap_or_default(),
+            )),
+            Err(err) => {
+                if err
+                    .as_service_error()
+                    .is_some_and(aws_sdk_s3::operation::head_object::HeadObjectError::is_not_found)
+                {
+                    Ok(None)
+                } else {
+                    Err(handle_sdk_error(err))
+                }
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncWritableStorageTraits for AsyncS3Store {
+    async fn set(&self, key: &StoreKey, value: AsyncBytes) -> Result<(), StorageError> {
+        if value.len() as u64 > self.multipart_threshold {
+            self.put_multipart(key, value).await
+        } else {
+            self.client
+                .put_object()
+                .bucket(&self.bucket)
+                .key(self.key_to_object_key(key))
+                .body(ByteStream::from(value))
+                .send()
+                .await
+                .map_err(handle_sdk_error)?;
+            Ok(())
+        }
+    }
+
+    fn supports_set_if_match(&self) -> bool {
+        true
+    }
+
+    /// Store `value` at `key` with a conditional `PutObject` request (`If-Match` or `If-None-Match: *`).
+    ///
+    /// The value is never written with a multipart upload, so it must not exceed the maximum `PutObject` size of 5 GiB.
+    async fn set_if_match(
+        &self,
+        key: &StoreKey,
+        value: AsyncBytes,
+        etag: Option<&StoreValueValidator>,
+    ) -> Result<Option<StoreValueValidator>, StorageError> {
+        let request = self
+            .client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(self.key_to_object_key(key))
+            .body(ByteStream::from(value));
+        let request = match etag {
+            Some(StoreValueValidator::EntityTag(etag)) => request.if_match(etag),
+            Some(etag) => {
+                return Err(StorageError::Unsupported(format!(
+                    "S3 conditional writes require an entity tag, not {etag:?}"
+                )))
+            }
+            None => request.if_none_match("*"),
+        };
+        match request.send().await {
+            Ok(output) => Ok(output
+                .e_tag()
+                .map(|etag| StoreValueValidator::EntityTag(etag.to_string()))),
+            Err(err) => {
+                // 412 Precondition Failed, or 409 Conflict for a concurrent conditional write
+                let status = err
+                    .raw_response()
+                    .map(|response| response.status().as_u16());
+                if matches!(status, Some(409 | 412)) {
+                    Err(StorageError::PreconditionFailed(key.clone()))
+                } else {
+                    Err(handle_sdk_error(err))
+                }
+            }
+        }
+    }
+
+    async fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        async_store_set_partial_values(self, key_offset_values).await
+    }
+
+    async fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(self.key_to_object_key(key))
+            .send()
+            .await
+            .map_err(handle_sdk_error)?;
+        Ok(())
+    }
+
+    async fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        self.delete_objects(keys.iter().map(|key| self.key_to_object_key(key)).collect())
+            .await
+    }
+
+    async fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        let object_keys = self
+            .list_objects(&self.prefix_to_object_prefix(prefix))
+            .await?
+            .into_iter()
+            .map(|(object_key, _)| object_key)
+            .collect();
+        self.delete_objects(object_keys).await
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncListableStorageTraits for AsyncS3Store {
+    async fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.list_prefix(&StorePrefix::root()).await
+    }
+
+    async fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        let mut keys = self
+            .list_objects(&self.prefix_to_object_prefix(prefix))
+            .await?
+            .into_iter()
+            .map(|(object_key, _)| self.object_key_to_key(&object_key))
+            .collect::<Result<Vec<_>, _>>()?;
+        keys.sort();
+        Ok(keys)
+    }
+
+    async fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        let mut pages = self
+            .client
+            .list_objects_v2()
+            .bucket(&self.bucket)
+            .prefix(self.prefix_to_object_prefix(prefix))
+            .delimiter("/")
+            .into_paginator()
+            .send();
+        let mut keys = Vec::new();
+        let mut prefixes = Vec::new();
+        while let Some(page) = pages.next().await {
+            let page = page.map_err(handle_sdk_error)?;
+            for object in page.contents() {
+                if let Some(object_key) = object.key() {
+                    keys.push(self.object_key_to_key(object_key)?);
+                }
+            }
+            for common_prefix in page.common_prefixes() {
+                if let Some(object_prefix) = common_prefix.prefix() {
+                    let prefix = object_prefix
+                        .strip_prefix(&self.prefix)
+                        .unwrap_or(object_prefix);
+                    prefixes.push(StorePrefix::new(prefix)?);
+                }
+            }
+        }
+        keys.sort();
+        prefixes.sort();
+        Ok(StoreKeysPrefixes::new(keys, prefixes))
+    }
+
+    async fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        Ok(self
+            .list_objects(&self.prefix_to_object_prefix(prefix))
+            .await?
+            .into_iter()
+            .map(|(_, size)| size)
+            .sum())
+    }
+}
@@ -0,0 +1,330 @@
+//! An Amazon S3 store for the [`zarrs`](https://docs.rs/zarrs/latest/zarrs/index.html) crate.
+//!
+//! The store is backed by the [AWS SDK for Rust](https://docs.rs/aws-sdk-s3/latest/aws_sdk_s3/).
+//! Compared to the generic `object_store` and `opendal` adapters, it:
+//! - retrieves multiple byte ranges of a value with parallel ranged `GetObject` requests,
+//! - uses suffix byte ranges directly, without first requesting the size of a value, and
+//! - writes values over a configurable size threshold with parallel multipart uploads.
+//!
+//! ```no_run
+//! # use std::sync::Arc;
+//! use zarrs_storage::AsyncReadableWritableListableStorage;
+//! use zarrs_s3::S3StoreBuilder;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let store: AsyncReadableWritableListableStorage = Arc::new(
+//!     S3StoreBuilder::new("bucket")
+//!         .region("us-east-1")
+//!         .prefix("path/to/hierarchy.zarr")
+//!         .build_async()
+//!         .await?,
+//! );
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! A synchronous [`S3Store`] can be created with [`S3StoreBuilder::build`].
+//!
+//! ## Licence
+//! `zarrs_s3` is licensed under either of
+//! - the Apache License, Version 2.0 [LICENSE-APACHE](https://docs.rs/crate/zarrs_s3/latest/source/LICENCE-APACHE) or <http://www.apache.org/licenses/LICENSE-2.0> or
+//! - the MIT license [LICENSE-MIT](https://docs.rs/crate/zarrs_s3/latest/source/LICENCE-MIT) or <http://opensource.org/licenses/MIT>, at your option.
+
+mod r#async;
+mod sync;
+
+pub use r#async::AsyncS3Store;
+pub use sync::S3Store;
+
+pub use aws_config;
+pub use aws_sdk_s3;
+
+use aws_sdk_s3::config::{Credentials, Region};
+use thiserror::Error;
+use zarrs_storage::{byte_range::ByteRange, StorageError};
+
+/// The default size threshold above which values are written with a multipart upload (16 MiB).
+pub const DEFAULT_MULTIPART_THRESHOLD: u64 = 16 * 1024 * 1024;
+
+/// The default part size of a multipart upload (8 MiB).
+pub const DEFAULT_MULTIPART_PART_SIZE: u64 = 8 * 1024 * 1024;
+
+/// The minimum part size of a multipart upload supported by S3 (5 MiB).
+///
+/// The last part of an upload may be smaller.
+pub const MINIMUM_MULTIPART_PART_SIZE: u64 = 5 * 1024 * 1024;
+
+/// The maximum number of keys that can be deleted in a single `DeleteObjects` request.
+const DELETE_OBJECTS_MAX_KEYS: usize = 1000;
+
+/// The maximum number of concurrent `GetObject` requests issued by `get_many`.
+const GET_MANY_MAX_CONCURRENT_REQUESTS: usize = 64;
+
+/// A builder for an [`AsyncS3Store`] or [`S3Store`].
+///
+/// The region, credentials, and endpoint are loaded from the environment (e.g. `AWS_REGION`, `AWS_ACCESS_KEY_ID`, `~/.aws/config`) unless explicitly set.
+#[derive(Debug, Clone)]
+pub struct S3StoreBuilder {
+    bucket: String,
+    prefix: String,
+    region: Option<String>,
+    credentials: Option<Credentials>,
+    endpoint_url: Option<String>,
+    force_path_style: bool,
+    multipart_threshold: u64,
+    multipart_part_size: u64,
+}
+
+impl S3StoreBuilder {
+    /// Create a new S3 store builder for `bucket`.
+    #[must_use]
+    pub fn new(bucket: impl Into<String>) -> Self {
+        Self {
+            bucket: bucket.into(),
+            prefix: String::new(),
+            region: None,
+            credentials: None,
+            endpoint_url: None,
+            force_path_style: false,
+            multipart_threshold: DEFAULT_MULTIPART_THRESHOLD,
+            multipart_part_size: DEFAULT_MULTIPART_PART_SIZE,
+        }
+    }
+
+    /// Set the prefix of the store root within the bucket.
+    ///
+    /// Defaults to the bucket root.
+    #[must_use]
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Set the region.
+    #[must_use]
+    pub fn region(mut self, region: impl Into<String>) -> Self {
+        self.region = Some(region.into());
+        self
+    }
+
+    /// Set static credentials from an access key ID, secret access key, and optional session token.
+    #[must_use]
+    pub fn credentials(
+        mut self,
+        access_key_id: impl Into<String>,
+        secret_access_key: impl Into<String>,
+        session_token: Option<String>,
+    ) -> Self {
+        self.credentials = Some(Credentials::new(
+            access_key_id,
+            secret_access_key,
+            session_token,
+            None,
+            "zarrs_s3",
+        ));
+        self
+    }
+
+    /// Set the endpoint URL, e.g. for an S3-compatible service such as `MinIO`.
+    #[must_use]
+    pub fn endpoint_url(mut self, endpoint_url: impl Into<String>) -> Self {
+        self.endpoint_url = Some(endpoint_url.into());
+        self
+    }
+
+    /// Set whether to use path-style addressing (`endpoint/bucket/key`) rather than virtual-hosted-style addressing.
+    ///
+    /// Defaults to false.
+    /// Many S3-compatible services require path-style addressing.
+    #[must_use]
+    pub const fn force_path_style(mut self, force_path_style: bool) -> Self {
+        self.force_path_style = force_path_style;
+        self
+    }
+
+    /// Set the size threshold in bytes above which values are written with a multipart upload.
+    ///
+    /// Defaults to [`DEFAULT_MULTIPART_THRESHOLD`].
+    #[must_use]
+    pub const fn multipart_threshold(mut self, multipart_threshold: u64) -> Self {
+        self.multipart_threshold = multipart_threshold;
+        self
+    }
+
+    /// Set the part size in bytes of multipart uploads.
+    ///
+    /// Defaults to [`DEFAULT_MULTIPART_PART_SIZE`].
+    /// Must be at least [`MINIMUM_MULTIPART_PART_SIZE`].
+    #[must_use]
+    pub const fn multipart_part_size(mut self, multipart_part_size: u64) -> Self {
+        self.multipart_part_size = multipart_part_size;
+        self
+    }
+
+    fn validate(&self) -> Result<(), S3StoreCreateError> {
+        if self.bucket.is_empty() {
+            return Err(S3StoreCreateError::InvalidBucket(self.bucket.clone()));
+        }
+        if self.multipart_part_size < MINIMUM_MULTIPART_PART_SIZE {
+            return Err(S3StoreCreateError::InvalidMultipartPartSize(
+                self.multipart_part_size,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Build an [`AsyncS3Store`].
+    ///
+    /// # Errors
+    /// Returns a [`S3StoreCreateError`] if the bucket name or multipart part size is invalid.
+    pub async fn build_async(self) -> Result<AsyncS3Store, S3StoreCreateError> {
+        self.validate()?;
+
+        let mut loader = aws_config::defaults(aws_config::BehaviorVersion::latest());
+        if let Some(region) = self.region {
+            loader = loader.region(Region::new(region));
+        }
+        if let Some(credentials) = self.credentials {
+            loader = loader.credentials_provider(credentials);
+        }
+        if let Some(endpoint_url) = self.endpoint_url {
+            loader = loader.endpoint_url(endpoint_url);
+        }
+        let sdk_config = loader.load().await;
+        let config = aws_sdk_s3::config::Builder::from(&sdk_config)
+            .force_path_style(self.force_path_style)
+            .build();
+        let client = aws_sdk_s3::Client::from_conf(config);
+
+        Ok(AsyncS3Store::new(client, self.bucket)
+            .with_prefix(&self.prefix)
+            .with_multipart(self.multipart_threshold, self.multipart_part_size))
+    }
+
+    /// Build a synchronous [`S3Store`].
+    ///
+    /// The store owns a multi-threaded `tokio` runtime.
+    ///
+    /// # Errors
+    /// Returns a [`S3StoreCreateError`] if the bucket name or multipart part size is invalid, or the runtime cannot be created.
+    pub fn build(self) -> Result<S3Store, S3StoreCreateError> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+        let store = runtime.block_on(self.build_async())?;
+        Ok(S3Store::new_with_runtime(store, runtime))
+    }
+}
+
+/// An S3 store creation error.
+#[derive(Debug, Error)]
+pub enum S3StoreCreateError {
+    /// An IO error.
+    #[error(transparent)]
+    IOError(#[from] std::io::Error),
+    /// The bucket name is not valid.
+    #[error("bucket {0} is not valid")]
+    InvalidBucket(String),
+    /// The multipart part size is less than [`MINIMUM_MULTIPART_PART_SIZE`].
+    #[error("multipart part size {0} is less than the minimum of {MINIMUM_MULTIPART_PART_SIZE}")]
+    InvalidMultipartPartSize(u64),
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn handle_sdk_error<E: std::error::Error + Send + Sync + 'static>(err: E) -> StorageError {
+    StorageError::Other(aws_sdk_s3::error::DisplayErrorContext(err).to_string())
+}
+
+/// Normalise a store prefix within a bucket so that it is empty or ends with `/`.
+fn normalise_prefix(prefix: &str) -> String {
+    let prefix = prefix.trim_matches('/');
+    if prefix.is_empty() {
+        String::new()
+    } else {
+        format!("{prefix}/")
+    }
+}
+
+/// Convert a [`ByteRange`] to a HTTP `Range` header value.
+///
+/// Returns [`None`] if the byte range is empty.
+fn byte_range_to_http_range(byte_range: &ByteRange) -> Option<String> {
+    match byte_range {
+        ByteRange::FromStart(offset, None) => Some(format!("bytes={offset}-")),
+        ByteRange::FromStart(_, Some(0)) | ByteRange::Suffix(0) => None,
+        ByteRange::FromStart(offset, Some(length)) => {
+            Some(format!("bytes={offset}-{}", offset + length - 1))
+        }
+        ByteRange::Suffix(length) => Some(format!("bytes=-{length}")),
+    }
+}
+
+/// Parse the complete length of a value from a `Content-Range` header value (e.g. `bytes 0-3/4`).
+fn content_range_size(content_range: &str) -> Option<u64> {
+    content_range
+        .rsplit_once('/')
+        .and_then(|(_, size)| size.parse().ok())
+}
+
+/// Split `size` bytes into multipart upload part ranges of `part_size` bytes.
+fn multipart_ranges(size: usize, part_size: usize) -> Vec<std::ops::Range<usize>> {
+    (0..size)
+        .step_by(part_size)
+        .map(|start| start..usize::min(start + part_size, size))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn s3_byte_range_to_http_range() {
+        assert_eq!(
+            byte_range_to_http_range(&ByteRange::FromStart(0, None)).as_deref(),
+            Some("bytes=0-")
+        );
+        assert_eq!(
+            byte_range_to_http_range(&ByteRange::FromStart(2, Some(3))).as_deref(),
+            Some("bytes=2-4")
+        );
+        assert_eq!(
+            byte_range_to_http_range(&ByteRange::Suffix(5)).as_deref(),
+            Some("bytes=-5")
+        );
+        assert!(byte_range_to_http_range(&ByteRange::FromStart(2, Some(0))).is_none());
+        assert!(byte_range_to_http_range(&ByteRange::Suffix(0)).is_none());
+    }
+
+    #[test]
+    fn s3_content_range_size() {
+        assert_eq!(content_range_size("bytes 0-3/4"), Some(4));
+        assert_eq!(content_range_size("bytes 0-3/*"), None);
+        assert_eq!(content_range_size("invalid"), None);
+    }
+
+    #[test]
+    fn s3_prefix() {
+        assert_eq!(normalise_prefix(""), "");
+        assert_eq!(normalise_prefix("/"), "");
+        assert_eq!(normalise_prefix("a/b"), "a/b/");
+        assert_eq!(normalise_prefix("/a/b/"), "a/b/");
+    }
+
+    #[test]
+    fn s3_multipart_ranges() {
+        assert_eq!(multipart_ranges(0, 4), vec![]);
+        assert_eq!(multipart_ranges(4, 4), vec![0..4]);
+        assert_eq!(multipart_ranges(10, 4), vec![0..4, 4..8, 8..10]);
+    }
+
+    #[test]
+    fn s3_builder_validate() {
+        assert!(S3StoreBuilder::new("").validate().is_err());
+        assert!(S3StoreBuilder::new("bucket")
+            .multipart_part_size(MINIMUM_MULTIPART_PART_SIZE - 1)
+            .validate()
+            .is_err());
+        assert!(S3StoreBuilder::new("bucket").validate().is_ok());
+    }
+}
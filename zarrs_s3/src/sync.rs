@@ -0,0 +1,130 @@
+use zarrs_storage::{
+    byte_range::ByteRange, AsyncListableStorageTraits, AsyncReadableStorageTraits,
+    AsyncWritableStorageTraits, Bytes, ListableStorageTraits, MaybeBytes, ReadableStorageTraits,
+    StorageError, StoreKey, StoreKeyOffsetValue, StoreKeyRange, StoreKeys, StoreKeysPrefixes,
+    StorePrefix, StoreValueValidator, WritableStorageTraits,
+};
+
+use crate::AsyncS3Store;
+
+/// A synchronous Amazon S3 store.
+///
+/// This store wraps an [`AsyncS3Store`] and a `tokio` runtime that drives its requests.
+/// Requests for multiple byte ranges or keys (e.g. with [`get_many`](ReadableStorageTraits::get_many)) are still issued in parallel.
+///
+/// An [`S3Store`] will panic if called within an asynchronous execution context!
+#[derive(Debug)]
+pub struct S3Store {
+    store: AsyncS3Store,
+    runtime: tokio::runtime::Runtime,
+}
+
+impl S3Store {
+    /// Create a new synchronous S3 store from an [`AsyncS3Store`] and the `tokio` runtime to drive it.
+    #[must_use]
+    pub fn new_with_runtime(store: AsyncS3Store, runtime: tokio::runtime::Runtime) -> Self {
+        Self { store, runtime }
+    }
+
+    /// Return the underlying [`AsyncS3Store`].
+    #[must_use]
+    pub const fn async_store(&self) -> &AsyncS3Store {
+        &self.store
+    }
+
+    fn block_on<F: core::future::Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+}
+
+impl ReadableStorageTraits for S3Store {
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        self.block_on(self.store.get(key))
+    }
+
+    fn get_many(&self, keys: &[StoreKey]) -> Result<Vec<MaybeBytes>, StorageError> {
+        self.block_on(self.store.get_many(keys))
+    }
+
+    fn get_with_etag(
+        &self,
+        key: &StoreKey,
+    ) -> Result<(MaybeBytes, Option<StoreValueValidator>), StorageError> {
+        self.block_on(self.store.get_with_etag(key))
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        self.block_on(self.store.get_partial_values_key(key, byte_ranges))
+    }
+
+    fn get_partial_values(
+        &self,
+        key_ranges: &[StoreKeyRange],
+    ) -> Result<Vec<MaybeBytes>, StorageError> {
+        self.block_on(self.store.get_partial_values(key_ranges))
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        self.block_on(self.store.size_key(key))
+    }
+}
+
+impl WritableStorageTraits for S3Store {
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        self.block_on(self.store.set(key, value))
+    }
+
+    fn supports_set_if_match(&self) -> bool {
+        self.store.supports_set_if_match()
+    }
+
+    fn set_if_match(
+        &self,
+        key: &StoreKey,
+        value: Bytes,
+        etag: Option<&StoreValueValidator>,
+    ) -> Result<Option<StoreValueValidator>, StorageError> {
+        self.block_on(self.store.set_if_match(key, value, etag))
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        self.block_on(self.store.set_partial_values(key_offset_values))
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.block_on(self.store.erase(key))
+    }
+
+    fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        self.block_on(self.store.erase_values(keys))
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.block_on(self.store.erase_prefix(prefix))
+    }
+}
+
+impl ListableStorageTraits for S3Store {
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.block_on(self.store.list())
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.block_on(self.store.list_prefix(prefix))
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.block_on(self.store.list_dir(prefix))
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.block_on(self.store.size_prefix(prefix))
+    }
+}
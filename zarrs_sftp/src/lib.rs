@@ -0,0 +1,661 @@
+//! An SFTP store for the [`zarrs`](https://docs.rs/zarrs/latest/zarrs/index.html) crate.
+//!
+//! The store is backed by [`ssh2`](https://docs.rs/ssh2/latest/ssh2/) (`libssh2`), and can access hierarchies on HPC login nodes or legacy servers without mounting them.
+//! A `libssh2` session serialises its operations, so the store maintains a pool of connections that are opened on demand for concurrent chunk retrieval.
+//!
+//! ```no_run
+//! # use std::sync::Arc;
+//! use zarrs_storage::ReadableWritableListableStorage;
+//! use zarrs_sftp::SftpStoreBuilder;
+//!
+//! let store: ReadableWritableListableStorage = Arc::new(
+//!     SftpStoreBuilder::new("login.hpc.example.com", "user")
+//!         .private_key_file("/home/user/.ssh/id_ed25519", None)
+//!         .root("/scratch/user/hierarchy.zarr")
+//!         .max_connections(16)
+//!         .build()?,
+//! );
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! Building this crate compiles `libssh2`, which requires `OpenSSL` on most platforms.
+//!
+//! ## Licence
+//! `zarrs_sftp` is licensed under either of
+//! - the Apache License, Version 2.0 [LICENSE-APACHE](https://docs.rs/crate/zarrs_sftp/latest/source/LICENCE-APACHE) or <http://www.apache.org/licenses/LICENSE-2.0> or
+//! - the MIT license [LICENSE-MIT](https://docs.rs/crate/zarrs_sftp/latest/source/LICENCE-MIT) or <http://opensource.org/licenses/MIT>, at your option.
+
+mod pool;
+
+pub use ssh2;
+
+use std::{
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use pool::{ConnectionConfig, ConnectionPool};
+use ssh2::{ErrorCode, OpenFlags, OpenType, Sftp};
+use thiserror::Error;
+use zarrs_storage::{
+    byte_range::{ByteRange, InvalidByteRangeError},
+    Bytes, ListableStorageTraits, ReadableStorageTraits, StorageError, StoreKey,
+    StoreKeyOffsetValue, StoreKeys, StoreKeysPrefixes, StorePrefix, WritableStorageTraits,
+};
+
+/// The default port of an SFTP server.
+pub const DEFAULT_PORT: u16 = 22;
+
+/// The default maximum number of concurrent connections of an [`SftpStore`].
+pub const DEFAULT_MAX_CONNECTIONS: usize = 8;
+
+/// The permissions of files created by an [`SftpStore`].
+const FILE_MODE: i32 = 0o644;
+
+/// The permissions of directories created by an [`SftpStore`].
+const DIR_MODE: i32 = 0o755;
+
+/// An SFTP authentication method.
+#[derive(Clone)]
+pub(crate) enum SftpAuth {
+    Password(String),
+    PrivateKeyFile {
+        private_key: PathBuf,
+        passphrase: Option<String>,
+    },
+    Agent,
+}
+
+impl std::fmt::Debug for SftpAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Secrets are not printed
+        match self {
+            Self::Password(_) => f.write_str("Password"),
+            Self::PrivateKeyFile { private_key, .. } => f
+                .debug_struct("PrivateKeyFile")
+                .field("private_key", private_key)
+                .finish_non_exhaustive(),
+            Self::Agent => f.write_str("Agent"),
+        }
+    }
+}
+
+/// A builder for an [`SftpStore`].
+///
+/// By default, the user is authenticated with an SSH agent and the host key is verified against `~/.ssh/known_hosts`.
+#[derive(Debug, Clone)]
+pub struct SftpStoreBuilder {
+    host: String,
+    port: u16,
+    username: String,
+    auth: SftpAuth,
+    root: String,
+    known_hosts: Option<PathBuf>,
+    max_connections: usize,
+    timeout: Option<Duration>,
+}
+
+impl SftpStoreBuilder {
+    /// Create a new SFTP store builder for `username` on `host`.
+    #[must_use]
+    pub fn new(host: impl Into<String>, username: impl Into<String>) -> Self {
+        Self {
+            host: host.into(),
+            port: DEFAULT_PORT,
+            username: username.into(),
+            auth: SftpAuth::Agent,
+            root: String::new(),
+            known_hosts: default_known_hosts(),
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+            timeout: None,
+        }
+    }
+
+    /// Set the port.
+    ///
+    /// Defaults to [`DEFAULT_PORT`].
+    #[must_use]
+    pub const fn port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
+    /// Authenticate with a password.
+    #[must_use]
+    pub fn password(mut self, password: impl Into<String>) -> Self {
+        self.auth = SftpAuth::Password(password.into());
+        self
+    }
+
+    /// Authenticate with a private key file and an optional passphrase.
+    #[must_use]
+    pub fn private_key_file(
+        mut self,
+        private_key: impl Into<PathBuf>,
+        passphrase: Option<String>,
+    ) -> Self {
+        self.auth = SftpAuth::PrivateKeyFile {
+            private_key: private_key.into(),
+            passphrase,
+        };
+        self
+    }
+
+    /// Authenticate with an SSH agent.
+    ///
+    /// This is the default.
+    #[must_use]
+    pub fn agent(mut self) -> Self {
+        self.auth = SftpAuth::Agent;
+        self
+    }
+
+    /// Set the remote directory of the store root.
+    ///
+    /// Relative paths are relative to the login directory of the user.
+    /// Defaults to the login directory.
+    #[must_use]
+    pub fn root(mut self, root: impl Into<String>) -> Self {
+        self.root = root.into();
+        self
+    }
+
+    /// Set the known hosts file used to verify the host key.
+    ///
+    /// Defaults to `~/.ssh/known_hosts`.
+    #[must_use]
+    pub fn known_hosts(mut self, known_hosts: impl Into<PathBuf>) -> Self {
+        self.known_hosts = Some(known_hosts.into());
+        self
+    }
+
+    /// Disable host key verification.
+    ///
+    /// This is insecure, and should only be used with trusted networks.
+    #[must_use]
+    pub fn disable_host_key_verification(mut self) -> Self {
+        self.known_hosts = None;
+        self
+    }
+
+    /// Set the maximum number of concurrent connections.
+    ///
+    /// Connections are opened on demand up to this limit, and operations wait for an idle connection once it is reached.
+    /// Defaults to [`DEFAULT_MAX_CONNECTIONS`].
+    #[must_use]
+    pub const fn max_connections(mut self, max_connections: usize) -> Self {
+        self.max_connections = max_connections;
+        self
+    }
+
+    /// Set the timeout of blocking session operations.
+    ///
+    /// Defaults to no timeout.
+    #[must_use]
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Build an [`SftpStore`].
+    ///
+    /// An initial connection is opened to check the host and credentials.
+    ///
+    /// # Errors
+    /// Returns a [`SftpStoreCreateError`] if the maximum number of connections is zero, or the initial connection cannot be opened, verified, or authenticated.
+    pub fn build(self) -> Result<SftpStore, SftpStoreCreateError> {
+        if self.max_connections == 0 {
+            return Err(SftpStoreCreateError::InvalidMaxConnections);
+        }
+        let config = ConnectionConfig {
+            host: self.host,
+            port: self.port,
+            username: self.username,
+            auth: self.auth,
+            known_hosts: self.known_hosts,
+            timeout: self.timeout,
+        };
+        let connection = config.connect()?;
+        Ok(SftpStore {
+            root: normalise_root(&self.root),
+            pool: ConnectionPool::new(config, self.max_connections, connection),
+        })
+    }
+}
+
+fn default_known_hosts() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| Path::new(&home).join(".ssh").join("known_hosts"))
+}
+
+/// Normalise the store root so that it does not have a trailing `/`, except for the filesystem root.
+fn normalise_root(root: &str) -> String {
+    let trimmed = root.trim_end_matches('/');
+    if trimmed.is_empty() && root.starts_with('/') {
+        "/".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Join a store key or prefix `path` to the store `root`.
+fn join_path(root: &str, path: &str) -> PathBuf {
+    let path = path.trim_end_matches('/');
+    match (root, path) {
+        ("", "") => PathBuf::from("."),
+        ("", path) => PathBuf::from(path),
+        (root, "") => PathBuf::from(root),
+        ("/", path) => PathBuf::from(format!("/{path}")),
+        (root, path) => PathBuf::from(format!("{root}/{path}")),
+    }
+}
+
+/// Return true if `err` indicates that the connection is no longer usable.
+fn is_connection_error(err: &io::Error) -> bool {
+    match err
+        .get_ref()
+        .and_then(|err| err.downcast_ref::<ssh2::Error>())
+    {
+        Some(err) => matches!(err.code(), ErrorCode::Session(_)),
+        None => matches!(
+            err.kind(),
+            io::ErrorKind::BrokenPipe
+                | io::ErrorKind::ConnectionAborted
+                | io::ErrorKind::ConnectionReset
+                | io::ErrorKind::TimedOut
+        ),
+    }
+}
+
+/// Map a not found error to [`None`], pass through other errors.
+fn not_found_to_none<T>(result: Result<T, ssh2::Error>) -> io::Result<Option<T>> {
+    match result.map_err(io::Error::from) {
+        Ok(value) => Ok(Some(value)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+fn file_name(path: &Path) -> io::Result<&str> {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} is not a valid store key component", path.display()),
+            )
+        })
+}
+
+/// Call `f` with the key and size of every file in `dir` and its subdirectories, where `key_prefix` is the store prefix of `dir`.
+fn walk(
+    sftp: &Sftp,
+    dir: &Path,
+    key_prefix: &str,
+    f: &mut impl FnMut(String, u64),
+) -> io::Result<()> {
+    let Some(entries) = not_found_to_none(sftp.readdir(dir))? else {
+        return Ok(());
+    };
+    for (path, stat) in entries {
+        let name = file_name(&path)?;
+        if stat.is_dir() {
+            walk(sftp, &path, &format!("{key_prefix}{name}/"), f)?;
+        } else if stat.is_file() {
+            f(format!("{key_prefix}{name}"), stat.size.unwrap_or_default());
+        }
+    }
+    Ok(())
+}
+
+/// Remove all files and directories within `dir`.
+fn remove_dir_contents(sftp: &Sftp, dir: &Path) -> io::Result<()> {
+    let Some(entries) = not_found_to_none(sftp.readdir(dir))? else {
+        return Ok(());
+    };
+    for (path, stat) in entries {
+        if stat.is_dir() {
+            remove_dir_contents(sftp, &path)?;
+            not_found_to_none(sftp.rmdir(&path))?;
+        } else {
+            not_found_to_none(sftp.unlink(&path))?;
+        }
+    }
+    Ok(())
+}
+
+/// Create `dir` and all of its missing parents.
+fn create_dir_all(sftp: &Sftp, dir: &Path) -> io::Result<()> {
+    if dir.as_os_str().is_empty() || sftp.stat(dir).is_ok() {
+        return Ok(());
+    }
+    if let Some(parent) = dir.parent() {
+        create_dir_all(sftp, parent)?;
+    }
+    match sftp.mkdir(dir, DIR_MODE) {
+        Ok(()) => Ok(()),
+        // The directory may have been created concurrently
+        Err(_) if sftp.stat(dir).is_ok_and(|stat| stat.is_dir()) => Ok(()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// A synchronous SFTP store.
+///
+/// Store keys are files relative to the store root, and are created with `0644` permissions.
+/// Operations use a connection from a pool, so concurrent operations run in parallel up to the maximum number of connections.
+pub struct SftpStore {
+    root: String,
+    pool: ConnectionPool,
+}
+
+impl std::fmt::Debug for SftpStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let config = self.pool.config();
+        f.debug_struct("SftpStore")
+            .field("host", &config.host)
+            .field("port", &config.port)
+            .field("username", &config.username)
+            .field("root", &self.root)
+            .field("max_connections", &self.pool.max_connections())
+            .finish_non_exhaustive()
+    }
+}
+
+impl SftpStore {
+    /// Return the remote directory of the store root.
+    #[must_use]
+    pub fn root(&self) -> &str {
+        &self.root
+    }
+
+    fn key_path(&self, key: &StoreKey) -> PathBuf {
+        join_path(&self.root, key.as_str())
+    }
+
+    fn prefix_path(&self, prefix: &StorePrefix) -> PathBuf {
+        join_path(&self.root, prefix.as_str())
+    }
+
+    /// Run `f` with a pooled connection.
+    ///
+    /// The connection is closed rather than returned to the pool if `f` fails with a connection error.
+    fn with_connection<T>(
+        &self,
+        f: impl FnOnce(&Sftp) -> Result<T, StorageError>,
+    ) -> Result<T, StorageError> {
+        let mut connection = self.pool.get()?;
+        let result = f(&connection);
+        if let Err(StorageError::IOError(err)) = &result {
+            if is_connection_error(err) {
+                connection.mark_broken();
+            }
+        }
+        result
+    }
+
+    /// List the keys and sizes of files with `prefix`.
+    fn walk_prefix(&self, prefix: &StorePrefix) -> Result<Vec<(String, u64)>, StorageError> {
+        let dir = self.prefix_path(prefix);
+        let mut entries = Vec::new();
+        self.with_connection(|sftp| {
+            walk(sftp, &dir, prefix.as_str(), &mut |key, size| {
+                entries.push((key, size));
+            })?;
+            Ok(())
+        })?;
+        Ok(entries)
+    }
+}
+
+impl ReadableStorageTraits for SftpStore {
+    fn get(&self, key: &StoreKey) -> Result<Option<Bytes>, StorageError> {
+        let path = self.key_path(key);
+        self.with_connection(|sftp| {
+            let Some(mut file) = not_found_to_none(sftp.open(&path))? else {
+                return Ok(None);
+            };
+            let mut value = Vec::new();
+            file.read_to_end(&mut value)?;
+            Ok(Some(value.into()))
+        })
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        let path = self.key_path(key);
+        self.with_connection(|sftp| {
+            let Some(mut file) = not_found_to_none(sftp.open(&path))? else {
+                return Ok(None);
+            };
+            let size = file
+                .stat()
+                .map_err(io::Error::from)?
+                .size
+                .ok_or_else(|| StorageError::UnknownKeySize(key.clone()))?;
+            let mut out = Vec::with_capacity(byte_ranges.len());
+            for byte_range in byte_ranges {
+                let start = byte_range.start(size);
+                let end = byte_range.end(size);
+                if end > size {
+                    return Err(InvalidByteRangeError::new(*byte_range, size).into());
+                }
+                file.seek(SeekFrom::Start(start))?;
+                let mut bytes = vec![0; usize::try_from(end - start).unwrap()];
+                file.read_exact(&mut bytes)?;
+                out.push(bytes.into());
+            }
+            Ok(Some(out))
+        })
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        let path = self.key_path(key);
+        self.with_connection(|sftp| {
+            Ok(not_found_to_none(sftp.stat(&path))?
+                .filter(ssh2::FileStat::is_file)
+                .and_then(|stat| stat.size))
+        })
+    }
+}
+
+impl WritableStorageTraits for SftpStore {
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        let path = self.key_path(key);
+        self.with_connection(|sftp| {
+            if let Some(parent) = path.parent() {
+                create_dir_all(sftp, parent)?;
+            }
+            let mut file = sftp
+                .open_mode(
+                    &path,
+                    OpenFlags::WRITE | OpenFlags::CREATE | OpenFlags::TRUNCATE,
+                    FILE_MODE,
+                    OpenType::File,
+                )
+                .map_err(io::Error::from)?;
+            file.write_all(&value)?;
+            Ok(())
+        })
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        self.with_connection(|sftp| {
+            // Reuse the open file for consecutive values of the same key
+            let mut open: Option<(&StoreKey, ssh2::File)> = None;
+            for key_offset_value in key_offset_values {
+                let key = key_offset_value.key();
+                let file = match &mut open {
+                    Some((open_key, file)) if *open_key == key => file,
+                    _ => {
+                        let path = self.key_path(key);
+                        if let Some(parent) = path.parent() {
+                            create_dir_all(sftp, parent)?;
+                        }
+                        let file = sftp
+                            .open_mode(
+                                &path,
+                                OpenFlags::WRITE | OpenFlags::CREATE,
+                                FILE_MODE,
+                                OpenType::File,
+                            )
+                            .map_err(io::Error::from)?;
+                        &mut open.insert((key, file)).1
+                    }
+                };
+                file.seek(SeekFrom::Start(key_offset_value.offset()))?;
+                file.write_all(key_offset_value.value())?;
+            }
+            Ok(())
+        })
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        let path = self.key_path(key);
+        self.with_connection(|sftp| {
+            not_found_to_none(sftp.unlink(&path))?;
+            Ok(())
+        })
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        let dir = self.prefix_path(prefix);
+        self.with_connection(|sftp| {
+            remove_dir_contents(sftp, &dir)?;
+            if !prefix.as_str().is_empty() {
+                not_found_to_none(sftp.rmdir(&dir))?;
+            }
+            Ok(())
+        })
+    }
+}
+
+impl ListableStorageTraits for SftpStore {
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.list_prefix(&StorePrefix::root())
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        let mut keys = self
+            .walk_prefix(prefix)?
+            .into_iter()
+            .map(|(key, _)| StoreKey::new(key))
+            .collect::<Result<StoreKeys, _>>()?;
+        keys.sort();
+        Ok(keys)
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        let dir = self.prefix_path(prefix);
+        let entries = self.with_connection(|sftp| {
+            Ok(not_found_to_none(sftp.readdir(&dir))?.unwrap_or_default())
+        })?;
+        let mut keys = StoreKeys::new();
+        let mut prefixes = Vec::new();
+        for (path, stat) in entries {
+            let name = file_name(&path)?;
+            if stat.is_dir() {
+                prefixes.push(StorePrefix::new(format!("{}{name}/", prefix.as_str()))?);
+            } else if stat.is_file() {
+                keys.push(StoreKey::new(format!("{}{name}", prefix.as_str()))?);
+            }
+        }
+        keys.sort();
+        prefixes.sort();
+        Ok(StoreKeysPrefixes::new(keys, prefixes))
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        Ok(self
+            .walk_prefix(prefix)?
+            .into_iter()
+            .map(|(_, size)| size)
+            .sum())
+    }
+}
+
+/// An SFTP store creation error.
+#[derive(Debug, Error)]
+pub enum SftpStoreCreateError {
+    /// An IO error.
+    #[error(transparent)]
+    IOError(#[from] std::io::Error),
+    /// An SSH error.
+    #[error(transparent)]
+    SshError(#[from] ssh2::Error),
+    /// The host key could not be verified.
+    #[error("host key verification failed: {0}")]
+    HostKeyVerification(String),
+    /// The user could not be authenticated.
+    #[error("authentication failed for user {0}")]
+    AuthenticationFailed(String),
+    /// The maximum number of connections is zero.
+    #[error("the maximum number of connections must be non-zero")]
+    InvalidMaxConnections,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn sftp_join_path() {
+        assert_eq!(join_path("", ""), PathBuf::from("."));
+        assert_eq!(join_path("", "a/b"), PathBuf::from("a/b"));
+        assert_eq!(join_path("", "a/"), PathBuf::from("a"));
+        assert_eq!(join_path("/", ""), PathBuf::from("/"));
+        assert_eq!(join_path("/", "a/b"), PathBuf::from("/a/b"));
+        assert_eq!(join_path("/data", "a/"), PathBuf::from("/data/a"));
+        assert_eq!(
+            join_path("data.zarr", "zarr.json"),
+            PathBuf::from("data.zarr/zarr.json")
+        );
+    }
+
+    #[test]
+    fn sftp_normalise_root() {
+        assert_eq!(normalise_root(""), "");
+        assert_eq!(normalise_root("/"), "/");
+        assert_eq!(normalise_root("//"), "/");
+        assert_eq!(normalise_root("/data/"), "/data");
+        assert_eq!(normalise_root("data.zarr"), "data.zarr");
+    }
+
+    #[test]
+    fn sftp_builder_validation() {
+        assert!(matches!(
+            SftpStoreBuilder::new("localhost", "user")
+                .max_connections(0)
+                .build(),
+            Err(SftpStoreCreateError::InvalidMaxConnections)
+        ));
+    }
+
+    /// Requires an SFTP server configured with `ZARRS_SFTP_HOST`, `ZARRS_SFTP_USERNAME`, and `ZARRS_SFTP_PASSWORD`.
+    #[test]
+    #[ignore = "requires an SFTP server"]
+    fn sftp() -> Result<(), Box<dyn Error>> {
+        let store = SftpStoreBuilder::new(
+            std::env::var("ZARRS_SFTP_HOST")?,
+            std::env::var("ZARRS_SFTP_USERNAME")?,
+        )
+        .password(std::env::var("ZARRS_SFTP_PASSWORD")?)
+        .disable_host_key_verification()
+        .root("zarrs_sftp_test")
+        .max_connections(4)
+        .build()?;
+        store.erase_prefix(&StorePrefix::root())?;
+        zarrs_storage::store_test::store_write(&store)?;
+        zarrs_storage::store_test::store_read(&store)?;
+        zarrs_storage::store_test::store_list(&store)?;
+        store.erase_prefix(&StorePrefix::root())?;
+        Ok(())
+    }
+}
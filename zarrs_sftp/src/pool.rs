@@ -0,0 +1,219 @@
+//! A pool of SFTP connections.
+
+use std::{
+    net::TcpStream,
+    ops::Deref,
+    path::PathBuf,
+    sync::{Condvar, Mutex},
+    time::Duration,
+};
+
+use ssh2::{CheckResult, KnownHostFileKind, Session, Sftp};
+use zarrs_storage::StorageError;
+
+use crate::{SftpAuth, SftpStoreCreateError};
+
+/// The parameters of an SFTP connection.
+#[derive(Debug, Clone)]
+pub(crate) struct ConnectionConfig {
+    pub(crate) host: String,
+    pub(crate) port: u16,
+    pub(crate) username: String,
+    pub(crate) auth: SftpAuth,
+    pub(crate) known_hosts: Option<PathBuf>,
+    pub(crate) timeout: Option<Duration>,
+}
+
+impl ConnectionConfig {
+    /// Open an authenticated SFTP connection.
+    pub(crate) fn connect(&self) -> Result<Connection, SftpStoreCreateError> {
+        let tcp = TcpStream::connect((self.host.as_str(), self.port))?;
+        let mut session = Session::new()?;
+        if let Some(timeout) = self.timeout {
+            session.set_timeout(u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX));
+        }
+        session.set_tcp_stream(tcp);
+        session.handshake()?;
+        self.verify_host_key(&session)?;
+
+        match &self.auth {
+            SftpAuth::Password(password) => session.userauth_password(&self.username, password)?,
+            SftpAuth::PrivateKeyFile {
+                private_key,
+                passphrase,
+            } => session.userauth_pubkey_file(
+                &self.username,
+                None,
+                private_key,
+                passphrase.as_deref(),
+            )?,
+            SftpAuth::Agent => session.userauth_agent(&self.username)?,
+        }
+        if !session.authenticated() {
+            return Err(SftpStoreCreateError::AuthenticationFailed(
+                self.username.clone(),
+            ));
+        }
+
+        let sftp = session.sftp()?;
+        Ok(Connection {
+            sftp,
+            _session: session,
+        })
+    }
+
+    /// Check the host key of `session` against the known hosts file, if any.
+    fn verify_host_key(&self, session: &Session) -> Result<(), SftpStoreCreateError> {
+        let Some(known_hosts_path) = &self.known_hosts else {
+            return Ok(());
+        };
+        let mut known_hosts = session.known_hosts()?;
+        known_hosts.read_file(known_hosts_path, KnownHostFileKind::OpenSSH)?;
+        let (key, _) = session.host_key().ok_or_else(|| {
+            SftpStoreCreateError::HostKeyVerification(format!(
+                "{} did not provide a host key",
+                self.host
+            ))
+        })?;
+        match known_hosts.check_port(&self.host, self.port, key) {
+            CheckResult::Match => Ok(()),
+            CheckResult::NotFound => Err(SftpStoreCreateError::HostKeyVerification(format!(
+                "{} is not in {}",
+                self.host,
+                known_hosts_path.display()
+            ))),
+            CheckResult::Mismatch => Err(SftpStoreCreateError::HostKeyVerification(format!(
+                "the host key of {} does not match {}",
+                self.host,
+                known_hosts_path.display()
+            ))),
+            CheckResult::Failure => Err(SftpStoreCreateError::HostKeyVerification(format!(
+                "failed to check the host key of {}",
+                self.host
+            ))),
+        }
+    }
+}
+
+/// An authenticated SFTP connection.
+pub(crate) struct Connection {
+    sftp: Sftp,
+    // The session must outlive the SFTP channel
+    _session: Session,
+}
+
+#[derive(Default)]
+struct ConnectionPoolState {
+    idle: Vec<Connection>,
+    /// The number of open connections, including those checked out.
+    open: usize,
+}
+
+/// A pool of up to `max_connections` SFTP connections, opened on demand.
+///
+/// A `libssh2` session serialises all operations on its channels, so concurrent operations require separate connections.
+pub(crate) struct ConnectionPool {
+    config: ConnectionConfig,
+    max_connections: usize,
+    state: Mutex<ConnectionPoolState>,
+    available: Condvar,
+}
+
+impl ConnectionPool {
+    /// Create a new connection pool with an initial open connection.
+    pub(crate) fn new(
+        config: ConnectionConfig,
+        max_connections: usize,
+        connection: Connection,
+    ) -> Self {
+        Self {
+            config,
+            max_connections,
+            state: Mutex::new(ConnectionPoolState {
+                idle: vec![connection],
+                open: 1,
+            }),
+            available: Condvar::new(),
+        }
+    }
+
+    pub(crate) const fn config(&self) -> &ConnectionConfig {
+        &self.config
+    }
+
+    pub(crate) const fn max_connections(&self) -> usize {
+        self.max_connections
+    }
+
+    /// Check out an idle connection, opening a new connection if none are idle and the pool is not full.
+    ///
+    /// Blocks until a connection is returned to the pool if the pool is full.
+    pub(crate) fn get(&self) -> Result<PooledConnection<'_>, StorageError> {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if let Some(connection) = state.idle.pop() {
+                return Ok(PooledConnection::new(self, connection));
+            }
+            if state.open < self.max_connections {
+                state.open += 1;
+                drop(state);
+                return match self.config.connect() {
+                    Ok(connection) => Ok(PooledConnection::new(self, connection)),
+                    Err(err) => {
+                        self.state.lock().unwrap().open -= 1;
+                        self.available.notify_one();
+                        Err(StorageError::Other(err.to_string()))
+                    }
+                };
+            }
+            state = self.available.wait(state).unwrap();
+        }
+    }
+}
+
+/// A connection checked out from a [`ConnectionPool`], returned to the pool on drop.
+pub(crate) struct PooledConnection<'a> {
+    pool: &'a ConnectionPool,
+    connection: Option<Connection>,
+    broken: bool,
+}
+
+impl<'a> PooledConnection<'a> {
+    fn new(pool: &'a ConnectionPool, connection: Connection) -> Self {
+        Self {
+            pool,
+            connection: Some(connection),
+            broken: false,
+        }
+    }
+
+    /// Close the connection on drop rather than returning it to the pool.
+    pub(crate) fn mark_broken(&mut self) {
+        self.broken = true;
+    }
+}
+
+impl Deref for PooledConnection<'_> {
+    type Target = Sftp;
+
+    fn deref(&self) -> &Sftp {
+        &self
+            .connection
+            .as_ref()
+            .expect("connection is set until drop")
+            .sftp
+    }
+}
+
+impl Drop for PooledConnection<'_> {
+    fn drop(&mut self) {
+        let connection = self.connection.take();
+        let mut state = self.pool.state.lock().unwrap();
+        match connection {
+            Some(connection) if !self.broken => state.idle.push(connection),
+            _ => state.open -= 1,
+        }
+        drop(state);
+        self.pool.available.notify_one();
+    }
+}
@@ -16,8 +16,10 @@ pub mod storage_adapter;
 mod storage_handle;
 mod storage_sync;
 mod storage_value_io;
+mod storage_value_stream;
 pub mod store;
 mod store_key;
+pub mod store_lock;
 mod store_prefix;
 
 pub mod byte_range;
@@ -55,6 +57,11 @@ pub use self::storage_handle::StorageHandle;
 
 pub use storage_value_io::StorageValueIO;
 
+pub use storage_value_stream::{StoreValueReader, StoreValueWriter};
+
+#[cfg(feature = "async")]
+pub use storage_value_stream::AsyncStoreValueStream;
+
 /// [`Arc`] wrapped readable storage.
 pub type ReadableStorage = Arc<dyn ReadableStorageTraits>;
 
@@ -118,6 +125,31 @@ pub type AsyncBytes = bytes::Bytes;
 /// When a value is read from a store, it returns `MaybeAsyncBytes` which is [`None`] if the key is not available.
 pub type MaybeAsyncBytes = Option<AsyncBytes>;
 
+/// A validator of a stored value which changes when the value changes, such as a HTTP entity tag.
+///
+/// See [`ReadableStorageTraits::get_if_modified`], [`ReadableStorageTraits::get_with_etag`], and [`WritableStorageTraits::set_if_match`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StoreValueValidator {
+    /// An opaque entity tag (e.g. a HTTP `ETag`).
+    EntityTag(String),
+    /// A last modification date (e.g. a HTTP `Last-Modified` date).
+    LastModified(String),
+    /// An opaque object generation or version (e.g. a Google Cloud Storage object generation).
+    Generation(String),
+}
+
+/// The result of a conditional retrieval of a value.
+///
+/// See [`ReadableStorageTraits::get_if_modified`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MaybeModifiedBytes {
+    /// The value has not been modified since it was validated.
+    NotModified,
+    /// The value, which is [`None`] if the key is not found, and its validator if supported by the store.
+    Modified(MaybeBytes, Option<StoreValueValidator>),
+}
+
 /// A [`StoreKey`] and [`ByteRange`].
 #[derive(Debug, Clone)]
 pub struct StoreKeyRange {
@@ -245,6 +277,9 @@ pub enum StorageError {
     /// Unknown key size where the key size must be known.
     #[error("{0}")]
     UnknownKeySize(StoreKey),
+    /// A conditional write failed because the value was modified by another writer.
+    #[error("the value of {0} was modified by another writer")]
+    PreconditionFailed(StoreKey),
     /// Any other error.
     #[error("{0}")]
     Other(String),
@@ -5,5 +5,15 @@
 #[cfg(feature = "async")]
 pub mod async_to_sync;
 
+pub mod cache;
+pub mod compression;
+#[cfg(feature = "dedup")]
+pub mod dedup;
+pub mod locking;
+pub mod mirror;
 pub mod performance_metrics;
+pub mod prefix;
+#[cfg(feature = "tracing")]
+pub mod tracing;
 pub mod usage_log;
+pub mod versioning;
@@ -6,8 +6,9 @@
 
 use crate::{
     byte_range::ByteRange, AsyncListableStorageTraits, AsyncReadableStorageTraits,
-    AsyncWritableStorageTraits, Bytes, ListableStorageTraits, ReadableStorageTraits, StorageError,
-    StoreKey, StoreKeys, StoreKeysPrefixes, StorePrefix, WritableStorageTraits,
+    AsyncWritableStorageTraits, Bytes, ListableStorageTraits, MaybeBytes, ReadableStorageTraits,
+    StorageError, StoreKey, StoreKeys, StoreKeysPrefixes, StorePrefix, StoreValueValidator,
+    WritableStorageTraits,
 };
 
 use std::sync::Arc;
@@ -56,6 +57,17 @@ impl<TStorage: ?Sized, TBlockOn: AsyncToSyncBlockOn> AsyncToSyncStorageAdapter<T
 impl<TStorage: ?Sized + AsyncReadableStorageTraits, TBlockOn: AsyncToSyncBlockOn>
     ReadableStorageTraits for AsyncToSyncStorageAdapter<TStorage, TBlockOn>
 {
+    fn get_many(&self, keys: &[StoreKey]) -> Result<Vec<MaybeBytes>, StorageError> {
+        self.block_on(self.storage.get_many(keys))
+    }
+
+    fn get_with_etag(
+        &self,
+        key: &StoreKey,
+    ) -> Result<(MaybeBytes, Option<StoreValueValidator>), StorageError> {
+        self.block_on(self.storage.get_with_etag(key))
+    }
+
     fn get_partial_values_key(
         &self,
         key: &StoreKey,
@@ -96,6 +108,19 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits, TBlockOn: AsyncToSyncBlockOn
         self.block_on(self.storage.set(key, value))
     }
 
+    fn supports_set_if_match(&self) -> bool {
+        self.storage.supports_set_if_match()
+    }
+
+    fn set_if_match(
+        &self,
+        key: &StoreKey,
+        value: Bytes,
+        etag: Option<&StoreValueValidator>,
+    ) -> Result<Option<StoreValueValidator>, StorageError> {
+        self.block_on(self.storage.set_if_match(key, value, etag))
+    }
+
     fn set_partial_values(
         &self,
         key_offset_values: &[crate::StoreKeyOffsetValue],
@@ -0,0 +1,791 @@
+//! A storage adapter which caches reads in memory.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    byte_range::{extract_byte_ranges, ByteRange},
+    Bytes, ListableStorageTraits, MaybeBytes, MaybeModifiedBytes, ReadableStorageTraits,
+    StorageError, StoreKey, StoreKeyOffsetValue, StoreKeys, StoreKeysPrefixes, StorePrefix,
+    StoreValueValidator, WritableStorageTraits,
+};
+
+#[cfg(feature = "async")]
+use crate::{
+    AsyncBytes, AsyncListableStorageTraits, AsyncReadableStorageTraits, AsyncWritableStorageTraits,
+    MaybeAsyncBytes,
+};
+
+/// The key of a cached value: an entire value if the byte range is [`None`], otherwise a byte range of a value.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct CacheKey {
+    key: StoreKey,
+    byte_range: Option<ByteRange>,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    value: Bytes,
+    /// The validator of an entire value, used to revalidate the value once it has expired.
+    validator: Option<StoreValueValidator>,
+    last_used: u64,
+    inserted: Instant,
+}
+
+/// The result of looking up a cached entire value.
+enum Cached {
+    Hit(Bytes),
+    /// An expired value which can be revalidated.
+    Expired(Bytes, StoreValueValidator),
+    Miss,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    entries: BTreeMap<CacheKey, CacheEntry>,
+    /// Cache keys ordered by their last use.
+    recency: BTreeMap<u64, CacheKey>,
+    /// The last use counter.
+    counter: u64,
+    /// The total size of all cached values in bytes.
+    size: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl CacheState {
+    fn remove(&mut self, cache_key: &CacheKey) {
+        if let Some(entry) = self.entries.remove(cache_key) {
+            self.recency.remove(&entry.last_used);
+            self.size -= entry.value.len() as u64;
+        }
+    }
+
+    /// Remove all cached values of `key`.
+    fn invalidate(&mut self, key: &StoreKey) {
+        let cache_keys: Vec<CacheKey> = self
+            .entries
+            .range(
+                CacheKey {
+                    key: key.clone(),
+                    byte_range: None,
+                }..,
+            )
+            .take_while(|(cache_key, _)| &cache_key.key == key)
+            .map(|(cache_key, _)| cache_key.clone())
+            .collect();
+        for cache_key in cache_keys {
+            self.remove(&cache_key);
+        }
+    }
+
+    /// Remove all cached values of keys with `prefix`.
+    fn invalidate_prefix(&mut self, prefix: &StorePrefix) {
+        let cache_keys: Vec<CacheKey> = self
+            .entries
+            .keys()
+            .filter(|cache_key| cache_key.key.has_prefix(prefix))
+            .cloned()
+            .collect();
+        for cache_key in cache_keys {
+            self.remove(&cache_key);
+        }
+    }
+}
+
+/// The cache storage adapter. Caches values and partial values read from a store in memory.
+///
+/// The cache holds least recently used (LRU) values, limited by a maximum number of bytes and/or entries, and values can expire after a time to live (TTL).
+/// Entire values retrieved with [`get`](ReadableStorageTraits::get) and the byte ranges retrieved with [`get_partial_values_key`](ReadableStorageTraits::get_partial_values_key) are cached separately.
+/// Partial reads are served from a cached entire value if available.
+/// Missing keys are not cached.
+///
+/// Expired entire values are revalidated with [`get_if_modified`](ReadableStorageTraits::get_if_modified) if the store returned a validator (e.g. the `ETag` of a HTTP store).
+/// If unmodified, the cached value is returned and its time to live restarts without retrieving the value again.
+/// This makes repeatedly polling metadata cheap.
+///
+/// Writes and erases through the adapter invalidate the cached values of affected keys.
+/// Writes to the underlying store that bypass the adapter are not visible until cached values are evicted or expire.
+///
+/// This is intended to avoid repeatedly retrieving hot chunks from slow stores (e.g. HTTP or S3).
+///
+/// ```rust
+/// # use std::{sync::Arc, time::Duration};
+/// # use zarrs_storage::{ReadableStorageTraits, WritableStorageTraits, StoreKey};
+/// # use zarrs_storage::store::MemoryStore;
+/// use zarrs_storage::storage_adapter::cache::CacheStorageAdapter;
+/// let store = Arc::new(MemoryStore::new());
+/// let store = CacheStorageAdapter::new(store)
+///     .with_max_bytes(64 * 1024 * 1024)
+///     .with_ttl(Duration::from_secs(60));
+/// let key = StoreKey::new("a/b")?;
+/// store.set(&key, vec![0, 1, 2].into())?;
+/// store.get(&key)?;
+/// store.get(&key)?;
+/// assert_eq!((store.hits(), store.misses()), (1, 1));
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct CacheStorageAdapter<TStorage: ?Sized> {
+    storage: Arc<TStorage>,
+    max_bytes: Option<u64>,
+    max_entries: Option<usize>,
+    ttl: Option<Duration>,
+    state: Mutex<CacheState>,
+}
+
+impl<TStorage: ?Sized> core::fmt::Debug for CacheStorageAdapter<TStorage> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "cache (max bytes: {:?}, max entries: {:?}, ttl: {:?})",
+            self.max_bytes, self.max_entries, self.ttl
+        )
+    }
+}
+
+impl<TStorage: ?Sized> CacheStorageAdapter<TStorage> {
+    /// Create a new cache storage adapter.
+    ///
+    /// The cache is unbounded and values do not expire by default.
+    #[must_use]
+    pub fn new(storage: Arc<TStorage>) -> Self {
+        Self {
+            storage,
+            max_bytes: None,
+            max_entries: None,
+            ttl: None,
+            state: Mutex::default(),
+        }
+    }
+
+    /// Set the maximum total size of cached values in bytes.
+    #[must_use]
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Set the maximum number of cached values.
+    #[must_use]
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = Some(max_entries);
+        self
+    }
+
+    /// Set the time to live of cached values.
+    #[must_use]
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Returns the number of reads served from the cache.
+    ///
+    /// # Panics
+    /// Panics if the internal lock is poisoned.
+    #[must_use]
+    pub fn hits(&self) -> u64 {
+        self.state.lock().unwrap().hits
+    }
+
+    /// Returns the number of reads not served from the cache.
+    ///
+    /// # Panics
+    /// Panics if the internal lock is poisoned.
+    #[must_use]
+    pub fn misses(&self) -> u64 {
+        self.state.lock().unwrap().misses
+    }
+
+    /// Returns the total size of cached values in bytes.
+    ///
+    /// # Panics
+    /// Panics if the internal lock is poisoned.
+    #[must_use]
+    pub fn cached_bytes(&self) -> u64 {
+        self.state.lock().unwrap().size
+    }
+
+    /// Returns the number of cached values.
+    ///
+    /// # Panics
+    /// Panics if the internal lock is poisoned.
+    #[must_use]
+    pub fn cached_entries(&self) -> usize {
+        self.state.lock().unwrap().entries.len()
+    }
+
+    /// Remove all cached values.
+    ///
+    /// # Panics
+    /// Panics if the internal lock is poisoned.
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.entries.clear();
+        state.recency.clear();
+        state.size = 0;
+    }
+
+    /// Return a cached value, marking it as the most recently used.
+    fn lookup(state: &mut CacheState, cache_key: &CacheKey, ttl: Option<Duration>) -> MaybeBytes {
+        let entry = state.entries.get_mut(cache_key)?;
+        if ttl.is_some_and(|ttl| entry.inserted.elapsed() > ttl) {
+            state.remove(cache_key);
+            return None;
+        }
+        state.recency.remove(&entry.last_used);
+        state.counter += 1;
+        entry.last_used = state.counter;
+        let value = entry.value.clone();
+        state.recency.insert(state.counter, cache_key.clone());
+        Some(value)
+    }
+
+    /// Cache a value, evicting the least recently used values to fit within the cache limits.
+    fn insert(
+        &self,
+        state: &mut CacheState,
+        cache_key: CacheKey,
+        value: Bytes,
+        validator: Option<StoreValueValidator>,
+    ) {
+        let size = value.len() as u64;
+        if self.max_bytes.is_some_and(|max_bytes| size > max_bytes) || self.max_entries == Some(0) {
+            return;
+        }
+        state.remove(&cache_key);
+        while self
+            .max_bytes
+            .is_some_and(|max_bytes| state.size + size > max_bytes)
+            || self
+                .max_entries
+                .is_some_and(|max_entries| state.entries.len() >= max_entries)
+        {
+            let Some((_, lru)) = state.recency.pop_first() else {
+                break;
+            };
+            state.remove(&lru);
+        }
+        state.counter += 1;
+        state.recency.insert(state.counter, cache_key.clone());
+        state.size += size;
+        state.entries.insert(
+            cache_key,
+            CacheEntry {
+                value,
+                validator,
+                last_used: state.counter,
+                inserted: Instant::now(),
+            },
+        );
+    }
+
+    fn get_cached(&self, key: &StoreKey) -> Cached {
+        let mut state = self.state.lock().unwrap();
+        let cache_key = CacheKey {
+            key: key.clone(),
+            byte_range: None,
+        };
+        if let Some(entry) = state.entries.get(&cache_key) {
+            if self.ttl.is_some_and(|ttl| entry.inserted.elapsed() > ttl) {
+                if let Some(validator) = entry.validator.clone() {
+                    let value = entry.value.clone();
+                    state.remove(&cache_key);
+                    return Cached::Expired(value, validator);
+                }
+            }
+        }
+        if let Some(value) = Self::lookup(&mut state, &cache_key, self.ttl) {
+            state.hits += 1;
+            Cached::Hit(value)
+        } else {
+            state.misses += 1;
+            Cached::Miss
+        }
+    }
+
+    /// Cache the `result` of a conditional retrieval of a value, which revalidates an `expired` cached value if [`Some`].
+    fn set_cached(
+        &self,
+        key: &StoreKey,
+        expired: Option<(Bytes, StoreValueValidator)>,
+        result: MaybeModifiedBytes,
+    ) -> Result<MaybeBytes, StorageError> {
+        let mut state = self.state.lock().unwrap();
+        let cache_key = CacheKey {
+            key: key.clone(),
+            byte_range: None,
+        };
+        match (result, expired) {
+            (MaybeModifiedBytes::NotModified, Some((value, validator))) => {
+                state.hits += 1;
+                self.insert(&mut state, cache_key, value.clone(), Some(validator));
+                Ok(Some(value))
+            }
+            (MaybeModifiedBytes::NotModified, None) => Err(StorageError::from(
+                "the store returned an unmodified value without a validator",
+            )),
+            (MaybeModifiedBytes::Modified(value, validator), expired) => {
+                if expired.is_some() {
+                    state.misses += 1;
+                }
+                if let Some(value) = &value {
+                    self.insert(&mut state, cache_key, value.clone(), validator);
+                }
+                Ok(value)
+            }
+        }
+    }
+
+    /// Return the cached byte ranges of `key`, or [`None`] for byte ranges that are not cached.
+    fn get_partial_cached(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Vec<MaybeBytes>, StorageError> {
+        let mut state = self.state.lock().unwrap();
+        let cache_key = CacheKey {
+            key: key.clone(),
+            byte_range: None,
+        };
+        if let Some(value) = Self::lookup(&mut state, &cache_key, self.ttl) {
+            let values = extract_byte_ranges(&value, byte_ranges)?;
+            state.hits += byte_ranges.len() as u64;
+            return Ok(values.into_iter().map(|value| Some(value.into())).collect());
+        }
+        let values: Vec<MaybeBytes> = byte_ranges
+            .iter()
+            .map(|byte_range| {
+                let cache_key = CacheKey {
+                    key: key.clone(),
+                    byte_range: Some(*byte_range),
+                };
+                Self::lookup(&mut state, &cache_key, self.ttl)
+            })
+            .collect();
+        let hits = values.iter().filter(|value| value.is_some()).count() as u64;
+        state.hits += hits;
+        state.misses += byte_ranges.len() as u64 - hits;
+        Ok(values)
+    }
+
+    /// Fill in and cache the byte ranges of `key` that were not cached.
+    fn set_partial_cached(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+        cached: Vec<MaybeBytes>,
+        retrieved: Option<Vec<Bytes>>,
+    ) -> Option<Vec<Bytes>> {
+        let mut retrieved = retrieved?.into_iter();
+        let mut state = self.state.lock().unwrap();
+        byte_ranges
+            .iter()
+            .zip(cached)
+            .map(|(byte_range, value)| {
+                if value.is_some() {
+                    value
+                } else {
+                    let value = retrieved.next()?;
+                    let cache_key = CacheKey {
+                        key: key.clone(),
+                        byte_range: Some(*byte_range),
+                    };
+                    self.insert(&mut state, cache_key, value.clone(), None);
+                    Some(value)
+                }
+            })
+            .collect()
+    }
+
+    fn invalidate(&self, key: &StoreKey) {
+        self.state.lock().unwrap().invalidate(key);
+    }
+
+    fn invalidate_prefix(&self, prefix: &StorePrefix) {
+        self.state.lock().unwrap().invalidate_prefix(prefix);
+    }
+}
+
+/// Return the byte ranges that are not cached.
+fn uncached_byte_ranges(byte_ranges: &[ByteRange], cached: &[MaybeBytes]) -> Vec<ByteRange> {
+    byte_ranges
+        .iter()
+        .zip(cached)
+        .filter(|(_, value)| value.is_none())
+        .map(|(byte_range, _)| *byte_range)
+        .collect()
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
+    for CacheStorageAdapter<TStorage>
+{
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        let expired = match self.get_cached(key) {
+            Cached::Hit(value) => return Ok(Some(value)),
+            Cached::Expired(value, validator) => Some((value, validator)),
+            Cached::Miss => None,
+        };
+        let validator = expired.as_ref().map(|(_, validator)| validator);
+        let result = self.storage.get_if_modified(key, validator)?;
+        self.set_cached(key, expired, result)
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        let cached = self.get_partial_cached(key, byte_ranges)?;
+        let uncached = uncached_byte_ranges(byte_ranges, &cached);
+        let retrieved = if uncached.is_empty() {
+            Some(vec![])
+        } else {
+            self.storage.get_partial_values_key(key, &uncached)?
+        };
+        Ok(self.set_partial_cached(key, byte_ranges, cached, retrieved))
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        let cache_key = CacheKey {
+            key: key.clone(),
+            byte_range: None,
+        };
+        if let Some(value) = Self::lookup(&mut self.state.lock().unwrap(), &cache_key, self.ttl) {
+            return Ok(Some(value.len() as u64));
+        }
+        self.storage.size_key(key)
+    }
+}
+
+impl<TStorage: ?Sized + ListableStorageTraits> ListableStorageTraits
+    for CacheStorageAdapter<TStorage>
+{
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.storage.list()
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.storage.list_prefix(prefix)
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.storage.list_dir(prefix)
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.storage.size_prefix(prefix)
+    }
+
+    fn size(&self) -> Result<u64, StorageError> {
+        self.storage.size()
+    }
+}
+
+impl<TStorage: ?Sized + WritableStorageTraits> WritableStorageTraits
+    for CacheStorageAdapter<TStorage>
+{
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        self.invalidate(key);
+        self.storage.set(key, value)
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        for key_offset_value in key_offset_values {
+            self.invalidate(key_offset_value.key());
+        }
+        self.storage.set_partial_values(key_offset_values)
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.invalidate(key);
+        self.storage.erase(key)
+    }
+
+    fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        for key in keys {
+            self.invalidate(key);
+        }
+        self.storage.erase_values(keys)
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.invalidate_prefix(prefix);
+        self.storage.erase_prefix(prefix)
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncReadableStorageTraits> AsyncReadableStorageTraits
+    for CacheStorageAdapter<TStorage>
+{
+    async fn get(&self, key: &StoreKey) -> Result<MaybeAsyncBytes, StorageError> {
+        let expired = match self.get_cached(key) {
+            Cached::Hit(value) => return Ok(Some(value)),
+            Cached::Expired(value, validator) => Some((value, validator)),
+            Cached::Miss => None,
+        };
+        let validator = expired.as_ref().map(|(_, validator)| validator);
+        let result = self.storage.get_if_modified(key, validator).await?;
+        self.set_cached(key, expired, result)
+    }
+
+    async fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<AsyncBytes>>, StorageError> {
+        let cached = self.get_partial_cached(key, byte_ranges)?;
+        let uncached = uncached_byte_ranges(byte_ranges, &cached);
+        let retrieved = if uncached.is_empty() {
+            Some(vec![])
+        } else {
+            self.storage.get_partial_values_key(key, &uncached).await?
+        };
+        Ok(self.set_partial_cached(key, byte_ranges, cached, retrieved))
+    }
+
+    async fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        let cache_key = CacheKey {
+            key: key.clone(),
+            byte_range: None,
+        };
+        let value = Self::lookup(&mut self.state.lock().unwrap(), &cache_key, self.ttl);
+        if let Some(value) = value {
+            return Ok(Some(value.len() as u64));
+        }
+        self.storage.size_key(key).await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncListableStorageTraits> AsyncListableStorageTraits
+    for CacheStorageAdapter<TStorage>
+{
+    async fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.storage.list().await
+    }
+
+    async fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.storage.list_prefix(prefix).await
+    }
+
+    async fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.storage.list_dir(prefix).await
+    }
+
+    async fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.storage.size_prefix(prefix).await
+    }
+
+    async fn size(&self) -> Result<u64, StorageError> {
+        self.storage.size().await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncWritableStorageTraits> AsyncWritableStorageTraits
+    for CacheStorageAdapter<TStorage>
+{
+    async fn set(&self, key: &StoreKey, value: AsyncBytes) -> Result<(), StorageError> {
+        self.invalidate(key);
+        self.storage.set(key, value).await
+    }
+
+    async fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        for key_offset_value in key_offset_values {
+            self.invalidate(key_offset_value.key());
+        }
+        self.storage.set_partial_values(key_offset_values).await
+    }
+
+    async fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.invalidate(key);
+        self.storage.erase(key).await
+    }
+
+    async fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        for key in keys {
+            self.invalidate(key);
+        }
+        self.storage.erase_values(keys).await
+    }
+
+    async fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.invalidate_prefix(prefix);
+        self.storage.erase_prefix(prefix).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        storage_adapter::performance_metrics::PerformanceMetricsStorageAdapter, store::MemoryStore,
+    };
+    use std::error::Error;
+
+    #[test]
+    fn cache() -> Result<(), Box<dyn Error>> {
+        let store = Arc::new(MemoryStore::new());
+        let cache = CacheStorageAdapter::new(store);
+        crate::store_test::store_write(&cache)?;
+        crate::store_test::store_read(&cache)?;
+        crate::store_test::store_list(&cache)?;
+        assert!(cache.hits() > 0);
+        Ok(())
+    }
+
+    #[test]
+    fn cache_reads() -> Result<(), Box<dyn Error>> {
+        let store = Arc::new(PerformanceMetricsStorageAdapter::new(Arc::new(
+            MemoryStore::new(),
+        )));
+        let cache = CacheStorageAdapter::new(store.clone());
+        let a = StoreKey::new("a")?;
+        cache.set(&a, vec![0, 1, 2, 3].into())?;
+
+        // Partial reads are cached per byte range
+        let byte_ranges = [ByteRange::FromStart(0, Some(2)), ByteRange::Suffix(1)];
+        let expected = Some(vec![vec![0, 1].into(), vec![3].into()]);
+        assert_eq!(cache.get_partial_values_key(&a, &byte_ranges)?, expected);
+        assert_eq!(cache.get_partial_values_key(&a, &byte_ranges)?, expected);
+        assert_eq!(store.reads(), 2);
+        assert_eq!((cache.hits(), cache.misses()), (2, 2));
+
+        // Partial reads are served from a cached entire value
+        assert_eq!(cache.get(&a)?.unwrap(), vec![0, 1, 2, 3]);
+        assert_eq!(
+            cache.get_partial_values_key(&a, &[ByteRange::FromStart(1, Some(2))])?,
+            Some(vec![vec![1, 2].into()])
+        );
+        assert_eq!(store.reads(), 3);
+
+        // Writes invalidate cached values
+        cache.set(&a, vec![4].into())?;
+        assert_eq!(cache.cached_entries(), 0);
+        assert_eq!(cache.get(&a)?.unwrap(), vec![4]);
+
+        // Missing keys are not cached
+        let b = StoreKey::new("b")?;
+        assert!(cache.get(&b)?.is_none());
+        assert!(cache.get_partial_values_key(&b, &byte_ranges)?.is_none());
+        assert_eq!(cache.cached_entries(), 1);
+        Ok(())
+    }
+
+    /// A store which supports conditional retrieval, and counts retrievals of unmodified values.
+    struct ValidatedStore {
+        store: MemoryStore,
+        not_modified: std::sync::atomic::AtomicUsize,
+    }
+
+    impl ReadableStorageTraits for ValidatedStore {
+        fn get_if_modified(
+            &self,
+            key: &StoreKey,
+            validator: Option<&StoreValueValidator>,
+        ) -> Result<MaybeModifiedBytes, StorageError> {
+            let value = self.store.get(key)?;
+            let current = value
+                .as_ref()
+                .map(|value| StoreValueValidator::EntityTag(format!("{value:?}")));
+            if validator.is_some() && validator == current.as_ref() {
+                self.not_modified
+                    .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                Ok(MaybeModifiedBytes::NotModified)
+            } else {
+                Ok(MaybeModifiedBytes::Modified(value, current))
+            }
+        }
+
+        fn get_partial_values_key(
+            &self,
+            key: &StoreKey,
+            byte_ranges: &[ByteRange],
+        ) -> Result<Option<Vec<Bytes>>, StorageError> {
+            self.store.get_partial_values_key(key, byte_ranges)
+        }
+
+        fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+            self.store.size_key(key)
+        }
+    }
+
+    #[test]
+    fn cache_revalidation() -> Result<(), Box<dyn Error>> {
+        let store = Arc::new(ValidatedStore {
+            store: MemoryStore::new(),
+            not_modified: 0.into(),
+        });
+        let key = StoreKey::new("zarr.json")?;
+        store.store.set(&key, vec![0].into())?;
+        let cache = CacheStorageAdapter::new(store.clone()).with_ttl(Duration::ZERO);
+        assert_eq!(cache.get(&key)?.unwrap(), vec![0]);
+
+        // Expired values are revalidated
+        std::thread::sleep(Duration::from_millis(1));
+        assert_eq!(cache.get(&key)?.unwrap(), vec![0]);
+        assert_eq!(
+            store
+                .not_modified
+                .load(std::sync::atomic::Ordering::Relaxed),
+            1
+        );
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+
+        // Modified values are retrieved
+        store.store.set(&key, vec![1].into())?;
+        std::thread::sleep(Duration::from_millis(1));
+        assert_eq!(cache.get(&key)?.unwrap(), vec![1]);
+        assert_eq!((cache.hits(), cache.misses()), (1, 2));
+        assert_eq!(cache.cached_entries(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn cache_eviction() -> Result<(), Box<dyn Error>> {
+        let store = Arc::new(MemoryStore::new());
+        let keys = ["a", "b", "c"]
+            .into_iter()
+            .map(StoreKey::new)
+            .collect::<Result<Vec<_>, _>>()?;
+        for key in &keys {
+            store.set(key, vec![0; 4].into())?;
+        }
+
+        let cache = CacheStorageAdapter::new(store.clone()).with_max_bytes(8);
+        cache.get(&keys[0])?;
+        cache.get(&keys[1])?;
+        cache.get(&keys[0])?;
+        cache.get(&keys[2])?;
+        assert_eq!(cache.cached_bytes(), 8);
+        assert_eq!((cache.hits(), cache.misses()), (1, 3));
+        cache.get(&keys[0])?;
+        assert_eq!(cache.hits(), 2);
+
+        let cache = CacheStorageAdapter::new(store.clone()).with_max_entries(1);
+        cache.get(&keys[0])?;
+        cache.get(&keys[1])?;
+        assert_eq!(cache.cached_entries(), 1);
+
+        let cache = CacheStorageAdapter::new(store).with_ttl(Duration::ZERO);
+        cache.get(&keys[0])?;
+        std::thread::sleep(Duration::from_millis(1));
+        cache.get(&keys[0])?;
+        assert_eq!(cache.hits(), 0);
+        Ok(())
+    }
+}
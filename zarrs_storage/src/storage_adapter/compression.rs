@@ -0,0 +1,496 @@
+//! A storage adapter which compresses store values.
+
+use std::sync::Arc;
+
+use crate::{
+    byte_range::{extract_byte_ranges, ByteRange},
+    store_set_partial_values, Bytes, ListableStorageTraits, MaybeBytes, ReadableStorageTraits,
+    StorageError, StoreKey, StoreKeyOffsetValue, StoreKeys, StoreKeysPrefixes, StorePrefix,
+    StoreValueReader, StoreValueWriter, WritableStorageTraits,
+};
+
+#[cfg(feature = "async")]
+use crate::{
+    async_store_set_partial_values, AsyncBytes, AsyncListableStorageTraits,
+    AsyncReadableStorageTraits, AsyncWritableStorageTraits, MaybeAsyncBytes,
+};
+
+/// A compressor of entire store values.
+pub trait ValueCompressor: Send + Sync {
+    /// Compress a store value.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if compression fails.
+    fn compress(&self, value: &[u8]) -> Result<Vec<u8>, StorageError>;
+
+    /// Decompress a store value.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if decompression fails.
+    fn decompress(&self, value: &[u8]) -> Result<Vec<u8>, StorageError>;
+
+    /// Return a reader of the decompressed value of a compressed `value` reader.
+    ///
+    /// The default implementation reads and decompresses the entire value with [`decompress`](ValueCompressor::decompress).
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if decompression fails.
+    fn decompress_reader<'a>(
+        &self,
+        mut value: StoreValueReader<'a>,
+    ) -> Result<StoreValueReader<'a>, StorageError> {
+        let mut compressed = Vec::new();
+        value.read_to_end(&mut compressed)?;
+        Ok(Box::new(std::io::Cursor::new(
+            self.decompress(&compressed)?,
+        )))
+    }
+
+    /// Return a writer that compresses a value into a `writer` of the compressed value.
+    ///
+    /// Finishing the returned writer finishes `writer`.
+    /// The default implementation buffers the entire value and compresses it with [`compress`](ValueCompressor::compress).
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if compression fails.
+    fn compress_writer<'a>(
+        &'a self,
+        writer: Box<dyn StoreValueWriter + 'a>,
+    ) -> Result<Box<dyn StoreValueWriter + 'a>, StorageError> {
+        Ok(Box::new(BufferedCompressWriter {
+            compressor: self,
+            writer,
+            buffer: Vec::new(),
+        }))
+    }
+}
+
+/// A [`StoreValueWriter`] that buffers a value and compresses it with [`ValueCompressor::compress`] when finished.
+struct BufferedCompressWriter<'a, TCompressor: ?Sized> {
+    compressor: &'a TCompressor,
+    writer: Box<dyn StoreValueWriter + 'a>,
+    buffer: Vec<u8>,
+}
+
+impl<TCompressor: ?Sized> std::io::Write for BufferedCompressWriter<'_, TCompressor> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<TCompressor: ?Sized + ValueCompressor> StoreValueWriter
+    for BufferedCompressWriter<'_, TCompressor>
+{
+    fn finish(mut self: Box<Self>) -> Result<(), StorageError> {
+        let compressed = self.compressor.compress(&self.buffer)?;
+        self.writer.write_all(&compressed)?;
+        self.writer.finish()
+    }
+}
+
+/// A [Zstandard](https://facebook.github.io/zstd/) [`ValueCompressor`].
+#[cfg(feature = "zstd")]
+#[derive(Debug, Clone, Copy)]
+pub struct ZstdValueCompressor {
+    level: i32,
+}
+
+#[cfg(feature = "zstd")]
+impl ZstdValueCompressor {
+    /// Create a new Zstandard value compressor with a compression `level`.
+    #[must_use]
+    pub const fn new(level: i32) -> Self {
+        Self { level }
+    }
+}
+
+#[cfg(feature = "zstd")]
+impl Default for ZstdValueCompressor {
+    fn default() -> Self {
+        Self::new(zstd::DEFAULT_COMPRESSION_LEVEL)
+    }
+}
+
+#[cfg(feature = "zstd")]
+impl ValueCompressor for ZstdValueCompressor {
+    fn compress(&self, value: &[u8]) -> Result<Vec<u8>, StorageError> {
+        Ok(zstd::encode_all(value, self.level)?)
+    }
+
+    fn decompress(&self, value: &[u8]) -> Result<Vec<u8>, StorageError> {
+        Ok(zstd::decode_all(value)?)
+    }
+
+    fn decompress_reader<'a>(
+        &self,
+        value: StoreValueReader<'a>,
+    ) -> Result<StoreValueReader<'a>, StorageError> {
+        Ok(Box::new(zstd::stream::read::Decoder::new(value)?))
+    }
+
+    fn compress_writer<'a>(
+        &'a self,
+        writer: Box<dyn StoreValueWriter + 'a>,
+    ) -> Result<Box<dyn StoreValueWriter + 'a>, StorageError> {
+        Ok(Box::new(ZstdCompressWriter(
+            zstd::stream::write::Encoder::new(writer, self.level)?,
+        )))
+    }
+}
+
+/// A [`StoreValueWriter`] that streams a value through a Zstandard encoder.
+#[cfg(feature = "zstd")]
+struct ZstdCompressWriter<'a>(
+    zstd::stream::write::Encoder<'static, Box<dyn StoreValueWriter + 'a>>,
+);
+
+#[cfg(feature = "zstd")]
+impl std::io::Write for ZstdCompressWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
+
+#[cfg(feature = "zstd")]
+impl StoreValueWriter for ZstdCompressWriter<'_> {
+    fn finish(self: Box<Self>) -> Result<(), StorageError> {
+        self.0.finish()?.finish()
+    }
+}
+
+/// The compression storage adapter. Compresses entire store values, independent of the codecs of an array.
+///
+/// This is useful for compressing metadata documents and the chunks of uncompressed arrays.
+///
+/// Values are compressed on write and decompressed on read with a [`ValueCompressor`].
+/// Partial reads and writes retrieve and decompress the entire value.
+/// Sizes (e.g. [`size_key`](ReadableStorageTraits::size_key) and [`size`](ListableStorageTraits::size)) are the compressed sizes in the underlying store.
+///
+/// ```rust
+/// # #[cfg(feature = "zstd")] {
+/// # use std::sync::Arc;
+/// # use zarrs_storage::{ReadableStorageTraits, WritableStorageTraits, StoreKey};
+/// # use zarrs_storage::store::MemoryStore;
+/// use zarrs_storage::storage_adapter::compression::{CompressionStorageAdapter, ZstdValueCompressor};
+/// let store = Arc::new(MemoryStore::new());
+/// let store = CompressionStorageAdapter::new(store, ZstdValueCompressor::default());
+/// let key = StoreKey::new("zarr.json")?;
+/// store.set(&key, vec![0; 1024].into())?;
+/// assert_eq!(store.get(&key)?.unwrap(), vec![0; 1024]);
+/// assert!(store.size_key(&key)?.unwrap() < 1024);
+/// # }
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct CompressionStorageAdapter<TStorage: ?Sized, TCompressor> {
+    storage: Arc<TStorage>,
+    compressor: TCompressor,
+}
+
+impl<TStorage: ?Sized, TCompressor> core::fmt::Debug
+    for CompressionStorageAdapter<TStorage, TCompressor>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "compression")
+    }
+}
+
+impl<TStorage: ?Sized, TCompressor: ValueCompressor>
+    CompressionStorageAdapter<TStorage, TCompressor>
+{
+    /// Create a new compression storage adapter compressing the values of `storage` with `compressor`.
+    #[must_use]
+    pub fn new(storage: Arc<TStorage>, compressor: TCompressor) -> Self {
+        Self {
+            storage,
+            compressor,
+        }
+    }
+
+    /// Return the compressor.
+    #[must_use]
+    pub const fn compressor(&self) -> &TCompressor {
+        &self.compressor
+    }
+
+    fn decompress(&self, value: MaybeBytes) -> Result<MaybeBytes, StorageError> {
+        value
+            .map(|value| Ok(self.compressor.decompress(&value)?.into()))
+            .transpose()
+    }
+
+    fn decompress_byte_ranges(
+        &self,
+        value: MaybeBytes,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        let Some(value) = self.decompress(value)? else {
+            return Ok(None);
+        };
+        Ok(Some(
+            extract_byte_ranges(&value, byte_ranges)?
+                .into_iter()
+                .map(Bytes::from)
+                .collect(),
+        ))
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits, TCompressor: ValueCompressor> ReadableStorageTraits
+    for CompressionStorageAdapter<TStorage, TCompressor>
+{
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        self.decompress(self.storage.get(key)?)
+    }
+
+    fn get_many(&self, keys: &[StoreKey]) -> Result<Vec<MaybeBytes>, StorageError> {
+        self.storage
+            .get_many(keys)?
+            .into_iter()
+            .map(|bytes| self.decompress(bytes))
+            .collect()
+    }
+
+    fn get_reader(&self, key: &StoreKey) -> Result<Option<StoreValueReader<'_>>, StorageError> {
+        self.storage
+            .get_reader(key)?
+            .map(|value| self.compressor.decompress_reader(value))
+            .transpose()
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        self.decompress_byte_ranges(self.storage.get(key)?, byte_ranges)
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        self.storage.size_key(key)
+    }
+}
+
+impl<TStorage: ?Sized + ListableStorageTraits, TCompressor: ValueCompressor> ListableStorageTraits
+    for CompressionStorageAdapter<TStorage, TCompressor>
+{
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.storage.list()
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.storage.list_prefix(prefix)
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.storage.list_dir(prefix)
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.storage.size_prefix(prefix)
+    }
+
+    fn size(&self) -> Result<u64, StorageError> {
+        self.storage.size()
+    }
+}
+
+impl<
+        TStorage: ?Sized + ReadableStorageTraits + WritableStorageTraits,
+        TCompressor: ValueCompressor,
+    > WritableStorageTraits for CompressionStorageAdapter<TStorage, TCompressor>
+{
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        self.storage
+            .set(key, self.compressor.compress(&value)?.into())
+    }
+
+    fn set_writer(&self, key: &StoreKey) -> Result<Box<dyn StoreValueWriter + '_>, StorageError> {
+        self.compressor
+            .compress_writer(self.storage.set_writer(key)?)
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        store_set_partial_values(self, key_offset_values)
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.storage.erase(key)
+    }
+
+    fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        self.storage.erase_values(keys)
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.storage.erase_prefix(prefix)
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncReadableStorageTraits, TCompressor: ValueCompressor>
+    AsyncReadableStorageTraits for CompressionStorageAdapter<TStorage, TCompressor>
+{
+    async fn get(&self, key: &StoreKey) -> Result<MaybeAsyncBytes, StorageError> {
+        self.decompress(self.storage.get(key).await?)
+    }
+
+    async fn get_many(&self, keys: &[StoreKey]) -> Result<Vec<MaybeAsyncBytes>, StorageError> {
+        self.storage
+            .get_many(keys)
+            .await?
+            .into_iter()
+            .map(|bytes| self.decompress(bytes))
+            .collect()
+    }
+
+    async fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<AsyncBytes>>, StorageError> {
+        self.decompress_byte_ranges(self.storage.get(key).await?, byte_ranges)
+    }
+
+    async fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        self.storage.size_key(key).await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncListableStorageTraits, TCompressor: ValueCompressor>
+    AsyncListableStorageTraits for CompressionStorageAdapter<TStorage, TCompressor>
+{
+    async fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.storage.list().await
+    }
+
+    async fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.storage.list_prefix(prefix).await
+    }
+
+    async fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.storage.list_dir(prefix).await
+    }
+
+    async fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.storage.size_prefix(prefix).await
+    }
+
+    async fn size(&self) -> Result<u64, StorageError> {
+        self.storage.size().await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<
+        TStorage: ?Sized + AsyncReadableStorageTraits + AsyncWritableStorageTraits,
+        TCompressor: ValueCompressor,
+    > AsyncWritableStorageTraits for CompressionStorageAdapter<TStorage, TCompressor>
+{
+    async fn set(&self, key: &StoreKey, value: AsyncBytes) -> Result<(), StorageError> {
+        self.storage
+            .set(key, self.compressor.compress(&value)?.into())
+            .await
+    }
+
+    async fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        async_store_set_partial_values(self, key_offset_values).await
+    }
+
+    async fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.storage.erase(key).await
+    }
+
+    async fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        self.storage.erase_values(keys).await
+    }
+
+    async fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.storage.erase_prefix(prefix).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::MemoryStore;
+    use std::{
+        error::Error,
+        io::{Read, Write},
+    };
+
+    /// A value compressor which reverses values.
+    struct ReverseValueCompressor;
+
+    impl ValueCompressor for ReverseValueCompressor {
+        fn compress(&self, value: &[u8]) -> Result<Vec<u8>, StorageError> {
+            Ok(value.iter().rev().copied().collect())
+        }
+
+        fn decompress(&self, value: &[u8]) -> Result<Vec<u8>, StorageError> {
+            Ok(value.iter().rev().copied().collect())
+        }
+    }
+
+    #[test]
+    fn compression() -> Result<(), Box<dyn Error>> {
+        let store = Arc::new(MemoryStore::new());
+        let adapter = CompressionStorageAdapter::new(store.clone(), ReverseValueCompressor);
+        crate::store_test::store_write(&adapter)?;
+        crate::store_test::store_read(&adapter)?;
+        crate::store_test::store_list(&adapter)?;
+
+        let key = StoreKey::new("a")?;
+        adapter.set(&key, vec![0, 1, 2].into())?;
+        assert_eq!(store.get(&key)?.unwrap(), vec![2, 1, 0]);
+        Ok(())
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn compression_zstd() -> Result<(), Box<dyn Error>> {
+        let store = Arc::new(MemoryStore::new());
+        let adapter = CompressionStorageAdapter::new(store.clone(), ZstdValueCompressor::new(3));
+        let key = StoreKey::new("a")?;
+        adapter.set(&key, vec![0; 1024].into())?;
+        assert_eq!(adapter.get(&key)?.unwrap(), vec![0; 1024]);
+        let size = adapter.size_key(&key)?.unwrap();
+        assert!(size < 1024);
+        assert_eq!(size, store.get(&key)?.unwrap().len() as u64);
+
+        adapter.set_partial_values(&[StoreKeyOffsetValue::new(key.clone(), 1022, &[1, 2, 3])])?;
+        assert_eq!(
+            adapter.get_partial_values_key(&key, &[ByteRange::Suffix(4)])?,
+            Some(vec![vec![0, 1, 2, 3].into()])
+        );
+
+        let key = StoreKey::new("stream")?;
+        let mut writer = adapter.set_writer(&key)?;
+        for _ in 0..1024 {
+            writer.write_all(&[0; 1024])?;
+        }
+        writer.finish()?;
+        assert!(store.size_key(&key)?.unwrap() < 1024 * 1024);
+        let mut value = Vec::new();
+        adapter.get_reader(&key)?.unwrap().read_to_end(&mut value)?;
+        assert_eq!(value, vec![0; 1024 * 1024]);
+        Ok(())
+    }
+}
@@ -0,0 +1,535 @@
+//! A storage adapter which deduplicates store values by content.
+
+use std::{collections::HashSet, fmt::Write, sync::Arc};
+
+use sha2::{Digest, Sha256};
+
+use crate::{
+    byte_range::{extract_byte_ranges, ByteRange},
+    store_set_partial_values, Bytes, ListableStorageTraits, MaybeBytes, ReadableStorageTraits,
+    StorageError, StoreKey, StoreKeyOffsetValue, StoreKeys, StoreKeysPrefixes, StorePrefix,
+    WritableStorageTraits,
+};
+
+#[cfg(feature = "async")]
+use crate::{
+    async_store_set_partial_values, AsyncBytes, AsyncListableStorageTraits,
+    AsyncReadableStorageTraits, AsyncWritableStorageTraits, MaybeAsyncBytes,
+};
+
+/// The default prefix of the content-addressed blobs of a [`DedupStorageAdapter`].
+pub const DEFAULT_BLOB_PREFIX: &str = "__dedup/";
+
+/// The magic bytes at the start of a reference to a content-addressed blob.
+const REFERENCE_MAGIC: &[u8] = b"zarrs_dedup:sha256:";
+
+/// The length of a hex encoded SHA-256 hash.
+const HASH_HEX_LEN: usize = 64;
+
+/// The deduplicating storage adapter. Stores identical values once in the underlying store.
+///
+/// Each value is hashed with SHA-256 and written once to a content-addressed blob under the blob prefix (default [`DEFAULT_BLOB_PREFIX`]).
+/// The key of the value holds a small reference to the blob, so the underlying store is a persistent key to hash index.
+/// This can dramatically reduce the storage of arrays with many identical chunks, such as sparse or label arrays.
+///
+/// Keys under the blob prefix are hidden from listing and cannot be written.
+/// Values in the underlying store that are not references (e.g. written before the adapter was introduced) are passed through unchanged.
+///
+/// Erasing a key only erases its reference.
+/// Blobs that are no longer referenced are erased by [`garbage_collect`](DedupStorageAdapter::garbage_collect), which must not run concurrently with writes.
+///
+/// ```rust
+/// # use std::sync::Arc;
+/// # use zarrs_storage::{ListableStorageTraits, ReadableStorageTraits, WritableStorageTraits, StoreKey, StorePrefix};
+/// # use zarrs_storage::store::MemoryStore;
+/// use zarrs_storage::storage_adapter::dedup::DedupStorageAdapter;
+/// let store = Arc::new(MemoryStore::new());
+/// let dedup = DedupStorageAdapter::new(store.clone());
+/// dedup.set(&StoreKey::new("c/0")?, vec![0; 1024].into())?;
+/// dedup.set(&StoreKey::new("c/1")?, vec![0; 1024].into())?;
+/// assert_eq!(dedup.get(&StoreKey::new("c/1")?)?.unwrap(), vec![0; 1024]);
+/// assert_eq!(dedup.size()?, 2048);
+/// assert!(store.size()? < 2048);
+///
+/// dedup.erase_prefix(&StorePrefix::root())?;
+/// assert_eq!(dedup.garbage_collect()?, 1);
+/// assert_eq!(store.size()?, 0);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct DedupStorageAdapter<TStorage: ?Sized> {
+    storage: Arc<TStorage>,
+    blob_prefix: StorePrefix,
+}
+
+impl<TStorage: ?Sized> core::fmt::Debug for DedupStorageAdapter<TStorage> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "dedup ({})", self.blob_prefix)
+    }
+}
+
+impl<TStorage: ?Sized> DedupStorageAdapter<TStorage> {
+    /// Create a new deduplicating storage adapter.
+    #[must_use]
+    pub fn new(storage: Arc<TStorage>) -> Self {
+        Self {
+            storage,
+            // SAFETY: the default blob prefix is a valid prefix
+            blob_prefix: unsafe { StorePrefix::new_unchecked(DEFAULT_BLOB_PREFIX) },
+        }
+    }
+
+    /// Set the prefix of the content-addressed blobs in the underlying store.
+    ///
+    /// The root prefix is not permitted and is ignored.
+    #[must_use]
+    pub fn with_blob_prefix(mut self, blob_prefix: StorePrefix) -> Self {
+        if blob_prefix != StorePrefix::root() {
+            self.blob_prefix = blob_prefix;
+        }
+        self
+    }
+
+    /// Return the prefix of the content-addressed blobs.
+    #[must_use]
+    pub const fn blob_prefix(&self) -> &StorePrefix {
+        &self.blob_prefix
+    }
+
+    fn is_blob_key(&self, key: &StoreKey) -> bool {
+        key.has_prefix(&self.blob_prefix)
+    }
+
+    fn is_blob_prefix(&self, prefix: &StorePrefix) -> bool {
+        prefix.as_str().starts_with(self.blob_prefix.as_str())
+    }
+
+    fn check_writable(&self, key: &StoreKey) -> Result<(), StorageError> {
+        if self.is_blob_key(key) {
+            Err(StorageError::Other(format!(
+                "{key} is reserved for the blobs of the dedup storage adapter"
+            )))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn blob_key(&self, hash: &str) -> StoreKey {
+        StoreKey::new(self.blob_prefix.as_str().to_string() + hash)
+            .expect("a prefix followed by a hash is a valid key")
+    }
+
+    /// Returns the blob key and reference of `value`.
+    fn reference(&self, value: &[u8]) -> (StoreKey, Vec<u8>) {
+        let hash = Sha256::digest(value).iter().fold(
+            String::with_capacity(HASH_HEX_LEN),
+            |mut hash, byte| {
+                let _ = write!(hash, "{byte:02x}");
+                hash
+            },
+        );
+        let reference = [REFERENCE_MAGIC, hash.as_bytes()].concat();
+        (self.blob_key(&hash), reference)
+    }
+
+    /// Returns the blob key of `value` if it is a reference.
+    fn resolve(&self, value: &[u8]) -> Option<StoreKey> {
+        let hash = value.strip_prefix(REFERENCE_MAGIC)?;
+        if hash.len() == HASH_HEX_LEN && hash.iter().all(u8::is_ascii_hexdigit) {
+            Some(self.blob_key(std::str::from_utf8(hash).ok()?))
+        } else {
+            None
+        }
+    }
+
+    fn filter_keys(&self, keys: StoreKeys) -> StoreKeys {
+        keys.into_iter()
+            .filter(|key| !self.is_blob_key(key))
+            .collect()
+    }
+
+    fn filter_keys_prefixes(&self, keys_prefixes: &StoreKeysPrefixes) -> StoreKeysPrefixes {
+        StoreKeysPrefixes::new(
+            self.filter_keys(keys_prefixes.keys().clone()),
+            keys_prefixes
+                .prefixes()
+                .iter()
+                .filter(|prefix| !self.is_blob_prefix(prefix))
+                .cloned()
+                .collect(),
+        )
+    }
+
+    fn missing_blob(key: &StoreKey, blob_key: &StoreKey) -> StorageError {
+        StorageError::Other(format!(
+            "the blob {blob_key} referenced by {key} is missing"
+        ))
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + ListableStorageTraits + WritableStorageTraits>
+    DedupStorageAdapter<TStorage>
+{
+    /// Erase all blobs that are not referenced by any key. Returns the number of blobs erased.
+    ///
+    /// This must not run concurrently with writes, otherwise a newly written value may lose its blob.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if there is an underlying error with the store.
+    pub fn garbage_collect(&self) -> Result<usize, StorageError> {
+        let mut referenced = HashSet::new();
+        for key in self.filter_keys(self.storage.list()?) {
+            if let Some(value) = self.storage.get(&key)? {
+                referenced.extend(self.resolve(&value));
+            }
+        }
+        let unreferenced: Vec<_> = self
+            .storage
+            .list_prefix(&self.blob_prefix)?
+            .into_iter()
+            .filter(|blob_key| !referenced.contains(blob_key))
+            .collect();
+        self.storage.erase_values(&unreferenced)?;
+        Ok(unreferenced.len())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<
+        TStorage: ?Sized
+            + AsyncReadableStorageTraits
+            + AsyncListableStorageTraits
+            + AsyncWritableStorageTraits,
+    > DedupStorageAdapter<TStorage>
+{
+    /// Asynchronously erase all blobs that are not referenced by any key. Returns the number of blobs erased.
+    ///
+    /// This must not run concurrently with writes, otherwise a newly written value may lose its blob.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if there is an underlying error with the store.
+    pub async fn async_garbage_collect(&self) -> Result<usize, StorageError> {
+        let mut referenced = HashSet::new();
+        for key in self.filter_keys(self.storage.list().await?) {
+            if let Some(value) = self.storage.get(&key).await? {
+                referenced.extend(self.resolve(&value));
+            }
+        }
+        let unreferenced: Vec<_> = self
+            .storage
+            .list_prefix(&self.blob_prefix)
+            .await?
+            .into_iter()
+            .filter(|blob_key| !referenced.contains(blob_key))
+            .collect();
+        self.storage.erase_values(&unreferenced).await?;
+        Ok(unreferenced.len())
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
+    for DedupStorageAdapter<TStorage>
+{
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        let Some(value) = self.storage.get(key)? else {
+            return Ok(None);
+        };
+        match self.resolve(&value) {
+            Some(blob_key) => Ok(Some(
+                self.storage
+                    .get(&blob_key)?
+                    .ok_or_else(|| Self::missing_blob(key, &blob_key))?,
+            )),
+            None => Ok(Some(value)),
+        }
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        let Some(value) = self.storage.get(key)? else {
+            return Ok(None);
+        };
+        match self.resolve(&value) {
+            Some(blob_key) => Ok(Some(
+                self.storage
+                    .get_partial_values_key(&blob_key, byte_ranges)?
+                    .ok_or_else(|| Self::missing_blob(key, &blob_key))?,
+            )),
+            None => Ok(Some(
+                extract_byte_ranges(&value, byte_ranges)?
+                    .into_iter()
+                    .map(Bytes::from)
+                    .collect(),
+            )),
+        }
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        let Some(value) = self.storage.get(key)? else {
+            return Ok(None);
+        };
+        match self.resolve(&value) {
+            Some(blob_key) => Ok(Some(
+                self.storage
+                    .size_key(&blob_key)?
+                    .ok_or_else(|| Self::missing_blob(key, &blob_key))?,
+            )),
+            None => Ok(Some(value.len() as u64)),
+        }
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + ListableStorageTraits> ListableStorageTraits
+    for DedupStorageAdapter<TStorage>
+{
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        Ok(self.filter_keys(self.storage.list()?))
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        Ok(self.filter_keys(self.storage.list_prefix(prefix)?))
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        Ok(self.filter_keys_prefixes(&self.storage.list_dir(prefix)?))
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        let mut size = 0;
+        for key in self.list_prefix(prefix)? {
+            size += self.size_key(&key)?.unwrap_or_default();
+        }
+        Ok(size)
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + ListableStorageTraits + WritableStorageTraits>
+    WritableStorageTraits for DedupStorageAdapter<TStorage>
+{
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        self.check_writable(key)?;
+        let (blob_key, reference) = self.reference(&value);
+        if self.storage.size_key(&blob_key)?.is_none() {
+            self.storage.set(&blob_key, value)?;
+        }
+        self.storage.set(key, reference.into())
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        store_set_partial_values(self, key_offset_values)
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.check_writable(key)?;
+        self.storage.erase(key)
+    }
+
+    fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        for key in keys {
+            self.check_writable(key)?;
+        }
+        self.storage.erase_values(keys)
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        // Blobs under the prefix may be referenced by keys outside of it, so only references are erased
+        self.storage.erase_values(&self.list_prefix(prefix)?)
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncReadableStorageTraits> AsyncReadableStorageTraits
+    for DedupStorageAdapter<TStorage>
+{
+    async fn get(&self, key: &StoreKey) -> Result<MaybeAsyncBytes, StorageError> {
+        let Some(value) = self.storage.get(key).await? else {
+            return Ok(None);
+        };
+        match self.resolve(&value) {
+            Some(blob_key) => Ok(Some(
+                self.storage
+                    .get(&blob_key)
+                    .await?
+                    .ok_or_else(|| Self::missing_blob(key, &blob_key))?,
+            )),
+            None => Ok(Some(value)),
+        }
+    }
+
+    async fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<AsyncBytes>>, StorageError> {
+        let Some(value) = self.storage.get(key).await? else {
+            return Ok(None);
+        };
+        match self.resolve(&value) {
+            Some(blob_key) => Ok(Some(
+                self.storage
+                    .get_partial_values_key(&blob_key, byte_ranges)
+                    .await?
+                    .ok_or_else(|| Self::missing_blob(key, &blob_key))?,
+            )),
+            None => Ok(Some(
+                extract_byte_ranges(&value, byte_ranges)?
+                    .into_iter()
+                    .map(AsyncBytes::from)
+                    .collect(),
+            )),
+        }
+    }
+
+    async fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        let Some(value) = self.storage.get(key).await? else {
+            return Ok(None);
+        };
+        match self.resolve(&value) {
+            Some(blob_key) => Ok(Some(
+                self.storage
+                    .size_key(&blob_key)
+                    .await?
+                    .ok_or_else(|| Self::missing_blob(key, &blob_key))?,
+            )),
+            None => Ok(Some(value.len() as u64)),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncReadableStorageTraits + AsyncListableStorageTraits>
+    AsyncListableStorageTraits for DedupStorageAdapter<TStorage>
+{
+    async fn list(&self) -> Result<StoreKeys, StorageError> {
+        Ok(self.filter_keys(self.storage.list().await?))
+    }
+
+    async fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        Ok(self.filter_keys(self.storage.list_prefix(prefix).await?))
+    }
+
+    async fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        Ok(self.filter_keys_prefixes(&self.storage.list_dir(prefix).await?))
+    }
+
+    async fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        let mut size = 0;
+        for key in AsyncListableStorageTraits::list_prefix(self, prefix).await? {
+            size += AsyncReadableStorageTraits::size_key(self, &key)
+                .await?
+                .unwrap_or_default();
+        }
+        Ok(size)
+    }
+
+    async fn size(&self) -> Result<u64, StorageError> {
+        AsyncListableStorageTraits::size_prefix(self, &StorePrefix::root()).await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<
+        TStorage: ?Sized
+            + AsyncReadableStorageTraits
+            + AsyncListableStorageTraits
+            + AsyncWritableStorageTraits,
+    > AsyncWritableStorageTraits for DedupStorageAdapter<TStorage>
+{
+    async fn set(&self, key: &StoreKey, value: AsyncBytes) -> Result<(), StorageError> {
+        self.check_writable(key)?;
+        let (blob_key, reference) = self.reference(&value);
+        if self.storage.size_key(&blob_key).await?.is_none() {
+            self.storage.set(&blob_key, value).await?;
+        }
+        self.storage.set(key, reference.into()).await
+    }
+
+    async fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        async_store_set_partial_values(self, key_offset_values).await
+    }
+
+    async fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.check_writable(key)?;
+        self.storage.erase(key).await
+    }
+
+    async fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        for key in keys {
+            self.check_writable(key)?;
+        }
+        self.storage.erase_values(keys).await
+    }
+
+    async fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        // Blobs under the prefix may be referenced by keys outside of it, so only references are erased
+        let keys = AsyncListableStorageTraits::list_prefix(self, prefix).await?;
+        self.storage.erase_values(&keys).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::MemoryStore;
+    use std::error::Error;
+
+    #[test]
+    fn dedup() -> Result<(), Box<dyn Error>> {
+        let store = Arc::new(MemoryStore::new());
+        let dedup = DedupStorageAdapter::new(store.clone());
+        crate::store_test::store_write(&dedup)?;
+        crate::store_test::store_read(&dedup)?;
+        crate::store_test::store_list(&dedup)?;
+        assert!(dedup.set(&dedup.blob_key("0"), vec![].into()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn dedup_garbage_collect() -> Result<(), Box<dyn Error>> {
+        let store = Arc::new(MemoryStore::new());
+        let dedup =
+            DedupStorageAdapter::new(store.clone()).with_blob_prefix(StorePrefix::new("x/blobs/")?);
+        let (a, b, c) = (
+            StoreKey::new("x/a")?,
+            StoreKey::new("b")?,
+            StoreKey::new("c")?,
+        );
+        dedup.set(&a, vec![1; 100].into())?;
+        dedup.set(&b, vec![1; 100].into())?;
+        dedup.set(&c, vec![2; 100].into())?;
+        assert_eq!(store.list_prefix(dedup.blob_prefix())?.len(), 2);
+        assert_eq!(dedup.list()?, vec![b.clone(), c.clone(), a.clone()]);
+        assert_eq!(
+            dedup.list_dir(&StorePrefix::new("x/")?)?,
+            StoreKeysPrefixes::new(vec![a.clone()], vec![])
+        );
+
+        // Erasing a prefix containing the blobs must not erase blobs referenced elsewhere
+        dedup.erase_prefix(&StorePrefix::new("x/")?)?;
+        assert_eq!(dedup.garbage_collect()?, 0);
+        assert_eq!(dedup.get(&b)?.unwrap(), vec![1; 100]);
+
+        dedup.set(&c, vec![1; 100].into())?;
+        assert_eq!(dedup.garbage_collect()?, 1);
+        assert_eq!(store.list_prefix(dedup.blob_prefix())?.len(), 1);
+        assert_eq!(dedup.get(&c)?.unwrap(), vec![1; 100]);
+
+        // Values which are not references are passed through
+        let plain = StoreKey::new("plain")?;
+        store.set(&plain, vec![3, 4, 5].into())?;
+        assert_eq!(dedup.get(&plain)?.unwrap(), vec![3, 4, 5]);
+        assert_eq!(dedup.size_key(&plain)?, Some(3));
+        Ok(())
+    }
+}
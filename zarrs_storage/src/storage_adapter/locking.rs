@@ -0,0 +1,226 @@
+//! A storage adapter which locks store keys with a [`StoreLocks`] backend.
+
+use std::sync::Arc;
+
+use crate::{
+    byte_range::ByteRange,
+    store_lock::{StoreKeyLockGuard, StoreLocks},
+    Bytes, ListableStorageTraits, MaybeBytes, MaybeModifiedBytes, ReadableStorageTraits,
+    StorageError, StoreKey, StoreKeyOffsetValue, StoreKeyRange, StoreKeys, StoreKeysPrefixes,
+    StorePrefix, StoreValueReader, StoreValueValidator, StoreValueWriter, WritableStorageTraits,
+};
+
+/// The locking storage adapter. Locks store keys with a [`StoreLocks`] backend.
+///
+/// All operations are forwarded to the underlying store, and [`lock_key`](WritableStorageTraits::lock_key) acquires a lock from the backend.
+/// Writers that hold the lock of a key while performing a read-modify-write of its value (e.g. [`Array::store_chunk_subset`](https://docs.rs/zarrs/latest/zarrs/array/struct.Array.html#method.store_chunk_subset)) are serialised with all other writers using the same backend.
+/// Operations other than [`lock_key`](WritableStorageTraits::lock_key) do not lock.
+///
+/// The async storage API is not supported.
+///
+/// ```rust
+/// # use std::sync::Arc;
+/// # use zarrs_storage::{WritableStorageTraits, StoreKey};
+/// # use zarrs_storage::store::MemoryStore;
+/// use zarrs_storage::storage_adapter::locking::LockingStorageAdapter;
+/// use zarrs_storage::store_lock::InProcessStoreLocks;
+/// let store = Arc::new(MemoryStore::new());
+/// let store = LockingStorageAdapter::new(store, Arc::new(InProcessStoreLocks::new()));
+/// let _lock = store.lock_key(&StoreKey::new("c/0")?)?;
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct LockingStorageAdapter<TStorage: ?Sized> {
+    storage: Arc<TStorage>,
+    locks: Arc<dyn StoreLocks>,
+}
+
+impl<TStorage: ?Sized> core::fmt::Debug for LockingStorageAdapter<TStorage> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "locking")
+    }
+}
+
+impl<TStorage: ?Sized> LockingStorageAdapter<TStorage> {
+    /// Create a new locking storage adapter locking the store keys of `storage` with `locks`.
+    #[must_use]
+    pub fn new(storage: Arc<TStorage>, locks: Arc<dyn StoreLocks>) -> Self {
+        Self { storage, locks }
+    }
+
+    /// Return the store locks.
+    #[must_use]
+    pub fn locks(&self) -> &Arc<dyn StoreLocks> {
+        &self.locks
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
+    for LockingStorageAdapter<TStorage>
+{
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        self.storage.get(key)
+    }
+
+    fn get_many(&self, keys: &[StoreKey]) -> Result<Vec<MaybeBytes>, StorageError> {
+        self.storage.get_many(keys)
+    }
+
+    fn get_reader(&self, key: &StoreKey) -> Result<Option<StoreValueReader<'_>>, StorageError> {
+        self.storage.get_reader(key)
+    }
+
+    fn get_if_modified(
+        &self,
+        key: &StoreKey,
+        validator: Option<&StoreValueValidator>,
+    ) -> Result<MaybeModifiedBytes, StorageError> {
+        self.storage.get_if_modified(key, validator)
+    }
+
+    fn get_with_etag(
+        &self,
+        key: &StoreKey,
+    ) -> Result<(MaybeBytes, Option<StoreValueValidator>), StorageError> {
+        self.storage.get_with_etag(key)
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        self.storage.get_partial_values_key(key, byte_ranges)
+    }
+
+    fn get_partial_values(
+        &self,
+        key_ranges: &[StoreKeyRange],
+    ) -> Result<Vec<MaybeBytes>, StorageError> {
+        self.storage.get_partial_values(key_ranges)
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        self.storage.size_key(key)
+    }
+}
+
+impl<TStorage: ?Sized + ListableStorageTraits> ListableStorageTraits
+    for LockingStorageAdapter<TStorage>
+{
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.storage.list()
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.storage.list_prefix(prefix)
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.storage.list_dir(prefix)
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.storage.size_prefix(prefix)
+    }
+
+    fn size(&self) -> Result<u64, StorageError> {
+        self.storage.size()
+    }
+}
+
+impl<TStorage: ?Sized + WritableStorageTraits> WritableStorageTraits
+    for LockingStorageAdapter<TStorage>
+{
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        self.storage.set(key, value)
+    }
+
+    fn set_writer(&self, key: &StoreKey) -> Result<Box<dyn StoreValueWriter + '_>, StorageError> {
+        self.storage.set_writer(key)
+    }
+
+    fn supports_set_if_match(&self) -> bool {
+        self.storage.supports_set_if_match()
+    }
+
+    fn set_if_match(
+        &self,
+        key: &StoreKey,
+        value: Bytes,
+        etag: Option<&StoreValueValidator>,
+    ) -> Result<Option<StoreValueValidator>, StorageError> {
+        self.storage.set_if_match(key, value, etag)
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        self.storage.set_partial_values(key_offset_values)
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.storage.erase(key)
+    }
+
+    fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        self.storage.erase_values(keys)
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.storage.erase_prefix(prefix)
+    }
+
+    fn supports_rename_prefix(&self) -> bool {
+        self.storage.supports_rename_prefix()
+    }
+
+    fn rename_prefix(&self, from: &StorePrefix, to: &StorePrefix) -> Result<(), StorageError> {
+        self.storage.rename_prefix(from, to)
+    }
+
+    fn lock_key(&self, key: &StoreKey) -> Result<StoreKeyLockGuard<'_>, StorageError> {
+        self.locks.lock(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::{store::MemoryStore, store_lock::InProcessStoreLocks};
+
+    use super::*;
+
+    #[test]
+    fn locking() {
+        let store = Arc::new(MemoryStore::new());
+        assert!(!store
+            .lock_key(&StoreKey::new("a").unwrap())
+            .unwrap()
+            .is_locked());
+
+        let store = LockingStorageAdapter::new(store, Arc::new(InProcessStoreLocks::new()));
+        let key = StoreKey::new("a").unwrap();
+        store.set(&key, vec![0].into()).unwrap();
+
+        // Concurrent read-modify-writes are serialised by the lock
+        let updates = AtomicUsize::new(0);
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..10 {
+                        let lock = store.lock_key(&key).unwrap();
+                        assert!(lock.is_locked());
+                        let value = store.get(&key).unwrap().unwrap();
+                        std::thread::yield_now();
+                        store.set(&key, vec![value[0] + 1].into()).unwrap();
+                        updates.fetch_add(1, Ordering::Relaxed);
+                    }
+                });
+            }
+        });
+        assert_eq!(updates.load(Ordering::Relaxed), 40);
+        assert_eq!(store.get(&key).unwrap().unwrap(), vec![40]);
+    }
+}
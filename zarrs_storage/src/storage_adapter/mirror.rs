@@ -0,0 +1,509 @@
+//! A storage adapter which mirrors writes to multiple stores.
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use crate::{
+    byte_range::ByteRange, Bytes, ListableStorageTraits, MaybeBytes, ReadableStorageTraits,
+    StorageError, StoreKey, StoreKeyOffsetValue, StoreKeys, StoreKeysPrefixes, StorePrefix,
+    WritableStorageTraits,
+};
+
+#[cfg(feature = "async")]
+use crate::{
+    AsyncBytes, AsyncListableStorageTraits, AsyncReadableStorageTraits, AsyncWritableStorageTraits,
+    MaybeAsyncBytes,
+};
+
+#[cfg(feature = "async")]
+use futures::future::BoxFuture;
+
+/// The write policy of a [`MirrorStorageAdapter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MirrorPolicy {
+    /// A write succeeds if it succeeds on the primary store, the first healthy store.
+    ///
+    /// The write is only applied to the other healthy stores if it succeeds on the primary store.
+    #[default]
+    PrimaryPreferred,
+    /// A write succeeds if it succeeds on a majority of all stores.
+    Quorum,
+}
+
+/// The mirror storage adapter. Writes to multiple stores and reads from the first healthy store.
+///
+/// Writes are applied to all healthy stores and succeed according to the [`MirrorPolicy`].
+/// A store is marked unhealthy if a write to it fails, as it may no longer mirror the other stores.
+/// Unhealthy stores are excluded from reads and writes until they are resynchronised and marked healthy with [`MirrorStorageAdapter::set_healthy`].
+///
+/// Reads are retrieved from the first healthy store, falling back to the next healthy store if a read fails.
+/// A failed read does not mark a store unhealthy.
+///
+/// ```rust
+/// # use std::sync::Arc;
+/// # use zarrs_storage::{ReadableStorageTraits, WritableStorageTraits, StoreKey};
+/// # use zarrs_storage::store::MemoryStore;
+/// use zarrs_storage::storage_adapter::mirror::{MirrorPolicy, MirrorStorageAdapter};
+/// let primary = Arc::new(MemoryStore::new());
+/// let backup = Arc::new(MemoryStore::new());
+/// let store = MirrorStorageAdapter::new(
+///     vec![primary.clone(), backup.clone()],
+///     MirrorPolicy::PrimaryPreferred,
+/// );
+/// let key = StoreKey::new("a/b")?;
+/// store.set(&key, vec![0, 1, 2].into())?;
+/// assert_eq!(backup.get(&key)?, primary.get(&key)?);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct MirrorStorageAdapter<TStorage: ?Sized> {
+    stores: Vec<Arc<TStorage>>,
+    healthy: Vec<AtomicBool>,
+    policy: MirrorPolicy,
+}
+
+impl<TStorage: ?Sized> core::fmt::Debug for MirrorStorageAdapter<TStorage> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "mirror ({:?}, {} of {} stores healthy)",
+            self.policy,
+            self.healthy_indices().len(),
+            self.stores.len()
+        )
+    }
+}
+
+impl<TStorage: ?Sized> MirrorStorageAdapter<TStorage> {
+    /// Create a new mirror storage adapter over `stores` with a write `policy`.
+    ///
+    /// The first store is the primary store. All stores are initially healthy.
+    #[must_use]
+    pub fn new(stores: Vec<Arc<TStorage>>, policy: MirrorPolicy) -> Self {
+        let healthy = stores.iter().map(|_| AtomicBool::new(true)).collect();
+        Self {
+            stores,
+            healthy,
+            policy,
+        }
+    }
+
+    /// Return the mirrored stores.
+    #[must_use]
+    pub fn stores(&self) -> &[Arc<TStorage>] {
+        &self.stores
+    }
+
+    /// Return the write policy.
+    #[must_use]
+    pub const fn policy(&self) -> MirrorPolicy {
+        self.policy
+    }
+
+    /// Returns true if the store at `index` is healthy.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    #[must_use]
+    pub fn is_healthy(&self, index: usize) -> bool {
+        self.healthy[index].load(Ordering::Relaxed)
+    }
+
+    /// Mark the store at `index` as healthy or unhealthy.
+    ///
+    /// A store should only be marked healthy once it mirrors the other healthy stores.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn set_healthy(&self, index: usize, healthy: bool) {
+        self.healthy[index].store(healthy, Ordering::Relaxed);
+    }
+
+    /// The number of successful writes required by the [`MirrorPolicy::Quorum`] policy.
+    fn quorum(&self) -> usize {
+        self.stores.len() / 2 + 1
+    }
+
+    fn healthy_indices(&self) -> Vec<usize> {
+        (0..self.stores.len())
+            .filter(|&index| self.is_healthy(index))
+            .collect()
+    }
+
+    fn no_healthy_stores() -> StorageError {
+        StorageError::Other("the mirror storage adapter has no healthy stores".to_string())
+    }
+
+    /// Resolve the results of a write to the stores at the indices of `results`, marking failed stores unhealthy.
+    fn resolve_writes(
+        &self,
+        results: Vec<(usize, Result<(), StorageError>)>,
+    ) -> Result<(), StorageError> {
+        let mut successes = 0;
+        let mut last_err = None;
+        for (index, result) in results {
+            match result {
+                Ok(()) => successes += 1,
+                Err(err) => {
+                    self.set_healthy(index, false);
+                    last_err = Some(err);
+                }
+            }
+        }
+        match self.policy {
+            MirrorPolicy::PrimaryPreferred => Ok(()),
+            MirrorPolicy::Quorum if successes >= self.quorum() => Ok(()),
+            MirrorPolicy::Quorum => Err(StorageError::Other(format!(
+                "write quorum not reached, {successes} of {} stores succeeded and {} are required{}",
+                self.stores.len(),
+                self.quorum(),
+                last_err.map_or_else(String::new, |err| format!(": {err}"))
+            ))),
+        }
+    }
+
+    /// Read from the first healthy store, falling back to the next healthy store on failure.
+    fn read<T>(&self, f: impl Fn(&TStorage) -> Result<T, StorageError>) -> Result<T, StorageError> {
+        let mut last_err = None;
+        for index in self.healthy_indices() {
+            match f(&self.stores[index]) {
+                Ok(value) => return Ok(value),
+                Err(err) => last_err = Some(err),
+            }
+        }
+        Err(last_err.unwrap_or_else(Self::no_healthy_stores))
+    }
+
+    /// Write to all healthy stores.
+    fn write(&self, f: impl Fn(&TStorage) -> Result<(), StorageError>) -> Result<(), StorageError> {
+        let healthy = self.healthy_indices();
+        let replicas = match self.policy {
+            MirrorPolicy::PrimaryPreferred => {
+                let (&primary, replicas) =
+                    healthy.split_first().ok_or_else(Self::no_healthy_stores)?;
+                if let Err(err) = f(&self.stores[primary]) {
+                    self.set_healthy(primary, false);
+                    return Err(err);
+                }
+                replicas
+            }
+            MirrorPolicy::Quorum => &healthy,
+        };
+        let results = replicas
+            .iter()
+            .map(|&index| (index, f(&self.stores[index])))
+            .collect();
+        self.resolve_writes(results)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<TStorage: ?Sized + Send + Sync> MirrorStorageAdapter<TStorage> {
+    /// Read from the first healthy store, falling back to the next healthy store on failure.
+    async fn read_async<'a, T>(
+        &'a self,
+        f: impl Fn(&'a TStorage) -> BoxFuture<'a, Result<T, StorageError>> + Send,
+    ) -> Result<T, StorageError> {
+        let mut last_err = None;
+        for index in self.healthy_indices() {
+            match f(&self.stores[index]).await {
+                Ok(value) => return Ok(value),
+                Err(err) => last_err = Some(err),
+            }
+        }
+        Err(last_err.unwrap_or_else(Self::no_healthy_stores))
+    }
+
+    /// Write to all healthy stores, concurrently.
+    async fn write_async<'a>(
+        &'a self,
+        f: impl Fn(&'a TStorage) -> BoxFuture<'a, Result<(), StorageError>> + Send,
+    ) -> Result<(), StorageError> {
+        let healthy = self.healthy_indices();
+        let replicas = match self.policy {
+            MirrorPolicy::PrimaryPreferred => {
+                let (&primary, replicas) =
+                    healthy.split_first().ok_or_else(Self::no_healthy_stores)?;
+                if let Err(err) = f(&self.stores[primary]).await {
+                    self.set_healthy(primary, false);
+                    return Err(err);
+                }
+                replicas
+            }
+            MirrorPolicy::Quorum => &healthy,
+        };
+        let f = &f;
+        let results = futures::future::join_all(
+            replicas
+                .iter()
+                .map(|&index| async move { (index, f(&self.stores[index]).await) }),
+        )
+        .await;
+        self.resolve_writes(results)
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
+    for MirrorStorageAdapter<TStorage>
+{
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        self.read(|storage| storage.get(key))
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        self.read(|storage| storage.get_partial_values_key(key, byte_ranges))
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        self.read(|storage| storage.size_key(key))
+    }
+}
+
+impl<TStorage: ?Sized + ListableStorageTraits> ListableStorageTraits
+    for MirrorStorageAdapter<TStorage>
+{
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.read(ListableStorageTraits::list)
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.read(|storage| storage.list_prefix(prefix))
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.read(|storage| storage.list_dir(prefix))
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.read(|storage| storage.size_prefix(prefix))
+    }
+
+    fn size(&self) -> Result<u64, StorageError> {
+        self.read(ListableStorageTraits::size)
+    }
+}
+
+impl<TStorage: ?Sized + WritableStorageTraits> WritableStorageTraits
+    for MirrorStorageAdapter<TStorage>
+{
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        self.write(|storage| storage.set(key, value.clone()))
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        self.write(|storage| storage.set_partial_values(key_offset_values))
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.write(|storage| storage.erase(key))
+    }
+
+    fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        self.write(|storage| storage.erase_values(keys))
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.write(|storage| storage.erase_prefix(prefix))
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncReadableStorageTraits> AsyncReadableStorageTraits
+    for MirrorStorageAdapter<TStorage>
+{
+    async fn get(&self, key: &StoreKey) -> Result<MaybeAsyncBytes, StorageError> {
+        self.read_async(|storage| storage.get(key)).await
+    }
+
+    async fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<AsyncBytes>>, StorageError> {
+        self.read_async(|storage| storage.get_partial_values_key(key, byte_ranges))
+            .await
+    }
+
+    async fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        self.read_async(|storage| storage.size_key(key)).await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncListableStorageTraits> AsyncListableStorageTraits
+    for MirrorStorageAdapter<TStorage>
+{
+    async fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.read_async(AsyncListableStorageTraits::list).await
+    }
+
+    async fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.read_async(|storage| storage.list_prefix(prefix)).await
+    }
+
+    async fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.read_async(|storage| storage.list_dir(prefix)).await
+    }
+
+    async fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.read_async(|storage| storage.size_prefix(prefix)).await
+    }
+
+    async fn size(&self) -> Result<u64, StorageError> {
+        self.read_async(AsyncListableStorageTraits::size).await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncWritableStorageTraits> AsyncWritableStorageTraits
+    for MirrorStorageAdapter<TStorage>
+{
+    async fn set(&self, key: &StoreKey, value: AsyncBytes) -> Result<(), StorageError> {
+        self.write_async(|storage| storage.set(key, value.clone()))
+            .await
+    }
+
+    async fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        self.write_async(|storage| storage.set_partial_values(key_offset_values))
+            .await
+    }
+
+    async fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.write_async(|storage| storage.erase(key)).await
+    }
+
+    async fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        self.write_async(|storage| storage.erase_values(keys)).await
+    }
+
+    async fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.write_async(|storage| storage.erase_prefix(prefix))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{store::MemoryStore, ReadableWritableStorageTraits};
+    use std::error::Error;
+
+    /// A store which fails all operations.
+    struct FailingStore;
+
+    impl ReadableStorageTraits for FailingStore {
+        fn get_partial_values_key(
+            &self,
+            _key: &StoreKey,
+            _byte_ranges: &[ByteRange],
+        ) -> Result<Option<Vec<Bytes>>, StorageError> {
+            Err("failing store".into())
+        }
+
+        fn size_key(&self, _key: &StoreKey) -> Result<Option<u64>, StorageError> {
+            Err("failing store".into())
+        }
+    }
+
+    impl WritableStorageTraits for FailingStore {
+        fn set(&self, _key: &StoreKey, _value: Bytes) -> Result<(), StorageError> {
+            Err("failing store".into())
+        }
+
+        fn set_partial_values(
+            &self,
+            _key_offset_values: &[StoreKeyOffsetValue],
+        ) -> Result<(), StorageError> {
+            Err("failing store".into())
+        }
+
+        fn erase(&self, _key: &StoreKey) -> Result<(), StorageError> {
+            Err("failing store".into())
+        }
+
+        fn erase_prefix(&self, _prefix: &StorePrefix) -> Result<(), StorageError> {
+            Err("failing store".into())
+        }
+    }
+
+    #[test]
+    fn mirror() -> Result<(), Box<dyn Error>> {
+        let a = Arc::new(MemoryStore::new());
+        let b = Arc::new(MemoryStore::new());
+        let store = MirrorStorageAdapter::new(vec![a.clone(), b.clone()], MirrorPolicy::Quorum);
+        crate::store_test::store_write(&store)?;
+        crate::store_test::store_read(&store)?;
+        crate::store_test::store_list(&store)?;
+        assert_eq!(a.list()?, b.list()?);
+        for key in a.list()? {
+            assert_eq!(a.get(&key)?, b.get(&key)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn mirror_primary_preferred() -> Result<(), Box<dyn Error>> {
+        let primary: Arc<dyn ReadableWritableStorageTraits> = Arc::new(MemoryStore::new());
+        let store: MirrorStorageAdapter<dyn ReadableWritableStorageTraits> =
+            MirrorStorageAdapter::new(
+                vec![primary.clone(), Arc::new(FailingStore)],
+                MirrorPolicy::PrimaryPreferred,
+            );
+        let key = StoreKey::new("a")?;
+        store.set(&key, vec![0, 1].into())?;
+        assert!(store.is_healthy(0));
+        assert!(!store.is_healthy(1));
+        assert_eq!(store.get(&key)?.unwrap(), vec![0, 1]);
+
+        // Reads fall back to the next healthy store
+        let store: MirrorStorageAdapter<dyn ReadableWritableStorageTraits> =
+            MirrorStorageAdapter::new(
+                vec![Arc::new(FailingStore), primary],
+                MirrorPolicy::PrimaryPreferred,
+            );
+        assert_eq!(store.get(&key)?.unwrap(), vec![0, 1]);
+        assert!(store.is_healthy(0));
+
+        // A failed write to the primary store fails
+        assert!(store.set(&key, vec![2].into()).is_err());
+        assert!(!store.is_healthy(0));
+        assert_eq!(store.get(&key)?.unwrap(), vec![0, 1]);
+        Ok(())
+    }
+
+    #[test]
+    fn mirror_quorum() -> Result<(), Box<dyn Error>> {
+        let store: MirrorStorageAdapter<dyn ReadableWritableStorageTraits> =
+            MirrorStorageAdapter::new(
+                vec![
+                    Arc::new(FailingStore),
+                    Arc::new(MemoryStore::new()),
+                    Arc::new(MemoryStore::new()),
+                ],
+                MirrorPolicy::Quorum,
+            );
+        let key = StoreKey::new("a")?;
+        store.set(&key, vec![0, 1].into())?;
+        assert!(!store.is_healthy(0));
+        assert_eq!(store.get(&key)?.unwrap(), vec![0, 1]);
+
+        store.set_healthy(0, true);
+        store.set_healthy(1, false);
+        assert!(store.set(&key, vec![2].into()).is_err());
+        Ok(())
+    }
+}
@@ -12,14 +12,313 @@ use crate::{
     MaybeAsyncBytes,
 };
 
-use std::sync::{
-    atomic::{AtomicUsize, Ordering},
-    Arc,
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
 };
 
+/// A storage operation, corresponding to a storage trait method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StorageOperation {
+    /// [`get`](ReadableStorageTraits::get).
+    Get,
+    /// [`get_partial_values_key`](ReadableStorageTraits::get_partial_values_key).
+    GetPartialValuesKey,
+    /// [`get_partial_values`](ReadableStorageTraits::get_partial_values).
+    GetPartialValues,
+    /// [`size_key`](ReadableStorageTraits::size_key).
+    SizeKey,
+    /// [`list`](ListableStorageTraits::list).
+    List,
+    /// [`list_prefix`](ListableStorageTraits::list_prefix).
+    ListPrefix,
+    /// [`list_dir`](ListableStorageTraits::list_dir).
+    ListDir,
+    /// [`size`](ListableStorageTraits::size).
+    Size,
+    /// [`size_prefix`](ListableStorageTraits::size_prefix).
+    SizePrefix,
+    /// [`set`](WritableStorageTraits::set).
+    Set,
+    /// [`set_partial_values`](WritableStorageTraits::set_partial_values).
+    SetPartialValues,
+    /// [`erase`](WritableStorageTraits::erase).
+    Erase,
+    /// [`erase_values`](WritableStorageTraits::erase_values).
+    EraseValues,
+    /// [`erase_prefix`](WritableStorageTraits::erase_prefix).
+    ErasePrefix,
+}
+
+impl StorageOperation {
+    /// All storage operations.
+    pub const ALL: [Self; 14] = [
+        Self::Get,
+        Self::GetPartialValuesKey,
+        Self::GetPartialValues,
+        Self::SizeKey,
+        Self::List,
+        Self::ListPrefix,
+        Self::ListDir,
+        Self::Size,
+        Self::SizePrefix,
+        Self::Set,
+        Self::SetPartialValues,
+        Self::Erase,
+        Self::EraseValues,
+        Self::ErasePrefix,
+    ];
+
+    /// Returns the name of the storage trait method of the operation.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Get => "get",
+            Self::GetPartialValuesKey => "get_partial_values_key",
+            Self::GetPartialValues => "get_partial_values",
+            Self::SizeKey => "size_key",
+            Self::List => "list",
+            Self::ListPrefix => "list_prefix",
+            Self::ListDir => "list_dir",
+            Self::Size => "size",
+            Self::SizePrefix => "size_prefix",
+            Self::Set => "set",
+            Self::SetPartialValues => "set_partial_values",
+            Self::Erase => "erase",
+            Self::EraseValues => "erase_values",
+            Self::ErasePrefix => "erase_prefix",
+        }
+    }
+}
+
+impl std::fmt::Display for StorageOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The upper bounds of the latency histogram buckets, excluding the final unbounded bucket.
+pub const LATENCY_BUCKETS: [Duration; 12] = [
+    Duration::from_micros(100),
+    Duration::from_micros(250),
+    Duration::from_micros(500),
+    Duration::from_millis(1),
+    Duration::from_micros(2500),
+    Duration::from_millis(5),
+    Duration::from_millis(10),
+    Duration::from_millis(25),
+    Duration::from_millis(50),
+    Duration::from_millis(100),
+    Duration::from_secs(1),
+    Duration::from_secs(10),
+];
+
+/// The metrics of a storage operation.
+#[derive(Debug, Default)]
+struct OperationMetrics {
+    calls: AtomicU64,
+    errors: AtomicU64,
+    /// The number of calls in each latency bucket, including the final unbounded bucket.
+    latency_buckets: [AtomicU64; LATENCY_BUCKETS.len() + 1],
+    latency_sum_ns: AtomicU64,
+}
+
+impl OperationMetrics {
+    fn record(&self, latency: Duration, error: bool) {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        if error {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+        let bucket = LATENCY_BUCKETS.partition_point(|&bound| bound < latency);
+        self.latency_buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.latency_sum_ns.fetch_add(
+            u64::try_from(latency.as_nanos()).unwrap_or(u64::MAX),
+            Ordering::Relaxed,
+        );
+    }
+
+    fn reset(&self) {
+        self.calls.store(0, Ordering::Relaxed);
+        self.errors.store(0, Ordering::Relaxed);
+        for bucket in &self.latency_buckets {
+            bucket.store(0, Ordering::Relaxed);
+        }
+        self.latency_sum_ns.store(0, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> OperationMetricsSnapshot {
+        OperationMetricsSnapshot {
+            calls: self.calls.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+            latency: LatencyHistogram {
+                bucket_counts: self
+                    .latency_buckets
+                    .iter()
+                    .map(|bucket| bucket.load(Ordering::Relaxed))
+                    .collect(),
+                sum: Duration::from_nanos(self.latency_sum_ns.load(Ordering::Relaxed)),
+            },
+        }
+    }
+}
+
+/// A latency histogram with buckets bounded by [`LATENCY_BUCKETS`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    /// The number of calls in each bucket.
+    ///
+    /// Bucket `i` counts calls with a latency greater than bucket `i - 1` and less than or equal to [`LATENCY_BUCKETS`]`[i]`.
+    /// The final bucket counts calls with a latency greater than the last bound of [`LATENCY_BUCKETS`].
+    pub bucket_counts: Vec<u64>,
+    /// The total latency of all calls.
+    pub sum: Duration,
+}
+
+impl LatencyHistogram {
+    /// Returns the number of calls in the histogram.
+    #[must_use]
+    pub fn count(&self) -> u64 {
+        self.bucket_counts.iter().sum()
+    }
+
+    /// Returns the mean latency, or [`None`] if the histogram is empty.
+    #[must_use]
+    pub fn mean(&self) -> Option<Duration> {
+        let count = u32::try_from(self.count()).unwrap_or(u32::MAX);
+        (count > 0).then(|| self.sum / count)
+    }
+}
+
+/// A snapshot of the metrics of a storage operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationMetricsSnapshot {
+    /// The number of calls.
+    pub calls: u64,
+    /// The number of calls that returned an error.
+    pub errors: u64,
+    /// The latency histogram of all calls.
+    pub latency: LatencyHistogram,
+}
+
+/// A snapshot of the metrics of a [`PerformanceMetricsStorageAdapter`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PerformanceMetricsSnapshot {
+    /// The number of bytes read.
+    pub bytes_read: usize,
+    /// The number of bytes written.
+    pub bytes_written: usize,
+    /// The number of read requests.
+    pub reads: usize,
+    /// The number of write requests.
+    pub writes: usize,
+    /// The number of key erase requests.
+    pub keys_erased: usize,
+    /// The metrics of each storage operation.
+    pub operations: BTreeMap<StorageOperation, OperationMetricsSnapshot>,
+}
+
+impl PerformanceMetricsSnapshot {
+    /// Encode the metrics in the [Prometheus text exposition format](https://prometheus.io/docs/instrumenting/exposition_formats/#text-based-format).
+    ///
+    /// Metric names are prefixed with `namespace` (e.g. `zarrs_store`), and operations are distinguished by an `operation` label.
+    #[must_use]
+    pub fn to_prometheus(&self, namespace: &str) -> String {
+        let mut text = String::new();
+        for (name, help, value) in [
+            (
+                "bytes_read_total",
+                "The number of bytes read.",
+                self.bytes_read,
+            ),
+            (
+                "bytes_written_total",
+                "The number of bytes written.",
+                self.bytes_written,
+            ),
+            ("reads_total", "The number of read requests.", self.reads),
+            ("writes_total", "The number of write requests.", self.writes),
+            (
+                "keys_erased_total",
+                "The number of key erase requests.",
+                self.keys_erased,
+            ),
+        ] {
+            let _ = writeln!(text, "# HELP {namespace}_{name} {help}");
+            let _ = writeln!(text, "# TYPE {namespace}_{name} counter");
+            let _ = writeln!(text, "{namespace}_{name} {value}");
+        }
+
+        let _ = writeln!(
+            text,
+            "# HELP {namespace}_operations_total The number of storage operation calls."
+        );
+        let _ = writeln!(text, "# TYPE {namespace}_operations_total counter");
+        for (operation, metrics) in &self.operations {
+            let _ = writeln!(
+                text,
+                "{namespace}_operations_total{{operation=\"{operation}\"}} {}",
+                metrics.calls
+            );
+        }
+
+        let _ = writeln!(
+            text,
+            "# HELP {namespace}_operation_errors_total The number of storage operation calls that returned an error."
+        );
+        let _ = writeln!(text, "# TYPE {namespace}_operation_errors_total counter");
+        for (operation, metrics) in &self.operations {
+            let _ = writeln!(
+                text,
+                "{namespace}_operation_errors_total{{operation=\"{operation}\"}} {}",
+                metrics.errors
+            );
+        }
+
+        let name = format!("{namespace}_operation_duration_seconds");
+        let _ = writeln!(
+            text,
+            "# HELP {name} The latency of storage operation calls."
+        );
+        let _ = writeln!(text, "# TYPE {name} histogram");
+        for (operation, metrics) in &self.operations {
+            let mut cumulative = 0;
+            for (i, count) in metrics.latency.bucket_counts.iter().enumerate() {
+                cumulative += count;
+                let le = LATENCY_BUCKETS.get(i).map_or_else(
+                    || "+Inf".to_string(),
+                    |bound| bound.as_secs_f64().to_string(),
+                );
+                let _ = writeln!(
+                    text,
+                    "{name}_bucket{{operation=\"{operation}\",le=\"{le}\"}} {cumulative}"
+                );
+            }
+            let _ = writeln!(
+                text,
+                "{name}_sum{{operation=\"{operation}\"}} {}",
+                metrics.latency.sum.as_secs_f64()
+            );
+            let _ = writeln!(
+                text,
+                "{name}_count{{operation=\"{operation}\"}} {cumulative}"
+            );
+        }
+        text
+    }
+}
+
 /// The performance metrics storage transformer. Accumulates metrics, such as bytes read and written.
 ///
 /// It is intended to aid in testing by allowing the application to validate that metrics (e.g., bytes read/written, total read/write operations) match expected values for specific operations.
+///
+/// The number of calls, errors, and a latency histogram are also recorded for each [`StorageOperation`].
+/// This can be used to monitor the health of a store in a long-running service, with all metrics retrieved by [`snapshot`](PerformanceMetricsStorageAdapter::snapshot).
+/// A snapshot can be encoded for [Prometheus](https://prometheus.io/) with [`PerformanceMetricsSnapshot::to_prometheus`].
 #[derive(Debug)]
 pub struct PerformanceMetricsStorageAdapter<TStorage: ?Sized> {
     storage: Arc<TStorage>,
@@ -28,6 +327,7 @@ pub struct PerformanceMetricsStorageAdapter<TStorage: ?Sized> {
     reads: AtomicUsize,
     writes: AtomicUsize,
     keys_erased: AtomicUsize,
+    operations: [OperationMetrics; StorageOperation::ALL.len()],
 }
 
 impl<TStorage: ?Sized> PerformanceMetricsStorageAdapter<TStorage> {
@@ -41,6 +341,7 @@ impl<TStorage: ?Sized> PerformanceMetricsStorageAdapter<TStorage> {
             reads: AtomicUsize::default(),
             writes: AtomicUsize::default(),
             keys_erased: AtomicUsize::default(),
+            operations: Default::default(),
         }
     }
 
@@ -50,6 +351,9 @@ impl<TStorage: ?Sized> PerformanceMetricsStorageAdapter<TStorage> {
         self.bytes_written.store(0, Ordering::Relaxed);
         self.reads.store(0, Ordering::Relaxed);
         self.writes.store(0, Ordering::Relaxed);
+        for operation in &self.operations {
+            operation.reset();
+        }
     }
 
     /// Returns the number of bytes read.
@@ -78,13 +382,58 @@ impl<TStorage: ?Sized> PerformanceMetricsStorageAdapter<TStorage> {
     pub fn keys_erased(&self) -> usize {
         self.keys_erased.load(Ordering::Relaxed)
     }
+
+    /// Returns a snapshot of the metrics of a storage operation.
+    #[must_use]
+    pub fn operation(&self, operation: StorageOperation) -> OperationMetricsSnapshot {
+        self.operations[operation as usize].snapshot()
+    }
+
+    /// Returns a snapshot of all metrics.
+    #[must_use]
+    pub fn snapshot(&self) -> PerformanceMetricsSnapshot {
+        PerformanceMetricsSnapshot {
+            bytes_read: self.bytes_read(),
+            bytes_written: self.bytes_written(),
+            reads: self.reads(),
+            writes: self.writes(),
+            keys_erased: self.keys_erased(),
+            operations: StorageOperation::ALL
+                .into_iter()
+                .map(|operation| (operation, self.operation(operation)))
+                .collect(),
+        }
+    }
+
+    fn timed<T>(
+        &self,
+        operation: StorageOperation,
+        f: impl FnOnce() -> Result<T, StorageError>,
+    ) -> Result<T, StorageError> {
+        let start = Instant::now();
+        let result = f();
+        self.operations[operation as usize].record(start.elapsed(), result.is_err());
+        result
+    }
+
+    #[cfg(feature = "async")]
+    async fn timed_async<T>(
+        &self,
+        operation: StorageOperation,
+        future: impl std::future::Future<Output = Result<T, StorageError>>,
+    ) -> Result<T, StorageError> {
+        let start = Instant::now();
+        let result = future.await;
+        self.operations[operation as usize].record(start.elapsed(), result.is_err());
+        result
+    }
 }
 
 impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
     for PerformanceMetricsStorageAdapter<TStorage>
 {
     fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
-        let value = self.storage.get(key);
+        let value = self.timed(StorageOperation::Get, || self.storage.get(key));
         let bytes_read = value
             .as_ref()
             .map_or(0, |v| v.as_ref().map_or(0, Bytes::len));
@@ -98,7 +447,9 @@ impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
         key: &StoreKey,
         byte_ranges: &[crate::byte_range::ByteRange],
     ) -> Result<Option<Vec<Bytes>>, StorageError> {
-        let values = self.storage.get_partial_values_key(key, byte_ranges)?;
+        let values = self.timed(StorageOperation::GetPartialValuesKey, || {
+            self.storage.get_partial_values_key(key, byte_ranges)
+        })?;
         if let Some(values) = &values {
             let bytes_read = values.iter().map(Bytes::len).sum();
             self.bytes_read.fetch_add(bytes_read, Ordering::Relaxed);
@@ -111,7 +462,9 @@ impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
         &self,
         key_ranges: &[StoreKeyRange],
     ) -> Result<Vec<MaybeBytes>, StorageError> {
-        let values = self.storage.get_partial_values(key_ranges)?;
+        let values = self.timed(StorageOperation::GetPartialValues, || {
+            self.storage.get_partial_values(key_ranges)
+        })?;
         let bytes_read = values
             .iter()
             .map(|value| value.as_ref().map_or(0, Bytes::len))
@@ -122,7 +475,7 @@ impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
     }
 
     fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
-        self.storage.size_key(key)
+        self.timed(StorageOperation::SizeKey, || self.storage.size_key(key))
     }
 }
 
@@ -130,23 +483,27 @@ impl<TStorage: ?Sized + ListableStorageTraits> ListableStorageTraits
     for PerformanceMetricsStorageAdapter<TStorage>
 {
     fn list(&self) -> Result<StoreKeys, StorageError> {
-        self.storage.list()
+        self.timed(StorageOperation::List, || self.storage.list())
     }
 
     fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
-        self.storage.list_prefix(prefix)
+        self.timed(StorageOperation::ListPrefix, || {
+            self.storage.list_prefix(prefix)
+        })
     }
 
     fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
-        self.storage.list_dir(prefix)
+        self.timed(StorageOperation::ListDir, || self.storage.list_dir(prefix))
     }
 
     fn size(&self) -> Result<u64, StorageError> {
-        self.storage.size()
+        self.timed(StorageOperation::Size, || self.storage.size())
     }
 
     fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
-        self.storage.size_prefix(prefix)
+        self.timed(StorageOperation::SizePrefix, || {
+            self.storage.size_prefix(prefix)
+        })
     }
 }
 
@@ -156,7 +513,7 @@ impl<TStorage: ?Sized + WritableStorageTraits> WritableStorageTraits
     fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
         self.bytes_written.fetch_add(value.len(), Ordering::Relaxed);
         self.writes.fetch_add(1, Ordering::Relaxed);
-        self.storage.set(key, value)
+        self.timed(StorageOperation::Set, || self.storage.set(key, value))
     }
 
     fn set_partial_values(
@@ -171,21 +528,27 @@ impl<TStorage: ?Sized + WritableStorageTraits> WritableStorageTraits
             .fetch_add(bytes_written, Ordering::Relaxed);
         self.writes
             .fetch_add(key_offset_values.len(), Ordering::Relaxed);
-        self.storage.set_partial_values(key_offset_values)
+        self.timed(StorageOperation::SetPartialValues, || {
+            self.storage.set_partial_values(key_offset_values)
+        })
     }
 
     fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
         self.keys_erased.fetch_add(1, Ordering::Relaxed);
-        self.storage.erase(key)
+        self.timed(StorageOperation::Erase, || self.storage.erase(key))
     }
 
     fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
         self.keys_erased.fetch_add(keys.len(), Ordering::Relaxed);
-        self.storage.erase_values(keys)
+        self.timed(StorageOperation::EraseValues, || {
+            self.storage.erase_values(keys)
+        })
     }
 
     fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
-        self.storage.erase_prefix(prefix)
+        self.timed(StorageOperation::ErasePrefix, || {
+            self.storage.erase_prefix(prefix)
+        })
     }
 }
 
@@ -195,7 +558,9 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits> AsyncReadableStorageTraits
     for PerformanceMetricsStorageAdapter<TStorage>
 {
     async fn get(&self, key: &StoreKey) -> Result<MaybeAsyncBytes, StorageError> {
-        let value = self.storage.get(key).await;
+        let value = self
+            .timed_async(StorageOperation::Get, self.storage.get(key))
+            .await;
         let bytes_read = value
             .as_ref()
             .map_or(0, |v| v.as_ref().map_or(0, AsyncBytes::len));
@@ -210,8 +575,10 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits> AsyncReadableStorageTraits
         byte_ranges: &[crate::byte_range::ByteRange],
     ) -> Result<Option<Vec<AsyncBytes>>, StorageError> {
         let values = self
-            .storage
-            .get_partial_values_key(key, byte_ranges)
+            .timed_async(
+                StorageOperation::GetPartialValuesKey,
+                self.storage.get_partial_values_key(key, byte_ranges),
+            )
             .await?;
         if let Some(values) = &values {
             let bytes_read = values.iter().map(AsyncBytes::len).sum();
@@ -225,7 +592,12 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits> AsyncReadableStorageTraits
         &self,
         key_ranges: &[StoreKeyRange],
     ) -> Result<Vec<MaybeAsyncBytes>, StorageError> {
-        let values = self.storage.get_partial_values(key_ranges).await?;
+        let values = self
+            .timed_async(
+                StorageOperation::GetPartialValues,
+                self.storage.get_partial_values(key_ranges),
+            )
+            .await?;
         let bytes_read = values
             .iter()
             .map(|value| value.as_ref().map_or(0, AsyncBytes::len))
@@ -236,7 +608,8 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits> AsyncReadableStorageTraits
     }
 
     async fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
-        self.storage.size_key(key).await
+        self.timed_async(StorageOperation::SizeKey, self.storage.size_key(key))
+            .await
     }
 }
 
@@ -246,23 +619,34 @@ impl<TStorage: ?Sized + AsyncListableStorageTraits> AsyncListableStorageTraits
     for PerformanceMetricsStorageAdapter<TStorage>
 {
     async fn list(&self) -> Result<StoreKeys, StorageError> {
-        self.storage.list().await
+        self.timed_async(StorageOperation::List, self.storage.list())
+            .await
     }
 
     async fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
-        self.storage.list_prefix(prefix).await
+        self.timed_async(
+            StorageOperation::ListPrefix,
+            self.storage.list_prefix(prefix),
+        )
+        .await
     }
 
     async fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
-        self.storage.list_dir(prefix).await
+        self.timed_async(StorageOperation::ListDir, self.storage.list_dir(prefix))
+            .await
     }
 
     async fn size(&self) -> Result<u64, StorageError> {
-        self.storage.size().await
+        self.timed_async(StorageOperation::Size, self.storage.size())
+            .await
     }
 
     async fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
-        self.storage.size_prefix(prefix).await
+        self.timed_async(
+            StorageOperation::SizePrefix,
+            self.storage.size_prefix(prefix),
+        )
+        .await
     }
 }
 
@@ -274,7 +658,8 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits> AsyncWritableStorageTraits
     async fn set(&self, key: &StoreKey, value: AsyncBytes) -> Result<(), StorageError> {
         self.bytes_written.fetch_add(value.len(), Ordering::Relaxed);
         self.writes.fetch_add(1, Ordering::Relaxed);
-        self.storage.set(key, value).await
+        self.timed_async(StorageOperation::Set, self.storage.set(key, value))
+            .await
     }
 
     async fn set_partial_values(
@@ -289,18 +674,89 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits> AsyncWritableStorageTraits
             .fetch_add(bytes_written, Ordering::Relaxed);
         self.writes
             .fetch_add(key_offset_values.len(), Ordering::Relaxed);
-        self.storage.set_partial_values(key_offset_values).await
+        self.timed_async(
+            StorageOperation::SetPartialValues,
+            self.storage.set_partial_values(key_offset_values),
+        )
+        .await
     }
 
     async fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
-        self.storage.erase(key).await
+        self.timed_async(StorageOperation::Erase, self.storage.erase(key))
+            .await
     }
 
     async fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
-        self.storage.erase_values(keys).await
+        self.timed_async(
+            StorageOperation::EraseValues,
+            self.storage.erase_values(keys),
+        )
+        .await
     }
 
     async fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
-        self.storage.erase_prefix(prefix).await
+        self.timed_async(
+            StorageOperation::ErasePrefix,
+            self.storage.erase_prefix(prefix),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::MemoryStore;
+
+    #[test]
+    fn performance_metrics() -> Result<(), Box<dyn std::error::Error>> {
+        let store = PerformanceMetricsStorageAdapter::new(Arc::new(MemoryStore::new()));
+        crate::store_test::store_write(&store)?;
+        crate::store_test::store_read(&store)?;
+        crate::store_test::store_list(&store)?;
+
+        let snapshot = store.snapshot();
+        assert_eq!(snapshot.operations.len(), StorageOperation::ALL.len());
+        assert_eq!(snapshot.bytes_read, store.bytes_read());
+        assert!(snapshot.operations[&StorageOperation::Set].calls > 0);
+        assert!(snapshot.operations[&StorageOperation::Get].calls > 0);
+        for metrics in snapshot.operations.values() {
+            assert_eq!(metrics.latency.count(), metrics.calls);
+            assert_eq!(
+                metrics.latency.bucket_counts.len(),
+                LATENCY_BUCKETS.len() + 1
+            );
+        }
+
+        store.reset();
+        let snapshot = store.snapshot();
+        assert!(snapshot
+            .operations
+            .values()
+            .all(|metrics| metrics.calls == 0 && metrics.latency.mean().is_none()));
+        Ok(())
+    }
+
+    #[test]
+    fn performance_metrics_prometheus() -> Result<(), Box<dyn std::error::Error>> {
+        let store = PerformanceMetricsStorageAdapter::new(Arc::new(MemoryStore::new()));
+        store.set(&"a".try_into()?, vec![0, 1, 2].into())?;
+        store.get(&"a".try_into()?)?;
+        store.get(&"b".try_into()?)?;
+
+        let get = store.operation(StorageOperation::Get);
+        assert_eq!(get.calls, 2);
+        assert_eq!(get.errors, 0);
+
+        let text = store.snapshot().to_prometheus("zarrs");
+        assert!(text.contains("zarrs_bytes_written_total 3\n"));
+        assert!(text.contains("zarrs_operations_total{operation=\"get\"} 2\n"));
+        assert!(text.contains("zarrs_operations_total{operation=\"set\"} 1\n"));
+        assert!(text.contains("# TYPE zarrs_operation_duration_seconds histogram\n"));
+        assert!(text.contains(
+            "zarrs_operation_duration_seconds_bucket{operation=\"get\",le=\"+Inf\"} 2\n"
+        ));
+        assert!(text.contains("zarrs_operation_duration_seconds_count{operation=\"get\"} 2\n"));
+        Ok(())
     }
 }
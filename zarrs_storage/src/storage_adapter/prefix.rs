@@ -0,0 +1,451 @@
+//! A storage adapter which roots all operations under a store prefix.
+
+use std::sync::Arc;
+
+use crate::{
+    byte_range::ByteRange, store_lock::StoreKeyLockGuard, Bytes, ListableStorageTraits, MaybeBytes,
+    ReadableStorageTraits, StorageError, StoreKey, StoreKeyOffsetValue, StoreKeyRange, StoreKeys,
+    StoreKeysPrefixes, StorePrefix, StorePrefixes, StoreValueReader, StoreValueValidator,
+    StoreValueWriter, WritableStorageTraits,
+};
+
+#[cfg(feature = "async")]
+use crate::{
+    AsyncBytes, AsyncListableStorageTraits, AsyncReadableStorageTraits, AsyncStoreValueStream,
+    AsyncWritableStorageTraits, MaybeAsyncBytes,
+};
+
+/// The prefix storage adapter. Roots all operations under a [`StorePrefix`] of the underlying store.
+///
+/// Keys and prefixes are prepended with the prefix before being passed to the underlying store, and the prefix is stripped from keys and prefixes returned by listing.
+/// This allows a single store (e.g. a bucket) to host many independent Zarr hierarchies that cannot access each other.
+///
+/// ```rust
+/// # use std::sync::Arc;
+/// # use zarrs_storage::{ListableStorageTraits, ReadableStorageTraits, WritableStorageTraits, StoreKey, StorePrefix};
+/// # use zarrs_storage::store::MemoryStore;
+/// use zarrs_storage::storage_adapter::prefix::PrefixStorageAdapter;
+/// let store = Arc::new(MemoryStore::new());
+/// let scoped = PrefixStorageAdapter::new(store.clone(), StorePrefix::new("hierarchy/")?);
+/// scoped.set(&StoreKey::new("zarr.json")?, vec![].into())?;
+/// assert_eq!(store.list()?, vec![StoreKey::new("hierarchy/zarr.json")?]);
+/// assert_eq!(scoped.list()?, vec![StoreKey::new("zarr.json")?]);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct PrefixStorageAdapter<TStorage: ?Sized> {
+    storage: Arc<TStorage>,
+    prefix: StorePrefix,
+}
+
+impl<TStorage: ?Sized> core::fmt::Debug for PrefixStorageAdapter<TStorage> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "prefix ({})", self.prefix)
+    }
+}
+
+impl<TStorage: ?Sized> PrefixStorageAdapter<TStorage> {
+    /// Create a new prefix storage adapter rooting all operations on `storage` under `prefix`.
+    #[must_use]
+    pub fn new(storage: Arc<TStorage>, prefix: StorePrefix) -> Self {
+        Self { storage, prefix }
+    }
+
+    /// Return the prefix.
+    #[must_use]
+    pub const fn prefix(&self) -> &StorePrefix {
+        &self.prefix
+    }
+
+    fn to_inner_key(&self, key: &StoreKey) -> StoreKey {
+        StoreKey::new(self.prefix.as_str().to_string() + key.as_str())
+            .expect("a prefix followed by a key is a valid key")
+    }
+
+    fn to_inner_prefix(&self, prefix: &StorePrefix) -> StorePrefix {
+        StorePrefix::new(self.prefix.as_str().to_string() + prefix.as_str())
+            .expect("a prefix followed by a prefix is a valid prefix")
+    }
+
+    fn strip_prefix_str(&self, inner: &str) -> Result<String, StorageError> {
+        inner
+            .strip_prefix(self.prefix.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| {
+                StorageError::Other(format!(
+                    "the underlying store returned {inner}, which is not under the prefix {}",
+                    self.prefix
+                ))
+            })
+    }
+
+    fn strip_keys(&self, keys: &[StoreKey]) -> Result<StoreKeys, StorageError> {
+        keys.iter()
+            .map(|key| Ok(StoreKey::new(self.strip_prefix_str(key.as_str())?)?))
+            .collect()
+    }
+
+    fn strip_prefixes(&self, prefixes: &[StorePrefix]) -> Result<StorePrefixes, StorageError> {
+        prefixes
+            .iter()
+            .map(|prefix| Ok(StorePrefix::new(self.strip_prefix_str(prefix.as_str())?)?))
+            .collect()
+    }
+
+    fn strip_keys_prefixes(
+        &self,
+        keys_prefixes: &StoreKeysPrefixes,
+    ) -> Result<StoreKeysPrefixes, StorageError> {
+        Ok(StoreKeysPrefixes::new(
+            self.strip_keys(keys_prefixes.keys())?,
+            self.strip_prefixes(keys_prefixes.prefixes())?,
+        ))
+    }
+
+    fn to_inner_key_ranges(&self, key_ranges: &[StoreKeyRange]) -> Vec<StoreKeyRange> {
+        key_ranges
+            .iter()
+            .map(|key_range| {
+                StoreKeyRange::new(self.to_inner_key(&key_range.key), key_range.byte_range)
+            })
+            .collect()
+    }
+
+    fn to_inner_key_offset_values<'a>(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue<'a>],
+    ) -> Vec<StoreKeyOffsetValue<'a>> {
+        key_offset_values
+            .iter()
+            .map(|key_offset_value| StoreKeyOffsetValue {
+                key: self.to_inner_key(key_offset_value.key()),
+                offset: key_offset_value.offset(),
+                value: key_offset_value.value,
+            })
+            .collect()
+    }
+
+    fn to_inner_keys(&self, keys: &[StoreKey]) -> Vec<StoreKey> {
+        keys.iter().map(|key| self.to_inner_key(key)).collect()
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
+    for PrefixStorageAdapter<TStorage>
+{
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        self.storage.get(&self.to_inner_key(key))
+    }
+
+    fn get_many(&self, keys: &[StoreKey]) -> Result<Vec<MaybeBytes>, StorageError> {
+        self.storage.get_many(&self.to_inner_keys(keys))
+    }
+
+    fn get_reader(&self, key: &StoreKey) -> Result<Option<StoreValueReader<'_>>, StorageError> {
+        self.storage.get_reader(&self.to_inner_key(key))
+    }
+
+    fn get_with_etag(
+        &self,
+        key: &StoreKey,
+    ) -> Result<(MaybeBytes, Option<StoreValueValidator>), StorageError> {
+        self.storage.get_with_etag(&self.to_inner_key(key))
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        self.storage
+            .get_partial_values_key(&self.to_inner_key(key), byte_ranges)
+    }
+
+    fn get_partial_values(
+        &self,
+        key_ranges: &[StoreKeyRange],
+    ) -> Result<Vec<MaybeBytes>, StorageError> {
+        self.storage
+            .get_partial_values(&self.to_inner_key_ranges(key_ranges))
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        self.storage.size_key(&self.to_inner_key(key))
+    }
+}
+
+impl<TStorage: ?Sized + ListableStorageTraits> ListableStorageTraits
+    for PrefixStorageAdapter<TStorage>
+{
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.strip_keys(&self.storage.list_prefix(&self.prefix)?)
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.strip_keys(&self.storage.list_prefix(&self.to_inner_prefix(prefix))?)
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.strip_keys_prefixes(&self.storage.list_dir(&self.to_inner_prefix(prefix))?)
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.storage.size_prefix(&self.to_inner_prefix(prefix))
+    }
+
+    fn size(&self) -> Result<u64, StorageError> {
+        self.storage.size_prefix(&self.prefix)
+    }
+}
+
+impl<TStorage: ?Sized + WritableStorageTraits> WritableStorageTraits
+    for PrefixStorageAdapter<TStorage>
+{
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        self.storage.set(&self.to_inner_key(key), value)
+    }
+
+    fn set_writer(&self, key: &StoreKey) -> Result<Box<dyn StoreValueWriter + '_>, StorageError> {
+        self.storage.set_writer(&self.to_inner_key(key))
+    }
+
+    fn supports_set_if_match(&self) -> bool {
+        self.storage.supports_set_if_match()
+    }
+
+    fn set_if_match(
+        &self,
+        key: &StoreKey,
+        value: Bytes,
+        etag: Option<&StoreValueValidator>,
+    ) -> Result<Option<StoreValueValidator>, StorageError> {
+        self.storage
+            .set_if_match(&self.to_inner_key(key), value, etag)
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        self.storage
+            .set_partial_values(&self.to_inner_key_offset_values(key_offset_values))
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.storage.erase(&self.to_inner_key(key))
+    }
+
+    fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        self.storage.erase_values(&self.to_inner_keys(keys))
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.storage.erase_prefix(&self.to_inner_prefix(prefix))
+    }
+
+    fn supports_rename_prefix(&self) -> bool {
+        self.storage.supports_rename_prefix()
+    }
+
+    fn rename_prefix(&self, from: &StorePrefix, to: &StorePrefix) -> Result<(), StorageError> {
+        self.storage
+            .rename_prefix(&self.to_inner_prefix(from), &self.to_inner_prefix(to))
+    }
+
+    fn lock_key(&self, key: &StoreKey) -> Result<StoreKeyLockGuard<'_>, StorageError> {
+        self.storage.lock_key(&self.to_inner_key(key))
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncReadableStorageTraits> AsyncReadableStorageTraits
+    for PrefixStorageAdapter<TStorage>
+{
+    async fn get(&self, key: &StoreKey) -> Result<MaybeAsyncBytes, StorageError> {
+        self.storage.get(&self.to_inner_key(key)).await
+    }
+
+    async fn get_many(&self, keys: &[StoreKey]) -> Result<Vec<MaybeAsyncBytes>, StorageError> {
+        self.storage.get_many(&self.to_inner_keys(keys)).await
+    }
+
+    async fn get_stream(
+        &self,
+        key: &StoreKey,
+    ) -> Result<Option<AsyncStoreValueStream<'_>>, StorageError> {
+        self.storage.get_stream(&self.to_inner_key(key)).await
+    }
+
+    async fn get_with_etag(
+        &self,
+        key: &StoreKey,
+    ) -> Result<(MaybeAsyncBytes, Option<StoreValueValidator>), StorageError> {
+        self.storage.get_with_etag(&self.to_inner_key(key)).await
+    }
+
+    async fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<AsyncBytes>>, StorageError> {
+        self.storage
+            .get_partial_values_key(&self.to_inner_key(key), byte_ranges)
+            .await
+    }
+
+    async fn get_partial_values(
+        &self,
+        key_ranges: &[StoreKeyRange],
+    ) -> Result<Vec<MaybeAsyncBytes>, StorageError> {
+        self.storage
+            .get_partial_values(&self.to_inner_key_ranges(key_ranges))
+            .await
+    }
+
+    async fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        self.storage.size_key(&self.to_inner_key(key)).await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncListableStorageTraits> AsyncListableStorageTraits
+    for PrefixStorageAdapter<TStorage>
+{
+    async fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.strip_keys(&self.storage.list_prefix(&self.prefix).await?)
+    }
+
+    async fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.strip_keys(
+            &self
+                .storage
+                .list_prefix(&self.to_inner_prefix(prefix))
+                .await?,
+        )
+    }
+
+    async fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.strip_keys_prefixes(&self.storage.list_dir(&self.to_inner_prefix(prefix)).await?)
+    }
+
+    async fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.storage
+            .size_prefix(&self.to_inner_prefix(prefix))
+            .await
+    }
+
+    async fn size(&self) -> Result<u64, StorageError> {
+        self.storage.size_prefix(&self.prefix).await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncWritableStorageTraits> AsyncWritableStorageTraits
+    for PrefixStorageAdapter<TStorage>
+{
+    async fn set(&self, key: &StoreKey, value: AsyncBytes) -> Result<(), StorageError> {
+        self.storage.set(&self.to_inner_key(key), value).await
+    }
+
+    async fn set_stream<'a>(
+        &self,
+        key: &StoreKey,
+        value: AsyncStoreValueStream<'a>,
+    ) -> Result<(), StorageError> {
+        self.storage
+            .set_stream(&self.to_inner_key(key), value)
+            .await
+    }
+
+    fn supports_set_if_match(&self) -> bool {
+        self.storage.supports_set_if_match()
+    }
+
+    async fn set_if_match(
+        &self,
+        key: &StoreKey,
+        value: AsyncBytes,
+        etag: Option<&StoreValueValidator>,
+    ) -> Result<Option<StoreValueValidator>, StorageError> {
+        self.storage
+            .set_if_match(&self.to_inner_key(key), value, etag)
+            .await
+    }
+
+    async fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        self.storage
+            .set_partial_values(&self.to_inner_key_offset_values(key_offset_values))
+            .await
+    }
+
+    async fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.storage.erase(&self.to_inner_key(key)).await
+    }
+
+    async fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        self.storage.erase_values(&self.to_inner_keys(keys)).await
+    }
+
+    async fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.storage
+            .erase_prefix(&self.to_inner_prefix(prefix))
+            .await
+    }
+
+    fn supports_rename_prefix(&self) -> bool {
+        self.storage.supports_rename_prefix()
+    }
+
+    async fn rename_prefix(
+        &self,
+        from: &StorePrefix,
+        to: &StorePrefix,
+    ) -> Result<(), StorageError> {
+        self.storage
+            .rename_prefix(&self.to_inner_prefix(from), &self.to_inner_prefix(to))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::MemoryStore;
+    use std::error::Error;
+
+    #[test]
+    fn prefix() -> Result<(), Box<dyn Error>> {
+        let store = Arc::new(MemoryStore::new());
+        let other = StoreKey::new("other/zarr.json")?;
+        store.set(&other, vec![0].into())?;
+
+        let scoped = PrefixStorageAdapter::new(store.clone(), StorePrefix::new("a/b/")?);
+        crate::store_test::store_write(&scoped)?;
+        crate::store_test::store_read(&scoped)?;
+        crate::store_test::store_list(&scoped)?;
+        let prefix = StorePrefix::new("a/b/")?;
+        assert!(store
+            .list()?
+            .iter()
+            .all(|key| key == &other || key.has_prefix(&prefix)));
+
+        scoped.erase_prefix(&StorePrefix::root())?;
+        assert_eq!(store.list()?, vec![other]);
+        Ok(())
+    }
+
+    #[test]
+    fn prefix_root() -> Result<(), Box<dyn Error>> {
+        let store = Arc::new(MemoryStore::new());
+        let scoped = PrefixStorageAdapter::new(store, StorePrefix::root());
+        crate::store_test::store_write(&scoped)?;
+        crate::store_test::store_read(&scoped)?;
+        crate::store_test::store_list(&scoped)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,477 @@
+//! A storage adapter which emits [`tracing`](https://docs.rs/tracing/latest/tracing/) spans and events.
+
+use std::{sync::Arc, time::Instant};
+
+use ::tracing::{debug, debug_span, error};
+
+use crate::{
+    byte_range::ByteRange, Bytes, ListableStorageTraits, MaybeBytes, ReadableStorageTraits,
+    StorageError, StoreKey, StoreKeyOffsetValue, StoreKeyRange, StoreKeys, StoreKeysPrefixes,
+    StorePrefix, WritableStorageTraits,
+};
+
+#[cfg(feature = "async")]
+use crate::{
+    AsyncBytes, AsyncListableStorageTraits, AsyncReadableStorageTraits, AsyncWritableStorageTraits,
+    MaybeAsyncBytes,
+};
+
+#[cfg(feature = "async")]
+use ::tracing::Instrument;
+
+/// The tracing storage adapter. Emits [`tracing`](https://docs.rs/tracing/latest/tracing/) spans and events around storage method calls.
+///
+/// Each storage method call is wrapped in a `DEBUG` span named after the method, with fields identifying the request (e.g. `key`, `byte_ranges`, `prefix`).
+/// A `DEBUG` event with the `latency_us` and `bytes` read or written (where applicable) is emitted when the call succeeds, or an `ERROR` event with the `error` if it fails.
+///
+/// This allows storage access to appear in distributed traces alongside application spans.
+///
+/// ```rust
+/// # use std::sync::Arc;
+/// # use zarrs_storage::store::MemoryStore;
+/// use zarrs_storage::storage_adapter::tracing::TracingStorageAdapter;
+/// let store = Arc::new(MemoryStore::new());
+/// let store = Arc::new(TracingStorageAdapter::new(store));
+/// ```
+pub struct TracingStorageAdapter<TStorage: ?Sized> {
+    storage: Arc<TStorage>,
+}
+
+impl<TStorage: ?Sized> core::fmt::Debug for TracingStorageAdapter<TStorage> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "tracing")
+    }
+}
+
+impl<TStorage: ?Sized> TracingStorageAdapter<TStorage> {
+    /// Create a new tracing storage adapter.
+    #[must_use]
+    pub fn new(storage: Arc<TStorage>) -> Self {
+        Self { storage }
+    }
+}
+
+/// Emit an event for the `result` of a storage method call that started at `start`.
+///
+/// `bytes` returns the number of bytes read or written, if applicable.
+fn traced<T>(
+    start: Instant,
+    result: Result<T, StorageError>,
+    bytes: impl FnOnce(&T) -> Option<usize>,
+) -> Result<T, StorageError> {
+    let latency_us = u64::try_from(start.elapsed().as_micros()).unwrap_or(u64::MAX);
+    match &result {
+        Ok(value) => {
+            if let Some(bytes) = bytes(value) {
+                debug!(latency_us, bytes, "ok");
+            } else {
+                debug!(latency_us, "ok");
+            }
+        }
+        Err(err) => error!(latency_us, error = %err, "failed"),
+    }
+    result
+}
+
+fn maybe_bytes_len(value: Option<&Bytes>) -> usize {
+    value.map_or(0, Bytes::len)
+}
+
+fn bytes_len(values: &[Bytes]) -> usize {
+    values.iter().map(Bytes::len).sum()
+}
+
+fn maybe_bytes_vec_len(values: &[MaybeBytes]) -> usize {
+    values
+        .iter()
+        .map(|value| maybe_bytes_len(value.as_ref()))
+        .sum()
+}
+
+fn key_offset_values_len(key_offset_values: &[StoreKeyOffsetValue]) -> usize {
+    key_offset_values
+        .iter()
+        .map(|key_offset_value| key_offset_value.value().len())
+        .sum()
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
+    for TracingStorageAdapter<TStorage>
+{
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        let _span = debug_span!("get", %key).entered();
+        traced(Instant::now(), self.storage.get(key), |value| {
+            Some(maybe_bytes_len(value.as_ref()))
+        })
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        let _span = debug_span!("get_partial_values_key", %key, ?byte_ranges).entered();
+        traced(
+            Instant::now(),
+            self.storage.get_partial_values_key(key, byte_ranges),
+            |values| Some(values.as_deref().map_or(0, bytes_len)),
+        )
+    }
+
+    fn get_partial_values(
+        &self,
+        key_ranges: &[StoreKeyRange],
+    ) -> Result<Vec<MaybeBytes>, StorageError> {
+        let _span = debug_span!("get_partial_values", key_ranges = key_ranges.len()).entered();
+        traced(
+            Instant::now(),
+            self.storage.get_partial_values(key_ranges),
+            |values| Some(maybe_bytes_vec_len(values)),
+        )
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        let _span = debug_span!("size_key", %key).entered();
+        traced(Instant::now(), self.storage.size_key(key), |_| None)
+    }
+}
+
+impl<TStorage: ?Sized + ListableStorageTraits> ListableStorageTraits
+    for TracingStorageAdapter<TStorage>
+{
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        let _span = debug_span!("list").entered();
+        traced(Instant::now(), self.storage.list(), |_| None)
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        let _span = debug_span!("list_prefix", %prefix).entered();
+        traced(Instant::now(), self.storage.list_prefix(prefix), |_| None)
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        let _span = debug_span!("list_dir", %prefix).entered();
+        traced(Instant::now(), self.storage.list_dir(prefix), |_| None)
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        let _span = debug_span!("size_prefix", %prefix).entered();
+        traced(Instant::now(), self.storage.size_prefix(prefix), |_| None)
+    }
+
+    fn size(&self) -> Result<u64, StorageError> {
+        let _span = debug_span!("size").entered();
+        traced(Instant::now(), self.storage.size(), |_| None)
+    }
+}
+
+impl<TStorage: ?Sized + WritableStorageTraits> WritableStorageTraits
+    for TracingStorageAdapter<TStorage>
+{
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        let _span = debug_span!("set", %key).entered();
+        let bytes = value.len();
+        traced(Instant::now(), self.storage.set(key, value), |()| {
+            Some(bytes)
+        })
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        let _span = debug_span!(
+            "set_partial_values",
+            key_offset_values = key_offset_values.len()
+        )
+        .entered();
+        traced(
+            Instant::now(),
+            self.storage.set_partial_values(key_offset_values),
+            |()| Some(key_offset_values_len(key_offset_values)),
+        )
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        let _span = debug_span!("erase", %key).entered();
+        traced(Instant::now(), self.storage.erase(key), |()| None)
+    }
+
+    fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        let _span = debug_span!("erase_values", keys = keys.len()).entered();
+        traced(Instant::now(), self.storage.erase_values(keys), |()| None)
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        let _span = debug_span!("erase_prefix", %prefix).entered();
+        traced(Instant::now(), self.storage.erase_prefix(prefix), |()| None)
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncReadableStorageTraits> AsyncReadableStorageTraits
+    for TracingStorageAdapter<TStorage>
+{
+    async fn get(&self, key: &StoreKey) -> Result<MaybeAsyncBytes, StorageError> {
+        async {
+            traced(Instant::now(), self.storage.get(key).await, |value| {
+                Some(maybe_bytes_len(value.as_ref()))
+            })
+        }
+        .instrument(debug_span!("get", %key))
+        .await
+    }
+
+    async fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<AsyncBytes>>, StorageError> {
+        async {
+            traced(
+                Instant::now(),
+                self.storage.get_partial_values_key(key, byte_ranges).await,
+                |values| Some(values.as_deref().map_or(0, bytes_len)),
+            )
+        }
+        .instrument(debug_span!("get_partial_values_key", %key, ?byte_ranges))
+        .await
+    }
+
+    async fn get_partial_values(
+        &self,
+        key_ranges: &[StoreKeyRange],
+    ) -> Result<Vec<MaybeAsyncBytes>, StorageError> {
+        async {
+            traced(
+                Instant::now(),
+                self.storage.get_partial_values(key_ranges).await,
+                |values| Some(maybe_bytes_vec_len(values)),
+            )
+        }
+        .instrument(debug_span!(
+            "get_partial_values",
+            key_ranges = key_ranges.len()
+        ))
+        .await
+    }
+
+    async fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        async { traced(Instant::now(), self.storage.size_key(key).await, |_| None) }
+            .instrument(debug_span!("size_key", %key))
+            .await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncListableStorageTraits> AsyncListableStorageTraits
+    for TracingStorageAdapter<TStorage>
+{
+    async fn list(&self) -> Result<StoreKeys, StorageError> {
+        async { traced(Instant::now(), self.storage.list().await, |_| None) }
+            .instrument(debug_span!("list"))
+            .await
+    }
+
+    async fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        async {
+            traced(
+                Instant::now(),
+                self.storage.list_prefix(prefix).await,
+                |_| None,
+            )
+        }
+        .instrument(debug_span!("list_prefix", %prefix))
+        .await
+    }
+
+    async fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        async {
+            traced(Instant::now(), self.storage.list_dir(prefix).await, |_| {
+                None
+            })
+        }
+        .instrument(debug_span!("list_dir", %prefix))
+        .await
+    }
+
+    async fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        async {
+            traced(
+                Instant::now(),
+                self.storage.size_prefix(prefix).await,
+                |_| None,
+            )
+        }
+        .instrument(debug_span!("size_prefix", %prefix))
+        .await
+    }
+
+    async fn size(&self) -> Result<u64, StorageError> {
+        async { traced(Instant::now(), self.storage.size().await, |_| None) }
+            .instrument(debug_span!("size"))
+            .await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncWritableStorageTraits> AsyncWritableStorageTraits
+    for TracingStorageAdapter<TStorage>
+{
+    async fn set(&self, key: &StoreKey, value: AsyncBytes) -> Result<(), StorageError> {
+        let bytes = value.len();
+        async {
+            traced(Instant::now(), self.storage.set(key, value).await, |()| {
+                Some(bytes)
+            })
+        }
+        .instrument(debug_span!("set", %key))
+        .await
+    }
+
+    async fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        async {
+            traced(
+                Instant::now(),
+                self.storage.set_partial_values(key_offset_values).await,
+                |()| Some(key_offset_values_len(key_offset_values)),
+            )
+        }
+        .instrument(debug_span!(
+            "set_partial_values",
+            key_offset_values = key_offset_values.len()
+        ))
+        .await
+    }
+
+    async fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        async { traced(Instant::now(), self.storage.erase(key).await, |()| None) }
+            .instrument(debug_span!("erase", %key))
+            .await
+    }
+
+    async fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        async {
+            traced(
+                Instant::now(),
+                self.storage.erase_values(keys).await,
+                |()| None,
+            )
+        }
+        .instrument(debug_span!("erase_values", keys = keys.len()))
+        .await
+    }
+
+    async fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        async {
+            traced(
+                Instant::now(),
+                self.storage.erase_prefix(prefix).await,
+                |()| None,
+            )
+        }
+        .instrument(debug_span!("erase_prefix", %prefix))
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::MemoryStore;
+    use std::{
+        error::Error,
+        sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    };
+
+    use ::tracing::{
+        field::{Field, Visit},
+        span, Event, Metadata, Subscriber,
+    };
+
+    #[derive(Default)]
+    struct Counts {
+        spans: AtomicU64,
+        events: AtomicUsize,
+        errors: AtomicUsize,
+        bytes: AtomicU64,
+    }
+
+    /// A subscriber which counts spans and events, and sums the `bytes` field of events.
+    struct CountingSubscriber(Arc<Counts>);
+
+    struct BytesVisitor<'a>(&'a AtomicU64);
+
+    impl Visit for BytesVisitor<'_> {
+        fn record_u64(&mut self, field: &Field, value: u64) {
+            if field.name() == "bytes" {
+                self.0.fetch_add(value, Ordering::Relaxed);
+            }
+        }
+
+        fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+    }
+
+    impl Subscriber for CountingSubscriber {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _span: &span::Attributes<'_>) -> span::Id {
+            span::Id::from_u64(self.0.spans.fetch_add(1, Ordering::Relaxed) + 1)
+        }
+
+        fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            self.0.events.fetch_add(1, Ordering::Relaxed);
+            if *event.metadata().level() == ::tracing::Level::ERROR {
+                self.0.errors.fetch_add(1, Ordering::Relaxed);
+            }
+            event.record(&mut BytesVisitor(&self.0.bytes));
+        }
+
+        fn enter(&self, _span: &span::Id) {}
+
+        fn exit(&self, _span: &span::Id) {}
+    }
+
+    #[test]
+    fn tracing() -> Result<(), Box<dyn Error>> {
+        let counts = Arc::new(Counts::default());
+        ::tracing::subscriber::with_default(CountingSubscriber(counts.clone()), || {
+            let store = TracingStorageAdapter::new(Arc::new(MemoryStore::new()));
+            crate::store_test::store_write(&store)?;
+            crate::store_test::store_read(&store)?;
+            crate::store_test::store_list(&store)?;
+            Ok::<_, Box<dyn Error>>(())
+        })?;
+        let events = counts.events.load(Ordering::Relaxed);
+        assert!(events > 0);
+        assert_eq!(counts.spans.load(Ordering::Relaxed), events as u64);
+        assert!(counts.bytes.load(Ordering::Relaxed) > 0);
+
+        let bytes_before = counts.bytes.load(Ordering::Relaxed);
+        let errors_before = counts.errors.load(Ordering::Relaxed);
+        ::tracing::subscriber::with_default(CountingSubscriber(counts.clone()), || {
+            let store = TracingStorageAdapter::new(Arc::new(MemoryStore::new()));
+            let key = StoreKey::new("a")?;
+            store.set(&key, vec![0; 5].into())?;
+            assert!(store
+                .get_partial_values_key(&key, &[ByteRange::FromStart(4, Some(2))])
+                .is_err());
+            Ok::<_, Box<dyn Error>>(())
+        })?;
+        assert_eq!(counts.bytes.load(Ordering::Relaxed) - bytes_before, 5);
+        assert_eq!(counts.errors.load(Ordering::Relaxed) - errors_before, 1);
+        Ok(())
+    }
+}
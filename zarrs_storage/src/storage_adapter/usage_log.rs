@@ -1,8 +1,12 @@
 //! A storage transformer which prints function calls.
 
 use std::{
+    fmt::Write as _,
+    fs::{File, OpenOptions},
     io::Write,
-    sync::{Arc, Mutex},
+    path::PathBuf,
+    sync::{mpsc::Sender, Arc, Mutex},
+    time::{Duration, Instant},
 };
 
 use itertools::Itertools;
@@ -37,7 +41,7 @@ use crate::{
 /// let store = Arc::new(UsageLogStorageAdapter::new(store, log_writer, || {
 ///     chrono::Utc::now().format("[%T%.3f] ").to_string()
 /// }));
-/// ````
+/// ```
 ///
 /// Applying array methods with the above [`UsageLogStorageAdapter`] prints outputs like:
 /// ```text
@@ -52,10 +56,46 @@ use crate::{
 /// [23:41:19.891] get(group/array/zarr.json) -> len=Ok(1315)
 /// [23:41:19.892] list() -> [group/array/c/0/0, group/array/c/1/0, group/array/zarr.json, group/zarr.json]
 /// ```
+///
+/// ### Example (structured JSON lines with rotation)
+/// A [`UsageLogStorageAdapter`] created with [`new_with_sink`](UsageLogStorageAdapter::new_with_sink) emits a [`UsageLogRecord`] for each storage method call to a [`UsageLogSink`].
+/// Records can be post-processed, for example, to compute access heatmaps per chunk.
+/// ```rust
+/// # use std::sync::{Arc, Mutex};
+/// # use zarrs_storage::store::MemoryStore;
+/// # use zarrs_storage::storage_adapter::usage_log::{UsageLogJsonLinesSink, UsageLogRotatingFile, UsageLogStorageAdapter};
+/// # let dir = tempfile::TempDir::new()?;
+/// # let path = dir.path().join("usage.jsonl");
+/// let store = Arc::new(MemoryStore::new());
+/// // Rotate the log when it exceeds 16 MiB, keeping 4 rotated logs
+/// let log_writer = Arc::new(Mutex::new(UsageLogRotatingFile::new(path, 16 << 20, 4)?));
+/// let sink = Arc::new(UsageLogJsonLinesSink::new(log_writer));
+/// let store = Arc::new(UsageLogStorageAdapter::new_with_sink(store, sink));
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// This outputs records like:
+/// ```text
+/// {"op":"set","key":"group/array/c/1/0","bytes":140,"duration_ns":2750,"outcome":"ok"}
+/// {"op":"get_partial_values_key","key":"group/array/c/0/0","byte_ranges":[{"suffix":36}],"bytes":36,"duration_ns":1340,"outcome":"ok"}
+/// {"op":"get","key":"zarr.json","duration_ns":420,"outcome":"not_found"}
+/// {"op":"list_dir","prefix":"group/","duration_ns":3120,"outcome":"ok"}
+/// ```
+///
+/// Records are written by the thread calling the storage method.
+/// To avoid blocking storage methods on slow sinks, records can be sent to a [`Sender`] and written on another thread or task.
 pub struct UsageLogStorageAdapter<TStorage: ?Sized> {
     storage: Arc<TStorage>,
-    handle: Arc<Mutex<dyn Write + Send + Sync>>,
-    prefix_func: fn() -> String,
+    output: UsageLogOutput,
+}
+
+/// The output of a [`UsageLogStorageAdapter`].
+enum UsageLogOutput {
+    Text {
+        handle: Arc<Mutex<dyn Write + Send + Sync>>,
+        prefix_func: fn() -> String,
+    },
+    Sink(Arc<dyn UsageLogSink>),
 }
 
 impl<TStorage: ?Sized> core::fmt::Debug for UsageLogStorageAdapter<TStorage> {
@@ -73,9 +113,707 @@ impl<TStorage: ?Sized> UsageLogStorageAdapter<TStorage> {
     ) -> Self {
         Self {
             storage,
-            handle,
-            prefix_func,
+            output: UsageLogOutput::Text {
+                handle,
+                prefix_func,
+            },
+        }
+    }
+
+    /// Create a new usage log storage adapter which emits a [`UsageLogRecord`] for each storage method call to `sink`.
+    pub fn new_with_sink(storage: Arc<TStorage>, sink: Arc<dyn UsageLogSink>) -> Self {
+        Self {
+            storage,
+            output: UsageLogOutput::Sink(sink),
+        }
+    }
+
+    /// Log a storage method call which started at `start` as a line of `text` or `records`.
+    fn log(
+        &self,
+        start: Instant,
+        text: impl FnOnce() -> String,
+        records: impl FnOnce(Duration) -> Vec<UsageLogRecord>,
+    ) -> Result<(), StorageError> {
+        match &self.output {
+            UsageLogOutput::Text {
+                handle,
+                prefix_func,
+            } => {
+                writeln!(handle.lock().unwrap(), "{}{}", prefix_func(), text())?;
+            }
+            UsageLogOutput::Sink(sink) => {
+                for record in records(start.elapsed()) {
+                    sink.write_record(&record)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn log_get(
+        &self,
+        start: Instant,
+        key: &StoreKey,
+        result: &Result<MaybeBytes, StorageError>,
+    ) -> Result<(), StorageError> {
+        self.log(
+            start,
+            || {
+                format!(
+                    "get({key}) -> len={:?}",
+                    result.as_ref().map(|v| v.as_ref().map_or(0, Bytes::len))
+                )
+            },
+            |duration| {
+                vec![
+                    UsageLogRecord::new("get", duration, UsageLogOutcome::new_maybe(result))
+                        .with_key(key)
+                        .with_bytes(
+                            result
+                                .as_ref()
+                                .ok()
+                                .and_then(|v| v.as_ref().map(Bytes::len)),
+                        ),
+                ]
+            },
+        )
+    }
+
+    fn log_get_partial_values_key(
+        &self,
+        start: Instant,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+        result: &Result<Option<Vec<Bytes>>, StorageError>,
+    ) -> Result<(), StorageError> {
+        self.log(
+            start,
+            || {
+                format!(
+                    "get_partial_values_key({key}, [{}]) -> len={:?}",
+                    byte_ranges.iter().format(", "),
+                    result.as_ref().map(|v| {
+                        v.as_ref()
+                            .map_or(vec![], |v| v.iter().map(Bytes::len).collect_vec())
+                    })
+                )
+            },
+            |duration| {
+                let bytes = result
+                    .as_ref()
+                    .ok()
+                    .and_then(|v| v.as_ref().map(|v| v.iter().map(Bytes::len).sum()));
+                vec![UsageLogRecord::new(
+                    "get_partial_values_key",
+                    duration,
+                    UsageLogOutcome::new_maybe(result),
+                )
+                .with_key(key)
+                .with_byte_ranges(byte_ranges.to_vec())
+                .with_bytes(bytes)]
+            },
+        )
+    }
+
+    fn log_get_partial_values(
+        &self,
+        start: Instant,
+        key_ranges: &[StoreKeyRange],
+        result: &Result<Vec<MaybeBytes>, StorageError>,
+    ) -> Result<(), StorageError> {
+        self.log(
+            start,
+            || {
+                format!(
+                    "get_partial_values([{}]) -> len={:?}",
+                    key_ranges.iter().format(", "),
+                    result
+                        .as_ref()
+                        .map(|v| v.iter().map(|v| v.iter().map(Bytes::len).collect_vec()))
+                )
+            },
+            |duration| {
+                // A record for each run of consecutive byte ranges of the same key
+                let values = result.as_ref().ok();
+                let mut position = 0;
+                key_ranges
+                    .iter()
+                    .chunk_by(|key_range| &key_range.key)
+                    .into_iter()
+                    .map(|(key, key_ranges)| {
+                        let byte_ranges = key_ranges
+                            .map(|key_range| key_range.byte_range)
+                            .collect_vec();
+                        let range = position..position + byte_ranges.len();
+                        position = range.end;
+                        let values = values.and_then(|values| values.get(range));
+                        let outcome = match (result, values) {
+                            (Err(err), _) => UsageLogOutcome::Error(err.to_string()),
+                            (Ok(_), Some(values)) if values.iter().all(Option::is_none) => {
+                                UsageLogOutcome::NotFound
+                            }
+                            (Ok(_), _) => UsageLogOutcome::Ok,
+                        };
+                        let bytes =
+                            values.map(|values| values.iter().flatten().map(Bytes::len).sum());
+                        UsageLogRecord::new("get_partial_values", duration, outcome)
+                            .with_key(key)
+                            .with_byte_ranges(byte_ranges)
+                            .with_bytes(bytes)
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    fn log_size_key(
+        &self,
+        start: Instant,
+        key: &StoreKey,
+        result: &Result<Option<u64>, StorageError>,
+    ) -> Result<(), StorageError> {
+        self.log(
+            start,
+            || format!("size_key({key}) -> {result:?}"),
+            |duration| {
+                vec![
+                    UsageLogRecord::new("size_key", duration, UsageLogOutcome::new_maybe(result))
+                        .with_key(key),
+                ]
+            },
+        )
+    }
+
+    fn log_list(
+        &self,
+        start: Instant,
+        prefix: Option<&StorePrefix>,
+        result: &Result<StoreKeys, StorageError>,
+    ) -> Result<(), StorageError> {
+        let op = if prefix.is_some() {
+            "list_prefix"
+        } else {
+            "list"
+        };
+        self.log(
+            start,
+            || {
+                format!(
+                    "{op}({}) -> [{}]",
+                    prefix.map_or("", StorePrefix::as_str),
+                    result.as_ref().unwrap_or(&vec![]).iter().format(", ")
+                )
+            },
+            |duration| {
+                let record = UsageLogRecord::new(op, duration, UsageLogOutcome::new(result));
+                vec![match prefix {
+                    Some(prefix) => record.with_prefix(prefix),
+                    None => record,
+                }]
+            },
+        )
+    }
+
+    fn log_list_dir(
+        &self,
+        start: Instant,
+        prefix: &StorePrefix,
+        result: &Result<StoreKeysPrefixes, StorageError>,
+    ) -> Result<(), StorageError> {
+        self.log(
+            start,
+            || {
+                format!(
+                    "list_dir({prefix}) -> (keys:[{}], prefixes:[{}])",
+                    result.as_ref().map_or(String::new(), |skp| skp
+                        .keys()
+                        .iter()
+                        .format(", ")
+                        .to_string()),
+                    result.as_ref().map_or(String::new(), |skp| skp
+                        .prefixes()
+                        .iter()
+                        .format(", ")
+                        .to_string()),
+                )
+            },
+            |duration| {
+                vec![
+                    UsageLogRecord::new("list_dir", duration, UsageLogOutcome::new(result))
+                        .with_prefix(prefix),
+                ]
+            },
+        )
+    }
+
+    fn log_size(
+        &self,
+        start: Instant,
+        prefix: Option<&StorePrefix>,
+        result: &Result<u64, StorageError>,
+    ) -> Result<(), StorageError> {
+        let op = if prefix.is_some() {
+            "size_prefix"
+        } else {
+            "size"
+        };
+        self.log(
+            start,
+            || {
+                format!(
+                    "{op}({}) -> {result:?}",
+                    prefix.map_or("", StorePrefix::as_str)
+                )
+            },
+            |duration| {
+                let record = UsageLogRecord::new(op, duration, UsageLogOutcome::new(result));
+                vec![match prefix {
+                    Some(prefix) => record.with_prefix(prefix),
+                    None => record,
+                }]
+            },
+        )
+    }
+
+    fn log_set(
+        &self,
+        start: Instant,
+        key: &StoreKey,
+        len: usize,
+        result: &Result<(), StorageError>,
+    ) -> Result<(), StorageError> {
+        self.log(
+            start,
+            || format!("set({key}, len={len}) -> {result:?}"),
+            |duration| {
+                vec![
+                    UsageLogRecord::new("set", duration, UsageLogOutcome::new(result))
+                        .with_key(key)
+                        .with_bytes(Some(len)),
+                ]
+            },
+        )
+    }
+
+    fn log_set_partial_values(
+        &self,
+        start: Instant,
+        key_offset_values: &[StoreKeyOffsetValue],
+        result: &Result<(), StorageError>,
+    ) -> Result<(), StorageError> {
+        struct DebugStoreKeyOffsetValue<'a>(&'a StoreKeyOffsetValue<'a>);
+        impl core::fmt::Debug for DebugStoreKeyOffsetValue<'_> {
+            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                write!(
+                    f,
+                    "({} offset={} len={})",
+                    self.0.key(),
+                    self.0.offset(),
+                    self.0.value().len()
+                )
+            }
+        }
+        self.log(
+            start,
+            || {
+                format!(
+                    "set_partial_values({:?}) -> {result:?}",
+                    key_offset_values
+                        .iter()
+                        .map(DebugStoreKeyOffsetValue)
+                        .collect_vec()
+                )
+            },
+            |duration| {
+                key_offset_values
+                    .iter()
+                    .map(|key_offset_value| {
+                        let len = key_offset_value.value().len();
+                        UsageLogRecord::new(
+                            "set_partial_values",
+                            duration,
+                            UsageLogOutcome::new(result),
+                        )
+                        .with_key(key_offset_value.key())
+                        .with_byte_ranges(vec![ByteRange::FromStart(
+                            key_offset_value.offset(),
+                            Some(len as u64),
+                        )])
+                        .with_bytes(Some(len))
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    fn log_erase(
+        &self,
+        start: Instant,
+        key: &StoreKey,
+        result: &Result<(), StorageError>,
+    ) -> Result<(), StorageError> {
+        self.log(
+            start,
+            || format!("erase({key}) -> {result:?}"),
+            |duration| {
+                vec![
+                    UsageLogRecord::new("erase", duration, UsageLogOutcome::new(result))
+                        .with_key(key),
+                ]
+            },
+        )
+    }
+
+    fn log_erase_values(
+        &self,
+        start: Instant,
+        keys: &[StoreKey],
+        result: &Result<(), StorageError>,
+    ) -> Result<(), StorageError> {
+        self.log(
+            start,
+            || format!("erase_values([{}]) -> {result:?}", keys.iter().format(", ")),
+            |duration| {
+                keys.iter()
+                    .map(|key| {
+                        UsageLogRecord::new("erase_values", duration, UsageLogOutcome::new(result))
+                            .with_key(key)
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    fn log_erase_prefix(
+        &self,
+        start: Instant,
+        prefix: &StorePrefix,
+        result: &Result<(), StorageError>,
+    ) -> Result<(), StorageError> {
+        self.log(
+            start,
+            || format!("erase_prefix({prefix}) -> {result:?}"),
+            |duration| {
+                vec![
+                    UsageLogRecord::new("erase_prefix", duration, UsageLogOutcome::new(result))
+                        .with_prefix(prefix),
+                ]
+            },
+        )
+    }
+}
+
+/// The outcome of a storage method call in a [`UsageLogRecord`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UsageLogOutcome {
+    /// The call succeeded.
+    Ok,
+    /// The call succeeded, but the key was not found.
+    NotFound,
+    /// The call failed with an error message.
+    Error(String),
+}
+
+impl UsageLogOutcome {
+    fn new<T>(result: &Result<T, StorageError>) -> Self {
+        match result {
+            Ok(_) => Self::Ok,
+            Err(err) => Self::Error(err.to_string()),
+        }
+    }
+
+    fn new_maybe<T>(result: &Result<Option<T>, StorageError>) -> Self {
+        match result {
+            Ok(Some(_)) => Self::Ok,
+            Ok(None) => Self::NotFound,
+            Err(err) => Self::Error(err.to_string()),
+        }
+    }
+}
+
+/// A structured record of a storage method call.
+///
+/// Storage methods operating on multiple keys (e.g. `get_partial_values`) emit a record for each key with the duration of the whole call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsageLogRecord {
+    op: &'static str,
+    key: Option<StoreKey>,
+    prefix: Option<StorePrefix>,
+    byte_ranges: Vec<ByteRange>,
+    bytes: Option<u64>,
+    duration: Duration,
+    outcome: UsageLogOutcome,
+}
+
+impl UsageLogRecord {
+    fn new(op: &'static str, duration: Duration, outcome: UsageLogOutcome) -> Self {
+        Self {
+            op,
+            key: None,
+            prefix: None,
+            byte_ranges: vec![],
+            bytes: None,
+            duration,
+            outcome,
+        }
+    }
+
+    fn with_key(mut self, key: &StoreKey) -> Self {
+        self.key = Some(key.clone());
+        self
+    }
+
+    fn with_prefix(mut self, prefix: &StorePrefix) -> Self {
+        self.prefix = Some(prefix.clone());
+        self
+    }
+
+    fn with_byte_ranges(mut self, byte_ranges: Vec<ByteRange>) -> Self {
+        self.byte_ranges = byte_ranges;
+        self
+    }
+
+    fn with_bytes(mut self, bytes: Option<usize>) -> Self {
+        self.bytes = bytes.map(|bytes| bytes as u64);
+        self
+    }
+
+    /// Returns the name of the storage method (e.g. `get_partial_values_key`).
+    #[must_use]
+    pub const fn op(&self) -> &'static str {
+        self.op
+    }
+
+    /// Returns the key, if the storage method operates on a key.
+    #[must_use]
+    pub const fn key(&self) -> Option<&StoreKey> {
+        self.key.as_ref()
+    }
+
+    /// Returns the prefix, if the storage method operates on a prefix.
+    #[must_use]
+    pub const fn prefix(&self) -> Option<&StorePrefix> {
+        self.prefix.as_ref()
+    }
+
+    /// Returns the byte ranges read or written.
+    #[must_use]
+    pub fn byte_ranges(&self) -> &[ByteRange] {
+        &self.byte_ranges
+    }
+
+    /// Returns the number of bytes read or written, if any.
+    #[must_use]
+    pub const fn bytes(&self) -> Option<u64> {
+        self.bytes
+    }
+
+    /// Returns the duration of the storage method call.
+    #[must_use]
+    pub const fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Returns the outcome of the storage method call.
+    #[must_use]
+    pub const fn outcome(&self) -> &UsageLogOutcome {
+        &self.outcome
+    }
+
+    /// Serialise the record as a single line JSON object.
+    ///
+    /// Absent fields are omitted.
+    /// Byte ranges are serialised as `{"offset":0,"length":10}`, `{"offset":10}` (to the end), or `{"suffix":10}`.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let mut json = format!(r#"{{"op":{}"#, json_string(self.op));
+        if let Some(key) = &self.key {
+            write!(json, r#","key":{}"#, json_string(key.as_str())).unwrap();
+        }
+        if let Some(prefix) = &self.prefix {
+            write!(json, r#","prefix":{}"#, json_string(prefix.as_str())).unwrap();
+        }
+        if !self.byte_ranges.is_empty() {
+            let byte_ranges = self
+                .byte_ranges
+                .iter()
+                .map(|byte_range| match byte_range {
+                    ByteRange::FromStart(offset, Some(length)) => {
+                        format!(r#"{{"offset":{offset},"length":{length}}}"#)
+                    }
+                    ByteRange::FromStart(offset, None) => format!(r#"{{"offset":{offset}}}"#),
+                    ByteRange::Suffix(length) => format!(r#"{{"suffix":{length}}}"#),
+                })
+                .join(",");
+            write!(json, r#","byte_ranges":[{byte_ranges}]"#).unwrap();
+        }
+        if let Some(bytes) = self.bytes {
+            write!(json, r#","bytes":{bytes}"#).unwrap();
+        }
+        write!(json, r#","duration_ns":{}"#, self.duration.as_nanos()).unwrap();
+        match &self.outcome {
+            UsageLogOutcome::Ok => json.push_str(r#","outcome":"ok""#),
+            UsageLogOutcome::NotFound => json.push_str(r#","outcome":"not_found""#),
+            UsageLogOutcome::Error(err) => {
+                write!(json, r#","outcome":"error","error":{}"#, json_string(err)).unwrap();
+            }
         }
+        json.push('}');
+        json
+    }
+}
+
+/// Quote and escape a JSON string.
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => write!(json, "\\u{:04x}", u32::from(c)).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// A sink for the [`UsageLogRecord`]s of a [`UsageLogStorageAdapter`].
+pub trait UsageLogSink: Send + Sync {
+    /// Write a usage log record.
+    ///
+    /// # Errors
+    /// Returns an [`std::io::Error`] if the record cannot be written, which is returned as a [`StorageError`] by the storage method.
+    fn write_record(&self, record: &UsageLogRecord) -> std::io::Result<()>;
+}
+
+/// A [`UsageLogSink`] which writes records as JSON lines.
+///
+/// See [`UsageLogRecord::to_json`].
+pub struct UsageLogJsonLinesSink {
+    handle: Arc<Mutex<dyn Write + Send + Sync>>,
+}
+
+impl core::fmt::Debug for UsageLogJsonLinesSink {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        writeln!(f, "usage log JSON lines sink")
+    }
+}
+
+impl UsageLogJsonLinesSink {
+    /// Create a new JSON lines sink writing to `handle`.
+    pub fn new(handle: Arc<Mutex<dyn Write + Send + Sync>>) -> Self {
+        Self { handle }
+    }
+}
+
+impl UsageLogSink for UsageLogJsonLinesSink {
+    fn write_record(&self, record: &UsageLogRecord) -> std::io::Result<()> {
+        let mut line = record.to_json();
+        line.push('\n');
+        self.handle.lock().unwrap().write_all(line.as_bytes())
+    }
+}
+
+/// Sends records to a channel, so they can be written without blocking storage methods.
+impl UsageLogSink for Sender<UsageLogRecord> {
+    fn write_record(&self, record: &UsageLogRecord) -> std::io::Result<()> {
+        self.send(record.clone())
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::BrokenPipe, err.to_string()))
+    }
+}
+
+/// A log file which is rotated when it exceeds a maximum size.
+///
+/// When a write of a new line would exceed the maximum size, `{path}.1` is renamed to `{path}.2` and so on, the log file is renamed to `{path}.1`, and a new log file is created.
+/// Lines are never split across files.
+#[derive(Debug)]
+pub struct UsageLogRotatingFile {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    file: File,
+    size: u64,
+    line_start: bool,
+}
+
+impl UsageLogRotatingFile {
+    /// Open a rotating log file at `path`, appending if it exists.
+    ///
+    /// The log is rotated when it exceeds `max_bytes`, and up to `max_files` rotated logs are kept.
+    ///
+    /// # Errors
+    /// Returns an [`std::io::Error`] if the log file cannot be opened.
+    pub fn new(
+        path: impl Into<PathBuf>,
+        max_bytes: u64,
+        max_files: usize,
+    ) -> std::io::Result<Self> {
+        let path = path.into();
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path,
+            max_bytes,
+            max_files,
+            file,
+            size,
+            line_start: true,
+        })
+    }
+
+    /// Returns the path of the rotated log with `index`.
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{index}"));
+        path.into()
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.file.flush()?;
+        if self.max_files > 0 {
+            let oldest = self.rotated_path(self.max_files);
+            if oldest.exists() {
+                std::fs::remove_file(oldest)?;
+            }
+            for index in (1..self.max_files).rev() {
+                let path = self.rotated_path(index);
+                if path.exists() {
+                    std::fs::rename(path, self.rotated_path(index + 1))?;
+                }
+            }
+            std::fs::rename(&self.path, self.rotated_path(1))?;
+        }
+        self.file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for UsageLogRotatingFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.line_start && self.size > 0 && self.size + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        if written > 0 {
+            self.line_start = buf[written - 1] == b'\n';
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.file.flush()
     }
 }
 
@@ -83,13 +821,9 @@ impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
     for UsageLogStorageAdapter<TStorage>
 {
     fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        let start = Instant::now();
         let result = self.storage.get(key);
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}get({key}) -> len={:?}",
-            (self.prefix_func)(),
-            result.as_ref().map(|v| v.as_ref().map_or(0, Bytes::len))
-        )?;
+        self.log_get(start, key, &result)?;
         result
     }
 
@@ -98,17 +832,9 @@ impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
         key: &StoreKey,
         byte_ranges: &[ByteRange],
     ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        let start = Instant::now();
         let result = self.storage.get_partial_values_key(key, byte_ranges);
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}get_partial_values_key({key}, [{}]) -> len={:?}",
-            (self.prefix_func)(),
-            byte_ranges.iter().format(", "),
-            result.as_ref().map(|v| {
-                v.as_ref()
-                    .map_or(vec![], |v| v.iter().map(Bytes::len).collect_vec())
-            })
-        )?;
+        self.log_get_partial_values_key(start, key, byte_ranges, &result)?;
         result
     }
 
@@ -116,26 +842,16 @@ impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
         &self,
         key_ranges: &[StoreKeyRange],
     ) -> Result<Vec<MaybeBytes>, StorageError> {
+        let start = Instant::now();
         let result = self.storage.get_partial_values(key_ranges);
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}get_partial_values([{}]) -> len={:?}",
-            (self.prefix_func)(),
-            key_ranges.iter().format(", "),
-            result
-                .as_ref()
-                .map(|v| { v.iter().map(|v| v.iter().map(Bytes::len).collect_vec()) })
-        )?;
+        self.log_get_partial_values(start, key_ranges, &result)?;
         result
     }
 
     fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        let start = Instant::now();
         let result = self.storage.size_key(key);
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}size_key({key}) -> {result:?}",
-            (self.prefix_func)()
-        )?;
+        self.log_size_key(start, key, &result)?;
         result
     }
 }
@@ -144,64 +860,37 @@ impl<TStorage: ?Sized + ListableStorageTraits> ListableStorageTraits
     for UsageLogStorageAdapter<TStorage>
 {
     fn list(&self) -> Result<StoreKeys, StorageError> {
+        let start = Instant::now();
         let result = self.storage.list();
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}list() -> [{}]",
-            (self.prefix_func)(),
-            result.as_ref().unwrap_or(&vec![]).iter().format(", ")
-        )?;
+        self.log_list(start, None, &result)?;
         result
     }
 
     fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        let start = Instant::now();
         let result = self.storage.list_prefix(prefix);
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}list_prefix({prefix}) -> [{}]",
-            (self.prefix_func)(),
-            result.as_ref().unwrap_or(&vec![]).iter().format(", ")
-        )?;
+        self.log_list(start, Some(prefix), &result)?;
         result
     }
 
     fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        let start = Instant::now();
         let result = self.storage.list_dir(prefix);
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}list_dir({prefix}) -> (keys:[{}], prefixes:[{}])",
-            (self.prefix_func)(),
-            result.as_ref().map_or(String::new(), |skp| skp
-                .keys()
-                .iter()
-                .format(", ")
-                .to_string()),
-            result.as_ref().map_or(String::new(), |skp| skp
-                .prefixes()
-                .iter()
-                .format(", ")
-                .to_string()),
-        )?;
+        self.log_list_dir(start, prefix, &result)?;
         result
     }
 
     fn size(&self) -> Result<u64, StorageError> {
+        let start = Instant::now();
         let result = self.storage.size();
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}size() -> {result:?}",
-            (self.prefix_func)()
-        )?;
+        self.log_size(start, None, &result)?;
         result
     }
 
     fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
-        let result: Result<u64, StorageError> = self.storage.size_prefix(prefix);
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}size_prefix({prefix}) -> {result:?}",
-            (self.prefix_func)()
-        )?;
+        let start = Instant::now();
+        let result = self.storage.size_prefix(prefix);
+        self.log_size(start, Some(prefix), &result)?;
         result
     }
 }
@@ -210,14 +899,10 @@ impl<TStorage: ?Sized + WritableStorageTraits> WritableStorageTraits
     for UsageLogStorageAdapter<TStorage>
 {
     fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        let start = Instant::now();
         let len = value.len();
         let result = self.storage.set(key, value);
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}set({key}, len={}) -> {result:?}",
-            (self.prefix_func)(),
-            len
-        )?;
+        self.log_set(start, key, len, &result)?;
         result
     }
 
@@ -225,59 +910,30 @@ impl<TStorage: ?Sized + WritableStorageTraits> WritableStorageTraits
         &self,
         key_offset_values: &[StoreKeyOffsetValue],
     ) -> Result<(), StorageError> {
-        struct DebugStoreKeyOffsetValue<'a>(&'a StoreKeyOffsetValue<'a>);
-        impl core::fmt::Debug for DebugStoreKeyOffsetValue<'_> {
-            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
-                write!(
-                    f,
-                    "({} offset={} len={})",
-                    self.0.key(),
-                    self.0.offset(),
-                    self.0.value().len()
-                )
-            }
-        }
+        let start = Instant::now();
         let result = self.storage.set_partial_values(key_offset_values);
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}set_partial_values({:?}) -> {result:?}",
-            (self.prefix_func)(),
-            key_offset_values
-                .iter()
-                .map(DebugStoreKeyOffsetValue)
-                .collect_vec()
-        )?;
+        self.log_set_partial_values(start, key_offset_values, &result)?;
         result
     }
 
     fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        let start = Instant::now();
         let result = self.storage.erase(key);
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}erase({key}) -> {result:?}",
-            (self.prefix_func)()
-        )?;
+        self.log_erase(start, key, &result)?;
         result
     }
 
     fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        let start = Instant::now();
         let result = self.storage.erase_values(keys);
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}erase_values([{}]) -> {result:?}",
-            keys.iter().format(", "),
-            (self.prefix_func)()
-        )?;
+        self.log_erase_values(start, keys, &result)?;
         result
     }
 
     fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        let start = Instant::now();
         let result = self.storage.erase_prefix(prefix);
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}erase_prefix({prefix}) -> {result:?}",
-            (self.prefix_func)()
-        )?;
+        self.log_erase_prefix(start, prefix, &result)?;
         result
     }
 }
@@ -288,15 +944,9 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits> AsyncReadableStorageTraits
     for UsageLogStorageAdapter<TStorage>
 {
     async fn get(&self, key: &StoreKey) -> Result<MaybeAsyncBytes, StorageError> {
+        let start = Instant::now();
         let result = self.storage.get(key).await;
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}get({key}) -> len={:?}",
-            (self.prefix_func)(),
-            result
-                .as_ref()
-                .map(|v| v.as_ref().map_or(0, AsyncBytes::len))
-        )?;
+        self.log_get(start, key, &result)?;
         result
     }
 
@@ -305,17 +955,9 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits> AsyncReadableStorageTraits
         key: &StoreKey,
         byte_ranges: &[ByteRange],
     ) -> Result<Option<Vec<AsyncBytes>>, StorageError> {
+        let start = Instant::now();
         let result = self.storage.get_partial_values_key(key, byte_ranges).await;
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}get_partial_values_key({key}, [{}]) -> len={:?}",
-            (self.prefix_func)(),
-            byte_ranges.iter().format(", "),
-            result.as_ref().map(|v| {
-                v.as_ref()
-                    .map_or(vec![], |v| v.iter().map(AsyncBytes::len).collect_vec())
-            })
-        )?;
+        self.log_get_partial_values_key(start, key, byte_ranges, &result)?;
         result
     }
 
@@ -323,27 +965,16 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits> AsyncReadableStorageTraits
         &self,
         key_ranges: &[StoreKeyRange],
     ) -> Result<Vec<MaybeAsyncBytes>, StorageError> {
+        let start = Instant::now();
         let result = self.storage.get_partial_values(key_ranges).await;
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}get_partial_values([{}]) -> len={:?}",
-            (self.prefix_func)(),
-            key_ranges.iter().format(", "),
-            result.as_ref().map(|v| {
-                v.iter()
-                    .map(|v| v.iter().map(AsyncBytes::len).collect_vec())
-            })
-        )?;
+        self.log_get_partial_values(start, key_ranges, &result)?;
         result
     }
 
     async fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        let start = Instant::now();
         let result = self.storage.size_key(key).await;
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}size_key({key}) -> {result:?}",
-            (self.prefix_func)()
-        )?;
+        self.log_size_key(start, key, &result)?;
         result
     }
 }
@@ -354,64 +985,37 @@ impl<TStorage: ?Sized + AsyncListableStorageTraits> AsyncListableStorageTraits
     for UsageLogStorageAdapter<TStorage>
 {
     async fn list(&self) -> Result<StoreKeys, StorageError> {
-        let keys = self.storage.list().await;
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}list() -> [{}]",
-            (self.prefix_func)(),
-            keys.as_ref().unwrap_or(&vec![]).iter().format(", "),
-        )?;
-        keys
+        let start = Instant::now();
+        let result = self.storage.list().await;
+        self.log_list(start, None, &result)?;
+        result
     }
 
     async fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        let start = Instant::now();
         let result = self.storage.list_prefix(prefix).await;
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}list_prefix({prefix}) -> [{}]",
-            (self.prefix_func)(),
-            result.as_ref().unwrap_or(&vec![]).iter().format(", ")
-        )?;
+        self.log_list(start, Some(prefix), &result)?;
         result
     }
 
     async fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        let start = Instant::now();
         let result = self.storage.list_dir(prefix).await;
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}list_dir({prefix}) -> (keys:[{}], prefixes:[{}])",
-            (self.prefix_func)(),
-            result.as_ref().map_or(String::new(), |skp| skp
-                .keys()
-                .iter()
-                .format(", ")
-                .to_string()),
-            result.as_ref().map_or(String::new(), |skp| skp
-                .prefixes()
-                .iter()
-                .format(", ")
-                .to_string()),
-        )?;
+        self.log_list_dir(start, prefix, &result)?;
         result
     }
 
     async fn size(&self) -> Result<u64, StorageError> {
+        let start = Instant::now();
         let result = self.storage.size().await;
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}size() -> {result:?}",
-            (self.prefix_func)()
-        )?;
+        self.log_size(start, None, &result)?;
         result
     }
 
     async fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
-        let result: Result<u64, StorageError> = self.storage.size_prefix(prefix).await;
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}size_prefix({prefix}) -> {result:?}",
-            (self.prefix_func)()
-        )?;
+        let start = Instant::now();
+        let result = self.storage.size_prefix(prefix).await;
+        self.log_size(start, Some(prefix), &result)?;
         result
     }
 }
@@ -422,13 +1026,10 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits> AsyncWritableStorageTraits
     for UsageLogStorageAdapter<TStorage>
 {
     async fn set(&self, key: &StoreKey, value: AsyncBytes) -> Result<(), StorageError> {
+        let start = Instant::now();
         let len = value.len();
         let result = self.storage.set(key, value).await;
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}set({key}, len={len}) -> {result:?}",
-            (self.prefix_func)()
-        )?;
+        self.log_set(start, key, len, &result)?;
         result
     }
 
@@ -436,43 +1037,168 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits> AsyncWritableStorageTraits
         &self,
         key_offset_values: &[StoreKeyOffsetValue],
     ) -> Result<(), StorageError> {
+        let start = Instant::now();
         let result = self.storage.set_partial_values(key_offset_values).await;
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}set_partial_values({key_offset_values:?}) -> {result:?}",
-            (self.prefix_func)()
-        )?;
+        self.log_set_partial_values(start, key_offset_values, &result)?;
         result
     }
 
     async fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        let start = Instant::now();
         let result = self.storage.erase(key).await;
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}erase({key}) -> {result:?}",
-            (self.prefix_func)()
-        )?;
+        self.log_erase(start, key, &result)?;
         result
     }
 
     async fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        let start = Instant::now();
         let result = self.storage.erase_values(keys).await;
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}erase_values([{}]) -> {result:?}",
-            (self.prefix_func)(),
-            keys.iter().format(", ")
-        )?;
+        self.log_erase_values(start, keys, &result)?;
         result
     }
 
     async fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        let start = Instant::now();
         let result = self.storage.erase_prefix(prefix).await;
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}erase_prefix({prefix}) -> {result:?}",
-            (self.prefix_func)()
-        )?;
+        self.log_erase_prefix(start, prefix, &result)?;
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::MemoryStore;
+    use std::{error::Error, sync::mpsc::channel};
+
+    #[test]
+    fn usage_log_text() -> Result<(), Box<dyn Error>> {
+        let log_writer = Arc::new(Mutex::new(Vec::new()));
+        let store =
+            UsageLogStorageAdapter::new(Arc::new(MemoryStore::new()), log_writer.clone(), || {
+                "> ".to_string()
+            });
+        crate::store_test::store_write(&store)?;
+        crate::store_test::store_read(&store)?;
+        crate::store_test::store_list(&store)?;
+        let log = String::from_utf8(log_writer.lock().unwrap().clone())?;
+        assert!(log.lines().all(|line| line.starts_with("> ")));
+        assert!(log.contains("> set(a/b, len=3) -> Ok(())\n"));
+        assert!(log.contains("> list_prefix(a/) -> ["));
+        Ok(())
+    }
+
+    #[test]
+    fn usage_log_records() -> Result<(), Box<dyn Error>> {
+        let (sender, receiver) = channel();
+        let store =
+            UsageLogStorageAdapter::new_with_sink(Arc::new(MemoryStore::new()), Arc::new(sender));
+        crate::store_test::store_write(&store)?;
+        crate::store_test::store_read(&store)?;
+        crate::store_test::store_list(&store)?;
+        drop(store);
+        let records: Vec<UsageLogRecord> = receiver.into_iter().collect();
+
+        let record = records
+            .iter()
+            .find(|record| record.op() == "get_partial_values_key")
+            .unwrap();
+        assert!(record.key().is_some());
+        assert!(!record.byte_ranges().is_empty());
+        assert!(records
+            .iter()
+            .any(|record| record.op() == "get" && record.outcome() == &UsageLogOutcome::NotFound));
+        assert!(records
+            .iter()
+            .filter(|record| record.op() == "list_prefix")
+            .all(|record| record.prefix().is_some()));
+        Ok(())
+    }
+
+    #[test]
+    fn usage_log_records_get_partial_values() -> Result<(), Box<dyn Error>> {
+        let (sender, receiver) = channel();
+        let store =
+            UsageLogStorageAdapter::new_with_sink(Arc::new(MemoryStore::new()), Arc::new(sender));
+        store.set(&"a".try_into()?, vec![0, 1, 2, 3].into())?;
+        store.get_partial_values(&[
+            StoreKeyRange::new("a".try_into()?, ByteRange::FromStart(0, Some(2))),
+            StoreKeyRange::new("a".try_into()?, ByteRange::Suffix(1)),
+            StoreKeyRange::new("b".try_into()?, ByteRange::FromStart(0, None)),
+        ])?;
+        drop(store);
+        // A record for each key
+        let records: Vec<_> = receiver.into_iter().skip(1).collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].key(), Some(&"a".try_into()?));
+        assert_eq!(records[0].byte_ranges().len(), 2);
+        assert_eq!(records[0].bytes(), Some(3));
+        assert_eq!(records[0].outcome(), &UsageLogOutcome::Ok);
+        assert_eq!(records[1].key(), Some(&"b".try_into()?));
+        assert_eq!(records[1].bytes(), Some(0));
+        assert_eq!(records[1].outcome(), &UsageLogOutcome::NotFound);
+        Ok(())
+    }
+
+    #[test]
+    fn usage_log_record_json() -> Result<(), Box<dyn Error>> {
+        let record = UsageLogRecord::new(
+            "get_partial_values_key",
+            Duration::from_nanos(1500),
+            UsageLogOutcome::Ok,
+        )
+        .with_key(&"a/\"b\"".try_into()?)
+        .with_byte_ranges(vec![
+            ByteRange::FromStart(0, Some(10)),
+            ByteRange::FromStart(10, None),
+            ByteRange::Suffix(4),
+        ])
+        .with_bytes(Some(16));
+        assert_eq!(
+            record.to_json(),
+            r#"{"op":"get_partial_values_key","key":"a/\"b\"","byte_ranges":[{"offset":0,"length":10},{"offset":10},{"suffix":4}],"bytes":16,"duration_ns":1500,"outcome":"ok"}"#
+        );
+        let record = UsageLogRecord::new(
+            "erase_prefix",
+            Duration::ZERO,
+            UsageLogOutcome::Error("a\nb".to_string()),
+        )
+        .with_prefix(&"a/".try_into()?);
+        assert_eq!(
+            record.to_json(),
+            r#"{"op":"erase_prefix","prefix":"a/","duration_ns":0,"outcome":"error","error":"a\nb"}"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn usage_log_rotating_file() -> Result<(), Box<dyn Error>> {
+        let dir = tempfile::TempDir::new()?;
+        let path = dir.path().join("usage.jsonl");
+        let log_writer = Arc::new(Mutex::new(UsageLogRotatingFile::new(&path, 400, 2)?));
+        let store = UsageLogStorageAdapter::new_with_sink(
+            Arc::new(MemoryStore::new()),
+            Arc::new(UsageLogJsonLinesSink::new(log_writer)),
+        );
+        for i in 0..32 {
+            store.set(&StoreKey::new(format!("c/{i}"))?, vec![0; i].into())?;
+        }
+        drop(store);
+
+        let mut rotated = path.clone().into_os_string();
+        rotated.push(".1");
+        let mut oldest = path.clone().into_os_string();
+        oldest.push(".2");
+        let mut removed = path.clone().into_os_string();
+        removed.push(".3");
+        for path in [path.into_os_string(), rotated, oldest] {
+            let log = std::fs::read_to_string(path)?;
+            assert!(log.len() <= 400);
+            assert!(log
+                .lines()
+                .all(|line| line.starts_with('{') && line.ends_with('}')));
+        }
+        assert!(!PathBuf::from(removed).exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,530 @@
+//! A storage adapter which records every write in an immutable snapshot.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use parking_lot::RwLock;
+
+use crate::{
+    byte_range::ByteRange, store_set_partial_values, Bytes, ListableStorageTraits, MaybeBytes,
+    ReadableStorageTraits, StorageError, StoreKey, StoreKeyOffsetValue, StoreKeys,
+    StoreKeysPrefixes, StorePrefix, WritableStorageTraits,
+};
+
+#[cfg(feature = "async")]
+use crate::{
+    async_store_set_partial_values, AsyncBytes, AsyncListableStorageTraits,
+    AsyncReadableStorageTraits, AsyncWritableStorageTraits, MaybeAsyncBytes,
+};
+
+/// A snapshot identifier.
+///
+/// Snapshot `0` is the empty store, and each write creates the next snapshot.
+pub type SnapshotId = u64;
+
+/// The underlying store prefix of snapshots that set a value.
+const SET_PREFIX: &str = "set/";
+
+/// The underlying store prefix of snapshots that erase a value.
+const ERASE_PREFIX: &str = "erase/";
+
+/// The key history of a [`VersioningStorageAdapter`].
+#[derive(Debug, Default)]
+struct VersionIndex {
+    /// The latest snapshot.
+    snapshot: SnapshotId,
+    /// The latest snapshot reserved by a write, which may not have completed.
+    reserved: SnapshotId,
+    /// The snapshots of each key, and whether the key was set (`true`) or erased (`false`).
+    keys: BTreeMap<StoreKey, BTreeMap<SnapshotId, bool>>,
+}
+
+impl VersionIndex {
+    fn reserve(&mut self) -> SnapshotId {
+        self.reserved = self.reserved.max(self.snapshot) + 1;
+        self.reserved
+    }
+
+    fn insert(&mut self, key: StoreKey, snapshot: SnapshotId, set: bool) {
+        self.snapshot = self.snapshot.max(snapshot);
+        self.keys.entry(key).or_default().insert(snapshot, set);
+    }
+
+    /// Returns the snapshot which set the value of `key` as of `snapshot`, or [`None`] if it does not exist.
+    fn lookup(&self, key: &StoreKey, snapshot: SnapshotId) -> Option<SnapshotId> {
+        let (&id, &set) = self.keys.get(key)?.range(..=snapshot).next_back()?;
+        set.then_some(id)
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix, snapshot: SnapshotId) -> StoreKeys {
+        self.keys
+            .keys()
+            .filter(|key| key.has_prefix(prefix) && self.lookup(key, snapshot).is_some())
+            .cloned()
+            .collect()
+    }
+}
+
+/// The versioning storage adapter. Records every write to the underlying store in an immutable snapshot.
+///
+/// Writes are copy-on-write: each [`set`](WritableStorageTraits::set) or erase of a key creates a new [`SnapshotId`], and existing values are never overwritten.
+/// A read-only view of the store as of any snapshot can be opened with [`as_of`](VersioningStorageAdapter::as_of), enabling time travel without external tooling.
+/// Partial writes retrieve and rewrite the entire value.
+///
+/// The adapter should have exclusive access to the underlying store, which holds a value under `set/<snapshot>/<key>` for each set and an empty tombstone under `erase/<snapshot>/<key>` for each erase.
+/// The history is read from the underlying store on creation, so snapshots persist between sessions.
+/// A snapshot taken while writes are in progress may not include those writes.
+///
+/// ```rust
+/// # use std::sync::Arc;
+/// # use zarrs_storage::{ListableStorageTraits, ReadableStorageTraits, WritableStorageTraits, StoreKey};
+/// # use zarrs_storage::store::MemoryStore;
+/// use zarrs_storage::storage_adapter::versioning::VersioningStorageAdapter;
+/// let store = VersioningStorageAdapter::new(Arc::new(MemoryStore::new()))?;
+/// let key = StoreKey::new("c/0")?;
+/// store.set(&key, vec![0].into())?;
+/// let snapshot = store.current_snapshot();
+/// store.set(&key, vec![1].into())?;
+/// store.erase(&key)?;
+/// assert!(store.get(&key)?.is_none());
+///
+/// let previous = store.as_of(snapshot);
+/// assert_eq!(previous.get(&key)?.unwrap(), vec![0]);
+/// assert!(previous.set(&key, vec![2].into()).is_err());
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct VersioningStorageAdapter<TStorage: ?Sized> {
+    storage: Arc<TStorage>,
+    index: Arc<RwLock<VersionIndex>>,
+    as_of: Option<SnapshotId>,
+}
+
+impl<TStorage: ?Sized> core::fmt::Debug for VersioningStorageAdapter<TStorage> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.as_of {
+            Some(snapshot) => write!(f, "versioning (as of {snapshot})"),
+            None => write!(f, "versioning"),
+        }
+    }
+}
+
+impl<TStorage: ?Sized + ListableStorageTraits> VersioningStorageAdapter<TStorage> {
+    /// Create a new versioning storage adapter, reading the snapshot history from `storage`.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if the history cannot be listed or `storage` has a key that is not a snapshot.
+    pub fn new(storage: Arc<TStorage>) -> Result<Self, StorageError> {
+        let keys = storage.list()?;
+        Self::with_history(storage, &keys)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<TStorage: ?Sized + AsyncListableStorageTraits> VersioningStorageAdapter<TStorage> {
+    /// Asynchronously create a new versioning storage adapter, reading the snapshot history from `storage`.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if the history cannot be listed or `storage` has a key that is not a snapshot.
+    pub async fn async_new(storage: Arc<TStorage>) -> Result<Self, StorageError> {
+        let keys = storage.list().await?;
+        Self::with_history(storage, &keys)
+    }
+}
+
+impl<TStorage: ?Sized> VersioningStorageAdapter<TStorage> {
+    fn with_history(storage: Arc<TStorage>, keys: &[StoreKey]) -> Result<Self, StorageError> {
+        let mut index = VersionIndex::default();
+        for key in keys {
+            let invalid = || {
+                StorageError::Other(format!(
+                    "{key} in the underlying store of a versioning storage adapter is not a snapshot"
+                ))
+            };
+            let (set, snapshot_key) =
+                if let Some(snapshot_key) = key.as_str().strip_prefix(SET_PREFIX) {
+                    (true, snapshot_key)
+                } else if let Some(snapshot_key) = key.as_str().strip_prefix(ERASE_PREFIX) {
+                    (false, snapshot_key)
+                } else {
+                    return Err(invalid());
+                };
+            let (snapshot, key) = snapshot_key.split_once('/').ok_or_else(invalid)?;
+            let snapshot = snapshot.parse().map_err(|_| invalid())?;
+            index.insert(StoreKey::new(key)?, snapshot, set);
+        }
+        Ok(Self {
+            storage,
+            index: Arc::new(RwLock::new(index)),
+            as_of: None,
+        })
+    }
+
+    /// Returns the latest snapshot, or the snapshot of a view opened with [`as_of`](VersioningStorageAdapter::as_of).
+    #[must_use]
+    pub fn current_snapshot(&self) -> SnapshotId {
+        self.as_of.unwrap_or_else(|| self.index.read().snapshot)
+    }
+
+    /// Open a read-only view of the store as of `snapshot`.
+    ///
+    /// The view is unaffected by subsequent writes.
+    #[must_use]
+    pub fn as_of(&self, snapshot: SnapshotId) -> Self {
+        Self {
+            storage: self.storage.clone(),
+            index: self.index.clone(),
+            as_of: Some(snapshot.min(self.current_snapshot())),
+        }
+    }
+
+    /// Returns the snapshots which set (`true`) or erased (`false`) `key`, up to the current snapshot.
+    #[must_use]
+    pub fn history(&self, key: &StoreKey) -> Vec<(SnapshotId, bool)> {
+        let snapshot = self.current_snapshot();
+        self.index
+            .read()
+            .keys
+            .get(key)
+            .map_or_else(Vec::new, |history| {
+                history
+                    .range(..=snapshot)
+                    .map(|(&id, &set)| (id, set))
+                    .collect()
+            })
+    }
+
+    fn snapshot_key(prefix: &str, snapshot: SnapshotId, key: &StoreKey) -> StoreKey {
+        StoreKey::new(format!("{prefix}{snapshot:020}/{key}"))
+            .expect("a snapshot prefix followed by a key is a valid key")
+    }
+
+    /// Returns the underlying key of the value of `key`, or [`None`] if it does not exist.
+    fn value_key(&self, key: &StoreKey) -> Option<StoreKey> {
+        let snapshot = self.current_snapshot();
+        self.index
+            .read()
+            .lookup(key, snapshot)
+            .map(|id| Self::snapshot_key(SET_PREFIX, id, key))
+    }
+
+    fn missing_value(key: &StoreKey) -> StorageError {
+        StorageError::Other(format!(
+            "the snapshot value of {key} is missing from the underlying store"
+        ))
+    }
+
+    fn check_writable(&self) -> Result<(), StorageError> {
+        if self.as_of.is_some() {
+            Err(StorageError::ReadOnly)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Reserve the next snapshot, returning it and its underlying key.
+    fn next_snapshot(&self, prefix: &str, key: &StoreKey) -> (SnapshotId, StoreKey) {
+        let snapshot = self.index.write().reserve();
+        (snapshot, Self::snapshot_key(prefix, snapshot, key))
+    }
+
+    fn list_prefix_index(&self, prefix: &StorePrefix) -> StoreKeys {
+        let snapshot = self.current_snapshot();
+        self.index.read().list_prefix(prefix, snapshot)
+    }
+
+    fn list_dir_index(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        let mut keys: StoreKeys = vec![];
+        let mut prefixes: BTreeSet<StorePrefix> = BTreeSet::default();
+        for key in self.list_prefix_index(prefix) {
+            let key_strip = key
+                .as_str()
+                .strip_prefix(prefix.as_str())
+                .unwrap_or_default();
+            match key_strip.split_once('/') {
+                Some((component, _)) => {
+                    prefixes.insert(StorePrefix::new(
+                        prefix.as_str().to_string() + component + "/",
+                    )?);
+                }
+                None => keys.push(key),
+            }
+        }
+        Ok(StoreKeysPrefixes::new(keys, prefixes.into_iter().collect()))
+    }
+
+    fn keys_to_erase(&self, keys: &[StoreKey]) -> Vec<StoreKey> {
+        keys.iter()
+            .filter(|key| self.value_key(key).is_some())
+            .cloned()
+            .collect()
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
+    for VersioningStorageAdapter<TStorage>
+{
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        let Some(value_key) = self.value_key(key) else {
+            return Ok(None);
+        };
+        Ok(Some(
+            self.storage
+                .get(&value_key)?
+                .ok_or_else(|| Self::missing_value(key))?,
+        ))
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        let Some(value_key) = self.value_key(key) else {
+            return Ok(None);
+        };
+        Ok(Some(
+            self.storage
+                .get_partial_values_key(&value_key, byte_ranges)?
+                .ok_or_else(|| Self::missing_value(key))?,
+        ))
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        let Some(value_key) = self.value_key(key) else {
+            return Ok(None);
+        };
+        Ok(Some(
+            self.storage
+                .size_key(&value_key)?
+                .ok_or_else(|| Self::missing_value(key))?,
+        ))
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits> ListableStorageTraits
+    for VersioningStorageAdapter<TStorage>
+{
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        Ok(self.list_prefix_index(&StorePrefix::root()))
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        Ok(self.list_prefix_index(prefix))
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.list_dir_index(prefix)
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        let mut size = 0;
+        for key in self.list_prefix_index(prefix) {
+            size += self.size_key(&key)?.unwrap_or_default();
+        }
+        Ok(size)
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + WritableStorageTraits> WritableStorageTraits
+    for VersioningStorageAdapter<TStorage>
+{
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        self.check_writable()?;
+        let (snapshot, snapshot_key) = self.next_snapshot(SET_PREFIX, key);
+        self.storage.set(&snapshot_key, value)?;
+        self.index.write().insert(key.clone(), snapshot, true);
+        Ok(())
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        self.check_writable()?;
+        store_set_partial_values(self, key_offset_values)
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.erase_values(std::slice::from_ref(key))
+    }
+
+    fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        self.check_writable()?;
+        for key in self.keys_to_erase(keys) {
+            let (snapshot, snapshot_key) = self.next_snapshot(ERASE_PREFIX, &key);
+            self.storage.set(&snapshot_key, Bytes::new())?;
+            self.index.write().insert(key, snapshot, false);
+        }
+        Ok(())
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.erase_values(&self.list_prefix_index(prefix))
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncReadableStorageTraits> AsyncReadableStorageTraits
+    for VersioningStorageAdapter<TStorage>
+{
+    async fn get(&self, key: &StoreKey) -> Result<MaybeAsyncBytes, StorageError> {
+        let Some(value_key) = self.value_key(key) else {
+            return Ok(None);
+        };
+        Ok(Some(
+            self.storage
+                .get(&value_key)
+                .await?
+                .ok_or_else(|| Self::missing_value(key))?,
+        ))
+    }
+
+    async fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<AsyncBytes>>, StorageError> {
+        let Some(value_key) = self.value_key(key) else {
+            return Ok(None);
+        };
+        Ok(Some(
+            self.storage
+                .get_partial_values_key(&value_key, byte_ranges)
+                .await?
+                .ok_or_else(|| Self::missing_value(key))?,
+        ))
+    }
+
+    async fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        let Some(value_key) = self.value_key(key) else {
+            return Ok(None);
+        };
+        Ok(Some(
+            self.storage
+                .size_key(&value_key)
+                .await?
+                .ok_or_else(|| Self::missing_value(key))?,
+        ))
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncReadableStorageTraits> AsyncListableStorageTraits
+    for VersioningStorageAdapter<TStorage>
+{
+    async fn list(&self) -> Result<StoreKeys, StorageError> {
+        Ok(self.list_prefix_index(&StorePrefix::root()))
+    }
+
+    async fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        Ok(self.list_prefix_index(prefix))
+    }
+
+    async fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.list_dir_index(prefix)
+    }
+
+    async fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        let mut size = 0;
+        for key in self.list_prefix_index(prefix) {
+            size += AsyncReadableStorageTraits::size_key(self, &key)
+                .await?
+                .unwrap_or_default();
+        }
+        Ok(size)
+    }
+
+    async fn size(&self) -> Result<u64, StorageError> {
+        AsyncListableStorageTraits::size_prefix(self, &StorePrefix::root()).await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncReadableStorageTraits + AsyncWritableStorageTraits>
+    AsyncWritableStorageTraits for VersioningStorageAdapter<TStorage>
+{
+    async fn set(&self, key: &StoreKey, value: AsyncBytes) -> Result<(), StorageError> {
+        self.check_writable()?;
+        let (snapshot, snapshot_key) = self.next_snapshot(SET_PREFIX, key);
+        self.storage.set(&snapshot_key, value).await?;
+        self.index.write().insert(key.clone(), snapshot, true);
+        Ok(())
+    }
+
+    async fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        self.check_writable()?;
+        async_store_set_partial_values(self, key_offset_values).await
+    }
+
+    async fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        AsyncWritableStorageTraits::erase_values(self, std::slice::from_ref(key)).await
+    }
+
+    async fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        self.check_writable()?;
+        for key in self.keys_to_erase(keys) {
+            let (snapshot, snapshot_key) = self.next_snapshot(ERASE_PREFIX, &key);
+            self.storage.set(&snapshot_key, AsyncBytes::new()).await?;
+            self.index.write().insert(key, snapshot, false);
+        }
+        Ok(())
+    }
+
+    async fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        AsyncWritableStorageTraits::erase_values(self, &self.list_prefix_index(prefix)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::MemoryStore;
+    use std::error::Error;
+
+    #[test]
+    fn versioning() -> Result<(), Box<dyn Error>> {
+        let store = VersioningStorageAdapter::new(Arc::new(MemoryStore::new()))?;
+        crate::store_test::store_write(&store)?;
+        crate::store_test::store_read(&store)?;
+        crate::store_test::store_list(&store)?;
+        Ok(())
+    }
+
+    #[test]
+    fn versioning_as_of() -> Result<(), Box<dyn Error>> {
+        let storage = Arc::new(MemoryStore::new());
+        let store = VersioningStorageAdapter::new(storage.clone())?;
+        let (a, b) = (StoreKey::new("a/0")?, StoreKey::new("a/1")?);
+        assert_eq!(store.current_snapshot(), 0);
+        store.set(&a, vec![0].into())?;
+        store.set(&b, vec![1].into())?;
+        store.set(&a, vec![2].into())?;
+        store.erase_prefix(&StorePrefix::new("a/")?)?;
+        assert_eq!(store.current_snapshot(), 5);
+        assert!(store.list()?.is_empty());
+        assert_eq!(store.history(&a), vec![(1, true), (3, true), (4, false)]);
+
+        let empty = store.as_of(0);
+        assert!(empty.list()?.is_empty());
+        let snapshot_2 = store.as_of(2);
+        assert_eq!(snapshot_2.list()?, vec![a.clone(), b.clone()]);
+        assert_eq!(snapshot_2.get(&a)?.unwrap(), vec![0]);
+        assert_eq!(store.as_of(3).get(&a)?.unwrap(), vec![2]);
+        assert_eq!(store.as_of(4).list()?, vec![b.clone()]);
+        assert!(matches!(snapshot_2.erase(&a), Err(StorageError::ReadOnly)));
+
+        // The history persists in the underlying store
+        let store = VersioningStorageAdapter::new(storage)?;
+        assert_eq!(store.current_snapshot(), 5);
+        assert_eq!(store.as_of(3).size()?, 2);
+        Ok(())
+    }
+}
@@ -4,8 +4,9 @@ use futures::{StreamExt, TryStreamExt};
 use itertools::Itertools;
 
 use super::{
-    byte_range::ByteRange, AsyncBytes, MaybeAsyncBytes, StorageError, StoreKey,
-    StoreKeyOffsetValue, StoreKeyRange, StoreKeys, StoreKeysPrefixes, StorePrefix, StorePrefixes,
+    byte_range::ByteRange, AsyncBytes, AsyncStoreValueStream, MaybeAsyncBytes, MaybeModifiedBytes,
+    StorageError, StoreKey, StoreKeyOffsetValue, StoreKeyRange, StoreKeys, StoreKeysPrefixes,
+    StorePrefix, StorePrefixes, StoreValueValidator,
 };
 
 /// Async readable storage traits.
@@ -25,6 +26,70 @@ pub trait AsyncReadableStorageTraits: Send + Sync {
             .map(|mut v| v.remove(0)))
     }
 
+    /// Retrieve the values (bytes) associated with a list of [`StoreKey`].
+    ///
+    /// See [`ReadableStorageTraits::get_many`](crate::ReadableStorageTraits::get_many).
+    /// The default implementation retrieves the values concurrently with [`get`](AsyncReadableStorageTraits::get).
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StorageError`] if there is an underlying storage error.
+    async fn get_many(&self, keys: &[StoreKey]) -> Result<Vec<MaybeAsyncBytes>, StorageError> {
+        futures::future::try_join_all(keys.iter().map(|key| self.get(key))).await
+    }
+
+    /// Return a stream of the value associated with a given [`StoreKey`].
+    ///
+    /// Returns [`None`] if the key is not found.
+    ///
+    /// See [`ReadableStorageTraits::get_reader`](crate::ReadableStorageTraits::get_reader).
+    /// The default implementation retrieves the whole value with [`get`](AsyncReadableStorageTraits::get).
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StorageError`] if there is an underlying storage error.
+    async fn get_stream(
+        &self,
+        key: &StoreKey,
+    ) -> Result<Option<AsyncStoreValueStream<'_>>, StorageError> {
+        Ok(self
+            .get(key)
+            .await?
+            .map(|value| futures::stream::once(async { Ok(value) }).boxed()))
+    }
+
+    /// Retrieve the value (bytes) associated with a given [`StoreKey`] if it does not match a `validator` from a previous retrieval.
+    ///
+    /// See [`ReadableStorageTraits::get_if_modified`](crate::ReadableStorageTraits::get_if_modified).
+    /// The default implementation retrieves the value with [`get`](AsyncReadableStorageTraits::get) and returns no validator.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StorageError`] if there is an underlying storage error.
+    async fn get_if_modified(
+        &self,
+        key: &StoreKey,
+        validator: Option<&StoreValueValidator>,
+    ) -> Result<MaybeModifiedBytes, StorageError> {
+        let _ = validator;
+        Ok(MaybeModifiedBytes::Modified(self.get(key).await?, None))
+    }
+
+    /// Retrieve the value (bytes) associated with a given [`StoreKey`] and its entity tag.
+    ///
+    /// See [`ReadableStorageTraits::get_with_etag`](crate::ReadableStorageTraits::get_with_etag).
+    /// The default implementation retrieves the value with [`get`](AsyncReadableStorageTraits::get) and returns no entity tag.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StorageError`] if there is an underlying storage error.
+    async fn get_with_etag(
+        &self,
+        key: &StoreKey,
+    ) -> Result<(MaybeAsyncBytes, Option<StoreValueValidator>), StorageError> {
+        Ok((self.get(key).await?, None))
+    }
+
     /// Retrieve partial bytes from a list of byte ranges for a store key.
     ///
     /// Returns [`None`] if the key is not found.
@@ -231,6 +296,46 @@ pub trait AsyncWritableStorageTraits: Send + Sync {
     /// Returns a [`StorageError`] on failure to store.
     async fn set(&self, key: &StoreKey, value: AsyncBytes) -> Result<(), StorageError>;
 
+    /// Store a stream of bytes at a [`StoreKey`].
+    ///
+    /// See [`WritableStorageTraits::set_writer`](crate::WritableStorageTraits::set_writer).
+    /// The default implementation collects the stream in memory and stores it with [`set`](AsyncWritableStorageTraits::set).
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] on failure to store, or if the stream yields an error.
+    async fn set_stream<'a>(
+        &self,
+        key: &StoreKey,
+        value: AsyncStoreValueStream<'a>,
+    ) -> Result<(), StorageError> {
+        let value: Vec<AsyncBytes> = value.try_collect().await?;
+        self.set(key, value.concat().into()).await
+    }
+
+    /// Returns true if the store supports conditional writes with [`set_if_match`](AsyncWritableStorageTraits::set_if_match).
+    fn supports_set_if_match(&self) -> bool {
+        false
+    }
+
+    /// Store bytes at a [`StoreKey`] only if its value has not changed since it was retrieved.
+    ///
+    /// See [`WritableStorageTraits::set_if_match`](crate::WritableStorageTraits::set_if_match).
+    /// The default implementation does not support conditional writes.
+    ///
+    /// # Errors
+    /// Returns [`StorageError::PreconditionFailed`] if the value was modified by another writer, [`StorageError::Unsupported`] if the store does not support conditional writes, or a [`StorageError`] if there is an underlying storage error.
+    async fn set_if_match(
+        &self,
+        key: &StoreKey,
+        value: AsyncBytes,
+        etag: Option<&StoreValueValidator>,
+    ) -> Result<Option<StoreValueValidator>, StorageError> {
+        let _ = (key, value, etag);
+        Err(StorageError::Unsupported(
+            "conditional writes are not supported by this store".to_string(),
+        ))
+    }
+
     /// Store bytes according to a list of [`StoreKeyOffsetValue`].
     ///
     /// # Errors
@@ -266,6 +371,29 @@ pub trait AsyncWritableStorageTraits: Send + Sync {
     /// # Errors
     /// Returns a [`StorageError`] if there is an underlying storage error.
     async fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError>;
+
+    /// Returns true if the store supports renaming a prefix with [`rename_prefix`](AsyncWritableStorageTraits::rename_prefix).
+    fn supports_rename_prefix(&self) -> bool {
+        false
+    }
+
+    /// Rename all [`StoreKey`] under the prefix `from` to be under the prefix `to`.
+    ///
+    /// See [`WritableStorageTraits::rename_prefix`](crate::WritableStorageTraits::rename_prefix).
+    /// The default implementation does not support renaming a prefix.
+    ///
+    /// # Errors
+    /// Returns [`StorageError::Unsupported`] if the store does not support renaming a prefix, or a [`StorageError`] if there is an underlying storage error.
+    async fn rename_prefix(
+        &self,
+        from: &StorePrefix,
+        to: &StorePrefix,
+    ) -> Result<(), StorageError> {
+        let _ = (from, to);
+        Err(StorageError::Unsupported(
+            "renaming a prefix is not supported by this store".to_string(),
+        ))
+    }
 }
 
 /// A supertrait of [`AsyncReadableStorageTraits`] and [`AsyncWritableStorageTraits`].
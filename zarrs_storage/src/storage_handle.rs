@@ -1,14 +1,15 @@
 use std::sync::Arc;
 
 use super::{
-    byte_range::ByteRange, Bytes, ListableStorageTraits, MaybeBytes, ReadableStorageTraits,
-    StorageError, StoreKey, StorePrefix, WritableStorageTraits,
+    byte_range::ByteRange, Bytes, ListableStorageTraits, MaybeBytes, MaybeModifiedBytes,
+    ReadableStorageTraits, StorageError, StoreKey, StorePrefix, StoreValueReader,
+    StoreValueValidator, StoreValueWriter, WritableStorageTraits,
 };
 
 #[cfg(feature = "async")]
 use super::{
-    AsyncBytes, AsyncListableStorageTraits, AsyncReadableStorageTraits, AsyncWritableStorageTraits,
-    MaybeAsyncBytes,
+    AsyncBytes, AsyncListableStorageTraits, AsyncReadableStorageTraits, AsyncStoreValueStream,
+    AsyncWritableStorageTraits, MaybeAsyncBytes,
 };
 
 /// A storage handle.
@@ -29,6 +30,29 @@ impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits for Storage
         self.0.get(key)
     }
 
+    fn get_many(&self, keys: &[StoreKey]) -> Result<Vec<MaybeBytes>, StorageError> {
+        self.0.get_many(keys)
+    }
+
+    fn get_reader(&self, key: &StoreKey) -> Result<Option<StoreValueReader<'_>>, StorageError> {
+        self.0.get_reader(key)
+    }
+
+    fn get_if_modified(
+        &self,
+        key: &StoreKey,
+        validator: Option<&StoreValueValidator>,
+    ) -> Result<MaybeModifiedBytes, StorageError> {
+        self.0.get_if_modified(key, validator)
+    }
+
+    fn get_with_etag(
+        &self,
+        key: &StoreKey,
+    ) -> Result<(MaybeBytes, Option<StoreValueValidator>), StorageError> {
+        self.0.get_with_etag(key)
+    }
+
     fn get_partial_values_key(
         &self,
         key: &StoreKey,
@@ -82,6 +106,23 @@ impl<TStorage: ?Sized + WritableStorageTraits> WritableStorageTraits for Storage
         self.0.set(key, value)
     }
 
+    fn set_writer(&self, key: &StoreKey) -> Result<Box<dyn StoreValueWriter + '_>, StorageError> {
+        self.0.set_writer(key)
+    }
+
+    fn supports_set_if_match(&self) -> bool {
+        self.0.supports_set_if_match()
+    }
+
+    fn set_if_match(
+        &self,
+        key: &StoreKey,
+        value: Bytes,
+        etag: Option<&StoreValueValidator>,
+    ) -> Result<Option<StoreValueValidator>, StorageError> {
+        self.0.set_if_match(key, value, etag)
+    }
+
     fn set_partial_values(
         &self,
         key_offset_values: &[super::StoreKeyOffsetValue],
@@ -100,6 +141,25 @@ impl<TStorage: ?Sized + WritableStorageTraits> WritableStorageTraits for Storage
     fn erase_prefix(&self, prefix: &super::StorePrefix) -> Result<(), super::StorageError> {
         self.0.erase_prefix(prefix)
     }
+
+    fn supports_rename_prefix(&self) -> bool {
+        self.0.supports_rename_prefix()
+    }
+
+    fn rename_prefix(
+        &self,
+        from: &super::StorePrefix,
+        to: &super::StorePrefix,
+    ) -> Result<(), super::StorageError> {
+        self.0.rename_prefix(from, to)
+    }
+
+    fn lock_key(
+        &self,
+        key: &super::StoreKey,
+    ) -> Result<crate::store_lock::StoreKeyLockGuard<'_>, super::StorageError> {
+        self.0.lock_key(key)
+    }
 }
 
 #[cfg(feature = "async")]
@@ -111,6 +171,32 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits> AsyncReadableStorageTraits
         self.0.get(key).await
     }
 
+    async fn get_many(&self, keys: &[StoreKey]) -> Result<Vec<MaybeAsyncBytes>, StorageError> {
+        self.0.get_many(keys).await
+    }
+
+    async fn get_stream(
+        &self,
+        key: &StoreKey,
+    ) -> Result<Option<AsyncStoreValueStream<'_>>, StorageError> {
+        self.0.get_stream(key).await
+    }
+
+    async fn get_if_modified(
+        &self,
+        key: &StoreKey,
+        validator: Option<&StoreValueValidator>,
+    ) -> Result<MaybeModifiedBytes, StorageError> {
+        self.0.get_if_modified(key, validator).await
+    }
+
+    async fn get_with_etag(
+        &self,
+        key: &StoreKey,
+    ) -> Result<(MaybeAsyncBytes, Option<StoreValueValidator>), StorageError> {
+        self.0.get_with_etag(key).await
+    }
+
     async fn get_partial_values_key(
         &self,
         key: &StoreKey,
@@ -172,6 +258,27 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits> AsyncWritableStorageTraits
         self.0.set(key, value).await
     }
 
+    async fn set_stream<'a>(
+        &self,
+        key: &StoreKey,
+        value: AsyncStoreValueStream<'a>,
+    ) -> Result<(), StorageError> {
+        self.0.set_stream(key, value).await
+    }
+
+    fn supports_set_if_match(&self) -> bool {
+        self.0.supports_set_if_match()
+    }
+
+    async fn set_if_match(
+        &self,
+        key: &StoreKey,
+        value: AsyncBytes,
+        etag: Option<&StoreValueValidator>,
+    ) -> Result<Option<StoreValueValidator>, StorageError> {
+        self.0.set_if_match(key, value, etag).await
+    }
+
     async fn set_partial_values(
         &self,
         key_offset_values: &[super::StoreKeyOffsetValue],
@@ -190,4 +297,16 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits> AsyncWritableStorageTraits
     async fn erase_prefix(&self, prefix: &super::StorePrefix) -> Result<(), super::StorageError> {
         self.0.erase_prefix(prefix).await
     }
+
+    fn supports_rename_prefix(&self) -> bool {
+        self.0.supports_rename_prefix()
+    }
+
+    async fn rename_prefix(
+        &self,
+        from: &super::StorePrefix,
+        to: &super::StorePrefix,
+    ) -> Result<(), super::StorageError> {
+        self.0.rename_prefix(from, to).await
+    }
 }
@@ -3,9 +3,11 @@ use std::sync::Arc;
 use itertools::Itertools;
 
 use super::{
-    byte_range::ByteRange, Bytes, MaybeBytes, StorageError, StoreKey, StoreKeyOffsetValue,
-    StoreKeyRange, StoreKeys, StoreKeysPrefixes, StorePrefix, StorePrefixes,
+    byte_range::ByteRange, Bytes, MaybeBytes, MaybeModifiedBytes, StorageError, StoreKey,
+    StoreKeyOffsetValue, StoreKeyRange, StoreKeys, StoreKeysPrefixes, StorePrefix, StorePrefixes,
+    StoreValueReader, StoreValueValidator, StoreValueWriter,
 };
+use crate::{storage_value_stream::BufferedStoreValueWriter, store_lock::StoreKeyLockGuard};
 
 /// Readable storage traits.
 pub trait ReadableStorageTraits: Send + Sync {
@@ -21,6 +23,67 @@ pub trait ReadableStorageTraits: Send + Sync {
             .map(|mut v| v.remove(0)))
     }
 
+    /// Retrieve the values (bytes) associated with a list of [`StoreKey`].
+    ///
+    /// # Output
+    /// A list of values in the order of the `keys`. It will be [`None`] for missing keys.
+    ///
+    /// Stores with a high per-request overhead (e.g. HTTP stores) can override this method to retrieve values concurrently or in a single batched request.
+    /// The default implementation retrieves each value sequentially with [`get`](ReadableStorageTraits::get).
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if there is an underlying storage error.
+    fn get_many(&self, keys: &[StoreKey]) -> Result<Vec<MaybeBytes>, StorageError> {
+        keys.iter().map(|key| self.get(key)).collect()
+    }
+
+    /// Return a reader of the value associated with a given [`StoreKey`].
+    ///
+    /// Returns [`None`] if the key is not found.
+    ///
+    /// Stores that can stream a value (e.g. filesystem and HTTP stores) override this method so that large values can be processed without holding them in memory.
+    /// The default implementation retrieves the whole value with [`get`](ReadableStorageTraits::get).
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if there is an underlying storage error.
+    fn get_reader(&self, key: &StoreKey) -> Result<Option<StoreValueReader<'_>>, StorageError> {
+        Ok(self
+            .get(key)?
+            .map(|value| Box::new(std::io::Cursor::new(value)) as StoreValueReader))
+    }
+
+    /// Retrieve the value (bytes) associated with a given [`StoreKey`] if it does not match a `validator` from a previous retrieval.
+    ///
+    /// Stores supporting conditional requests (e.g. HTTP stores) return a [`StoreValueValidator`] with the value, and [`MaybeModifiedBytes::NotModified`] if the value matches `validator`.
+    /// This allows a cache of the value to be revalidated without retrieving it again.
+    /// The default implementation retrieves the value with [`get`](ReadableStorageTraits::get) and returns no validator.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if there is an underlying storage error.
+    fn get_if_modified(
+        &self,
+        key: &StoreKey,
+        validator: Option<&StoreValueValidator>,
+    ) -> Result<MaybeModifiedBytes, StorageError> {
+        let _ = validator;
+        Ok(MaybeModifiedBytes::Modified(self.get(key)?, None))
+    }
+
+    /// Retrieve the value (bytes) associated with a given [`StoreKey`] and its entity tag.
+    ///
+    /// The entity tag identifies the current version of the value, and can be passed to [`WritableStorageTraits::set_if_match`] to only overwrite the value if it has not changed.
+    /// The value is [`None`] if the key is not found, and the entity tag is [`None`] if the key is not found or the store does not support entity tags.
+    /// The default implementation retrieves the value with [`get`](ReadableStorageTraits::get) and returns no entity tag.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if there is an underlying storage error.
+    fn get_with_etag(
+        &self,
+        key: &StoreKey,
+    ) -> Result<(MaybeBytes, Option<StoreValueValidator>), StorageError> {
+        Ok((self.get(key)?, None))
+    }
+
     /// Retrieve partial bytes from a list of byte ranges for a store key.
     ///
     /// Returns [`None`] if the key is not found.
@@ -164,9 +227,7 @@ pub fn store_set_partial_values<T: ReadableWritableStorageTraits>(
         .into_iter()
         .map(|(key, group)| (key.clone(), group.into_iter().cloned().collect::<Vec<_>>()))
         .try_for_each(|(key, group)| {
-            // Lock the store key
-            // let mutex = store.mutex(&key)?;
-            // let _lock = mutex.lock();
+            // The store key is not locked here, callers hold the lock (see WritableStorageTraits::lock_key)
 
             // Read the store key
             let bytes = store.get(&key)?.unwrap_or_default();
@@ -211,6 +272,45 @@ pub trait WritableStorageTraits: Send + Sync {
     /// Returns a [`StorageError`] on failure to store.
     fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError>;
 
+    /// Return a writer of the value at a [`StoreKey`].
+    ///
+    /// The value is stored when the writer is [finished](StoreValueWriter::finish).
+    /// Stores that can stream a value (e.g. filesystem stores) override this method so that large values can be written without holding them in memory.
+    /// The default implementation buffers the value in memory and stores it with [`set`](WritableStorageTraits::set).
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if there is an underlying storage error.
+    fn set_writer(&self, key: &StoreKey) -> Result<Box<dyn StoreValueWriter + '_>, StorageError> {
+        Ok(Box::new(BufferedStoreValueWriter::new(self, key)))
+    }
+
+    /// Returns true if the store supports conditional writes with [`set_if_match`](WritableStorageTraits::set_if_match).
+    fn supports_set_if_match(&self) -> bool {
+        false
+    }
+
+    /// Store bytes at a [`StoreKey`] only if its value has not changed since it was retrieved.
+    ///
+    /// If `etag` is [`Some`], the value is only stored if the current value has that entity tag (see [`ReadableStorageTraits::get_with_etag`]).
+    /// If `etag` is [`None`], the value is only stored if the key does not exist.
+    /// Returns the entity tag of the stored value if supported by the store.
+    ///
+    /// The default implementation does not support conditional writes.
+    ///
+    /// # Errors
+    /// Returns [`StorageError::PreconditionFailed`] if the value was modified by another writer, [`StorageError::Unsupported`] if the store does not support conditional writes, or a [`StorageError`] if there is an underlying storage error.
+    fn set_if_match(
+        &self,
+        key: &StoreKey,
+        value: Bytes,
+        etag: Option<&StoreValueValidator>,
+    ) -> Result<Option<StoreValueValidator>, StorageError> {
+        let _ = (key, value, etag);
+        Err(StorageError::Unsupported(
+            "conditional writes are not supported by this store".to_string(),
+        ))
+    }
+
     /// Store bytes according to a list of [`StoreKeyOffsetValue`].
     ///
     /// # Errors
@@ -240,16 +340,44 @@ pub trait WritableStorageTraits: Send + Sync {
     /// # Errors
     /// Returns a [`StorageError`] is the prefix is not in the store, or the erase otherwise fails.
     fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError>;
+
+    /// Returns true if the store supports renaming a prefix with [`rename_prefix`](WritableStorageTraits::rename_prefix).
+    fn supports_rename_prefix(&self) -> bool {
+        false
+    }
+
+    /// Rename all [`StoreKey`] under the prefix `from` to be under the prefix `to`.
+    ///
+    /// This can be much faster than copying and erasing each key (e.g. a single directory rename in a filesystem).
+    /// There must be no keys under `to`, and `to` must not be under `from`.
+    ///
+    /// The default implementation does not support renaming a prefix.
+    ///
+    /// # Errors
+    /// Returns [`StorageError::Unsupported`] if the store does not support renaming a prefix, or a [`StorageError`] if there is an underlying storage error.
+    fn rename_prefix(&self, from: &StorePrefix, to: &StorePrefix) -> Result<(), StorageError> {
+        let _ = (from, to);
+        Err(StorageError::Unsupported(
+            "renaming a prefix is not supported by this store".to_string(),
+        ))
+    }
+
+    /// Acquire an exclusive lock on the store value at `key`, blocking until it is available.
+    ///
+    /// Writers performing a read-modify-write of a value (e.g. a partial write of a chunk) hold this lock to coordinate with other writers.
+    /// The guard returned by the default implementation does not lock.
+    /// See [`LockingStorageAdapter`](crate::storage_adapter::locking::LockingStorageAdapter) to lock keys with a [`StoreLocks`](crate::store_lock::StoreLocks) backend.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if the lock cannot be acquired.
+    fn lock_key(&self, key: &StoreKey) -> Result<StoreKeyLockGuard<'_>, StorageError> {
+        let _ = key;
+        Ok(StoreKeyLockGuard::unlocked())
+    }
 }
 
 /// A supertrait of [`ReadableStorageTraits`] and [`WritableStorageTraits`].
-pub trait ReadableWritableStorageTraits: ReadableStorageTraits + WritableStorageTraits {
-    // /// Returns the mutex for the store value at `key`.
-    // ///
-    // /// # Errors
-    // /// Returns a [`StorageError`] if the mutex cannot be retrieved.
-    // fn mutex(&self, key: &StoreKey) -> Result<StoreKeyMutex, StorageError>;
-}
+pub trait ReadableWritableStorageTraits: ReadableStorageTraits + WritableStorageTraits {}
 
 impl<T> ReadableWritableStorageTraits for T where T: ReadableStorageTraits + WritableStorageTraits {}
 
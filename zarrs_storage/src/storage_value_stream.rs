@@ -0,0 +1,63 @@
+use std::io::{Read, Write};
+
+use super::{Bytes, StorageError, StoreKey, WritableStorageTraits};
+
+/// A reader of a store value.
+///
+/// Returned by [`ReadableStorageTraits::get_reader`](crate::ReadableStorageTraits::get_reader).
+pub type StoreValueReader<'a> = Box<dyn Read + Send + 'a>;
+
+/// A writer of a store value.
+///
+/// Returned by [`WritableStorageTraits::set_writer`](crate::WritableStorageTraits::set_writer).
+/// The value is only guaranteed to be stored once [`finish`](StoreValueWriter::finish) returns successfully.
+/// A writer that is dropped without being finished may leave the value unset or partially written.
+pub trait StoreValueWriter: Write + Send {
+    /// Finish writing the value.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if there is an underlying storage error.
+    fn finish(self: Box<Self>) -> Result<(), StorageError>;
+}
+
+/// A [`StoreValueWriter`] that buffers the value in memory and stores it with [`WritableStorageTraits::set`] when finished.
+pub(crate) struct BufferedStoreValueWriter<'a, TStorage: ?Sized + WritableStorageTraits> {
+    storage: &'a TStorage,
+    key: StoreKey,
+    buffer: Vec<u8>,
+}
+
+impl<'a, TStorage: ?Sized + WritableStorageTraits> BufferedStoreValueWriter<'a, TStorage> {
+    pub(crate) fn new(storage: &'a TStorage, key: &StoreKey) -> Self {
+        Self {
+            storage,
+            key: key.clone(),
+            buffer: Vec::new(),
+        }
+    }
+}
+
+impl<TStorage: ?Sized + WritableStorageTraits> Write for BufferedStoreValueWriter<'_, TStorage> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<TStorage: ?Sized + WritableStorageTraits> StoreValueWriter
+    for BufferedStoreValueWriter<'_, TStorage>
+{
+    fn finish(self: Box<Self>) -> Result<(), StorageError> {
+        self.storage.set(&self.key, Bytes::from(self.buffer))
+    }
+}
+
+#[cfg(feature = "async")]
+/// A stream of the bytes of a store value.
+///
+/// Returned by [`AsyncReadableStorageTraits::get_stream`](crate::AsyncReadableStorageTraits::get_stream) and passed to [`AsyncWritableStorageTraits::set_stream`](crate::AsyncWritableStorageTraits::set_stream).
+pub type AsyncStoreValueStream<'a> =
+    futures::stream::BoxStream<'a, Result<super::AsyncBytes, StorageError>>;
@@ -2,5 +2,11 @@
 //!
 //! See <https://zarr-specs.readthedocs.io/en/latest/v3/core/v3.0.html#id21>
 
+mod bounded_memory_store;
 mod memory_store;
+mod tiered_store;
+mod union_store;
+pub use bounded_memory_store::BoundedMemoryStore;
 pub use memory_store::MemoryStore;
+pub use tiered_store::{TieredStore, TieredWritePolicy};
+pub use union_store::UnionStore;
@@ -0,0 +1,336 @@
+//! A synchronous in-memory store with a byte capacity and least recently used (LRU) eviction.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Mutex,
+};
+
+use crate::{
+    byte_range::{ByteOffset, ByteRange, InvalidByteRangeError},
+    Bytes, ListableStorageTraits, MaybeBytes, ReadableStorageTraits, StorageError, StoreKey,
+    StoreKeyOffsetValue, StoreKeys, StoreKeysPrefixes, StorePrefix, WritableStorageTraits,
+};
+
+/// A synchronous in-memory store with a byte capacity and least recently used (LRU) eviction.
+///
+/// When a write would exceed the capacity, the least recently used keys are evicted until the store fits within its capacity.
+/// Reading or writing a key marks it as the most recently used.
+/// Writing a value that alone exceeds the capacity is an error.
+///
+/// This is suitable as a scratch store for streaming pipelines that must not exhaust memory.
+/// Evicted keys are silently dropped, so this store should only hold data that can be recomputed or refetched.
+#[derive(Debug)]
+pub struct BoundedMemoryStore {
+    capacity: u64,
+    state: Mutex<BoundedMemoryStoreState>,
+}
+
+#[derive(Debug, Default)]
+struct BoundedMemoryStoreState {
+    data_map: BTreeMap<StoreKey, BoundedMemoryStoreEntry>,
+    /// Keys ordered by their last use.
+    recency: BTreeMap<u64, StoreKey>,
+    /// The last use counter.
+    counter: u64,
+    /// The total size of all values in bytes.
+    size: u64,
+    /// The number of keys evicted.
+    evictions: u64,
+}
+
+#[derive(Debug)]
+struct BoundedMemoryStoreEntry {
+    data: Vec<u8>,
+    last_used: u64,
+}
+
+impl BoundedMemoryStoreState {
+    /// Mark `key` as the most recently used.
+    fn touch(&mut self, key: &StoreKey) {
+        if let Some(entry) = self.data_map.get_mut(key) {
+            self.recency.remove(&entry.last_used);
+            self.counter += 1;
+            entry.last_used = self.counter;
+            self.recency.insert(self.counter, key.clone());
+        }
+    }
+
+    fn remove(&mut self, key: &StoreKey) {
+        if let Some(entry) = self.data_map.remove(key) {
+            self.recency.remove(&entry.last_used);
+            self.size -= entry.data.len() as u64;
+        }
+    }
+
+    /// Evict the least recently used keys other than `key` until the store fits within `capacity`.
+    fn evict(&mut self, key: &StoreKey, capacity: u64) {
+        while self.size > capacity {
+            let lru = self
+                .recency
+                .values()
+                .find(|&lru| lru != key)
+                .cloned()
+                .expect("the value of key fits within capacity");
+            self.remove(&lru);
+            self.evictions += 1;
+        }
+    }
+}
+
+impl BoundedMemoryStore {
+    /// Create a new bounded memory store with a `capacity` in bytes.
+    #[must_use]
+    pub fn new(capacity: u64) -> Self {
+        Self {
+            capacity,
+            state: Mutex::default(),
+        }
+    }
+
+    /// Return the capacity of the store in bytes.
+    #[must_use]
+    pub const fn capacity(&self) -> u64 {
+        self.capacity
+    }
+
+    /// Return the number of keys that have been evicted.
+    ///
+    /// # Panics
+    /// Panics if the internal lock is poisoned.
+    #[must_use]
+    pub fn evictions(&self) -> u64 {
+        self.state.lock().unwrap().evictions
+    }
+
+    fn set_impl(
+        &self,
+        key: &StoreKey,
+        value: &[u8],
+        offset: ByteOffset,
+        truncate: bool,
+    ) -> Result<(), StorageError> {
+        let mut state = self.state.lock().unwrap();
+        let current_length = state
+            .data_map
+            .get(key)
+            .map_or(0, |entry| entry.data.len() as u64);
+        let length = if truncate {
+            offset + value.len() as u64
+        } else {
+            current_length.max(offset + value.len() as u64)
+        };
+        if length > self.capacity {
+            return Err(StorageError::Other(format!(
+                "value of key {key} with size {length} exceeds the bounded memory store capacity {}",
+                self.capacity
+            )));
+        }
+
+        let counter = state.counter + 1;
+        state.counter = counter;
+        let entry = state
+            .data_map
+            .entry(key.clone())
+            .or_insert_with(|| BoundedMemoryStoreEntry {
+                data: Vec::new(),
+                last_used: counter,
+            });
+        let last_used = std::mem::replace(&mut entry.last_used, counter);
+        let data = &mut entry.data;
+        if offset == 0 && data.is_empty() {
+            // fast path
+            *data = value.to_vec();
+        } else {
+            let length = usize::try_from(length).unwrap();
+            data.resize(length, 0);
+            let offset = usize::try_from(offset).unwrap();
+            data[offset..offset + value.len()].copy_from_slice(value);
+        }
+        state.recency.remove(&last_used);
+        state.recency.insert(counter, key.clone());
+        state.size = state.size - current_length + length;
+
+        state.evict(key, self.capacity);
+        Ok(())
+    }
+}
+
+impl ReadableStorageTraits for BoundedMemoryStore {
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        let mut state = self.state.lock().unwrap();
+        state.touch(key);
+        Ok(state
+            .data_map
+            .get(key)
+            .map(|entry| entry.data.clone().into()))
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        let mut state = self.state.lock().unwrap();
+        state.touch(key);
+        let Some(entry) = state.data_map.get(key) else {
+            return Ok(None);
+        };
+        let data = &entry.data;
+        let mut out = Vec::with_capacity(byte_ranges.len());
+        for byte_range in byte_ranges {
+            let start = usize::try_from(byte_range.start(data.len() as u64)).unwrap();
+            let end = usize::try_from(byte_range.end(data.len() as u64)).unwrap();
+            if end > data.len() {
+                return Err(InvalidByteRangeError::new(*byte_range, data.len() as u64).into());
+            }
+            out.push(data[start..end].to_vec().into());
+        }
+        Ok(Some(out))
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        let state = self.state.lock().unwrap();
+        Ok(state.data_map.get(key).map(|entry| entry.data.len() as u64))
+    }
+}
+
+impl WritableStorageTraits for BoundedMemoryStore {
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        self.set_impl(key, &value, 0, true)
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        for key_offset_value in key_offset_values {
+            self.set_impl(
+                key_offset_value.key(),
+                key_offset_value.value(),
+                key_offset_value.offset(),
+                false,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.state.lock().unwrap().remove(key);
+        Ok(())
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        let mut state = self.state.lock().unwrap();
+        let keys: Vec<StoreKey> = state
+            .data_map
+            .keys()
+            .filter(|key| key.has_prefix(prefix))
+            .cloned()
+            .collect();
+        for key in keys {
+            state.remove(&key);
+        }
+        Ok(())
+    }
+}
+
+impl ListableStorageTraits for BoundedMemoryStore {
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        let state = self.state.lock().unwrap();
+        Ok(state.data_map.keys().cloned().collect())
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        let state = self.state.lock().unwrap();
+        Ok(state
+            .data_map
+            .keys()
+            .filter(|&key| key.has_prefix(prefix))
+            .cloned()
+            .collect())
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        let mut keys: StoreKeys = vec![];
+        let mut prefixes: BTreeSet<StorePrefix> = BTreeSet::default();
+        let state = self.state.lock().unwrap();
+        for key in state.data_map.keys() {
+            if key.has_prefix(prefix) {
+                let key_strip = key.as_str().strip_prefix(prefix.as_str()).unwrap();
+                let key_strip = key_strip.strip_prefix('/').unwrap_or(key_strip);
+                let components: Vec<_> = key_strip.split('/').collect();
+                if components.len() > 1 {
+                    prefixes.insert(StorePrefix::new(
+                        prefix.as_str().to_string() + components[0] + "/",
+                    )?);
+                } else if key.parent().eq(prefix) {
+                    keys.push(key.clone());
+                }
+            }
+        }
+        let prefixes: Vec<StorePrefix> = prefixes.into_iter().collect();
+        Ok(StoreKeysPrefixes { keys, prefixes })
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        let state = self.state.lock().unwrap();
+        Ok(state
+            .data_map
+            .iter()
+            .filter(|(key, _)| key.has_prefix(prefix))
+            .map(|(_, entry)| entry.data.len() as u64)
+            .sum())
+    }
+
+    fn size(&self) -> Result<u64, StorageError> {
+        Ok(self.state.lock().unwrap().size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn bounded_memory() -> Result<(), Box<dyn Error>> {
+        let store = BoundedMemoryStore::new(1024 * 1024);
+        crate::store_test::store_write(&store)?;
+        crate::store_test::store_read(&store)?;
+        crate::store_test::store_list(&store)?;
+        assert_eq!(store.evictions(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn bounded_memory_eviction() -> Result<(), Box<dyn Error>> {
+        let store = BoundedMemoryStore::new(10);
+        let a = StoreKey::new("a")?;
+        let b = StoreKey::new("b")?;
+        let c = StoreKey::new("c")?;
+        store.set(&a, vec![0; 4].into())?;
+        store.set(&b, vec![1; 4].into())?;
+        assert_eq!(store.size()?, 8);
+
+        // Reading a makes b the least recently used
+        assert!(store.get(&a)?.is_some());
+        store.set(&c, vec![2; 4].into())?;
+        assert_eq!(store.list()?, vec![a.clone(), c.clone()]);
+        assert_eq!(store.size()?, 8);
+        assert_eq!(store.evictions(), 1);
+
+        // Growing c with a partial write evicts a
+        store.set_partial_values(&[StoreKeyOffsetValue::new(c.clone(), 6, &[3, 3])])?;
+        assert_eq!(store.list()?, vec![c.clone()]);
+        assert_eq!(store.get(&c)?.unwrap(), vec![2, 2, 2, 2, 0, 0, 3, 3]);
+        assert_eq!(store.size()?, 8);
+
+        // Values exceeding the capacity are rejected and do not evict anything
+        assert!(store.set(&a, vec![0; 11].into()).is_err());
+        assert_eq!(store.list()?, vec![c.clone()]);
+
+        store.erase(&c)?;
+        assert_eq!(store.size()?, 0);
+        Ok(())
+    }
+}
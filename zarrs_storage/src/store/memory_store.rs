@@ -158,6 +158,27 @@ impl WritableStorageTraits for MemoryStore {
         }
         Ok(())
     }
+
+    fn supports_rename_prefix(&self) -> bool {
+        true
+    }
+
+    fn rename_prefix(&self, from: &StorePrefix, to: &StorePrefix) -> Result<(), StorageError> {
+        let mut data_map = self.data_map.lock().unwrap();
+        let keys: Vec<StoreKey> = data_map
+            .keys()
+            .filter(|key| key.has_prefix(from))
+            .cloned()
+            .collect();
+        for key in keys {
+            let Some(value) = data_map.remove(&key) else {
+                continue;
+            };
+            let suffix = key.as_str().strip_prefix(from.as_str()).unwrap_or_default();
+            data_map.insert(StoreKey::new(format!("{}{suffix}", to.as_str()))?, value);
+        }
+        Ok(())
+    }
 }
 
 impl ListableStorageTraits for MemoryStore {
@@ -222,6 +243,7 @@ mod tests {
         crate::store_test::store_write(&store)?;
         crate::store_test::store_read(&store)?;
         crate::store_test::store_list(&store)?;
+        crate::store_test::store_rename_prefix(&store)?;
         Ok(())
     }
 }
@@ -0,0 +1,430 @@
+//! A synchronous store composing a fast store over a slow store.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    byte_range::{extract_byte_ranges, ByteRange},
+    Bytes, ListableStorageTraits, MaybeBytes, ReadableStorageTraits, StorageError, StoreKey,
+    StoreKeyOffsetValue, StoreKeys, StoreKeysPrefixes, StorePrefix, WritableStorageTraits,
+};
+
+/// The write policy of a [`TieredStore`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TieredWritePolicy {
+    /// Write values to the slow store and then the fast store.
+    #[default]
+    WriteThrough,
+    /// Write values to the fast store only and defer writing to the slow store until [`TieredStore::flush`].
+    WriteBack,
+}
+
+/// A synchronous store composing a fast store (e.g. memory or local disk) over a slow store (e.g. HTTP or S3).
+///
+/// Reads check the fast store first and then the slow store.
+/// Values read from the slow store are promoted (written) to the fast store, unless promotion is disabled with [`TieredStore::with_promotion`].
+/// Partial reads that miss the fast store retrieve and promote the entire value.
+///
+/// Writes are governed by a [`TieredWritePolicy`]:
+///  - [`WriteThrough`](TieredWritePolicy::WriteThrough) (default): values are written to the slow store and then the fast store.
+///  - [`WriteBack`](TieredWritePolicy::WriteBack): values are written to the fast store only and are written to the slow store by [`TieredStore::flush`].
+///    The fast store must not evict values, otherwise unflushed writes are lost.
+///    Unflushed writes are not written on drop.
+///
+/// Erases apply to both stores, and listing merges the keys of both stores.
+///
+/// ```rust
+/// # use std::sync::Arc;
+/// # use zarrs_storage::{ReadableStorageTraits, WritableStorageTraits, StoreKey};
+/// use zarrs_storage::store::{MemoryStore, TieredStore, TieredWritePolicy};
+/// let fast = Arc::new(MemoryStore::new());
+/// let slow = Arc::new(MemoryStore::new());
+/// let store = TieredStore::new(fast.clone(), slow.clone())
+///     .with_write_policy(TieredWritePolicy::WriteBack);
+/// let key = StoreKey::new("a/b")?;
+/// store.set(&key, vec![0, 1, 2].into())?;
+/// assert!(slow.get(&key)?.is_none());
+/// store.flush()?;
+/// assert!(slow.get(&key)?.is_some());
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct TieredStore<TFast: ?Sized, TSlow: ?Sized> {
+    fast: Arc<TFast>,
+    slow: Arc<TSlow>,
+    write_policy: TieredWritePolicy,
+    promote: bool,
+    /// Keys written to the fast store but not yet the slow store, with the generation of their last write.
+    dirty: Mutex<TieredStoreDirty>,
+}
+
+#[derive(Default)]
+struct TieredStoreDirty {
+    keys: BTreeMap<StoreKey, u64>,
+    generation: u64,
+}
+
+impl<TFast: ?Sized, TSlow: ?Sized> core::fmt::Debug for TieredStore<TFast, TSlow> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "tiered store ({:?}, promote: {})",
+            self.write_policy, self.promote
+        )
+    }
+}
+
+impl<TFast: ?Sized, TSlow: ?Sized> TieredStore<TFast, TSlow> {
+    /// Create a new tiered store with a `fast` store over a `slow` store.
+    ///
+    /// The write policy defaults to [`TieredWritePolicy::WriteThrough`] and promotion is enabled.
+    #[must_use]
+    pub fn new(fast: Arc<TFast>, slow: Arc<TSlow>) -> Self {
+        Self {
+            fast,
+            slow,
+            write_policy: TieredWritePolicy::default(),
+            promote: true,
+            dirty: Mutex::default(),
+        }
+    }
+
+    /// Set the write policy.
+    #[must_use]
+    pub fn with_write_policy(mut self, write_policy: TieredWritePolicy) -> Self {
+        self.write_policy = write_policy;
+        self
+    }
+
+    /// Enable or disable promotion of values read from the slow store to the fast store.
+    #[must_use]
+    pub fn with_promotion(mut self, promote: bool) -> Self {
+        self.promote = promote;
+        self
+    }
+
+    /// Return the fast store.
+    #[must_use]
+    pub const fn fast(&self) -> &Arc<TFast> {
+        &self.fast
+    }
+
+    /// Return the slow store.
+    #[must_use]
+    pub const fn slow(&self) -> &Arc<TSlow> {
+        &self.slow
+    }
+
+    /// Return the write policy.
+    #[must_use]
+    pub const fn write_policy(&self) -> TieredWritePolicy {
+        self.write_policy
+    }
+
+    /// Return the keys that have been written to the fast store but not yet flushed to the slow store.
+    ///
+    /// # Panics
+    /// Panics if the internal lock is poisoned.
+    #[must_use]
+    pub fn dirty_keys(&self) -> StoreKeys {
+        self.dirty.lock().unwrap().keys.keys().cloned().collect()
+    }
+
+    fn mark_dirty(&self, key: &StoreKey) {
+        let mut dirty = self.dirty.lock().unwrap();
+        dirty.generation += 1;
+        let generation = dirty.generation;
+        dirty.keys.insert(key.clone(), generation);
+    }
+}
+
+impl<TFast: ?Sized + ReadableStorageTraits, TSlow: ?Sized + WritableStorageTraits>
+    TieredStore<TFast, TSlow>
+{
+    /// Write all unflushed values in the fast store to the slow store.
+    ///
+    /// This is a no-op with the [`TieredWritePolicy::WriteThrough`] policy.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if a value cannot be read from the fast store or written to the slow store.
+    /// Values that were not flushed remain dirty.
+    ///
+    /// # Panics
+    /// Panics if the internal lock is poisoned.
+    pub fn flush(&self) -> Result<(), StorageError> {
+        let dirty: Vec<(StoreKey, u64)> = self
+            .dirty
+            .lock()
+            .unwrap()
+            .keys
+            .iter()
+            .map(|(key, generation)| (key.clone(), *generation))
+            .collect();
+        for (key, generation) in dirty {
+            let value = self.fast.get(&key)?.ok_or_else(|| {
+                StorageError::Other(format!(
+                    "unflushed key {key} is no longer in the fast store of the tiered store"
+                ))
+            })?;
+            self.slow.set(&key, value)?;
+
+            // Keep the key dirty if it was written again during the flush
+            let mut dirty = self.dirty.lock().unwrap();
+            if dirty.keys.get(&key) == Some(&generation) {
+                dirty.keys.remove(&key);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<TFast: ?Sized + WritableStorageTraits, TSlow: ?Sized> TieredStore<TFast, TSlow> {
+    /// Write `value` to the fast store, erasing any stale value if the write fails (e.g. it exceeds a capacity).
+    fn set_fast(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        if self.fast.set(key, value).is_err() {
+            self.fast.erase(key)?;
+        }
+        Ok(())
+    }
+}
+
+impl<
+        TFast: ?Sized + ReadableStorageTraits + WritableStorageTraits,
+        TSlow: ?Sized + ReadableStorageTraits,
+    > ReadableStorageTraits for TieredStore<TFast, TSlow>
+{
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        if let Some(value) = self.fast.get(key)? {
+            return Ok(Some(value));
+        }
+        let value = self.slow.get(key)?;
+        if self.promote {
+            if let Some(value) = &value {
+                self.set_fast(key, value.clone())?;
+            }
+        }
+        Ok(value)
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        if let Some(values) = self.fast.get_partial_values_key(key, byte_ranges)? {
+            return Ok(Some(values));
+        }
+        if self.promote {
+            let Some(value) = self.slow.get(key)? else {
+                return Ok(None);
+            };
+            let values = extract_byte_ranges(&value, byte_ranges)?
+                .into_iter()
+                .map(Bytes::from)
+                .collect();
+            self.set_fast(key, value)?;
+            Ok(Some(values))
+        } else {
+            self.slow.get_partial_values_key(key, byte_ranges)
+        }
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        if let Some(size) = self.fast.size_key(key)? {
+            return Ok(Some(size));
+        }
+        self.slow.size_key(key)
+    }
+}
+
+impl<
+        TFast: ?Sized + ReadableStorageTraits + WritableStorageTraits,
+        TSlow: ?Sized + ReadableStorageTraits + WritableStorageTraits,
+    > WritableStorageTraits for TieredStore<TFast, TSlow>
+{
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        match self.write_policy {
+            TieredWritePolicy::WriteThrough => {
+                self.slow.set(key, value.clone())?;
+                self.set_fast(key, value)
+            }
+            TieredWritePolicy::WriteBack => {
+                self.fast.set(key, value)?;
+                self.mark_dirty(key);
+                Ok(())
+            }
+        }
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        let keys: BTreeSet<&StoreKey> = key_offset_values
+            .iter()
+            .map(StoreKeyOffsetValue::key)
+            .collect();
+        match self.write_policy {
+            TieredWritePolicy::WriteThrough => {
+                self.slow.set_partial_values(key_offset_values)?;
+                // Invalidate rather than update the fast store, which may not hold the entire value
+                for key in keys {
+                    self.fast.erase(key)?;
+                }
+                Ok(())
+            }
+            TieredWritePolicy::WriteBack => {
+                // The fast store must hold the entire value before it is partially updated
+                for key in &keys {
+                    if self.fast.size_key(key)?.is_none() {
+                        if let Some(value) = self.slow.get(key)? {
+                            self.fast.set(key, value)?;
+                        }
+                    }
+                }
+                self.fast.set_partial_values(key_offset_values)?;
+                for key in keys {
+                    self.mark_dirty(key);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.dirty.lock().unwrap().keys.remove(key);
+        self.fast.erase(key)?;
+        self.slow.erase(key)
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.dirty
+            .lock()
+            .unwrap()
+            .keys
+            .retain(|key, _| !key.has_prefix(prefix));
+        self.fast.erase_prefix(prefix)?;
+        self.slow.erase_prefix(prefix)
+    }
+}
+
+/// Merge two sorted lists, removing duplicates.
+fn merge_sorted<T: Ord>(a: Vec<T>, b: Vec<T>) -> Vec<T> {
+    let mut merged: Vec<T> = a.into_iter().chain(b).collect();
+    merged.sort();
+    merged.dedup();
+    merged
+}
+
+impl<
+        TFast: ?Sized + ReadableStorageTraits + ListableStorageTraits,
+        TSlow: ?Sized + ReadableStorageTraits + ListableStorageTraits,
+    > ListableStorageTraits for TieredStore<TFast, TSlow>
+{
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        Ok(merge_sorted(self.fast.list()?, self.slow.list()?))
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        Ok(merge_sorted(
+            self.fast.list_prefix(prefix)?,
+            self.slow.list_prefix(prefix)?,
+        ))
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        let fast = self.fast.list_dir(prefix)?;
+        let slow = self.slow.list_dir(prefix)?;
+        Ok(StoreKeysPrefixes::new(
+            merge_sorted(fast.keys().clone(), slow.keys().clone()),
+            merge_sorted(fast.prefixes().clone(), slow.prefixes().clone()),
+        ))
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        // A key may be in both stores, so sizes are retrieved per key
+        let mut size = 0;
+        for key in self.list_prefix(prefix)? {
+            if let Some(size_key) = self.fast.size_key(&key)? {
+                size += size_key;
+            } else if let Some(size_key) = self.slow.size_key(&key)? {
+                size += size_key;
+            }
+        }
+        Ok(size)
+    }
+
+    fn size(&self) -> Result<u64, StorageError> {
+        self.size_prefix(&StorePrefix::root())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::MemoryStore;
+    use std::error::Error;
+
+    #[test]
+    fn tiered_write_through() -> Result<(), Box<dyn Error>> {
+        let fast = Arc::new(MemoryStore::new());
+        let slow = Arc::new(MemoryStore::new());
+        let store = TieredStore::new(fast.clone(), slow.clone());
+        crate::store_test::store_write(&store)?;
+        crate::store_test::store_read(&store)?;
+        crate::store_test::store_list(&store)?;
+        assert_eq!(fast.list()?, slow.list()?);
+        assert!(store.dirty_keys().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn tiered_write_back() -> Result<(), Box<dyn Error>> {
+        let fast = Arc::new(MemoryStore::new());
+        let slow = Arc::new(MemoryStore::new());
+        let store = TieredStore::new(fast.clone(), slow.clone())
+            .with_write_policy(TieredWritePolicy::WriteBack);
+        crate::store_test::store_write(&store)?;
+        crate::store_test::store_read(&store)?;
+        crate::store_test::store_list(&store)?;
+        assert!(slow.list()?.is_empty());
+        assert!(!store.dirty_keys().is_empty());
+        store.flush()?;
+        assert!(store.dirty_keys().is_empty());
+        assert_eq!(fast.list()?, slow.list()?);
+        for key in slow.list()? {
+            assert_eq!(fast.get(&key)?, slow.get(&key)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn tiered_promotion() -> Result<(), Box<dyn Error>> {
+        let fast = Arc::new(MemoryStore::new());
+        let slow = Arc::new(MemoryStore::new());
+        let a = StoreKey::new("a")?;
+        let b = StoreKey::new("b")?;
+        slow.set(&a, vec![0, 1, 2, 3].into())?;
+        slow.set(&b, vec![4, 5, 6, 7].into())?;
+
+        let store = TieredStore::new(fast.clone(), slow.clone());
+        assert_eq!(store.get(&a)?.unwrap(), vec![0, 1, 2, 3]);
+        assert_eq!(
+            store.get_partial_values_key(&b, &[ByteRange::Suffix(2)])?,
+            Some(vec![vec![6, 7].into()])
+        );
+        assert_eq!(fast.list()?, vec![a.clone(), b.clone()]);
+
+        // Partial writes invalidate the fast store
+        store.set_partial_values(&[StoreKeyOffsetValue::new(a.clone(), 1, &[9])])?;
+        assert!(fast.get(&a)?.is_none());
+        assert_eq!(store.get(&a)?.unwrap(), vec![0, 9, 2, 3]);
+
+        fast.erase_prefix(&StorePrefix::root())?;
+        let store = store.with_promotion(false);
+        assert_eq!(store.get(&a)?.unwrap(), vec![0, 9, 2, 3]);
+        assert!(fast.list()?.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,280 @@
+//! A read-only store overlaying multiple stores.
+
+use std::{collections::BTreeSet, sync::Arc};
+
+use crate::{
+    byte_range::ByteRange, Bytes, ListableStorageTraits, MaybeBytes, ReadableStorageTraits,
+    StorageError, StoreKey, StoreKeys, StoreKeysPrefixes, StorePrefix,
+};
+
+#[cfg(feature = "async")]
+use crate::{AsyncBytes, AsyncListableStorageTraits, AsyncReadableStorageTraits, MaybeAsyncBytes};
+
+/// A read-only store overlaying multiple stores, where the first store with a key wins.
+///
+/// Reads check each store in order and return the value from the first store containing the key.
+/// Listing merges the keys and prefixes of all stores.
+///
+/// This allows a local "patch" store to shadow the values of a base store, such as correcting a few chunks of a published remote dataset without copying it.
+/// Use [`ReadableListableStorageTraits`](crate::ReadableListableStorageTraits) trait objects to overlay stores of different types.
+///
+/// ```rust
+/// # use std::sync::Arc;
+/// # use zarrs_storage::{ListableStorageTraits, ReadableStorageTraits, WritableStorageTraits, StoreKey};
+/// use zarrs_storage::store::{MemoryStore, UnionStore};
+/// let patch = Arc::new(MemoryStore::new());
+/// let base = Arc::new(MemoryStore::new());
+/// base.set(&StoreKey::new("c/0")?, vec![0].into())?;
+/// base.set(&StoreKey::new("c/1")?, vec![0].into())?;
+/// patch.set(&StoreKey::new("c/1")?, vec![1].into())?;
+/// let store = UnionStore::new(vec![patch, base]);
+/// assert_eq!(store.get(&StoreKey::new("c/0")?)?.unwrap(), vec![0]);
+/// assert_eq!(store.get(&StoreKey::new("c/1")?)?.unwrap(), vec![1]);
+/// assert_eq!(store.list()?.len(), 2);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct UnionStore<TStorage: ?Sized> {
+    stores: Vec<Arc<TStorage>>,
+}
+
+impl<TStorage: ?Sized> core::fmt::Debug for UnionStore<TStorage> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "union store ({} stores)", self.stores.len())
+    }
+}
+
+impl<TStorage: ?Sized> UnionStore<TStorage> {
+    /// Create a new union store overlaying `stores` in order of precedence.
+    #[must_use]
+    pub fn new(stores: Vec<Arc<TStorage>>) -> Self {
+        Self { stores }
+    }
+
+    /// Return the stores in order of precedence.
+    #[must_use]
+    pub fn stores(&self) -> &[Arc<TStorage>] {
+        &self.stores
+    }
+}
+
+fn merge_keys(keys: impl IntoIterator<Item = StoreKeys>) -> StoreKeys {
+    keys.into_iter()
+        .flatten()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+fn merge_keys_prefixes(keys_prefixes: &[StoreKeysPrefixes]) -> StoreKeysPrefixes {
+    let keys = merge_keys(keys_prefixes.iter().map(|kp| kp.keys().clone()));
+    let prefixes = keys_prefixes
+        .iter()
+        .flat_map(|kp| kp.prefixes().iter().cloned())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    StoreKeysPrefixes::new(keys, prefixes)
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits for UnionStore<TStorage> {
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        for store in &self.stores {
+            if let Some(value) = store.get(key)? {
+                return Ok(Some(value));
+            }
+        }
+        Ok(None)
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        for store in &self.stores {
+            if let Some(values) = store.get_partial_values_key(key, byte_ranges)? {
+                return Ok(Some(values));
+            }
+        }
+        Ok(None)
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        for store in &self.stores {
+            if let Some(size) = store.size_key(key)? {
+                return Ok(Some(size));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + ListableStorageTraits> ListableStorageTraits
+    for UnionStore<TStorage>
+{
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        Ok(merge_keys(
+            self.stores
+                .iter()
+                .map(|store| store.list())
+                .collect::<Result<Vec<_>, _>>()?,
+        ))
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        Ok(merge_keys(
+            self.stores
+                .iter()
+                .map(|store| store.list_prefix(prefix))
+                .collect::<Result<Vec<_>, _>>()?,
+        ))
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        Ok(merge_keys_prefixes(
+            &self
+                .stores
+                .iter()
+                .map(|store| store.list_dir(prefix))
+                .collect::<Result<Vec<_>, _>>()?,
+        ))
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        let mut size = 0;
+        for key in self.list_prefix(prefix)? {
+            size += self.size_key(&key)?.unwrap_or_default();
+        }
+        Ok(size)
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncReadableStorageTraits> AsyncReadableStorageTraits
+    for UnionStore<TStorage>
+{
+    async fn get(&self, key: &StoreKey) -> Result<MaybeAsyncBytes, StorageError> {
+        for store in &self.stores {
+            if let Some(value) = store.get(key).await? {
+                return Ok(Some(value));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<AsyncBytes>>, StorageError> {
+        for store in &self.stores {
+            if let Some(values) = store.get_partial_values_key(key, byte_ranges).await? {
+                return Ok(Some(values));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        for store in &self.stores {
+            if let Some(size) = store.size_key(key).await? {
+                return Ok(Some(size));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncReadableStorageTraits + AsyncListableStorageTraits>
+    AsyncListableStorageTraits for UnionStore<TStorage>
+{
+    async fn list(&self) -> Result<StoreKeys, StorageError> {
+        let mut keys = Vec::with_capacity(self.stores.len());
+        for store in &self.stores {
+            keys.push(store.list().await?);
+        }
+        Ok(merge_keys(keys))
+    }
+
+    async fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        let mut keys = Vec::with_capacity(self.stores.len());
+        for store in &self.stores {
+            keys.push(store.list_prefix(prefix).await?);
+        }
+        Ok(merge_keys(keys))
+    }
+
+    async fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        let mut keys_prefixes = Vec::with_capacity(self.stores.len());
+        for store in &self.stores {
+            keys_prefixes.push(store.list_dir(prefix).await?);
+        }
+        Ok(merge_keys_prefixes(&keys_prefixes))
+    }
+
+    async fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        let mut size = 0;
+        for key in AsyncListableStorageTraits::list_prefix(self, prefix).await? {
+            size += AsyncReadableStorageTraits::size_key(self, &key)
+                .await?
+                .unwrap_or_default();
+        }
+        Ok(size)
+    }
+
+    async fn size(&self) -> Result<u64, StorageError> {
+        AsyncListableStorageTraits::size_prefix(self, &StorePrefix::root()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{store::MemoryStore, ReadableListableStorageTraits, WritableStorageTraits};
+    use std::error::Error;
+
+    #[test]
+    fn union() -> Result<(), Box<dyn Error>> {
+        let base = Arc::new(MemoryStore::new());
+        crate::store_test::store_write(base.as_ref())?;
+        let store = UnionStore::new(vec![Arc::new(MemoryStore::new()), base]);
+        crate::store_test::store_read(&store)?;
+        crate::store_test::store_list(&store)?;
+        Ok(())
+    }
+
+    #[test]
+    fn union_shadow() -> Result<(), Box<dyn Error>> {
+        let patch = Arc::new(MemoryStore::new());
+        let base = Arc::new(MemoryStore::new());
+        let (a, b, c) = (
+            StoreKey::new("a/0")?,
+            StoreKey::new("a/1")?,
+            StoreKey::new("b/0")?,
+        );
+        base.set(&a, vec![0, 0].into())?;
+        base.set(&b, vec![0, 0].into())?;
+        patch.set(&b, vec![1].into())?;
+        patch.set(&c, vec![1].into())?;
+        let stores: Vec<Arc<dyn ReadableListableStorageTraits>> = vec![patch, base];
+        let store = UnionStore::new(stores);
+        assert_eq!(store.get(&a)?.unwrap(), vec![0, 0]);
+        assert_eq!(store.get(&b)?.unwrap(), vec![1]);
+        assert_eq!(store.size_key(&b)?, Some(1));
+        assert_eq!(
+            store.get_partial_values_key(&a, &[ByteRange::FromStart(1, None)])?,
+            Some(vec![vec![0].into()])
+        );
+        assert_eq!(store.list()?, vec![a, b.clone(), c.clone()]);
+        assert_eq!(
+            store.list_dir(&StorePrefix::root())?.prefixes(),
+            &[StorePrefix::new("a/")?, StorePrefix::new("b/")?]
+        );
+        assert_eq!(store.size()?, 4);
+        assert!(store.get(&StoreKey::new("d")?)?.is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+//! Store key locks.
+//!
+//! A [`StoreLocks`] backend provides exclusive locks on store keys, which coordinate writers performing a read-modify-write of a value (e.g. a partial write of a chunk or shard).
+//! Stores do not lock keys by default, so a store must be wrapped in a [`LockingStorageAdapter`](crate::storage_adapter::locking::LockingStorageAdapter) for writers to coordinate.
+//!
+//! The locks of a backend are only respected by writers using the same backend:
+//! - [`InProcessStoreLocks`] coordinates the threads of a single process,
+//! - a `FileStoreLocks` of the `zarrs_filesystem` crate coordinates the processes of a single host with advisory file locks, and
+//! - [`RedisStoreLocks`] (with the `redis` feature) coordinates the processes of many hosts with leases held in a Redis server.
+//!
+//! Other backends (e.g. etcd) can be supported by implementing [`StoreLocks`].
+
+#[cfg(feature = "redis")]
+mod redis;
+#[cfg(feature = "redis")]
+pub use redis::RedisStoreLocks;
+
+use std::{collections::HashMap, sync::Arc};
+
+use parking_lot::{Mutex, RawMutex};
+
+use crate::{StorageError, StoreKey};
+
+/// A guard of an exclusive lock on a store key.
+///
+/// The lock is released when the guard is dropped.
+#[must_use = "the lock is released when the guard is dropped"]
+pub struct StoreKeyLockGuard<'a>(Option<Box<dyn Send + 'a>>);
+
+impl<'a> StoreKeyLockGuard<'a> {
+    /// Create a new store key lock guard which releases the lock when `guard` is dropped.
+    pub fn new(guard: impl Send + 'a) -> Self {
+        Self(Some(Box::new(guard)))
+    }
+
+    /// Create a guard that does not hold a lock.
+    ///
+    /// This is returned by stores that do not lock keys.
+    pub const fn unlocked() -> Self {
+        Self(None)
+    }
+
+    /// Returns true if the guard holds a lock.
+    #[must_use]
+    pub const fn is_locked(&self) -> bool {
+        self.0.is_some()
+    }
+}
+
+impl core::fmt::Debug for StoreKeyLockGuard<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("StoreKeyLockGuard")
+            .field("locked", &self.is_locked())
+            .finish()
+    }
+}
+
+/// A store key lock backend.
+pub trait StoreLocks: Send + Sync {
+    /// Acquire an exclusive lock on `key`, blocking until it is available.
+    ///
+    /// The locks are not reentrant, so a thread holding a lock on a key must not lock it again.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if the lock cannot be acquired.
+    fn lock(&self, key: &StoreKey) -> Result<StoreKeyLockGuard<'_>, StorageError>;
+}
+
+/// [`Arc`] wrapped store locks.
+pub type StoreLocksHandle = Arc<dyn StoreLocks>;
+
+/// In-process store key locks.
+///
+/// Coordinates the threads of a single process that share the same [`InProcessStoreLocks`].
+#[derive(Debug, Default)]
+pub struct InProcessStoreLocks {
+    locks: Mutex<HashMap<StoreKey, Arc<Mutex<()>>>>,
+}
+
+impl InProcessStoreLocks {
+    /// Create new in-process store locks.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl StoreLocks for InProcessStoreLocks {
+    fn lock(&self, key: &StoreKey) -> Result<StoreKeyLockGuard<'_>, StorageError> {
+        let mutex = self.locks.lock().entry(key.clone()).or_default().clone();
+        let guard: parking_lot::lock_api::ArcMutexGuard<RawMutex, ()> = mutex.lock_arc();
+        Ok(StoreKeyLockGuard::new(guard))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    /// Check that `locks` serialise read-modify-write updates of a counter by many threads.
+    pub(crate) fn store_locks_exclusive(locks: &dyn StoreLocks) {
+        let key = StoreKey::new("c/0").unwrap();
+        let counter = AtomicUsize::new(0);
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..10 {
+                        let guard = locks.lock(&key).unwrap();
+                        assert!(guard.is_locked());
+                        let value = counter.load(Ordering::SeqCst);
+                        std::thread::yield_now();
+                        counter.store(value + 1, Ordering::SeqCst);
+                    }
+                });
+            }
+        });
+        assert_eq!(counter.load(Ordering::SeqCst), 40);
+    }
+
+    #[test]
+    fn in_process_store_locks() {
+        let locks = InProcessStoreLocks::new();
+        store_locks_exclusive(&locks);
+
+        // Locks of different keys are independent
+        let _a = locks.lock(&StoreKey::new("a").unwrap()).unwrap();
+        let _b = locks.lock(&StoreKey::new("b").unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,320 @@
+//! Redis store key locks.
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpStream, ToSocketAddrs},
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{StorageError, StoreKey};
+
+use super::{StoreKeyLockGuard, StoreLocks};
+
+/// Deletes the lock key only if it still holds the token of the lock owner.
+const RELEASE_SCRIPT: &str =
+    "if redis.call('get', KEYS[1]) == ARGV[1] then return redis.call('del', KEYS[1]) else return 0 end";
+
+/// Redis store key locks.
+///
+/// Coordinates the processes of many hosts which lock keys through the same Redis server.
+///
+/// A lock is a lease on a Redis key (`{key_prefix}{store key}`) which is set with `SET NX PX` to a token unique to the lock owner, and deleted when the guard is dropped only if it still holds that token.
+/// A lease expires after its time to live, so a process that crashes while holding a lock cannot block other processes indefinitely.
+/// The time to live must exceed the time a lock is held for, otherwise another process may acquire the lock before it is released.
+///
+/// The Redis protocol (RESP) is spoken directly over TCP, and each lock uses its own connection.
+/// TLS and authentication are not supported.
+#[derive(Debug, Clone)]
+pub struct RedisStoreLocks {
+    address: String,
+    key_prefix: String,
+    ttl: Duration,
+    retry_interval: Duration,
+    timeout: Option<Duration>,
+}
+
+impl RedisStoreLocks {
+    /// Create new Redis store locks for the Redis server at `address` (e.g. `127.0.0.1:6379`).
+    ///
+    /// Defaults to a key prefix of `zarrs:lock:`, a time to live of 30 seconds, a retry interval of 10 milliseconds, and no timeout.
+    #[must_use]
+    pub fn new(address: impl Into<String>) -> Self {
+        Self {
+            address: address.into(),
+            key_prefix: "zarrs:lock:".to_string(),
+            ttl: Duration::from_secs(30),
+            retry_interval: Duration::from_millis(10),
+            timeout: None,
+        }
+    }
+
+    /// Set the prefix of the Redis keys holding the locks.
+    ///
+    /// Stores sharing a Redis server should use distinct prefixes.
+    #[must_use]
+    pub fn with_key_prefix(mut self, key_prefix: impl Into<String>) -> Self {
+        self.key_prefix = key_prefix.into();
+        self
+    }
+
+    /// Set the time to live of a lock.
+    #[must_use]
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Set the interval between attempts to acquire a held lock.
+    #[must_use]
+    pub fn with_retry_interval(mut self, retry_interval: Duration) -> Self {
+        self.retry_interval = retry_interval;
+        self
+    }
+
+    /// Set the time after which an attempt to acquire a held lock fails.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn connect(&self) -> Result<RedisConnection, StorageError> {
+        let address = self.address.to_socket_addrs()?.next().ok_or_else(|| {
+            StorageError::Other(format!("invalid redis address {}", self.address))
+        })?;
+        let stream = TcpStream::connect(address)?;
+        stream.set_nodelay(true)?;
+        Ok(RedisConnection {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+        })
+    }
+}
+
+/// Returns a token unique to this lock owner.
+fn lock_token() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos());
+    format!(
+        "{}:{}:{nanos}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+impl StoreLocks for RedisStoreLocks {
+    fn lock(&self, key: &StoreKey) -> Result<StoreKeyLockGuard<'_>, StorageError> {
+        let mut connection = self.connect()?;
+        let lock_key = format!("{}{}", self.key_prefix, key.as_str());
+        let token = lock_token();
+        let ttl = self.ttl.as_millis().max(1).to_string();
+        let start = Instant::now();
+        loop {
+            let reply = connection.command(&["SET", &lock_key, &token, "NX", "PX", &ttl])?;
+            match reply {
+                RespReply::Simple(_) => break,
+                RespReply::Nil => {
+                    if self
+                        .timeout
+                        .is_some_and(|timeout| start.elapsed() >= timeout)
+                    {
+                        return Err(StorageError::Other(format!(
+                            "timed out acquiring redis lock on {key}"
+                        )));
+                    }
+                    std::thread::sleep(self.retry_interval);
+                }
+                reply => {
+                    return Err(StorageError::Other(format!(
+                        "unexpected redis reply {reply:?} acquiring lock on {key}"
+                    )))
+                }
+            }
+        }
+        Ok(StoreKeyLockGuard::new(RedisLockGuard {
+            connection,
+            lock_key,
+            token,
+        }))
+    }
+}
+
+/// Releases a Redis lock when dropped.
+struct RedisLockGuard {
+    connection: RedisConnection,
+    lock_key: String,
+    token: String,
+}
+
+impl Drop for RedisLockGuard {
+    fn drop(&mut self) {
+        // If releasing fails, the lock is released when it expires
+        let _ =
+            self.connection
+                .command(&["EVAL", RELEASE_SCRIPT, "1", &self.lock_key, &self.token]);
+    }
+}
+
+/// A reply to a Redis command.
+#[derive(Debug, PartialEq, Eq)]
+enum RespReply {
+    Simple(String),
+    Integer(i64),
+    Bulk(Vec<u8>),
+    Nil,
+}
+
+/// A minimal Redis connection.
+struct RedisConnection {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl RedisConnection {
+    /// Send a command and read its reply.
+    fn command(&mut self, args: &[&str]) -> Result<RespReply, StorageError> {
+        let mut request = format!("*{}\r\n", args.len()).into_bytes();
+        for arg in args {
+            request.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+            request.extend_from_slice(arg.as_bytes());
+            request.extend_from_slice(b"\r\n");
+        }
+        self.writer.write_all(&request)?;
+        self.read_reply()
+    }
+
+    fn read_line(&mut self) -> Result<String, StorageError> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(StorageError::Other(
+                "redis connection closed unexpectedly".to_string(),
+            ));
+        }
+        Ok(line.trim_end_matches(['\r', '\n']).to_string())
+    }
+
+    fn read_reply(&mut self) -> Result<RespReply, StorageError> {
+        let line = self.read_line()?;
+        let invalid = || StorageError::Other(format!("invalid redis reply {line}"));
+        let kind = line.chars().next().ok_or_else(invalid)?;
+        let rest = &line[kind.len_utf8()..];
+        match kind {
+            '+' => Ok(RespReply::Simple(rest.to_string())),
+            '-' => Err(StorageError::Other(format!("redis error: {rest}"))),
+            ':' => Ok(RespReply::Integer(rest.parse().map_err(|_| invalid())?)),
+            '$' => {
+                let length: i64 = rest.parse().map_err(|_| invalid())?;
+                if length < 0 {
+                    return Ok(RespReply::Nil);
+                }
+                let length = usize::try_from(length).map_err(|_| invalid())?;
+                let mut value = vec![0; length + 2];
+                std::io::Read::read_exact(&mut self.reader, &mut value)?;
+                value.truncate(length);
+                Ok(RespReply::Bulk(value))
+            }
+            '*' if rest == "-1" => Ok(RespReply::Nil),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        io::Read,
+        net::TcpListener,
+        sync::{Arc, Mutex},
+    };
+
+    use super::*;
+
+    /// Read a RESP command from `reader`.
+    fn read_command(reader: &mut BufReader<TcpStream>) -> Option<Vec<String>> {
+        let mut line = String::new();
+        if reader.read_line(&mut line).ok()? == 0 {
+            return None;
+        }
+        let count: usize = line.trim_end()[1..].parse().unwrap();
+        let mut args = Vec::with_capacity(count);
+        for _ in 0..count {
+            line.clear();
+            reader.read_line(&mut line).unwrap();
+            let length: usize = line.trim_end()[1..].parse().unwrap();
+            let mut arg = vec![0; length + 2];
+            reader.read_exact(&mut arg).unwrap();
+            arg.truncate(length);
+            args.push(String::from_utf8(arg).unwrap());
+        }
+        Some(args)
+    }
+
+    /// Serve the `SET NX` and release script commands of [`RedisStoreLocks`], ignoring expiry.
+    fn serve_mock_redis() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let values = Arc::new(Mutex::new(HashMap::<String, String>::new()));
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let values = values.clone();
+                std::thread::spawn(move || {
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    while let Some(args) = read_command(&mut reader) {
+                        let mut values = values.lock().unwrap();
+                        let reply: &[u8] = match args[0].as_str() {
+                            "SET" => {
+                                assert_eq!(&args[3..5], ["NX", "PX"]);
+                                if values.contains_key(&args[1]) {
+                                    b"$-1\r\n"
+                                } else {
+                                    values.insert(args[1].clone(), args[2].clone());
+                                    b"+OK\r\n"
+                                }
+                            }
+                            "EVAL" => {
+                                if values.get(&args[3]) == Some(&args[4]) {
+                                    values.remove(&args[3]);
+                                    b":1\r\n"
+                                } else {
+                                    b":0\r\n"
+                                }
+                            }
+                            _ => b"-ERR unknown command\r\n",
+                        };
+                        stream.write_all(reply).unwrap();
+                    }
+                });
+            }
+        });
+        address
+    }
+
+    #[test]
+    fn redis_store_locks() {
+        let address = serve_mock_redis();
+        let locks = RedisStoreLocks::new(address).with_retry_interval(Duration::from_millis(1));
+        super::super::tests::store_locks_exclusive(&locks);
+
+        // A held lock times out
+        let locks = locks.with_timeout(Some(Duration::from_millis(20)));
+        let key = StoreKey::new("a").unwrap();
+        let guard = locks.lock(&key).unwrap();
+        assert!(locks.lock(&key).is_err());
+        drop(guard);
+        assert!(locks.lock(&key).is_ok());
+    }
+
+    #[test]
+    fn redis_store_locks_connection_refused() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        drop(listener);
+        let locks = RedisStoreLocks::new(address);
+        assert!(locks.lock(&StoreKey::new("a").unwrap()).is_err());
+    }
+}
@@ -1,10 +1,16 @@
-use std::error::Error;
+use std::{
+    error::Error,
+    io::{Read, Write},
+};
 
 use crate::{
-    byte_range::ByteRange, ListableStorageTraits, ReadableStorageTraits, StoreKeyOffsetValue,
-    StoreKeyRange, StorePrefix, WritableStorageTraits,
+    byte_range::ByteRange, ListableStorageTraits, ReadableStorageTraits, StorageError,
+    StoreKeyOffsetValue, StoreKeyRange, StorePrefix, WritableStorageTraits,
 };
 
+#[cfg(feature = "async")]
+use futures::{StreamExt, TryStreamExt};
+
 #[cfg(feature = "async")]
 use crate::{AsyncListableStorageTraits, AsyncReadableStorageTraits, AsyncWritableStorageTraits};
 
@@ -35,7 +41,10 @@ pub fn store_write<T: WritableStorageTraits>(store: &T) -> Result<(), Box<dyn Er
     store.set(&"a/d/e".try_into()?, vec![].into())?;
     store.set(&"a/f/g".try_into()?, vec![].into())?;
     store.set(&"a/f/h".try_into()?, vec![].into())?;
-    store.set(&"i/j/k".try_into()?, vec![0, 1].into())?;
+    let mut writer = store.set_writer(&"i/j/k".try_into()?)?;
+    writer.write_all(&[0])?;
+    writer.write_all(&[1])?;
+    writer.finish()?;
 
     store.set(&"erase".try_into()?, vec![].into())?;
     store.erase(&"erase".try_into()?)?;
@@ -61,6 +70,21 @@ pub fn store_read<T: ReadableStorageTraits>(store: &T) -> Result<(), Box<dyn Err
         store.get(&"a/b".try_into()?)?,
         Some(vec![0, 1, 2, 3].into())
     );
+    assert_eq!(
+        store.get_many(&[
+            "i/j/k".try_into()?,
+            "notfound".try_into()?,
+            "a/b".try_into()?
+        ])?,
+        vec![Some(vec![0, 1].into()), None, Some(vec![0, 1, 2, 3].into())]
+    );
+    assert!(store.get_reader(&"notfound".try_into()?)?.is_none());
+    let mut value = Vec::new();
+    store
+        .get_reader(&"a/b".try_into()?)?
+        .unwrap()
+        .read_to_end(&mut value)?;
+    assert_eq!(value, [0, 1, 2, 3]);
     assert_eq!(store.size_key(&"a/b".try_into()?)?, Some(4));
     assert_eq!(store.size_key(&"a/c".try_into()?)?, Some(1));
     assert_eq!(store.size_key(&"i/j/k".try_into()?)?, Some(2));
@@ -188,7 +212,12 @@ pub async fn async_store_write<T: AsyncWritableStorageTraits>(
     store.set(&"a/d/e".try_into()?, vec![].into()).await?;
     store.set(&"a/f/g".try_into()?, vec![].into()).await?;
     store.set(&"a/f/h".try_into()?, vec![].into()).await?;
-    store.set(&"i/j/k".try_into()?, vec![0, 1].into()).await?;
+    store
+        .set_stream(
+            &"i/j/k".try_into()?,
+            futures::stream::iter([Ok(vec![0].into()), Ok(vec![1].into())]).boxed(),
+        )
+        .await?;
 
     store.set(&"erase".try_into()?, vec![].into()).await?;
     store.erase(&"erase".try_into()?).await?;
@@ -227,6 +256,24 @@ pub async fn async_store_read<T: AsyncReadableStorageTraits>(
         store.get(&"a/b".try_into()?).await?,
         Some(vec![0, 1, 2, 3].into())
     );
+    assert_eq!(
+        store
+            .get_many(&[
+                "i/j/k".try_into()?,
+                "notfound".try_into()?,
+                "a/b".try_into()?
+            ])
+            .await?,
+        vec![Some(vec![0, 1].into()), None, Some(vec![0, 1, 2, 3].into())]
+    );
+    assert!(store.get_stream(&"notfound".try_into()?).await?.is_none());
+    let value: Vec<_> = store
+        .get_stream(&"a/b".try_into()?)
+        .await?
+        .unwrap()
+        .try_collect()
+        .await?;
+    assert_eq!(value.concat(), [0, 1, 2, 3]);
     assert_eq!(store.size_key(&"a/b".try_into()?).await?, Some(4));
     assert_eq!(store.size_key(&"a/c".try_into()?).await?, Some(1));
     assert_eq!(store.size_key(&"i/j/k".try_into()?).await?, Some(2));
@@ -323,3 +370,144 @@ pub async fn async_store_list<T: AsyncListableStorageTraits>(
     }
     Ok(())
 }
+
+#[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+/// Check conditional writes with [`WritableStorageTraits::set_if_match`] and [`ReadableStorageTraits::get_with_etag`].
+///
+/// Uses the key `set_if_match`, which is erased when finished.
+pub fn store_set_if_match<T: ReadableStorageTraits + WritableStorageTraits>(
+    store: &T,
+) -> Result<(), Box<dyn Error>> {
+    assert!(store.supports_set_if_match());
+    let key = "set_if_match".try_into()?;
+    store.erase(&key)?;
+    assert_eq!(store.get_with_etag(&key)?, (None, None));
+
+    // Create
+    let etag_0 = store.set_if_match(&key, vec![0].into(), None)?;
+    let (value, etag) = store.get_with_etag(&key)?;
+    assert_eq!(value, Some(vec![0].into()));
+    assert!(etag.is_some());
+    assert!(etag_0.is_none() || etag_0 == etag);
+    let etag_0 = etag.unwrap();
+    assert!(matches!(
+        store.set_if_match(&key, vec![1].into(), None),
+        Err(StorageError::PreconditionFailed(_))
+    ));
+
+    // Update
+    store.set_if_match(&key, vec![1, 1].into(), Some(&etag_0))?;
+    assert_eq!(store.get(&key)?, Some(vec![1, 1].into()));
+    assert!(matches!(
+        store.set_if_match(&key, vec![2].into(), Some(&etag_0)),
+        Err(StorageError::PreconditionFailed(_))
+    ));
+    assert_eq!(store.get(&key)?, Some(vec![1, 1].into()));
+
+    // A concurrent unconditional write changes the entity tag
+    let (_, etag_1) = store.get_with_etag(&key)?;
+    store.set(&key, vec![3, 3, 3].into())?;
+    assert!(matches!(
+        store.set_if_match(&key, vec![4].into(), etag_1.as_ref()),
+        Err(StorageError::PreconditionFailed(_))
+    ));
+
+    store.erase(&key)?;
+    assert!(matches!(
+        store.set_if_match(&key, vec![5].into(), etag_1.as_ref()),
+        Err(StorageError::PreconditionFailed(_))
+    ));
+    Ok(())
+}
+
+#[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+/// Check renaming a prefix with [`WritableStorageTraits::rename_prefix`].
+///
+/// Uses the prefixes `rename_from/` and `rename_to/`, which are erased when finished.
+pub fn store_rename_prefix<
+    T: ReadableStorageTraits + WritableStorageTraits + ListableStorageTraits,
+>(
+    store: &T,
+) -> Result<(), Box<dyn Error>> {
+    assert!(store.supports_rename_prefix());
+    let from = StorePrefix::new("rename_from/")?;
+    let to = StorePrefix::new("rename_to/")?;
+    store.erase_prefix(&from)?;
+    store.erase_prefix(&to)?;
+    store.set(&"rename_from/a".try_into()?, vec![0].into())?;
+    store.set(&"rename_from/b/c".try_into()?, vec![1, 2].into())?;
+
+    store.rename_prefix(&from, &to)?;
+    assert!(store.list_prefix(&from)?.is_empty());
+    assert_eq!(
+        store.list_prefix(&to)?,
+        &["rename_to/a".try_into()?, "rename_to/b/c".try_into()?]
+    );
+    assert_eq!(
+        store.get(&"rename_to/b/c".try_into()?)?,
+        Some(vec![1, 2].into())
+    );
+
+    store.erase_prefix(&to)?;
+    Ok(())
+}
+
+#[cfg(feature = "async")]
+#[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+/// Check conditional writes with [`AsyncWritableStorageTraits::set_if_match`] and [`AsyncReadableStorageTraits::get_with_etag`].
+///
+/// Uses the key `set_if_match`, which is erased when finished.
+pub async fn async_store_set_if_match<
+    T: AsyncReadableStorageTraits + AsyncWritableStorageTraits,
+>(
+    store: &T,
+) -> Result<(), Box<dyn Error>> {
+    assert!(store.supports_set_if_match());
+    let key = "set_if_match".try_into()?;
+    store.erase(&key).await?;
+    assert_eq!(store.get_with_etag(&key).await?, (None, None));
+
+    // Create
+    let etag_0 = store.set_if_match(&key, vec![0].into(), None).await?;
+    let (value, etag) = store.get_with_etag(&key).await?;
+    assert_eq!(value, Some(vec![0].into()));
+    assert!(etag.is_some());
+    assert!(etag_0.is_none() || etag_0 == etag);
+    let etag_0 = etag.unwrap();
+    assert!(matches!(
+        store.set_if_match(&key, vec![1].into(), None).await,
+        Err(StorageError::PreconditionFailed(_))
+    ));
+
+    // Update
+    store
+        .set_if_match(&key, vec![1, 1].into(), Some(&etag_0))
+        .await?;
+    assert_eq!(store.get(&key).await?, Some(vec![1, 1].into()));
+    assert!(matches!(
+        store
+            .set_if_match(&key, vec![2].into(), Some(&etag_0))
+            .await,
+        Err(StorageError::PreconditionFailed(_))
+    ));
+    assert_eq!(store.get(&key).await?, Some(vec![1, 1].into()));
+
+    // A concurrent unconditional write changes the entity tag
+    let (_, etag_1) = store.get_with_etag(&key).await?;
+    store.set(&key, vec![3, 3, 3].into()).await?;
+    assert!(matches!(
+        store
+            .set_if_match(&key, vec![4].into(), etag_1.as_ref())
+            .await,
+        Err(StorageError::PreconditionFailed(_))
+    ));
+
+    store.erase(&key).await?;
+    assert!(matches!(
+        store
+            .set_if_match(&key, vec![5].into(), etag_1.as_ref())
+            .await,
+        Err(StorageError::PreconditionFailed(_))
+    ));
+    Ok(())
+}
@@ -1,5 +1,6 @@
-//! A storage adapter for `zip` files for the [`zarrs`](https://docs.rs/zarrs/latest/zarrs/index.html) crate.
+//! A storage adapter and store for `zip` files for the [`zarrs`](https://docs.rs/zarrs/latest/zarrs/index.html) crate.
 //!
+//! A [`ZipStorageAdapter`] reads a zip file stored in any readable store:
 //! ```
 //! # use std::path::PathBuf;
 //! # use std::sync::Arc;
@@ -15,11 +16,28 @@
 //! # Ok::<_, Box<dyn std::error::Error>>(())
 //! ```
 //!
+//! A [`ZipStore`] reads and writes a zip file on the local filesystem, such as a `zarr.zip` created by `zarr-python`:
+//! ```
+//! # use std::sync::Arc;
+//! use zarrs_zip::ZipStore;
+//!
+//! # let tmp_dir = tempfile::TempDir::new()?;
+//! # let path = tmp_dir.path().join("hierarchy.zarr.zip");
+//! let store = Arc::new(ZipStore::open_writable(&path)?);
+//! // write to the store...
+//! store.flush()?;
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
 //! ## Licence
 //! `zarrs_zip` is licensed under either of
 //! - the Apache License, Version 2.0 [LICENSE-APACHE](https://docs.rs/crate/zarrs_zip/latest/source/LICENCE-APACHE) or <http://www.apache.org/licenses/LICENSE-2.0> or
 //! - the MIT license [LICENSE-MIT](https://docs.rs/crate/zarrs_zip/latest/source/LICENCE-MIT) or <http://opensource.org/licenses/MIT>, at your option.
 
+mod zip_store;
+
+pub use zip_store::{ZipStore, ZipStoreCreateError, ZipStoreOptions};
+
 use zarrs_storage::{
     byte_range::{extract_byte_ranges_read, ByteRange},
     Bytes, ListableStorageTraits, ReadableStorageTraits, StorageError, StorageValueIO, StoreKey,
@@ -0,0 +1,434 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use thiserror::Error;
+use zarrs_storage::{
+    byte_range::{extract_byte_ranges, extract_byte_ranges_read, ByteRange},
+    store_set_partial_values, Bytes, ListableStorageTraits, ReadableStorageTraits, StorageError,
+    StoreKey, StoreKeyOffsetValue, StoreKeys, StoreKeysPrefixes, StorePrefix,
+    WritableStorageTraits,
+};
+use zip::{result::ZipError, write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+/// Options for use with [`ZipStore`].
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub struct ZipStoreOptions {
+    compression_method: CompressionMethod,
+}
+
+impl Default for ZipStoreOptions {
+    fn default() -> Self {
+        Self {
+            compression_method: CompressionMethod::Stored,
+        }
+    }
+}
+
+impl ZipStoreOptions {
+    /// Set the compression method of entries written to the archive.
+    ///
+    /// Defaults to [`CompressionMethod::Stored`] (no compression), since chunks are typically already compressed by the codecs of an array.
+    pub fn compression_method(&mut self, compression_method: CompressionMethod) -> &mut Self {
+        self.compression_method = compression_method;
+        self
+    }
+}
+
+struct ZipStoreState {
+    /// The archive on disk, if it exists.
+    archive: Option<ZipArchive<File>>,
+    /// Values set or erased (`None`) since the archive was last written.
+    pending: BTreeMap<String, Option<Bytes>>,
+}
+
+/// A synchronous zip archive store.
+///
+/// Store keys map directly to the names of entries in the archive, as in the `ZipStore` of `zarr-python`.
+///
+/// A read-only store can be opened with [`ZipStore::open`], and a writable store with [`ZipStore::open_writable`].
+/// Zip archives cannot be updated in place, so writes to a writable store are held in memory until [`ZipStore::flush`] is called or the store is dropped.
+/// A flush writes a new archive alongside the existing one (copying unchanged entries without recompression) and then replaces it.
+pub struct ZipStore {
+    path: PathBuf,
+    readonly: bool,
+    options: ZipStoreOptions,
+    state: Mutex<ZipStoreState>,
+}
+
+impl std::fmt::Debug for ZipStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ZipStore")
+            .field("path", &self.path)
+            .field("readonly", &self.readonly)
+            .field("options", &self.options)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ZipStore {
+    /// Open an existing zip archive at `path` as a read-only store.
+    ///
+    /// # Errors
+    /// Returns a [`ZipStoreCreateError`] if `path` is a directory or is not a valid zip archive.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, ZipStoreCreateError> {
+        let path = path.as_ref().to_path_buf();
+        let archive = Self::open_archive(&path)?;
+        Ok(Self {
+            path,
+            readonly: true,
+            options: ZipStoreOptions::default(),
+            state: Mutex::new(ZipStoreState {
+                archive: Some(archive),
+                pending: BTreeMap::new(),
+            }),
+        })
+    }
+
+    /// Open a zip archive at `path` as a writable store, creating it if it does not exist.
+    ///
+    /// # Errors
+    /// Returns a [`ZipStoreCreateError`] if `path` is a directory or is not a valid zip archive.
+    pub fn open_writable<P: AsRef<Path>>(path: P) -> Result<Self, ZipStoreCreateError> {
+        Self::open_writable_with_options(path, ZipStoreOptions::default())
+    }
+
+    /// Open a zip archive at `path` as a writable store with `options`, creating it if it does not exist.
+    ///
+    /// # Errors
+    /// Returns a [`ZipStoreCreateError`] if `path` is a directory or is not a valid zip archive.
+    pub fn open_writable_with_options<P: AsRef<Path>>(
+        path: P,
+        options: ZipStoreOptions,
+    ) -> Result<Self, ZipStoreCreateError> {
+        let path = path.as_ref().to_path_buf();
+        let archive = if path.exists() {
+            Some(Self::open_archive(&path)?)
+        } else {
+            None
+        };
+        Ok(Self {
+            path,
+            readonly: false,
+            options,
+            state: Mutex::new(ZipStoreState {
+                archive,
+                pending: BTreeMap::new(),
+            }),
+        })
+    }
+
+    fn open_archive(path: &Path) -> Result<ZipArchive<File>, ZipStoreCreateError> {
+        if path.is_dir() {
+            return Err(ZipStoreCreateError::ExistingDir(path.to_path_buf()));
+        }
+        ZipArchive::new(File::open(path)?)
+            .map_err(|err| ZipStoreCreateError::ZipError(err.to_string()))
+    }
+
+    /// Return the path of the zip archive.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write pending changes to the zip archive.
+    ///
+    /// This is called automatically when the store is dropped, but any error is ignored.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if the archive cannot be written.
+    ///
+    /// # Panics
+    /// Panics if the internal state mutex is poisoned.
+    pub fn flush(&self) -> Result<(), StorageError> {
+        let mut state = self.state.lock().unwrap();
+        if self.readonly || (state.pending.is_empty() && state.archive.is_some()) {
+            return Ok(());
+        }
+
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        let tmp_path = PathBuf::from(tmp_path);
+        let mut writer = ZipWriter::new(File::create(&tmp_path)?);
+
+        let ZipStoreState { archive, pending } = &mut *state;
+        if let Some(archive) = archive.as_mut() {
+            for i in 0..archive.len() {
+                let file = archive.by_index_raw(i).map_err(handle_zip_error)?;
+                if !pending.contains_key(file.name()) {
+                    writer.raw_copy_file(file).map_err(handle_zip_error)?;
+                }
+            }
+        }
+        for (name, value) in pending.iter() {
+            if let Some(value) = value {
+                let options = SimpleFileOptions::default()
+                    .compression_method(self.options.compression_method)
+                    .large_file(value.len() as u64 >= u64::from(u32::MAX));
+                writer.start_file(name, options).map_err(handle_zip_error)?;
+                writer.write_all(value)?;
+            }
+        }
+        writer.finish().map_err(handle_zip_error)?;
+
+        std::fs::rename(&tmp_path, &self.path)?;
+        state.archive = Some(ZipArchive::new(File::open(&self.path)?).map_err(handle_zip_error)?);
+        state.pending.clear();
+        Ok(())
+    }
+
+    /// Return the sorted keys of all values, including pending changes.
+    fn keys(state: &ZipStoreState) -> BTreeSet<String> {
+        let mut keys: BTreeSet<String> = state
+            .archive
+            .as_ref()
+            .map(|archive| {
+                archive
+                    .file_names()
+                    .filter(|name| !name.ends_with('/'))
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        for (name, value) in &state.pending {
+            if value.is_some() {
+                keys.insert(name.clone());
+            } else {
+                keys.remove(name);
+            }
+        }
+        keys
+    }
+}
+
+impl Drop for ZipStore {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+fn handle_zip_error(err: ZipError) -> StorageError {
+    match err {
+        ZipError::Io(err) => StorageError::IOError(err),
+        _ => StorageError::Other(err.to_string()),
+    }
+}
+
+impl ReadableStorageTraits for ZipStore {
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Bytes>>, StorageError> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(value) = state.pending.get(key.as_str()) {
+            return match value {
+                Some(value) => Ok(Some(
+                    extract_byte_ranges(value, byte_ranges)?
+                        .into_iter()
+                        .map(Bytes::from)
+                        .collect(),
+                )),
+                None => Ok(None),
+            };
+        }
+        let Some(archive) = state.archive.as_mut() else {
+            return Ok(None);
+        };
+        let mut file = match archive.by_name(key.as_str()) {
+            Ok(file) => file,
+            Err(ZipError::FileNotFound) => return Ok(None),
+            Err(err) => return Err(handle_zip_error(err)),
+        };
+        let size = file.size();
+        Ok(Some(
+            extract_byte_ranges_read(&mut file, size, byte_ranges)?
+                .into_iter()
+                .map(Bytes::from)
+                .collect(),
+        ))
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(value) = state.pending.get(key.as_str()) {
+            return Ok(value.as_ref().map(|value| value.len() as u64));
+        }
+        let Some(archive) = state.archive.as_mut() else {
+            return Ok(None);
+        };
+        let size = match archive.by_name(key.as_str()) {
+            Ok(file) => Some(file.size()),
+            Err(ZipError::FileNotFound) => None,
+            Err(err) => return Err(handle_zip_error(err)),
+        };
+        Ok(size)
+    }
+}
+
+impl WritableStorageTraits for ZipStore {
+    fn set(&self, key: &StoreKey, value: Bytes) -> Result<(), StorageError> {
+        if self.readonly {
+            return Err(StorageError::ReadOnly);
+        }
+        self.state
+            .lock()
+            .unwrap()
+            .pending
+            .insert(key.as_str().to_string(), Some(value));
+        Ok(())
+    }
+
+    fn set_partial_values(
+        &self,
+        key_offset_values: &[StoreKeyOffsetValue],
+    ) -> Result<(), StorageError> {
+        store_set_partial_values(self, key_offset_values)
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        if self.readonly {
+            return Err(StorageError::ReadOnly);
+        }
+        self.state
+            .lock()
+            .unwrap()
+            .pending
+            .insert(key.as_str().to_string(), None);
+        Ok(())
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        if self.readonly {
+            return Err(StorageError::ReadOnly);
+        }
+        let mut state = self.state.lock().unwrap();
+        for key in Self::keys(&state) {
+            if key.starts_with(prefix.as_str()) {
+                state.pending.insert(key, None);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ListableStorageTraits for ZipStore {
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.list_prefix(&StorePrefix::root())
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        let state = self.state.lock().unwrap();
+        Self::keys(&state)
+            .into_iter()
+            .filter(|key| key.starts_with(prefix.as_str()))
+            .map(|key| Ok(StoreKey::new(key)?))
+            .collect()
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        let mut keys = StoreKeys::new();
+        let mut prefixes = BTreeSet::new();
+        for key in self.list_prefix(prefix)? {
+            let name = &key.as_str()[prefix.as_str().len()..];
+            if let Some((child, _)) = name.split_once('/') {
+                prefixes.insert(StorePrefix::new(format!("{}{child}/", prefix.as_str()))?);
+            } else {
+                keys.push(key);
+            }
+        }
+        Ok(StoreKeysPrefixes::new(keys, prefixes.into_iter().collect()))
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        let mut size = 0;
+        for key in self.list_prefix(prefix)? {
+            if let Some(size_key) = self.size_key(&key)? {
+                size += size_key;
+            }
+        }
+        Ok(size)
+    }
+}
+
+/// A zip store creation error.
+#[derive(Debug, Error)]
+pub enum ZipStoreCreateError {
+    /// An IO error.
+    #[error(transparent)]
+    IOError(#[from] std::io::Error),
+    /// An existing directory.
+    #[error("{0} is an existing directory, not a zip file")]
+    ExistingDir(PathBuf),
+    /// A zip error.
+    #[error("{0}")]
+    ZipError(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn zip_store() -> Result<(), Box<dyn Error>> {
+        let path = tempfile::TempDir::new()?;
+        let path = path.path().join("test.zarr.zip");
+
+        let store = ZipStore::open_writable(&path)?;
+        zarrs_storage::store_test::store_write(&store)?;
+        zarrs_storage::store_test::store_read(&store)?;
+        zarrs_storage::store_test::store_list(&store)?;
+        store.flush()?;
+        zarrs_storage::store_test::store_read(&store)?;
+        zarrs_storage::store_test::store_list(&store)?;
+        drop(store);
+
+        let store = ZipStore::open(&path)?;
+        zarrs_storage::store_test::store_read(&store)?;
+        zarrs_storage::store_test::store_list(&store)?;
+        assert!(store.set(&"a/b".try_into()?, vec![].into()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn zip_store_update() -> Result<(), Box<dyn Error>> {
+        let path = tempfile::TempDir::new()?;
+        let path = path.path().join("test.zarr.zip");
+
+        let mut options = ZipStoreOptions::default();
+        options.compression_method(CompressionMethod::Deflated);
+        let store = ZipStore::open_writable_with_options(&path, options)?;
+        store.set(&"a".try_into()?, vec![0; 64].into())?;
+        store.set(&"b/c".try_into()?, vec![1, 2].into())?;
+        drop(store);
+
+        let store = ZipStore::open_writable(&path)?;
+        store.set(&"a".try_into()?, vec![3].into())?;
+        store.erase(&"b/c".try_into()?)?;
+        store.set(&"d".try_into()?, vec![4, 5].into())?;
+        drop(store);
+
+        let store = ZipStore::open(&path)?;
+        assert_eq!(store.list()?, &["a".try_into()?, "d".try_into()?]);
+        assert_eq!(store.get(&"a".try_into()?)?.unwrap(), vec![3]);
+        assert_eq!(store.size_key(&"d".try_into()?)?, Some(2));
+        assert!(store.get(&"b/c".try_into()?)?.is_none());
+
+        // The archive is readable by the zip storage adapter
+        let fs_store = std::sync::Arc::new(zarrs_filesystem::FilesystemStore::new(
+            path.parent().unwrap(),
+        )?);
+        let adapter = crate::ZipStorageAdapter::new(fs_store, StoreKey::new("test.zarr.zip")?)?;
+        assert_eq!(adapter.get(&"d".try_into()?)?.unwrap(), vec![4, 5]);
+        Ok(())
+    }
+}